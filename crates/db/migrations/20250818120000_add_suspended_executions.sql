@@ -0,0 +1,15 @@
+PRAGMA foreign_keys = ON;
+
+-- Coding agent executions stopped by a graceful shutdown, kept so they can be resumed
+CREATE TABLE suspended_executions (
+    id                    BLOB PRIMARY KEY,
+    execution_process_id  BLOB NOT NULL,
+    task_attempt_id       BLOB NOT NULL,
+    session_id            TEXT,  -- External session ID to resume from, if the agent reported one
+    resumed_at            TEXT,
+    created_at            TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (execution_process_id) REFERENCES execution_processes(id) ON DELETE CASCADE,
+    FOREIGN KEY (task_attempt_id) REFERENCES task_attempts(id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_suspended_executions_task_attempt_id ON suspended_executions(task_attempt_id);
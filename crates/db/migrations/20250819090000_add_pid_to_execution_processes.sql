@@ -0,0 +1,2 @@
+-- Process group of the running executor, used to recover executions after an unclean shutdown
+ALTER TABLE execution_processes ADD COLUMN pid INTEGER;
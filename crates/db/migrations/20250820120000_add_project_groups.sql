@@ -0,0 +1,19 @@
+PRAGMA foreign_keys = ON;
+
+CREATE TABLE project_groups (
+    id          BLOB PRIMARY KEY,
+    name        TEXT NOT NULL UNIQUE,
+    created_at  TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    updated_at  TEXT NOT NULL DEFAULT (datetime('now', 'subsec'))
+);
+
+CREATE TABLE project_group_members (
+    project_group_id  BLOB NOT NULL,
+    project_id        BLOB NOT NULL,
+    position          INTEGER NOT NULL DEFAULT 0,  -- Swimlane order on the combined board
+    PRIMARY KEY (project_group_id, project_id),
+    FOREIGN KEY (project_group_id) REFERENCES project_groups(id) ON DELETE CASCADE,
+    FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_project_group_members_project_id ON project_group_members(project_id);
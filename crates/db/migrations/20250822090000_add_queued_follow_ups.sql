@@ -0,0 +1,13 @@
+PRAGMA foreign_keys = ON;
+
+CREATE TABLE queued_follow_ups (
+    id               BLOB PRIMARY KEY,
+    task_attempt_id  BLOB NOT NULL,
+    prompt           TEXT NOT NULL,
+    variant          TEXT,
+    position         INTEGER NOT NULL DEFAULT 0,  -- Dispatch order within the attempt
+    created_at       TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (task_attempt_id) REFERENCES task_attempts(id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_queued_follow_ups_task_attempt_id ON queued_follow_ups(task_attempt_id);
@@ -0,0 +1,14 @@
+PRAGMA foreign_keys = ON;
+
+-- Intervals an execution's process group spent stopped by the user
+CREATE TABLE execution_pauses (
+    id                    BLOB PRIMARY KEY,
+    execution_process_id  BLOB NOT NULL,
+    task_attempt_id       BLOB NOT NULL,
+    paused_at             TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    resumed_at            TEXT,
+    FOREIGN KEY (execution_process_id) REFERENCES execution_processes(id) ON DELETE CASCADE,
+    FOREIGN KEY (task_attempt_id) REFERENCES task_attempts(id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_execution_pauses_task_attempt_id ON execution_pauses(task_attempt_id);
@@ -0,0 +1,20 @@
+PRAGMA foreign_keys = ON;
+
+-- Cost agents reported for their runs. Kept when attempts or projects are deleted, so
+-- monthly spend doesn't drop with them.
+CREATE TABLE execution_costs (
+    id                    BLOB PRIMARY KEY,
+    execution_process_id  BLOB NOT NULL UNIQUE,
+    project_id            BLOB NOT NULL,
+    cost_usd              REAL NOT NULL,
+    recorded_at           TEXT NOT NULL DEFAULT (datetime('now', 'subsec'))
+);
+
+CREATE INDEX idx_execution_costs_recorded_at ON execution_costs(recorded_at);
+
+CREATE TABLE project_budgets (
+    project_id       BLOB PRIMARY KEY,
+    monthly_cap_usd  REAL NOT NULL,
+    updated_at       TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE
+);
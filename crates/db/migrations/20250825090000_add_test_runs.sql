@@ -0,0 +1,21 @@
+PRAGMA foreign_keys = ON;
+
+-- Test stage run after the coding agent, its output is parsed in the given format
+ALTER TABLE projects ADD COLUMN test_script TEXT;
+ALTER TABLE projects ADD COLUMN test_result_format TEXT
+    CHECK (test_result_format IN ('junit', 'cargo_json'));
+
+CREATE TABLE test_runs (
+    id                    BLOB PRIMARY KEY,
+    task_attempt_id       BLOB NOT NULL,
+    execution_process_id  BLOB NOT NULL UNIQUE,
+    passed                INTEGER NOT NULL,
+    failed                INTEGER NOT NULL,
+    skipped               INTEGER NOT NULL,
+    failed_tests          TEXT NOT NULL DEFAULT '[]',
+    created_at            TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (task_attempt_id) REFERENCES task_attempts(id) ON DELETE CASCADE,
+    FOREIGN KEY (execution_process_id) REFERENCES execution_processes(id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_test_runs_task_attempt_id ON test_runs(task_attempt_id);
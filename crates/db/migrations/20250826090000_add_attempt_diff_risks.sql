@@ -0,0 +1,9 @@
+PRAGMA foreign_keys = ON;
+
+-- Risk signals of an attempt's diff against its base branch, refreshed after each agent run
+CREATE TABLE attempt_diff_risks (
+    task_attempt_id  BLOB PRIMARY KEY,
+    risk             TEXT NOT NULL,
+    updated_at       TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (task_attempt_id) REFERENCES task_attempts(id) ON DELETE CASCADE
+);
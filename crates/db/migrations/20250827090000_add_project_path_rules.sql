@@ -0,0 +1,11 @@
+PRAGMA foreign_keys = ON;
+
+-- Globs restricting which paths attempts of a project may change
+CREATE TABLE project_path_rules (
+    project_id         BLOB PRIMARY KEY,
+    allowed_paths      TEXT NOT NULL DEFAULT '[]',
+    forbidden_paths    TEXT NOT NULL DEFAULT '[]',
+    revert_violations  INTEGER NOT NULL DEFAULT 0,
+    updated_at         TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE
+);
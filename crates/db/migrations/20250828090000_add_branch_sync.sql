@@ -0,0 +1,24 @@
+PRAGMA foreign_keys = ON;
+
+-- How and when attempt branches of a project are kept up to date with their base branch
+CREATE TABLE project_branch_sync (
+    project_id  BLOB PRIMARY KEY,
+    strategy    TEXT NOT NULL DEFAULT 'rebase'
+                   CHECK (strategy IN ('rebase', 'merge')),
+    schedule    TEXT NOT NULL DEFAULT 'off'
+                   CHECK (schedule IN ('off', 'nightly', 'on_base_change')),
+    updated_at  TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE
+);
+
+-- Outcome of the latest automatic sync of an attempt branch
+CREATE TABLE attempt_branch_syncs (
+    task_attempt_id   BLOB PRIMARY KEY,
+    status            TEXT NOT NULL
+                         CHECK (status IN ('up_to_date', 'synced', 'conflicts', 'failed')),
+    base_commit       TEXT,
+    conflicted_files  TEXT NOT NULL DEFAULT '[]',
+    error             TEXT,
+    synced_at         TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (task_attempt_id) REFERENCES task_attempts(id) ON DELETE CASCADE
+);
@@ -0,0 +1,14 @@
+PRAGMA foreign_keys = ON;
+
+-- Plan produced by the planning phase of a plan-then-execute attempt
+CREATE TABLE attempt_plans (
+    task_attempt_id  BLOB PRIMARY KEY,
+    status           TEXT NOT NULL DEFAULT 'planning'
+                        CHECK (status IN ('planning', 'awaiting_approval', 'approved', 'rejected', 'failed')),
+    plan             TEXT,
+    -- Variant of the attempt's profile running the approved plan, NULL for the default
+    execute_variant  TEXT,
+    created_at       TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    updated_at       TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (task_attempt_id) REFERENCES task_attempts(id) ON DELETE CASCADE
+);
@@ -0,0 +1,51 @@
+PRAGMA foreign_keys = ON;
+
+-- Allow the 'review' run reason for reviewer agent runs
+ALTER TABLE execution_processes
+  ADD COLUMN run_reason_new TEXT NOT NULL DEFAULT 'setupscript'
+    CHECK (run_reason_new IN ('setupscript',
+                              'cleanupscript',
+                              'codingagent',
+                              'devserver',
+                              'review'));
+
+UPDATE execution_processes
+  SET run_reason_new = run_reason;
+
+DROP INDEX IF EXISTS idx_execution_processes_type;
+
+ALTER TABLE execution_processes DROP COLUMN run_reason;
+
+ALTER TABLE execution_processes
+  RENAME COLUMN run_reason_new TO run_reason;
+
+CREATE INDEX idx_execution_processes_type
+        ON execution_processes(run_reason);
+
+-- Second profile reviewing the changes of a project's attempts once the implementer is done
+CREATE TABLE project_reviewers (
+    project_id          BLOB PRIMARY KEY,
+    enabled             INTEGER NOT NULL DEFAULT 0,
+    profile             TEXT NOT NULL,
+    variant             TEXT,
+    -- Pull requests need the latest review to approve the changes
+    gate_pull_requests  INTEGER NOT NULL DEFAULT 0,
+    updated_at          TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE
+);
+
+-- Structured reviews of an attempt's changes, one per reviewer run
+CREATE TABLE attempt_reviews (
+    id                    BLOB PRIMARY KEY,
+    task_attempt_id       BLOB NOT NULL,
+    execution_process_id  BLOB NOT NULL,
+    verdict               TEXT NOT NULL
+                             CHECK (verdict IN ('approved', 'changes_requested', 'failed')),
+    summary               TEXT NOT NULL,
+    comments              TEXT NOT NULL DEFAULT '[]',
+    created_at            TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (task_attempt_id) REFERENCES task_attempts(id) ON DELETE CASCADE,
+    FOREIGN KEY (execution_process_id) REFERENCES execution_processes(id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_attempt_reviews_task_attempt_id ON attempt_reviews(task_attempt_id);
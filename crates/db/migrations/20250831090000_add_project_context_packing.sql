@@ -0,0 +1,13 @@
+PRAGMA foreign_keys = ON;
+
+-- Repository files picked for the initial prompt of a project's attempts
+CREATE TABLE project_context_packing (
+    project_id      BLOB PRIMARY KEY,
+    enabled         INTEGER NOT NULL DEFAULT 0,
+    include_paths   TEXT NOT NULL DEFAULT '[]',
+    recent_commits  INTEGER NOT NULL DEFAULT 0,
+    keyword_search  INTEGER NOT NULL DEFAULT 1,
+    token_budget    INTEGER NOT NULL DEFAULT 4000,
+    updated_at      TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE
+);
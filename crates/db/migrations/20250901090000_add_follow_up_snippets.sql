@@ -0,0 +1,43 @@
+PRAGMA foreign_keys = ON;
+
+-- Reusable follow-up prompts, global or for one project
+CREATE TABLE follow_up_snippets (
+    id          BLOB PRIMARY KEY,
+    project_id  BLOB,  -- NULL for global snippets
+    name        TEXT NOT NULL,
+    prompt      TEXT NOT NULL,
+    created_at  TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    updated_at  TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_follow_up_snippets_project_id ON follow_up_snippets(project_id);
+
+-- A project snippet may share a global snippet's name, it replaces it in that project
+CREATE UNIQUE INDEX idx_follow_up_snippets_unique_name_project
+ON follow_up_snippets(project_id, name)
+WHERE project_id IS NOT NULL;
+
+CREATE UNIQUE INDEX idx_follow_up_snippets_unique_name_global
+ON follow_up_snippets(name)
+WHERE project_id IS NULL;
+
+INSERT INTO follow_up_snippets (id, project_id, name, prompt) VALUES
+(
+    randomblob(16),
+    NULL,
+    'Add tests',
+    'Add tests covering the changes you made, following the conventions of the existing tests. Run them and fix any failures.'
+),
+(
+    randomblob(16),
+    NULL,
+    'Address review comments',
+    'Address the review comments below. Keep the changes focused on what they ask for and explain anything you decided not to change.'
+),
+(
+    randomblob(16),
+    NULL,
+    'Split into smaller commits',
+    'Split your changes into smaller commits, each one a self-contained step with a clear message. Don''t change the resulting code.'
+);
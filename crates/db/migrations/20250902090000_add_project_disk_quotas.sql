@@ -0,0 +1,11 @@
+PRAGMA foreign_keys = ON;
+
+-- Disk space a project's worktrees may take up before new attempts are refused
+CREATE TABLE project_disk_quotas (
+    project_id  BLOB PRIMARY KEY,
+    max_bytes   INTEGER NOT NULL,
+    -- Remove worktrees of merged attempts when the quota is exceeded, before refusing
+    auto_gc     INTEGER NOT NULL DEFAULT 0,
+    updated_at  TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE
+);
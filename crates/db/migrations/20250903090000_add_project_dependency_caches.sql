@@ -0,0 +1,10 @@
+PRAGMA foreign_keys = ON;
+
+-- Package caches shared by all worktrees of a project
+CREATE TABLE project_dependency_caches (
+    project_id           BLOB PRIMARY KEY,
+    shared_cargo_target  INTEGER NOT NULL DEFAULT 0,
+    shared_pnpm_store    INTEGER NOT NULL DEFAULT 0,
+    updated_at           TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE
+);
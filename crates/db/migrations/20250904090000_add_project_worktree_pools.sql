@@ -0,0 +1,9 @@
+PRAGMA foreign_keys = ON;
+
+-- Worktrees kept ready for a project's next attempts
+CREATE TABLE project_worktree_pools (
+    project_id  BLOB PRIMARY KEY,
+    size        INTEGER NOT NULL DEFAULT 0,
+    updated_at  TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE
+);
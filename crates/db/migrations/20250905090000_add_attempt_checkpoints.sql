@@ -0,0 +1,17 @@
+PRAGMA foreign_keys = ON;
+
+-- Snapshots of an attempt's worktree, committed on hidden refs so it can be rolled back
+CREATE TABLE attempt_checkpoints (
+    id                    BLOB PRIMARY KEY,
+    task_attempt_id       BLOB NOT NULL,
+    execution_process_id  BLOB,
+    reason                TEXT NOT NULL
+                             CHECK (reason IN ('before_execution', 'periodic', 'agent_finished', 'before_rollback')),
+    commit_sha            TEXT NOT NULL,
+    head_commit           TEXT NOT NULL,
+    created_at            TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (task_attempt_id) REFERENCES task_attempts(id) ON DELETE CASCADE,
+    FOREIGN KEY (execution_process_id) REFERENCES execution_processes(id) ON DELETE SET NULL
+);
+
+CREATE INDEX idx_attempt_checkpoints_task_attempt_id ON attempt_checkpoints(task_attempt_id);
@@ -0,0 +1,15 @@
+PRAGMA foreign_keys = ON;
+
+-- Merges of an attempt that were undone by reverting their commit on the base branch
+CREATE TABLE attempt_unmerges (
+    id                  BLOB PRIMARY KEY,
+    task_attempt_id     BLOB NOT NULL,
+    base_branch         TEXT NOT NULL,
+    merge_commit        TEXT NOT NULL,
+    revert_commit       TEXT NOT NULL,
+    subsequent_commits  INTEGER NOT NULL DEFAULT 0,
+    created_at          TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (task_attempt_id) REFERENCES task_attempts(id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_attempt_unmerges_task_attempt_id ON attempt_unmerges(task_attempt_id);
@@ -0,0 +1,13 @@
+PRAGMA foreign_keys = ON;
+
+-- Variables set for the processes run in a project's worktrees. Secret values are kept in
+-- the secrets store, their value here is NULL.
+CREATE TABLE project_env_vars (
+    project_id  BLOB NOT NULL,
+    key         TEXT NOT NULL,
+    value       TEXT,
+    secret      BOOLEAN NOT NULL DEFAULT 0,
+    updated_at  TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    PRIMARY KEY (project_id, key),
+    FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE
+);
@@ -0,0 +1,20 @@
+PRAGMA foreign_keys = ON;
+
+-- Attempts waiting to be merged into their base branch one at a time, each rebased and
+-- verified against the base branch as it is when its turn comes
+CREATE TABLE merge_queue_entries (
+    id               BLOB PRIMARY KEY,
+    project_id       BLOB NOT NULL,
+    task_attempt_id  BLOB NOT NULL,
+    status           TEXT NOT NULL DEFAULT 'queued'
+                        CHECK (status IN ('queued', 'rebasing', 'verifying', 'merged', 'failed')),
+    merge_commit     TEXT,
+    error            TEXT,
+    created_at       TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    updated_at       TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE,
+    FOREIGN KEY (task_attempt_id) REFERENCES task_attempts(id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_merge_queue_entries_project_status ON merge_queue_entries(project_id, status);
+CREATE INDEX idx_merge_queue_entries_task_attempt_id ON merge_queue_entries(task_attempt_id);
@@ -0,0 +1,9 @@
+PRAGMA foreign_keys = ON;
+
+-- Whether a project's setup scripts and coding agents run in its repository's devcontainer
+CREATE TABLE project_devcontainers (
+    project_id  BLOB PRIMARY KEY,
+    enabled     INTEGER NOT NULL DEFAULT 0,
+    updated_at  TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE
+);
@@ -0,0 +1,11 @@
+PRAGMA foreign_keys = ON;
+
+-- Whether a project's processes run in the development shell of its Nix flake or devenv
+CREATE TABLE project_nix_envs (
+    project_id  BLOB PRIMARY KEY,
+    enabled     INTEGER NOT NULL DEFAULT 0,
+    tool        TEXT NOT NULL DEFAULT 'flake'
+                    CHECK (tool IN ('flake', 'devenv')),
+    updated_at  TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE
+);
@@ -0,0 +1,9 @@
+-- What resuming a suspended execution needs once the server is back: the profile that ran,
+-- the worktree it ran in and what it was asked to do. Executions cut off by a crash or a
+-- reboot are recorded too, when the server next starts.
+ALTER TABLE suspended_executions ADD COLUMN reason TEXT NOT NULL DEFAULT 'shutdown'
+    CHECK (reason IN ('shutdown', 'crash'));
+ALTER TABLE suspended_executions ADD COLUMN profile TEXT;
+ALTER TABLE suspended_executions ADD COLUMN variant TEXT;
+ALTER TABLE suspended_executions ADD COLUMN worktree_path TEXT;
+ALTER TABLE suspended_executions ADD COLUMN prompt TEXT;
@@ -0,0 +1,11 @@
+-- API keys of model providers injected into the agents using them. The keys themselves are
+-- kept in the secrets store, this records which are set and whether they worked.
+CREATE TABLE provider_keys (
+    provider          TEXT PRIMARY KEY
+                          CHECK (provider IN ('anthropic', 'openai', 'google', 'sourcegraph')),
+    key_hint          TEXT NOT NULL,
+    valid             INTEGER,  -- NULL until validated, or when the provider couldn't be reached
+    validation_error  TEXT,
+    validated_at      TEXT,
+    updated_at        TEXT NOT NULL DEFAULT (datetime('now', 'subsec'))
+);
@@ -0,0 +1,9 @@
+PRAGMA foreign_keys = ON;
+
+-- Tokens agents reported for their runs. Like costs, kept when attempts are deleted.
+CREATE TABLE execution_token_usage (
+    execution_process_id  BLOB PRIMARY KEY,
+    input_tokens          INTEGER NOT NULL,
+    output_tokens         INTEGER NOT NULL,
+    recorded_at           TEXT NOT NULL DEFAULT (datetime('now', 'subsec'))
+);
@@ -0,0 +1,45 @@
+PRAGMA foreign_keys = ON;
+
+-- Completed tasks replayed against several profiles to compare them
+CREATE TABLE evaluations (
+    id            BLOB PRIMARY KEY,
+    project_id    BLOB NOT NULL,
+    name          TEXT NOT NULL,
+    status        TEXT NOT NULL DEFAULT 'running'
+                     CHECK (status IN ('running', 'completed', 'failed', 'cancelled')),
+    error         TEXT,
+    created_at    TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    completed_at  TEXT,
+    FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE
+);
+
+-- One task replayed by one profile variant, scored once its attempt settles
+CREATE TABLE evaluation_runs (
+    id                BLOB PRIMARY KEY,
+    evaluation_id     BLOB NOT NULL,
+    task_id           BLOB NOT NULL,
+    profile           TEXT NOT NULL,
+    variant           TEXT,
+    -- Branch the replay starts from, the state of the repository before the task was done
+    base_branch       TEXT NOT NULL,
+    task_attempt_id   BLOB,
+    status            TEXT NOT NULL DEFAULT 'pending'
+                         CHECK (status IN ('pending', 'running', 'completed', 'failed')),
+    error             TEXT,
+    agent_completed   INTEGER,
+    verified          INTEGER,  -- NULL when no verification or test script ran
+    tests_passed      INTEGER,
+    tests_failed      INTEGER,
+    score             REAL,
+    cost_usd          REAL,
+    input_tokens      INTEGER,
+    output_tokens     INTEGER,
+    duration_seconds  INTEGER,
+    created_at        TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    completed_at      TEXT,
+    FOREIGN KEY (evaluation_id) REFERENCES evaluations(id) ON DELETE CASCADE,
+    FOREIGN KEY (task_id) REFERENCES tasks(id) ON DELETE CASCADE,
+    FOREIGN KEY (task_attempt_id) REFERENCES task_attempts(id) ON DELETE SET NULL
+);
+
+CREATE INDEX idx_evaluation_runs_evaluation_id ON evaluation_runs(evaluation_id);
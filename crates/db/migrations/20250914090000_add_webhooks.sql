@@ -0,0 +1,32 @@
+PRAGMA foreign_keys = ON;
+
+-- Endpoints board and execution events are posted to. Their signing secrets are kept in the
+-- secrets store.
+CREATE TABLE webhooks (
+    id          BLOB PRIMARY KEY,
+    url         TEXT NOT NULL,
+    events      TEXT NOT NULL DEFAULT '[]',  -- JSON array of event names, empty for all
+    enabled     INTEGER NOT NULL DEFAULT 1,
+    created_at  TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    updated_at  TEXT NOT NULL DEFAULT (datetime('now', 'subsec'))
+);
+
+-- Each event posted to a webhook, retried with backoff until it's accepted or given up on
+CREATE TABLE webhook_deliveries (
+    id               BLOB PRIMARY KEY,
+    webhook_id       BLOB NOT NULL,
+    event            TEXT NOT NULL,
+    data             TEXT NOT NULL,
+    status           TEXT NOT NULL DEFAULT 'pending'
+                        CHECK (status IN ('pending', 'delivered', 'failed')),
+    attempts         INTEGER NOT NULL DEFAULT 0,
+    next_attempt_at  TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    response_status  INTEGER,
+    last_error       TEXT,
+    created_at       TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    delivered_at     TEXT,
+    FOREIGN KEY (webhook_id) REFERENCES webhooks(id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_webhook_deliveries_due ON webhook_deliveries(status, next_attempt_at);
+CREATE INDEX idx_webhook_deliveries_webhook_id ON webhook_deliveries(webhook_id, created_at);
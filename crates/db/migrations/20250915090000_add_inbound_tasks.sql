@@ -0,0 +1,16 @@
+PRAGMA foreign_keys = ON;
+
+-- Cards opened by alerts posted to the inbound API, by the key the sender dedupes alerts on.
+-- An alert firing again updates its open card instead of adding another.
+CREATE TABLE inbound_tasks (
+    project_id     BLOB NOT NULL,
+    dedupe_key     TEXT NOT NULL,
+    task_id        BLOB NOT NULL,
+    source         TEXT,
+    occurrences    INTEGER NOT NULL DEFAULT 1,
+    first_seen_at  TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    last_seen_at   TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    PRIMARY KEY (project_id, dedupe_key),
+    FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE,
+    FOREIGN KEY (task_id) REFERENCES tasks(id) ON DELETE CASCADE
+);
@@ -0,0 +1,16 @@
+PRAGMA foreign_keys = ON;
+
+-- GitHub Actions workflows of a project watched for failures on its base branch
+CREATE TABLE project_ci_triage (
+    project_id   BLOB PRIMARY KEY,
+    enabled      INTEGER NOT NULL DEFAULT 0,
+    -- Workflow files watched, e.g. "ci.yml", every workflow when empty
+    workflows    TEXT NOT NULL DEFAULT '[]',
+    -- Branch watched, the repository's default branch when NULL
+    branch       TEXT,
+    start_agent  INTEGER NOT NULL DEFAULT 0,
+    -- Newest workflow run already looked at
+    last_run_id  INTEGER,
+    updated_at   TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE
+);
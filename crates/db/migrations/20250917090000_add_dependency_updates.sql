@@ -0,0 +1,40 @@
+PRAGMA foreign_keys = ON;
+
+-- Scheduled dependency updates of a project, one pull request per group of packages
+CREATE TABLE project_dependency_updates (
+    project_id    BLOB PRIMARY KEY,
+    enabled       INTEGER NOT NULL DEFAULT 0,
+    schedule      TEXT NOT NULL DEFAULT 'weekly'
+                     CHECK (schedule IN ('daily', 'weekly')),
+    -- Packages updated together, [{"name": "...", "patterns": ["..."]}]. Packages no
+    -- group matches are updated in one more pull request.
+    groups        TEXT NOT NULL DEFAULT '[]',
+    allow_major   INTEGER NOT NULL DEFAULT 0,
+    -- Packages never updated
+    ignored       TEXT NOT NULL DEFAULT '[]',
+    -- Extra instructions for the agent, e.g. a package to hold back
+    instructions  TEXT,
+    last_run_at   TEXT,
+    updated_at    TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE
+);
+
+-- One group of packages updated by one attempt, turned into a pull request once it settles
+CREATE TABLE dependency_update_runs (
+    id               BLOB PRIMARY KEY,
+    project_id       BLOB NOT NULL,
+    group_name       TEXT NOT NULL,
+    task_id          BLOB NOT NULL,
+    task_attempt_id  BLOB,
+    status           TEXT NOT NULL DEFAULT 'running'
+                        CHECK (status IN ('running', 'pr_opened', 'no_changes', 'failed')),
+    pr_url           TEXT,
+    error            TEXT,
+    created_at       TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    completed_at     TEXT,
+    FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE,
+    FOREIGN KEY (task_id) REFERENCES tasks(id) ON DELETE CASCADE,
+    FOREIGN KEY (task_attempt_id) REFERENCES task_attempts(id) ON DELETE SET NULL
+);
+
+CREATE INDEX idx_dependency_update_runs_project_id ON dependency_update_runs(project_id);
@@ -0,0 +1,45 @@
+PRAGMA foreign_keys = ON;
+
+-- Allow the 'commitplan' run reason for agent runs planning an attempt's commits
+ALTER TABLE execution_processes
+  ADD COLUMN run_reason_new TEXT NOT NULL DEFAULT 'setupscript'
+    CHECK (run_reason_new IN ('setupscript',
+                              'cleanupscript',
+                              'codingagent',
+                              'devserver',
+                              'review',
+                              'commitplan'));
+
+UPDATE execution_processes
+  SET run_reason_new = run_reason;
+
+DROP INDEX IF EXISTS idx_execution_processes_type;
+
+ALTER TABLE execution_processes DROP COLUMN run_reason;
+
+ALTER TABLE execution_processes
+  RENAME COLUMN run_reason_new TO run_reason;
+
+CREATE INDEX idx_execution_processes_type
+        ON execution_processes(run_reason);
+
+-- Split of an attempt's changes into logical commits, planned by an agent run and applied
+-- to the attempt's branch once it finished
+CREATE TABLE attempt_commit_plans (
+    id                    BLOB PRIMARY KEY,
+    task_attempt_id       BLOB NOT NULL,
+    execution_process_id  BLOB NOT NULL,
+    status                TEXT NOT NULL DEFAULT 'planning'
+                             CHECK (status IN ('planning', 'applied', 'failed')),
+    -- [{"message": "...", "files": ["..."]}], in commit order
+    commits               TEXT NOT NULL DEFAULT '[]',
+    -- Branch head before the split, for going back to it
+    original_head         TEXT,
+    error                 TEXT,
+    created_at            TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    applied_at            TEXT,
+    FOREIGN KEY (task_attempt_id) REFERENCES task_attempts(id) ON DELETE CASCADE,
+    FOREIGN KEY (execution_process_id) REFERENCES execution_processes(id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_attempt_commit_plans_task_attempt_id ON attempt_commit_plans(task_attempt_id);
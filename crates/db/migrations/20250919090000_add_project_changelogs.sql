@@ -0,0 +1,15 @@
+PRAGMA foreign_keys = ON;
+
+-- Changelog fragment written into the repository when an attempt of a project is merged
+CREATE TABLE project_changelogs (
+    project_id             BLOB PRIMARY KEY,
+    enabled                INTEGER NOT NULL DEFAULT 0,
+    format                 TEXT NOT NULL DEFAULT 'keep_a_changelog'
+                              CHECK (format IN ('keep_a_changelog', 'conventional_commits')),
+    -- Directory of the fragments, relative to the repository root
+    directory              TEXT NOT NULL DEFAULT 'changelog.d',
+    -- Merge commits get a conventional commit subject, e.g. `feat(api): ...`
+    conventional_commits   INTEGER NOT NULL DEFAULT 0,
+    updated_at             TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE
+);
@@ -0,0 +1,14 @@
+PRAGMA foreign_keys = ON;
+
+-- Read-only links showing one attempt's transcript and diff to anyone holding the token
+CREATE TABLE attempt_share_links (
+    id               BLOB PRIMARY KEY,
+    task_attempt_id  BLOB NOT NULL,
+    token            TEXT NOT NULL UNIQUE,
+    -- Links without an expiry work until they are revoked
+    expires_at       TEXT,
+    created_at       TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (task_attempt_id) REFERENCES task_attempts(id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_attempt_share_links_task_attempt_id ON attempt_share_links(task_attempt_id);
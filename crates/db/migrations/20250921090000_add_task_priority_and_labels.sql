@@ -0,0 +1,4 @@
+-- Priority and free-form labels of a task, both optional. Labels are a JSON array of strings.
+ALTER TABLE tasks ADD COLUMN priority TEXT
+    CHECK (priority IN ('urgent', 'high', 'medium', 'low'));
+ALTER TABLE tasks ADD COLUMN labels TEXT NOT NULL DEFAULT '[]';
@@ -0,0 +1,2 @@
+-- Branch or tag the task's attempts start from. NULL follows the project's current branch.
+ALTER TABLE tasks ADD COLUMN base_branch TEXT;
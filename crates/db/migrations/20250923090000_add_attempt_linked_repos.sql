@@ -0,0 +1,21 @@
+PRAGMA foreign_keys = ON;
+
+-- Worktrees an attempt keeps in other projects' repositories, for changes that have to land
+-- together with the attempt's own, e.g. an API and its client
+CREATE TABLE attempt_linked_repos (
+    id               BLOB PRIMARY KEY,
+    task_attempt_id  BLOB NOT NULL,
+    project_id       BLOB NOT NULL,
+    base_branch      TEXT NOT NULL,
+    -- Set once the worktree exists, under the attempt's own worktree
+    branch           TEXT,
+    container_ref    TEXT,
+    merge_commit     TEXT,
+    pr_url           TEXT,
+    created_at       TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (task_attempt_id) REFERENCES task_attempts(id) ON DELETE CASCADE,
+    FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE,
+    UNIQUE (task_attempt_id, project_id)
+);
+
+CREATE INDEX idx_attempt_linked_repos_task_attempt_id ON attempt_linked_repos(task_attempt_id);
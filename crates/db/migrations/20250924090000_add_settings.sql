@@ -0,0 +1,31 @@
+PRAGMA foreign_keys = ON;
+
+-- config.json and profiles.json as stored by the server, the files in the data directory are
+-- kept as an editable mirror of these rows
+CREATE TABLE settings (
+    name        TEXT PRIMARY KEY,
+    content     TEXT NOT NULL,
+    -- Bumped on every change, the replaced content moves to settings_revisions
+    revision    INTEGER NOT NULL DEFAULT 1,
+    updated_at  TEXT NOT NULL DEFAULT (datetime('now', 'subsec'))
+);
+
+-- Earlier contents of a settings document, for the history and rollbacks
+CREATE TABLE settings_revisions (
+    id          INTEGER PRIMARY KEY AUTOINCREMENT,
+    name        TEXT NOT NULL,
+    revision    INTEGER NOT NULL,
+    content     TEXT NOT NULL,
+    -- When the next revision replaced this one
+    replaced_at TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    UNIQUE (name, revision)
+);
+
+-- A user's changes on top of a settings document, as a JSON merge patch
+CREATE TABLE settings_overrides (
+    user_id     TEXT NOT NULL,
+    name        TEXT NOT NULL,
+    patch       TEXT NOT NULL,
+    updated_at  TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    PRIMARY KEY (user_id, name)
+);
@@ -0,0 +1,3 @@
+-- Start time of the process group leader, so a process that reused the pid after a restart
+-- isn't mistaken for the executor
+ALTER TABLE execution_processes ADD COLUMN pid_started_at TEXT;
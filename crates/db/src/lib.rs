@@ -1,4 +1,8 @@
-use std::{str::FromStr, sync::Arc};
+use std::{
+    path::{Path, PathBuf},
+    str::FromStr,
+    sync::Arc,
+};
 
 use sqlx::{
     Error, Pool, Sqlite, SqlitePool,
@@ -8,6 +12,40 @@ use utils::assets::asset_dir;
 
 pub mod models;
 
+/// Location of the live database
+pub fn database_path() -> PathBuf {
+    asset_dir().join("db.sqlite")
+}
+
+/// A snapshot staged here replaces the live database on the next start
+pub fn staged_restore_path() -> PathBuf {
+    asset_dir().join("db.sqlite.restore")
+}
+
+/// Swap in a staged restore before any connection is opened. The replaced database is
+/// kept as `db.sqlite.pre-restore`, together with its WAL files so it stays consistent.
+fn apply_staged_restore() -> std::io::Result<()> {
+    let staged = staged_restore_path();
+    if !staged.exists() {
+        return Ok(());
+    }
+
+    let live = database_path();
+    for suffix in ["", "-wal", "-shm"] {
+        let from = PathBuf::from(format!("{}{suffix}", live.display()));
+        let to = PathBuf::from(format!("{}.pre-restore{suffix}", live.display()));
+        if to.exists() {
+            std::fs::remove_file(&to)?;
+        }
+        if from.exists() {
+            std::fs::rename(&from, &to)?;
+        }
+    }
+    std::fs::rename(&staged, &live)?;
+    tracing::info!("Restored database from staged snapshot");
+    Ok(())
+}
+
 #[derive(Clone)]
 pub struct DBService {
     pub pool: Pool<Sqlite>,
@@ -15,10 +53,8 @@ pub struct DBService {
 
 impl DBService {
     pub async fn new() -> Result<DBService, Error> {
-        let database_url = format!(
-            "sqlite://{}",
-            asset_dir().join("db.sqlite").to_string_lossy()
-        );
+        apply_staged_restore()?;
+        let database_url = format!("sqlite://{}", database_path().to_string_lossy());
         let options = SqliteConnectOptions::from_str(&database_url)?.create_if_missing(true);
         let pool = SqlitePool::connect_with(options).await?;
         sqlx::migrate!("./migrations").run(&pool).await?;
@@ -49,10 +85,8 @@ impl DBService {
             + Sync
             + 'static,
     {
-        let database_url = format!(
-            "sqlite://{}",
-            asset_dir().join("db.sqlite").to_string_lossy()
-        );
+        apply_staged_restore()?;
+        let database_url = format!("sqlite://{}", database_path().to_string_lossy());
         let options = SqliteConnectOptions::from_str(&database_url)?.create_if_missing(true);
 
         let pool = if let Some(hook) = after_connect {
@@ -73,4 +107,13 @@ impl DBService {
         sqlx::migrate!("./migrations").run(&pool).await?;
         Ok(pool)
     }
+
+    /// Write a consistent snapshot of the database to `path`, which must not exist yet
+    pub async fn vacuum_into(&self, path: &Path) -> Result<(), Error> {
+        sqlx::query("VACUUM INTO $1")
+            .bind(path.to_string_lossy().to_string())
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
 }
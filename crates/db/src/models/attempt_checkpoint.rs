@@ -0,0 +1,167 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool, Type};
+use ts_rs::TS;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Copy, Type, Serialize, Deserialize, PartialEq, TS)]
+#[sqlx(type_name = "checkpoint_reason", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum CheckpointReason {
+    BeforeExecution,
+    /// Taken at intervals while a coding agent runs
+    Periodic,
+    AgentFinished,
+    /// The worktree as it was before rolling back to another checkpoint
+    BeforeRollback,
+}
+
+/// A snapshot of an attempt's worktree: a commit of its files, uncommitted ones included, on
+/// top of the HEAD it had
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct AttemptCheckpoint {
+    pub id: Uuid,
+    pub task_attempt_id: Uuid,
+    pub execution_process_id: Option<Uuid>,
+    pub reason: CheckpointReason,
+    pub commit_sha: String,
+    pub head_commit: String,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone)]
+pub struct CreateAttemptCheckpoint {
+    pub task_attempt_id: Uuid,
+    pub execution_process_id: Option<Uuid>,
+    pub reason: CheckpointReason,
+    pub commit_sha: String,
+    pub head_commit: String,
+}
+
+impl AttemptCheckpoint {
+    pub async fn create(
+        pool: &SqlitePool,
+        data: &CreateAttemptCheckpoint,
+        id: Uuid,
+    ) -> Result<Self, sqlx::Error> {
+        sqlx::query_as!(
+            AttemptCheckpoint,
+            r#"INSERT INTO attempt_checkpoints (id, task_attempt_id, execution_process_id, reason, commit_sha, head_commit)
+               VALUES ($1, $2, $3, $4, $5, $6)
+               RETURNING
+                id as "id!: Uuid",
+                task_attempt_id as "task_attempt_id!: Uuid",
+                execution_process_id as "execution_process_id: Uuid",
+                reason as "reason!: CheckpointReason",
+                commit_sha,
+                head_commit,
+                created_at as "created_at!: DateTime<Utc>""#,
+            id,
+            data.task_attempt_id,
+            data.execution_process_id,
+            data.reason,
+            data.commit_sha,
+            data.head_commit
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    pub async fn find_by_id(pool: &SqlitePool, id: Uuid) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            AttemptCheckpoint,
+            r#"SELECT
+                id as "id!: Uuid",
+                task_attempt_id as "task_attempt_id!: Uuid",
+                execution_process_id as "execution_process_id: Uuid",
+                reason as "reason!: CheckpointReason",
+                commit_sha,
+                head_commit,
+                created_at as "created_at!: DateTime<Utc>"
+               FROM attempt_checkpoints
+               WHERE id = $1"#,
+            id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// The attempt's checkpoints, newest first
+    pub async fn find_by_task_attempt_id(
+        pool: &SqlitePool,
+        task_attempt_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            AttemptCheckpoint,
+            r#"SELECT
+                id as "id!: Uuid",
+                task_attempt_id as "task_attempt_id!: Uuid",
+                execution_process_id as "execution_process_id: Uuid",
+                reason as "reason!: CheckpointReason",
+                commit_sha,
+                head_commit,
+                created_at as "created_at!: DateTime<Utc>"
+               FROM attempt_checkpoints
+               WHERE task_attempt_id = $1
+               ORDER BY created_at DESC"#,
+            task_attempt_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn find_latest(
+        pool: &SqlitePool,
+        task_attempt_id: Uuid,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            AttemptCheckpoint,
+            r#"SELECT
+                id as "id!: Uuid",
+                task_attempt_id as "task_attempt_id!: Uuid",
+                execution_process_id as "execution_process_id: Uuid",
+                reason as "reason!: CheckpointReason",
+                commit_sha,
+                head_commit,
+                created_at as "created_at!: DateTime<Utc>"
+               FROM attempt_checkpoints
+               WHERE task_attempt_id = $1
+               ORDER BY created_at DESC
+               LIMIT 1"#,
+            task_attempt_id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// Delete the attempt's checkpoints beyond the `keep` newest, returns the deleted ones
+    pub async fn delete_oldest(
+        pool: &SqlitePool,
+        task_attempt_id: Uuid,
+        keep: i64,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            AttemptCheckpoint,
+            r#"DELETE FROM attempt_checkpoints
+               WHERE task_attempt_id = $1
+                 AND id NOT IN (
+                    SELECT id FROM attempt_checkpoints
+                    WHERE task_attempt_id = $1
+                    ORDER BY created_at DESC
+                    LIMIT $2
+                 )
+               RETURNING
+                id as "id!: Uuid",
+                task_attempt_id as "task_attempt_id!: Uuid",
+                execution_process_id as "execution_process_id: Uuid",
+                reason as "reason!: CheckpointReason",
+                commit_sha,
+                head_commit,
+                created_at as "created_at!: DateTime<Utc>""#,
+            task_attempt_id,
+            keep
+        )
+        .fetch_all(pool)
+        .await
+    }
+}
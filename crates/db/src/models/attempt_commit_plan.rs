@@ -0,0 +1,155 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool, Type, types::Json};
+use ts_rs::TS;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Copy, Type, Serialize, Deserialize, PartialEq, TS)]
+#[sqlx(type_name = "commit_plan_status", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum CommitPlanStatus {
+    Planning,
+    /// The branch holds the planned commits
+    Applied,
+    /// The agent run failed, its output held no plan or the plan couldn't be applied
+    Failed,
+}
+
+/// One commit of a plan, with the files it takes in their final state
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+pub struct PlannedCommit {
+    pub message: String,
+    pub files: Vec<String>,
+}
+
+/// Split of an attempt's changes into logical commits, planned by an agent run
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct AttemptCommitPlan {
+    pub id: Uuid,
+    pub task_attempt_id: Uuid,
+    pub execution_process_id: Uuid,
+    pub status: CommitPlanStatus,
+    /// In commit order, files no commit names end up in a last commit
+    #[ts(type = "Array<PlannedCommit>")]
+    pub commits: Json<Vec<PlannedCommit>>,
+    /// Branch head before the split
+    pub original_head: Option<String>,
+    pub error: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub applied_at: Option<DateTime<Utc>>,
+}
+
+impl AttemptCommitPlan {
+    pub async fn create(
+        pool: &SqlitePool,
+        task_attempt_id: Uuid,
+        execution_process_id: Uuid,
+    ) -> Result<Self, sqlx::Error> {
+        let id = Uuid::new_v4();
+        sqlx::query_as!(
+            AttemptCommitPlan,
+            r#"INSERT INTO attempt_commit_plans (id, task_attempt_id, execution_process_id)
+               VALUES ($1, $2, $3)
+               RETURNING
+                id as "id!: Uuid",
+                task_attempt_id as "task_attempt_id!: Uuid",
+                execution_process_id as "execution_process_id!: Uuid",
+                status as "status!: CommitPlanStatus",
+                commits as "commits!: Json<Vec<PlannedCommit>>",
+                original_head,
+                error,
+                created_at as "created_at!: DateTime<Utc>",
+                applied_at as "applied_at: DateTime<Utc>""#,
+            id,
+            task_attempt_id,
+            execution_process_id
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    pub async fn find_latest_by_task_attempt_id(
+        pool: &SqlitePool,
+        task_attempt_id: Uuid,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            AttemptCommitPlan,
+            r#"SELECT
+                id as "id!: Uuid",
+                task_attempt_id as "task_attempt_id!: Uuid",
+                execution_process_id as "execution_process_id!: Uuid",
+                status as "status!: CommitPlanStatus",
+                commits as "commits!: Json<Vec<PlannedCommit>>",
+                original_head,
+                error,
+                created_at as "created_at!: DateTime<Utc>",
+                applied_at as "applied_at: DateTime<Utc>"
+               FROM attempt_commit_plans
+               WHERE task_attempt_id = $1
+               ORDER BY created_at DESC
+               LIMIT 1"#,
+            task_attempt_id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    pub async fn find_by_execution_process_id(
+        pool: &SqlitePool,
+        execution_process_id: Uuid,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            AttemptCommitPlan,
+            r#"SELECT
+                id as "id!: Uuid",
+                task_attempt_id as "task_attempt_id!: Uuid",
+                execution_process_id as "execution_process_id!: Uuid",
+                status as "status!: CommitPlanStatus",
+                commits as "commits!: Json<Vec<PlannedCommit>>",
+                original_head,
+                error,
+                created_at as "created_at!: DateTime<Utc>",
+                applied_at as "applied_at: DateTime<Utc>"
+               FROM attempt_commit_plans
+               WHERE execution_process_id = $1"#,
+            execution_process_id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    pub async fn finish(
+        pool: &SqlitePool,
+        id: Uuid,
+        status: CommitPlanStatus,
+        commits: &[PlannedCommit],
+        original_head: Option<&str>,
+        error: Option<&str>,
+    ) -> Result<Self, sqlx::Error> {
+        let commits = Json(commits);
+        sqlx::query_as!(
+            AttemptCommitPlan,
+            r#"UPDATE attempt_commit_plans
+               SET status = $2, commits = $3, original_head = $4, error = $5,
+                   applied_at = CASE WHEN $2 = 'applied' THEN datetime('now', 'subsec') END
+               WHERE id = $1
+               RETURNING
+                id as "id!: Uuid",
+                task_attempt_id as "task_attempt_id!: Uuid",
+                execution_process_id as "execution_process_id!: Uuid",
+                status as "status!: CommitPlanStatus",
+                commits as "commits!: Json<Vec<PlannedCommit>>",
+                original_head,
+                error,
+                created_at as "created_at!: DateTime<Utc>",
+                applied_at as "applied_at: DateTime<Utc>""#,
+            id,
+            status,
+            commits,
+            original_head,
+            error
+        )
+        .fetch_one(pool)
+        .await
+    }
+}
@@ -0,0 +1,106 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool, types::Json};
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// Reason an attempt's changes deserve a careful review
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum RiskFlag {
+    /// More lines added and removed than a reviewer can read closely
+    LargeDiff {
+        #[ts(type = "number")]
+        lines_changed: i64,
+    },
+    /// Files outside the paths the repository expects agents to touch
+    OutsideExpectedPaths { files: Vec<String> },
+    /// CI or workflow definitions
+    CiChanges { files: Vec<String> },
+    /// Mostly removed code
+    DeletionHeavy {
+        #[ts(type = "number")]
+        lines_removed: i64,
+        #[ts(type = "number")]
+        lines_added: i64,
+    },
+    /// Dependency manifests or lockfiles
+    DependencyChanges { files: Vec<String> },
+}
+
+impl RiskFlag {
+    /// Serialized `kind` tag of the flag
+    pub fn kind(&self) -> &'static str {
+        match self {
+            RiskFlag::LargeDiff { .. } => "large_diff",
+            RiskFlag::OutsideExpectedPaths { .. } => "outside_expected_paths",
+            RiskFlag::CiChanges { .. } => "ci_changes",
+            RiskFlag::DeletionHeavy { .. } => "deletion_heavy",
+            RiskFlag::DependencyChanges { .. } => "dependency_changes",
+        }
+    }
+}
+
+/// Size and risk signals of an attempt's diff against its base branch
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, TS)]
+pub struct DiffRisk {
+    #[ts(type = "number")]
+    pub files_changed: i64,
+    #[ts(type = "number")]
+    pub lines_added: i64,
+    #[ts(type = "number")]
+    pub lines_removed: i64,
+    pub flags: Vec<RiskFlag>,
+}
+
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct AttemptDiffRisk {
+    pub task_attempt_id: Uuid,
+    #[ts(type = "DiffRisk")]
+    pub risk: Json<DiffRisk>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl AttemptDiffRisk {
+    pub async fn find_by_task_attempt_id(
+        pool: &SqlitePool,
+        task_attempt_id: Uuid,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            AttemptDiffRisk,
+            r#"SELECT
+                task_attempt_id as "task_attempt_id!: Uuid",
+                risk as "risk!: Json<DiffRisk>",
+                updated_at as "updated_at!: DateTime<Utc>"
+               FROM attempt_diff_risks
+               WHERE task_attempt_id = $1"#,
+            task_attempt_id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    pub async fn upsert(
+        pool: &SqlitePool,
+        task_attempt_id: Uuid,
+        risk: &DiffRisk,
+    ) -> Result<Self, sqlx::Error> {
+        let risk = Json(risk);
+        sqlx::query_as!(
+            AttemptDiffRisk,
+            r#"INSERT INTO attempt_diff_risks (task_attempt_id, risk)
+               VALUES ($1, $2)
+               ON CONFLICT (task_attempt_id) DO UPDATE SET
+                risk = excluded.risk,
+                updated_at = datetime('now', 'subsec')
+               RETURNING
+                task_attempt_id as "task_attempt_id!: Uuid",
+                risk as "risk!: Json<DiffRisk>",
+                updated_at as "updated_at!: DateTime<Utc>""#,
+            task_attempt_id,
+            risk
+        )
+        .fetch_one(pool)
+        .await
+    }
+}
@@ -0,0 +1,135 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool};
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// Another project's repository an attempt changes alongside its own, in a worktree nested
+/// under the attempt's worktree so the agent sees both
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct AttemptLinkedRepo {
+    pub id: Uuid,
+    pub task_attempt_id: Uuid,
+    pub project_id: Uuid,
+    pub base_branch: String,
+    /// `None` until the worktree is created with the attempt's
+    pub branch: Option<String>,
+    pub container_ref: Option<String>,
+    pub merge_commit: Option<String>,
+    pub pr_url: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// A project to change in the same attempt
+#[derive(Debug, Clone, Deserialize, TS)]
+pub struct CreateAttemptLinkedRepo {
+    pub project_id: Uuid,
+    /// Branch or tag the worktree starts from, the project's current branch when `None`
+    #[serde(default)]
+    pub base_branch: Option<String>,
+}
+
+impl AttemptLinkedRepo {
+    pub async fn create(
+        pool: &SqlitePool,
+        task_attempt_id: Uuid,
+        project_id: Uuid,
+        base_branch: &str,
+    ) -> Result<Self, sqlx::Error> {
+        let id = Uuid::new_v4();
+        sqlx::query_as!(
+            AttemptLinkedRepo,
+            r#"INSERT INTO attempt_linked_repos (id, task_attempt_id, project_id, base_branch)
+               VALUES ($1, $2, $3, $4)
+               RETURNING
+                id as "id!: Uuid",
+                task_attempt_id as "task_attempt_id!: Uuid",
+                project_id as "project_id!: Uuid",
+                base_branch,
+                branch,
+                container_ref,
+                merge_commit,
+                pr_url,
+                created_at as "created_at!: DateTime<Utc>""#,
+            id,
+            task_attempt_id,
+            project_id,
+            base_branch
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    /// Linked repositories of an attempt, in the order they were added
+    pub async fn find_by_task_attempt_id(
+        pool: &SqlitePool,
+        task_attempt_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            AttemptLinkedRepo,
+            r#"SELECT
+                id as "id!: Uuid",
+                task_attempt_id as "task_attempt_id!: Uuid",
+                project_id as "project_id!: Uuid",
+                base_branch,
+                branch,
+                container_ref,
+                merge_commit,
+                pr_url,
+                created_at as "created_at!: DateTime<Utc>"
+               FROM attempt_linked_repos
+               WHERE task_attempt_id = $1
+               ORDER BY created_at ASC"#,
+            task_attempt_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn update_worktree(
+        pool: &SqlitePool,
+        id: Uuid,
+        branch: &str,
+        container_ref: &str,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            "UPDATE attempt_linked_repos SET branch = $2, container_ref = $3 WHERE id = $1",
+            id,
+            branch,
+            container_ref
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn update_merge_commit(
+        pool: &SqlitePool,
+        id: Uuid,
+        merge_commit: &str,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            "UPDATE attempt_linked_repos SET merge_commit = $2 WHERE id = $1",
+            id,
+            merge_commit
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn update_pr_url(
+        pool: &SqlitePool,
+        id: Uuid,
+        pr_url: &str,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            "UPDATE attempt_linked_repos SET pr_url = $2 WHERE id = $1",
+            id,
+            pr_url
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,104 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool, Type};
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// Phase of a plan-then-execute attempt
+#[derive(Debug, Clone, Copy, Type, Serialize, Deserialize, PartialEq, TS)]
+#[sqlx(type_name = "plan_status", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum PlanStatus {
+    /// The agent is drafting the plan
+    Planning,
+    /// The plan waits for the user to approve or reject it
+    AwaitingApproval,
+    /// The execution phase runs or ran with the approved plan
+    Approved,
+    Rejected,
+    /// The planning run ended without a plan
+    Failed,
+}
+
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct AttemptPlan {
+    pub task_attempt_id: Uuid,
+    pub status: PlanStatus,
+    pub plan: Option<String>,
+    /// Variant of the attempt's profile that executes the approved plan
+    pub execute_variant: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl AttemptPlan {
+    pub async fn create(
+        pool: &SqlitePool,
+        task_attempt_id: Uuid,
+        execute_variant: Option<&str>,
+    ) -> Result<Self, sqlx::Error> {
+        sqlx::query_as!(
+            AttemptPlan,
+            r#"INSERT INTO attempt_plans (task_attempt_id, execute_variant)
+               VALUES ($1, $2)
+               RETURNING
+                task_attempt_id as "task_attempt_id!: Uuid",
+                status as "status!: PlanStatus",
+                plan,
+                execute_variant,
+                created_at as "created_at!: DateTime<Utc>",
+                updated_at as "updated_at!: DateTime<Utc>""#,
+            task_attempt_id,
+            execute_variant
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    pub async fn find_by_task_attempt_id(
+        pool: &SqlitePool,
+        task_attempt_id: Uuid,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            AttemptPlan,
+            r#"SELECT
+                task_attempt_id as "task_attempt_id!: Uuid",
+                status as "status!: PlanStatus",
+                plan,
+                execute_variant,
+                created_at as "created_at!: DateTime<Utc>",
+                updated_at as "updated_at!: DateTime<Utc>"
+               FROM attempt_plans
+               WHERE task_attempt_id = $1"#,
+            task_attempt_id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    pub async fn update(
+        pool: &SqlitePool,
+        task_attempt_id: Uuid,
+        status: PlanStatus,
+        plan: Option<&str>,
+    ) -> Result<Self, sqlx::Error> {
+        sqlx::query_as!(
+            AttemptPlan,
+            r#"UPDATE attempt_plans
+               SET status = $2, plan = $3, updated_at = datetime('now', 'subsec')
+               WHERE task_attempt_id = $1
+               RETURNING
+                task_attempt_id as "task_attempt_id!: Uuid",
+                status as "status!: PlanStatus",
+                plan,
+                execute_variant,
+                created_at as "created_at!: DateTime<Utc>",
+                updated_at as "updated_at!: DateTime<Utc>""#,
+            task_attempt_id,
+            status,
+            plan
+        )
+        .fetch_one(pool)
+        .await
+    }
+}
@@ -0,0 +1,97 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool, Type, types::Json};
+use ts_rs::TS;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Copy, Type, Serialize, Deserialize, PartialEq, TS)]
+#[sqlx(type_name = "review_verdict", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum ReviewVerdict {
+    Approved,
+    ChangesRequested,
+    /// The reviewer run failed or its output held no review
+    Failed,
+}
+
+/// Remark of the reviewer, tied to a file and line when it points at one
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+pub struct ReviewComment {
+    #[serde(default)]
+    pub file: Option<String>,
+    #[serde(default)]
+    pub line: Option<u32>,
+    pub comment: String,
+}
+
+/// Review of an attempt's changes by the project's reviewer profile
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct AttemptReview {
+    pub id: Uuid,
+    pub task_attempt_id: Uuid,
+    pub execution_process_id: Uuid,
+    pub verdict: ReviewVerdict,
+    pub summary: String,
+    #[ts(type = "Array<ReviewComment>")]
+    pub comments: Json<Vec<ReviewComment>>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl AttemptReview {
+    pub async fn create(
+        pool: &SqlitePool,
+        task_attempt_id: Uuid,
+        execution_process_id: Uuid,
+        verdict: ReviewVerdict,
+        summary: &str,
+        comments: &[ReviewComment],
+    ) -> Result<Self, sqlx::Error> {
+        let id = Uuid::new_v4();
+        let comments = Json(comments);
+        sqlx::query_as!(
+            AttemptReview,
+            r#"INSERT INTO attempt_reviews (id, task_attempt_id, execution_process_id, verdict, summary, comments)
+               VALUES ($1, $2, $3, $4, $5, $6)
+               RETURNING
+                id as "id!: Uuid",
+                task_attempt_id as "task_attempt_id!: Uuid",
+                execution_process_id as "execution_process_id!: Uuid",
+                verdict as "verdict!: ReviewVerdict",
+                summary,
+                comments as "comments!: Json<Vec<ReviewComment>>",
+                created_at as "created_at!: DateTime<Utc>""#,
+            id,
+            task_attempt_id,
+            execution_process_id,
+            verdict,
+            summary,
+            comments
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    /// Reviews of an attempt, newest first
+    pub async fn find_by_task_attempt_id(
+        pool: &SqlitePool,
+        task_attempt_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            AttemptReview,
+            r#"SELECT
+                id as "id!: Uuid",
+                task_attempt_id as "task_attempt_id!: Uuid",
+                execution_process_id as "execution_process_id!: Uuid",
+                verdict as "verdict!: ReviewVerdict",
+                summary,
+                comments as "comments!: Json<Vec<ReviewComment>>",
+                created_at as "created_at!: DateTime<Utc>"
+               FROM attempt_reviews
+               WHERE task_attempt_id = $1
+               ORDER BY created_at DESC"#,
+            task_attempt_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+}
@@ -0,0 +1,117 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool};
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// Read-only link showing an attempt's transcript and diff at `/share/{token}`
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct AttemptShareLink {
+    pub id: Uuid,
+    pub task_attempt_id: Uuid,
+    pub token: String,
+    /// `None` for links that work until they are revoked
+    pub expires_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Deserialize, TS)]
+pub struct CreateAttemptShareLink {
+    /// Hours until the link stops working, it never expires when `None`
+    #[serde(default)]
+    pub expires_in_hours: Option<u32>,
+}
+
+fn generate_token() -> String {
+    format!("{}{}", Uuid::new_v4().simple(), Uuid::new_v4().simple())
+}
+
+impl AttemptShareLink {
+    pub fn is_expired(&self, now: DateTime<Utc>) -> bool {
+        self.expires_at.is_some_and(|expires_at| expires_at <= now)
+    }
+
+    pub async fn create(
+        pool: &SqlitePool,
+        task_attempt_id: Uuid,
+        expires_at: Option<DateTime<Utc>>,
+    ) -> Result<Self, sqlx::Error> {
+        let id = Uuid::new_v4();
+        let token = generate_token();
+        sqlx::query_as!(
+            AttemptShareLink,
+            r#"INSERT INTO attempt_share_links (id, task_attempt_id, token, expires_at)
+               VALUES ($1, $2, $3, $4)
+               RETURNING
+                id as "id!: Uuid",
+                task_attempt_id as "task_attempt_id!: Uuid",
+                token,
+                expires_at as "expires_at: DateTime<Utc>",
+                created_at as "created_at!: DateTime<Utc>""#,
+            id,
+            task_attempt_id,
+            token,
+            expires_at
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    /// Links of an attempt, newest first, expired ones included
+    pub async fn find_by_task_attempt_id(
+        pool: &SqlitePool,
+        task_attempt_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            AttemptShareLink,
+            r#"SELECT
+                id as "id!: Uuid",
+                task_attempt_id as "task_attempt_id!: Uuid",
+                token,
+                expires_at as "expires_at: DateTime<Utc>",
+                created_at as "created_at!: DateTime<Utc>"
+               FROM attempt_share_links
+               WHERE task_attempt_id = $1
+               ORDER BY created_at DESC"#,
+            task_attempt_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn find_by_token(
+        pool: &SqlitePool,
+        token: &str,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            AttemptShareLink,
+            r#"SELECT
+                id as "id!: Uuid",
+                task_attempt_id as "task_attempt_id!: Uuid",
+                token,
+                expires_at as "expires_at: DateTime<Utc>",
+                created_at as "created_at!: DateTime<Utc>"
+               FROM attempt_share_links
+               WHERE token = $1"#,
+            token
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// Revoke a link of the attempt, the number of links deleted
+    pub async fn delete(
+        pool: &SqlitePool,
+        id: Uuid,
+        task_attempt_id: Uuid,
+    ) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!(
+            "DELETE FROM attempt_share_links WHERE id = $1 AND task_attempt_id = $2",
+            id,
+            task_attempt_id
+        )
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected())
+    }
+}
@@ -0,0 +1,82 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool};
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// A merge of an attempt undone by a commit reverting it on the base branch
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct AttemptUnmerge {
+    pub id: Uuid,
+    pub task_attempt_id: Uuid,
+    pub base_branch: String,
+    pub merge_commit: String,
+    pub revert_commit: String,
+    /// Commits the base branch had on top of the merge when it was reverted
+    #[ts(type = "number")]
+    pub subsequent_commits: i64,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone)]
+pub struct CreateAttemptUnmerge {
+    pub task_attempt_id: Uuid,
+    pub base_branch: String,
+    pub merge_commit: String,
+    pub revert_commit: String,
+    pub subsequent_commits: i64,
+}
+
+impl AttemptUnmerge {
+    pub async fn create(
+        pool: &SqlitePool,
+        data: &CreateAttemptUnmerge,
+        id: Uuid,
+    ) -> Result<Self, sqlx::Error> {
+        sqlx::query_as!(
+            AttemptUnmerge,
+            r#"INSERT INTO attempt_unmerges (id, task_attempt_id, base_branch, merge_commit, revert_commit, subsequent_commits)
+               VALUES ($1, $2, $3, $4, $5, $6)
+               RETURNING
+                id as "id!: Uuid",
+                task_attempt_id as "task_attempt_id!: Uuid",
+                base_branch,
+                merge_commit,
+                revert_commit,
+                subsequent_commits,
+                created_at as "created_at!: DateTime<Utc>""#,
+            id,
+            data.task_attempt_id,
+            data.base_branch,
+            data.merge_commit,
+            data.revert_commit,
+            data.subsequent_commits
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    /// The attempt's undone merges, newest first
+    pub async fn find_by_task_attempt_id(
+        pool: &SqlitePool,
+        task_attempt_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            AttemptUnmerge,
+            r#"SELECT
+                id as "id!: Uuid",
+                task_attempt_id as "task_attempt_id!: Uuid",
+                base_branch,
+                merge_commit,
+                revert_commit,
+                subsequent_commits,
+                created_at as "created_at!: DateTime<Utc>"
+               FROM attempt_unmerges
+               WHERE task_attempt_id = $1
+               ORDER BY created_at DESC"#,
+            task_attempt_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+}
@@ -0,0 +1,100 @@
+use chrono::{DateTime, Utc};
+use sqlx::{FromRow, SqlitePool};
+use uuid::Uuid;
+
+/// How an attempt went and what it used, for usage statistics
+#[derive(Debug, Clone, FromRow)]
+pub struct AttemptUsage {
+    pub id: Uuid,
+    pub project_id: Uuid,
+    pub project_name: String,
+    pub profile: String,
+    pub created_at: DateTime<Utc>,
+    /// Whether the attempt's latest coding agent run completed
+    pub succeeded: bool,
+    pub merged: bool,
+    pub cost_usd: f64,
+    pub input_tokens: i64,
+    pub output_tokens: i64,
+}
+
+impl AttemptUsage {
+    /// Attempts created since `since`, a UTC `YYYY-MM-DD HH:MM:SS` timestamp, oldest first
+    pub async fn find_since(pool: &SqlitePool, since: &str) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            AttemptUsage,
+            r#"SELECT
+                ta.id as "id!: Uuid",
+                t.project_id as "project_id!: Uuid",
+                p.name as "project_name!",
+                ta.profile as "profile!",
+                ta.created_at as "created_at!: DateTime<Utc>",
+                COALESCE((SELECT ep.status = 'completed'
+                          FROM execution_processes ep
+                          WHERE ep.task_attempt_id = ta.id AND ep.run_reason = 'codingagent'
+                          ORDER BY ep.created_at DESC
+                          LIMIT 1), 0) as "succeeded!: bool",
+                (ta.merge_commit IS NOT NULL OR ta.pr_status = 'merged') as "merged!: bool",
+                COALESCE((SELECT SUM(ec.cost_usd)
+                          FROM execution_costs ec
+                          JOIN execution_processes ep ON ep.id = ec.execution_process_id
+                          WHERE ep.task_attempt_id = ta.id), 0.0) as "cost_usd!: f64",
+                COALESCE((SELECT SUM(u.input_tokens)
+                          FROM execution_token_usage u
+                          JOIN execution_processes ep ON ep.id = u.execution_process_id
+                          WHERE ep.task_attempt_id = ta.id), 0) as "input_tokens!: i64",
+                COALESCE((SELECT SUM(u.output_tokens)
+                          FROM execution_token_usage u
+                          JOIN execution_processes ep ON ep.id = u.execution_process_id
+                          WHERE ep.task_attempt_id = ta.id), 0) as "output_tokens!: i64"
+               FROM task_attempts ta
+               JOIN tasks t ON t.id = ta.task_id
+               JOIN projects p ON p.id = t.project_id
+               WHERE ta.created_at >= $1
+               ORDER BY ta.created_at"#,
+            since
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn find_by_id(
+        pool: &SqlitePool,
+        task_attempt_id: Uuid,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            AttemptUsage,
+            r#"SELECT
+                ta.id as "id!: Uuid",
+                t.project_id as "project_id!: Uuid",
+                p.name as "project_name!",
+                ta.profile as "profile!",
+                ta.created_at as "created_at!: DateTime<Utc>",
+                COALESCE((SELECT ep.status = 'completed'
+                          FROM execution_processes ep
+                          WHERE ep.task_attempt_id = ta.id AND ep.run_reason = 'codingagent'
+                          ORDER BY ep.created_at DESC
+                          LIMIT 1), 0) as "succeeded!: bool",
+                (ta.merge_commit IS NOT NULL OR ta.pr_status = 'merged') as "merged!: bool",
+                COALESCE((SELECT SUM(ec.cost_usd)
+                          FROM execution_costs ec
+                          JOIN execution_processes ep ON ep.id = ec.execution_process_id
+                          WHERE ep.task_attempt_id = ta.id), 0.0) as "cost_usd!: f64",
+                COALESCE((SELECT SUM(u.input_tokens)
+                          FROM execution_token_usage u
+                          JOIN execution_processes ep ON ep.id = u.execution_process_id
+                          WHERE ep.task_attempt_id = ta.id), 0) as "input_tokens!: i64",
+                COALESCE((SELECT SUM(u.output_tokens)
+                          FROM execution_token_usage u
+                          JOIN execution_processes ep ON ep.id = u.execution_process_id
+                          WHERE ep.task_attempt_id = ta.id), 0) as "output_tokens!: i64"
+               FROM task_attempts ta
+               JOIN tasks t ON t.id = ta.task_id
+               JOIN projects p ON p.id = t.project_id
+               WHERE ta.id = $1"#,
+            task_attempt_id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+}
@@ -0,0 +1,243 @@
+use std::path::PathBuf;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool, Type, types::Json};
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// How an attempt branch takes in new commits of its base branch
+#[derive(Debug, Clone, Copy, Type, Serialize, Deserialize, PartialEq, TS)]
+#[sqlx(type_name = "branch_sync_strategy", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum BranchSyncStrategy {
+    /// Replay the attempt's commits on top of the base branch
+    Rebase,
+    /// Merge the base branch into the attempt branch
+    Merge,
+}
+
+/// When attempt branches are synced with their base branch
+#[derive(Debug, Clone, Copy, Type, Serialize, Deserialize, PartialEq, TS)]
+#[sqlx(type_name = "branch_sync_schedule", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum BranchSyncSchedule {
+    Off,
+    /// Once a night, after 2am local time
+    Nightly,
+    /// Whenever the base branch points to a new commit
+    OnBaseChange,
+}
+
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct ProjectBranchSync {
+    pub project_id: Uuid,
+    pub strategy: BranchSyncStrategy,
+    pub schedule: BranchSyncSchedule,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize, TS)]
+pub struct UpdateProjectBranchSync {
+    pub strategy: BranchSyncStrategy,
+    pub schedule: BranchSyncSchedule,
+}
+
+#[derive(Debug, Clone, Copy, Type, Serialize, Deserialize, PartialEq, TS)]
+#[sqlx(type_name = "branch_sync_status", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum BranchSyncStatus {
+    /// The branch already contained the base branch
+    UpToDate,
+    Synced,
+    /// The base branch conflicts with the attempt, the branch was left untouched
+    Conflicts,
+    Failed,
+}
+
+/// Outcome of the latest automatic sync of an attempt branch
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct AttemptBranchSync {
+    pub task_attempt_id: Uuid,
+    pub status: BranchSyncStatus,
+    /// Base branch commit the sync ran against
+    pub base_commit: Option<String>,
+    #[ts(type = "Array<string>")]
+    pub conflicted_files: Json<Vec<String>>,
+    pub error: Option<String>,
+    pub synced_at: DateTime<Utc>,
+}
+
+/// An attempt branch of a project with branch sync turned on
+#[derive(Debug, Clone)]
+pub struct BranchSyncCandidate {
+    pub task_attempt_id: Uuid,
+    pub git_repo_path: PathBuf,
+    /// Worktree to update along with the branch, `None` once it was cleaned up
+    pub worktree_path: Option<PathBuf>,
+    pub branch: String,
+    pub base_branch: String,
+    pub strategy: BranchSyncStrategy,
+    pub schedule: BranchSyncSchedule,
+    pub last_base_commit: Option<String>,
+    pub last_synced_at: Option<DateTime<Utc>>,
+}
+
+impl ProjectBranchSync {
+    pub async fn find_by_project_id(
+        pool: &SqlitePool,
+        project_id: Uuid,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            ProjectBranchSync,
+            r#"SELECT
+                project_id as "project_id!: Uuid",
+                strategy as "strategy!: BranchSyncStrategy",
+                schedule as "schedule!: BranchSyncSchedule",
+                updated_at as "updated_at!: DateTime<Utc>"
+               FROM project_branch_sync
+               WHERE project_id = $1"#,
+            project_id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    pub async fn upsert(
+        pool: &SqlitePool,
+        project_id: Uuid,
+        data: &UpdateProjectBranchSync,
+    ) -> Result<Self, sqlx::Error> {
+        sqlx::query_as!(
+            ProjectBranchSync,
+            r#"INSERT INTO project_branch_sync (project_id, strategy, schedule)
+               VALUES ($1, $2, $3)
+               ON CONFLICT (project_id) DO UPDATE SET
+                strategy = excluded.strategy,
+                schedule = excluded.schedule,
+                updated_at = datetime('now', 'subsec')
+               RETURNING
+                project_id as "project_id!: Uuid",
+                strategy as "strategy!: BranchSyncStrategy",
+                schedule as "schedule!: BranchSyncSchedule",
+                updated_at as "updated_at!: DateTime<Utc>""#,
+            project_id,
+            data.strategy,
+            data.schedule
+        )
+        .fetch_one(pool)
+        .await
+    }
+}
+
+impl AttemptBranchSync {
+    pub async fn find_by_task_attempt_id(
+        pool: &SqlitePool,
+        task_attempt_id: Uuid,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            AttemptBranchSync,
+            r#"SELECT
+                task_attempt_id as "task_attempt_id!: Uuid",
+                status as "status!: BranchSyncStatus",
+                base_commit,
+                conflicted_files as "conflicted_files!: Json<Vec<String>>",
+                error,
+                synced_at as "synced_at!: DateTime<Utc>"
+               FROM attempt_branch_syncs
+               WHERE task_attempt_id = $1"#,
+            task_attempt_id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    pub async fn upsert(
+        pool: &SqlitePool,
+        task_attempt_id: Uuid,
+        status: BranchSyncStatus,
+        base_commit: Option<&str>,
+        conflicted_files: &[String],
+        error: Option<&str>,
+    ) -> Result<Self, sqlx::Error> {
+        let conflicted_files = Json(conflicted_files);
+        sqlx::query_as!(
+            AttemptBranchSync,
+            r#"INSERT INTO attempt_branch_syncs (task_attempt_id, status, base_commit, conflicted_files, error)
+               VALUES ($1, $2, $3, $4, $5)
+               ON CONFLICT (task_attempt_id) DO UPDATE SET
+                status = excluded.status,
+                base_commit = excluded.base_commit,
+                conflicted_files = excluded.conflicted_files,
+                error = excluded.error,
+                synced_at = datetime('now', 'subsec')
+               RETURNING
+                task_attempt_id as "task_attempt_id!: Uuid",
+                status as "status!: BranchSyncStatus",
+                base_commit,
+                conflicted_files as "conflicted_files!: Json<Vec<String>>",
+                error,
+                synced_at as "synced_at!: DateTime<Utc>""#,
+            task_attempt_id,
+            status,
+            base_commit,
+            conflicted_files,
+            error
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    /// Unmerged attempt branches of open tasks in projects with a sync schedule, skipping
+    /// attempts with a running process
+    pub async fn find_candidates(
+        pool: &SqlitePool,
+    ) -> Result<Vec<BranchSyncCandidate>, sqlx::Error> {
+        let records = sqlx::query!(
+            r#"SELECT
+                ta.id               AS "task_attempt_id!: Uuid",
+                p.git_repo_path     AS "git_repo_path!",
+                ta.container_ref,
+                ta.worktree_deleted AS "worktree_deleted!: bool",
+                ta.branch           AS "branch!",
+                ta.base_branch,
+                s.strategy          AS "strategy!: BranchSyncStrategy",
+                s.schedule          AS "schedule!: BranchSyncSchedule",
+                abs.base_commit     AS "last_base_commit?",
+                abs.synced_at       AS "last_synced_at?: DateTime<Utc>"
+               FROM task_attempts ta
+               JOIN tasks t ON ta.task_id = t.id
+               JOIN projects p ON t.project_id = p.id
+               JOIN project_branch_sync s ON s.project_id = p.id
+               LEFT JOIN attempt_branch_syncs abs ON abs.task_attempt_id = ta.id
+               WHERE s.schedule != 'off'
+                 AND ta.branch IS NOT NULL
+                 AND ta.merge_commit IS NULL
+                 AND (ta.pr_status IS NULL OR ta.pr_status = 'open')
+                 AND t.status NOT IN ('done', 'cancelled')
+                 AND ta.id NOT IN (
+                    SELECT task_attempt_id FROM execution_processes WHERE completed_at IS NULL
+                 )"#
+        )
+        .fetch_all(pool)
+        .await?;
+
+        Ok(records
+            .into_iter()
+            .map(|r| BranchSyncCandidate {
+                task_attempt_id: r.task_attempt_id,
+                git_repo_path: PathBuf::from(r.git_repo_path),
+                worktree_path: r
+                    .container_ref
+                    .filter(|_| !r.worktree_deleted)
+                    .map(PathBuf::from),
+                branch: r.branch,
+                base_branch: r.base_branch,
+                strategy: r.strategy,
+                schedule: r.schedule,
+                last_base_commit: r.last_base_commit,
+                last_synced_at: r.last_synced_at,
+            })
+            .collect())
+    }
+}
@@ -0,0 +1,137 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool, types::Json};
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// GitHub Actions workflows watched for failures on a project's base branch
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct ProjectCiTriage {
+    pub project_id: Uuid,
+    pub enabled: bool,
+    /// Workflow files watched, e.g. `ci.yml`, every workflow when empty
+    #[ts(type = "Array<string>")]
+    pub workflows: Json<Vec<String>>,
+    /// Branch watched, the repository's default branch when `None`
+    pub branch: Option<String>,
+    /// Start an attempt on each card a failure opens
+    pub start_agent: bool,
+    /// Newest workflow run already looked at
+    #[ts(type = "number | null")]
+    pub last_run_id: Option<i64>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize, TS)]
+pub struct UpdateProjectCiTriage {
+    pub enabled: bool,
+    pub workflows: Vec<String>,
+    pub branch: Option<String>,
+    pub start_agent: bool,
+}
+
+impl ProjectCiTriage {
+    /// Whether runs of the workflow at `path`, e.g. `.github/workflows/ci.yml`, are watched
+    pub fn watches(&self, path: &str) -> bool {
+        let file = path.rsplit('/').next().unwrap_or(path);
+        self.workflows.is_empty() || self.workflows.iter().any(|w| w == file || w == path)
+    }
+
+    pub async fn find_by_project_id(
+        pool: &SqlitePool,
+        project_id: Uuid,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            ProjectCiTriage,
+            r#"SELECT
+                project_id as "project_id!: Uuid",
+                enabled as "enabled!: bool",
+                workflows as "workflows!: Json<Vec<String>>",
+                branch,
+                start_agent as "start_agent!: bool",
+                last_run_id,
+                updated_at as "updated_at!: DateTime<Utc>"
+               FROM project_ci_triage
+               WHERE project_id = $1"#,
+            project_id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    pub async fn find_enabled(pool: &SqlitePool) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            ProjectCiTriage,
+            r#"SELECT
+                project_id as "project_id!: Uuid",
+                enabled as "enabled!: bool",
+                workflows as "workflows!: Json<Vec<String>>",
+                branch,
+                start_agent as "start_agent!: bool",
+                last_run_id,
+                updated_at as "updated_at!: DateTime<Utc>"
+               FROM project_ci_triage
+               WHERE enabled = 1"#
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Save the settings. Changing what's watched starts over from the newest run, so
+    /// failures from before aren't filed.
+    pub async fn upsert(
+        pool: &SqlitePool,
+        project_id: Uuid,
+        data: &UpdateProjectCiTriage,
+    ) -> Result<Self, sqlx::Error> {
+        let workflows = Json(&data.workflows);
+        sqlx::query_as!(
+            ProjectCiTriage,
+            r#"INSERT INTO project_ci_triage (project_id, enabled, workflows, branch, start_agent)
+               VALUES ($1, $2, $3, $4, $5)
+               ON CONFLICT (project_id) DO UPDATE SET
+                last_run_id = CASE
+                    WHEN project_ci_triage.branch IS excluded.branch
+                     AND project_ci_triage.workflows = excluded.workflows
+                     AND project_ci_triage.enabled = excluded.enabled
+                    THEN project_ci_triage.last_run_id
+                    ELSE NULL
+                END,
+                enabled = excluded.enabled,
+                workflows = excluded.workflows,
+                branch = excluded.branch,
+                start_agent = excluded.start_agent,
+                updated_at = datetime('now', 'subsec')
+               RETURNING
+                project_id as "project_id!: Uuid",
+                enabled as "enabled!: bool",
+                workflows as "workflows!: Json<Vec<String>>",
+                branch,
+                start_agent as "start_agent!: bool",
+                last_run_id,
+                updated_at as "updated_at!: DateTime<Utc>""#,
+            project_id,
+            data.enabled,
+            workflows,
+            data.branch,
+            data.start_agent
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    pub async fn set_last_run_id(
+        pool: &SqlitePool,
+        project_id: Uuid,
+        last_run_id: i64,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            "UPDATE project_ci_triage SET last_run_id = $2 WHERE project_id = $1",
+            project_id,
+            last_run_id
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,305 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool, Type, types::Json};
+use ts_rs::TS;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Copy, Type, Serialize, Deserialize, PartialEq, Eq, TS)]
+#[sqlx(type_name = "dependency_update_schedule", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum DependencyUpdateSchedule {
+    /// Every night, after 2am local time
+    Daily,
+    /// Monday nights, after 2am local time
+    Weekly,
+}
+
+/// Packages updated together in one pull request
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+pub struct DependencyGroup {
+    pub name: String,
+    /// Package names, `*` matching any run of characters, e.g. `@types/*`
+    pub patterns: Vec<String>,
+}
+
+/// Scheduled dependency updates of a project
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct ProjectDependencyUpdates {
+    pub project_id: Uuid,
+    pub enabled: bool,
+    pub schedule: DependencyUpdateSchedule,
+    /// Packages no group matches are updated in one more pull request
+    #[ts(type = "Array<DependencyGroup>")]
+    pub groups: Json<Vec<DependencyGroup>>,
+    /// Allow updates across major versions
+    pub allow_major: bool,
+    /// Packages never updated, same patterns as groups
+    #[ts(type = "Array<string>")]
+    pub ignored: Json<Vec<String>>,
+    /// Extra instructions for the agent
+    pub instructions: Option<String>,
+    pub last_run_at: Option<DateTime<Utc>>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize, TS)]
+pub struct UpdateProjectDependencyUpdates {
+    pub enabled: bool,
+    pub schedule: DependencyUpdateSchedule,
+    pub groups: Vec<DependencyGroup>,
+    pub allow_major: bool,
+    pub ignored: Vec<String>,
+    pub instructions: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, Type, Serialize, Deserialize, PartialEq, Eq, TS)]
+#[sqlx(type_name = "dependency_update_run_status", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum DependencyUpdateRunStatus {
+    Running,
+    PrOpened,
+    /// The agent found nothing to update
+    NoChanges,
+    /// The attempt couldn't be started, didn't pass or couldn't be pushed, the task is left
+    /// for review
+    Failed,
+}
+
+/// One group of packages updated by one attempt
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct DependencyUpdateRun {
+    pub id: Uuid,
+    pub project_id: Uuid,
+    pub group_name: String,
+    pub task_id: Uuid,
+    pub task_attempt_id: Option<Uuid>,
+    pub status: DependencyUpdateRunStatus,
+    pub pr_url: Option<String>,
+    pub error: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub completed_at: Option<DateTime<Utc>>,
+}
+
+impl ProjectDependencyUpdates {
+    pub async fn find_by_project_id(
+        pool: &SqlitePool,
+        project_id: Uuid,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            ProjectDependencyUpdates,
+            r#"SELECT
+                project_id as "project_id!: Uuid",
+                enabled as "enabled!: bool",
+                schedule as "schedule!: DependencyUpdateSchedule",
+                groups as "groups!: Json<Vec<DependencyGroup>>",
+                allow_major as "allow_major!: bool",
+                ignored as "ignored!: Json<Vec<String>>",
+                instructions,
+                last_run_at as "last_run_at: DateTime<Utc>",
+                updated_at as "updated_at!: DateTime<Utc>"
+               FROM project_dependency_updates
+               WHERE project_id = $1"#,
+            project_id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    pub async fn find_enabled(pool: &SqlitePool) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            ProjectDependencyUpdates,
+            r#"SELECT
+                project_id as "project_id!: Uuid",
+                enabled as "enabled!: bool",
+                schedule as "schedule!: DependencyUpdateSchedule",
+                groups as "groups!: Json<Vec<DependencyGroup>>",
+                allow_major as "allow_major!: bool",
+                ignored as "ignored!: Json<Vec<String>>",
+                instructions,
+                last_run_at as "last_run_at: DateTime<Utc>",
+                updated_at as "updated_at!: DateTime<Utc>"
+               FROM project_dependency_updates
+               WHERE enabled = 1"#
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn upsert(
+        pool: &SqlitePool,
+        project_id: Uuid,
+        data: &UpdateProjectDependencyUpdates,
+    ) -> Result<Self, sqlx::Error> {
+        let groups = Json(&data.groups);
+        let ignored = Json(&data.ignored);
+        sqlx::query_as!(
+            ProjectDependencyUpdates,
+            r#"INSERT INTO project_dependency_updates
+                (project_id, enabled, schedule, groups, allow_major, ignored, instructions)
+               VALUES ($1, $2, $3, $4, $5, $6, $7)
+               ON CONFLICT (project_id) DO UPDATE SET
+                enabled = excluded.enabled,
+                schedule = excluded.schedule,
+                groups = excluded.groups,
+                allow_major = excluded.allow_major,
+                ignored = excluded.ignored,
+                instructions = excluded.instructions,
+                updated_at = datetime('now', 'subsec')
+               RETURNING
+                project_id as "project_id!: Uuid",
+                enabled as "enabled!: bool",
+                schedule as "schedule!: DependencyUpdateSchedule",
+                groups as "groups!: Json<Vec<DependencyGroup>>",
+                allow_major as "allow_major!: bool",
+                ignored as "ignored!: Json<Vec<String>>",
+                instructions,
+                last_run_at as "last_run_at: DateTime<Utc>",
+                updated_at as "updated_at!: DateTime<Utc>""#,
+            project_id,
+            data.enabled,
+            data.schedule,
+            groups,
+            data.allow_major,
+            ignored,
+            data.instructions
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    pub async fn set_last_run_at(
+        pool: &SqlitePool,
+        project_id: Uuid,
+        last_run_at: DateTime<Utc>,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            "UPDATE project_dependency_updates SET last_run_at = $2 WHERE project_id = $1",
+            project_id,
+            last_run_at
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+}
+
+impl DependencyUpdateRun {
+    pub async fn create(
+        pool: &SqlitePool,
+        project_id: Uuid,
+        group_name: &str,
+        task_id: Uuid,
+    ) -> Result<Self, sqlx::Error> {
+        let id = Uuid::new_v4();
+        sqlx::query_as!(
+            DependencyUpdateRun,
+            r#"INSERT INTO dependency_update_runs (id, project_id, group_name, task_id)
+               VALUES ($1, $2, $3, $4)
+               RETURNING
+                id as "id!: Uuid",
+                project_id as "project_id!: Uuid",
+                group_name,
+                task_id as "task_id!: Uuid",
+                task_attempt_id as "task_attempt_id: Uuid",
+                status as "status!: DependencyUpdateRunStatus",
+                pr_url,
+                error,
+                created_at as "created_at!: DateTime<Utc>",
+                completed_at as "completed_at: DateTime<Utc>""#,
+            id,
+            project_id,
+            group_name,
+            task_id
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    pub async fn find_running(pool: &SqlitePool) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            DependencyUpdateRun,
+            r#"SELECT
+                id as "id!: Uuid",
+                project_id as "project_id!: Uuid",
+                group_name,
+                task_id as "task_id!: Uuid",
+                task_attempt_id as "task_attempt_id: Uuid",
+                status as "status!: DependencyUpdateRunStatus",
+                pr_url,
+                error,
+                created_at as "created_at!: DateTime<Utc>",
+                completed_at as "completed_at: DateTime<Utc>"
+               FROM dependency_update_runs
+               WHERE status = 'running'
+               ORDER BY rowid"#
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Newest first
+    pub async fn find_by_project_id(
+        pool: &SqlitePool,
+        project_id: Uuid,
+        limit: i64,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            DependencyUpdateRun,
+            r#"SELECT
+                id as "id!: Uuid",
+                project_id as "project_id!: Uuid",
+                group_name,
+                task_id as "task_id!: Uuid",
+                task_attempt_id as "task_attempt_id: Uuid",
+                status as "status!: DependencyUpdateRunStatus",
+                pr_url,
+                error,
+                created_at as "created_at!: DateTime<Utc>",
+                completed_at as "completed_at: DateTime<Utc>"
+               FROM dependency_update_runs
+               WHERE project_id = $1
+               ORDER BY created_at DESC
+               LIMIT $2"#,
+            project_id,
+            limit
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn set_attempt(
+        pool: &SqlitePool,
+        id: Uuid,
+        task_attempt_id: Uuid,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            "UPDATE dependency_update_runs SET task_attempt_id = $2 WHERE id = $1",
+            id,
+            task_attempt_id
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn finish(
+        pool: &SqlitePool,
+        id: Uuid,
+        status: DependencyUpdateRunStatus,
+        pr_url: Option<&str>,
+        error: Option<&str>,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"UPDATE dependency_update_runs
+               SET status = $2, pr_url = $3, error = $4, completed_at = datetime('now', 'subsec')
+               WHERE id = $1"#,
+            id,
+            status,
+            pr_url,
+            error
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+}
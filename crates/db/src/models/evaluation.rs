@@ -0,0 +1,345 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool, Type};
+use ts_rs::TS;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Copy, Type, Serialize, Deserialize, PartialEq, Eq, TS)]
+#[sqlx(type_name = "evaluation_status", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum EvaluationStatus {
+    Running,
+    Completed,
+    Failed,
+    Cancelled,
+}
+
+#[derive(Debug, Clone, Copy, Type, Serialize, Deserialize, PartialEq, Eq, TS)]
+#[sqlx(type_name = "evaluation_run_status", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum EvaluationRunStatus {
+    Pending,
+    Running,
+    /// The attempt settled and was scored, whether or not the agent succeeded
+    Completed,
+    /// The attempt couldn't be started or didn't settle in time
+    Failed,
+}
+
+/// Completed tasks of a project replayed against several profiles
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct Evaluation {
+    pub id: Uuid,
+    pub project_id: Uuid,
+    pub name: String,
+    pub status: EvaluationStatus,
+    pub error: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub completed_at: Option<DateTime<Utc>>,
+}
+
+/// One task replayed by one profile variant
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct EvaluationRun {
+    pub id: Uuid,
+    pub evaluation_id: Uuid,
+    pub task_id: Uuid,
+    pub profile: String,
+    pub variant: Option<String>,
+    /// Branch the replay starts from
+    pub base_branch: String,
+    pub task_attempt_id: Option<Uuid>,
+    pub status: EvaluationRunStatus,
+    pub error: Option<String>,
+    pub agent_completed: Option<bool>,
+    /// Whether the verification or test script passed, `None` when neither ran
+    pub verified: Option<bool>,
+    #[ts(type = "number | null")]
+    pub tests_passed: Option<i64>,
+    #[ts(type = "number | null")]
+    pub tests_failed: Option<i64>,
+    /// From 0 to 1, see `services::evaluation::score`
+    pub score: Option<f64>,
+    pub cost_usd: Option<f64>,
+    #[ts(type = "number | null")]
+    pub input_tokens: Option<i64>,
+    #[ts(type = "number | null")]
+    pub output_tokens: Option<i64>,
+    /// Time the coding agent ran for
+    #[ts(type = "number | null")]
+    pub duration_seconds: Option<i64>,
+    pub created_at: DateTime<Utc>,
+    pub completed_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone)]
+pub struct CreateEvaluationRun {
+    pub task_id: Uuid,
+    pub profile: String,
+    pub variant: Option<String>,
+    pub base_branch: String,
+}
+
+/// What a settled replay scored
+#[derive(Debug, Clone, PartialEq)]
+pub struct EvaluationRunResult {
+    pub agent_completed: bool,
+    pub verified: Option<bool>,
+    pub tests_passed: Option<i64>,
+    pub tests_failed: Option<i64>,
+    pub score: f64,
+    pub cost_usd: f64,
+    pub input_tokens: i64,
+    pub output_tokens: i64,
+    pub duration_seconds: i64,
+}
+
+impl Evaluation {
+    pub async fn create(
+        pool: &SqlitePool,
+        project_id: Uuid,
+        name: &str,
+    ) -> Result<Self, sqlx::Error> {
+        let id = Uuid::new_v4();
+        sqlx::query_as!(
+            Evaluation,
+            r#"INSERT INTO evaluations (id, project_id, name)
+               VALUES ($1, $2, $3)
+               RETURNING
+                id as "id!: Uuid",
+                project_id as "project_id!: Uuid",
+                name,
+                status as "status!: EvaluationStatus",
+                error,
+                created_at as "created_at!: DateTime<Utc>",
+                completed_at as "completed_at: DateTime<Utc>""#,
+            id,
+            project_id,
+            name
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    pub async fn find_by_id(pool: &SqlitePool, id: Uuid) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            Evaluation,
+            r#"SELECT
+                id as "id!: Uuid",
+                project_id as "project_id!: Uuid",
+                name,
+                status as "status!: EvaluationStatus",
+                error,
+                created_at as "created_at!: DateTime<Utc>",
+                completed_at as "completed_at: DateTime<Utc>"
+               FROM evaluations
+               WHERE id = $1"#,
+            id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// Newest first
+    pub async fn find_by_project_id(
+        pool: &SqlitePool,
+        project_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            Evaluation,
+            r#"SELECT
+                id as "id!: Uuid",
+                project_id as "project_id!: Uuid",
+                name,
+                status as "status!: EvaluationStatus",
+                error,
+                created_at as "created_at!: DateTime<Utc>",
+                completed_at as "completed_at: DateTime<Utc>"
+               FROM evaluations
+               WHERE project_id = $1
+               ORDER BY created_at DESC"#,
+            project_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// End a running evaluation, returns whether it was still running
+    pub async fn finish(
+        pool: &SqlitePool,
+        id: Uuid,
+        status: EvaluationStatus,
+        error: Option<&str>,
+    ) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query!(
+            r#"UPDATE evaluations
+               SET status = $2, error = $3, completed_at = datetime('now', 'subsec')
+               WHERE id = $1 AND status = 'running'"#,
+            id,
+            status,
+            error
+        )
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Fail evaluations, and their runs, left running by a server that stopped. Returns how
+    /// many evaluations were failed.
+    pub async fn fail_interrupted(pool: &SqlitePool) -> Result<u64, sqlx::Error> {
+        sqlx::query!(
+            r#"UPDATE evaluation_runs
+               SET status = 'failed', error = 'Interrupted by a server restart',
+                   completed_at = datetime('now', 'subsec')
+               WHERE status = 'running'"#
+        )
+        .execute(pool)
+        .await?;
+        let result = sqlx::query!(
+            r#"UPDATE evaluations
+               SET status = 'failed', error = 'Interrupted by a server restart',
+                   completed_at = datetime('now', 'subsec')
+               WHERE status = 'running'"#
+        )
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected())
+    }
+}
+
+impl EvaluationRun {
+    pub async fn create(
+        pool: &SqlitePool,
+        evaluation_id: Uuid,
+        data: &CreateEvaluationRun,
+    ) -> Result<Self, sqlx::Error> {
+        let id = Uuid::new_v4();
+        sqlx::query_as!(
+            EvaluationRun,
+            r#"INSERT INTO evaluation_runs (id, evaluation_id, task_id, profile, variant, base_branch)
+               VALUES ($1, $2, $3, $4, $5, $6)
+               RETURNING
+                id as "id!: Uuid",
+                evaluation_id as "evaluation_id!: Uuid",
+                task_id as "task_id!: Uuid",
+                profile,
+                variant,
+                base_branch,
+                task_attempt_id as "task_attempt_id: Uuid",
+                status as "status!: EvaluationRunStatus",
+                error,
+                agent_completed as "agent_completed: bool",
+                verified as "verified: bool",
+                tests_passed,
+                tests_failed,
+                score,
+                cost_usd,
+                input_tokens,
+                output_tokens,
+                duration_seconds,
+                created_at as "created_at!: DateTime<Utc>",
+                completed_at as "completed_at: DateTime<Utc>""#,
+            id,
+            evaluation_id,
+            data.task_id,
+            data.profile,
+            data.variant,
+            data.base_branch
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    /// In the order they run
+    pub async fn find_by_evaluation_id(
+        pool: &SqlitePool,
+        evaluation_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            EvaluationRun,
+            r#"SELECT
+                id as "id!: Uuid",
+                evaluation_id as "evaluation_id!: Uuid",
+                task_id as "task_id!: Uuid",
+                profile,
+                variant,
+                base_branch,
+                task_attempt_id as "task_attempt_id: Uuid",
+                status as "status!: EvaluationRunStatus",
+                error,
+                agent_completed as "agent_completed: bool",
+                verified as "verified: bool",
+                tests_passed,
+                tests_failed,
+                score,
+                cost_usd,
+                input_tokens,
+                output_tokens,
+                duration_seconds,
+                created_at as "created_at!: DateTime<Utc>",
+                completed_at as "completed_at: DateTime<Utc>"
+               FROM evaluation_runs
+               WHERE evaluation_id = $1
+               ORDER BY rowid"#,
+            evaluation_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn start(
+        pool: &SqlitePool,
+        id: Uuid,
+        task_attempt_id: Uuid,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"UPDATE evaluation_runs SET status = 'running', task_attempt_id = $2 WHERE id = $1"#,
+            id,
+            task_attempt_id
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn complete(
+        pool: &SqlitePool,
+        id: Uuid,
+        result: &EvaluationRunResult,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"UPDATE evaluation_runs
+               SET status = 'completed', agent_completed = $2, verified = $3,
+                   tests_passed = $4, tests_failed = $5, score = $6, cost_usd = $7,
+                   input_tokens = $8, output_tokens = $9, duration_seconds = $10,
+                   completed_at = datetime('now', 'subsec')
+               WHERE id = $1"#,
+            id,
+            result.agent_completed,
+            result.verified,
+            result.tests_passed,
+            result.tests_failed,
+            result.score,
+            result.cost_usd,
+            result.input_tokens,
+            result.output_tokens,
+            result.duration_seconds
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn fail(pool: &SqlitePool, id: Uuid, error: &str) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"UPDATE evaluation_runs
+               SET status = 'failed', error = $2, completed_at = datetime('now', 'subsec')
+               WHERE id = $1"#,
+            id,
+            error
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+}
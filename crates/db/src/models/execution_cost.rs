@@ -0,0 +1,70 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool};
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// Cost an agent reported for one execution, attributed to the execution's project
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct ExecutionCost {
+    pub id: Uuid,
+    pub execution_process_id: Uuid,
+    pub project_id: Uuid,
+    pub cost_usd: f64,
+    pub recorded_at: DateTime<Utc>,
+}
+
+/// Spend of a project since a point in time
+#[derive(Debug, Clone, FromRow)]
+pub struct ProjectSpend {
+    pub project_id: Uuid,
+    pub spend_usd: f64,
+}
+
+impl ExecutionCost {
+    /// Record the cost of an execution, replacing an earlier report for it
+    pub async fn record(
+        pool: &SqlitePool,
+        execution_process_id: Uuid,
+        cost_usd: f64,
+    ) -> Result<(), sqlx::Error> {
+        let id = Uuid::new_v4();
+        sqlx::query!(
+            r#"INSERT INTO execution_costs (id, execution_process_id, project_id, cost_usd)
+               SELECT $1, ep.id, t.project_id, $3
+               FROM execution_processes ep
+               JOIN task_attempts ta ON ta.id = ep.task_attempt_id
+               JOIN tasks t ON t.id = ta.task_id
+               WHERE ep.id = $2
+               ON CONFLICT (execution_process_id) DO UPDATE SET
+                cost_usd = excluded.cost_usd,
+                recorded_at = datetime('now', 'subsec')"#,
+            id,
+            execution_process_id,
+            cost_usd
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Spend per project of the costs recorded since `since`, a UTC `YYYY-MM-DD HH:MM:SS`
+    /// timestamp
+    pub async fn spend_by_project_since(
+        pool: &SqlitePool,
+        since: &str,
+    ) -> Result<Vec<ProjectSpend>, sqlx::Error> {
+        sqlx::query_as!(
+            ProjectSpend,
+            r#"SELECT
+                project_id as "project_id!: Uuid",
+                SUM(cost_usd) as "spend_usd!: f64"
+               FROM execution_costs
+               WHERE recorded_at >= $1
+               GROUP BY project_id"#,
+            since
+        )
+        .fetch_all(pool)
+        .await
+    }
+}
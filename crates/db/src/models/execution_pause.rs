@@ -0,0 +1,223 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool};
+use ts_rs::TS;
+use uuid::Uuid;
+
+use super::execution_process::{ExecutionProcess, ExecutionProcessRunReason};
+
+/// An interval an execution's process group spent stopped by the user
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct ExecutionPause {
+    pub id: Uuid,
+    pub execution_process_id: Uuid,
+    pub task_attempt_id: Uuid,
+    pub paused_at: DateTime<Utc>,
+    pub resumed_at: Option<DateTime<Utc>>,
+}
+
+/// Time an attempt's executions spent running and paused, dev servers excluded
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, TS)]
+pub struct AttemptRuntime {
+    #[ts(type = "number")]
+    pub running_ms: i64,
+    #[ts(type = "number")]
+    pub paused_ms: i64,
+    /// Whether an execution of the attempt is paused right now
+    pub paused: bool,
+}
+
+impl AttemptRuntime {
+    pub fn compute(
+        processes: &[ExecutionProcess],
+        pauses: &[ExecutionPause],
+        now: DateTime<Utc>,
+    ) -> Self {
+        let mut runtime = Self::default();
+        for process in processes
+            .iter()
+            .filter(|p| p.run_reason != ExecutionProcessRunReason::DevServer)
+        {
+            let end = process.completed_at.unwrap_or(now);
+            let total = (end - process.started_at).num_milliseconds().max(0);
+            let paused = ExecutionPause::paused_ms(process, pauses, end);
+            runtime.running_ms += total - paused;
+            runtime.paused_ms += paused;
+            runtime.paused |= process.completed_at.is_none()
+                && pauses.iter().any(|pause| {
+                    pause.execution_process_id == process.id && pause.resumed_at.is_none()
+                });
+        }
+        runtime
+    }
+}
+
+impl ExecutionPause {
+    /// Time `process` spent paused until `end`, its completion or now
+    pub fn paused_ms(process: &ExecutionProcess, pauses: &[Self], end: DateTime<Utc>) -> i64 {
+        let total = (end - process.started_at).num_milliseconds().max(0);
+        // A pause still open when the process finished ends with it
+        let paused: i64 = pauses
+            .iter()
+            .filter(|pause| pause.execution_process_id == process.id)
+            .map(|pause| {
+                let resumed = pause.resumed_at.unwrap_or(end).min(end);
+                (resumed - pause.paused_at).num_milliseconds().max(0)
+            })
+            .sum();
+        paused.min(total)
+    }
+
+    pub async fn create(
+        pool: &SqlitePool,
+        execution_process_id: Uuid,
+        task_attempt_id: Uuid,
+    ) -> Result<Self, sqlx::Error> {
+        let id = Uuid::new_v4();
+        sqlx::query_as!(
+            ExecutionPause,
+            r#"INSERT INTO execution_pauses (id, execution_process_id, task_attempt_id)
+               VALUES ($1, $2, $3)
+               RETURNING
+                id as "id!: Uuid",
+                execution_process_id as "execution_process_id!: Uuid",
+                task_attempt_id as "task_attempt_id!: Uuid",
+                paused_at as "paused_at!: DateTime<Utc>",
+                resumed_at as "resumed_at: DateTime<Utc>""#,
+            id,
+            execution_process_id,
+            task_attempt_id
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    pub async fn find_by_task_attempt_id(
+        pool: &SqlitePool,
+        task_attempt_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            ExecutionPause,
+            r#"SELECT
+                id as "id!: Uuid",
+                execution_process_id as "execution_process_id!: Uuid",
+                task_attempt_id as "task_attempt_id!: Uuid",
+                paused_at as "paused_at!: DateTime<Utc>",
+                resumed_at as "resumed_at: DateTime<Utc>"
+               FROM execution_pauses
+               WHERE task_attempt_id = $1
+               ORDER BY paused_at ASC"#,
+            task_attempt_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Whether the execution is paused and not resumed yet
+    pub async fn is_paused(
+        pool: &SqlitePool,
+        execution_process_id: Uuid,
+    ) -> Result<bool, sqlx::Error> {
+        let count = sqlx::query_scalar!(
+            r#"SELECT COUNT(*) as "count!: i64"
+               FROM execution_pauses
+               WHERE execution_process_id = $1 AND resumed_at IS NULL"#,
+            execution_process_id
+        )
+        .fetch_one(pool)
+        .await?;
+        Ok(count > 0)
+    }
+
+    /// End the execution's open pause, if it has one
+    pub async fn mark_resumed(
+        pool: &SqlitePool,
+        execution_process_id: Uuid,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            "UPDATE execution_pauses SET resumed_at = datetime('now', 'subsec')
+             WHERE execution_process_id = $1 AND resumed_at IS NULL",
+            execution_process_id
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Duration;
+
+    use super::*;
+    use crate::models::execution_process::{ExecutionProcessStatus, ExecutorActionField};
+
+    fn process(
+        run_reason: ExecutionProcessRunReason,
+        started_at: DateTime<Utc>,
+        completed_at: Option<DateTime<Utc>>,
+    ) -> ExecutionProcess {
+        ExecutionProcess {
+            id: Uuid::new_v4(),
+            task_attempt_id: Uuid::new_v4(),
+            run_reason,
+            executor_action: sqlx::types::Json(ExecutorActionField::Other(serde_json::Value::Null)),
+            status: ExecutionProcessStatus::Running,
+            exit_code: None,
+            started_at,
+            completed_at,
+            created_at: started_at,
+            updated_at: started_at,
+        }
+    }
+
+    fn pause(
+        process: &ExecutionProcess,
+        paused_at: DateTime<Utc>,
+        resumed_at: Option<DateTime<Utc>>,
+    ) -> ExecutionPause {
+        ExecutionPause {
+            id: Uuid::new_v4(),
+            execution_process_id: process.id,
+            task_attempt_id: process.task_attempt_id,
+            paused_at,
+            resumed_at,
+        }
+    }
+
+    #[test]
+    fn test_runtime_separates_paused_time() {
+        let t0 = Utc::now();
+        let min = Duration::minutes(1);
+        let setup = process(ExecutionProcessRunReason::SetupScript, t0, Some(t0 + min));
+        let agent = process(ExecutionProcessRunReason::CodingAgent, t0 + min, None);
+        let dev = process(ExecutionProcessRunReason::DevServer, t0, None);
+        let pauses = [
+            pause(&agent, t0 + min * 2, Some(t0 + min * 3)),
+            pause(&agent, t0 + min * 5, None),
+            pause(&dev, t0, None),
+        ];
+
+        let runtime = AttemptRuntime::compute(&[setup, agent, dev], &pauses, t0 + min * 10);
+        assert_eq!(runtime.running_ms, (min * 4).num_milliseconds());
+        assert_eq!(runtime.paused_ms, (min * 6).num_milliseconds());
+        assert!(runtime.paused);
+    }
+
+    #[test]
+    fn test_open_pause_ends_with_its_process() {
+        let t0 = Utc::now();
+        let min = Duration::minutes(1);
+        let agent = process(
+            ExecutionProcessRunReason::CodingAgent,
+            t0,
+            Some(t0 + min * 4),
+        );
+        let pauses = [pause(&agent, t0 + min, None)];
+
+        let runtime = AttemptRuntime::compute(&[agent], &pauses, t0 + min * 10);
+        assert_eq!(runtime.running_ms, min.num_milliseconds());
+        assert_eq!(runtime.paused_ms, (min * 3).num_milliseconds());
+        assert!(!runtime.paused);
+    }
+}
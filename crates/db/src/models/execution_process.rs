@@ -1,9 +1,12 @@
+use std::cmp::Ordering;
+
 use chrono::{DateTime, Utc};
 use executors::actions::ExecutorAction;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use sqlx::{FromRow, SqlitePool, Type};
 use ts_rs::TS;
+use utils::pagination::{Listable, serialized_eq};
 use uuid::Uuid;
 
 use super::{task::Task, task_attempt::TaskAttempt};
@@ -26,6 +29,10 @@ pub enum ExecutionProcessRunReason {
     CleanupScript,
     CodingAgent,
     DevServer,
+    /// The project's reviewer profile reviewing the attempt's changes
+    Review,
+    /// An agent splitting the attempt's changes into logical commits
+    CommitPlan,
 }
 
 #[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
@@ -72,6 +79,14 @@ pub enum ExecutorActionField {
     Other(Value),
 }
 
+/// Process group of an executor, persisted to recover it after a restart
+#[derive(Debug, Clone)]
+pub struct PersistedProcessGroup {
+    pub pgid: i64,
+    /// Start time of the group leader, `None` for executions spawned before it was recorded
+    pub started_at: Option<String>,
+}
+
 impl ExecutionProcess {
     /// Find execution process by ID
     pub async fn find_by_id(pool: &SqlitePool, id: Uuid) -> Result<Option<Self>, sqlx::Error> {
@@ -338,6 +353,45 @@ impl ExecutionProcess {
         Ok(())
     }
 
+    /// Persist the process group id of the spawned executor and its leader's start time
+    pub async fn update_pid(
+        pool: &SqlitePool,
+        id: Uuid,
+        pid: i64,
+        started_at: Option<&str>,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            "UPDATE execution_processes SET pid = $1, pid_started_at = $2 WHERE id = $3",
+            pid,
+            started_at,
+            id
+        )
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Find the persisted process group of an execution process
+    pub async fn find_pid(
+        pool: &SqlitePool,
+        id: Uuid,
+    ) -> Result<Option<PersistedProcessGroup>, sqlx::Error> {
+        let record = sqlx::query!(
+            r#"SELECT pid as "pid: i64", pid_started_at FROM execution_processes WHERE id = $1"#,
+            id
+        )
+        .fetch_optional(pool)
+        .await?;
+
+        Ok(record.and_then(|r| {
+            r.pid.map(|pgid| PersistedProcessGroup {
+                pgid,
+                started_at: r.pid_started_at,
+            })
+        }))
+    }
+
     pub async fn delete_by_task_attempt_id(
         pool: &SqlitePool,
         task_attempt_id: Uuid,
@@ -392,3 +446,24 @@ impl ExecutionProcess {
         })
     }
 }
+
+impl Listable for ExecutionProcess {
+    const SORT_FIELDS: &'static [&'static str] = &["started_at", "completed_at", "created_at"];
+    const FILTER_FIELDS: &'static [&'static str] = &["status", "run_reason"];
+
+    fn compare_by(&self, other: &Self, field: &str) -> Ordering {
+        match field {
+            "started_at" => self.started_at.cmp(&other.started_at),
+            "completed_at" => self.completed_at.cmp(&other.completed_at),
+            _ => self.created_at.cmp(&other.created_at),
+        }
+    }
+
+    fn matches_filter(&self, field: &str, value: &str) -> bool {
+        match field {
+            "status" => serialized_eq(&self.status, value),
+            "run_reason" => serialized_eq(&self.run_reason, value),
+            _ => true,
+        }
+    }
+}
@@ -70,6 +70,27 @@ impl ExecutionProcessLogs {
         .await
     }
 
+    /// Read up to `len` bytes of the stored JSONL starting at byte `offset`, without loading
+    /// the rest of the log. Returns an empty chunk past the end.
+    pub async fn read_chunk(
+        pool: &SqlitePool,
+        execution_id: Uuid,
+        offset: i64,
+        len: i64,
+    ) -> Result<Vec<u8>, sqlx::Error> {
+        let chunk = sqlx::query_scalar!(
+            r#"SELECT substr(CAST(logs AS BLOB), $2 + 1, $3) as "chunk!: Vec<u8>"
+               FROM execution_process_logs
+               WHERE execution_id = $1"#,
+            execution_id,
+            offset,
+            len
+        )
+        .fetch_optional(pool)
+        .await?;
+        Ok(chunk.unwrap_or_default())
+    }
+
     /// Parse JSONL logs back into Vec<LogMsg>
     pub fn parse_logs(&self) -> Result<Vec<LogMsg>, serde_json::Error> {
         let mut messages = Vec::new();
@@ -0,0 +1,41 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool};
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// Tokens an agent reported using for one execution
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct ExecutionTokenUsage {
+    pub execution_process_id: Uuid,
+    #[ts(type = "number")]
+    pub input_tokens: i64,
+    #[ts(type = "number")]
+    pub output_tokens: i64,
+    pub recorded_at: DateTime<Utc>,
+}
+
+impl ExecutionTokenUsage {
+    /// Record the tokens of an execution, replacing an earlier report for it
+    pub async fn record(
+        pool: &SqlitePool,
+        execution_process_id: Uuid,
+        input_tokens: i64,
+        output_tokens: i64,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"INSERT INTO execution_token_usage (execution_process_id, input_tokens, output_tokens)
+               VALUES ($1, $2, $3)
+               ON CONFLICT (execution_process_id) DO UPDATE SET
+                input_tokens = excluded.input_tokens,
+                output_tokens = excluded.output_tokens,
+                recorded_at = datetime('now', 'subsec')"#,
+            execution_process_id,
+            input_tokens,
+            output_tokens
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+}
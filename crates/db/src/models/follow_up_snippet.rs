@@ -0,0 +1,198 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool};
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// A reusable follow-up prompt, such as "add tests"
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct FollowUpSnippet {
+    pub id: Uuid,
+    /// `None` for global snippets, a project's snippet replaces the global one of the same name
+    pub project_id: Option<Uuid>,
+    pub name: String,
+    pub prompt: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize, TS)]
+pub struct CreateFollowUpSnippet {
+    pub project_id: Option<Uuid>,
+    pub name: String,
+    pub prompt: String,
+}
+
+#[derive(Debug, Deserialize, TS)]
+pub struct UpdateFollowUpSnippet {
+    pub name: Option<String>,
+    pub prompt: Option<String>,
+}
+
+impl FollowUpSnippet {
+    pub async fn find_all(pool: &SqlitePool) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            FollowUpSnippet,
+            r#"SELECT id as "id!: Uuid", project_id as "project_id?: Uuid", name, prompt, created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>"
+               FROM follow_up_snippets
+               ORDER BY project_id IS NULL DESC, name ASC"#
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn find_global(pool: &SqlitePool) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            FollowUpSnippet,
+            r#"SELECT id as "id!: Uuid", project_id as "project_id?: Uuid", name, prompt, created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>"
+               FROM follow_up_snippets
+               WHERE project_id IS NULL
+               ORDER BY name ASC"#
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Snippets available in a project: its own and the global ones it doesn't replace
+    pub async fn find_for_project(
+        pool: &SqlitePool,
+        project_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        let project = sqlx::query_as!(
+            FollowUpSnippet,
+            r#"SELECT id as "id!: Uuid", project_id as "project_id?: Uuid", name, prompt, created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>"
+               FROM follow_up_snippets
+               WHERE project_id = $1
+               ORDER BY name ASC"#,
+            project_id
+        )
+        .fetch_all(pool)
+        .await?;
+        let global = Self::find_global(pool).await?;
+        Ok(Self::merge(project, global))
+    }
+
+    fn merge(project: Vec<Self>, global: Vec<Self>) -> Vec<Self> {
+        let mut snippets = project;
+        let replaced: Vec<String> = snippets.iter().map(|s| s.name.clone()).collect();
+        snippets.extend(global.into_iter().filter(|s| !replaced.contains(&s.name)));
+        snippets.sort_by(|a, b| a.name.cmp(&b.name));
+        snippets
+    }
+
+    pub async fn find_by_id(pool: &SqlitePool, id: Uuid) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            FollowUpSnippet,
+            r#"SELECT id as "id!: Uuid", project_id as "project_id?: Uuid", name, prompt, created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>"
+               FROM follow_up_snippets
+               WHERE id = $1"#,
+            id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    pub async fn create(
+        pool: &SqlitePool,
+        data: &CreateFollowUpSnippet,
+    ) -> Result<Self, sqlx::Error> {
+        let id = Uuid::new_v4();
+        sqlx::query_as!(
+            FollowUpSnippet,
+            r#"INSERT INTO follow_up_snippets (id, project_id, name, prompt)
+               VALUES ($1, $2, $3, $4)
+               RETURNING id as "id!: Uuid", project_id as "project_id?: Uuid", name, prompt, created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>""#,
+            id,
+            data.project_id,
+            data.name,
+            data.prompt
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    pub async fn update(
+        pool: &SqlitePool,
+        id: Uuid,
+        data: &UpdateFollowUpSnippet,
+    ) -> Result<Self, sqlx::Error> {
+        let existing = Self::find_by_id(pool, id)
+            .await?
+            .ok_or(sqlx::Error::RowNotFound)?;
+        let name = data.name.as_ref().unwrap_or(&existing.name);
+        let prompt = data.prompt.as_ref().unwrap_or(&existing.prompt);
+
+        sqlx::query_as!(
+            FollowUpSnippet,
+            r#"UPDATE follow_up_snippets
+               SET name = $2, prompt = $3, updated_at = datetime('now', 'subsec')
+               WHERE id = $1
+               RETURNING id as "id!: Uuid", project_id as "project_id?: Uuid", name, prompt, created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>""#,
+            id,
+            name,
+            prompt
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    pub async fn delete(pool: &SqlitePool, id: Uuid) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!("DELETE FROM follow_up_snippets WHERE id = $1", id)
+            .execute(pool)
+            .await?;
+        Ok(result.rows_affected())
+    }
+
+    /// The follow-up prompt with this snippet, followed by what the user added
+    pub fn apply(&self, prompt: &str) -> String {
+        let prompt = prompt.trim();
+        if prompt.is_empty() {
+            self.prompt.clone()
+        } else {
+            format!("{}\n\n{}", self.prompt, prompt)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn snippet(project_id: Option<Uuid>, name: &str) -> FollowUpSnippet {
+        FollowUpSnippet {
+            id: Uuid::new_v4(),
+            project_id,
+            name: name.to_string(),
+            prompt: format!("{name} prompt"),
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_project_snippets_replace_global_ones_by_name() {
+        let project_id = Some(Uuid::new_v4());
+        let merged = FollowUpSnippet::merge(
+            vec![snippet(project_id, "Add tests")],
+            vec![snippet(None, "Add tests"), snippet(None, "Address review")],
+        );
+        let names: Vec<_> = merged
+            .iter()
+            .map(|s| (s.name.as_str(), s.project_id))
+            .collect();
+        assert_eq!(
+            names,
+            vec![("Add tests", project_id), ("Address review", None)]
+        );
+    }
+
+    #[test]
+    fn test_apply() {
+        let add_tests = snippet(None, "Add tests");
+        assert_eq!(add_tests.apply("  "), "Add tests prompt");
+        assert_eq!(
+            add_tests.apply("Only for the parser"),
+            "Add tests prompt\n\nOnly for the parser"
+        );
+    }
+}
@@ -0,0 +1,106 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool};
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// The card an alert's dedupe key points at
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct InboundTask {
+    pub project_id: Uuid,
+    pub dedupe_key: String,
+    pub task_id: Uuid,
+    /// Sender of the alert, e.g. `sentry`
+    pub source: Option<String>,
+    #[ts(type = "number")]
+    pub occurrences: i64,
+    pub first_seen_at: DateTime<Utc>,
+    pub last_seen_at: DateTime<Utc>,
+}
+
+impl InboundTask {
+    pub async fn find(
+        pool: &SqlitePool,
+        project_id: Uuid,
+        dedupe_key: &str,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            InboundTask,
+            r#"SELECT
+                project_id as "project_id!: Uuid",
+                dedupe_key,
+                task_id as "task_id!: Uuid",
+                source,
+                occurrences,
+                first_seen_at as "first_seen_at!: DateTime<Utc>",
+                last_seen_at as "last_seen_at!: DateTime<Utc>"
+               FROM inbound_tasks
+               WHERE project_id = $1 AND dedupe_key = $2"#,
+            project_id,
+            dedupe_key
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// Point a dedupe key at a new card, starting its count over
+    pub async fn assign(
+        pool: &SqlitePool,
+        project_id: Uuid,
+        dedupe_key: &str,
+        task_id: Uuid,
+        source: Option<&str>,
+    ) -> Result<Self, sqlx::Error> {
+        sqlx::query_as!(
+            InboundTask,
+            r#"INSERT INTO inbound_tasks (project_id, dedupe_key, task_id, source)
+               VALUES ($1, $2, $3, $4)
+               ON CONFLICT(project_id, dedupe_key) DO UPDATE SET
+                task_id = excluded.task_id,
+                source = excluded.source,
+                occurrences = 1,
+                first_seen_at = datetime('now', 'subsec'),
+                last_seen_at = datetime('now', 'subsec')
+               RETURNING
+                project_id as "project_id!: Uuid",
+                dedupe_key,
+                task_id as "task_id!: Uuid",
+                source,
+                occurrences,
+                first_seen_at as "first_seen_at!: DateTime<Utc>",
+                last_seen_at as "last_seen_at!: DateTime<Utc>""#,
+            project_id,
+            dedupe_key,
+            task_id,
+            source
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    /// Count another firing of the alert on its card
+    pub async fn record_occurrence(
+        pool: &SqlitePool,
+        project_id: Uuid,
+        dedupe_key: &str,
+    ) -> Result<Self, sqlx::Error> {
+        sqlx::query_as!(
+            InboundTask,
+            r#"UPDATE inbound_tasks
+               SET occurrences = occurrences + 1, last_seen_at = datetime('now', 'subsec')
+               WHERE project_id = $1 AND dedupe_key = $2
+               RETURNING
+                project_id as "project_id!: Uuid",
+                dedupe_key,
+                task_id as "task_id!: Uuid",
+                source,
+                occurrences,
+                first_seen_at as "first_seen_at!: DateTime<Utc>",
+                last_seen_at as "last_seen_at!: DateTime<Utc>""#,
+            project_id,
+            dedupe_key
+        )
+        .fetch_one(pool)
+        .await
+    }
+}
@@ -0,0 +1,175 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool, Type};
+use ts_rs::TS;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Copy, Type, Serialize, Deserialize, PartialEq, TS)]
+#[sqlx(type_name = "merge_queue_status", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum MergeQueueStatus {
+    Queued,
+    /// Replaying the attempt's commits on top of the current base branch
+    Rebasing,
+    /// Running the repository's verification command on the rebased branch
+    Verifying,
+    Merged,
+    /// Rebasing, verifying or merging failed, the attempt left the queue unmerged
+    Failed,
+}
+
+/// An attempt's place in its project's merge queue
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct MergeQueueEntry {
+    pub id: Uuid,
+    pub project_id: Uuid,
+    pub task_attempt_id: Uuid,
+    pub status: MergeQueueStatus,
+    pub merge_commit: Option<String>,
+    pub error: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl MergeQueueEntry {
+    pub fn is_active(&self) -> bool {
+        matches!(
+            self.status,
+            MergeQueueStatus::Queued | MergeQueueStatus::Rebasing | MergeQueueStatus::Verifying
+        )
+    }
+
+    pub async fn create(
+        pool: &SqlitePool,
+        project_id: Uuid,
+        task_attempt_id: Uuid,
+    ) -> Result<Self, sqlx::Error> {
+        let id = Uuid::new_v4();
+        sqlx::query_as!(
+            MergeQueueEntry,
+            r#"INSERT INTO merge_queue_entries (id, project_id, task_attempt_id)
+               VALUES ($1, $2, $3)
+               RETURNING
+                id as "id!: Uuid",
+                project_id as "project_id!: Uuid",
+                task_attempt_id as "task_attempt_id!: Uuid",
+                status as "status!: MergeQueueStatus",
+                merge_commit,
+                error,
+                created_at as "created_at!: DateTime<Utc>",
+                updated_at as "updated_at!: DateTime<Utc>""#,
+            id,
+            project_id,
+            task_attempt_id
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    /// The project's queue in merge order, the entry being merged first
+    pub async fn find_active_by_project_id(
+        pool: &SqlitePool,
+        project_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            MergeQueueEntry,
+            r#"SELECT
+                id as "id!: Uuid",
+                project_id as "project_id!: Uuid",
+                task_attempt_id as "task_attempt_id!: Uuid",
+                status as "status!: MergeQueueStatus",
+                merge_commit,
+                error,
+                created_at as "created_at!: DateTime<Utc>",
+                updated_at as "updated_at!: DateTime<Utc>"
+               FROM merge_queue_entries
+               WHERE project_id = $1 AND status IN ('queued', 'rebasing', 'verifying')
+               ORDER BY created_at ASC"#,
+            project_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// The attempt's latest entry, whether still queued or done
+    pub async fn find_latest_by_task_attempt_id(
+        pool: &SqlitePool,
+        task_attempt_id: Uuid,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            MergeQueueEntry,
+            r#"SELECT
+                id as "id!: Uuid",
+                project_id as "project_id!: Uuid",
+                task_attempt_id as "task_attempt_id!: Uuid",
+                status as "status!: MergeQueueStatus",
+                merge_commit,
+                error,
+                created_at as "created_at!: DateTime<Utc>",
+                updated_at as "updated_at!: DateTime<Utc>"
+               FROM merge_queue_entries
+               WHERE task_attempt_id = $1
+               ORDER BY created_at DESC
+               LIMIT 1"#,
+            task_attempt_id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// Projects with attempts waiting for their turn or being merged
+    pub async fn find_project_ids_with_queue(pool: &SqlitePool) -> Result<Vec<Uuid>, sqlx::Error> {
+        sqlx::query_scalar!(
+            r#"SELECT DISTINCT project_id as "project_id!: Uuid"
+               FROM merge_queue_entries
+               WHERE status IN ('queued', 'rebasing', 'verifying')"#
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn update_status(
+        pool: &SqlitePool,
+        id: Uuid,
+        status: MergeQueueStatus,
+        merge_commit: Option<&str>,
+        error: Option<&str>,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"UPDATE merge_queue_entries
+               SET status = $2, merge_commit = $3, error = $4, updated_at = datetime('now', 'subsec')
+               WHERE id = $1"#,
+            id,
+            status,
+            merge_commit,
+            error
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Take the attempt out of the queue, unless its merge already started
+    pub async fn dequeue(pool: &SqlitePool, task_attempt_id: Uuid) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query!(
+            r#"DELETE FROM merge_queue_entries
+               WHERE task_attempt_id = $1 AND status = 'queued'"#,
+            task_attempt_id
+        )
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Put entries whose merge was interrupted by a restart back in line, call at startup
+    pub async fn requeue_interrupted(pool: &SqlitePool) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!(
+            r#"UPDATE merge_queue_entries
+               SET status = 'queued', updated_at = datetime('now', 'subsec')
+               WHERE status IN ('rebasing', 'verifying')"#
+        )
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected())
+    }
+}
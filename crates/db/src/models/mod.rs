@@ -1,7 +1,45 @@
+pub mod attempt_checkpoint;
+pub mod attempt_commit_plan;
+pub mod attempt_diff_risk;
+pub mod attempt_linked_repo;
+pub mod attempt_plan;
+pub mod attempt_review;
+pub mod attempt_share_link;
+pub mod attempt_unmerge;
+pub mod attempt_usage;
+pub mod branch_sync;
+pub mod ci_triage;
+pub mod dependency_update;
+pub mod evaluation;
+pub mod execution_cost;
+pub mod execution_pause;
 pub mod execution_process;
 pub mod execution_process_logs;
+pub mod execution_token_usage;
 pub mod executor_session;
+pub mod follow_up_snippet;
+pub mod inbound_task;
+pub mod merge_queue;
 pub mod project;
+pub mod project_budget;
+pub mod project_changelog;
+pub mod project_context_packing;
+pub mod project_dependency_cache;
+pub mod project_devcontainer;
+pub mod project_disk_quota;
+pub mod project_env_var;
+pub mod project_group;
+pub mod project_nix_env;
+pub mod project_path_rules;
+pub mod project_reviewer;
+pub mod project_worktree_pool;
+pub mod provider_key;
+pub mod queued_follow_up;
+pub mod setting;
+pub mod suspended_execution;
 pub mod task;
+pub mod task_attachment;
 pub mod task_attempt;
 pub mod task_template;
+pub mod test_run;
+pub mod webhook;
@@ -1,10 +1,11 @@
-use std::path::PathBuf;
+use std::{cmp::Ordering, path::PathBuf};
 
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
-use sqlx::{FromRow, SqlitePool};
+use sqlx::{FromRow, SqlitePool, Type};
 use thiserror::Error;
 use ts_rs::TS;
+use utils::pagination::Listable;
 use uuid::Uuid;
 
 #[derive(Debug, Error)]
@@ -21,6 +22,17 @@ pub enum ProjectError {
     CreateFailed(String),
 }
 
+/// How the output of a project's test script is read
+#[derive(Debug, Clone, Copy, Type, Serialize, Deserialize, PartialEq, TS)]
+#[sqlx(type_name = "test_result_format", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum TestResultFormat {
+    /// JUnit XML report printed to stdout
+    Junit,
+    /// libtest JSON events, from `cargo test -- -Z unstable-options --format json`
+    CargoJson,
+}
+
 #[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
 pub struct Project {
     pub id: Uuid,
@@ -30,6 +42,8 @@ pub struct Project {
     pub dev_script: Option<String>,
     pub cleanup_script: Option<String>,
     pub copy_files: Option<String>,
+    pub test_script: Option<String>,
+    pub test_result_format: Option<TestResultFormat>,
 
     #[ts(type = "Date")]
     pub created_at: DateTime<Utc>,
@@ -46,6 +60,8 @@ pub struct CreateProject {
     pub dev_script: Option<String>,
     pub cleanup_script: Option<String>,
     pub copy_files: Option<String>,
+    pub test_script: Option<String>,
+    pub test_result_format: Option<TestResultFormat>,
 }
 
 #[derive(Debug, Deserialize, TS)]
@@ -56,6 +72,8 @@ pub struct UpdateProject {
     pub dev_script: Option<String>,
     pub cleanup_script: Option<String>,
     pub copy_files: Option<String>,
+    pub test_script: Option<String>,
+    pub test_result_format: Option<TestResultFormat>,
 }
 
 #[derive(Debug, Serialize, TS)]
@@ -67,6 +85,8 @@ pub struct ProjectWithBranch {
     pub dev_script: Option<String>,
     pub cleanup_script: Option<String>,
     pub copy_files: Option<String>,
+    pub test_script: Option<String>,
+    pub test_result_format: Option<TestResultFormat>,
     pub current_branch: Option<String>,
 
     #[ts(type = "Date")]
@@ -85,6 +105,8 @@ impl ProjectWithBranch {
             dev_script: project.dev_script,
             cleanup_script: project.cleanup_script,
             copy_files: project.copy_files,
+            test_script: project.test_script,
+            test_result_format: project.test_result_format,
             current_branch,
             created_at: project.created_at,
             updated_at: project.updated_at,
@@ -110,7 +132,7 @@ impl Project {
     pub async fn find_all(pool: &SqlitePool) -> Result<Vec<Self>, sqlx::Error> {
         sqlx::query_as!(
             Project,
-            r#"SELECT id as "id!: Uuid", name, git_repo_path, setup_script, dev_script, cleanup_script, copy_files, created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>" FROM projects ORDER BY created_at DESC"#
+            r#"SELECT id as "id!: Uuid", name, git_repo_path, setup_script, dev_script, cleanup_script, copy_files, test_script, test_result_format as "test_result_format: TestResultFormat", created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>" FROM projects ORDER BY created_at DESC"#
         )
         .fetch_all(pool)
         .await
@@ -119,7 +141,7 @@ impl Project {
     pub async fn find_by_id(pool: &SqlitePool, id: Uuid) -> Result<Option<Self>, sqlx::Error> {
         sqlx::query_as!(
             Project,
-            r#"SELECT id as "id!: Uuid", name, git_repo_path, setup_script, dev_script, cleanup_script, copy_files, created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>" FROM projects WHERE id = $1"#,
+            r#"SELECT id as "id!: Uuid", name, git_repo_path, setup_script, dev_script, cleanup_script, copy_files, test_script, test_result_format as "test_result_format: TestResultFormat", created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>" FROM projects WHERE id = $1"#,
             id
         )
         .fetch_optional(pool)
@@ -132,7 +154,7 @@ impl Project {
     ) -> Result<Option<Self>, sqlx::Error> {
         sqlx::query_as!(
             Project,
-            r#"SELECT id as "id!: Uuid", name, git_repo_path, setup_script, dev_script, cleanup_script, copy_files, created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>" FROM projects WHERE git_repo_path = $1"#,
+            r#"SELECT id as "id!: Uuid", name, git_repo_path, setup_script, dev_script, cleanup_script, copy_files, test_script, test_result_format as "test_result_format: TestResultFormat", created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>" FROM projects WHERE git_repo_path = $1"#,
             git_repo_path
         )
         .fetch_optional(pool)
@@ -146,7 +168,7 @@ impl Project {
     ) -> Result<Option<Self>, sqlx::Error> {
         sqlx::query_as!(
             Project,
-            r#"SELECT id as "id!: Uuid", name, git_repo_path, setup_script, dev_script, cleanup_script, copy_files, created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>" FROM projects WHERE git_repo_path = $1 AND id != $2"#,
+            r#"SELECT id as "id!: Uuid", name, git_repo_path, setup_script, dev_script, cleanup_script, copy_files, test_script, test_result_format as "test_result_format: TestResultFormat", created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>" FROM projects WHERE git_repo_path = $1 AND id != $2"#,
             git_repo_path,
             exclude_id
         )
@@ -161,14 +183,16 @@ impl Project {
     ) -> Result<Self, sqlx::Error> {
         sqlx::query_as!(
             Project,
-            r#"INSERT INTO projects (id, name, git_repo_path, setup_script, dev_script, cleanup_script, copy_files) VALUES ($1, $2, $3, $4, $5, $6, $7) RETURNING id as "id!: Uuid", name, git_repo_path, setup_script, dev_script, cleanup_script, copy_files, created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>""#,
+            r#"INSERT INTO projects (id, name, git_repo_path, setup_script, dev_script, cleanup_script, copy_files, test_script, test_result_format) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9) RETURNING id as "id!: Uuid", name, git_repo_path, setup_script, dev_script, cleanup_script, copy_files, test_script, test_result_format as "test_result_format: TestResultFormat", created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>""#,
             project_id,
             data.name,
             data.git_repo_path,
             data.setup_script,
             data.dev_script,
             data.cleanup_script,
-            data.copy_files
+            data.copy_files,
+            data.test_script,
+            data.test_result_format
         )
         .fetch_one(pool)
         .await
@@ -183,17 +207,21 @@ impl Project {
         dev_script: Option<String>,
         cleanup_script: Option<String>,
         copy_files: Option<String>,
+        test_script: Option<String>,
+        test_result_format: Option<TestResultFormat>,
     ) -> Result<Self, sqlx::Error> {
         sqlx::query_as!(
             Project,
-            r#"UPDATE projects SET name = $2, git_repo_path = $3, setup_script = $4, dev_script = $5, cleanup_script = $6, copy_files = $7 WHERE id = $1 RETURNING id as "id!: Uuid", name, git_repo_path, setup_script, dev_script, cleanup_script, copy_files, created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>""#,
+            r#"UPDATE projects SET name = $2, git_repo_path = $3, setup_script = $4, dev_script = $5, cleanup_script = $6, copy_files = $7, test_script = $8, test_result_format = $9 WHERE id = $1 RETURNING id as "id!: Uuid", name, git_repo_path, setup_script, dev_script, cleanup_script, copy_files, test_script, test_result_format as "test_result_format: TestResultFormat", created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>""#,
             id,
             name,
             git_repo_path,
             setup_script,
             dev_script,
             cleanup_script,
-            copy_files
+            copy_files,
+            test_script,
+            test_result_format
         )
         .fetch_one(pool)
         .await
@@ -221,3 +249,24 @@ impl Project {
         Ok(result.count > 0)
     }
 }
+
+impl Listable for Project {
+    const SORT_FIELDS: &'static [&'static str] = &["name", "created_at", "updated_at"];
+    const FILTER_FIELDS: &'static [&'static str] = &["name"];
+
+    fn compare_by(&self, other: &Self, field: &str) -> Ordering {
+        match field {
+            "name" => self.name.to_lowercase().cmp(&other.name.to_lowercase()),
+            "updated_at" => self.updated_at.cmp(&other.updated_at),
+            _ => self.created_at.cmp(&other.created_at),
+        }
+    }
+
+    fn matches_filter(&self, field: &str, value: &str) -> bool {
+        match field {
+            // Substring match, so the filter doubles as a search box
+            "name" => self.name.to_lowercase().contains(&value.to_lowercase()),
+            _ => true,
+        }
+    }
+}
@@ -0,0 +1,85 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool};
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// Monthly spend cap of a project, new attempts are refused once it's reached
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct ProjectBudget {
+    pub project_id: Uuid,
+    pub monthly_cap_usd: f64,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize, TS)]
+pub struct UpdateProjectBudget {
+    /// `null` removes the cap
+    pub monthly_cap_usd: Option<f64>,
+}
+
+impl ProjectBudget {
+    pub async fn find_all(pool: &SqlitePool) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            ProjectBudget,
+            r#"SELECT
+                project_id as "project_id!: Uuid",
+                monthly_cap_usd,
+                updated_at as "updated_at!: DateTime<Utc>"
+               FROM project_budgets"#
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn find_by_project_id(
+        pool: &SqlitePool,
+        project_id: Uuid,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            ProjectBudget,
+            r#"SELECT
+                project_id as "project_id!: Uuid",
+                monthly_cap_usd,
+                updated_at as "updated_at!: DateTime<Utc>"
+               FROM project_budgets
+               WHERE project_id = $1"#,
+            project_id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    pub async fn upsert(
+        pool: &SqlitePool,
+        project_id: Uuid,
+        monthly_cap_usd: f64,
+    ) -> Result<Self, sqlx::Error> {
+        sqlx::query_as!(
+            ProjectBudget,
+            r#"INSERT INTO project_budgets (project_id, monthly_cap_usd)
+               VALUES ($1, $2)
+               ON CONFLICT (project_id) DO UPDATE SET
+                monthly_cap_usd = excluded.monthly_cap_usd,
+                updated_at = datetime('now', 'subsec')
+               RETURNING
+                project_id as "project_id!: Uuid",
+                monthly_cap_usd,
+                updated_at as "updated_at!: DateTime<Utc>""#,
+            project_id,
+            monthly_cap_usd
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    pub async fn delete(pool: &SqlitePool, project_id: Uuid) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            "DELETE FROM project_budgets WHERE project_id = $1",
+            project_id
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,91 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool, Type};
+use ts_rs::TS;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Copy, Type, Serialize, Deserialize, PartialEq, Eq, TS)]
+#[sqlx(type_name = "changelog_format", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum ChangelogFormat {
+    /// A `### Added`/`### Changed`/... section with the task as its entry
+    KeepAChangelog,
+    /// The task as a conventional commit, e.g. `feat(api): ...`
+    ConventionalCommits,
+}
+
+/// Changelog fragment written into the repository when an attempt of a project is merged
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct ProjectChangelog {
+    pub project_id: Uuid,
+    pub enabled: bool,
+    pub format: ChangelogFormat,
+    /// Directory of the fragments, relative to the repository root
+    pub directory: String,
+    /// Merge commits get a conventional commit subject
+    pub conventional_commits: bool,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize, TS)]
+pub struct UpdateProjectChangelog {
+    pub enabled: bool,
+    pub format: ChangelogFormat,
+    pub directory: String,
+    pub conventional_commits: bool,
+}
+
+impl ProjectChangelog {
+    pub async fn find_by_project_id(
+        pool: &SqlitePool,
+        project_id: Uuid,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            ProjectChangelog,
+            r#"SELECT
+                project_id as "project_id!: Uuid",
+                enabled as "enabled!: bool",
+                format as "format!: ChangelogFormat",
+                directory,
+                conventional_commits as "conventional_commits!: bool",
+                updated_at as "updated_at!: DateTime<Utc>"
+               FROM project_changelogs
+               WHERE project_id = $1"#,
+            project_id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    pub async fn upsert(
+        pool: &SqlitePool,
+        project_id: Uuid,
+        data: &UpdateProjectChangelog,
+    ) -> Result<Self, sqlx::Error> {
+        sqlx::query_as!(
+            ProjectChangelog,
+            r#"INSERT INTO project_changelogs (project_id, enabled, format, directory, conventional_commits)
+               VALUES ($1, $2, $3, $4, $5)
+               ON CONFLICT (project_id) DO UPDATE SET
+                enabled = excluded.enabled,
+                format = excluded.format,
+                directory = excluded.directory,
+                conventional_commits = excluded.conventional_commits,
+                updated_at = datetime('now', 'subsec')
+               RETURNING
+                project_id as "project_id!: Uuid",
+                enabled as "enabled!: bool",
+                format as "format!: ChangelogFormat",
+                directory,
+                conventional_commits as "conventional_commits!: bool",
+                updated_at as "updated_at!: DateTime<Utc>""#,
+            project_id,
+            data.enabled,
+            data.format,
+            data.directory,
+            data.conventional_commits
+        )
+        .fetch_one(pool)
+        .await
+    }
+}
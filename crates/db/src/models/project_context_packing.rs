@@ -0,0 +1,95 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool, types::Json};
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// How the initial prompt of a project's attempts is packed with relevant repository files
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct ProjectContextPacking {
+    pub project_id: Uuid,
+    pub enabled: bool,
+    /// Globs relative to the repository root, matching files are always included
+    #[ts(type = "Array<string>")]
+    pub include_paths: Json<Vec<String>>,
+    /// Include files changed by this many of the latest commits, none when 0
+    #[ts(type = "number")]
+    pub recent_commits: i64,
+    /// Include files whose path or content mentions words of the task
+    pub keyword_search: bool,
+    /// Rough number of tokens the packed files may add to the prompt
+    #[ts(type = "number")]
+    pub token_budget: i64,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize, TS)]
+pub struct UpdateProjectContextPacking {
+    pub enabled: bool,
+    pub include_paths: Vec<String>,
+    #[ts(type = "number")]
+    pub recent_commits: i64,
+    pub keyword_search: bool,
+    #[ts(type = "number")]
+    pub token_budget: i64,
+}
+
+impl ProjectContextPacking {
+    pub async fn find_by_project_id(
+        pool: &SqlitePool,
+        project_id: Uuid,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            ProjectContextPacking,
+            r#"SELECT
+                project_id as "project_id!: Uuid",
+                enabled as "enabled!: bool",
+                include_paths as "include_paths!: Json<Vec<String>>",
+                recent_commits as "recent_commits!: i64",
+                keyword_search as "keyword_search!: bool",
+                token_budget as "token_budget!: i64",
+                updated_at as "updated_at!: DateTime<Utc>"
+               FROM project_context_packing
+               WHERE project_id = $1"#,
+            project_id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    pub async fn upsert(
+        pool: &SqlitePool,
+        project_id: Uuid,
+        data: &UpdateProjectContextPacking,
+    ) -> Result<Self, sqlx::Error> {
+        let include_paths = Json(&data.include_paths);
+        sqlx::query_as!(
+            ProjectContextPacking,
+            r#"INSERT INTO project_context_packing (project_id, enabled, include_paths, recent_commits, keyword_search, token_budget)
+               VALUES ($1, $2, $3, $4, $5, $6)
+               ON CONFLICT (project_id) DO UPDATE SET
+                enabled = excluded.enabled,
+                include_paths = excluded.include_paths,
+                recent_commits = excluded.recent_commits,
+                keyword_search = excluded.keyword_search,
+                token_budget = excluded.token_budget,
+                updated_at = datetime('now', 'subsec')
+               RETURNING
+                project_id as "project_id!: Uuid",
+                enabled as "enabled!: bool",
+                include_paths as "include_paths!: Json<Vec<String>>",
+                recent_commits as "recent_commits!: i64",
+                keyword_search as "keyword_search!: bool",
+                token_budget as "token_budget!: i64",
+                updated_at as "updated_at!: DateTime<Utc>""#,
+            project_id,
+            data.enabled,
+            include_paths,
+            data.recent_commits,
+            data.keyword_search,
+            data.token_budget
+        )
+        .fetch_one(pool)
+        .await
+    }
+}
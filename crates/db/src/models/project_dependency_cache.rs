@@ -0,0 +1,70 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool};
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// Package caches a project's attempts share instead of filling one per worktree
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct ProjectDependencyCache {
+    pub project_id: Uuid,
+    /// One Cargo target directory for all attempts, through `CARGO_TARGET_DIR`
+    pub shared_cargo_target: bool,
+    /// One pnpm store for all attempts, through `npm_config_store_dir`
+    pub shared_pnpm_store: bool,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize, TS)]
+pub struct UpdateProjectDependencyCache {
+    pub shared_cargo_target: bool,
+    pub shared_pnpm_store: bool,
+}
+
+impl ProjectDependencyCache {
+    pub async fn find_by_project_id(
+        pool: &SqlitePool,
+        project_id: Uuid,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            ProjectDependencyCache,
+            r#"SELECT
+                project_id as "project_id!: Uuid",
+                shared_cargo_target as "shared_cargo_target!: bool",
+                shared_pnpm_store as "shared_pnpm_store!: bool",
+                updated_at as "updated_at!: DateTime<Utc>"
+               FROM project_dependency_caches
+               WHERE project_id = $1"#,
+            project_id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    pub async fn upsert(
+        pool: &SqlitePool,
+        project_id: Uuid,
+        data: &UpdateProjectDependencyCache,
+    ) -> Result<Self, sqlx::Error> {
+        sqlx::query_as!(
+            ProjectDependencyCache,
+            r#"INSERT INTO project_dependency_caches
+                (project_id, shared_cargo_target, shared_pnpm_store)
+               VALUES ($1, $2, $3)
+               ON CONFLICT (project_id) DO UPDATE SET
+                shared_cargo_target = excluded.shared_cargo_target,
+                shared_pnpm_store = excluded.shared_pnpm_store,
+                updated_at = datetime('now', 'subsec')
+               RETURNING
+                project_id as "project_id!: Uuid",
+                shared_cargo_target as "shared_cargo_target!: bool",
+                shared_pnpm_store as "shared_pnpm_store!: bool",
+                updated_at as "updated_at!: DateTime<Utc>""#,
+            project_id,
+            data.shared_cargo_target,
+            data.shared_pnpm_store
+        )
+        .fetch_one(pool)
+        .await
+    }
+}
@@ -0,0 +1,62 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool};
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// Whether a project's processes run in the devcontainer its repository defines
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct ProjectDevcontainer {
+    pub project_id: Uuid,
+    /// Run setup scripts and coding agents inside the devcontainer instead of on the host
+    pub enabled: bool,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize, TS)]
+pub struct UpdateProjectDevcontainer {
+    pub enabled: bool,
+}
+
+impl ProjectDevcontainer {
+    pub async fn find_by_project_id(
+        pool: &SqlitePool,
+        project_id: Uuid,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            ProjectDevcontainer,
+            r#"SELECT
+                project_id as "project_id!: Uuid",
+                enabled as "enabled!: bool",
+                updated_at as "updated_at!: DateTime<Utc>"
+               FROM project_devcontainers
+               WHERE project_id = $1"#,
+            project_id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    pub async fn upsert(
+        pool: &SqlitePool,
+        project_id: Uuid,
+        data: &UpdateProjectDevcontainer,
+    ) -> Result<Self, sqlx::Error> {
+        sqlx::query_as!(
+            ProjectDevcontainer,
+            r#"INSERT INTO project_devcontainers (project_id, enabled)
+               VALUES ($1, $2)
+               ON CONFLICT (project_id) DO UPDATE SET
+                enabled = excluded.enabled,
+                updated_at = datetime('now', 'subsec')
+               RETURNING
+                project_id as "project_id!: Uuid",
+                enabled as "enabled!: bool",
+                updated_at as "updated_at!: DateTime<Utc>""#,
+            project_id,
+            data.enabled
+        )
+        .fetch_one(pool)
+        .await
+    }
+}
@@ -0,0 +1,83 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool};
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// Disk space a project's worktrees may use, new attempts are refused once it's exceeded
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct ProjectDiskQuota {
+    pub project_id: Uuid,
+    #[ts(type = "number")]
+    pub max_bytes: i64,
+    /// Remove the worktrees of merged attempts before refusing a new attempt
+    pub auto_gc: bool,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize, TS)]
+pub struct UpdateProjectDiskQuota {
+    /// `null` removes the quota
+    #[ts(type = "number | null")]
+    pub max_bytes: Option<i64>,
+    #[serde(default)]
+    pub auto_gc: bool,
+}
+
+impl ProjectDiskQuota {
+    pub async fn find_by_project_id(
+        pool: &SqlitePool,
+        project_id: Uuid,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            ProjectDiskQuota,
+            r#"SELECT
+                project_id as "project_id!: Uuid",
+                max_bytes,
+                auto_gc as "auto_gc!: bool",
+                updated_at as "updated_at!: DateTime<Utc>"
+               FROM project_disk_quotas
+               WHERE project_id = $1"#,
+            project_id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    pub async fn upsert(
+        pool: &SqlitePool,
+        project_id: Uuid,
+        max_bytes: i64,
+        auto_gc: bool,
+    ) -> Result<Self, sqlx::Error> {
+        sqlx::query_as!(
+            ProjectDiskQuota,
+            r#"INSERT INTO project_disk_quotas (project_id, max_bytes, auto_gc)
+               VALUES ($1, $2, $3)
+               ON CONFLICT (project_id) DO UPDATE SET
+                max_bytes = excluded.max_bytes,
+                auto_gc = excluded.auto_gc,
+                updated_at = datetime('now', 'subsec')
+               RETURNING
+                project_id as "project_id!: Uuid",
+                max_bytes,
+                auto_gc as "auto_gc!: bool",
+                updated_at as "updated_at!: DateTime<Utc>""#,
+            project_id,
+            max_bytes,
+            auto_gc
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    pub async fn delete(pool: &SqlitePool, project_id: Uuid) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            "DELETE FROM project_disk_quotas WHERE project_id = $1",
+            project_id
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,86 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool};
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// A variable set for the setup scripts, dev servers and agents run in a project's
+/// worktrees. Secret values are kept in the secrets store and left out here.
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct ProjectEnvVar {
+    pub project_id: Uuid,
+    pub key: String,
+    pub value: Option<String>,
+    pub secret: bool,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// A variable as edited. Secrets without a value keep the one already stored.
+#[derive(Debug, Clone, Deserialize, TS)]
+pub struct ProjectEnvVarInput {
+    pub key: String,
+    pub value: Option<String>,
+    pub secret: bool,
+}
+
+#[derive(Debug, Deserialize, TS)]
+pub struct UpdateProjectEnv {
+    pub vars: Vec<ProjectEnvVarInput>,
+}
+
+impl ProjectEnvVar {
+    /// The project's variables, by name
+    pub async fn find_by_project_id(
+        pool: &SqlitePool,
+        project_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            ProjectEnvVar,
+            r#"SELECT
+                project_id as "project_id!: Uuid",
+                key,
+                value,
+                secret as "secret!: bool",
+                updated_at as "updated_at!: DateTime<Utc>"
+               FROM project_env_vars
+               WHERE project_id = $1
+               ORDER BY key"#,
+            project_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Replace the project's variables. Values of secrets are never written.
+    pub async fn replace(
+        pool: &SqlitePool,
+        project_id: Uuid,
+        vars: &[ProjectEnvVarInput],
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        let mut tx = pool.begin().await?;
+        sqlx::query!(
+            "DELETE FROM project_env_vars WHERE project_id = $1",
+            project_id
+        )
+        .execute(&mut *tx)
+        .await?;
+        for var in vars {
+            let value = if var.secret {
+                None
+            } else {
+                var.value.as_deref()
+            };
+            sqlx::query!(
+                "INSERT INTO project_env_vars (project_id, key, value, secret) VALUES ($1, $2, $3, $4)",
+                project_id,
+                var.key,
+                value,
+                var.secret
+            )
+            .execute(&mut *tx)
+            .await?;
+        }
+        tx.commit().await?;
+        Self::find_by_project_id(pool, project_id).await
+    }
+}
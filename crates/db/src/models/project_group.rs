@@ -0,0 +1,174 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::SqlitePool;
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// A named set of projects shown together on one board, one swimlane per project
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct ProjectGroup {
+    pub id: Uuid,
+    pub name: String,
+    /// Member projects in swimlane order
+    pub project_ids: Vec<Uuid>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize, TS)]
+pub struct CreateProjectGroup {
+    pub name: String,
+    pub project_ids: Vec<Uuid>,
+}
+
+#[derive(Debug, Deserialize, TS)]
+pub struct UpdateProjectGroup {
+    pub name: Option<String>,
+    /// Replaces the members, in swimlane order
+    pub project_ids: Option<Vec<Uuid>>,
+}
+
+struct ProjectGroupRow {
+    id: Uuid,
+    name: String,
+    created_at: DateTime<Utc>,
+    updated_at: DateTime<Utc>,
+}
+
+impl ProjectGroup {
+    async fn with_members(pool: &SqlitePool, row: ProjectGroupRow) -> Result<Self, sqlx::Error> {
+        let project_ids = Self::find_project_ids(pool, row.id).await?;
+        Ok(Self {
+            id: row.id,
+            name: row.name,
+            project_ids,
+            created_at: row.created_at,
+            updated_at: row.updated_at,
+        })
+    }
+
+    pub async fn find_all(pool: &SqlitePool) -> Result<Vec<Self>, sqlx::Error> {
+        let rows = sqlx::query_as!(
+            ProjectGroupRow,
+            r#"SELECT id as "id!: Uuid", name, created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>"
+               FROM project_groups
+               ORDER BY name ASC"#
+        )
+        .fetch_all(pool)
+        .await?;
+
+        let mut groups = Vec::with_capacity(rows.len());
+        for row in rows {
+            groups.push(Self::with_members(pool, row).await?);
+        }
+        Ok(groups)
+    }
+
+    pub async fn find_by_id(pool: &SqlitePool, id: Uuid) -> Result<Option<Self>, sqlx::Error> {
+        let row = sqlx::query_as!(
+            ProjectGroupRow,
+            r#"SELECT id as "id!: Uuid", name, created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>"
+               FROM project_groups
+               WHERE id = $1"#,
+            id
+        )
+        .fetch_optional(pool)
+        .await?;
+
+        match row {
+            Some(row) => Ok(Some(Self::with_members(pool, row).await?)),
+            None => Ok(None),
+        }
+    }
+
+    pub async fn find_project_ids(pool: &SqlitePool, id: Uuid) -> Result<Vec<Uuid>, sqlx::Error> {
+        let records = sqlx::query!(
+            r#"SELECT project_id as "project_id!: Uuid"
+               FROM project_group_members
+               WHERE project_group_id = $1
+               ORDER BY position ASC"#,
+            id
+        )
+        .fetch_all(pool)
+        .await?;
+        Ok(records.into_iter().map(|r| r.project_id).collect())
+    }
+
+    pub async fn create(pool: &SqlitePool, data: &CreateProjectGroup) -> Result<Self, sqlx::Error> {
+        let id = Uuid::new_v4();
+        let mut tx = pool.begin().await?;
+        sqlx::query!(
+            "INSERT INTO project_groups (id, name) VALUES ($1, $2)",
+            id,
+            data.name
+        )
+        .execute(&mut *tx)
+        .await?;
+        Self::replace_members(&mut tx, id, &data.project_ids).await?;
+        tx.commit().await?;
+
+        Self::find_by_id(pool, id)
+            .await?
+            .ok_or(sqlx::Error::RowNotFound)
+    }
+
+    pub async fn update(
+        pool: &SqlitePool,
+        id: Uuid,
+        data: &UpdateProjectGroup,
+    ) -> Result<Self, sqlx::Error> {
+        let existing = Self::find_by_id(pool, id)
+            .await?
+            .ok_or(sqlx::Error::RowNotFound)?;
+        let name = data.name.as_ref().unwrap_or(&existing.name);
+
+        let mut tx = pool.begin().await?;
+        sqlx::query!(
+            "UPDATE project_groups SET name = $2, updated_at = datetime('now', 'subsec') WHERE id = $1",
+            id,
+            name
+        )
+        .execute(&mut *tx)
+        .await?;
+        if let Some(project_ids) = &data.project_ids {
+            Self::replace_members(&mut tx, id, project_ids).await?;
+        }
+        tx.commit().await?;
+
+        Self::find_by_id(pool, id)
+            .await?
+            .ok_or(sqlx::Error::RowNotFound)
+    }
+
+    async fn replace_members(
+        tx: &mut sqlx::Transaction<'_, sqlx::Sqlite>,
+        id: Uuid,
+        project_ids: &[Uuid],
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            "DELETE FROM project_group_members WHERE project_group_id = $1",
+            id
+        )
+        .execute(&mut **tx)
+        .await?;
+        for (position, project_id) in project_ids.iter().enumerate() {
+            let position = position as i64;
+            sqlx::query!(
+                "INSERT OR IGNORE INTO project_group_members (project_group_id, project_id, position) VALUES ($1, $2, $3)",
+                id,
+                project_id,
+                position
+            )
+            .execute(&mut **tx)
+            .await?;
+        }
+        Ok(())
+    }
+
+    pub async fn delete(pool: &SqlitePool, id: Uuid) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!("DELETE FROM project_groups WHERE id = $1", id)
+            .execute(pool)
+            .await?;
+        Ok(result.rows_affected())
+    }
+}
@@ -0,0 +1,79 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool, Type};
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// What defines a project's development shell
+#[derive(Debug, Clone, Copy, Type, Serialize, Deserialize, PartialEq, Eq, TS)]
+#[sqlx(type_name = "nix_tool", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum NixTool {
+    /// The `devShells.default` of `flake.nix`, as `nix develop` enters it
+    Flake,
+    /// `devenv.nix`, as `devenv shell` enters it
+    Devenv,
+}
+
+/// Whether a project's processes run in the development shell its repository defines
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct ProjectNixEnv {
+    pub project_id: Uuid,
+    /// Run setup scripts, coding agents, test scripts and dev servers in the shell
+    pub enabled: bool,
+    pub tool: NixTool,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize, TS)]
+pub struct UpdateProjectNixEnv {
+    pub enabled: bool,
+    pub tool: NixTool,
+}
+
+impl ProjectNixEnv {
+    pub async fn find_by_project_id(
+        pool: &SqlitePool,
+        project_id: Uuid,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            ProjectNixEnv,
+            r#"SELECT
+                project_id as "project_id!: Uuid",
+                enabled as "enabled!: bool",
+                tool as "tool!: NixTool",
+                updated_at as "updated_at!: DateTime<Utc>"
+               FROM project_nix_envs
+               WHERE project_id = $1"#,
+            project_id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    pub async fn upsert(
+        pool: &SqlitePool,
+        project_id: Uuid,
+        data: &UpdateProjectNixEnv,
+    ) -> Result<Self, sqlx::Error> {
+        sqlx::query_as!(
+            ProjectNixEnv,
+            r#"INSERT INTO project_nix_envs (project_id, enabled, tool)
+               VALUES ($1, $2, $3)
+               ON CONFLICT (project_id) DO UPDATE SET
+                enabled = excluded.enabled,
+                tool = excluded.tool,
+                updated_at = datetime('now', 'subsec')
+               RETURNING
+                project_id as "project_id!: Uuid",
+                enabled as "enabled!: bool",
+                tool as "tool!: NixTool",
+                updated_at as "updated_at!: DateTime<Utc>""#,
+            project_id,
+            data.enabled,
+            data.tool
+        )
+        .fetch_one(pool)
+        .await
+    }
+}
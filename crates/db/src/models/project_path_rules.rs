@@ -0,0 +1,80 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool, types::Json};
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// Paths attempts of a project may change, as globs relative to the repository root
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct ProjectPathRules {
+    pub project_id: Uuid,
+    /// Only these paths may change, any path when empty
+    #[ts(type = "Array<string>")]
+    pub allowed_paths: Json<Vec<String>>,
+    /// These paths may never change, even when allowed
+    #[ts(type = "Array<string>")]
+    pub forbidden_paths: Json<Vec<String>>,
+    /// Ask the agent to revert its out-of-scope changes once its run completes
+    pub revert_violations: bool,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize, TS)]
+pub struct UpdateProjectPathRules {
+    pub allowed_paths: Vec<String>,
+    pub forbidden_paths: Vec<String>,
+    pub revert_violations: bool,
+}
+
+impl ProjectPathRules {
+    pub async fn find_by_project_id(
+        pool: &SqlitePool,
+        project_id: Uuid,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            ProjectPathRules,
+            r#"SELECT
+                project_id as "project_id!: Uuid",
+                allowed_paths as "allowed_paths!: Json<Vec<String>>",
+                forbidden_paths as "forbidden_paths!: Json<Vec<String>>",
+                revert_violations as "revert_violations!: bool",
+                updated_at as "updated_at!: DateTime<Utc>"
+               FROM project_path_rules
+               WHERE project_id = $1"#,
+            project_id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    pub async fn upsert(
+        pool: &SqlitePool,
+        project_id: Uuid,
+        data: &UpdateProjectPathRules,
+    ) -> Result<Self, sqlx::Error> {
+        let allowed_paths = Json(&data.allowed_paths);
+        let forbidden_paths = Json(&data.forbidden_paths);
+        sqlx::query_as!(
+            ProjectPathRules,
+            r#"INSERT INTO project_path_rules (project_id, allowed_paths, forbidden_paths, revert_violations)
+               VALUES ($1, $2, $3, $4)
+               ON CONFLICT (project_id) DO UPDATE SET
+                allowed_paths = excluded.allowed_paths,
+                forbidden_paths = excluded.forbidden_paths,
+                revert_violations = excluded.revert_violations,
+                updated_at = datetime('now', 'subsec')
+               RETURNING
+                project_id as "project_id!: Uuid",
+                allowed_paths as "allowed_paths!: Json<Vec<String>>",
+                forbidden_paths as "forbidden_paths!: Json<Vec<String>>",
+                revert_violations as "revert_violations!: bool",
+                updated_at as "updated_at!: DateTime<Utc>""#,
+            project_id,
+            allowed_paths,
+            forbidden_paths,
+            data.revert_violations
+        )
+        .fetch_one(pool)
+        .await
+    }
+}
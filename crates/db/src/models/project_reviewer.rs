@@ -0,0 +1,80 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool};
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// Profile reviewing the changes of a project's attempts once the implementing agent is done
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct ProjectReviewer {
+    pub project_id: Uuid,
+    pub enabled: bool,
+    pub profile: String,
+    pub variant: Option<String>,
+    /// Pull requests need the latest review to approve the changes
+    pub gate_pull_requests: bool,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize, TS)]
+pub struct UpdateProjectReviewer {
+    pub enabled: bool,
+    pub profile: String,
+    pub variant: Option<String>,
+    pub gate_pull_requests: bool,
+}
+
+impl ProjectReviewer {
+    pub async fn find_by_project_id(
+        pool: &SqlitePool,
+        project_id: Uuid,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            ProjectReviewer,
+            r#"SELECT
+                project_id as "project_id!: Uuid",
+                enabled as "enabled!: bool",
+                profile,
+                variant,
+                gate_pull_requests as "gate_pull_requests!: bool",
+                updated_at as "updated_at!: DateTime<Utc>"
+               FROM project_reviewers
+               WHERE project_id = $1"#,
+            project_id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    pub async fn upsert(
+        pool: &SqlitePool,
+        project_id: Uuid,
+        data: &UpdateProjectReviewer,
+    ) -> Result<Self, sqlx::Error> {
+        sqlx::query_as!(
+            ProjectReviewer,
+            r#"INSERT INTO project_reviewers (project_id, enabled, profile, variant, gate_pull_requests)
+               VALUES ($1, $2, $3, $4, $5)
+               ON CONFLICT (project_id) DO UPDATE SET
+                enabled = excluded.enabled,
+                profile = excluded.profile,
+                variant = excluded.variant,
+                gate_pull_requests = excluded.gate_pull_requests,
+                updated_at = datetime('now', 'subsec')
+               RETURNING
+                project_id as "project_id!: Uuid",
+                enabled as "enabled!: bool",
+                profile,
+                variant,
+                gate_pull_requests as "gate_pull_requests!: bool",
+                updated_at as "updated_at!: DateTime<Utc>""#,
+            project_id,
+            data.enabled,
+            data.profile,
+            data.variant,
+            data.gate_pull_requests
+        )
+        .fetch_one(pool)
+        .await
+    }
+}
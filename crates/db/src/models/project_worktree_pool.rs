@@ -0,0 +1,88 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool};
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// How many worktrees are kept ready for a project's next attempts
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct ProjectWorktreePool {
+    pub project_id: Uuid,
+    #[ts(type = "number")]
+    pub size: i64,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize, TS)]
+pub struct UpdateProjectWorktreePool {
+    #[ts(type = "number")]
+    pub size: i64,
+}
+
+impl ProjectWorktreePool {
+    pub async fn find_by_project_id(
+        pool: &SqlitePool,
+        project_id: Uuid,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            ProjectWorktreePool,
+            r#"SELECT
+                project_id as "project_id!: Uuid",
+                size,
+                updated_at as "updated_at!: DateTime<Utc>"
+               FROM project_worktree_pools
+               WHERE project_id = $1"#,
+            project_id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// Pools of projects in use: with an attempt started, or the pool changed, in the last
+    /// week. The worktrees of other projects aren't worth keeping ready.
+    pub async fn find_active(pool: &SqlitePool) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            ProjectWorktreePool,
+            r#"SELECT
+                pwp.project_id as "project_id!: Uuid",
+                pwp.size,
+                pwp.updated_at as "updated_at!: DateTime<Utc>"
+               FROM project_worktree_pools pwp
+               WHERE pwp.size > 0
+                 AND (
+                    pwp.updated_at > datetime('now', '-7 days')
+                    OR EXISTS (
+                        SELECT 1 FROM task_attempts ta
+                        JOIN tasks t ON ta.task_id = t.id
+                        WHERE t.project_id = pwp.project_id
+                          AND ta.created_at > datetime('now', '-7 days')
+                    )
+                 )"#
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn upsert(
+        pool: &SqlitePool,
+        project_id: Uuid,
+        size: i64,
+    ) -> Result<Self, sqlx::Error> {
+        sqlx::query_as!(
+            ProjectWorktreePool,
+            r#"INSERT INTO project_worktree_pools (project_id, size)
+               VALUES ($1, $2)
+               ON CONFLICT (project_id) DO UPDATE SET
+                size = excluded.size,
+                updated_at = datetime('now', 'subsec')
+               RETURNING
+                project_id as "project_id!: Uuid",
+                size,
+                updated_at as "updated_at!: DateTime<Utc>""#,
+            project_id,
+            size
+        )
+        .fetch_one(pool)
+        .await
+    }
+}
@@ -0,0 +1,147 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool, Type};
+use ts_rs::TS;
+
+#[derive(Debug, Clone, Copy, Type, Serialize, Deserialize, PartialEq, Eq, Hash, TS)]
+#[sqlx(type_name = "key_provider", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum KeyProvider {
+    Anthropic,
+    #[serde(rename = "openai")]
+    #[sqlx(rename = "openai")]
+    OpenAi,
+    Google,
+    Sourcegraph,
+}
+
+impl KeyProvider {
+    pub const ALL: [KeyProvider; 4] = [
+        KeyProvider::Anthropic,
+        KeyProvider::OpenAi,
+        KeyProvider::Google,
+        KeyProvider::Sourcegraph,
+    ];
+}
+
+/// A stored provider API key, the key itself left out
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct ProviderKey {
+    pub provider: KeyProvider,
+    /// Last characters of the key, to tell keys apart
+    pub key_hint: String,
+    /// Whether the provider accepted the key, `None` before it was checked or when the
+    /// provider couldn't be reached
+    pub valid: Option<bool>,
+    pub validation_error: Option<String>,
+    pub validated_at: Option<DateTime<Utc>>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize, TS)]
+pub struct SetProviderKey {
+    pub key: String,
+}
+
+impl ProviderKey {
+    pub async fn find_all(pool: &SqlitePool) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            ProviderKey,
+            r#"SELECT
+                provider as "provider!: KeyProvider",
+                key_hint,
+                valid as "valid: bool",
+                validation_error,
+                validated_at as "validated_at: DateTime<Utc>",
+                updated_at as "updated_at!: DateTime<Utc>"
+               FROM provider_keys
+               ORDER BY provider"#
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn find(
+        pool: &SqlitePool,
+        provider: KeyProvider,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            ProviderKey,
+            r#"SELECT
+                provider as "provider!: KeyProvider",
+                key_hint,
+                valid as "valid: bool",
+                validation_error,
+                validated_at as "validated_at: DateTime<Utc>",
+                updated_at as "updated_at!: DateTime<Utc>"
+               FROM provider_keys
+               WHERE provider = $1"#,
+            provider
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// Record a new key, forgetting how the previous one validated
+    pub async fn upsert(
+        pool: &SqlitePool,
+        provider: KeyProvider,
+        key_hint: &str,
+    ) -> Result<Self, sqlx::Error> {
+        sqlx::query_as!(
+            ProviderKey,
+            r#"INSERT INTO provider_keys (provider, key_hint)
+               VALUES ($1, $2)
+               ON CONFLICT (provider) DO UPDATE SET
+                key_hint = excluded.key_hint,
+                valid = NULL,
+                validation_error = NULL,
+                validated_at = NULL,
+                updated_at = datetime('now', 'subsec')
+               RETURNING
+                provider as "provider!: KeyProvider",
+                key_hint,
+                valid as "valid: bool",
+                validation_error,
+                validated_at as "validated_at: DateTime<Utc>",
+                updated_at as "updated_at!: DateTime<Utc>""#,
+            provider,
+            key_hint
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    pub async fn record_validation(
+        pool: &SqlitePool,
+        provider: KeyProvider,
+        valid: Option<bool>,
+        validation_error: Option<&str>,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            ProviderKey,
+            r#"UPDATE provider_keys
+               SET valid = $2, validation_error = $3, validated_at = datetime('now', 'subsec')
+               WHERE provider = $1
+               RETURNING
+                provider as "provider!: KeyProvider",
+                key_hint,
+                valid as "valid: bool",
+                validation_error,
+                validated_at as "validated_at: DateTime<Utc>",
+                updated_at as "updated_at!: DateTime<Utc>""#,
+            provider,
+            valid,
+            validation_error
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    pub async fn delete(pool: &SqlitePool, provider: KeyProvider) -> Result<(), sqlx::Error> {
+        sqlx::query!("DELETE FROM provider_keys WHERE provider = $1", provider)
+            .execute(pool)
+            .await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,128 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool};
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// A follow-up prompt submitted while its attempt was busy, sent once the attempt is idle
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct QueuedFollowUp {
+    pub id: Uuid,
+    pub task_attempt_id: Uuid,
+    pub prompt: String,
+    pub variant: Option<String>,
+    #[ts(type = "number")]
+    pub position: i64,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize, TS)]
+pub struct CreateQueuedFollowUp {
+    pub prompt: String,
+    pub variant: Option<String>,
+}
+
+#[derive(Debug, Deserialize, TS)]
+pub struct ReorderQueuedFollowUps {
+    /// Queued follow-up ids in their new dispatch order
+    pub ids: Vec<Uuid>,
+}
+
+impl QueuedFollowUp {
+    /// Queue of an attempt in dispatch order
+    pub async fn find_by_task_attempt_id(
+        pool: &SqlitePool,
+        task_attempt_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            QueuedFollowUp,
+            r#"SELECT id as "id!: Uuid", task_attempt_id as "task_attempt_id!: Uuid", prompt, variant, position, created_at as "created_at!: DateTime<Utc>"
+               FROM queued_follow_ups
+               WHERE task_attempt_id = $1
+               ORDER BY position ASC, created_at ASC"#,
+            task_attempt_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Attempts with at least one queued follow-up
+    pub async fn find_task_attempt_ids(pool: &SqlitePool) -> Result<Vec<Uuid>, sqlx::Error> {
+        sqlx::query_scalar!(
+            r#"SELECT DISTINCT task_attempt_id as "task_attempt_id!: Uuid"
+               FROM queued_follow_ups"#
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn find_by_id(pool: &SqlitePool, id: Uuid) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            QueuedFollowUp,
+            r#"SELECT id as "id!: Uuid", task_attempt_id as "task_attempt_id!: Uuid", prompt, variant, position, created_at as "created_at!: DateTime<Utc>"
+               FROM queued_follow_ups
+               WHERE id = $1"#,
+            id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// Add a follow-up to the end of an attempt's queue
+    pub async fn create(
+        pool: &SqlitePool,
+        task_attempt_id: Uuid,
+        data: &CreateQueuedFollowUp,
+    ) -> Result<Self, sqlx::Error> {
+        let id = Uuid::new_v4();
+        sqlx::query_as!(
+            QueuedFollowUp,
+            r#"INSERT INTO queued_follow_ups (id, task_attempt_id, prompt, variant, position)
+               VALUES ($1, $2, $3, $4, (SELECT COALESCE(MAX(position), -1) + 1 FROM queued_follow_ups WHERE task_attempt_id = $2))
+               RETURNING id as "id!: Uuid", task_attempt_id as "task_attempt_id!: Uuid", prompt, variant, position, created_at as "created_at!: DateTime<Utc>""#,
+            id,
+            task_attempt_id,
+            data.prompt,
+            data.variant
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    /// Put an attempt's queue in the order of `ids`. Queued follow-ups missing from `ids`
+    /// keep their relative order after the listed ones.
+    pub async fn reorder(
+        pool: &SqlitePool,
+        task_attempt_id: Uuid,
+        ids: &[Uuid],
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        let mut queue = Self::find_by_task_attempt_id(pool, task_attempt_id).await?;
+        queue.sort_by_key(|queued| {
+            ids.iter()
+                .position(|id| *id == queued.id)
+                .unwrap_or(ids.len())
+        });
+
+        let mut tx = pool.begin().await?;
+        for (position, queued) in queue.iter().enumerate() {
+            let position = position as i64;
+            sqlx::query!(
+                "UPDATE queued_follow_ups SET position = $2 WHERE id = $1",
+                queued.id,
+                position
+            )
+            .execute(&mut *tx)
+            .await?;
+        }
+        tx.commit().await?;
+
+        Self::find_by_task_attempt_id(pool, task_attempt_id).await
+    }
+
+    pub async fn delete(pool: &SqlitePool, id: Uuid) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!("DELETE FROM queued_follow_ups WHERE id = $1", id)
+            .execute(pool)
+            .await?;
+        Ok(result.rows_affected())
+    }
+}
@@ -0,0 +1,298 @@
+use chrono::{DateTime, Utc};
+use sqlx::{FromRow, SqlitePool};
+
+/// A settings document, e.g. the config, as the raw JSON mirrored to its file in the data
+/// directory
+#[derive(Debug, Clone, FromRow)]
+pub struct Setting {
+    pub name: String,
+    pub content: String,
+    pub revision: i64,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Content a settings document had before one of its saves
+#[derive(Debug, Clone, FromRow)]
+pub struct SettingRevision {
+    pub name: String,
+    pub revision: i64,
+    pub content: String,
+    pub replaced_at: DateTime<Utc>,
+}
+
+/// A user's JSON merge patch on top of a settings document
+#[derive(Debug, Clone, FromRow)]
+pub struct SettingOverride {
+    pub user_id: String,
+    pub name: String,
+    pub patch: String,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl Setting {
+    pub async fn find(pool: &SqlitePool, name: &str) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            Setting,
+            r#"SELECT
+                name as "name!",
+                content,
+                revision as "revision!: i64",
+                updated_at as "updated_at!: DateTime<Utc>"
+               FROM settings
+               WHERE name = $1"#,
+            name
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// Replace the content of `name`, moving the current one into the revisions, of which
+    /// the `keep_revisions` newest are kept. Saving the content it already has changes
+    /// nothing.
+    pub async fn save(
+        pool: &SqlitePool,
+        name: &str,
+        content: &str,
+        keep_revisions: i64,
+    ) -> Result<Self, sqlx::Error> {
+        let mut tx = pool.begin().await?;
+        let current = sqlx::query!(
+            r#"SELECT content, revision as "revision!: i64" FROM settings WHERE name = $1"#,
+            name
+        )
+        .fetch_optional(&mut *tx)
+        .await?;
+
+        match current {
+            Some(current) if current.content == content => {}
+            Some(current) => {
+                sqlx::query!(
+                    "INSERT INTO settings_revisions (name, revision, content) VALUES ($1, $2, $3)",
+                    name,
+                    current.revision,
+                    current.content
+                )
+                .execute(&mut *tx)
+                .await?;
+                sqlx::query!(
+                    r#"UPDATE settings
+                       SET content = $2, revision = revision + 1, updated_at = datetime('now', 'subsec')
+                       WHERE name = $1"#,
+                    name,
+                    content
+                )
+                .execute(&mut *tx)
+                .await?;
+                sqlx::query!(
+                    r#"DELETE FROM settings_revisions
+                       WHERE name = $1
+                         AND id NOT IN (
+                            SELECT id FROM settings_revisions
+                            WHERE name = $1
+                            ORDER BY revision DESC
+                            LIMIT $2
+                         )"#,
+                    name,
+                    keep_revisions
+                )
+                .execute(&mut *tx)
+                .await?;
+            }
+            None => {
+                sqlx::query!(
+                    "INSERT INTO settings (name, content) VALUES ($1, $2)",
+                    name,
+                    content
+                )
+                .execute(&mut *tx)
+                .await?;
+            }
+        }
+        tx.commit().await?;
+
+        Self::find(pool, name)
+            .await?
+            .ok_or(sqlx::Error::RowNotFound)
+    }
+
+    /// Create `name` along with contents it had before, oldest first with the time each was
+    /// replaced, e.g. for a history kept outside the database until now
+    pub async fn create_with_history(
+        pool: &SqlitePool,
+        name: &str,
+        content: &str,
+        history: &[(String, DateTime<Utc>)],
+    ) -> Result<Self, sqlx::Error> {
+        let mut tx = pool.begin().await?;
+        for (index, (old_content, replaced_at)) in history.iter().enumerate() {
+            let revision = index as i64 + 1;
+            sqlx::query!(
+                r#"INSERT INTO settings_revisions (name, revision, content, replaced_at)
+                   VALUES ($1, $2, $3, $4)"#,
+                name,
+                revision,
+                old_content,
+                replaced_at
+            )
+            .execute(&mut *tx)
+            .await?;
+        }
+        let revision = history.len() as i64 + 1;
+        sqlx::query!(
+            "INSERT INTO settings (name, content, revision) VALUES ($1, $2, $3)",
+            name,
+            content,
+            revision
+        )
+        .execute(&mut *tx)
+        .await?;
+        tx.commit().await?;
+
+        Self::find(pool, name)
+            .await?
+            .ok_or(sqlx::Error::RowNotFound)
+    }
+}
+
+impl SettingRevision {
+    /// Earlier contents of `name`, newest first
+    pub async fn find_by_name(pool: &SqlitePool, name: &str) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            SettingRevision,
+            r#"SELECT
+                name,
+                revision as "revision!: i64",
+                content,
+                replaced_at as "replaced_at!: DateTime<Utc>"
+               FROM settings_revisions
+               WHERE name = $1
+               ORDER BY revision DESC"#,
+            name
+        )
+        .fetch_all(pool)
+        .await
+    }
+}
+
+impl SettingOverride {
+    pub async fn find(
+        pool: &SqlitePool,
+        user_id: &str,
+        name: &str,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            SettingOverride,
+            r#"SELECT
+                user_id as "user_id!",
+                name as "name!",
+                patch,
+                updated_at as "updated_at!: DateTime<Utc>"
+               FROM settings_overrides
+               WHERE user_id = $1 AND name = $2"#,
+            user_id,
+            name
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    pub async fn upsert(
+        pool: &SqlitePool,
+        user_id: &str,
+        name: &str,
+        patch: &str,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"INSERT INTO settings_overrides (user_id, name, patch)
+               VALUES ($1, $2, $3)
+               ON CONFLICT (user_id, name) DO UPDATE SET
+                patch = excluded.patch,
+                updated_at = datetime('now', 'subsec')"#,
+            user_id,
+            name,
+            patch
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn delete(pool: &SqlitePool, user_id: &str, name: &str) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!(
+            "DELETE FROM settings_overrides WHERE user_id = $1 AND name = $2",
+            user_id,
+            name
+        )
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Duration;
+    use sqlx::sqlite::SqlitePoolOptions;
+
+    use super::*;
+
+    async fn pool() -> SqlitePool {
+        // Every connection to `:memory:` opens its own database
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        sqlx::migrate!("./migrations").run(&pool).await.unwrap();
+        pool
+    }
+
+    fn contents(revisions: &[SettingRevision]) -> Vec<&str> {
+        revisions.iter().map(|r| r.content.as_str()).collect()
+    }
+
+    #[tokio::test]
+    async fn test_save_keeps_bounded_revisions_newest_first() {
+        let pool = pool().await;
+        for content in ["one", "one", "two", "three", "four"] {
+            Setting::save(&pool, "config", content, 2).await.unwrap();
+        }
+
+        let setting = Setting::find(&pool, "config").await.unwrap().unwrap();
+        assert_eq!(setting.content, "four");
+        // Saving unchanged content isn't a revision
+        assert_eq!(setting.revision, 4);
+        let revisions = SettingRevision::find_by_name(&pool, "config")
+            .await
+            .unwrap();
+        assert_eq!(contents(&revisions), ["three", "two"]);
+        assert_eq!(revisions[0].revision, 3);
+        assert!(
+            SettingRevision::find_by_name(&pool, "profiles")
+                .await
+                .unwrap()
+                .is_empty()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_history_carries_on_after_import() {
+        let pool = pool().await;
+        let replaced_at = Utc::now() - Duration::days(1);
+        let history = [
+            ("one".to_string(), replaced_at),
+            ("two".to_string(), replaced_at + Duration::hours(1)),
+        ];
+        let setting = Setting::create_with_history(&pool, "config", "three", &history)
+            .await
+            .unwrap();
+        assert_eq!(setting.revision, 3);
+
+        Setting::save(&pool, "config", "four", 3).await.unwrap();
+        let revisions = SettingRevision::find_by_name(&pool, "config")
+            .await
+            .unwrap();
+        assert_eq!(contents(&revisions), ["three", "two", "one"]);
+        assert_eq!(revisions[2].replaced_at, replaced_at);
+    }
+}
@@ -0,0 +1,147 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool, Type};
+use ts_rs::TS;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Copy, Type, Serialize, Deserialize, PartialEq, Eq, TS)]
+#[sqlx(type_name = "suspend_reason", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum SuspendReason {
+    /// Stopped by a graceful shutdown
+    Shutdown,
+    /// Found cut off when the server started again, e.g. after a crash or a reboot
+    Crash,
+}
+
+/// A coding agent execution that was stopped by a shutdown and can be resumed
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct SuspendedExecution {
+    pub id: Uuid,
+    pub execution_process_id: Uuid,
+    pub task_attempt_id: Uuid,
+    pub session_id: Option<String>,
+    pub reason: SuspendReason,
+    pub profile: Option<String>,
+    pub variant: Option<String>,
+    /// Worktree the execution ran in, recreated from the attempt branch if it's gone
+    pub worktree_path: Option<String>,
+    /// Prompt of the stopped execution
+    pub prompt: Option<String>,
+    pub resumed_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug)]
+pub struct CreateSuspendedExecution {
+    pub execution_process_id: Uuid,
+    pub task_attempt_id: Uuid,
+    pub session_id: Option<String>,
+    pub reason: SuspendReason,
+    pub profile: Option<String>,
+    pub variant: Option<String>,
+    pub worktree_path: Option<String>,
+    pub prompt: Option<String>,
+}
+
+impl SuspendedExecution {
+    pub async fn create(
+        pool: &SqlitePool,
+        data: &CreateSuspendedExecution,
+    ) -> Result<Self, sqlx::Error> {
+        let id = Uuid::new_v4();
+        sqlx::query_as!(
+            SuspendedExecution,
+            r#"INSERT INTO suspended_executions
+                (id, execution_process_id, task_attempt_id, session_id, reason, profile, variant,
+                 worktree_path, prompt)
+               VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+               RETURNING
+                id as "id!: Uuid",
+                execution_process_id as "execution_process_id!: Uuid",
+                task_attempt_id as "task_attempt_id!: Uuid",
+                session_id,
+                reason as "reason!: SuspendReason",
+                profile,
+                variant,
+                worktree_path,
+                prompt,
+                resumed_at as "resumed_at: DateTime<Utc>",
+                created_at as "created_at!: DateTime<Utc>""#,
+            id,
+            data.execution_process_id,
+            data.task_attempt_id,
+            data.session_id,
+            data.reason,
+            data.profile,
+            data.variant,
+            data.worktree_path,
+            data.prompt
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    /// Suspended executions that have not been resumed yet, oldest first
+    pub async fn find_pending(pool: &SqlitePool) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            SuspendedExecution,
+            r#"SELECT
+                id as "id!: Uuid",
+                execution_process_id as "execution_process_id!: Uuid",
+                task_attempt_id as "task_attempt_id!: Uuid",
+                session_id,
+                reason as "reason!: SuspendReason",
+                profile,
+                variant,
+                worktree_path,
+                prompt,
+                resumed_at as "resumed_at: DateTime<Utc>",
+                created_at as "created_at!: DateTime<Utc>"
+               FROM suspended_executions
+               WHERE resumed_at IS NULL
+               ORDER BY created_at ASC"#
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// The attempt's latest execution waiting to be resumed
+    pub async fn find_pending_by_task_attempt_id(
+        pool: &SqlitePool,
+        task_attempt_id: Uuid,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            SuspendedExecution,
+            r#"SELECT
+                id as "id!: Uuid",
+                execution_process_id as "execution_process_id!: Uuid",
+                task_attempt_id as "task_attempt_id!: Uuid",
+                session_id,
+                reason as "reason!: SuspendReason",
+                profile,
+                variant,
+                worktree_path,
+                prompt,
+                resumed_at as "resumed_at: DateTime<Utc>",
+                created_at as "created_at!: DateTime<Utc>"
+               FROM suspended_executions
+               WHERE task_attempt_id = $1 AND resumed_at IS NULL
+               ORDER BY created_at DESC
+               LIMIT 1"#,
+            task_attempt_id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    pub async fn mark_resumed(pool: &SqlitePool, id: Uuid) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            "UPDATE suspended_executions SET resumed_at = datetime('now', 'subsec') WHERE id = $1",
+            id
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+}
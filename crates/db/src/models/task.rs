@@ -1,10 +1,16 @@
+use std::cmp::Ordering;
+
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
-use sqlx::{FromRow, SqlitePool, Type};
+use sqlx::{FromRow, SqlitePool, Type, types::Json};
 use ts_rs::TS;
+use utils::pagination::{Listable, serialized_eq};
 use uuid::Uuid;
 
-use super::project::Project;
+use super::{
+    attempt_diff_risk::DiffRisk, attempt_plan::PlanStatus, attempt_review::ReviewVerdict,
+    branch_sync::BranchSyncStatus, project::Project,
+};
 
 #[derive(Debug, Clone, Type, Serialize, Deserialize, PartialEq, TS)]
 #[sqlx(type_name = "task_status", rename_all = "lowercase")]
@@ -17,6 +23,17 @@ pub enum TaskStatus {
     Cancelled,
 }
 
+/// Declared from most to least pressing, so sorting by priority puts urgent tasks first
+#[derive(Debug, Clone, Copy, Type, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, TS)]
+#[sqlx(type_name = "task_priority", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum TaskPriority {
+    Urgent,
+    High,
+    Medium,
+    Low,
+}
+
 #[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
 pub struct Task {
     pub id: Uuid,
@@ -25,6 +42,11 @@ pub struct Task {
     pub description: Option<String>,
     pub status: TaskStatus,
     pub parent_task_attempt: Option<Uuid>, // Foreign key to parent TaskAttempt
+    pub priority: Option<TaskPriority>,
+    #[ts(type = "Array<string>")]
+    pub labels: Json<Vec<String>>,
+    /// Branch or tag attempts start from, `None` for the project's current branch
+    pub base_branch: Option<String>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
@@ -37,12 +59,30 @@ pub struct TaskWithAttemptStatus {
     pub description: Option<String>,
     pub status: TaskStatus,
     pub parent_task_attempt: Option<Uuid>,
+    pub priority: Option<TaskPriority>,
+    #[ts(type = "Array<string>")]
+    pub labels: Json<Vec<String>>,
+    /// Branch or tag attempts start from, `None` for the project's current branch
+    pub base_branch: Option<String>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
     pub has_in_progress_attempt: bool,
     pub has_merged_attempt: bool,
     pub last_attempt_failed: bool,
     pub profile: String,
+    /// Counts of the latest test run across the task's attempts
+    #[ts(type = "number | null")]
+    pub tests_passed: Option<i64>,
+    #[ts(type = "number | null")]
+    pub tests_failed: Option<i64>,
+    /// Diff risk of the task's latest attempt, once an agent run finished
+    pub diff_risk: Option<DiffRisk>,
+    /// Outcome of the latest automatic sync of the latest attempt's branch with its base
+    pub branch_sync_status: Option<BranchSyncStatus>,
+    /// Phase of the latest attempt when it plans before executing
+    pub plan_status: Option<PlanStatus>,
+    /// Verdict of the latest review of the latest attempt
+    pub review_verdict: Option<ReviewVerdict>,
 }
 
 #[derive(Debug, Deserialize, TS)]
@@ -51,6 +91,12 @@ pub struct CreateTask {
     pub title: String,
     pub description: Option<String>,
     pub parent_task_attempt: Option<Uuid>,
+    #[serde(default)]
+    pub priority: Option<TaskPriority>,
+    #[serde(default)]
+    pub labels: Vec<String>,
+    #[serde(default)]
+    pub base_branch: Option<String>,
 }
 
 #[derive(Debug, Deserialize, TS)]
@@ -86,6 +132,9 @@ impl Task {
   t.description,
   t.status                        AS "status!: TaskStatus",
   t.parent_task_attempt           AS "parent_task_attempt: Uuid",
+  t.priority                      AS "priority: TaskPriority",
+  t.labels                        AS "labels!: Json<Vec<String>>",
+  t.base_branch,
   t.created_at                    AS "created_at!: DateTime<Utc>",
   t.updated_at                    AS "updated_at!: DateTime<Utc>",
 
@@ -125,7 +174,61 @@ impl Task {
       WHERE ta.task_id = t.id
      ORDER BY ta.created_at DESC
       LIMIT 1
-    )                               AS "profile!: String"
+    )                               AS "profile!: String",
+
+  ( SELECT tr.passed
+      FROM test_runs tr
+      JOIN task_attempts ta
+        ON tr.task_attempt_id = ta.id
+     WHERE ta.task_id = t.id
+     ORDER BY tr.created_at DESC
+     LIMIT 1
+  )                                 AS "tests_passed: i64",
+
+  ( SELECT tr.failed
+      FROM test_runs tr
+      JOIN task_attempts ta
+        ON tr.task_attempt_id = ta.id
+     WHERE ta.task_id = t.id
+     ORDER BY tr.created_at DESC
+     LIMIT 1
+  )                                 AS "tests_failed: i64",
+
+  ( SELECT dr.risk
+      FROM attempt_diff_risks dr
+      JOIN task_attempts ta
+        ON dr.task_attempt_id = ta.id
+     WHERE ta.task_id = t.id
+     ORDER BY ta.created_at DESC
+     LIMIT 1
+  )                                 AS "diff_risk: Json<DiffRisk>",
+
+  ( SELECT bs.status
+      FROM attempt_branch_syncs bs
+      JOIN task_attempts ta
+        ON bs.task_attempt_id = ta.id
+     WHERE ta.task_id = t.id
+     ORDER BY ta.created_at DESC
+     LIMIT 1
+  )                                 AS "branch_sync_status: BranchSyncStatus",
+
+  ( SELECT ap.status
+      FROM attempt_plans ap
+      JOIN task_attempts ta
+        ON ap.task_attempt_id = ta.id
+     WHERE ta.task_id = t.id
+     ORDER BY ta.created_at DESC
+     LIMIT 1
+  )                                 AS "plan_status: PlanStatus",
+
+  ( SELECT ar.verdict
+      FROM attempt_reviews ar
+      JOIN task_attempts ta
+        ON ar.task_attempt_id = ta.id
+     WHERE ta.task_id = t.id
+     ORDER BY ta.created_at DESC, ar.created_at DESC
+     LIMIT 1
+  )                                 AS "review_verdict: ReviewVerdict"
 
 FROM tasks t
 WHERE t.project_id = $1
@@ -144,12 +247,21 @@ ORDER BY t.created_at DESC"#,
                 description: rec.description,
                 status: rec.status,
                 parent_task_attempt: rec.parent_task_attempt,
+                priority: rec.priority,
+                labels: rec.labels,
+                base_branch: rec.base_branch,
                 created_at: rec.created_at,
                 updated_at: rec.updated_at,
                 has_in_progress_attempt: rec.has_in_progress_attempt != 0,
                 has_merged_attempt: rec.has_merged_attempt != 0,
                 last_attempt_failed: rec.last_attempt_failed != 0,
                 profile: rec.profile,
+                tests_passed: rec.tests_passed,
+                tests_failed: rec.tests_failed,
+                diff_risk: rec.diff_risk.map(|risk| risk.0),
+                branch_sync_status: rec.branch_sync_status,
+                plan_status: rec.plan_status,
+                review_verdict: rec.review_verdict,
             })
             .collect();
 
@@ -159,7 +271,7 @@ ORDER BY t.created_at DESC"#,
     pub async fn find_by_id(pool: &SqlitePool, id: Uuid) -> Result<Option<Self>, sqlx::Error> {
         sqlx::query_as!(
             Task,
-            r#"SELECT id as "id!: Uuid", project_id as "project_id!: Uuid", title, description, status as "status!: TaskStatus", parent_task_attempt as "parent_task_attempt: Uuid", created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>"
+            r#"SELECT id as "id!: Uuid", project_id as "project_id!: Uuid", title, description, status as "status!: TaskStatus", parent_task_attempt as "parent_task_attempt: Uuid", priority as "priority: TaskPriority", labels as "labels!: Json<Vec<String>>", base_branch, created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>"
                FROM tasks 
                WHERE id = $1"#,
             id
@@ -171,7 +283,7 @@ ORDER BY t.created_at DESC"#,
     pub async fn find_by_rowid(pool: &SqlitePool, rowid: i64) -> Result<Option<Self>, sqlx::Error> {
         sqlx::query_as!(
             Task,
-            r#"SELECT id as "id!: Uuid", project_id as "project_id!: Uuid", title, description, status as "status!: TaskStatus", parent_task_attempt as "parent_task_attempt: Uuid", created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>"
+            r#"SELECT id as "id!: Uuid", project_id as "project_id!: Uuid", title, description, status as "status!: TaskStatus", parent_task_attempt as "parent_task_attempt: Uuid", priority as "priority: TaskPriority", labels as "labels!: Json<Vec<String>>", base_branch, created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>"
                FROM tasks 
                WHERE rowid = $1"#,
             rowid
@@ -187,7 +299,7 @@ ORDER BY t.created_at DESC"#,
     ) -> Result<Option<Self>, sqlx::Error> {
         sqlx::query_as!(
             Task,
-            r#"SELECT id as "id!: Uuid", project_id as "project_id!: Uuid", title, description, status as "status!: TaskStatus", parent_task_attempt as "parent_task_attempt: Uuid", created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>"
+            r#"SELECT id as "id!: Uuid", project_id as "project_id!: Uuid", title, description, status as "status!: TaskStatus", parent_task_attempt as "parent_task_attempt: Uuid", priority as "priority: TaskPriority", labels as "labels!: Json<Vec<String>>", base_branch, created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>"
                FROM tasks 
                WHERE id = $1 AND project_id = $2"#,
             id,
@@ -202,17 +314,21 @@ ORDER BY t.created_at DESC"#,
         data: &CreateTask,
         task_id: Uuid,
     ) -> Result<Self, sqlx::Error> {
+        let labels = Json(&data.labels);
         sqlx::query_as!(
             Task,
-            r#"INSERT INTO tasks (id, project_id, title, description, status, parent_task_attempt) 
-               VALUES ($1, $2, $3, $4, $5, $6) 
-               RETURNING id as "id!: Uuid", project_id as "project_id!: Uuid", title, description, status as "status!: TaskStatus", parent_task_attempt as "parent_task_attempt: Uuid", created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>""#,
+            r#"INSERT INTO tasks (id, project_id, title, description, status, parent_task_attempt, priority, labels, base_branch) 
+               VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9) 
+               RETURNING id as "id!: Uuid", project_id as "project_id!: Uuid", title, description, status as "status!: TaskStatus", parent_task_attempt as "parent_task_attempt: Uuid", priority as "priority: TaskPriority", labels as "labels!: Json<Vec<String>>", base_branch, created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>""#,
             task_id,
             data.project_id,
             data.title,
             data.description,
             TaskStatus::Todo as TaskStatus,
-            data.parent_task_attempt
+            data.parent_task_attempt,
+            data.priority,
+            labels,
+            data.base_branch
         )
         .fetch_one(pool)
         .await
@@ -230,9 +346,9 @@ ORDER BY t.created_at DESC"#,
         sqlx::query_as!(
             Task,
             r#"UPDATE tasks 
-               SET title = $3, description = $4, status = $5, parent_task_attempt = $6 
+               SET title = $3, description = $4, status = $5, parent_task_attempt = $6, updated_at = datetime('now', 'subsec') 
                WHERE id = $1 AND project_id = $2 
-               RETURNING id as "id!: Uuid", project_id as "project_id!: Uuid", title, description, status as "status!: TaskStatus", parent_task_attempt as "parent_task_attempt: Uuid", created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>""#,
+               RETURNING id as "id!: Uuid", project_id as "project_id!: Uuid", title, description, status as "status!: TaskStatus", parent_task_attempt as "parent_task_attempt: Uuid", priority as "priority: TaskPriority", labels as "labels!: Json<Vec<String>>", base_branch, created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>""#,
             id,
             project_id,
             title,
@@ -244,13 +360,41 @@ ORDER BY t.created_at DESC"#,
         .await
     }
 
+    /// Like `update`, but only if the task hasn't been written since `existing` was read, in
+    /// the same statement so no other write can land in between. `None` if it has been.
+    pub async fn update_if_unmodified(
+        pool: &SqlitePool,
+        existing: &Task,
+        title: String,
+        description: Option<String>,
+        status: TaskStatus,
+        parent_task_attempt: Option<Uuid>,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            Task,
+            r#"UPDATE tasks 
+               SET title = $3, description = $4, status = $5, parent_task_attempt = $6, updated_at = datetime('now', 'subsec') 
+               WHERE id = $1 AND project_id = $2 AND datetime(updated_at, 'subsec') = datetime($7, 'subsec') 
+               RETURNING id as "id!: Uuid", project_id as "project_id!: Uuid", title, description, status as "status!: TaskStatus", parent_task_attempt as "parent_task_attempt: Uuid", priority as "priority: TaskPriority", labels as "labels!: Json<Vec<String>>", base_branch, created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>""#,
+            existing.id,
+            existing.project_id,
+            title,
+            description,
+            status,
+            parent_task_attempt,
+            existing.updated_at
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
     pub async fn update_status(
         pool: &SqlitePool,
         id: Uuid,
         status: TaskStatus,
     ) -> Result<(), sqlx::Error> {
         sqlx::query!(
-            "UPDATE tasks SET status = $2, updated_at = CURRENT_TIMESTAMP WHERE id = $1",
+            "UPDATE tasks SET status = $2, updated_at = datetime('now', 'subsec') WHERE id = $1",
             id,
             status
         )
@@ -259,6 +403,22 @@ ORDER BY t.created_at DESC"#,
         Ok(())
     }
 
+    /// Point the task's future attempts at `base_branch`, or back at the project's current branch
+    pub async fn set_base_branch(
+        pool: &SqlitePool,
+        id: Uuid,
+        base_branch: Option<&str>,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            "UPDATE tasks SET base_branch = $2, updated_at = datetime('now', 'subsec') WHERE id = $1",
+            id,
+            base_branch
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
     pub async fn delete(pool: &SqlitePool, id: Uuid) -> Result<u64, sqlx::Error> {
         let result = sqlx::query!("DELETE FROM tasks WHERE id = $1", id)
             .execute(pool)
@@ -288,7 +448,7 @@ ORDER BY t.created_at DESC"#,
         // Find both children and parent for this attempt
         sqlx::query_as!(
             Task,
-            r#"SELECT DISTINCT t.id as "id!: Uuid", t.project_id as "project_id!: Uuid", t.title, t.description, t.status as "status!: TaskStatus", t.parent_task_attempt as "parent_task_attempt: Uuid", t.created_at as "created_at!: DateTime<Utc>", t.updated_at as "updated_at!: DateTime<Utc>"
+            r#"SELECT DISTINCT t.id as "id!: Uuid", t.project_id as "project_id!: Uuid", t.title, t.description, t.status as "status!: TaskStatus", t.parent_task_attempt as "parent_task_attempt: Uuid", t.priority as "priority: TaskPriority", t.labels as "labels!: Json<Vec<String>>", t.base_branch, t.created_at as "created_at!: DateTime<Utc>", t.updated_at as "updated_at!: DateTime<Utc>"
                FROM tasks t
                WHERE (
                    -- Find children: tasks that have this attempt as parent
@@ -310,3 +470,50 @@ ORDER BY t.created_at DESC"#,
         .await
     }
 }
+
+impl Listable for TaskWithAttemptStatus {
+    const SORT_FIELDS: &'static [&'static str] =
+        &["title", "status", "priority", "created_at", "updated_at"];
+    const FILTER_FIELDS: &'static [&'static str] = &[
+        "status",
+        "priority",
+        "label",
+        "profile",
+        "has_in_progress_attempt",
+        "has_merged_attempt",
+        "last_attempt_failed",
+        "risk_flag",
+    ];
+
+    fn compare_by(&self, other: &Self, field: &str) -> Ordering {
+        match field {
+            "title" => self.title.to_lowercase().cmp(&other.title.to_lowercase()),
+            "status" => (self.status.clone() as u8).cmp(&(other.status.clone() as u8)),
+            // Tasks without a priority go last
+            "priority" => (self.priority.is_none(), self.priority)
+                .cmp(&(other.priority.is_none(), other.priority)),
+            "updated_at" => self.updated_at.cmp(&other.updated_at),
+            _ => self.created_at.cmp(&other.created_at),
+        }
+    }
+
+    fn matches_filter(&self, field: &str, value: &str) -> bool {
+        match field {
+            "status" => serialized_eq(&self.status, value),
+            "priority" => serialized_eq(&self.priority, value),
+            "label" => self
+                .labels
+                .iter()
+                .any(|label| label.eq_ignore_ascii_case(value)),
+            "profile" => self.profile.eq_ignore_ascii_case(value),
+            "has_in_progress_attempt" => serialized_eq(&self.has_in_progress_attempt, value),
+            "has_merged_attempt" => serialized_eq(&self.has_merged_attempt, value),
+            "last_attempt_failed" => serialized_eq(&self.last_attempt_failed, value),
+            "risk_flag" => self
+                .diff_risk
+                .as_ref()
+                .is_some_and(|risk| risk.flags.iter().any(|flag| flag.kind() == value)),
+            _ => true,
+        }
+    }
+}
@@ -0,0 +1,89 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool};
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// A file uploaded to a task, its contents live in the attachment store
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct TaskAttachment {
+    pub id: Uuid,
+    pub task_id: Uuid,
+    pub file_name: String,
+    pub content_type: String,
+    #[ts(type = "number")]
+    pub size_bytes: i64,
+    pub created_at: DateTime<Utc>,
+}
+
+pub struct CreateTaskAttachment<'a> {
+    pub file_name: &'a str,
+    pub content_type: &'a str,
+    pub size_bytes: i64,
+}
+
+impl TaskAttachment {
+    /// Whether the attachment is an image in a format agents with image input can read
+    pub fn is_image(&self) -> bool {
+        matches!(
+            self.content_type.as_str(),
+            "image/png" | "image/jpeg" | "image/gif" | "image/webp"
+        )
+    }
+
+    pub async fn find_by_task_id(
+        pool: &SqlitePool,
+        task_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            TaskAttachment,
+            r#"SELECT id as "id!: Uuid", task_id as "task_id!: Uuid", file_name, content_type, size_bytes, created_at as "created_at!: DateTime<Utc>"
+               FROM task_attachments
+               WHERE task_id = $1
+               ORDER BY created_at ASC"#,
+            task_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn find_by_id(pool: &SqlitePool, id: Uuid) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            TaskAttachment,
+            r#"SELECT id as "id!: Uuid", task_id as "task_id!: Uuid", file_name, content_type, size_bytes, created_at as "created_at!: DateTime<Utc>"
+               FROM task_attachments
+               WHERE id = $1"#,
+            id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    pub async fn create(
+        pool: &SqlitePool,
+        id: Uuid,
+        task_id: Uuid,
+        data: &CreateTaskAttachment<'_>,
+    ) -> Result<Self, sqlx::Error> {
+        sqlx::query_as!(
+            TaskAttachment,
+            r#"INSERT INTO task_attachments (id, task_id, file_name, content_type, size_bytes)
+               VALUES ($1, $2, $3, $4, $5)
+               RETURNING id as "id!: Uuid", task_id as "task_id!: Uuid", file_name, content_type, size_bytes, created_at as "created_at!: DateTime<Utc>""#,
+            id,
+            task_id,
+            data.file_name,
+            data.content_type,
+            data.size_bytes
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    pub async fn delete(pool: &SqlitePool, id: Uuid) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!("DELETE FROM task_attachments WHERE id = $1", id)
+            .execute(pool)
+            .await?;
+        Ok(result.rows_affected())
+    }
+}
@@ -1,8 +1,11 @@
+use std::cmp::Ordering;
+
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use sqlx::{FromRow, SqlitePool, Type};
 use thiserror::Error;
 use ts_rs::TS;
+use utils::pagination::Listable;
 use uuid::Uuid;
 
 use super::{project::Project, task::Task};
@@ -395,6 +398,45 @@ impl TaskAttempt {
             .collect())
     }
 
+    /// Attempts of a project whose worktree hasn't been cleaned up
+    pub async fn find_with_worktree_by_project_id(
+        pool: &SqlitePool,
+        project_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            TaskAttempt,
+            r#"SELECT  ta.id                AS "id!: Uuid",
+                       ta.task_id           AS "task_id!: Uuid",
+                       ta.container_ref,
+                       ta.branch,
+                       ta.merge_commit,
+                       ta.base_branch,
+                       ta.profile AS "profile!",
+                       ta.pr_url,
+                       ta.pr_number,
+                       ta.pr_status,
+                       ta.pr_merged_at      AS "pr_merged_at: DateTime<Utc>",
+                       ta.worktree_deleted  AS "worktree_deleted!: bool",
+                       ta.setup_completed_at AS "setup_completed_at: DateTime<Utc>",
+                       ta.created_at        AS "created_at!: DateTime<Utc>",
+                       ta.updated_at        AS "updated_at!: DateTime<Utc>"
+               FROM    task_attempts ta
+               JOIN    tasks t ON ta.task_id = t.id
+               WHERE   t.project_id = $1
+                 AND   ta.worktree_deleted = FALSE
+                 AND   ta.container_ref IS NOT NULL
+               ORDER BY ta.created_at ASC"#,
+            project_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Whether the attempt's changes reached its base branch, by merging here or through its PR
+    pub fn is_merged(&self) -> bool {
+        self.merge_commit.is_some() || self.pr_status.as_deref() == Some("merged")
+    }
+
     pub async fn find_by_worktree_deleted(
         pool: &SqlitePool,
     ) -> Result<Vec<(Uuid, String)>, sqlx::Error> {
@@ -519,6 +561,21 @@ impl TaskAttempt {
         Ok(())
     }
 
+    /// Forget the attempt's merge, once it was reverted
+    pub async fn clear_merge_commit(
+        pool: &SqlitePool,
+        attempt_id: Uuid,
+    ) -> Result<(), TaskAttemptError> {
+        sqlx::query!(
+            "UPDATE task_attempts SET merge_commit = NULL, updated_at = datetime('now') WHERE id = $1",
+            attempt_id
+        )
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+
     pub async fn update_base_branch(
         pool: &SqlitePool,
         attempt_id: Uuid,
@@ -596,3 +653,24 @@ impl TaskAttempt {
             .collect())
     }
 }
+
+impl Listable for TaskAttempt {
+    const SORT_FIELDS: &'static [&'static str] = &["created_at", "updated_at"];
+    const FILTER_FIELDS: &'static [&'static str] = &["profile", "base_branch", "pr_status"];
+
+    fn compare_by(&self, other: &Self, field: &str) -> Ordering {
+        match field {
+            "updated_at" => self.updated_at.cmp(&other.updated_at),
+            _ => self.created_at.cmp(&other.created_at),
+        }
+    }
+
+    fn matches_filter(&self, field: &str, value: &str) -> bool {
+        match field {
+            "profile" => self.profile.eq_ignore_ascii_case(value),
+            "base_branch" => self.base_branch == value,
+            "pr_status" => self.pr_status.as_deref() == Some(value),
+            _ => true,
+        }
+    }
+}
@@ -0,0 +1,95 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool, types::Json};
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// Counts parsed from the output of a test stage
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, TS)]
+pub struct TestSummary {
+    #[ts(type = "number")]
+    pub passed: i64,
+    #[ts(type = "number")]
+    pub failed: i64,
+    #[ts(type = "number")]
+    pub skipped: i64,
+    /// Names of the failed tests, in report order
+    pub failed_tests: Vec<String>,
+}
+
+/// Result of the test stage of an attempt's execution chain
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct TestRun {
+    pub id: Uuid,
+    pub task_attempt_id: Uuid,
+    pub execution_process_id: Uuid,
+    #[ts(type = "number")]
+    pub passed: i64,
+    #[ts(type = "number")]
+    pub failed: i64,
+    #[ts(type = "number")]
+    pub skipped: i64,
+    #[ts(type = "Array<string>")]
+    pub failed_tests: Json<Vec<String>>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl TestRun {
+    pub async fn create(
+        pool: &SqlitePool,
+        task_attempt_id: Uuid,
+        execution_process_id: Uuid,
+        summary: &TestSummary,
+    ) -> Result<Self, sqlx::Error> {
+        let id = Uuid::new_v4();
+        let failed_tests = Json(&summary.failed_tests);
+        sqlx::query_as!(
+            TestRun,
+            r#"INSERT INTO test_runs (id, task_attempt_id, execution_process_id, passed, failed, skipped, failed_tests)
+               VALUES ($1, $2, $3, $4, $5, $6, $7)
+               RETURNING
+                id as "id!: Uuid",
+                task_attempt_id as "task_attempt_id!: Uuid",
+                execution_process_id as "execution_process_id!: Uuid",
+                passed,
+                failed,
+                skipped,
+                failed_tests as "failed_tests!: Json<Vec<String>>",
+                created_at as "created_at!: DateTime<Utc>""#,
+            id,
+            task_attempt_id,
+            execution_process_id,
+            summary.passed,
+            summary.failed,
+            summary.skipped,
+            failed_tests
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    /// Test runs of an attempt, newest first
+    pub async fn find_by_task_attempt_id(
+        pool: &SqlitePool,
+        task_attempt_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            TestRun,
+            r#"SELECT
+                id as "id!: Uuid",
+                task_attempt_id as "task_attempt_id!: Uuid",
+                execution_process_id as "execution_process_id!: Uuid",
+                passed,
+                failed,
+                skipped,
+                failed_tests as "failed_tests!: Json<Vec<String>>",
+                created_at as "created_at!: DateTime<Utc>"
+               FROM test_runs
+               WHERE task_attempt_id = $1
+               ORDER BY created_at DESC"#,
+            task_attempt_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+}
@@ -0,0 +1,345 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sqlx::{FromRow, SqlitePool, Type, types::Json};
+use ts_rs::TS;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Copy, Type, Serialize, Deserialize, PartialEq, Eq, Hash, TS)]
+#[sqlx(type_name = "webhook_event")]
+pub enum WebhookEvent {
+    #[serde(rename = "task.created")]
+    #[sqlx(rename = "task.created")]
+    TaskCreated,
+    #[serde(rename = "task.updated")]
+    #[sqlx(rename = "task.updated")]
+    TaskUpdated,
+    #[serde(rename = "attempt.created")]
+    #[sqlx(rename = "attempt.created")]
+    AttemptCreated,
+    #[serde(rename = "attempt.updated")]
+    #[sqlx(rename = "attempt.updated")]
+    AttemptUpdated,
+    #[serde(rename = "execution.started")]
+    #[sqlx(rename = "execution.started")]
+    ExecutionStarted,
+    #[serde(rename = "execution.completed")]
+    #[sqlx(rename = "execution.completed")]
+    ExecutionCompleted,
+    #[serde(rename = "execution.failed")]
+    #[sqlx(rename = "execution.failed")]
+    ExecutionFailed,
+    #[serde(rename = "execution.killed")]
+    #[sqlx(rename = "execution.killed")]
+    ExecutionKilled,
+    /// Sent on request to check an endpoint, whatever events it subscribed to
+    #[serde(rename = "ping")]
+    #[sqlx(rename = "ping")]
+    Ping,
+}
+
+#[derive(Debug, Clone, Copy, Type, Serialize, Deserialize, PartialEq, Eq, TS)]
+#[sqlx(type_name = "webhook_delivery_status", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum WebhookDeliveryStatus {
+    Pending,
+    Delivered,
+    /// Every attempt failed
+    Failed,
+}
+
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct Webhook {
+    pub id: Uuid,
+    pub url: String,
+    /// Events posted to the endpoint, all of them when empty
+    #[ts(type = "Array<WebhookEvent>")]
+    pub events: Json<Vec<WebhookEvent>>,
+    pub enabled: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Deserialize, TS)]
+pub struct CreateWebhook {
+    pub url: String,
+    #[serde(default)]
+    pub events: Vec<WebhookEvent>,
+}
+
+#[derive(Debug, Clone, Deserialize, TS)]
+pub struct UpdateWebhook {
+    pub url: String,
+    pub events: Vec<WebhookEvent>,
+    pub enabled: bool,
+}
+
+/// An event posted, or to be posted, to a webhook
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct WebhookDelivery {
+    pub id: Uuid,
+    pub webhook_id: Uuid,
+    pub event: WebhookEvent,
+    /// The task, attempt or execution process the event is about
+    #[ts(type = "JsonValue")]
+    pub data: Json<Value>,
+    pub status: WebhookDeliveryStatus,
+    #[ts(type = "number")]
+    pub attempts: i64,
+    pub next_attempt_at: DateTime<Utc>,
+    /// HTTP status of the last response, `None` when the endpoint couldn't be reached
+    #[ts(type = "number | null")]
+    pub response_status: Option<i64>,
+    pub last_error: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub delivered_at: Option<DateTime<Utc>>,
+}
+
+impl Webhook {
+    pub fn subscribes_to(&self, event: WebhookEvent) -> bool {
+        self.enabled && (self.events.is_empty() || self.events.contains(&event))
+    }
+
+    pub async fn find_all(pool: &SqlitePool) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            Webhook,
+            r#"SELECT
+                id as "id!: Uuid",
+                url,
+                events as "events!: Json<Vec<WebhookEvent>>",
+                enabled as "enabled!: bool",
+                created_at as "created_at!: DateTime<Utc>",
+                updated_at as "updated_at!: DateTime<Utc>"
+               FROM webhooks
+               ORDER BY created_at"#
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn find_by_id(pool: &SqlitePool, id: Uuid) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            Webhook,
+            r#"SELECT
+                id as "id!: Uuid",
+                url,
+                events as "events!: Json<Vec<WebhookEvent>>",
+                enabled as "enabled!: bool",
+                created_at as "created_at!: DateTime<Utc>",
+                updated_at as "updated_at!: DateTime<Utc>"
+               FROM webhooks
+               WHERE id = $1"#,
+            id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    pub async fn create(pool: &SqlitePool, data: &CreateWebhook) -> Result<Self, sqlx::Error> {
+        let id = Uuid::new_v4();
+        let events = Json(&data.events);
+        sqlx::query_as!(
+            Webhook,
+            r#"INSERT INTO webhooks (id, url, events)
+               VALUES ($1, $2, $3)
+               RETURNING
+                id as "id!: Uuid",
+                url,
+                events as "events!: Json<Vec<WebhookEvent>>",
+                enabled as "enabled!: bool",
+                created_at as "created_at!: DateTime<Utc>",
+                updated_at as "updated_at!: DateTime<Utc>""#,
+            id,
+            data.url,
+            events
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    pub async fn update(
+        pool: &SqlitePool,
+        id: Uuid,
+        data: &UpdateWebhook,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        let events = Json(&data.events);
+        sqlx::query_as!(
+            Webhook,
+            r#"UPDATE webhooks
+               SET url = $2, events = $3, enabled = $4, updated_at = datetime('now', 'subsec')
+               WHERE id = $1
+               RETURNING
+                id as "id!: Uuid",
+                url,
+                events as "events!: Json<Vec<WebhookEvent>>",
+                enabled as "enabled!: bool",
+                created_at as "created_at!: DateTime<Utc>",
+                updated_at as "updated_at!: DateTime<Utc>""#,
+            id,
+            data.url,
+            events,
+            data.enabled
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    pub async fn delete(pool: &SqlitePool, id: Uuid) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!("DELETE FROM webhooks WHERE id = $1", id)
+            .execute(pool)
+            .await?;
+        Ok(result.rows_affected())
+    }
+}
+
+impl WebhookDelivery {
+    pub async fn create(
+        pool: &SqlitePool,
+        webhook_id: Uuid,
+        event: WebhookEvent,
+        data: &Value,
+    ) -> Result<Self, sqlx::Error> {
+        let id = Uuid::new_v4();
+        let data = Json(data);
+        sqlx::query_as!(
+            WebhookDelivery,
+            r#"INSERT INTO webhook_deliveries (id, webhook_id, event, data)
+               VALUES ($1, $2, $3, $4)
+               RETURNING
+                id as "id!: Uuid",
+                webhook_id as "webhook_id!: Uuid",
+                event as "event!: WebhookEvent",
+                data as "data!: Json<Value>",
+                status as "status!: WebhookDeliveryStatus",
+                attempts,
+                next_attempt_at as "next_attempt_at!: DateTime<Utc>",
+                response_status,
+                last_error,
+                created_at as "created_at!: DateTime<Utc>",
+                delivered_at as "delivered_at: DateTime<Utc>""#,
+            id,
+            webhook_id,
+            event,
+            data
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    /// Deliveries of a webhook, newest first
+    pub async fn find_by_webhook_id(
+        pool: &SqlitePool,
+        webhook_id: Uuid,
+        limit: i64,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            WebhookDelivery,
+            r#"SELECT
+                id as "id!: Uuid",
+                webhook_id as "webhook_id!: Uuid",
+                event as "event!: WebhookEvent",
+                data as "data!: Json<Value>",
+                status as "status!: WebhookDeliveryStatus",
+                attempts,
+                next_attempt_at as "next_attempt_at!: DateTime<Utc>",
+                response_status,
+                last_error,
+                created_at as "created_at!: DateTime<Utc>",
+                delivered_at as "delivered_at: DateTime<Utc>"
+               FROM webhook_deliveries
+               WHERE webhook_id = $1
+               ORDER BY created_at DESC
+               LIMIT $2"#,
+            webhook_id,
+            limit
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Pending deliveries of enabled webhooks whose next attempt is due, oldest first
+    pub async fn find_due(pool: &SqlitePool, limit: i64) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            WebhookDelivery,
+            r#"SELECT
+                d.id as "id!: Uuid",
+                d.webhook_id as "webhook_id!: Uuid",
+                d.event as "event!: WebhookEvent",
+                d.data as "data!: Json<Value>",
+                d.status as "status!: WebhookDeliveryStatus",
+                d.attempts,
+                d.next_attempt_at as "next_attempt_at!: DateTime<Utc>",
+                d.response_status,
+                d.last_error,
+                d.created_at as "created_at!: DateTime<Utc>",
+                d.delivered_at as "delivered_at: DateTime<Utc>"
+               FROM webhook_deliveries d
+               JOIN webhooks w ON w.id = d.webhook_id
+               WHERE d.status = 'pending'
+                 AND w.enabled = 1
+                 AND d.next_attempt_at <= datetime('now', 'subsec')
+               ORDER BY d.next_attempt_at
+               LIMIT $1"#,
+            limit
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn mark_delivered(
+        pool: &SqlitePool,
+        id: Uuid,
+        response_status: i64,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"UPDATE webhook_deliveries
+               SET status = 'delivered', attempts = attempts + 1, response_status = $2,
+                   last_error = NULL, delivered_at = datetime('now', 'subsec')
+               WHERE id = $1"#,
+            id,
+            response_status
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Record a failed attempt. The delivery is tried again after `retry_in_seconds`, or
+    /// given up on when that's `None`.
+    pub async fn record_failure(
+        pool: &SqlitePool,
+        id: Uuid,
+        response_status: Option<i64>,
+        error: &str,
+        retry_in_seconds: Option<i64>,
+    ) -> Result<(), sqlx::Error> {
+        let retry_modifier = retry_in_seconds.map(|seconds| format!("+{seconds} seconds"));
+        sqlx::query!(
+            r#"UPDATE webhook_deliveries
+               SET status = CASE WHEN $4 IS NULL THEN 'failed' ELSE 'pending' END,
+                   attempts = attempts + 1, response_status = $2, last_error = $3,
+                   next_attempt_at = COALESCE(datetime('now', 'subsec', $4), next_attempt_at)
+               WHERE id = $1"#,
+            id,
+            response_status,
+            error,
+            retry_modifier
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Forget deliveries that ended more than `days` ago
+    pub async fn prune(pool: &SqlitePool, days: i64) -> Result<u64, sqlx::Error> {
+        let modifier = format!("-{days} days");
+        let result = sqlx::query!(
+            r#"DELETE FROM webhook_deliveries
+               WHERE status != 'pending' AND created_at < datetime('now', $1)"#,
+            modifier
+        )
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected())
+    }
+}
@@ -1,4 +1,8 @@
-use std::{collections::HashMap, sync::Arc};
+use std::{
+    collections::HashMap,
+    sync::Arc,
+    time::{Duration, Instant},
+};
 
 use anyhow::Error as AnyhowError;
 use async_trait::async_trait;
@@ -6,7 +10,10 @@ use axum::response::sse::Event;
 use db::{
     DBService,
     models::{
+        evaluation::Evaluation,
         execution_process::{ExecutionProcess, ExecutionProcessRunReason, ExecutionProcessStatus},
+        execution_process_logs::ExecutionProcessLogs,
+        suspended_execution::SuspendReason,
         task::{Task, TaskStatus},
         task_attempt::{TaskAttempt, TaskAttemptError},
     },
@@ -16,22 +23,37 @@ use futures::{StreamExt, TryStreamExt};
 use git2::Error as Git2Error;
 use serde_json::Value;
 use services::services::{
+    agent_login::AgentLoginService,
     analytics::AnalyticsService,
     auth::{AuthError, AuthService},
-    config::{Config, ConfigError},
+    backup::BackupService,
+    branch_sync::BranchSyncService,
+    checkpoints::CheckpointService,
+    ci_triage,
+    config::{self, Config, ConfigError, ShutdownMode},
+    config_watcher::ConfigWatcher,
+    conflicts::ConflictTracker,
     container::{ContainerError, ContainerService},
+    dependency_updates,
+    dev_server::DevServerService,
+    diff_stats::DiffStatsService,
     events::{EventError, EventService},
     filesystem::{FilesystemError, FilesystemService},
     filesystem_watcher::FilesystemWatcherError,
     git::{GitService, GitServiceError},
+    merge_queue::MergeQueueService,
     pr_monitor::PrMonitorService,
+    pty::PtyService,
+    repo_clone::CloneJobs,
     sentry::SentryService,
+    shutdown::ShutdownService,
+    webhooks::WebhookService,
     worktree_manager::WorktreeError,
 };
 use sqlx::{Error as SqlxError, types::Uuid};
 use thiserror::Error;
 use tokio::sync::RwLock;
-use utils::msg_store::MsgStore;
+use utils::{log_msg::LogMsg, msg_store::MsgStore};
 
 #[derive(Debug, Error)]
 pub enum DeploymentError {
@@ -79,7 +101,7 @@ pub trait Deployment: Clone + Send + Sync + 'static {
 
     fn db(&self) -> &DBService;
 
-    fn analytics(&self) -> &Option<AnalyticsService>;
+    fn analytics(&self) -> &AnalyticsService;
 
     fn container(&self) -> &impl ContainerService;
 
@@ -93,6 +115,26 @@ pub trait Deployment: Clone + Send + Sync + 'static {
 
     fn events(&self) -> &EventService;
 
+    fn shutdown(&self) -> &ShutdownService;
+
+    fn backup(&self) -> &BackupService;
+
+    fn agent_login(&self) -> &AgentLoginService;
+
+    fn config_watcher(&self) -> &ConfigWatcher;
+
+    fn pty(&self) -> &PtyService;
+
+    fn dev_servers(&self) -> &DevServerService;
+
+    fn diff_stats(&self) -> &DiffStatsService;
+
+    fn checkpoints(&self) -> &CheckpointService;
+
+    fn conflicts(&self) -> &ConflictTracker;
+
+    fn clones(&self) -> &CloneJobs;
+
     async fn update_sentry_scope(&self) -> Result<(), DeploymentError> {
         let user_id = self.user_id();
         let config = self.config().read().await;
@@ -104,23 +146,63 @@ pub trait Deployment: Clone + Send + Sync + 'static {
         Ok(())
     }
 
+    /// Store `config`, the running config, leaving out what the user's override changed
+    async fn save_config(&self, config: &Config) -> Result<(), ConfigError> {
+        config::save_config(&self.db().pool, self.user_id(), config).await
+    }
+
     async fn spawn_pr_monitor_service(&self) -> tokio::task::JoinHandle<()> {
         let db = self.db().clone();
         let config = self.config().clone();
         PrMonitorService::spawn(db, config).await
     }
 
-    async fn track_if_analytics_allowed(&self, event_name: &str, properties: Value) {
-        if let Some(true) = self.config().read().await.analytics_enabled {
-            // Does the user allow analytics?
-            if let Some(analytics) = self.analytics() {
-                // Is analytics setup?
-                analytics.track_event(self.user_id(), event_name, Some(properties.clone()));
-            }
-        }
+    async fn spawn_branch_sync_service(&self) -> tokio::task::JoinHandle<()> {
+        let db = self.db().clone();
+        let git = self.git().clone();
+        BranchSyncService::spawn(db, git).await
+    }
+
+    async fn spawn_merge_queue_service(&self) -> tokio::task::JoinHandle<()> {
+        let db = self.db().clone();
+        let git = self.git().clone();
+        let conflicts = self.conflicts().clone();
+        MergeQueueService::spawn(db, git, conflicts).await
+    }
+
+    async fn spawn_webhook_service(&self) -> tokio::task::JoinHandle<()> {
+        let db = self.db().clone();
+        let events = self.events().msg_store().clone();
+        WebhookService::spawn(db, events).await
+    }
+
+    /// Watch the GitHub Actions workflows projects opted into, filing failures as cards
+    async fn spawn_ci_triage_service(&self) -> tokio::task::JoinHandle<()> {
+        let deployment = self.clone();
+        tokio::spawn(async move {
+            let config = deployment.config().clone();
+            ci_triage::run(deployment.container(), config).await
+        })
+    }
+
+    /// Run the scheduled dependency updates of projects, opening a pull request per group
+    async fn spawn_dependency_update_service(&self) -> tokio::task::JoinHandle<()> {
+        let deployment = self.clone();
+        tokio::spawn(async move {
+            let config = deployment.config().clone();
+            dependency_updates::run(deployment.container(), config).await
+        })
+    }
+
+    /// Record a usage event locally, and export it if the user allows analytics
+    async fn track_event(&self, event_name: &str, properties: Value) {
+        self.analytics().track_event(event_name, properties).await;
     }
 
-    /// Cleanup executions marked as running in the db, call at startup
+    /// Recover executions marked as running in the db, call at startup.
+    ///
+    /// Executions whose process group survived an unclean shutdown are re-attached, the
+    /// rest are marked failed with a note in their logs, as are evaluations left running.
     async fn cleanup_orphan_executions(&self) -> Result<(), DeploymentError> {
         let running_processes = ExecutionProcess::find_running(&self.db().pool).await?;
         for process in running_processes {
@@ -129,6 +211,61 @@ pub trait Deployment: Clone + Send + Sync + 'static {
                 process.id,
                 process.task_attempt_id
             );
+            match self.container().reattach_execution(&process).await {
+                Ok(true) => {
+                    tracing::info!(
+                        "Re-attached to execution process {} after restart",
+                        process.id
+                    );
+                    continue;
+                }
+                Ok(false) => {}
+                Err(e) => {
+                    tracing::error!(
+                        "Failed to re-attach to execution process {}: {}",
+                        process.id,
+                        e
+                    );
+                }
+            }
+            // Coding agents can carry on from their session, see `resume_suspended`
+            let resumable = process.run_reason == ExecutionProcessRunReason::CodingAgent
+                && match self
+                    .container()
+                    .record_suspended(&process, SuspendReason::Crash)
+                    .await
+                {
+                    Ok(suspended) => suspended.session_id.is_some(),
+                    Err(e) => {
+                        tracing::error!(
+                            "Failed to record interrupted execution {}: {}",
+                            process.id,
+                            e
+                        );
+                        false
+                    }
+                };
+            let note = LogMsg::Stderr(if resumable {
+                "Execution was interrupted by an unclean shutdown of vibe-kanban, resume the attempt to continue its session\n"
+                    .to_string()
+            } else {
+                "Execution was interrupted by an unclean shutdown of vibe-kanban and could not be recovered\n"
+                    .to_string()
+            });
+            if let Ok(line) = serde_json::to_string(&note)
+                && let Err(e) = ExecutionProcessLogs::append_log_line(
+                    &self.db().pool,
+                    process.id,
+                    &format!("{line}\n"),
+                )
+                .await
+            {
+                tracing::error!(
+                    "Failed to record recovery note for execution process {}: {}",
+                    process.id,
+                    e
+                );
+            }
             // Update the execution process status first
             if let Err(e) = ExecutionProcess::update_completion(
                 &self.db().pool,
@@ -165,9 +302,79 @@ pub trait Deployment: Clone + Send + Sync + 'static {
                 );
             }
         }
+        // Evaluations are driven by the server, so they ended with it
+        let interrupted = Evaluation::fail_interrupted(&self.db().pool).await?;
+        if interrupted > 0 {
+            tracing::info!("Marked {} interrupted evaluations as failed", interrupted);
+        }
         Ok(())
     }
 
+    /// Stop accepting new work and hand over running executions before the server exits.
+    ///
+    /// In `Wait` mode running coding agents get up to `wait_timeout_secs` to finish. Anything
+    /// still running afterwards is stopped, and coding agents are recorded as suspended so
+    /// they can be resumed from their session on the next start.
+    async fn shutdown_gracefully(&self) {
+        self.shutdown().begin_draining();
+
+        let shutdown_config = self.config().read().await.shutdown.clone();
+        if shutdown_config.mode == ShutdownMode::Wait {
+            let deadline =
+                Instant::now() + Duration::from_secs(shutdown_config.wait_timeout_secs as u64);
+            loop {
+                let remaining = match ExecutionProcess::find_running(&self.db().pool).await {
+                    Ok(processes) => processes
+                        .iter()
+                        .filter(|p| p.run_reason != ExecutionProcessRunReason::DevServer)
+                        .count(),
+                    Err(e) => {
+                        tracing::error!("Failed to check running executions: {}", e);
+                        break;
+                    }
+                };
+                if remaining == 0 || Instant::now() >= deadline {
+                    break;
+                }
+                tracing::info!(
+                    "Waiting for {} running execution(s) before shutting down",
+                    remaining
+                );
+                tokio::time::sleep(Duration::from_secs(1)).await;
+            }
+        }
+
+        let running_processes = match ExecutionProcess::find_running(&self.db().pool).await {
+            Ok(processes) => processes,
+            Err(e) => {
+                tracing::error!("Failed to load running executions on shutdown: {}", e);
+                Vec::new()
+            }
+        };
+        for process in running_processes {
+            if process.run_reason == ExecutionProcessRunReason::CodingAgent
+                && let Err(e) = self
+                    .container()
+                    .record_suspended(&process, SuspendReason::Shutdown)
+                    .await
+            {
+                tracing::error!("Failed to record suspended execution {}: {}", process.id, e);
+            }
+            if let Err(e) = self.container().stop_execution(&process).await {
+                tracing::error!(
+                    "Failed to stop execution process {} on shutdown: {}",
+                    process.id,
+                    e
+                );
+            } else {
+                tracing::info!("Stopped execution process {} on shutdown", process.id);
+            }
+        }
+
+        self.pty().terminate_all();
+        self.shutdown().cancel_streams();
+    }
+
     async fn stream_events(
         &self,
     ) -> futures::stream::BoxStream<'static, Result<Event, std::io::Error>> {
@@ -7,6 +7,7 @@ use ts_rs::TS;
 
 use crate::{
     actions::Executable,
+    env::ExecutionEnv,
     executors::{CodingAgent, ExecutorError, StandardCodingAgentExecutor},
     profile::ProfileVariantLabel,
 };
@@ -20,10 +21,14 @@ pub struct CodingAgentFollowUpRequest {
 
 #[async_trait]
 impl Executable for CodingAgentFollowUpRequest {
-    async fn spawn(&self, current_dir: &PathBuf) -> Result<AsyncGroupChild, ExecutorError> {
+    async fn spawn(
+        &self,
+        current_dir: &PathBuf,
+        env: &ExecutionEnv,
+    ) -> Result<AsyncGroupChild, ExecutorError> {
         let executor = CodingAgent::from_profile_variant_label(&self.profile_variant_label)?;
         executor
-            .spawn_follow_up(current_dir, &self.prompt, &self.session_id)
+            .spawn_follow_up(current_dir, &self.prompt, &self.session_id, env)
             .await
     }
 }
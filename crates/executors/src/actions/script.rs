@@ -3,11 +3,10 @@ use std::path::PathBuf;
 use async_trait::async_trait;
 use command_group::{AsyncCommandGroup, AsyncGroupChild};
 use serde::{Deserialize, Serialize};
-use tokio::process::Command;
 use ts_rs::TS;
-use utils::shell::get_shell_command;
+use utils::port_file::{DEV_SERVER_PORT_FILE_ENV, dev_server_port_file};
 
-use crate::{actions::Executable, executors::ExecutorError};
+use crate::{actions::Executable, env::ExecutionEnv, executors::ExecutorError};
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, TS)]
 pub enum ScriptRequestLanguage {
@@ -19,6 +18,9 @@ pub enum ScriptContext {
     SetupScript,
     CleanupScript,
     DevServer,
+    VerificationScript,
+    TestScript,
+    LintScript,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, TS)]
@@ -30,17 +32,30 @@ pub struct ScriptRequest {
 
 #[async_trait]
 impl Executable for ScriptRequest {
-    async fn spawn(&self, current_dir: &PathBuf) -> Result<AsyncGroupChild, ExecutorError> {
-        let (shell_cmd, shell_arg) = get_shell_command();
-        let mut command = Command::new(shell_cmd);
+    async fn spawn(
+        &self,
+        current_dir: &PathBuf,
+        env: &ExecutionEnv,
+    ) -> Result<AsyncGroupChild, ExecutorError> {
+        let mut env = env.clone();
+        if self.context == ScriptContext::DevServer {
+            // A port file left by the previous run would point at a stopped server
+            let port_file = dev_server_port_file(current_dir);
+            if let Some(dir) = port_file.parent() {
+                tokio::fs::create_dir_all(dir).await?;
+            }
+            let _ = tokio::fs::remove_file(&port_file).await;
+            env.insert(
+                DEV_SERVER_PORT_FILE_ENV,
+                port_file.to_string_lossy().to_string(),
+            );
+        }
+
+        let mut command = env.shell_command(current_dir, &self.script);
         command
             .kill_on_drop(true)
             .stdout(std::process::Stdio::piped())
-            .stderr(std::process::Stdio::piped())
-            .arg(shell_arg)
-            .arg(&self.script)
-            .current_dir(current_dir);
-
+            .stderr(std::process::Stdio::piped());
         let child = command.group_spawn()?;
 
         Ok(child)
@@ -0,0 +1,96 @@
+use std::{collections::BTreeMap, path::Path};
+
+use tokio::process::Command;
+use utils::shell::get_shell_command;
+
+/// A running container an execution's processes run in instead of the host. The host
+/// directories they use are mounted at the same paths inside it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ContainerTarget {
+    pub container_id: String,
+    /// User the processes run as, the image's default when `None`
+    pub user: Option<String>,
+}
+
+/// Environment variables set for an execution's processes, on top of the server's own
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ExecutionEnv {
+    vars: BTreeMap<String, String>,
+    container: Option<ContainerTarget>,
+}
+
+impl ExecutionEnv {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn insert(&mut self, key: impl Into<String>, value: impl Into<String>) {
+        self.vars.insert(key.into(), value.into());
+    }
+
+    /// Set a variable unless the environment already does
+    pub fn insert_default(&mut self, key: impl Into<String>, value: impl Into<String>) {
+        self.vars.entry(key.into()).or_insert_with(|| value.into());
+    }
+
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.vars.get(key).map(String::as_str)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.vars.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.vars.iter().map(|(k, v)| (k.as_str(), v.as_str()))
+    }
+
+    /// Run the processes in a container instead of on the host
+    pub fn set_container(&mut self, container: ContainerTarget) {
+        self.container = Some(container);
+    }
+
+    pub fn container(&self) -> Option<&ContainerTarget> {
+        self.container.as_ref()
+    }
+
+    /// Add the other environment's variables, overriding ones set in both
+    pub fn extend(&mut self, other: ExecutionEnv) {
+        self.vars.extend(other.vars);
+        if other.container.is_some() {
+            self.container = other.container;
+        }
+    }
+
+    pub fn apply(&self, command: &mut Command) {
+        command.envs(&self.vars);
+    }
+
+    /// Command running `script` in the shell from `current_dir`, with the environment's
+    /// variables. In a container it goes through `docker exec`, whose variables have to be
+    /// passed on its command line.
+    pub fn shell_command(&self, current_dir: &Path, script: &str) -> Command {
+        let Some(container) = &self.container else {
+            let (shell_cmd, shell_arg) = get_shell_command();
+            let mut command = Command::new(shell_cmd);
+            command.arg(shell_arg).arg(script).current_dir(current_dir);
+            self.apply(&mut command);
+            return command;
+        };
+
+        let mut command = Command::new("docker");
+        command.arg("exec").arg("-i").arg("-w").arg(current_dir);
+        if let Some(user) = &container.user {
+            command.arg("-u").arg(user);
+        }
+        for (key, value) in &self.vars {
+            command.arg("-e").arg(format!("{key}={value}"));
+        }
+        command
+            .arg(&container.container_id)
+            .arg("sh")
+            .arg("-c")
+            .arg(script);
+        command
+    }
+}
@@ -5,15 +5,13 @@ use command_group::{AsyncCommandGroup, AsyncGroupChild};
 use futures::StreamExt;
 use json_patch::Patch;
 use serde::{Deserialize, Serialize};
-use tokio::{io::AsyncWriteExt, process::Command};
+use tokio::io::AsyncWriteExt;
 use ts_rs::TS;
-use utils::{
-    diff::create_unified_diff, msg_store::MsgStore, path::make_path_relative,
-    shell::get_shell_command,
-};
+use utils::{diff::create_unified_diff, msg_store::MsgStore, path::make_path_relative};
 
 use crate::{
     command::CommandBuilder,
+    env::ExecutionEnv,
     executors::{ExecutorError, StandardCodingAgentExecutor},
     logs::{
         ActionType, FileChange, NormalizedEntry, NormalizedEntryType, TodoItem as LogsTodoItem,
@@ -34,19 +32,16 @@ impl StandardCodingAgentExecutor for Amp {
         &self,
         current_dir: &PathBuf,
         prompt: &str,
+        env: &ExecutionEnv,
     ) -> Result<AsyncGroupChild, ExecutorError> {
-        let (shell_cmd, shell_arg) = get_shell_command();
         let amp_command = self.command.build_initial();
 
-        let mut command = Command::new(shell_cmd);
+        let mut command = env.shell_command(current_dir, &amp_command);
         command
             .kill_on_drop(true)
             .stdin(Stdio::piped()) // <-- open a pipe
             .stdout(Stdio::piped())
-            .stderr(Stdio::piped())
-            .current_dir(current_dir)
-            .arg(shell_arg)
-            .arg(amp_command);
+            .stderr(Stdio::piped());
 
         let mut child = command.group_spawn()?;
 
@@ -64,24 +59,21 @@ impl StandardCodingAgentExecutor for Amp {
         current_dir: &PathBuf,
         prompt: &str,
         session_id: &str,
+        env: &ExecutionEnv,
     ) -> Result<AsyncGroupChild, ExecutorError> {
         // Use shell command for cross-platform compatibility
-        let (shell_cmd, shell_arg) = get_shell_command();
         let amp_command = self.command.build_follow_up(&[
             "threads".to_string(),
             "continue".to_string(),
             session_id.to_string(),
         ]);
 
-        let mut command = Command::new(shell_cmd);
+        let mut command = env.shell_command(current_dir, &amp_command);
         command
             .kill_on_drop(true)
             .stdin(Stdio::piped())
             .stdout(Stdio::piped())
-            .stderr(Stdio::piped())
-            .current_dir(current_dir)
-            .arg(shell_arg)
-            .arg(&amp_command);
+            .stderr(Stdio::piped());
 
         let mut child = command.group_spawn()?;
 
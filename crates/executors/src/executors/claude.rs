@@ -4,31 +4,196 @@ use async_trait::async_trait;
 use command_group::{AsyncCommandGroup, AsyncGroupChild};
 use futures::StreamExt;
 use serde::{Deserialize, Serialize};
-use tokio::{io::AsyncWriteExt, process::Command};
+use tokio::io::AsyncWriteExt;
 use ts_rs::TS;
 use utils::{
     diff::{concatenate_diff_hunks, create_unified_diff, create_unified_diff_hunk},
-    log_msg::LogMsg,
+    log_msg::{LogMsg, TokenUsage},
     msg_store::MsgStore,
     path::make_path_relative,
-    shell::get_shell_command,
+    shell::quote_shell_arg,
 };
 
 use crate::{
     command::CommandBuilder,
+    env::ExecutionEnv,
     executors::{ExecutorError, StandardCodingAgentExecutor},
     logs::{
-        ActionType, FileChange, NormalizedEntry, NormalizedEntryType, TodoItem,
+        ActionType, FileChange, NormalizedEntry, NormalizedEntryType, TodoItem, ToolCall,
         stderr_processor::normalize_stderr_logs,
         utils::{EntryIndexProvider, patch::ConversationPatch},
     },
 };
 
+/// How Claude handles tool calls that need permission, passed as `--permission-mode`
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, TS)]
+#[serde(rename_all = "camelCase")]
+pub enum PermissionMode {
+    /// Tools needing permission are refused unless allowed
+    Default,
+    /// File edits are accepted, other tools need permission
+    AcceptEdits,
+    /// Every tool call is accepted
+    BypassPermissions,
+    /// Claude only plans and changes nothing
+    Plan,
+}
+
 /// An executor that uses Claude CLI to process tasks
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, TS)]
 pub struct ClaudeCode {
     pub command: CommandBuilder,
     pub plan: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub permission_mode: Option<PermissionMode>,
+    /// Tools allowed without asking, e.g. `Bash(git diff:*)`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub allowed_tools: Vec<String>,
+    /// Tools never allowed, even in bypass mode
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub disallowed_tools: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_turns: Option<u32>,
+}
+
+impl ClaudeCode {
+    /// CLI flags for the typed permission settings, appended to the params
+    fn permission_args(&self) -> Vec<String> {
+        let mut args = Vec::new();
+        if let Some(mode) = self.permission_mode {
+            args.push("--permission-mode".to_string());
+            args.push(
+                match mode {
+                    PermissionMode::Default => "default",
+                    PermissionMode::AcceptEdits => "acceptEdits",
+                    PermissionMode::BypassPermissions => "bypassPermissions",
+                    PermissionMode::Plan => "plan",
+                }
+                .to_string(),
+            );
+        }
+        for (flag, tools) in [
+            ("--allowedTools", &self.allowed_tools),
+            ("--disallowedTools", &self.disallowed_tools),
+        ] {
+            if !tools.is_empty() {
+                args.push(flag.to_string());
+                args.extend(tools.iter().map(|tool| quote_shell_arg(tool)));
+            }
+        }
+        if let Some(max_turns) = self.max_turns {
+            args.push("--max-turns".to_string());
+            args.push(max_turns.to_string());
+        }
+        args
+    }
+
+    /// Whether runs only plan, so their output can be reviewed before anything changes
+    pub fn is_planning(&self) -> bool {
+        self.plan || self.permission_mode == Some(PermissionMode::Plan)
+    }
+
+    /// Plan written by a planning run: the last plan handed to `ExitPlanMode`, falling
+    /// back to Claude's final answer
+    pub fn extract_plan(stdout: &str) -> Option<String> {
+        stdout
+            .lines()
+            .filter_map(
+                |line| match serde_json::from_str::<ClaudeJson>(line.trim()) {
+                    Ok(ClaudeJson::Assistant { message, .. }) => {
+                        message.content.into_iter().find_map(|item| match item {
+                            ClaudeContentItem::ToolUse {
+                                tool_data: ClaudeToolData::ExitPlanMode { plan },
+                                ..
+                            } => Some(plan),
+                            _ => None,
+                        })
+                    }
+                    _ => None,
+                },
+            )
+            .filter(|plan| !plan.trim().is_empty())
+            .last()
+            .or_else(|| Self::final_message(stdout))
+    }
+
+    /// Tool calls in Claude's output, with the outcome of those whose result came back.
+    /// MCP tools are named `mcp__<server>__<tool>`.
+    pub fn tool_calls(stdout: &str) -> Vec<ToolCall> {
+        let mut calls: Vec<(Option<String>, ToolCall)> = Vec::new();
+        let tool_call = |name: &str| match name
+            .strip_prefix("mcp__")
+            .and_then(|rest| rest.split_once("__"))
+        {
+            Some((server, tool)) => ToolCall::new(tool, Some(server.to_string())),
+            None => ToolCall::new(name, None),
+        };
+        for line in stdout.lines() {
+            match serde_json::from_str::<ClaudeJson>(line.trim()) {
+                Ok(ClaudeJson::Assistant { message, .. } | ClaudeJson::User { message, .. }) => {
+                    for item in message.content {
+                        match item {
+                            ClaudeContentItem::ToolUse { id, tool_data } => {
+                                calls.push((Some(id), tool_call(tool_data.get_name())));
+                            }
+                            ClaudeContentItem::ToolResult {
+                                tool_use_id,
+                                is_error,
+                                ..
+                            } => {
+                                if let Some((_, call)) = calls
+                                    .iter_mut()
+                                    .find(|(id, _)| id.as_deref() == Some(tool_use_id.as_str()))
+                                {
+                                    call.failed = Some(is_error.unwrap_or(false));
+                                }
+                            }
+                            _ => {}
+                        }
+                    }
+                }
+                Ok(ClaudeJson::ToolUse { tool_name, .. }) => {
+                    calls.push((None, tool_call(&tool_name)));
+                }
+                Ok(ClaudeJson::ToolResult { is_error, .. }) => {
+                    if let Some((_, call)) = calls
+                        .iter_mut()
+                        .rev()
+                        .find(|(id, call)| id.is_none() && call.failed.is_none())
+                    {
+                        call.failed = Some(is_error.unwrap_or(false));
+                    }
+                }
+                _ => {}
+            }
+        }
+        calls.into_iter().map(|(_, call)| call).collect()
+    }
+
+    /// Claude's final answer: the run's result, else its last text message
+    pub fn final_message(stdout: &str) -> Option<String> {
+        stdout
+            .lines()
+            .filter_map(
+                |line| match serde_json::from_str::<ClaudeJson>(line.trim()) {
+                    Ok(ClaudeJson::Assistant { message, .. }) => message
+                        .content
+                        .into_iter()
+                        .rev()
+                        .find_map(|item| match item {
+                            ClaudeContentItem::Text { text } => Some(text),
+                            _ => None,
+                        }),
+                    Ok(ClaudeJson::Result {
+                        result: Some(serde_json::Value::String(result)),
+                        ..
+                    }) => Some(result),
+                    _ => None,
+                },
+            )
+            .filter(|message| !message.trim().is_empty())
+            .last()
+    }
 }
 
 #[async_trait]
@@ -37,24 +202,21 @@ impl StandardCodingAgentExecutor for ClaudeCode {
         &self,
         current_dir: &PathBuf,
         prompt: &str,
+        env: &ExecutionEnv,
     ) -> Result<AsyncGroupChild, ExecutorError> {
-        let (shell_cmd, shell_arg) = get_shell_command();
+        let base_command = self.command.build_follow_up(&self.permission_args());
         let claude_command = if self.plan {
-            let base_command = self.command.build_initial();
             create_watchkill_script(&base_command)
         } else {
-            self.command.build_initial()
+            base_command
         };
 
-        let mut command = Command::new(shell_cmd);
+        let mut command = env.shell_command(current_dir, &claude_command);
         command
             .kill_on_drop(true)
             .stdin(Stdio::piped())
             .stdout(Stdio::piped())
-            .stderr(Stdio::piped())
-            .current_dir(current_dir)
-            .arg(shell_arg)
-            .arg(&claude_command);
+            .stderr(Stdio::piped());
 
         let mut child = command.group_spawn()?;
 
@@ -72,28 +234,24 @@ impl StandardCodingAgentExecutor for ClaudeCode {
         current_dir: &PathBuf,
         prompt: &str,
         session_id: &str,
+        env: &ExecutionEnv,
     ) -> Result<AsyncGroupChild, ExecutorError> {
-        let (shell_cmd, shell_arg) = get_shell_command();
         // Build follow-up command with --resume {session_id}
+        let mut args = self.permission_args();
+        args.extend(["--resume".to_string(), session_id.to_string()]);
+        let base_command = self.command.build_follow_up(&args);
         let claude_command = if self.plan {
-            let base_command = self
-                .command
-                .build_follow_up(&["--resume".to_string(), session_id.to_string()]);
             create_watchkill_script(&base_command)
         } else {
-            self.command
-                .build_follow_up(&["--resume".to_string(), session_id.to_string()])
+            base_command
         };
 
-        let mut command = Command::new(shell_cmd);
+        let mut command = env.shell_command(current_dir, &claude_command);
         command
             .kill_on_drop(true)
             .stdin(Stdio::piped())
             .stdout(Stdio::piped())
-            .stderr(Stdio::piped())
-            .current_dir(current_dir)
-            .arg(shell_arg)
-            .arg(&claude_command);
+            .stderr(Stdio::piped());
 
         let mut child = command.group_spawn()?;
 
@@ -137,7 +295,7 @@ while IFS= read -r line; do
     if [[ $line == *"$word"* ]]; then
         exit 0
     fi
-done < <($command <&0 2>&1)
+done < <(eval "$command" <&0 2>&1)
 
 exit_code=${{PIPESTATUS[0]}}
 exit "$exit_code"
@@ -173,7 +331,11 @@ impl ClaudeLogProcessor {
             while let Some(Ok(msg)) = stream.next().await {
                 let chunk = match msg {
                     LogMsg::Stdout(x) => x,
-                    LogMsg::JsonPatch(_) | LogMsg::SessionId(_) | LogMsg::Stderr(_) => continue,
+                    LogMsg::JsonPatch(_)
+                    | LogMsg::SessionId(_)
+                    | LogMsg::Cost(_)
+                    | LogMsg::Tokens(_)
+                    | LogMsg::Stderr(_) => continue,
                     LogMsg::Finished => break,
                 };
 
@@ -209,6 +371,20 @@ impl ClaudeLogProcessor {
                                 session_id_extracted = true;
                             }
 
+                            if let ClaudeJson::Result {
+                                total_cost_usd,
+                                usage,
+                                ..
+                            } = &claude_json
+                            {
+                                if let Some(usage) = usage {
+                                    msg_store.push_tokens(usage.total());
+                                }
+                                if let Some(cost) = total_cost_usd {
+                                    msg_store.push_cost(*cost);
+                                }
+                            }
+
                             // Convert to normalized entries and create patches
                             for entry in
                                 processor.to_normalized_entries(&claude_json, &worktree_path)
@@ -620,12 +796,39 @@ pub enum ClaudeJson {
         is_error: Option<bool>,
         duration_ms: Option<u64>,
         result: Option<serde_json::Value>,
+        total_cost_usd: Option<f64>,
+        #[serde(default)]
+        usage: Option<ClaudeUsage>,
     },
     // Catch-all for unknown message types
     #[serde(other)]
     Unknown,
 }
 
+/// Tokens of a whole run, as the result message reports them
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq)]
+pub struct ClaudeUsage {
+    #[serde(default)]
+    pub input_tokens: u64,
+    #[serde(default)]
+    pub cache_creation_input_tokens: u64,
+    #[serde(default)]
+    pub cache_read_input_tokens: u64,
+    #[serde(default)]
+    pub output_tokens: u64,
+}
+
+impl ClaudeUsage {
+    pub fn total(&self) -> TokenUsage {
+        TokenUsage {
+            input_tokens: self.input_tokens
+                + self.cache_creation_input_tokens
+                + self.cache_read_input_tokens,
+            output_tokens: self.output_tokens,
+        }
+    }
+}
+
 #[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
 pub struct ClaudeMessage {
     pub id: Option<String>,
@@ -829,6 +1032,33 @@ mod tests {
         assert_eq!(entries.len(), 0); // Should be ignored like in old implementation
     }
 
+    #[test]
+    fn test_result_message_cost() {
+        let result_json = r#"{"type":"result","subtype":"success","is_error":false,"duration_ms":6059,"result":"Final result","total_cost_usd":0.1234,"usage":{"input_tokens":12,"cache_creation_input_tokens":3000,"cache_read_input_tokens":40000,"output_tokens":900}}"#;
+        let parsed: ClaudeJson = serde_json::from_str(result_json).unwrap();
+
+        assert!(matches!(
+            parsed,
+            ClaudeJson::Result {
+                total_cost_usd: Some(cost),
+                ..
+            } if cost == 0.1234
+        ));
+        let ClaudeJson::Result {
+            usage: Some(usage), ..
+        } = parsed
+        else {
+            panic!("usage not parsed");
+        };
+        assert_eq!(
+            usage.total(),
+            TokenUsage {
+                input_tokens: 43_012,
+                output_tokens: 900
+            }
+        );
+    }
+
     #[test]
     fn test_thinking_content() {
         let thinking_json = r#"{"type":"assistant","message":{"role":"assistant","content":[{"type":"thinking","thinking":"Let me think about this..."}]}}"#;
@@ -934,6 +1164,10 @@ mod tests {
         let executor = ClaudeCode {
             command: CommandBuilder::new(""),
             plan: false,
+            permission_mode: None,
+            allowed_tools: Vec::new(),
+            disallowed_tools: Vec::new(),
+            max_turns: None,
         };
         let msg_store = Arc::new(MsgStore::new());
         let current_dir = std::path::PathBuf::from("/tmp/test-worktree");
@@ -1034,4 +1268,73 @@ mod tests {
 
         // ToolResult entry is ignored - no third entry
     }
+
+    #[test]
+    fn test_permission_args_from_typed_config() {
+        let claude: ClaudeCode = serde_json::from_str(
+            r#"{
+                "command": {"base": "claude", "params": ["-p"]},
+                "plan": false,
+                "permission_mode": "acceptEdits",
+                "allowed_tools": ["Read", "Bash(git diff:*)"],
+                "disallowed_tools": ["WebFetch"],
+                "max_turns": 30
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(
+            claude.command.build_follow_up(&claude.permission_args()),
+            format!(
+                "claude -p --permission-mode acceptEdits --allowedTools {} {} --disallowedTools {} --max-turns 30",
+                quote_shell_arg("Read"),
+                quote_shell_arg("Bash(git diff:*)"),
+                quote_shell_arg("WebFetch")
+            )
+        );
+
+        // Configs written before the typed fields keep their params as they are
+        let claude: ClaudeCode = serde_json::from_str(
+            r#"{"command": {"base": "claude", "params": null}, "plan": true}"#,
+        )
+        .unwrap();
+        assert!(claude.permission_args().is_empty());
+    }
+
+    #[test]
+    fn test_extract_plan_prefers_exit_plan_mode() {
+        let stdout = r#"{"type":"system","subtype":"init","session_id":"abc"}
+{"type":"assistant","message":{"role":"assistant","content":[{"type":"text","text":"Let me look around."}]}}
+{"type":"assistant","message":{"role":"assistant","content":[{"type":"tool_use","id":"t1","name":"ExitPlanMode","input":{"plan":"1. Add the route\n2. Test it"}}]}}
+{"type":"result","subtype":"success","is_error":false,"result":"Plan ready"}"#;
+        assert_eq!(
+            ClaudeCode::extract_plan(stdout).as_deref(),
+            Some("1. Add the route\n2. Test it")
+        );
+
+        // Without ExitPlanMode the final answer is the plan
+        let stdout = r#"{"type":"assistant","message":{"role":"assistant","content":[{"type":"text","text":"Thinking"}]}}
+{"type":"result","subtype":"success","is_error":false,"result":"Change foo.rs"}"#;
+        assert_eq!(
+            ClaudeCode::extract_plan(stdout).as_deref(),
+            Some("Change foo.rs")
+        );
+        assert_eq!(ClaudeCode::extract_plan("not json"), None);
+    }
+
+    #[test]
+    fn test_tool_calls_pair_results_and_split_mcp_names() {
+        let stdout = r#"{"type":"assistant","message":{"role":"assistant","content":[{"type":"tool_use","id":"t1","name":"Read","input":{"file_path":"a.rs"}},{"type":"tool_use","id":"t2","name":"mcp__github__search_issues","input":{"q":"bug"}}]}}
+{"type":"user","message":{"role":"user","content":[{"type":"tool_result","tool_use_id":"t2","content":"rate limited","is_error":true},{"type":"tool_result","tool_use_id":"t1","content":"fn main() {}"}]}}
+{"type":"assistant","message":{"role":"assistant","content":[{"type":"tool_use","id":"t3","name":"Bash","input":{"command":"cargo test"}}]}}"#;
+        let calls = ClaudeCode::tool_calls(stdout);
+        assert_eq!(calls.len(), 3);
+        assert_eq!(
+            (calls[0].tool.as_str(), calls[0].failed),
+            ("Read", Some(false))
+        );
+        assert_eq!(calls[1].tool, "search_issues");
+        assert_eq!(calls[1].mcp_server.as_deref(), Some("github"));
+        assert_eq!(calls[1].failed, Some(true));
+        assert_eq!((calls[2].tool.as_str(), calls[2].failed), ("Bash", None));
+    }
 }
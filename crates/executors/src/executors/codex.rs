@@ -5,20 +5,20 @@ use command_group::{AsyncCommandGroup, AsyncGroupChild};
 use futures::StreamExt;
 use regex::Regex;
 use serde::{Deserialize, Serialize};
-use tokio::{io::AsyncWriteExt, process::Command};
+use tokio::io::AsyncWriteExt;
 use ts_rs::TS;
 use utils::{
     diff::{concatenate_diff_hunks, extract_unified_diff_hunks},
     msg_store::MsgStore,
     path::make_path_relative,
-    shell::get_shell_command,
 };
 
 use crate::{
     command::CommandBuilder,
+    env::ExecutionEnv,
     executors::{ExecutorError, StandardCodingAgentExecutor},
     logs::{
-        ActionType, FileChange, NormalizedEntry, NormalizedEntryType,
+        ActionType, FileChange, NormalizedEntry, NormalizedEntryType, ToolCall,
         utils::{EntryIndexProvider, patch::ConversationPatch},
     },
 };
@@ -104,10 +104,169 @@ impl SessionHandler {
     }
 }
 
+/// What commands run by Codex may touch, passed as `--sandbox`
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, TS)]
+#[serde(rename_all = "kebab-case")]
+pub enum SandboxMode {
+    /// Read files only, no writes or network
+    ReadOnly,
+    /// Write inside the worktree, no network
+    WorkspaceWrite,
+    /// No sandbox at all
+    DangerFullAccess,
+}
+
+/// When Codex asks before running a command, passed as the `approval_policy` config
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, TS)]
+#[serde(rename_all = "kebab-case")]
+pub enum ApprovalPolicy {
+    /// Only commands known to be safe run without asking
+    Untrusted,
+    /// Ask only when a command fails in the sandbox
+    OnFailure,
+    /// The model decides when to ask
+    OnRequest,
+    /// Never ask, failures go back to the model
+    Never,
+}
+
 /// An executor that uses Codex CLI to process tasks
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, TS)]
 pub struct Codex {
     pub command: CommandBuilder,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sandbox: Option<SandboxMode>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub approval: Option<ApprovalPolicy>,
+}
+
+impl Codex {
+    /// CLI flags for the typed sandbox and approval settings, appended to the params
+    fn policy_args(&self) -> Vec<String> {
+        let mut args = Vec::new();
+        if let Some(sandbox) = self.sandbox {
+            args.push("--sandbox".to_string());
+            args.push(
+                match sandbox {
+                    SandboxMode::ReadOnly => "read-only",
+                    SandboxMode::WorkspaceWrite => "workspace-write",
+                    SandboxMode::DangerFullAccess => "danger-full-access",
+                }
+                .to_string(),
+            );
+        }
+        if let Some(approval) = self.approval {
+            args.push("-c".to_string());
+            args.push(format!(
+                "approval_policy={}",
+                match approval {
+                    ApprovalPolicy::Untrusted => "untrusted",
+                    ApprovalPolicy::OnFailure => "on-failure",
+                    ApprovalPolicy::OnRequest => "on-request",
+                    ApprovalPolicy::Never => "never",
+                }
+            ));
+        }
+        args
+    }
+
+    /// Whether runs can't touch the worktree, so they can draft a plan for review
+    pub fn is_planning(&self) -> bool {
+        self.sandbox == Some(SandboxMode::ReadOnly)
+    }
+
+    /// MCP tool calls and shell commands in Codex's output, paired with their end events
+    /// by call id
+    pub fn tool_calls(stdout: &str) -> Vec<ToolCall> {
+        let mut calls: Vec<(Option<String>, ToolCall)> = Vec::new();
+        for line in stdout.lines() {
+            let Ok(CodexJson::StructuredMessage { msg, .. }) =
+                serde_json::from_str::<CodexJson>(line.trim())
+            else {
+                continue;
+            };
+            match msg {
+                CodexMsgContent::McpToolCallBegin {
+                    call_id,
+                    invocation,
+                } => calls.push((
+                    Some(call_id),
+                    ToolCall::new(invocation.tool, Some(invocation.server)),
+                )),
+                CodexMsgContent::McpToolCallEnd {
+                    call_id,
+                    duration,
+                    result,
+                    ..
+                } => {
+                    if let Some(call) = pending_call(&mut calls, Some(&call_id)) {
+                        call.failed = Some(
+                            result.get("Err").is_some()
+                                || result
+                                    .pointer("/Ok/isError")
+                                    .and_then(|v| v.as_bool())
+                                    .unwrap_or(false),
+                        );
+                        call.duration_ms = duration_ms(&duration);
+                    }
+                }
+                CodexMsgContent::ExecCommandBegin { call_id, .. } => {
+                    calls.push((call_id, ToolCall::new("shell", None)));
+                }
+                CodexMsgContent::ExecCommandEnd {
+                    call_id, success, ..
+                } => {
+                    if let Some(call) = pending_call(&mut calls, call_id.as_deref()) {
+                        call.failed = success.map(|success| !success);
+                    }
+                }
+                _ => {}
+            }
+        }
+        calls.into_iter().map(|(_, call)| call).collect()
+    }
+
+    /// Codex's last message, the plan of a planning run
+    pub fn final_message(stdout: &str) -> Option<String> {
+        stdout
+            .lines()
+            .filter_map(
+                |line| match serde_json::from_str::<CodexJson>(line.trim()) {
+                    Ok(CodexJson::StructuredMessage {
+                        msg: CodexMsgContent::AgentMessage { message },
+                        ..
+                    }) => Some(message),
+                    Ok(CodexJson::StructuredMessage {
+                        msg:
+                            CodexMsgContent::TaskComplete {
+                                last_agent_message: Some(message),
+                            },
+                        ..
+                    }) => Some(message),
+                    _ => None,
+                },
+            )
+            .filter(|message| !message.trim().is_empty())
+            .last()
+    }
+}
+
+/// Call with id `call_id` whose end event wasn't seen yet
+fn pending_call<'a>(
+    calls: &'a mut [(Option<String>, ToolCall)],
+    call_id: Option<&str>,
+) -> Option<&'a mut ToolCall> {
+    calls
+        .iter_mut()
+        .find(|(id, call)| call_id.is_some() && id.as_deref() == call_id && call.failed.is_none())
+        .map(|(_, call)| call)
+}
+
+/// Milliseconds of a serialized `Duration` (`{"secs": .., "nanos": ..}`)
+fn duration_ms(duration: &serde_json::Value) -> Option<u64> {
+    let secs = duration.get("secs")?.as_u64()?;
+    let nanos = duration.get("nanos").and_then(|v| v.as_u64()).unwrap_or(0);
+    Some(secs * 1_000 + nanos / 1_000_000)
 }
 
 #[async_trait]
@@ -116,21 +275,19 @@ impl StandardCodingAgentExecutor for Codex {
         &self,
         current_dir: &PathBuf,
         prompt: &str,
+        env: &ExecutionEnv,
     ) -> Result<AsyncGroupChild, ExecutorError> {
-        let (shell_cmd, shell_arg) = get_shell_command();
-        let codex_command = self.command.build_initial();
+        let codex_command = self.command.build_follow_up(&self.policy_args());
 
-        let mut command = Command::new(shell_cmd);
+        let mut env = env.clone();
+        env.insert_default("NODE_NO_WARNINGS", "1");
+        env.insert_default("RUST_LOG", "info");
+        let mut command = env.shell_command(current_dir, &codex_command);
         command
             .kill_on_drop(true)
             .stdin(Stdio::piped())
             .stdout(Stdio::piped())
-            .stderr(Stdio::piped())
-            .current_dir(current_dir)
-            .arg(shell_arg)
-            .arg(&codex_command)
-            .env("NODE_NO_WARNINGS", "1")
-            .env("RUST_LOG", "info");
+            .stderr(Stdio::piped());
 
         let mut child = command.group_spawn()?;
 
@@ -148,6 +305,7 @@ impl StandardCodingAgentExecutor for Codex {
         current_dir: &PathBuf,
         prompt: &str,
         session_id: &str,
+        env: &ExecutionEnv,
     ) -> Result<AsyncGroupChild, ExecutorError> {
         // Find the rollout file for the given session_id using SessionHandler
         let rollout_file_path =
@@ -155,23 +313,22 @@ impl StandardCodingAgentExecutor for Codex {
                 ExecutorError::SpawnError(std::io::Error::new(std::io::ErrorKind::NotFound, e))
             })?;
 
-        let (shell_cmd, shell_arg) = get_shell_command();
-        let codex_command = self.command.build_follow_up(&[
+        let mut args = self.policy_args();
+        args.extend([
             "-c".to_string(),
             format!("experimental_resume={}", rollout_file_path.display()),
         ]);
+        let codex_command = self.command.build_follow_up(&args);
 
-        let mut command = Command::new(shell_cmd);
+        let mut env = env.clone();
+        env.insert_default("NODE_NO_WARNINGS", "1");
+        env.insert_default("RUST_LOG", "info");
+        let mut command = env.shell_command(current_dir, &codex_command);
         command
             .kill_on_drop(true)
             .stdin(Stdio::piped())
             .stdout(Stdio::piped())
-            .stderr(Stdio::piped())
-            .current_dir(current_dir)
-            .arg(shell_arg)
-            .arg(&codex_command)
-            .env("NODE_NO_WARNINGS", "1")
-            .env("RUST_LOG", "info");
+            .stderr(Stdio::piped());
 
         let mut child = command.group_spawn()?;
 
@@ -1060,4 +1217,56 @@ invalid json line here
         let entries = parsed.to_normalized_entries(&current_dir);
         assert!(entries.is_none()); // Should return None
     }
+
+    #[test]
+    fn test_policy_args_from_typed_config() {
+        let codex: Codex = serde_json::from_str(
+            r#"{"command": {"base": "codex exec", "params": ["--json"]}, "sandbox": "workspace-write", "approval": "on-failure"}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            codex.command.build_follow_up(&codex.policy_args()),
+            "codex exec --json --sandbox workspace-write -c approval_policy=on-failure"
+        );
+
+        // Configs written before the typed fields keep their params as they are
+        let codex: Codex =
+            serde_json::from_str(r#"{"command": {"base": "codex exec", "params": null}}"#).unwrap();
+        assert!(codex.policy_args().is_empty());
+        assert!(!serde_json::to_string(&codex).unwrap().contains("sandbox"));
+    }
+
+    #[test]
+    fn test_final_message_is_last_agent_message() {
+        let stdout = r#"{"prompt":"Plan the change"}
+{"id":"1","msg":{"type":"agent_message","message":"Looking at the routes"}}
+{"id":"1","msg":{"type":"agent_message","message":"1. Add the route\n2. Test it"}}
+{"id":"1","msg":{"type":"task_complete","last_agent_message":null}}"#;
+        assert_eq!(
+            Codex::final_message(stdout).as_deref(),
+            Some("1. Add the route\n2. Test it")
+        );
+        assert_eq!(Codex::final_message(r#"{"prompt":"Plan"}"#), None);
+    }
+
+    #[test]
+    fn test_tool_calls_read_mcp_and_exec_events() {
+        let stdout = r#"{"id":"1","msg":{"type":"mcp_tool_call_begin","call_id":"c1","invocation":{"server":"linear","tool":"get_issue","arguments":{}}}}
+{"id":"1","msg":{"type":"exec_command_begin","call_id":"c2","command":["cargo","test"],"cwd":"/repo"}}
+{"id":"1","msg":{"type":"mcp_tool_call_end","call_id":"c1","invocation":{"server":"linear","tool":"get_issue","arguments":{}},"duration":{"secs":1,"nanos":250000000},"result":{"Ok":{"content":[],"isError":false}}}}
+{"id":"1","msg":{"type":"exec_command_end","call_id":"c2","stdout":"","stderr":"1 failed","success":false}}
+{"id":"1","msg":{"type":"mcp_tool_call_begin","call_id":"c3","invocation":{"server":"linear","tool":"get_issue"}}}
+{"id":"1","msg":{"type":"mcp_tool_call_end","call_id":"c3","invocation":{"server":"linear","tool":"get_issue"},"result":{"Err":"timed out"}}}"#;
+        let calls = Codex::tool_calls(stdout);
+        assert_eq!(calls.len(), 3);
+        assert_eq!(calls[0].tool, "get_issue");
+        assert_eq!(calls[0].mcp_server.as_deref(), Some("linear"));
+        assert_eq!(calls[0].failed, Some(false));
+        assert_eq!(calls[0].duration_ms, Some(1_250));
+        assert_eq!(
+            (calls[1].tool.as_str(), calls[1].failed),
+            ("shell", Some(true))
+        );
+        assert_eq!((calls[2].failed, calls[2].duration_ms), (Some(true), None));
+    }
 }
@@ -5,7 +5,7 @@ use async_trait::async_trait;
 use command_group::{AsyncCommandGroup, AsyncGroupChild};
 use futures::StreamExt;
 use serde::{Deserialize, Serialize};
-use tokio::{io::AsyncWriteExt, process::Command};
+use tokio::io::AsyncWriteExt;
 use ts_rs::TS;
 use utils::{
     diff::{
@@ -14,11 +14,11 @@ use utils::{
     },
     msg_store::MsgStore,
     path::make_path_relative,
-    shell::get_shell_command,
 };
 
 use crate::{
     command::CommandBuilder,
+    env::ExecutionEnv,
     executors::{ExecutorError, StandardCodingAgentExecutor},
     logs::{
         ActionType, FileChange, NormalizedEntry, NormalizedEntryType, TodoItem,
@@ -39,19 +39,16 @@ impl StandardCodingAgentExecutor for Cursor {
         &self,
         current_dir: &PathBuf,
         prompt: &str,
+        env: &ExecutionEnv,
     ) -> Result<AsyncGroupChild, ExecutorError> {
-        let (shell_cmd, shell_arg) = get_shell_command();
         let agent_cmd = self.command.build_initial();
 
-        let mut command = Command::new(shell_cmd);
+        let mut command = env.shell_command(current_dir, &agent_cmd);
         command
             .kill_on_drop(true)
             .stdin(Stdio::piped())
             .stdout(Stdio::piped())
-            .stderr(Stdio::piped())
-            .current_dir(current_dir)
-            .arg(shell_arg)
-            .arg(&agent_cmd);
+            .stderr(Stdio::piped());
 
         let mut child = command.group_spawn()?;
 
@@ -68,21 +65,18 @@ impl StandardCodingAgentExecutor for Cursor {
         current_dir: &PathBuf,
         prompt: &str,
         session_id: &str,
+        env: &ExecutionEnv,
     ) -> Result<AsyncGroupChild, ExecutorError> {
-        let (shell_cmd, shell_arg) = get_shell_command();
         let agent_cmd = self
             .command
             .build_follow_up(&["--resume".to_string(), session_id.to_string()]);
 
-        let mut command = Command::new(shell_cmd);
+        let mut command = env.shell_command(current_dir, &agent_cmd);
         command
             .kill_on_drop(true)
             .stdin(Stdio::piped())
             .stdout(Stdio::piped())
-            .stderr(Stdio::piped())
-            .current_dir(current_dir)
-            .arg(shell_arg)
-            .arg(&agent_cmd);
+            .stderr(Stdio::piped());
 
         let mut child = command.group_spawn()?;
 
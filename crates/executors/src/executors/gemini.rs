@@ -7,13 +7,13 @@ use serde::{Deserialize, Serialize};
 use tokio::{
     fs::{self, OpenOptions},
     io::AsyncWriteExt,
-    process::Command,
 };
 use ts_rs::TS;
-use utils::{msg_store::MsgStore, shell::get_shell_command};
+use utils::msg_store::MsgStore;
 
 use crate::{
     command::CommandBuilder,
+    env::ExecutionEnv,
     executors::{ExecutorError, StandardCodingAgentExecutor},
     logs::{
         NormalizedEntry, NormalizedEntryType, plain_text_processor::PlainTextLogProcessor,
@@ -34,21 +34,18 @@ impl StandardCodingAgentExecutor for Gemini {
         &self,
         current_dir: &PathBuf,
         prompt: &str,
+        env: &ExecutionEnv,
     ) -> Result<AsyncGroupChild, ExecutorError> {
-        let (shell_cmd, shell_arg) = get_shell_command();
         let gemini_command = self.command.build_initial();
 
-        let mut command = Command::new(shell_cmd);
-
+        let mut env = env.clone();
+        env.insert_default("NODE_NO_WARNINGS", "1");
+        let mut command = env.shell_command(current_dir, &gemini_command);
         command
             .kill_on_drop(true)
             .stdin(Stdio::piped())
             .stdout(Stdio::piped())
-            .stderr(Stdio::piped())
-            .current_dir(current_dir)
-            .arg(shell_arg)
-            .arg(gemini_command)
-            .env("NODE_NO_WARNINGS", "1");
+            .stderr(Stdio::piped());
 
         let mut child = command.group_spawn()?;
 
@@ -75,24 +72,21 @@ impl StandardCodingAgentExecutor for Gemini {
         current_dir: &PathBuf,
         prompt: &str,
         _session_id: &str,
+        env: &ExecutionEnv,
     ) -> Result<AsyncGroupChild, ExecutorError> {
         // Build comprehensive prompt with session context
         let followup_prompt = Self::build_followup_prompt(current_dir, prompt).await?;
 
-        let (shell_cmd, shell_arg) = get_shell_command();
         let gemini_command = self.command.build_follow_up(&[]);
 
-        let mut command = Command::new(shell_cmd);
-
+        let mut env = env.clone();
+        env.insert_default("NODE_NO_WARNINGS", "1");
+        let mut command = env.shell_command(current_dir, &gemini_command);
         command
             .kill_on_drop(true)
             .stdin(Stdio::piped())
             .stdout(Stdio::piped())
-            .stderr(Stdio::piped())
-            .current_dir(current_dir)
-            .arg(shell_arg)
-            .arg(gemini_command)
-            .env("NODE_NO_WARNINGS", "1");
+            .stderr(Stdio::piped());
 
         let mut child = command.group_spawn()?;
 
@@ -10,10 +10,12 @@ use ts_rs::TS;
 use utils::msg_store::MsgStore;
 
 use crate::{
+    env::ExecutionEnv,
     executors::{
         amp::Amp, claude::ClaudeCode, codex::Codex, cursor::Cursor, gemini::Gemini,
         opencode::Opencode,
     },
+    logs::ToolCall,
     mcp_config::McpConfig,
     profile::{ProfileConfigs, ProfileVariantLabel},
 };
@@ -138,6 +140,83 @@ impl CodingAgent {
         }
     }
 
+    /// Shell command that runs the agent's own login flow, `None` if the agent only
+    /// authenticates interactively on first use
+    pub fn login_command(&self) -> Option<&'static str> {
+        match self {
+            Self::ClaudeCode(_) => Some("npx -y @anthropic-ai/claude-code@latest setup-token"),
+            Self::Amp(_) => Some("npx -y @sourcegraph/amp login"),
+            Self::Gemini(_) => None,
+            Self::Codex(_) => Some("npx -y @openai/codex login"),
+            Self::Opencode(_) => Some("npx -y opencode-ai@latest auth login"),
+            Self::Cursor(_) => Some("cursor-agent login"),
+        }
+    }
+
+    /// Shell command that exits successfully only when the agent has working credentials
+    pub fn auth_check_command(&self) -> Option<&'static str> {
+        match self {
+            // No status command, a one-turn prompt fails unless the credentials work
+            Self::ClaudeCode(_) => {
+                Some("npx -y @anthropic-ai/claude-code@latest -p 'Reply with OK' --max-turns 1")
+            }
+            Self::Amp(_) => Some("npx -y @sourcegraph/amp --execute 'Reply with OK'"),
+            Self::Gemini(_) => Some("npx -y @google/gemini-cli@latest -p 'Reply with OK'"),
+            Self::Codex(_) => Some("npx -y @openai/codex login status"),
+            Self::Opencode(_) => Some("npx -y opencode-ai@latest auth list"),
+            Self::Cursor(_) => Some("cursor-agent status"),
+        }
+    }
+
+    /// How to point the agent at an image in its worktree so it looks at the image, `None`
+    /// for agents without image input
+    pub fn image_reference(&self, path: &str) -> Option<String> {
+        match self {
+            // Claude Code opens images it's given a path to
+            Self::ClaudeCode(_) => Some(path.to_string()),
+            // Gemini CLI inlines `@path` references into the prompt
+            Self::Gemini(_) => Some(format!("@{path}")),
+            Self::Amp(_) | Self::Codex(_) | Self::Opencode(_) | Self::Cursor(_) => None,
+        }
+    }
+
+    /// Whether the agent can run a planning phase that changes nothing
+    pub fn supports_planning(&self) -> bool {
+        match self {
+            Self::ClaudeCode(claude) => claude.is_planning(),
+            Self::Codex(codex) => codex.is_planning(),
+            Self::Amp(_) | Self::Gemini(_) | Self::Opencode(_) | Self::Cursor(_) => false,
+        }
+    }
+
+    /// Plan written by a planning run, read from the agent's stdout
+    pub fn extract_plan(&self, stdout: &str) -> Option<String> {
+        match self {
+            Self::ClaudeCode(_) => ClaudeCode::extract_plan(stdout),
+            Self::Codex(_) => Codex::final_message(stdout),
+            Self::Amp(_) | Self::Gemini(_) | Self::Opencode(_) | Self::Cursor(_) => None,
+        }
+    }
+
+    /// The agent's final answer read from its stdout, `None` for agents whose output
+    /// isn't parsed
+    pub fn final_message(&self, stdout: &str) -> Option<String> {
+        match self {
+            Self::ClaudeCode(_) => ClaudeCode::final_message(stdout),
+            Self::Codex(_) => Codex::final_message(stdout),
+            Self::Amp(_) | Self::Gemini(_) | Self::Opencode(_) | Self::Cursor(_) => None,
+        }
+    }
+
+    /// Tool calls read from the agent's stdout, empty for agents whose output isn't parsed
+    pub fn tool_calls(&self, stdout: &str) -> Vec<ToolCall> {
+        match self {
+            Self::ClaudeCode(_) => ClaudeCode::tool_calls(stdout),
+            Self::Codex(_) => Codex::tool_calls(stdout),
+            Self::Amp(_) | Self::Gemini(_) | Self::Opencode(_) | Self::Cursor(_) => Vec::new(),
+        }
+    }
+
     pub fn default_mcp_config_path(&self) -> Option<PathBuf> {
         match self {
             //ExecutorConfig::CharmOpencode => {
@@ -175,12 +254,14 @@ pub trait StandardCodingAgentExecutor {
         &self,
         current_dir: &PathBuf,
         prompt: &str,
+        env: &ExecutionEnv,
     ) -> Result<AsyncGroupChild, ExecutorError>;
     async fn spawn_follow_up(
         &self,
         current_dir: &PathBuf,
         prompt: &str,
         session_id: &str,
+        env: &ExecutionEnv,
     ) -> Result<AsyncGroupChild, ExecutorError>;
     fn normalize_logs(&self, _raw_logs_event_store: Arc<MsgStore>, _worktree_path: &PathBuf);
 }
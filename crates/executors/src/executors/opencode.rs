@@ -7,15 +7,13 @@ use futures::{StreamExt, future::ready, stream::BoxStream};
 use lazy_static::lazy_static;
 use regex::Regex;
 use serde::{Deserialize, Serialize};
-use tokio::{io::AsyncWriteExt, process::Command};
+use tokio::io::AsyncWriteExt;
 use ts_rs::TS;
-use utils::{
-    diff::create_unified_diff, msg_store::MsgStore, path::make_path_relative,
-    shell::get_shell_command,
-};
+use utils::{diff::create_unified_diff, msg_store::MsgStore, path::make_path_relative};
 
 use crate::{
     command::CommandBuilder,
+    env::ExecutionEnv,
     executors::{ExecutorError, StandardCodingAgentExecutor},
     logs::{
         ActionType, FileChange, NormalizedEntry, NormalizedEntryType, TodoItem,
@@ -36,20 +34,18 @@ impl StandardCodingAgentExecutor for Opencode {
         &self,
         current_dir: &PathBuf,
         prompt: &str,
+        env: &ExecutionEnv,
     ) -> Result<AsyncGroupChild, ExecutorError> {
-        let (shell_cmd, shell_arg) = get_shell_command();
         let opencode_command = self.command.build_initial();
 
-        let mut command = Command::new(shell_cmd);
+        let mut env = env.clone();
+        env.insert_default("NODE_NO_WARNINGS", "1");
+        let mut command = env.shell_command(current_dir, &opencode_command);
         command
             .kill_on_drop(true)
             .stdin(Stdio::piped())
             .stdout(Stdio::piped()) // Keep stdout but we won't use it
-            .stderr(Stdio::piped())
-            .current_dir(current_dir)
-            .arg(shell_arg)
-            .arg(opencode_command)
-            .env("NODE_NO_WARNINGS", "1");
+            .stderr(Stdio::piped());
 
         let mut child = command.group_spawn()?;
 
@@ -67,22 +63,20 @@ impl StandardCodingAgentExecutor for Opencode {
         current_dir: &PathBuf,
         prompt: &str,
         session_id: &str,
+        env: &ExecutionEnv,
     ) -> Result<AsyncGroupChild, ExecutorError> {
-        let (shell_cmd, shell_arg) = get_shell_command();
         let opencode_command = self
             .command
             .build_follow_up(&["--session".to_string(), session_id.to_string()]);
 
-        let mut command = Command::new(shell_cmd);
+        let mut env = env.clone();
+        env.insert_default("NODE_NO_WARNINGS", "1");
+        let mut command = env.shell_command(current_dir, &opencode_command);
         command
             .kill_on_drop(true)
             .stdin(Stdio::piped())
             .stdout(Stdio::piped()) // Keep stdout but we won't use it
-            .stderr(Stdio::piped())
-            .current_dir(current_dir)
-            .arg(shell_arg)
-            .arg(&opencode_command)
-            .env("NODE_NO_WARNINGS", "1");
+            .stderr(Stdio::piped());
 
         let mut child = command.group_spawn()?;
 
@@ -1,5 +1,6 @@
 pub mod actions;
 pub mod command;
+pub mod env;
 pub mod executors;
 pub mod logs;
 pub mod mcp_config;
@@ -5,6 +5,28 @@ pub mod plain_text_processor;
 pub mod stderr_processor;
 pub mod utils;
 
+/// Tool call an agent made, as read from its output
+#[derive(Debug, Clone, PartialEq)]
+pub struct ToolCall {
+    pub tool: String,
+    /// MCP server providing the tool, `None` for the agent's built-in tools
+    pub mcp_server: Option<String>,
+    /// `None` while the call's result wasn't seen
+    pub failed: Option<bool>,
+    pub duration_ms: Option<u64>,
+}
+
+impl ToolCall {
+    pub(crate) fn new(tool: impl Into<String>, mcp_server: Option<String>) -> Self {
+        Self {
+            tool: tool.into(),
+            mcp_server,
+            failed: None,
+            duration_ms: None,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, TS)]
 pub struct NormalizedConversation {
     pub entries: Vec<NormalizedEntry>,
@@ -6,6 +6,7 @@ use std::{
 };
 
 use lazy_static::lazy_static;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use ts_rs::TS;
 
@@ -50,7 +51,7 @@ impl ProfileConfig {
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, TS)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, TS, JsonSchema)]
 pub struct ProfileVariantLabel {
     pub profile: String,
     pub variant: Option<String>,
@@ -87,6 +88,15 @@ impl ProfileConfigs {
     }
 
     fn load() -> Self {
+        let (profiles, from_file) = Self::load_own();
+        match Self::load_baseline() {
+            Some(baseline) => profiles.with_baseline(baseline, from_file),
+            None => profiles,
+        }
+    }
+
+    /// Profiles of profiles.json, or the defaults. The flag tells whether the file was used.
+    fn load_own() -> (Self, bool) {
         let profiles_path = utils::assets::profiles_path();
 
         // load from profiles.json if it exists, otherwise use defaults
@@ -94,20 +104,68 @@ impl ProfileConfigs {
             Ok(content) => content,
             Err(e) => {
                 tracing::warn!("Failed to read profiles.json: {}, using defaults", e);
-                return Self::from_defaults();
+                return (Self::from_defaults(), false);
             }
         };
 
         match serde_json::from_str::<Self>(&content) {
             Ok(profiles) => {
                 tracing::info!("Loaded all profiles from profiles.json");
-                profiles
+                (profiles, true)
             }
             Err(e) => {
                 tracing::warn!("Failed to parse profiles.json: {}, using defaults", e);
-                Self::from_defaults()
+                (Self::from_defaults(), false)
+            }
+        }
+    }
+
+    /// Profiles of the organization baseline the server verified and cached at startup
+    fn load_baseline() -> Option<Self> {
+        // The baseline also carries config and MCP servers, which the server applies
+        #[derive(Deserialize)]
+        struct BaselineProfiles {
+            #[serde(default)]
+            profiles: Vec<ProfileConfig>,
+        }
+
+        let content = fs::read_to_string(utils::assets::baseline_path()).ok()?;
+        match serde_json::from_str::<BaselineProfiles>(&content) {
+            Ok(baseline) => Some(Self {
+                profiles: baseline.profiles,
+            }),
+            Err(e) => {
+                tracing::warn!("Failed to parse baseline profiles: {}", e);
+                None
+            }
+        }
+    }
+
+    /// Add the baseline's profiles beneath these. A baseline profile replaces a built-in
+    /// default of the same label, but never one the user configured in profiles.json.
+    fn with_baseline(mut self, baseline: Self, user_profiles: bool) -> Self {
+        for profile in baseline.profiles {
+            match self
+                .profiles
+                .iter()
+                .position(|p| p.default.label == profile.default.label)
+            {
+                Some(_) if user_profiles => {}
+                Some(index) => self.profiles[index] = profile,
+                None => self.profiles.push(profile),
             }
         }
+        self
+    }
+
+    /// Parses profiles.json content, along with the JSON pointers of the fields that were
+    /// ignored because no profile setting has their name, e.g. misspelled ones
+    pub fn parse_with_ignored(content: &str) -> Result<(Self, Vec<String>), serde_json::Error> {
+        let profiles: Self = serde_json::from_str(content)?;
+        let input: serde_json::Value = serde_json::from_str(content)?;
+        let mut ignored = Vec::new();
+        ignored_fields(&input, &serde_json::to_value(&profiles)?, "", &mut ignored);
+        Ok((profiles, ignored))
     }
 
     pub fn from_defaults() -> Self {
@@ -168,9 +226,43 @@ impl ProfileConfigs {
     }
 }
 
+/// Fields of `input` missing from `parsed`, what it deserialized into and serialized back as.
+/// Nulls are left out, they read the same as a missing optional field.
+fn ignored_fields(
+    input: &serde_json::Value,
+    parsed: &serde_json::Value,
+    pointer: &str,
+    ignored: &mut Vec<String>,
+) {
+    use serde_json::Value;
+
+    match (input, parsed) {
+        (Value::Object(input), Value::Object(parsed)) => {
+            for (key, value) in input {
+                let field = format!("{pointer}/{}", key.replace('~', "~0").replace('/', "~1"));
+                match parsed.get(key) {
+                    _ if value.is_null() => {}
+                    Some(parsed) => ignored_fields(value, parsed, &field, ignored),
+                    None => ignored.push(field),
+                }
+            }
+        }
+        (Value::Array(input), Value::Array(parsed)) => {
+            for (index, (value, parsed)) in input.iter().zip(parsed).enumerate() {
+                ignored_fields(value, parsed, &format!("{pointer}/{index}"), ignored);
+            }
+        }
+        _ => {}
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::executors::{
+        claude::PermissionMode,
+        codex::{ApprovalPolicy, SandboxMode},
+    };
     #[test]
     fn default_profiles_have_expected_base_and_noninteractive_or_json_flags() {
         // Build default profiles and make lookup by label easy
@@ -199,6 +291,23 @@ mod tests {
         assert!(claude_code_command.contains("npx -y @anthropic-ai/claude-code@latest"));
         assert!(claude_code_command.contains("-p"));
         assert!(claude_code_command.contains("--dangerously-skip-permissions"));
+        match &profiles
+            .get_profile("claude-code")
+            .and_then(|p| p.get_variant("accept-edits"))
+            .unwrap()
+            .agent
+        {
+            crate::executors::CodingAgent::ClaudeCode(claude) => {
+                assert_eq!(claude.permission_mode, Some(PermissionMode::AcceptEdits));
+                assert!(
+                    !claude
+                        .command
+                        .build_initial()
+                        .contains("--dangerously-skip-permissions")
+                );
+            }
+            _ => panic!("Claude Code variant accept-edits runs another agent"),
+        }
 
         let claude_code_router_command = get_profile_command("claude-code-router");
         assert!(claude_code_router_command.contains("npx -y @musistudio/claude-code-router code"));
@@ -216,6 +325,20 @@ mod tests {
         let codex_command = get_profile_command("codex");
         assert!(codex_command.contains("npx -y @openai/codex exec"));
         assert!(codex_command.contains("--json"));
+        let codex = profiles.get_profile("codex").unwrap();
+        for (variant, sandbox) in [
+            ("read-only", SandboxMode::ReadOnly),
+            ("workspace-write", SandboxMode::WorkspaceWrite),
+            ("full-access", SandboxMode::DangerFullAccess),
+        ] {
+            match &codex.get_variant(variant).unwrap().agent {
+                crate::executors::CodingAgent::Codex(codex) => {
+                    assert_eq!(codex.sandbox, Some(sandbox));
+                    assert_eq!(codex.approval, Some(ApprovalPolicy::Never));
+                }
+                _ => panic!("Codex variant {variant} runs another agent"),
+            }
+        }
 
         let qwen_code_command = get_profile_command("qwen-code");
         assert!(qwen_code_command.contains("npx -y @qwen-code/qwen-code@latest"));
@@ -285,4 +408,48 @@ mod tests {
             _ => panic!("Expected Gemini agent"),
         }
     }
+
+    #[test]
+    fn test_baseline_profiles_merge_beneath_user_profiles() {
+        let mut org_claude = ProfileConfigs::from_defaults()
+            .get_profile("claude-code")
+            .unwrap()
+            .clone();
+        org_claude.default.mcp_config_path = Some("/etc/org/claude.json".to_string());
+        let mut org_only = org_claude.clone();
+        org_only.default.label = "org-claude".to_string();
+        let baseline = ProfileConfigs {
+            profiles: vec![org_claude.clone(), org_only],
+        };
+
+        // Without profiles.json the baseline replaces the built-in default
+        let merged = ProfileConfigs::from_defaults().with_baseline(baseline.clone(), false);
+        assert_eq!(merged.get_profile("claude-code"), Some(&org_claude));
+        assert!(merged.get_profile("org-claude").is_some());
+        assert_eq!(
+            merged.profiles.len(),
+            ProfileConfigs::from_defaults().profiles.len() + 1
+        );
+
+        // A profile the user configured wins over the baseline's
+        let merged = ProfileConfigs::from_defaults().with_baseline(baseline, true);
+        assert_eq!(
+            merged.get_profile("claude-code"),
+            ProfileConfigs::from_defaults().get_profile("claude-code")
+        );
+        assert!(merged.get_profile("org-claude").is_some());
+    }
+
+    #[test]
+    fn test_parse_reports_ignored_fields() {
+        let (_, ignored) = ProfileConfigs::parse_with_ignored(DEFAULT_PROFILES_JSON).unwrap();
+        assert!(ignored.is_empty(), "{ignored:?}");
+
+        let mut content: serde_json::Value = serde_json::from_str(DEFAULT_PROFILES_JSON).unwrap();
+        content["profiles"][0]["CLAUDE_CODE"]["plann"] = serde_json::json!(true);
+        content["theme"] = serde_json::json!("dark");
+        let (profiles, ignored) = ProfileConfigs::parse_with_ignored(&content.to_string()).unwrap();
+        assert_eq!(profiles, ProfileConfigs::from_defaults());
+        assert_eq!(ignored, vec!["/profiles/0/CLAUDE_CODE/plann", "/theme"]);
+    }
 }
@@ -13,6 +13,7 @@ use futures::{StreamExt, stream::BoxStream};
 use tokio::io::{AsyncWrite, AsyncWriteExt};
 use tokio_stream::wrappers::UnboundedReceiverStream;
 use tokio_util::io::ReaderStream;
+use utils::output_encoding::OutputDecoder;
 
 use crate::executors::ExecutorError;
 
@@ -55,13 +56,14 @@ pub fn duplicate_stdout(
     // Read original stdout and write to both new ChildStdout and duplicate stream
     tokio::spawn(async move {
         let mut stdout_stream = ReaderStream::new(original_stdout);
+        let mut decoder = OutputDecoder::default();
 
         while let Some(res) = stdout_stream.next().await {
             match res {
                 Ok(data) => {
                     let _ = fd_writer.write_all(&data).await;
 
-                    let string_chunk = String::from_utf8_lossy(&data).into_owned();
+                    let string_chunk = decoder.decode(&data);
                     let _ = dup_writer.send(Ok(string_chunk));
                 }
                 Err(err) => {
@@ -41,3 +41,369 @@ pub async fn kill_process_group(child: &mut AsyncGroupChild) -> Result<(), Conta
     let _ = child.wait().await;
     Ok(())
 }
+
+/// Start time of process `pid`, which tells it apart from a later process reusing the pid
+pub fn process_start_time(pid: u32) -> Option<String> {
+    #[cfg(target_os = "linux")]
+    {
+        // Start time in clock ticks since boot, qualified by the boot it was counted in
+        let boot_id = std::fs::read_to_string("/proc/sys/kernel/random/boot_id").ok()?;
+        let stat = std::fs::read_to_string(format!("/proc/{pid}/stat")).ok()?;
+        // The command name may hold spaces, `starttime` is the 20th field after it
+        let start_ticks = stat.rsplit_once(')')?.1.split_whitespace().nth(19)?;
+        Some(format!("{}:{start_ticks}", boot_id.trim()))
+    }
+    #[cfg(all(unix, not(target_os = "linux")))]
+    {
+        let output = std::process::Command::new("ps")
+            .args(["-o", "lstart=", "-p", &pid.to_string()])
+            .output()
+            .ok()?;
+        let started = String::from_utf8_lossy(&output.stdout).trim().to_string();
+        (output.status.success() && !started.is_empty()).then_some(started)
+    }
+    #[cfg(windows)]
+    {
+        windows_process::creation_time(pid)
+    }
+    #[cfg(not(any(unix, windows)))]
+    {
+        let _ = pid;
+        None
+    }
+}
+
+/// Whether the process group `pgid` is still led by the process that started at
+/// `started_at`, rather than by an unrelated process that reused the pid
+pub fn process_group_alive(pgid: i64, started_at: &str) -> bool {
+    u32::try_from(pgid)
+        .ok()
+        .and_then(process_start_time)
+        .is_some_and(|start| start == started_at)
+}
+
+/// Kill a process group we no longer hold a child handle for, e.g. one recovered after a
+/// restart. Signals stop as soon as the group is no longer the one started at `started_at`.
+pub async fn kill_orphaned_process_group(
+    pgid: i64,
+    started_at: &str,
+) -> Result<(), ContainerError> {
+    #[cfg(unix)]
+    {
+        for sig in [Signal::SIGINT, Signal::SIGTERM, Signal::SIGKILL] {
+            if !process_group_alive(pgid, started_at) {
+                break;
+            }
+            if let Err(e) = killpg(Pid::from_raw(pgid as i32), sig) {
+                tracing::warn!(
+                    "Failed to send signal {:?} to process group {}: {}",
+                    sig,
+                    pgid,
+                    e
+                );
+            }
+            tokio::time::sleep(Duration::from_secs(2)).await;
+        }
+    }
+    #[cfg(not(unix))]
+    {
+        let _ = (pgid, started_at);
+    }
+    Ok(())
+}
+
+/// Prepare a freshly spawned process group for `suspend_process_group`. On Windows, where
+/// there are no process groups, it puts the process in a job that its children join.
+pub fn track_process_group(pid: u32) {
+    #[cfg(windows)]
+    {
+        if let Err(e) = windows_process::track(pid) {
+            tracing::warn!("Failed to put process {} in a job: {}", pid, e);
+        }
+    }
+    #[cfg(not(windows))]
+    {
+        let _ = pid;
+    }
+}
+
+/// Stop every process in the group without ending it, so it can be continued later
+pub fn suspend_process_group(pgid: i64) -> Result<(), ContainerError> {
+    #[cfg(unix)]
+    {
+        killpg(Pid::from_raw(pgid as i32), Signal::SIGSTOP)
+            .map_err(|e| ContainerError::Io(std::io::Error::other(e)))
+    }
+    #[cfg(windows)]
+    {
+        windows_process::set_suspended(pgid as u32, true).map_err(ContainerError::Io)
+    }
+    #[cfg(not(any(unix, windows)))]
+    {
+        let _ = pgid;
+        Err(ContainerError::PauseUnsupported)
+    }
+}
+
+/// Continue a process group stopped by `suspend_process_group`
+pub fn resume_process_group(pgid: i64) -> Result<(), ContainerError> {
+    #[cfg(unix)]
+    {
+        killpg(Pid::from_raw(pgid as i32), Signal::SIGCONT)
+            .map_err(|e| ContainerError::Io(std::io::Error::other(e)))
+    }
+    #[cfg(windows)]
+    {
+        windows_process::set_suspended(pgid as u32, false).map_err(ContainerError::Io)
+    }
+    #[cfg(not(any(unix, windows)))]
+    {
+        let _ = pgid;
+        Err(ContainerError::PauseUnsupported)
+    }
+}
+
+/// Windows has no process groups to signal. Each executor's process is put in a Job Object
+/// when it starts, which also takes in every process it starts later; pausing suspends each
+/// process of the job. A process found after a restart has no job, its tree is then found
+/// through the processes' parents, which misses processes whose parent already exited.
+#[cfg(windows)]
+mod windows_process {
+    use std::{
+        collections::{HashMap, HashSet},
+        io,
+        sync::{Mutex, OnceLock},
+    };
+
+    use windows_sys::Win32::{
+        Foundation::{
+            CloseHandle, ERROR_INVALID_PARAMETER, ERROR_MORE_DATA, FILETIME, GetLastError, HANDLE,
+            INVALID_HANDLE_VALUE, STILL_ACTIVE,
+        },
+        System::{
+            Diagnostics::ToolHelp::{
+                CreateToolhelp32Snapshot, PROCESSENTRY32W, Process32FirstW, Process32NextW,
+                TH32CS_SNAPPROCESS,
+            },
+            JobObjects::{
+                AssignProcessToJobObject, CreateJobObjectW, JOBOBJECT_BASIC_ACCOUNTING_INFORMATION,
+                JOBOBJECT_BASIC_PROCESS_ID_LIST, JobObjectBasicAccountingInformation,
+                JobObjectBasicProcessIdList, QueryInformationJobObject,
+            },
+            Threading::{
+                GetExitCodeProcess, GetProcessTimes, OpenProcess,
+                PROCESS_QUERY_LIMITED_INFORMATION, PROCESS_SET_QUOTA, PROCESS_SUSPEND_RESUME,
+                PROCESS_TERMINATE,
+            },
+        },
+    };
+
+    #[link(name = "ntdll")]
+    unsafe extern "system" {
+        fn NtSuspendProcess(process: HANDLE) -> i32;
+        fn NtResumeProcess(process: HANDLE) -> i32;
+    }
+
+    struct Job(HANDLE);
+
+    // The handle is only used through the job functions, which may be called from any thread
+    unsafe impl Send for Job {}
+
+    impl Drop for Job {
+        fn drop(&mut self) {
+            unsafe {
+                CloseHandle(self.0);
+            }
+        }
+    }
+
+    impl Job {
+        fn process_ids(&self) -> io::Result<Vec<u32>> {
+            // The list header takes the room of two ids
+            let mut capacity = 64;
+            loop {
+                let mut buffer = vec![0usize; capacity + 2];
+                let list = buffer
+                    .as_mut_ptr()
+                    .cast::<JOBOBJECT_BASIC_PROCESS_ID_LIST>();
+                let size = (buffer.len() * std::mem::size_of::<usize>()) as u32;
+                unsafe {
+                    if QueryInformationJobObject(
+                        self.0,
+                        JobObjectBasicProcessIdList,
+                        list.cast(),
+                        size,
+                        std::ptr::null_mut(),
+                    ) == 0
+                    {
+                        if GetLastError() == ERROR_MORE_DATA {
+                            capacity *= 2;
+                            continue;
+                        }
+                        return Err(io::Error::last_os_error());
+                    }
+                    let count = (*list).NumberOfProcessIdsInList as usize;
+                    let ids = std::ptr::addr_of!((*list).ProcessIdList).cast::<usize>();
+                    return Ok(std::slice::from_raw_parts(ids, count)
+                        .iter()
+                        .map(|pid| *pid as u32)
+                        .collect());
+                }
+            }
+        }
+
+        fn is_empty(&self) -> bool {
+            unsafe {
+                let mut info: JOBOBJECT_BASIC_ACCOUNTING_INFORMATION = std::mem::zeroed();
+                QueryInformationJobObject(
+                    self.0,
+                    JobObjectBasicAccountingInformation,
+                    (&mut info as *mut JOBOBJECT_BASIC_ACCOUNTING_INFORMATION).cast(),
+                    std::mem::size_of::<JOBOBJECT_BASIC_ACCOUNTING_INFORMATION>() as u32,
+                    std::ptr::null_mut(),
+                ) != 0
+                    && info.ActiveProcesses == 0
+            }
+        }
+    }
+
+    /// Jobs by the pid of the process they were created for
+    fn jobs() -> &'static Mutex<HashMap<u32, Job>> {
+        static JOBS: OnceLock<Mutex<HashMap<u32, Job>>> = OnceLock::new();
+        JOBS.get_or_init(Default::default)
+    }
+
+    /// Put `pid` in a job of its own, the processes it starts from now on join it too
+    pub fn track(pid: u32) -> io::Result<()> {
+        let job = unsafe {
+            let job = CreateJobObjectW(std::ptr::null(), std::ptr::null());
+            if job.is_null() {
+                return Err(io::Error::last_os_error());
+            }
+            let job = Job(job);
+            let process = OpenProcess(PROCESS_SET_QUOTA | PROCESS_TERMINATE, 0, pid);
+            if process.is_null() {
+                return Err(io::Error::last_os_error());
+            }
+            let assigned = AssignProcessToJobObject(job.0, process) != 0;
+            let error = io::Error::last_os_error();
+            CloseHandle(process);
+            if !assigned {
+                return Err(error);
+            }
+            job
+        };
+        let mut jobs = jobs().lock().unwrap_or_else(|e| e.into_inner());
+        jobs.retain(|_, job| !job.is_empty());
+        jobs.insert(pid, job);
+        Ok(())
+    }
+
+    /// Creation time of a running process, in 100ns intervals since 1601
+    pub fn creation_time(pid: u32) -> Option<String> {
+        unsafe {
+            let process = OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, 0, pid);
+            if process.is_null() {
+                return None;
+            }
+            let mut exit_code = 0;
+            let mut times: [FILETIME; 4] = std::mem::zeroed();
+            let [created, exited, kernel, user] = &mut times;
+            let running = GetExitCodeProcess(process, &mut exit_code) != 0
+                && exit_code == STILL_ACTIVE as u32;
+            let timed = GetProcessTimes(process, created, exited, kernel, user) != 0;
+            CloseHandle(process);
+            (running && timed).then(|| {
+                let created = times[0];
+                ((u64::from(created.dwHighDateTime) << 32) | u64::from(created.dwLowDateTime))
+                    .to_string()
+            })
+        }
+    }
+
+    /// `root` and every process started from it, directly or not
+    fn process_tree(root: u32) -> io::Result<Vec<u32>> {
+        let mut parents = Vec::new();
+        unsafe {
+            let snapshot = CreateToolhelp32Snapshot(TH32CS_SNAPPROCESS, 0);
+            if snapshot == INVALID_HANDLE_VALUE {
+                return Err(io::Error::last_os_error());
+            }
+            let mut entry: PROCESSENTRY32W = std::mem::zeroed();
+            entry.dwSize = std::mem::size_of::<PROCESSENTRY32W>() as u32;
+            let mut more = Process32FirstW(snapshot, &mut entry) != 0;
+            while more {
+                parents.push((entry.th32ProcessID, entry.th32ParentProcessID));
+                more = Process32NextW(snapshot, &mut entry) != 0;
+            }
+            CloseHandle(snapshot);
+        }
+
+        // Processes are listed in no particular order, repeat until no more join the tree
+        let mut tree = HashSet::from([root]);
+        loop {
+            let size = tree.len();
+            for (pid, parent) in &parents {
+                if *pid != root && tree.contains(parent) {
+                    tree.insert(*pid);
+                }
+            }
+            if tree.len() == size {
+                return Ok(tree.into_iter().collect());
+            }
+        }
+    }
+
+    /// Suspend or resume all threads of `pid`. A process that already exited is skipped.
+    fn set_process_suspended(pid: u32, suspend: bool) -> io::Result<()> {
+        unsafe {
+            let process = OpenProcess(PROCESS_SUSPEND_RESUME, 0, pid);
+            if process.is_null() {
+                return match GetLastError() {
+                    ERROR_INVALID_PARAMETER => Ok(()),
+                    _ => Err(io::Error::last_os_error()),
+                };
+            }
+            let status = if suspend {
+                NtSuspendProcess(process)
+            } else {
+                NtResumeProcess(process)
+            };
+            CloseHandle(process);
+            if status < 0 {
+                return Err(io::Error::other(format!(
+                    "NTSTATUS {status:#x} for process {pid}"
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    /// Suspend or resume `root` and the processes it started. The processes are listed again
+    /// until no new one shows up, as one may start one while the others are being suspended.
+    /// When one of them fails, those already done are switched back.
+    pub fn set_suspended(root: u32, suspend: bool) -> io::Result<()> {
+        let jobs = jobs().lock().unwrap_or_else(|e| e.into_inner());
+        let job = jobs.get(&root);
+        let mut done = Vec::new();
+        loop {
+            let pids = match job {
+                Some(job) => job.process_ids()?,
+                None => process_tree(root)?,
+            };
+            let new: Vec<u32> = pids.into_iter().filter(|pid| !done.contains(pid)).collect();
+            if new.is_empty() {
+                return Ok(());
+            }
+            for pid in new {
+                if let Err(e) = set_process_suspended(pid, suspend) {
+                    for pid in &done {
+                        let _ = set_process_suspended(*pid, !suspend);
+                    }
+                    return Err(e);
+                }
+                done.push(pid);
+            }
+        }
+    }
+}
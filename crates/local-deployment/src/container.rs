@@ -14,37 +14,64 @@ use command_group::AsyncGroupChild;
 use db::{
     DBService,
     models::{
+        attempt_checkpoint::CheckpointReason,
+        execution_pause::ExecutionPause,
         execution_process::{
             ExecutionContext, ExecutionProcess, ExecutionProcessRunReason, ExecutionProcessStatus,
         },
+        execution_process_logs::ExecutionProcessLogs,
         executor_session::ExecutorSession,
         project::Project,
+        project_worktree_pool::ProjectWorktreePool,
         task::{Task, TaskStatus},
         task_attempt::TaskAttempt,
     },
 };
 use deployment::DeploymentError;
 use executors::{
-    actions::{Executable, ExecutorAction},
+    actions::{Executable, ExecutorAction, ExecutorActionType, script::ScriptContext},
+    env::{ContainerTarget, ExecutionEnv},
+    executors::CodingAgent,
     logs::utils::{ConversationPatch, patch::escape_json_pointer_segment},
 };
 use futures::{StreamExt, TryStreamExt, stream::select};
-use notify_debouncer_full::DebouncedEvent;
 use serde_json::json;
 use services::services::{
-    analytics::AnalyticsContext,
+    analytics::AnalyticsService,
+    checkpoints::CheckpointService,
     config::Config,
+    conflicts::ConflictTracker,
     container::{ContainerError, ContainerRef, ContainerService},
-    filesystem_watcher,
-    git::{DiffTarget, GitService},
+    dependency_cache::DependencyCache,
+    dev_server::DevServerService,
+    devcontainer::Devcontainer,
+    diff_cache::DiffCache,
+    diff_watcher::DiffWatchers,
+    feature_flags::{self, FeatureFlag},
+    git::{DiffTarget, GitService, GitServiceError},
+    linked_repos,
+    nix_env::NixEnv,
     notification::NotificationService,
+    project_env, provider_keys,
+    repo_config::RepoConfig,
+    secrets::SecretsStore,
+    shutdown::ShutdownService,
     worktree_manager::WorktreeManager,
+    worktree_pool::{MAX_POOL_SIZE, WARM_PREFIX, WarmWorktree, WorktreePool},
+    worktree_templates::WorktreeTemplates,
+};
+use tokio::{
+    sync::{RwLock, broadcast::error::RecvError},
+    task::JoinHandle,
 };
-use tokio::{sync::RwLock, task::JoinHandle};
 use tokio_util::io::ReaderStream;
+use tracing::Instrument;
 use utils::{
+    diff::Diff,
     log_msg::LogMsg,
     msg_store::MsgStore,
+    otel::attempt_span,
+    output_encoding::{OutputDecoder, encoding_for_label},
     text::{git_branch_id, short_uuid},
 };
 use uuid::Uuid;
@@ -58,18 +85,38 @@ pub struct LocalContainerService {
     msg_stores: Arc<RwLock<HashMap<Uuid, Arc<MsgStore>>>>,
     config: Arc<RwLock<Config>>,
     git: GitService,
-    analytics: Option<AnalyticsContext>,
+    analytics: AnalyticsService,
+    shutdown: ShutdownService,
+    dev_servers: DevServerService,
+    worktree_pool: WorktreePool,
+    diff_cache: DiffCache,
+    diff_watchers: DiffWatchers,
+    checkpoints: CheckpointService,
+    conflicts: ConflictTracker,
 }
 
+/// How often worktree pools are topped up and rid of outdated worktrees
+const WORKTREE_POOL_INTERVAL: Duration = Duration::from_secs(300);
+
+/// Longest the setup script of a pooled worktree may run
+const WARM_SETUP_TIMEOUT: Duration = Duration::from_secs(30 * 60);
+
 impl LocalContainerService {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         db: DBService,
         msg_stores: Arc<RwLock<HashMap<Uuid, Arc<MsgStore>>>>,
         config: Arc<RwLock<Config>>,
         git: GitService,
-        analytics: Option<AnalyticsContext>,
+        analytics: AnalyticsService,
+        shutdown: ShutdownService,
+        dev_servers: DevServerService,
+        diff_cache: DiffCache,
+        checkpoints: CheckpointService,
+        conflicts: ConflictTracker,
     ) -> Self {
         let child_store = Arc::new(RwLock::new(HashMap::new()));
+        let diff_watchers = DiffWatchers::new(git.clone(), diff_cache.clone());
 
         LocalContainerService {
             db,
@@ -78,6 +125,13 @@ impl LocalContainerService {
             config,
             git,
             analytics,
+            shutdown,
+            dev_servers,
+            worktree_pool: WorktreePool::new(),
+            diff_watchers,
+            diff_cache,
+            checkpoints,
+            conflicts,
         }
     }
 
@@ -91,6 +145,35 @@ impl LocalContainerService {
         map.insert(id, Arc::new(RwLock::new(exec)));
     }
 
+    /// Process group of an execution and its leader's start time, if the group is still the
+    /// one that was spawned for it. Groups without a recorded start time can't be told apart
+    /// from a process that reused the pid, so they're never treated as ours.
+    async fn verified_process_group(
+        &self,
+        execution_process: &ExecutionProcess,
+    ) -> Result<Option<(i64, String)>, ContainerError> {
+        let group = ExecutionProcess::find_pid(&self.db.pool, execution_process.id).await?;
+        Ok(group.and_then(|group| {
+            let started_at = group.started_at?;
+            command::process_group_alive(group.pgid, &started_at)
+                .then_some((group.pgid, started_at))
+        }))
+    }
+
+    /// Process group of a running execution, whether or not we hold its child handle
+    async fn live_process_group(
+        &self,
+        execution_process: &ExecutionProcess,
+    ) -> Result<i64, ContainerError> {
+        self.verified_process_group(execution_process)
+            .await?
+            .map(|(pgid, _)| pgid)
+            .ok_or(ContainerError::Other(anyhow!(
+                "Process group not found for execution {}",
+                execution_process.id
+            )))
+    }
+
     pub async fn remove_child_from_store(&self, id: &Uuid) {
         let mut map = self.child_store.write().await;
         map.remove(id);
@@ -111,6 +194,73 @@ impl LocalContainerService {
             ))
     }
 
+    /// Send the attempt's next queued follow-up once its chain completed, instead of
+    /// handing the attempt over for review. Returns whether one was sent.
+    async fn continue_with_queued_follow_up(&self, ctx: &ExecutionContext) -> bool {
+        if ctx.execution_process.status != ExecutionProcessStatus::Completed {
+            return false;
+        }
+        match self.dispatch_queued_follow_up(&ctx.task_attempt).await {
+            Ok(started) => started.is_some(),
+            Err(e) => {
+                tracing::error!(
+                    "Failed to send queued follow-up for task attempt {}: {}",
+                    ctx.task_attempt.id,
+                    e
+                );
+                false
+            }
+        }
+    }
+
+    /// Have the project's reviewer look at the attempt once its chain completed, instead
+    /// of handing it over right away. Returns whether a review was started.
+    async fn continue_with_review(&self, ctx: &ExecutionContext) -> bool {
+        match self.dispatch_review(ctx).await {
+            Ok(started) => started.is_some(),
+            Err(e) => {
+                tracing::error!(
+                    "Failed to start review for task attempt {}: {}",
+                    ctx.task_attempt.id,
+                    e
+                );
+                false
+            }
+        }
+    }
+
+    /// Send a failed lint stage back to the agent instead of halting the attempt. Returns
+    /// whether a fix was requested.
+    async fn continue_with_lint_fix(&self, ctx: &ExecutionContext) -> bool {
+        match self.dispatch_lint_fix(ctx).await {
+            Ok(started) => started.is_some(),
+            Err(e) => {
+                tracing::error!(
+                    "Failed to send lint fix for task attempt {}: {}",
+                    ctx.task_attempt.id,
+                    e
+                );
+                false
+            }
+        }
+    }
+
+    /// Ask the agent to revert out-of-scope changes once its chain completed. Returns
+    /// whether a follow-up was sent.
+    async fn continue_with_path_revert(&self, ctx: &ExecutionContext) -> bool {
+        match self.dispatch_path_revert(ctx).await {
+            Ok(started) => started.is_some(),
+            Err(e) => {
+                tracing::error!(
+                    "Failed to send path revert for task attempt {}: {}",
+                    ctx.task_attempt.id,
+                    e
+                );
+                false
+            }
+        }
+    }
+
     /// Defensively check for externally deleted worktrees and mark them as deleted in the database
     async fn check_externally_deleted_worktrees(db: &DBService) -> Result<(), DeploymentError> {
         let active_attempts = TaskAttempt::find_by_worktree_deleted(&db.pool).await?;
@@ -210,6 +360,8 @@ impl LocalContainerService {
         worktree_path: PathBuf,
         git_repo_path: PathBuf,
     ) -> Result<(), DeploymentError> {
+        Devcontainer::remove(&worktree_path).await;
+        linked_repos::cleanup_worktrees(&db.pool, attempt_id).await?;
         WorktreeManager::cleanup_worktree(&worktree_path, Some(&git_repo_path)).await?;
         // Mark worktree as deleted in database after successful cleanup
         TaskAttempt::mark_worktree_deleted(&db.pool, attempt_id).await?;
@@ -266,8 +418,9 @@ impl LocalContainerService {
 
     /// Spawn a background task that polls the child process for completion and
     /// cleans up the execution entry when it exits.
-    pub fn spawn_exit_monitor(&self, exec_id: &Uuid) -> JoinHandle<()> {
-        let exec_id = *exec_id;
+    pub fn spawn_exit_monitor(&self, execution_process: &ExecutionProcess) -> JoinHandle<()> {
+        let exec_id = execution_process.id;
+        let span = attempt_span("exit_monitor", execution_process.task_attempt_id);
         let child_store = self.child_store.clone();
         let msg_stores = self.msg_stores.clone();
         let db = self.db.clone();
@@ -275,7 +428,7 @@ impl LocalContainerService {
         let container = self.clone();
         let analytics = self.analytics.clone();
 
-        tokio::spawn(async move {
+        let monitor = async move {
             loop {
                 let status_opt = {
                     let child_lock = {
@@ -322,6 +475,11 @@ impl LocalContainerService {
                     }
 
                     if let Ok(ctx) = ExecutionProcess::load_context(&db.pool, exec_id).await {
+                        // Failing tests exit non-zero, their report is read either way
+                        if let Err(e) = container.record_test_run(&ctx).await {
+                            tracing::warn!("Failed to read test results of {}: {}", exec_id, e);
+                        }
+
                         if matches!(
                             ctx.execution_process.status,
                             ExecutionProcessStatus::Completed
@@ -340,7 +498,28 @@ impl LocalContainerService {
                             }
                         }
 
-                        if Self::should_finalize(&ctx) {
+                        if let Err(e) = container.record_plan(&ctx).await {
+                            tracing::warn!("Failed to record the plan of {}: {}", exec_id, e);
+                        }
+                        if let Err(e) = container.record_review(&ctx).await {
+                            tracing::warn!("Failed to record the review of {}: {}", exec_id, e);
+                        }
+                        if let Err(e) = container.record_commit_plan(&ctx).await {
+                            tracing::warn!("Failed to apply the commit plan of {}: {}", exec_id, e);
+                        }
+
+                        // Failed agent runs may leave changes behind as well
+                        if let Err(e) = container.record_diff_risk(&ctx).await {
+                            tracing::warn!("Failed to assess diff risk of {}: {}", exec_id, e);
+                        }
+
+                        if container.continue_with_lint_fix(&ctx).await {
+                            // The agent gets another turn to fix its lint violations
+                        } else if Self::should_finalize(&ctx)
+                            && !container.continue_with_path_revert(&ctx).await
+                            && !container.continue_with_queued_follow_up(&ctx).await
+                            && !container.continue_with_review(&ctx).await
+                        {
                             if let Err(e) =
                                 Task::update_status(&db.pool, ctx.task.id, TaskStatus::InReview)
                                     .await
@@ -349,23 +528,36 @@ impl LocalContainerService {
                             }
                             let notify_cfg = config.read().await.notifications.clone();
                             NotificationService::notify_execution_halted(notify_cfg, &ctx).await;
+                        } else if matches!(
+                            ctx.execution_process.status,
+                            ExecutionProcessStatus::Failed
+                        ) && !matches!(
+                            ctx.execution_process.run_reason,
+                            ExecutionProcessRunReason::DevServer
+                        ) {
+                            // A failure part way through the chain stops the remaining actions, a stop
+                            // requested by the user doesn't need announcing
+                            let notify_cfg = config.read().await.notifications.clone();
+                            NotificationService::notify_execution_halted(notify_cfg, &ctx).await;
                         }
 
                         // Fire event when CodingAgent execution has finished
-                        if config.read().await.analytics_enabled == Some(true)
-                            && matches!(
-                                &ctx.execution_process.run_reason,
-                                ExecutionProcessRunReason::CodingAgent
-                            )
-                            && let Some(analytics) = &analytics
-                        {
-                            analytics.analytics_service.track_event(&analytics.user_id, "task_attempt_finished", Some(json!({
-                                    "task_id": ctx.task.id.to_string(),
-                                    "project_id": ctx.task.project_id.to_string(),
-                                    "attempt_id": ctx.task_attempt.id.to_string(),
-                                    "execution_success": matches!(ctx.execution_process.status, ExecutionProcessStatus::Completed),
-                                    "exit_code": ctx.execution_process.exit_code,
-                                })));
+                        if matches!(
+                            &ctx.execution_process.run_reason,
+                            ExecutionProcessRunReason::CodingAgent
+                        ) {
+                            analytics
+                                .track_event(
+                                    "task_attempt_finished",
+                                    json!({
+                                        "task_id": ctx.task.id.to_string(),
+                                        "project_id": ctx.task.project_id.to_string(),
+                                        "attempt_id": ctx.task_attempt.id.to_string(),
+                                        "execution_success": matches!(ctx.execution_process.status, ExecutionProcessStatus::Completed),
+                                        "exit_code": ctx.execution_process.exit_code,
+                                    }),
+                                )
+                                .await;
                         }
                     }
 
@@ -391,7 +583,74 @@ impl LocalContainerService {
                 // still running, sleep and try again
                 tokio::time::sleep(Duration::from_millis(250)).await;
             }
-        })
+        };
+        tokio::spawn(monitor.instrument(span))
+    }
+
+    /// Watch a process group recovered after a restart. Its output pipes died with the
+    /// previous server, so only its exit is tracked.
+    fn spawn_recovered_exit_monitor(
+        &self,
+        execution_process: &ExecutionProcess,
+        pgid: i64,
+        started_at: String,
+    ) -> JoinHandle<()> {
+        let exec_id = execution_process.id;
+        let span = attempt_span("recovered_exit_monitor", execution_process.task_attempt_id);
+        let db = self.db.clone();
+        let config = self.config.clone();
+
+        let monitor = async move {
+            while command::process_group_alive(pgid, &started_at) {
+                tokio::time::sleep(Duration::from_secs(1)).await;
+            }
+
+            // Only the parent of a process can read its exit code, so a recovered one can't be
+            // taken as successful: its changes stay uncommitted and the chain stops here
+            if ExecutionProcess::was_killed(&db.pool, exec_id).await {
+                tracing::info!("Recovered execution process {} was stopped", exec_id);
+                return;
+            }
+            if let Err(e) = ExecutionProcess::update_completion(
+                &db.pool,
+                exec_id,
+                ExecutionProcessStatus::Failed,
+                None,
+            )
+            .await
+            {
+                tracing::error!(
+                    "Failed to update recovered execution process completion: {}",
+                    e
+                );
+            }
+            let note = LogMsg::Stderr(
+                "Execution finished after a restart of vibe-kanban, its exit code is unknown\n"
+                    .to_string(),
+            );
+            if let Ok(line) = serde_json::to_string(&note)
+                && let Err(e) =
+                    ExecutionProcessLogs::append_log_line(&db.pool, exec_id, &format!("{line}\n"))
+                        .await
+            {
+                tracing::error!("Failed to write log note for {}: {}", exec_id, e);
+            }
+
+            if let Ok(ctx) = ExecutionProcess::load_context(&db.pool, exec_id).await
+                && Self::should_finalize(&ctx)
+            {
+                if let Err(e) =
+                    Task::update_status(&db.pool, ctx.task.id, TaskStatus::InReview).await
+                {
+                    tracing::error!("Failed to update task status to InReview: {e}");
+                }
+                let notify_cfg = config.read().await.notifications.clone();
+                NotificationService::notify_execution_halted(notify_cfg, &ctx).await;
+            }
+
+            tracing::info!("Recovered execution process {} exited", exec_id);
+        };
+        tokio::spawn(monitor.instrument(span))
     }
 
     pub fn dir_name_from_task_attempt(attempt_id: &Uuid, task_title: &str) -> String {
@@ -405,13 +664,21 @@ impl LocalContainerService {
         let out = child.inner().stdout.take().expect("no stdout");
         let err = child.inner().stderr.take().expect("no stderr");
 
+        let label = self.config.read().await.output_encoding.clone();
+        let encoding = encoding_for_label(label.as_deref()).unwrap_or_else(|e| {
+            tracing::warn!("{}, detecting the output encoding instead", e);
+            None
+        });
+
         // Map stdout bytes -> LogMsg::Stdout
-        let out = ReaderStream::new(out)
-            .map_ok(|chunk| LogMsg::Stdout(String::from_utf8_lossy(&chunk).into_owned()));
+        let mut out_decoder = OutputDecoder::new(encoding);
+        let out =
+            ReaderStream::new(out).map_ok(move |chunk| LogMsg::Stdout(out_decoder.decode(&chunk)));
 
         // Map stderr bytes -> LogMsg::Stderr
-        let err = ReaderStream::new(err)
-            .map_ok(|chunk| LogMsg::Stderr(String::from_utf8_lossy(&chunk).into_owned()));
+        let mut err_decoder = OutputDecoder::new(encoding);
+        let err =
+            ReaderStream::new(err).map_ok(move |chunk| LogMsg::Stderr(err_decoder.decode(&chunk)));
 
         // If you have a JSON Patch source, map it to LogMsg::JsonPatch too, then select all three.
 
@@ -457,22 +724,11 @@ impl LocalContainerService {
         Ok(project_repo_path)
     }
 
-    /// Create a diff stream for merged attempts (never changes)
-    fn create_merged_diff_stream(
-        &self,
-        project_repo_path: &Path,
-        merge_commit_id: &str,
-    ) -> Result<futures::stream::BoxStream<'static, Result<Event, std::io::Error>>, ContainerError>
-    {
-        let diffs = self.git().get_diffs(
-            DiffTarget::Commit {
-                repo_path: project_repo_path,
-                commit_sha: merge_commit_id,
-            },
-            None,
-        )?;
-
-        let stream = futures::stream::iter(diffs.into_iter().map(|diff| {
+    /// Create a diff stream that never changes, for merged attempts and snapshots
+    fn create_static_diff_stream(
+        diffs: Vec<Diff>,
+    ) -> futures::stream::BoxStream<'static, Result<Event, std::io::Error>> {
+        futures::stream::iter(diffs.into_iter().map(|diff| {
             let entry_index = GitService::diff_path(&diff);
             let patch =
                 ConversationPatch::add_diff(escape_json_pointer_segment(&entry_index), diff);
@@ -482,149 +738,103 @@ impl LocalContainerService {
         .chain(futures::stream::once(async {
             Ok::<_, std::io::Error>(LogMsg::Finished.to_sse_event())
         }))
-        .boxed();
-
-        Ok(stream)
+        .boxed()
     }
 
-    /// Create a live diff stream for ongoing attempts
-    async fn create_live_diff_stream(
+    /// Create a live diff stream for ongoing attempts, starting from the cached diff and
+    /// following the attempt's shared worktree watcher
+    fn create_live_diff_stream(
         &self,
-        project_repo_path: &Path,
+        task_attempt: &TaskAttempt,
         worktree_path: &Path,
+        project_repo_path: &Path,
         task_branch: &str,
-        base_branch: &str,
     ) -> Result<futures::stream::BoxStream<'static, Result<Event, std::io::Error>>, ContainerError>
     {
-        // Get initial snapshot
-        let git_service = self.git().clone();
-        let initial_diffs = git_service.get_diffs(
-            DiffTarget::Worktree {
-                worktree_path,
-                branch_name: task_branch,
-                base_branch,
-            },
-            None,
+        // Subscribe before diffing, so changes made meanwhile aren't missed
+        let mut subscription = self.diff_watchers.subscribe(
+            task_attempt.id,
+            worktree_path,
+            task_branch,
+            &task_attempt.base_branch,
+        )?;
+        let initial_diffs = self.diff_cache.attempt_diffs(
+            &self.git,
+            task_attempt,
+            worktree_path,
+            project_repo_path,
         )?;
 
-        let initial_stream = futures::stream::iter(initial_diffs.into_iter().map(|diff| {
-            let entry_index = GitService::diff_path(&diff);
-            let patch =
-                ConversationPatch::add_diff(escape_json_pointer_segment(&entry_index), diff);
-            let event = LogMsg::JsonPatch(patch).to_sse_event();
-            Ok::<_, std::io::Error>(event)
-        }))
-        .boxed();
-
-        // Create live update stream
-        let project_repo_path = project_repo_path.to_path_buf();
+        let git = self.git.clone();
+        let diff_cache = self.diff_cache.clone();
+        let task_attempt = task_attempt.clone();
         let worktree_path = worktree_path.to_path_buf();
-        let task_branch = task_branch.to_string();
-        let base_branch = base_branch.to_string();
-
-        let live_stream = {
-            let git_service = git_service.clone();
-            try_stream! {
-                let (_debouncer, mut rx, canonical_worktree_path) =
-                    filesystem_watcher::async_watcher(worktree_path.clone())
-                        .map_err(|e| io::Error::other(e.to_string()))?;
-
-                while let Some(result) = rx.next().await {
-                    match result {
-                        Ok(events) => {
-                            let changed_paths = Self::extract_changed_paths(&events, &canonical_worktree_path, &worktree_path);
-
-                            if !changed_paths.is_empty() {
-                                for event in Self::process_file_changes(
-                                    &git_service,
-                                    &project_repo_path,
-                                    &worktree_path,
-                                    &task_branch,
-                                    &base_branch,
-                                    &changed_paths,
-                                ).map_err(|e| io::Error::other(e.to_string()))? {
-                                    yield event;
-                                }
-                            }
-                        }
-                        Err(errors) => {
-                            let error_msg = errors.iter()
-                                .map(|e| e.to_string())
-                                .collect::<Vec<_>>()
-                                .join("; ");
-                            Err(io::Error::other(error_msg))?;
-                        }
+        let project_repo_path = project_repo_path.to_path_buf();
+
+        Ok(try_stream! {
+            // Files the client has a diff of
+            let mut streamed = HashSet::new();
+            for event in Self::diff_change_events(&[], &initial_diffs.diffs, &mut streamed) {
+                yield event;
+            }
+
+            loop {
+                let events = match subscription.recv().await {
+                    Ok(changes) => Self::diff_change_events(
+                        &changes.changed_paths,
+                        &changes.diffs,
+                        &mut streamed,
+                    ),
+                    Err(RecvError::Lagged(skipped)) => {
+                        tracing::debug!(
+                            "Diff stream of attempt {} skipped {} changes, resyncing",
+                            task_attempt.id,
+                            skipped
+                        );
+                        let current = diff_cache
+                            .attempt_diffs(&git, &task_attempt, &worktree_path, &project_repo_path)
+                            .map_err(|e| io::Error::other(e.to_string()))?;
+                        let known: Vec<String> = streamed.iter().cloned().collect();
+                        Self::diff_change_events(&known, &current.diffs, &mut streamed)
                     }
+                    Err(RecvError::Closed) => break,
+                };
+                for event in events {
+                    yield event;
                 }
             }
-        }.boxed();
-
-        let combined_stream = select(initial_stream, live_stream);
-        Ok(combined_stream.boxed())
+        }
+        .boxed())
     }
 
-    /// Extract changed file paths from filesystem events
-    fn extract_changed_paths(
-        events: &[DebouncedEvent],
-        canonical_worktree_path: &Path,
-        worktree_path: &Path,
-    ) -> Vec<String> {
-        events
-            .iter()
-            .flat_map(|event| &event.paths)
-            .filter_map(|path| {
-                path.strip_prefix(canonical_worktree_path)
-                    .or_else(|_| path.strip_prefix(worktree_path))
-                    .ok()
-                    .map(|p| p.to_string_lossy().replace('\\', "/"))
-            })
-            .collect()
-    }
-
-    /// Process file changes and generate diff events
-    fn process_file_changes(
-        git_service: &GitService,
-        project_repo_path: &Path,
-        worktree_path: &Path,
-        task_branch: &str,
-        base_branch: &str,
+    /// Patches bringing a streamed diff up to date: changed files with a diff are added or
+    /// replaced, the others the client has are removed
+    fn diff_change_events(
         changed_paths: &[String],
-    ) -> Result<Vec<Event>, ContainerError> {
-        let path_filter: Vec<&str> = changed_paths.iter().map(|s| s.as_str()).collect();
-
-        let current_diffs = git_service.get_diffs(
-            DiffTarget::Worktree {
-                worktree_path,
-                branch_name: task_branch,
-                base_branch,
-            },
-            Some(&path_filter),
-        )?;
-
+        diffs: &[Diff],
+        streamed: &mut HashSet<String>,
+    ) -> Vec<Event> {
         let mut events = Vec::new();
         let mut files_with_diffs = HashSet::new();
 
-        // Add/update files that have diffs
-        for diff in current_diffs {
-            let file_path = GitService::diff_path(&diff);
-            files_with_diffs.insert(file_path.clone());
-
-            let patch = ConversationPatch::add_diff(escape_json_pointer_segment(&file_path), diff);
-            let event = LogMsg::JsonPatch(patch).to_sse_event();
-            events.push(event);
+        for diff in diffs {
+            let file_path = GitService::diff_path(diff);
+            let patch =
+                ConversationPatch::add_diff(escape_json_pointer_segment(&file_path), diff.clone());
+            events.push(LogMsg::JsonPatch(patch).to_sse_event());
+            streamed.insert(file_path.clone());
+            files_with_diffs.insert(file_path);
         }
 
-        // Remove files that changed but no longer have diffs
         for changed_path in changed_paths {
-            if !files_with_diffs.contains(changed_path) {
+            if !files_with_diffs.contains(changed_path) && streamed.remove(changed_path) {
                 let patch =
                     ConversationPatch::remove_diff(escape_json_pointer_segment(changed_path));
-                let event = LogMsg::JsonPatch(patch).to_sse_event();
-                events.push(event);
+                events.push(LogMsg::JsonPatch(patch).to_sse_event());
             }
         }
 
-        Ok(events)
+        events
     }
 }
 
@@ -642,26 +852,64 @@ impl ContainerService for LocalContainerService {
         &self.git
     }
 
+    fn shutdown(&self) -> &ShutdownService {
+        &self.shutdown
+    }
+
+    fn worktree_pool(&self) -> &WorktreePool {
+        &self.worktree_pool
+    }
+
+    fn spawn_fill_worktree_pool(&self, project_id: Uuid) {
+        let container = self.clone();
+        tokio::spawn(async move {
+            match ProjectWorktreePool::find_by_project_id(&container.db.pool, project_id).await {
+                Ok(pool) => {
+                    let size = pool.map_or(0, |pool| pool.size);
+                    container.fill_worktree_pool(project_id, size).await;
+                }
+                Err(e) => {
+                    tracing::error!(
+                        "Failed to load the worktree pool of project {}: {}",
+                        project_id,
+                        e
+                    );
+                }
+            }
+        });
+    }
+
     fn task_attempt_to_current_dir(&self, task_attempt: &TaskAttempt) -> PathBuf {
         PathBuf::from(task_attempt.container_ref.clone().unwrap_or_default())
     }
 
+    async fn agents_paused(&self) -> bool {
+        self.config.read().await.agents_paused
+    }
+
+    async fn feature_enabled(&self, flag: FeatureFlag) -> bool {
+        feature_flags::is_enabled(&self.config.read().await.feature_flags, flag)
+    }
+
     /// Create a container
+    #[tracing::instrument(skip_all, fields(attempt_id = %task_attempt.id))]
     async fn create(&self, task_attempt: &TaskAttempt) -> Result<ContainerRef, ContainerError> {
         let task = task_attempt
             .parent_task(&self.db.pool)
             .await?
             .ok_or(sqlx::Error::RowNotFound)?;
 
-        let task_branch_name =
+        let worktree_dir_name =
             LocalContainerService::dir_name_from_task_attempt(&task_attempt.id, &task.title);
-        let worktree_path = WorktreeManager::get_worktree_base_dir().join(&task_branch_name);
+        let worktree_path = WorktreeManager::get_worktree_base_dir().join(&worktree_dir_name);
 
         let project = task
             .parent_project(&self.db.pool)
             .await?
             .ok_or(sqlx::Error::RowNotFound)?;
 
+        let task_branch_name = self.task_branch_name(task_attempt, &task, &project)?;
+
         WorktreeManager::create_worktree(
             &project.git_repo_path,
             &task_branch_name,
@@ -681,6 +929,16 @@ impl ContainerService for LocalContainerService {
                     tracing::warn!("Failed to copy project files: {}", e);
                 });
         }
+        if let Err(e) = self
+            .apply_worktree_templates(project.id, &worktree_path)
+            .await
+        {
+            tracing::warn!("Failed to write worktree templates: {}", e);
+        }
+
+        if let Some(cache) = DependencyCache::for_project(&self.db.pool, project.id).await? {
+            cache.prepare().await?;
+        }
 
         // Update both container_ref and branch in the database
         TaskAttempt::update_container_ref(
@@ -695,6 +953,61 @@ impl ContainerService for LocalContainerService {
         Ok(worktree_path.to_string_lossy().to_string())
     }
 
+    async fn claim_warm_worktree(
+        &self,
+        task_attempt: &TaskAttempt,
+    ) -> Result<Option<ContainerRef>, ContainerError> {
+        let task = task_attempt
+            .parent_task(&self.db.pool)
+            .await?
+            .ok_or(sqlx::Error::RowNotFound)?;
+        let project = task
+            .parent_project(&self.db.pool)
+            .await?
+            .ok_or(sqlx::Error::RowNotFound)?;
+
+        let Ok(base_commit) = self
+            .git
+            .get_branch_commit(&project.git_repo_path, &task_attempt.base_branch)
+        else {
+            return Ok(None);
+        };
+        let Some(warm) =
+            self.worktree_pool
+                .take(project.id, &task_attempt.base_branch, &base_commit)
+        else {
+            return Ok(None);
+        };
+
+        let task_branch_name = self.task_branch_name(task_attempt, &task, &project)?;
+        if let Err(e) =
+            self.git
+                .rename_branch(&project.git_repo_path, &warm.branch, &task_branch_name)
+        {
+            tracing::warn!(
+                "Failed to claim warm worktree {} for task attempt {}: {}",
+                warm.path.display(),
+                task_attempt.id,
+                e
+            );
+            self.discard_warm_worktree(&warm).await;
+            return Ok(None);
+        }
+
+        let container_ref = warm.path.to_string_lossy().to_string();
+        TaskAttempt::update_container_ref(&self.db.pool, task_attempt.id, &container_ref).await?;
+        TaskAttempt::update_branch(&self.db.pool, task_attempt.id, &task_branch_name).await?;
+        tracing::info!(
+            "Task attempt {} claimed warm worktree {}",
+            task_attempt.id,
+            container_ref
+        );
+
+        // Set up a replacement
+        self.spawn_fill_worktree_pool(project.id);
+        Ok(Some(container_ref))
+    }
+
     async fn delete_inner(&self, task_attempt: &TaskAttempt) -> Result<(), ContainerError> {
         // cleanup the container, here that means deleting the worktree
         let task = task_attempt
@@ -709,18 +1022,18 @@ impl ContainerService for LocalContainerService {
                 None
             }
         };
-        WorktreeManager::cleanup_worktree(
-            &PathBuf::from(task_attempt.container_ref.clone().unwrap_or_default()),
-            git_repo_path.as_deref(),
-        )
-        .await
-        .unwrap_or_else(|e| {
-            tracing::warn!(
-                "Failed to clean up worktree for task attempt {}: {}",
-                task_attempt.id,
-                e
-            );
-        });
+        let worktree_path = PathBuf::from(task_attempt.container_ref.clone().unwrap_or_default());
+        Devcontainer::remove(&worktree_path).await;
+        linked_repos::cleanup_worktrees(&self.db.pool, task_attempt.id).await?;
+        WorktreeManager::cleanup_worktree(&worktree_path, git_repo_path.as_deref())
+            .await
+            .unwrap_or_else(|e| {
+                tracing::warn!(
+                    "Failed to clean up worktree for task attempt {}: {}",
+                    task_attempt.id,
+                    e
+                );
+            });
         Ok(())
     }
 
@@ -755,10 +1068,15 @@ impl ContainerService for LocalContainerService {
             &worktree_path,
         )
         .await?;
+        linked_repos::ensure_worktrees(&self.db.pool, task_attempt.id).await?;
 
         Ok(container_ref.to_string())
     }
 
+    #[tracing::instrument(
+        skip_all,
+        fields(attempt_id = %task_attempt.id, execution_process_id = %execution_process.id)
+    )]
     async fn start_execution_inner(
         &self,
         task_attempt: &TaskAttempt,
@@ -774,16 +1092,124 @@ impl ContainerService for LocalContainerService {
             )))?;
         let current_dir = PathBuf::from(container_ref);
 
+        // Point the processes at the project's shared package caches
+        let task = task_attempt
+            .parent_task(&self.db.pool)
+            .await?
+            .ok_or(sqlx::Error::RowNotFound)?;
+        let mut env = self.execution_env(task.project_id).await?;
+        if let Some(mut agent_env) = self.provider_key_env(executor_action).await? {
+            agent_env.extend(env);
+            env = agent_env;
+        }
+        let container = if matches!(
+            execution_process.run_reason,
+            ExecutionProcessRunReason::SetupScript | ExecutionProcessRunReason::CodingAgent
+        ) {
+            self.devcontainer_target(task.project_id, &current_dir)
+                .await?
+        } else {
+            None
+        };
+        match container {
+            Some(container) => env.set_container(container),
+            None => {
+                env = self
+                    .with_nix_env(task.project_id, &current_dir, env)
+                    .await?
+            }
+        }
+
+        // Checkpoint the worktree at phase boundaries, dev servers don't edit it
+        if execution_process.run_reason != ExecutionProcessRunReason::DevServer {
+            self.checkpoints
+                .create_logged(
+                    task_attempt.id,
+                    &current_dir,
+                    CheckpointReason::BeforeExecution,
+                    Some(execution_process.id),
+                )
+                .await;
+        }
+
         // Create the child and stream, add to execution tracker
-        let mut child = executor_action.spawn(&current_dir).await?;
+        let mut child = executor_action.spawn(&current_dir, &env).await?;
 
         self.track_child_msgs_in_store(execution_process.id, &mut child)
             .await;
 
+        if let ExecutorActionType::ScriptRequest(script) = executor_action.typ()
+            && script.context == ScriptContext::DevServer
+            && let Some(store) = self.get_msg_store_by_id(&execution_process.id).await
+        {
+            self.dev_servers
+                .watch(execution_process.id, &current_dir, store);
+        }
+
+        // While the coding agent edits the worktree, checkpoint it and keep its diff current
+        if execution_process.run_reason == ExecutionProcessRunReason::CodingAgent
+            && let Some(store) = self.get_msg_store_by_id(&execution_process.id).await
+        {
+            self.checkpoints.watch(
+                task_attempt.id,
+                execution_process.id,
+                &current_dir,
+                store.clone(),
+            );
+            let project_repo_path = self.get_project_repo_path(task_attempt).await?;
+            if let Err(e) = self.diff_watchers.watch_while_running(
+                task_attempt,
+                &current_dir,
+                &project_repo_path,
+                store.clone(),
+            ) {
+                tracing::warn!(
+                    "Failed to watch the worktree of attempt {}: {}",
+                    task_attempt.id,
+                    e
+                );
+            }
+            match self.diff_watchers.subscribe(
+                task_attempt.id,
+                &current_dir,
+                task_attempt.branch.as_deref().unwrap_or_default(),
+                &task_attempt.base_branch,
+            ) {
+                Ok(subscription) => {
+                    self.conflicts.track_while_running(
+                        task.project_id,
+                        task.id,
+                        task_attempt.id,
+                        task_attempt.base_branch.clone(),
+                        subscription,
+                        store,
+                    );
+                }
+                Err(e) => tracing::warn!(
+                    "Failed to track the edits of attempt {}: {}",
+                    task_attempt.id,
+                    e
+                ),
+            }
+        }
+
+        // Persist the process group so the execution can be recovered after a crash
+        if let Some(pid) = child.inner().id() {
+            command::track_process_group(pid);
+            let started_at = command::process_start_time(pid);
+            ExecutionProcess::update_pid(
+                &self.db.pool,
+                execution_process.id,
+                pid as i64,
+                started_at.as_deref(),
+            )
+            .await?;
+        }
+
         self.add_child_to_store(execution_process.id, child).await;
 
         // Spawn exit monitor
-        let _hn = self.spawn_exit_monitor(&execution_process.id);
+        let _hn = self.spawn_exit_monitor(execution_process);
 
         Ok(())
     }
@@ -792,12 +1218,17 @@ impl ContainerService for LocalContainerService {
         &self,
         execution_process: &ExecutionProcess,
     ) -> Result<(), ContainerError> {
-        let child = self
-            .get_child_from_store(&execution_process.id)
-            .await
-            .ok_or_else(|| {
-                ContainerError::Other(anyhow!("Child process not found for execution"))
-            })?;
+        let child = self.get_child_from_store(&execution_process.id).await;
+        // Executions recovered after a restart have no child handle, only a process group
+        let recovered_group = match child {
+            Some(_) => None,
+            None => self.verified_process_group(execution_process).await?,
+        };
+        if child.is_none() && recovered_group.is_none() {
+            return Err(ContainerError::Other(anyhow!(
+                "Child process not found for execution"
+            )));
+        }
         ExecutionProcess::update_completion(
             &self.db.pool,
             execution_process.id,
@@ -806,8 +1237,22 @@ impl ContainerService for LocalContainerService {
         )
         .await?;
 
+        // A stopped process group only reacts to SIGKILL, continue it so it can exit cleanly
+        if ExecutionPause::is_paused(&self.db.pool, execution_process.id).await? {
+            if let Some((pgid, _)) = self.verified_process_group(execution_process).await?
+                && let Err(e) = command::resume_process_group(pgid)
+            {
+                tracing::warn!(
+                    "Failed to continue paused execution {}: {}",
+                    execution_process.id,
+                    e
+                );
+            }
+            ExecutionPause::mark_resumed(&self.db.pool, execution_process.id).await?;
+        }
+
         // Kill the child process and remove from the store
-        {
+        if let Some(child) = child {
             let mut child_guard = child.write().await;
             if let Err(e) = command::kill_process_group(&mut child_guard).await {
                 tracing::error!(
@@ -817,6 +1262,8 @@ impl ContainerService for LocalContainerService {
                 );
                 return Err(e);
             }
+        } else if let Some((pgid, started_at)) = recovered_group {
+            command::kill_orphaned_process_group(pgid, &started_at).await?;
         }
         self.remove_child_from_store(&execution_process.id).await;
 
@@ -845,19 +1292,73 @@ impl ContainerService for LocalContainerService {
         Ok(())
     }
 
+    async fn suspend_execution(
+        &self,
+        execution_process: &ExecutionProcess,
+    ) -> Result<(), ContainerError> {
+        let pgid = self.live_process_group(execution_process).await?;
+        command::suspend_process_group(pgid)?;
+        tracing::info!("Paused execution process {}", execution_process.id);
+        Ok(())
+    }
+
+    async fn continue_execution(
+        &self,
+        execution_process: &ExecutionProcess,
+    ) -> Result<(), ContainerError> {
+        let pgid = self.live_process_group(execution_process).await?;
+        command::resume_process_group(pgid)?;
+        tracing::info!("Resumed execution process {}", execution_process.id);
+        Ok(())
+    }
+
+    async fn reattach_execution(
+        &self,
+        execution_process: &ExecutionProcess,
+    ) -> Result<bool, ContainerError> {
+        let Some((pgid, started_at)) = self.verified_process_group(execution_process).await? else {
+            return Ok(false);
+        };
+
+        self.spawn_recovered_exit_monitor(execution_process, pgid, started_at);
+        Ok(true)
+    }
+
     async fn get_diff(
         &self,
         task_attempt: &TaskAttempt,
+        against: Option<&str>,
     ) -> Result<futures::stream::BoxStream<'static, Result<Event, std::io::Error>>, ContainerError>
     {
         let container_ref = self.ensure_container_exists(task_attempt).await?;
 
         let worktree_path = PathBuf::from(container_ref);
+
+        // Snapshot against another ref, e.g. a release tag (static diff)
+        if let Some(against) = against {
+            let diffs = self.git.get_diffs(
+                DiffTarget::WorktreeAgainst {
+                    worktree_path: &worktree_path,
+                    against,
+                },
+                None,
+            )?;
+            return Ok(Self::create_static_diff_stream(diffs));
+        }
+
         let project_repo_path = self.get_project_repo_path(task_attempt).await?;
 
         // Handle merged attempts (static diff)
-        if let Some(merge_commit_id) = &task_attempt.merge_commit {
-            return self.create_merged_diff_stream(&project_repo_path, merge_commit_id);
+        if task_attempt.merge_commit.is_some() {
+            let diffs = self.diff_cache.attempt_diffs(
+                &self.git,
+                task_attempt,
+                &worktree_path,
+                &project_repo_path,
+            )?;
+            return Ok(Self::create_static_diff_stream(Arc::unwrap_or_clone(
+                diffs.diffs,
+            )));
         }
 
         let task_branch = task_attempt
@@ -870,14 +1371,14 @@ impl ContainerService for LocalContainerService {
 
         // Handle ongoing attempts (live streaming diff)
         self.create_live_diff_stream(
-            &project_repo_path,
+            task_attempt,
             &worktree_path,
+            &project_repo_path,
             &task_branch,
-            &task_attempt.base_branch,
         )
-        .await
     }
 
+    #[tracing::instrument(skip_all, fields(execution_process_id = %ctx.execution_process.id))]
     async fn try_commit_changes(&self, ctx: &ExecutionContext) -> Result<(), ContainerError> {
         if !matches!(
             ctx.execution_process.run_reason,
@@ -942,7 +1443,9 @@ impl ContainerService for LocalContainerService {
             message
         );
 
-        Ok(self.git().commit(Path::new(container_ref), &message)?)
+        self.git().commit(Path::new(container_ref), &message)?;
+        linked_repos::commit_all(&self.db.pool, self.git(), ctx.task_attempt.id, &message).await?;
+        Ok(())
     }
 
     /// Copy files from the original project directory to the worktree
@@ -992,3 +1495,311 @@ impl ContainerService for LocalContainerService {
         Ok(())
     }
 }
+
+impl LocalContainerService {
+    /// Environment of the processes run in the project's worktrees: the project's variables,
+    /// pointing them at the project's shared package caches unless they say otherwise
+    async fn execution_env(&self, project_id: Uuid) -> Result<ExecutionEnv, ContainerError> {
+        let mut env = ExecutionEnv::new();
+        if let Some(cache) = DependencyCache::for_project(&self.db.pool, project_id).await? {
+            // Worktrees created before the caches were enabled haven't prepared them
+            cache.prepare().await?;
+            env = cache.env();
+        }
+        env.extend(
+            project_env::load(&self.db.pool, SecretsStore::default_store(), project_id).await?,
+        );
+        Ok(env)
+    }
+
+    /// The started devcontainer of a worktree, when the project runs its processes in one and
+    /// the container sandbox is turned on. The container also mounts the repository, which
+    /// the worktree's git metadata points into, and the shared package caches.
+    async fn devcontainer_target(
+        &self,
+        project_id: Uuid,
+        worktree_path: &Path,
+    ) -> Result<Option<ContainerTarget>, ContainerError> {
+        if !self.feature_enabled(FeatureFlag::ContainerSandbox).await {
+            return Ok(None);
+        }
+        let Some(devcontainer) =
+            Devcontainer::for_worktree(&self.db.pool, project_id, worktree_path).await?
+        else {
+            return Ok(None);
+        };
+        let project = Project::find_by_id(&self.db.pool, project_id)
+            .await?
+            .ok_or(sqlx::Error::RowNotFound)?;
+        let cache_dir = utils::path::get_dependency_cache_dir().join(project_id.to_string());
+        let mut mounts = vec![project.git_repo_path.as_path()];
+        if cache_dir.exists() {
+            mounts.push(cache_dir.as_path());
+        }
+        Ok(Some(devcontainer.ensure(&mounts).await?))
+    }
+
+    /// The stored API keys of the providers a coding agent action's agent uses, for the
+    /// project's variables to override
+    async fn provider_key_env(
+        &self,
+        executor_action: &ExecutorAction,
+    ) -> Result<Option<ExecutionEnv>, ContainerError> {
+        let label = match executor_action.typ() {
+            ExecutorActionType::CodingAgentInitialRequest(request) => {
+                &request.profile_variant_label
+            }
+            ExecutorActionType::CodingAgentFollowUpRequest(request) => {
+                &request.profile_variant_label
+            }
+            ExecutorActionType::ScriptRequest(_) => return Ok(None),
+        };
+        let agent = CodingAgent::from_profile_variant_label(label)?;
+        Ok(Some(
+            provider_keys::agent_env(&self.db.pool, SecretsStore::default_store(), &agent).await?,
+        ))
+    }
+
+    /// `env` on top of the worktree's Nix development shell, when the project runs its
+    /// processes in one, so the project's own variables win
+    async fn with_nix_env(
+        &self,
+        project_id: Uuid,
+        worktree_path: &Path,
+        env: ExecutionEnv,
+    ) -> Result<ExecutionEnv, ContainerError> {
+        let Some(nix_env) = NixEnv::for_worktree(&self.db.pool, project_id, worktree_path).await?
+        else {
+            return Ok(env);
+        };
+        let mut shell_env = nix_env.env().await?;
+        shell_env.extend(env);
+        Ok(shell_env)
+    }
+
+    /// Write the project's templates into a new worktree, keeping them out of its commits
+    async fn apply_worktree_templates(
+        &self,
+        project_id: Uuid,
+        worktree_path: &Path,
+    ) -> Result<(), ContainerError> {
+        let env = self.execution_env(project_id).await?;
+        let written = WorktreeTemplates::for_project(project_id)
+            .apply(worktree_path, &env)
+            .await?;
+        if !written.is_empty() {
+            self.git.exclude_paths(worktree_path, &written)?;
+            tracing::info!(
+                "Wrote {} template file(s) into {}",
+                written.len(),
+                worktree_path.display()
+            );
+        }
+        Ok(())
+    }
+
+    /// Branch of the attempt's worktree. The repo's branch template may contain slashes, so
+    /// it only names the branch.
+    fn task_branch_name(
+        &self,
+        task_attempt: &TaskAttempt,
+        task: &Task,
+        project: &Project,
+    ) -> Result<String, ContainerError> {
+        let worktree_dir_name =
+            LocalContainerService::dir_name_from_task_attempt(&task_attempt.id, &task.title);
+        Ok(
+            RepoConfig::load(&self.git, &project.git_repo_path, &task_attempt.base_branch)?
+                .branch_name(&task_attempt.id, &task.title)
+                .unwrap_or(worktree_dir_name),
+        )
+    }
+
+    /// Keep the worktree pools of active projects full, and drop the pools of others
+    pub fn spawn_worktree_pool(&self) {
+        let container = self.clone();
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(WORKTREE_POOL_INTERVAL);
+            loop {
+                interval.tick().await;
+                if let Err(e) = container.refill_worktree_pools().await {
+                    tracing::error!("Failed to refill worktree pools: {}", e);
+                }
+            }
+        });
+    }
+
+    async fn refill_worktree_pools(&self) -> Result<(), ContainerError> {
+        let pools = ProjectWorktreePool::find_active(&self.db.pool).await?;
+        let active = pools.iter().map(|pool| pool.project_id).collect();
+        for warm in self.worktree_pool.remove_projects_except(&active) {
+            self.discard_warm_worktree(&warm).await;
+        }
+        for pool in pools {
+            self.fill_worktree_pool(pool.project_id, pool.size).await;
+        }
+        Ok(())
+    }
+
+    async fn fill_worktree_pool(&self, project_id: Uuid, size: i64) {
+        // Another fill of the project is underway
+        if !self.worktree_pool.start_filling(project_id) {
+            return;
+        }
+        if let Err(e) = self.fill_worktree_pool_inner(project_id, size).await {
+            tracing::warn!(
+                "Failed to fill the worktree pool of project {}: {}",
+                project_id,
+                e
+            );
+        }
+        self.worktree_pool.finish_filling(project_id);
+    }
+
+    async fn fill_worktree_pool_inner(
+        &self,
+        project_id: Uuid,
+        size: i64,
+    ) -> Result<(), ContainerError> {
+        let Some(project) = Project::find_by_id(&self.db.pool, project_id).await? else {
+            return Ok(());
+        };
+        let size = size.clamp(0, MAX_POOL_SIZE) as usize;
+        // Attempts start from the branch checked out in the repo unless told otherwise
+        let base_branch = self
+            .git
+            .get_current_branch(&project.git_repo_path)
+            .map_err(GitServiceError::from)?;
+        let base_commit = self
+            .git
+            .get_branch_commit(&project.git_repo_path, &base_branch)?;
+
+        let mut outdated = self
+            .worktree_pool
+            .remove_stale(project_id, &base_branch, &base_commit);
+        outdated.extend(self.worktree_pool.shrink(project_id, size));
+        for warm in &outdated {
+            self.discard_warm_worktree(warm).await;
+        }
+        // Branches of worktrees left by a previous run
+        self.git
+            .delete_unused_branches(&project.git_repo_path, WARM_PREFIX)?;
+
+        while self.worktree_pool.ready_count(project_id) < size {
+            if self.shutdown.is_draining() {
+                break;
+            }
+            let warm = self
+                .create_warm_worktree(&project, &base_branch, &base_commit)
+                .await?;
+            tracing::info!(
+                "Added warm worktree {} to the pool of project {}",
+                warm.path.display(),
+                project.name
+            );
+            self.worktree_pool.add(project_id, warm);
+        }
+        Ok(())
+    }
+
+    /// Set up a worktree the way an attempt's would be, up to and including its setup script
+    async fn create_warm_worktree(
+        &self,
+        project: &Project,
+        base_branch: &str,
+        base_commit: &str,
+    ) -> Result<WarmWorktree, ContainerError> {
+        let name = format!("{WARM_PREFIX}{}", &Uuid::new_v4().simple().to_string()[..8]);
+        let warm = WarmWorktree {
+            repo_path: project.git_repo_path.clone(),
+            path: WorktreeManager::get_worktree_base_dir().join(&name),
+            branch: name,
+            base_branch: base_branch.to_string(),
+            base_commit: base_commit.to_string(),
+        };
+        WorktreeManager::create_worktree(
+            &project.git_repo_path,
+            &warm.branch,
+            &warm.path,
+            Some(base_branch),
+            true, // create new branch
+        )
+        .await?;
+
+        if let Err(e) = self.set_up_warm_worktree(project, &warm).await {
+            self.discard_warm_worktree(&warm).await;
+            return Err(e);
+        }
+        Ok(warm)
+    }
+
+    async fn set_up_warm_worktree(
+        &self,
+        project: &Project,
+        warm: &WarmWorktree,
+    ) -> Result<(), ContainerError> {
+        if let Some(copy_files) = &project.copy_files
+            && !copy_files.trim().is_empty()
+        {
+            self.copy_project_files(&project.git_repo_path, &warm.path, copy_files)
+                .await?;
+        }
+        self.apply_worktree_templates(project.id, &warm.path)
+            .await?;
+
+        let setup_script = RepoConfig::load(&self.git, &project.git_repo_path, &warm.base_branch)?
+            .setup_script
+            .or_else(|| project.setup_script.clone());
+        let Some(setup_script) = setup_script else {
+            return Ok(());
+        };
+        let mut env = self.execution_env(project.id).await?;
+        match self.devcontainer_target(project.id, &warm.path).await? {
+            Some(container) => env.set_container(container),
+            None => env = self.with_nix_env(project.id, &warm.path, env).await?,
+        }
+
+        let mut command = env.shell_command(&warm.path, &setup_script);
+        command
+            .kill_on_drop(true)
+            .stdin(std::process::Stdio::null())
+            .stdout(std::process::Stdio::null())
+            .stderr(std::process::Stdio::piped());
+        let output = tokio::time::timeout(WARM_SETUP_TIMEOUT, command.output())
+            .await
+            .map_err(|_| ContainerError::Other(anyhow!("Setup script timed out")))??;
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            let stderr = stderr.trim();
+            // The end of the output says what went wrong
+            let tail = stderr
+                .char_indices()
+                .rev()
+                .nth(499)
+                .map_or(stderr, |(i, _)| &stderr[i..]);
+            return Err(ContainerError::Other(anyhow!(
+                "Setup script failed with {}: {}",
+                output.status,
+                tail
+            )));
+        }
+        Ok(())
+    }
+
+    async fn discard_warm_worktree(&self, warm: &WarmWorktree) {
+        if let Err(e) = WorktreeManager::cleanup_worktree(&warm.path, Some(&warm.repo_path)).await {
+            tracing::warn!(
+                "Failed to remove warm worktree {}: {}",
+                warm.path.display(),
+                e
+            );
+        }
+        // Warm branch names all have the same length, this one is the only match
+        if let Err(e) = self
+            .git
+            .delete_unused_branches(&warm.repo_path, &warm.branch)
+        {
+            tracing::warn!("Failed to delete branch {}: {}", warm.branch, e);
+        }
+    }
+}
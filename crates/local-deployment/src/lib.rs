@@ -3,18 +3,33 @@ use std::{collections::HashMap, sync::Arc};
 use async_trait::async_trait;
 use db::DBService;
 use deployment::{Deployment, DeploymentError};
+use executors::profile::ProfileConfigs;
 use services::services::{
-    analytics::{AnalyticsConfig, AnalyticsContext, AnalyticsService, generate_user_id},
+    agent_login::AgentLoginService,
+    analytics::{AnalyticsService, generate_user_id},
     auth::AuthService,
-    config::{Config, load_config_from_file, save_config_to_file},
+    backup::BackupService,
+    checkpoints::CheckpointService,
+    config::{
+        Config, load_config, save_config,
+        store::{self, SettingsFile},
+    },
+    config_watcher::ConfigWatcher,
+    conflicts::ConflictTracker,
     container::ContainerService,
+    dev_server::DevServerService,
+    diff_cache::DiffCache,
+    diff_stats::DiffStatsService,
     events::EventService,
     filesystem::FilesystemService,
     git::GitService,
+    pty::PtyService,
+    repo_clone::CloneJobs,
     sentry::SentryService,
+    shutdown::ShutdownService,
 };
 use tokio::sync::RwLock;
-use utils::{assets::config_path, msg_store::MsgStore};
+use utils::{i18n::set_locale, msg_store::MsgStore};
 use uuid::Uuid;
 
 use crate::container::LocalContainerService;
@@ -28,31 +43,28 @@ pub struct LocalDeployment {
     sentry: SentryService,
     user_id: String,
     db: DBService,
-    analytics: Option<AnalyticsService>,
+    analytics: AnalyticsService,
     msg_stores: Arc<RwLock<HashMap<Uuid, Arc<MsgStore>>>>,
     container: LocalContainerService,
     git: GitService,
     auth: AuthService,
     filesystem: FilesystemService,
     events: EventService,
+    shutdown: ShutdownService,
+    backup: BackupService,
+    agent_login: AgentLoginService,
+    config_watcher: ConfigWatcher,
+    pty: PtyService,
+    dev_servers: DevServerService,
+    diff_stats: DiffStatsService,
+    checkpoints: CheckpointService,
+    conflicts: ConflictTracker,
+    clones: CloneJobs,
 }
 
 #[async_trait]
 impl Deployment for LocalDeployment {
     async fn new() -> Result<Self, DeploymentError> {
-        let raw_config = load_config_from_file(&config_path()).await;
-        // Immediately save config, as it may have just been migrated
-        save_config_to_file(&raw_config, &config_path()).await?;
-
-        let config = Arc::new(RwLock::new(raw_config));
-        let sentry = SentryService::new();
-        let user_id = generate_user_id();
-        let analytics = AnalyticsConfig::new().map(AnalyticsService::new);
-        let git = GitService::new();
-        let msg_stores = Arc::new(RwLock::new(HashMap::new()));
-        let auth = AuthService::new();
-        let filesystem = FilesystemService::new();
-
         // Create shared components for EventService
         let events_msg_store = Arc::new(MsgStore::new());
         let events_entry_count = Arc::new(RwLock::new(0));
@@ -67,22 +79,53 @@ impl Deployment for LocalDeployment {
             DBService::new_with_after_connect(hook).await?
         };
 
-        // We need to make analytics accessible to the ContainerService
-        // TODO: Handle this more gracefully
-        let analytics_ctx = analytics.as_ref().map(|s| AnalyticsContext {
-            user_id: user_id.clone(),
-            analytics_service: s.clone(),
-        });
+        // Edits to the settings files made while the server was stopped are imported
+        let exported = store::sync_files(&db.pool).await?;
+        if exported.contains(&SettingsFile::Profiles) {
+            ProfileConfigs::reload();
+        }
+        let user_id = generate_user_id();
+        let raw_config = load_config(&db.pool, &user_id).await?;
+        // Immediately save config, as it may have just been migrated
+        save_config(&db.pool, &user_id, &raw_config).await?;
+        set_locale(raw_config.language);
+
+        let config = Arc::new(RwLock::new(raw_config));
+        let sentry = SentryService::new();
+        let analytics = AnalyticsService::new(user_id.clone(), config.clone());
+        let git = GitService::new();
+        let msg_stores = Arc::new(RwLock::new(HashMap::new()));
+        let auth = AuthService::new();
+        let filesystem = FilesystemService::new();
+        let shutdown = ShutdownService::new();
+        let dev_servers = DevServerService::new();
+
+        let diff_cache = DiffCache::new();
+        let checkpoints = CheckpointService::new(db.clone(), git.clone());
+        let conflicts = ConflictTracker::new();
+        let clones = CloneJobs::new();
+
         let container = LocalContainerService::new(
             db.clone(),
             msg_stores.clone(),
             config.clone(),
             git.clone(),
-            analytics_ctx,
+            analytics.clone(),
+            shutdown.clone(),
+            dev_servers.clone(),
+            diff_cache.clone(),
+            checkpoints.clone(),
+            conflicts.clone(),
         );
         container.spawn_worktree_cleanup().await;
+        container.spawn_worktree_pool();
 
         let events = EventService::new(db.clone(), events_msg_store, events_entry_count);
+        let backup = BackupService::new(db.clone(), config.clone());
+        let agent_login = AgentLoginService::new();
+        let config_watcher = ConfigWatcher::spawn(config.clone(), db.pool.clone(), user_id.clone());
+        let pty = PtyService::new();
+        let diff_stats = DiffStatsService::new(diff_cache);
 
         Ok(Self {
             config,
@@ -96,6 +139,16 @@ impl Deployment for LocalDeployment {
             auth,
             filesystem,
             events,
+            shutdown,
+            backup,
+            agent_login,
+            config_watcher,
+            pty,
+            dev_servers,
+            diff_stats,
+            checkpoints,
+            conflicts,
+            clones,
         })
     }
 
@@ -119,7 +172,7 @@ impl Deployment for LocalDeployment {
         &self.db
     }
 
-    fn analytics(&self) -> &Option<AnalyticsService> {
+    fn analytics(&self) -> &AnalyticsService {
         &self.analytics
     }
 
@@ -145,4 +198,44 @@ impl Deployment for LocalDeployment {
     fn events(&self) -> &EventService {
         &self.events
     }
+
+    fn shutdown(&self) -> &ShutdownService {
+        &self.shutdown
+    }
+
+    fn backup(&self) -> &BackupService {
+        &self.backup
+    }
+
+    fn agent_login(&self) -> &AgentLoginService {
+        &self.agent_login
+    }
+
+    fn config_watcher(&self) -> &ConfigWatcher {
+        &self.config_watcher
+    }
+
+    fn pty(&self) -> &PtyService {
+        &self.pty
+    }
+
+    fn dev_servers(&self) -> &DevServerService {
+        &self.dev_servers
+    }
+
+    fn diff_stats(&self) -> &DiffStatsService {
+        &self.diff_stats
+    }
+
+    fn checkpoints(&self) -> &CheckpointService {
+        &self.checkpoints
+    }
+
+    fn conflicts(&self) -> &ConflictTracker {
+        &self.conflicts
+    }
+
+    fn clones(&self) -> &CloneJobs {
+        &self.clones
+    }
 }
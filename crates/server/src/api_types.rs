@@ -0,0 +1,368 @@
+//! Every type the frontend shares with the server, exported as TypeScript by the
+//! `generate_types` binary into `shared/types.ts`. The file carries an `API_VERSION` derived
+//! from the declarations, which the server reports on every response so a frontend built
+//! against other types is caught at request time.
+
+use std::sync::OnceLock;
+
+use ts_rs::TS;
+
+/// Header the server sends its API version in, and the frontend the version it was built for
+pub const API_VERSION_HEADER: &str = "x-api-version";
+
+const HEADER: &str = "// This file was generated by `crates/core/src/bin/generate_types.rs`.\n
+// Do not edit this file manually.\n
+// If you are an AI, and you absolutely have to edit this file, please confirm with the user first.";
+
+/// TypeScript declarations of the executors', config's and routes' types, in file order
+pub fn decls() -> Vec<String> {
+    vec![
+        services::services::filesystem::DirectoryEntry::decl(),
+        services::services::filesystem::DirectoryListResponse::decl(),
+        db::models::project::Project::decl(),
+        db::models::project::ProjectWithBranch::decl(),
+        db::models::project::CreateProject::decl(),
+        db::models::project::UpdateProject::decl(),
+        db::models::project::SearchResult::decl(),
+        db::models::project::SearchMatchType::decl(),
+        db::models::project::TestResultFormat::decl(),
+        executors::actions::ExecutorAction::decl(),
+        executors::mcp_config::McpConfig::decl(),
+        executors::actions::ExecutorActionType::decl(),
+        executors::actions::script::ScriptContext::decl(),
+        executors::actions::script::ScriptRequest::decl(),
+        executors::actions::script::ScriptRequestLanguage::decl(),
+        db::models::task_template::TaskTemplate::decl(),
+        db::models::task_template::CreateTaskTemplate::decl(),
+        db::models::task_template::UpdateTaskTemplate::decl(),
+        db::models::follow_up_snippet::FollowUpSnippet::decl(),
+        db::models::follow_up_snippet::CreateFollowUpSnippet::decl(),
+        db::models::follow_up_snippet::UpdateFollowUpSnippet::decl(),
+        db::models::project_group::ProjectGroup::decl(),
+        db::models::project_group::CreateProjectGroup::decl(),
+        db::models::project_group::UpdateProjectGroup::decl(),
+        db::models::task_attachment::TaskAttachment::decl(),
+        db::models::queued_follow_up::QueuedFollowUp::decl(),
+        db::models::queued_follow_up::CreateQueuedFollowUp::decl(),
+        db::models::queued_follow_up::ReorderQueuedFollowUps::decl(),
+        db::models::execution_pause::AttemptRuntime::decl(),
+        db::models::suspended_execution::SuspendReason::decl(),
+        db::models::suspended_execution::SuspendedExecution::decl(),
+        db::models::attempt_checkpoint::CheckpointReason::decl(),
+        db::models::attempt_checkpoint::AttemptCheckpoint::decl(),
+        db::models::attempt_unmerge::AttemptUnmerge::decl(),
+        db::models::project_budget::ProjectBudget::decl(),
+        db::models::project_budget::UpdateProjectBudget::decl(),
+        db::models::project_disk_quota::ProjectDiskQuota::decl(),
+        db::models::project_disk_quota::UpdateProjectDiskQuota::decl(),
+        db::models::provider_key::KeyProvider::decl(),
+        db::models::provider_key::ProviderKey::decl(),
+        db::models::provider_key::SetProviderKey::decl(),
+        db::models::test_run::TestRun::decl(),
+        db::models::attempt_diff_risk::RiskFlag::decl(),
+        db::models::attempt_diff_risk::DiffRisk::decl(),
+        db::models::task::TaskStatus::decl(),
+        db::models::task::TaskPriority::decl(),
+        db::models::task::Task::decl(),
+        db::models::task::TaskWithAttemptStatus::decl(),
+        db::models::task::CreateTask::decl(),
+        db::models::task::UpdateTask::decl(),
+        services::services::quick_task::QuickCreateTask::decl(),
+        utils::response::ApiResponse::<()>::decl(),
+        utils::response::ApiWarning::decl(),
+        utils::i18n::Locale::decl(),
+        utils::i18n::MessageCode::decl(),
+        crate::error::ProblemDetails::decl(),
+        crate::error::ProblemField::decl(),
+        utils::pagination::Paginated::<()>::decl(),
+        utils::pagination::PageMeta::decl(),
+        crate::routes::config::UserSystemInfo::decl(),
+        crate::routes::config::Environment::decl(),
+        services::services::self_update::UpdateStatus::decl(),
+        services::services::self_update::SelfUpdateOutcome::decl(),
+        services::services::feature_flags::FeatureFlag::decl(),
+        services::services::feature_flags::FeatureFlagState::decl(),
+        crate::routes::feature_flags::SetFeatureFlag::decl(),
+        crate::routes::config::McpServerQuery::decl(),
+        crate::routes::config::UpdateMcpServersBody::decl(),
+        crate::routes::config::GetMcpServerResponse::decl(),
+        crate::routes::debug::ServerLogs::decl(),
+        services::services::support_bundle::CreateSupportBundle::decl(),
+        crate::routes::task_attempts::CreateFollowUpAttempt::decl(),
+        crate::routes::task_attempts::CreateGitHubPrRequest::decl(),
+        services::services::github_service::GitHubServiceError::decl(),
+        services::services::config::Config::decl(),
+        services::services::config::NotificationConfig::decl(),
+        services::services::config::EventSounds::decl(),
+        services::services::config::ThemeMode::decl(),
+        services::services::config::EditorConfig::decl(),
+        services::services::config::EditorType::decl(),
+        services::services::config::GitHubConfig::decl(),
+        services::services::config::SoundFile::decl(),
+        services::services::sounds::CustomSound::decl(),
+        services::services::sounds::ImportSoundRequest::decl(),
+        services::services::config::ShutdownConfig::decl(),
+        services::services::config::ShutdownMode::decl(),
+        services::services::config::BackupConfig::decl(),
+        services::services::config::BudgetConfig::decl(),
+        services::services::config::TelemetryConfig::decl(),
+        services::services::config::TelemetryExporter::decl(),
+        services::services::config::FeatureFlags::decl(),
+        services::services::config::ConfigFieldError::decl(),
+        services::services::config::history::ConfigVersion::decl(),
+        services::services::backup::BackupInfo::decl(),
+        services::services::backup::RestoreBackupRequest::decl(),
+        services::services::budget::ProjectSpendSummary::decl(),
+        services::services::budget::SpendSummary::decl(),
+        services::services::cost_estimate::CostEstimateRequest::decl(),
+        services::services::cost_estimate::TokenRange::decl(),
+        services::services::cost_estimate::CostRange::decl(),
+        services::services::cost_estimate::CostEstimate::decl(),
+        services::services::usage::UsageGroupBy::decl(),
+        services::services::usage::UsageBucket::decl(),
+        services::services::usage::UsageReport::decl(),
+        db::models::evaluation::EvaluationStatus::decl(),
+        db::models::evaluation::EvaluationRunStatus::decl(),
+        db::models::evaluation::Evaluation::decl(),
+        db::models::evaluation::EvaluationRun::decl(),
+        services::services::evaluation::CreateEvaluation::decl(),
+        services::services::evaluation::ProfileScore::decl(),
+        services::services::evaluation::EvaluationReport::decl(),
+        db::models::webhook::WebhookEvent::decl(),
+        db::models::webhook::WebhookDeliveryStatus::decl(),
+        db::models::webhook::Webhook::decl(),
+        db::models::webhook::CreateWebhook::decl(),
+        db::models::webhook::UpdateWebhook::decl(),
+        db::models::webhook::WebhookDelivery::decl(),
+        crate::routes::webhooks::WebhookWithSecret::decl(),
+        db::models::inbound_task::InboundTask::decl(),
+        crate::routes::inbound::InboundTaskRequest::decl(),
+        crate::routes::inbound::InboundTaskAction::decl(),
+        crate::routes::inbound::InboundTaskResponse::decl(),
+        services::services::sentry_issues::SentryIntegrationStatus::decl(),
+        services::services::sentry_issues::SetSentryCredentials::decl(),
+        crate::routes::sentry_issues::ImportSentryIssue::decl(),
+        services::services::disk_quota::WorktreeUsage::decl(),
+        services::services::disk_quota::ProjectDiskUsage::decl(),
+        services::services::secret_scan::SecretFinding::decl(),
+        services::services::secret_scan::SecretScanReport::decl(),
+        db::models::project_path_rules::ProjectPathRules::decl(),
+        db::models::project_path_rules::UpdateProjectPathRules::decl(),
+        services::services::path_rules::PathViolationReason::decl(),
+        services::services::path_rules::PathViolation::decl(),
+        db::models::branch_sync::BranchSyncStrategy::decl(),
+        db::models::branch_sync::BranchSyncSchedule::decl(),
+        db::models::branch_sync::ProjectBranchSync::decl(),
+        db::models::branch_sync::UpdateProjectBranchSync::decl(),
+        db::models::ci_triage::ProjectCiTriage::decl(),
+        db::models::ci_triage::UpdateProjectCiTriage::decl(),
+        db::models::dependency_update::DependencyUpdateSchedule::decl(),
+        db::models::dependency_update::DependencyGroup::decl(),
+        db::models::dependency_update::ProjectDependencyUpdates::decl(),
+        db::models::dependency_update::UpdateProjectDependencyUpdates::decl(),
+        db::models::dependency_update::DependencyUpdateRunStatus::decl(),
+        db::models::dependency_update::DependencyUpdateRun::decl(),
+        db::models::project_changelog::ChangelogFormat::decl(),
+        db::models::project_changelog::ProjectChangelog::decl(),
+        db::models::project_changelog::UpdateProjectChangelog::decl(),
+        db::models::branch_sync::BranchSyncStatus::decl(),
+        db::models::branch_sync::AttemptBranchSync::decl(),
+        db::models::merge_queue::MergeQueueStatus::decl(),
+        db::models::merge_queue::MergeQueueEntry::decl(),
+        db::models::attempt_plan::PlanStatus::decl(),
+        db::models::attempt_plan::AttemptPlan::decl(),
+        db::models::project_reviewer::ProjectReviewer::decl(),
+        db::models::project_reviewer::UpdateProjectReviewer::decl(),
+        db::models::project_context_packing::ProjectContextPacking::decl(),
+        db::models::project_context_packing::UpdateProjectContextPacking::decl(),
+        db::models::project_dependency_cache::ProjectDependencyCache::decl(),
+        db::models::project_dependency_cache::UpdateProjectDependencyCache::decl(),
+        db::models::project_devcontainer::ProjectDevcontainer::decl(),
+        db::models::project_devcontainer::UpdateProjectDevcontainer::decl(),
+        db::models::project_nix_env::NixTool::decl(),
+        db::models::project_nix_env::ProjectNixEnv::decl(),
+        db::models::project_nix_env::UpdateProjectNixEnv::decl(),
+        db::models::project_env_var::ProjectEnvVar::decl(),
+        db::models::project_env_var::ProjectEnvVarInput::decl(),
+        db::models::project_env_var::UpdateProjectEnv::decl(),
+        db::models::project_worktree_pool::ProjectWorktreePool::decl(),
+        db::models::project_worktree_pool::UpdateProjectWorktreePool::decl(),
+        services::services::worktree_pool::WorktreePoolStatus::decl(),
+        services::services::worktree_templates::WorktreeTemplate::decl(),
+        services::services::worktree_templates::WorktreeTemplateList::decl(),
+        services::services::project_templates::ProjectTemplate::decl(),
+        services::services::project_templates::TemplateSource::decl(),
+        services::services::project_templates::CreateFirstTask::decl(),
+        services::services::project_templates::CreateProjectFromTemplate::decl(),
+        services::services::git::CloneProgress::decl(),
+        services::services::package_manager::PackageManager::decl(),
+        services::services::repo_clone::CloneProject::decl(),
+        services::services::repo_clone::CloneJob::decl(),
+        services::services::repo_clone::CloneEvent::decl(),
+        services::services::workspace_scan::DiscoveredRepo::decl(),
+        services::services::workspace_scan::LastCommit::decl(),
+        services::services::script_detection::ScriptKind::decl(),
+        services::services::script_detection::ScriptSuggestion::decl(),
+        services::services::script_detection::DetectedScripts::decl(),
+        services::services::devcontainer::ProjectDevcontainerStatus::decl(),
+        services::services::nix_env::ProjectNixEnvStatus::decl(),
+        db::models::attempt_review::ReviewVerdict::decl(),
+        db::models::attempt_review::ReviewComment::decl(),
+        db::models::attempt_review::AttemptReview::decl(),
+        db::models::attempt_share_link::AttemptShareLink::decl(),
+        db::models::attempt_share_link::CreateAttemptShareLink::decl(),
+        services::services::share_links::SharedProcess::decl(),
+        services::services::share_links::SharedAttempt::decl(),
+        db::models::attempt_linked_repo::AttemptLinkedRepo::decl(),
+        db::models::attempt_linked_repo::CreateAttemptLinkedRepo::decl(),
+        services::services::linked_repos::LinkedRepoDiff::decl(),
+        db::models::attempt_commit_plan::CommitPlanStatus::decl(),
+        db::models::attempt_commit_plan::PlannedCommit::decl(),
+        db::models::attempt_commit_plan::AttemptCommitPlan::decl(),
+        crate::routes::task_attempts::CreateCommitPlanRequest::decl(),
+        services::services::agent_login::AgentLoginStatus::decl(),
+        services::services::agent_login::AgentLoginSession::decl(),
+        services::services::agent_login::StartAgentLoginRequest::decl(),
+        services::services::agent_login::AgentLoginInput::decl(),
+        crate::routes::workspaces::WorkspaceInfo::decl(),
+        crate::routes::workspaces::WorkspacesResponse::decl(),
+        crate::routes::workspaces::WorkspaceRequest::decl(),
+        crate::routes::editor::EditorOpenRequest::decl(),
+        crate::routes::editor::EditorOpenResponse::decl(),
+        crate::routes::terminal::TerminalQuery::decl(),
+        crate::routes::terminal::TerminalClientMessage::decl(),
+        crate::routes::terminal::TerminalServerMessage::decl(),
+        services::services::config::TerminalConfig::decl(),
+        services::services::config::TerminalMode::decl(),
+        services::services::dev_server::PortSource::decl(),
+        services::services::dev_server::DevServerUrl::decl(),
+        services::services::dev_server::DevServerInfo::decl(),
+        services::services::diff_stats::FileDiffStats::decl(),
+        services::services::diff_stats::LanguageDiffStats::decl(),
+        services::services::diff_stats::DiffStats::decl(),
+        services::services::attachments::ContextFile::decl(),
+        crate::routes::project_groups::ProjectSwimlane::decl(),
+        crate::routes::project_groups::ProjectGroupBoard::decl(),
+        crate::routes::project_groups::CreateGroupTask::decl(),
+        services::services::auth::DeviceFlowStartResponse::decl(),
+        crate::routes::auth::DevicePollStatus::decl(),
+        crate::routes::auth::CheckTokenResponse::decl(),
+        services::services::git::GitBranch::decl(),
+        services::services::git::BranchStatus::decl(),
+        utils::diff::Diff::decl(),
+        utils::diff::FileDiffDetails::decl(),
+        services::services::github_service::CheckState::decl(),
+        services::services::github_service::CommitCheck::decl(),
+        services::services::ci_checks::ChecksState::decl(),
+        services::services::ci_checks::AttemptChecks::decl(),
+        services::services::github_service::RepositoryInfo::decl(),
+        services::services::analytics::AnalyticsEvent::decl(),
+        services::services::analytics::AnalyticsEventCount::decl(),
+        services::services::tool_usage::ToolUsage::decl(),
+        services::services::tool_usage::AttemptToolUsage::decl(),
+        services::services::presence::PresenceToolCall::decl(),
+        services::services::presence::AgentPresence::decl(),
+        services::services::timeline::TimelinePhaseKind::decl(),
+        services::services::timeline::TimelinePhase::decl(),
+        services::services::timeline::AttemptTimeline::decl(),
+        executors::command::CommandBuilder::decl(),
+        executors::profile::ProfileVariantLabel::decl(),
+        executors::profile::ProfileConfig::decl(),
+        executors::profile::VariantAgentConfig::decl(),
+        executors::profile::ProfileConfigs::decl(),
+        executors::executors::claude::PermissionMode::decl(),
+        executors::executors::claude::ClaudeCode::decl(),
+        executors::executors::gemini::Gemini::decl(),
+        executors::executors::amp::Amp::decl(),
+        executors::executors::codex::SandboxMode::decl(),
+        executors::executors::codex::ApprovalPolicy::decl(),
+        executors::executors::codex::Codex::decl(),
+        executors::executors::cursor::Cursor::decl(),
+        executors::executors::opencode::Opencode::decl(),
+        executors::actions::coding_agent_initial::CodingAgentInitialRequest::decl(),
+        executors::actions::coding_agent_follow_up::CodingAgentFollowUpRequest::decl(),
+        crate::routes::tasks::SetTaskBaseBranch::decl(),
+        crate::routes::task_attempts::CreateTaskAttemptBody::decl(),
+        crate::routes::task_attempts::ApprovePlanRequest::decl(),
+        crate::routes::task_attempts::RebaseTaskAttemptRequest::decl(),
+        db::models::task_attempt::TaskAttempt::decl(),
+        db::models::execution_process::ExecutionProcess::decl(),
+        db::models::execution_process::ExecutionProcessStatus::decl(),
+        db::models::execution_process::ExecutionProcessRunReason::decl(),
+        services::services::events::EventPatch::decl(),
+        services::services::events::EventPatchInner::decl(),
+        services::services::events::RecordTypes::decl(),
+        services::services::board::BoardDelta::decl(),
+        services::services::conflicts::AttemptConflict::decl(),
+        services::services::merge_queue::MergeQueuePosition::decl(),
+        executors::logs::NormalizedConversation::decl(),
+        executors::logs::NormalizedEntry::decl(),
+        executors::logs::NormalizedEntryType::decl(),
+        executors::logs::FileChange::decl(),
+        executors::logs::ActionType::decl(),
+        executors::logs::TodoItem::decl(),
+        executors::logs::utils::patch::PatchType::decl(),
+        serde_json::Value::decl(),
+    ]
+}
+
+/// The declarations as they appear in `shared/types.ts`, each one exported
+fn types_body() -> String {
+    decls()
+        .into_iter()
+        .map(|d| {
+            let trimmed = d.trim_start();
+            if trimmed.starts_with("export") {
+                d
+            } else {
+                format!("export {trimmed}")
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n\n")
+}
+
+/// 64-bit FNV-1a, stable across Rust versions unlike `DefaultHasher`
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf29ce484222325, |hash, byte| {
+        (hash ^ u64::from(*byte)).wrapping_mul(0x100000001b3)
+    })
+}
+
+/// Hash of the exported declarations: any change to a shared type changes it, nothing else does
+pub fn api_version() -> &'static str {
+    static API_VERSION: OnceLock<String> = OnceLock::new();
+    API_VERSION.get_or_init(|| format!("{:016x}", fnv1a(types_body().as_bytes())))
+}
+
+/// The whole of `shared/types.ts`
+pub fn types_content() -> String {
+    format!(
+        "{HEADER}\n\n\
+         // Sent as `{API_VERSION_HEADER}`, the server rejects requests built against other types\n\
+         export const API_VERSION = \"{}\";\n\n{}",
+        api_version(),
+        types_body()
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fnv1a() {
+        assert_eq!(fnv1a(b""), 0xcbf29ce484222325);
+        assert_eq!(fnv1a(b"a"), 0xaf63dc4c8601ec8c);
+    }
+
+    #[test]
+    fn test_types_content_carries_api_version() {
+        let content = types_content();
+        assert!(content.contains(&format!(
+            "export const API_VERSION = \"{}\";",
+            api_version()
+        )));
+        assert!(content.contains("export type Project = {"));
+    }
+}
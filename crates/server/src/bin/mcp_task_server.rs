@@ -1,7 +1,10 @@
 use std::str::FromStr;
 
 use rmcp::{transport::stdio, ServiceExt};
-use server::mcp::task_server::TaskServer;
+use server::{
+    mcp::task_server::TaskServer,
+    settings::{Cli, ServerSettings},
+};
 use sqlx::{sqlite::SqliteConnectOptions, SqlitePool};
 use tracing_subscriber::{prelude::*, EnvFilter};
 use utils::{assets::asset_dir, sentry::sentry_layer};
@@ -34,6 +37,9 @@ fn main() -> anyhow::Result<()> {
                 .with(sentry_layer())
                 .init();
 
+            // Share the server's data directory, CLI flags belong to the server binary
+            ServerSettings::load_from(&Cli::default(), std::env::vars())?.apply_directories()?;
+
             let version = env!("CARGO_PKG_VERSION");
             tracing::debug!("[MCP] Starting MCP task server version {version}...");
 
@@ -1,5 +1,5 @@
 use axum::{
-    http::StatusCode,
+    http::{header, HeaderValue, StatusCode},
     response::{IntoResponse, Response},
     Json,
 };
@@ -7,14 +7,81 @@ use db::models::{project::ProjectError, task_attempt::TaskAttemptError};
 use deployment::DeploymentError;
 use executors::executors::ExecutorError;
 use git2::Error as Git2Error;
+use serde::Serialize;
 use services::services::{
-    auth::AuthError, config::ConfigError, container::ContainerError, git::GitServiceError,
-    github_service::GitHubServiceError, worktree_manager::WorktreeError,
+    agent_login::AgentLoginError,
+    attachments::AttachmentError,
+    auth::AuthError,
+    backup::BackupError,
+    budget::BudgetError,
+    changelog::ChangelogError,
+    checkpoints::CheckpointError,
+    config::{ConfigError, ConfigFieldError},
+    container::ContainerError,
+    context_pack::ContextPackError,
+    dependency_updates::DependencyUpdateError,
+    disk_quota::DiskQuotaError,
+    evaluation::EvaluationError,
+    filesystem::FilesystemError,
+    git::GitServiceError,
+    github_service::GitHubServiceError,
+    merge_queue::MergeQueueError,
+    path_rules::PathRulesError,
+    project_env::ProjectEnvError,
+    project_templates::ProjectTemplateError,
+    provider_keys::ProviderKeyError,
+    pty::PtyError,
+    repo_clone::RepoCloneError,
+    repo_config::RepoConfigError,
+    secret_scan::SecretScanError,
+    self_update::SelfUpdateError,
+    sentry_issues::SentryIssueError,
+    share_links::ShareLinkError,
+    sounds::SoundError,
+    support_bundle::SupportBundleError,
+    webhooks::WebhookError,
+    worktree_manager::WorktreeError,
+    worktree_templates::WorktreeTemplateError,
 };
 use thiserror::Error;
-use utils::response::ApiResponse;
+use ts_rs::TS;
+use utils::i18n::{Message, MessageCode};
 
-#[derive(Debug, Error, ts_rs::TS)]
+pub const PROBLEM_JSON_CONTENT_TYPE: &str = "application/problem+json";
+
+/// Error body in the RFC 7807 problem details format
+#[derive(Debug, Serialize, TS)]
+pub struct ProblemDetails {
+    /// `urn:vibe-kanban:problem:{code}`
+    #[serde(rename = "type")]
+    pub problem_type: String,
+    /// Reason phrase of `status`
+    pub title: String,
+    pub status: u16,
+    /// What went wrong, in the locale from the user's config
+    pub detail: String,
+    pub code: MessageCode,
+    pub errors: Vec<ProblemField>,
+}
+
+/// A problem with one field of the request body
+#[derive(Debug, Serialize, TS)]
+pub struct ProblemField {
+    /// JSON pointer to the field, e.g. `/editor/custom_command`
+    pub pointer: String,
+    pub detail: String,
+}
+
+impl From<&ConfigFieldError> for ProblemField {
+    fn from(error: &ConfigFieldError) -> Self {
+        Self {
+            pointer: format!("/{}", error.field.replace('.', "/")),
+            detail: error.message.clone(),
+        }
+    }
+}
+
+#[derive(Debug, Error, TS)]
 #[ts(type = "string")]
 pub enum ApiError {
     #[error(transparent)]
@@ -39,6 +106,68 @@ pub enum ApiError {
     Worktree(#[from] WorktreeError),
     #[error(transparent)]
     Config(#[from] ConfigError),
+    #[error(transparent)]
+    Backup(#[from] BackupError),
+    #[error(transparent)]
+    AgentLogin(#[from] AgentLoginError),
+    #[error(transparent)]
+    RepoConfig(#[from] RepoConfigError),
+    #[error(transparent)]
+    Sound(#[from] SoundError),
+    #[error(transparent)]
+    Pty(#[from] PtyError),
+    #[error(transparent)]
+    Attachment(#[from] AttachmentError),
+    #[error(transparent)]
+    Budget(#[from] BudgetError),
+    #[error(transparent)]
+    SecretScan(#[from] SecretScanError),
+    #[error(transparent)]
+    PathRules(#[from] PathRulesError),
+    #[error(transparent)]
+    ContextPack(#[from] ContextPackError),
+    #[error(transparent)]
+    DiskQuota(#[from] DiskQuotaError),
+    #[error(transparent)]
+    Checkpoint(#[from] CheckpointError),
+    #[error(transparent)]
+    ProjectEnv(#[from] ProjectEnvError),
+    #[error(transparent)]
+    ProviderKey(#[from] ProviderKeyError),
+    #[error(transparent)]
+    Evaluation(#[from] EvaluationError),
+    #[error(transparent)]
+    Webhook(#[from] WebhookError),
+    #[error(transparent)]
+    SentryIssue(#[from] SentryIssueError),
+    #[error(transparent)]
+    DependencyUpdate(#[from] DependencyUpdateError),
+    #[error(transparent)]
+    Changelog(#[from] ChangelogError),
+    #[error(transparent)]
+    WorktreeTemplate(#[from] WorktreeTemplateError),
+    #[error(transparent)]
+    Filesystem(#[from] FilesystemError),
+    #[error(transparent)]
+    MergeQueue(#[from] MergeQueueError),
+    #[error(transparent)]
+    ProjectTemplate(#[from] ProjectTemplateError),
+    #[error(transparent)]
+    RepoClone(#[from] RepoCloneError),
+    #[error(transparent)]
+    SupportBundle(#[from] SupportBundleError),
+    #[error(transparent)]
+    SelfUpdate(#[from] SelfUpdateError),
+    #[error(transparent)]
+    ShareLink(#[from] ShareLinkError),
+    #[error("A project group with this name already exists")]
+    ProjectGroupNameTaken,
+    #[error("{branch} has {commits} commit(s) on top of the merge, confirm to revert it anyway")]
+    UnmergeNeedsConfirmation { branch: String, commits: usize },
+    #[error("{0}")]
+    BadRequest(String),
+    #[error("{0}")]
+    PreconditionFailed(String),
 }
 
 impl From<Git2Error> for ApiError {
@@ -49,22 +178,436 @@ impl From<Git2Error> for ApiError {
 
 impl IntoResponse for ApiError {
     fn into_response(self) -> Response {
-        let (status_code, error_type) = match &self {
-            ApiError::Project(_) => (StatusCode::INTERNAL_SERVER_ERROR, "ProjectError"),
-            ApiError::TaskAttempt(_) => (StatusCode::INTERNAL_SERVER_ERROR, "TaskAttemptError"),
-            ApiError::GitService(_) => (StatusCode::INTERNAL_SERVER_ERROR, "GitServiceError"),
-            ApiError::GitHubService(_) => (StatusCode::INTERNAL_SERVER_ERROR, "GitHubServiceError"),
-            ApiError::Auth(_) => (StatusCode::INTERNAL_SERVER_ERROR, "AuthError"),
-            ApiError::Deployment(_) => (StatusCode::INTERNAL_SERVER_ERROR, "DeploymentError"),
-            ApiError::Container(_) => (StatusCode::INTERNAL_SERVER_ERROR, "ContainerError"),
-            ApiError::Executor(_) => (StatusCode::INTERNAL_SERVER_ERROR, "ExecutorError"),
-            ApiError::Database(_) => (StatusCode::INTERNAL_SERVER_ERROR, "DatabaseError"),
-            ApiError::Worktree(_) => (StatusCode::INTERNAL_SERVER_ERROR, "WorktreeError"),
-            ApiError::Config(_) => (StatusCode::INTERNAL_SERVER_ERROR, "ConfigError"),
+        let (status_code, code) = match &self {
+            ApiError::Project(ProjectError::GitRepoPathExists) => {
+                (StatusCode::CONFLICT, MessageCode::ProjectRepoPathTaken)
+            }
+            ApiError::Project(_) => (StatusCode::INTERNAL_SERVER_ERROR, MessageCode::ProjectError),
+            ApiError::TaskAttempt(_) => (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                MessageCode::TaskAttemptError,
+            ),
+            ApiError::GitService(_) => (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                MessageCode::GitServiceError,
+            ),
+            ApiError::GitHubService(_) => (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                MessageCode::GitHubServiceError,
+            ),
+            ApiError::Auth(_) => (StatusCode::INTERNAL_SERVER_ERROR, MessageCode::AuthError),
+            ApiError::Deployment(_) => (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                MessageCode::DeploymentError,
+            ),
+            ApiError::Container(ContainerError::ShuttingDown) => {
+                (StatusCode::SERVICE_UNAVAILABLE, MessageCode::ContainerError)
+            }
+            ApiError::Container(
+                ContainerError::NothingToPause
+                | ContainerError::NothingToResume
+                | ContainerError::AgentsPaused,
+            ) => (StatusCode::CONFLICT, MessageCode::ContainerError),
+            ApiError::Container(ContainerError::DiskQuota(DiskQuotaError::QuotaExceeded {
+                ..
+            })) => (
+                StatusCode::INSUFFICIENT_STORAGE,
+                MessageCode::DiskQuotaError,
+            ),
+            ApiError::Container(ContainerError::PauseUnsupported) => {
+                (StatusCode::NOT_IMPLEMENTED, MessageCode::ContainerError)
+            }
+            ApiError::Container(ContainerError::RepoConfig(RepoConfigError::Parse(_))) => {
+                (StatusCode::BAD_REQUEST, MessageCode::ContainerError)
+            }
+            ApiError::Container(_) => (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                MessageCode::ContainerError,
+            ),
+            ApiError::Executor(_) => (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                MessageCode::ExecutorError,
+            ),
+            ApiError::Database(_) => (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                MessageCode::DatabaseError,
+            ),
+            ApiError::Worktree(_) => (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                MessageCode::WorktreeError,
+            ),
+            ApiError::Config(ConfigError::InvalidFields(_)) => {
+                (StatusCode::UNPROCESSABLE_ENTITY, MessageCode::ConfigInvalid)
+            }
+            ApiError::Config(ConfigError::ProfileNotFound(_)) => {
+                (StatusCode::NOT_FOUND, MessageCode::ProfileNotFound)
+            }
+            ApiError::Config(ConfigError::InvalidProfiles(_)) => {
+                (StatusCode::BAD_REQUEST, MessageCode::ProfilesInvalid)
+            }
+            ApiError::Config(ConfigError::McpUnsupported) => {
+                (StatusCode::BAD_REQUEST, MessageCode::McpUnsupported)
+            }
+            ApiError::Config(ConfigError::McpConfigPathUnknown) => (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                MessageCode::McpConfigPathUnknown,
+            ),
+            ApiError::Config(ConfigError::McpUpdateFailed(_)) => (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                MessageCode::McpUpdateFailed,
+            ),
+            ApiError::Config(ConfigError::VersionNotFound(_)) => {
+                (StatusCode::NOT_FOUND, MessageCode::ConfigError)
+            }
+            ApiError::Config(ConfigError::InvalidOverride(_)) => {
+                (StatusCode::BAD_REQUEST, MessageCode::ConfigError)
+            }
+            ApiError::Config(_) => (StatusCode::INTERNAL_SERVER_ERROR, MessageCode::ConfigError),
+            ApiError::Backup(BackupError::NotFound(_)) => {
+                (StatusCode::NOT_FOUND, MessageCode::BackupError)
+            }
+            ApiError::Backup(BackupError::InvalidName(_) | BackupError::Corrupt(_)) => {
+                (StatusCode::BAD_REQUEST, MessageCode::BackupError)
+            }
+            ApiError::Backup(_) => (StatusCode::INTERNAL_SERVER_ERROR, MessageCode::BackupError),
+            ApiError::AgentLogin(AgentLoginError::NotFound(_)) => {
+                (StatusCode::NOT_FOUND, MessageCode::AgentLoginError)
+            }
+            ApiError::AgentLogin(AgentLoginError::Io(_)) => (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                MessageCode::AgentLoginError,
+            ),
+            ApiError::AgentLogin(_) => (StatusCode::BAD_REQUEST, MessageCode::AgentLoginError),
+            ApiError::RepoConfig(RepoConfigError::Parse(_)) => {
+                (StatusCode::BAD_REQUEST, MessageCode::RepoConfigError)
+            }
+            ApiError::RepoConfig(_) => (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                MessageCode::RepoConfigError,
+            ),
+            ApiError::Sound(SoundError::NotFound(_)) => {
+                (StatusCode::NOT_FOUND, MessageCode::SoundError)
+            }
+            ApiError::Sound(SoundError::InvalidName(_) | SoundError::UnsupportedFormat) => {
+                (StatusCode::BAD_REQUEST, MessageCode::SoundError)
+            }
+            ApiError::Sound(SoundError::TooLarge) => {
+                (StatusCode::PAYLOAD_TOO_LARGE, MessageCode::SoundError)
+            }
+            ApiError::Sound(_) => (StatusCode::INTERNAL_SERVER_ERROR, MessageCode::SoundError),
+            ApiError::Pty(
+                PtyError::Disabled | PtyError::NotAllowed(_) | PtyError::CrossOrigin(_),
+            ) => (StatusCode::FORBIDDEN, MessageCode::PtyError),
+            ApiError::Pty(_) => (StatusCode::INTERNAL_SERVER_ERROR, MessageCode::PtyError),
+            ApiError::Attachment(AttachmentError::NotFound(_)) => {
+                (StatusCode::NOT_FOUND, MessageCode::AttachmentError)
+            }
+            ApiError::Attachment(AttachmentError::InvalidName(_) | AttachmentError::TooMany) => {
+                (StatusCode::BAD_REQUEST, MessageCode::AttachmentError)
+            }
+            ApiError::Attachment(AttachmentError::TooLarge) => {
+                (StatusCode::PAYLOAD_TOO_LARGE, MessageCode::AttachmentError)
+            }
+            ApiError::Attachment(_) => (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                MessageCode::AttachmentError,
+            ),
+            ApiError::Budget(BudgetError::Database(_)) => {
+                (StatusCode::INTERNAL_SERVER_ERROR, MessageCode::BudgetError)
+            }
+            ApiError::Budget(_) => (StatusCode::PAYMENT_REQUIRED, MessageCode::BudgetError),
+            ApiError::SecretScan(SecretScanError::FindingsPresent(_)) => {
+                (StatusCode::CONFLICT, MessageCode::SecretScanError)
+            }
+            ApiError::SecretScan(_) => (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                MessageCode::SecretScanError,
+            ),
+            ApiError::PathRules(PathRulesError::InvalidGlob(..)) => {
+                (StatusCode::BAD_REQUEST, MessageCode::PathRulesError)
+            }
+            ApiError::PathRules(_) => (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                MessageCode::PathRulesError,
+            ),
+            ApiError::ContextPack(ContextPackError::InvalidGlob(..)) => {
+                (StatusCode::BAD_REQUEST, MessageCode::ContextPackError)
+            }
+            ApiError::ContextPack(_) => (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                MessageCode::ContextPackError,
+            ),
+            ApiError::DiskQuota(DiskQuotaError::QuotaExceeded { .. }) => (
+                StatusCode::INSUFFICIENT_STORAGE,
+                MessageCode::DiskQuotaError,
+            ),
+            ApiError::DiskQuota(_) => (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                MessageCode::DiskQuotaError,
+            ),
+            ApiError::Checkpoint(CheckpointError::NotFound(_)) => {
+                (StatusCode::NOT_FOUND, MessageCode::CheckpointError)
+            }
+            ApiError::Checkpoint(CheckpointError::ExecutionRunning) => {
+                (StatusCode::CONFLICT, MessageCode::CheckpointError)
+            }
+            ApiError::Checkpoint(_) => (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                MessageCode::CheckpointError,
+            ),
+            ApiError::ProjectEnv(
+                ProjectEnvError::InvalidKey(_)
+                | ProjectEnvError::DuplicateKey(_)
+                | ProjectEnvError::MissingSecret(_),
+            ) => (StatusCode::BAD_REQUEST, MessageCode::ProjectEnvError),
+            ApiError::ProjectEnv(_) => (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                MessageCode::ProjectEnvError,
+            ),
+            ApiError::ProviderKey(ProviderKeyError::Empty) => {
+                (StatusCode::BAD_REQUEST, MessageCode::ProviderKeyError)
+            }
+            ApiError::ProviderKey(_) => (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                MessageCode::ProviderKeyError,
+            ),
+            ApiError::Evaluation(EvaluationError::Invalid(_)) => {
+                (StatusCode::BAD_REQUEST, MessageCode::EvaluationError)
+            }
+            ApiError::Evaluation(_) => (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                MessageCode::EvaluationError,
+            ),
+            ApiError::Webhook(WebhookError::InvalidUrl(_)) => {
+                (StatusCode::BAD_REQUEST, MessageCode::WebhookError)
+            }
+            ApiError::Webhook(WebhookError::NotFound) => {
+                (StatusCode::NOT_FOUND, MessageCode::WebhookError)
+            }
+            ApiError::Webhook(_) => (StatusCode::INTERNAL_SERVER_ERROR, MessageCode::WebhookError),
+            ApiError::SentryIssue(SentryIssueError::InvalidLink(_) | SentryIssueError::NoToken) => {
+                (StatusCode::BAD_REQUEST, MessageCode::SentryError)
+            }
+            ApiError::SentryIssue(SentryIssueError::NotFound(_)) => {
+                (StatusCode::NOT_FOUND, MessageCode::SentryError)
+            }
+            ApiError::SentryIssue(SentryIssueError::Api(_) | SentryIssueError::Http(_)) => {
+                (StatusCode::BAD_GATEWAY, MessageCode::SentryError)
+            }
+            ApiError::SentryIssue(_) => {
+                (StatusCode::INTERNAL_SERVER_ERROR, MessageCode::SentryError)
+            }
+            ApiError::DependencyUpdate(DependencyUpdateError::AlreadyRunning) => {
+                (StatusCode::CONFLICT, MessageCode::DependencyUpdateError)
+            }
+            ApiError::DependencyUpdate(DependencyUpdateError::Budget(_)) => (
+                StatusCode::PAYMENT_REQUIRED,
+                MessageCode::DependencyUpdateError,
+            ),
+            ApiError::DependencyUpdate(_) => (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                MessageCode::DependencyUpdateError,
+            ),
+            ApiError::Changelog(ChangelogError::InvalidDirectory(_)) => {
+                (StatusCode::BAD_REQUEST, MessageCode::ChangelogError)
+            }
+            ApiError::Changelog(_) => (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                MessageCode::ChangelogError,
+            ),
+            ApiError::WorktreeTemplate(
+                WorktreeTemplateError::InvalidPath(_) | WorktreeTemplateError::NotText(_),
+            ) => (StatusCode::BAD_REQUEST, MessageCode::WorktreeTemplateError),
+            ApiError::WorktreeTemplate(WorktreeTemplateError::TooLarge) => (
+                StatusCode::PAYLOAD_TOO_LARGE,
+                MessageCode::WorktreeTemplateError,
+            ),
+            ApiError::WorktreeTemplate(WorktreeTemplateError::NotFound(_)) => {
+                (StatusCode::NOT_FOUND, MessageCode::WorktreeTemplateError)
+            }
+            ApiError::WorktreeTemplate(_) => (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                MessageCode::WorktreeTemplateError,
+            ),
+            ApiError::Filesystem(FilesystemError::DirectoryDoesNotExist) => {
+                (StatusCode::NOT_FOUND, MessageCode::DirectoryNotFound)
+            }
+            ApiError::Filesystem(FilesystemError::PathIsNotDirectory) => {
+                (StatusCode::BAD_REQUEST, MessageCode::NotADirectory)
+            }
+            ApiError::Filesystem(FilesystemError::Io(_)) => (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                MessageCode::DirectoryReadFailed,
+            ),
+            ApiError::MergeQueue(
+                MergeQueueError::AlreadyMerged
+                | MergeQueueError::AlreadyQueued
+                | MergeQueueError::NotQueued,
+            ) => (StatusCode::CONFLICT, MessageCode::MergeQueueError),
+            ApiError::MergeQueue(MergeQueueError::NoBranch | MergeQueueError::WorktreeMissing) => {
+                (StatusCode::BAD_REQUEST, MessageCode::MergeQueueError)
+            }
+            ApiError::MergeQueue(_) => (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                MessageCode::MergeQueueError,
+            ),
+            ApiError::ProjectTemplate(
+                ProjectTemplateError::UnknownTemplate(_)
+                | ProjectTemplateError::TargetNotEmpty(_)
+                | ProjectTemplateError::Clone(_),
+            ) => (StatusCode::BAD_REQUEST, MessageCode::ProjectTemplateError),
+            ApiError::ProjectTemplate(_) => (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                MessageCode::ProjectTemplateError,
+            ),
+            ApiError::RepoClone(RepoCloneError::UnknownClone(_)) => {
+                (StatusCode::NOT_FOUND, MessageCode::RepoCloneError)
+            }
+            ApiError::RepoClone(
+                RepoCloneError::InvalidUrl(_) | RepoCloneError::TargetExists(_),
+            ) => (StatusCode::BAD_REQUEST, MessageCode::RepoCloneError),
+            ApiError::RepoClone(_) => (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                MessageCode::RepoCloneError,
+            ),
+            ApiError::SupportBundle(_) => (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                MessageCode::SupportBundleError,
+            ),
+            ApiError::SelfUpdate(SelfUpdateError::Offline) => (
+                StatusCode::SERVICE_UNAVAILABLE,
+                MessageCode::SelfUpdateError,
+            ),
+            ApiError::SelfUpdate(_) => (StatusCode::BAD_GATEWAY, MessageCode::SelfUpdateError),
+            ApiError::ShareLink(ShareLinkError::NotFound) => {
+                (StatusCode::NOT_FOUND, MessageCode::ShareLinkError)
+            }
+            ApiError::ShareLink(ShareLinkError::Expired) => {
+                (StatusCode::GONE, MessageCode::ShareLinkError)
+            }
+            ApiError::ShareLink(ShareLinkError::InvalidExpiry) => {
+                (StatusCode::BAD_REQUEST, MessageCode::ShareLinkError)
+            }
+            ApiError::ShareLink(_) => (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                MessageCode::ShareLinkError,
+            ),
+            ApiError::ProjectGroupNameTaken => {
+                (StatusCode::CONFLICT, MessageCode::ProjectGroupNameTaken)
+            }
+            ApiError::UnmergeNeedsConfirmation { .. } => {
+                (StatusCode::CONFLICT, MessageCode::UnmergeNeedsConfirmation)
+            }
+            ApiError::BadRequest(_) => (StatusCode::BAD_REQUEST, MessageCode::BadRequest),
+            ApiError::PreconditionFailed(_) => (
+                StatusCode::PRECONDITION_FAILED,
+                MessageCode::PreconditionFailed,
+            ),
+        };
+
+        let problem = ProblemDetails {
+            problem_type: format!("urn:vibe-kanban:problem:{code}"),
+            title: status_code
+                .canonical_reason()
+                .unwrap_or("Error")
+                .to_string(),
+            status: status_code.as_u16(),
+            detail: self.message(code).localized(),
+            code,
+            errors: self.field_errors(),
+        };
+        let mut response = (status_code, Json(problem)).into_response();
+        response.headers_mut().insert(
+            header::CONTENT_TYPE,
+            HeaderValue::from_static(PROBLEM_JSON_CONTENT_TYPE),
+        );
+        response
+    }
+}
+
+impl ApiError {
+    /// The catalog message for this error. Errors with a code of their own fill in its
+    /// parameters, the rest wrap their text in the code of their category.
+    fn message(&self, code: MessageCode) -> Message {
+        match self {
+            ApiError::Config(ConfigError::ProfileNotFound(profile)) => {
+                Message::new(code).with("profile", profile)
+            }
+            ApiError::Config(ConfigError::InvalidProfiles(e)) => Message::detail(code, e),
+            ApiError::Config(ConfigError::McpUpdateFailed(e)) => Message::detail(code, e),
+            ApiError::Config(ConfigError::InvalidFields(errors)) => {
+                Message::new(code).with("count", errors.len())
+            }
+            ApiError::Filesystem(FilesystemError::Io(e)) => Message::detail(code, e),
+            ApiError::UnmergeNeedsConfirmation { branch, commits } => Message::new(code)
+                .with("branch", branch)
+                .with("count", commits),
+            _ => Message::detail(code, self),
+        }
+    }
+
+    /// Problems with individual fields of the request body
+    fn field_errors(&self) -> Vec<ProblemField> {
+        match self {
+            ApiError::Config(ConfigError::InvalidFields(errors)) => {
+                errors.iter().map(ProblemField::from).collect()
+            }
+            _ => Vec::new(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::body::to_bytes;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_invalid_config_is_a_problem_with_field_pointers() {
+        let error = ApiError::Config(ConfigError::InvalidFields(vec![ConfigFieldError {
+            field: "editor.custom_command".to_string(),
+            message: "Command not found".to_string(),
+        }]));
+        let response = error.into_response();
+        assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+        assert_eq!(
+            response.headers()[header::CONTENT_TYPE],
+            PROBLEM_JSON_CONTENT_TYPE
+        );
+
+        let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let problem: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(problem["type"], "urn:vibe-kanban:problem:config_invalid");
+        assert_eq!(problem["status"], 422);
+        assert_eq!(problem["code"], "config_invalid");
+        assert_eq!(
+            problem["errors"],
+            serde_json::json!([{
+                "pointer": "/editor/custom_command",
+                "detail": "Command not found",
+            }])
+        );
+    }
+
+    #[tokio::test]
+    async fn test_unmerge_confirmation_is_not_a_stale_etag() {
+        let error = ApiError::UnmergeNeedsConfirmation {
+            branch: "main".to_string(),
+            commits: 2,
         };
+        let response = error.into_response();
+        assert_eq!(response.status(), StatusCode::CONFLICT);
+
+        let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let problem: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(problem["code"], "unmerge_needs_confirmation");
+        assert_eq!(
+            problem["detail"],
+            "main has 2 commit(s) on top of the merge, confirm to revert it anyway"
+        );
 
-        let error_message = format!("{}: {}", error_type, self);
-        let response = ApiResponse::<()>::error(&error_message);
-        (status_code, Json(response)).into_response()
+        let stale = ApiError::PreconditionFailed("stale".to_string()).into_response();
+        assert_eq!(stale.status(), StatusCode::PRECONDITION_FAILED);
     }
 }
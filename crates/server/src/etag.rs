@@ -0,0 +1,49 @@
+use std::{
+    collections::hash_map::DefaultHasher,
+    hash::{Hash, Hasher},
+};
+
+use axum::http::{header, HeaderMap, HeaderName, HeaderValue};
+use serde::Serialize;
+
+use crate::error::ApiError;
+
+/// Response parts carrying an `ETag` header alongside the body
+pub type WithETag<T> = ([(HeaderName, HeaderValue); 1], T);
+
+/// Strong ETag derived from the serialized representation of `value`.
+pub fn etag_for<T: Serialize>(value: &T) -> String {
+    let mut hasher = DefaultHasher::new();
+    serde_json::to_string(value)
+        .unwrap_or_default()
+        .hash(&mut hasher);
+    format!("\"{:016x}\"", hasher.finish())
+}
+
+pub fn with_etag<S: Serialize, T>(entity: &S, body: T) -> WithETag<T> {
+    let etag = HeaderValue::from_str(&etag_for(entity)).expect("hex etag is a valid header");
+    ([(header::ETAG, etag)], body)
+}
+
+/// Rejects the request if it carries an `If-Match` header that doesn't match `current`.
+///
+/// Requests without `If-Match` are allowed through, so clients opt in to optimistic
+/// concurrency by echoing back the `ETag` they last saw.
+pub fn check_if_match<T: Serialize>(headers: &HeaderMap, current: &T) -> Result<(), ApiError> {
+    let Some(if_match) = headers.get(header::IF_MATCH) else {
+        return Ok(());
+    };
+    let if_match = if_match.to_str().unwrap_or_default();
+    let current = etag_for(current);
+    let matches = if_match
+        .split(',')
+        .map(str::trim)
+        .any(|candidate| candidate == "*" || candidate.trim_start_matches("W/") == current);
+    if matches {
+        Ok(())
+    } else {
+        Err(ApiError::PreconditionFailed(
+            "Resource was modified since it was last fetched".to_string(),
+        ))
+    }
+}
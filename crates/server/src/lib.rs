@@ -1,7 +1,11 @@
+pub mod api_types;
 pub mod error;
+pub mod etag;
 pub mod mcp;
 pub mod middleware;
 pub mod routes;
+pub mod settings;
+pub mod tray;
 
 // #[cfg(feature = "cloud")]
 // type DeploymentImpl = vibe_kanban_cloud::deployment::CloudDeployment;
@@ -1,12 +1,20 @@
+use std::future::{Future, IntoFuture};
+
 use anyhow::{self, Error as AnyhowError};
 use deployment::{Deployment, DeploymentError};
-use server::{routes, DeploymentImpl};
+use server::{
+    routes,
+    settings::{ServerSettings, SettingsError},
+    DeploymentImpl,
+};
+use services::services::{baseline::sync_baseline, self_update};
 use sqlx::Error as SqlxError;
-use strip_ansi_escapes::strip;
 use thiserror::Error;
+use tokio_util::sync::CancellationToken;
 use tracing_subscriber::{prelude::*, EnvFilter};
 use utils::{
-    assets::asset_dir, browser::open_browser, port_file::write_port_file, sentry::sentry_layer,
+    assets::asset_dir, browser::open_browser, otel::otlp_layer, port_file::write_port_file,
+    sentry::sentry_layer, server_log,
 };
 
 #[derive(Debug, Error)]
@@ -18,52 +26,97 @@ pub enum VibeKanbanError {
     #[error(transparent)]
     Deployment(#[from] DeploymentError),
     #[error(transparent)]
+    Settings(#[from] SettingsError),
+    #[error(transparent)]
     Other(#[from] AnyhowError),
 }
 
 #[tokio::main]
 async fn main() -> Result<(), VibeKanbanError> {
-    let log_level = std::env::var("RUST_LOG").unwrap_or_else(|_| "info".to_string());
+    let settings = ServerSettings::load()?;
+    settings.apply_directories()?;
+    utils::offline::set_offline(settings.offline);
+
+    let log_level = &settings.log_level;
     let filter_string = format!(
         "warn,server={level},services={level},db={level},executors={level},deployment={level},local_deployment={level},utils={level}",
         level = log_level
     );
-    let env_filter = EnvFilter::try_new(filter_string).expect("Failed to create tracing filter");
+    let env_filter = EnvFilter::try_new(&filter_string).expect("Failed to create tracing filter");
+    let (otel_layer, tracer_provider) = match settings.otlp_traces_endpoint.as_deref() {
+        Some(endpoint) => {
+            let (layer, provider) = otlp_layer(endpoint).map_err(anyhow::Error::from)?;
+            let filter =
+                EnvFilter::try_new(&filter_string).expect("Failed to create tracing filter");
+            (Some(layer.with_filter(filter)), Some(provider))
+        }
+        None => (None, None),
+    };
+    // Kept in the data dir so diagnostics outlive the terminal's scrollback
+    let (file_layer, file_layer_error) = match server_log::file_layer() {
+        Ok(layer) => {
+            let filter =
+                EnvFilter::try_new(&filter_string).expect("Failed to create tracing filter");
+            (Some(layer.with_filter(filter)), None)
+        }
+        Err(e) => (None, Some(e)),
+    };
     tracing_subscriber::registry()
         .with(tracing_subscriber::fmt::layer().with_filter(env_filter))
+        .with(file_layer)
         .with(sentry_layer())
+        .with(otel_layer)
         .init();
+    if let Some(e) = file_layer_error {
+        tracing::warn!("Failed to open the server log file: {}", e);
+    }
+    if let Some(endpoint) = &settings.otlp_traces_endpoint {
+        tracing::info!("Exporting traces to {}", endpoint);
+    }
+    if settings.offline {
+        tracing::info!("Offline mode, outside services won't be contacted");
+    }
+    if let Some(per_minute) = settings.rate_limit_per_minute {
+        tracing::info!("Limiting API clients to {} requests per minute", per_minute);
+    }
 
     // Create asset directory if it doesn't exist
     if !asset_dir().exists() {
         std::fs::create_dir_all(asset_dir())?;
     }
 
+    // Applied before the deployment loads the config and profiles it adds to
+    let baseline = settings.baseline_source()?;
+    if let Err(e) = sync_baseline(baseline.as_ref()).await {
+        tracing::warn!("Failed to apply the baseline config: {}", e);
+    }
+
     let deployment = DeploymentImpl::new().await?;
     deployment.update_sentry_scope().await?;
     deployment.cleanup_orphan_executions().await?;
-    deployment.spawn_pr_monitor_service().await;
+    // Watching PRs polls GitHub, and update checks the npm registry
+    if !settings.offline {
+        deployment.spawn_pr_monitor_service().await;
+        tokio::spawn(self_update::run());
+    }
+    deployment.spawn_branch_sync_service().await;
+    deployment.spawn_merge_queue_service().await;
+    deployment.spawn_webhook_service().await;
+    deployment.spawn_ci_triage_service().await;
+    deployment.spawn_dependency_update_service().await;
+    deployment.backup().spawn_daily_snapshots().await;
     deployment
-        .track_if_analytics_allowed("session_start", serde_json::json!({}))
+        .track_event("session_start", serde_json::json!({}))
         .await;
 
-    let app_router = routes::router(deployment);
-
-    let port = std::env::var("BACKEND_PORT")
-        .or_else(|_| std::env::var("PORT"))
-        .ok()
-        .and_then(|s| {
-            // remove any ANSI codes, then turn into String
-            let cleaned =
-                String::from_utf8(strip(s.as_bytes())).expect("UTF-8 after stripping ANSI");
-            cleaned.trim().parse::<u16>().ok()
-        })
-        .unwrap_or_else(|| {
-            tracing::info!("No PORT environment variable set, using port 0 for auto-assignment");
-            0
-        }); // Use 0 to find free port if no specific port provided
-
-    let host = std::env::var("HOST").unwrap_or_else(|_| "127.0.0.1".to_string());
+    let app_router = routes::router(deployment.clone(), &settings);
+
+    let port = settings.port;
+    if port == 0 {
+        tracing::info!("No port configured, using port 0 for auto-assignment");
+    }
+
+    let host = &settings.host;
     let listener = tokio::net::TcpListener::bind(format!("{host}:{port}")).await?;
     let actual_port = listener.local_addr()?.port(); // get → 53427 (example)
 
@@ -83,6 +136,99 @@ async fn main() -> Result<(), VibeKanbanError> {
         }
     }
 
-    axum::serve(listener, app_router).await?;
+    let shutdown = CancellationToken::new();
+    let shutdown_service = deployment.shutdown().clone();
+    let server = axum::serve(listener, app_router)
+        .with_graceful_shutdown(shutdown_signal(deployment.clone(), shutdown.clone()));
+    if settings.tray {
+        let board_url = format!("http://127.0.0.1:{actual_port}");
+        serve_with_tray(server.into_future(), deployment, board_url, shutdown).await?;
+    } else {
+        server.await?;
+    }
+
+    // Flush the spans still waiting to be exported
+    if let Some(provider) = tracer_provider {
+        if let Err(e) = provider.shutdown() {
+            tracing::warn!("Failed to flush traces: {}", e);
+        }
+    }
+    if shutdown_service.restart_for_update_requested() {
+        tracing::info!("Handing over to the launcher to install the update");
+        std::process::exit(self_update::UPDATE_EXIT_CODE);
+    }
+    Ok(())
+}
+
+/// Serves from a task while the tray's event loop takes over the main thread
+#[cfg(feature = "tray")]
+async fn serve_with_tray(
+    server: impl Future<Output = std::io::Result<()>> + Send + 'static,
+    deployment: DeploymentImpl,
+    board_url: String,
+    shutdown: CancellationToken,
+) -> Result<(), VibeKanbanError> {
+    let stopped = CancellationToken::new();
+    let server = tokio::spawn({
+        let stopped = stopped.clone();
+        async move {
+            let result = server.await;
+            stopped.cancel();
+            result
+        }
+    });
+    tokio::task::block_in_place(|| {
+        server::tray::app::run(deployment, board_url, shutdown, stopped)
+    });
+    server.await.map_err(anyhow::Error::from)??;
+    Ok(())
+}
+
+#[cfg(not(feature = "tray"))]
+async fn serve_with_tray(
+    server: impl Future<Output = std::io::Result<()>>,
+    _deployment: DeploymentImpl,
+    _board_url: String,
+    _shutdown: CancellationToken,
+) -> Result<(), VibeKanbanError> {
+    tracing::warn!("This build has no tray support, rebuild with the `tray` feature");
+    server.await?;
     Ok(())
 }
+
+/// Resolves once Ctrl+C, SIGTERM or `shutdown` is received and running executions have been
+/// handed over
+async fn shutdown_signal(deployment: DeploymentImpl, shutdown: CancellationToken) {
+    let ctrl_c = async {
+        if let Err(e) = tokio::signal::ctrl_c().await {
+            tracing::error!("Failed to listen for Ctrl+C: {}", e);
+            std::future::pending::<()>().await;
+        }
+    };
+
+    #[cfg(unix)]
+    let terminate = async {
+        match tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate()) {
+            Ok(mut signal) => {
+                signal.recv().await;
+            }
+            Err(e) => {
+                tracing::error!("Failed to listen for SIGTERM: {}", e);
+                std::future::pending::<()>().await;
+            }
+        }
+    };
+
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    tokio::select! {
+        _ = ctrl_c => {},
+        _ = terminate => {},
+        _ = shutdown.cancelled() => {},
+        _ = deployment.shutdown().requested() => {},
+    }
+
+    tracing::info!("Shutdown requested, handing over running executions...");
+    deployment.shutdown_gracefully().await;
+}
@@ -269,6 +269,9 @@ impl TaskServer {
             title: title.clone(),
             description: description.clone(),
             parent_task_attempt: None,
+            priority: None,
+            labels: Vec::new(),
+            base_branch: None,
         };
 
         match Task::create(&self.pool, &create_task_data, task_id).await {
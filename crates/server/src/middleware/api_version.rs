@@ -0,0 +1,86 @@
+use axum::{
+    extract::Request,
+    http::{HeaderValue, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Json, Response},
+};
+use utils::{
+    i18n::{Message, MessageCode},
+    response::ApiResponse,
+};
+
+use crate::api_types::{api_version, API_VERSION_HEADER};
+
+/// Reports the server's API version on every response, and rejects with 409 a request that
+/// names another one: its bodies would be built from types the server no longer has.
+///
+/// Requests without the header, e.g. from scripts or the MCP server, pass straight through.
+pub async fn api_version_middleware(request: Request, next: Next) -> Response {
+    let server_version = api_version();
+    let client_version = request
+        .headers()
+        .get(API_VERSION_HEADER)
+        .and_then(|v| v.to_str().ok())
+        .map(str::trim)
+        .filter(|v| !v.is_empty() && *v != server_version)
+        .map(str::to_string);
+
+    let mut response = match client_version {
+        None => next.run(request).await,
+        Some(client_version) => {
+            tracing::warn!(
+                "Rejected {} {} from a frontend built for API version {}, the server runs {}",
+                request.method(),
+                request.uri().path(),
+                client_version,
+                server_version
+            );
+            let message = Message::new(MessageCode::ApiVersionMismatch)
+                .with("client", client_version)
+                .with("server", server_version);
+            (
+                StatusCode::CONFLICT,
+                Json(ApiResponse::<()>::localized_error(message)),
+            )
+                .into_response()
+        }
+    };
+    response
+        .headers_mut()
+        .insert(API_VERSION_HEADER, HeaderValue::from_static(server_version));
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::{body::Body, http, middleware::from_fn, routing::get, Router};
+    use tower::ServiceExt;
+
+    use super::*;
+
+    async fn call(version: Option<&str>) -> Response {
+        let app = Router::new()
+            .route("/", get(|| async { "ok" }))
+            .layer(from_fn(api_version_middleware));
+        let mut request = http::Request::get("/");
+        if let Some(version) = version {
+            request = request.header(API_VERSION_HEADER, version);
+        }
+        app.oneshot(request.body(Body::empty()).unwrap())
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_other_api_version_is_rejected() {
+        for version in [None, Some(api_version())] {
+            let response = call(version).await;
+            assert_eq!(response.status(), StatusCode::OK);
+            assert_eq!(response.headers()[API_VERSION_HEADER], api_version());
+        }
+
+        let response = call(Some("0000000000000000")).await;
+        assert_eq!(response.status(), StatusCode::CONFLICT);
+        assert_eq!(response.headers()[API_VERSION_HEADER], api_version());
+    }
+}
@@ -0,0 +1,240 @@
+use std::{
+    collections::{hash_map::DefaultHasher, HashMap},
+    hash::{Hash, Hasher},
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use axum::{
+    body::{to_bytes, Body, Bytes},
+    extract::{Request, State},
+    http::{header, HeaderValue, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Json, Response},
+};
+use utils::{i18n::MessageCode, response::ApiResponse};
+
+pub const IDEMPOTENCY_KEY_HEADER: &str = "idempotency-key";
+
+/// How long a completed response is replayed for
+const IDEMPOTENCY_TTL: Duration = Duration::from_secs(24 * 60 * 60);
+/// Request bodies above this size are rejected rather than buffered
+const MAX_BODY_BYTES: usize = 2 * 1024 * 1024;
+
+enum IdempotencyEntry {
+    InFlight,
+    Completed {
+        fingerprint: u64,
+        status: StatusCode,
+        content_type: Option<HeaderValue>,
+        body: Bytes,
+        completed_at: Instant,
+    },
+}
+
+/// In-memory record of responses keyed by the client's `Idempotency-Key`.
+#[derive(Clone, Default)]
+pub struct IdempotencyStore {
+    entries: Arc<Mutex<HashMap<String, IdempotencyEntry>>>,
+}
+
+impl IdempotencyStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+/// Clears a key's `InFlight` entry unless the response got stored, including when the
+/// handler is dropped because the client went away
+struct InFlightGuard {
+    store: IdempotencyStore,
+    key: String,
+    completed: bool,
+}
+
+impl InFlightGuard {
+    fn complete(mut self, entry: IdempotencyEntry) {
+        self.store
+            .entries
+            .lock()
+            .unwrap()
+            .insert(self.key.clone(), entry);
+        self.completed = true;
+    }
+}
+
+impl Drop for InFlightGuard {
+    fn drop(&mut self) {
+        if !self.completed {
+            if let Ok(mut entries) = self.store.entries.lock() {
+                entries.remove(&self.key);
+            }
+        }
+    }
+}
+
+fn fingerprint(method: &str, path: &str, body: &[u8]) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    method.hash(&mut hasher);
+    path.hash(&mut hasher);
+    body.hash(&mut hasher);
+    hasher.finish()
+}
+
+fn reject(status: StatusCode, code: MessageCode) -> Response {
+    (status, Json(ApiResponse::<()>::localized_error(code))).into_response()
+}
+
+/// Replays the stored response when a request is retried with the same `Idempotency-Key`.
+///
+/// Requests without the header pass straight through. A key reused for a different
+/// method, path or body is rejected, as is a retry that arrives while the original
+/// is still running. Server errors and requests the client gave up on are not stored so
+/// the client can retry them.
+pub async fn idempotency_middleware(
+    State(store): State<IdempotencyStore>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let Some(key) = request
+        .headers()
+        .get(IDEMPOTENCY_KEY_HEADER)
+        .and_then(|v| v.to_str().ok())
+        .map(|v| v.trim().to_string())
+        .filter(|v| !v.is_empty())
+    else {
+        return next.run(request).await;
+    };
+
+    let (parts, body) = request.into_parts();
+    let body = match to_bytes(body, MAX_BODY_BYTES).await {
+        Ok(body) => body,
+        Err(_) => {
+            return reject(
+                StatusCode::PAYLOAD_TOO_LARGE,
+                MessageCode::RequestBodyTooLarge,
+            )
+        }
+    };
+    let fingerprint = fingerprint(parts.method.as_str(), parts.uri.path(), &body);
+
+    {
+        let mut entries = store.entries.lock().unwrap();
+        entries.retain(|_, entry| match entry {
+            IdempotencyEntry::Completed { completed_at, .. } => {
+                completed_at.elapsed() < IDEMPOTENCY_TTL
+            }
+            IdempotencyEntry::InFlight => true,
+        });
+
+        match entries.get(&key) {
+            Some(IdempotencyEntry::InFlight) => {
+                return reject(StatusCode::CONFLICT, MessageCode::IdempotencyKeyInProgress);
+            }
+            Some(IdempotencyEntry::Completed {
+                fingerprint: stored,
+                status,
+                content_type,
+                body,
+                ..
+            }) => {
+                if *stored != fingerprint {
+                    return reject(
+                        StatusCode::UNPROCESSABLE_ENTITY,
+                        MessageCode::IdempotencyKeyReused,
+                    );
+                }
+                tracing::debug!("Replaying response for Idempotency-Key {}", key);
+                let mut response = Response::builder()
+                    .status(*status)
+                    .header("idempotent-replayed", "true");
+                if let Some(content_type) = content_type {
+                    response = response.header(header::CONTENT_TYPE, content_type);
+                }
+                return response.body(Body::from(body.clone())).unwrap();
+            }
+            None => {
+                entries.insert(key.clone(), IdempotencyEntry::InFlight);
+            }
+        }
+    }
+
+    let guard = InFlightGuard {
+        store: store.clone(),
+        key: key.clone(),
+        completed: false,
+    };
+    let response = next.run(Request::from_parts(parts, Body::from(body))).await;
+    let (parts, body) = response.into_parts();
+
+    if parts.status.is_server_error() {
+        return Response::from_parts(parts, body);
+    }
+
+    let body = match to_bytes(body, usize::MAX).await {
+        Ok(body) => body,
+        Err(e) => {
+            tracing::error!(
+                "Failed to buffer response for Idempotency-Key {}: {}",
+                key,
+                e
+            );
+            return reject(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                MessageCode::ResponseReadFailed,
+            );
+        }
+    };
+    guard.complete(IdempotencyEntry::Completed {
+        fingerprint,
+        status: parts.status,
+        content_type: parts.headers.get(header::CONTENT_TYPE).cloned(),
+        body: body.clone(),
+        completed_at: Instant::now(),
+    });
+
+    Response::from_parts(parts, Body::from(body))
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::{http, middleware::from_fn_with_state, routing::post, Router};
+    use tower::ServiceExt;
+
+    use super::*;
+
+    fn request(body: &'static str) -> Request {
+        http::Request::post("/")
+            .header(IDEMPOTENCY_KEY_HEADER, "key")
+            .body(Body::from(body))
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_key_is_released_when_the_client_goes_away() {
+        let app = Router::new()
+            .route(
+                "/",
+                post(|body: String| async move {
+                    if body == "hang" {
+                        std::future::pending::<()>().await;
+                    }
+                    "ok"
+                }),
+            )
+            .layer(from_fn_with_state(
+                IdempotencyStore::new(),
+                idempotency_middleware,
+            ));
+
+        let hanging = tokio::spawn(app.clone().oneshot(request("hang")));
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        let response = app.clone().oneshot(request("ok")).await.unwrap();
+        assert_eq!(response.status(), StatusCode::CONFLICT);
+
+        hanging.abort();
+        let _ = hanging.await;
+        let response = app.oneshot(request("ok")).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+}
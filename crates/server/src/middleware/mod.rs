@@ -1,3 +1,11 @@
+pub mod api_version;
+pub mod idempotency;
 pub mod model_loaders;
+pub mod rate_limit;
+pub mod request_id;
 
+pub use api_version::*;
+pub use idempotency::*;
 pub use model_loaders::*;
+pub use rate_limit::*;
+pub use request_id::*;
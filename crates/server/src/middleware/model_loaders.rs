@@ -5,10 +5,13 @@ use axum::{
     response::Response,
 };
 use db::models::{
-    execution_process::ExecutionProcess, project::Project, task::Task, task_attempt::TaskAttempt,
+    execution_process::ExecutionProcess, follow_up_snippet::FollowUpSnippet, project::Project,
+    project_group::ProjectGroup, task::Task, task_attempt::TaskAttempt,
     task_template::TaskTemplate,
 };
 use deployment::Deployment;
+use tracing::Instrument;
+use utils::otel::join_attempt_trace;
 use uuid::Uuid;
 
 use crate::DeploymentImpl;
@@ -86,11 +89,20 @@ pub async fn load_task_attempt_middleware(
         }
     };
 
+    // The request belongs to the attempt's trace
+    let span = tracing::info_span!(
+        "attempt_request",
+        attempt_id = %attempt.id,
+        method = %request.method(),
+        path = %request.uri().path()
+    );
+    join_attempt_trace(&span, attempt.id);
+
     // Insert the attempt into extensions
     request.extensions_mut().insert(attempt);
 
     // Continue on
-    Ok(next.run(request).await)
+    Ok(next.run(request).instrument(span).await)
 }
 
 pub async fn load_execution_process_middleware(
@@ -203,3 +215,47 @@ pub async fn load_task_template_middleware(
     // Continue with the next middleware/handler
     Ok(next.run(request).await)
 }
+
+pub async fn load_follow_up_snippet_middleware(
+    State(deployment): State<DeploymentImpl>,
+    Path(snippet_id): Path<Uuid>,
+    mut request: Request,
+    next: Next,
+) -> Result<Response, StatusCode> {
+    let snippet = match FollowUpSnippet::find_by_id(&deployment.db().pool, snippet_id).await {
+        Ok(Some(snippet)) => snippet,
+        Ok(None) => {
+            tracing::warn!("FollowUpSnippet {} not found", snippet_id);
+            return Err(StatusCode::NOT_FOUND);
+        }
+        Err(e) => {
+            tracing::error!("Failed to fetch follow-up snippet {}: {}", snippet_id, e);
+            return Err(StatusCode::INTERNAL_SERVER_ERROR);
+        }
+    };
+
+    request.extensions_mut().insert(snippet);
+    Ok(next.run(request).await)
+}
+
+pub async fn load_project_group_middleware(
+    State(deployment): State<DeploymentImpl>,
+    Path(group_id): Path<Uuid>,
+    mut request: Request,
+    next: Next,
+) -> Result<Response, StatusCode> {
+    let group = match ProjectGroup::find_by_id(&deployment.db().pool, group_id).await {
+        Ok(Some(group)) => group,
+        Ok(None) => {
+            tracing::warn!("ProjectGroup {} not found", group_id);
+            return Err(StatusCode::NOT_FOUND);
+        }
+        Err(e) => {
+            tracing::error!("Failed to fetch project group {}: {}", group_id, e);
+            return Err(StatusCode::INTERNAL_SERVER_ERROR);
+        }
+    };
+
+    request.extensions_mut().insert(group);
+    Ok(next.run(request).await)
+}
@@ -0,0 +1,251 @@
+use std::{
+    collections::{hash_map::DefaultHasher, BTreeMap, HashMap},
+    fmt,
+    hash::{Hash, Hasher},
+    net::{IpAddr, Ipv4Addr, SocketAddr},
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use axum::{
+    extract::{ConnectInfo, Request, State},
+    http::{header, HeaderValue, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Json, Response},
+};
+use tokio::sync::Mutex;
+use utils::{i18n::MessageCode, response::ApiResponse};
+
+/// Buckets kept, the least recently used one is dropped for a new client. A dropped bucket
+/// comes back full, as it would have after a minute without requests.
+const MAX_TRACKED_CLIENTS: usize = 10_000;
+
+/// What a bucket is kept for: the bearer token a request carries, else the peer's IP
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum ClientKey {
+    /// A hash of the token, so the token itself isn't kept
+    Token(u64),
+    Ip(IpAddr),
+}
+
+impl fmt::Display for ClientKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Token(hash) => write!(f, "token {hash:016x}"),
+            Self::Ip(ip) => write!(f, "{ip}"),
+        }
+    }
+}
+
+struct Bucket {
+    tokens: f64,
+    updated_at: Instant,
+    /// Position of the client's last request in `Buckets::recency`
+    last_used: u64,
+}
+
+#[derive(Default)]
+struct Buckets {
+    by_client: HashMap<ClientKey, Bucket>,
+    /// Clients by their last request, least recent first
+    recency: BTreeMap<u64, ClientKey>,
+    next_use: u64,
+}
+
+impl Buckets {
+    /// The client's bucket, marked as the most recently used
+    fn touch(&mut self, client: ClientKey, capacity: f64, now: Instant) -> &mut Bucket {
+        let used = self.next_use;
+        self.next_use += 1;
+        if let Some(bucket) = self.by_client.get(&client) {
+            self.recency.remove(&bucket.last_used);
+        } else if self.by_client.len() >= MAX_TRACKED_CLIENTS {
+            if let Some((_, evicted)) = self.recency.pop_first() {
+                self.by_client.remove(&evicted);
+            }
+        }
+        self.recency.insert(used, client);
+
+        let bucket = self.by_client.entry(client).or_insert(Bucket {
+            tokens: capacity,
+            updated_at: now,
+            last_used: used,
+        });
+        bucket.last_used = used;
+        bucket
+    }
+}
+
+/// Token bucket per API token, or per IP for requests without one: up to `per_minute` requests at once, refilled evenly over a
+/// minute
+#[derive(Clone)]
+pub struct RateLimiter {
+    per_minute: u32,
+    buckets: Arc<Mutex<Buckets>>,
+}
+
+impl RateLimiter {
+    pub fn new(per_minute: u32) -> Self {
+        Self {
+            per_minute: per_minute.max(1),
+            buckets: Arc::default(),
+        }
+    }
+
+    /// Take a token from the client's bucket, or tell how long until one is available
+    async fn acquire(&self, client: ClientKey, now: Instant) -> Result<(), Duration> {
+        let capacity = self.per_minute as f64;
+        let per_second = capacity / 60.0;
+        let mut buckets = self.buckets.lock().await;
+        let bucket = buckets.touch(client, capacity, now);
+        let elapsed = now.saturating_duration_since(bucket.updated_at);
+        bucket.tokens = (bucket.tokens + elapsed.as_secs_f64() * per_second).min(capacity);
+        bucket.updated_at = now;
+        if bucket.tokens >= 1.0 {
+            bucket.tokens -= 1.0;
+            Ok(())
+        } else {
+            Err(Duration::from_secs_f64((1.0 - bucket.tokens) / per_second))
+        }
+    }
+}
+
+/// The request's bearer token, so clients behind one proxy are told apart, else the peer's
+/// IP. Other headers are left out, a client could vary them to get a fresh bucket on every
+/// request.
+fn client_key(request: &Request) -> ClientKey {
+    let token = request
+        .headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.split_once(' '))
+        .filter(|(scheme, _)| scheme.eq_ignore_ascii_case("bearer"))
+        .map(|(_, token)| token.trim())
+        .filter(|token| !token.is_empty());
+    if let Some(token) = token {
+        let mut hasher = DefaultHasher::new();
+        token.hash(&mut hasher);
+        return ClientKey::Token(hasher.finish());
+    }
+    ClientKey::Ip(
+        request
+            .extensions()
+            .get::<ConnectInfo<SocketAddr>>()
+            .map(|ConnectInfo(addr)| addr.ip())
+            .unwrap_or(IpAddr::V4(Ipv4Addr::UNSPECIFIED)),
+    )
+}
+
+/// Rejects requests over the client's rate with 429 and a `Retry-After` in seconds
+pub async fn rate_limit_middleware(
+    State(limiter): State<RateLimiter>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let client = client_key(&request);
+    match limiter.acquire(client, Instant::now()).await {
+        Ok(()) => next.run(request).await,
+        Err(retry_after) => {
+            let seconds = retry_after.as_secs_f64().ceil().max(1.0) as u64;
+            tracing::debug!("Rate limited {}, retry after {}s", client, seconds);
+            let mut response = (
+                StatusCode::TOO_MANY_REQUESTS,
+                Json(ApiResponse::<()>::localized_error(MessageCode::RateLimited)),
+            )
+                .into_response();
+            response
+                .headers_mut()
+                .insert(header::RETRY_AFTER, HeaderValue::from(seconds));
+            response
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::{body::Body, http, middleware::from_fn_with_state, routing::get, Router};
+    use tower::ServiceExt;
+
+    use super::*;
+
+    fn ip(last: u8) -> ClientKey {
+        ClientKey::Ip(IpAddr::V4(Ipv4Addr::new(10, 0, 0, last)))
+    }
+
+    #[tokio::test]
+    async fn test_bucket_allows_a_burst_then_refills() {
+        let limiter = RateLimiter::new(60);
+        let start = Instant::now();
+        for _ in 0..60 {
+            assert!(limiter.acquire(ip(1), start).await.is_ok());
+        }
+        let retry_after = limiter.acquire(ip(1), start).await.unwrap_err();
+        assert_eq!(retry_after, Duration::from_secs(1));
+
+        // Other clients have their own bucket
+        assert!(limiter.acquire(ip(2), start).await.is_ok());
+
+        let later = start + Duration::from_secs(2);
+        assert!(limiter.acquire(ip(1), later).await.is_ok());
+        assert!(limiter.acquire(ip(1), later).await.is_ok());
+        assert!(limiter.acquire(ip(1), later).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_least_recently_used_bucket_is_dropped() {
+        let limiter = RateLimiter::new(1);
+        let now = Instant::now();
+        assert!(limiter.acquire(ip(1), now).await.is_ok());
+        for n in 1..MAX_TRACKED_CLIENTS as u32 {
+            let client = ClientKey::Ip(IpAddr::V4(Ipv4Addr::from(0x0B00_0000 + n)));
+            assert!(limiter.acquire(client, now).await.is_ok());
+        }
+        // The first client is still limited, the map is full but nobody was dropped yet
+        assert!(limiter.acquire(ip(1), now).await.is_err());
+
+        // A new client drops the least recently used bucket rather than the first client's
+        assert!(limiter.acquire(ip(2), now).await.is_ok());
+        assert!(limiter.acquire(ip(1), now).await.is_err());
+        let buckets = limiter.buckets.lock().await;
+        assert_eq!(buckets.by_client.len(), MAX_TRACKED_CLIENTS);
+        assert_eq!(buckets.recency.len(), MAX_TRACKED_CLIENTS);
+        assert!(!buckets
+            .by_client
+            .contains_key(&ClientKey::Ip(IpAddr::V4(Ipv4Addr::from(0x0B00_0001)))));
+    }
+
+    #[tokio::test]
+    async fn test_tokens_from_one_ip_have_their_own_buckets() {
+        let app = Router::new()
+            .route("/", get(|| async { "ok" }))
+            .layer(from_fn_with_state(
+                RateLimiter::new(1),
+                rate_limit_middleware,
+            ));
+        let call = |token: Option<&str>| {
+            let mut request = http::Request::get("/");
+            if let Some(token) = token {
+                request = request.header(header::AUTHORIZATION, format!("Bearer {token}"));
+            }
+            let mut request = request.body(Body::empty()).unwrap();
+            // Both clients reach the server through the same local proxy
+            request
+                .extensions_mut()
+                .insert(ConnectInfo(SocketAddr::from(([127, 0, 0, 1], 8080))));
+            app.clone().oneshot(request)
+        };
+
+        assert_eq!(call(Some("alice")).await.unwrap().status(), StatusCode::OK);
+        assert_eq!(
+            call(Some("alice")).await.unwrap().status(),
+            StatusCode::TOO_MANY_REQUESTS
+        );
+        assert_eq!(call(Some("bob")).await.unwrap().status(), StatusCode::OK);
+        // Requests without a token share the IP's bucket
+        assert_eq!(call(None).await.unwrap().status(), StatusCode::OK);
+        assert_eq!(
+            call(None).await.unwrap().status(),
+            StatusCode::TOO_MANY_REQUESTS
+        );
+    }
+}
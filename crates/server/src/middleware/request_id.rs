@@ -0,0 +1,63 @@
+use axum::{extract::Request, http::HeaderValue, middleware::Next, response::Response};
+use tracing::Instrument;
+use utils::response::{scope_request, REQUEST_ID_HEADER};
+use uuid::Uuid;
+
+/// Longest request id taken from a client, anything longer gets a new one
+const MAX_REQUEST_ID_LEN: usize = 128;
+
+/// The client's `x-request-id` when it is a sane one, a new UUID otherwise
+fn request_id(request: &Request) -> String {
+    request
+        .headers()
+        .get(REQUEST_ID_HEADER)
+        .and_then(|v| v.to_str().ok())
+        .map(str::trim)
+        .filter(|id| {
+            !id.is_empty()
+                && id.len() <= MAX_REQUEST_ID_LEN
+                && id.chars().all(|c| c.is_ascii_graphic())
+        })
+        .map(str::to_string)
+        .unwrap_or_else(|| Uuid::new_v4().to_string())
+}
+
+/// Gives every request an id, sent back in the `x-request-id` header and the `ApiResponse`
+/// envelope, and attached to the request's log lines. Warnings raised while the request runs
+/// end up in its response.
+pub async fn request_id_middleware(request: Request, next: Next) -> Response {
+    let id = request_id(&request);
+    let span = tracing::info_span!("request", id = %id);
+    let mut response = scope_request(id.clone(), next.run(request))
+        .instrument(span)
+        .await;
+    if let Ok(value) = HeaderValue::from_str(&id) {
+        response.headers_mut().insert(REQUEST_ID_HEADER, value);
+    }
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::{body::Body, http};
+
+    use super::*;
+
+    fn request(id: Option<&str>) -> Request {
+        let mut request = http::Request::get("/");
+        if let Some(id) = id {
+            request = request.header(REQUEST_ID_HEADER, id);
+        }
+        request.body(Body::empty()).unwrap()
+    }
+
+    #[test]
+    fn test_request_id_is_taken_from_the_client_when_sane() {
+        assert_eq!(request_id(&request(Some("trace-42"))), "trace-42");
+        let long = "x".repeat(200);
+        for id in [None, Some(""), Some("has space"), Some(long.as_str())] {
+            let generated = request_id(&request(id));
+            assert!(Uuid::parse_str(&generated).is_ok(), "{generated}");
+        }
+    }
+}
@@ -0,0 +1,73 @@
+use axum::{
+    extract::{Query, State},
+    response::Json as ResponseJson,
+    routing::get,
+    Router,
+};
+use chrono::Utc;
+use deployment::Deployment;
+use serde::Deserialize;
+use services::services::{
+    analytics::{AnalyticsEvent, AnalyticsEventCount},
+    usage::{UsageGroupBy, UsageReport, DEFAULT_USAGE_DAYS},
+};
+use utils::response::ApiResponse;
+
+use crate::{error::ApiError, DeploymentImpl};
+
+const DEFAULT_EVENT_LIMIT: usize = 100;
+
+#[derive(Debug, Deserialize)]
+pub struct AnalyticsEventsQuery {
+    pub name: Option<String>,
+    pub limit: Option<usize>,
+}
+
+/// Usage events recorded since the server started, newest first. They are kept locally
+/// whether or not telemetry is enabled.
+pub async fn get_analytics_events(
+    State(deployment): State<DeploymentImpl>,
+    Query(query): Query<AnalyticsEventsQuery>,
+) -> ResponseJson<ApiResponse<Vec<AnalyticsEvent>>> {
+    let events = deployment.analytics().recent_events(
+        query.name.as_deref(),
+        query.limit.unwrap_or(DEFAULT_EVENT_LIMIT),
+    );
+    ResponseJson(ApiResponse::success(events))
+}
+
+pub async fn get_analytics_summary(
+    State(deployment): State<DeploymentImpl>,
+) -> ResponseJson<ApiResponse<Vec<AnalyticsEventCount>>> {
+    ResponseJson(ApiResponse::success(deployment.analytics().event_counts()))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UsageQuery {
+    #[serde(default)]
+    pub group_by: UsageGroupBy,
+    pub days: Option<i64>,
+}
+
+/// Attempts, success rates, tokens and cost of the last days, grouped by day, profile or
+/// project
+pub async fn get_usage(
+    State(deployment): State<DeploymentImpl>,
+    Query(query): Query<UsageQuery>,
+) -> Result<ResponseJson<ApiResponse<UsageReport>>, ApiError> {
+    let report = UsageReport::load(
+        &deployment.db().pool,
+        query.group_by,
+        query.days.unwrap_or(DEFAULT_USAGE_DAYS),
+        Utc::now(),
+    )
+    .await?;
+    Ok(ResponseJson(ApiResponse::success(report)))
+}
+
+pub fn router() -> Router<DeploymentImpl> {
+    Router::new()
+        .route("/analytics/events", get(get_analytics_events))
+        .route("/analytics/summary", get(get_analytics_summary))
+        .route("/analytics/usage", get(get_usage))
+}
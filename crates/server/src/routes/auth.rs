@@ -11,7 +11,7 @@ use octocrab::auth::Continue;
 use serde::{Deserialize, Serialize};
 use services::services::{
     auth::{AuthError, DeviceFlowStartResponse},
-    config::save_config_to_file,
+    github_auth,
     github_service::{GitHubService, GitHubServiceError},
 };
 use utils::response::ApiResponse;
@@ -74,22 +74,19 @@ async fn device_poll(
     };
     // Save to config
     {
-        let config_path = utils::assets::config_path();
         let mut config = deployment.config().write().await;
         config.github.username = Some(user_info.username.clone());
         config.github.primary_email = user_info.primary_email.clone();
         config.github.oauth_token = Some(user_info.token.to_string());
         config.github_login_acknowledged = true; // Also acknowledge the GitHub login step
-        save_config_to_file(&config.clone(), &config_path).await?;
+        deployment.save_config(&config).await?;
     }
     let _ = deployment.update_sentry_scope().await;
     let props = serde_json::json!({
         "username": user_info.username,
         "email": user_info.primary_email,
     });
-    deployment
-        .track_if_analytics_allowed("$identify", props)
-        .await;
+    deployment.track_event("$identify", props).await;
     Ok(ResponseJson(ApiResponse::success(
         DevicePollStatus::Success,
     )))
@@ -100,7 +97,7 @@ async fn github_check_token(
     State(deployment): State<DeploymentImpl>,
 ) -> Result<ResponseJson<ApiResponse<CheckTokenResponse>>, ApiError> {
     let gh_config = deployment.config().read().await.github.clone();
-    let Some(token) = gh_config.token() else {
+    let Some(token) = github_auth::access_token(&gh_config).await? else {
         return Ok(ResponseJson(ApiResponse::success(
             CheckTokenResponse::Invalid,
         )));
@@ -1,32 +1,73 @@
 use std::collections::HashMap;
 
 use axum::{
-    body::Body,
-    extract::{Path, Query, State},
-    http,
-    response::{Json as ResponseJson, Response},
-    routing::{get, put},
-    Json, Router,
+    body::{Body, Bytes},
+    extract::{DefaultBodyLimit, Path, Query, State},
+    http::{self, HeaderMap},
+    response::{
+        sse::{Event, KeepAlive},
+        Json as ResponseJson, Response, Sse,
+    },
+    routing::{get, post, put},
+    BoxError, Json, Router,
 };
-use deployment::{Deployment, DeploymentError};
+use deployment::Deployment;
 use executors::{
     mcp_config::{read_agent_config, write_agent_config, McpConfig},
     profile::ProfileConfigs,
 };
+use futures_util::{stream, Stream, StreamExt};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
-use services::services::config::{save_config_to_file, Config, ConfigError, SoundFile};
-use tokio::fs;
+use services::services::{
+    config::{
+        apply_override,
+        history::{self, ConfigVersion},
+        keep_credentials, load_stored, set_user_override,
+        store::{self, SettingsFile},
+        user_override, validate_config, with_user_override, Config, ConfigError, FeatureFlags,
+    },
+    config_watcher::ConfigChange,
+    feature_flags,
+    self_update::{self, UpdateStatus},
+    sounds::{CustomSound, ImportSoundRequest, Sound, SoundLibrary, MAX_SOUND_BYTES},
+};
+use tokio::{fs, sync::broadcast::error::RecvError};
 use ts_rs::TS;
-use utils::{assets::config_path, response::ApiResponse};
+use utils::{
+    i18n::{set_locale, Message, MessageCode},
+    response::{self, ApiResponse},
+};
 
-use crate::{error::ApiError, DeploymentImpl};
+use crate::{
+    error::ApiError,
+    etag::{check_if_match, with_etag, WithETag},
+    DeploymentImpl,
+};
 
 pub fn router() -> Router<DeploymentImpl> {
     Router::new()
         .route("/info", get(get_user_system_info))
         .route("/config", put(update_config))
-        .route("/sounds/{sound}", get(get_sound))
+        .route("/config/history", get(get_config_history))
+        .route(
+            "/config/override",
+            get(get_config_override)
+                .put(update_config_override)
+                .delete(delete_config_override),
+        )
+        .route("/config/rollback/{n}", post(rollback_config))
+        .route("/config/schema", get(get_config_schema))
+        .route("/config/stream", get(stream_config))
+        .route("/sounds", get(list_sounds))
+        .route("/sounds/import", post(import_sound))
+        .route(
+            "/sounds/{sound}",
+            get(get_sound)
+                .put(upload_sound)
+                .delete(delete_sound)
+                .layer(DefaultBodyLimit::max(MAX_SOUND_BYTES)),
+        )
         .route("/mcp-config", get(get_mcp_servers).post(update_mcp_servers))
         .route("/profiles", get(get_profiles).put(update_profiles))
 }
@@ -63,55 +104,276 @@ pub struct UserSystemInfo {
     #[serde(flatten)]
     pub profiles: ProfileConfigs,
     pub environment: Environment,
+    /// Whether a newer version is out, refreshed in the background
+    pub update: UpdateStatus,
+    /// Experimental subsystems in use, environment overrides applied
+    pub feature_flags: FeatureFlags,
+}
+
+impl UserSystemInfo {
+    fn new(config: &Config) -> Self {
+        Self {
+            config: config.clone(),
+            profiles: ProfileConfigs::get_cached(),
+            environment: Environment::new(),
+            update: self_update::status(),
+            feature_flags: feature_flags::effective(&config.feature_flags),
+        }
+    }
 }
 
 // TODO: update frontend, BE schema has changed, this replaces GET /config and /config/constants
 #[axum::debug_handler]
 async fn get_user_system_info(
     State(deployment): State<DeploymentImpl>,
-) -> ResponseJson<ApiResponse<UserSystemInfo>> {
+) -> WithETag<ResponseJson<ApiResponse<UserSystemInfo>>> {
     let config = deployment.config().read().await;
 
-    let user_system_info = UserSystemInfo {
-        config: config.clone(),
-        profiles: ProfileConfigs::get_cached(),
-        environment: Environment::new(),
-    };
+    // The ETag covers the config only, which is what `PUT /config` replaces
+    with_etag(
+        &*config,
+        ResponseJson(ApiResponse::success(UserSystemInfo::new(&config))),
+    )
+}
+
+/// The current `UserSystemInfo` as a `config` event, then again whenever the config or
+/// profiles change, through the API or by editing the files
+async fn stream_config(
+    State(deployment): State<DeploymentImpl>,
+) -> Sse<impl Stream<Item = Result<Event, BoxError>>> {
+    let changes = stream::unfold(
+        deployment.config_watcher().subscribe(),
+        |mut changes| async move {
+            match changes.recv().await {
+                // A lagging subscriber only needs the latest state
+                Ok(_) | Err(RecvError::Lagged(_)) => Some(((), changes)),
+                Err(RecvError::Closed) => None,
+            }
+        },
+    );
+    let state = deployment.clone();
+    let events = stream::once(async {})
+        .chain(changes)
+        .then(move |_| {
+            let deployment = state.clone();
+            async move {
+                let info = UserSystemInfo::new(&*deployment.config().read().await);
+                Event::default()
+                    .event("config")
+                    .json_data(info)
+                    .map_err(|e| -> BoxError { e.into() })
+            }
+        })
+        .boxed();
+
+    Sse::new(deployment.shutdown().guard_stream(events)).keep_alive(KeepAlive::default())
+}
+
+/// JSON Schema of the config accepted by `PUT /config`
+async fn get_config_schema() -> ResponseJson<ApiResponse<Value>> {
+    let schema = schemars::schema_for!(Config);
+    ResponseJson(ApiResponse::success(
+        serde_json::to_value(schema).unwrap_or_default(),
+    ))
+}
+
+/// Check that `new_config` can take the place of `config`
+fn check_config(config: &Config, new_config: &mut Config) -> Result<(), ApiError> {
+    // Pausing also suspends running agents, so it isn't changed through the config
+    new_config.agents_paused = config.agents_paused;
 
-    ResponseJson(ApiResponse::success(user_system_info))
+    let errors = validate_config(new_config, &ProfileConfigs::get_cached());
+    if !errors.is_empty() {
+        return Err(ConfigError::InvalidFields(errors).into());
+    }
+    Ok(())
+}
+
+/// Validate and save `new_config` in place of `config`, the stored config it replaces is kept
+/// in the history
+async fn replace_config(
+    deployment: &DeploymentImpl,
+    config: &mut Config,
+    mut new_config: Config,
+) -> Result<(), ApiError> {
+    check_config(config, &mut new_config)?;
+    deployment.save_config(&new_config).await?;
+    set_locale(new_config.language);
+    *config = new_config;
+    Ok(())
 }
 
 async fn update_config(
     State(deployment): State<DeploymentImpl>,
+    headers: HeaderMap,
     Json(new_config): Json<Config>,
-) -> ResponseJson<ApiResponse<Config>> {
-    let config_path = config_path();
+) -> Result<WithETag<ResponseJson<ApiResponse<Config>>>, ApiError> {
+    // Hold the write lock across the check and save so concurrent updates serialize
+    let mut config = deployment.config().write().await;
+    check_if_match(&headers, &*config)?;
+    replace_config(&deployment, &mut config, new_config).await?;
+    let new_config = config.clone();
+    drop(config);
+
+    Ok(with_etag(
+        &new_config,
+        ResponseJson(ApiResponse::success(new_config.clone())),
+    ))
+}
 
-    match save_config_to_file(&new_config, &config_path).await {
-        Ok(_) => {
-            let mut config = deployment.config().write().await;
-            *config = new_config.clone();
-            drop(config);
+/// Previous configs, newest first, with the fields each differs in from the current one
+async fn get_config_history(
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Vec<ConfigVersion>>>, ApiError> {
+    let versions = history::list(&deployment.db().pool).await?;
+    Ok(ResponseJson(ApiResponse::success(versions)))
+}
 
-            ResponseJson(ApiResponse::success(new_config))
-        }
-        Err(e) => ResponseJson(ApiResponse::error(&format!("Failed to save config: {}", e))),
-    }
+/// POST /config/rollback/{n}: restore the `n`th previous config, 1 being the one before the
+/// last save. Credentials and the user's override stay as they are, and the config replaced
+/// goes into the history so the rollback can be undone in turn.
+async fn rollback_config(
+    State(deployment): State<DeploymentImpl>,
+    Path(n): Path<usize>,
+) -> Result<WithETag<ResponseJson<ApiResponse<Config>>>, ApiError> {
+    let pool = &deployment.db().pool;
+    let mut config = deployment.config().write().await;
+    let restored = history::load(pool, n).await?;
+    let mut restored = with_user_override(pool, deployment.user_id(), restored).await;
+    keep_credentials(&config, &mut restored);
+    replace_config(&deployment, &mut config, restored).await?;
+    let restored = config.clone();
+    drop(config);
+    tracing::info!("Config rolled back to version {}", n);
+
+    Ok(with_etag(
+        &restored,
+        ResponseJson(ApiResponse::success(restored.clone())),
+    ))
+}
+
+/// The user's changes on top of the stored config as a JSON merge patch, `null` without any
+async fn get_config_override(
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Option<Value>>>, ApiError> {
+    let patch = user_override(&deployment.db().pool, deployment.user_id()).await?;
+    Ok(ResponseJson(ApiResponse::success(patch)))
+}
+
+/// Run with `new_config` after the user's override changed
+fn apply_config_override(
+    deployment: &DeploymentImpl,
+    config: &mut Config,
+    new_config: Config,
+) -> WithETag<ResponseJson<ApiResponse<Config>>> {
+    set_locale(new_config.language);
+    *config = new_config.clone();
+    // The files don't change, so the watcher won't announce it
+    deployment.config_watcher().announce(ConfigChange::Config);
+    with_etag(
+        &new_config,
+        ResponseJson(ApiResponse::success(new_config.clone())),
+    )
 }
 
-async fn get_sound(Path(sound): Path<SoundFile>) -> Result<Response, ApiError> {
-    let sound = sound.serve().await.map_err(DeploymentError::Other)?;
+/// PUT /config/override: replace the user's override with a JSON merge patch, e.g.
+/// `{"workspace_dir": "~/work"}`. The stored config stays as it is, and saves of the config
+/// leave the overridden fields out of it.
+async fn update_config_override(
+    State(deployment): State<DeploymentImpl>,
+    Json(patch): Json<Value>,
+) -> Result<WithETag<ResponseJson<ApiResponse<Config>>>, ApiError> {
+    let pool = &deployment.db().pool;
+    let mut config = deployment.config().write().await;
+    let mut new_config = apply_override(&load_stored(pool).await?, &patch)?;
+    check_config(&config, &mut new_config)?;
+    set_user_override(pool, deployment.user_id(), Some(&patch)).await?;
+    tracing::info!("Config override updated");
+    Ok(apply_config_override(&deployment, &mut config, new_config))
+}
+
+/// DELETE /config/override: go back to the stored config
+async fn delete_config_override(
+    State(deployment): State<DeploymentImpl>,
+) -> Result<WithETag<ResponseJson<ApiResponse<Config>>>, ApiError> {
+    let pool = &deployment.db().pool;
+    let mut config = deployment.config().write().await;
+    let mut new_config = load_stored(pool).await?;
+    new_config.agents_paused = config.agents_paused;
+    set_user_override(pool, deployment.user_id(), None).await?;
+    tracing::info!("Config override removed");
+    Ok(apply_config_override(&deployment, &mut config, new_config))
+}
+
+/// A built-in sound by its `SoundFile` name, or a custom sound by file name
+async fn get_sound(Path(sound): Path<String>) -> Result<Response, ApiError> {
+    let sound = SoundLibrary::default().load(&Sound::parse(&sound)?).await?;
     let response = Response::builder()
         .status(http::StatusCode::OK)
         .header(
             http::header::CONTENT_TYPE,
-            http::HeaderValue::from_static("audio/wav"),
+            http::HeaderValue::from_static(sound.content_type),
         )
-        .body(Body::from(sound.data.into_owned()))
+        .body(Body::from(sound.data))
         .unwrap();
     Ok(response)
 }
 
+async fn list_sounds() -> Result<ResponseJson<ApiResponse<Vec<CustomSound>>>, ApiError> {
+    let sounds = SoundLibrary::default().list().await?;
+    Ok(ResponseJson(ApiResponse::success(sounds)))
+}
+
+/// Upload a custom sound as the raw request body, named after the path segment
+async fn upload_sound(
+    Path(file_name): Path<String>,
+    body: Bytes,
+) -> Result<ResponseJson<ApiResponse<CustomSound>>, ApiError> {
+    let sound = SoundLibrary::default().save(&file_name, &body).await?;
+    Ok(ResponseJson(ApiResponse::success(sound)))
+}
+
+/// Copy an audio file from the server's filesystem into the data directory
+async fn import_sound(
+    Json(request): Json<ImportSoundRequest>,
+) -> Result<ResponseJson<ApiResponse<CustomSound>>, ApiError> {
+    let path = utils::path::expand_tilde(request.path.trim());
+    if !path.is_file() {
+        return Err(ApiError::BadRequest(format!(
+            "File not found: {}",
+            path.display()
+        )));
+    }
+    let sound = SoundLibrary::default().import(&path).await?;
+    Ok(ResponseJson(ApiResponse::success(sound)))
+}
+
+/// Delete a custom sound, events that used it go back to the default sound
+async fn delete_sound(
+    State(deployment): State<DeploymentImpl>,
+    Path(name): Path<String>,
+) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
+    SoundLibrary::default().delete(&name).await?;
+
+    let mut config = deployment.config().write().await;
+    let event_sounds = &mut config.notifications.event_sounds;
+    let mut changed = false;
+    for id in [
+        &mut event_sounds.success,
+        &mut event_sounds.failure,
+        &mut event_sounds.needs_attention,
+    ] {
+        if id.as_deref() == Some(name.as_str()) {
+            *id = None;
+            changed = true;
+        }
+    }
+    if changed {
+        deployment.save_config(&config).await?;
+    }
+    Ok(ResponseJson(ApiResponse::success(())))
+}
+
 #[derive(TS, Debug, Deserialize)]
 pub struct McpServerQuery {
     profile: String,
@@ -134,28 +396,18 @@ async fn get_mcp_servers(
     Query(query): Query<McpServerQuery>,
 ) -> Result<ResponseJson<ApiResponse<GetMcpServerResponse>>, ApiError> {
     let profiles = ProfileConfigs::get_cached();
-    let profile = profiles.get_profile(&query.profile).ok_or_else(|| {
-        ApiError::Config(ConfigError::ValidationError(format!(
-            "Profile not found: {}",
-            query.profile
-        )))
-    })?;
+    let profile = profiles
+        .get_profile(&query.profile)
+        .ok_or_else(|| ConfigError::ProfileNotFound(query.profile.clone()))?;
 
     if !profile.default.agent.supports_mcp() {
-        return Ok(ResponseJson(ApiResponse::error(
-            "This executor does not support MCP servers",
-        )));
+        return Err(ConfigError::McpUnsupported.into());
     }
 
     // Resolve supplied config path or agent default
-    let config_path = match profile.get_mcp_config_path() {
-        Some(path) => path,
-        None => {
-            return Ok(ResponseJson(ApiResponse::error(
-                "Could not determine config file path",
-            )));
-        }
-    };
+    let config_path = profile
+        .get_mcp_config_path()
+        .ok_or(ConfigError::McpConfigPathUnknown)?;
 
     let mut mcpc = profile.default.agent.get_mcp_config();
     let raw_config = read_agent_config(&config_path, &mcpc).await?;
@@ -175,39 +427,24 @@ async fn update_mcp_servers(
     let profiles = ProfileConfigs::get_cached();
     let agent = &profiles
         .get_profile(&query.profile)
-        .ok_or_else(|| {
-            ApiError::Config(ConfigError::ValidationError(format!(
-                "Profile not found: {}",
-                query.profile
-            )))
-        })?
+        .ok_or_else(|| ConfigError::ProfileNotFound(query.profile.clone()))?
         .default
         .agent;
 
     if !agent.supports_mcp() {
-        return Ok(ResponseJson(ApiResponse::error(
-            "This executor does not support MCP servers",
-        )));
+        return Err(ConfigError::McpUnsupported.into());
     }
 
     // Resolve supplied config path or agent default
-    let config_path = match agent.default_mcp_config_path() {
-        Some(path) => path,
-        None => {
-            return Ok(ResponseJson(ApiResponse::error(
-                "Could not determine config file path",
-            )))
-        }
-    };
+    let config_path = agent
+        .default_mcp_config_path()
+        .ok_or(ConfigError::McpConfigPathUnknown)?;
 
     let mcpc = agent.get_mcp_config();
-    match update_mcp_servers_in_config(&config_path, &mcpc, payload.servers).await {
-        Ok(message) => Ok(ResponseJson(ApiResponse::success(message))),
-        Err(e) => Ok(ResponseJson(ApiResponse::error(&format!(
-            "Failed to update MCP servers: {}",
-            e
-        )))),
-    }
+    let message = update_mcp_servers_in_config(&config_path, &mcpc, payload.servers)
+        .await
+        .map_err(|e| ConfigError::McpUpdateFailed(e.to_string()))?;
+    Ok(ResponseJson(ApiResponse::success(message)))
 }
 
 async fn update_mcp_servers_in_config(
@@ -246,7 +483,10 @@ async fn update_mcp_servers_in_config(
 }
 
 /// Helper function to get MCP servers from config using a path
-fn get_mcp_servers_from_config_path(raw_config: &Value, path: &[String]) -> HashMap<String, Value> {
+pub(crate) fn get_mcp_servers_from_config_path(
+    raw_config: &Value,
+    path: &[String],
+) -> HashMap<String, Value> {
     let mut current = raw_config;
     for part in path {
         current = match current.get(part) {
@@ -306,15 +546,24 @@ pub struct ProfilesContent {
     pub path: String,
 }
 
+fn warn_ignored_profile_fields(ignored: &[String]) {
+    if !ignored.is_empty() {
+        response::warn(
+            Message::new(MessageCode::ProfilesUnknownFields).with("fields", ignored.join(", ")),
+        );
+    }
+}
+
 async fn get_profiles(
-    State(_deployment): State<DeploymentImpl>,
-) -> ResponseJson<ApiResponse<ProfilesContent>> {
-    let profiles_path = utils::assets::profiles_path();
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<ProfilesContent>>, ApiError> {
+    let profiles_path = SettingsFile::Profiles.path();
 
     let mut profiles = ProfileConfigs::from_defaults();
-    if let Ok(user_content) = std::fs::read_to_string(&profiles_path) {
-        match serde_json::from_str::<ProfileConfigs>(&user_content) {
-            Ok(user_profiles) => {
+    if let Some(user_content) = store::load(&deployment.db().pool, SettingsFile::Profiles).await? {
+        match ProfileConfigs::parse_with_ignored(&user_content) {
+            Ok((user_profiles, ignored)) => {
+                warn_ignored_profile_fields(&ignored);
                 // Override defaults with user profiles that have the same label
                 for user_profile in user_profiles.profiles {
                     if let Some(default_profile) = profiles
@@ -329,7 +578,8 @@ async fn get_profiles(
                 }
             }
             Err(e) => {
-                tracing::error!("Failed to parse profiles.json: {}", e);
+                tracing::error!("Failed to parse the stored profiles: {}", e);
+                response::warn(Message::detail(MessageCode::ProfilesUnreadable, e));
             }
         }
     }
@@ -340,42 +590,28 @@ async fn get_profiles(
             .unwrap_or_else(|_| "{}".to_string())
     });
 
-    ResponseJson(ApiResponse::success(ProfilesContent {
+    Ok(ResponseJson(ApiResponse::success(ProfilesContent {
         content,
         path: profiles_path.display().to_string(),
-    }))
+    })))
 }
 
 async fn update_profiles(
-    State(_deployment): State<DeploymentImpl>,
+    State(deployment): State<DeploymentImpl>,
     body: String,
-) -> ResponseJson<ApiResponse<String>> {
-    let profiles: ProfileConfigs = match serde_json::from_str(&body) {
-        Ok(p) => p,
-        Err(e) => {
-            return ResponseJson(ApiResponse::error(&format!(
-                "Invalid profiles format: {}",
-                e
-            )))
-        }
-    };
-
-    let profiles_path = utils::assets::profiles_path();
+) -> Result<ResponseJson<ApiResponse<String>>, ApiError> {
+    let (profiles, ignored) =
+        ProfileConfigs::parse_with_ignored(&body).map_err(ConfigError::InvalidProfiles)?;
+    // Saved without them, as they would be ignored on every load anyway
+    warn_ignored_profile_fields(&ignored);
 
     // Simply save all profiles as provided by the user
     let formatted = serde_json::to_string_pretty(&profiles).unwrap();
-    match fs::write(&profiles_path, formatted).await {
-        Ok(_) => {
-            tracing::info!("All profiles saved to {:?}", profiles_path);
-            // Reload the cached profiles
-            ProfileConfigs::reload();
-            ResponseJson(ApiResponse::success(
-                "Profiles updated successfully".to_string(),
-            ))
-        }
-        Err(e) => ResponseJson(ApiResponse::error(&format!(
-            "Failed to save profiles: {}",
-            e
-        ))),
-    }
+    store::save(&deployment.db().pool, SettingsFile::Profiles, &formatted).await?;
+    tracing::info!("All profiles saved");
+    // Reload the cached profiles
+    ProfileConfigs::reload();
+    Ok(ResponseJson(ApiResponse::success(
+        "Profiles updated successfully".to_string(),
+    )))
 }
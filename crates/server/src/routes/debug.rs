@@ -0,0 +1,139 @@
+use axum::{
+    body::Body,
+    extract::{Query, State},
+    http::{header, StatusCode},
+    response::{Json as ResponseJson, Response},
+    routing::{get, post},
+    Json, Router,
+};
+use chrono::Utc;
+use db::models::{execution_process::ExecutionProcess, task_attempt::TaskAttempt};
+use deployment::Deployment;
+use executors::profile::ProfileConfigs;
+use serde::{Deserialize, Serialize};
+use services::services::{
+    config,
+    container::ContainerService,
+    filesystem::FilesystemError,
+    log_export::{self, EntryFilter},
+    support_bundle::{CreateSupportBundle, SupportBundle},
+};
+use sqlx::Error as SqlxError;
+use ts_rs::TS;
+use utils::{response::ApiResponse, server_log};
+
+use crate::{error::ApiError, routes::config::Environment, DeploymentImpl};
+
+const DEFAULT_TAIL: usize = 200;
+const DEFAULT_BUNDLE_TAIL: usize = 1000;
+
+#[derive(Debug, Deserialize)]
+pub struct ServerLogQuery {
+    /// Number of most recent entries, capped at a few thousand
+    pub tail: Option<usize>,
+}
+
+/// The end of the server's JSON lines log, for attaching to bug reports
+#[derive(Debug, Serialize, TS)]
+pub struct ServerLogs {
+    /// Where the log file lives, rotated copies sit next to it
+    pub path: String,
+    /// Log events oldest first, as written by the tracing JSON formatter
+    pub entries: Vec<serde_json::Value>,
+}
+
+pub async fn get_server_logs(
+    Query(query): Query<ServerLogQuery>,
+) -> Result<ResponseJson<ApiResponse<ServerLogs>>, ApiError> {
+    let entries = read_server_log(query.tail.unwrap_or(DEFAULT_TAIL)).await?;
+    Ok(ResponseJson(ApiResponse::success(ServerLogs {
+        path: server_log::server_log_path().to_string_lossy().to_string(),
+        entries,
+    })))
+}
+
+async fn read_server_log(tail: usize) -> Result<Vec<serde_json::Value>, ApiError> {
+    let path = server_log::server_log_path();
+    let entries = tokio::task::spawn_blocking(move || server_log::tail(&path, tail))
+        .await
+        .map_err(|e| FilesystemError::Io(std::io::Error::other(e)))?
+        .map_err(FilesystemError::Io)?;
+    Ok(entries)
+}
+
+/// POST /debug/support-bundle: a zip with the config and profiles, the environment, recent
+/// server logs and optionally an attempt's normalized agent logs, secrets redacted, for
+/// reporting executor bugs upstream
+pub async fn create_support_bundle(
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<CreateSupportBundle>,
+) -> Result<Response, ApiError> {
+    let mut bundle = SupportBundle::new();
+    let config = config::redacted(&*deployment.config().read().await);
+    bundle.add_json("config.json", &config)?;
+    bundle.add_json("profiles.json", &ProfileConfigs::get_cached())?;
+    bundle.add_json(
+        "environment.json",
+        &serde_json::json!({
+            "version": env!("CARGO_PKG_VERSION"),
+            "os": Environment::new(),
+            "generated_at": Utc::now(),
+        }),
+    )?;
+    let server_log =
+        read_server_log(payload.server_log_tail.unwrap_or(DEFAULT_BUNDLE_TAIL)).await?;
+    bundle.add_jsonl("server-log.jsonl", &server_log)?;
+
+    if let Some(attempt_id) = payload.attempt_id {
+        let pool = &deployment.db().pool;
+        let attempt = TaskAttempt::find_by_id(pool, attempt_id)
+            .await?
+            .ok_or(SqlxError::RowNotFound)?;
+        let processes = ExecutionProcess::find_by_task_attempt_id(pool, attempt.id).await?;
+        let mut entries = Vec::new();
+        for process in &processes {
+            let normalized = deployment.container().normalized_entries(process).await?;
+            entries.extend(log_export::entries_jsonl(
+                process,
+                &normalized,
+                &EntryFilter::default(),
+            ));
+        }
+        bundle.add_json(
+            "attempt/attempt.json",
+            &serde_json::json!({ "attempt": attempt, "execution_processes": processes }),
+        )?;
+        bundle.add_text(
+            "attempt/normalized-logs.jsonl",
+            &String::from_utf8_lossy(&entries),
+        )?;
+    }
+
+    let files = bundle.files().len();
+    let bytes = bundle.finish()?;
+    deployment
+        .track_event(
+            "support_bundle_created",
+            serde_json::json!({ "files": files, "with_attempt": payload.attempt_id.is_some() }),
+        )
+        .await;
+    let response = Response::builder()
+        .status(StatusCode::OK)
+        .header(header::CONTENT_TYPE, "application/zip")
+        .header(
+            header::CONTENT_DISPOSITION,
+            format!(
+                "attachment; filename=\"vibe-kanban-support-{}.zip\"",
+                Utc::now().format("%Y%m%d-%H%M%S")
+            ),
+        )
+        .body(Body::from(bytes))
+        .unwrap();
+    Ok(response)
+}
+
+pub fn router() -> Router<DeploymentImpl> {
+    Router::new()
+        .route("/debug/server-logs", get(get_server_logs))
+        .route("/debug/support-bundle", post(create_support_bundle))
+}
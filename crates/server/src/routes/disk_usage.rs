@@ -0,0 +1,65 @@
+use axum::{extract::State, response::Json as ResponseJson, Extension, Json};
+use db::models::{
+    project::Project,
+    project_disk_quota::{ProjectDiskQuota, UpdateProjectDiskQuota},
+};
+use deployment::Deployment;
+use services::services::{container::ContainerService, disk_quota::ProjectDiskUsage};
+use utils::response::ApiResponse;
+
+use crate::{error::ApiError, DeploymentImpl};
+
+/// Refuse new work in a project whose worktrees exceed its disk quota, see
+/// `ContainerService::ensure_within_disk_quota`. Attempts check it when they start.
+pub async fn ensure_within_disk_quota(
+    deployment: &DeploymentImpl,
+    project: &Project,
+) -> Result<(), ApiError> {
+    Ok(deployment
+        .container()
+        .ensure_within_disk_quota(project)
+        .await?)
+}
+
+pub async fn get_project_disk_usage(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<ProjectDiskUsage>>, ApiError> {
+    let usage = ProjectDiskUsage::measure(&deployment.db().pool, project.id).await?;
+    Ok(ResponseJson(ApiResponse::success(usage)))
+}
+
+pub async fn update_project_disk_quota(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<UpdateProjectDiskQuota>,
+) -> Result<ResponseJson<ApiResponse<Option<ProjectDiskQuota>>>, ApiError> {
+    let pool = &deployment.db().pool;
+    let quota = match payload.max_bytes {
+        Some(max_bytes) if max_bytes <= 0 => {
+            return Err(ApiError::BadRequest(
+                "The quota must be a positive number of bytes".to_string(),
+            ));
+        }
+        Some(max_bytes) => {
+            Some(ProjectDiskQuota::upsert(pool, project.id, max_bytes, payload.auto_gc).await?)
+        }
+        None => {
+            ProjectDiskQuota::delete(pool, project.id).await?;
+            None
+        }
+    };
+    Ok(ResponseJson(ApiResponse::success(quota)))
+}
+
+/// Removes the worktrees of merged attempts now, whatever the quota
+pub async fn collect_project_worktrees(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<ProjectDiskUsage>>, ApiError> {
+    let pool = &deployment.db().pool;
+    let usage = ProjectDiskUsage::measure(pool, project.id).await?;
+    collect_merged_worktrees(&deployment, &usage).await?;
+    let usage = ProjectDiskUsage::measure(pool, project.id).await?;
+    Ok(ResponseJson(ApiResponse::success(usage)))
+}
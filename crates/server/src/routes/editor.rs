@@ -0,0 +1,103 @@
+use std::path::{Component, Path, PathBuf};
+
+use axum::{extract::State, response::Json as ResponseJson, routing::post, Json, Router};
+use db::models::task_attempt::{TaskAttempt, TaskAttemptError};
+use deployment::Deployment;
+use serde::{Deserialize, Serialize};
+use services::services::config::EditorConfig;
+use ts_rs::TS;
+use utils::response::ApiResponse;
+use uuid::Uuid;
+
+use crate::{error::ApiError, DeploymentImpl};
+
+#[derive(Debug, Deserialize, TS)]
+pub struct EditorOpenRequest {
+    pub attempt_id: Uuid,
+    /// Relative to the attempt's worktree, the worktree itself when omitted
+    pub file_path: Option<String>,
+    /// 1-based, e.g. the first line of a diff hunk
+    pub line: Option<u32>,
+    pub column: Option<u32>,
+    /// Overrides the configured editor
+    pub editor_type: Option<String>,
+}
+
+#[derive(Debug, Default, Serialize, TS)]
+pub struct EditorOpenResponse {
+    /// Set when the editor runs on the browser's machine over Remote SSH, the browser
+    /// opens this link instead of the server launching the editor
+    pub url: Option<String>,
+}
+
+pub fn router() -> Router<DeploymentImpl> {
+    Router::new().route("/editor/open", post(open_in_editor))
+}
+
+/// Resolve a path relative to a worktree, refusing paths that leave it
+pub fn worktree_path(worktree: &Path, relative: Option<&str>) -> Result<PathBuf, ApiError> {
+    let Some(relative) = relative.map(str::trim).filter(|p| !p.is_empty()) else {
+        return Ok(worktree.to_path_buf());
+    };
+    let relative = Path::new(relative);
+    if !relative
+        .components()
+        .all(|c| matches!(c, Component::Normal(_) | Component::CurDir))
+    {
+        return Err(ApiError::BadRequest(format!(
+            "File path must be relative to the worktree: {}",
+            relative.display()
+        )));
+    }
+    Ok(worktree.join(relative))
+}
+
+/// Open `path` with `editor`, returning the link to open instead for a remote editor
+pub fn open_path(
+    editor: &EditorConfig,
+    path: &Path,
+    line: Option<u32>,
+    column: Option<u32>,
+) -> Result<EditorOpenResponse, ApiError> {
+    let path_str = path.to_string_lossy();
+    match editor.remote_url(&path_str, line, column) {
+        Some(Ok(url)) => Ok(EditorOpenResponse { url: Some(url) }),
+        Some(Err(message)) => Err(ApiError::BadRequest(message)),
+        None => {
+            editor.open_file_at(&path_str, line, column).map_err(|e| {
+                ApiError::TaskAttempt(TaskAttemptError::ValidationError(format!(
+                    "Failed to open editor: {}",
+                    e
+                )))
+            })?;
+            tracing::info!("Opened editor at path: {}", path.display());
+            Ok(EditorOpenResponse::default())
+        }
+    }
+}
+
+async fn open_in_editor(
+    State(deployment): State<DeploymentImpl>,
+    Json(request): Json<EditorOpenRequest>,
+) -> Result<ResponseJson<ApiResponse<EditorOpenResponse>>, ApiError> {
+    let attempt = TaskAttempt::find_by_id(&deployment.db().pool, request.attempt_id)
+        .await?
+        .ok_or_else(|| {
+            ApiError::BadRequest(format!("Task attempt not found: {}", request.attempt_id))
+        })?;
+    let worktree = attempt.container_ref.as_deref().ok_or_else(|| {
+        ApiError::TaskAttempt(TaskAttemptError::ValidationError(
+            "No container ref found".to_string(),
+        ))
+    })?;
+    let path = worktree_path(Path::new(worktree), request.file_path.as_deref())?;
+
+    let editor = deployment
+        .config()
+        .read()
+        .await
+        .editor
+        .with_override(request.editor_type.as_deref());
+    let response = open_path(&editor, &path, request.line, request.column)?;
+    Ok(ResponseJson(ApiResponse::success(response)))
+}
@@ -0,0 +1,102 @@
+use axum::{
+    extract::{Path, Query, State},
+    response::Json as ResponseJson,
+    routing::{get, post},
+    Json, Router,
+};
+use db::models::{
+    evaluation::{Evaluation, EvaluationStatus},
+    project::Project,
+};
+use deployment::Deployment;
+use serde::Deserialize;
+use services::services::evaluation::{self, CreateEvaluation, EvaluationReport};
+use sqlx::Error as SqlxError;
+use utils::response::ApiResponse;
+use uuid::Uuid;
+
+use crate::{
+    error::ApiError,
+    routes::{disk_usage, spend},
+    DeploymentImpl,
+};
+
+#[derive(Debug, Deserialize)]
+pub struct EvaluationQuery {
+    pub project_id: Uuid,
+}
+
+pub async fn get_evaluations(
+    State(deployment): State<DeploymentImpl>,
+    Query(query): Query<EvaluationQuery>,
+) -> Result<ResponseJson<ApiResponse<Vec<Evaluation>>>, ApiError> {
+    Ok(ResponseJson(ApiResponse::success(
+        Evaluation::find_by_project_id(&deployment.db().pool, query.project_id).await?,
+    )))
+}
+
+/// Replay done tasks against the picked profiles. The replays run one at a time in the
+/// background, follow them through the evaluation's report.
+pub async fn create_evaluation(
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<CreateEvaluation>,
+) -> Result<ResponseJson<ApiResponse<Evaluation>>, ApiError> {
+    let project = Project::find_by_id(&deployment.db().pool, payload.project_id)
+        .await?
+        .ok_or(SqlxError::RowNotFound)?;
+    spend::ensure_within_budget(&deployment, project.id).await?;
+    disk_usage::ensure_within_disk_quota(&deployment, &project).await?;
+
+    let created = evaluation::create(&deployment.db().pool, &project, &payload).await?;
+    let evaluation_id = created.id;
+    let background = deployment.clone();
+    tokio::spawn(async move {
+        evaluation::run(background.container(), evaluation_id).await;
+    });
+
+    deployment
+        .track_event(
+            "evaluation_started",
+            serde_json::json!({
+                "project_id": project.id.to_string(),
+                "profiles": payload.profiles.len(),
+            }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(created)))
+}
+
+pub async fn get_evaluation_report(
+    Path(id): Path<Uuid>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<EvaluationReport>>, ApiError> {
+    let report = EvaluationReport::load(&deployment.db().pool, id)
+        .await?
+        .ok_or(SqlxError::RowNotFound)?;
+    Ok(ResponseJson(ApiResponse::success(report)))
+}
+
+/// Stop an evaluation, the replay in progress is stopped and the rest are left pending
+pub async fn cancel_evaluation(
+    Path(id): Path<Uuid>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Evaluation>>, ApiError> {
+    let pool = &deployment.db().pool;
+    if !Evaluation::finish(pool, id, EvaluationStatus::Cancelled, None).await? {
+        return Err(ApiError::BadRequest(
+            "The evaluation isn't running".to_string(),
+        ));
+    }
+    let evaluation = Evaluation::find_by_id(pool, id)
+        .await?
+        .ok_or(SqlxError::RowNotFound)?;
+    Ok(ResponseJson(ApiResponse::success(evaluation)))
+}
+
+pub fn router() -> Router<DeploymentImpl> {
+    Router::new()
+        .route("/evaluations", get(get_evaluations).post(create_evaluation))
+        .route("/evaluations/{id}", get(get_evaluation_report))
+        .route("/evaluations/{id}/cancel", post(cancel_evaluation))
+}
@@ -17,7 +17,9 @@ pub async fn events(
 ) -> Result<Sse<impl futures_util::Stream<Item = Result<Event, BoxError>>>, axum::http::StatusCode>
 {
     // Ask the container service for a combined "history + live" stream
-    let stream = deployment.stream_events().await;
+    let stream = deployment
+        .shutdown()
+        .guard_stream(deployment.stream_events().await);
     Ok(Sse::new(stream.map_err(|e| -> BoxError { e.into() })).keep_alive(KeepAlive::default()))
 }
 
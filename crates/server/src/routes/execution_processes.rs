@@ -13,7 +13,10 @@ use deployment::Deployment;
 use futures_util::TryStreamExt;
 use serde::Deserialize;
 use services::services::container::ContainerService;
-use utils::response::ApiResponse;
+use utils::{
+    pagination::{ListQuery, Paginated},
+    response::ApiResponse,
+};
 use uuid::Uuid;
 
 use crate::{error::ApiError, middleware::load_execution_process_middleware, DeploymentImpl};
@@ -26,12 +29,16 @@ pub struct ExecutionProcessQuery {
 pub async fn get_execution_processes(
     State(deployment): State<DeploymentImpl>,
     Query(query): Query<ExecutionProcessQuery>,
-) -> Result<ResponseJson<ApiResponse<Vec<ExecutionProcess>>>, ApiError> {
+    list: ListQuery,
+) -> Result<ResponseJson<ApiResponse<Paginated<ExecutionProcess>>>, ApiError> {
     let pool = &deployment.db().pool;
     let execution_processes =
         ExecutionProcess::find_by_task_attempt_id(pool, query.task_attempt_id).await?;
+    let page = list
+        .apply(execution_processes)
+        .map_err(ApiError::BadRequest)?;
 
-    Ok(ResponseJson(ApiResponse::success(execution_processes)))
+    Ok(ResponseJson(ApiResponse::paginated(page)))
 }
 
 pub async fn get_execution_process_by_id(
@@ -52,6 +59,7 @@ pub async fn stream_raw_logs(
         .stream_raw_logs(&exec_id)
         .await
         .ok_or(axum::http::StatusCode::NOT_FOUND)?;
+    let stream = deployment.shutdown().guard_stream(stream);
 
     Ok(Sse::new(stream.map_err(|e| -> BoxError { e.into() })).keep_alive(KeepAlive::default()))
 }
@@ -67,6 +75,7 @@ pub async fn stream_normalized_logs(
         .stream_normalized_logs(&exec_id)
         .await
         .ok_or(axum::http::StatusCode::NOT_FOUND)?;
+    let stream = deployment.shutdown().guard_stream(stream);
 
     Ok(Sse::new(stream.map_err(|e| -> BoxError { e.into() })).keep_alive(KeepAlive::default()))
 }
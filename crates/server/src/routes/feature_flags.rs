@@ -0,0 +1,67 @@
+use axum::{
+    extract::{Path, State},
+    response::Json as ResponseJson,
+    routing::{get, put},
+    Json, Router,
+};
+use deployment::Deployment;
+use serde::Deserialize;
+use services::services::feature_flags::{self, FeatureFlag, FeatureFlagState};
+use ts_rs::TS;
+use utils::response::ApiResponse;
+
+use crate::{error::ApiError, DeploymentImpl};
+
+#[derive(Debug, Deserialize, TS)]
+pub struct SetFeatureFlag {
+    pub enabled: bool,
+}
+
+pub async fn list_feature_flags(
+    State(deployment): State<DeploymentImpl>,
+) -> ResponseJson<ApiResponse<Vec<FeatureFlagState>>> {
+    let config = deployment.config().read().await;
+    ResponseJson(ApiResponse::success(feature_flags::states(
+        &config.feature_flags,
+    )))
+}
+
+/// Turn an experimental subsystem on or off. Work already running keeps going, the flag
+/// applies to what starts afterwards.
+pub async fn set_feature_flag(
+    State(deployment): State<DeploymentImpl>,
+    Path(flag): Path<FeatureFlag>,
+    Json(payload): Json<SetFeatureFlag>,
+) -> Result<ResponseJson<ApiResponse<Vec<FeatureFlagState>>>, ApiError> {
+    if feature_flags::env_override(flag).is_some() {
+        return Err(ApiError::BadRequest(format!(
+            "{} is set by {}, unset it to change the flag here",
+            flag,
+            flag.env_var()
+        )));
+    }
+    let mut config = deployment.config().write().await;
+    flag.set(&mut config.feature_flags, payload.enabled);
+    deployment.save_config(&config).await?;
+    let states = feature_flags::states(&config.feature_flags);
+    drop(config);
+
+    tracing::info!(
+        "Feature flag {} {}",
+        flag,
+        if payload.enabled { "on" } else { "off" }
+    );
+    deployment
+        .track_event(
+            "feature_flag_changed",
+            serde_json::json!({ "flag": flag, "enabled": payload.enabled }),
+        )
+        .await;
+    Ok(ResponseJson(ApiResponse::success(states)))
+}
+
+pub fn router() -> Router<DeploymentImpl> {
+    Router::new()
+        .route("/feature-flags", get(list_feature_flags))
+        .route("/feature-flags/{flag}", put(set_feature_flag))
+}
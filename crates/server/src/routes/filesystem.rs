@@ -6,7 +6,7 @@ use axum::{
 };
 use deployment::Deployment;
 use serde::Deserialize;
-use services::services::filesystem::{DirectoryEntry, DirectoryListResponse, FilesystemError};
+use services::services::filesystem::{DirectoryEntry, DirectoryListResponse};
 use utils::response::ApiResponse;
 
 use crate::{error::ApiError, DeploymentImpl};
@@ -20,48 +20,19 @@ pub async fn list_directory(
     State(deployment): State<DeploymentImpl>,
     Query(query): Query<ListDirectoryQuery>,
 ) -> Result<ResponseJson<ApiResponse<DirectoryListResponse>>, ApiError> {
-    match deployment.filesystem().list_directory(query.path).await {
-        Ok(response) => Ok(ResponseJson(ApiResponse::success(response))),
-        Err(FilesystemError::DirectoryDoesNotExist) => {
-            Ok(ResponseJson(ApiResponse::error("Directory does not exist")))
-        }
-        Err(FilesystemError::PathIsNotDirectory) => {
-            Ok(ResponseJson(ApiResponse::error("Path is not a directory")))
-        }
-        Err(FilesystemError::Io(e)) => {
-            tracing::error!("Failed to read directory: {}", e);
-            Ok(ResponseJson(ApiResponse::error(&format!(
-                "Failed to read directory: {}",
-                e
-            ))))
-        }
-    }
+    let response = deployment.filesystem().list_directory(query.path).await?;
+    Ok(ResponseJson(ApiResponse::success(response)))
 }
 
 pub async fn list_git_repos(
     State(deployment): State<DeploymentImpl>,
     Query(query): Query<ListDirectoryQuery>,
 ) -> Result<ResponseJson<ApiResponse<Vec<DirectoryEntry>>>, ApiError> {
-    match deployment
+    let response = deployment
         .filesystem()
         .list_git_repos(query.path, Some(4))
-        .await
-    {
-        Ok(response) => Ok(ResponseJson(ApiResponse::success(response))),
-        Err(FilesystemError::DirectoryDoesNotExist) => {
-            Ok(ResponseJson(ApiResponse::error("Directory does not exist")))
-        }
-        Err(FilesystemError::PathIsNotDirectory) => {
-            Ok(ResponseJson(ApiResponse::error("Path is not a directory")))
-        }
-        Err(FilesystemError::Io(e)) => {
-            tracing::error!("Failed to read directory: {}", e);
-            Ok(ResponseJson(ApiResponse::error(&format!(
-                "Failed to read directory: {}",
-                e
-            ))))
-        }
-    }
+        .await?;
+    Ok(ResponseJson(ApiResponse::success(response)))
 }
 
 pub fn router() -> Router<DeploymentImpl> {
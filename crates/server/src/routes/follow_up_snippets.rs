@@ -0,0 +1,100 @@
+use axum::{
+    extract::{Query, State},
+    middleware::from_fn_with_state,
+    response::Json as ResponseJson,
+    routing::get,
+    Extension, Json, Router,
+};
+use db::models::follow_up_snippet::{
+    CreateFollowUpSnippet, FollowUpSnippet, UpdateFollowUpSnippet,
+};
+use deployment::Deployment;
+use serde::Deserialize;
+use sqlx::Error as SqlxError;
+use utils::response::ApiResponse;
+use uuid::Uuid;
+
+use crate::{error::ApiError, middleware::load_follow_up_snippet_middleware, DeploymentImpl};
+
+#[derive(Debug, Deserialize)]
+pub struct FollowUpSnippetQuery {
+    global: Option<bool>,
+    project_id: Option<Uuid>,
+}
+
+pub async fn get_snippets(
+    State(deployment): State<DeploymentImpl>,
+    Query(query): Query<FollowUpSnippetQuery>,
+) -> Result<ResponseJson<ApiResponse<Vec<FollowUpSnippet>>>, ApiError> {
+    let pool = &deployment.db().pool;
+    let snippets = match (query.global, query.project_id) {
+        (None, None) => FollowUpSnippet::find_all(pool).await?,
+        (Some(true), None) => FollowUpSnippet::find_global(pool).await?,
+        (Some(false), None) => vec![],
+        // What the project's follow-ups can use, its snippets replacing global ones
+        (None | Some(false), Some(project_id)) => {
+            FollowUpSnippet::find_for_project(pool, project_id).await?
+        }
+        (Some(true), Some(_)) => {
+            return Err(ApiError::Database(SqlxError::InvalidArgument(
+                "Cannot query both global and project-specific snippets".to_string(),
+            )));
+        }
+    };
+    Ok(ResponseJson(ApiResponse::success(snippets)))
+}
+
+pub async fn get_snippet(
+    Extension(snippet): Extension<FollowUpSnippet>,
+) -> Result<ResponseJson<ApiResponse<FollowUpSnippet>>, ApiError> {
+    Ok(ResponseJson(ApiResponse::success(snippet)))
+}
+
+pub async fn create_snippet(
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<CreateFollowUpSnippet>,
+) -> Result<ResponseJson<ApiResponse<FollowUpSnippet>>, ApiError> {
+    Ok(ResponseJson(ApiResponse::success(
+        FollowUpSnippet::create(&deployment.db().pool, &payload).await?,
+    )))
+}
+
+pub async fn update_snippet(
+    Extension(snippet): Extension<FollowUpSnippet>,
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<UpdateFollowUpSnippet>,
+) -> Result<ResponseJson<ApiResponse<FollowUpSnippet>>, ApiError> {
+    Ok(ResponseJson(ApiResponse::success(
+        FollowUpSnippet::update(&deployment.db().pool, snippet.id, &payload).await?,
+    )))
+}
+
+pub async fn delete_snippet(
+    Extension(snippet): Extension<FollowUpSnippet>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
+    let rows_affected = FollowUpSnippet::delete(&deployment.db().pool, snippet.id).await?;
+    if rows_affected == 0 {
+        Err(ApiError::Database(SqlxError::RowNotFound))
+    } else {
+        Ok(ResponseJson(ApiResponse::success(())))
+    }
+}
+
+pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
+    let snippet_router = Router::new()
+        .route(
+            "/",
+            get(get_snippet).put(update_snippet).delete(delete_snippet),
+        )
+        .layer(from_fn_with_state(
+            deployment.clone(),
+            load_follow_up_snippet_middleware,
+        ));
+
+    let inner = Router::new()
+        .route("/", get(get_snippets).post(create_snippet))
+        .nest("/{snippet_id}", snippet_router);
+
+    Router::new().nest("/follow-up-snippets", inner)
+}
@@ -1,30 +1,114 @@
+use std::{
+    borrow::Cow,
+    path::{Component, Path, PathBuf},
+};
+
 use axum::{
     body::Body,
+    extract::State,
     http::HeaderValue,
     response::{IntoResponse, Response},
+    routing::get,
+    Router,
 };
 use reqwest::{header, StatusCode};
+#[cfg(feature = "embed-frontend")]
 use rust_embed::RustEmbed;
 
+use crate::settings::ServerSettings;
+
+#[cfg(feature = "embed-frontend")]
 #[derive(RustEmbed)]
 #[folder = "../../frontend/dist"]
 pub struct Assets;
 
-pub async fn serve_frontend(uri: axum::extract::Path<String>) -> impl IntoResponse {
+/// Tells the frontend not to load anything from outside services, e.g. Sentry
+const OFFLINE_META: &str = r#"<meta name="vibe-kanban-offline" content="true" />"#;
+
+/// Where the built frontend is served from
+#[derive(Debug, Clone)]
+pub struct Frontend {
+    /// Serve from this directory instead of the embedded build
+    dir: Option<PathBuf>,
+    offline: bool,
+}
+
+impl Frontend {
+    pub fn new(settings: &ServerSettings) -> Self {
+        Self {
+            dir: settings.frontend_dir.clone(),
+            offline: settings.offline,
+        }
+    }
+
+    fn load(&self, path: &str) -> Option<Cow<'static, [u8]>> {
+        match &self.dir {
+            Some(dir) => {
+                // Only plain paths inside the directory are served
+                let relative = Path::new(path);
+                if !relative
+                    .components()
+                    .all(|component| matches!(component, Component::Normal(_)))
+                {
+                    return None;
+                }
+                std::fs::read(dir.join(relative)).ok().map(Cow::Owned)
+            }
+            #[cfg(feature = "embed-frontend")]
+            None => Assets::get(path).map(|file| file.data),
+            #[cfg(not(feature = "embed-frontend"))]
+            None => None,
+        }
+    }
+
+    fn index(&self) -> Option<Vec<u8>> {
+        let index = self.load("index.html")?;
+        if !self.offline {
+            return Some(index.into_owned());
+        }
+        let html = String::from_utf8_lossy(&index);
+        Some(
+            html.replacen("<head>", &format!("<head>\n    {OFFLINE_META}"), 1)
+                .into_bytes(),
+        )
+    }
+}
+
+pub fn router(settings: &ServerSettings) -> Router {
+    Router::new()
+        .route("/", get(serve_frontend_root))
+        .route("/{*path}", get(serve_frontend))
+        .with_state(Frontend::new(settings))
+}
+
+pub async fn serve_frontend(
+    State(frontend): State<Frontend>,
+    uri: axum::extract::Path<String>,
+) -> impl IntoResponse {
     let path = uri.trim_start_matches('/');
-    serve_file(path).await
+    serve_file(&frontend, path).await
 }
 
-pub async fn serve_frontend_root() -> impl IntoResponse {
-    serve_file("index.html").await
+pub async fn serve_frontend_root(State(frontend): State<Frontend>) -> impl IntoResponse {
+    serve_file(&frontend, "index.html").await
 }
 
-async fn serve_file(path: &str) -> impl IntoResponse {
-    let file = Assets::get(path);
+async fn serve_file(frontend: &Frontend, path: &str) -> Response {
+    let file = if path == "index.html" {
+        None
+    } else {
+        frontend.load(path)
+    };
 
     match file {
         Some(content) => {
             let mime = mime_guess::from_path(path).first_or_octet_stream();
+            // Vite puts a content hash in the names of everything under assets/
+            let cache_control = if path.starts_with("assets/") {
+                "public, max-age=31536000, immutable"
+            } else {
+                "no-cache"
+            };
 
             Response::builder()
                 .status(StatusCode::OK)
@@ -32,16 +116,18 @@ async fn serve_file(path: &str) -> impl IntoResponse {
                     header::CONTENT_TYPE,
                     HeaderValue::from_str(mime.as_ref()).unwrap(),
                 )
-                .body(Body::from(content.data.into_owned()))
+                .header(header::CACHE_CONTROL, cache_control)
+                .body(Body::from(content.into_owned()))
                 .unwrap()
         }
         None => {
             // For SPA routing, serve index.html for unknown routes
-            if let Some(index) = Assets::get("index.html") {
+            if let Some(index) = frontend.index() {
                 Response::builder()
                     .status(StatusCode::OK)
                     .header(header::CONTENT_TYPE, HeaderValue::from_static("text/html"))
-                    .body(Body::from(index.data.into_owned()))
+                    .header(header::CACHE_CONTROL, "no-cache")
+                    .body(Body::from(index))
                     .unwrap()
             } else {
                 Response::builder()
@@ -52,3 +138,45 @@ async fn serve_file(path: &str) -> impl IntoResponse {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn frontend(dir: &Path, offline: bool) -> Frontend {
+        Frontend {
+            dir: Some(dir.to_path_buf()),
+            offline,
+        }
+    }
+
+    #[test]
+    fn test_frontend_dir_serves_only_files_inside_it() {
+        let root = tempfile::tempdir().unwrap();
+        let dist = root.path().join("dist");
+        std::fs::create_dir_all(dist.join("assets")).unwrap();
+        std::fs::write(dist.join("assets/app.js"), "app").unwrap();
+        std::fs::write(root.path().join("secret.txt"), "secret").unwrap();
+
+        let frontend = frontend(&dist, false);
+        assert_eq!(frontend.load("assets/app.js").as_deref(), Some(&b"app"[..]));
+        assert_eq!(frontend.load("../secret.txt"), None);
+        assert_eq!(frontend.load("/etc/passwd"), None);
+        assert_eq!(frontend.load("missing.js"), None);
+    }
+
+    #[test]
+    fn test_offline_index_tells_the_frontend() {
+        let dist = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dist.path().join("index.html"),
+            "<html><head><title>vk</title></head></html>",
+        )
+        .unwrap();
+
+        let online = frontend(dist.path(), false).index().unwrap();
+        assert!(!String::from_utf8(online).unwrap().contains(OFFLINE_META));
+        let offline = String::from_utf8(frontend(dist.path(), true).index().unwrap()).unwrap();
+        assert!(offline.starts_with(&format!("<html><head>\n    {OFFLINE_META}<title>")));
+    }
+}
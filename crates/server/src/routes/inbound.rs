@@ -0,0 +1,190 @@
+use axum::{extract::State, response::Json as ResponseJson, routing::post, Json, Router};
+use db::models::{
+    inbound_task::InboundTask,
+    project::Project,
+    task::{CreateTask, Task, TaskStatus},
+};
+use deployment::Deployment;
+use serde::{Deserialize, Serialize};
+use sqlx::Error as SqlxError;
+use ts_rs::TS;
+use utils::response::ApiResponse;
+use uuid::Uuid;
+
+use crate::{error::ApiError, routes::tasks::start_new_task, DeploymentImpl};
+
+/// An alert posted by an integration such as Sentry, PagerDuty or CI
+#[derive(Debug, Deserialize, TS)]
+pub struct InboundTaskRequest {
+    pub title: String,
+    pub body: Option<String>,
+    /// Alerts with the same key update one open card instead of adding more
+    pub dedupe_key: Option<String>,
+    /// Project id, name or repository folder name. May be left out when there's only one
+    /// project.
+    pub project: Option<String>,
+    /// Sender of the alert, e.g. `sentry`
+    pub source: Option<String>,
+    /// Start an agent on the card when it's created
+    #[serde(default)]
+    pub start_agent: bool,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, TS)]
+#[serde(rename_all = "lowercase")]
+pub enum InboundTaskAction {
+    Created,
+    Updated,
+}
+
+#[derive(Debug, Serialize, TS)]
+pub struct InboundTaskResponse {
+    pub task: Task,
+    pub action: InboundTaskAction,
+    /// Times the alert has fired on this card
+    #[ts(type = "number")]
+    pub occurrences: i64,
+    pub agent_started: bool,
+}
+
+pub(crate) async fn resolve_project(
+    deployment: &DeploymentImpl,
+    hint: Option<&str>,
+) -> Result<Project, ApiError> {
+    let projects = Project::find_all(&deployment.db().pool).await?;
+    let Some(hint) = hint.map(str::trim).filter(|hint| !hint.is_empty()) else {
+        if projects.len() != 1 {
+            return Err(ApiError::BadRequest(
+                "project is required unless there is exactly one project".to_string(),
+            ));
+        }
+        return Ok(projects.into_iter().next().unwrap());
+    };
+
+    let id = Uuid::parse_str(hint).ok();
+    projects
+        .into_iter()
+        .find(|project| {
+            Some(project.id) == id
+                || project.name.eq_ignore_ascii_case(hint)
+                || project
+                    .git_repo_path
+                    .file_name()
+                    .is_some_and(|name| name.eq_ignore_ascii_case(hint))
+        })
+        .ok_or_else(|| ApiError::BadRequest(format!("No project matches '{hint}'")))
+}
+
+fn is_open(task: &Task) -> bool {
+    !matches!(task.status, TaskStatus::Done | TaskStatus::Cancelled)
+}
+
+/// Create a card in `project` for an alert, or update the open card an earlier alert with
+/// the same dedupe key created
+pub(crate) async fn file_alert(
+    deployment: &DeploymentImpl,
+    project: &Project,
+    payload: &InboundTaskRequest,
+) -> Result<InboundTaskResponse, ApiError> {
+    let title = payload.title.trim();
+    if title.is_empty() {
+        return Err(ApiError::BadRequest("title must not be empty".to_string()));
+    }
+    let dedupe_key = payload
+        .dedupe_key
+        .as_deref()
+        .map(str::trim)
+        .filter(|key| !key.is_empty());
+    let source = payload.source.as_deref();
+    let pool = &deployment.db().pool;
+
+    let open_task = match dedupe_key {
+        Some(key) => match InboundTask::find(pool, project.id, key).await? {
+            Some(entry) => Task::find_by_id(pool, entry.task_id)
+                .await?
+                .filter(is_open)
+                .map(|task| (key, task)),
+            None => None,
+        },
+        None => None,
+    };
+
+    if let Some((key, task)) = open_task {
+        let task = Task::update(
+            pool,
+            task.id,
+            task.project_id,
+            title.to_string(),
+            payload.body.clone().or(task.description),
+            task.status,
+            task.parent_task_attempt,
+        )
+        .await?;
+        let entry = InboundTask::record_occurrence(pool, project.id, key).await?;
+        deployment
+            .track_event(
+                "inbound_task_updated",
+                serde_json::json!({ "source": source, "occurrences": entry.occurrences }),
+            )
+            .await;
+        return Ok(InboundTaskResponse {
+            task,
+            action: InboundTaskAction::Updated,
+            occurrences: entry.occurrences,
+            agent_started: false,
+        });
+    }
+
+    let create = CreateTask {
+        project_id: project.id,
+        title: title.to_string(),
+        description: payload.body.clone(),
+        parent_task_attempt: None,
+        priority: None,
+        labels: Vec::new(),
+        base_branch: None,
+    };
+    let task = if payload.start_agent {
+        let started = start_new_task(deployment, &create, None).await?;
+        Task::find_by_id(pool, started.id)
+            .await?
+            .ok_or(ApiError::Database(SqlxError::RowNotFound))?
+    } else {
+        Task::create(pool, &create, Uuid::new_v4()).await?
+    };
+    let occurrences = match dedupe_key {
+        Some(key) => {
+            InboundTask::assign(pool, project.id, key, task.id, source)
+                .await?
+                .occurrences
+        }
+        None => 1,
+    };
+    deployment
+        .track_event(
+            "inbound_task_created",
+            serde_json::json!({ "source": source, "start_agent": payload.start_agent }),
+        )
+        .await;
+
+    Ok(InboundTaskResponse {
+        task,
+        action: InboundTaskAction::Created,
+        occurrences,
+        agent_started: payload.start_agent,
+    })
+}
+
+pub async fn receive_inbound_task(
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<InboundTaskRequest>,
+) -> Result<ResponseJson<ApiResponse<InboundTaskResponse>>, ApiError> {
+    let project = resolve_project(&deployment, payload.project.as_deref()).await?;
+    Ok(ResponseJson(ApiResponse::success(
+        file_alert(&deployment, &project, &payload).await?,
+    )))
+}
+
+pub fn router() -> Router<DeploymentImpl> {
+    Router::new().route("/inbound/tasks", post(receive_inbound_task))
+}
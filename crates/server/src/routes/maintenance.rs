@@ -0,0 +1,141 @@
+use std::time::Duration;
+
+use axum::{
+    extract::State,
+    response::Json as ResponseJson,
+    routing::{get, post},
+    Json, Router,
+};
+use deployment::Deployment;
+use services::services::{
+    backup::{BackupInfo, RestoreBackupRequest},
+    container::ContainerService,
+    self_update::{self, SelfUpdateOutcome, UpdateStatus},
+};
+use utils::response::ApiResponse;
+
+use crate::{error::ApiError, DeploymentImpl};
+
+pub async fn create_backup(
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<BackupInfo>>, ApiError> {
+    let backup = deployment.backup().create_backup().await?;
+    Ok(ResponseJson(ApiResponse::success(backup)))
+}
+
+pub async fn list_backups(
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Vec<BackupInfo>>>, ApiError> {
+    let backups = deployment.backup().list_backups().await?;
+    Ok(ResponseJson(ApiResponse::success(backups)))
+}
+
+/// Stages a snapshot to replace the database, it takes effect after a restart
+pub async fn restore_backup(
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<RestoreBackupRequest>,
+) -> Result<ResponseJson<ApiResponse<BackupInfo>>, ApiError> {
+    let backup = deployment
+        .backup()
+        .stage_restore(&payload.file_name)
+        .await?;
+    Ok(ResponseJson(ApiResponse::success(backup)))
+}
+
+async fn set_agents_paused(deployment: &DeploymentImpl, paused: bool) -> Result<(), ApiError> {
+    let mut config = deployment.config().write().await;
+    config.agents_paused = paused;
+    deployment.save_config(&config).await?;
+    tracing::info!("Agents {}", if paused { "paused" } else { "resumed" });
+    Ok(())
+}
+
+/// Pause every agent at once, for when the machine or a provider's spending cap needs them
+/// stopped. Nothing new starts until they are resumed, which holds across restarts. Returns
+/// how many executions were suspended.
+pub async fn pause_all_agents(deployment: &DeploymentImpl) -> Result<usize, ApiError> {
+    set_agents_paused(deployment, true).await?;
+    Ok(deployment.container().pause_all_executions().await?)
+}
+
+/// Continue every paused execution and send the follow-ups queued in the meantime
+pub async fn resume_all_agents(deployment: &DeploymentImpl) -> Result<usize, ApiError> {
+    set_agents_paused(deployment, false).await?;
+    Ok(deployment.container().resume_all_executions().await?)
+}
+
+pub async fn pause_agents(
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<usize>>, ApiError> {
+    let paused = pause_all_agents(&deployment).await?;
+    Ok(ResponseJson(ApiResponse::success(paused)))
+}
+
+pub async fn resume_agents(
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<usize>>, ApiError> {
+    let resumed = resume_all_agents(&deployment).await?;
+    Ok(ResponseJson(ApiResponse::success(resumed)))
+}
+
+/// Ask the npm registry for the latest version now instead of waiting for the next check
+pub async fn check_for_update() -> Result<ResponseJson<ApiResponse<UpdateStatus>>, ApiError> {
+    Ok(ResponseJson(ApiResponse::success(
+        self_update::check().await?,
+    )))
+}
+
+/// Update to the latest version. A server started by the npx wrapper shuts down, handing
+/// its running executions over as on any shutdown, and the wrapper installs and starts the
+/// new version. Other installs get instructions instead.
+pub async fn start_self_update(
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<SelfUpdateOutcome>>, ApiError> {
+    let mut status = self_update::status();
+    if status.latest_version.is_none() {
+        status = self_update::check().await?;
+    }
+    let outcome = match status.latest_version {
+        Some(version) if status.update_available => {
+            if status.can_self_update {
+                SelfUpdateOutcome::Restarting { version }
+            } else {
+                SelfUpdateOutcome::Manual {
+                    instructions: self_update::manual_instructions(),
+                }
+            }
+        }
+        _ => SelfUpdateOutcome::UpToDate,
+    };
+
+    if let SelfUpdateOutcome::Restarting { version } = &outcome {
+        deployment
+            .track_event(
+                "self_update_started",
+                serde_json::json!({ "from": status.current_version, "to": version }),
+            )
+            .await;
+        tracing::info!("Restarting to update to {}", version);
+        // After the response has gone out
+        let shutdown = deployment.shutdown().clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(500)).await;
+            shutdown.request_restart_for_update();
+        });
+    }
+    Ok(ResponseJson(ApiResponse::success(outcome)))
+}
+
+pub fn router(_deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
+    Router::new().nest(
+        "/maintenance",
+        Router::new()
+            .route("/backup", post(create_backup))
+            .route("/backups", get(list_backups))
+            .route("/restore", post(restore_backup))
+            .route("/pause-agents", post(pause_agents))
+            .route("/resume-agents", post(resume_agents))
+            .route("/update-check", post(check_for_update))
+            .route("/self-update", post(start_self_update)),
+    )
+}
@@ -1,43 +1,112 @@
+use std::net::SocketAddr;
+
 use axum::{
-    routing::{get, IntoMakeService},
+    extract::{connect_info::IntoMakeServiceWithConnectInfo, DefaultBodyLimit},
+    middleware::{from_fn, from_fn_with_state},
+    routing::get,
     Router,
 };
 
-use crate::DeploymentImpl;
+use crate::{
+    middleware::{
+        api_version_middleware, rate_limit_middleware, request_id_middleware, RateLimiter,
+    },
+    settings::ServerSettings,
+    DeploymentImpl,
+};
 
+pub mod analytics;
 pub mod auth;
 pub mod config;
 pub mod containers;
-pub mod filesystem;
-// pub mod github;
+pub mod debug;
+pub mod disk_usage;
+pub mod editor;
+pub mod evaluations;
 pub mod events;
 pub mod execution_processes;
+pub mod feature_flags;
+pub mod filesystem;
+pub mod follow_up_snippets;
 pub mod frontend;
+pub mod github;
 pub mod health;
+pub mod inbound;
+pub mod maintenance;
+pub mod onboarding;
+pub mod presence;
+pub mod preview;
+pub mod project_groups;
 pub mod projects;
+pub mod provider_keys;
+pub mod sentry_issues;
+pub mod share;
+pub mod spend;
+pub mod task_attachments;
 pub mod task_attempts;
 pub mod task_templates;
 pub mod tasks;
+pub mod terminal;
+pub mod webhooks;
+pub mod workspaces;
+
+pub fn router(
+    deployment: DeploymentImpl,
+    settings: &ServerSettings,
+) -> IntoMakeServiceWithConnectInfo<Router, SocketAddr> {
+    let preview_routes = preview::router(&deployment);
 
-pub fn router(deployment: DeploymentImpl) -> IntoMakeService<Router> {
     // Create routers with different middleware layers
-    let base_routes = Router::new()
-        .route("/health", get(health::health_check))
+    let mut base_routes = Router::new()
+        .merge(analytics::router())
         .merge(config::router())
         .merge(containers::router(&deployment))
+        .merge(debug::router())
+        .merge(editor::router())
+        .merge(evaluations::router())
+        .merge(feature_flags::router())
         .merge(projects::router(&deployment))
+        .merge(project_groups::router(&deployment))
         .merge(tasks::router(&deployment))
+        .merge(task_attachments::router())
         .merge(task_attempts::router(&deployment))
         .merge(execution_processes::router(&deployment))
         .merge(task_templates::router(&deployment))
+        .merge(follow_up_snippets::router(&deployment))
+        .merge(github::router())
         .merge(auth::router(&deployment))
         .merge(filesystem::router())
+        .merge(inbound::router())
         .merge(events::router(&deployment))
+        .merge(maintenance::router(&deployment))
+        .merge(onboarding::router())
+        .merge(presence::router())
+        .merge(provider_keys::router())
+        .merge(sentry_issues::router())
+        .merge(share::router())
+        .merge(spend::router())
+        .merge(webhooks::router())
+        .merge(workspaces::router())
+        // Routes with a larger limit of their own, e.g. uploads, keep it
+        .layer(DefaultBodyLimit::max(settings.max_body_bytes));
+    if let Some(per_minute) = settings.rate_limit_per_minute {
+        base_routes = base_routes.layer(from_fn_with_state(
+            RateLimiter::new(per_minute),
+            rate_limit_middleware,
+        ));
+    }
+    // Health checks are added after the limits so they always get through
+    let base_routes = base_routes
+        .route("/health", get(health::health_check))
+        // Every API response, health checks included, reports the version
+        .layer(from_fn(api_version_middleware))
+        // Outermost, so rejections by the other layers carry a request id too
+        .layer(from_fn(request_id_middleware))
         .with_state(deployment);
 
     Router::new()
-        .route("/", get(frontend::serve_frontend_root))
-        .route("/{*path}", get(frontend::serve_frontend))
+        .merge(frontend::router(settings))
         .nest("/api", base_routes)
-        .into_make_service()
+        .merge(preview_routes)
+        .into_make_service_with_connect_info::<SocketAddr>()
 }
@@ -0,0 +1,84 @@
+use axum::{
+    extract::{Path, State},
+    response::{
+        sse::{Event, KeepAlive},
+        Json as ResponseJson, Sse,
+    },
+    routing::{get, post},
+    BoxError, Json, Router,
+};
+use deployment::Deployment;
+use futures_util::TryStreamExt;
+use services::services::agent_login::{AgentLoginInput, AgentLoginSession, StartAgentLoginRequest};
+use utils::response::ApiResponse;
+use uuid::Uuid;
+
+use crate::{error::ApiError, DeploymentImpl};
+
+/// Start the login flow of the agent behind a profile, e.g. `claude setup-token`
+pub async fn start_agent_login(
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<StartAgentLoginRequest>,
+) -> Result<ResponseJson<ApiResponse<AgentLoginSession>>, ApiError> {
+    let session = deployment.agent_login().start(&payload.profile).await?;
+    deployment
+        .track_event(
+            "agent_login_started",
+            serde_json::json!({ "profile": &session.profile }),
+        )
+        .await;
+    Ok(ResponseJson(ApiResponse::success(session)))
+}
+
+pub async fn get_agent_login(
+    State(deployment): State<DeploymentImpl>,
+    Path(login_id): Path<Uuid>,
+) -> Result<ResponseJson<ApiResponse<AgentLoginSession>>, ApiError> {
+    let session = deployment.agent_login().get(login_id).await?;
+    Ok(ResponseJson(ApiResponse::success(session)))
+}
+
+/// Login output as SSE, ending with a `finished` event once the credentials were checked
+pub async fn stream_agent_login(
+    State(deployment): State<DeploymentImpl>,
+    Path(login_id): Path<Uuid>,
+) -> Result<Sse<impl futures_util::Stream<Item = Result<Event, BoxError>>>, ApiError> {
+    let store = deployment.agent_login().output_store(login_id).await?;
+    let stream = deployment.shutdown().guard_stream(store.sse_stream());
+
+    Ok(Sse::new(stream.map_err(|e| -> BoxError { e.into() })).keep_alive(KeepAlive::default()))
+}
+
+pub async fn send_agent_login_input(
+    State(deployment): State<DeploymentImpl>,
+    Path(login_id): Path<Uuid>,
+    Json(payload): Json<AgentLoginInput>,
+) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
+    deployment
+        .agent_login()
+        .send_input(login_id, &payload.input)
+        .await?;
+    Ok(ResponseJson(ApiResponse::success(())))
+}
+
+pub async fn cancel_agent_login(
+    State(deployment): State<DeploymentImpl>,
+    Path(login_id): Path<Uuid>,
+) -> Result<ResponseJson<ApiResponse<AgentLoginSession>>, ApiError> {
+    let session = deployment.agent_login().cancel(login_id).await?;
+    Ok(ResponseJson(ApiResponse::success(session)))
+}
+
+pub fn router() -> Router<DeploymentImpl> {
+    let login_router = Router::new()
+        .route("/", get(get_agent_login))
+        .route("/stream", get(stream_agent_login))
+        .route("/input", post(send_agent_login_input))
+        .route("/cancel", post(cancel_agent_login));
+
+    let inner = Router::new()
+        .route("/agent-logins", post(start_agent_login))
+        .nest("/agent-logins/{login_id}", login_router);
+
+    Router::new().nest("/onboarding", inner)
+}
@@ -0,0 +1,101 @@
+use axum::{
+    extract::{Query, State},
+    response::Json as ResponseJson,
+    routing::get,
+    Router,
+};
+use chrono::Utc;
+use db::models::execution_process::{ExecutionProcess, ExecutionProcessRunReason};
+use deployment::Deployment;
+use executors::actions::ExecutorActionType;
+use serde::Deserialize;
+use services::services::{
+    container::ContainerService,
+    presence::{self, AgentActivity, AgentPresence},
+};
+use utils::response::ApiResponse;
+use uuid::Uuid;
+
+use crate::{error::ApiError, DeploymentImpl};
+
+#[derive(Debug, Deserialize)]
+pub struct PresenceQuery {
+    pub project_id: Option<Uuid>,
+}
+
+/// Running coding agents, oldest first, with what each is doing according to its normalized
+/// conversation. Setup scripts, dev servers and the like are left out.
+async fn load_presence(
+    deployment: &DeploymentImpl,
+    project_id: Option<Uuid>,
+) -> Result<Vec<AgentPresence>, ApiError> {
+    let pool = &deployment.db().pool;
+    let now = Utc::now();
+    let mut presences = Vec::new();
+    for process in ExecutionProcess::find_running(pool).await? {
+        if process.run_reason != ExecutionProcessRunReason::CodingAgent {
+            continue;
+        }
+        let Ok(action) = process.executor_action() else {
+            continue;
+        };
+        let label = match action.typ() {
+            ExecutorActionType::CodingAgentInitialRequest(request) => {
+                request.profile_variant_label.clone()
+            }
+            ExecutorActionType::CodingAgentFollowUpRequest(request) => {
+                request.profile_variant_label.clone()
+            }
+            _ => continue,
+        };
+        let Some(task_attempt) = process.parent_task_attempt(pool).await? else {
+            continue;
+        };
+        let Some(task) = task_attempt.parent_task(pool).await? else {
+            continue;
+        };
+        if project_id.is_some_and(|project_id| project_id != task.project_id) {
+            continue;
+        }
+        let entries = deployment.container().normalized_entries(&process).await?;
+        let activity = AgentActivity::from_entries(&entries);
+        presences.push(AgentPresence {
+            execution_process_id: process.id,
+            task_attempt_id: task_attempt.id,
+            task_id: task.id,
+            project_id: task.project_id,
+            task_title: task.title,
+            profile: label.profile,
+            variant: label.variant,
+            started_at: process.started_at,
+            elapsed_secs: (now - process.started_at).num_seconds(),
+            activity: activity.activity,
+            last_tool_call: activity.last_tool_call,
+        });
+    }
+    Ok(presences)
+}
+
+pub async fn get_presence(
+    State(deployment): State<DeploymentImpl>,
+    Query(query): Query<PresenceQuery>,
+) -> Result<ResponseJson<ApiResponse<Vec<AgentPresence>>>, ApiError> {
+    let presences = load_presence(&deployment, query.project_id).await?;
+    Ok(ResponseJson(ApiResponse::success(presences)))
+}
+
+/// GET /presence/status-line: the running agents as one line of plain text, for a terminal
+/// status bar, e.g. `set -g status-right "#(curl -s localhost:PORT/api/presence/status-line)"`
+pub async fn get_status_line(
+    State(deployment): State<DeploymentImpl>,
+    Query(query): Query<PresenceQuery>,
+) -> Result<String, ApiError> {
+    let presences = load_presence(&deployment, query.project_id).await?;
+    Ok(presence::status_line(&presences))
+}
+
+pub fn router() -> Router<DeploymentImpl> {
+    Router::new()
+        .route("/presence", get(get_presence))
+        .route("/presence/status-line", get(get_status_line))
+}
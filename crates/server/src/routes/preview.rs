@@ -0,0 +1,512 @@
+//! Reverse proxy serving an attempt's dev server under `/preview/{attempt_id}/`, so a
+//! preview only needs the port vibe-kanban already listens on.
+//!
+//! Paths are forwarded without the prefix. Redirects, cookie paths and root-relative
+//! links in HTML are rewritten to keep the browser under the prefix; apps that build
+//! absolute paths in scripts work best with a relative base, e.g. vite's `base: './'`.
+//!
+//! Previews share the API's origin, so every proxied page is sandboxed into an opaque
+//! origin of its own: its scripts run, but can't call the API with the user's authority.
+
+use std::{
+    collections::HashMap,
+    sync::{Arc, OnceLock},
+    time::{Duration, Instant},
+};
+
+use axum::{
+    body::Body,
+    extract::{
+        ws::{CloseFrame, Message, WebSocket, WebSocketUpgrade},
+        FromRequestParts, Path, Request, State,
+    },
+    http::{header, HeaderMap, HeaderName, HeaderValue, StatusCode},
+    response::{IntoResponse, Redirect, Response},
+    routing::any,
+    Extension, Router,
+};
+use db::models::{
+    execution_process::{ExecutionProcess, ExecutionProcessRunReason, ExecutionProcessStatus},
+    task_attempt::TaskAttempt,
+};
+use deployment::Deployment;
+use futures_util::{SinkExt, StreamExt};
+use regex::Regex;
+use serde::Deserialize;
+use services::services::dev_server::DevServerUrl;
+use tokio::sync::Mutex;
+use tokio_tungstenite::tungstenite::{
+    self, client::IntoClientRequest, protocol::frame::coding::CloseCode,
+};
+use uuid::Uuid;
+
+use crate::DeploymentImpl;
+
+/// How long a resolved dev server address is reused, a page load makes many requests
+const TARGET_TTL: Duration = Duration::from_secs(2);
+
+/// Sandbox without `allow-same-origin`, keeping the previewed app out of the API's origin
+const PREVIEW_CSP: &str = "sandbox allow-scripts allow-forms allow-popups";
+
+/// Headers that describe a single connection and must not be forwarded
+const HOP_BY_HOP_HEADERS: [&str; 8] = [
+    "connection",
+    "keep-alive",
+    "proxy-authenticate",
+    "proxy-authorization",
+    "te",
+    "trailer",
+    "transfer-encoding",
+    "upgrade",
+];
+
+#[derive(Clone)]
+pub struct PreviewProxy {
+    client: reqwest::Client,
+    targets: Arc<Mutex<HashMap<Uuid, (DevServerUrl, Instant)>>>,
+}
+
+impl PreviewProxy {
+    pub fn new() -> Self {
+        let client = reqwest::Client::builder()
+            // Redirects go back to the browser, rewritten to stay under the prefix
+            .redirect(reqwest::redirect::Policy::none())
+            // Dev servers on https use self-signed certificates, and only local ports are proxied
+            .danger_accept_invalid_certs(true)
+            .build()
+            .expect("Failed to build preview proxy client");
+        Self {
+            client,
+            targets: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    /// Address of the attempt's running dev server
+    async fn target(
+        &self,
+        deployment: &DeploymentImpl,
+        attempt_id: Uuid,
+    ) -> Result<DevServerUrl, Response> {
+        if let Some((target, resolved_at)) = self.targets.lock().await.get(&attempt_id) {
+            if resolved_at.elapsed() < TARGET_TTL {
+                return Ok(target.clone());
+            }
+        }
+
+        let pool = &deployment.db().pool;
+        let attempt = TaskAttempt::find_by_id(pool, attempt_id)
+            .await
+            .map_err(|e| error_page(StatusCode::INTERNAL_SERVER_ERROR, &e.to_string()))?
+            .ok_or_else(|| error_page(StatusCode::NOT_FOUND, "Task attempt not found"))?;
+        let dev_server = ExecutionProcess::find_latest_by_task_attempt_and_run_reason(
+            pool,
+            attempt_id,
+            &ExecutionProcessRunReason::DevServer,
+        )
+        .await
+        .map_err(|e| error_page(StatusCode::INTERNAL_SERVER_ERROR, &e.to_string()))?
+        .filter(|process| process.status == ExecutionProcessStatus::Running);
+        let (Some(dev_server), Some(worktree)) = (dev_server, attempt.container_ref.as_deref())
+        else {
+            return Err(error_page(
+                StatusCode::BAD_GATEWAY,
+                "No dev server is running for this attempt",
+            ));
+        };
+        let target = deployment
+            .dev_servers()
+            .preview_target(dev_server.id, std::path::Path::new(worktree))
+            .await
+            .ok_or_else(|| {
+                error_page(
+                    StatusCode::BAD_GATEWAY,
+                    "The dev server isn't listening on a port yet",
+                )
+            })?;
+
+        self.targets
+            .lock()
+            .await
+            .insert(attempt_id, (target.clone(), Instant::now()));
+        Ok(target)
+    }
+}
+
+impl Default for PreviewProxy {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct PreviewParams {
+    attempt_id: Uuid,
+}
+
+pub fn router(deployment: &DeploymentImpl) -> Router {
+    Router::new()
+        .route("/preview/{attempt_id}", any(redirect_to_root))
+        .route("/preview/{attempt_id}/", any(proxy_preview))
+        .route("/preview/{attempt_id}/{*path}", any(proxy_preview))
+        .layer(Extension(PreviewProxy::new()))
+        .with_state(deployment.clone())
+}
+
+fn preview_prefix(attempt_id: Uuid) -> String {
+    format!("/preview/{attempt_id}")
+}
+
+/// Relative links on the dev server's root page only resolve with a trailing slash
+async fn redirect_to_root(Path(params): Path<PreviewParams>) -> Redirect {
+    Redirect::permanent(&format!("{}/", preview_prefix(params.attempt_id)))
+}
+
+async fn proxy_preview(
+    State(deployment): State<DeploymentImpl>,
+    Extension(proxy): Extension<PreviewProxy>,
+    Path(params): Path<PreviewParams>,
+    request: Request,
+) -> Response {
+    let target = match proxy.target(&deployment, params.attempt_id).await {
+        Ok(target) => target,
+        Err(response) => return response,
+    };
+    let prefix = preview_prefix(params.attempt_id);
+    let path_and_query = request
+        .uri()
+        .path_and_query()
+        .map(|p| p.as_str())
+        .unwrap_or("/");
+    let upstream_path = match path_and_query.strip_prefix(&prefix) {
+        Some(rest) if rest.starts_with('/') => rest.to_string(),
+        Some(rest) => format!("/{rest}"),
+        None => "/".to_string(),
+    };
+
+    let is_websocket = request
+        .headers()
+        .get(header::UPGRADE)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|v| v.eq_ignore_ascii_case("websocket"));
+    if is_websocket {
+        let (mut parts, _) = request.into_parts();
+        return match WebSocketUpgrade::from_request_parts(&mut parts, &()).await {
+            Ok(ws) => proxy_websocket(ws, &parts.headers, &target, &upstream_path).await,
+            Err(rejection) => rejection.into_response(),
+        };
+    }
+
+    match proxy_http(&proxy.client, request, &target, &upstream_path, &prefix).await {
+        Ok(response) => response,
+        Err(e) => {
+            tracing::debug!("Preview request to {} failed: {}", target.url, e);
+            error_page(
+                StatusCode::BAD_GATEWAY,
+                &format!("Failed to reach the dev server: {e}"),
+            )
+        }
+    }
+}
+
+async fn proxy_http(
+    client: &reqwest::Client,
+    request: Request,
+    target: &DevServerUrl,
+    upstream_path: &str,
+    prefix: &str,
+) -> Result<Response, reqwest::Error> {
+    let (parts, body) = request.into_parts();
+    let mut headers = forwardable_headers(&parts.headers);
+    // HTML is rewritten, so ask for it uncompressed
+    headers.remove(header::ACCEPT_ENCODING);
+    headers.remove(header::HOST);
+    if let Some(host) = parts.headers.get(header::HOST) {
+        headers.insert("x-forwarded-host", host.clone());
+    }
+    if let Ok(prefix) = HeaderValue::from_str(prefix) {
+        headers.insert("x-forwarded-prefix", prefix);
+    }
+
+    let upstream = client
+        .request(parts.method, format!("{}{}", target.url, upstream_path))
+        .headers(headers)
+        .body(reqwest::Body::wrap_stream(body.into_data_stream()))
+        .send()
+        .await?;
+
+    let status = upstream.status();
+    let mut headers = forwardable_headers(upstream.headers());
+    if let Some(location) = headers
+        .get(header::LOCATION)
+        .and_then(|v| v.to_str().ok())
+        .map(|location| rewrite_location(location, target, prefix))
+        .and_then(|location| HeaderValue::from_str(&location).ok())
+    {
+        headers.insert(header::LOCATION, location);
+    }
+    let cookies: Vec<HeaderValue> = headers
+        .get_all(header::SET_COOKIE)
+        .iter()
+        .filter_map(|cookie| cookie.to_str().ok())
+        .filter_map(|cookie| HeaderValue::from_str(&rewrite_cookie_path(cookie, prefix)).ok())
+        .collect();
+    headers.remove(header::SET_COOKIE);
+    for cookie in cookies {
+        headers.append(header::SET_COOKIE, cookie);
+    }
+    sandbox(&mut headers);
+
+    let is_html = headers
+        .get(header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|v| v.starts_with("text/html"));
+    let body = if is_html {
+        headers.remove(header::CONTENT_LENGTH);
+        Body::from(rewrite_html(&upstream.text().await?, prefix))
+    } else {
+        Body::from_stream(upstream.bytes_stream())
+    };
+
+    let mut response = Response::new(body);
+    *response.status_mut() = status;
+    *response.headers_mut() = headers;
+    Ok(response)
+}
+
+async fn proxy_websocket(
+    ws: WebSocketUpgrade,
+    headers: &HeaderMap,
+    target: &DevServerUrl,
+    upstream_path: &str,
+) -> Response {
+    let upstream_url = format!("{}{}", target.url.replacen("http", "ws", 1), upstream_path);
+    let mut upstream_request = match upstream_url.as_str().into_client_request() {
+        Ok(request) => request,
+        Err(e) => return error_page(StatusCode::BAD_GATEWAY, &e.to_string()),
+    };
+    // Dev servers pick their HMR socket by subprotocol, e.g. `vite-hmr`
+    if let Some(protocols) = headers.get(header::SEC_WEBSOCKET_PROTOCOL) {
+        upstream_request
+            .headers_mut()
+            .insert(header::SEC_WEBSOCKET_PROTOCOL, protocols.clone());
+    }
+
+    let (upstream, upstream_response) =
+        match tokio_tungstenite::connect_async(upstream_request).await {
+            Ok(connection) => connection,
+            Err(e) => {
+                return error_page(
+                    StatusCode::BAD_GATEWAY,
+                    &format!("Failed to reach the dev server: {e}"),
+                )
+            }
+        };
+    let selected: Vec<String> = upstream_response
+        .headers()
+        .get(header::SEC_WEBSOCKET_PROTOCOL)
+        .and_then(|v| v.to_str().ok())
+        .map(|v| v.to_string())
+        .into_iter()
+        .collect();
+
+    ws.protocols(selected)
+        .on_upgrade(move |socket| pump_websocket(socket, upstream))
+}
+
+async fn pump_websocket(
+    socket: WebSocket,
+    upstream: tokio_tungstenite::WebSocketStream<
+        tokio_tungstenite::MaybeTlsStream<tokio::net::TcpStream>,
+    >,
+) {
+    let (mut client_tx, mut client_rx) = socket.split();
+    let (mut upstream_tx, mut upstream_rx) = upstream.split();
+
+    let to_upstream = async {
+        while let Some(Ok(message)) = client_rx.next().await {
+            let close = matches!(message, Message::Close(_));
+            if upstream_tx
+                .send(to_upstream_message(message))
+                .await
+                .is_err()
+                || close
+            {
+                break;
+            }
+        }
+    };
+    let to_client = async {
+        while let Some(Ok(message)) = upstream_rx.next().await {
+            let Some(message) = to_client_message(message) else {
+                continue;
+            };
+            let close = matches!(message, Message::Close(_));
+            if client_tx.send(message).await.is_err() || close {
+                break;
+            }
+        }
+    };
+    // Either side closing ends the proxied connection
+    tokio::select! {
+        _ = to_upstream => {}
+        _ = to_client => {}
+    }
+}
+
+fn to_upstream_message(message: Message) -> tungstenite::Message {
+    match message {
+        Message::Text(text) => tungstenite::Message::text(text.as_str()),
+        Message::Binary(data) => tungstenite::Message::Binary(data),
+        Message::Ping(data) => tungstenite::Message::Ping(data),
+        Message::Pong(data) => tungstenite::Message::Pong(data),
+        Message::Close(frame) => {
+            tungstenite::Message::Close(frame.map(|frame| tungstenite::protocol::CloseFrame {
+                code: CloseCode::from(frame.code),
+                reason: frame.reason.as_str().into(),
+            }))
+        }
+    }
+}
+
+fn to_client_message(message: tungstenite::Message) -> Option<Message> {
+    Some(match message {
+        tungstenite::Message::Text(text) => Message::Text(text.as_str().into()),
+        tungstenite::Message::Binary(data) => Message::Binary(data),
+        tungstenite::Message::Ping(data) => Message::Ping(data),
+        tungstenite::Message::Pong(data) => Message::Pong(data),
+        tungstenite::Message::Close(frame) => Message::Close(frame.map(|frame| CloseFrame {
+            code: frame.code.into(),
+            reason: frame.reason.as_str().into(),
+        })),
+        tungstenite::Message::Frame(_) => return None,
+    })
+}
+
+fn forwardable_headers(headers: &HeaderMap) -> HeaderMap {
+    let mut forwarded = headers.clone();
+    for name in HOP_BY_HOP_HEADERS {
+        forwarded.remove(HeaderName::from_static(name));
+    }
+    forwarded
+}
+
+/// Added next to any policy the dev server sends, browsers enforce all of them
+fn sandbox(headers: &mut HeaderMap) {
+    headers.append(
+        header::CONTENT_SECURITY_POLICY,
+        HeaderValue::from_static(PREVIEW_CSP),
+    );
+}
+
+fn error_page(status: StatusCode, message: &str) -> Response {
+    (status, message.to_string()).into_response()
+}
+
+/// Keep redirects to the dev server itself under the preview prefix
+fn rewrite_location(location: &str, target: &DevServerUrl, prefix: &str) -> String {
+    let origins = [
+        target.url.clone(),
+        target.url.replacen("localhost", "127.0.0.1", 1),
+    ];
+    let path = origins
+        .iter()
+        .find_map(|origin| location.strip_prefix(origin.as_str()))
+        .unwrap_or(location);
+    if path.starts_with('/') && !path.starts_with("//") {
+        format!("{prefix}{path}")
+    } else if path.is_empty() {
+        format!("{prefix}/")
+    } else {
+        path.to_string()
+    }
+}
+
+fn rewrite_cookie_path(cookie: &str, prefix: &str) -> String {
+    static COOKIE_PATH: OnceLock<Regex> = OnceLock::new();
+    COOKIE_PATH
+        .get_or_init(|| Regex::new(r"(?i)(;\s*path=)/").unwrap())
+        .replace(cookie, format!("${{1}}{prefix}/"))
+        .into_owned()
+}
+
+/// Point root-relative `src`, `href` and `action` attributes at the preview prefix
+fn rewrite_html(html: &str, prefix: &str) -> String {
+    static ROOT_RELATIVE_LINK: OnceLock<Regex> = OnceLock::new();
+    ROOT_RELATIVE_LINK
+        .get_or_init(|| {
+            Regex::new(r#"(?i)(\s(?:src|href|action)\s*=\s*["'])/([^/]|["'])"#).unwrap()
+        })
+        .replace_all(html, format!("${{1}}{prefix}/${{2}}"))
+        .into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use services::services::dev_server::PortSource;
+
+    use super::*;
+
+    fn target() -> DevServerUrl {
+        DevServerUrl {
+            port: 5173,
+            url: "http://localhost:5173".to_string(),
+            source: PortSource::Logs,
+            listening: true,
+        }
+    }
+
+    #[test]
+    fn test_rewrite_html_links() {
+        let html = r#"<script type="module" src="/@vite/client"></script>
+<link href='/style.css'><a href="/">home</a><a href="//cdn.example.com/x.js">cdn</a>
+<img src="logo.png"><form action="/login">"#;
+        assert_eq!(
+            rewrite_html(html, "/preview/abc"),
+            r#"<script type="module" src="/preview/abc/@vite/client"></script>
+<link href='/preview/abc/style.css'><a href="/preview/abc/">home</a><a href="//cdn.example.com/x.js">cdn</a>
+<img src="logo.png"><form action="/preview/abc/login">"#
+        );
+    }
+
+    #[test]
+    fn test_rewrite_redirects_and_cookies() {
+        let prefix = "/preview/abc";
+        assert_eq!(
+            rewrite_location("/login?next=/", &target(), prefix),
+            "/preview/abc/login?next=/"
+        );
+        assert_eq!(
+            rewrite_location("http://localhost:5173/docs", &target(), prefix),
+            "/preview/abc/docs"
+        );
+        assert_eq!(
+            rewrite_location("http://127.0.0.1:5173", &target(), prefix),
+            "/preview/abc/"
+        );
+        assert_eq!(
+            rewrite_location("https://github.com/login", &target(), prefix),
+            "https://github.com/login"
+        );
+        assert_eq!(
+            rewrite_cookie_path("session=1; Path=/; HttpOnly", prefix),
+            "session=1; Path=/preview/abc/; HttpOnly"
+        );
+    }
+
+    #[test]
+    fn test_sandbox_keeps_the_dev_server_policy() {
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            header::CONTENT_SECURITY_POLICY,
+            HeaderValue::from_static("default-src 'self'"),
+        );
+        sandbox(&mut headers);
+        let policies: Vec<_> = headers
+            .get_all(header::CONTENT_SECURITY_POLICY)
+            .iter()
+            .map(|v| v.to_str().unwrap())
+            .collect();
+        assert_eq!(policies, ["default-src 'self'", PREVIEW_CSP]);
+        assert!(!PREVIEW_CSP.contains("allow-same-origin"));
+    }
+}
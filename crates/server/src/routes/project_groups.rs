@@ -0,0 +1,214 @@
+use axum::{
+    extract::State,
+    middleware::from_fn_with_state,
+    response::Json as ResponseJson,
+    routing::{get, post},
+    Extension, Json, Router,
+};
+use db::models::{
+    project::Project,
+    project_group::{CreateProjectGroup, ProjectGroup, UpdateProjectGroup},
+    task::{CreateTask, Task, TaskWithAttemptStatus},
+};
+use deployment::Deployment;
+use serde::{Deserialize, Serialize};
+use sqlx::Error as SqlxError;
+use ts_rs::TS;
+use utils::response::ApiResponse;
+use uuid::Uuid;
+
+use crate::{
+    error::ApiError, middleware::load_project_group_middleware, routes::tasks::start_new_task,
+    DeploymentImpl,
+};
+
+/// One project's tasks on a group board
+#[derive(Debug, Serialize, TS)]
+pub struct ProjectSwimlane {
+    pub project: Project,
+    pub tasks: Vec<TaskWithAttemptStatus>,
+}
+
+#[derive(Debug, Serialize, TS)]
+pub struct ProjectGroupBoard {
+    pub group: ProjectGroup,
+    pub swimlanes: Vec<ProjectSwimlane>,
+}
+
+/// Create the same task in several projects of a group and start an attempt in each
+#[derive(Debug, Deserialize, TS)]
+pub struct CreateGroupTask {
+    pub title: String,
+    pub description: Option<String>,
+    /// Defaults to every project in the group
+    pub project_ids: Option<Vec<Uuid>>,
+}
+
+pub async fn get_project_groups(
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Vec<ProjectGroup>>>, ApiError> {
+    let groups = ProjectGroup::find_all(&deployment.db().pool).await?;
+    Ok(ResponseJson(ApiResponse::success(groups)))
+}
+
+pub async fn get_project_group(
+    Extension(group): Extension<ProjectGroup>,
+) -> Result<ResponseJson<ApiResponse<ProjectGroup>>, ApiError> {
+    Ok(ResponseJson(ApiResponse::success(group)))
+}
+
+pub async fn create_project_group(
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<CreateProjectGroup>,
+) -> Result<ResponseJson<ApiResponse<ProjectGroup>>, ApiError> {
+    if payload.name.trim().is_empty() {
+        return Err(ApiError::BadRequest(
+            "Project group name must not be empty".to_string(),
+        ));
+    }
+    ensure_projects_exist(&deployment, &payload.project_ids).await?;
+
+    let group = ProjectGroup::create(&deployment.db().pool, &payload)
+        .await
+        .map_err(name_taken)?;
+    Ok(ResponseJson(ApiResponse::success(group)))
+}
+
+/// Group names are unique, the database rejects a second group with the same one
+fn name_taken(e: SqlxError) -> ApiError {
+    match e.as_database_error() {
+        Some(db_error) if db_error.is_unique_violation() => ApiError::ProjectGroupNameTaken,
+        _ => ApiError::Database(e),
+    }
+}
+
+pub async fn update_project_group(
+    Extension(group): Extension<ProjectGroup>,
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<UpdateProjectGroup>,
+) -> Result<ResponseJson<ApiResponse<ProjectGroup>>, ApiError> {
+    if payload.name.as_ref().is_some_and(|n| n.trim().is_empty()) {
+        return Err(ApiError::BadRequest(
+            "Project group name must not be empty".to_string(),
+        ));
+    }
+    if let Some(project_ids) = &payload.project_ids {
+        ensure_projects_exist(&deployment, project_ids).await?;
+    }
+
+    let group = ProjectGroup::update(&deployment.db().pool, group.id, &payload)
+        .await
+        .map_err(name_taken)?;
+    Ok(ResponseJson(ApiResponse::success(group)))
+}
+
+pub async fn delete_project_group(
+    Extension(group): Extension<ProjectGroup>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
+    let rows_affected = ProjectGroup::delete(&deployment.db().pool, group.id).await?;
+    if rows_affected == 0 {
+        Err(ApiError::Database(SqlxError::RowNotFound))
+    } else {
+        Ok(ResponseJson(ApiResponse::success(())))
+    }
+}
+
+pub async fn get_project_group_board(
+    Extension(group): Extension<ProjectGroup>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<ProjectGroupBoard>>, ApiError> {
+    let pool = &deployment.db().pool;
+    let mut swimlanes = Vec::with_capacity(group.project_ids.len());
+    for project_id in &group.project_ids {
+        let Some(project) = Project::find_by_id(pool, *project_id).await? else {
+            continue;
+        };
+        let tasks = Task::find_by_project_id_with_attempt_status(pool, project.id).await?;
+        swimlanes.push(ProjectSwimlane { project, tasks });
+    }
+
+    Ok(ResponseJson(ApiResponse::success(ProjectGroupBoard {
+        group,
+        swimlanes,
+    })))
+}
+
+pub async fn create_group_task(
+    Extension(group): Extension<ProjectGroup>,
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<CreateGroupTask>,
+) -> Result<ResponseJson<ApiResponse<Vec<TaskWithAttemptStatus>>>, ApiError> {
+    let project_ids = payload
+        .project_ids
+        .unwrap_or_else(|| group.project_ids.clone());
+    if project_ids.is_empty() {
+        return Err(ApiError::BadRequest(
+            "No projects selected for the task".to_string(),
+        ));
+    }
+    if let Some(outsider) = project_ids
+        .iter()
+        .find(|id| !group.project_ids.contains(id))
+    {
+        return Err(ApiError::BadRequest(format!(
+            "Project {outsider} is not part of group '{}'",
+            group.name
+        )));
+    }
+
+    let mut tasks = Vec::with_capacity(project_ids.len());
+    for project_id in project_ids {
+        let create = CreateTask {
+            project_id,
+            title: payload.title.clone(),
+            description: payload.description.clone(),
+            parent_task_attempt: None,
+            priority: None,
+            labels: Vec::new(),
+            base_branch: None,
+        };
+        tasks.push(start_new_task(&deployment, &create, None).await?);
+    }
+
+    Ok(ResponseJson(ApiResponse::success(tasks)))
+}
+
+async fn ensure_projects_exist(
+    deployment: &DeploymentImpl,
+    project_ids: &[Uuid],
+) -> Result<(), ApiError> {
+    for project_id in project_ids {
+        if Project::find_by_id(&deployment.db().pool, *project_id)
+            .await?
+            .is_none()
+        {
+            return Err(ApiError::BadRequest(format!(
+                "Project {project_id} does not exist"
+            )));
+        }
+    }
+    Ok(())
+}
+
+pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
+    let group_router = Router::new()
+        .route(
+            "/",
+            get(get_project_group)
+                .put(update_project_group)
+                .delete(delete_project_group),
+        )
+        .route("/board", get(get_project_group_board))
+        .route("/tasks", post(create_group_task))
+        .layer(from_fn_with_state(
+            deployment.clone(),
+            load_project_group_middleware,
+        ));
+
+    let inner = Router::new()
+        .route("/", get(get_project_groups).post(create_project_group))
+        .nest("/{group_id}", group_router);
+
+    Router::new().nest("/project-groups", inner)
+}
@@ -1,29 +1,92 @@
-use std::{collections::HashMap, path::Path};
+use std::{
+    collections::{HashMap, HashSet},
+    path::Path,
+};
 
 use axum::{
+    body::Bytes,
     extract::{Query, State},
     http::StatusCode,
     middleware::from_fn_with_state,
-    response::Json as ResponseJson,
-    routing::{get, post},
-    Extension, Json, Router,
+    response::{
+        sse::{Event, KeepAlive},
+        Json as ResponseJson, Sse,
+    },
+    routing::{get, post, put},
+    BoxError, Extension, Json, Router,
 };
-use db::models::project::{
-    CreateProject, Project, ProjectError, SearchMatchType, SearchResult, UpdateProject,
+use db::models::{
+    branch_sync::{ProjectBranchSync, UpdateProjectBranchSync},
+    ci_triage::{ProjectCiTriage, UpdateProjectCiTriage},
+    dependency_update::{
+        DependencyUpdateRun, ProjectDependencyUpdates, UpdateProjectDependencyUpdates,
+    },
+    merge_queue::MergeQueueEntry,
+    project::{CreateProject, Project, ProjectError, SearchMatchType, SearchResult, UpdateProject},
+    project_changelog::{ProjectChangelog, UpdateProjectChangelog},
+    project_context_packing::{ProjectContextPacking, UpdateProjectContextPacking},
+    project_dependency_cache::{ProjectDependencyCache, UpdateProjectDependencyCache},
+    project_devcontainer::{ProjectDevcontainer, UpdateProjectDevcontainer},
+    project_env_var::{ProjectEnvVar, UpdateProjectEnv},
+    project_nix_env::{ProjectNixEnv, UpdateProjectNixEnv},
+    project_path_rules::{ProjectPathRules, UpdateProjectPathRules},
+    project_reviewer::{ProjectReviewer, UpdateProjectReviewer},
+    project_worktree_pool::{ProjectWorktreePool, UpdateProjectWorktreePool},
+    task::{CreateTask, Task},
 };
 use deployment::Deployment;
+use executors::{executors::CodingAgent, profile::ProfileVariantLabel};
+use futures_util::{Stream, StreamExt};
 use ignore::WalkBuilder;
-use services::services::git::GitBranch;
-use utils::response::ApiResponse;
+use services::services::{
+    board, changelog,
+    conflicts::AttemptConflict,
+    container::ContainerService,
+    context_pack::ContextPacker,
+    cost_estimate::{self, CostEstimate, CostEstimateRequest},
+    dependency_updates,
+    devcontainer::ProjectDevcontainerStatus,
+    filesystem::FilesystemError,
+    git::{GitBranch, GitServiceError},
+    github_auth,
+    nix_env::ProjectNixEnvStatus,
+    path_rules::PathRules,
+    project_env,
+    project_templates::{self, CreateProjectFromTemplate, ProjectTemplate, TemplateSource},
+    repo_clone::{self, CloneEvent, CloneJob, CloneProject, RepoCloneError},
+    script_detection::{self, DetectedScripts, ScriptKind},
+    secrets::SecretsStore,
+    workspace_scan::{self, DiscoveredRepo},
+    worktree_pool::{WorktreePoolStatus, MAX_POOL_SIZE},
+    worktree_templates::{WorktreeTemplate, WorktreeTemplateList, WorktreeTemplates},
+};
+use utils::{
+    i18n::{Message, MessageCode},
+    pagination::{ListQuery, Paginated},
+    response::ApiResponse,
+};
 use uuid::Uuid;
 
-use crate::{error::ApiError, middleware::load_project_middleware, DeploymentImpl};
+use crate::{
+    error::ApiError,
+    middleware::load_project_middleware,
+    routes::{
+        disk_usage::{
+            collect_project_worktrees, get_project_disk_usage, update_project_disk_quota,
+        },
+        editor::{self, EditorOpenResponse},
+        spend::update_project_budget,
+    },
+    DeploymentImpl,
+};
 
 pub async fn get_projects(
     State(deployment): State<DeploymentImpl>,
-) -> Result<ResponseJson<ApiResponse<Vec<Project>>>, ApiError> {
+    list: ListQuery,
+) -> Result<ResponseJson<ApiResponse<Paginated<Project>>>, ApiError> {
     let projects = Project::find_all(&deployment.db().pool).await?;
-    Ok(ResponseJson(ApiResponse::success(projects)))
+    let page = list.apply(projects).map_err(ApiError::BadRequest)?;
+    Ok(ResponseJson(ApiResponse::paginated(page)))
 }
 
 pub async fn get_project(
@@ -32,17 +95,31 @@ pub async fn get_project(
     Ok(ResponseJson(ApiResponse::success(project)))
 }
 
+#[derive(serde::Deserialize)]
+pub struct BranchesQuery {
+    /// List tags after the branches, for picking where an attempt starts
+    #[serde(default)]
+    include_tags: bool,
+}
+
 pub async fn get_project_branches(
     Extension(project): Extension<Project>,
     State(deployment): State<DeploymentImpl>,
+    Query(query): Query<BranchesQuery>,
 ) -> Result<ResponseJson<ApiResponse<Vec<GitBranch>>>, ApiError> {
-    let branches = deployment.git().get_all_branches(&project.git_repo_path)?;
+    let branches = if query.include_tags {
+        deployment
+            .git()
+            .get_all_branches_and_tags(&project.git_repo_path)?
+    } else {
+        deployment.git().get_all_branches(&project.git_repo_path)?
+    };
     Ok(ResponseJson(ApiResponse::success(branches)))
 }
 
 pub async fn create_project(
     State(deployment): State<DeploymentImpl>,
-    Json(payload): Json<CreateProject>,
+    Json(mut payload): Json<CreateProject>,
 ) -> Result<ResponseJson<ApiResponse<Project>>, ApiError> {
     let id = Uuid::new_v4();
 
@@ -51,9 +128,7 @@ pub async fn create_project(
     // Check if git repo path is already used by another project
     match Project::find_by_git_repo_path(&deployment.db().pool, &payload.git_repo_path).await {
         Ok(Some(_)) => {
-            return Ok(ResponseJson(ApiResponse::error(
-                "A project with this git repository path already exists",
-            )));
+            return Err(ProjectError::GitRepoPathExists.into());
         }
         Ok(None) => {
             // Path is available, continue
@@ -69,31 +144,43 @@ pub async fn create_project(
     if payload.use_existing_repo {
         // For existing repos, validate that the path exists and is a git repository
         if !path.exists() {
-            return Ok(ResponseJson(ApiResponse::error(
-                "The specified path does not exist",
+            return Ok(ResponseJson(ApiResponse::localized_error(
+                MessageCode::PathNotFound,
             )));
         }
 
         if !path.is_dir() {
-            return Ok(ResponseJson(ApiResponse::error(
-                "The specified path is not a directory",
+            return Ok(ResponseJson(ApiResponse::localized_error(
+                MessageCode::PathNotADirectory,
             )));
         }
 
         if !path.join(".git").exists() {
-            return Ok(ResponseJson(ApiResponse::error(
-                "The specified directory is not a git repository",
+            return Ok(ResponseJson(ApiResponse::localized_error(
+                MessageCode::NotAGitRepository,
             )));
         }
 
         // Ensure existing repo has a main branch if it's empty
         if let Err(e) = deployment.git().ensure_main_branch_exists(path) {
             tracing::error!("Failed to ensure main branch exists: {}", e);
-            return Ok(ResponseJson(ApiResponse::error(&format!(
-                "Failed to ensure main branch exists: {}",
-                e
+            return Ok(ResponseJson(ApiResponse::localized_error(Message::detail(
+                MessageCode::MainBranchSetupFailed,
+                e,
             ))));
         }
+
+        // Fill in scripts left empty with confident guesses from the repository's manifests
+        let detected = script_detection::detect_scripts(path);
+        payload.setup_script = payload
+            .setup_script
+            .or_else(|| detected.confident(ScriptKind::Setup));
+        payload.dev_script = payload
+            .dev_script
+            .or_else(|| detected.confident(ScriptKind::Dev));
+        payload.test_script = payload
+            .test_script
+            .or_else(|| detected.confident(ScriptKind::Test));
     } else {
         // For new repos, create directory and initialize git
 
@@ -101,9 +188,9 @@ pub async fn create_project(
         if !path.exists() {
             if let Err(e) = std::fs::create_dir_all(path) {
                 tracing::error!("Failed to create directory: {}", e);
-                return Ok(ResponseJson(ApiResponse::error(&format!(
-                    "Failed to create directory: {}",
-                    e
+                return Ok(ResponseJson(ApiResponse::localized_error(Message::detail(
+                    MessageCode::DirectoryCreateFailed,
+                    e,
                 ))));
             }
         }
@@ -112,9 +199,9 @@ pub async fn create_project(
         if !path.join(".git").exists() {
             if let Err(e) = deployment.git().initialize_repo_with_main_branch(path) {
                 tracing::error!("Failed to initialize git repository: {}", e);
-                return Ok(ResponseJson(ApiResponse::error(&format!(
-                    "Failed to initialize git repository: {}",
-                    e
+                return Ok(ResponseJson(ApiResponse::localized_error(Message::detail(
+                    MessageCode::GitInitFailed,
+                    e,
                 ))));
             }
         }
@@ -124,7 +211,7 @@ pub async fn create_project(
         Ok(project) => {
             // Track project creation event
             deployment
-                .track_if_analytics_allowed(
+                .track_event(
                     "project_created",
                     serde_json::json!({
                         "project_id": project.id.to_string(),
@@ -141,6 +228,228 @@ pub async fn create_project(
     }
 }
 
+/// GET /projects/discover: git repositories in the configured workspace directory that
+/// aren't projects yet, for adding existing checkouts in bulk
+pub async fn discover_projects(
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Vec<DiscoveredRepo>>>, ApiError> {
+    let workspace_dir = deployment.config().read().await.workspace_dir.clone();
+    let Some(dir) = workspace_dir.filter(|dir| !dir.trim().is_empty()) else {
+        return Err(ApiError::BadRequest(
+            "Set a workspace directory in settings to discover repositories".to_string(),
+        ));
+    };
+    let dir = utils::path::expand_tilde(dir.trim());
+    let registered: Vec<_> = Project::find_all(&deployment.db().pool)
+        .await?
+        .into_iter()
+        .map(|project| project.git_repo_path)
+        .collect();
+
+    let repos = tokio::task::spawn_blocking(move || workspace_scan::discover(&dir, &registered))
+        .await
+        .map_err(|e| FilesystemError::Io(std::io::Error::other(e)))??;
+    Ok(ResponseJson(ApiResponse::success(repos)))
+}
+
+pub async fn get_project_templates() -> ResponseJson<ApiResponse<Vec<ProjectTemplate>>> {
+    ResponseJson(ApiResponse::success(project_templates::builtin_templates()))
+}
+
+/// Create a project in a new directory, with a repository scaffolded from a built-in template
+/// or copied from a template repository, and optionally its first task
+pub async fn create_project_from_template(
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<CreateProjectFromTemplate>,
+) -> Result<ResponseJson<ApiResponse<Project>>, ApiError> {
+    let pool = &deployment.db().pool;
+    if Project::find_by_git_repo_path(pool, &payload.git_repo_path)
+        .await
+        .map_err(|e| ProjectError::GitRepoCheckFailed(e.to_string()))?
+        .is_some()
+    {
+        return Err(ProjectError::GitRepoPathExists.into());
+    }
+
+    let git = deployment.git().clone();
+    let path = std::path::PathBuf::from(&payload.git_repo_path);
+    let (name, source) = (payload.name.clone(), payload.template.clone());
+    let template = tokio::task::spawn_blocking(move || {
+        project_templates::create_repository(&git, &path, &name, &source)
+    })
+    .await
+    .map_err(|e| ProjectError::CreateFailed(e.to_string()))??;
+
+    let create = CreateProject {
+        name: payload.name,
+        git_repo_path: payload.git_repo_path,
+        use_existing_repo: true,
+        setup_script: payload
+            .setup_script
+            .or_else(|| template.as_ref().and_then(|t| t.setup_script.clone())),
+        dev_script: payload
+            .dev_script
+            .or_else(|| template.as_ref().and_then(|t| t.dev_script.clone())),
+        cleanup_script: payload.cleanup_script,
+        copy_files: payload.copy_files,
+        test_script: payload
+            .test_script
+            .or_else(|| template.as_ref().and_then(|t| t.test_script.clone())),
+        test_result_format: payload
+            .test_result_format
+            .or_else(|| template.as_ref().and_then(|t| t.test_result_format)),
+    };
+    let project = Project::create(pool, &create, Uuid::new_v4())
+        .await
+        .map_err(|e| ProjectError::CreateFailed(e.to_string()))?;
+
+    if let Some(first_task) = payload.first_task {
+        let task = CreateTask {
+            project_id: project.id,
+            title: first_task.title,
+            description: first_task.description,
+            parent_task_attempt: None,
+            priority: None,
+            labels: Vec::new(),
+            base_branch: None,
+        };
+        Task::create(pool, &task, Uuid::new_v4()).await?;
+    }
+
+    deployment
+        .track_event(
+            "project_created",
+            serde_json::json!({
+                "project_id": project.id.to_string(),
+                "use_existing_repo": false,
+                "template": match &payload.template {
+                    TemplateSource::Empty => "empty".to_string(),
+                    TemplateSource::Builtin { id } => id.clone(),
+                    TemplateSource::Repository { .. } => "repository".to_string(),
+                },
+                "has_setup_script": create.setup_script.is_some(),
+                "has_dev_script": create.dev_script.is_some(),
+            }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(project)))
+}
+
+/// Start cloning a remote repository into a new project, follow it with
+/// [`stream_clone_progress`]
+pub async fn clone_project(
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<CloneProject>,
+) -> Result<ResponseJson<ApiResponse<CloneJob>>, ApiError> {
+    let repo_name = repo_clone::repo_name_from_url(&payload.url)?;
+    let workspace_dir = deployment.config().read().await.workspace_dir.clone();
+    let path = payload
+        .git_repo_path
+        .as_deref()
+        .map(utils::path::expand_tilde)
+        .unwrap_or_else(|| repo_clone::default_clone_path(&repo_name, workspace_dir.as_deref()));
+    let git_repo_path = path.to_string_lossy().to_string();
+    if Project::find_by_git_repo_path(&deployment.db().pool, &git_repo_path)
+        .await
+        .map_err(|e| ProjectError::GitRepoCheckFailed(e.to_string()))?
+        .is_some()
+    {
+        return Err(ProjectError::GitRepoPathExists.into());
+    }
+
+    let token = if payload.url.starts_with("https://github.com/") {
+        let github_config = deployment.config().read().await.github.clone();
+        github_auth::access_token(&github_config).await?
+    } else {
+        None
+    };
+    let (id, events) = deployment.clones().start();
+    let job = CloneJob {
+        id,
+        git_repo_path: git_repo_path.clone(),
+    };
+
+    tokio::spawn(async move {
+        let outcome = async {
+            let cloned =
+                repo_clone::clone_repository(payload.url, path.clone(), token, events.clone())
+                    .await?;
+            let detected = script_detection::detect_scripts(&path);
+            let create = CreateProject {
+                name: payload.name.unwrap_or(repo_name),
+                git_repo_path,
+                use_existing_repo: true,
+                setup_script: payload
+                    .setup_script
+                    .or_else(|| detected.confident(ScriptKind::Setup)),
+                dev_script: payload
+                    .dev_script
+                    .or_else(|| detected.confident(ScriptKind::Dev)),
+                cleanup_script: None,
+                copy_files: None,
+                test_script: payload
+                    .test_script
+                    .or_else(|| detected.confident(ScriptKind::Test)),
+                test_result_format: None,
+            };
+            let project = Project::create(&deployment.db().pool, &create, Uuid::new_v4())
+                .await
+                .map_err(|e| ProjectError::CreateFailed(e.to_string()))?;
+            deployment
+                .track_event(
+                    "project_created",
+                    serde_json::json!({
+                        "project_id": project.id.to_string(),
+                        "use_existing_repo": false,
+                        "cloned": true,
+                        "package_manager": cloned.package_manager,
+                        "has_setup_script": create.setup_script.is_some(),
+                        "has_dev_script": create.dev_script.is_some(),
+                    }),
+                )
+                .await;
+            Ok::<_, ApiError>(CloneEvent::Done {
+                project,
+                default_branch: cloned.default_branch,
+                package_manager: cloned.package_manager,
+            })
+        }
+        .await;
+
+        let event = outcome.unwrap_or_else(|e| {
+            tracing::warn!("Failed to clone project into {}: {}", path.display(), e);
+            CloneEvent::Failed {
+                error: e.to_string(),
+            }
+        });
+        events.send_replace(event);
+        deployment.clones().finish(id);
+    });
+
+    Ok(ResponseJson(ApiResponse::success(job)))
+}
+
+/// Progress of a clone as `clone` events, ending with the created project or the failure
+pub async fn stream_clone_progress(
+    axum::extract::Path(clone_id): axum::extract::Path<Uuid>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<Sse<impl Stream<Item = Result<Event, BoxError>>>, ApiError> {
+    let events = deployment
+        .clones()
+        .events(clone_id)
+        .ok_or(ApiError::RepoClone(RepoCloneError::UnknownClone(clone_id)))?
+        .map(|event| {
+            Event::default()
+                .event("clone")
+                .json_data(event)
+                .map_err(|e| -> BoxError { e.into() })
+        })
+        .boxed();
+
+    Ok(Sse::new(deployment.shutdown().guard_stream(events)).keep_alive(KeepAlive::default()))
+}
+
 pub async fn update_project(
     Extension(existing_project): Extension<Project>,
     State(deployment): State<DeploymentImpl>,
@@ -157,8 +466,8 @@ pub async fn update_project(
             .await
             {
                 Ok(Some(_)) => {
-                    return Ok(ResponseJson(ApiResponse::error(
-                        "A project with this git repository path already exists",
+                    return Ok(ResponseJson(ApiResponse::localized_error(
+                        MessageCode::ProjectRepoPathTaken,
                     )));
                 }
                 Ok(None) => {
@@ -182,6 +491,8 @@ pub async fn update_project(
         dev_script,
         cleanup_script,
         copy_files,
+        test_script,
+        test_result_format,
     } = payload;
 
     let name = name.unwrap_or(existing_project.name);
@@ -197,6 +508,8 @@ pub async fn update_project(
         dev_script,
         cleanup_script,
         copy_files,
+        test_script,
+        test_result_format,
     )
     .await
     {
@@ -212,6 +525,29 @@ pub async fn delete_project(
     Extension(project): Extension<Project>,
     State(deployment): State<DeploymentImpl>,
 ) -> Result<ResponseJson<ApiResponse<()>>, StatusCode> {
+    if let Err(e) = project_env::delete_secrets(
+        &deployment.db().pool,
+        SecretsStore::default_store(),
+        project.id,
+    )
+    .await
+    {
+        tracing::warn!(
+            "Failed to delete the secrets of project {}: {}",
+            project.id,
+            e
+        );
+    }
+    if let Err(e) = WorktreeTemplates::for_project(project.id)
+        .delete_all()
+        .await
+    {
+        tracing::warn!(
+            "Failed to delete the templates of project {}: {}",
+            project.id,
+            e
+        );
+    }
     match Project::delete(&deployment.db().pool, project.id).await {
         Ok(rows_affected) => {
             if rows_affected == 0 {
@@ -236,8 +572,8 @@ pub async fn open_project_in_editor(
     Extension(project): Extension<Project>,
     State(deployment): State<DeploymentImpl>,
     Json(payload): Json<Option<OpenEditorRequest>>,
-) -> Result<ResponseJson<ApiResponse<()>>, StatusCode> {
-    let path = project.git_repo_path.to_string_lossy();
+) -> Result<ResponseJson<ApiResponse<EditorOpenResponse>>, StatusCode> {
+    let path = &project.git_repo_path;
 
     let editor_config = {
         let config = deployment.config().read().await;
@@ -245,10 +581,14 @@ pub async fn open_project_in_editor(
         config.editor.with_override(editor_type_str)
     };
 
-    match editor_config.open_file(&path) {
-        Ok(_) => {
-            tracing::info!("Opened editor for project {} at path: {}", project.id, path);
-            Ok(ResponseJson(ApiResponse::success(())))
+    match editor::open_path(&editor_config, path, None, None) {
+        Ok(response) => {
+            tracing::info!(
+                "Opened editor for project {} at path: {}",
+                project.id,
+                path.display()
+            );
+            Ok(ResponseJson(ApiResponse::success(response)))
         }
         Err(e) => {
             tracing::error!("Failed to open editor for project {}: {}", project.id, e);
@@ -264,8 +604,8 @@ pub async fn search_project_files(
     let query = match params.get("q") {
         Some(q) if !q.trim().is_empty() => q.trim(),
         _ => {
-            return Ok(ResponseJson(ApiResponse::error(
-                "Query parameter 'q' is required and cannot be empty",
+            return Ok(ResponseJson(ApiResponse::localized_error(
+                MessageCode::SearchQueryRequired,
             )));
         }
     };
@@ -374,6 +714,444 @@ async fn search_files_in_repo(
     Ok(results)
 }
 
+pub async fn get_project_path_rules(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Option<ProjectPathRules>>>, ApiError> {
+    let rules = ProjectPathRules::find_by_project_id(&deployment.db().pool, project.id).await?;
+    Ok(ResponseJson(ApiResponse::success(rules)))
+}
+
+pub async fn update_project_path_rules(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<UpdateProjectPathRules>,
+) -> Result<ResponseJson<ApiResponse<ProjectPathRules>>, ApiError> {
+    // Refuse globs that would fail every later check
+    PathRules::new(&payload.allowed_paths, &payload.forbidden_paths)?;
+    let rules = ProjectPathRules::upsert(&deployment.db().pool, project.id, &payload).await?;
+    Ok(ResponseJson(ApiResponse::success(rules)))
+}
+
+pub async fn get_project_branch_sync(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Option<ProjectBranchSync>>>, ApiError> {
+    let sync = ProjectBranchSync::find_by_project_id(&deployment.db().pool, project.id).await?;
+    Ok(ResponseJson(ApiResponse::success(sync)))
+}
+
+pub async fn update_project_branch_sync(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<UpdateProjectBranchSync>,
+) -> Result<ResponseJson<ApiResponse<ProjectBranchSync>>, ApiError> {
+    let sync = ProjectBranchSync::upsert(&deployment.db().pool, project.id, &payload).await?;
+    Ok(ResponseJson(ApiResponse::success(sync)))
+}
+
+pub async fn get_project_ci_triage(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Option<ProjectCiTriage>>>, ApiError> {
+    let triage = ProjectCiTriage::find_by_project_id(&deployment.db().pool, project.id).await?;
+    Ok(ResponseJson(ApiResponse::success(triage)))
+}
+
+pub async fn update_project_ci_triage(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+    Json(mut payload): Json<UpdateProjectCiTriage>,
+) -> Result<ResponseJson<ApiResponse<ProjectCiTriage>>, ApiError> {
+    payload.workflows = payload
+        .workflows
+        .iter()
+        .map(|workflow| workflow.trim().to_string())
+        .filter(|workflow| !workflow.is_empty())
+        .collect();
+    payload.branch = payload
+        .branch
+        .map(|branch| branch.trim().to_string())
+        .filter(|branch| !branch.is_empty());
+    let triage = ProjectCiTriage::upsert(&deployment.db().pool, project.id, &payload).await?;
+    Ok(ResponseJson(ApiResponse::success(triage)))
+}
+
+pub async fn get_project_dependency_updates(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Option<ProjectDependencyUpdates>>>, ApiError> {
+    let settings =
+        ProjectDependencyUpdates::find_by_project_id(&deployment.db().pool, project.id).await?;
+    Ok(ResponseJson(ApiResponse::success(settings)))
+}
+
+pub async fn update_project_dependency_updates(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+    Json(mut payload): Json<UpdateProjectDependencyUpdates>,
+) -> Result<ResponseJson<ApiResponse<ProjectDependencyUpdates>>, ApiError> {
+    let trimmed = |patterns: &[String]| -> Vec<String> {
+        patterns
+            .iter()
+            .map(|pattern| pattern.trim().to_string())
+            .filter(|pattern| !pattern.is_empty())
+            .collect()
+    };
+    let mut names = HashSet::new();
+    for group in &mut payload.groups {
+        group.name = group.name.trim().to_string();
+        group.patterns = trimmed(&group.patterns);
+        if group.name.is_empty() || group.patterns.is_empty() {
+            return Err(ApiError::BadRequest(
+                "Every dependency group needs a name and at least one pattern".to_string(),
+            ));
+        }
+        if !names.insert(group.name.to_lowercase()) {
+            return Err(ApiError::BadRequest(format!(
+                "There is more than one dependency group named '{}'",
+                group.name
+            )));
+        }
+    }
+    payload.ignored = trimmed(&payload.ignored);
+    payload.instructions = payload
+        .instructions
+        .map(|instructions| instructions.trim().to_string())
+        .filter(|instructions| !instructions.is_empty());
+    let settings =
+        ProjectDependencyUpdates::upsert(&deployment.db().pool, project.id, &payload).await?;
+    Ok(ResponseJson(ApiResponse::success(settings)))
+}
+
+/// Latest dependency update runs of a project, newest first
+pub async fn get_project_dependency_update_runs(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Vec<DependencyUpdateRun>>>, ApiError> {
+    let runs =
+        DependencyUpdateRun::find_by_project_id(&deployment.db().pool, project.id, 20).await?;
+    Ok(ResponseJson(ApiResponse::success(runs)))
+}
+
+/// Run the dependency updates of a project now instead of waiting for its schedule
+pub async fn run_project_dependency_updates(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Vec<DependencyUpdateRun>>>, ApiError> {
+    let settings = ProjectDependencyUpdates::find_by_project_id(&deployment.db().pool, project.id)
+        .await?
+        .ok_or_else(|| {
+            ApiError::BadRequest("Save the dependency update settings first".to_string())
+        })?;
+    let runs =
+        dependency_updates::start_runs(deployment.container(), deployment.config(), &settings)
+            .await?;
+    deployment
+        .track_event(
+            "dependency_updates_started",
+            serde_json::json!({ "project_id": project.id.to_string(), "groups": runs.len() }),
+        )
+        .await;
+    Ok(ResponseJson(ApiResponse::success(runs)))
+}
+
+pub async fn get_project_changelog(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Option<ProjectChangelog>>>, ApiError> {
+    let settings = ProjectChangelog::find_by_project_id(&deployment.db().pool, project.id).await?;
+    Ok(ResponseJson(ApiResponse::success(settings)))
+}
+
+pub async fn update_project_changelog(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+    Json(mut payload): Json<UpdateProjectChangelog>,
+) -> Result<ResponseJson<ApiResponse<ProjectChangelog>>, ApiError> {
+    payload.directory = changelog::fragment_dir(&payload.directory)?
+        .to_string_lossy()
+        .into_owned();
+    let settings = ProjectChangelog::upsert(&deployment.db().pool, project.id, &payload).await?;
+    Ok(ResponseJson(ApiResponse::success(settings)))
+}
+
+pub async fn get_project_reviewer(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Option<ProjectReviewer>>>, ApiError> {
+    let reviewer = ProjectReviewer::find_by_project_id(&deployment.db().pool, project.id).await?;
+    Ok(ResponseJson(ApiResponse::success(reviewer)))
+}
+
+pub async fn update_project_reviewer(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<UpdateProjectReviewer>,
+) -> Result<ResponseJson<ApiResponse<ProjectReviewer>>, ApiError> {
+    CodingAgent::from_profile_variant_label(&ProfileVariantLabel {
+        profile: payload.profile.clone(),
+        variant: payload.variant.clone(),
+    })
+    .map_err(|e| ApiError::BadRequest(e.to_string()))?;
+    let reviewer = ProjectReviewer::upsert(&deployment.db().pool, project.id, &payload).await?;
+    Ok(ResponseJson(ApiResponse::success(reviewer)))
+}
+
+/// What an attempt with the prompt and profile might cost, shown before it's started
+pub async fn estimate_project_cost(
+    Extension(project): Extension<Project>,
+    Json(payload): Json<CostEstimateRequest>,
+) -> Result<ResponseJson<ApiResponse<CostEstimate>>, ApiError> {
+    let agent = CodingAgent::from_profile_variant_label(&payload.profile_variant_label)
+        .map_err(|e| ApiError::BadRequest(e.to_string()))?;
+    let repo_tokens = match payload.repo_tokens {
+        Some(tokens) => tokens as u64,
+        None => {
+            let repo_path = project.git_repo_path.clone();
+            tokio::task::spawn_blocking(move || cost_estimate::repo_tokens(&repo_path))
+                .await
+                .map_err(|e| GitServiceError::IoError(std::io::Error::other(e)))??
+        }
+    };
+    Ok(ResponseJson(ApiResponse::success(cost_estimate::estimate(
+        &agent,
+        payload.model,
+        &payload.prompt,
+        repo_tokens,
+    ))))
+}
+
+pub async fn get_project_context_packing(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Option<ProjectContextPacking>>>, ApiError> {
+    let packing =
+        ProjectContextPacking::find_by_project_id(&deployment.db().pool, project.id).await?;
+    Ok(ResponseJson(ApiResponse::success(packing)))
+}
+
+pub async fn update_project_context_packing(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<UpdateProjectContextPacking>,
+) -> Result<ResponseJson<ApiResponse<ProjectContextPacking>>, ApiError> {
+    if payload.token_budget <= 0 || payload.recent_commits < 0 {
+        return Err(ApiError::BadRequest(
+            "The token budget must be positive and recent commits can't be negative".to_string(),
+        ));
+    }
+    // Refuse globs that would fail every attempt's packing
+    ContextPacker::new(&payload.include_paths, 0, false, 0)?;
+    let packing =
+        ProjectContextPacking::upsert(&deployment.db().pool, project.id, &payload).await?;
+    Ok(ResponseJson(ApiResponse::success(packing)))
+}
+
+pub async fn get_project_dependency_cache(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Option<ProjectDependencyCache>>>, ApiError> {
+    let cache =
+        ProjectDependencyCache::find_by_project_id(&deployment.db().pool, project.id).await?;
+    Ok(ResponseJson(ApiResponse::success(cache)))
+}
+
+/// Takes effect for the next process of each attempt, the caches are never emptied
+pub async fn update_project_dependency_cache(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<UpdateProjectDependencyCache>,
+) -> Result<ResponseJson<ApiResponse<ProjectDependencyCache>>, ApiError> {
+    let cache = ProjectDependencyCache::upsert(&deployment.db().pool, project.id, &payload).await?;
+    Ok(ResponseJson(ApiResponse::success(cache)))
+}
+
+pub async fn get_project_devcontainer(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<ProjectDevcontainerStatus>>, ApiError> {
+    let status =
+        ProjectDevcontainerStatus::load(&deployment.db().pool, project.id, &project.git_repo_path)
+            .await?;
+    Ok(ResponseJson(ApiResponse::success(status)))
+}
+
+/// Takes effect for attempts started afterwards, running containers are left alone
+pub async fn update_project_devcontainer(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<UpdateProjectDevcontainer>,
+) -> Result<ResponseJson<ApiResponse<ProjectDevcontainer>>, ApiError> {
+    let settings = ProjectDevcontainer::upsert(&deployment.db().pool, project.id, &payload).await?;
+    Ok(ResponseJson(ApiResponse::success(settings)))
+}
+
+pub async fn get_project_nix_env(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<ProjectNixEnvStatus>>, ApiError> {
+    let status =
+        ProjectNixEnvStatus::load(&deployment.db().pool, project.id, &project.git_repo_path)
+            .await?;
+    Ok(ResponseJson(ApiResponse::success(status)))
+}
+
+/// Takes effect for the next process of each attempt, evaluated shells stay cached
+pub async fn update_project_nix_env(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<UpdateProjectNixEnv>,
+) -> Result<ResponseJson<ApiResponse<ProjectNixEnv>>, ApiError> {
+    let settings = ProjectNixEnv::upsert(&deployment.db().pool, project.id, &payload).await?;
+    Ok(ResponseJson(ApiResponse::success(settings)))
+}
+
+/// Variables set for the project's setup scripts, dev servers and agents, secret values left
+/// out
+pub async fn get_project_env(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Vec<ProjectEnvVar>>>, ApiError> {
+    let vars = ProjectEnvVar::find_by_project_id(&deployment.db().pool, project.id).await?;
+    Ok(ResponseJson(ApiResponse::success(vars)))
+}
+
+/// Takes effect for the next process of each attempt
+pub async fn update_project_env(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<UpdateProjectEnv>,
+) -> Result<ResponseJson<ApiResponse<Vec<ProjectEnvVar>>>, ApiError> {
+    let vars = project_env::save(
+        &deployment.db().pool,
+        SecretsStore::default_store(),
+        project.id,
+        &payload.vars,
+    )
+    .await?;
+    Ok(ResponseJson(ApiResponse::success(vars)))
+}
+
+/// Files written into each new worktree of the project
+pub async fn get_project_worktree_templates(
+    Extension(project): Extension<Project>,
+) -> Result<ResponseJson<ApiResponse<WorktreeTemplateList>>, ApiError> {
+    let list = WorktreeTemplates::for_project(project.id).list().await?;
+    Ok(ResponseJson(ApiResponse::success(list)))
+}
+
+#[derive(serde::Deserialize)]
+pub struct WorktreeTemplateQuery {
+    path: String,
+}
+
+/// Add or replace a template, for worktrees created from now on
+pub async fn upload_project_worktree_template(
+    Extension(project): Extension<Project>,
+    Query(query): Query<WorktreeTemplateQuery>,
+    body: Bytes,
+) -> Result<ResponseJson<ApiResponse<WorktreeTemplate>>, ApiError> {
+    let template = WorktreeTemplates::for_project(project.id)
+        .save(&query.path, &body)
+        .await?;
+    Ok(ResponseJson(ApiResponse::success(template)))
+}
+
+pub async fn delete_project_worktree_template(
+    Extension(project): Extension<Project>,
+    Query(query): Query<WorktreeTemplateQuery>,
+) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
+    WorktreeTemplates::for_project(project.id)
+        .delete(&query.path)
+        .await?;
+    Ok(ResponseJson(ApiResponse::success(())))
+}
+
+pub async fn get_project_worktree_pool(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<WorktreePoolStatus>>, ApiError> {
+    let size = ProjectWorktreePool::find_by_project_id(&deployment.db().pool, project.id)
+        .await?
+        .map_or(0, |pool| pool.size);
+    let status = deployment
+        .container()
+        .worktree_pool()
+        .status(project.id, size);
+    Ok(ResponseJson(ApiResponse::success(status)))
+}
+
+/// Worktrees are set up or removed in the background to match the new size
+pub async fn update_project_worktree_pool(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<UpdateProjectWorktreePool>,
+) -> Result<ResponseJson<ApiResponse<WorktreePoolStatus>>, ApiError> {
+    if !(0..=MAX_POOL_SIZE).contains(&payload.size) {
+        return Err(ApiError::BadRequest(format!(
+            "A project can keep between 0 and {MAX_POOL_SIZE} worktrees ready"
+        )));
+    }
+    let pool = ProjectWorktreePool::upsert(&deployment.db().pool, project.id, payload.size).await?;
+    deployment.container().spawn_fill_worktree_pool(project.id);
+    let status = deployment
+        .container()
+        .worktree_pool()
+        .status(project.id, pool.size);
+    Ok(ResponseJson(ApiResponse::success(status)))
+}
+
+/// Changes to the project's task board as `board` events, starting with every card
+pub async fn stream_project_board(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+) -> Sse<impl Stream<Item = Result<Event, BoxError>>> {
+    let deltas = board::board_stream(
+        deployment.db().clone(),
+        deployment.events().msg_store().clone(),
+        project.id,
+    )
+    .map(|delta| {
+        Event::default()
+            .event("board")
+            .json_data(delta)
+            .map_err(|e| -> BoxError { e.into() })
+    })
+    .boxed();
+
+    Sse::new(deployment.shutdown().guard_stream(deltas)).keep_alive(KeepAlive::default())
+}
+
+/// Setup, dev and test commands proposed from the project repository's manifests
+pub async fn detect_project_scripts(
+    Extension(project): Extension<Project>,
+) -> ResponseJson<ApiResponse<DetectedScripts>> {
+    ResponseJson(ApiResponse::success(script_detection::detect_scripts(
+        &project.git_repo_path,
+    )))
+}
+
+/// Unmerged attempts of the project that changed the same files as another
+pub async fn get_project_conflicts(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+) -> ResponseJson<ApiResponse<Vec<AttemptConflict>>> {
+    ResponseJson(ApiResponse::success(
+        deployment.conflicts().conflicts(project.id),
+    ))
+}
+
+/// Attempts waiting in the project's merge queue, in the order they will merge
+pub async fn get_project_merge_queue(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Vec<MergeQueueEntry>>>, ApiError> {
+    let queue =
+        MergeQueueEntry::find_active_by_project_id(&deployment.db().pool, project.id).await?;
+    Ok(ResponseJson(ApiResponse::success(queue)))
+}
+
 pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
     let project_id_router = Router::new()
         .route(
@@ -381,8 +1159,72 @@ pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
             get(get_project).put(update_project).delete(delete_project),
         )
         .route("/branches", get(get_project_branches))
+        .route("/board/stream", get(stream_project_board))
+        .route("/conflicts", get(get_project_conflicts))
+        .route("/detect-scripts", get(detect_project_scripts))
+        .route("/merge-queue", get(get_project_merge_queue))
         .route("/search", get(search_project_files))
         .route("/open-editor", post(open_project_in_editor))
+        .route("/budget", put(update_project_budget))
+        .route("/disk-usage", get(get_project_disk_usage))
+        .route("/disk-usage/gc", post(collect_project_worktrees))
+        .route("/disk-quota", put(update_project_disk_quota))
+        .route(
+            "/path-rules",
+            get(get_project_path_rules).put(update_project_path_rules),
+        )
+        .route(
+            "/branch-sync",
+            get(get_project_branch_sync).put(update_project_branch_sync),
+        )
+        .route(
+            "/ci-triage",
+            get(get_project_ci_triage).put(update_project_ci_triage),
+        )
+        .route(
+            "/dependency-updates",
+            get(get_project_dependency_updates).put(update_project_dependency_updates),
+        )
+        .route(
+            "/dependency-updates/runs",
+            get(get_project_dependency_update_runs).post(run_project_dependency_updates),
+        )
+        .route(
+            "/reviewer",
+            get(get_project_reviewer).put(update_project_reviewer),
+        )
+        .route(
+            "/changelog",
+            get(get_project_changelog).put(update_project_changelog),
+        )
+        .route(
+            "/context-packing",
+            get(get_project_context_packing).put(update_project_context_packing),
+        )
+        .route(
+            "/dependency-cache",
+            get(get_project_dependency_cache).put(update_project_dependency_cache),
+        )
+        .route(
+            "/devcontainer",
+            get(get_project_devcontainer).put(update_project_devcontainer),
+        )
+        .route("/cost-estimate", post(estimate_project_cost))
+        .route(
+            "/nix-env",
+            get(get_project_nix_env).put(update_project_nix_env),
+        )
+        .route("/env", get(get_project_env).put(update_project_env))
+        .route(
+            "/worktree-templates",
+            get(get_project_worktree_templates)
+                .put(upload_project_worktree_template)
+                .delete(delete_project_worktree_template),
+        )
+        .route(
+            "/worktree-pool",
+            get(get_project_worktree_pool).put(update_project_worktree_pool),
+        )
         .layer(from_fn_with_state(
             deployment.clone(),
             load_project_middleware,
@@ -390,6 +1232,11 @@ pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
 
     let projects_router = Router::new()
         .route("/", get(get_projects).post(create_project))
+        .route("/discover", get(discover_projects))
+        .route("/templates", get(get_project_templates))
+        .route("/from-template", post(create_project_from_template))
+        .route("/clone", post(clone_project))
+        .route("/clones/{clone_id}/stream", get(stream_clone_progress))
         .nest("/{id}", project_id_router);
 
     Router::new().nest("/projects", projects_router)
@@ -0,0 +1,77 @@
+use axum::{
+    extract::{Path, State},
+    response::Json as ResponseJson,
+    routing::{get, post, put},
+    Json, Router,
+};
+use db::models::provider_key::{KeyProvider, ProviderKey, SetProviderKey};
+use deployment::Deployment;
+use services::services::{provider_keys, secrets::SecretsStore};
+use utils::response::ApiResponse;
+
+use crate::{error::ApiError, DeploymentImpl};
+
+/// The stored keys, without the keys themselves
+pub async fn get_provider_keys(
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Vec<ProviderKey>>>, ApiError> {
+    Ok(ResponseJson(ApiResponse::success(
+        ProviderKey::find_all(&deployment.db().pool).await?,
+    )))
+}
+
+/// Store a provider's key, replacing any before, and check it with the provider
+pub async fn set_provider_key(
+    Path(provider): Path<KeyProvider>,
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<SetProviderKey>,
+) -> Result<ResponseJson<ApiResponse<ProviderKey>>, ApiError> {
+    let key = provider_keys::set(
+        &deployment.db().pool,
+        SecretsStore::default_store(),
+        provider,
+        &payload.key,
+    )
+    .await?;
+    Ok(ResponseJson(ApiResponse::success(key)))
+}
+
+pub async fn validate_provider_key(
+    Path(provider): Path<KeyProvider>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<ProviderKey>>, ApiError> {
+    let key = provider_keys::validate(
+        &deployment.db().pool,
+        SecretsStore::default_store(),
+        provider,
+    )
+    .await?
+    .ok_or_else(|| ApiError::BadRequest("No key is stored for this provider".to_string()))?;
+    Ok(ResponseJson(ApiResponse::success(key)))
+}
+
+pub async fn delete_provider_key(
+    Path(provider): Path<KeyProvider>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
+    provider_keys::delete(
+        &deployment.db().pool,
+        SecretsStore::default_store(),
+        provider,
+    )
+    .await?;
+    Ok(ResponseJson(ApiResponse::success(())))
+}
+
+pub fn router() -> Router<DeploymentImpl> {
+    Router::new()
+        .route("/provider-keys", get(get_provider_keys))
+        .route(
+            "/provider-keys/{provider}",
+            put(set_provider_key).delete(delete_provider_key),
+        )
+        .route(
+            "/provider-keys/{provider}/validate",
+            post(validate_provider_key),
+        )
+}
@@ -0,0 +1,179 @@
+use axum::{
+    body::Bytes,
+    extract::{Query, State},
+    http::HeaderMap,
+    response::Json as ResponseJson,
+    routing::{get, post},
+    Json, Router,
+};
+use db::models::project::Project;
+use deployment::Deployment;
+use serde::Deserialize;
+use services::services::{
+    secrets::SecretsStore,
+    sentry_issues::{
+        self, IssueRef, SentryIntegrationStatus, SentryIssueError, SetSentryCredentials,
+        CLIENT_SECRET_KEY,
+    },
+};
+use ts_rs::TS;
+use utils::response::ApiResponse;
+
+use crate::{
+    error::ApiError,
+    routes::inbound::{file_alert, resolve_project, InboundTaskRequest, InboundTaskResponse},
+    DeploymentImpl,
+};
+
+const SIGNATURE_HEADER: &str = "sentry-hook-signature";
+const RESOURCE_HEADER: &str = "sentry-hook-resource";
+
+#[derive(Debug, Deserialize, TS)]
+pub struct ImportSentryIssue {
+    /// Link to the issue in Sentry
+    pub link: String,
+    /// Project id, name or repository folder name, matched against the stack trace when
+    /// left out
+    pub project: Option<String>,
+    #[serde(default)]
+    pub start_agent: bool,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct WebhookQuery {
+    pub project: Option<String>,
+    #[serde(default)]
+    pub start_agent: bool,
+}
+
+/// The project named by `hint`, else the one holding the stack trace, else the only one
+async fn project_for(
+    deployment: &DeploymentImpl,
+    hint: Option<&str>,
+    issue: &sentry_issues::SentryIssue,
+) -> Result<Project, ApiError> {
+    if hint.is_none() {
+        let projects = Project::find_all(&deployment.db().pool).await?;
+        if let Some(project) = sentry_issues::match_project(&projects, issue) {
+            return Ok(project.clone());
+        }
+    }
+    resolve_project(deployment, hint).await
+}
+
+/// Fetch an issue and file it as a card, one per issue while the card is open
+async fn file_issue(
+    deployment: &DeploymentImpl,
+    issue_ref: &IssueRef,
+    project: Option<&str>,
+    start_agent: bool,
+) -> Result<InboundTaskResponse, ApiError> {
+    let issue = sentry_issues::fetch_issue(SecretsStore::default_store(), issue_ref).await?;
+    let project = project_for(deployment, project, &issue).await?;
+    file_alert(
+        deployment,
+        &project,
+        &InboundTaskRequest {
+            title: sentry_issues::task_title(&issue),
+            body: Some(sentry_issues::task_prompt(&issue)),
+            dedupe_key: Some(format!("sentry:{}", issue.id)),
+            project: None,
+            source: Some("sentry".to_string()),
+            start_agent,
+        },
+    )
+    .await
+}
+
+pub async fn get_sentry_status(
+) -> Result<ResponseJson<ApiResponse<SentryIntegrationStatus>>, ApiError> {
+    Ok(ResponseJson(ApiResponse::success(sentry_issues::status(
+        SecretsStore::default_store(),
+    )?)))
+}
+
+pub async fn set_sentry_credentials(
+    Json(payload): Json<SetSentryCredentials>,
+) -> Result<ResponseJson<ApiResponse<SentryIntegrationStatus>>, ApiError> {
+    Ok(ResponseJson(ApiResponse::success(
+        sentry_issues::set_credentials(SecretsStore::default_store(), &payload)?,
+    )))
+}
+
+/// Create a task to fix the issue behind a Sentry link, with its stack trace and breadcrumbs
+pub async fn import_sentry_issue(
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<ImportSentryIssue>,
+) -> Result<ResponseJson<ApiResponse<InboundTaskResponse>>, ApiError> {
+    let issue_ref = sentry_issues::parse_issue_link(&payload.link)?;
+    let response = file_issue(
+        &deployment,
+        &issue_ref,
+        payload.project.as_deref(),
+        payload.start_agent,
+    )
+    .await?;
+    Ok(ResponseJson(ApiResponse::success(response)))
+}
+
+/// Receiver for the issue and alert webhooks of a Sentry internal integration. Other
+/// resources, e.g. installation, are acknowledged and ignored.
+pub async fn receive_sentry_webhook(
+    State(deployment): State<DeploymentImpl>,
+    Query(query): Query<WebhookQuery>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Result<ResponseJson<ApiResponse<Option<InboundTaskResponse>>>, ApiError> {
+    let secret = SecretsStore::default_store()
+        .get(CLIENT_SECRET_KEY)
+        .map_err(SentryIssueError::from)?
+        .ok_or_else(|| {
+            ApiError::BadRequest("Set the Sentry client secret to receive webhooks".to_string())
+        })?;
+    let signature = headers
+        .get(SIGNATURE_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .unwrap_or_default();
+    if !sentry_issues::verify_signature(&secret, &body, signature) {
+        return Err(ApiError::BadRequest(
+            "Invalid Sentry webhook signature".to_string(),
+        ));
+    }
+
+    let resource = headers
+        .get(RESOURCE_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .unwrap_or_default();
+    let payload: serde_json::Value = serde_json::from_slice(&body)
+        .map_err(|e| ApiError::BadRequest(format!("Invalid webhook body: {e}")))?;
+    // Only new issues and fired alerts open a card, not e.g. an issue being resolved
+    let action = payload["action"].as_str().unwrap_or_default();
+    let issue_ref = match (resource, action) {
+        ("issue", "created") | ("event_alert", "triggered") => {
+            sentry_issues::issue_from_webhook(&payload)
+        }
+        _ => None,
+    };
+    let Some(issue_ref) = issue_ref else {
+        return Ok(ResponseJson(ApiResponse::success(None)));
+    };
+
+    let response = file_issue(
+        &deployment,
+        &issue_ref,
+        query.project.as_deref(),
+        query.start_agent,
+    )
+    .await?;
+    Ok(ResponseJson(ApiResponse::success(Some(response))))
+}
+
+pub fn router() -> Router<DeploymentImpl> {
+    Router::new()
+        .route(
+            "/integrations/sentry",
+            get(get_sentry_status).put(set_sentry_credentials),
+        )
+        .route("/integrations/sentry/issues", post(import_sentry_issue))
+        .route("/integrations/sentry/webhook", post(receive_sentry_webhook))
+}
@@ -0,0 +1,145 @@
+use std::path::Path as FsPath;
+
+use axum::{
+    extract::{Path, State},
+    response::Json as ResponseJson,
+    routing::get,
+    Extension, Json, Router,
+};
+use db::models::{
+    attempt_share_link::{AttemptShareLink, CreateAttemptShareLink},
+    execution_process::ExecutionProcess,
+    task_attempt::TaskAttempt,
+};
+use deployment::Deployment;
+use services::services::{
+    container::ContainerService,
+    share_links::{self, ShareLinkError, SharedAttempt, SharedProcess},
+};
+use sqlx::Error as SqlxError;
+use utils::response::ApiResponse;
+use uuid::Uuid;
+
+use crate::{error::ApiError, DeploymentImpl};
+
+pub async fn get_share_links(
+    Extension(task_attempt): Extension<TaskAttempt>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Vec<AttemptShareLink>>>, ApiError> {
+    let links =
+        AttemptShareLink::find_by_task_attempt_id(&deployment.db().pool, task_attempt.id).await?;
+    Ok(ResponseJson(ApiResponse::success(links)))
+}
+
+pub async fn create_share_link(
+    Extension(task_attempt): Extension<TaskAttempt>,
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<CreateAttemptShareLink>,
+) -> Result<ResponseJson<ApiResponse<AttemptShareLink>>, ApiError> {
+    let link = share_links::create(&deployment.db().pool, task_attempt.id, &payload).await?;
+    deployment
+        .track_event(
+            "attempt_share_link_created",
+            serde_json::json!({ "expires_in_hours": payload.expires_in_hours }),
+        )
+        .await;
+    Ok(ResponseJson(ApiResponse::success(link)))
+}
+
+/// The link stops working straight away, pages already open keep what they loaded
+pub async fn revoke_share_link(
+    Extension(task_attempt): Extension<TaskAttempt>,
+    State(deployment): State<DeploymentImpl>,
+    Path((_, link_id)): Path<(Uuid, Uuid)>,
+) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
+    let deleted = AttemptShareLink::delete(&deployment.db().pool, link_id, task_attempt.id).await?;
+    if deleted == 0 {
+        return Err(ShareLinkError::NotFound.into());
+    }
+    Ok(ResponseJson(ApiResponse::success(())))
+}
+
+/// GET /share/{token}: the attempt behind a share link, read-only. The token is the only
+/// thing checked, so the response holds nothing beyond that one attempt.
+pub async fn get_shared_attempt(
+    State(deployment): State<DeploymentImpl>,
+    Path(token): Path<String>,
+) -> Result<ResponseJson<ApiResponse<SharedAttempt>>, ApiError> {
+    let pool = &deployment.db().pool;
+    let link = share_links::resolve(pool, &token).await?;
+    let task_attempt = TaskAttempt::find_by_id(pool, link.task_attempt_id)
+        .await?
+        .ok_or(ShareLinkError::NotFound)?;
+    let task = task_attempt
+        .parent_task(pool)
+        .await?
+        .ok_or(ShareLinkError::NotFound)?;
+    let project = task
+        .parent_project(pool)
+        .await?
+        .ok_or(SqlxError::RowNotFound)?;
+
+    let mut processes = Vec::new();
+    for process in ExecutionProcess::find_by_task_attempt_id(pool, task_attempt.id).await? {
+        let entries = deployment.container().normalized_entries(&process).await?;
+        if entries.is_empty() {
+            continue;
+        }
+        processes.push(SharedProcess {
+            run_reason: process.run_reason,
+            status: process.status,
+            started_at: process.started_at,
+            completed_at: process.completed_at,
+            entries,
+        });
+    }
+
+    // Read-only: merged attempts show their merge commit, others the worktree as it is. A
+    // cleaned up worktree is not recreated for a link anyone may open, it shows no diff.
+    let worktree = task_attempt
+        .container_ref
+        .as_deref()
+        .map(FsPath::new)
+        .filter(|path| !task_attempt.worktree_deleted && path.is_dir());
+    let diff_path = if task_attempt.merge_commit.is_some() {
+        // The merge commit is read from the project repository, no worktree is needed
+        Some(project.git_repo_path.as_path())
+    } else if task_attempt.branch.is_some() {
+        worktree
+    } else {
+        None
+    };
+    let diffs = match diff_path {
+        Some(worktree_path) => deployment
+            .diff_stats()
+            .diff_cache()
+            .attempt_diffs(
+                deployment.git(),
+                &task_attempt,
+                worktree_path,
+                &project.git_repo_path,
+            )?
+            .diffs
+            .to_vec(),
+        None => Vec::new(),
+    };
+
+    let shared = SharedAttempt {
+        task_title: task.title,
+        task_description: task.description,
+        profile: task_attempt.profile,
+        branch: task_attempt.branch,
+        base_branch: task_attempt.base_branch,
+        merged: task_attempt.merge_commit.is_some(),
+        created_at: task_attempt.created_at,
+        expires_at: link.expires_at,
+        processes,
+        diffs,
+    };
+    Ok(ResponseJson(ApiResponse::success(shared.redacted()?)))
+}
+
+/// Managing an attempt's links goes through the attempt, see `task_attempts::router`
+pub fn router() -> Router<DeploymentImpl> {
+    Router::new().route("/share/{token}", get(get_shared_attempt))
+}
@@ -0,0 +1,59 @@
+use axum::{extract::State, response::Json as ResponseJson, routing::get, Extension, Json, Router};
+use chrono::Utc;
+use db::models::{
+    project::Project,
+    project_budget::{ProjectBudget, UpdateProjectBudget},
+};
+use deployment::Deployment;
+use services::services::budget::SpendSummary;
+use utils::response::ApiResponse;
+use uuid::Uuid;
+
+use crate::{error::ApiError, DeploymentImpl};
+
+async fn load_summary(deployment: &DeploymentImpl) -> Result<SpendSummary, ApiError> {
+    let global_cap = deployment.config().read().await.budget.monthly_cap_usd;
+    Ok(SpendSummary::load(&deployment.db().pool, global_cap, Utc::now()).await?)
+}
+
+/// Refuse new attempts in a project whose monthly budget, or the global one, is spent
+pub async fn ensure_within_budget(
+    deployment: &DeploymentImpl,
+    project_id: Uuid,
+) -> Result<(), ApiError> {
+    Ok(load_summary(deployment).await?.check(project_id)?)
+}
+
+pub async fn get_spend(
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<SpendSummary>>, ApiError> {
+    Ok(ResponseJson(ApiResponse::success(
+        load_summary(&deployment).await?,
+    )))
+}
+
+pub async fn update_project_budget(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<UpdateProjectBudget>,
+) -> Result<ResponseJson<ApiResponse<Option<ProjectBudget>>>, ApiError> {
+    let pool = &deployment.db().pool;
+    let budget = match payload.monthly_cap_usd {
+        Some(cap) if !(cap >= 0.0 && cap.is_finite()) => {
+            return Err(ApiError::BadRequest(
+                "The cap must be a positive amount".to_string(),
+            ));
+        }
+        Some(cap) => Some(ProjectBudget::upsert(pool, project.id, cap).await?),
+        None => {
+            ProjectBudget::delete(pool, project.id).await?;
+            None
+        }
+    };
+    Ok(ResponseJson(ApiResponse::success(budget)))
+}
+
+/// Budgets of single projects are set through their project
+pub fn router() -> Router<DeploymentImpl> {
+    Router::new().route("/spend", get(get_spend))
+}
@@ -0,0 +1,111 @@
+use axum::{
+    body::{Body, Bytes},
+    extract::{Path, Query, State},
+    http::{self, HeaderMap},
+    response::{Json as ResponseJson, Response},
+    routing::get,
+    Extension, Router,
+};
+use db::models::{task::Task, task_attachment::TaskAttachment};
+use deployment::Deployment;
+use serde::Deserialize;
+use services::services::attachments::{AttachmentError, AttachmentStore, ImageFormat};
+use utils::response::ApiResponse;
+use uuid::Uuid;
+
+use crate::{error::ApiError, DeploymentImpl};
+
+#[derive(Debug, Deserialize)]
+pub struct UploadAttachmentQuery {
+    pub file_name: String,
+}
+
+pub async fn list_attachments(
+    Extension(task): Extension<Task>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Vec<TaskAttachment>>>, ApiError> {
+    let attachments = TaskAttachment::find_by_task_id(&deployment.db().pool, task.id).await?;
+    Ok(ResponseJson(ApiResponse::success(attachments)))
+}
+
+/// Attach a file to a task, sent as the raw request body
+pub async fn upload_attachment(
+    Extension(task): Extension<Task>,
+    State(deployment): State<DeploymentImpl>,
+    Query(query): Query<UploadAttachmentQuery>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Result<ResponseJson<ApiResponse<TaskAttachment>>, ApiError> {
+    let guessed = mime_guess::from_path(&query.file_name).first_raw();
+    let content_type = headers
+        .get(http::header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .filter(|value| *value != "application/octet-stream")
+        .or(guessed);
+    let attachment = AttachmentStore::default()
+        .save(
+            &deployment.db().pool,
+            task.id,
+            &query.file_name,
+            content_type,
+            &body,
+        )
+        .await?;
+    Ok(ResponseJson(ApiResponse::success(attachment)))
+}
+
+async fn find_attachment(
+    deployment: &DeploymentImpl,
+    attachment_id: Uuid,
+) -> Result<TaskAttachment, ApiError> {
+    TaskAttachment::find_by_id(&deployment.db().pool, attachment_id)
+        .await?
+        .ok_or(ApiError::Attachment(AttachmentError::NotFound(
+            attachment_id,
+        )))
+}
+
+/// Contents of an attachment, for previews and downloads. Only images recognised by their
+/// contents are shown inline, anything else is served as a download so an uploaded page or
+/// script can never run on the app's origin.
+pub async fn get_attachment(
+    State(deployment): State<DeploymentImpl>,
+    Path(attachment_id): Path<Uuid>,
+) -> Result<Response, ApiError> {
+    let attachment = find_attachment(&deployment, attachment_id).await?;
+    let data = AttachmentStore::default().load(&attachment).await?;
+    let (content_type, disposition) = match ImageFormat::detect(&data) {
+        Some(format) => (format.content_type(), "inline"),
+        None => ("application/octet-stream", "attachment"),
+    };
+    let response = Response::builder()
+        .status(http::StatusCode::OK)
+        .header(http::header::CONTENT_TYPE, content_type)
+        .header(http::header::X_CONTENT_TYPE_OPTIONS, "nosniff")
+        .header(
+            http::header::CONTENT_DISPOSITION,
+            format!("{disposition}; filename=\"{}\"", attachment.file_name),
+        )
+        .body(Body::from(data))
+        .unwrap();
+    Ok(response)
+}
+
+pub async fn delete_attachment(
+    State(deployment): State<DeploymentImpl>,
+    Path(attachment_id): Path<Uuid>,
+) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
+    let attachment = find_attachment(&deployment, attachment_id).await?;
+    AttachmentStore::default()
+        .delete(&deployment.db().pool, &attachment)
+        .await?;
+    Ok(ResponseJson(ApiResponse::success(())))
+}
+
+/// Attachments by id, listing and uploading go through their task
+pub fn router() -> Router<DeploymentImpl> {
+    Router::new().route(
+        "/task-attachments/{attachment_id}",
+        get(get_attachment).delete(delete_attachment),
+    )
+}
@@ -1,41 +1,97 @@
+use async_compression::tokio::bufread::GzipEncoder;
 use axum::{
-    extract::{Query, State},
-    http::StatusCode,
+    body::{Body, Bytes},
+    extract::{DefaultBodyLimit, Path, Query, State},
+    http::{header, StatusCode},
     middleware::from_fn_with_state,
     response::{
         sse::{Event, KeepAlive},
-        Json as ResponseJson, Sse,
+        Json as ResponseJson, Response, Sse,
     },
-    routing::{get, post},
+    routing::{delete, get, post},
     BoxError, Extension, Json, Router,
 };
 use db::models::{
-    execution_process::{ExecutionProcess, ExecutionProcessRunReason},
+    attempt_checkpoint::AttemptCheckpoint,
+    attempt_commit_plan::AttemptCommitPlan,
+    attempt_diff_risk::{AttemptDiffRisk, DiffRisk},
+    attempt_linked_repo::{AttemptLinkedRepo, CreateAttemptLinkedRepo},
+    attempt_plan::{AttemptPlan, PlanStatus},
+    attempt_review::{AttemptReview, ReviewVerdict},
+    attempt_unmerge::{AttemptUnmerge, CreateAttemptUnmerge},
+    branch_sync::AttemptBranchSync,
+    execution_pause::{AttemptRuntime, ExecutionPause},
+    execution_process::{ExecutionProcess, ExecutionProcessRunReason, ExecutionProcessStatus},
+    follow_up_snippet::FollowUpSnippet,
+    merge_queue::MergeQueueEntry,
+    project::Project,
+    project_path_rules::ProjectPathRules,
+    project_reviewer::ProjectReviewer,
+    queued_follow_up::{CreateQueuedFollowUp, QueuedFollowUp, ReorderQueuedFollowUps},
+    suspended_execution::SuspendedExecution,
     task::{Task, TaskStatus},
     task_attempt::{CreateTaskAttempt, TaskAttempt, TaskAttemptError},
+    test_run::TestRun,
 };
 use deployment::Deployment;
 use executors::{
     actions::{
-        coding_agent_follow_up::CodingAgentFollowUpRequest,
         script::{ScriptContext, ScriptRequest, ScriptRequestLanguage},
         ExecutorAction, ExecutorActionType,
     },
+    executors::CodingAgent,
+    mcp_config::read_agent_config,
     profile::{ProfileConfigs, ProfileVariantLabel},
 };
-use futures_util::TryStreamExt;
+use futures_util::{StreamExt, TryStreamExt};
 use serde::{Deserialize, Serialize};
 use services::services::{
-    container::ContainerService,
-    git::{BranchStatus, GitService},
+    attachments::{ContextFile, ContextFolder, MAX_ATTACHMENT_BYTES},
+    changelog,
+    checkpoints::CheckpointError,
+    ci_checks::{self, AttemptChecks},
+    container::{ContainerError, ContainerService},
+    dev_server::DevServerInfo,
+    diff_risk,
+    diff_stats::DiffStats,
+    feature_flags::FeatureFlag,
+    git::{BranchStatus, GitService, GitServiceError},
+    github_auth,
     github_service::{CreatePrRequest, GitHubRepoInfo, GitHubService, GitHubServiceError},
+    linked_repos::{self, LinkedRepoDiff},
+    log_export,
+    merge_queue::{self, MergeQueueError, MergeQueuePosition},
+    path_rules::{PathRules, PathViolation},
+    planning,
+    repo_config::RepoConfig,
+    secret_scan::{self, SecretScanReport},
+    timeline::AttemptTimeline,
+    tool_usage::AttemptToolUsage,
 };
-use sqlx::Error as SqlxError;
+use sqlx::{Error as SqlxError, SqlitePool};
+use tokio_util::io::{ReaderStream, StreamReader};
+use tracing::Instrument;
 use ts_rs::TS;
-use utils::response::ApiResponse;
+use utils::{
+    i18n::MessageCode,
+    otel::attempt_span,
+    pagination::{ListQuery, Paginated},
+    response::ApiResponse,
+};
 use uuid::Uuid;
 
-use crate::{error::ApiError, middleware::load_task_attempt_middleware, DeploymentImpl};
+use crate::{
+    error::ApiError,
+    middleware::{idempotency_middleware, load_task_attempt_middleware, IdempotencyStore},
+    routes::{
+        config,
+        editor::{self, EditorOpenResponse},
+        share, spend,
+        tasks::ensure_base_exists,
+        terminal,
+    },
+    DeploymentImpl,
+};
 
 #[derive(Debug, Deserialize, Serialize, TS)]
 pub struct RebaseTaskAttemptRequest {
@@ -47,6 +103,17 @@ pub struct CreateGitHubPrRequest {
     pub title: String,
     pub body: Option<String>,
     pub base_branch: Option<String>,
+    /// Push the branch to the user's fork and open the pull request from there. Left
+    /// out, the fork is used when the user can't push to the repository.
+    #[serde(default)]
+    pub push_to_fork: Option<bool>,
+}
+
+/// Lets merges and PRs through despite secret scan findings
+#[derive(Debug, Default, Deserialize)]
+pub struct SecretScanOverrideQuery {
+    #[serde(default)]
+    pub allow_secrets: bool,
 }
 
 #[derive(Debug, Serialize)]
@@ -227,10 +294,12 @@ pub struct TaskAttemptQuery {
 pub async fn get_task_attempts(
     State(deployment): State<DeploymentImpl>,
     Query(query): Query<TaskAttemptQuery>,
-) -> Result<ResponseJson<ApiResponse<Vec<TaskAttempt>>>, ApiError> {
+    list: ListQuery,
+) -> Result<ResponseJson<ApiResponse<Paginated<TaskAttempt>>>, ApiError> {
     let pool = &deployment.db().pool;
     let attempts = TaskAttempt::fetch_all(pool, query.task_id).await?;
-    Ok(ResponseJson(ApiResponse::success(attempts)))
+    let page = list.apply(attempts).map_err(ApiError::BadRequest)?;
+    Ok(ResponseJson(ApiResponse::paginated(page)))
 }
 
 pub async fn get_task_attempt(
@@ -245,6 +314,65 @@ pub struct CreateTaskAttemptBody {
     pub task_id: Uuid,
     pub profile_variant_label: Option<ProfileVariantLabel>,
     pub base_branch: String,
+    /// Variant of the profile that plans first. The attempt then waits for the plan to be
+    /// approved before the chosen variant executes it.
+    #[serde(default)]
+    pub plan_variant: Option<String>,
+    /// Summarize the task's earlier attempts in the prompt
+    #[serde(default)]
+    pub carry_over_history: bool,
+    /// Other projects to change in the same attempt, their worktrees nested in this one's
+    #[serde(default)]
+    pub linked_repos: Vec<CreateAttemptLinkedRepo>,
+}
+
+/// The linked projects with the base branch each starts from, checked before the attempt
+/// exists so a typo doesn't leave half an attempt behind
+async fn resolve_linked_repos(
+    deployment: &DeploymentImpl,
+    project: &Project,
+    linked_repos: &[CreateAttemptLinkedRepo],
+) -> Result<Vec<(Uuid, String)>, ApiError> {
+    if linked_repos.is_empty() {
+        return Ok(Vec::new());
+    }
+    if !deployment
+        .container()
+        .feature_enabled(FeatureFlag::MultiRepoAttempts)
+        .await
+    {
+        return Err(ApiError::PreconditionFailed(
+            "Linked repositories need the multi-repo attempts feature flag".to_string(),
+        ));
+    }
+    let mut resolved: Vec<(Uuid, String)> = Vec::with_capacity(linked_repos.len());
+    for linked in linked_repos {
+        if linked.project_id == project.id
+            || resolved
+                .iter()
+                .any(|(project_id, _)| *project_id == linked.project_id)
+        {
+            return Err(ApiError::BadRequest(
+                "Each linked project can only be given once, and not the task's own".to_string(),
+            ));
+        }
+        let linked_project = Project::find_by_id(&deployment.db().pool, linked.project_id)
+            .await?
+            .ok_or_else(|| {
+                ApiError::BadRequest(format!("Linked project {} not found", linked.project_id))
+            })?;
+        let base_branch = match &linked.base_branch {
+            Some(base_branch) => {
+                ensure_base_exists(&linked_project, base_branch)?;
+                base_branch.clone()
+            }
+            None => deployment
+                .git()
+                .get_current_branch(&linked_project.git_repo_path)?,
+        };
+        resolved.push((linked_project.id, base_branch));
+    }
+    Ok(resolved)
 }
 
 #[axum::debug_handler]
@@ -252,9 +380,26 @@ pub async fn create_task_attempt(
     State(deployment): State<DeploymentImpl>,
     Json(payload): Json<CreateTaskAttemptBody>,
 ) -> Result<ResponseJson<ApiResponse<TaskAttempt>>, ApiError> {
-    let profile_variant_label = payload
-        .profile_variant_label
-        .unwrap_or(deployment.config().read().await.profile.clone());
+    let task = Task::find_by_id(&deployment.db().pool, payload.task_id)
+        .await?
+        .ok_or(SqlxError::RowNotFound)?;
+    spend::ensure_within_budget(&deployment, task.project_id).await?;
+    let project = task
+        .parent_project(&deployment.db().pool)
+        .await?
+        .ok_or(SqlxError::RowNotFound)?;
+    ensure_base_exists(&project, &payload.base_branch)?;
+    let linked_repos = resolve_linked_repos(&deployment, &project, &payload.linked_repos).await?;
+
+    let profile_variant_label = match payload.profile_variant_label {
+        Some(label) => label,
+        None => RepoConfig::load(
+            deployment.git(),
+            &project.git_repo_path,
+            &payload.base_branch,
+        )?
+        .profile_or(deployment.config().read().await.profile.clone()),
+    };
 
     let profiles = ProfileConfigs::get_cached();
     let profile = profiles
@@ -266,6 +411,48 @@ pub async fn create_task_attempt(
             )))
         })?;
 
+    // The planning phase runs first, the chosen variant executes the approved plan
+    if payload.plan_variant.is_some()
+        && !deployment
+            .container()
+            .feature_enabled(FeatureFlag::MultiAgentPipelines)
+            .await
+    {
+        return Err(ApiError::PreconditionFailed(
+            "Planning phases need the multi-agent pipelines feature flag".to_string(),
+        ));
+    }
+    let plan_variant_label = match &payload.plan_variant {
+        Some(variant) => {
+            let label = ProfileVariantLabel {
+                profile: profile_variant_label.profile.clone(),
+                variant: Some(variant.clone()),
+            };
+            let planner = CodingAgent::from_profile_variant_label(&label).map_err(|e| {
+                ApiError::TaskAttempt(TaskAttemptError::ValidationError(e.to_string()))
+            })?;
+            if !planner.supports_planning() {
+                return Err(ApiError::TaskAttempt(TaskAttemptError::ValidationError(
+                    format!("Variant {variant} can't run a planning phase"),
+                )));
+            }
+            Some(label)
+        }
+        None => None,
+    };
+
+    // Remember a base other than the current branch, so the task's next attempts start there
+    // too. Tasks that already have one keep the latest choice.
+    let current_branch = deployment
+        .git()
+        .get_current_branch(&project.git_repo_path)
+        .ok();
+    if task.base_branch.as_ref() != Some(&payload.base_branch)
+        && (task.base_branch.is_some() || current_branch.as_ref() != Some(&payload.base_branch))
+    {
+        Task::set_base_branch(&deployment.db().pool, task.id, Some(&payload.base_branch)).await?;
+    }
+
     let task_attempt = TaskAttempt::create(
         &deployment.db().pool,
         &CreateTaskAttempt {
@@ -275,14 +462,37 @@ pub async fn create_task_attempt(
         payload.task_id,
     )
     .await?;
+    for (project_id, base_branch) in &linked_repos {
+        AttemptLinkedRepo::create(
+            &deployment.db().pool,
+            task_attempt.id,
+            *project_id,
+            base_branch,
+        )
+        .await?;
+    }
+
+    if plan_variant_label.is_some() {
+        AttemptPlan::create(
+            &deployment.db().pool,
+            task_attempt.id,
+            profile_variant_label.variant.as_deref(),
+        )
+        .await?;
+    }
 
     let execution_process = deployment
         .container()
-        .start_attempt(&task_attempt, profile_variant_label.clone())
+        .start_attempt(
+            &task_attempt,
+            plan_variant_label.unwrap_or_else(|| profile_variant_label.clone()),
+            payload.carry_over_history,
+        )
+        .instrument(attempt_span("start_attempt", task_attempt.id))
         .await?;
 
     deployment
-        .track_if_analytics_allowed(
+        .track_event(
             "task_attempt_started",
             serde_json::json!({
                 "task_id": task_attempt.task_id.to_string(),
@@ -300,8 +510,38 @@ pub async fn create_task_attempt(
 
 #[derive(Debug, Deserialize, TS)]
 pub struct CreateFollowUpAttempt {
+    /// Added after the snippet's prompt when there is one
+    #[serde(default)]
     pub prompt: String,
     pub variant: Option<String>,
+    /// A follow-up snippet, global or of the attempt's project, to start the prompt with
+    pub snippet_id: Option<Uuid>,
+}
+
+/// The snippet's prompt followed by `prompt`, or `prompt` alone without a snippet
+async fn follow_up_prompt(
+    deployment: &DeploymentImpl,
+    task_attempt: &TaskAttempt,
+    prompt: String,
+    snippet_id: Option<Uuid>,
+) -> Result<String, ApiError> {
+    let Some(snippet_id) = snippet_id else {
+        return Ok(prompt);
+    };
+    let pool = &deployment.db().pool;
+    let task = task_attempt
+        .parent_task(pool)
+        .await?
+        .ok_or(ApiError::TaskAttempt(TaskAttemptError::TaskNotFound))?;
+    let snippet = FollowUpSnippet::find_by_id(pool, snippet_id)
+        .await?
+        .filter(|snippet| snippet.project_id.is_none_or(|id| id == task.project_id))
+        .ok_or_else(|| {
+            ApiError::TaskAttempt(TaskAttemptError::ValidationError(format!(
+                "Follow-up snippet {snippet_id} not found in this project"
+            )))
+        })?;
+    Ok(snippet.apply(&prompt))
 }
 
 pub async fn follow_up(
@@ -311,110 +551,570 @@ pub async fn follow_up(
 ) -> Result<ResponseJson<ApiResponse<ExecutionProcess>>, ApiError> {
     tracing::info!("{:?}", task_attempt);
 
-    // Get session_id with simple query
-    let session_id = ExecutionProcess::find_latest_session_id_by_task_attempt(
+    let prompt = follow_up_prompt(
+        &deployment,
+        &task_attempt,
+        payload.prompt,
+        payload.snippet_id,
+    )
+    .await?;
+    let execution_process = deployment
+        .container()
+        .start_follow_up(&task_attempt, prompt, payload.variant)
+        .await?;
+
+    Ok(ResponseJson(ApiResponse::success(execution_process)))
+}
+
+pub async fn get_task_attempt_plan(
+    Extension(task_attempt): Extension<TaskAttempt>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Option<AttemptPlan>>>, ApiError> {
+    let plan = AttemptPlan::find_by_task_attempt_id(&deployment.db().pool, task_attempt.id).await?;
+    Ok(ResponseJson(ApiResponse::success(plan)))
+}
+
+#[derive(Debug, Deserialize, TS)]
+pub struct ApprovePlanRequest {
+    /// The plan as edited by the reviewer, the agent's plan when `None`
+    pub plan: Option<String>,
+}
+
+async fn plan_awaiting_approval(
+    deployment: &DeploymentImpl,
+    task_attempt: &TaskAttempt,
+) -> Result<AttemptPlan, ApiError> {
+    AttemptPlan::find_by_task_attempt_id(&deployment.db().pool, task_attempt.id)
+        .await?
+        .filter(|plan| plan.status == PlanStatus::AwaitingApproval)
+        .ok_or_else(|| {
+            ApiError::TaskAttempt(TaskAttemptError::ValidationError(
+                "No plan awaiting approval".to_string(),
+            ))
+        })
+}
+
+/// Approve the attempt's plan and start its execution phase with it
+pub async fn approve_task_attempt_plan(
+    Extension(task_attempt): Extension<TaskAttempt>,
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<ApprovePlanRequest>,
+) -> Result<ResponseJson<ApiResponse<AttemptPlan>>, ApiError> {
+    let attempt_plan = plan_awaiting_approval(&deployment, &task_attempt).await?;
+    let plan = payload
+        .plan
+        .or(attempt_plan.plan)
+        .filter(|plan| !plan.trim().is_empty())
+        .ok_or_else(|| {
+            ApiError::TaskAttempt(TaskAttemptError::ValidationError(
+                "The plan is empty".to_string(),
+            ))
+        })?;
+
+    deployment
+        .container()
+        .start_follow_up(
+            &task_attempt,
+            planning::execute_prompt(&plan),
+            attempt_plan.execute_variant,
+        )
+        .await?;
+    let attempt_plan = AttemptPlan::update(
         &deployment.db().pool,
         task_attempt.id,
+        PlanStatus::Approved,
+        Some(&plan),
     )
-    .await?
-    .ok_or(ApiError::TaskAttempt(TaskAttemptError::ValidationError(
-        "Couldn't find a prior CodingAgent execution that already has a session_id".to_string(),
-    )))?;
+    .await?;
+    Ok(ResponseJson(ApiResponse::success(attempt_plan)))
+}
 
-    // Get ExecutionProcess for profile data
-    let latest_execution_process = ExecutionProcess::find_latest_by_task_attempt_and_run_reason(
+pub async fn reject_task_attempt_plan(
+    Extension(task_attempt): Extension<TaskAttempt>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<AttemptPlan>>, ApiError> {
+    let attempt_plan = plan_awaiting_approval(&deployment, &task_attempt).await?;
+    let attempt_plan = AttemptPlan::update(
         &deployment.db().pool,
         task_attempt.id,
-        &ExecutionProcessRunReason::CodingAgent,
+        PlanStatus::Rejected,
+        attempt_plan.plan.as_deref(),
     )
-    .await?
-    .ok_or(ApiError::TaskAttempt(TaskAttemptError::ValidationError(
-        "Couldn't find initial coding agent process, has it run yet?".to_string(),
-    )))?;
-    let initial_profile_variant_label = match &latest_execution_process
-        .executor_action()
-        .map_err(|e| ApiError::TaskAttempt(TaskAttemptError::ValidationError(e.to_string())))?
-        .typ
+    .await?;
+    Ok(ResponseJson(ApiResponse::success(attempt_plan)))
+}
+
+pub async fn get_follow_up_queue(
+    Extension(task_attempt): Extension<TaskAttempt>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Vec<QueuedFollowUp>>>, ApiError> {
+    let queue =
+        QueuedFollowUp::find_by_task_attempt_id(&deployment.db().pool, task_attempt.id).await?;
+    Ok(ResponseJson(ApiResponse::success(queue)))
+}
+
+/// Queue a follow-up to send once the attempt is idle, or right away if it already is
+pub async fn queue_follow_up(
+    Extension(task_attempt): Extension<TaskAttempt>,
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<CreateQueuedFollowUp>,
+) -> Result<ResponseJson<ApiResponse<QueuedFollowUp>>, ApiError> {
+    let queued = QueuedFollowUp::create(&deployment.db().pool, task_attempt.id, &payload).await?;
+    if let Err(e) = deployment
+        .container()
+        .dispatch_queued_follow_up(&task_attempt)
+        .await
     {
-        ExecutorActionType::CodingAgentInitialRequest(request) => {
-            Ok(request.profile_variant_label.clone())
-        }
-        ExecutorActionType::CodingAgentFollowUpRequest(request) => {
-            Ok(request.profile_variant_label.clone())
-        }
-        _ => Err(ApiError::TaskAttempt(TaskAttemptError::ValidationError(
-            "Couldn't find profile from initial request".to_string(),
-        ))),
-    }?;
-
-    let profile_variant_label = ProfileVariantLabel {
-        profile: initial_profile_variant_label.profile,
-        variant: payload.variant,
-    };
+        tracing::warn!(
+            "Queued follow-up {} for task attempt {} could not be sent yet: {}",
+            queued.id,
+            task_attempt.id,
+            e
+        );
+    }
+    Ok(ResponseJson(ApiResponse::success(queued)))
+}
 
-    // Get parent task
-    let task = task_attempt
-        .parent_task(&deployment.db().pool)
+pub async fn reorder_follow_up_queue(
+    Extension(task_attempt): Extension<TaskAttempt>,
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<ReorderQueuedFollowUps>,
+) -> Result<ResponseJson<ApiResponse<Vec<QueuedFollowUp>>>, ApiError> {
+    let queue =
+        QueuedFollowUp::reorder(&deployment.db().pool, task_attempt.id, &payload.ids).await?;
+    Ok(ResponseJson(ApiResponse::success(queue)))
+}
+
+pub async fn delete_queued_follow_up(
+    State(deployment): State<DeploymentImpl>,
+    Path(id): Path<Uuid>,
+) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
+    let rows_affected = QueuedFollowUp::delete(&deployment.db().pool, id).await?;
+    if rows_affected == 0 {
+        Err(ApiError::Database(SqlxError::RowNotFound))
+    } else {
+        Ok(ResponseJson(ApiResponse::success(())))
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct DiffQuery {
+    /// Ref to compare the worktree against instead of the base branch, e.g. `main`, a tag
+    /// or another attempt's branch
+    pub against: Option<String>,
+}
+
+pub async fn get_task_attempt_diff(
+    Extension(task_attempt): Extension<TaskAttempt>,
+    State(deployment): State<DeploymentImpl>,
+    Query(query): Query<DiffQuery>,
+    // ) -> Result<ResponseJson<ApiResponse<WorktreeDiff>>, ApiError> {
+) -> Result<Sse<impl futures_util::Stream<Item = Result<Event, BoxError>>>, axum::http::StatusCode>
+{
+    let against = query
+        .against
+        .as_deref()
+        .map(str::trim)
+        .filter(|against| !against.is_empty());
+    let stream = deployment
+        .container()
+        .get_diff(&task_attempt, against)
+        .await
+        .map_err(|e| match e {
+            ContainerError::GitServiceError(GitServiceError::RefNotFound(_)) => {
+                StatusCode::NOT_FOUND
+            }
+            _ => StatusCode::INTERNAL_SERVER_ERROR,
+        })?;
+    let stream = deployment.shutdown().guard_stream(stream);
+
+    Ok(Sse::new(stream.map_err(|e| -> BoxError { e.into() })).keep_alive(KeepAlive::default()))
+}
+
+/// Blocks changes with secret scan findings from leaving the worktree unless overridden
+fn ensure_no_secrets(
+    task_attempt: &TaskAttempt,
+    worktree_path: &std::path::Path,
+    branch_name: &str,
+    allow_secrets: bool,
+) -> Result<(), ApiError> {
+    let report = secret_scan::scan_worktree(worktree_path, branch_name, &task_attempt.base_branch)?;
+    if allow_secrets && !report.findings.is_empty() {
+        tracing::warn!(
+            "Secret scan of attempt {} overridden with {} finding(s)",
+            task_attempt.id,
+            report.findings.len()
+        );
+        return Ok(());
+    }
+    Ok(report.check()?)
+}
+
+/// Refuse a pull request unless the latest review approved the changes, when the project's
+/// reviewer gates pull requests
+async fn ensure_review_approved(
+    deployment: &DeploymentImpl,
+    task_attempt: &TaskAttempt,
+    project_id: Uuid,
+) -> Result<(), ApiError> {
+    let pool = &deployment.db().pool;
+    let gated = ProjectReviewer::find_by_project_id(pool, project_id)
         .await?
-        .ok_or(SqlxError::RowNotFound)?;
+        .is_some_and(|reviewer| reviewer.enabled && reviewer.gate_pull_requests);
+    if !gated {
+        return Ok(());
+    }
+    let latest = AttemptReview::find_by_task_attempt_id(pool, task_attempt.id)
+        .await?
+        .into_iter()
+        .next();
+    match latest.map(|review| review.verdict) {
+        Some(ReviewVerdict::Approved) => Ok(()),
+        Some(ReviewVerdict::ChangesRequested) => Err(ApiError::PreconditionFailed(
+            "The reviewer requested changes, pull requests need an approving review".to_string(),
+        )),
+        Some(ReviewVerdict::Failed) | None => Err(ApiError::PreconditionFailed(
+            "Pull requests need an approving review, the attempt has none yet".to_string(),
+        )),
+    }
+}
 
-    // Get parent project
-    let project = task
-        .parent_project(&deployment.db().pool)
+pub async fn get_task_attempt_reviews(
+    Extension(task_attempt): Extension<TaskAttempt>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Vec<AttemptReview>>>, ApiError> {
+    let reviews =
+        AttemptReview::find_by_task_attempt_id(&deployment.db().pool, task_attempt.id).await?;
+    Ok(ResponseJson(ApiResponse::success(reviews)))
+}
+
+#[derive(Debug, Deserialize, TS)]
+pub struct CreateCommitPlanRequest {
+    /// Profile planning the commits, a cheaper one will do. The attempt's profile when `None`
+    pub profile_variant_label: Option<ProfileVariantLabel>,
+}
+
+/// Have an agent split the attempt's changes into logical commits with conventional commit
+/// messages, applied to its branch once the agent is done
+pub async fn create_task_attempt_commit_plan(
+    Extension(task_attempt): Extension<TaskAttempt>,
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<CreateCommitPlanRequest>,
+) -> Result<ResponseJson<ApiResponse<AttemptCommitPlan>>, ApiError> {
+    if task_attempt.merge_commit.is_some() {
+        return Err(ApiError::BadRequest(
+            "The attempt is already merged".to_string(),
+        ));
+    }
+    // Running executions would keep changing the files being split
+    let processes =
+        ExecutionProcess::find_by_task_attempt_id(&deployment.db().pool, task_attempt.id).await?;
+    if processes.iter().any(|p| {
+        p.status == ExecutionProcessStatus::Running
+            && p.run_reason != ExecutionProcessRunReason::DevServer
+    }) {
+        return Err(ApiError::PreconditionFailed(
+            "Wait for the attempt's executions to finish before splitting its changes".to_string(),
+        ));
+    }
+
+    let profile_variant_label = payload
+        .profile_variant_label
+        .unwrap_or_else(|| ProfileVariantLabel::default(task_attempt.profile.clone()));
+    let plan = deployment
+        .container()
+        .dispatch_commit_plan(&task_attempt, profile_variant_label)
         .await?
-        .ok_or(SqlxError::RowNotFound)?;
+        .ok_or_else(|| ApiError::BadRequest("The attempt has no changes to commit".to_string()))?;
+    Ok(ResponseJson(ApiResponse::success(plan)))
+}
 
-    let cleanup_action = project.cleanup_script.map(|script| {
-        Box::new(ExecutorAction::new(
-            ExecutorActionType::ScriptRequest(ScriptRequest {
-                script,
-                language: ScriptRequestLanguage::Bash,
-                context: ScriptContext::CleanupScript,
-            }),
-            None,
+pub async fn get_task_attempt_commit_plan(
+    Extension(task_attempt): Extension<TaskAttempt>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Option<AttemptCommitPlan>>>, ApiError> {
+    let plan =
+        AttemptCommitPlan::find_latest_by_task_attempt_id(&deployment.db().pool, task_attempt.id)
+            .await?;
+    Ok(ResponseJson(ApiResponse::success(plan)))
+}
+
+/// Which tools the attempt's coding agent runs called, and which configured MCP servers went
+/// unused
+pub async fn get_task_attempt_tool_usage(
+    Extension(task_attempt): Extension<TaskAttempt>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<AttemptToolUsage>>, ApiError> {
+    let (calls, labels) = deployment
+        .container()
+        .attempt_tool_calls(task_attempt.id)
+        .await?;
+
+    let profiles = ProfileConfigs::get_cached();
+    let mut configured: Vec<String> = Vec::new();
+    for label in labels {
+        let Some(profile) = profiles.get_profile(&label.profile) else {
+            continue;
+        };
+        if !profile.default.agent.supports_mcp() {
+            continue;
+        }
+        let Some(config_path) = profile.get_mcp_config_path() else {
+            continue;
+        };
+        let mcpc = profile.default.agent.get_mcp_config();
+        // An unreadable config only means unused servers can't be listed
+        let Ok(raw_config) = read_agent_config(&config_path, &mcpc).await else {
+            continue;
+        };
+        configured.extend(
+            config::get_mcp_servers_from_config_path(&raw_config, &mcpc.servers_path).into_keys(),
+        );
+    }
+
+    Ok(ResponseJson(ApiResponse::success(AttemptToolUsage::new(
+        &calls,
+        &configured,
+    ))))
+}
+
+pub async fn get_task_attempt_secret_scan(
+    Extension(task_attempt): Extension<TaskAttempt>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<SecretScanReport>>, ApiError> {
+    let container_ref = deployment
+        .container()
+        .ensure_container_exists(&task_attempt)
+        .await?;
+    let branch_name = task_attempt.branch.as_ref().ok_or_else(|| {
+        ApiError::TaskAttempt(TaskAttemptError::ValidationError(
+            "No branch found for task attempt".to_string(),
         ))
-    });
+    })?;
+    let report = secret_scan::scan_worktree(
+        std::path::Path::new(&container_ref),
+        branch_name,
+        &task_attempt.base_branch,
+    )?;
+    Ok(ResponseJson(ApiResponse::success(report)))
+}
+
+/// Assesses the attempt's current diff, and stores it for the task cards
+pub async fn get_task_attempt_diff_risk(
+    Extension(task_attempt): Extension<TaskAttempt>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<DiffRisk>>, ApiError> {
+    let pool = &deployment.db().pool;
+    let container_ref = deployment
+        .container()
+        .ensure_container_exists(&task_attempt)
+        .await?;
+    let branch_name = task_attempt.branch.as_ref().ok_or_else(|| {
+        ApiError::TaskAttempt(TaskAttemptError::ValidationError(
+            "No branch found for task attempt".to_string(),
+        ))
+    })?;
+    let project = task_attempt
+        .parent_task(pool)
+        .await?
+        .ok_or(ApiError::TaskAttempt(TaskAttemptError::TaskNotFound))?
+        .parent_project(pool)
+        .await?
+        .ok_or(SqlxError::RowNotFound)?;
+    let repo_config = RepoConfig::load(
+        deployment.git(),
+        &project.git_repo_path,
+        &task_attempt.base_branch,
+    )?;
+    let risk = diff_risk::assess_worktree(
+        std::path::Path::new(&container_ref),
+        branch_name,
+        &task_attempt.base_branch,
+        &repo_config.expected_paths,
+    )?;
+    AttemptDiffRisk::upsert(pool, task_attempt.id, &risk).await?;
+    Ok(ResponseJson(ApiResponse::success(risk)))
+}
 
-    let follow_up_action = ExecutorAction::new(
-        ExecutorActionType::CodingAgentFollowUpRequest(CodingAgentFollowUpRequest {
-            prompt: payload.prompt,
-            session_id,
-            profile_variant_label,
-        }),
-        cleanup_action,
-    );
+/// Lines added and removed by the attempt's diff per file and language, without the diff
+/// The changes in each of the attempt's linked repositories, shown next to its own diff
+pub async fn get_task_attempt_linked_repos(
+    Extension(task_attempt): Extension<TaskAttempt>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Vec<LinkedRepoDiff>>>, ApiError> {
+    let diffs =
+        linked_repos::linked_diffs(&deployment.db().pool, deployment.git(), task_attempt.id)
+            .await
+            .map_err(ContainerError::from)?;
+    Ok(ResponseJson(ApiResponse::success(diffs)))
+}
 
-    let execution_process = deployment
+pub async fn get_task_attempt_diff_stats(
+    Extension(task_attempt): Extension<TaskAttempt>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<DiffStats>>, ApiError> {
+    let pool = &deployment.db().pool;
+    let container_ref = deployment
         .container()
-        .start_execution(
+        .ensure_container_exists(&task_attempt)
+        .await?;
+    if task_attempt.merge_commit.is_none() && task_attempt.branch.is_none() {
+        return Err(ApiError::TaskAttempt(TaskAttemptError::ValidationError(
+            "No branch found for task attempt".to_string(),
+        )));
+    }
+    let project = task_attempt
+        .parent_task(pool)
+        .await?
+        .ok_or(ApiError::TaskAttempt(TaskAttemptError::TaskNotFound))?
+        .parent_project(pool)
+        .await?
+        .ok_or(SqlxError::RowNotFound)?;
+    let stats = deployment
+        .diff_stats()
+        .attempt_stats(
+            deployment.git(),
             &task_attempt,
-            &follow_up_action,
-            &ExecutionProcessRunReason::CodingAgent,
+            std::path::Path::new(&container_ref),
+            &project.git_repo_path,
         )
         .await?;
+    Ok(ResponseJson(ApiResponse::success(stats)))
+}
 
-    Ok(ResponseJson(ApiResponse::success(execution_process)))
+/// Changes of the attempt outside its project's path rules, empty without rules
+pub async fn get_task_attempt_path_violations(
+    Extension(task_attempt): Extension<TaskAttempt>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Vec<PathViolation>>>, ApiError> {
+    let pool = &deployment.db().pool;
+    let task = task_attempt
+        .parent_task(pool)
+        .await?
+        .ok_or(ApiError::TaskAttempt(TaskAttemptError::TaskNotFound))?;
+    let Some(rules) = ProjectPathRules::find_by_project_id(pool, task.project_id).await? else {
+        return Ok(ResponseJson(ApiResponse::success(Vec::new())));
+    };
+    let container_ref = deployment
+        .container()
+        .ensure_container_exists(&task_attempt)
+        .await?;
+    let branch_name = task_attempt.branch.as_ref().ok_or_else(|| {
+        ApiError::TaskAttempt(TaskAttemptError::ValidationError(
+            "No branch found for task attempt".to_string(),
+        ))
+    })?;
+    let violations = PathRules::from_project(&rules)?.worktree_violations(
+        std::path::Path::new(&container_ref),
+        branch_name,
+        &task_attempt.base_branch,
+    )?;
+    Ok(ResponseJson(ApiResponse::success(violations)))
+}
+
+pub async fn get_task_attempt_branch_sync(
+    Extension(task_attempt): Extension<TaskAttempt>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Option<AttemptBranchSync>>>, ApiError> {
+    let sync =
+        AttemptBranchSync::find_by_task_attempt_id(&deployment.db().pool, task_attempt.id).await?;
+    Ok(ResponseJson(ApiResponse::success(sync)))
+}
+
+/// GitHub checks of the attempt branch, with the stored GitHub credentials
+async fn load_attempt_checks(
+    deployment: &DeploymentImpl,
+    task_attempt: &TaskAttempt,
+    repo_path: &std::path::Path,
+) -> Result<AttemptChecks, ApiError> {
+    let branch = task_attempt.branch.as_ref().ok_or_else(|| {
+        ApiError::TaskAttempt(TaskAttemptError::ValidationError(
+            "No branch found for task attempt".to_string(),
+        ))
+    })?;
+    let github_config = deployment.config().read().await.github.clone();
+    let github_token = github_auth::access_token(&github_config)
+        .await?
+        .ok_or(GitHubServiceError::TokenInvalid)?;
+    let github_service = GitHubService::new(&github_token)?;
+    Ok(ci_checks::attempt_checks(
+        deployment.git(),
+        &github_service,
+        repo_path,
+        branch,
+        &task_attempt.base_branch,
+    )
+    .await?)
 }
 
-pub async fn get_task_attempt_diff(
+pub async fn get_task_attempt_checks(
     Extension(task_attempt): Extension<TaskAttempt>,
     State(deployment): State<DeploymentImpl>,
-    // ) -> Result<ResponseJson<ApiResponse<WorktreeDiff>>, ApiError> {
-) -> Result<Sse<impl futures_util::Stream<Item = Result<Event, BoxError>>>, axum::http::StatusCode>
-{
-    let stream = deployment
-        .container()
-        .get_diff(&task_attempt)
-        .await
-        .map_err(|_e| StatusCode::INTERNAL_SERVER_ERROR)?;
-
-    Ok(Sse::new(stream.map_err(|e| -> BoxError { e.into() })).keep_alive(KeepAlive::default()))
+) -> Result<ResponseJson<ApiResponse<AttemptChecks>>, ApiError> {
+    let pool = &deployment.db().pool;
+    let task = task_attempt
+        .parent_task(pool)
+        .await?
+        .ok_or(ApiError::TaskAttempt(TaskAttemptError::TaskNotFound))?;
+    let ctx = TaskAttempt::load_context(pool, task_attempt.id, task.id, task.project_id).await?;
+    let checks =
+        load_attempt_checks(&deployment, &task_attempt, &ctx.project.git_repo_path).await?;
+    Ok(ResponseJson(ApiResponse::success(checks)))
 }
 
 #[axum::debug_handler]
+/// Merge the attempt's linked repositories with its commit message, before the attempt itself
+/// so that it only counts as merged once all of them are. Ones merged by an earlier try that
+/// failed further on are skipped.
+async fn merge_linked_repos(
+    pool: &SqlitePool,
+    git: &GitService,
+    attempt_id: Uuid,
+    commit_message: &str,
+) -> Result<(), ApiError> {
+    // Check them all before merging any
+    let mut pending = Vec::new();
+    for linked in AttemptLinkedRepo::find_by_task_attempt_id(pool, attempt_id).await? {
+        if linked.merge_commit.is_some() {
+            continue;
+        }
+        let (Some(container_ref), Some(branch)) = (&linked.container_ref, &linked.branch) else {
+            continue;
+        };
+        let project = Project::find_by_id(pool, linked.project_id)
+            .await?
+            .ok_or(SqlxError::RowNotFound)?;
+        if git.is_tag(&project.git_repo_path, &linked.base_branch)? {
+            return Err(ApiError::PreconditionFailed(format!(
+                "{} of {} is a tag and can't be merged into, create PRs instead",
+                linked.base_branch, project.name
+            )));
+        }
+        pending.push((
+            linked.id,
+            project.git_repo_path,
+            container_ref.clone(),
+            branch.clone(),
+            linked.base_branch.clone(),
+        ));
+    }
+    for (id, repo_path, container_ref, branch, base_branch) in pending {
+        let merge_commit = git.merge_changes(
+            &repo_path,
+            std::path::Path::new(&container_ref),
+            &branch,
+            &base_branch,
+            commit_message,
+        )?;
+        AttemptLinkedRepo::update_merge_commit(pool, id, &merge_commit).await?;
+    }
+    Ok(())
+}
+
 pub async fn merge_task_attempt(
     Extension(task_attempt): Extension<TaskAttempt>,
     State(deployment): State<DeploymentImpl>,
+    Query(query): Query<SecretScanOverrideQuery>,
 ) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
     let pool = &deployment.db().pool;
 
@@ -430,28 +1130,55 @@ pub async fn merge_task_attempt(
         .await?;
     let worktree_path = std::path::Path::new(&container_ref);
 
-    let task_uuid_str = task.id.to_string();
-    let first_uuid_section = task_uuid_str.split('-').next().unwrap_or(&task_uuid_str);
-
-    // Create commit message with task title and description
-    let mut commit_message = format!("{} (vibe-kanban {})", ctx.task.title, first_uuid_section);
-
-    // Add description on next line if it exists
-    if let Some(description) = &ctx.task.description {
-        if !description.trim().is_empty() {
-            commit_message.push_str("\n\n");
-            commit_message.push_str(description);
-        }
-    }
-
     // Get branch name from task attempt
     let branch_name = ctx.task_attempt.branch.as_ref().ok_or_else(|| {
         ApiError::TaskAttempt(TaskAttemptError::ValidationError(
             "No branch found for task attempt".to_string(),
         ))
     })?;
+    if deployment
+        .git()
+        .is_tag(&ctx.project.git_repo_path, &ctx.task_attempt.base_branch)?
+    {
+        return Err(ApiError::PreconditionFailed(format!(
+            "{} is a tag and can't be merged into, create a PR against a branch instead",
+            ctx.task_attempt.base_branch
+        )));
+    }
+    ensure_no_secrets(
+        &ctx.task_attempt,
+        worktree_path,
+        branch_name,
+        query.allow_secrets,
+    )?;
+
+    if deployment.config().read().await.require_passing_checks {
+        let checks =
+            load_attempt_checks(&deployment, &ctx.task_attempt, &ctx.project.git_repo_path).await?;
+        if let Some(reason) = checks.state.blocking_reason() {
+            return Err(ApiError::PreconditionFailed(reason.to_string()));
+        }
+    }
+
+    // Attempts that changed the same files merge one at a time, when configured
+    let conflicts = deployment.conflicts();
+    let _merge_guard = if deployment
+        .config()
+        .read()
+        .await
+        .serialize_conflicting_merges
+        && conflicts.has_conflicts(ctx.project.id, task_attempt.id)
+    {
+        Some(conflicts.merge_guard(ctx.project.id).await)
+    } else {
+        None
+    };
 
-    let merge_commit_id = GitService::new().merge_changes(
+    let git = GitService::new();
+    let commit_message =
+        changelog::prepare_merge(pool, &git, &ctx, worktree_path, branch_name).await?;
+    merge_linked_repos(pool, &git, task_attempt.id, &commit_message).await?;
+    let merge_commit_id = git.merge_changes(
         &ctx.project.git_repo_path,
         worktree_path,
         branch_name,
@@ -461,9 +1188,10 @@ pub async fn merge_task_attempt(
 
     TaskAttempt::update_merge_commit(pool, task_attempt.id, &merge_commit_id).await?;
     Task::update_status(pool, ctx.task.id, TaskStatus::Done).await?;
+    conflicts.forget(ctx.project.id, task_attempt.id);
 
     deployment
-        .track_if_analytics_allowed(
+        .track_event(
             "task_attempt_merged",
             serde_json::json!({
                 "task_id": ctx.task.id.to_string(),
@@ -476,16 +1204,222 @@ pub async fn merge_task_attempt(
     Ok(ResponseJson(ApiResponse::success(())))
 }
 
+pub async fn get_task_attempt_merge_queue(
+    Extension(task_attempt): Extension<TaskAttempt>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<MergeQueuePosition>>, ApiError> {
+    let position = MergeQueuePosition::find(&deployment.db().pool, task_attempt.id).await?;
+    Ok(ResponseJson(ApiResponse::success(position)))
+}
+
+/// Queue the attempt to be merged once the project's earlier queued attempts landed. It is
+/// rebased onto its base branch and verified again right before it merges.
+pub async fn enqueue_task_attempt_merge(
+    Extension(task_attempt): Extension<TaskAttempt>,
+    State(deployment): State<DeploymentImpl>,
+    Query(query): Query<SecretScanOverrideQuery>,
+) -> Result<ResponseJson<ApiResponse<MergeQueuePosition>>, ApiError> {
+    let pool = &deployment.db().pool;
+
+    let task = task_attempt
+        .parent_task(pool)
+        .await?
+        .ok_or(ApiError::TaskAttempt(TaskAttemptError::TaskNotFound))?;
+    let ctx = TaskAttempt::load_context(pool, task_attempt.id, task.id, task.project_id).await?;
+
+    let container_ref = deployment
+        .container()
+        .ensure_container_exists(&task_attempt)
+        .await?;
+    let branch_name = ctx
+        .task_attempt
+        .branch
+        .as_ref()
+        .ok_or(MergeQueueError::NoBranch)?;
+    ensure_no_secrets(
+        &ctx.task_attempt,
+        std::path::Path::new(&container_ref),
+        branch_name,
+        query.allow_secrets,
+    )?;
+
+    merge_queue::enqueue(pool, &ctx).await?;
+
+    deployment
+        .track_event(
+            "task_attempt_merge_queued",
+            serde_json::json!({
+                "task_id": ctx.task.id.to_string(),
+                "project_id": ctx.project.id.to_string(),
+                "attempt_id": task_attempt.id.to_string(),
+            }),
+        )
+        .await;
+
+    let position = MergeQueuePosition::find(pool, task_attempt.id).await?;
+    Ok(ResponseJson(ApiResponse::success(position)))
+}
+
+/// Take the attempt out of the merge queue, only while it still waits for its turn
+pub async fn dequeue_task_attempt_merge(
+    Extension(task_attempt): Extension<TaskAttempt>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
+    if !MergeQueueEntry::dequeue(&deployment.db().pool, task_attempt.id).await? {
+        return Err(MergeQueueError::NotQueued.into());
+    }
+    Ok(ResponseJson(ApiResponse::success(())))
+}
+
+#[derive(serde::Deserialize)]
+pub struct UnmergeQuery {
+    /// Revert the merge even when the base branch has commits on top of it
+    #[serde(default)]
+    allow_subsequent: bool,
+}
+
+/// Undo the attempt's merge by reverting its commit on the base branch. Refused when commits
+/// landed on top of the merge since, unless `allow_subsequent` is set, and for merges done
+/// through a pull request.
+pub async fn unmerge_task_attempt(
+    Extension(task_attempt): Extension<TaskAttempt>,
+    State(deployment): State<DeploymentImpl>,
+    Query(query): Query<UnmergeQuery>,
+) -> Result<ResponseJson<ApiResponse<AttemptUnmerge>>, ApiError> {
+    let pool = &deployment.db().pool;
+    let merge_commit = task_attempt
+        .merge_commit
+        .clone()
+        .ok_or_else(|| ApiError::BadRequest("The attempt hasn't been merged".to_string()))?;
+    if task_attempt.pr_status.as_deref() == Some("merged") {
+        return Err(ApiError::BadRequest(
+            "The attempt was merged through its pull request, revert it there".to_string(),
+        ));
+    }
+
+    let task = task_attempt
+        .parent_task(pool)
+        .await?
+        .ok_or(ApiError::TaskAttempt(TaskAttemptError::TaskNotFound))?;
+    let ctx = TaskAttempt::load_context(pool, task_attempt.id, task.id, task.project_id).await?;
+    let repo_path = &ctx.project.git_repo_path;
+    let base_branch = &task_attempt.base_branch;
+
+    let git = GitService::new();
+    let subsequent_commits = git.commits_since(repo_path, base_branch, &merge_commit)?;
+    if subsequent_commits > 0 && !query.allow_subsequent {
+        return Err(ApiError::UnmergeNeedsConfirmation {
+            branch: base_branch.clone(),
+            commits: subsequent_commits,
+        });
+    }
+    let revert_commit = git.revert_commit(repo_path, base_branch, &merge_commit)?;
+
+    let unmerge = AttemptUnmerge::create(
+        pool,
+        &CreateAttemptUnmerge {
+            task_attempt_id: task_attempt.id,
+            base_branch: base_branch.clone(),
+            merge_commit,
+            revert_commit,
+            subsequent_commits: subsequent_commits as i64,
+        },
+        Uuid::new_v4(),
+    )
+    .await?;
+    TaskAttempt::clear_merge_commit(pool, task_attempt.id).await?;
+    Task::update_status(pool, task.id, TaskStatus::InReview).await?;
+
+    deployment
+        .track_event(
+            "task_attempt_unmerged",
+            serde_json::json!({
+                "task_id": task.id.to_string(),
+                "project_id": task.project_id.to_string(),
+                "attempt_id": task_attempt.id.to_string(),
+                "subsequent_commits": subsequent_commits,
+            }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(unmerge)))
+}
+
+/// Push the branches of linked repositories without a PR yet and open one for each, noting the
+/// repository they land together with. The ids of the linked repositories with their PR URLs.
+async fn create_linked_prs(
+    github_service: &GitHubService,
+    github_token: &str,
+    linked: &[(AttemptLinkedRepo, Project)],
+    request: &CreateGitHubPrRequest,
+    primary: &GitHubRepoInfo,
+) -> Result<Vec<(Uuid, String)>, GitHubServiceError> {
+    let git = GitService::new();
+    let mut created = Vec::new();
+    for (repo, project) in linked {
+        let (Some(container_ref), Some(branch), None) =
+            (&repo.container_ref, &repo.branch, &repo.pr_url)
+        else {
+            continue;
+        };
+        let (owner, repo_name) = git.get_github_repo_info(&project.git_repo_path)?;
+        git.push_to_github(std::path::Path::new(container_ref), branch, github_token)?;
+        let note = format!(
+            "Lands together with a change to {}/{}.",
+            primary.owner, primary.repo_name
+        );
+        let body = match &request.body {
+            Some(body) if !body.trim().is_empty() => format!("{body}\n\n{note}"),
+            _ => note,
+        };
+        let pr_request = CreatePrRequest {
+            title: request.title.clone(),
+            body: Some(body),
+            head_branch: branch.clone(),
+            base_branch: repo.base_branch.clone(),
+            head_repo: None,
+        };
+        let pr = github_service
+            .create_pr(&GitHubRepoInfo { owner, repo_name }, &pr_request)
+            .await?;
+        created.push((repo.id, pr.url));
+    }
+    Ok(created)
+}
+
+/// The PR body listing the linked repositories' PRs that have to land with it
+fn with_linked_prs(body: Option<String>, linked_pr_urls: &[String]) -> Option<String> {
+    if linked_pr_urls.is_empty() {
+        return body;
+    }
+    let mut note = "Lands together with:".to_string();
+    for url in linked_pr_urls {
+        note.push_str(&format!("\n- {url}"));
+    }
+    Some(match body.filter(|body| !body.trim().is_empty()) {
+        Some(body) => format!("{body}\n\n{note}"),
+        None => note,
+    })
+}
+
 pub async fn create_github_pr(
     Extension(task_attempt): Extension<TaskAttempt>,
     State(deployment): State<DeploymentImpl>,
+    Query(query): Query<SecretScanOverrideQuery>,
     Json(request): Json<CreateGitHubPrRequest>,
 ) -> Result<ResponseJson<ApiResponse<String, GitHubServiceError>>, ApiError> {
     let github_config = deployment.config().read().await.github.clone();
-    let Some(github_token) = github_config.token() else {
-        return Ok(ResponseJson(ApiResponse::error_with_data(
-            GitHubServiceError::TokenInvalid,
-        )));
+    let github_token = match github_auth::access_token(&github_config).await {
+        Ok(Some(token)) => token,
+        Ok(None) => {
+            return Ok(ResponseJson(ApiResponse::error_with_data(
+                GitHubServiceError::TokenInvalid,
+            )));
+        }
+        Err(e) if e.is_api_data() => {
+            return Ok(ResponseJson(ApiResponse::error_with_data(e)));
+        }
+        Err(e) => return Err(ApiError::GitHubService(e)),
     };
     // Create GitHub service instance
     let github_service = GitHubService::new(&github_token)?;
@@ -516,6 +1450,7 @@ pub async fn create_github_pr(
         .await?
         .ok_or(ApiError::TaskAttempt(TaskAttemptError::TaskNotFound))?;
     let ctx = TaskAttempt::load_context(pool, task_attempt.id, task.id, task.project_id).await?;
+    ensure_review_approved(&deployment, &task_attempt, task.project_id).await?;
 
     // Ensure worktree exists (recreate if needed for cold task support)
     let container_ref = deployment
@@ -534,25 +1469,89 @@ pub async fn create_github_pr(
             "No branch found for task attempt".to_string(),
         ))
     })?;
+    ensure_no_secrets(
+        &ctx.task_attempt,
+        worktree_path,
+        branch_name,
+        query.allow_secrets,
+    )?;
+
+    let push_to_fork = match request.push_to_fork {
+        Some(push_to_fork) => push_to_fork,
+        None => match github_service.can_push(&repo_info).await {
+            Ok(can_push) => !can_push,
+            Err(e) if e.is_api_data() => return Ok(ResponseJson(ApiResponse::error_with_data(e))),
+            Err(e) => return Err(ApiError::GitHubService(e)),
+        },
+    };
+    let head_repo = if push_to_fork {
+        match github_service.ensure_fork(&repo_info).await {
+            Ok(fork) => Some(fork),
+            Err(e) if e.is_api_data() => return Ok(ResponseJson(ApiResponse::error_with_data(e))),
+            Err(e) => return Err(ApiError::GitHubService(e)),
+        }
+    } else {
+        None
+    };
 
     // Push the branch to GitHub first
-    if let Err(e) = GitService::new().push_to_github(worktree_path, branch_name, &github_token) {
+    let push_result = match &head_repo {
+        Some(fork) => GitService::new().push_to_url(
+            worktree_path,
+            &fork.https_url(),
+            branch_name,
+            &github_token,
+        ),
+        None => GitService::new().push_to_github(worktree_path, branch_name, &github_token),
+    };
+    if let Err(e) = push_result {
         tracing::error!("Failed to push branch to GitHub: {}", e);
         let gh_e = GitHubServiceError::from(e);
         if gh_e.is_api_data() {
             return Ok(ResponseJson(ApiResponse::error_with_data(gh_e)));
         } else {
-            return Ok(ResponseJson(ApiResponse::error(
-                "Failed to push branch to GitHub",
+            return Ok(ResponseJson(ApiResponse::localized_error(
+                MessageCode::GitHubPushFailed,
             )));
         }
     }
+    // Linked repositories get their PRs first, so that this one can list them
+    let mut linked = Vec::new();
+    for repo in AttemptLinkedRepo::find_by_task_attempt_id(pool, task_attempt.id).await? {
+        let project = Project::find_by_id(pool, repo.project_id)
+            .await?
+            .ok_or(SqlxError::RowNotFound)?;
+        linked.push((repo, project));
+    }
+    let new_linked_prs = match create_linked_prs(
+        &github_service,
+        &github_token,
+        &linked,
+        &request,
+        &repo_info,
+    )
+    .await
+    {
+        Ok(prs) => prs,
+        Err(e) if e.is_api_data() => return Ok(ResponseJson(ApiResponse::error_with_data(e))),
+        Err(e) => return Err(ApiError::GitHubService(e)),
+    };
+    let mut linked_pr_urls: Vec<String> = linked
+        .iter()
+        .filter_map(|(repo, _)| repo.pr_url.clone())
+        .collect();
+    for (id, url) in new_linked_prs {
+        AttemptLinkedRepo::update_pr_url(pool, id, &url).await?;
+        linked_pr_urls.push(url);
+    }
+
     // Create the PR using GitHub service
     let pr_request = CreatePrRequest {
         title: request.title.clone(),
-        body: request.body.clone(),
+        body: with_linked_prs(request.body.clone(), &linked_pr_urls),
         head_branch: branch_name.clone(),
         base_branch: base_branch.clone(),
+        head_repo: head_repo.clone(),
     };
 
     match github_service.create_pr(&repo_info, &pr_request).await {
@@ -571,12 +1570,13 @@ pub async fn create_github_pr(
             }
 
             deployment
-                .track_if_analytics_allowed(
+                .track_event(
                     "github_pr_created",
                     serde_json::json!({
                         "task_id": ctx.task.id.to_string(),
                         "project_id": ctx.project.id.to_string(),
                         "attempt_id": task_attempt.id.to_string(),
+                        "from_fork": head_repo.is_some(),
                     }),
                 )
                 .await;
@@ -592,7 +1592,9 @@ pub async fn create_github_pr(
             if e.is_api_data() {
                 Ok(ResponseJson(ApiResponse::error_with_data(e)))
             } else {
-                Ok(ResponseJson(ApiResponse::error("Failed to create PR")))
+                Ok(ResponseJson(ApiResponse::localized_error(
+                    MessageCode::PullRequestCreateFailed,
+                )))
             }
         }
     }
@@ -602,13 +1604,14 @@ pub async fn create_github_pr(
 pub struct OpenEditorRequest {
     editor_type: Option<String>,
     file_path: Option<String>,
+    line: Option<u32>,
 }
 
 pub async fn open_task_attempt_in_editor(
     Extension(task_attempt): Extension<TaskAttempt>,
     State(deployment): State<DeploymentImpl>,
     Json(payload): Json<Option<OpenEditorRequest>>,
-) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
+) -> Result<ResponseJson<ApiResponse<EditorOpenResponse>>, ApiError> {
     // Get the task attempt to access the worktree path
     let attempt = &task_attempt;
     let base_path = attempt.container_ref.as_ref().ok_or_else(|| {
@@ -622,11 +1625,10 @@ pub async fn open_task_attempt_in_editor(
     })?;
 
     // If a specific file path is provided, use it; otherwise use the base path
-    let path = if let Some(file_path) = payload.as_ref().and_then(|req| req.file_path.as_ref()) {
-        std::path::Path::new(base_path).join(file_path)
-    } else {
-        std::path::PathBuf::from(base_path)
-    };
+    let path = editor::worktree_path(
+        std::path::Path::new(base_path),
+        payload.as_ref().and_then(|req| req.file_path.as_deref()),
+    )?;
 
     let editor_config = {
         let config = deployment.config().read().await;
@@ -634,24 +1636,16 @@ pub async fn open_task_attempt_in_editor(
         config.editor.with_override(editor_type_str)
     };
 
-    match editor_config.open_file(&path.to_string_lossy()) {
-        Ok(_) => {
-            tracing::info!(
-                "Opened editor for task attempt {} at path: {}",
-                task_attempt.id,
-                path.display()
-            );
-            Ok(ResponseJson(ApiResponse::success(())))
-        }
+    let line = payload.as_ref().and_then(|req| req.line);
+    match editor::open_path(&editor_config, &path, line, None) {
+        Ok(response) => Ok(ResponseJson(ApiResponse::success(response))),
         Err(e) => {
             tracing::error!(
                 "Failed to open editor for attempt {}: {}",
                 task_attempt.id,
                 e
             );
-            Err(ApiError::TaskAttempt(TaskAttemptError::ValidationError(
-                format!("Failed to open editor: {}", e),
-            )))
+            Err(e)
         }
     }
 }
@@ -841,14 +1835,75 @@ pub async fn start_dev_server(
             )
             .await?
     } else {
-        return Ok(ResponseJson(ApiResponse::error(
-            "No dev server script configured for this project",
+        return Ok(ResponseJson(ApiResponse::localized_error(
+            MessageCode::DevServerScriptMissing,
         )));
     };
 
     Ok(ResponseJson(ApiResponse::success(())))
 }
 
+/// URLs of the attempt's running dev server, discovered from its output or port file
+pub async fn get_dev_server(
+    Extension(task_attempt): Extension<TaskAttempt>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Option<DevServerInfo>>>, ApiError> {
+    let dev_server = ExecutionProcess::find_latest_by_task_attempt_and_run_reason(
+        &deployment.db().pool,
+        task_attempt.id,
+        &ExecutionProcessRunReason::DevServer,
+    )
+    .await?
+    .filter(|process| process.status == ExecutionProcessStatus::Running);
+    let (Some(dev_server), Some(worktree)) = (dev_server, task_attempt.container_ref.as_deref())
+    else {
+        return Ok(ResponseJson(ApiResponse::success(None)));
+    };
+
+    let info = deployment
+        .dev_servers()
+        .info(dev_server.id, std::path::Path::new(worktree))
+        .await;
+    Ok(ResponseJson(ApiResponse::success(Some(info))))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ContextFileQuery {
+    pub file_name: String,
+}
+
+pub async fn get_context_files(
+    Extension(task_attempt): Extension<TaskAttempt>,
+) -> Result<ResponseJson<ApiResponse<Vec<ContextFile>>>, ApiError> {
+    let files = match task_attempt.container_ref.as_deref() {
+        Some(worktree) => {
+            ContextFolder::new(std::path::Path::new(worktree))
+                .list()
+                .await?
+        }
+        None => Vec::new(),
+    };
+    Ok(ResponseJson(ApiResponse::success(files)))
+}
+
+/// Write a reference file, sent as the raw request body, into the attempt's context folder.
+/// The next follow-up mentions it to the agent.
+pub async fn upload_context_file(
+    Extension(task_attempt): Extension<TaskAttempt>,
+    State(deployment): State<DeploymentImpl>,
+    Query(query): Query<ContextFileQuery>,
+    body: Bytes,
+) -> Result<ResponseJson<ApiResponse<ContextFile>>, ApiError> {
+    let container_ref = deployment
+        .container()
+        .ensure_container_exists(&task_attempt)
+        .await?;
+    let file = ContextFolder::new(std::path::Path::new(&container_ref))
+        .save(&query.file_name, &body)
+        .await?;
+    Ok(ResponseJson(ApiResponse::success(file)))
+}
+
 // /// Find plan content with context by searching through multiple processes in the same attempt
 // async fn find_plan_content_with_context(
 //     pool: &SqlitePool,
@@ -1012,28 +2067,281 @@ pub async fn stop_task_attempt_execution(
     Ok(ResponseJson(ApiResponse::success(())))
 }
 
+/// Stop the attempt's agent in place, to free the CPU without losing its progress
+pub async fn pause_task_attempt(
+    Extension(task_attempt): Extension<TaskAttempt>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
+    deployment.container().pause_attempt(&task_attempt).await?;
+    Ok(ResponseJson(ApiResponse::success(())))
+}
+
+/// Continue the attempt's paused executions, or when none are paused, the agent session a
+/// shutdown or reboot cut off
+pub async fn resume_task_attempt(
+    Extension(task_attempt): Extension<TaskAttempt>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
+    deployment.container().resume_attempt(&task_attempt).await?;
+    Ok(ResponseJson(ApiResponse::success(())))
+}
+
+/// The agent session of the attempt a shutdown or reboot cut off, if it wasn't resumed yet
+pub async fn get_task_attempt_suspended(
+    Extension(task_attempt): Extension<TaskAttempt>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Option<SuspendedExecution>>>, ApiError> {
+    let suspended =
+        SuspendedExecution::find_pending_by_task_attempt_id(&deployment.db().pool, task_attempt.id)
+            .await?;
+    Ok(ResponseJson(ApiResponse::success(suspended)))
+}
+
+/// Snapshots of the attempt's worktree it can be rolled back to, newest first
+pub async fn get_task_attempt_checkpoints(
+    Extension(task_attempt): Extension<TaskAttempt>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Vec<AttemptCheckpoint>>>, ApiError> {
+    let checkpoints =
+        AttemptCheckpoint::find_by_task_attempt_id(&deployment.db().pool, task_attempt.id).await?;
+    Ok(ResponseJson(ApiResponse::success(checkpoints)))
+}
+
+#[derive(serde::Deserialize)]
+pub struct RollbackQuery {
+    checkpoint: Uuid,
+}
+
+/// Roll the attempt's worktree back to a checkpoint. Returns the checkpoint of the worktree
+/// as it was before, to undo the rollback with.
+pub async fn rollback_task_attempt(
+    Extension(task_attempt): Extension<TaskAttempt>,
+    Query(query): Query<RollbackQuery>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Option<AttemptCheckpoint>>>, ApiError> {
+    // Running executions would keep editing the restored worktree
+    let processes =
+        ExecutionProcess::find_by_task_attempt_id(&deployment.db().pool, task_attempt.id).await?;
+    if processes.iter().any(|p| {
+        p.status == ExecutionProcessStatus::Running
+            && p.run_reason != ExecutionProcessRunReason::DevServer
+    }) {
+        return Err(CheckpointError::ExecutionRunning.into());
+    }
+
+    let container_ref = deployment
+        .container()
+        .ensure_container_exists(&task_attempt)
+        .await?;
+    let backup = deployment
+        .checkpoints()
+        .rollback(
+            task_attempt.id,
+            std::path::Path::new(&container_ref),
+            query.checkpoint,
+        )
+        .await?;
+    Ok(ResponseJson(ApiResponse::success(backup)))
+}
+
+pub async fn get_task_attempt_runtime(
+    Extension(task_attempt): Extension<TaskAttempt>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<AttemptRuntime>>, ApiError> {
+    let pool = &deployment.db().pool;
+    let processes = ExecutionProcess::find_by_task_attempt_id(pool, task_attempt.id).await?;
+    let pauses = ExecutionPause::find_by_task_attempt_id(pool, task_attempt.id).await?;
+    Ok(ResponseJson(ApiResponse::success(AttemptRuntime::compute(
+        &processes,
+        &pauses,
+        chrono::Utc::now(),
+    ))))
+}
+
+/// The attempt's phases with their boundaries, for seeing where its time went
+pub async fn get_task_attempt_timeline(
+    Extension(task_attempt): Extension<TaskAttempt>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<AttemptTimeline>>, ApiError> {
+    let pool = &deployment.db().pool;
+    let processes = ExecutionProcess::find_by_task_attempt_id(pool, task_attempt.id).await?;
+    let pauses = ExecutionPause::find_by_task_attempt_id(pool, task_attempt.id).await?;
+    Ok(ResponseJson(ApiResponse::success(
+        AttemptTimeline::compute(
+            task_attempt.created_at,
+            &processes,
+            &pauses,
+            chrono::Utc::now(),
+        ),
+    )))
+}
+
+/// All output of the attempt's executions as gzipped plain text. The log is compressed while
+/// it's read from the database, so large sessions are never held in memory at once.
+pub async fn download_task_attempt_raw_logs(
+    Extension(task_attempt): Extension<TaskAttempt>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<Response, ApiError> {
+    let pool = deployment.db().pool.clone();
+    let processes = ExecutionProcess::find_by_task_attempt_id(&pool, task_attempt.id).await?;
+    let text = log_export::raw_log_text(pool, processes).map_ok(std::io::Cursor::new);
+    let gzip = GzipEncoder::new(StreamReader::new(text));
+    let response = Response::builder()
+        .status(StatusCode::OK)
+        .header(header::CONTENT_TYPE, "application/gzip")
+        .header(
+            header::CONTENT_DISPOSITION,
+            format!(
+                "attachment; filename=\"attempt-{}.log.gz\"",
+                task_attempt.id
+            ),
+        )
+        .body(Body::from_stream(ReaderStream::new(gzip)))
+        .unwrap();
+    Ok(response)
+}
+
+#[derive(Debug, Deserialize)]
+pub struct LogExportQuery {
+    /// Comma-separated entry types, e.g. `tool_use,assistant`
+    pub types: Option<String>,
+    pub since: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+/// The normalized entries of the attempt's coding agent runs as JSON Lines, one entry per
+/// line tagged with its process, for analysis outside the app. Processes are normalized one
+/// at a time as the response is written.
+pub async fn export_task_attempt_logs(
+    Extension(task_attempt): Extension<TaskAttempt>,
+    State(deployment): State<DeploymentImpl>,
+    Query(query): Query<LogExportQuery>,
+) -> Result<Response, ApiError> {
+    let filter = log_export::EntryFilter::new(query.types.as_deref(), query.since)
+        .map_err(|e| ApiError::BadRequest(e.to_string()))?;
+    let processes =
+        ExecutionProcess::find_by_task_attempt_id(&deployment.db().pool, task_attempt.id).await?;
+    let lines = futures_util::stream::iter(processes).then(move |process| {
+        let deployment = deployment.clone();
+        let filter = filter.clone();
+        async move {
+            let entries = deployment
+                .container()
+                .normalized_entries(&process)
+                .await
+                .map_err(std::io::Error::other)?;
+            Ok::<_, std::io::Error>(log_export::entries_jsonl(&process, &entries, &filter))
+        }
+    });
+    let response = Response::builder()
+        .status(StatusCode::OK)
+        .header(header::CONTENT_TYPE, "application/x-ndjson")
+        .header(
+            header::CONTENT_DISPOSITION,
+            format!("attachment; filename=\"attempt-{}.jsonl\"", task_attempt.id),
+        )
+        .body(Body::from_stream(lines))
+        .unwrap();
+    Ok(response)
+}
+
+/// Results of the attempt's test stage, newest first
+pub async fn get_task_attempt_test_runs(
+    Extension(task_attempt): Extension<TaskAttempt>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Vec<TestRun>>>, ApiError> {
+    let test_runs =
+        TestRun::find_by_task_attempt_id(&deployment.db().pool, task_attempt.id).await?;
+    Ok(ResponseJson(ApiResponse::success(test_runs)))
+}
+
 pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
+    // Retried attempt creation or merges must not start agents or merge twice
+    let idempotency = from_fn_with_state(IdempotencyStore::new(), idempotency_middleware);
+
     let task_attempt_id_router = Router::new()
         .route("/", get(get_task_attempt))
         .route("/follow-up", post(follow_up))
+        .route(
+            "/follow-up-queue",
+            get(get_follow_up_queue)
+                .post(queue_follow_up)
+                .put(reorder_follow_up_queue),
+        )
         .route("/start-dev-server", post(start_dev_server))
+        .route("/dev-server", get(get_dev_server))
+        .route(
+            "/context-files",
+            get(get_context_files)
+                .post(upload_context_file)
+                .layer(DefaultBodyLimit::max(MAX_ATTACHMENT_BYTES)),
+        )
         .route("/branch-status", get(get_task_attempt_branch_status))
         .route("/diff", get(get_task_attempt_diff))
-        .route("/merge", post(merge_task_attempt))
+        .route("/diff/stats", get(get_task_attempt_diff_stats))
+        .route("/linked-repos", get(get_task_attempt_linked_repos))
+        .route(
+            "/merge",
+            post(merge_task_attempt).layer(idempotency.clone()),
+        )
+        .route(
+            "/merge-queue",
+            get(get_task_attempt_merge_queue)
+                .post(enqueue_task_attempt_merge)
+                .delete(dequeue_task_attempt_merge),
+        )
+        .route("/checks", get(get_task_attempt_checks))
+        .route("/unmerge", post(unmerge_task_attempt))
         .route("/rebase", post(rebase_task_attempt))
         .route("/pr", post(create_github_pr))
         .route("/open-editor", post(open_task_attempt_in_editor))
         .route("/delete-file", post(delete_task_attempt_file))
         .route("/children", get(get_task_attempt_children))
         .route("/stop", post(stop_task_attempt_execution))
+        .route("/pause", post(pause_task_attempt))
+        .route("/resume", post(resume_task_attempt))
+        .route("/suspended", get(get_task_attempt_suspended))
+        .route("/runtime", get(get_task_attempt_runtime))
+        .route("/checkpoints", get(get_task_attempt_checkpoints))
+        .route("/rollback", post(rollback_task_attempt))
+        .route("/timeline", get(get_task_attempt_timeline))
+        .route("/logs/raw.gz", get(download_task_attempt_raw_logs))
+        .route("/logs/export", get(export_task_attempt_logs))
+        .route("/test-runs", get(get_task_attempt_test_runs))
+        .route("/secret-scan", get(get_task_attempt_secret_scan))
+        .route("/diff-risk", get(get_task_attempt_diff_risk))
+        .route("/path-violations", get(get_task_attempt_path_violations))
+        .route("/branch-sync", get(get_task_attempt_branch_sync))
+        .route("/plan", get(get_task_attempt_plan))
+        .route("/reviews", get(get_task_attempt_reviews))
+        .route(
+            "/commit-plan",
+            get(get_task_attempt_commit_plan).post(create_task_attempt_commit_plan),
+        )
+        .route("/tool-usage", get(get_task_attempt_tool_usage))
+        .route("/plan/approve", post(approve_task_attempt_plan))
+        .route("/plan/reject", post(reject_task_attempt_plan))
+        .route("/terminal", get(terminal::attempt_terminal))
+        .route(
+            "/share-links",
+            get(share::get_share_links).post(share::create_share_link),
+        )
+        .route("/share-links/{link_id}", delete(share::revoke_share_link))
         .layer(from_fn_with_state(
             deployment.clone(),
             load_task_attempt_middleware,
         ));
 
     let task_attempts_router = Router::new()
-        .route("/", get(get_task_attempts).post(create_task_attempt))
+        .route(
+            "/",
+            get(get_task_attempts).merge(post(create_task_attempt).layer(idempotency)),
+        )
         .nest("/{id}", task_attempt_id_router);
 
-    Router::new().nest("/task-attempts", task_attempts_router)
+    Router::new()
+        .nest("/task-attempts", task_attempts_router)
+        .route(
+            "/queued-follow-ups/{queued_follow_up_id}",
+            delete(delete_queued_follow_up),
+        )
 }
@@ -1,8 +1,9 @@
 use axum::{
-    extract::{Query, State},
+    extract::{DefaultBodyLimit, Query, State},
+    http::{header, HeaderMap},
     middleware::from_fn_with_state,
     response::Json as ResponseJson,
-    routing::{get, post},
+    routing::{get, post, put},
     Extension, Json, Router,
 };
 use db::models::{
@@ -11,13 +12,34 @@ use db::models::{
     task_attempt::{CreateTaskAttempt, TaskAttempt, TaskAttemptError},
 };
 use deployment::Deployment;
+use executors::profile::{ProfileConfigs, ProfileVariantLabel};
 use serde::Deserialize;
-use services::services::{container::ContainerService, git::GitService};
+use services::services::{
+    attachments::{AttachmentStore, MAX_ATTACHMENT_BYTES},
+    container::ContainerService,
+    git::{GitService, GitServiceError},
+    quick_task::{QuickCreateTask, QuickTask, QuickTaskError},
+    repo_config::RepoConfig,
+};
 use sqlx::Error as SqlxError;
-use utils::response::ApiResponse;
+use tracing::Instrument;
+use utils::{
+    otel::attempt_span,
+    pagination::{ListQuery, Paginated},
+    response::ApiResponse,
+};
 use uuid::Uuid;
 
-use crate::{error::ApiError, middleware::load_task_middleware, DeploymentImpl};
+use crate::{
+    error::ApiError,
+    etag::{check_if_match, with_etag, WithETag},
+    middleware::{idempotency_middleware, load_task_middleware, IdempotencyStore},
+    routes::{
+        spend,
+        task_attachments::{list_attachments, upload_attachment},
+    },
+    DeploymentImpl,
+};
 
 #[derive(Debug, Deserialize)]
 pub struct TaskQuery {
@@ -27,19 +49,36 @@ pub struct TaskQuery {
 pub async fn get_tasks(
     State(deployment): State<DeploymentImpl>,
     Query(query): Query<TaskQuery>,
-) -> Result<ResponseJson<ApiResponse<Vec<TaskWithAttemptStatus>>>, ApiError> {
+    list: ListQuery,
+) -> Result<ResponseJson<ApiResponse<Paginated<TaskWithAttemptStatus>>>, ApiError> {
     let tasks =
         Task::find_by_project_id_with_attempt_status(&deployment.db().pool, query.project_id)
             .await?;
+    let page = list.apply(tasks).map_err(ApiError::BadRequest)?;
 
-    Ok(ResponseJson(ApiResponse::success(tasks)))
+    Ok(ResponseJson(ApiResponse::paginated(page)))
 }
 
 pub async fn get_task(
     Extension(task): Extension<Task>,
     State(_deployment): State<DeploymentImpl>,
-) -> Result<ResponseJson<ApiResponse<Task>>, ApiError> {
-    Ok(ResponseJson(ApiResponse::success(task)))
+) -> Result<WithETag<ResponseJson<ApiResponse<Task>>>, ApiError> {
+    Ok(with_etag(
+        &task,
+        ResponseJson(ApiResponse::success(task.clone())),
+    ))
+}
+
+/// Fails with a bad request unless `project` has a branch or tag called `base_branch` for
+/// attempts to start from
+pub(crate) fn ensure_base_exists(project: &Project, base_branch: &str) -> Result<(), ApiError> {
+    match GitService::new().ensure_base_exists(&project.git_repo_path, base_branch) {
+        Err(GitServiceError::BranchNotFound(_)) => Err(ApiError::BadRequest(format!(
+            "{} has no branch or tag named {}",
+            project.name, base_branch
+        ))),
+        result => result.map_err(ApiError::from),
+    }
 }
 
 pub async fn create_task(
@@ -48,6 +87,13 @@ pub async fn create_task(
 ) -> Result<ResponseJson<ApiResponse<Task>>, ApiError> {
     let id = Uuid::new_v4();
 
+    if let Some(base_branch) = &payload.base_branch {
+        let project = Project::find_by_id(&deployment.db().pool, payload.project_id)
+            .await?
+            .ok_or(ApiError::Database(SqlxError::RowNotFound))?;
+        ensure_base_exists(&project, base_branch)?;
+    }
+
     tracing::debug!(
         "Creating task '{}' in project {}",
         payload.title,
@@ -58,7 +104,7 @@ pub async fn create_task(
 
     // Track task creation event
     deployment
-        .track_if_analytics_allowed(
+        .track_event(
             "task_created",
             serde_json::json!({
             "task_id": task.id.to_string(),
@@ -75,11 +121,85 @@ pub async fn create_task_and_start(
     State(deployment): State<DeploymentImpl>,
     Json(payload): Json<CreateTask>,
 ) -> Result<ResponseJson<ApiResponse<TaskWithAttemptStatus>>, ApiError> {
+    let task = start_new_task(&deployment, &payload, None).await?;
+    Ok(ResponseJson(ApiResponse::success(task)))
+}
+
+/// POST /tasks/quick: a task from one line of text, see [`QuickTask`] for the tokens. An
+/// `@profile` token starts its first attempt with that profile right away.
+pub async fn quick_create_task(
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<QuickCreateTask>,
+) -> Result<ResponseJson<ApiResponse<Task>>, ApiError> {
+    let pool = &deployment.db().pool;
+    let bad_request = |e: QuickTaskError| ApiError::BadRequest(e.to_string());
+    let quick = QuickTask::parse(&payload.text).map_err(bad_request)?;
+    let profile = quick
+        .profile_label(&ProfileConfigs::get_cached())
+        .map_err(bad_request)?;
+    let projects = Project::find_all(pool).await?;
+    let create = quick
+        .into_create_task(&projects, payload.project_id)
+        .map_err(bad_request)?;
+
+    let started = profile.is_some();
+    let task = match profile {
+        Some(profile) => {
+            let task = start_new_task(&deployment, &create, Some(profile)).await?;
+            Task::find_by_id(pool, task.id)
+                .await?
+                .ok_or(ApiError::Database(SqlxError::RowNotFound))?
+        }
+        None => {
+            let task = Task::create(pool, &create, Uuid::new_v4()).await?;
+            deployment
+                .track_event(
+                    "task_created",
+                    serde_json::json!({
+                        "task_id": task.id.to_string(),
+                        "project_id": task.project_id,
+                        "has_description": false,
+                    }),
+                )
+                .await;
+            task
+        }
+    };
+    deployment
+        .track_event(
+            "task_quick_created",
+            serde_json::json!({
+                "task_id": task.id.to_string(),
+                "has_priority": task.priority.is_some(),
+                "labels": task.labels.len(),
+                "started": started,
+            }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(task)))
+}
+
+/// Create a task and start its first attempt on the task's base branch or else the project's
+/// current branch, with `profile` or else the default profile
+pub(crate) async fn start_new_task(
+    deployment: &DeploymentImpl,
+    payload: &CreateTask,
+    profile: Option<ProfileVariantLabel>,
+) -> Result<TaskWithAttemptStatus, ApiError> {
+    spend::ensure_within_budget(deployment, payload.project_id).await?;
+    let project = Project::find_by_id(&deployment.db().pool, payload.project_id)
+        .await?
+        .ok_or(ApiError::Database(SqlxError::RowNotFound))?;
+    if let Some(base_branch) = &payload.base_branch {
+        ensure_base_exists(&project, base_branch)?;
+    }
+
     // create the task first
     let task_id = Uuid::new_v4();
-    let task = Task::create(&deployment.db().pool, &payload, task_id).await?;
+    let task = Task::create(&deployment.db().pool, payload, task_id).await?;
     deployment
-        .track_if_analytics_allowed(
+        .track_event(
             "task_created",
             serde_json::json!({
                 "task_id": task.id.to_string(),
@@ -89,12 +209,16 @@ pub async fn create_task_and_start(
         )
         .await;
 
-    // use the default executor profile and the current branch for the task attempt
-    let default_profile_variant = deployment.config().read().await.profile.clone();
-    let project = Project::find_by_id(&deployment.db().pool, payload.project_id)
-        .await?
-        .ok_or(ApiError::Database(SqlxError::RowNotFound))?;
-    let branch = GitService::new().get_current_branch(&project.git_repo_path)?;
+    // use the default executor profile and the current branch unless the task has its own
+    let branch = match &task.base_branch {
+        Some(base_branch) => base_branch.clone(),
+        None => GitService::new().get_current_branch(&project.git_repo_path)?,
+    };
+    let default_profile_variant = match profile {
+        Some(profile) => profile,
+        None => RepoConfig::load(deployment.git(), &project.git_repo_path, &branch)?
+            .profile_or(deployment.config().read().await.profile.clone()),
+    };
     let profile_label = executors::profile::ProfileConfigs::get_cached()
         .get_profile(&default_profile_variant.profile)
         .map(|profile| profile.default.label.clone())
@@ -116,10 +240,11 @@ pub async fn create_task_and_start(
     .await?;
     let execution_process = deployment
         .container()
-        .start_attempt(&task_attempt, default_profile_variant.clone())
+        .start_attempt(&task_attempt, default_profile_variant.clone(), false)
+        .instrument(attempt_span("start_attempt", task_attempt.id))
         .await?;
     deployment
-        .track_if_analytics_allowed(
+        .track_event(
             "task_attempt_started",
             serde_json::json!({
                 "task_id": task.id.to_string(),
@@ -135,27 +260,39 @@ pub async fn create_task_and_start(
         .ok_or(ApiError::Database(SqlxError::RowNotFound))?;
 
     tracing::info!("Started execution process {}", execution_process.id);
-    Ok(ResponseJson(ApiResponse::success(TaskWithAttemptStatus {
+    Ok(TaskWithAttemptStatus {
         id: task.id,
         title: task.title,
         description: task.description,
         project_id: task.project_id,
         status: task.status,
         parent_task_attempt: task.parent_task_attempt,
+        priority: task.priority,
+        labels: task.labels,
+        base_branch: task.base_branch,
         created_at: task.created_at,
         updated_at: task.updated_at,
         has_in_progress_attempt: true,
         has_merged_attempt: false,
         last_attempt_failed: false,
         profile: task_attempt.profile,
-    })))
+        tests_passed: None,
+        tests_failed: None,
+        diff_risk: None,
+        branch_sync_status: None,
+        plan_status: None,
+        review_verdict: None,
+    })
 }
 
 pub async fn update_task(
     Extension(existing_task): Extension<Task>,
     State(deployment): State<DeploymentImpl>,
+    headers: HeaderMap,
     Json(payload): Json<UpdateTask>,
-) -> Result<ResponseJson<ApiResponse<Task>>, ApiError> {
+) -> Result<WithETag<ResponseJson<ApiResponse<Task>>>, ApiError> {
+    check_if_match(&headers, &existing_task)?;
+
     // Use existing values if not provided in update
     let title = payload.title.unwrap_or(existing_task.title);
     let description = payload.description.or(existing_task.description);
@@ -164,17 +301,73 @@ pub async fn update_task(
         .parent_task_attempt
         .or(existing_task.parent_task_attempt);
 
-    let task = Task::update(
-        &deployment.db().pool,
-        existing_task.id,
-        existing_task.project_id,
-        title,
-        description,
-        status,
-        parent_task_attempt,
-    )
-    .await?;
+    let pool = &deployment.db().pool;
+    let task = if headers.contains_key(header::IF_MATCH) {
+        // The check above is only a fast path, the update itself makes sure nothing was
+        // written since `existing_task` was loaded
+        Task::update_if_unmodified(
+            pool,
+            &existing_task,
+            title,
+            description,
+            status,
+            parent_task_attempt,
+        )
+        .await?
+        .ok_or_else(|| {
+            ApiError::PreconditionFailed(
+                "Resource was modified since it was last fetched".to_string(),
+            )
+        })?
+    } else {
+        Task::update(
+            pool,
+            existing_task.id,
+            existing_task.project_id,
+            title,
+            description,
+            status,
+            parent_task_attempt,
+        )
+        .await?
+    };
+
+    Ok(with_etag(
+        &task,
+        ResponseJson(ApiResponse::success(task.clone())),
+    ))
+}
+
+#[derive(Debug, Deserialize, ts_rs::TS)]
+pub struct SetTaskBaseBranch {
+    /// `None` goes back to the project's current branch
+    pub base_branch: Option<String>,
+}
 
+/// PUT /tasks/{task_id}/base-branch: the branch or tag the task's next attempts start from.
+/// Existing attempts keep their worktrees and base.
+pub async fn set_task_base_branch(
+    Extension(task): Extension<Task>,
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<SetTaskBaseBranch>,
+) -> Result<ResponseJson<ApiResponse<Task>>, ApiError> {
+    let pool = &deployment.db().pool;
+    let base_branch = payload
+        .base_branch
+        .as_deref()
+        .map(str::trim)
+        .filter(|base_branch| !base_branch.is_empty());
+    if let Some(base_branch) = base_branch {
+        let project = task
+            .parent_project(pool)
+            .await?
+            .ok_or(ApiError::Database(SqlxError::RowNotFound))?;
+        ensure_base_exists(&project, base_branch)?;
+    }
+    Task::set_base_branch(pool, task.id, base_branch).await?;
+    let task = Task::find_by_id(pool, task.id)
+        .await?
+        .ok_or(ApiError::Database(SqlxError::RowNotFound))?;
     Ok(ResponseJson(ApiResponse::success(task)))
 }
 
@@ -187,6 +380,7 @@ pub async fn delete_task(
         .unwrap_or_default();
     // Delete all attempts including their containers
     for attempt in attempts {
+        deployment.conflicts().forget(task.project_id, attempt.id);
         deployment
             .container()
             .delete(&attempt)
@@ -201,6 +395,9 @@ pub async fn delete_task(
             });
     }
     let rows_affected = Task::delete(&deployment.db().pool, task.id).await?;
+    if let Err(e) = AttachmentStore::default().delete_task(task.id).await {
+        tracing::warn!("Failed to delete attachments of task {}: {}", task.id, e);
+    }
 
     if rows_affected == 0 {
         Err(ApiError::Database(SqlxError::RowNotFound))
@@ -212,13 +409,130 @@ pub async fn delete_task(
 pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
     let task_id_router = Router::new()
         .route("/", get(get_task).put(update_task).delete(delete_task))
+        .route("/base-branch", put(set_task_base_branch))
+        .route(
+            "/attachments",
+            get(list_attachments)
+                .post(upload_attachment)
+                .layer(DefaultBodyLimit::max(MAX_ATTACHMENT_BYTES)),
+        )
         .layer(from_fn_with_state(deployment.clone(), load_task_middleware));
 
     let inner = Router::new()
         .route("/", get(get_tasks).post(create_task))
-        .route("/create-and-start", post(create_task_and_start))
+        .route("/quick", post(quick_create_task))
+        .route(
+            "/create-and-start",
+            post(create_task_and_start).layer(from_fn_with_state(
+                IdempotencyStore::new(),
+                idempotency_middleware,
+            )),
+        )
         .nest("/{task_id}", task_id_router);
 
     // mount under /projects/:project_id/tasks
     Router::new().nest("/tasks", inner)
 }
+
+#[cfg(test)]
+mod tests {
+    use axum::{
+        body::Body,
+        http::{self, StatusCode},
+    };
+    use db::models::{project::CreateProject, project_disk_quota::ProjectDiskQuota};
+    use tower::ServiceExt;
+
+    use super::*;
+
+    fn new_task(project_id: Uuid, title: &str) -> CreateTask {
+        CreateTask {
+            project_id,
+            title: title.to_string(),
+            description: None,
+            parent_task_attempt: None,
+            priority: None,
+            labels: Vec::new(),
+            base_branch: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_create_and_start_refuses_over_disk_quota() {
+        let data_dir = tempfile::tempdir().unwrap();
+        utils::assets::set_asset_dir(data_dir.path().to_path_buf());
+        let deployment = DeploymentImpl::new().await.unwrap();
+        let pool = &deployment.db().pool;
+
+        let repo = tempfile::tempdir().unwrap();
+        GitService::new()
+            .initialize_repo_with_main_branch(repo.path())
+            .unwrap();
+        let project = Project::create(
+            pool,
+            &CreateProject {
+                name: "web".to_string(),
+                git_repo_path: repo.path().to_string_lossy().into_owned(),
+                use_existing_repo: true,
+                setup_script: None,
+                dev_script: None,
+                cleanup_script: None,
+                copy_files: None,
+                test_script: None,
+                test_result_format: None,
+            },
+            Uuid::new_v4(),
+        )
+        .await
+        .unwrap();
+
+        // An earlier attempt's worktree already takes up more than the quota
+        let worktree = tempfile::tempdir().unwrap();
+        std::fs::write(worktree.path().join("build.log"), "x".repeat(1024)).unwrap();
+        let task = Task::create(pool, &new_task(project.id, "Build"), Uuid::new_v4())
+            .await
+            .unwrap();
+        let attempt = TaskAttempt::create(
+            pool,
+            &CreateTaskAttempt {
+                profile: "claude-code".to_string(),
+                base_branch: "main".to_string(),
+            },
+            task.id,
+        )
+        .await
+        .unwrap();
+        TaskAttempt::update_container_ref(pool, attempt.id, &worktree.path().to_string_lossy())
+            .await
+            .unwrap();
+        ProjectDiskQuota::upsert(pool, project.id, 100, false)
+            .await
+            .unwrap();
+
+        let body = serde_json::to_string(&serde_json::json!({
+            "project_id": project.id,
+            "title": "Fix the build",
+            "description": null,
+            "parent_task_attempt": null,
+        }))
+        .unwrap();
+        let response = router(&deployment)
+            .with_state(deployment.clone())
+            .oneshot(
+                http::Request::post("/tasks/create-and-start")
+                    .header(header::CONTENT_TYPE, "application/json")
+                    .body(Body::from(body))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::INSUFFICIENT_STORAGE);
+
+        // The new attempt never got a worktree
+        let with_worktree = TaskAttempt::find_with_worktree_by_project_id(pool, project.id)
+            .await
+            .unwrap();
+        assert_eq!(with_worktree.len(), 1);
+        assert_eq!(with_worktree[0].id, attempt.id);
+    }
+}
@@ -0,0 +1,193 @@
+use std::path::Path;
+
+use axum::{
+    extract::{
+        ws::{Message, WebSocket, WebSocketUpgrade},
+        Query, State,
+    },
+    http::{header, uri::Authority, HeaderMap, Uri},
+    response::Response,
+    Extension,
+};
+use db::models::task_attempt::{TaskAttempt, TaskAttemptError};
+use deployment::Deployment;
+use futures_util::{SinkExt, StreamExt};
+use serde::{Deserialize, Serialize};
+use services::services::{
+    container::ContainerError,
+    pty::{PtyCommand, PtyError, PtyEvent, PtySession, PtySize},
+};
+use ts_rs::TS;
+
+use crate::{error::ApiError, DeploymentImpl};
+
+#[derive(Debug, Deserialize, TS)]
+pub struct TerminalQuery {
+    /// Command to run, an interactive shell when omitted
+    pub command: Option<String>,
+    pub cols: Option<u16>,
+    pub rows: Option<u16>,
+}
+
+/// Sent by the browser as text frames
+#[derive(Debug, Deserialize, TS)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum TerminalClientMessage {
+    Input { data: String },
+    Resize { cols: u16, rows: u16 },
+}
+
+/// Sent to the browser as text frames, terminal output arrives as binary frames
+#[derive(Debug, Serialize, TS)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum TerminalServerMessage {
+    Exit { code: Option<u32> },
+    Error { message: String },
+}
+
+/// Whether a websocket upgrade comes from a page served by this server. Browsers let any
+/// site open websockets to localhost, only the `Origin` they send tells them apart. Clients
+/// other than browsers don't send one.
+fn is_own_origin(headers: &HeaderMap) -> bool {
+    let Some(origin) = headers.get(header::ORIGIN) else {
+        return true;
+    };
+    let origin = origin.to_str().ok().and_then(|o| o.parse::<Uri>().ok());
+    let host = headers
+        .get(header::HOST)
+        .and_then(|h| h.to_str().ok())
+        .and_then(|h| h.parse::<Authority>().ok());
+    match (origin.as_ref().and_then(Uri::authority), host) {
+        (Some(origin), Some(host)) => {
+            origin == &host
+                // The frontend dev server proxies the API from its own port
+                || (cfg!(debug_assertions) && origin.host().eq_ignore_ascii_case(host.host()))
+        }
+        _ => false,
+    }
+}
+
+/// Open a terminal in the attempt's worktree over a websocket. The process is started
+/// before the upgrade so a refused command is reported as a normal API error.
+pub async fn attempt_terminal(
+    ws: WebSocketUpgrade,
+    Extension(task_attempt): Extension<TaskAttempt>,
+    State(deployment): State<DeploymentImpl>,
+    Query(query): Query<TerminalQuery>,
+    headers: HeaderMap,
+) -> Result<Response, ApiError> {
+    if !is_own_origin(&headers) {
+        let origin = headers
+            .get(header::ORIGIN)
+            .and_then(|o| o.to_str().ok())
+            .unwrap_or_default();
+        return Err(PtyError::CrossOrigin(origin.to_string()).into());
+    }
+    if deployment.shutdown().is_draining() {
+        return Err(ApiError::Container(ContainerError::ShuttingDown));
+    }
+    let worktree = task_attempt
+        .container_ref
+        .as_deref()
+        .map(Path::new)
+        .filter(|path| path.is_dir())
+        .ok_or_else(|| {
+            ApiError::TaskAttempt(TaskAttemptError::ValidationError(
+                "The attempt has no worktree".to_string(),
+            ))
+        })?;
+
+    let terminal_config = deployment.config().read().await.terminal.clone();
+    let command = PtyCommand::from_config(&terminal_config, query.command.as_deref())?;
+    let size = PtySize {
+        rows: query.rows.unwrap_or(24),
+        cols: query.cols.unwrap_or(80),
+        ..Default::default()
+    };
+    let session = deployment.pty().spawn(worktree, command, size)?;
+    tracing::info!(
+        "Opened terminal session {} for task attempt {}",
+        session.id,
+        task_attempt.id
+    );
+
+    Ok(ws.on_upgrade(move |socket| run_session(socket, session)))
+}
+
+async fn run_session(socket: WebSocket, mut session: PtySession) {
+    let (mut sender, mut receiver) = socket.split();
+    let send_message = |message: TerminalServerMessage| {
+        Message::Text(serde_json::to_string(&message).unwrap_or_default().into())
+    };
+
+    loop {
+        tokio::select! {
+            event = session.next_event() => match event {
+                Some(PtyEvent::Output(data)) => {
+                    if sender.send(Message::Binary(data.into())).await.is_err() {
+                        break;
+                    }
+                }
+                Some(PtyEvent::Exit(code)) => {
+                    let _ = sender.send(send_message(TerminalServerMessage::Exit { code })).await;
+                    break;
+                }
+                None => break,
+            },
+            message = receiver.next() => match message {
+                Some(Ok(Message::Text(text))) => {
+                    let result = match serde_json::from_str::<TerminalClientMessage>(&text) {
+                        Ok(TerminalClientMessage::Input { data }) => session.write(data.as_bytes()),
+                        Ok(TerminalClientMessage::Resize { cols, rows }) => session.resize(cols, rows),
+                        Err(e) => {
+                            let message = format!("Invalid terminal message: {e}");
+                            let _ = sender.send(send_message(TerminalServerMessage::Error { message })).await;
+                            continue;
+                        }
+                    };
+                    if let Err(e) = result {
+                        let message = e.to_string();
+                        let _ = sender.send(send_message(TerminalServerMessage::Error { message })).await;
+                        break;
+                    }
+                }
+                // Closing the socket ends the session, dropping it stops the processes
+                Some(Ok(Message::Close(_))) | Some(Err(_)) | None => break,
+                Some(Ok(_)) => {}
+            },
+        }
+    }
+
+    tracing::info!("Closed terminal session {}", session.id);
+    let _ = sender.close().await;
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::http::HeaderValue;
+
+    use super::*;
+
+    fn headers(origin: Option<&'static str>, host: &'static str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(header::HOST, HeaderValue::from_static(host));
+        if let Some(origin) = origin {
+            headers.insert(header::ORIGIN, HeaderValue::from_static(origin));
+        }
+        headers
+    }
+
+    #[test]
+    fn test_is_own_origin() {
+        assert!(is_own_origin(&headers(
+            Some("http://127.0.0.1:3001"),
+            "127.0.0.1:3001"
+        )));
+        assert!(is_own_origin(&headers(None, "127.0.0.1:3001")));
+        assert!(!is_own_origin(&headers(
+            Some("https://evil.example"),
+            "127.0.0.1:3001"
+        )));
+        assert!(!is_own_origin(&headers(Some("null"), "127.0.0.1:3001")));
+    }
+}
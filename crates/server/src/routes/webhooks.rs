@@ -0,0 +1,119 @@
+use axum::{
+    extract::{Path, Query, State},
+    response::Json as ResponseJson,
+    routing::{get, post, put},
+    Json, Router,
+};
+use db::models::webhook::{CreateWebhook, UpdateWebhook, Webhook, WebhookDelivery};
+use deployment::Deployment;
+use serde::{Deserialize, Serialize};
+use services::services::webhooks::{self, WebhookError};
+use ts_rs::TS;
+use utils::response::ApiResponse;
+use uuid::Uuid;
+
+use crate::{error::ApiError, DeploymentImpl};
+
+const DEFAULT_DELIVERY_LIMIT: i64 = 50;
+const MAX_DELIVERY_LIMIT: i64 = 500;
+
+/// A webhook with its signing secret, only returned when the secret is set
+#[derive(Debug, Serialize, TS)]
+pub struct WebhookWithSecret {
+    pub webhook: Webhook,
+    pub secret: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct DeliveryQuery {
+    pub limit: Option<i64>,
+}
+
+pub async fn get_webhooks(
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Vec<Webhook>>>, ApiError> {
+    Ok(ResponseJson(ApiResponse::success(
+        Webhook::find_all(&deployment.db().pool).await?,
+    )))
+}
+
+pub async fn create_webhook(
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<CreateWebhook>,
+) -> Result<ResponseJson<ApiResponse<WebhookWithSecret>>, ApiError> {
+    let (webhook, secret) = webhooks::create(&deployment.db().pool, &payload).await?;
+    deployment
+        .track_event(
+            "webhook_created",
+            serde_json::json!({ "events": payload.events.len() }),
+        )
+        .await;
+    Ok(ResponseJson(ApiResponse::success(WebhookWithSecret {
+        webhook,
+        secret,
+    })))
+}
+
+pub async fn update_webhook(
+    Path(id): Path<Uuid>,
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<UpdateWebhook>,
+) -> Result<ResponseJson<ApiResponse<Webhook>>, ApiError> {
+    webhooks::validate_url(&payload.url)?;
+    let webhook = Webhook::update(&deployment.db().pool, id, &payload)
+        .await?
+        .ok_or(WebhookError::NotFound)?;
+    Ok(ResponseJson(ApiResponse::success(webhook)))
+}
+
+pub async fn delete_webhook(
+    Path(id): Path<Uuid>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
+    webhooks::delete(&deployment.db().pool, id).await?;
+    Ok(ResponseJson(ApiResponse::success(())))
+}
+
+/// Replace the signing secret, deliveries still pending are signed with the new one
+pub async fn rotate_webhook_secret(
+    Path(id): Path<Uuid>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<String>>, ApiError> {
+    Ok(ResponseJson(ApiResponse::success(
+        webhooks::rotate_secret(&deployment.db().pool, id).await?,
+    )))
+}
+
+/// Queue a ping, delivered within seconds like any other event
+pub async fn ping_webhook(
+    Path(id): Path<Uuid>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<WebhookDelivery>>, ApiError> {
+    Ok(ResponseJson(ApiResponse::success(
+        webhooks::ping(&deployment.db().pool, id).await?,
+    )))
+}
+
+/// Recent deliveries of a webhook, newest first
+pub async fn get_webhook_deliveries(
+    Path(id): Path<Uuid>,
+    State(deployment): State<DeploymentImpl>,
+    Query(query): Query<DeliveryQuery>,
+) -> Result<ResponseJson<ApiResponse<Vec<WebhookDelivery>>>, ApiError> {
+    let limit = query
+        .limit
+        .unwrap_or(DEFAULT_DELIVERY_LIMIT)
+        .clamp(1, MAX_DELIVERY_LIMIT);
+    Ok(ResponseJson(ApiResponse::success(
+        WebhookDelivery::find_by_webhook_id(&deployment.db().pool, id, limit).await?,
+    )))
+}
+
+pub fn router() -> Router<DeploymentImpl> {
+    Router::new()
+        .route("/webhooks", get(get_webhooks).post(create_webhook))
+        .route("/webhooks/{id}", put(update_webhook).delete(delete_webhook))
+        .route("/webhooks/{id}/secret", post(rotate_webhook_secret))
+        .route("/webhooks/{id}/ping", post(ping_webhook))
+        .route("/webhooks/{id}/deliveries", get(get_webhook_deliveries))
+}
@@ -0,0 +1,90 @@
+use axum::{
+    response::Json as ResponseJson,
+    routing::{get, post},
+    Json, Router,
+};
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+use utils::{
+    assets::asset_dir,
+    response::ApiResponse,
+    workspaces::{self, DEFAULT_WORKSPACE},
+};
+
+use crate::{error::ApiError, DeploymentImpl};
+
+#[derive(Debug, Serialize, TS)]
+pub struct WorkspaceInfo {
+    pub name: String,
+    pub path: String,
+}
+
+#[derive(Debug, Serialize, TS)]
+pub struct WorkspacesResponse {
+    /// Workspace this server is running on
+    pub active: String,
+    /// Workspace that will be opened on the next start
+    pub selected: String,
+    pub workspaces: Vec<WorkspaceInfo>,
+}
+
+#[derive(Debug, Deserialize, TS)]
+pub struct WorkspaceRequest {
+    pub name: String,
+}
+
+fn active_and_base() -> (String, std::path::PathBuf) {
+    match workspaces::active() {
+        Some((name, base)) => (name.to_string(), base.to_path_buf()),
+        None => (DEFAULT_WORKSPACE.to_string(), asset_dir()),
+    }
+}
+
+fn workspaces_response() -> WorkspacesResponse {
+    let (active, base) = active_and_base();
+    let selected = workspaces::read_selected(&base).unwrap_or_else(|| active.clone());
+    let workspaces = workspaces::list(&base)
+        .into_iter()
+        .map(|name| WorkspaceInfo {
+            path: workspaces::workspace_dir(&base, &name)
+                .to_string_lossy()
+                .to_string(),
+            name,
+        })
+        .collect();
+    WorkspacesResponse {
+        active,
+        selected,
+        workspaces,
+    }
+}
+
+pub async fn list_workspaces() -> Result<ResponseJson<ApiResponse<WorkspacesResponse>>, ApiError> {
+    Ok(ResponseJson(ApiResponse::success(workspaces_response())))
+}
+
+pub async fn create_workspace(
+    Json(payload): Json<WorkspaceRequest>,
+) -> Result<ResponseJson<ApiResponse<WorkspacesResponse>>, ApiError> {
+    let (_, base) = active_and_base();
+    workspaces::create(&base, &payload.name).map_err(ApiError::BadRequest)?;
+    Ok(ResponseJson(ApiResponse::success(workspaces_response())))
+}
+
+/// Workspaces own the database and config, so switching takes effect after a restart
+pub async fn select_workspace(
+    Json(payload): Json<WorkspaceRequest>,
+) -> Result<ResponseJson<ApiResponse<WorkspacesResponse>>, ApiError> {
+    let (_, base) = active_and_base();
+    workspaces::write_selected(&base, &payload.name).map_err(ApiError::BadRequest)?;
+    Ok(ResponseJson(ApiResponse::success(workspaces_response())))
+}
+
+pub fn router() -> Router<DeploymentImpl> {
+    Router::new().nest(
+        "/workspaces",
+        Router::new()
+            .route("/", get(list_workspaces).post(create_workspace))
+            .route("/select", post(select_workspace)),
+    )
+}
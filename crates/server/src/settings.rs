@@ -0,0 +1,537 @@
+use std::path::{Path, PathBuf};
+
+use clap::Parser;
+use serde::Deserialize;
+use services::services::baseline::BaselineSource;
+use thiserror::Error;
+use utils::{
+    assets::asset_dir,
+    workspaces::{self, DEFAULT_WORKSPACE},
+};
+
+/// Prefix for environment variables that override server settings
+pub const ENV_PREFIX: &str = "VK_";
+
+const DEFAULT_HOST: &str = "127.0.0.1";
+const DEFAULT_LOG_LEVEL: &str = "info";
+const SETTINGS_FILE_NAME: &str = "server.toml";
+/// Prompts with pasted logs can be large, so this is well above axum's 2 MiB default
+const DEFAULT_MAX_BODY_BYTES: usize = 16 * 1024 * 1024;
+
+#[derive(Debug, Error)]
+pub enum SettingsError {
+    #[error("Failed to read settings file {path}: {source}")]
+    Io {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+    #[error("Failed to parse settings file {path}: {source}")]
+    Parse {
+        path: PathBuf,
+        source: toml::de::Error,
+    },
+    #[error("Invalid value '{value}' for {key}")]
+    InvalidEnv { key: String, value: String },
+    #[error("Invalid workspace: {0}")]
+    InvalidWorkspace(String),
+    #[error("Invalid baseline checksum: {0}")]
+    InvalidBaselineChecksum(String),
+}
+
+/// Command line flags, the highest precedence layer
+#[derive(Debug, Default, Parser)]
+#[command(name = "vibe-kanban", version, about)]
+pub struct Cli {
+    /// Path to a TOML settings file (defaults to server.toml in the data directory)
+    #[arg(long)]
+    pub config: Option<PathBuf>,
+    #[arg(long)]
+    pub host: Option<String>,
+    /// Port to listen on, 0 picks a free port
+    #[arg(long)]
+    pub port: Option<u16>,
+    /// Directory for the database, config and profiles
+    #[arg(long)]
+    pub data_dir: Option<PathBuf>,
+    /// Named workspace to open, each has its own database, config and profiles
+    #[arg(long)]
+    pub workspace: Option<String>,
+    /// Directory task attempt worktrees are created in
+    #[arg(long)]
+    pub workspace_dir: Option<PathBuf>,
+    /// Log level for vibe-kanban crates, e.g. `debug`
+    #[arg(long)]
+    pub log_level: Option<String>,
+    /// URL of an organization baseline config, merged beneath the user's settings
+    #[arg(long)]
+    pub baseline_url: Option<String>,
+    /// SHA-256 the baseline's content must have, required with a baseline URL
+    #[arg(long)]
+    pub baseline_sha256: Option<String>,
+    /// OTLP collector to export traces to over HTTP, e.g. `http://localhost:4318`
+    #[arg(long)]
+    pub otlp_traces_endpoint: Option<String>,
+    /// Largest request body accepted, in bytes
+    #[arg(long)]
+    pub max_body_bytes: Option<usize>,
+    /// Requests per minute each client may make to the API, unlimited when unset or 0
+    #[arg(long)]
+    pub rate_limit_per_minute: Option<u32>,
+    /// Serve the frontend from this directory instead of the one built into the binary
+    #[arg(long)]
+    pub frontend_dir: Option<PathBuf>,
+    /// Don't contact outside services: telemetry, Sentry, baseline fetches, GitHub polling
+    #[arg(long)]
+    pub offline: bool,
+    /// Show running attempts and quick actions in the system tray, needs the `tray` feature
+    #[arg(long)]
+    pub tray: bool,
+}
+
+/// One source of settings, unset fields fall through to the layer below
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct SettingsLayer {
+    pub host: Option<String>,
+    pub port: Option<u16>,
+    pub data_dir: Option<PathBuf>,
+    pub workspace: Option<String>,
+    pub workspace_dir: Option<PathBuf>,
+    pub log_level: Option<String>,
+    pub baseline_url: Option<String>,
+    pub baseline_sha256: Option<String>,
+    pub otlp_traces_endpoint: Option<String>,
+    pub max_body_bytes: Option<usize>,
+    pub rate_limit_per_minute: Option<u32>,
+    pub frontend_dir: Option<PathBuf>,
+    pub offline: Option<bool>,
+    pub tray: Option<bool>,
+}
+
+impl SettingsLayer {
+    /// Fields set in `other` win over fields set in `self`
+    fn overlay(self, other: SettingsLayer) -> SettingsLayer {
+        SettingsLayer {
+            host: other.host.or(self.host),
+            port: other.port.or(self.port),
+            data_dir: other.data_dir.or(self.data_dir),
+            workspace: other.workspace.or(self.workspace),
+            workspace_dir: other.workspace_dir.or(self.workspace_dir),
+            log_level: other.log_level.or(self.log_level),
+            baseline_url: other.baseline_url.or(self.baseline_url),
+            baseline_sha256: other.baseline_sha256.or(self.baseline_sha256),
+            otlp_traces_endpoint: other.otlp_traces_endpoint.or(self.otlp_traces_endpoint),
+            max_body_bytes: other.max_body_bytes.or(self.max_body_bytes),
+            rate_limit_per_minute: other.rate_limit_per_minute.or(self.rate_limit_per_minute),
+            frontend_dir: other.frontend_dir.or(self.frontend_dir),
+            offline: other.offline.or(self.offline),
+            tray: other.tray.or(self.tray),
+        }
+    }
+
+    /// Reads `VK_*` variables, falling back to the legacy `HOST`, `BACKEND_PORT`, `PORT`
+    /// and `RUST_LOG` names.
+    fn from_env<I>(vars: I) -> Result<SettingsLayer, SettingsError>
+    where
+        I: IntoIterator<Item = (String, String)>,
+    {
+        let vars: std::collections::HashMap<String, String> = vars.into_iter().collect();
+        let get = |names: &[&str]| {
+            names.iter().find_map(|name| {
+                vars.get(*name)
+                    .map(|v| (name.to_string(), v.trim().to_string()))
+                    .filter(|(_, v)| !v.is_empty())
+            })
+        };
+        let key = |name: &str| format!("{ENV_PREFIX}{name}");
+
+        let port = match get(&[key("PORT").as_str(), "BACKEND_PORT", "PORT"]) {
+            Some((name, value)) => {
+                // Dev scripts can pass the port through colored output
+                let cleaned = String::from_utf8(strip_ansi_escapes::strip(value.as_bytes()))
+                    .unwrap_or_default();
+                Some(
+                    cleaned
+                        .trim()
+                        .parse::<u16>()
+                        .map_err(|_| SettingsError::InvalidEnv { key: name, value })?,
+                )
+            }
+            None => None,
+        };
+        let max_body_bytes = match get(&[key("MAX_BODY_BYTES").as_str()]) {
+            Some((name, value)) => Some(
+                value
+                    .parse::<usize>()
+                    .map_err(|_| SettingsError::InvalidEnv { key: name, value })?,
+            ),
+            None => None,
+        };
+        let rate_limit_per_minute = match get(&[key("RATE_LIMIT_PER_MINUTE").as_str()]) {
+            Some((name, value)) => Some(
+                value
+                    .parse::<u32>()
+                    .map_err(|_| SettingsError::InvalidEnv { key: name, value })?,
+            ),
+            None => None,
+        };
+        let flag = |name: &str| match get(&[key(name).as_str()]) {
+            Some((name, value)) => match value.to_ascii_lowercase().as_str() {
+                "1" | "true" | "yes" => Ok(Some(true)),
+                "0" | "false" | "no" => Ok(Some(false)),
+                _ => Err(SettingsError::InvalidEnv { key: name, value }),
+            },
+            None => Ok(None),
+        };
+        let offline = flag("OFFLINE")?;
+        let tray = flag("TRAY")?;
+
+        Ok(SettingsLayer {
+            host: get(&[key("HOST").as_str(), "HOST"]).map(|(_, v)| v),
+            port,
+            data_dir: get(&[key("DATA_DIR").as_str()]).map(|(_, v)| PathBuf::from(v)),
+            workspace: get(&[key("WORKSPACE").as_str()]).map(|(_, v)| v),
+            workspace_dir: get(&[key("WORKSPACE_DIR").as_str()]).map(|(_, v)| PathBuf::from(v)),
+            log_level: get(&[key("LOG_LEVEL").as_str(), "RUST_LOG"]).map(|(_, v)| v),
+            baseline_url: get(&[key("BASELINE_URL").as_str()]).map(|(_, v)| v),
+            baseline_sha256: get(&[key("BASELINE_SHA256").as_str()]).map(|(_, v)| v),
+            otlp_traces_endpoint: get(&[key("OTLP_TRACES_ENDPOINT").as_str()]).map(|(_, v)| v),
+            max_body_bytes,
+            rate_limit_per_minute,
+            frontend_dir: get(&[key("FRONTEND_DIR").as_str()]).map(|(_, v)| PathBuf::from(v)),
+            offline,
+            tray,
+        })
+    }
+
+    fn from_file(path: &Path) -> Result<SettingsLayer, SettingsError> {
+        let content = std::fs::read_to_string(path).map_err(|source| SettingsError::Io {
+            path: path.to_path_buf(),
+            source,
+        })?;
+        toml::from_str(&content).map_err(|source| SettingsError::Parse {
+            path: path.to_path_buf(),
+            source,
+        })
+    }
+}
+
+impl From<&Cli> for SettingsLayer {
+    fn from(cli: &Cli) -> Self {
+        SettingsLayer {
+            host: cli.host.clone(),
+            port: cli.port,
+            data_dir: cli.data_dir.clone(),
+            workspace: cli.workspace.clone(),
+            workspace_dir: cli.workspace_dir.clone(),
+            log_level: cli.log_level.clone(),
+            baseline_url: cli.baseline_url.clone(),
+            baseline_sha256: cli.baseline_sha256.clone(),
+            otlp_traces_endpoint: cli.otlp_traces_endpoint.clone(),
+            max_body_bytes: cli.max_body_bytes,
+            rate_limit_per_minute: cli.rate_limit_per_minute,
+            frontend_dir: cli.frontend_dir.clone(),
+            // A flag can only turn offline mode on
+            offline: cli.offline.then_some(true),
+            tray: cli.tray.then_some(true),
+        }
+    }
+}
+
+/// Resolved server settings: defaults < settings file < `VK_` env vars < CLI flags.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ServerSettings {
+    pub host: String,
+    pub port: u16,
+    /// `None` keeps the platform default data directory
+    pub data_dir: Option<PathBuf>,
+    /// `None` opens the workspace last selected through the API, or the default one
+    pub workspace: Option<String>,
+    /// `None` keeps the default worktree directory under the system temp dir
+    pub workspace_dir: Option<PathBuf>,
+    pub log_level: String,
+    /// Organization baseline fetched at startup, `None` when no URL is set
+    pub baseline_url: Option<String>,
+    pub baseline_sha256: Option<String>,
+    /// Traces are only exported when a collector is set
+    pub otlp_traces_endpoint: Option<String>,
+    pub max_body_bytes: usize,
+    /// `None` leaves the API unlimited
+    pub rate_limit_per_minute: Option<u32>,
+    /// `None` serves the frontend built into the binary
+    pub frontend_dir: Option<PathBuf>,
+    pub offline: bool,
+    pub tray: bool,
+}
+
+impl ServerSettings {
+    /// Resolve settings from the process' CLI arguments and environment
+    pub fn load() -> Result<Self, SettingsError> {
+        Self::load_from(&Cli::parse(), std::env::vars())
+    }
+
+    pub fn load_from<I>(cli: &Cli, env: I) -> Result<Self, SettingsError>
+    where
+        I: IntoIterator<Item = (String, String)>,
+    {
+        let env: Vec<(String, String)> = env.into_iter().collect();
+        let config_key = format!("{ENV_PREFIX}CONFIG");
+        let file_path = cli.config.clone().or_else(|| {
+            env.iter()
+                .find(|(k, v)| *k == config_key && !v.trim().is_empty())
+                .map(|(_, v)| PathBuf::from(v.trim()))
+        });
+        let env = SettingsLayer::from_env(env)?;
+        let file = match file_path {
+            Some(path) => SettingsLayer::from_file(&path)?,
+            None => {
+                let default_path = asset_dir().join(SETTINGS_FILE_NAME);
+                if default_path.exists() {
+                    SettingsLayer::from_file(&default_path)?
+                } else {
+                    SettingsLayer::default()
+                }
+            }
+        };
+
+        Ok(Self::resolve(file, env, cli.into()))
+    }
+
+    fn resolve(file: SettingsLayer, env: SettingsLayer, cli: SettingsLayer) -> Self {
+        let merged = file.overlay(env).overlay(cli);
+        ServerSettings {
+            host: merged.host.unwrap_or_else(|| DEFAULT_HOST.to_string()),
+            port: merged.port.unwrap_or(0),
+            data_dir: merged.data_dir,
+            workspace: merged.workspace,
+            workspace_dir: merged.workspace_dir,
+            log_level: merged
+                .log_level
+                .unwrap_or_else(|| DEFAULT_LOG_LEVEL.to_string()),
+            baseline_url: merged.baseline_url,
+            baseline_sha256: merged.baseline_sha256,
+            otlp_traces_endpoint: merged.otlp_traces_endpoint,
+            max_body_bytes: merged.max_body_bytes.unwrap_or(DEFAULT_MAX_BODY_BYTES),
+            rate_limit_per_minute: merged.rate_limit_per_minute.filter(|limit| *limit > 0),
+            frontend_dir: merged.frontend_dir,
+            offline: merged.offline.unwrap_or(false),
+            tray: merged.tray.unwrap_or(false),
+        }
+    }
+
+    /// The baseline to fetch, which must come with the SHA-256 of its content
+    pub fn baseline_source(&self) -> Result<Option<BaselineSource>, SettingsError> {
+        let Some(url) = &self.baseline_url else {
+            return Ok(None);
+        };
+        let sha256 = self.baseline_sha256.as_deref().unwrap_or_default().trim();
+        if sha256.len() != 64 || !sha256.chars().all(|c| c.is_ascii_hexdigit()) {
+            return Err(SettingsError::InvalidBaselineChecksum(format!(
+                "the baseline URL needs the hex SHA-256 of its content, got '{sha256}'"
+            )));
+        }
+        Ok(Some(BaselineSource {
+            url: url.clone(),
+            sha256: sha256.to_string(),
+        }))
+    }
+
+    /// Point the global data and workspace directories at the configured paths.
+    /// Must run before anything reads them, i.e. before the deployment is created.
+    pub fn apply_directories(&self) -> Result<(), SettingsError> {
+        let base = self.data_dir.clone().unwrap_or_else(asset_dir);
+        let workspace = self
+            .workspace
+            .clone()
+            .or_else(|| workspaces::read_selected(&base))
+            .unwrap_or_else(|| DEFAULT_WORKSPACE.to_string());
+        workspaces::validate_name(&workspace).map_err(SettingsError::InvalidWorkspace)?;
+
+        if self.data_dir.is_some() || workspace != DEFAULT_WORKSPACE {
+            utils::assets::set_asset_dir(workspaces::workspace_dir(&base, &workspace));
+        }
+        workspaces::set_active(workspace, base);
+        if let Some(workspace_dir) = &self.workspace_dir {
+            utils::path::set_workspace_dir(workspace_dir.clone());
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn env(pairs: &[(&str, &str)]) -> SettingsLayer {
+        SettingsLayer::from_env(pairs.iter().map(|(k, v)| (k.to_string(), v.to_string()))).unwrap()
+    }
+
+    #[test]
+    fn test_precedence() {
+        let file = SettingsLayer {
+            host: Some("0.0.0.0".to_string()),
+            port: Some(3000),
+            log_level: Some("warn".to_string()),
+            ..Default::default()
+        };
+        let env = env(&[("VK_PORT", "4000"), ("VK_DATA_DIR", "/data")]);
+        let cli = SettingsLayer {
+            port: Some(5000),
+            ..Default::default()
+        };
+
+        let settings = ServerSettings::resolve(file, env, cli);
+        assert_eq!(settings.host, "0.0.0.0");
+        assert_eq!(settings.port, 5000);
+        assert_eq!(settings.data_dir, Some(PathBuf::from("/data")));
+        assert_eq!(settings.workspace_dir, None);
+        assert_eq!(settings.log_level, "warn");
+        assert_eq!(settings.otlp_traces_endpoint, None);
+    }
+
+    #[test]
+    fn test_env_prefers_vk_over_legacy_names() {
+        let layer = env(&[("PORT", "1"), ("BACKEND_PORT", "2"), ("VK_PORT", "3")]);
+        assert_eq!(layer.port, Some(3));
+        let layer = env(&[("PORT", "1"), ("RUST_LOG", "debug")]);
+        assert_eq!(layer.port, Some(1));
+        assert_eq!(layer.log_level, Some("debug".to_string()));
+        let layer = env(&[("VK_OTLP_TRACES_ENDPOINT", "http://localhost:4318")]);
+        assert_eq!(
+            layer.otlp_traces_endpoint,
+            Some("http://localhost:4318".to_string())
+        );
+
+        assert!(SettingsLayer::from_env(vec![("VK_PORT".to_string(), "abc".to_string())]).is_err());
+    }
+
+    #[test]
+    fn test_request_limits() {
+        let layer = env(&[
+            ("VK_MAX_BODY_BYTES", "1048576"),
+            ("VK_RATE_LIMIT_PER_MINUTE", "120"),
+        ]);
+        let settings =
+            ServerSettings::resolve(SettingsLayer::default(), layer, SettingsLayer::default());
+        assert_eq!(settings.max_body_bytes, 1024 * 1024);
+        assert_eq!(settings.rate_limit_per_minute, Some(120));
+
+        // 0 turns the rate limit off
+        let cli = SettingsLayer {
+            rate_limit_per_minute: Some(0),
+            ..Default::default()
+        };
+        let settings = ServerSettings::resolve(
+            SettingsLayer::default(),
+            env(&[("VK_RATE_LIMIT_PER_MINUTE", "120")]),
+            cli,
+        );
+        assert_eq!(settings.rate_limit_per_minute, None);
+
+        assert!(SettingsLayer::from_env(vec![(
+            "VK_RATE_LIMIT_PER_MINUTE".to_string(),
+            "lots".to_string()
+        )])
+        .is_err());
+    }
+
+    #[test]
+    fn test_baseline_url_needs_a_checksum() {
+        let sha256 = "ab".repeat(32);
+        let layer = env(&[
+            ("VK_BASELINE_URL", "https://example.com/baseline.json"),
+            ("VK_BASELINE_SHA256", &sha256),
+        ]);
+        let settings =
+            ServerSettings::resolve(SettingsLayer::default(), layer.clone(), Default::default());
+        assert_eq!(
+            settings.baseline_source().unwrap(),
+            Some(BaselineSource {
+                url: "https://example.com/baseline.json".to_string(),
+                sha256,
+            })
+        );
+
+        let without_checksum = SettingsLayer {
+            baseline_sha256: None,
+            ..layer
+        };
+        let settings = ServerSettings::resolve(
+            SettingsLayer::default(),
+            without_checksum,
+            Default::default(),
+        );
+        assert!(settings.baseline_source().is_err());
+
+        let settings = ServerSettings::resolve(
+            SettingsLayer::default(),
+            SettingsLayer::default(),
+            SettingsLayer::default(),
+        );
+        assert_eq!(settings.baseline_source().unwrap(), None);
+    }
+
+    #[test]
+    fn test_defaults() {
+        let settings = ServerSettings::resolve(
+            SettingsLayer::default(),
+            SettingsLayer::default(),
+            SettingsLayer::default(),
+        );
+        assert_eq!(settings.host, DEFAULT_HOST);
+        assert_eq!(settings.port, 0);
+        assert_eq!(settings.log_level, DEFAULT_LOG_LEVEL);
+        assert_eq!(settings.max_body_bytes, DEFAULT_MAX_BODY_BYTES);
+        assert_eq!(settings.rate_limit_per_minute, None);
+        assert!(!settings.offline);
+        assert!(!settings.tray);
+    }
+
+    #[test]
+    fn test_offline_from_file_env_or_flag() {
+        let file = SettingsLayer {
+            offline: Some(true),
+            ..Default::default()
+        };
+        let settings = ServerSettings::resolve(file.clone(), env(&[]), SettingsLayer::default());
+        assert!(settings.offline);
+        let settings =
+            ServerSettings::resolve(file, env(&[("VK_OFFLINE", "false")]), Default::default());
+        assert!(!settings.offline);
+
+        let cli = Cli {
+            offline: true,
+            ..Default::default()
+        };
+        let settings = ServerSettings::resolve(
+            SettingsLayer::default(),
+            env(&[("VK_OFFLINE", "0")]),
+            (&cli).into(),
+        );
+        assert!(settings.offline);
+        assert!(
+            SettingsLayer::from_env(vec![("VK_OFFLINE".to_string(), "maybe".to_string())]).is_err()
+        );
+    }
+
+    #[test]
+    fn test_tray_from_env_or_flag() {
+        let settings = ServerSettings::resolve(
+            SettingsLayer::default(),
+            env(&[("VK_TRAY", "yes")]),
+            SettingsLayer::default(),
+        );
+        assert!(settings.tray);
+
+        let cli = Cli {
+            tray: true,
+            ..Default::default()
+        };
+        let settings = ServerSettings::resolve(
+            SettingsLayer::default(),
+            env(&[("VK_TRAY", "false")]),
+            (&cli).into(),
+        );
+        assert!(settings.tray);
+    }
+}
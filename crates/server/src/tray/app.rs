@@ -0,0 +1,185 @@
+//! The tray icon and its menu, on the platform's event loop
+
+use tao::{
+    event::{Event, StartCause},
+    event_loop::{ControlFlow, EventLoopBuilder},
+    platform::run_return::EventLoopExtRunReturn,
+};
+use tokio::runtime::Handle;
+use tokio_util::sync::CancellationToken;
+use tray_icon::{
+    menu::{Menu, MenuEvent, MenuItem, PredefinedMenuItem},
+    Icon, TrayIcon, TrayIconBuilder,
+};
+use utils::browser::open_browser;
+
+use super::{watch_status, TrayStatus};
+use crate::{
+    routes::maintenance::{pause_all_agents, resume_all_agents},
+    DeploymentImpl,
+};
+
+const ICON_SIZE: u32 = 32;
+
+enum TrayEvent {
+    Status(TrayStatus),
+    Menu(MenuEvent),
+    /// The server stopped
+    Exit,
+}
+
+struct TrayMenu {
+    menu: Menu,
+    status: MenuItem,
+    open_board: MenuItem,
+    toggle_pause: MenuItem,
+    quit: MenuItem,
+}
+
+impl TrayMenu {
+    fn new() -> Result<Self, tray_icon::menu::Error> {
+        let tray_menu = TrayMenu {
+            menu: Menu::new(),
+            status: MenuItem::new(TrayStatus::default().summary(), false, None),
+            open_board: MenuItem::new("Open board", true, None),
+            toggle_pause: MenuItem::new("Pause all agents", true, None),
+            quit: MenuItem::new("Quit", true, None),
+        };
+        tray_menu.menu.append_items(&[
+            &tray_menu.status,
+            &PredefinedMenuItem::separator(),
+            &tray_menu.open_board,
+            &tray_menu.toggle_pause,
+            &PredefinedMenuItem::separator(),
+            &tray_menu.quit,
+        ])?;
+        Ok(tray_menu)
+    }
+
+    fn show(&self, status: &TrayStatus) {
+        self.status.set_text(status.summary());
+        self.toggle_pause.set_text(if status.agents_paused {
+            "Resume all agents"
+        } else {
+            "Pause all agents"
+        });
+    }
+}
+
+/// A filled circle, drawn rather than decoded so the tray doesn't need an image crate
+fn icon() -> Result<Icon, tray_icon::BadIcon> {
+    let center = (ICON_SIZE as f32 - 1.0) / 2.0;
+    let radius = ICON_SIZE as f32 / 2.0 - 2.0;
+    let mut rgba = Vec::with_capacity((ICON_SIZE * ICON_SIZE * 4) as usize);
+    for y in 0..ICON_SIZE {
+        for x in 0..ICON_SIZE {
+            let distance = (x as f32 - center).hypot(y as f32 - center);
+            let alpha = if distance <= radius { 0xff } else { 0 };
+            rgba.extend_from_slice(&[0x1f, 0x1f, 0x1f, alpha]);
+        }
+    }
+    Icon::from_rgba(rgba, ICON_SIZE, ICON_SIZE)
+}
+
+fn build_tray(menu: &TrayMenu) -> anyhow::Result<TrayIcon> {
+    Ok(TrayIconBuilder::new()
+        .with_menu(Box::new(menu.menu.clone()))
+        .with_tooltip(TrayStatus::default().summary())
+        .with_icon(icon()?)
+        .with_icon_as_template(true)
+        .build()?)
+}
+
+/// Runs the tray on the current thread, which must be the main one, until `stopped` is
+/// cancelled. Quitting from the menu cancels `shutdown`.
+pub fn run(
+    deployment: DeploymentImpl,
+    board_url: String,
+    shutdown: CancellationToken,
+    stopped: CancellationToken,
+) {
+    let handle = Handle::current();
+    let mut event_loop = EventLoopBuilder::<TrayEvent>::with_user_event().build();
+    #[cfg(target_os = "macos")]
+    {
+        use tao::platform::macos::{ActivationPolicy, EventLoopExtMacOS};
+        // Menubar only, without a Dock icon
+        event_loop.set_activation_policy(ActivationPolicy::Accessory);
+    }
+
+    let proxy = event_loop.create_proxy();
+    MenuEvent::set_event_handler(Some(move |event| {
+        let _ = proxy.send_event(TrayEvent::Menu(event));
+    }));
+    let proxy = event_loop.create_proxy();
+    handle.spawn(watch_status(deployment.clone(), move |status| {
+        proxy.send_event(TrayEvent::Status(status)).is_ok()
+    }));
+    let proxy = event_loop.create_proxy();
+    handle.spawn(async move {
+        stopped.cancelled().await;
+        let _ = proxy.send_event(TrayEvent::Exit);
+    });
+
+    let menu = match TrayMenu::new() {
+        Ok(menu) => menu,
+        Err(e) => {
+            tracing::error!("Failed to create the tray menu: {}", e);
+            return;
+        }
+    };
+    let mut tray = None;
+    let mut status = TrayStatus::default();
+    event_loop.run_return(|event, _, control_flow| {
+        *control_flow = ControlFlow::Wait;
+        match event {
+            // Platforms want the icon created once the event loop runs
+            Event::NewEvents(StartCause::Init) => match build_tray(&menu) {
+                Ok(icon) => tray = Some(icon),
+                Err(e) => tracing::error!("Failed to create the tray icon: {}", e),
+            },
+            Event::UserEvent(TrayEvent::Status(new_status)) => {
+                status = new_status;
+                menu.show(&status);
+                if let Some(tray) = &tray {
+                    tray.set_title(Some(status.title()));
+                    if let Err(e) = tray.set_tooltip(Some(status.summary())) {
+                        tracing::debug!("Failed to update the tray tooltip: {}", e);
+                    }
+                }
+            }
+            Event::UserEvent(TrayEvent::Menu(event)) => {
+                if event.id == *menu.open_board.id() {
+                    let url = board_url.clone();
+                    handle.spawn(async move {
+                        if let Err(e) = open_browser(&url).await {
+                            tracing::warn!("Failed to open {}: {}", url, e);
+                        }
+                    });
+                } else if event.id == *menu.toggle_pause.id() {
+                    let deployment = deployment.clone();
+                    let resume = status.agents_paused;
+                    handle.spawn(async move {
+                        let result = if resume {
+                            resume_all_agents(&deployment).await
+                        } else {
+                            pause_all_agents(&deployment).await
+                        };
+                        if let Err(e) = result {
+                            tracing::error!("Failed to pause or resume agents: {}", e);
+                        }
+                    });
+                } else if event.id == *menu.quit.id() {
+                    menu.quit.set_enabled(false);
+                    menu.quit.set_text("Shutting down...");
+                    shutdown.cancel();
+                }
+            }
+            Event::UserEvent(TrayEvent::Exit) => {
+                tray.take();
+                *control_flow = ControlFlow::Exit;
+            }
+            _ => {}
+        }
+    });
+}
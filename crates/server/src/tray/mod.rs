@@ -0,0 +1,142 @@
+//! Tray companion mode: a menubar icon showing how many attempts are running, with quick
+//! actions to open the board and pause or resume every agent. The icon needs the `tray`
+//! feature. Completion notifications keep coming from `NotificationService`, with the
+//! notification settings of the config.
+
+#[cfg(feature = "tray")]
+pub mod app;
+
+use std::{collections::HashSet, time::Duration};
+
+use db::models::execution_process::{ExecutionProcess, ExecutionProcessRunReason};
+use deployment::Deployment;
+
+use crate::DeploymentImpl;
+
+/// How often the tray refreshes its status
+pub const POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// What the tray shows
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TrayStatus {
+    /// Attempts with a process other than a dev server running
+    pub running_attempts: usize,
+    pub agents_paused: bool,
+}
+
+impl TrayStatus {
+    pub fn from_processes(running: &[ExecutionProcess], agents_paused: bool) -> Self {
+        let running_attempts = running
+            .iter()
+            .filter(|process| process.run_reason != ExecutionProcessRunReason::DevServer)
+            .map(|process| process.task_attempt_id)
+            .collect::<HashSet<_>>()
+            .len();
+        TrayStatus {
+            running_attempts,
+            agents_paused,
+        }
+    }
+
+    pub async fn load(deployment: &DeploymentImpl) -> Result<Self, sqlx::Error> {
+        let running = ExecutionProcess::find_running(&deployment.db().pool).await?;
+        let agents_paused = deployment.config().read().await.agents_paused;
+        Ok(Self::from_processes(&running, agents_paused))
+    }
+
+    /// First line of the menu and the icon's tooltip
+    pub fn summary(&self) -> String {
+        let attempts = match self.running_attempts {
+            0 => "No attempts running".to_string(),
+            1 => "1 attempt running".to_string(),
+            n => format!("{n} attempts running"),
+        };
+        if self.agents_paused {
+            format!("{attempts}, agents paused")
+        } else {
+            attempts
+        }
+    }
+
+    /// Shown next to the icon where the platform supports it, empty while nothing runs
+    pub fn title(&self) -> String {
+        match self.running_attempts {
+            0 => String::new(),
+            n => n.to_string(),
+        }
+    }
+}
+
+/// Polls the status and hands over every change, until `on_change` returns false
+pub async fn watch_status(
+    deployment: DeploymentImpl,
+    mut on_change: impl FnMut(TrayStatus) -> bool,
+) {
+    let mut last = None;
+    loop {
+        match TrayStatus::load(&deployment).await {
+            Ok(status) if last != Some(status) => {
+                if !on_change(status) {
+                    return;
+                }
+                last = Some(status);
+            }
+            Ok(_) => {}
+            Err(e) => tracing::warn!("Failed to load the tray status: {}", e),
+        }
+        tokio::time::sleep(POLL_INTERVAL).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+    use db::models::execution_process::{ExecutionProcessStatus, ExecutorActionField};
+    use uuid::Uuid;
+
+    use super::*;
+
+    fn process(task_attempt_id: Uuid, run_reason: ExecutionProcessRunReason) -> ExecutionProcess {
+        let now = Utc::now();
+        ExecutionProcess {
+            id: Uuid::new_v4(),
+            task_attempt_id,
+            run_reason,
+            executor_action: sqlx::types::Json(ExecutorActionField::Other(serde_json::Value::Null)),
+            status: ExecutionProcessStatus::Running,
+            exit_code: None,
+            started_at: now,
+            completed_at: None,
+            created_at: now,
+            updated_at: now,
+        }
+    }
+
+    #[test]
+    fn test_counts_attempts_not_processes() {
+        let attempt = Uuid::new_v4();
+        let running = vec![
+            process(attempt, ExecutionProcessRunReason::CodingAgent),
+            process(attempt, ExecutionProcessRunReason::SetupScript),
+            process(Uuid::new_v4(), ExecutionProcessRunReason::CodingAgent),
+            process(Uuid::new_v4(), ExecutionProcessRunReason::DevServer),
+        ];
+        let status = TrayStatus::from_processes(&running, false);
+        assert_eq!(status.running_attempts, 2);
+        assert_eq!(status.summary(), "2 attempts running");
+        assert_eq!(status.title(), "2");
+    }
+
+    #[test]
+    fn test_summary() {
+        let idle = TrayStatus::default();
+        assert_eq!(idle.summary(), "No attempts running");
+        assert_eq!(idle.title(), "");
+
+        let paused = TrayStatus {
+            running_attempts: 1,
+            agents_paused: true,
+        };
+        assert_eq!(paused.summary(), "1 attempt running, agents paused");
+    }
+}
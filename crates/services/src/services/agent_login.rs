@@ -0,0 +1,278 @@
+use std::{collections::HashMap, process::Stdio, sync::Arc, time::Duration};
+
+use chrono::{DateTime, Utc};
+use command_group::{AsyncCommandGroup, AsyncGroupChild};
+use executors::{
+    executors::{CodingAgent, ExecutorError},
+    profile::ProfileVariantLabel,
+};
+use futures::{TryStreamExt, stream::select};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use tokio::{
+    io::AsyncWriteExt,
+    process::{ChildStdin, Command},
+    sync::{Mutex, RwLock},
+    task::JoinHandle,
+};
+use tokio_util::{io::ReaderStream, sync::CancellationToken};
+use ts_rs::TS;
+use utils::{log_msg::LogMsg, msg_store::MsgStore, shell::get_shell_command};
+use uuid::Uuid;
+
+#[derive(Debug, Error)]
+pub enum AgentLoginError {
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    #[error(transparent)]
+    Executor(#[from] ExecutorError),
+    #[error("{0} has no login command, it signs in the first time it runs")]
+    NoLoginCommand(String),
+    #[error("Login session not found: {0}")]
+    NotFound(Uuid),
+    #[error("Login session {0} is no longer running")]
+    NotRunning(Uuid),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "lowercase")]
+pub enum AgentLoginStatus {
+    Running,
+    Verifying,
+    Succeeded,
+    Failed,
+    Cancelled,
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct AgentLoginSession {
+    pub id: Uuid,
+    pub profile: String,
+    pub command: String,
+    pub status: AgentLoginStatus,
+    /// Whether the agent's credentials were checked after the login flow finished
+    pub verified: bool,
+    pub error: Option<String>,
+    pub started_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize, TS)]
+pub struct StartAgentLoginRequest {
+    pub profile: String,
+}
+
+#[derive(Debug, Deserialize, TS)]
+pub struct AgentLoginInput {
+    /// Sent to the login process followed by a newline, e.g. a pasted auth code
+    pub input: String,
+}
+
+/// How long a finished session stays around for the UI to read its outcome and output
+const FINISHED_LOGIN_TTL: Duration = Duration::from_secs(10 * 60);
+
+type Logins = Arc<RwLock<HashMap<Uuid, Arc<LoginProcess>>>>;
+
+struct LoginProcess {
+    session: RwLock<AgentLoginSession>,
+    store: Arc<MsgStore>,
+    stdin: Mutex<Option<ChildStdin>>,
+    cancel: CancellationToken,
+}
+
+/// Runs agent login flows as managed subprocesses during onboarding, so credentials are
+/// in place before the first task starts
+#[derive(Clone, Default)]
+pub struct AgentLoginService {
+    logins: Logins,
+}
+
+impl AgentLoginService {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Start the login flow for the default variant of `profile`
+    pub async fn start(&self, profile: &str) -> Result<AgentLoginSession, AgentLoginError> {
+        let agent = CodingAgent::from_profile_variant_label(&ProfileVariantLabel::default(
+            profile.to_string(),
+        ))?;
+        let login_command = agent
+            .login_command()
+            .ok_or_else(|| AgentLoginError::NoLoginCommand(profile.to_string()))?;
+
+        let mut child = spawn_shell(login_command, true)?;
+        let store = Arc::new(MsgStore::new());
+        let output = forward_output(&store, &mut child);
+        let stdin = child.inner().stdin.take();
+
+        let session = AgentLoginSession {
+            id: Uuid::new_v4(),
+            profile: profile.to_string(),
+            command: login_command.to_string(),
+            status: AgentLoginStatus::Running,
+            verified: false,
+            error: None,
+            started_at: Utc::now(),
+        };
+        let process = Arc::new(LoginProcess {
+            session: RwLock::new(session.clone()),
+            store,
+            stdin: Mutex::new(stdin),
+            cancel: CancellationToken::new(),
+        });
+        self.logins
+            .write()
+            .await
+            .insert(session.id, process.clone());
+
+        let logins = self.logins.clone();
+        let id = session.id;
+        tokio::spawn(async move {
+            monitor_login(&process, agent, child, output).await;
+            tokio::time::sleep(FINISHED_LOGIN_TTL).await;
+            logins.write().await.remove(&id);
+        });
+        Ok(session)
+    }
+
+    pub async fn get(&self, id: Uuid) -> Result<AgentLoginSession, AgentLoginError> {
+        Ok(self.process(id).await?.session.read().await.clone())
+    }
+
+    /// Output of the login process so far, then live until it finishes
+    pub async fn output_store(&self, id: Uuid) -> Result<Arc<MsgStore>, AgentLoginError> {
+        Ok(self.process(id).await?.store.clone())
+    }
+
+    /// Write a line to the login process, for flows that ask for a code or a choice
+    pub async fn send_input(&self, id: Uuid, input: &str) -> Result<(), AgentLoginError> {
+        let process = self.process(id).await?;
+        let mut stdin = process.stdin.lock().await;
+        let stdin = stdin.as_mut().ok_or(AgentLoginError::NotRunning(id))?;
+        stdin.write_all(format!("{input}\n").as_bytes()).await?;
+        stdin.flush().await?;
+        Ok(())
+    }
+
+    pub async fn cancel(&self, id: Uuid) -> Result<AgentLoginSession, AgentLoginError> {
+        let process = self.process(id).await?;
+        if process.session.read().await.status != AgentLoginStatus::Running {
+            return Err(AgentLoginError::NotRunning(id));
+        }
+        process.session.write().await.status = AgentLoginStatus::Cancelled;
+        process.cancel.cancel();
+        Ok(process.session.read().await.clone())
+    }
+
+    async fn process(&self, id: Uuid) -> Result<Arc<LoginProcess>, AgentLoginError> {
+        self.logins
+            .read()
+            .await
+            .get(&id)
+            .cloned()
+            .ok_or(AgentLoginError::NotFound(id))
+    }
+}
+
+fn spawn_shell(command: &str, interactive: bool) -> Result<AsyncGroupChild, std::io::Error> {
+    let (shell_cmd, shell_arg) = get_shell_command();
+    let mut cmd = Command::new(shell_cmd);
+    cmd.kill_on_drop(true)
+        .stdin(if interactive {
+            Stdio::piped()
+        } else {
+            Stdio::null()
+        })
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .arg(shell_arg)
+        .arg(command);
+    cmd.group_spawn()
+}
+
+/// Stream the child's stdout and stderr into `store`, the handle completes at EOF
+fn forward_output(store: &Arc<MsgStore>, child: &mut AsyncGroupChild) -> Option<JoinHandle<()>> {
+    let out = child.inner().stdout.take().map(|out| {
+        ReaderStream::new(out)
+            .map_ok(|chunk| LogMsg::Stdout(String::from_utf8_lossy(&chunk).into_owned()))
+    });
+    let err = child.inner().stderr.take().map(|err| {
+        ReaderStream::new(err)
+            .map_ok(|chunk| LogMsg::Stderr(String::from_utf8_lossy(&chunk).into_owned()))
+    });
+    match (out, err) {
+        (Some(out), Some(err)) => Some(store.clone().spawn_forwarder(select(out, err))),
+        _ => None,
+    }
+}
+
+/// Wait for the login process, then check the credentials it produced
+async fn monitor_login(
+    process: &LoginProcess,
+    agent: CodingAgent,
+    mut child: AsyncGroupChild,
+    output: Option<JoinHandle<()>>,
+) {
+    let exit = tokio::select! {
+        exit = child.wait() => Some(exit),
+        _ = process.cancel.cancelled() => None,
+    };
+    if exit.is_none()
+        && let Err(e) = child.kill().await
+    {
+        tracing::warn!("Failed to kill agent login process: {}", e);
+    }
+    process.stdin.lock().await.take();
+    if let Some(output) = output {
+        let _ = output.await;
+    }
+
+    let result = match exit {
+        None => None,
+        Some(Ok(status)) if status.success() => Some(verify(process, &agent).await),
+        Some(Ok(status)) => Some(Err(format!("Login exited with {status}"))),
+        Some(Err(e)) => Some(Err(e.to_string())),
+    };
+
+    let mut session = process.session.write().await;
+    match result {
+        None => session.status = AgentLoginStatus::Cancelled,
+        Some(Ok(verified)) => {
+            session.status = AgentLoginStatus::Succeeded;
+            session.verified = verified;
+        }
+        Some(Err(e)) => {
+            session.status = AgentLoginStatus::Failed;
+            session.error = Some(e);
+        }
+    }
+    drop(session);
+    process.store.push_finished();
+}
+
+/// Run the agent's credential check if it has one, returning whether it was checked
+async fn verify(process: &LoginProcess, agent: &CodingAgent) -> Result<bool, String> {
+    let Some(check) = agent.auth_check_command() else {
+        return Ok(false);
+    };
+    process.session.write().await.status = AgentLoginStatus::Verifying;
+    process
+        .store
+        .push_stdout(format!("\nVerifying credentials with `{check}`\n"));
+    run_auth_check(&process.store, check).await?;
+    Ok(true)
+}
+
+async fn run_auth_check(store: &Arc<MsgStore>, command: &str) -> Result<(), String> {
+    let mut child = spawn_shell(command, false).map_err(|e| e.to_string())?;
+    let output = forward_output(store, &mut child);
+    let status = child.wait().await.map_err(|e| e.to_string())?;
+    if let Some(output) = output {
+        let _ = output.await;
+    }
+    if status.success() {
+        Ok(())
+    } else {
+        Err(format!("Credential check exited with {status}"))
+    }
+}
@@ -1,16 +1,46 @@
 use std::{
-    collections::hash_map::DefaultHasher,
+    collections::{VecDeque, hash_map::DefaultHasher},
     hash::{Hash, Hasher},
+    sync::{Arc, Mutex},
     time::Duration,
 };
 
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
 use os_info;
+use serde::Serialize;
 use serde_json::{Value, json};
+use tokio::sync::RwLock;
+use ts_rs::TS;
+use utils::offline::is_offline;
 
-#[derive(Debug, Clone)]
-pub struct AnalyticsContext {
-    pub user_id: String,
-    pub analytics_service: AnalyticsService,
+use crate::services::config::{Config, TelemetryExporter};
+
+/// Events kept in memory for the local usage endpoint, the oldest are dropped first
+const LOCAL_EVENT_CAPACITY: usize = 1000;
+/// Sets person properties rather than recording usage, so it is only ever exported
+const IDENTIFY_EVENT: &str = "$identify";
+
+/// A usage event, as kept in the local buffer and handed to exporters
+#[derive(Debug, Clone, PartialEq, Serialize, TS)]
+pub struct AnalyticsEvent {
+    pub name: String,
+    pub properties: Value,
+    pub timestamp: DateTime<Utc>,
+}
+
+/// How often an event was recorded since the server started
+#[derive(Debug, Clone, PartialEq, Serialize, TS)]
+pub struct AnalyticsEventCount {
+    pub name: String,
+    pub count: u32,
+    pub last_at: DateTime<Utc>,
+}
+
+/// Sends usage events off the machine
+#[async_trait]
+pub trait AnalyticsExporter: Send + Sync {
+    async fn export(&self, user_id: &str, event: &AnalyticsEvent) -> Result<(), reqwest::Error>;
 }
 
 #[derive(Debug, Clone)]
@@ -35,23 +65,14 @@ impl AnalyticsConfig {
     }
 }
 
-#[derive(Clone, Debug)]
-pub struct AnalyticsService {
+pub struct PostHogExporter {
     config: AnalyticsConfig,
     client: reqwest::Client,
 }
 
-impl AnalyticsService {
-    pub fn new(config: AnalyticsConfig) -> Self {
-        let client = reqwest::Client::builder()
-            .timeout(Duration::from_secs(30))
-            .build()
-            .unwrap();
-
-        Self { config, client }
-    }
-
-    pub fn track_event(&self, user_id: &str, event_name: &str, properties: Option<Value>) {
+#[async_trait]
+impl AnalyticsExporter for PostHogExporter {
+    async fn export(&self, user_id: &str, event: &AnalyticsEvent) -> Result<(), reqwest::Error> {
         let endpoint = format!(
             "{}/capture/",
             self.config.posthog_api_endpoint.trim_end_matches('/')
@@ -59,58 +80,219 @@ impl AnalyticsService {
 
         let mut payload = json!({
             "api_key": self.config.posthog_api_key,
-            "event": event_name,
+            "event": event.name,
             "distinct_id": user_id,
         });
-        if event_name == "$identify" {
+        if event.name == IDENTIFY_EVENT {
             // For $identify, set person properties in $set
-            if let Some(props) = properties {
-                payload["$set"] = props;
-            }
+            payload["$set"] = event.properties.clone();
         } else {
-            // For other events, use properties as before
-            let mut event_properties = properties.unwrap_or_else(|| json!({}));
+            let mut event_properties = event.properties.clone();
             if let Some(props) = event_properties.as_object_mut() {
-                props.insert(
-                    "timestamp".to_string(),
-                    json!(chrono::Utc::now().to_rfc3339()),
-                );
+                props.insert("timestamp".to_string(), json!(event.timestamp.to_rfc3339()));
                 props.insert("version".to_string(), json!(env!("CARGO_PKG_VERSION")));
                 props.insert("device".to_string(), get_device_info());
             }
             payload["properties"] = event_properties;
         }
 
-        let client = self.client.clone();
-        let event_name = event_name.to_string();
+        self.client
+            .post(&endpoint)
+            .header("Content-Type", "application/json")
+            .json(&payload)
+            .send()
+            .await?
+            .error_for_status()?;
+        Ok(())
+    }
+}
+
+/// Sends events as log records to an OpenTelemetry collector over OTLP/HTTP
+pub struct OtlpExporter {
+    endpoint: String,
+    client: reqwest::Client,
+}
+
+#[async_trait]
+impl AnalyticsExporter for OtlpExporter {
+    async fn export(&self, user_id: &str, event: &AnalyticsEvent) -> Result<(), reqwest::Error> {
+        let endpoint = format!("{}/v1/logs", self.endpoint.trim_end_matches('/'));
+        self.client
+            .post(&endpoint)
+            .json(&otlp_payload(user_id, event))
+            .send()
+            .await?
+            .error_for_status()?;
+        Ok(())
+    }
+}
+
+/// OTLP JSON logs request with the event as one log record, its properties as attributes
+fn otlp_payload(user_id: &str, event: &AnalyticsEvent) -> Value {
+    let mut attributes = vec![
+        otlp_attribute("event.name", &json!(event.name)),
+        otlp_attribute("user.id", &json!(user_id)),
+    ];
+    if let Some(properties) = event.properties.as_object() {
+        attributes.extend(
+            properties
+                .iter()
+                .map(|(key, value)| otlp_attribute(key, value)),
+        );
+    }
+    json!({
+        "resourceLogs": [{
+            "resource": {
+                "attributes": [
+                    otlp_attribute("service.name", &json!("vibe-kanban")),
+                    otlp_attribute("service.version", &json!(env!("CARGO_PKG_VERSION"))),
+                ],
+            },
+            "scopeLogs": [{
+                "scope": { "name": "vibe-kanban.analytics" },
+                "logRecords": [{
+                    "timeUnixNano": event
+                        .timestamp
+                        .timestamp_nanos_opt()
+                        .unwrap_or_default()
+                        .to_string(),
+                    "body": { "stringValue": event.name },
+                    "attributes": attributes,
+                }],
+            }],
+        }],
+    })
+}
+
+fn otlp_attribute(key: &str, value: &Value) -> Value {
+    let value = match value {
+        Value::String(s) => json!({ "stringValue": s }),
+        Value::Bool(b) => json!({ "boolValue": b }),
+        // OTLP JSON encodes 64 bit integers as strings
+        Value::Number(n) if n.is_i64() => json!({ "intValue": n.to_string() }),
+        Value::Number(n) => json!({ "doubleValue": n.as_f64() }),
+        other => json!({ "stringValue": other.to_string() }),
+    };
+    json!({ "key": key, "value": value })
+}
+
+/// Usage event pipeline. Every event lands in a local buffer that never leaves the
+/// machine, and is exported through the configured exporter while `analytics_enabled` is on.
+#[derive(Clone)]
+pub struct AnalyticsService {
+    user_id: String,
+    config: Arc<RwLock<Config>>,
+    posthog: Option<Arc<PostHogExporter>>,
+    client: reqwest::Client,
+    events: Arc<Mutex<VecDeque<AnalyticsEvent>>>,
+}
+
+impl AnalyticsService {
+    pub fn new(user_id: String, config: Arc<RwLock<Config>>) -> Self {
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(30))
+            .build()
+            .unwrap();
+        let posthog = AnalyticsConfig::new().map(|config| {
+            Arc::new(PostHogExporter {
+                config,
+                client: client.clone(),
+            })
+        });
+
+        Self {
+            user_id,
+            config,
+            posthog,
+            client,
+            events: Arc::new(Mutex::new(VecDeque::new())),
+        }
+    }
+
+    pub async fn track_event(&self, event_name: &str, properties: Value) {
+        let event = AnalyticsEvent {
+            name: event_name.to_string(),
+            properties,
+            timestamp: Utc::now(),
+        };
+        if event.name != IDENTIFY_EVENT {
+            self.record(event.clone());
+        }
 
+        let Some(exporter) = self.exporter().await else {
+            return;
+        };
+        let user_id = self.user_id.clone();
         tokio::spawn(async move {
-            match client
-                .post(&endpoint)
-                .header("Content-Type", "application/json")
-                .json(&payload)
-                .send()
-                .await
-            {
-                Ok(response) => {
-                    if response.status().is_success() {
-                        tracing::debug!("Event '{}' sent successfully", event_name);
-                    } else {
-                        let status = response.status();
-                        let response_text = response.text().await.unwrap_or_default();
-                        tracing::error!(
-                            "Failed to send event. Status: {}. Response: {}",
-                            status,
-                            response_text
-                        );
-                    }
-                }
-                Err(e) => {
-                    tracing::error!("Error sending event '{}': {}", event_name, e);
-                }
+            match exporter.export(&user_id, &event).await {
+                Ok(()) => tracing::debug!("Event '{}' sent successfully", event.name),
+                Err(e) => tracing::error!("Error sending event '{}': {}", event.name, e),
             }
         });
     }
+
+    /// The exporter the user chose, `None` while telemetry is off
+    async fn exporter(&self) -> Option<Arc<dyn AnalyticsExporter>> {
+        let config = self.config.read().await;
+        if config.analytics_enabled != Some(true) || is_offline() {
+            return None;
+        }
+        match config.telemetry.exporter {
+            TelemetryExporter::Posthog => self
+                .posthog
+                .clone()
+                .map(|exporter| exporter as Arc<dyn AnalyticsExporter>),
+            TelemetryExporter::Otlp => {
+                let endpoint = config.telemetry.otlp_endpoint.clone()?;
+                Some(Arc::new(OtlpExporter {
+                    endpoint,
+                    client: self.client.clone(),
+                }))
+            }
+            TelemetryExporter::None => None,
+        }
+    }
+
+    fn record(&self, event: AnalyticsEvent) {
+        let mut events = self.events.lock().unwrap();
+        if events.len() == LOCAL_EVENT_CAPACITY {
+            events.pop_front();
+        }
+        events.push_back(event);
+    }
+
+    /// Locally recorded events, newest first
+    pub fn recent_events(&self, name: Option<&str>, limit: usize) -> Vec<AnalyticsEvent> {
+        self.events
+            .lock()
+            .unwrap()
+            .iter()
+            .rev()
+            .filter(|event| name.is_none_or(|name| event.name == name))
+            .take(limit)
+            .cloned()
+            .collect()
+    }
+
+    /// Locally recorded events per name, most frequent first
+    pub fn event_counts(&self) -> Vec<AnalyticsEventCount> {
+        let mut counts: Vec<AnalyticsEventCount> = Vec::new();
+        for event in self.events.lock().unwrap().iter() {
+            match counts.iter_mut().find(|count| count.name == event.name) {
+                Some(count) => {
+                    count.count += 1;
+                    count.last_at = event.timestamp;
+                }
+                None => counts.push(AnalyticsEventCount {
+                    name: event.name.clone(),
+                    count: 1,
+                    last_at: event.timestamp,
+                }),
+            }
+        }
+        counts.sort_by(|a, b| b.count.cmp(&a.count).then(a.name.cmp(&b.name)));
+        counts
+    }
 }
 
 /// Generates a consistent, anonymous user ID for npm package telemetry.
@@ -191,6 +373,81 @@ mod tests {
         assert_eq!(id.len(), 25);
     }
 
+    fn service(config: Config) -> AnalyticsService {
+        AnalyticsService::new("user".to_string(), Arc::new(RwLock::new(config)))
+    }
+
+    #[tokio::test]
+    async fn test_events_are_kept_locally_with_telemetry_off() {
+        let analytics = service(Config::default());
+        analytics.track_event("task_created", json!({"n": 1})).await;
+        analytics
+            .track_event("$identify", json!({"email": "a@b.c"}))
+            .await;
+        analytics.track_event("task_created", json!({"n": 2})).await;
+        analytics.track_event("session_start", json!({})).await;
+
+        assert!(analytics.exporter().await.is_none());
+        let recent = analytics.recent_events(Some("task_created"), 10);
+        assert_eq!(recent.len(), 2);
+        assert_eq!(recent[0].properties, json!({"n": 2}));
+        assert_eq!(analytics.recent_events(None, 1)[0].name, "session_start");
+        assert_eq!(
+            analytics
+                .event_counts()
+                .iter()
+                .map(|c| (c.name.as_str(), c.count))
+                .collect::<Vec<_>>(),
+            vec![("task_created", 2), ("session_start", 1)]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_local_buffer_drops_oldest_events() {
+        let analytics = service(Config::default());
+        for n in 0..LOCAL_EVENT_CAPACITY + 5 {
+            analytics.track_event("tick", json!({ "n": n })).await;
+        }
+        let events = analytics.recent_events(None, usize::MAX);
+        assert_eq!(events.len(), LOCAL_EVENT_CAPACITY);
+        assert_eq!(events.last().unwrap().properties, json!({"n": 5}));
+    }
+
+    #[tokio::test]
+    async fn test_exporter_follows_config() {
+        let mut config = Config {
+            analytics_enabled: Some(true),
+            ..Config::default()
+        };
+        config.telemetry.exporter = TelemetryExporter::Otlp;
+        assert!(service(config.clone()).exporter().await.is_none());
+        config.telemetry.otlp_endpoint = Some("http://localhost:4318".to_string());
+        assert!(service(config.clone()).exporter().await.is_some());
+        config.telemetry.exporter = TelemetryExporter::None;
+        assert!(service(config).exporter().await.is_none());
+    }
+
+    #[test]
+    fn test_otlp_payload_maps_properties_to_attributes() {
+        let event = AnalyticsEvent {
+            name: "task_attempt_finished".to_string(),
+            properties: json!({"execution_success": true, "exit_code": 0, "ratio": 0.5}),
+            timestamp: DateTime::from_timestamp(1, 0).unwrap(),
+        };
+        let payload = otlp_payload("user", &event);
+        let record = &payload["resourceLogs"][0]["scopeLogs"][0]["logRecords"][0];
+        assert_eq!(record["timeUnixNano"], "1000000000");
+        assert_eq!(record["body"]["stringValue"], "task_attempt_finished");
+        let attributes = record["attributes"].as_array().unwrap();
+        assert!(attributes.contains(&json!({"key": "user.id", "value": {"stringValue": "user"}})));
+        assert!(attributes.contains(&json!({"key": "exit_code", "value": {"intValue": "0"}})));
+        assert!(attributes.contains(&json!({
+            "key": "execution_success",
+            "value": {"boolValue": true}
+        })));
+        assert!(attributes.contains(&json!({"key": "ratio", "value": {"doubleValue": 0.5}})));
+    }
+
     #[test]
     fn test_consistency() {
         let id1 = generate_user_id();
@@ -0,0 +1,536 @@
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Utc};
+use db::models::task_attachment::{CreateTaskAttachment, TaskAttachment};
+use executors::executors::CodingAgent;
+use serde::Serialize;
+use sqlx::SqlitePool;
+use thiserror::Error;
+use tokio::io::AsyncReadExt;
+use ts_rs::TS;
+use utils::{assets::asset_dir, magic::magic_at};
+use uuid::Uuid;
+
+/// Largest attachment accepted, enough for full resolution screenshots and logs
+pub const MAX_ATTACHMENT_BYTES: usize = 20 * 1024 * 1024;
+
+/// Most attachments a single task can hold
+pub const MAX_ATTACHMENTS_PER_TASK: usize = 20;
+
+/// Where attachments are written in a worktree, relative to its root
+pub const WORKTREE_ATTACHMENTS_DIR: &str = ".vibe-kanban/attachments";
+
+/// Folder in a worktree for reference files added while an attempt runs
+pub const WORKTREE_CONTEXT_DIR: &str = ".vibe-context";
+
+#[derive(Debug, Error)]
+pub enum AttachmentError {
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    #[error(transparent)]
+    Database(#[from] sqlx::Error),
+    #[error("Attachment not found: {0}")]
+    NotFound(Uuid),
+    #[error("Invalid attachment name: {0}")]
+    InvalidName(String),
+    #[error("Attachment is larger than {} MB", MAX_ATTACHMENT_BYTES / 1024 / 1024)]
+    TooLarge,
+    #[error("A task can have at most {MAX_ATTACHMENTS_PER_TASK} attachments")]
+    TooMany,
+}
+
+/// Image formats agents with image input can read
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImageFormat {
+    Png,
+    Jpeg,
+    Gif,
+    Webp,
+}
+
+impl ImageFormat {
+    /// The image format of an uploaded file, `None` for anything else
+    pub fn detect(data: &[u8]) -> Option<Self> {
+        if magic_at(data, 0, b"\x89PNG\r\n\x1a\n") {
+            Some(Self::Png)
+        } else if magic_at(data, 0, b"\xFF\xD8\xFF") {
+            Some(Self::Jpeg)
+        } else if magic_at(data, 0, b"GIF87a") || magic_at(data, 0, b"GIF89a") {
+            Some(Self::Gif)
+        } else if magic_at(data, 0, b"RIFF") && magic_at(data, 8, b"WEBP") {
+            Some(Self::Webp)
+        } else {
+            None
+        }
+    }
+
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            Self::Png => "image/png",
+            Self::Jpeg => "image/jpeg",
+            Self::Gif => "image/gif",
+            Self::Webp => "image/webp",
+        }
+    }
+}
+
+/// An attachment copied into a worktree for an agent to read
+#[derive(Debug, Clone, PartialEq)]
+pub struct StagedAttachment {
+    /// Path relative to the worktree root
+    pub path: String,
+    pub is_image: bool,
+}
+
+/// A reference file in an attempt's context folder
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct ContextFile {
+    /// Path relative to the worktree root
+    pub path: String,
+    #[ts(type = "number")]
+    pub size_bytes: u64,
+    pub is_image: bool,
+    pub added_at: DateTime<Utc>,
+}
+
+impl From<&ContextFile> for StagedAttachment {
+    fn from(file: &ContextFile) -> Self {
+        Self {
+            path: file.path.clone(),
+            is_image: file.is_image,
+        }
+    }
+}
+
+/// Files uploaded to tasks, kept in `attachments/{task_id}/` under the data directory
+#[derive(Debug, Clone)]
+pub struct AttachmentStore {
+    dir: PathBuf,
+}
+
+impl Default for AttachmentStore {
+    fn default() -> Self {
+        Self::new(asset_dir().join("attachments"))
+    }
+}
+
+impl AttachmentStore {
+    pub fn new(dir: PathBuf) -> Self {
+        Self { dir }
+    }
+
+    /// Store an uploaded file and record it on the task. Images are recognised by their
+    /// contents, other files keep the content type they were uploaded with.
+    pub async fn save(
+        &self,
+        pool: &SqlitePool,
+        task_id: Uuid,
+        file_name: &str,
+        content_type: Option<&str>,
+        data: &[u8],
+    ) -> Result<TaskAttachment, AttachmentError> {
+        if data.len() > MAX_ATTACHMENT_BYTES {
+            return Err(AttachmentError::TooLarge);
+        }
+        if TaskAttachment::find_by_task_id(pool, task_id).await?.len() >= MAX_ATTACHMENTS_PER_TASK {
+            return Err(AttachmentError::TooMany);
+        }
+        let file_name = Self::sanitize_name(file_name)?;
+        let content_type = match ImageFormat::detect(data) {
+            Some(format) => format.content_type(),
+            // A file claiming to be an image we can't read is stored as plain data
+            None => content_type
+                .filter(|t| !t.is_empty() && !t.starts_with("image/"))
+                .unwrap_or("application/octet-stream"),
+        };
+
+        let id = Uuid::new_v4();
+        let path = self.dir.join(task_id.to_string()).join(id.to_string());
+        tokio::fs::create_dir_all(path.parent().unwrap()).await?;
+        tokio::fs::write(&path, data).await?;
+        let created = TaskAttachment::create(
+            pool,
+            id,
+            task_id,
+            &CreateTaskAttachment {
+                file_name: &file_name,
+                content_type,
+                size_bytes: data.len() as i64,
+            },
+        )
+        .await;
+        if created.is_err() {
+            let _ = tokio::fs::remove_file(&path).await;
+        }
+        Ok(created?)
+    }
+
+    pub async fn load(&self, attachment: &TaskAttachment) -> Result<Vec<u8>, AttachmentError> {
+        match tokio::fs::read(self.path(attachment)).await {
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                Err(AttachmentError::NotFound(attachment.id))
+            }
+            result => Ok(result?),
+        }
+    }
+
+    pub async fn delete(
+        &self,
+        pool: &SqlitePool,
+        attachment: &TaskAttachment,
+    ) -> Result<(), AttachmentError> {
+        TaskAttachment::delete(pool, attachment.id).await?;
+        match tokio::fs::remove_file(self.path(attachment)).await {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e.into()),
+            _ => Ok(()),
+        }
+    }
+
+    /// Remove the files of a deleted task, its rows go with the task
+    pub async fn delete_task(&self, task_id: Uuid) -> Result<(), AttachmentError> {
+        match tokio::fs::remove_dir_all(self.dir.join(task_id.to_string())).await {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e.into()),
+            _ => Ok(()),
+        }
+    }
+
+    /// Copy attachments into a worktree under their upload names. The directory ignores
+    /// its own contents, so they never end up in the attempt's commits.
+    pub async fn stage(
+        &self,
+        worktree: &Path,
+        attachments: &[TaskAttachment],
+    ) -> Result<Vec<StagedAttachment>, AttachmentError> {
+        if attachments.is_empty() {
+            return Ok(Vec::new());
+        }
+        let dir = worktree.join(WORKTREE_ATTACHMENTS_DIR);
+        create_ignored_dir(&dir).await?;
+
+        let mut staged: Vec<StagedAttachment> = Vec::with_capacity(attachments.len());
+        for attachment in attachments {
+            let name = Self::unique_name(&attachment.file_name, |name| {
+                staged
+                    .iter()
+                    .any(|s| s.path == format!("{WORKTREE_ATTACHMENTS_DIR}/{name}"))
+            });
+            tokio::fs::copy(self.path(attachment), dir.join(&name)).await?;
+            staged.push(StagedAttachment {
+                path: format!("{WORKTREE_ATTACHMENTS_DIR}/{name}"),
+                is_image: attachment.is_image(),
+            });
+        }
+        Ok(staged)
+    }
+
+    fn path(&self, attachment: &TaskAttachment) -> PathBuf {
+        self.dir
+            .join(attachment.task_id.to_string())
+            .join(attachment.id.to_string())
+    }
+
+    /// `name`, or `name` with a counter before its extension if `taken` says it's in use
+    fn unique_name(name: &str, taken: impl Fn(&str) -> bool) -> String {
+        if !taken(name) {
+            return name.to_string();
+        }
+        let path = Path::new(name);
+        let stem = path.file_stem().and_then(|s| s.to_str()).unwrap_or(name);
+        let extension = path
+            .extension()
+            .and_then(|e| e.to_str())
+            .map(|e| format!(".{e}"))
+            .unwrap_or_default();
+        (2..)
+            .map(|n| format!("{stem}-{n}{extension}"))
+            .find(|candidate| !taken(candidate))
+            .unwrap()
+    }
+
+    /// File name reduced to characters that are safe in a path and in a prompt
+    fn sanitize_name(file_name: &str) -> Result<String, AttachmentError> {
+        let base = file_name.rsplit(['/', '\\']).next().unwrap_or_default();
+        let sanitized: String = base
+            .chars()
+            .map(|c| {
+                if c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.') {
+                    c
+                } else {
+                    '-'
+                }
+            })
+            .collect();
+        let sanitized = sanitized.trim_start_matches(['.', '-']);
+        if sanitized.is_empty() {
+            return Err(AttachmentError::InvalidName(file_name.to_string()));
+        }
+        Ok(sanitized.to_string())
+    }
+}
+
+/// Reference files written into an attempt's worktree, mockups, logs or data the agent
+/// can read alongside the code
+pub struct ContextFolder {
+    worktree: PathBuf,
+}
+
+impl ContextFolder {
+    pub fn new(worktree: &Path) -> Self {
+        Self {
+            worktree: worktree.to_path_buf(),
+        }
+    }
+
+    /// Write a file into the context folder, next to any file with the same name
+    pub async fn save(&self, file_name: &str, data: &[u8]) -> Result<ContextFile, AttachmentError> {
+        if data.len() > MAX_ATTACHMENT_BYTES {
+            return Err(AttachmentError::TooLarge);
+        }
+        let dir = self.worktree.join(WORKTREE_CONTEXT_DIR);
+        create_ignored_dir(&dir).await?;
+        let name =
+            AttachmentStore::unique_name(&AttachmentStore::sanitize_name(file_name)?, |name| {
+                dir.join(name).exists()
+            });
+        tokio::fs::write(dir.join(&name), data).await?;
+        Ok(ContextFile {
+            path: format!("{WORKTREE_CONTEXT_DIR}/{name}"),
+            size_bytes: data.len() as u64,
+            is_image: ImageFormat::detect(data).is_some(),
+            added_at: Utc::now(),
+        })
+    }
+
+    /// Files in the context folder, oldest first
+    pub async fn list(&self) -> Result<Vec<ContextFile>, AttachmentError> {
+        let dir = self.worktree.join(WORKTREE_CONTEXT_DIR);
+        if !dir.exists() {
+            return Ok(Vec::new());
+        }
+        let mut files = Vec::new();
+        let mut entries = tokio::fs::read_dir(&dir).await?;
+        while let Some(entry) = entries.next_entry().await? {
+            let Some(name) = entry.file_name().to_str().map(str::to_string) else {
+                continue;
+            };
+            if name == ".gitignore" || !entry.file_type().await?.is_file() {
+                continue;
+            }
+            let metadata = entry.metadata().await?;
+            let mut header = [0u8; 12];
+            let read = tokio::fs::File::open(entry.path())
+                .await?
+                .read(&mut header)
+                .await?;
+            files.push(ContextFile {
+                path: format!("{WORKTREE_CONTEXT_DIR}/{name}"),
+                size_bytes: metadata.len(),
+                is_image: ImageFormat::detect(&header[..read]).is_some(),
+                added_at: metadata.modified()?.into(),
+            });
+        }
+        files.sort_by(|a, b| a.added_at.cmp(&b.added_at).then(a.path.cmp(&b.path)));
+        Ok(files)
+    }
+}
+
+/// Create a worktree folder that ignores its own contents, so nothing in it is committed
+async fn create_ignored_dir(dir: &Path) -> Result<(), AttachmentError> {
+    tokio::fs::create_dir_all(dir).await?;
+    tokio::fs::write(dir.join(".gitignore"), "*\n").await?;
+    Ok(())
+}
+
+/// Prompt section pointing the agent at files in its worktree, under `heading`. Images are
+/// only listed for agents that can look at them, in the form the agent expects.
+pub fn attachments_prompt(
+    heading: &str,
+    staged: &[StagedAttachment],
+    agent: &CodingAgent,
+) -> String {
+    let lines: Vec<String> = staged
+        .iter()
+        .filter_map(|attachment| {
+            if !attachment.is_image {
+                return Some(format!("- {}", attachment.path));
+            }
+            let reference = agent.image_reference(&attachment.path);
+            if reference.is_none() {
+                tracing::debug!(
+                    "Not passing image {} to an agent without image input",
+                    attachment.path
+                );
+            }
+            reference.map(|reference| format!("- {reference} (image)"))
+        })
+        .collect();
+    if lines.is_empty() {
+        return String::new();
+    }
+    format!("\n\n{heading}:\n{}", lines.join("\n"))
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+    use executors::{
+        command::CommandBuilder,
+        executors::{codex::Codex, gemini::Gemini},
+    };
+
+    use super::*;
+
+    fn attachment(
+        dir: &Path,
+        task_id: Uuid,
+        file_name: &str,
+        content_type: &str,
+    ) -> TaskAttachment {
+        let attachment = TaskAttachment {
+            id: Uuid::new_v4(),
+            task_id,
+            file_name: file_name.to_string(),
+            content_type: content_type.to_string(),
+            size_bytes: 4,
+            created_at: Utc::now(),
+        };
+        let path = dir
+            .join(task_id.to_string())
+            .join(attachment.id.to_string());
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, b"data").unwrap();
+        attachment
+    }
+
+    #[test]
+    fn test_detect_image_format() {
+        assert_eq!(
+            ImageFormat::detect(b"\x89PNG\r\n\x1a\n\x00\x00"),
+            Some(ImageFormat::Png)
+        );
+        assert_eq!(
+            ImageFormat::detect(b"\xFF\xD8\xFF\xE0"),
+            Some(ImageFormat::Jpeg)
+        );
+        assert_eq!(ImageFormat::detect(b"GIF89a"), Some(ImageFormat::Gif));
+        assert_eq!(
+            ImageFormat::detect(b"RIFF\x24\x00\x00\x00WEBPVP8 "),
+            Some(ImageFormat::Webp)
+        );
+        assert_eq!(ImageFormat::detect(b"<svg xmlns="), None);
+        assert_eq!(ImageFormat::detect(b""), None);
+    }
+
+    #[test]
+    fn test_sanitize_name() {
+        assert_eq!(
+            AttachmentStore::sanitize_name("Screen Shot 2025-08-21.png").unwrap(),
+            "Screen-Shot-2025-08-21.png"
+        );
+        assert_eq!(
+            AttachmentStore::sanitize_name("../../etc/passwd").unwrap(),
+            "passwd"
+        );
+        assert_eq!(AttachmentStore::sanitize_name(".env").unwrap(), "env");
+        for name in ["", "..", "dir/"] {
+            assert!(matches!(
+                AttachmentStore::sanitize_name(name),
+                Err(AttachmentError::InvalidName(_))
+            ));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_stage_into_worktree() {
+        let data = tempfile::tempdir().unwrap();
+        let worktree = tempfile::tempdir().unwrap();
+        let store = AttachmentStore::new(data.path().to_path_buf());
+        let task_id = Uuid::new_v4();
+        let attachments = [
+            attachment(data.path(), task_id, "bug.png", "image/png"),
+            attachment(data.path(), task_id, "bug.png", "image/png"),
+            attachment(data.path(), task_id, "crash.log", "text/plain"),
+        ];
+
+        let staged = store.stage(worktree.path(), &attachments).await.unwrap();
+        let paths: Vec<_> = staged.iter().map(|s| s.path.as_str()).collect();
+        assert_eq!(
+            paths,
+            [
+                ".vibe-kanban/attachments/bug.png",
+                ".vibe-kanban/attachments/bug-2.png",
+                ".vibe-kanban/attachments/crash.log"
+            ]
+        );
+        for path in paths {
+            assert_eq!(std::fs::read(worktree.path().join(path)).unwrap(), b"data");
+        }
+        assert_eq!(
+            std::fs::read_to_string(
+                worktree
+                    .path()
+                    .join(WORKTREE_ATTACHMENTS_DIR)
+                    .join(".gitignore")
+            )
+            .unwrap(),
+            "*\n"
+        );
+
+        let gemini = attachments_prompt(
+            "Attachments",
+            &staged,
+            &CodingAgent::Gemini(Gemini {
+                command: CommandBuilder::new("gemini"),
+            }),
+        );
+        assert!(gemini.contains("- @.vibe-kanban/attachments/bug.png (image)"));
+        assert!(gemini.contains("- .vibe-kanban/attachments/crash.log"));
+
+        // Images are left out for agents without image input
+        let codex = CodingAgent::Codex(Codex {
+            command: CommandBuilder::new("codex"),
+            sandbox: None,
+            approval: None,
+        });
+        let prompt = attachments_prompt("Attachments", &staged, &codex);
+        assert!(!prompt.contains("bug.png"));
+        assert!(prompt.contains("crash.log"));
+
+        assert_eq!(attachments_prompt("Attachments", &[], &codex), "");
+    }
+
+    #[tokio::test]
+    async fn test_context_folder() {
+        let worktree = tempfile::tempdir().unwrap();
+        let folder = ContextFolder::new(worktree.path());
+        assert!(folder.list().await.unwrap().is_empty());
+
+        let mockup = folder
+            .save("mockup.png", b"\x89PNG\r\n\x1a\n\x00\x00")
+            .await
+            .unwrap();
+        assert_eq!(mockup.path, ".vibe-context/mockup.png");
+        assert!(mockup.is_image);
+        let again = folder
+            .save("mockup.png", b"\x89PNG\r\n\x1a\n")
+            .await
+            .unwrap();
+        assert_eq!(again.path, ".vibe-context/mockup-2.png");
+        folder.save("data.csv", b"a,b\n1,2\n").await.unwrap();
+
+        let files = folder.list().await.unwrap();
+        let mut paths: Vec<_> = files.iter().map(|f| f.path.as_str()).collect();
+        paths.sort();
+        assert_eq!(
+            paths,
+            [
+                ".vibe-context/data.csv",
+                ".vibe-context/mockup-2.png",
+                ".vibe-context/mockup.png"
+            ]
+        );
+        let csv = files.iter().find(|f| f.path.ends_with(".csv")).unwrap();
+        assert!(!csv.is_image);
+        assert_eq!(csv.size_bytes, 8);
+    }
+}
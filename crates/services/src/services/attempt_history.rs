@@ -0,0 +1,219 @@
+use db::models::{
+    attempt_review::{AttemptReview, ReviewVerdict},
+    execution_process::ExecutionProcessStatus,
+    test_run::TestRun,
+};
+
+/// Longest single field (follow-up, agent message, review) of a carried over attempt
+const MAX_FIELD_CHARS: usize = 500;
+/// Longest history section, the oldest attempts are left out first
+const MAX_HISTORY_CHARS: usize = 6_000;
+
+const HISTORY_HEADING: &str =
+    "Earlier attempts at this task, learn from them and don't repeat what didn't work:";
+
+/// Coding agent run of an earlier attempt
+#[derive(Debug, Clone)]
+pub struct PriorRun {
+    pub prompt: String,
+    pub status: ExecutionProcessStatus,
+    /// The agent's final answer, read from the run's stored logs
+    pub final_message: Option<String>,
+}
+
+/// What an earlier attempt of a task did and how it ended
+#[derive(Debug, Clone)]
+pub struct PriorAttempt {
+    pub profile: String,
+    pub merged: bool,
+    /// Oldest first, the first run got the task itself as prompt
+    pub runs: Vec<PriorRun>,
+    pub latest_test_run: Option<TestRun>,
+    pub latest_review: Option<AttemptReview>,
+}
+
+/// Prompt section summarizing the earlier attempts (oldest first), empty without any
+pub fn history_prompt(attempts: &[PriorAttempt]) -> String {
+    let mut blocks: Vec<String> = Vec::new();
+    let mut used = 0;
+    for (index, attempt) in attempts.iter().enumerate().rev() {
+        let block = render_attempt(index + 1, attempt);
+        if used + block.len() > MAX_HISTORY_CHARS {
+            break;
+        }
+        used += block.len();
+        blocks.push(block);
+    }
+    if blocks.is_empty() {
+        return String::new();
+    }
+    blocks.reverse();
+    format!("\n\n{HISTORY_HEADING}{}", blocks.concat())
+}
+
+fn render_attempt(number: usize, attempt: &PriorAttempt) -> String {
+    let mut lines = Vec::new();
+    for (index, run) in attempt.runs.iter().enumerate() {
+        if index > 0 {
+            lines.push(format!("- Follow-up: {}", condense(&run.prompt)));
+        }
+        match run.status {
+            ExecutionProcessStatus::Failed => lines.push("- The agent run failed".to_string()),
+            ExecutionProcessStatus::Killed => lines.push("- The agent run was stopped".to_string()),
+            ExecutionProcessStatus::Running | ExecutionProcessStatus::Completed => {}
+        }
+        if let Some(message) = &run.final_message {
+            lines.push(format!("- Agent's answer: {}", condense(message)));
+        }
+    }
+    if let Some(run) = &attempt.latest_test_run {
+        let mut tests = format!("- Tests: {} passed, {} failed", run.passed, run.failed);
+        if !run.failed_tests.is_empty() {
+            tests.push_str(&format!(" ({})", condense(&run.failed_tests.join(", "))));
+        }
+        lines.push(tests);
+    }
+    if let Some(review) = &attempt.latest_review {
+        let verdict = match review.verdict {
+            ReviewVerdict::Approved => "approved",
+            ReviewVerdict::ChangesRequested => "changes requested",
+            ReviewVerdict::Failed => "failed",
+        };
+        lines.push(format!(
+            "- Review ({verdict}): {}",
+            condense(&review.summary)
+        ));
+        for comment in review.comments.iter() {
+            let location = match (&comment.file, comment.line) {
+                (Some(file), Some(line)) => format!("{file}:{line}: "),
+                (Some(file), None) => format!("{file}: "),
+                _ => String::new(),
+            };
+            lines.push(format!("  - {location}{}", condense(&comment.comment)));
+        }
+    }
+    if attempt.merged {
+        lines.push("- Its changes were merged".to_string());
+    }
+    if lines.is_empty() {
+        lines.push("- Nothing was recorded".to_string());
+    }
+    format!(
+        "\n\nAttempt {number} ({}):\n{}",
+        attempt.profile,
+        lines.join("\n")
+    )
+}
+
+/// `text` on a single line, cut off after `MAX_FIELD_CHARS`
+fn condense(text: &str) -> String {
+    let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    match text.char_indices().nth(MAX_FIELD_CHARS) {
+        Some((end, _)) => format!("{}...", &text[..end]),
+        None => text,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+    use db::models::attempt_review::ReviewComment;
+    use sqlx::types::Json;
+    use uuid::Uuid;
+
+    use super::*;
+
+    fn run(prompt: &str, status: ExecutionProcessStatus, answer: Option<&str>) -> PriorRun {
+        PriorRun {
+            prompt: prompt.to_string(),
+            status,
+            final_message: answer.map(str::to_string),
+        }
+    }
+
+    fn attempt(runs: Vec<PriorRun>) -> PriorAttempt {
+        PriorAttempt {
+            profile: "claude-code".to_string(),
+            merged: false,
+            runs,
+            latest_test_run: None,
+            latest_review: None,
+        }
+    }
+
+    #[test]
+    fn test_history_prompt_summarizes_runs_tests_and_review() {
+        let mut first = attempt(vec![
+            run(
+                "Add a flag",
+                ExecutionProcessStatus::Completed,
+                Some("Added --flag"),
+            ),
+            run("Also document\n  it", ExecutionProcessStatus::Failed, None),
+        ]);
+        first.latest_test_run = Some(TestRun {
+            id: Uuid::new_v4(),
+            task_attempt_id: Uuid::new_v4(),
+            execution_process_id: Uuid::new_v4(),
+            passed: 3,
+            failed: 1,
+            skipped: 0,
+            failed_tests: Json(vec!["cli::parses_flag".to_string()]),
+            created_at: Utc::now(),
+        });
+        first.latest_review = Some(AttemptReview {
+            id: Uuid::new_v4(),
+            task_attempt_id: Uuid::new_v4(),
+            execution_process_id: Uuid::new_v4(),
+            verdict: ReviewVerdict::ChangesRequested,
+            summary: "Flag is never read".to_string(),
+            comments: Json(vec![ReviewComment {
+                file: Some("src/cli.rs".to_string()),
+                line: Some(7),
+                comment: "Unused".to_string(),
+            }]),
+            created_at: Utc::now(),
+        });
+        let second = attempt(vec![run(
+            "Add a flag",
+            ExecutionProcessStatus::Killed,
+            None,
+        )]);
+
+        assert_eq!(
+            history_prompt(&[first, second]),
+            format!(
+                "\n\n{HISTORY_HEADING}\n\nAttempt 1 (claude-code):\n\
+                 - Agent's answer: Added --flag\n\
+                 - Follow-up: Also document it\n\
+                 - The agent run failed\n\
+                 - Tests: 3 passed, 1 failed (cli::parses_flag)\n\
+                 - Review (changes requested): Flag is never read\n  \
+                 - src/cli.rs:7: Unused\n\n\
+                 Attempt 2 (claude-code):\n\
+                 - The agent run was stopped"
+            )
+        );
+        assert_eq!(history_prompt(&[]), "");
+    }
+
+    #[test]
+    fn test_history_prompt_leaves_out_oldest_attempts_first() {
+        let answer = "x".repeat(MAX_FIELD_CHARS * 2);
+        let attempts: Vec<PriorAttempt> = (0..20)
+            .map(|_| {
+                attempt(vec![run(
+                    "t",
+                    ExecutionProcessStatus::Completed,
+                    Some(&answer),
+                )])
+            })
+            .collect();
+
+        let history = history_prompt(&attempts);
+        assert!(history.len() <= MAX_HISTORY_CHARS + HISTORY_HEADING.len() + 2);
+        assert!(history.contains("Attempt 20 "));
+        assert!(!history.contains("Attempt 1 "));
+        assert!(history.contains(&format!("{}...", "x".repeat(MAX_FIELD_CHARS))));
+    }
+}
@@ -0,0 +1,211 @@
+use std::{
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::Duration,
+};
+
+use chrono::{DateTime, Utc};
+use db::{DBService, staged_restore_path};
+use serde::{Deserialize, Serialize};
+use sqlx::{
+    Connection,
+    sqlite::{SqliteConnectOptions, SqliteConnection},
+};
+use thiserror::Error;
+use tokio::{sync::RwLock, time::interval};
+use ts_rs::TS;
+use utils::assets::asset_dir;
+
+use crate::services::config::{BackupConfig, Config};
+
+const SNAPSHOT_PREFIX: &str = "vibe-kanban-";
+const SNAPSHOT_EXTENSION: &str = "sqlite";
+const DAILY_SNAPSHOT_INTERVAL: Duration = Duration::from_secs(24 * 60 * 60);
+
+#[derive(Debug, Error)]
+pub enum BackupError {
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    #[error(transparent)]
+    Sqlx(#[from] sqlx::Error),
+    #[error("Backup not found: {0}")]
+    NotFound(String),
+    #[error("Invalid backup name: {0}")]
+    InvalidName(String),
+    #[error("Backup failed integrity check: {0}")]
+    Corrupt(String),
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct BackupInfo {
+    pub file_name: String,
+    pub path: String,
+    #[ts(type = "number")]
+    pub size_bytes: u64,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize, TS)]
+pub struct RestoreBackupRequest {
+    pub file_name: String,
+}
+
+/// Creates SQLite snapshots with `VACUUM INTO`, prunes old ones and stages restores
+#[derive(Clone)]
+pub struct BackupService {
+    db: DBService,
+    config: Arc<RwLock<Config>>,
+}
+
+impl BackupService {
+    pub fn new(db: DBService, config: Arc<RwLock<Config>>) -> Self {
+        Self { db, config }
+    }
+
+    /// Directory snapshots are written to
+    pub async fn backup_dir(&self) -> PathBuf {
+        Self::resolve_dir(&self.config.read().await.backup)
+    }
+
+    fn resolve_dir(config: &BackupConfig) -> PathBuf {
+        match &config.directory {
+            Some(dir) if !dir.trim().is_empty() => utils::path::expand_tilde(dir.trim()),
+            _ => asset_dir().join("backups"),
+        }
+    }
+
+    /// Take a snapshot now and apply the retention policy
+    pub async fn create_backup(&self) -> Result<BackupInfo, BackupError> {
+        let config = self.config.read().await.backup.clone();
+        let dir = Self::resolve_dir(&config);
+        tokio::fs::create_dir_all(&dir).await?;
+
+        let file_name = format!(
+            "{SNAPSHOT_PREFIX}{}.{SNAPSHOT_EXTENSION}",
+            Utc::now().format("%Y%m%d-%H%M%S-%3f")
+        );
+        let path = dir.join(&file_name);
+        self.db.vacuum_into(&path).await?;
+        tracing::info!("Created database backup at {}", path.display());
+
+        self.prune(&dir, config.retention_count).await?;
+        Self::backup_info(&path).await
+    }
+
+    /// Snapshots in the backup directory, newest first
+    pub async fn list_backups(&self) -> Result<Vec<BackupInfo>, BackupError> {
+        Self::list_in(&self.backup_dir().await).await
+    }
+
+    async fn list_in(dir: &Path) -> Result<Vec<BackupInfo>, BackupError> {
+        if !dir.exists() {
+            return Ok(Vec::new());
+        }
+        let mut backups = Vec::new();
+        let mut entries = tokio::fs::read_dir(dir).await?;
+        while let Some(entry) = entries.next_entry().await? {
+            let path = entry.path();
+            if path
+                .file_name()
+                .and_then(|n| n.to_str())
+                .is_some_and(Self::is_snapshot_name)
+            {
+                backups.push(Self::backup_info(&path).await?);
+            }
+        }
+        backups.sort_by(|a, b| b.file_name.cmp(&a.file_name));
+        Ok(backups)
+    }
+
+    async fn prune(&self, dir: &Path, retention_count: u32) -> Result<(), BackupError> {
+        let backups = Self::list_in(dir).await?;
+        for backup in backups.iter().skip(retention_count.max(1) as usize) {
+            tracing::info!("Removing old database backup {}", backup.file_name);
+            tokio::fs::remove_file(&backup.path).await?;
+        }
+        Ok(())
+    }
+
+    /// Verify a snapshot and stage it to replace the database on the next restart.
+    /// Swapping the file under the running pool would corrupt it, so this never happens live.
+    pub async fn stage_restore(&self, file_name: &str) -> Result<BackupInfo, BackupError> {
+        if !Self::is_snapshot_name(file_name) {
+            return Err(BackupError::InvalidName(file_name.to_string()));
+        }
+        let path = self.backup_dir().await.join(file_name);
+        if !path.exists() {
+            return Err(BackupError::NotFound(file_name.to_string()));
+        }
+
+        let options = SqliteConnectOptions::new().filename(&path).read_only(true);
+        let mut conn = SqliteConnection::connect_with(&options).await?;
+        let (result,): (String,) = sqlx::query_as("PRAGMA integrity_check")
+            .fetch_one(&mut conn)
+            .await?;
+        conn.close().await?;
+        if result != "ok" {
+            return Err(BackupError::Corrupt(result));
+        }
+
+        tokio::fs::copy(&path, staged_restore_path()).await?;
+        tracing::info!(
+            "Staged database restore from {}, it will be applied on the next restart",
+            path.display()
+        );
+        Self::backup_info(&path).await
+    }
+
+    /// Take a snapshot every day while `daily_snapshots` is enabled
+    pub async fn spawn_daily_snapshots(&self) -> tokio::task::JoinHandle<()> {
+        let service = self.clone();
+        tokio::spawn(async move {
+            let mut interval = interval(Duration::from_secs(60 * 60));
+            loop {
+                interval.tick().await;
+                if !service.config.read().await.backup.daily_snapshots {
+                    continue;
+                }
+                match service.latest_snapshot_age().await {
+                    Ok(Some(age)) if age < DAILY_SNAPSHOT_INTERVAL => continue,
+                    Ok(_) => {}
+                    Err(e) => {
+                        tracing::error!("Failed to inspect database backups: {}", e);
+                        continue;
+                    }
+                }
+                if let Err(e) = service.create_backup().await {
+                    tracing::error!("Scheduled database backup failed: {}", e);
+                }
+            }
+        })
+    }
+
+    async fn latest_snapshot_age(&self) -> Result<Option<Duration>, BackupError> {
+        let latest = self.list_backups().await?.into_iter().next();
+        Ok(latest.map(|b| (Utc::now() - b.created_at).to_std().unwrap_or_default()))
+    }
+
+    fn is_snapshot_name(name: &str) -> bool {
+        name.starts_with(SNAPSHOT_PREFIX)
+            && name.ends_with(&format!(".{SNAPSHOT_EXTENSION}"))
+            && !name.contains(['/', '\\'])
+            && !name.contains("..")
+    }
+
+    async fn backup_info(path: &Path) -> Result<BackupInfo, BackupError> {
+        let metadata = tokio::fs::metadata(path).await?;
+        let created_at = metadata
+            .modified()
+            .map(DateTime::<Utc>::from)
+            .unwrap_or_else(|_| Utc::now());
+        Ok(BackupInfo {
+            file_name: path
+                .file_name()
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or_default(),
+            path: path.to_string_lossy().to_string(),
+            size_bytes: metadata.len(),
+            created_at,
+        })
+    }
+}
@@ -0,0 +1,321 @@
+use std::{collections::HashMap, path::Path, time::Duration};
+
+use executors::{
+    executors::ExecutorError,
+    mcp_config::{read_agent_config, write_agent_config},
+    profile::{ProfileConfig, ProfileConfigs},
+};
+use serde::Deserialize;
+use serde_json::{Map, Value};
+use sha2::{Digest, Sha256};
+use thiserror::Error;
+use utils::{
+    assets::{baseline_path, config_path},
+    offline::is_offline,
+};
+
+use crate::services::config::Config;
+
+const FETCH_TIMEOUT: Duration = Duration::from_secs(15);
+
+#[derive(Debug, Error)]
+pub enum BaselineError {
+    #[error(transparent)]
+    Http(#[from] reqwest::Error),
+    #[error("Baseline checksum mismatch, expected {expected} but got {actual}")]
+    ChecksumMismatch { expected: String, actual: String },
+    #[error(transparent)]
+    Json(#[from] serde_json::Error),
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    #[error(transparent)]
+    Executor(#[from] ExecutorError),
+    #[error(transparent)]
+    Join(#[from] tokio::task::JoinError),
+    #[error("Offline mode, not fetching")]
+    Offline,
+}
+
+/// Where an administrator publishes the organization baseline, and the SHA-256 of its content
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BaselineSource {
+    pub url: String,
+    pub sha256: String,
+}
+
+/// Settings a platform team rolls out to everyone, each applied beneath the user's own
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct Baseline {
+    /// Fields of config.json, used where the user's config doesn't set them
+    pub config: Map<String, Value>,
+    /// Added to the profiles, replacing built-in defaults but not the user's profiles.json
+    pub profiles: Vec<ProfileConfig>,
+    /// MCP servers by profile label, added to the agent's config file unless it has one of
+    /// the same name
+    pub mcp_servers: HashMap<String, Map<String, Value>>,
+}
+
+impl Baseline {
+    /// Parse baseline JSON whose SHA-256 must be `sha256`
+    fn verified(bytes: &[u8], sha256: &str) -> Result<Self, BaselineError> {
+        let actual = format!("{:x}", Sha256::digest(bytes));
+        if !actual.eq_ignore_ascii_case(sha256.trim()) {
+            return Err(BaselineError::ChecksumMismatch {
+                expected: sha256.trim().to_lowercase(),
+                actual,
+            });
+        }
+        Ok(serde_json::from_slice(bytes)?)
+    }
+
+    /// Merge the baseline config beneath the user's config.json, keeping a config that
+    /// the merge would break or that still has to be migrated as it is
+    fn apply_config(&self, config_path: &Path) -> Result<(), BaselineError> {
+        if self.config.is_empty() {
+            return Ok(());
+        }
+        let user = match std::fs::read_to_string(config_path) {
+            Ok(raw) => Some(serde_json::from_str::<Value>(&raw)?),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => None,
+            Err(e) => return Err(e.into()),
+        };
+        let Some(merged) = merged_config(&self.config, user.as_ref()) else {
+            return Ok(());
+        };
+        if serde_json::from_value::<Config>(merged.clone()).is_err() {
+            tracing::warn!("Baseline config doesn't fit the config schema, skipping it");
+            return Ok(());
+        }
+        std::fs::write(config_path, serde_json::to_string_pretty(&merged)?)?;
+        Ok(())
+    }
+
+    async fn apply_mcp_servers(&self) {
+        let profiles = ProfileConfigs::get_cached();
+        for (label, servers) in &self.mcp_servers {
+            if let Err(e) = add_mcp_servers(&profiles, label, servers).await {
+                tracing::warn!("Failed to add baseline MCP servers of {}: {}", label, e);
+            }
+        }
+    }
+}
+
+/// Fetch the organization baseline and apply it beneath the user's settings. Falls back to
+/// the last verified copy when the URL can't be reached, and drops any copy without a source.
+/// Runs at startup, before the deployment loads the config.
+pub async fn sync_baseline(source: Option<&BaselineSource>) -> Result<(), BaselineError> {
+    let path = baseline_path();
+    let Some(source) = source else {
+        if path.exists() {
+            std::fs::remove_file(&path)?;
+            tokio::task::spawn_blocking(ProfileConfigs::reload).await?;
+        }
+        return Ok(());
+    };
+
+    let fetched = match fetch(&source.url).await {
+        Ok(bytes) => Baseline::verified(&bytes, &source.sha256).map(|baseline| (baseline, bytes)),
+        Err(e) => Err(e),
+    };
+    let baseline = match fetched {
+        Ok((baseline, bytes)) => {
+            std::fs::write(&path, bytes)?;
+            baseline
+        }
+        Err(e) => {
+            let cached = std::fs::read(&path)
+                .ok()
+                .and_then(|bytes| Baseline::verified(&bytes, &source.sha256).ok());
+            match cached {
+                Some(baseline) => {
+                    tracing::warn!(
+                        "Failed to fetch baseline from {}: {}, using the cached copy",
+                        source.url,
+                        e
+                    );
+                    baseline
+                }
+                None => {
+                    let _ = std::fs::remove_file(&path);
+                    tokio::task::spawn_blocking(ProfileConfigs::reload).await?;
+                    return Err(e);
+                }
+            }
+        }
+    };
+
+    // Profiles read the cached copy themselves, so it also applies when they're reloaded
+    tokio::task::spawn_blocking(ProfileConfigs::reload).await?;
+    baseline.apply_config(&config_path())?;
+    baseline.apply_mcp_servers().await;
+    tracing::info!("Applied baseline from {}", source.url);
+    Ok(())
+}
+
+async fn fetch(url: &str) -> Result<Vec<u8>, BaselineError> {
+    // The cached copy is used instead
+    if is_offline() {
+        return Err(BaselineError::Offline);
+    }
+    let client = reqwest::Client::builder().timeout(FETCH_TIMEOUT).build()?;
+    let response = client.get(url).send().await?.error_for_status()?;
+    Ok(response.bytes().await?.to_vec())
+}
+
+/// The config with the user's fields over the baseline's over the defaults. `None` when
+/// nothing changes, or when the user's config is of an older version still to be migrated.
+fn merged_config(baseline: &Map<String, Value>, user: Option<&Value>) -> Option<Value> {
+    let defaults = serde_json::to_value(Config::default()).ok()?;
+    if let Some(user) = user
+        && user.get("config_version") != defaults.get("config_version")
+    {
+        return None;
+    }
+    let mut merged = defaults;
+    overlay(&mut merged, &Value::Object(baseline.clone()));
+    if let Some(user) = user {
+        overlay(&mut merged, user);
+    }
+    (Some(&merged) != user).then_some(merged)
+}
+
+/// Fields of `top` win over those of `base`, objects are merged field by field
+fn overlay(base: &mut Value, top: &Value) {
+    match (base, top) {
+        (Value::Object(base), Value::Object(top)) => {
+            for (key, value) in top {
+                match base.get_mut(key) {
+                    Some(existing) => overlay(existing, value),
+                    None => {
+                        base.insert(key.clone(), value.clone());
+                    }
+                }
+            }
+        }
+        (base, top) => *base = top.clone(),
+    }
+}
+
+async fn add_mcp_servers(
+    profiles: &ProfileConfigs,
+    label: &str,
+    servers: &Map<String, Value>,
+) -> Result<(), BaselineError> {
+    let Some(profile) = profiles.get_profile(label) else {
+        tracing::warn!("Baseline MCP servers name an unknown profile: {}", label);
+        return Ok(());
+    };
+    if !profile.default.agent.supports_mcp() {
+        return Ok(());
+    }
+    let Some(config_path) = profile.get_mcp_config_path() else {
+        return Ok(());
+    };
+    let mcpc = profile.default.agent.get_mcp_config();
+    let mut raw_config = read_agent_config(&config_path, &mcpc).await?;
+    if add_missing_servers(&mut raw_config, &mcpc.servers_path, servers) {
+        write_agent_config(&config_path, &mcpc, &raw_config).await?;
+    }
+    Ok(())
+}
+
+/// Add the servers missing from the servers object at `path`, true when any was added
+fn add_missing_servers(config: &mut Value, path: &[String], servers: &Map<String, Value>) -> bool {
+    let mut current = config;
+    for part in path {
+        let Some(object) = current.as_object_mut() else {
+            return false;
+        };
+        current = object
+            .entry(part.clone())
+            .or_insert_with(|| Value::Object(Map::new()));
+    }
+    let Some(existing) = current.as_object_mut() else {
+        return false;
+    };
+    let mut added = false;
+    for (name, server) in servers {
+        if !existing.contains_key(name) {
+            existing.insert(name.clone(), server.clone());
+            added = true;
+        }
+    }
+    added
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn object(value: Value) -> Map<String, Value> {
+        value.as_object().unwrap().clone()
+    }
+
+    #[test]
+    fn test_baseline_must_match_its_checksum() {
+        let bytes = br#"{"config": {"theme": "DARK"}}"#;
+        let sha256 = format!("{:x}", Sha256::digest(bytes));
+
+        let baseline = Baseline::verified(bytes, &sha256.to_uppercase()).unwrap();
+        assert_eq!(baseline.config.get("theme"), Some(&json!("DARK")));
+        assert!(baseline.profiles.is_empty());
+        assert!(matches!(
+            Baseline::verified(b"{}", &sha256),
+            Err(BaselineError::ChecksumMismatch { .. })
+        ));
+    }
+
+    #[test]
+    fn test_user_config_wins_over_baseline() {
+        let baseline = object(json!({
+            "theme": "DARK",
+            "editor": {"editor_type": "CURSOR", "custom_command": "org-edit"}
+        }));
+
+        let fresh = merged_config(&baseline, None).unwrap();
+        assert_eq!(fresh["theme"], "DARK");
+        assert_eq!(fresh["editor"]["custom_command"], "org-edit");
+        assert!(serde_json::from_value::<Config>(fresh).is_ok());
+
+        let mut user = serde_json::to_value(Config::default()).unwrap();
+        user.as_object_mut().unwrap().remove("theme");
+        user["editor"]
+            .as_object_mut()
+            .unwrap()
+            .remove("custom_command");
+        user["editor"]["editor_type"] = json!("WINDSURF");
+        let merged = merged_config(&baseline, Some(&user)).unwrap();
+        assert_eq!(merged["theme"], "DARK");
+        assert_eq!(merged["editor"]["editor_type"], "WINDSURF");
+        assert_eq!(merged["editor"]["custom_command"], "org-edit");
+
+        // Nothing to merge into a complete config, or into one that still gets migrated
+        assert_eq!(merged_config(&baseline, Some(&merged)), None);
+        assert_eq!(
+            merged_config(&baseline, Some(&json!({"config_version": "v4"}))),
+            None
+        );
+    }
+
+    #[test]
+    fn test_baseline_mcp_servers_never_replace_the_users() {
+        let mut config = json!({"mcp": {"servers": {"linear": {"command": "mine"}}}});
+        let path = vec!["mcp".to_string(), "servers".to_string()];
+        let servers = object(json!({
+            "linear": {"command": "org"},
+            "sentry": {"command": "org"}
+        }));
+
+        assert!(add_missing_servers(&mut config, &path, &servers));
+        assert_eq!(config["mcp"]["servers"]["linear"]["command"], "mine");
+        assert_eq!(config["mcp"]["servers"]["sentry"]["command"], "org");
+        assert!(!add_missing_servers(&mut config, &path, &servers));
+
+        let mut empty = json!({});
+        assert!(add_missing_servers(&mut empty, &path, &servers));
+        assert_eq!(empty["mcp"]["servers"]["linear"]["command"], "org");
+    }
+}
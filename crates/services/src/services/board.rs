@@ -0,0 +1,284 @@
+use std::{collections::HashMap, sync::Arc, time::Duration};
+
+use db::{
+    DBService,
+    models::task::{Task, TaskStatus, TaskWithAttemptStatus},
+};
+use futures::{StreamExt, stream::BoxStream};
+use serde::Serialize;
+use tokio::sync::{
+    broadcast::{self, error::RecvError},
+    mpsc,
+};
+use tokio_stream::wrappers::ReceiverStream;
+use ts_rs::TS;
+use utils::{log_msg::LogMsg, msg_store::MsgStore};
+use uuid::Uuid;
+
+/// Changes arriving within this window are sent as one set of deltas, an agent run touches
+/// several rows in quick succession
+const COALESCE_WINDOW: Duration = Duration::from_millis(200);
+
+/// A change to a project's task board
+#[derive(Debug, Clone, Serialize, TS)]
+#[serde(tag = "type", rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum BoardDelta {
+    /// Every card, sent first and again after the subscriber fell behind
+    Snapshot {
+        tasks: Vec<TaskWithAttemptStatus>,
+    },
+    /// A card was added or changed, including the state of its attempts.
+    /// `previous_status` is set when it moved to another column.
+    TaskUpserted {
+        task: TaskWithAttemptStatus,
+        previous_status: Option<TaskStatus>,
+    },
+    TaskRemoved {
+        task_id: Uuid,
+    },
+}
+
+/// The board as last sent to a subscriber, to diff the next state against
+#[derive(Default)]
+struct BoardState {
+    cards: HashMap<Uuid, (TaskStatus, serde_json::Value)>,
+}
+
+impl BoardState {
+    fn snapshot(&mut self, tasks: Vec<TaskWithAttemptStatus>) -> BoardDelta {
+        self.cards = tasks.iter().map(card_entry).collect();
+        BoardDelta::Snapshot { tasks }
+    }
+
+    /// Deltas that turn the last sent board into `tasks`
+    fn diff(&mut self, tasks: Vec<TaskWithAttemptStatus>) -> Vec<BoardDelta> {
+        let mut previous = std::mem::take(&mut self.cards);
+        let mut deltas = Vec::new();
+        for task in tasks {
+            let (id, (status, value)) = card_entry(&task);
+            match previous.remove(&id) {
+                Some((old_status, old_value)) if old_value == value => {
+                    self.cards.insert(id, (old_status, old_value));
+                    continue;
+                }
+                Some((old_status, _)) => {
+                    let previous_status = (old_status != status).then_some(old_status);
+                    deltas.push(BoardDelta::TaskUpserted {
+                        task,
+                        previous_status,
+                    });
+                }
+                None => deltas.push(BoardDelta::TaskUpserted {
+                    task,
+                    previous_status: None,
+                }),
+            }
+            self.cards.insert(id, (status, value));
+        }
+        deltas.extend(
+            previous
+                .into_keys()
+                .map(|task_id| BoardDelta::TaskRemoved { task_id }),
+        );
+        deltas
+    }
+}
+
+fn card_entry(task: &TaskWithAttemptStatus) -> (Uuid, (TaskStatus, serde_json::Value)) {
+    let value = serde_json::to_value(task).unwrap_or_default();
+    (task.id, (task.status.clone(), value))
+}
+
+/// Whether a database change event could change the board of `project_id`. Only task rows
+/// carry their project, attempt and process changes are checked by reloading the board.
+fn affects_board(msg: &LogMsg, project_id: Uuid) -> bool {
+    let LogMsg::JsonPatch(patch) = msg else {
+        return false;
+    };
+    let Ok(patch) = serde_json::to_value(patch) else {
+        return true;
+    };
+    patch.as_array().into_iter().flatten().any(|op| {
+        let record = &op["value"]["record"];
+        match record["type"].as_str() {
+            Some("TASK") => record["data"]["project_id"].as_str() == Some(&project_id.to_string()),
+            _ => true,
+        }
+    })
+}
+
+/// Deltas of the board of `project_id`, starting with a snapshot. The stream ends when the
+/// subscriber drops it.
+pub fn board_stream(
+    db: DBService,
+    events: Arc<MsgStore>,
+    project_id: Uuid,
+) -> BoxStream<'static, BoardDelta> {
+    // Subscribe before loading the snapshot so no change falls in between
+    let receiver = events.get_receiver();
+    let (tx, rx) = mpsc::channel(16);
+    tokio::spawn(forward_board_changes(db, receiver, project_id, tx));
+    ReceiverStream::new(rx).boxed()
+}
+
+async fn forward_board_changes(
+    db: DBService,
+    mut receiver: broadcast::Receiver<LogMsg>,
+    project_id: Uuid,
+    tx: mpsc::Sender<BoardDelta>,
+) {
+    let mut state = BoardState::default();
+    let mut resync = true;
+    loop {
+        if !resync {
+            let changed = tokio::select! {
+                _ = tx.closed() => return,
+                changed = receiver.recv() => changed,
+            };
+            match changed {
+                Ok(msg) if !affects_board(&msg, project_id) => continue,
+                Ok(_) => {}
+                Err(RecvError::Lagged(_)) => resync = true,
+                Err(RecvError::Closed) => return,
+            }
+            tokio::time::sleep(COALESCE_WINDOW).await;
+            // The reload below picks up everything that arrived while waiting
+            loop {
+                match receiver.try_recv() {
+                    Ok(_) => {}
+                    Err(broadcast::error::TryRecvError::Lagged(_)) => resync = true,
+                    Err(_) => break,
+                }
+            }
+        }
+
+        let tasks = match Task::find_by_project_id_with_attempt_status(&db.pool, project_id).await {
+            Ok(tasks) => tasks,
+            Err(e) => {
+                // Ending the stream makes the client reconnect and start over
+                tracing::error!("Failed to load board of project {}: {}", project_id, e);
+                return;
+            }
+        };
+        let deltas = if resync {
+            resync = false;
+            vec![state.snapshot(tasks)]
+        } else {
+            state.diff(tasks)
+        };
+        for delta in deltas {
+            if tx.send(delta).await.is_err() {
+                return;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{DateTime, Utc};
+    use sqlx::types::Json;
+
+    use super::*;
+
+    fn card(id: Uuid, status: TaskStatus) -> TaskWithAttemptStatus {
+        TaskWithAttemptStatus {
+            id,
+            project_id: Uuid::nil(),
+            title: "Fix login".to_string(),
+            description: None,
+            status,
+            parent_task_attempt: None,
+            priority: None,
+            labels: Json(Vec::new()),
+            base_branch: None,
+            created_at: DateTime::<Utc>::UNIX_EPOCH,
+            updated_at: DateTime::<Utc>::UNIX_EPOCH,
+            has_in_progress_attempt: false,
+            has_merged_attempt: false,
+            last_attempt_failed: false,
+            profile: "claude-code".to_string(),
+            tests_passed: None,
+            tests_failed: None,
+            diff_risk: None,
+            branch_sync_status: None,
+            plan_status: None,
+            review_verdict: None,
+        }
+    }
+
+    #[test]
+    fn test_diff_reports_moves_progress_and_removals() {
+        let (moved, running, removed, unchanged) = (
+            Uuid::new_v4(),
+            Uuid::new_v4(),
+            Uuid::new_v4(),
+            Uuid::new_v4(),
+        );
+        let mut state = BoardState::default();
+        state.snapshot(vec![
+            card(moved, TaskStatus::Todo),
+            card(running, TaskStatus::InProgress),
+            card(removed, TaskStatus::Todo),
+            card(unchanged, TaskStatus::Done),
+        ]);
+
+        let mut running_card = card(running, TaskStatus::InProgress);
+        running_card.has_in_progress_attempt = true;
+        let added = Uuid::new_v4();
+        let deltas = state.diff(vec![
+            card(moved, TaskStatus::InReview),
+            running_card,
+            card(unchanged, TaskStatus::Done),
+            card(added, TaskStatus::Todo),
+        ]);
+
+        let summary: Vec<_> = deltas
+            .iter()
+            .map(|delta| match delta {
+                BoardDelta::TaskUpserted {
+                    task,
+                    previous_status,
+                } => (task.id, previous_status.clone()),
+                BoardDelta::TaskRemoved { task_id } => (*task_id, None),
+                BoardDelta::Snapshot { .. } => panic!("unexpected snapshot"),
+            })
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                (moved, Some(TaskStatus::Todo)),
+                (running, None),
+                (added, None),
+                (removed, None),
+            ]
+        );
+        assert_eq!(state.diff(vec![]).len(), 4);
+    }
+
+    #[test]
+    fn test_task_changes_of_other_projects_are_ignored() {
+        let project_id = Uuid::new_v4();
+        let event = |record: serde_json::Value| {
+            LogMsg::JsonPatch(
+                serde_json::from_value(serde_json::json!([{
+                    "op": "add",
+                    "path": "/entries/1",
+                    "value": {"db_op": "update", "record": record},
+                }]))
+                .unwrap(),
+            )
+        };
+
+        let other = serde_json::json!({"type": "TASK", "data": {"project_id": Uuid::new_v4()}});
+        let own = serde_json::json!({"type": "TASK", "data": {"project_id": project_id}});
+        let attempt = serde_json::json!({"type": "TASK_ATTEMPT", "data": {}});
+        assert!(!affects_board(&event(other), project_id));
+        assert!(affects_board(&event(own), project_id));
+        assert!(affects_board(&event(attempt), project_id));
+        assert!(!affects_board(
+            &LogMsg::Stdout("hi".to_string()),
+            project_id
+        ));
+    }
+}
@@ -0,0 +1,229 @@
+use std::time::Duration;
+
+use chrono::{DateTime, Days, NaiveTime, TimeZone, Utc};
+use db::{
+    DBService,
+    models::branch_sync::{
+        AttemptBranchSync, BranchSyncCandidate, BranchSyncSchedule, BranchSyncStatus,
+    },
+};
+use tokio::time::interval;
+use tracing::{debug, error, info, warn};
+use utils::otel::attempt_span;
+
+use super::git::{BaseSyncOutcome, GitService};
+
+/// Local hour after which the nightly sync runs
+const NIGHTLY_SYNC_HOUR: u32 = 2;
+
+/// Service keeping long-lived attempt branches up to date with their base branch
+pub struct BranchSyncService {
+    db: DBService,
+    git: GitService,
+    poll_interval: Duration,
+}
+
+impl BranchSyncService {
+    pub async fn spawn(db: DBService, git: GitService) -> tokio::task::JoinHandle<()> {
+        let service = Self {
+            db,
+            git,
+            poll_interval: Duration::from_secs(300), // Check every 5 minutes
+        };
+        tokio::spawn(async move {
+            service.start().await;
+        })
+    }
+
+    async fn start(&self) {
+        info!(
+            "Starting branch sync service with interval {:?}",
+            self.poll_interval
+        );
+
+        let mut interval = interval(self.poll_interval);
+
+        loop {
+            interval.tick().await;
+            if let Err(e) = self.sync_due_branches().await {
+                error!("Error syncing attempt branches: {}", e);
+            }
+        }
+    }
+
+    async fn sync_due_branches(&self) -> Result<(), sqlx::Error> {
+        let now = chrono::Local::now();
+        for candidate in AttemptBranchSync::find_candidates(&self.db.pool).await? {
+            let base_commit = match self
+                .git
+                .get_branch_commit(&candidate.git_repo_path, &candidate.base_branch)
+            {
+                Ok(commit) => commit,
+                Err(e) => {
+                    debug!(
+                        "Skipping branch sync of attempt {}: {}",
+                        candidate.task_attempt_id, e
+                    );
+                    continue;
+                }
+            };
+            if is_due(
+                candidate.schedule,
+                candidate.last_base_commit.as_deref(),
+                candidate.last_synced_at,
+                &base_commit,
+                now,
+            ) {
+                self.sync_branch(&candidate, &base_commit).await?;
+            }
+        }
+        Ok(())
+    }
+
+    async fn sync_branch(
+        &self,
+        candidate: &BranchSyncCandidate,
+        base_commit: &str,
+    ) -> Result<(), sqlx::Error> {
+        let attempt_id = candidate.task_attempt_id;
+        let result = attempt_span("branch_sync", attempt_id).in_scope(|| {
+            self.git.sync_branch_with_base(
+                &candidate.git_repo_path,
+                candidate.worktree_path.as_deref(),
+                &candidate.branch,
+                &candidate.base_branch,
+                candidate.strategy,
+            )
+        });
+        let pool = &self.db.pool;
+        match result {
+            Ok(sync) => match sync.outcome {
+                BaseSyncOutcome::UpToDate => {
+                    AttemptBranchSync::upsert(
+                        pool,
+                        attempt_id,
+                        BranchSyncStatus::UpToDate,
+                        Some(&sync.base_commit),
+                        &[],
+                        None,
+                    )
+                    .await?;
+                }
+                BaseSyncOutcome::Synced(head) => {
+                    info!(
+                        "Synced branch {} of attempt {} with {}, now at {}",
+                        candidate.branch, attempt_id, candidate.base_branch, head
+                    );
+                    AttemptBranchSync::upsert(
+                        pool,
+                        attempt_id,
+                        BranchSyncStatus::Synced,
+                        Some(&sync.base_commit),
+                        &[],
+                        None,
+                    )
+                    .await?;
+                }
+                BaseSyncOutcome::Conflicts(files) => {
+                    warn!(
+                        "Branch {} of attempt {} conflicts with {} in {} file(s)",
+                        candidate.branch,
+                        attempt_id,
+                        candidate.base_branch,
+                        files.len()
+                    );
+                    AttemptBranchSync::upsert(
+                        pool,
+                        attempt_id,
+                        BranchSyncStatus::Conflicts,
+                        Some(&sync.base_commit),
+                        &files,
+                        None,
+                    )
+                    .await?;
+                }
+            },
+            Err(e) => {
+                warn!(
+                    "Failed to sync branch {} of attempt {}: {}",
+                    candidate.branch, attempt_id, e
+                );
+                AttemptBranchSync::upsert(
+                    pool,
+                    attempt_id,
+                    BranchSyncStatus::Failed,
+                    Some(base_commit),
+                    &[],
+                    Some(&e.to_string()),
+                )
+                .await?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Whether a branch last synced against `last_base_commit` at `last_synced_at` is due for a
+/// sync now that its base branch points to `base_commit`. Branches never synced are synced
+/// at the next check.
+pub fn is_due<Tz: TimeZone>(
+    schedule: BranchSyncSchedule,
+    last_base_commit: Option<&str>,
+    last_synced_at: Option<DateTime<Utc>>,
+    base_commit: &str,
+    now: DateTime<Tz>,
+) -> bool {
+    match schedule {
+        BranchSyncSchedule::Off => false,
+        BranchSyncSchedule::OnBaseChange => last_base_commit != Some(base_commit),
+        BranchSyncSchedule::Nightly => {
+            last_synced_at.is_none_or(|at| at < latest_nightly_sync(&now))
+        }
+    }
+}
+
+/// Start of the latest nightly sync window in the time zone of `now`
+pub(crate) fn latest_nightly_sync<Tz: TimeZone>(now: &DateTime<Tz>) -> DateTime<Utc> {
+    let sync_time = NaiveTime::from_hms_opt(NIGHTLY_SYNC_HOUR, 0, 0).unwrap();
+    let mut day = now.date_naive();
+    if now.time() < sync_time {
+        day = day - Days::new(1);
+    }
+    now.timezone()
+        .from_local_datetime(&day.and_time(sync_time))
+        .earliest()
+        .map(|start| start.with_timezone(&Utc))
+        // The sync hour was skipped by a DST change, fall back to the start of the day
+        .unwrap_or_else(|| day.and_hms_opt(0, 0, 0).unwrap().and_utc())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(s: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(s).unwrap().with_timezone(&Utc)
+    }
+
+    #[test]
+    fn test_is_due_per_schedule() {
+        let now = at("2025-08-28T10:00:00Z");
+        assert!(!is_due(BranchSyncSchedule::Off, None, None, "abc", now));
+
+        let on_change = BranchSyncSchedule::OnBaseChange;
+        assert!(is_due(on_change, None, None, "abc", now));
+        assert!(is_due(on_change, Some("old"), Some(now), "abc", now));
+        assert!(!is_due(on_change, Some("abc"), Some(now), "abc", now));
+
+        // Synced before last night's window, or not yet tonight
+        let nightly = BranchSyncSchedule::Nightly;
+        assert!(is_due(nightly, None, None, "abc", now));
+        let last = Some(at("2025-08-28T01:59:00Z"));
+        assert!(is_due(nightly, Some("abc"), last, "abc", now));
+        let last = Some(at("2025-08-28T02:05:00Z"));
+        assert!(!is_due(nightly, Some("old"), last, "abc", now));
+        let early = at("2025-08-28T01:00:00Z");
+        let last = Some(at("2025-08-27T03:00:00Z"));
+        assert!(!is_due(nightly, Some("abc"), last, "abc", early));
+    }
+}
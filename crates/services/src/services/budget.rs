@@ -0,0 +1,162 @@
+use chrono::{DateTime, Datelike, Utc};
+use db::models::{execution_cost::ExecutionCost, project::Project, project_budget::ProjectBudget};
+use serde::Serialize;
+use sqlx::SqlitePool;
+use thiserror::Error;
+use ts_rs::TS;
+use uuid::Uuid;
+
+#[derive(Debug, Error)]
+pub enum BudgetError {
+    #[error(transparent)]
+    Database(#[from] sqlx::Error),
+    #[error(
+        "Monthly budget of project {project} reached: ${spent:.2} of ${cap:.2} spent this month"
+    )]
+    ProjectCapReached {
+        project: String,
+        spent: f64,
+        cap: f64,
+    },
+    #[error("Global monthly budget reached: ${spent:.2} of ${cap:.2} spent this month")]
+    GlobalCapReached { spent: f64, cap: f64 },
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, TS)]
+pub struct ProjectSpendSummary {
+    pub project_id: Uuid,
+    /// `None` once the project is deleted, its spend still counts towards the month
+    pub project_name: Option<String>,
+    pub spend_usd: f64,
+    pub monthly_cap_usd: Option<f64>,
+}
+
+/// Estimated spend of the current calendar month (UTC), from the costs agents report
+#[derive(Debug, Clone, PartialEq, Serialize, TS)]
+pub struct SpendSummary {
+    /// `YYYY-MM`
+    pub month: String,
+    pub total_usd: f64,
+    pub monthly_cap_usd: Option<f64>,
+    pub projects: Vec<ProjectSpendSummary>,
+}
+
+impl SpendSummary {
+    /// Spend of this month so far, with the caps that apply to it
+    pub async fn load(
+        pool: &SqlitePool,
+        global_cap: Option<f64>,
+        now: DateTime<Utc>,
+    ) -> Result<Self, BudgetError> {
+        let since = format!("{:04}-{:02}-01 00:00:00", now.year(), now.month());
+        let spend = ExecutionCost::spend_by_project_since(pool, &since).await?;
+        let budgets = ProjectBudget::find_all(pool).await?;
+        let projects = Project::find_all(pool).await?;
+
+        let mut summaries: Vec<ProjectSpendSummary> = spend
+            .iter()
+            .map(|s| ProjectSpendSummary {
+                project_id: s.project_id,
+                project_name: None,
+                spend_usd: s.spend_usd,
+                monthly_cap_usd: None,
+            })
+            .collect();
+        // Capped projects are listed even before they spend anything
+        for budget in &budgets {
+            match summaries
+                .iter_mut()
+                .find(|s| s.project_id == budget.project_id)
+            {
+                Some(summary) => summary.monthly_cap_usd = Some(budget.monthly_cap_usd),
+                None => summaries.push(ProjectSpendSummary {
+                    project_id: budget.project_id,
+                    project_name: None,
+                    spend_usd: 0.0,
+                    monthly_cap_usd: Some(budget.monthly_cap_usd),
+                }),
+            }
+        }
+        for summary in &mut summaries {
+            summary.project_name = projects
+                .iter()
+                .find(|p| p.id == summary.project_id)
+                .map(|p| p.name.clone());
+        }
+        summaries.sort_by(|a, b| b.spend_usd.total_cmp(&a.spend_usd));
+
+        Ok(Self {
+            month: format!("{:04}-{:02}", now.year(), now.month()),
+            total_usd: spend.iter().fold(0.0, |total, s| total + s.spend_usd),
+            monthly_cap_usd: global_cap,
+            projects: summaries,
+        })
+    }
+
+    /// Whether a new attempt may start in the project, refused once the project's or the
+    /// global cap is reached
+    pub fn check(&self, project_id: Uuid) -> Result<(), BudgetError> {
+        if let Some(cap) = self.monthly_cap_usd
+            && self.total_usd >= cap
+        {
+            return Err(BudgetError::GlobalCapReached {
+                spent: self.total_usd,
+                cap,
+            });
+        }
+        if let Some(project) = self.projects.iter().find(|p| p.project_id == project_id)
+            && let Some(cap) = project.monthly_cap_usd
+            && project.spend_usd >= cap
+        {
+            return Err(BudgetError::ProjectCapReached {
+                project: project
+                    .project_name
+                    .clone()
+                    .unwrap_or_else(|| project_id.to_string()),
+                spent: project.spend_usd,
+                cap,
+            });
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn project(spend_usd: f64, monthly_cap_usd: Option<f64>) -> ProjectSpendSummary {
+        ProjectSpendSummary {
+            project_id: Uuid::new_v4(),
+            project_name: Some("web".to_string()),
+            spend_usd,
+            monthly_cap_usd,
+        }
+    }
+
+    #[test]
+    fn test_check_refuses_reached_caps() {
+        let capped = project(50.0, Some(50.0));
+        let open = project(10.0, Some(100.0));
+        let mut summary = SpendSummary {
+            month: "2025-08".to_string(),
+            total_usd: 60.0,
+            monthly_cap_usd: None,
+            projects: vec![capped.clone(), open.clone()],
+        };
+
+        let err = summary.check(capped.project_id).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Monthly budget of project web reached: $50.00 of $50.00 spent this month"
+        );
+        assert!(summary.check(open.project_id).is_ok());
+        assert!(summary.check(Uuid::new_v4()).is_ok());
+
+        summary.monthly_cap_usd = Some(60.0);
+        assert!(matches!(
+            summary.check(open.project_id),
+            Err(BudgetError::GlobalCapReached { .. })
+        ));
+    }
+}
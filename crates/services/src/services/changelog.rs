@@ -0,0 +1,425 @@
+use std::path::{Component, Path, PathBuf};
+
+use db::models::{
+    project_changelog::{ChangelogFormat, ProjectChangelog},
+    task::Task,
+    task_attempt::TaskAttemptContext,
+};
+use sqlx::SqlitePool;
+use thiserror::Error;
+use utils::{
+    diff::Diff,
+    text::{git_branch_id, short_uuid},
+};
+
+use super::{
+    git::{DiffTarget, GitService, GitServiceError},
+    merge_queue::merge_commit_message,
+};
+
+#[derive(Debug, Error)]
+pub enum ChangelogError {
+    #[error(transparent)]
+    Database(#[from] sqlx::Error),
+    #[error(transparent)]
+    Git(#[from] GitServiceError),
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    #[error("The changelog directory must be a relative path inside the repository: {0}")]
+    InvalidDirectory(String),
+}
+
+/// Kind of change an attempt makes, as a conventional commit type
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChangeKind {
+    Feat,
+    Fix,
+    Docs,
+    Test,
+    Refactor,
+    Chore,
+}
+
+impl ChangeKind {
+    const ALL: [ChangeKind; 6] = [
+        ChangeKind::Feat,
+        ChangeKind::Fix,
+        ChangeKind::Docs,
+        ChangeKind::Test,
+        ChangeKind::Refactor,
+        ChangeKind::Chore,
+    ];
+
+    fn commit_type(self) -> &'static str {
+        match self {
+            ChangeKind::Feat => "feat",
+            ChangeKind::Fix => "fix",
+            ChangeKind::Docs => "docs",
+            ChangeKind::Test => "test",
+            ChangeKind::Refactor => "refactor",
+            ChangeKind::Chore => "chore",
+        }
+    }
+}
+
+/// Files that only configure the build or the repository
+const CHORE_FILES: &[&str] = &[
+    "Cargo.toml",
+    "Cargo.lock",
+    "package.json",
+    "package-lock.json",
+    "pnpm-lock.yaml",
+    "yarn.lock",
+    ".gitignore",
+];
+
+/// The directory a project's fragments go in, checked to stay inside the repository
+pub fn fragment_dir(directory: &str) -> Result<PathBuf, ChangelogError> {
+    let path = Path::new(directory.trim());
+    if path.as_os_str().is_empty()
+        || !path
+            .components()
+            .all(|component| matches!(component, Component::Normal(_) | Component::CurDir))
+    {
+        return Err(ChangelogError::InvalidDirectory(directory.to_string()));
+    }
+    Ok(path.to_path_buf())
+}
+
+/// Kind of change from a conventional prefix of the task's title, the changed paths when
+/// they're all docs, tests or repository configuration, then words of the title
+pub fn classify(title: &str, paths: &[String]) -> ChangeKind {
+    if let Some((kind, _)) = conventional_prefix(title) {
+        return kind;
+    }
+    let is_doc = |path: &String| {
+        path.starts_with("docs/")
+            || [".md", ".mdx", ".rst"]
+                .iter()
+                .any(|ext| path.ends_with(ext))
+    };
+    let is_test = |path: &String| {
+        path.split('/').any(|part| {
+            matches!(part, "test" | "tests" | "__tests__" | "e2e")
+                || part.contains(".test.")
+                || part.contains(".spec.")
+                || part.ends_with("_test.rs")
+        })
+    };
+    let is_chore = |path: &String| {
+        path.starts_with(".github/")
+            || CHORE_FILES.contains(&path.rsplit('/').next().unwrap_or(path))
+    };
+    if !paths.is_empty() {
+        if paths.iter().all(is_doc) {
+            return ChangeKind::Docs;
+        }
+        if paths.iter().all(is_test) {
+            return ChangeKind::Test;
+        }
+        if paths.iter().all(is_chore) {
+            return ChangeKind::Chore;
+        }
+    }
+
+    let lower = title.to_lowercase();
+    let words: Vec<&str> = lower
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .collect();
+    let has = |candidates: &[&str]| words.iter().any(|word| candidates.contains(word));
+    if has(&[
+        "fix",
+        "fixes",
+        "fixed",
+        "bug",
+        "bugfix",
+        "crash",
+        "broken",
+        "regression",
+    ]) {
+        ChangeKind::Fix
+    } else if has(&["refactor", "rename", "cleanup", "simplify", "restructure"]) {
+        ChangeKind::Refactor
+    } else {
+        ChangeKind::Feat
+    }
+}
+
+/// Kind and rest of a title already written as a conventional commit, e.g. `fix(ui): ...`
+fn conventional_prefix(title: &str) -> Option<(ChangeKind, &str)> {
+    let (head, rest) = title.split_once(':')?;
+    let commit_type = head.split('(').next()?.trim_end_matches('!').trim();
+    let kind = ChangeKind::ALL
+        .into_iter()
+        .find(|kind| kind.commit_type().eq_ignore_ascii_case(commit_type))?;
+    Some((kind, rest.trim()))
+}
+
+/// Directory all changed paths share at the top, as the conventional commit scope
+pub fn scope(paths: &[String]) -> Option<&str> {
+    let mut dirs = paths
+        .iter()
+        .map(|path| path.split_once('/').map(|(dir, _)| dir));
+    let first = dirs.next()??;
+    dirs.all(|dir| dir == Some(first)).then_some(first)
+}
+
+/// Subject of the task as a conventional commit, kept as it is when the title already is one
+pub fn conventional_subject(title: &str, kind: ChangeKind, scope: Option<&str>) -> String {
+    let title = title.trim();
+    if conventional_prefix(title).is_some() {
+        return title.to_string();
+    }
+    match scope {
+        Some(scope) => format!("{}({scope}): {title}", kind.commit_type()),
+        None => format!("{}: {title}", kind.commit_type()),
+    }
+}
+
+/// Changelog fragment of a task
+pub fn render_fragment(
+    format: ChangelogFormat,
+    task: &Task,
+    kind: ChangeKind,
+    scope: Option<&str>,
+    only_removals: bool,
+) -> String {
+    match format {
+        ChangelogFormat::KeepAChangelog => {
+            let section = match kind {
+                _ if only_removals => "Removed",
+                ChangeKind::Feat => "Added",
+                ChangeKind::Fix => "Fixed",
+                _ => "Changed",
+            };
+            let title =
+                conventional_prefix(&task.title).map_or(task.title.trim(), |(_, rest)| rest);
+            format!("### {section}\n\n- {title}\n")
+        }
+        ChangelogFormat::ConventionalCommits => {
+            let mut fragment = conventional_subject(&task.title, kind, scope);
+            if let Some(description) = &task.description
+                && !description.trim().is_empty()
+            {
+                fragment.push_str("\n\n");
+                fragment.push_str(description.trim());
+            }
+            fragment.push('\n');
+            fragment
+        }
+    }
+}
+
+/// Fragment file of a task, named after it so fragments of different tasks don't collide
+pub fn fragment_path(directory: &Path, task: &Task) -> PathBuf {
+    directory.join(format!(
+        "{}-{}.md",
+        short_uuid(&task.id),
+        git_branch_id(&task.title)
+    ))
+}
+
+/// Message to merge the attempt with. When the project writes changelog fragments, the
+/// attempt's fragment is committed to its branch first so the merge takes it along.
+pub async fn prepare_merge(
+    pool: &SqlitePool,
+    git: &GitService,
+    ctx: &TaskAttemptContext,
+    worktree_path: &Path,
+    branch_name: &str,
+) -> Result<String, ChangelogError> {
+    let message = merge_commit_message(&ctx.task);
+    let Some(settings) = ProjectChangelog::find_by_project_id(pool, ctx.project.id)
+        .await?
+        .filter(|settings| settings.enabled || settings.conventional_commits)
+    else {
+        return Ok(message);
+    };
+    let directory = fragment_dir(&settings.directory)?;
+
+    // A fragment left by an earlier merge try isn't part of the change
+    let diffs: Vec<Diff> = git
+        .get_diffs(
+            DiffTarget::Worktree {
+                worktree_path,
+                branch_name,
+                base_branch: &ctx.task_attempt.base_branch,
+            },
+            None,
+        )?
+        .into_iter()
+        .filter(|diff| !Path::new(&GitService::diff_path(diff)).starts_with(&directory))
+        .collect();
+    let paths: Vec<String> = diffs.iter().map(GitService::diff_path).collect();
+    let kind = classify(&ctx.task.title, &paths);
+    let commit_scope = scope(&paths);
+
+    if settings.enabled {
+        let only_removals = !diffs.is_empty() && diffs.iter().all(|diff| diff.new_file.is_none());
+        let fragment = render_fragment(
+            settings.format,
+            &ctx.task,
+            kind,
+            commit_scope,
+            only_removals,
+        );
+        let path = worktree_path.join(fragment_path(&directory, &ctx.task));
+        std::fs::create_dir_all(path.parent().unwrap_or(worktree_path))?;
+        std::fs::write(&path, fragment)?;
+        git.commit(
+            worktree_path,
+            &format!("docs: add changelog entry for {}", ctx.task.title.trim()),
+        )?;
+    }
+
+    if !settings.conventional_commits {
+        return Ok(message);
+    }
+    let subject = conventional_subject(&ctx.task.title, kind, commit_scope);
+    Ok(match message.strip_prefix(ctx.task.title.as_str()) {
+        Some(rest) => format!("{subject}{rest}"),
+        None => message,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+    use db::models::task::TaskStatus;
+    use sqlx::types::Json;
+    use uuid::Uuid;
+
+    use super::*;
+
+    fn paths(paths: &[&str]) -> Vec<String> {
+        paths.iter().map(|path| path.to_string()).collect()
+    }
+
+    fn task(title: &str, description: Option<&str>) -> Task {
+        Task {
+            id: Uuid::nil(),
+            project_id: Uuid::nil(),
+            title: title.to_string(),
+            description: description.map(str::to_string),
+            status: TaskStatus::Done,
+            parent_task_attempt: None,
+            priority: None,
+            labels: Json(Vec::new()),
+            base_branch: None,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_classify() {
+        let code = paths(&["src/lib.rs"]);
+        assert_eq!(
+            classify("refactor(api): split routes", &code),
+            ChangeKind::Refactor
+        );
+        assert_eq!(
+            classify("Crash when the list is empty", &code),
+            ChangeKind::Fix
+        );
+        assert_eq!(
+            classify("Rename the config module", &code),
+            ChangeKind::Refactor
+        );
+        assert_eq!(classify("Add a dark mode", &code), ChangeKind::Feat);
+        assert_eq!(
+            classify("Add a dark mode", &paths(&["README.md", "docs/theme.png"])),
+            ChangeKind::Docs
+        );
+        assert_eq!(
+            classify(
+                "Cover the parser",
+                &paths(&["src/parser.test.ts", "tests/parse.rs"])
+            ),
+            ChangeKind::Test
+        );
+        assert_eq!(
+            classify(
+                "Bump serde",
+                &paths(&["Cargo.toml", "crates/db/Cargo.toml"])
+            ),
+            ChangeKind::Chore
+        );
+    }
+
+    #[test]
+    fn test_scope_and_subject() {
+        assert_eq!(
+            scope(&paths(&["frontend/a.ts", "frontend/b/c.ts"])),
+            Some("frontend")
+        );
+        assert_eq!(scope(&paths(&["frontend/a.ts", "crates/b.rs"])), None);
+        assert_eq!(scope(&paths(&["README.md"])), None);
+
+        assert_eq!(
+            conventional_subject("Add a dark mode", ChangeKind::Feat, Some("frontend")),
+            "feat(frontend): Add a dark mode"
+        );
+        assert_eq!(
+            conventional_subject("fix: empty list crash", ChangeKind::Feat, Some("frontend")),
+            "fix: empty list crash"
+        );
+    }
+
+    #[test]
+    fn test_render_fragment() {
+        let fix = task(
+            "fix(ui): empty list crash",
+            Some("Shows a placeholder instead.\n"),
+        );
+        assert_eq!(
+            render_fragment(
+                ChangelogFormat::KeepAChangelog,
+                &fix,
+                ChangeKind::Fix,
+                None,
+                false
+            ),
+            "### Fixed\n\n- empty list crash\n"
+        );
+        assert_eq!(
+            render_fragment(
+                ChangelogFormat::ConventionalCommits,
+                &fix,
+                ChangeKind::Fix,
+                None,
+                false
+            ),
+            "fix(ui): empty list crash\n\nShows a placeholder instead.\n"
+        );
+
+        let removal = task("Drop the legacy importer", None);
+        assert_eq!(
+            render_fragment(
+                ChangelogFormat::KeepAChangelog,
+                &removal,
+                ChangeKind::Feat,
+                None,
+                true
+            ),
+            "### Removed\n\n- Drop the legacy importer\n"
+        );
+        assert_eq!(
+            fragment_path(Path::new("changelog.d"), &removal),
+            PathBuf::from("changelog.d/0000-drop-the-l.md")
+        );
+    }
+
+    #[test]
+    fn test_fragment_dir_stays_in_repository() {
+        assert_eq!(
+            fragment_dir(" changelog.d ").unwrap(),
+            PathBuf::from("changelog.d")
+        );
+        assert!(fragment_dir("docs/changes").is_ok());
+        assert!(fragment_dir("").is_err());
+        assert!(fragment_dir("/tmp/changes").is_err());
+        assert!(fragment_dir("../changes").is_err());
+    }
+}
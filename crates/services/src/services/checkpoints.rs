@@ -0,0 +1,267 @@
+use std::{path::Path, sync::Arc, time::Duration};
+
+use db::{
+    DBService,
+    models::attempt_checkpoint::{AttemptCheckpoint, CheckpointReason, CreateAttemptCheckpoint},
+};
+use futures::StreamExt;
+use thiserror::Error;
+use tokio::task::JoinHandle;
+use utils::{log_msg::LogMsg, msg_store::MsgStore};
+use uuid::Uuid;
+
+use super::git::{GitService, GitServiceError};
+
+/// How often a running coding agent's worktree is snapshotted, when it changed
+const CHECKPOINT_INTERVAL: Duration = Duration::from_secs(5 * 60);
+
+/// Checkpoints kept per attempt, older ones are dropped with their refs
+const MAX_CHECKPOINTS: i64 = 50;
+
+#[derive(Debug, Error)]
+pub enum CheckpointError {
+    #[error(transparent)]
+    Database(#[from] sqlx::Error),
+    #[error(transparent)]
+    Git(#[from] GitServiceError),
+    #[error("Checkpoint {0} not found")]
+    NotFound(Uuid),
+    #[error("Stop the attempt's running executions before rolling it back")]
+    ExecutionRunning,
+}
+
+/// Hidden ref a checkpoint's commit is kept under, outside of branches and tags
+pub fn checkpoint_ref(task_attempt_id: Uuid, checkpoint_id: Uuid) -> String {
+    format!("refs/vibe-kanban/checkpoints/{task_attempt_id}/{checkpoint_id}")
+}
+
+/// Snapshots of attempt worktrees, taken before each execution and while coding agents run,
+/// so a worktree can be rolled back after an agent damaged it
+#[derive(Clone)]
+pub struct CheckpointService {
+    db: DBService,
+    git: GitService,
+}
+
+impl CheckpointService {
+    pub fn new(db: DBService, git: GitService) -> Self {
+        Self { db, git }
+    }
+
+    /// Snapshot the worktree, `None` when it's unchanged since the attempt's latest checkpoint
+    pub async fn create(
+        &self,
+        task_attempt_id: Uuid,
+        worktree_path: &Path,
+        reason: CheckpointReason,
+        execution_process_id: Option<Uuid>,
+    ) -> Result<Option<AttemptCheckpoint>, CheckpointError> {
+        let pool = &self.db.pool;
+        let latest = AttemptCheckpoint::find_latest(pool, task_attempt_id).await?;
+        let id = Uuid::new_v4();
+        let Some(snapshot) = self.git.snapshot_worktree(
+            worktree_path,
+            &checkpoint_ref(task_attempt_id, id),
+            &format!("vibe-kanban checkpoint ({reason:?})"),
+            latest.as_ref().map(|c| c.commit_sha.as_str()),
+        )?
+        else {
+            return Ok(None);
+        };
+        let checkpoint = AttemptCheckpoint::create(
+            pool,
+            &CreateAttemptCheckpoint {
+                task_attempt_id,
+                execution_process_id,
+                reason,
+                commit_sha: snapshot.commit,
+                head_commit: snapshot.head,
+            },
+            id,
+        )
+        .await?;
+
+        for dropped in
+            AttemptCheckpoint::delete_oldest(pool, task_attempt_id, MAX_CHECKPOINTS).await?
+        {
+            let ref_name = checkpoint_ref(task_attempt_id, dropped.id);
+            if let Err(e) = self.git.delete_reference(worktree_path, &ref_name) {
+                tracing::warn!("Failed to delete checkpoint ref {}: {}", ref_name, e);
+            }
+        }
+        Ok(Some(checkpoint))
+    }
+
+    /// Snapshot a coding agent's worktree at intervals while it runs and once its output
+    /// finishes
+    pub fn watch(
+        &self,
+        task_attempt_id: Uuid,
+        execution_process_id: Uuid,
+        worktree_path: &Path,
+        store: Arc<MsgStore>,
+    ) -> JoinHandle<()> {
+        let checkpoints = self.clone();
+        let worktree_path = worktree_path.to_path_buf();
+        tokio::spawn(async move {
+            let mut ticks = tokio::time::interval(CHECKPOINT_INTERVAL);
+            // The first tick is immediate, a checkpoint was just taken before the agent started
+            ticks.tick().await;
+            let mut stream = store.history_plus_stream();
+            loop {
+                let reason = tokio::select! {
+                    _ = ticks.tick() => CheckpointReason::Periodic,
+                    msg = stream.next() => match msg {
+                        Some(Ok(LogMsg::Finished)) | None => CheckpointReason::AgentFinished,
+                        _ => continue,
+                    },
+                };
+                checkpoints
+                    .create_logged(
+                        task_attempt_id,
+                        &worktree_path,
+                        reason,
+                        Some(execution_process_id),
+                    )
+                    .await;
+                if reason == CheckpointReason::AgentFinished {
+                    break;
+                }
+            }
+        })
+    }
+
+    /// Like `create`, for checkpoints nothing waits on: failures are only logged
+    pub async fn create_logged(
+        &self,
+        task_attempt_id: Uuid,
+        worktree_path: &Path,
+        reason: CheckpointReason,
+        execution_process_id: Option<Uuid>,
+    ) {
+        if let Err(e) = self
+            .create(task_attempt_id, worktree_path, reason, execution_process_id)
+            .await
+        {
+            tracing::warn!(
+                "Failed to checkpoint the worktree of attempt {}: {}",
+                task_attempt_id,
+                e
+            );
+        }
+    }
+
+    /// Roll the attempt's worktree back to one of its checkpoints. The worktree is
+    /// checkpointed first, so the rollback can be undone; that checkpoint is returned unless
+    /// nothing changed since the latest one.
+    pub async fn rollback(
+        &self,
+        task_attempt_id: Uuid,
+        worktree_path: &Path,
+        checkpoint_id: Uuid,
+    ) -> Result<Option<AttemptCheckpoint>, CheckpointError> {
+        let checkpoint = AttemptCheckpoint::find_by_id(&self.db.pool, checkpoint_id)
+            .await?
+            .filter(|c| c.task_attempt_id == task_attempt_id)
+            .ok_or(CheckpointError::NotFound(checkpoint_id))?;
+        let backup = self
+            .create(
+                task_attempt_id,
+                worktree_path,
+                CheckpointReason::BeforeRollback,
+                None,
+            )
+            .await?;
+        self.git
+            .restore_snapshot(worktree_path, &checkpoint.commit_sha)?;
+        tracing::info!(
+            "Rolled attempt {} back to checkpoint {}",
+            task_attempt_id,
+            checkpoint.id
+        );
+        Ok(backup)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tempfile::TempDir;
+
+    use super::*;
+
+    fn write(dir: &Path, name: &str, content: &str) {
+        std::fs::write(dir.join(name), content).unwrap();
+    }
+
+    fn read(dir: &Path, name: &str) -> Option<String> {
+        std::fs::read_to_string(dir.join(name)).ok()
+    }
+
+    #[test]
+    fn test_snapshot_and_restore_worktree() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path();
+        let git = GitService::new();
+        git.initialize_repo_with_main_branch(path).unwrap();
+        let mut config = git2::Repository::open(path).unwrap().config().unwrap();
+        config.set_str("user.name", "Test User").unwrap();
+        config.set_str("user.email", "test@example.com").unwrap();
+        write(path, ".gitignore", "target/\n");
+        write(path, "lib.rs", "fn a() {}\n");
+        git.commit(path, "add lib").unwrap();
+
+        // Uncommitted and untracked changes are part of the snapshot
+        write(path, "lib.rs", "fn a() {}\nfn b() {}\n");
+        write(path, "new.rs", "fn c() {}\n");
+        let attempt_id = Uuid::new_v4();
+        let first = git
+            .snapshot_worktree(
+                path,
+                &checkpoint_ref(attempt_id, Uuid::new_v4()),
+                "cp",
+                None,
+            )
+            .unwrap()
+            .unwrap();
+        assert!(
+            git.snapshot_worktree(path, "refs/vibe-kanban/unused", "cp", Some(&first.commit))
+                .unwrap()
+                .is_none()
+        );
+
+        // The agent commits, deletes a file and creates others, one of them ignored
+        write(path, "lib.rs", "");
+        git.commit(path, "empty lib").unwrap();
+        std::fs::remove_file(path.join("new.rs")).unwrap();
+        write(path, "junk.rs", "junk\n");
+        std::fs::create_dir(path.join("target")).unwrap();
+        write(path, "target/out", "build\n");
+
+        git.restore_snapshot(path, &first.commit).unwrap();
+
+        assert_eq!(read(path, "lib.rs").unwrap(), "fn a() {}\nfn b() {}\n");
+        assert_eq!(read(path, "new.rs").unwrap(), "fn c() {}\n");
+        assert_eq!(read(path, "junk.rs"), None);
+        assert_eq!(read(path, "target/out").unwrap(), "build\n");
+        let repo = git2::Repository::open(path).unwrap();
+        assert_eq!(
+            repo.head()
+                .unwrap()
+                .peel_to_commit()
+                .unwrap()
+                .id()
+                .to_string(),
+            first.head
+        );
+        // What was uncommitted is again
+        let statuses = repo.statuses(None).unwrap();
+        let status = |name: &str| {
+            statuses
+                .iter()
+                .find(|s| s.path() == Some(name))
+                .map(|s| s.status())
+        };
+        assert_eq!(status("lib.rs"), Some(git2::Status::WT_MODIFIED));
+        assert_eq!(status("new.rs"), Some(git2::Status::WT_NEW));
+    }
+}
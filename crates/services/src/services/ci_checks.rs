@@ -0,0 +1,150 @@
+use std::path::Path;
+
+use serde::Serialize;
+use ts_rs::TS;
+
+use super::{
+    git::GitService,
+    github_service::{CheckState, CommitCheck, GitHubRepoInfo, GitHubService, GitHubServiceError},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, TS)]
+#[serde(rename_all = "snake_case")]
+pub enum ChecksState {
+    /// The attempt branch doesn't exist on GitHub
+    NotPushed,
+    /// The attempt branch has local commits GitHub didn't get yet
+    Outdated,
+    Pending,
+    Passed,
+    Failed,
+}
+
+impl ChecksState {
+    /// Why a local merge waits for this state, `None` once checks passed
+    pub fn blocking_reason(self) -> Option<&'static str> {
+        match self {
+            ChecksState::NotPushed => Some("Push the attempt branch so GitHub can run its checks"),
+            ChecksState::Outdated => {
+                Some("Push the attempt branch's latest commits so GitHub can check them")
+            }
+            ChecksState::Pending => Some("GitHub checks of the attempt branch are still running"),
+            ChecksState::Failed => Some("GitHub checks of the attempt branch failed"),
+            ChecksState::Passed => None,
+        }
+    }
+}
+
+/// GitHub checks of an attempt branch's pushed commit
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct AttemptChecks {
+    pub state: ChecksState,
+    pub local_commit: String,
+    /// Commit the attempt branch points to on GitHub
+    pub remote_commit: Option<String>,
+    /// Checks the base branch's protection requires, every check counts when empty
+    pub required: Vec<String>,
+    pub checks: Vec<CommitCheck>,
+}
+
+/// Load the checks GitHub reported for the attempt branch
+pub async fn attempt_checks(
+    git: &GitService,
+    github: &GitHubService,
+    repo_path: &Path,
+    branch: &str,
+    base_branch: &str,
+) -> Result<AttemptChecks, GitHubServiceError> {
+    let (owner, repo_name) = git.get_github_repo_info(repo_path)?;
+    let repo_info = GitHubRepoInfo { owner, repo_name };
+    let local_commit = git.get_branch_commit(repo_path, branch)?;
+    let base_branch = base_branch.strip_prefix("origin/").unwrap_or(base_branch);
+    let required = github
+        .get_branch(&repo_info, base_branch)
+        .await?
+        .map(|base| base.required_checks)
+        .unwrap_or_default();
+
+    let Some(remote) = github.get_branch(&repo_info, branch).await? else {
+        return Ok(AttemptChecks {
+            state: ChecksState::NotPushed,
+            local_commit,
+            remote_commit: None,
+            required,
+            checks: Vec::new(),
+        });
+    };
+    let checks = github.get_commit_checks(&repo_info, &remote.commit).await?;
+    let state = if remote.commit != local_commit {
+        ChecksState::Outdated
+    } else {
+        evaluate(&required, &checks)
+    };
+    Ok(AttemptChecks {
+        state,
+        local_commit,
+        remote_commit: Some(remote.commit),
+        required,
+        checks,
+    })
+}
+
+/// Whether the checks that count passed. Required checks that haven't reported yet are
+/// pending, without required checks every reported check counts.
+fn evaluate(required: &[String], checks: &[CommitCheck]) -> ChecksState {
+    let counted: Vec<&CommitCheck> = checks
+        .iter()
+        .filter(|check| required.is_empty() || required.contains(&check.name))
+        .collect();
+    let missing = required
+        .iter()
+        .any(|name| !checks.iter().any(|check| &check.name == name));
+    if counted
+        .iter()
+        .any(|check| check.state == CheckState::Failure)
+    {
+        ChecksState::Failed
+    } else if missing
+        || counted
+            .iter()
+            .any(|check| check.state == CheckState::Pending)
+    {
+        ChecksState::Pending
+    } else {
+        ChecksState::Passed
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn check(name: &str, state: CheckState) -> CommitCheck {
+        CommitCheck {
+            name: name.to_string(),
+            state,
+            url: None,
+        }
+    }
+
+    #[test]
+    fn test_only_required_checks_gate_when_configured() {
+        let checks = vec![
+            check("build", CheckState::Success),
+            check("lint", CheckState::Failure),
+        ];
+        assert_eq!(evaluate(&[], &checks), ChecksState::Failed);
+        assert_eq!(
+            evaluate(&["build".to_string()], &checks),
+            ChecksState::Passed
+        );
+        assert_eq!(
+            evaluate(&["build".to_string(), "test".to_string()], &checks),
+            ChecksState::Pending
+        );
+        assert_eq!(
+            evaluate(&[], &[check("build", CheckState::Pending)]),
+            ChecksState::Pending
+        );
+    }
+}
@@ -0,0 +1,435 @@
+use std::{sync::Arc, time::Duration};
+
+use chrono::Utc;
+use db::models::{
+    ci_triage::ProjectCiTriage,
+    inbound_task::InboundTask,
+    project::Project,
+    task::{CreateTask, Task, TaskStatus},
+    task_attempt::{CreateTaskAttempt, TaskAttempt},
+};
+use executors::profile::ProfileConfigs;
+use thiserror::Error;
+use tokio::sync::RwLock;
+use uuid::Uuid;
+
+use super::{
+    attachments::{AttachmentError, AttachmentStore},
+    budget::{BudgetError, SpendSummary},
+    config::Config,
+    container::{ContainerError, ContainerService},
+    git::{GitService, GitServiceError},
+    github_auth,
+    github_service::{GitHubRepoInfo, GitHubService, GitHubServiceError, WorkflowJob, WorkflowRun},
+    repo_config::{RepoConfig, RepoConfigError},
+};
+
+const POLL_INTERVAL: Duration = Duration::from_secs(120);
+/// Failed jobs of a run whose logs are fetched
+const MAX_JOBS: usize = 3;
+/// Lines of a log kept before and after its first error in the prompt
+const LINES_BEFORE_ERROR: usize = 40;
+const LINES_AFTER_ERROR: usize = 10;
+/// Tail of a log kept when it has no error marker
+const TAIL_LINES: usize = 50;
+/// Tail of each log attached to the task
+const MAX_LOG_BYTES: usize = 2 * 1024 * 1024;
+
+#[derive(Debug, Error)]
+pub enum CiTriageError {
+    #[error(transparent)]
+    Database(#[from] sqlx::Error),
+    #[error(transparent)]
+    GitHub(#[from] GitHubServiceError),
+    #[error(transparent)]
+    Git(#[from] GitServiceError),
+    #[error(transparent)]
+    RepoConfig(#[from] RepoConfigError),
+    #[error(transparent)]
+    Budget(#[from] BudgetError),
+    #[error(transparent)]
+    Container(#[from] ContainerError),
+    #[error(transparent)]
+    Attachment(#[from] AttachmentError),
+    #[error("Project {0} not found")]
+    ProjectNotFound(Uuid),
+    #[error("Profile not found: {0}")]
+    ProfileNotFound(String),
+}
+
+/// A project's repository on GitHub and the branch of it that's watched
+struct Watched<'a> {
+    project: &'a Project,
+    repo_info: GitHubRepoInfo,
+    branch: String,
+    start_agent: bool,
+}
+
+/// A failed job with its log
+struct FailedJob {
+    job: WorkflowJob,
+    log: String,
+}
+
+/// Whether a log line is one GitHub marks as an error, e.g. `##[error]Process completed
+/// with exit code 1.`
+fn is_error_line(line: &str) -> bool {
+    line.contains("##[error]")
+}
+
+/// Drop the timestamp GitHub puts in front of every log line
+fn strip_timestamp(line: &str) -> &str {
+    match line.split_once(' ') {
+        Some((stamp, rest))
+            if stamp.len() >= 20
+                && stamp.ends_with('Z')
+                && stamp.starts_with(|c: char| c.is_ascii_digit()) =>
+        {
+            rest
+        }
+        _ => line,
+    }
+}
+
+/// Lines of a job log around its first error, or its tail when no line is marked as one
+pub fn failure_excerpt(log: &str) -> String {
+    let lines: Vec<&str> = log.lines().map(strip_timestamp).collect();
+    let range = match lines.iter().position(|line| is_error_line(line)) {
+        Some(first_error) => {
+            first_error.saturating_sub(LINES_BEFORE_ERROR)
+                ..(first_error + LINES_AFTER_ERROR + 1).min(lines.len())
+        }
+        None => lines.len().saturating_sub(TAIL_LINES)..lines.len(),
+    };
+    lines[range].join("\n")
+}
+
+fn workflow_name(run: &WorkflowRun) -> &str {
+    run.name.as_deref().unwrap_or(&run.path)
+}
+
+pub fn task_title(run: &WorkflowRun, branch: &str) -> String {
+    format!("Fix CI: {} failing on {branch}", workflow_name(run))
+}
+
+fn task_prompt(run: &WorkflowRun, branch: &str, failed: &[FailedJob]) -> String {
+    let commit = run.head_sha.get(..7).unwrap_or(&run.head_sha);
+    let mut prompt = format!(
+        "The {} workflow failed on {branch} at commit {commit}: {}\n",
+        workflow_name(run),
+        run.html_url
+    );
+    for FailedJob { job, log } in failed {
+        match job.failed_step() {
+            Some(step) => prompt.push_str(&format!("\nJob {}, step {step}:\n", job.name)),
+            None => prompt.push_str(&format!("\nJob {}:\n", job.name)),
+        }
+        prompt.push_str(&format!("```\n{}\n```\n", failure_excerpt(log)));
+    }
+    prompt.push_str(
+        "\nThe full job logs are attached. Find the cause of the failure, fix it and make sure the workflow would pass.\n",
+    );
+    prompt
+}
+
+fn log_file_name(job: &WorkflowJob) -> String {
+    let name: String = job
+        .name
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '-' })
+        .collect();
+    format!("ci-{}-{}.log", name.trim_matches('-'), job.id)
+}
+
+/// Start an attempt on a card a failure opened, from the branch that failed
+async fn start_attempt<C: ContainerService + Sync>(
+    container: &C,
+    config: &Arc<RwLock<Config>>,
+    project: &Project,
+    task: &Task,
+    branch: &str,
+) -> Result<(), CiTriageError> {
+    let pool = &container.db().pool;
+    let global_cap = config.read().await.budget.monthly_cap_usd;
+    SpendSummary::load(pool, global_cap, Utc::now())
+        .await?
+        .check(project.id)?;
+
+    let label = RepoConfig::load(&GitService::new(), &project.git_repo_path, branch)?
+        .profile_or(config.read().await.profile.clone());
+    let profile = ProfileConfigs::get_cached()
+        .get_profile(&label.profile)
+        .map(|profile| profile.default.label.clone())
+        .ok_or_else(|| CiTriageError::ProfileNotFound(label.profile.clone()))?;
+    let attempt = TaskAttempt::create(
+        pool,
+        &CreateTaskAttempt {
+            profile,
+            base_branch: branch.to_string(),
+        },
+        task.id,
+    )
+    .await?;
+    container.start_attempt(&attempt, label, false).await?;
+    Ok(())
+}
+
+/// File a failed run as a card, updating the card an earlier failure of the workflow
+/// opened while it's still open
+async fn file_failure<C: ContainerService + Sync>(
+    container: &C,
+    config: &Arc<RwLock<Config>>,
+    github: &GitHubService,
+    watched: &Watched<'_>,
+    run: &WorkflowRun,
+) -> Result<(), CiTriageError> {
+    let pool = &container.db().pool;
+    let Watched {
+        project,
+        repo_info,
+        branch,
+        start_agent,
+    } = watched;
+    let mut failed = Vec::new();
+    for job in github
+        .list_run_jobs(repo_info, run.id)
+        .await?
+        .into_iter()
+        .filter(WorkflowJob::failed)
+        .take(MAX_JOBS)
+    {
+        let log = github.get_job_logs(repo_info, job.id).await?;
+        failed.push(FailedJob { job, log });
+    }
+
+    let title = task_title(run, branch);
+    let description = task_prompt(run, branch, &failed);
+    let dedupe_key = format!("ci:{}:{branch}", run.path);
+    let open_task = match InboundTask::find(pool, project.id, &dedupe_key).await? {
+        Some(entry) => Task::find_by_id(pool, entry.task_id)
+            .await?
+            .filter(|task| !matches!(task.status, TaskStatus::Done | TaskStatus::Cancelled)),
+        None => None,
+    };
+    if let Some(task) = open_task {
+        Task::update(
+            pool,
+            task.id,
+            task.project_id,
+            title,
+            Some(description),
+            task.status,
+            task.parent_task_attempt,
+        )
+        .await?;
+        InboundTask::record_occurrence(pool, project.id, &dedupe_key).await?;
+        return Ok(());
+    }
+
+    let task = Task::create(
+        pool,
+        &CreateTask {
+            project_id: project.id,
+            title,
+            description: Some(description),
+            parent_task_attempt: None,
+            priority: None,
+            labels: Vec::new(),
+            base_branch: None,
+        },
+        Uuid::new_v4(),
+    )
+    .await?;
+    InboundTask::assign(
+        pool,
+        project.id,
+        &dedupe_key,
+        task.id,
+        Some("github_actions"),
+    )
+    .await?;
+    let store = AttachmentStore::default();
+    for FailedJob { job, log } in &failed {
+        let tail = &log.as_bytes()[log.len().saturating_sub(MAX_LOG_BYTES)..];
+        store
+            .save(pool, task.id, &log_file_name(job), Some("text/plain"), tail)
+            .await?;
+    }
+    tracing::info!(
+        "Filed failed run {} of {} as task {}",
+        run.id,
+        run.path,
+        task.id
+    );
+
+    if *start_agent {
+        start_attempt(container, config, project, &task, branch).await?;
+    }
+    Ok(())
+}
+
+async fn check_project<C: ContainerService + Sync>(
+    container: &C,
+    config: &Arc<RwLock<Config>>,
+    github: &GitHubService,
+    watch: &ProjectCiTriage,
+) -> Result<(), CiTriageError> {
+    let pool = &container.db().pool;
+    let project = Project::find_by_id(pool, watch.project_id)
+        .await?
+        .ok_or(CiTriageError::ProjectNotFound(watch.project_id))?;
+    let (owner, repo_name) = GitService::new().get_github_repo_info(&project.git_repo_path)?;
+    let repo_info = GitHubRepoInfo { owner, repo_name };
+    let branch = match &watch.branch {
+        Some(branch) => branch.clone(),
+        None => github.get_default_branch(&repo_info).await?,
+    };
+
+    let runs = github.list_workflow_runs(&repo_info, &branch).await?;
+    let Some(newest) = runs.iter().map(|run| run.id).max() else {
+        return Ok(());
+    };
+    // Failures from before the project was watched aren't filed
+    let Some(last_run_id) = watch.last_run_id else {
+        ProjectCiTriage::set_last_run_id(pool, project.id, newest).await?;
+        return Ok(());
+    };
+
+    let mut failed: Vec<&WorkflowRun> = runs
+        .iter()
+        .filter(|run| run.id > last_run_id && run.failed() && watch.watches(&run.path))
+        .collect();
+    failed.sort_by_key(|run| run.id);
+    let watched = Watched {
+        project: &project,
+        repo_info,
+        branch,
+        start_agent: watch.start_agent,
+    };
+    for run in failed {
+        // A run that can't be filed isn't retried, so one bad run can't block the rest
+        if let Err(e) = file_failure(container, config, github, &watched, run).await {
+            tracing::error!(
+                "Failed to file failed run {} of {}: {}",
+                run.id,
+                run.path,
+                e
+            );
+        }
+    }
+    ProjectCiTriage::set_last_run_id(pool, project.id, newest).await?;
+    Ok(())
+}
+
+/// Poll the watched workflows of every project, filing failed runs as cards. Runs until
+/// the server stops.
+pub async fn run<C: ContainerService + Sync>(container: &C, config: Arc<RwLock<Config>>) {
+    let mut interval = tokio::time::interval(POLL_INTERVAL);
+    loop {
+        interval.tick().await;
+        let watches = match ProjectCiTriage::find_enabled(&container.db().pool).await {
+            Ok(watches) => watches,
+            Err(e) => {
+                tracing::error!("Failed to load CI triage settings: {}", e);
+                continue;
+            }
+        };
+        if watches.is_empty() {
+            continue;
+        }
+        let github_config = config.read().await.github.clone();
+        let token = match github_auth::access_token(&github_config).await {
+            Ok(Some(token)) => token,
+            Ok(None) => {
+                tracing::debug!("Skipping CI triage without a GitHub token");
+                continue;
+            }
+            Err(e) => {
+                tracing::error!("Failed to get a GitHub token for CI triage: {}", e);
+                continue;
+            }
+        };
+        let github = match GitHubService::new(&token) {
+            Ok(github) => github,
+            Err(e) => {
+                tracing::error!("Failed to create GitHub client for CI triage: {}", e);
+                continue;
+            }
+        };
+        for watch in &watches {
+            if let Err(e) = check_project(container, &config, &github, watch).await {
+                tracing::error!("Failed to check CI of project {}: {}", watch.project_id, e);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run() -> WorkflowRun {
+        WorkflowRun {
+            id: 7,
+            name: Some("CI".to_string()),
+            path: ".github/workflows/ci.yml".to_string(),
+            head_sha: "0123456789abcdef".to_string(),
+            conclusion: Some("failure".to_string()),
+            html_url: "https://github.com/acme/shop/actions/runs/7".to_string(),
+        }
+    }
+
+    #[test]
+    fn excerpt_surrounds_the_first_error() {
+        let mut log: Vec<String> = (0..100)
+            .map(|i| format!("2025-09-16T10:00:00.0000000Z line {i}"))
+            .collect();
+        log[60] =
+            "2025-09-16T10:00:00.0000000Z ##[error]Process completed with exit code 1.".to_string();
+        let excerpt = failure_excerpt(&log.join("\n"));
+        let lines: Vec<&str> = excerpt.lines().collect();
+        assert_eq!(lines.first(), Some(&"line 20"));
+        assert_eq!(lines[40], "##[error]Process completed with exit code 1.");
+        assert_eq!(lines.last(), Some(&"line 70"));
+    }
+
+    #[test]
+    fn excerpt_falls_back_to_the_tail() {
+        let log: Vec<String> = (0..100).map(|i| format!("line {i}")).collect();
+        let excerpt = failure_excerpt(&log.join("\n"));
+        assert_eq!(excerpt.lines().next(), Some("line 50"));
+        assert_eq!(excerpt.lines().count(), TAIL_LINES);
+    }
+
+    #[test]
+    fn prompt_names_the_failed_step() {
+        let job = WorkflowJob {
+            id: 11,
+            name: "test (ubuntu)".to_string(),
+            conclusion: Some("failure".to_string()),
+            html_url: None,
+            steps: vec![
+                serde_json::from_value(
+                    serde_json::json!({"name": "Checkout", "conclusion": "success"}),
+                )
+                .unwrap(),
+                serde_json::from_value(
+                    serde_json::json!({"name": "Run tests", "conclusion": "failure"}),
+                )
+                .unwrap(),
+            ],
+        };
+        assert_eq!(log_file_name(&job), "ci-test--ubuntu-11.log");
+        let prompt = task_prompt(
+            &run(),
+            "main",
+            &[FailedJob {
+                job,
+                log: "##[error]boom".to_string(),
+            }],
+        );
+        assert!(prompt.starts_with("The CI workflow failed on main at commit 0123456: "));
+        assert!(prompt.contains("\nJob test (ubuntu), step Run tests:\n```\n##[error]boom\n```\n"));
+        assert_eq!(task_title(&run(), "main"), "Fix CI: CI failing on main");
+    }
+}
@@ -0,0 +1,121 @@
+use db::models::attempt_commit_plan::PlannedCommit;
+use serde::Deserialize;
+
+use super::review::cut_diff;
+
+/// Message of the commit taking the files no planned commit names
+pub const REMAINING_CHANGES_MESSAGE: &str = "chore: remaining changes";
+
+const COMMIT_PLAN_PROMPT: &str = "Split another agent's changes for the task below into \
+logical commits, in the order they should be applied. Don't change any files and don't run \
+git, the commits are made for you. Each changed file goes in exactly one commit, give each \
+commit a conventional commit message (`feat: ...`, `fix: ...`, `refactor: ...`, `test: ...`, \
+`docs: ...`, `chore: ...`) describing what it changes.";
+
+const COMMIT_PLAN_FORMAT: &str = r#"Finish with your plan as a single JSON object in a ```json block:
+{"commits": [{"message": "<conventional commit message>", "files": ["<path>", ...]}]}"#;
+
+#[derive(Deserialize)]
+struct PlannerOutput {
+    commits: Vec<PlannedCommit>,
+}
+
+/// Prompt of an agent run planning the commits of `diff`, which changes `files`
+pub fn commit_plan_prompt(task_prompt: &str, files: &[String], diff: &str) -> String {
+    let files: String = files.iter().map(|file| format!("- {file}\n")).collect();
+    let diff = cut_diff(diff);
+    format!(
+        "{COMMIT_PLAN_PROMPT}\n\n{task_prompt}\n\nChanged files:\n{files}\n\
+         ```diff\n{diff}\n```\n\n{COMMIT_PLAN_FORMAT}"
+    )
+}
+
+/// Read the plan from the agent's final answer: the last JSON object holding commits. Commits
+/// without a message or files are dropped, as are files `files` doesn't list or an earlier
+/// commit already took.
+pub fn parse_commit_plan(output: &str, files: &[String]) -> Option<Vec<PlannedCommit>> {
+    let plan = output.match_indices('{').rev().find_map(|(start, _)| {
+        serde_json::Deserializer::from_str(&output[start..])
+            .into_iter::<PlannerOutput>()
+            .next()?
+            .ok()
+    })?;
+
+    let mut taken: Vec<&String> = Vec::new();
+    let mut commits = Vec::new();
+    for commit in plan.commits {
+        let message = commit.message.trim();
+        let commit_files: Vec<String> = commit
+            .files
+            .into_iter()
+            .filter_map(|file| files.iter().find(|changed| **changed == file))
+            .filter(|file| !taken.contains(file))
+            .cloned()
+            .collect();
+        if message.is_empty() || commit_files.is_empty() {
+            continue;
+        }
+        taken.extend(files.iter().filter(|file| commit_files.contains(file)));
+        commits.push(PlannedCommit {
+            message: message.to_string(),
+            files: commit_files,
+        });
+    }
+    (!commits.is_empty()).then_some(commits)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn files() -> Vec<String> {
+        ["src/lib.rs", "src/api.rs", "README.md"]
+            .map(String::from)
+            .to_vec()
+    }
+
+    #[test]
+    fn test_parse_commit_plan_keeps_changed_files_once() {
+        let output = r#"Grouped the changes, {"not": "a plan"}.
+
+```json
+{"commits": [
+  {"message": "feat: add the endpoint", "files": ["src/api.rs", "src/lib.rs", "src/other.rs"]},
+  {"message": "docs: describe the endpoint", "files": ["README.md", "src/lib.rs"]},
+  {"message": "chore: nothing left", "files": ["src/api.rs"]},
+  {"message": " ", "files": ["README.md"]}
+]}
+```"#;
+        let commits = parse_commit_plan(output, &files()).unwrap();
+        assert_eq!(
+            commits,
+            vec![
+                PlannedCommit {
+                    message: "feat: add the endpoint".to_string(),
+                    files: vec!["src/api.rs".to_string(), "src/lib.rs".to_string()],
+                },
+                PlannedCommit {
+                    message: "docs: describe the endpoint".to_string(),
+                    files: vec!["README.md".to_string()],
+                },
+            ]
+        );
+
+        assert_eq!(parse_commit_plan("Looks like one commit", &files()), None);
+        assert_eq!(
+            parse_commit_plan(
+                r#"{"commits": [{"message": "x", "files": ["a.rs"]}]}"#,
+                &files()
+            ),
+            None
+        );
+    }
+
+    #[test]
+    fn test_commit_plan_prompt_lists_files() {
+        let prompt = commit_plan_prompt("Add a flag", &files(), "+x\n");
+        assert!(prompt.contains("Changed files:\n- src/lib.rs\n- src/api.rs\n- README.md\n"));
+        assert!(prompt.contains("```diff\n+x\n\n```"));
+        assert!(prompt.ends_with(COMMIT_PLAN_FORMAT));
+    }
+}
@@ -0,0 +1,195 @@
+//! Previous versions of the config, from the revisions the database keeps of it
+
+use std::{io::ErrorKind, path::Path};
+
+use chrono::{DateTime, NaiveDateTime, Utc};
+use db::models::setting::SettingRevision;
+use serde::Serialize;
+use serde_json::Value;
+use sqlx::SqlitePool;
+use ts_rs::TS;
+
+use super::{
+    Config, ConfigError, redacted,
+    store::{self, SettingsFile},
+};
+
+/// The config as it was before one of the saves since
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct ConfigVersion {
+    /// 1 for the config before the last save, 2 for the one before that, and so on
+    pub n: usize,
+    /// When the save replacing it happened
+    pub replaced_at: DateTime<Utc>,
+    /// Dotted paths of the fields that differ from the current config, e.g. `editor.custom_command`
+    pub changed_fields: Vec<String>,
+    /// Credentials redacted
+    pub config: Config,
+}
+
+/// Time format in the names of the files the history was kept in before the database
+const LEGACY_TIMESTAMP_FORMAT: &str = "%Y%m%dT%H%M%S%3fZ";
+
+/// The versions kept as `config-<timestamp>.json` files in `history_dir` before the history
+/// moved into the database, the `max` newest, oldest first with the time each was replaced
+pub(super) fn read_legacy_versions(
+    history_dir: &Path,
+    max: usize,
+) -> Result<Vec<(String, DateTime<Utc>)>, ConfigError> {
+    let entries = match std::fs::read_dir(history_dir) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e.into()),
+    };
+    let mut versions = Vec::new();
+    for entry in entries {
+        let path = entry?.path();
+        let replaced_at = path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .and_then(|name| name.strip_prefix("config-")?.strip_suffix(".json"))
+            .and_then(|timestamp| {
+                NaiveDateTime::parse_from_str(timestamp, LEGACY_TIMESTAMP_FORMAT).ok()
+            });
+        if let Some(replaced_at) = replaced_at {
+            versions.push((std::fs::read_to_string(&path)?, replaced_at.and_utc()));
+        }
+    }
+    versions.sort_by_key(|(_, replaced_at)| *replaced_at);
+    Ok(versions.split_off(versions.len().saturating_sub(max)))
+}
+
+fn parse(raw_config: String) -> Result<Config, ConfigError> {
+    // `Config::from` falls back to the defaults, a broken file shouldn't be restored as those
+    serde_json::from_str::<Value>(&raw_config)?;
+    Ok(Config::from(raw_config))
+}
+
+/// Dotted paths of the leaves that differ between two serialized configs
+fn changed_fields(old: &Value, new: &Value, prefix: &str, changed: &mut Vec<String>) {
+    match (old, new) {
+        (Value::Object(old), Value::Object(new)) => {
+            let mut keys: Vec<&String> = old.keys().chain(new.keys()).collect();
+            keys.sort();
+            keys.dedup();
+            for key in keys {
+                let path = match prefix {
+                    "" => key.clone(),
+                    prefix => format!("{prefix}.{key}"),
+                };
+                match (old.get(key), new.get(key)) {
+                    (Some(old), Some(new)) => changed_fields(old, new, &path, changed),
+                    _ => changed.push(path),
+                }
+            }
+        }
+        (old, new) if old != new => changed.push(prefix.to_string()),
+        _ => {}
+    }
+}
+
+/// The kept versions, newest first, each compared with the stored config. The user's override
+/// isn't part of either.
+pub async fn list(pool: &SqlitePool) -> Result<Vec<ConfigVersion>, ConfigError> {
+    let current = match store::load(pool, SettingsFile::Config).await? {
+        Some(raw_config) => redacted(&parse(raw_config)?),
+        None => return Ok(Vec::new()),
+    };
+    let current = serde_json::to_value(current)?;
+    let mut versions = Vec::new();
+    let revisions = SettingRevision::find_by_name(pool, SettingsFile::Config.name()).await?;
+    for (index, revision) in revisions.into_iter().enumerate() {
+        let config = match parse(revision.content) {
+            Ok(config) => redacted(&config),
+            Err(e) => {
+                tracing::warn!(
+                    "Skipping unreadable config revision {}: {}",
+                    revision.revision,
+                    e
+                );
+                continue;
+            }
+        };
+        let mut changed = Vec::new();
+        changed_fields(&serde_json::to_value(&config)?, &current, "", &mut changed);
+        versions.push(ConfigVersion {
+            n: index + 1,
+            replaced_at: revision.replaced_at,
+            changed_fields: changed,
+            config,
+        });
+    }
+    Ok(versions)
+}
+
+/// The config of version `n`, its credentials still references into the secrets store
+pub async fn load(pool: &SqlitePool, n: usize) -> Result<Config, ConfigError> {
+    let revisions = SettingRevision::find_by_name(pool, SettingsFile::Config.name()).await?;
+    let revision = n
+        .checked_sub(1)
+        .and_then(|index| revisions.into_iter().nth(index))
+        .ok_or(ConfigError::VersionNotFound(n))?;
+    parse(revision.content)
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn test_changed_fields_are_dotted_leaf_paths() {
+        let old = json!({
+            "workspace_dir": "one",
+            "editor": {"editor_type": "ZED", "custom_command": null},
+            "language": "EN",
+        });
+        let new = json!({
+            "workspace_dir": "two",
+            "editor": {"editor_type": "ZED", "custom_command": "vim"},
+            "language": "EN",
+            "telemetry": {"exporter": "NONE"},
+        });
+        let mut changed = Vec::new();
+        changed_fields(&old, &new, "", &mut changed);
+        assert_eq!(
+            changed,
+            ["editor.custom_command", "telemetry", "workspace_dir"]
+        );
+    }
+
+    #[test]
+    fn test_broken_versions_are_not_restored_as_defaults() {
+        assert!(parse("{\"workspace_dir\": ".to_string()).is_err());
+        let config = Config {
+            workspace_dir: Some("kept".to_string()),
+            ..Config::default()
+        };
+        let parsed = parse(serde_json::to_string(&config).unwrap()).unwrap();
+        assert_eq!(parsed.workspace_dir.as_deref(), Some("kept"));
+    }
+
+    #[test]
+    fn test_legacy_versions_are_read_oldest_first() {
+        let dir = tempfile::tempdir().unwrap();
+        assert!(
+            read_legacy_versions(&dir.path().join("missing"), 2)
+                .unwrap()
+                .is_empty()
+        );
+        for (name, content) in [
+            ("config-20250102T090000000Z.json", "two"),
+            ("config-20250101T090000000Z.json", "one"),
+            ("config-20250103T090000000Z.json", "three"),
+            ("notes.txt", "not a version"),
+        ] {
+            std::fs::write(dir.path().join(name), content).unwrap();
+        }
+
+        let versions = read_legacy_versions(dir.path(), 2).unwrap();
+        let contents: Vec<_> = versions.iter().map(|(c, _)| c.as_str()).collect();
+        assert_eq!(contents, ["two", "three"]);
+        assert_eq!(versions[0].1.to_rfc3339(), "2025-01-02T09:00:00+00:00");
+    }
+}
@@ -1,7 +1,21 @@
-use std::path::PathBuf;
-
+use db::models::setting::SettingOverride;
+use executors::profile::ProfileConfigs;
+use serde::Serialize;
+use serde_json::Value;
+use sqlx::SqlitePool;
 use thiserror::Error;
+use ts_rs::TS;
+use utils::output_encoding::encoding_for_label;
+
+use self::store::SettingsFile;
+use crate::services::{
+    github_auth,
+    secrets::{SecretsError, SecretsStore},
+    sounds::{Sound, SoundLibrary},
+};
 
+pub mod history;
+pub mod store;
 mod versions;
 
 #[derive(Debug, Error)]
@@ -12,33 +26,611 @@ pub enum ConfigError {
     Json(#[from] serde_json::Error),
     #[error("Validation error: {0}")]
     ValidationError(String),
+    #[error(transparent)]
+    Secrets(#[from] SecretsError),
+    #[error(transparent)]
+    Join(#[from] tokio::task::JoinError),
+    #[error(transparent)]
+    Database(#[from] sqlx::Error),
+    #[error("Profile not found: {0}")]
+    ProfileNotFound(String),
+    #[error("Invalid profiles format: {0}")]
+    InvalidProfiles(serde_json::Error),
+    #[error("This executor does not support MCP servers")]
+    McpUnsupported,
+    #[error("Could not determine config file path")]
+    McpConfigPathUnknown,
+    #[error("Failed to update MCP servers: {0}")]
+    McpUpdateFailed(String),
+    #[error("Config has {} invalid field(s)", .0.len())]
+    InvalidFields(Vec<ConfigFieldError>),
+    #[error("No config version {0} in the history")]
+    VersionNotFound(usize),
+    #[error("Invalid config override: {0}")]
+    InvalidOverride(String),
 }
 
-pub type Config = versions::v4::Config;
-pub type NotificationConfig = versions::v4::NotificationConfig;
-pub type EditorConfig = versions::v4::EditorConfig;
-pub type ThemeMode = versions::v4::ThemeMode;
-pub type SoundFile = versions::v4::SoundFile;
-pub type EditorType = versions::v4::EditorType;
-pub type GitHubConfig = versions::v4::GitHubConfig;
-
-/// Will always return config, trying old schemas or eventually returning default
-pub async fn load_config_from_file(config_path: &PathBuf) -> Config {
-    match std::fs::read_to_string(config_path) {
-        Ok(raw_config) => Config::from(raw_config),
-        Err(_) => {
-            tracing::info!("No config file found, creating one");
-            Config::default()
+pub type Config = versions::v5::Config;
+pub type NotificationConfig = versions::v5::NotificationConfig;
+pub type EventSounds = versions::v5::EventSounds;
+pub type EditorConfig = versions::v5::EditorConfig;
+pub type ThemeMode = versions::v5::ThemeMode;
+pub type SoundFile = versions::v5::SoundFile;
+pub type EditorType = versions::v5::EditorType;
+pub type GitHubConfig = versions::v5::GitHubConfig;
+pub type ShutdownConfig = versions::v5::ShutdownConfig;
+pub type ShutdownMode = versions::v5::ShutdownMode;
+pub type BackupConfig = versions::v5::BackupConfig;
+pub type BudgetConfig = versions::v5::BudgetConfig;
+pub type TerminalConfig = versions::v5::TerminalConfig;
+pub type TerminalMode = versions::v5::TerminalMode;
+pub type TelemetryConfig = versions::v5::TelemetryConfig;
+pub type TelemetryExporter = versions::v5::TelemetryExporter;
+pub type FeatureFlags = versions::v5::FeatureFlags;
+
+/// A config value that parses but cannot be used
+#[derive(Debug, Clone, PartialEq, Serialize, TS)]
+pub struct ConfigFieldError {
+    /// Dotted path of the field, e.g. `editor.custom_command`
+    pub field: String,
+    pub message: String,
+}
+
+impl ConfigFieldError {
+    fn new(field: &str, message: impl Into<String>) -> Self {
+        Self {
+            field: field.to_string(),
+            message: message.into(),
         }
     }
 }
 
-/// Saves the config to the given path
-pub async fn save_config_to_file(
+/// The stored config, trying old schemas or eventually returning default. Credentials are
+/// still references into the secrets store.
+async fn load_stored_config(pool: &SqlitePool) -> Result<Config, ConfigError> {
+    Ok(match store::load(pool, SettingsFile::Config).await? {
+        Some(raw_config) => Config::from(raw_config),
+        None => {
+            tracing::info!("No stored config, creating one");
+            Config::default()
+        }
+    })
+}
+
+async fn with_secrets(mut config: Config) -> Result<Config, ConfigError> {
+    Ok(tokio::task::spawn_blocking(move || {
+        resolve_secrets(&mut config, SecretsStore::default_store());
+        config
+    })
+    .await?)
+}
+
+/// The stored config with its credentials, without any user's override
+pub async fn load_stored(pool: &SqlitePool) -> Result<Config, ConfigError> {
+    with_secrets(load_stored_config(pool).await?).await
+}
+
+/// The config the server runs with: the stored one with the user's override applied
+pub async fn load_config(pool: &SqlitePool, user_id: &str) -> Result<Config, ConfigError> {
+    let config = load_stored_config(pool).await?;
+    with_secrets(with_user_override(pool, user_id, config).await).await
+}
+
+/// Imports a config.json that changed on disk while the server runs. Unlike `load_config`
+/// this fails on an unparseable file instead of falling back to defaults, so a half-written
+/// edit doesn't reset the running config.
+pub async fn reload_config_from_file(
+    pool: &SqlitePool,
+    user_id: &str,
+) -> Result<Config, ConfigError> {
+    let config = store::import_file::<Config>(pool, SettingsFile::Config).await?;
+    with_secrets(with_user_override(pool, user_id, config).await).await
+}
+
+/// Saves the running config, moving credentials into the secrets store and leaving out
+/// what the user's override changed. Plaintext credentials from older versions are migrated
+/// the first time this runs.
+pub async fn save_config(
+    pool: &SqlitePool,
+    user_id: &str,
     config: &Config,
-    config_path: &PathBuf,
 ) -> Result<(), ConfigError> {
-    let raw_config = serde_json::to_string_pretty(config)?;
-    std::fs::write(config_path, raw_config)?;
+    let mut stored = config.clone();
+    let mut stored = tokio::task::spawn_blocking(move || {
+        store_secrets(&mut stored, SecretsStore::default_store()).map(|_| stored)
+    })
+    .await??;
+    if let Some(patch) = user_override(pool, user_id).await? {
+        let previous = serde_json::to_value(load_stored_config(pool).await?)?;
+        let mut value = serde_json::to_value(&stored)?;
+        without_override(&mut value, &previous, &patch);
+        stored = serde_json::from_value(value)?;
+    }
+    let raw_config = serde_json::to_string_pretty(&stored)?;
+    store::save(pool, SettingsFile::Config, &raw_config).await
+}
+
+/// The user's changes on top of the stored config, as a JSON merge patch
+pub async fn user_override(pool: &SqlitePool, user_id: &str) -> Result<Option<Value>, ConfigError> {
+    match SettingOverride::find(pool, user_id, SettingsFile::Config.name()).await? {
+        Some(row) => Ok(Some(serde_json::from_str(&row.patch)?)),
+        None => Ok(None),
+    }
+}
+
+/// Replace the user's override, or remove it with `None`. Credentials can't be overridden,
+/// they would be stored outside the secrets store.
+pub async fn set_user_override(
+    pool: &SqlitePool,
+    user_id: &str,
+    patch: Option<&Value>,
+) -> Result<(), ConfigError> {
+    if let Some(patch) = patch {
+        check_override(patch)?;
+    }
+    let name = SettingsFile::Config.name();
+    match patch {
+        Some(patch) => {
+            SettingOverride::upsert(pool, user_id, name, &serde_json::to_string(patch)?).await?
+        }
+        None => {
+            SettingOverride::delete(pool, user_id, name).await?;
+        }
+    }
+    Ok(())
+}
+
+fn check_override(patch: &Value) -> Result<(), ConfigError> {
+    if !patch.is_object() {
+        return Err(ConfigError::InvalidOverride(
+            "it must be a JSON object".to_string(),
+        ));
+    }
+    for (key, _) in secret_fields(&mut Config::default()) {
+        if patch
+            .pointer(&format!("/{}", key.replace('.', "/")))
+            .is_some()
+        {
+            return Err(ConfigError::InvalidOverride(format!(
+                "{key} can't be overridden"
+            )));
+        }
+    }
     Ok(())
 }
+
+/// `config` with `patch` merged in, failing when the result isn't a valid config
+pub fn apply_override(config: &Config, patch: &Value) -> Result<Config, ConfigError> {
+    check_override(patch)?;
+    let mut value = serde_json::to_value(config)?;
+    json_patch::merge(&mut value, patch);
+    serde_json::from_value(value).map_err(|e| ConfigError::InvalidOverride(e.to_string()))
+}
+
+/// `config` with the user's override applied, or as it is when the override no longer fits
+/// the config, e.g. after a schema change
+pub async fn with_user_override(pool: &SqlitePool, user_id: &str, config: Config) -> Config {
+    let patch = match user_override(pool, user_id).await {
+        Ok(Some(patch)) => patch,
+        Ok(None) => return config,
+        Err(e) => {
+            tracing::warn!("Failed to read the config override: {}", e);
+            return config;
+        }
+    };
+    apply_override(&config, &patch).unwrap_or_else(|e| {
+        tracing::warn!("Ignoring the config override, it no longer applies: {}", e);
+        config
+    })
+}
+
+/// Undo `patch` in `config` where it still has the values the patch set, taking them from
+/// `stored` instead, so saving the running config doesn't copy the override into the stored
+/// one. Overridden values the user changed since are saved.
+fn without_override(config: &mut Value, stored: &Value, patch: &Value) {
+    let (Value::Object(config), Value::Object(patch)) = (config, patch) else {
+        return;
+    };
+    for (key, patched) in patch {
+        let stored = stored.get(key);
+        let restore = match config.get_mut(key) {
+            Some(value) if patched.is_object() && value.is_object() => {
+                without_override(value, stored.unwrap_or(&Value::Null), patched);
+                false
+            }
+            Some(value) => value == patched,
+            // A `null` in a merge patch removes the field
+            None => patched.is_null(),
+        };
+        if restore {
+            match stored {
+                Some(stored) => config.insert(key.clone(), stored.clone()),
+                None => config.remove(key),
+            };
+        }
+    }
+}
+
+/// Config fields holding credentials, with the secrets store key each is kept under
+fn secret_fields(config: &mut Config) -> [(&'static str, &mut Option<String>); 3] {
+    [
+        ("github.pat", &mut config.github.pat),
+        ("github.oauth_token", &mut config.github.oauth_token),
+        ("github.app_private_key", &mut config.github.app_private_key),
+    ]
+}
+
+/// A copy of `config` safe to share, with every credential that's set replaced by a marker
+pub fn redacted(config: &Config) -> Config {
+    let mut config = config.clone();
+    for (_, field) in secret_fields(&mut config) {
+        if field.is_some() {
+            *field = Some("[redacted]".to_string());
+        }
+    }
+    config
+}
+
+/// Carry the credentials of `from` over into `into`, for a config restored from the history
+/// that shouldn't bring back old ones or drop the current ones
+pub fn keep_credentials(from: &Config, into: &mut Config) {
+    let mut from = from.clone();
+    for ((_, credential), (_, kept)) in secret_fields(&mut from)
+        .into_iter()
+        .zip(secret_fields(into))
+    {
+        *kept = credential.take();
+    }
+}
+
+/// Replace secret references with the secrets they point to. A secret that can't be read,
+/// e.g. from a locked keychain, keeps its reference so the next save doesn't delete it.
+fn resolve_secrets(config: &mut Config, store: &SecretsStore) {
+    for (key, field) in secret_fields(config) {
+        let Some(reference) = field.as_deref().and_then(SecretsStore::parse_reference) else {
+            continue;
+        };
+        match store.get(reference) {
+            Ok(secret) => *field = secret,
+            Err(e) => tracing::error!(
+                "Failed to read secret {}, keeping its reference: {}",
+                key,
+                e
+            ),
+        }
+    }
+}
+
+/// Move credentials into the secrets store, leaving references in their place
+fn store_secrets(config: &mut Config, store: &SecretsStore) -> Result<(), SecretsError> {
+    for (key, field) in secret_fields(config) {
+        match field.as_deref() {
+            Some(value) if SecretsStore::parse_reference(value).is_some() => {}
+            Some(secret) => {
+                store.set(key, secret)?;
+                *field = Some(SecretsStore::reference(key));
+            }
+            None => store.delete(key)?,
+        }
+    }
+    Ok(())
+}
+
+/// Check the constraints serde cannot express, returning an error for every bad field
+pub fn validate_config(config: &Config, profiles: &ProfileConfigs) -> Vec<ConfigFieldError> {
+    let mut errors = Vec::new();
+
+    if let Err(message) = config.editor.validate() {
+        errors.push(ConfigFieldError::new("editor.custom_command", message));
+    }
+
+    if let Err(message) = config.editor.validate_remote() {
+        errors.push(ConfigFieldError::new("editor.remote_ssh_host", message));
+    }
+
+    if let Some(dir) = config.workspace_dir.as_deref()
+        && !dir.trim().is_empty()
+        && !utils::path::expand_tilde(dir.trim()).is_dir()
+    {
+        errors.push(ConfigFieldError::new(
+            "workspace_dir",
+            format!("Directory does not exist: {dir}"),
+        ));
+    }
+
+    let github = &config.github;
+    let app_fields = [
+        github.app_id.is_some(),
+        github.app_installation_id.is_some(),
+        github.app_private_key.is_some(),
+    ];
+    if app_fields.contains(&true) && app_fields.contains(&false) {
+        errors.push(ConfigFieldError::new(
+            "github.app_id",
+            "A GitHub App needs its ID, installation ID and private key",
+        ));
+    }
+    if let Some(key) = github.app_private_key.as_deref()
+        && SecretsStore::parse_reference(key).is_none()
+        && let Err(message) = github_auth::validate_private_key(key)
+    {
+        errors.push(ConfigFieldError::new("github.app_private_key", message));
+    }
+
+    if let Err(message) = encoding_for_label(config.output_encoding.as_deref()) {
+        errors.push(ConfigFieldError::new("output_encoding", message));
+    }
+
+    match profiles.get_profile(&config.profile.profile) {
+        None => errors.push(ConfigFieldError::new(
+            "profile.profile",
+            format!("Unknown profile: {}", config.profile.profile),
+        )),
+        Some(profile) => {
+            if let Some(variant) = config.profile.variant.as_deref()
+                && profile.get_variant(variant).is_none()
+            {
+                errors.push(ConfigFieldError::new(
+                    "profile.variant",
+                    format!("Unknown variant of {}: {variant}", config.profile.profile),
+                ));
+            }
+        }
+    }
+
+    let sounds = SoundLibrary::default();
+    let event_sounds = &config.notifications.event_sounds;
+    for (field, id) in [
+        ("notifications.event_sounds.success", &event_sounds.success),
+        ("notifications.event_sounds.failure", &event_sounds.failure),
+        (
+            "notifications.event_sounds.needs_attention",
+            &event_sounds.needs_attention,
+        ),
+    ] {
+        let Some(id) = id.as_deref() else {
+            continue;
+        };
+        match Sound::parse(id) {
+            Ok(sound) if sounds.exists(&sound) => {}
+            Ok(_) => errors.push(ConfigFieldError::new(
+                field,
+                format!("Sound not found: {id}"),
+            )),
+            Err(e) => errors.push(ConfigFieldError::new(field, e.to_string())),
+        }
+    }
+
+    if config.backup.retention_count == 0 {
+        errors.push(ConfigFieldError::new(
+            "backup.retention_count",
+            "At least one snapshot must be kept",
+        ));
+    }
+
+    if let Some(cap) = config.budget.monthly_cap_usd
+        && !(cap >= 0.0 && cap.is_finite())
+    {
+        errors.push(ConfigFieldError::new(
+            "budget.monthly_cap_usd",
+            "The cap must be a positive amount",
+        ));
+    }
+
+    if config.analytics_enabled == Some(true)
+        && config.telemetry.exporter == TelemetryExporter::Otlp
+    {
+        let endpoint = config
+            .telemetry
+            .otlp_endpoint
+            .as_deref()
+            .unwrap_or("")
+            .trim();
+        if !(endpoint.starts_with("http://") || endpoint.starts_with("https://")) {
+            errors.push(ConfigFieldError::new(
+                "telemetry.otlp_endpoint",
+                "An http(s) collector URL is needed to export over OTLP",
+            ));
+        }
+    }
+
+    errors
+}
+
+#[cfg(test)]
+mod tests {
+    use executors::profile::ProfileVariantLabel;
+
+    use super::*;
+
+    #[test]
+    fn test_secrets_round_trip_through_config() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = SecretsStore::encrypted_file(dir.path().to_path_buf());
+
+        let mut config = Config::default();
+        config.github.oauth_token = Some("gho_plaintext".to_string());
+        let mut on_disk = config.clone();
+        store_secrets(&mut on_disk, &store).unwrap();
+
+        assert_eq!(
+            on_disk.github.oauth_token.as_deref(),
+            Some("secret:github.oauth_token")
+        );
+        assert_eq!(on_disk.github.pat, None);
+        assert!(
+            !serde_json::to_string(&on_disk)
+                .unwrap()
+                .contains("gho_plaintext")
+        );
+
+        resolve_secrets(&mut on_disk, &store);
+        assert_eq!(on_disk.github.oauth_token.as_deref(), Some("gho_plaintext"));
+
+        // Logging out clears the stored token
+        on_disk.github.oauth_token = None;
+        store_secrets(&mut on_disk, &store).unwrap();
+        assert_eq!(store.get("github.oauth_token").unwrap(), None);
+    }
+
+    #[test]
+    fn test_unreadable_secret_keeps_its_reference() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = SecretsStore::encrypted_file(dir.path().to_path_buf());
+        let mut config = Config::default();
+        config.github.pat = Some("ghp_plaintext".to_string());
+        store_secrets(&mut config, &store).unwrap();
+
+        // A store that can't be decrypted anymore, like a keychain that stays locked
+        for entry in std::fs::read_dir(dir.path()).unwrap() {
+            std::fs::write(entry.unwrap().path(), "not base64").unwrap();
+        }
+        resolve_secrets(&mut config, &store);
+        assert_eq!(config.github.pat.as_deref(), Some("secret:github.pat"));
+
+        // Saving it again leaves the reference, and the secret behind it, alone
+        store_secrets(&mut config, &store).unwrap();
+        assert_eq!(config.github.pat.as_deref(), Some("secret:github.pat"));
+    }
+
+    #[test]
+    fn test_default_config_is_valid() {
+        let errors = validate_config(&Config::default(), &ProfileConfigs::from_defaults());
+        assert_eq!(errors, vec![]);
+    }
+
+    #[test]
+    fn test_validate_config_reports_each_field() {
+        let profiles = ProfileConfigs::from_defaults();
+        let mut config = Config {
+            editor: serde_json::from_value(serde_json::json!({
+                "editor_type": "CUSTOM",
+                "custom_command": "definitely-not-an-editor-vk --wait",
+            }))
+            .unwrap(),
+            workspace_dir: Some("/definitely/not/a/dir".to_string()),
+            profile: ProfileVariantLabel::default("no-such-agent".to_string()),
+            ..Config::default()
+        };
+        config.backup.retention_count = 0;
+        config.budget.monthly_cap_usd = Some(-5.0);
+        config.notifications.event_sounds.failure = Some("no-such-sound.wav".to_string());
+        config.analytics_enabled = Some(true);
+        config.telemetry.exporter = TelemetryExporter::Otlp;
+        config.github.app_id = Some(42);
+        config.github.app_private_key = Some("not a key".to_string());
+
+        let fields: Vec<_> = validate_config(&config, &profiles)
+            .into_iter()
+            .map(|e| e.field)
+            .collect();
+        assert_eq!(
+            fields,
+            [
+                "editor.custom_command",
+                "workspace_dir",
+                "github.app_id",
+                "github.app_private_key",
+                "profile.profile",
+                "notifications.event_sounds.failure",
+                "backup.retention_count",
+                "budget.monthly_cap_usd",
+                "telemetry.otlp_endpoint",
+            ]
+        );
+
+        config.profile = ProfileVariantLabel::with_variant(
+            "claude-code".to_string(),
+            "no-such-variant".to_string(),
+        );
+        let errors = validate_config(&config, &profiles);
+        assert!(errors.iter().any(|e| e.field == "profile.variant"));
+    }
+
+    #[test]
+    fn test_override_is_left_out_of_the_stored_config() {
+        let stored = Config {
+            workspace_dir: Some("/stored".to_string()),
+            ..Config::default()
+        };
+        let patch = serde_json::json!({
+            "workspace_dir": "/mine",
+            "budget": {"monthly_cap_usd": 50.0},
+        });
+        let mut running = apply_override(&stored, &patch).unwrap();
+        assert_eq!(running.workspace_dir.as_deref(), Some("/mine"));
+        assert_eq!(running.budget.monthly_cap_usd, Some(50.0));
+
+        // A field outside the override and an overridden one the user changed since are saved
+        running.analytics_enabled = Some(false);
+        running.budget.monthly_cap_usd = Some(80.0);
+        let mut value = serde_json::to_value(&running).unwrap();
+        without_override(&mut value, &serde_json::to_value(&stored).unwrap(), &patch);
+        let saved: Config = serde_json::from_value(value).unwrap();
+        assert_eq!(saved.workspace_dir.as_deref(), Some("/stored"));
+        assert_eq!(saved.analytics_enabled, Some(false));
+        assert_eq!(saved.budget.monthly_cap_usd, Some(80.0));
+    }
+
+    fn editor(value: serde_json::Value) -> EditorConfig {
+        serde_json::from_value(value).unwrap()
+    }
+
+    #[test]
+    fn test_editor_command_at_line() {
+        let path = "/work/src/main.rs";
+        assert_eq!(
+            editor(serde_json::json!({"editor_type": "CURSOR", "custom_command": null}))
+                .command_for(path, Some(12), Some(3)),
+            ["cursor", "--goto", "/work/src/main.rs:12:3"]
+        );
+        assert_eq!(
+            editor(serde_json::json!({"editor_type": "RUST_ROVER", "custom_command": null}))
+                .command_for(path, Some(12), None),
+            ["rustrover", "--line", "12", "/work/src/main.rs"]
+        );
+        assert_eq!(
+            editor(serde_json::json!({"editor_type": "ZED", "custom_command": null})).command_for(
+                path,
+                Some(12),
+                None
+            ),
+            ["zed", "/work/src/main.rs:12"]
+        );
+        assert_eq!(
+            editor(
+                serde_json::json!({"editor_type": "CUSTOM", "custom_command": "vim +{line} {path}"})
+            )
+            .command_for(path, Some(12), None),
+            ["vim", "+12", "/work/src/main.rs"]
+        );
+        assert_eq!(
+            editor(serde_json::json!({"editor_type": "VS_CODE", "custom_command": null}))
+                .command_for(path, None, None),
+            ["code", "/work/src/main.rs"]
+        );
+    }
+
+    #[test]
+    fn test_editor_remote_url() {
+        let remote = editor(serde_json::json!({
+            "editor_type": "VS_CODE",
+            "custom_command": null,
+            "remote_ssh_host": "devbox",
+            "remote_ssh_user": "me",
+        }));
+        assert_eq!(
+            remote.remote_url("/work/src/main.rs", Some(4), None),
+            Some(Ok(
+                "vscode://vscode-remote/ssh-remote+me@devbox/work/src/main.rs:4".to_string()
+            ))
+        );
+        assert!(remote.validate_remote().is_ok());
+
+        let local = editor(serde_json::json!({"editor_type": "ZED", "custom_command": null}));
+        assert_eq!(local.remote_url("/work", None, None), None);
+
+        let unsupported = remote.with_override(Some("INTELLI_J"));
+        assert!(unsupported.validate_remote().is_err());
+    }
+}
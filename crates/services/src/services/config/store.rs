@@ -0,0 +1,163 @@
+//! The config and profiles are stored in the database, which keeps their revisions and the
+//! users' overrides. `config.json` and `profiles.json` in the data directory mirror it and
+//! can still be edited by hand: edits are imported as a new revision.
+
+use std::{io::ErrorKind, path::PathBuf, sync::OnceLock};
+
+use chrono::{DateTime, Utc};
+use db::models::setting::Setting;
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+use sqlx::SqlitePool;
+use tokio::sync::Mutex;
+use utils::assets::{config_history_dir, config_path, profiles_path};
+
+use super::{ConfigError, history};
+
+/// Revisions kept per file, the oldest is deleted once another one comes in
+pub const MAX_SETTINGS_REVISIONS: i64 = 20;
+
+/// A settings file mirrored from the database
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SettingsFile {
+    Config,
+    Profiles,
+}
+
+impl SettingsFile {
+    pub const ALL: [Self; 2] = [Self::Config, Self::Profiles];
+
+    /// Key of its row in the database
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Config => "config",
+            Self::Profiles => "profiles",
+        }
+    }
+
+    pub fn path(self) -> PathBuf {
+        match self {
+            Self::Config => config_path(),
+            Self::Profiles => profiles_path(),
+        }
+    }
+}
+
+/// Held from a database write until its file is written, so an import can't read the file in
+/// between and bring back the content just replaced
+fn write_lock() -> &'static Mutex<()> {
+    static LOCK: OnceLock<Mutex<()>> = OnceLock::new();
+    LOCK.get_or_init(|| Mutex::new(()))
+}
+
+fn read_file(file: SettingsFile) -> Result<Option<String>, ConfigError> {
+    match std::fs::read_to_string(file.path()) {
+        Ok(content) => Ok(Some(content)),
+        Err(e) if e.kind() == ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Write the file unless it already holds `content`, so the watcher isn't woken for nothing
+fn write_file(file: SettingsFile, content: &str) -> Result<(), ConfigError> {
+    if read_file(file)?.as_deref() != Some(content) {
+        std::fs::write(file.path(), content)?;
+    }
+    Ok(())
+}
+
+/// The stored content, `None` before the first save
+pub async fn load(pool: &SqlitePool, file: SettingsFile) -> Result<Option<String>, ConfigError> {
+    Ok(Setting::find(pool, file.name())
+        .await?
+        .map(|setting| setting.content))
+}
+
+/// Store `content` as a new revision, then mirror it to the file
+pub async fn save(pool: &SqlitePool, file: SettingsFile, content: &str) -> Result<(), ConfigError> {
+    let _lock = write_lock().lock().await;
+    Setting::save(pool, file.name(), content, MAX_SETTINGS_REVISIONS).await?;
+    write_file(file, content)
+}
+
+/// Store the file's content if it was edited outside the server, returning it parsed. A file
+/// that doesn't parse as `T` isn't stored, it may be half-written.
+pub async fn import_file<T: DeserializeOwned>(
+    pool: &SqlitePool,
+    file: SettingsFile,
+) -> Result<T, ConfigError> {
+    let _lock = write_lock().lock().await;
+    let content = std::fs::read_to_string(file.path())?;
+    let parsed = serde_json::from_str(&content)?;
+    // Saves through the server write the file last, leaving nothing to import
+    Setting::save(pool, file.name(), &content, MAX_SETTINGS_REVISIONS).await?;
+    Ok(parsed)
+}
+
+/// Reconcile the database and the files at startup: a file missing from either side is
+/// copied over, and when both differ the one changed last wins, so edits made while the
+/// server was stopped aren't lost. Returns the files rewritten from the database.
+pub async fn sync_files(pool: &SqlitePool) -> Result<Vec<SettingsFile>, ConfigError> {
+    let _lock = write_lock().lock().await;
+    let mut exported = Vec::new();
+    for file in SettingsFile::ALL {
+        let stored = Setting::find(pool, file.name()).await?;
+        let on_disk = read_file(file)?;
+        let import = match (&stored, &on_disk) {
+            (_, None) => false,
+            (None, Some(_)) => true,
+            (Some(stored), Some(content)) if stored.content == *content => continue,
+            (Some(stored), Some(_)) => std::fs::metadata(file.path())?
+                .modified()
+                .is_ok_and(|modified| DateTime::<Utc>::from(modified) > stored.updated_at),
+        };
+
+        match (import, on_disk, stored) {
+            (true, Some(content), stored) => {
+                if let Err(e) = serde_json::from_str::<Value>(&content) {
+                    tracing::warn!(
+                        "Not importing {:?}, it isn't valid JSON: {}",
+                        file.path(),
+                        e
+                    );
+                    continue;
+                }
+                tracing::info!("Importing {:?} into the database", file.path());
+                if stored.is_none() && file == SettingsFile::Config {
+                    import_config_with_history(pool, &content).await?;
+                } else {
+                    Setting::save(pool, file.name(), &content, MAX_SETTINGS_REVISIONS).await?;
+                }
+            }
+            (false, _, Some(stored)) => {
+                tracing::info!("Restoring {:?} from the database", file.path());
+                std::fs::write(file.path(), &stored.content)?;
+                exported.push(file);
+            }
+            _ => {}
+        }
+    }
+    Ok(exported)
+}
+
+/// First import of the config, along with the versions kept in `config_history/` before the
+/// history moved into the database. The files go once they're stored.
+async fn import_config_with_history(pool: &SqlitePool, content: &str) -> Result<(), ConfigError> {
+    let history_dir = config_history_dir();
+    let history = history::read_legacy_versions(&history_dir, MAX_SETTINGS_REVISIONS as usize)?;
+    Setting::create_with_history(pool, SettingsFile::Config.name(), content, &history).await?;
+    if !history.is_empty() {
+        tracing::info!(
+            "Imported {} config versions from {:?}",
+            history.len(),
+            history_dir
+        );
+    }
+    match std::fs::remove_dir_all(&history_dir) {
+        Err(e) if e.kind() != ErrorKind::NotFound => {
+            tracing::warn!("Failed to remove {:?}: {}", history_dir, e)
+        }
+        _ => {}
+    }
+    Ok(())
+}
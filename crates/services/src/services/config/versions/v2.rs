@@ -1,6 +1,7 @@
 use std::{path::PathBuf, str::FromStr};
 
 use anyhow::Error;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use strum_macros::EnumString;
 use ts_rs::TS;
@@ -103,13 +104,24 @@ impl Default for Config {
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[derive(Debug, Clone, Serialize, Deserialize, TS, JsonSchema)]
 pub struct GitHubConfig {
     pub pat: Option<String>,
     pub oauth_token: Option<String>,
     pub username: Option<String>,
     pub primary_email: Option<String>,
     pub default_pr_base: Option<String>,
+    /// GitHub App acting for vibe-kanban instead of a personal token, used once the
+    /// installation and private key are set as well
+    #[serde(default)]
+    #[ts(type = "number | null")]
+    pub app_id: Option<u64>,
+    #[serde(default)]
+    #[ts(type = "number | null")]
+    pub app_installation_id: Option<u64>,
+    /// PEM encoded private key of the app
+    #[serde(default)]
+    pub app_private_key: Option<String>,
 }
 
 impl From<v1::GitHubConfig> for GitHubConfig {
@@ -120,15 +132,29 @@ impl From<v1::GitHubConfig> for GitHubConfig {
             username: old.username,
             primary_email: old.primary_email,
             default_pr_base: old.default_pr_base,
+            app_id: None,
+            app_installation_id: None,
+            app_private_key: None,
         }
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[derive(Debug, Clone, Serialize, Deserialize, TS, JsonSchema)]
 pub struct NotificationConfig {
     pub sound_enabled: bool,
     pub push_enabled: bool,
     pub sound_file: SoundFile,
+    #[serde(default)]
+    pub event_sounds: EventSounds,
+}
+
+/// Sound played for each kind of notification, either a `SoundFile` name or the file name
+/// of a custom sound. Events without one play `sound_file`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, TS, JsonSchema)]
+pub struct EventSounds {
+    pub success: Option<String>,
+    pub failure: Option<String>,
+    pub needs_attention: Option<String>,
 }
 
 impl From<v1::Config> for NotificationConfig {
@@ -137,6 +163,7 @@ impl From<v1::Config> for NotificationConfig {
             sound_enabled: old.sound_alerts,
             push_enabled: old.push_notifications,
             sound_file: SoundFile::from(old.sound_file), // Now SCREAMING_SNAKE_CASE
+            event_sounds: EventSounds::default(),
         }
     }
 }
@@ -147,6 +174,7 @@ impl Default for NotificationConfig {
             sound_enabled: true,
             push_enabled: true,
             sound_file: SoundFile::CowMooing,
+            event_sounds: EventSounds::default(),
         }
     }
 }
@@ -159,6 +187,9 @@ impl Default for GitHubConfig {
             username: None,
             primary_email: None,
             default_pr_base: Some("main".to_string()),
+            app_id: None,
+            app_installation_id: None,
+            app_private_key: None,
         }
     }
 }
@@ -172,7 +203,7 @@ impl GitHubConfig {
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, TS, EnumString)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS, JsonSchema, EnumString)]
 #[ts(use_ts_enum)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 #[strum(serialize_all = "SCREAMING_SNAKE_CASE")]
@@ -265,10 +296,16 @@ impl From<v1::SoundFile> for SoundFile {
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[derive(Debug, Clone, Serialize, Deserialize, TS, JsonSchema)]
 pub struct EditorConfig {
     editor_type: EditorType,
     custom_command: Option<String>,
+    /// Host the server runs on, as seen from the browser's machine. When set, VS Code style
+    /// editors open worktrees over Remote SSH, like `code --remote ssh-remote+<host>`.
+    #[serde(default)]
+    remote_ssh_host: Option<String>,
+    #[serde(default)]
+    remote_ssh_user: Option<String>,
 }
 
 impl From<v1::EditorConfig> for EditorConfig {
@@ -276,11 +313,13 @@ impl From<v1::EditorConfig> for EditorConfig {
         Self {
             editor_type: EditorType::from(old.editor_type), // Now SCREAMING_SNAKE_CASE
             custom_command: old.custom_command,
+            remote_ssh_host: None,
+            remote_ssh_user: None,
         }
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, TS, EnumString)]
+#[derive(Debug, Clone, Serialize, Deserialize, TS, JsonSchema, EnumString)]
 #[ts(use_ts_enum)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 #[strum(serialize_all = "SCREAMING_SNAKE_CASE")]
@@ -289,6 +328,11 @@ pub enum EditorType {
     Cursor,
     Windsurf,
     IntelliJ,
+    Webstorm,
+    Pycharm,
+    Goland,
+    RustRover,
+    Clion,
     Zed,
     Custom,
 }
@@ -311,6 +355,8 @@ impl Default for EditorConfig {
         Self {
             editor_type: EditorType::VsCode,
             custom_command: None,
+            remote_ssh_host: None,
+            remote_ssh_user: None,
         }
     }
 }
@@ -322,6 +368,11 @@ impl EditorConfig {
             EditorType::Cursor => vec!["cursor".to_string()],
             EditorType::Windsurf => vec!["windsurf".to_string()],
             EditorType::IntelliJ => vec!["idea".to_string()],
+            EditorType::Webstorm => vec!["webstorm".to_string()],
+            EditorType::Pycharm => vec!["pycharm".to_string()],
+            EditorType::Goland => vec!["goland".to_string()],
+            EditorType::RustRover => vec!["rustrover".to_string()],
+            EditorType::Clion => vec!["clion".to_string()],
             EditorType::Zed => vec!["zed".to_string()],
             EditorType::Custom => {
                 if let Some(custom) = &self.custom_command {
@@ -333,8 +384,112 @@ impl EditorConfig {
         }
     }
 
-    pub fn open_file(&self, path: &str) -> Result<(), std::io::Error> {
+    /// URL scheme of editors built on VS Code, which all understand `--goto` and Remote SSH
+    fn vscode_scheme(&self) -> Option<&'static str> {
+        match self.editor_type {
+            EditorType::VsCode => Some("vscode"),
+            EditorType::Cursor => Some("cursor"),
+            EditorType::Windsurf => Some("windsurf"),
+            _ => None,
+        }
+    }
+
+    fn is_jetbrains(&self) -> bool {
+        matches!(
+            self.editor_type,
+            EditorType::IntelliJ
+                | EditorType::Webstorm
+                | EditorType::Pycharm
+                | EditorType::Goland
+                | EditorType::RustRover
+                | EditorType::Clion
+        )
+    }
+
+    /// Full command line opening `path`, at `line` and `column` (1-based) when given. A
+    /// custom command can place them with `{path}`, `{line}` and `{column}`, otherwise the
+    /// path is appended.
+    pub fn command_for(&self, path: &str, line: Option<u32>, column: Option<u32>) -> Vec<String> {
         let mut command = self.get_command();
+        let position = match (line, column) {
+            (Some(line), Some(column)) => format!("{path}:{line}:{column}"),
+            (Some(line), None) => format!("{path}:{line}"),
+            _ => path.to_string(),
+        };
+
+        if self.vscode_scheme().is_some() {
+            if line.is_some() {
+                command.push("--goto".to_string());
+            }
+            command.push(position);
+        } else if self.is_jetbrains() {
+            if let Some(line) = line {
+                command.extend(["--line".to_string(), line.to_string()]);
+                if let Some(column) = column {
+                    command.extend(["--column".to_string(), column.to_string()]);
+                }
+            }
+            command.push(path.to_string());
+        } else if matches!(self.editor_type, EditorType::Zed) {
+            command.push(position);
+        } else if command.iter().any(|arg| arg.contains("{path}")) {
+            let line = line.unwrap_or(1).to_string();
+            let column = column.unwrap_or(1).to_string();
+            for arg in &mut command {
+                *arg = arg
+                    .replace("{path}", path)
+                    .replace("{line}", &line)
+                    .replace("{column}", &column);
+            }
+        } else {
+            command.push(path.to_string());
+        }
+        command
+    }
+
+    /// Link that opens `path` in the editor over Remote SSH, for when the editor runs on the
+    /// browser's machine rather than the server's. `None` without a remote host.
+    pub fn remote_url(
+        &self,
+        path: &str,
+        line: Option<u32>,
+        column: Option<u32>,
+    ) -> Option<Result<String, String>> {
+        let host = self.remote_ssh_host.as_deref().map(str::trim)?;
+        if host.is_empty() {
+            return None;
+        }
+        let Some(scheme) = self.vscode_scheme() else {
+            return Some(Err(
+                "Remote SSH is only supported for VS Code, Cursor and Windsurf".to_string(),
+            ));
+        };
+        let authority = match self.remote_ssh_user.as_deref().map(str::trim) {
+            Some(user) if !user.is_empty() => format!("{user}@{host}"),
+            _ => host.to_string(),
+        };
+        let mut url = format!("{scheme}://vscode-remote/ssh-remote+{authority}{path}");
+        if let Some(line) = line {
+            url.push_str(&format!(":{line}"));
+            if let Some(column) = column {
+                url.push_str(&format!(":{column}"));
+            }
+        }
+        Some(Ok(url))
+    }
+
+    pub fn open_file(&self, path: &str) -> Result<(), std::io::Error> {
+        self.open_file_at(path, None, None)
+    }
+
+    /// Launch the editor on the server's machine at `path`, optionally at a line and column
+    pub fn open_file_at(
+        &self,
+        path: &str,
+        line: Option<u32>,
+        column: Option<u32>,
+    ) -> Result<(), std::io::Error> {
+        let mut command = self.command_for(path, line, column);
 
         if command.is_empty() {
             return Err(std::io::Error::new(
@@ -355,18 +510,44 @@ impl EditorConfig {
         for arg in &command[1..] {
             cmd.arg(arg);
         }
-        cmd.arg(path);
         cmd.spawn()?;
         Ok(())
     }
 
+    /// Check a custom editor command resolves to an executable. Built-in editors are not
+    /// checked, they are often installed after the editor is picked.
+    pub fn validate(&self) -> Result<(), String> {
+        if !matches!(self.editor_type, EditorType::Custom) {
+            return Ok(());
+        }
+        let Some(program) = self
+            .custom_command
+            .as_deref()
+            .and_then(|command| command.split_whitespace().next())
+        else {
+            return Err("A custom editor needs a command".to_string());
+        };
+        if utils::shell::resolve_executable_path(program).is_none() {
+            return Err(format!("Editor command '{program}' not found"));
+        }
+        Ok(())
+    }
+
+    /// Check a remote host is only set for an editor that can use it
+    pub fn validate_remote(&self) -> Result<(), String> {
+        match self.remote_url("/", None, None) {
+            Some(Err(message)) => Err(message),
+            _ => Ok(()),
+        }
+    }
+
     pub fn with_override(&self, editor_type_str: Option<&str>) -> Self {
         if let Some(editor_type_str) = editor_type_str {
             let editor_type =
                 EditorType::from_str(editor_type_str).unwrap_or(self.editor_type.clone());
             EditorConfig {
                 editor_type,
-                custom_command: self.custom_command.clone(),
+                ..self.clone()
             }
         } else {
             self.clone()
@@ -374,7 +555,7 @@ impl EditorConfig {
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, TS, EnumString)]
+#[derive(Debug, Clone, Serialize, Deserialize, TS, JsonSchema, EnumString)]
 #[ts(use_ts_enum)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 #[strum(serialize_all = "SCREAMING_SNAKE_CASE")]
@@ -1,7 +1,9 @@
 use anyhow::Error;
 use serde::{Deserialize, Serialize};
 use ts_rs::TS;
-pub use v2::{EditorConfig, EditorType, GitHubConfig, NotificationConfig, SoundFile, ThemeMode};
+pub use v2::{
+    EditorConfig, EditorType, EventSounds, GitHubConfig, NotificationConfig, SoundFile, ThemeMode,
+};
 
 use crate::services::config::versions::v2;
 
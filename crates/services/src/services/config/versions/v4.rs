@@ -2,7 +2,9 @@ use anyhow::Error;
 use executors::profile::ProfileVariantLabel;
 use serde::{Deserialize, Serialize};
 use ts_rs::TS;
-pub use v3::{EditorConfig, EditorType, GitHubConfig, NotificationConfig, SoundFile, ThemeMode};
+pub use v3::{
+    EditorConfig, EditorType, EventSounds, GitHubConfig, NotificationConfig, SoundFile, ThemeMode,
+};
 
 use crate::services::config::versions::v3;
 
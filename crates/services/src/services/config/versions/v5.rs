@@ -0,0 +1,290 @@
+use anyhow::Error;
+use executors::profile::ProfileVariantLabel;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use strum_macros::EnumString;
+use ts_rs::TS;
+use utils::i18n::Locale;
+pub use v4::{
+    EditorConfig, EditorType, EventSounds, GitHubConfig, NotificationConfig, SoundFile, ThemeMode,
+};
+
+use crate::services::config::versions::v4;
+
+#[derive(Clone, Debug, Serialize, Deserialize, TS, JsonSchema)]
+pub struct Config {
+    pub config_version: String,
+    pub theme: ThemeMode,
+    pub profile: ProfileVariantLabel,
+    pub disclaimer_acknowledged: bool,
+    pub onboarding_acknowledged: bool,
+    pub github_login_acknowledged: bool,
+    pub telemetry_acknowledged: bool,
+    pub notifications: NotificationConfig,
+    pub editor: EditorConfig,
+    pub github: GitHubConfig,
+    pub analytics_enabled: Option<bool>,
+    pub workspace_dir: Option<String>,
+    pub shutdown: ShutdownConfig,
+    #[serde(default)]
+    pub backup: BackupConfig,
+    #[serde(default)]
+    pub terminal: TerminalConfig,
+    /// Set while every agent is paused, so nothing starts until they are resumed. Only
+    /// changed through the pause endpoints, which also suspend running agents.
+    #[serde(default)]
+    pub agents_paused: bool,
+    #[serde(default)]
+    pub budget: BudgetConfig,
+    #[serde(default)]
+    pub telemetry: TelemetryConfig,
+    /// Language of error and status messages from the server
+    #[serde(default)]
+    pub language: Locale,
+    /// Encoding of agent and script output, as a label such as `utf-16le` or `windows-1252`.
+    /// Unset detects it from the output and falls back to the locale's charset, then UTF-8.
+    #[serde(default)]
+    pub output_encoding: Option<String>,
+    /// Merge attempts that changed the same files as another unmerged attempt one at a time
+    #[serde(default)]
+    pub serialize_conflicting_merges: bool,
+    /// Refuse local merges until GitHub checks of the pushed attempt branch passed
+    #[serde(default)]
+    pub require_passing_checks: bool,
+    /// Experimental subsystems turned on, `VK_FEATURE_<NAME>` variables override them
+    #[serde(default)]
+    pub feature_flags: FeatureFlags,
+}
+
+/// What happens to running agents when the server is asked to stop. `Suspend` stops them
+/// straight away and records their sessions so they can be resumed, `Wait` lets them finish
+/// and suspends whatever is still running after `wait_timeout_secs`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, TS, JsonSchema, EnumString)]
+#[ts(use_ts_enum)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+#[strum(serialize_all = "SCREAMING_SNAKE_CASE")]
+pub enum ShutdownMode {
+    #[default]
+    Suspend,
+    Wait,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, TS, JsonSchema)]
+pub struct ShutdownConfig {
+    pub mode: ShutdownMode,
+    pub wait_timeout_secs: u32,
+}
+
+impl Default for ShutdownConfig {
+    fn default() -> Self {
+        Self {
+            mode: ShutdownMode::default(),
+            wait_timeout_secs: 600,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, TS, JsonSchema)]
+pub struct BackupConfig {
+    /// Where snapshots are written, defaults to `backups` in the data directory
+    pub directory: Option<String>,
+    pub daily_snapshots: bool,
+    /// Number of snapshots kept, older ones are deleted after each new snapshot
+    pub retention_count: u32,
+}
+
+impl Default for BackupConfig {
+    fn default() -> Self {
+        Self {
+            directory: None,
+            daily_snapshots: false,
+            retention_count: 7,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize, TS, JsonSchema)]
+pub struct BudgetConfig {
+    /// Estimated spend across all projects after which new attempts are refused, per
+    /// calendar month (UTC)
+    pub monthly_cap_usd: Option<f64>,
+}
+
+/// Where usage events are sent while `analytics_enabled` is on. They are kept in a local
+/// buffer the app can show either way, and never leave the machine with `None`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, TS, JsonSchema, EnumString)]
+#[ts(use_ts_enum)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+#[strum(serialize_all = "SCREAMING_SNAKE_CASE")]
+pub enum TelemetryExporter {
+    #[default]
+    Posthog,
+    Otlp,
+    None,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize, TS, JsonSchema)]
+pub struct TelemetryConfig {
+    pub exporter: TelemetryExporter,
+    /// Base URL of an OTLP/HTTP collector, e.g. `http://localhost:4318`. Events are sent
+    /// to its `/v1/logs` as log records.
+    pub otlp_endpoint: Option<String>,
+}
+
+/// What the in-app terminal may run. `AllowedCommands` only starts commands whose leading
+/// words match an entry of `allowed_commands`, `FullShell` opens the user's shell.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, TS, JsonSchema, EnumString)]
+#[ts(use_ts_enum)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+#[strum(serialize_all = "SCREAMING_SNAKE_CASE")]
+pub enum TerminalMode {
+    Disabled,
+    #[default]
+    AllowedCommands,
+    FullShell,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, TS, JsonSchema)]
+pub struct TerminalConfig {
+    pub mode: TerminalMode,
+    /// Command prefixes, e.g. `npm test` allows `npm test -- --watch` but not `npm publish`
+    pub allowed_commands: Vec<String>,
+}
+
+impl Default for TerminalConfig {
+    fn default() -> Self {
+        Self {
+            mode: TerminalMode::default(),
+            allowed_commands: [
+                "npm test",
+                "npm run",
+                "pnpm test",
+                "pnpm run",
+                "yarn test",
+                "cargo check",
+                "cargo test",
+                "git status",
+                "git diff",
+                "git log",
+            ]
+            .map(String::from)
+            .to_vec(),
+        }
+    }
+}
+
+/// Experimental subsystems that can be turned off at runtime. The ones that shipped before
+/// they were gated default to on, so upgrading keeps configured reviewers and devcontainers.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, TS, JsonSchema)]
+#[serde(default)]
+pub struct FeatureFlags {
+    /// Agents handing work to other agents: reviewer agents and plan-then-execute attempts
+    pub multi_agent_pipelines: bool,
+    /// Running attempt processes in the project's devcontainer
+    pub container_sandbox: bool,
+    /// Running agents on another machine, nothing uses it yet
+    pub remote_exec: bool,
+    /// Attempts with worktrees in several projects' repositories, for changes landing together
+    pub multi_repo_attempts: bool,
+}
+
+impl Default for FeatureFlags {
+    fn default() -> Self {
+        Self {
+            multi_agent_pipelines: true,
+            container_sandbox: true,
+            remote_exec: false,
+            multi_repo_attempts: false,
+        }
+    }
+}
+
+impl Config {
+    pub fn from_previous_version(raw_config: &str) -> Result<Self, Error> {
+        let old_config = match serde_json::from_str::<v4::Config>(raw_config) {
+            Ok(cfg) => cfg,
+            Err(e) => {
+                tracing::error!("❌ Failed to parse config: {}", e);
+                tracing::error!("   at line {}, column {}", e.line(), e.column());
+                return Err(e.into());
+            }
+        };
+
+        Ok(Self {
+            config_version: "v5".to_string(),
+            theme: old_config.theme,
+            profile: old_config.profile,
+            disclaimer_acknowledged: old_config.disclaimer_acknowledged,
+            onboarding_acknowledged: old_config.onboarding_acknowledged,
+            github_login_acknowledged: old_config.github_login_acknowledged,
+            telemetry_acknowledged: old_config.telemetry_acknowledged,
+            notifications: old_config.notifications,
+            editor: old_config.editor,
+            github: old_config.github,
+            analytics_enabled: old_config.analytics_enabled,
+            workspace_dir: old_config.workspace_dir,
+            shutdown: ShutdownConfig::default(),
+            backup: BackupConfig::default(),
+            terminal: TerminalConfig::default(),
+            agents_paused: false,
+            budget: BudgetConfig::default(),
+            telemetry: TelemetryConfig::default(),
+            language: Locale::default(),
+            output_encoding: None,
+            serialize_conflicting_merges: false,
+            require_passing_checks: false,
+            feature_flags: FeatureFlags::default(),
+        })
+    }
+}
+
+impl From<String> for Config {
+    fn from(raw_config: String) -> Self {
+        if let Ok(config) = serde_json::from_str::<Config>(&raw_config)
+            && config.config_version == "v5"
+        {
+            return config;
+        }
+
+        match Self::from_previous_version(&raw_config) {
+            Ok(config) => {
+                tracing::info!("Config upgraded to v5");
+                config
+            }
+            Err(e) => {
+                tracing::warn!("Config migration failed: {}, using default", e);
+                Self::default()
+            }
+        }
+    }
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            config_version: "v5".to_string(),
+            theme: ThemeMode::System,
+            profile: ProfileVariantLabel::default("claude-code".to_string()),
+            disclaimer_acknowledged: false,
+            onboarding_acknowledged: false,
+            github_login_acknowledged: false,
+            telemetry_acknowledged: false,
+            notifications: NotificationConfig::default(),
+            editor: EditorConfig::default(),
+            github: GitHubConfig::default(),
+            analytics_enabled: None,
+            workspace_dir: None,
+            shutdown: ShutdownConfig::default(),
+            backup: BackupConfig::default(),
+            terminal: TerminalConfig::default(),
+            agents_paused: false,
+            budget: BudgetConfig::default(),
+            telemetry: TelemetryConfig::default(),
+            language: Locale::default(),
+            output_encoding: None,
+            serialize_conflicting_merges: false,
+            require_passing_checks: false,
+            feature_flags: FeatureFlags::default(),
+        }
+    }
+}
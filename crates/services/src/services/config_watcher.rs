@@ -0,0 +1,170 @@
+use std::{
+    path::Path,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use executors::profile::ProfileConfigs;
+use notify::{RecommendedWatcher, RecursiveMode};
+use notify_debouncer_full::{DebounceEventResult, Debouncer, RecommendedCache, new_debouncer};
+use serde_json::Value;
+use sqlx::SqlitePool;
+use tokio::sync::{RwLock, broadcast, mpsc};
+use utils::{
+    assets::{asset_dir, config_path, profiles_path},
+    i18n::set_locale,
+};
+
+use crate::services::config::{
+    Config, reload_config_from_file,
+    store::{self, SettingsFile},
+};
+
+/// Which settings file changed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConfigChange {
+    Config,
+    Profiles,
+}
+
+/// Watches `config.json` and `profiles.json` in the data directory. Changes made through the
+/// API and edits from outside the server both land there, so every change is reloaded into
+/// memory and announced to subscribers from this one place. Edits from outside are imported
+/// into the database first, the server's own writes are already in it.
+#[derive(Clone)]
+pub struct ConfigWatcher {
+    changes: broadcast::Sender<ConfigChange>,
+    _debouncer: Option<Arc<Mutex<Debouncer<RecommendedWatcher, RecommendedCache>>>>,
+}
+
+impl ConfigWatcher {
+    pub fn spawn(config: Arc<RwLock<Config>>, pool: SqlitePool, user_id: String) -> Self {
+        let (changes, _) = broadcast::channel(16);
+        let (tx, rx) = mpsc::unbounded_channel();
+        let debouncer = match watch_settings_files(&asset_dir(), tx) {
+            Ok(debouncer) => Some(Arc::new(Mutex::new(debouncer))),
+            Err(e) => {
+                tracing::warn!("Failed to watch config files, external edits won't apply: {e}");
+                None
+            }
+        };
+        tokio::spawn(reload_changes(rx, config, pool, user_id, changes.clone()));
+
+        Self {
+            changes,
+            _debouncer: debouncer,
+        }
+    }
+
+    /// Receives an item after each change has been applied to the running server
+    pub fn subscribe(&self) -> broadcast::Receiver<ConfigChange> {
+        self.changes.subscribe()
+    }
+
+    /// Tell subscribers about a change that doesn't touch the files, e.g. to the user's
+    /// config override
+    pub fn announce(&self, change: ConfigChange) {
+        let _ = self.changes.send(change);
+    }
+}
+
+fn settings_file_change(path: &Path) -> Option<ConfigChange> {
+    let file_name = path.file_name()?;
+    if Some(file_name) == config_path().file_name() {
+        Some(ConfigChange::Config)
+    } else if Some(file_name) == profiles_path().file_name() {
+        Some(ConfigChange::Profiles)
+    } else {
+        None
+    }
+}
+
+fn watch_settings_files(
+    dir: &Path,
+    tx: mpsc::UnboundedSender<ConfigChange>,
+) -> Result<Debouncer<RecommendedWatcher, RecommendedCache>, notify::Error> {
+    let mut debouncer = new_debouncer(
+        Duration::from_millis(200),
+        None,
+        move |res: DebounceEventResult| match res {
+            Ok(events) => {
+                let mut changes: Vec<_> = events
+                    .iter()
+                    .filter(|event| !event.kind.is_access())
+                    .flat_map(|event| event.paths.iter())
+                    .filter_map(|path| settings_file_change(path))
+                    .collect();
+                changes.dedup();
+                for change in changes {
+                    let _ = tx.send(change);
+                }
+            }
+            Err(errors) => {
+                for e in errors {
+                    tracing::warn!("Config file watch error: {}", e);
+                }
+            }
+        },
+    )?;
+    // The data directory also holds the database, non-settings files are filtered above
+    debouncer.watch(dir, RecursiveMode::NonRecursive)?;
+    Ok(debouncer)
+}
+
+async fn reload_changes(
+    mut rx: mpsc::UnboundedReceiver<ConfigChange>,
+    config: Arc<RwLock<Config>>,
+    pool: SqlitePool,
+    user_id: String,
+    changes: broadcast::Sender<ConfigChange>,
+) {
+    while let Some(change) = rx.recv().await {
+        match change {
+            ConfigChange::Config => match reload_config_from_file(&pool, &user_id).await {
+                Ok(reloaded) => {
+                    let mut current = config.write().await;
+                    if serde_json::to_value(&*current).ok() != serde_json::to_value(&reloaded).ok()
+                    {
+                        tracing::info!("config.json changed on disk, reloading");
+                        set_locale(reloaded.language);
+                        *current = reloaded;
+                    }
+                }
+                Err(e) => {
+                    tracing::warn!("Ignoring config.json change that failed to load: {}", e);
+                    continue;
+                }
+            },
+            ConfigChange::Profiles => {
+                if let Err(e) = store::import_file::<Value>(&pool, SettingsFile::Profiles).await {
+                    tracing::warn!("Ignoring profiles.json change that failed to load: {}", e);
+                    continue;
+                }
+                tokio::task::spawn_blocking(ProfileConfigs::reload)
+                    .await
+                    .unwrap_or_else(|e| tracing::error!("Failed to reload profiles: {}", e));
+            }
+        }
+        // No subscribers is fine, the send error only reports that
+        let _ = changes.send(change);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_only_settings_files_are_watched() {
+        let dir = Path::new("data");
+        assert_eq!(
+            settings_file_change(&dir.join("config.json")),
+            Some(ConfigChange::Config)
+        );
+        assert_eq!(
+            settings_file_change(&dir.join("profiles.json")),
+            Some(ConfigChange::Profiles)
+        );
+        assert_eq!(settings_file_change(&dir.join("db.sqlite-wal")), None);
+    }
+}
@@ -0,0 +1,259 @@
+use std::{
+    collections::{BTreeSet, HashMap},
+    sync::{Arc, Mutex},
+};
+
+use futures::StreamExt;
+use serde::Serialize;
+use tokio::{
+    sync::{OwnedMutexGuard, broadcast::error::RecvError},
+    task::JoinHandle,
+};
+use ts_rs::TS;
+use utils::{log_msg::LogMsg, msg_store::MsgStore};
+use uuid::Uuid;
+
+use super::diff_watcher::{DiffChanges, DiffSubscription};
+
+/// Two unmerged attempts of a project that changed some of the same files against the same
+/// base branch, so merging one will likely make the other conflict
+#[derive(Debug, Clone, PartialEq, Serialize, TS)]
+pub struct AttemptConflict {
+    pub task_attempt_id: Uuid,
+    pub task_id: Uuid,
+    pub other_task_attempt_id: Uuid,
+    pub other_task_id: Uuid,
+    /// Worktree-relative paths both attempts changed
+    pub paths: Vec<String>,
+}
+
+struct AttemptEdits {
+    task_id: Uuid,
+    base_branch: String,
+    paths: BTreeSet<String>,
+}
+
+#[derive(Default)]
+struct ProjectEdits {
+    attempts: HashMap<Uuid, AttemptEdits>,
+    merges: Arc<tokio::sync::Mutex<()>>,
+}
+
+/// Files each unmerged attempt changed, learned from the edit events of its worktree while
+/// its agent runs. Kept in memory, attempts edited before a restart are tracked again once an
+/// agent edits them.
+#[derive(Clone, Default)]
+pub struct ConflictTracker {
+    projects: Arc<Mutex<HashMap<Uuid, ProjectEdits>>>,
+}
+
+impl ConflictTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record the files of a batch of changes, those without a diff are back to their base
+    /// version and no longer count
+    pub fn record(
+        &self,
+        project_id: Uuid,
+        task_id: Uuid,
+        task_attempt_id: Uuid,
+        base_branch: &str,
+        changes: &DiffChanges,
+    ) {
+        let mut projects = self.projects.lock().unwrap();
+        let edits = projects
+            .entry(project_id)
+            .or_default()
+            .attempts
+            .entry(task_attempt_id)
+            .or_insert_with(|| AttemptEdits {
+                task_id,
+                base_branch: base_branch.to_string(),
+                paths: BTreeSet::new(),
+            });
+        let diffed: BTreeSet<&str> = changes
+            .diffs
+            .iter()
+            .flat_map(|diff| [&diff.old_file, &diff.new_file])
+            .flatten()
+            .filter_map(|file| file.file_name.as_deref())
+            .collect();
+        for path in &changes.changed_paths {
+            if diffed.contains(path.as_str()) {
+                edits.paths.insert(path.clone());
+            } else {
+                edits.paths.remove(path);
+            }
+        }
+    }
+
+    /// Stop tracking an attempt, once it's merged or deleted
+    pub fn forget(&self, project_id: Uuid, task_attempt_id: Uuid) {
+        if let Some(project) = self.projects.lock().unwrap().get_mut(&project_id) {
+            project.attempts.remove(&task_attempt_id);
+        }
+    }
+
+    /// Every pair of the project's attempts that changed the same files
+    pub fn conflicts(&self, project_id: Uuid) -> Vec<AttemptConflict> {
+        let projects = self.projects.lock().unwrap();
+        let Some(project) = projects.get(&project_id) else {
+            return Vec::new();
+        };
+        let mut attempts: Vec<_> = project.attempts.iter().collect();
+        attempts.sort_by_key(|(id, _)| **id);
+
+        let mut conflicts = Vec::new();
+        for (i, (attempt_id, edits)) in attempts.iter().enumerate() {
+            for (other_id, other) in &attempts[i + 1..] {
+                if edits.task_id == other.task_id || edits.base_branch != other.base_branch {
+                    continue;
+                }
+                let paths: Vec<String> = edits.paths.intersection(&other.paths).cloned().collect();
+                if !paths.is_empty() {
+                    conflicts.push(AttemptConflict {
+                        task_attempt_id: **attempt_id,
+                        task_id: edits.task_id,
+                        other_task_attempt_id: **other_id,
+                        other_task_id: other.task_id,
+                        paths,
+                    });
+                }
+            }
+        }
+        conflicts
+    }
+
+    pub fn has_conflicts(&self, project_id: Uuid, task_attempt_id: Uuid) -> bool {
+        self.conflicts(project_id).iter().any(|conflict| {
+            conflict.task_attempt_id == task_attempt_id
+                || conflict.other_task_attempt_id == task_attempt_id
+        })
+    }
+
+    /// Wait for the project's earlier merges to finish. Merges hold the guard until done, so
+    /// they land one at a time in the order they asked.
+    pub async fn merge_guard(&self, project_id: Uuid) -> OwnedMutexGuard<()> {
+        let merges = self
+            .projects
+            .lock()
+            .unwrap()
+            .entry(project_id)
+            .or_default()
+            .merges
+            .clone();
+        merges.lock_owned().await
+    }
+
+    /// Record the changes of an attempt's worktree while its coding agent runs, until its
+    /// output finishes
+    pub fn track_while_running(
+        &self,
+        project_id: Uuid,
+        task_id: Uuid,
+        task_attempt_id: Uuid,
+        base_branch: String,
+        mut subscription: DiffSubscription,
+        store: Arc<MsgStore>,
+    ) -> JoinHandle<()> {
+        let tracker = self.clone();
+        tokio::spawn(async move {
+            let mut output = store.history_plus_stream();
+            loop {
+                tokio::select! {
+                    msg = output.next() => match msg {
+                        Some(Ok(LogMsg::Finished)) | Some(Err(_)) | None => break,
+                        Some(Ok(_)) => {}
+                    },
+                    changes = subscription.recv() => match changes {
+                        Ok(changes) => tracker.record(
+                            project_id,
+                            task_id,
+                            task_attempt_id,
+                            &base_branch,
+                            &changes,
+                        ),
+                        // Missed changes are picked up when the files change again
+                        Err(RecvError::Lagged(_)) => {}
+                        Err(RecvError::Closed) => break,
+                    },
+                }
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use utils::diff::{Diff, FileDiffDetails};
+
+    use super::*;
+
+    fn changes(paths: &[&str], diffed: &[&str]) -> DiffChanges {
+        DiffChanges {
+            changed_paths: paths.iter().map(|p| p.to_string()).collect(),
+            diffs: diffed
+                .iter()
+                .map(|path| Diff {
+                    old_file: None,
+                    new_file: Some(FileDiffDetails {
+                        file_name: Some(path.to_string()),
+                        content: None,
+                    }),
+                    hunks: Vec::new(),
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn test_overlapping_edits_on_the_same_base_conflict() {
+        let tracker = ConflictTracker::new();
+        let project = Uuid::new_v4();
+        let (first, second, other_base) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+        let both = ["src/lib.rs", "README.md"];
+        tracker.record(
+            project,
+            Uuid::new_v4(),
+            first,
+            "main",
+            &changes(&both, &both),
+        );
+        tracker.record(
+            project,
+            Uuid::new_v4(),
+            second,
+            "main",
+            &changes(&both, &both[..1]),
+        );
+        tracker.record(
+            project,
+            Uuid::new_v4(),
+            other_base,
+            "dev",
+            &changes(&both, &both),
+        );
+
+        let conflicts = tracker.conflicts(project);
+        assert_eq!(conflicts.len(), 1);
+        assert_eq!(conflicts[0].paths, vec!["src/lib.rs".to_string()]);
+        assert!(tracker.has_conflicts(project, first));
+        assert!(!tracker.has_conflicts(project, other_base));
+
+        // Reverting the file to its base version resolves the overlap
+        tracker.record(
+            project,
+            Uuid::nil(),
+            second,
+            "main",
+            &changes(&both[..1], &[]),
+        );
+        assert!(tracker.conflicts(project).is_empty());
+
+        tracker.record(project, Uuid::nil(), second, "main", &changes(&both, &both));
+        tracker.forget(project, first);
+        assert!(!tracker.has_conflicts(project, second));
+    }
+}
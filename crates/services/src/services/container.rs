@@ -1,10 +1,11 @@
 use std::{
     collections::HashMap,
-    path::PathBuf,
+    path::{Path, PathBuf},
     sync::{
         Arc,
         atomic::{AtomicUsize, Ordering},
     },
+    time::Duration,
 };
 
 use anyhow::Error as AnyhowError;
@@ -13,36 +14,81 @@ use axum::response::sse::Event;
 use db::{
     DBService,
     models::{
+        attempt_commit_plan::{AttemptCommitPlan, CommitPlanStatus},
+        attempt_diff_risk::{AttemptDiffRisk, DiffRisk},
+        attempt_plan::{AttemptPlan, PlanStatus},
+        attempt_review::{AttemptReview, ReviewVerdict},
+        execution_cost::ExecutionCost,
+        execution_pause::ExecutionPause,
         execution_process::{
             CreateExecutionProcess, ExecutionContext, ExecutionProcess, ExecutionProcessRunReason,
             ExecutionProcessStatus,
         },
         execution_process_logs::ExecutionProcessLogs,
+        execution_token_usage::ExecutionTokenUsage,
         executor_session::{CreateExecutorSession, ExecutorSession},
+        project::Project,
+        project_context_packing::ProjectContextPacking,
+        project_disk_quota::ProjectDiskQuota,
+        project_path_rules::ProjectPathRules,
+        project_reviewer::ProjectReviewer,
+        queued_follow_up::QueuedFollowUp,
+        suspended_execution::{CreateSuspendedExecution, SuspendReason, SuspendedExecution},
         task::{Task, TaskStatus},
+        task_attachment::TaskAttachment,
         task_attempt::{TaskAttempt, TaskAttemptError},
+        test_run::TestRun,
     },
 };
 use executors::{
     actions::{
         ExecutorAction, ExecutorActionType,
+        coding_agent_follow_up::CodingAgentFollowUpRequest,
         coding_agent_initial::CodingAgentInitialRequest,
         script::{ScriptContext, ScriptRequest, ScriptRequestLanguage},
     },
     executors::{CodingAgent, ExecutorError, StandardCodingAgentExecutor},
-    logs::utils::patch::ConversationPatch,
+    logs::{NormalizedEntry, ToolCall, utils::patch::ConversationPatch},
     profile::ProfileVariantLabel,
 };
 use futures::{StreamExt, TryStreamExt, future};
 use sqlx::Error as SqlxError;
 use thiserror::Error;
-use tokio::{sync::RwLock, task::JoinHandle};
-use utils::{log_msg::LogMsg, msg_store::MsgStore};
+use tokio::{
+    sync::{Mutex, RwLock},
+    task::JoinHandle,
+};
+use tracing::{Instrument, Span};
+use utils::{log_msg::LogMsg, msg_store::MsgStore, otel::attempt_span};
 use uuid::Uuid;
 
 use crate::services::{
-    git::{GitService, GitServiceError},
+    attachments::{AttachmentError, AttachmentStore, ContextFolder, attachments_prompt},
+    attempt_history::{PriorAttempt, PriorRun, history_prompt},
+    commit_plan::{REMAINING_CHANGES_MESSAGE, commit_plan_prompt, parse_commit_plan},
+    context_pack::ContextPacker,
+    dependency_cache::DependencyCacheError,
+    devcontainer::DevcontainerError,
+    diff_risk::assess_worktree,
+    diff_watcher::DiffWatcherError,
+    disk_quota::{DiskQuotaError, ProjectDiskUsage},
+    feature_flags::FeatureFlag,
+    git::{DiffTarget, GitService, GitServiceError},
+    linked_repos::{self, LinkedRepoError, linked_repos_prompt},
+    lint::{MAX_LINT_FIX_ROUNDS, consecutive_lint_fixes, lint_fix_prompt},
+    log_export,
+    nix_env::NixEnvError,
+    path_rules::{PathRules, PathRulesError, is_path_revert_prompt, path_revert_prompt},
+    planning::plan_prompt,
+    project_env::ProjectEnvError,
+    provider_keys::ProviderKeyError,
+    repo_config::{RepoConfig, RepoConfigError},
+    review::{parse_review, render_diff, review_prompt},
+    shutdown::ShutdownService,
+    test_results::{TestReportError, parse_test_output, test_action},
     worktree_manager::WorktreeError,
+    worktree_pool::WorktreePool,
+    worktree_templates::WorktreeTemplateError,
 };
 pub type ContainerRef = String;
 
@@ -63,9 +109,69 @@ pub enum ContainerError {
     #[error(transparent)]
     TaskAttemptError(#[from] TaskAttemptError),
     #[error(transparent)]
+    RepoConfig(#[from] RepoConfigError),
+    #[error(transparent)]
+    Attachment(#[from] AttachmentError),
+    #[error(transparent)]
+    TestReport(#[from] TestReportError),
+    #[error(transparent)]
+    PathRules(#[from] PathRulesError),
+    #[error(transparent)]
+    DependencyCache(#[from] DependencyCacheError),
+    #[error(transparent)]
+    Devcontainer(#[from] DevcontainerError),
+    #[error(transparent)]
+    DiffWatcher(#[from] DiffWatcherError),
+    #[error(transparent)]
+    NixEnv(#[from] NixEnvError),
+    #[error(transparent)]
+    ProjectEnv(#[from] ProjectEnvError),
+    #[error(transparent)]
+    ProviderKey(#[from] ProviderKeyError),
+    #[error(transparent)]
+    WorktreeTemplate(#[from] WorktreeTemplateError),
+    #[error(transparent)]
+    LinkedRepo(#[from] LinkedRepoError),
+    #[error(transparent)]
+    DiskQuota(#[from] DiskQuotaError),
+    #[error("Server is shutting down, not starting new executions")]
+    ShuttingDown,
+    #[error("Pausing executions is not supported on this platform")]
+    PauseUnsupported,
+    #[error("No running execution to pause")]
+    NothingToPause,
+    #[error("No paused or interrupted execution to resume")]
+    NothingToResume,
+    #[error("All agents are paused, resume them to start new work")]
+    AgentsPaused,
+    #[error(transparent)]
     Other(#[from] AnyhowError), // Catches any unclassified errors
 }
 
+/// How long normalizing a stored log again may take before the entries so far are used
+const RENORMALIZE_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Follow-up continuing a session that was cut off, repeating what the agent was asked in
+/// case its session didn't record all of it
+fn resume_prompt(reason: SuspendReason, prompt: Option<&str>) -> String {
+    let cause = match reason {
+        SuspendReason::Shutdown => "the server shutting down",
+        SuspendReason::Crash => "the server or the machine stopping unexpectedly",
+    };
+    let mut resume = format!(
+        "Your previous run was interrupted by {cause} before it finished. Check the state of \
+         the worktree, then continue where you left off."
+    );
+    if let Some(prompt) = prompt.filter(|p| !p.trim().is_empty()) {
+        resume.push_str("\n\nYou were working on:\n\n");
+        resume.push_str(prompt);
+    }
+    resume
+}
+
+/// Held while deciding whether to send a queued follow-up
+static FOLLOW_UP_DISPATCH: Mutex<()> = Mutex::const_new(());
+
 #[async_trait]
 pub trait ContainerService {
     fn msg_stores(&self) -> &Arc<RwLock<HashMap<Uuid, Arc<MsgStore>>>>;
@@ -74,15 +180,79 @@ pub trait ContainerService {
 
     fn git(&self) -> &GitService;
 
+    fn shutdown(&self) -> &ShutdownService;
+
     fn task_attempt_to_current_dir(&self, task_attempt: &TaskAttempt) -> PathBuf;
 
+    /// Whether every agent is paused by the global switch, see `Config::agents_paused`
+    async fn agents_paused(&self) -> bool;
+
+    /// Whether an experimental subsystem is turned on, environment overrides included
+    async fn feature_enabled(&self, flag: FeatureFlag) -> bool;
+
     async fn create(&self, task_attempt: &TaskAttempt) -> Result<ContainerRef, ContainerError>;
 
+    fn worktree_pool(&self) -> &WorktreePool;
+
+    /// Bring the project's worktree pool to its configured size in the background
+    fn spawn_fill_worktree_pool(&self, project_id: Uuid);
+
+    /// Give the attempt a worktree from its project's pool, checked out to its base branch
+    /// with the setup script already run. `None` when none is ready.
+    async fn claim_warm_worktree(
+        &self,
+        task_attempt: &TaskAttempt,
+    ) -> Result<Option<ContainerRef>, ContainerError>;
+
     async fn delete(&self, task_attempt: &TaskAttempt) -> Result<(), ContainerError> {
         self.try_stop(task_attempt).await;
         self.delete_inner(task_attempt).await
     }
 
+    /// Refuse new attempts in a project whose worktrees exceed its disk quota. With automatic
+    /// cleanup, the worktrees of merged attempts are removed first.
+    async fn ensure_within_disk_quota(&self, project: &Project) -> Result<(), ContainerError> {
+        let pool = &self.db().pool;
+        // Measuring walks every worktree, only worth it with a quota
+        if ProjectDiskQuota::find_by_project_id(pool, project.id)
+            .await?
+            .is_none()
+        {
+            return Ok(());
+        }
+        let mut usage = ProjectDiskUsage::measure(pool, project.id).await?;
+        if usage.exceeded() && usage.auto_gc && self.collect_merged_worktrees(&usage).await? > 0 {
+            usage = ProjectDiskUsage::measure(pool, project.id).await?;
+        }
+        Ok(usage.check(&project.name)?)
+    }
+
+    /// Removes the worktrees of the project's merged attempts that aren't running, returns
+    /// how many were removed
+    async fn collect_merged_worktrees(
+        &self,
+        usage: &ProjectDiskUsage,
+    ) -> Result<usize, ContainerError> {
+        let pool = &self.db().pool;
+        let mut removed = 0;
+        for attempt_id in usage.collectable_attempts() {
+            let Some(attempt) = TaskAttempt::find_by_id(pool, attempt_id).await? else {
+                continue;
+            };
+            self.delete(&attempt).await?;
+            TaskAttempt::mark_worktree_deleted(pool, attempt.id).await?;
+            removed += 1;
+        }
+        if removed > 0 {
+            tracing::info!(
+                "Removed {} worktrees of merged attempts in project {}",
+                removed,
+                usage.project_id
+            );
+        }
+        Ok(removed)
+    }
+
     async fn try_stop(&self, task_attempt: &TaskAttempt) {
         // stop all execution processes for this attempt
         if let Ok(processes) =
@@ -103,6 +273,197 @@ pub trait ContainerService {
         }
     }
 
+    /// Stop the attempt's running executions in place, dev servers excluded, keeping their
+    /// progress until they are resumed
+    async fn pause_attempt(&self, task_attempt: &TaskAttempt) -> Result<(), ContainerError> {
+        let pool = &self.db().pool;
+        let mut paused = false;
+        for process in ExecutionProcess::find_by_task_attempt_id(pool, task_attempt.id).await? {
+            if process.status != ExecutionProcessStatus::Running
+                || process.run_reason == ExecutionProcessRunReason::DevServer
+                || ExecutionPause::is_paused(pool, process.id).await?
+            {
+                continue;
+            }
+            self.suspend_execution(&process).await?;
+            ExecutionPause::create(pool, process.id, task_attempt.id).await?;
+            paused = true;
+        }
+        if !paused {
+            return Err(ContainerError::NothingToPause);
+        }
+        Ok(())
+    }
+
+    async fn resume_attempt(&self, task_attempt: &TaskAttempt) -> Result<(), ContainerError> {
+        if self.agents_paused().await {
+            return Err(ContainerError::AgentsPaused);
+        }
+        let pool = &self.db().pool;
+        let mut resumed = false;
+        for process in ExecutionProcess::find_by_task_attempt_id(pool, task_attempt.id).await? {
+            if process.status != ExecutionProcessStatus::Running
+                || !ExecutionPause::is_paused(pool, process.id).await?
+            {
+                continue;
+            }
+            self.continue_execution(&process).await?;
+            ExecutionPause::mark_resumed(pool, process.id).await?;
+            resumed = true;
+        }
+        if !resumed {
+            // Nothing paused, continue the session a shutdown or reboot cut off instead
+            self.resume_suspended(task_attempt).await?;
+        }
+        Ok(())
+    }
+
+    /// Record a coding agent execution being stopped by a shutdown, with what resuming it
+    /// from its session needs
+    async fn record_suspended(
+        &self,
+        process: &ExecutionProcess,
+        reason: SuspendReason,
+    ) -> Result<SuspendedExecution, ContainerError> {
+        let pool = &self.db().pool;
+        let session_id = ExecutorSession::find_by_execution_process_id(pool, process.id)
+            .await?
+            .and_then(|session| session.session_id);
+        let (label, prompt) = match process.executor_action()?.typ() {
+            ExecutorActionType::CodingAgentInitialRequest(request) => (
+                Some(request.profile_variant_label.clone()),
+                Some(request.prompt.clone()),
+            ),
+            ExecutorActionType::CodingAgentFollowUpRequest(request) => (
+                Some(request.profile_variant_label.clone()),
+                Some(request.prompt.clone()),
+            ),
+            _ => (None, None),
+        };
+        let worktree_path = TaskAttempt::find_by_id(pool, process.task_attempt_id)
+            .await?
+            .and_then(|attempt| attempt.container_ref);
+        Ok(SuspendedExecution::create(
+            pool,
+            &CreateSuspendedExecution {
+                execution_process_id: process.id,
+                task_attempt_id: process.task_attempt_id,
+                session_id,
+                reason,
+                profile: label.as_ref().map(|label| label.profile.clone()),
+                variant: label.and_then(|label| label.variant),
+                worktree_path,
+                prompt,
+            },
+        )
+        .await?)
+    }
+
+    /// Continue the attempt's agent session a shutdown or reboot stopped, in its worktree,
+    /// recreated if the reboot cleared it
+    async fn resume_suspended(
+        &self,
+        task_attempt: &TaskAttempt,
+    ) -> Result<ExecutionProcess, ContainerError> {
+        let pool = &self.db().pool;
+        let suspended = SuspendedExecution::find_pending_by_task_attempt_id(pool, task_attempt.id)
+            .await?
+            .ok_or(ContainerError::NothingToResume)?;
+        // The user already carried on with a follow-up or a new run of the agent
+        let latest = ExecutionProcess::find_latest_by_task_attempt_and_run_reason(
+            pool,
+            task_attempt.id,
+            &ExecutionProcessRunReason::CodingAgent,
+        )
+        .await?;
+        if latest.is_some_and(|latest| latest.id != suspended.execution_process_id) {
+            SuspendedExecution::mark_resumed(pool, suspended.id).await?;
+            return Err(ContainerError::NothingToResume);
+        }
+        if suspended.session_id.is_none() {
+            return Err(ContainerError::TaskAttemptError(
+                TaskAttemptError::ValidationError(
+                    "The interrupted agent never reported a session, start a new attempt instead"
+                        .to_string(),
+                ),
+            ));
+        }
+
+        self.ensure_container_exists(task_attempt).await?;
+        let execution_process = self
+            .start_follow_up(
+                task_attempt,
+                resume_prompt(suspended.reason, suspended.prompt.as_deref()),
+                suspended.variant.clone(),
+            )
+            .await?;
+        SuspendedExecution::mark_resumed(pool, suspended.id).await?;
+        tracing::info!(
+            "Resumed session of execution {} in attempt {}",
+            suspended.execution_process_id,
+            task_attempt.id
+        );
+        Ok(execution_process)
+    }
+
+    /// Suspend every running execution, dev servers excluded. Returns how many were
+    /// suspended.
+    async fn pause_all_executions(&self) -> Result<usize, ContainerError> {
+        let pool = &self.db().pool;
+        let mut paused = 0;
+        for process in ExecutionProcess::find_running(pool).await? {
+            if process.run_reason == ExecutionProcessRunReason::DevServer
+                || ExecutionPause::is_paused(pool, process.id).await?
+            {
+                continue;
+            }
+            match self.suspend_execution(&process).await {
+                Ok(()) => {
+                    ExecutionPause::create(pool, process.id, process.task_attempt_id).await?;
+                    paused += 1;
+                }
+                Err(e) => tracing::error!("Failed to pause execution {}: {}", process.id, e),
+            }
+        }
+        Ok(paused)
+    }
+
+    /// Continue every paused execution, then send the follow-ups queued in the meantime.
+    /// Returns how many executions were resumed.
+    async fn resume_all_executions(&self) -> Result<usize, ContainerError> {
+        let pool = &self.db().pool;
+        let mut resumed = 0;
+        for process in ExecutionProcess::find_running(pool).await? {
+            if !ExecutionPause::is_paused(pool, process.id).await? {
+                continue;
+            }
+            match self.continue_execution(&process).await {
+                Ok(()) => {
+                    ExecutionPause::mark_resumed(pool, process.id).await?;
+                    resumed += 1;
+                }
+                Err(e) => tracing::error!("Failed to resume execution {}: {}", process.id, e),
+            }
+        }
+        for task_attempt_id in QueuedFollowUp::find_task_attempt_ids(pool).await? {
+            let Some(task_attempt) = TaskAttempt::find_by_id(pool, task_attempt_id).await? else {
+                continue;
+            };
+            if let Err(e) = self
+                .dispatch_queued_follow_up(&task_attempt)
+                .instrument(attempt_span("dispatch_queued_follow_up", task_attempt.id))
+                .await
+            {
+                tracing::error!(
+                    "Failed to send queued follow-up for task attempt {}: {}",
+                    task_attempt_id,
+                    e
+                );
+            }
+        }
+        Ok(resumed)
+    }
+
     async fn delete_inner(&self, task_attempt: &TaskAttempt) -> Result<(), ContainerError>;
 
     async fn ensure_container_exists(
@@ -122,6 +483,25 @@ pub trait ContainerService {
         execution_process: &ExecutionProcess,
     ) -> Result<(), ContainerError>;
 
+    /// Suspend a running execution's processes without ending them
+    async fn suspend_execution(
+        &self,
+        execution_process: &ExecutionProcess,
+    ) -> Result<(), ContainerError>;
+
+    /// Continue an execution stopped by `suspend_execution`
+    async fn continue_execution(
+        &self,
+        execution_process: &ExecutionProcess,
+    ) -> Result<(), ContainerError>;
+
+    /// Resume tracking an execution left running by a previous server instance.
+    /// Returns `false` when its process group is gone and it can't be recovered.
+    async fn reattach_execution(
+        &self,
+        execution_process: &ExecutionProcess,
+    ) -> Result<bool, ContainerError>;
+
     async fn try_commit_changes(&self, ctx: &ExecutionContext) -> Result<(), ContainerError>;
 
     async fn copy_project_files(
@@ -131,9 +511,12 @@ pub trait ContainerService {
         copy_files: &str,
     ) -> Result<(), ContainerError>;
 
+    /// Stream the attempt's diff against its base branch, following changes, or a snapshot of
+    /// its worktree against the ref `against`
     async fn get_diff(
         &self,
         task_attempt: &TaskAttempt,
+        against: Option<&str>,
     ) -> Result<futures::stream::BoxStream<'static, Result<Event, std::io::Error>>, ContainerError>;
 
     /// Fetch the MsgStore for a given execution ID, panicking if missing.
@@ -142,6 +525,753 @@ pub trait ContainerService {
         map.get(uuid).cloned()
     }
 
+    /// Parse the output of a finished test stage into a test run of its attempt. `None` for
+    /// other executions, and when the project doesn't set a result format.
+    async fn record_test_run(
+        &self,
+        ctx: &ExecutionContext,
+    ) -> Result<Option<TestRun>, ContainerError> {
+        let is_test_stage = matches!(
+            ctx.execution_process.executor_action()?.typ(),
+            ExecutorActionType::ScriptRequest(script) if script.context == ScriptContext::TestScript
+        );
+        if !is_test_stage || ctx.execution_process.status == ExecutionProcessStatus::Killed {
+            return Ok(None);
+        }
+        let Some(format) = Project::find_by_id(&self.db().pool, ctx.task.project_id)
+            .await?
+            .and_then(|project| project.test_result_format)
+        else {
+            return Ok(None);
+        };
+        let Some(store) = self.get_msg_store_by_id(&ctx.execution_process.id).await else {
+            return Ok(None);
+        };
+        let stdout: String = store
+            .get_history()
+            .into_iter()
+            .filter_map(|msg| match msg {
+                LogMsg::Stdout(chunk) => Some(chunk),
+                _ => None,
+            })
+            .collect();
+        let summary = parse_test_output(format, &stdout)?;
+        let test_run = TestRun::create(
+            &self.db().pool,
+            ctx.task_attempt.id,
+            ctx.execution_process.id,
+            &summary,
+        )
+        .await?;
+        Ok(Some(test_run))
+    }
+
+    /// Refresh the diff risk of an attempt after one of its agent runs finished. `None` for
+    /// other executions, and when the attempt has no worktree yet.
+    async fn record_diff_risk(
+        &self,
+        ctx: &ExecutionContext,
+    ) -> Result<Option<DiffRisk>, ContainerError> {
+        if ctx.execution_process.run_reason != ExecutionProcessRunReason::CodingAgent {
+            return Ok(None);
+        }
+        let (Some(container_ref), Some(branch)) =
+            (&ctx.task_attempt.container_ref, &ctx.task_attempt.branch)
+        else {
+            return Ok(None);
+        };
+        let project = ctx
+            .task
+            .parent_project(&self.db().pool)
+            .await?
+            .ok_or(SqlxError::RowNotFound)?;
+        let repo_config = RepoConfig::load(
+            self.git(),
+            &project.git_repo_path,
+            &ctx.task_attempt.base_branch,
+        )?;
+        let risk = assess_worktree(
+            Path::new(container_ref),
+            branch,
+            &ctx.task_attempt.base_branch,
+            &repo_config.expected_paths,
+        )?;
+        AttemptDiffRisk::upsert(&self.db().pool, ctx.task_attempt.id, &risk).await?;
+        Ok(Some(risk))
+    }
+
+    /// Store the plan written by the planning phase of a plan-then-execute attempt, which
+    /// then waits for approval. `None` for other executions.
+    async fn record_plan(
+        &self,
+        ctx: &ExecutionContext,
+    ) -> Result<Option<AttemptPlan>, ContainerError> {
+        let ExecutorActionType::CodingAgentInitialRequest(request) =
+            ctx.execution_process.executor_action()?.typ()
+        else {
+            return Ok(None);
+        };
+        let Some(attempt_plan) =
+            AttemptPlan::find_by_task_attempt_id(&self.db().pool, ctx.task_attempt.id).await?
+        else {
+            return Ok(None);
+        };
+        if attempt_plan.status != PlanStatus::Planning {
+            return Ok(None);
+        }
+
+        let mut plan = None;
+        if ctx.execution_process.status == ExecutionProcessStatus::Completed
+            && let Some(store) = self.get_msg_store_by_id(&ctx.execution_process.id).await
+        {
+            let stdout: String = store
+                .get_history()
+                .into_iter()
+                .filter_map(|msg| match msg {
+                    LogMsg::Stdout(chunk) => Some(chunk),
+                    _ => None,
+                })
+                .collect();
+            plan = CodingAgent::from_profile_variant_label(&request.profile_variant_label)?
+                .extract_plan(&stdout);
+        }
+        let status = match plan {
+            Some(_) => PlanStatus::AwaitingApproval,
+            None => PlanStatus::Failed,
+        };
+        let attempt_plan = AttemptPlan::update(
+            &self.db().pool,
+            ctx.task_attempt.id,
+            status,
+            plan.as_deref(),
+        )
+        .await?;
+        Ok(Some(attempt_plan))
+    }
+
+    /// Have the project's reviewer profile review the attempt's changes once the
+    /// implementing agent's chain completed. `None` when no review was started.
+    async fn dispatch_review(
+        &self,
+        ctx: &ExecutionContext,
+    ) -> Result<Option<ExecutionProcess>, ContainerError> {
+        if ctx.execution_process.status != ExecutionProcessStatus::Completed
+            || !matches!(
+                ctx.execution_process.run_reason,
+                ExecutionProcessRunReason::CodingAgent | ExecutionProcessRunReason::CleanupScript
+            )
+        {
+            return Ok(None);
+        }
+        let Some(reviewer) =
+            ProjectReviewer::find_by_project_id(&self.db().pool, ctx.task.project_id).await?
+        else {
+            return Ok(None);
+        };
+        if !reviewer.enabled || !self.feature_enabled(FeatureFlag::MultiAgentPipelines).await {
+            return Ok(None);
+        }
+        // A plan waiting for approval has no changes to review yet
+        if AttemptPlan::find_by_task_attempt_id(&self.db().pool, ctx.task_attempt.id)
+            .await?
+            .is_some_and(|plan| plan.status != PlanStatus::Approved)
+        {
+            return Ok(None);
+        }
+        let (Some(container_ref), Some(branch)) =
+            (&ctx.task_attempt.container_ref, &ctx.task_attempt.branch)
+        else {
+            return Ok(None);
+        };
+        let diffs = self.git().get_diffs(
+            DiffTarget::Worktree {
+                worktree_path: Path::new(container_ref),
+                branch_name: branch,
+                base_branch: &ctx.task_attempt.base_branch,
+            },
+            None,
+        )?;
+        if diffs.is_empty() {
+            return Ok(None);
+        }
+
+        let executor_action = ExecutorAction::new(
+            ExecutorActionType::CodingAgentInitialRequest(CodingAgentInitialRequest {
+                prompt: review_prompt(&ctx.task.to_prompt(), &render_diff(&diffs)),
+                profile_variant_label: ProfileVariantLabel {
+                    profile: reviewer.profile,
+                    variant: reviewer.variant,
+                },
+            }),
+            None,
+        );
+        let execution_process = self
+            .start_execution(
+                &ctx.task_attempt,
+                &executor_action,
+                &ExecutionProcessRunReason::Review,
+            )
+            .await?;
+        Ok(Some(execution_process))
+    }
+
+    /// Store the review a reviewer run reported. `None` for other executions.
+    async fn record_review(
+        &self,
+        ctx: &ExecutionContext,
+    ) -> Result<Option<AttemptReview>, ContainerError> {
+        if ctx.execution_process.run_reason != ExecutionProcessRunReason::Review {
+            return Ok(None);
+        }
+        let ExecutorActionType::CodingAgentInitialRequest(request) =
+            ctx.execution_process.executor_action()?.typ()
+        else {
+            return Ok(None);
+        };
+
+        let mut review = None;
+        if ctx.execution_process.status == ExecutionProcessStatus::Completed
+            && let Some(store) = self.get_msg_store_by_id(&ctx.execution_process.id).await
+        {
+            let stdout: String = store
+                .get_history()
+                .into_iter()
+                .filter_map(|msg| match msg {
+                    LogMsg::Stdout(chunk) => Some(chunk),
+                    _ => None,
+                })
+                .collect();
+            let answer = CodingAgent::from_profile_variant_label(&request.profile_variant_label)?
+                .final_message(&stdout)
+                .unwrap_or(stdout);
+            review = parse_review(&answer);
+        }
+        let (verdict, summary, comments) = match review {
+            Some(review) => (review.verdict, review.summary, review.comments),
+            None => (
+                ReviewVerdict::Failed,
+                "The reviewer finished without a review".to_string(),
+                Vec::new(),
+            ),
+        };
+        let review = AttemptReview::create(
+            &self.db().pool,
+            ctx.task_attempt.id,
+            ctx.execution_process.id,
+            verdict,
+            &summary,
+            &comments,
+        )
+        .await?;
+        Ok(Some(review))
+    }
+
+    /// Have `profile_variant_label` plan how the attempt's changes split into logical commits,
+    /// applied to its branch once the run finished. `None` when the attempt has no changes.
+    async fn dispatch_commit_plan(
+        &self,
+        task_attempt: &TaskAttempt,
+        profile_variant_label: ProfileVariantLabel,
+    ) -> Result<Option<AttemptCommitPlan>, ContainerError> {
+        let Some(branch) = &task_attempt.branch else {
+            return Ok(None);
+        };
+        let container_ref = self.ensure_container_exists(task_attempt).await?;
+        let diffs = self.git().get_diffs(
+            DiffTarget::Worktree {
+                worktree_path: Path::new(&container_ref),
+                branch_name: branch,
+                base_branch: &task_attempt.base_branch,
+            },
+            None,
+        )?;
+        if diffs.is_empty() {
+            return Ok(None);
+        }
+        let task = task_attempt
+            .parent_task(&self.db().pool)
+            .await?
+            .ok_or(SqlxError::RowNotFound)?;
+        let files: Vec<String> = diffs.iter().map(GitService::diff_path).collect();
+
+        let executor_action = ExecutorAction::new(
+            ExecutorActionType::CodingAgentInitialRequest(CodingAgentInitialRequest {
+                prompt: commit_plan_prompt(&task.to_prompt(), &files, &render_diff(&diffs)),
+                profile_variant_label,
+            }),
+            None,
+        );
+        let execution_process = self
+            .start_execution(
+                task_attempt,
+                &executor_action,
+                &ExecutionProcessRunReason::CommitPlan,
+            )
+            .await?;
+        let plan =
+            AttemptCommitPlan::create(&self.db().pool, task_attempt.id, execution_process.id)
+                .await?;
+        Ok(Some(plan))
+    }
+
+    /// Split the attempt's changes into the commits a commit plan run reported. `None` for
+    /// other executions.
+    async fn record_commit_plan(
+        &self,
+        ctx: &ExecutionContext,
+    ) -> Result<Option<AttemptCommitPlan>, ContainerError> {
+        if ctx.execution_process.run_reason != ExecutionProcessRunReason::CommitPlan {
+            return Ok(None);
+        }
+        let ExecutorActionType::CodingAgentInitialRequest(request) =
+            ctx.execution_process.executor_action()?.typ()
+        else {
+            return Ok(None);
+        };
+        let Some(plan) = AttemptCommitPlan::find_by_execution_process_id(
+            &self.db().pool,
+            ctx.execution_process.id,
+        )
+        .await?
+        else {
+            return Ok(None);
+        };
+        let (Some(container_ref), Some(branch)) =
+            (&ctx.task_attempt.container_ref, &ctx.task_attempt.branch)
+        else {
+            return Ok(None);
+        };
+        let worktree_path = Path::new(container_ref);
+
+        let mut commits = None;
+        if ctx.execution_process.status == ExecutionProcessStatus::Completed
+            && let Some(store) = self.get_msg_store_by_id(&ctx.execution_process.id).await
+        {
+            let stdout: String = store
+                .get_history()
+                .into_iter()
+                .filter_map(|msg| match msg {
+                    LogMsg::Stdout(chunk) => Some(chunk),
+                    _ => None,
+                })
+                .collect();
+            let answer = CodingAgent::from_profile_variant_label(&request.profile_variant_label)?
+                .final_message(&stdout)
+                .unwrap_or(stdout);
+            let files: Vec<String> = self
+                .git()
+                .get_diffs(
+                    DiffTarget::Worktree {
+                        worktree_path,
+                        branch_name: branch,
+                        base_branch: &ctx.task_attempt.base_branch,
+                    },
+                    None,
+                )?
+                .iter()
+                .map(GitService::diff_path)
+                .collect();
+            commits = parse_commit_plan(&answer, &files);
+        }
+        let Some(commits) = commits else {
+            let plan = AttemptCommitPlan::finish(
+                &self.db().pool,
+                plan.id,
+                CommitPlanStatus::Failed,
+                &[],
+                None,
+                Some("The agent finished without a commit plan"),
+            )
+            .await?;
+            return Ok(Some(plan));
+        };
+
+        let plan = match self.git().split_into_commits(
+            worktree_path,
+            &ctx.task_attempt.base_branch,
+            &commits,
+            REMAINING_CHANGES_MESSAGE,
+        ) {
+            Ok(original_head) => {
+                AttemptCommitPlan::finish(
+                    &self.db().pool,
+                    plan.id,
+                    CommitPlanStatus::Applied,
+                    &commits,
+                    Some(&original_head),
+                    None,
+                )
+                .await?
+            }
+            Err(e) => {
+                AttemptCommitPlan::finish(
+                    &self.db().pool,
+                    plan.id,
+                    CommitPlanStatus::Failed,
+                    &commits,
+                    None,
+                    Some(&e.to_string()),
+                )
+                .await?
+            }
+        };
+        Ok(Some(plan))
+    }
+
+    /// Repository files relevant to the task for the initial prompt, when the project packs
+    /// them. Packing is best effort, the attempt starts without it on failure
+    #[tracing::instrument(skip_all, fields(task_id = %task.id))]
+    async fn context_pack(&self, project_id: Uuid, worktree_path: &Path, task: &Task) -> String {
+        let settings =
+            match ProjectContextPacking::find_by_project_id(&self.db().pool, project_id).await {
+                Ok(Some(settings)) if settings.enabled => settings,
+                Ok(_) => return String::new(),
+                Err(e) => {
+                    tracing::warn!(
+                        "Failed to load context packing of project {}: {}",
+                        project_id,
+                        e
+                    );
+                    return String::new();
+                }
+            };
+        let worktree_path = worktree_path.to_path_buf();
+        let task_text = format!(
+            "{}\n{}",
+            task.title,
+            task.description.as_deref().unwrap_or_default()
+        );
+        let packed = tokio::task::spawn_blocking(move || {
+            ContextPacker::from_project(&settings)?.pack(&worktree_path, &task_text)
+        })
+        .await;
+        match packed {
+            Ok(Ok(section)) => section,
+            Ok(Err(e)) => {
+                tracing::warn!("Failed to pack context for task {}: {}", task.id, e);
+                String::new()
+            }
+            Err(e) => {
+                tracing::warn!("Context packing for task {} panicked: {}", task.id, e);
+                String::new()
+            }
+        }
+    }
+
+    /// Stdout of a process as stored in the database, `None` before any was stored
+    async fn stored_stdout(&self, execution_id: Uuid) -> Result<Option<String>, ContainerError> {
+        let Some(logs) =
+            ExecutionProcessLogs::find_by_execution_id(&self.db().pool, execution_id).await?
+        else {
+            return Ok(None);
+        };
+        Ok(Some(
+            logs.parse_logs()
+                .unwrap_or_default()
+                .into_iter()
+                .filter_map(|msg| match msg {
+                    LogMsg::Stdout(chunk) => Some(chunk),
+                    _ => None,
+                })
+                .collect(),
+        ))
+    }
+
+    /// Normalized conversation of a coding agent process, empty for other processes. Logs of
+    /// processes no longer in memory are normalized again from the database.
+    async fn normalized_entries(
+        &self,
+        process: &ExecutionProcess,
+    ) -> Result<Vec<NormalizedEntry>, ContainerError> {
+        if let Some(store) = self.get_msg_store_by_id(&process.id).await {
+            return Ok(log_export::normalized_entries(&store.get_history()));
+        }
+        let label = match process.executor_action()?.typ() {
+            ExecutorActionType::CodingAgentInitialRequest(request) => {
+                request.profile_variant_label.clone()
+            }
+            ExecutorActionType::CodingAgentFollowUpRequest(request) => {
+                request.profile_variant_label.clone()
+            }
+            _ => return Ok(Vec::new()),
+        };
+        let Ok(agent) = CodingAgent::from_profile_variant_label(&label) else {
+            return Ok(Vec::new());
+        };
+        let Some(logs) =
+            ExecutionProcessLogs::find_by_execution_id(&self.db().pool, process.id).await?
+        else {
+            return Ok(Vec::new());
+        };
+        let task_attempt = process
+            .parent_task_attempt(&self.db().pool)
+            .await?
+            .ok_or(SqlxError::RowNotFound)?;
+
+        let store = Arc::new(MsgStore::new());
+        for msg in logs.parse_logs().unwrap_or_default() {
+            if matches!(msg, LogMsg::Stdout(_) | LogMsg::Stderr(_)) {
+                store.push(msg);
+            }
+        }
+        store.push_finished();
+        agent.normalize_logs(
+            store.clone(),
+            &self.task_attempt_to_current_dir(&task_attempt),
+        );
+        // The normalizers hold on to the store until they've read all of it
+        let deadline = tokio::time::Instant::now() + RENORMALIZE_TIMEOUT;
+        while Arc::strong_count(&store) > 1 && tokio::time::Instant::now() < deadline {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        Ok(log_export::normalized_entries(&store.get_history()))
+    }
+
+    /// Tool calls of the attempt's agent runs, oldest first, with the profiles that ran
+    async fn attempt_tool_calls(
+        &self,
+        task_attempt_id: Uuid,
+    ) -> Result<(Vec<ToolCall>, Vec<ProfileVariantLabel>), ContainerError> {
+        let mut calls = Vec::new();
+        let mut labels: Vec<ProfileVariantLabel> = Vec::new();
+        for process in
+            ExecutionProcess::find_by_task_attempt_id(&self.db().pool, task_attempt_id).await?
+        {
+            let label = match process.executor_action()?.typ() {
+                ExecutorActionType::CodingAgentInitialRequest(request) => {
+                    request.profile_variant_label.clone()
+                }
+                ExecutorActionType::CodingAgentFollowUpRequest(request) => {
+                    request.profile_variant_label.clone()
+                }
+                _ => continue,
+            };
+            if let (Some(stdout), Ok(agent)) = (
+                self.stored_stdout(process.id).await?,
+                CodingAgent::from_profile_variant_label(&label),
+            ) {
+                calls.extend(agent.tool_calls(&stdout));
+            }
+            if !labels.contains(&label) {
+                labels.push(label);
+            }
+        }
+        Ok((calls, labels))
+    }
+
+    /// Earlier attempts of the task, oldest first, with what their coding agent runs did
+    #[tracing::instrument(skip_all, fields(attempt_id = %task_attempt.id))]
+    async fn prior_attempts(
+        &self,
+        task_attempt: &TaskAttempt,
+    ) -> Result<Vec<PriorAttempt>, ContainerError> {
+        let pool = &self.db().pool;
+        let mut attempts = Vec::new();
+        for attempt in TaskAttempt::fetch_all(pool, Some(task_attempt.task_id))
+            .await?
+            .into_iter()
+            .rev()
+            .filter(|attempt| attempt.id != task_attempt.id)
+        {
+            let mut runs = Vec::new();
+            let mut profile = attempt.profile.clone();
+            for process in ExecutionProcess::find_by_task_attempt_id(pool, attempt.id).await? {
+                if process.run_reason != ExecutionProcessRunReason::CodingAgent {
+                    continue;
+                }
+                let (prompt, label) = match process.executor_action()?.typ() {
+                    ExecutorActionType::CodingAgentInitialRequest(request) => (
+                        request.prompt.clone(),
+                        request.profile_variant_label.clone(),
+                    ),
+                    ExecutorActionType::CodingAgentFollowUpRequest(request) => (
+                        request.prompt.clone(),
+                        request.profile_variant_label.clone(),
+                    ),
+                    _ => continue,
+                };
+                if runs.is_empty() {
+                    profile = match &label.variant {
+                        Some(variant) => format!("{} {}", label.profile, variant),
+                        None => label.profile.clone(),
+                    };
+                }
+                let final_message = match (
+                    self.stored_stdout(process.id).await?,
+                    CodingAgent::from_profile_variant_label(&label),
+                ) {
+                    (Some(stdout), Ok(agent)) => agent.final_message(&stdout),
+                    _ => None,
+                };
+                runs.push(PriorRun {
+                    prompt,
+                    status: process.status,
+                    final_message,
+                });
+            }
+            attempts.push(PriorAttempt {
+                profile,
+                merged: attempt.merge_commit.is_some() || attempt.pr_merged_at.is_some(),
+                runs,
+                latest_test_run: TestRun::find_by_task_attempt_id(pool, attempt.id)
+                    .await?
+                    .into_iter()
+                    .next(),
+                latest_review: AttemptReview::find_by_task_attempt_id(pool, attempt.id)
+                    .await?
+                    .into_iter()
+                    .next(),
+            });
+        }
+        Ok(attempts)
+    }
+
+    /// Prompts and variants of the attempt's coding agent runs, oldest first
+    async fn agent_requests(
+        &self,
+        task_attempt_id: Uuid,
+    ) -> Result<Vec<(String, Option<String>)>, ContainerError> {
+        Ok(
+            ExecutionProcess::find_by_task_attempt_id(&self.db().pool, task_attempt_id)
+                .await?
+                .iter()
+                .filter(|process| process.run_reason == ExecutionProcessRunReason::CodingAgent)
+                .filter_map(|process| match process.executor_action().ok()?.typ() {
+                    ExecutorActionType::CodingAgentInitialRequest(request) => Some((
+                        request.prompt.clone(),
+                        request.profile_variant_label.variant.clone(),
+                    )),
+                    ExecutorActionType::CodingAgentFollowUpRequest(request) => Some((
+                        request.prompt.clone(),
+                        request.profile_variant_label.variant.clone(),
+                    )),
+                    _ => None,
+                })
+                .collect(),
+        )
+    }
+
+    /// Send the output of a failed lint stage back to the agent, when the repository asks
+    /// for auto-fixes. `None` when no follow-up was sent.
+    async fn dispatch_lint_fix(
+        &self,
+        ctx: &ExecutionContext,
+    ) -> Result<Option<ExecutionProcess>, ContainerError> {
+        if ctx.execution_process.status != ExecutionProcessStatus::Failed {
+            return Ok(None);
+        }
+        let ExecutorActionType::ScriptRequest(script) =
+            ctx.execution_process.executor_action()?.typ()
+        else {
+            return Ok(None);
+        };
+        if script.context != ScriptContext::LintScript {
+            return Ok(None);
+        }
+        let project = ctx
+            .task
+            .parent_project(&self.db().pool)
+            .await?
+            .ok_or(SqlxError::RowNotFound)?;
+        let repo_config = RepoConfig::load(
+            self.git(),
+            &project.git_repo_path,
+            &ctx.task_attempt.base_branch,
+        )?;
+        if !repo_config.lint_auto_fix {
+            return Ok(None);
+        }
+
+        let agent_requests = self.agent_requests(ctx.task_attempt.id).await?;
+        let rounds = consecutive_lint_fixes(agent_requests.iter().map(|(p, _)| p.as_str()));
+        if rounds >= MAX_LINT_FIX_ROUNDS {
+            tracing::info!(
+                "Lint still failing for task attempt {} after {} fixes, handing it back",
+                ctx.task_attempt.id,
+                rounds
+            );
+            return Ok(None);
+        }
+
+        let output: String = match self.get_msg_store_by_id(&ctx.execution_process.id).await {
+            Some(store) => store
+                .get_history()
+                .into_iter()
+                .filter_map(|msg| match msg {
+                    LogMsg::Stdout(chunk) | LogMsg::Stderr(chunk) => Some(chunk),
+                    _ => None,
+                })
+                .collect(),
+            None => String::new(),
+        };
+        // The fix keeps the variant the agent last ran with
+        let variant = agent_requests
+            .last()
+            .and_then(|(_, variant)| variant.clone());
+        let execution_process = self
+            .start_follow_up(
+                &ctx.task_attempt,
+                lint_fix_prompt(&script.script, &output),
+                variant,
+            )
+            .await?;
+        Ok(Some(execution_process))
+    }
+
+    /// Ask the agent to revert changes outside the project's path rules once its chain
+    /// completed, when the project asks for it. Only one revert is requested in a row, a
+    /// second violation is handed back to the user. `None` when no follow-up was sent.
+    async fn dispatch_path_revert(
+        &self,
+        ctx: &ExecutionContext,
+    ) -> Result<Option<ExecutionProcess>, ContainerError> {
+        if ctx.execution_process.status != ExecutionProcessStatus::Completed {
+            return Ok(None);
+        }
+        let Some(rules) =
+            ProjectPathRules::find_by_project_id(&self.db().pool, ctx.task.project_id).await?
+        else {
+            return Ok(None);
+        };
+        if !rules.revert_violations {
+            return Ok(None);
+        }
+        let (Some(container_ref), Some(branch)) =
+            (&ctx.task_attempt.container_ref, &ctx.task_attempt.branch)
+        else {
+            return Ok(None);
+        };
+        let violations = PathRules::from_project(&rules)?.worktree_violations(
+            Path::new(container_ref),
+            branch,
+            &ctx.task_attempt.base_branch,
+        )?;
+        if violations.is_empty() {
+            return Ok(None);
+        }
+
+        let agent_requests = self.agent_requests(ctx.task_attempt.id).await?;
+        let Some((last_prompt, variant)) = agent_requests.last() else {
+            return Ok(None);
+        };
+        if is_path_revert_prompt(last_prompt) {
+            tracing::info!(
+                "Task attempt {} still changes {} out-of-scope path(s) after a revert, handing it back",
+                ctx.task_attempt.id,
+                violations.len()
+            );
+            return Ok(None);
+        }
+        let execution_process = self
+            .start_follow_up(
+                &ctx.task_attempt,
+                path_revert_prompt(&violations),
+                variant.clone(),
+            )
+            .await?;
+        Ok(Some(execution_process))
+    }
+
     async fn stream_raw_logs(
         &self,
         id: &Uuid,
@@ -418,6 +1548,33 @@ pub trait ContainerService {
                                 );
                             }
                         }
+                        LogMsg::Cost(cost_usd) => {
+                            if let Err(e) =
+                                ExecutionCost::record(&db.pool, execution_id, *cost_usd).await
+                            {
+                                tracing::error!(
+                                    "Failed to record cost for execution process {}: {}",
+                                    execution_id,
+                                    e
+                                );
+                            }
+                        }
+                        LogMsg::Tokens(usage) => {
+                            if let Err(e) = ExecutionTokenUsage::record(
+                                &db.pool,
+                                execution_id,
+                                usage.input_tokens as i64,
+                                usage.output_tokens as i64,
+                            )
+                            .await
+                            {
+                                tracing::error!(
+                                    "Failed to record tokens for execution process {}: {}",
+                                    execution_id,
+                                    e
+                                );
+                            }
+                        }
                         LogMsg::Finished => {
                             break;
                         }
@@ -428,13 +1585,20 @@ pub trait ContainerService {
         })
     }
 
+    /// Start the attempt's execution chain. With `carry_over_history` the prompt summarizes
+    /// the task's earlier attempts.
     async fn start_attempt(
         &self,
         task_attempt: &TaskAttempt,
         profile_variant_label: ProfileVariantLabel,
+        carry_over_history: bool,
     ) -> Result<ExecutionProcess, ContainerError> {
-        // Create container
-        self.create(task_attempt).await?;
+        if self.shutdown().is_draining() {
+            return Err(ContainerError::ShuttingDown);
+        }
+        if self.agents_paused().await {
+            return Err(ContainerError::AgentsPaused);
+        }
 
         // Get parent task
         let task = task_attempt
@@ -447,25 +1611,93 @@ pub trait ContainerService {
             .parent_project(&self.db().pool)
             .await?
             .ok_or(SqlxError::RowNotFound)?;
+        self.ensure_within_disk_quota(&project).await?;
+
+        // Create container, unless one was set up ahead of time
+        let warm = self.claim_warm_worktree(task_attempt).await?.is_some();
+        if !warm {
+            self.create(task_attempt).await?;
+        }
 
         // // Get latest version of task attempt
         let task_attempt = TaskAttempt::find_by_id(&self.db().pool, task_attempt.id)
             .await?
             .ok_or(SqlxError::RowNotFound)?;
+        let linked_worktrees =
+            linked_repos::create_worktrees(&self.db().pool, self.git(), &task_attempt).await?;
 
-        let cleanup_action = project.cleanup_script.map(|script| {
-            Box::new(ExecutorAction::new(
+        let repo_config = RepoConfig::load(
+            self.git(),
+            &project.git_repo_path,
+            &task_attempt.base_branch,
+        )?;
+
+        // Attachments are copied into the worktree and referenced from the prompt
+        let attachments = TaskAttachment::find_by_task_id(&self.db().pool, task.id).await?;
+        let staged = AttachmentStore::default()
+            .stage(
+                &self.task_attempt_to_current_dir(&task_attempt),
+                &attachments,
+            )
+            .await?;
+        let agent = CodingAgent::from_profile_variant_label(&profile_variant_label)?;
+        let context = self
+            .context_pack(
+                project.id,
+                &self.task_attempt_to_current_dir(&task_attempt),
+                &task,
+            )
+            .await;
+        let history = if carry_over_history {
+            history_prompt(&self.prior_attempts(&task_attempt).await?)
+        } else {
+            String::new()
+        };
+        let mut prompt = format!(
+            "{}{}{}{}{}",
+            task.to_prompt(),
+            linked_repos_prompt(&project.name, &linked_worktrees),
+            attachments_prompt(
+                "Attachments (files in the worktree, read them as needed)",
+                &staged,
+                &agent
+            ),
+            history,
+            context
+        );
+        let planning = AttemptPlan::find_by_task_attempt_id(&self.db().pool, task_attempt.id)
+            .await?
+            .is_some_and(|plan| plan.status == PlanStatus::Planning);
+        if planning {
+            prompt = plan_prompt(&prompt);
+        }
+
+        // The test stage runs last, on the worktree as it will be reviewed
+        let test_stage = test_action(&project);
+        let cleanup_action = match project.cleanup_script {
+            Some(script) => Some(Box::new(ExecutorAction::new(
                 ExecutorActionType::ScriptRequest(ScriptRequest {
                     script,
                     language: ScriptRequestLanguage::Bash,
                     context: ScriptContext::CleanupScript,
                 }),
-                None,
-            ))
-        });
+                test_stage,
+            ))),
+            None => test_stage,
+        };
+        // A planning phase changes nothing, the stages run after the approved plan instead
+        let after_agent_action = if planning {
+            None
+        } else {
+            repo_config.with_lint(repo_config.with_verification(cleanup_action))
+        };
 
         // Choose whether to execute the setup_script or coding agent first
-        let execution_process = if let Some(setup_script) = project.setup_script {
+        let setup_script = repo_config
+            .setup_script
+            .or(project.setup_script)
+            .filter(|_| !warm);
+        let execution_process = if let Some(setup_script) = setup_script {
             let executor_action = ExecutorAction::new(
                 ExecutorActionType::ScriptRequest(ScriptRequest {
                     script: setup_script,
@@ -475,10 +1707,10 @@ pub trait ContainerService {
                 // once the setup script is done, run the initial coding agent request
                 Some(Box::new(ExecutorAction::new(
                     ExecutorActionType::CodingAgentInitialRequest(CodingAgentInitialRequest {
-                        prompt: task.to_prompt(),
+                        prompt,
                         profile_variant_label,
                     }),
-                    cleanup_action,
+                    after_agent_action,
                 ))),
             );
 
@@ -491,10 +1723,10 @@ pub trait ContainerService {
         } else {
             let executor_action = ExecutorAction::new(
                 ExecutorActionType::CodingAgentInitialRequest(CodingAgentInitialRequest {
-                    prompt: task.to_prompt(),
+                    prompt,
                     profile_variant_label,
                 }),
-                cleanup_action,
+                after_agent_action,
             );
 
             self.start_execution(
@@ -507,12 +1739,205 @@ pub trait ContainerService {
         Ok(execution_process)
     }
 
+    /// Continue an attempt's agent session with another prompt, optionally switching to
+    /// another variant of the attempt's profile
+    #[tracing::instrument(skip_all, fields(attempt_id = %task_attempt.id))]
+    async fn start_follow_up(
+        &self,
+        task_attempt: &TaskAttempt,
+        prompt: String,
+        variant: Option<String>,
+    ) -> Result<ExecutionProcess, ContainerError> {
+        if self.agents_paused().await {
+            return Err(ContainerError::AgentsPaused);
+        }
+        // Get session_id with simple query
+        let session_id = ExecutionProcess::find_latest_session_id_by_task_attempt(
+            &self.db().pool,
+            task_attempt.id,
+        )
+        .await?
+        .ok_or(ContainerError::TaskAttemptError(
+            TaskAttemptError::ValidationError(
+                "Couldn't find a prior CodingAgent execution that already has a session_id"
+                    .to_string(),
+            ),
+        ))?;
+
+        // Get ExecutionProcess for profile data
+        let latest_execution_process =
+            ExecutionProcess::find_latest_by_task_attempt_and_run_reason(
+                &self.db().pool,
+                task_attempt.id,
+                &ExecutionProcessRunReason::CodingAgent,
+            )
+            .await?
+            .ok_or(ContainerError::TaskAttemptError(
+                TaskAttemptError::ValidationError(
+                    "Couldn't find initial coding agent process, has it run yet?".to_string(),
+                ),
+            ))?;
+        let initial_profile_variant_label = match &latest_execution_process
+            .executor_action()
+            .map_err(|e| {
+                ContainerError::TaskAttemptError(TaskAttemptError::ValidationError(e.to_string()))
+            })?
+            .typ
+        {
+            ExecutorActionType::CodingAgentInitialRequest(request) => {
+                Ok(request.profile_variant_label.clone())
+            }
+            ExecutorActionType::CodingAgentFollowUpRequest(request) => {
+                Ok(request.profile_variant_label.clone())
+            }
+            _ => Err(ContainerError::TaskAttemptError(
+                TaskAttemptError::ValidationError(
+                    "Couldn't find profile from initial request".to_string(),
+                ),
+            )),
+        }?;
+
+        let profile_variant_label = ProfileVariantLabel {
+            profile: initial_profile_variant_label.profile,
+            variant,
+        };
+
+        // Get parent task
+        let task = task_attempt
+            .parent_task(&self.db().pool)
+            .await?
+            .ok_or(SqlxError::RowNotFound)?;
+
+        // Get parent project
+        let project = task
+            .parent_project(&self.db().pool)
+            .await?
+            .ok_or(SqlxError::RowNotFound)?;
+
+        // The test stage runs last, on the worktree as it will be reviewed
+        let test_stage = test_action(&project);
+        let cleanup_action = match project.cleanup_script {
+            Some(script) => Some(Box::new(ExecutorAction::new(
+                ExecutorActionType::ScriptRequest(ScriptRequest {
+                    script,
+                    language: ScriptRequestLanguage::Bash,
+                    context: ScriptContext::CleanupScript,
+                }),
+                test_stage,
+            ))),
+            None => test_stage,
+        };
+
+        let repo_config = RepoConfig::load(
+            self.git(),
+            &project.git_repo_path,
+            &task_attempt.base_branch,
+        )?;
+
+        // Mention reference files added to the context folder since the agent last ran
+        let mut prompt = prompt;
+        if let Some(worktree) = task_attempt.container_ref.as_deref() {
+            let new_files: Vec<_> = ContextFolder::new(std::path::Path::new(worktree))
+                .list()
+                .await?
+                .iter()
+                .filter(|file| file.added_at >= latest_execution_process.started_at)
+                .map(Into::into)
+                .collect();
+            let agent = CodingAgent::from_profile_variant_label(&profile_variant_label)?;
+            prompt.push_str(&attachments_prompt(
+                "Reference files added to the worktree, read them as needed",
+                &new_files,
+                &agent,
+            ));
+        }
+
+        let follow_up_action = ExecutorAction::new(
+            ExecutorActionType::CodingAgentFollowUpRequest(CodingAgentFollowUpRequest {
+                prompt,
+                session_id,
+                profile_variant_label,
+            }),
+            repo_config.with_lint(repo_config.with_verification(cleanup_action)),
+        );
+
+        self.start_execution(
+            task_attempt,
+            &follow_up_action,
+            &ExecutionProcessRunReason::CodingAgent,
+        )
+        .await
+    }
+
+    /// Send the next queued follow-up of an attempt unless something is still running in
+    /// it. Returns `None` when the attempt is busy or its queue is empty.
+    #[tracing::instrument(skip_all, fields(attempt_id = %task_attempt.id, decision))]
+    async fn dispatch_queued_follow_up(
+        &self,
+        task_attempt: &TaskAttempt,
+    ) -> Result<Option<ExecutionProcess>, ContainerError> {
+        // The idle check and the start happen under one lock, so an attempt finishing while
+        // a prompt is being queued can't send two follow-ups at once
+        let _guard = FOLLOW_UP_DISPATCH.lock().await;
+        // Queued prompts wait for the agents to be resumed
+        if self.agents_paused().await {
+            Span::current().record("decision", "agents_paused");
+            return Ok(None);
+        }
+        let busy = ExecutionProcess::find_by_task_attempt_id(&self.db().pool, task_attempt.id)
+            .await?
+            .iter()
+            .any(|process| {
+                process.status == ExecutionProcessStatus::Running
+                    && process.run_reason != ExecutionProcessRunReason::DevServer
+            });
+        if busy {
+            Span::current().record("decision", "busy");
+            return Ok(None);
+        }
+        let Some(next) = QueuedFollowUp::find_by_task_attempt_id(&self.db().pool, task_attempt.id)
+            .await?
+            .into_iter()
+            .next()
+        else {
+            Span::current().record("decision", "queue_empty");
+            return Ok(None);
+        };
+        Span::current().record("decision", "send");
+
+        tracing::info!(
+            "Sending queued follow-up {} for task attempt {}",
+            next.id,
+            task_attempt.id
+        );
+        let execution_process = self
+            .start_follow_up(task_attempt, next.prompt, next.variant)
+            .await?;
+        QueuedFollowUp::delete(&self.db().pool, next.id).await?;
+        Ok(Some(execution_process))
+    }
+
+    #[tracing::instrument(
+        skip_all,
+        fields(attempt_id = %task_attempt.id, ?run_reason, execution_process_id, suspended)
+    )]
     async fn start_execution(
         &self,
         task_attempt: &TaskAttempt,
         executor_action: &ExecutorAction,
         run_reason: &ExecutionProcessRunReason,
     ) -> Result<ExecutionProcess, ContainerError> {
+        // While draining, only let already running chains (setup -> agent -> cleanup) continue
+        if self.shutdown().is_draining()
+            && (run_reason == &ExecutionProcessRunReason::DevServer
+                || matches!(
+                    executor_action.typ(),
+                    ExecutorActionType::CodingAgentFollowUpRequest(_)
+                ))
+        {
+            return Err(ContainerError::ShuttingDown);
+        }
+
         // Update task status to InProgress when starting an attempt
         let task = task_attempt
             .parent_task(&self.db().pool)
@@ -533,6 +1958,10 @@ pub trait ContainerService {
         let execution_process =
             ExecutionProcess::create(&self.db().pool, &create_execution_process, Uuid::new_v4())
                 .await?;
+        Span::current().record(
+            "execution_process_id",
+            tracing::field::display(execution_process.id),
+        );
 
         if let Some(prompt) = match executor_action.typ() {
             ExecutorActionType::CodingAgentInitialRequest(coding_agent_request) => {
@@ -563,6 +1992,16 @@ pub trait ContainerService {
             .start_execution_inner(task_attempt, &execution_process, executor_action)
             .await?;
 
+        // The next step of a chain that was running when the agents were paused starts
+        // suspended, and continues with them
+        let suspend =
+            run_reason != &ExecutionProcessRunReason::DevServer && self.agents_paused().await;
+        Span::current().record("suspended", suspend);
+        if suspend {
+            self.suspend_execution(&execution_process).await?;
+            ExecutionPause::create(&self.db().pool, execution_process.id, task_attempt.id).await?;
+        }
+
         // Start processing normalised logs for executor requests and follow ups
         match executor_action.typ() {
             ExecutorActionType::CodingAgentInitialRequest(request) => {
@@ -623,16 +2062,19 @@ pub trait ContainerService {
         };
 
         // Determine the run reason of the next action
-        let next_run_reason = match ctx.execution_process.run_reason {
-            ExecutionProcessRunReason::SetupScript => ExecutionProcessRunReason::CodingAgent,
-            ExecutionProcessRunReason::CodingAgent => ExecutionProcessRunReason::CleanupScript,
-            _ => {
-                tracing::warn!(
-                    "Unexpected run reason: {:?}, defaulting to current reason",
-                    ctx.execution_process.run_reason
-                );
-                ctx.execution_process.run_reason.clone()
+        let next_run_reason = match next_action.typ() {
+            ExecutorActionType::CodingAgentInitialRequest(_)
+            | ExecutorActionType::CodingAgentFollowUpRequest(_) => {
+                ExecutionProcessRunReason::CodingAgent
             }
+            ExecutorActionType::ScriptRequest(script) => match script.context {
+                ScriptContext::SetupScript => ExecutionProcessRunReason::SetupScript,
+                ScriptContext::CleanupScript
+                | ScriptContext::VerificationScript
+                | ScriptContext::TestScript
+                | ScriptContext::LintScript => ExecutionProcessRunReason::CleanupScript,
+                ScriptContext::DevServer => ExecutionProcessRunReason::DevServer,
+            },
         };
 
         self.start_execution(&ctx.task_attempt, next_action, &next_run_reason)
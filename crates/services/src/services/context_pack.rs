@@ -0,0 +1,296 @@
+use std::path::Path;
+
+use db::models::project_context_packing::ProjectContextPacking;
+use globset::{Glob, GlobSet, GlobSetBuilder};
+use ignore::WalkBuilder;
+use thiserror::Error;
+
+use super::git::{GitService, GitServiceError};
+
+/// Rough size of a token, budgets are converted to characters with it
+const CHARS_PER_TOKEN: usize = 4;
+/// Lines shown from the start of each packed file
+const EXCERPT_LINES: usize = 40;
+/// Larger files are never packed
+const MAX_FILE_BYTES: u64 = 200_000;
+const MAX_KEYWORDS: usize = 20;
+
+/// Files matching an include glob rank above any other file
+const INCLUDED_SCORE: usize = 1_000;
+const PATH_KEYWORD_SCORE: usize = 3;
+const CONTENT_KEYWORD_SCORE: usize = 1;
+const RECENT_SCORE: usize = 2;
+
+const CONTEXT_HEADING: &str = "Repository files that look relevant to this task (the start of each file is shown, read them in full as needed):";
+
+const STOPWORDS: &[&str] = &[
+    "about", "after", "also", "been", "before", "does", "each", "from", "have", "into", "just",
+    "like", "make", "more", "must", "need", "only", "other", "should", "some", "such", "sure",
+    "than", "that", "their", "them", "then", "there", "these", "they", "this", "those", "what",
+    "when", "where", "which", "while", "will", "with", "would", "your",
+];
+
+#[derive(Debug, Error)]
+pub enum ContextPackError {
+    #[error("Invalid path glob '{0}': {1}")]
+    InvalidGlob(String, globset::Error),
+    #[error(transparent)]
+    Git(#[from] GitServiceError),
+    #[error(transparent)]
+    Walk(#[from] ignore::Error),
+}
+
+/// Text file of the repository, path relative to its root
+#[derive(Debug, Clone)]
+struct RepoFile {
+    path: String,
+    content: String,
+}
+
+/// Picks the files of a repository relevant to a task and packs them into a prompt section
+#[derive(Debug, Clone)]
+pub struct ContextPacker {
+    include: GlobSet,
+    recent_commits: usize,
+    keyword_search: bool,
+    budget_chars: usize,
+}
+
+impl ContextPacker {
+    pub fn new(
+        include_paths: &[String],
+        recent_commits: usize,
+        keyword_search: bool,
+        token_budget: usize,
+    ) -> Result<Self, ContextPackError> {
+        let mut builder = GlobSetBuilder::new();
+        for pattern in include_paths {
+            let glob = Glob::new(pattern)
+                .map_err(|e| ContextPackError::InvalidGlob(pattern.clone(), e))?;
+            builder.add(glob);
+        }
+        let include = builder
+            .build()
+            .map_err(|e| ContextPackError::InvalidGlob(include_paths.join(", "), e))?;
+        Ok(Self {
+            include,
+            recent_commits,
+            keyword_search,
+            budget_chars: token_budget * CHARS_PER_TOKEN,
+        })
+    }
+
+    pub fn from_project(settings: &ProjectContextPacking) -> Result<Self, ContextPackError> {
+        Self::new(
+            &settings.include_paths,
+            settings.recent_commits.max(0) as usize,
+            settings.keyword_search,
+            settings.token_budget.max(0) as usize,
+        )
+    }
+
+    /// Prompt section packing the files relevant to `task_text`, empty when none is
+    pub fn pack(&self, repo_path: &Path, task_text: &str) -> Result<String, ContextPackError> {
+        let recent = if self.recent_commits > 0 {
+            GitService::new().recently_changed_paths(repo_path, self.recent_commits)?
+        } else {
+            Vec::new()
+        };
+        let keywords = if self.keyword_search {
+            keywords(task_text)
+        } else {
+            Vec::new()
+        };
+
+        let mut ranked: Vec<(usize, RepoFile)> = repo_files(repo_path)?
+            .into_iter()
+            .map(|file| (self.score(&file, &recent, &keywords), file))
+            .filter(|(score, _)| *score > 0)
+            .collect();
+        ranked.sort_by(|(a, a_file), (b, b_file)| b.cmp(a).then(a_file.path.cmp(&b_file.path)));
+        Ok(render(
+            ranked.into_iter().map(|(_, file)| file),
+            self.budget_chars,
+        ))
+    }
+
+    fn score(&self, file: &RepoFile, recent: &[String], keywords: &[String]) -> usize {
+        let mut score = 0;
+        if self.include.is_match(&file.path) {
+            score += INCLUDED_SCORE;
+        }
+        if recent.contains(&file.path) {
+            score += RECENT_SCORE;
+        }
+        if !keywords.is_empty() {
+            let path = file.path.to_lowercase();
+            let content = file.content.to_lowercase();
+            for keyword in keywords {
+                if path.contains(keyword.as_str()) {
+                    score += PATH_KEYWORD_SCORE;
+                }
+                if content.contains(keyword.as_str()) {
+                    score += CONTENT_KEYWORD_SCORE;
+                }
+            }
+        }
+        score
+    }
+}
+
+/// Distinct words of a task worth searching the repository for
+fn keywords(text: &str) -> Vec<String> {
+    let mut keywords: Vec<String> = Vec::new();
+    for word in text
+        .split(|c: char| !c.is_alphanumeric() && c != '_')
+        .map(str::to_lowercase)
+    {
+        if word.chars().count() < 4
+            || STOPWORDS.contains(&word.as_str())
+            || keywords.contains(&word)
+        {
+            continue;
+        }
+        keywords.push(word);
+        if keywords.len() == MAX_KEYWORDS {
+            break;
+        }
+    }
+    keywords
+}
+
+/// Text files of the repository that git doesn't ignore
+fn repo_files(repo_path: &Path) -> Result<Vec<RepoFile>, ContextPackError> {
+    let mut files = Vec::new();
+    for entry in WalkBuilder::new(repo_path)
+        .git_ignore(true)
+        .git_global(true)
+        .git_exclude(true)
+        .hidden(false)
+        .filter_entry(|entry| entry.file_name() != ".git")
+        .build()
+    {
+        let entry = entry?;
+        if !entry.file_type().is_some_and(|kind| kind.is_file())
+            || entry
+                .metadata()
+                .map_or(true, |metadata| metadata.len() > MAX_FILE_BYTES)
+        {
+            continue;
+        }
+        let Ok(bytes) = std::fs::read(entry.path()) else {
+            continue;
+        };
+        let Ok(content) = String::from_utf8(bytes) else {
+            continue;
+        };
+        let Ok(relative) = entry.path().strip_prefix(repo_path) else {
+            continue;
+        };
+        if content.contains('\0') {
+            continue;
+        }
+        files.push(RepoFile {
+            path: relative.to_string_lossy().replace('\\', "/"),
+            content,
+        });
+    }
+    Ok(files)
+}
+
+/// Excerpts of the best ranked files within the budget, the next ones by path only
+fn render(files: impl IntoIterator<Item = RepoFile>, budget_chars: usize) -> String {
+    let mut excerpts = String::new();
+    let mut listed = String::new();
+    let mut used = 0;
+    for file in files {
+        let mut lines = file.content.lines();
+        let mut excerpt = lines
+            .by_ref()
+            .take(EXCERPT_LINES)
+            .collect::<Vec<_>>()
+            .join("\n");
+        if lines.next().is_some() {
+            excerpt.push_str("\n...");
+        }
+        let entry = format!("\n\n`{}`:\n```\n{}\n```", file.path, excerpt);
+        if listed.is_empty() && used + entry.len() <= budget_chars {
+            used += entry.len();
+            excerpts.push_str(&entry);
+            continue;
+        }
+        let line = format!("\n- {}", file.path);
+        if used + line.len() > budget_chars {
+            break;
+        }
+        used += line.len();
+        listed.push_str(&line);
+    }
+
+    if excerpts.is_empty() && listed.is_empty() {
+        return String::new();
+    }
+    let mut section = format!("\n\n{CONTEXT_HEADING}{excerpts}");
+    if !listed.is_empty() {
+        section.push_str("\n\nMore files that may be relevant:");
+        section.push_str(&listed);
+    }
+    section
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn file(path: &str, content: &str) -> RepoFile {
+        RepoFile {
+            path: path.to_string(),
+            content: content.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_keywords_skip_short_words_stopwords_and_duplicates() {
+        assert_eq!(
+            keywords("Fix the login_form so that it shows Login errors, with retry"),
+            vec!["login_form", "shows", "login", "errors", "retry"]
+        );
+    }
+
+    #[test]
+    fn test_score_ranks_included_then_keyword_and_recent_files() {
+        let packer = ContextPacker::new(&["docs/*.md".to_string()], 5, true, 1000).unwrap();
+        let keywords = keywords("Add a billing export");
+        let recent = vec!["src/lib.rs".to_string()];
+
+        let included = packer.score(&file("docs/guide.md", ""), &recent, &keywords);
+        let by_path = packer.score(&file("src/billing.rs", "fn total()"), &recent, &keywords);
+        let by_content = packer.score(&file("src/api.rs", "// export"), &recent, &keywords);
+        let changed = packer.score(&file("src/lib.rs", ""), &recent, &keywords);
+        let unrelated = packer.score(&file("src/main.rs", "fn main()"), &recent, &keywords);
+
+        assert!(included > by_path && by_path > changed && changed > by_content);
+        assert_eq!(unrelated, 0);
+        assert!(ContextPacker::new(&["src/[".to_string()], 0, true, 1000).is_err());
+    }
+
+    #[test]
+    fn test_render_stays_within_budget() {
+        let long = (1..=50).map(|n| format!("line {n}")).collect::<Vec<_>>();
+        let files = vec![
+            file("a.rs", &long.join("\n")),
+            file("b.rs", &"x".repeat(2_000)),
+            file("c.rs", "short"),
+        ];
+
+        let section = render(files.clone(), 1_000);
+        assert!(section.contains("`a.rs`:\n```\nline 1\n"));
+        assert!(section.contains("line 40\n...\n```"));
+        assert!(!section.contains("line 41"));
+        // b.rs doesn't fit, it and everything after it is listed by path
+        assert!(section.ends_with("More files that may be relevant:\n- b.rs\n- c.rs"));
+
+        assert_eq!(render(files, 5), "");
+        assert_eq!(render(Vec::new(), 1_000), "");
+    }
+}
@@ -0,0 +1,242 @@
+use std::{collections::HashMap, path::Path, sync::OnceLock};
+
+use executors::{executors::CodingAgent, profile::ProfileVariantLabel};
+use git2::Repository;
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+use super::git::GitServiceError;
+
+const MODEL_PRICING_JSON: &str = include_str!("../../model_pricing.json");
+
+/// Rough size of a token in English text and code
+const CHARS_PER_TOKEN: u64 = 4;
+/// System prompt and tool definitions the agents send along with every turn
+const SYSTEM_TOKENS: u64 = 12_000;
+
+/// Bounds of a run: a short one answering from a few files, and a long one exploring the
+/// repository over many turns
+struct RunShape {
+    turns: u64,
+    /// Share of the repository read over the run, capped
+    explored_divisor: u64,
+    max_explored_tokens: u64,
+    output_tokens: u64,
+}
+
+const SHORT_RUN: RunShape = RunShape {
+    turns: 5,
+    explored_divisor: 20,
+    max_explored_tokens: 20_000,
+    output_tokens: 2_000,
+};
+
+const LONG_RUN: RunShape = RunShape {
+    turns: 30,
+    explored_divisor: 5,
+    max_explored_tokens: 100_000,
+    output_tokens: 20_000,
+};
+
+#[derive(Debug, Deserialize)]
+struct ModelPrice {
+    id: String,
+    /// Parts of model names the price applies to, the longest match wins
+    aliases: Vec<String>,
+    input_usd_per_mtok: f64,
+    output_usd_per_mtok: f64,
+}
+
+#[derive(Debug, Deserialize)]
+struct PricingTable {
+    models: Vec<ModelPrice>,
+    /// Model each agent uses unless its profile picks one, by the agent's profile key
+    agent_defaults: HashMap<String, String>,
+}
+
+fn pricing() -> &'static PricingTable {
+    static PRICING: OnceLock<PricingTable> = OnceLock::new();
+    PRICING.get_or_init(|| {
+        serde_json::from_str(MODEL_PRICING_JSON).expect("bundled model pricing is valid")
+    })
+}
+
+impl PricingTable {
+    fn find(&self, model: &str) -> Option<&ModelPrice> {
+        let model = model.to_lowercase();
+        self.models
+            .iter()
+            .flat_map(|price| {
+                std::iter::once(&price.id)
+                    .chain(&price.aliases)
+                    .filter(|alias| model.contains(alias.as_str()))
+                    .map(move |alias| (alias.len(), price))
+            })
+            .max_by_key(|(len, _)| *len)
+            .map(|(_, price)| price)
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, TS)]
+pub struct CostEstimateRequest {
+    pub prompt: String,
+    pub profile_variant_label: ProfileVariantLabel,
+    /// Model to price, instead of the one the profile runs
+    pub model: Option<String>,
+    /// Tokens of the repository, instead of counting its tracked files
+    pub repo_tokens: Option<u32>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, TS)]
+pub struct TokenRange {
+    pub low: u32,
+    pub high: u32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, TS)]
+pub struct CostRange {
+    pub low_usd: f64,
+    pub high_usd: f64,
+}
+
+/// What an attempt might use before it starts, from the prompt, the model and the size of
+/// the repository. Prompt caching isn't accounted for, so the high end is pessimistic.
+#[derive(Debug, Clone, PartialEq, Serialize, TS)]
+pub struct CostEstimate {
+    /// `None` when neither the profile nor the agent's default names a model
+    pub model: Option<String>,
+    pub prompt_tokens: u32,
+    pub repo_tokens: u32,
+    pub input_tokens: TokenRange,
+    pub output_tokens: TokenRange,
+    /// `None` when the model has no bundled price
+    pub cost: Option<CostRange>,
+}
+
+fn to_u32(tokens: u64) -> u32 {
+    tokens.min(u32::MAX as u64) as u32
+}
+
+pub fn count_tokens(text: &str) -> u64 {
+    (text.chars().count() as u64).div_ceil(CHARS_PER_TOKEN)
+}
+
+/// Tokens of the repository's tracked files, from the sizes its index records
+pub fn repo_tokens(repo_path: &Path) -> Result<u64, GitServiceError> {
+    let repo = Repository::open(repo_path)?;
+    let index = repo.index()?;
+    let bytes: u64 = index.iter().map(|entry| entry.file_size as u64).sum();
+    Ok(bytes / CHARS_PER_TOKEN)
+}
+
+/// `--model x`, `--model=x` or `-m x` in the agent's command
+fn configured_model(agent: &CodingAgent) -> Option<String> {
+    let value = serde_json::to_value(agent).ok()?;
+    let command = value.as_object()?.values().next()?.get("command")?;
+    let mut words: Vec<String> = command
+        .get("base")
+        .and_then(|base| base.as_str())
+        .map(|base| base.split_whitespace().map(str::to_string).collect())
+        .unwrap_or_default();
+    if let Some(params) = command.get("params").and_then(|params| params.as_array()) {
+        words.extend(params.iter().filter_map(|p| p.as_str()).map(str::to_string));
+    }
+    let mut words = words.iter();
+    while let Some(word) = words.next() {
+        if let Some(model) = word.strip_prefix("--model=") {
+            return Some(model.to_string());
+        }
+        if word == "--model" || word == "-m" {
+            return words.next().cloned();
+        }
+    }
+    None
+}
+
+fn default_model(agent: &CodingAgent) -> Option<String> {
+    let value = serde_json::to_value(agent).ok()?;
+    let key = value.as_object()?.keys().next()?;
+    pricing().agent_defaults.get(key).cloned()
+}
+
+fn run_tokens(shape: &RunShape, prompt_tokens: u64, repo_tokens: u64) -> (u64, u64) {
+    let explored = (repo_tokens / shape.explored_divisor).min(shape.max_explored_tokens);
+    // The context grows as files are read, so the average turn carries half of them
+    let input = shape.turns * (SYSTEM_TOKENS + prompt_tokens + explored / 2);
+    (input, shape.output_tokens)
+}
+
+pub fn estimate(
+    agent: &CodingAgent,
+    model: Option<String>,
+    prompt: &str,
+    repo_tokens: u64,
+) -> CostEstimate {
+    let model = model
+        .filter(|model| !model.trim().is_empty())
+        .or_else(|| configured_model(agent))
+        .or_else(|| default_model(agent));
+    let prompt_tokens = count_tokens(prompt);
+    let (input_low, output_low) = run_tokens(&SHORT_RUN, prompt_tokens, repo_tokens);
+    let (input_high, output_high) = run_tokens(&LONG_RUN, prompt_tokens, repo_tokens);
+    let cost = model
+        .as_deref()
+        .and_then(|model| pricing().find(model))
+        .map(|price| {
+            let usd = |input: u64, output: u64| {
+                (input as f64 * price.input_usd_per_mtok
+                    + output as f64 * price.output_usd_per_mtok)
+                    / 1_000_000.0
+            };
+            CostRange {
+                low_usd: usd(input_low, output_low),
+                high_usd: usd(input_high, output_high),
+            }
+        });
+    CostEstimate {
+        model,
+        prompt_tokens: to_u32(prompt_tokens),
+        repo_tokens: to_u32(repo_tokens),
+        input_tokens: TokenRange {
+            low: to_u32(input_low),
+            high: to_u32(input_high),
+        },
+        output_tokens: TokenRange {
+            low: to_u32(output_low),
+            high: to_u32(output_high),
+        },
+        cost,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_estimate_prices_the_model_the_profile_picks() {
+        let agent: CodingAgent = serde_json::from_value(serde_json::json!({
+            "CLAUDE_CODE": {
+                "command": { "base": "claude", "params": ["-p", "--model", "opus"] },
+                "plan": false
+            }
+        }))
+        .unwrap();
+        assert_eq!(
+            pricing().find("claude-opus-4-1-20250805").unwrap().id,
+            "claude-opus-4"
+        );
+        assert_eq!(pricing().find("gpt-5-mini").unwrap().id, "gpt-5-mini");
+
+        let priced = estimate(&agent, None, &"x".repeat(4_000), 400_000);
+        assert_eq!(priced.model.as_deref(), Some("opus"));
+        assert_eq!(priced.prompt_tokens, 1_000);
+        // 5 turns of the system prompt, the prompt and half of 20k explored tokens
+        assert_eq!(priced.input_tokens.low, 5 * (12_000 + 1_000 + 10_000));
+        let cost = priced.cost.unwrap();
+        assert!(cost.low_usd > 0.0 && cost.low_usd < cost.high_usd);
+
+        let unpriced = estimate(&agent, Some("some-local-model".into()), "hi", 0);
+        assert_eq!(unpriced.cost, None);
+    }
+}
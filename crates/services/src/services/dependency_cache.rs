@@ -0,0 +1,117 @@
+use std::{
+    io,
+    path::{Path, PathBuf},
+};
+
+use db::models::project_dependency_cache::ProjectDependencyCache;
+use executors::env::ExecutionEnv;
+use sqlx::SqlitePool;
+use thiserror::Error;
+use uuid::Uuid;
+
+#[derive(Debug, Error)]
+pub enum DependencyCacheError {
+    #[error(transparent)]
+    Database(#[from] sqlx::Error),
+    #[error(transparent)]
+    Io(#[from] io::Error),
+}
+
+/// Package caches shared by all attempts of a project. Each attempt gets a fresh worktree, so
+/// without them every attempt rebuilds Cargo's target directory and refills pnpm's store.
+/// The caches sit next to the worktrees, on the same filesystem, so pnpm can hardlink packages
+/// out of its store.
+pub struct DependencyCache {
+    settings: ProjectDependencyCache,
+    root: PathBuf,
+}
+
+impl DependencyCache {
+    /// The project's caches, `None` unless it shares at least one
+    pub async fn for_project(
+        pool: &SqlitePool,
+        project_id: Uuid,
+    ) -> Result<Option<Self>, DependencyCacheError> {
+        let settings = ProjectDependencyCache::find_by_project_id(pool, project_id).await?;
+        Ok(settings
+            .filter(|s| s.shared_cargo_target || s.shared_pnpm_store)
+            .map(|settings| Self {
+                root: utils::path::get_dependency_cache_dir().join(project_id.to_string()),
+                settings,
+            }))
+    }
+
+    fn cargo_target_dir(&self) -> Option<PathBuf> {
+        self.settings
+            .shared_cargo_target
+            .then(|| self.root.join("cargo-target"))
+    }
+
+    fn pnpm_store_dir(&self) -> Option<PathBuf> {
+        self.settings
+            .shared_pnpm_store
+            .then(|| self.root.join("pnpm-store"))
+    }
+
+    /// Creates the cache directories, done when a worktree is created
+    pub async fn prepare(&self) -> Result<(), DependencyCacheError> {
+        for dir in [self.cargo_target_dir(), self.pnpm_store_dir()]
+            .into_iter()
+            .flatten()
+        {
+            tokio::fs::create_dir_all(&dir).await?;
+        }
+        Ok(())
+    }
+
+    /// Variables pointing the package managers of an attempt's processes at the caches.
+    /// Concurrent Cargo builds wait on the shared target directory's lock instead of racing.
+    pub fn env(&self) -> ExecutionEnv {
+        let mut env = ExecutionEnv::new();
+        if let Some(dir) = self.cargo_target_dir() {
+            env.insert("CARGO_TARGET_DIR", path_string(&dir));
+        }
+        if let Some(dir) = self.pnpm_store_dir() {
+            env.insert("npm_config_store_dir", path_string(&dir));
+        }
+        env
+    }
+}
+
+fn path_string(path: &Path) -> String {
+    path.to_string_lossy().into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+
+    use super::*;
+
+    fn cache(shared_cargo_target: bool, shared_pnpm_store: bool, root: &Path) -> DependencyCache {
+        DependencyCache {
+            settings: ProjectDependencyCache {
+                project_id: Uuid::new_v4(),
+                shared_cargo_target,
+                shared_pnpm_store,
+                updated_at: Utc::now(),
+            },
+            root: root.to_path_buf(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_env_points_at_prepared_caches() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = cache(true, false, dir.path());
+        cache.prepare().await.unwrap();
+
+        let env = cache.env();
+        let target = dir.path().join("cargo-target");
+        assert_eq!(env.get("CARGO_TARGET_DIR"), Some(target.to_str().unwrap()));
+        assert!(target.is_dir());
+        // Caches the project doesn't share are left alone
+        assert_eq!(env.get("npm_config_store_dir"), None);
+        assert!(!dir.path().join("pnpm-store").exists());
+    }
+}
@@ -0,0 +1,587 @@
+use std::{path::Path, sync::Arc, time::Duration};
+
+use chrono::{DateTime, Datelike, TimeZone, Utc};
+use db::models::{
+    dependency_update::{
+        DependencyUpdateRun, DependencyUpdateRunStatus, DependencyUpdateSchedule,
+        ProjectDependencyUpdates,
+    },
+    execution_process::{ExecutionProcess, ExecutionProcessRunReason, ExecutionProcessStatus},
+    project::Project,
+    task::{CreateTask, Task, TaskStatus},
+    task_attempt::{CreateTaskAttempt, TaskAttempt},
+};
+use executors::profile::ProfileConfigs;
+use thiserror::Error;
+use tokio::sync::RwLock;
+use uuid::Uuid;
+
+use super::{
+    branch_sync::latest_nightly_sync,
+    budget::{BudgetError, SpendSummary},
+    config::Config,
+    container::{ContainerError, ContainerService},
+    evaluation::is_verification,
+    git::{GitService, GitServiceError},
+    github_auth,
+    github_service::{CreatePrRequest, GitHubRepoInfo, GitHubService, GitHubServiceError},
+    package_manager::PackageManager,
+    repo_config::{RepoConfig, RepoConfigError},
+    secret_scan::{self, SecretScanError},
+};
+
+const POLL_INTERVAL: Duration = Duration::from_secs(300);
+/// Seconds without a running execution before an attempt counts as settled, so the gap
+/// between one stage of the chain and the next isn't taken for the end
+const SETTLE_SECS: i64 = 60;
+/// Group of the packages no configured group matches
+const OTHER_GROUP: &str = "other";
+/// Only group when none are configured
+const ALL_GROUP: &str = "all";
+
+#[derive(Debug, Error)]
+pub enum DependencyUpdateError {
+    #[error(transparent)]
+    Database(#[from] sqlx::Error),
+    #[error(transparent)]
+    Git(#[from] GitServiceError),
+    #[error(transparent)]
+    GitHub(#[from] GitHubServiceError),
+    #[error(transparent)]
+    RepoConfig(#[from] RepoConfigError),
+    #[error(transparent)]
+    Budget(#[from] BudgetError),
+    #[error(transparent)]
+    Container(#[from] ContainerError),
+    #[error(transparent)]
+    SecretScan(#[from] SecretScanError),
+    #[error("Project {0} not found")]
+    ProjectNotFound(Uuid),
+    #[error("Profile not found: {0}")]
+    ProfileNotFound(String),
+    #[error("Dependency updates of this project are already running")]
+    AlreadyRunning,
+    #[error("The attempt was deleted")]
+    AttemptDeleted,
+    #[error("A GitHub token is needed to open pull requests")]
+    NoGitHubToken,
+}
+
+/// Packages one attempt updates
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlannedGroup {
+    pub name: String,
+    /// Patterns of the packages updated, every package when empty
+    pub include: Vec<String>,
+    /// Patterns of packages left alone: those of other groups and the ignored ones
+    pub exclude: Vec<String>,
+}
+
+/// Whether updates last run at `last_run_at` are due again. Projects never updated are
+/// updated at the next check.
+pub fn is_due<Tz: TimeZone>(
+    schedule: DependencyUpdateSchedule,
+    last_run_at: Option<DateTime<Utc>>,
+    now: DateTime<Tz>,
+) -> bool {
+    last_run_at.is_none_or(|at| at < latest_window(schedule, &now))
+}
+
+/// Start of the latest update window in the time zone of `now`
+fn latest_window<Tz: TimeZone>(
+    schedule: DependencyUpdateSchedule,
+    now: &DateTime<Tz>,
+) -> DateTime<Utc> {
+    let nightly = latest_nightly_sync(now);
+    match schedule {
+        DependencyUpdateSchedule::Daily => nightly,
+        DependencyUpdateSchedule::Weekly => {
+            let days = nightly
+                .with_timezone(&now.timezone())
+                .weekday()
+                .num_days_from_monday();
+            nightly - chrono::Duration::days(days.into())
+        }
+    }
+}
+
+/// One group per configured group, a package going to the first that matches it, then one
+/// for the packages none match
+pub fn plan_groups(settings: &ProjectDependencyUpdates) -> Vec<PlannedGroup> {
+    let ignored = settings.ignored.0.clone();
+    if settings.groups.is_empty() {
+        return vec![PlannedGroup {
+            name: ALL_GROUP.to_string(),
+            include: Vec::new(),
+            exclude: ignored,
+        }];
+    }
+
+    let mut planned = Vec::new();
+    let mut claimed: Vec<String> = Vec::new();
+    for group in settings.groups.iter() {
+        planned.push(PlannedGroup {
+            name: group.name.clone(),
+            include: group.patterns.clone(),
+            exclude: claimed.iter().chain(&ignored).cloned().collect(),
+        });
+        claimed.extend(group.patterns.iter().cloned());
+    }
+    planned.push(PlannedGroup {
+        name: OTHER_GROUP.to_string(),
+        include: Vec::new(),
+        exclude: claimed.into_iter().chain(ignored).collect(),
+    });
+    planned
+}
+
+fn group_label(group_name: &str) -> String {
+    if group_name == ALL_GROUP {
+        "dependencies".to_string()
+    } else {
+        format!("{group_name} dependencies")
+    }
+}
+
+pub fn task_title(group_name: &str) -> String {
+    format!("Update {}", group_label(group_name))
+}
+
+pub fn pr_title(group_name: &str) -> String {
+    format!("chore(deps): update {}", group_label(group_name))
+}
+
+pub fn task_prompt(
+    settings: &ProjectDependencyUpdates,
+    group: &PlannedGroup,
+    manager: Option<PackageManager>,
+) -> String {
+    let mut prompt = match manager {
+        Some(manager) => format!(
+            "Update the dependencies of this repository, managed with {}.\n",
+            format!("{manager:?}").to_lowercase()
+        ),
+        None => "Update the dependencies of this repository.\n".to_string(),
+    };
+    if !group.include.is_empty() {
+        prompt.push_str(&format!(
+            "Only update packages matching: {}\n",
+            group.include.join(", ")
+        ));
+    }
+    if !group.exclude.is_empty() {
+        prompt.push_str(&format!(
+            "Leave packages matching these alone: {}\n",
+            group.exclude.join(", ")
+        ));
+    }
+    if settings.allow_major {
+        prompt.push_str(
+            "Major version updates are allowed, adapt the code to their breaking changes.\n",
+        );
+    } else {
+        prompt.push_str("Stay within the current major version of every package.\n");
+    }
+    if let Some(instructions) = settings.instructions.as_deref().map(str::trim)
+        && !instructions.is_empty()
+    {
+        prompt.push_str(&format!("\n{instructions}\n"));
+    }
+    prompt.push_str(
+        "\nUpdate the lockfile along with the manifests, then build the project and run its tests, fixing what the updates break. Revert an update that can't be made to work and say why. Make no changes when nothing needs updating.\n",
+    );
+    prompt
+}
+
+/// Why a settled attempt can't be turned into a pull request, if it can't
+fn failure(processes: &[ExecutionProcess]) -> Option<&'static str> {
+    let agent_completed = processes
+        .iter()
+        .rev()
+        .find(|process| process.run_reason == ExecutionProcessRunReason::CodingAgent)
+        .is_some_and(|process| process.status == ExecutionProcessStatus::Completed);
+    if !agent_completed {
+        return Some("The agent didn't finish");
+    }
+    let verified = processes
+        .iter()
+        .rev()
+        .find(|process| is_verification(process))
+        .is_none_or(|process| process.status == ExecutionProcessStatus::Completed);
+    (!verified).then_some("Verification failed")
+}
+
+/// Whether nothing has run on an attempt for a while
+fn is_settled(processes: &[ExecutionProcess], now: DateTime<Utc>) -> bool {
+    if processes
+        .iter()
+        .any(|process| process.status == ExecutionProcessStatus::Running)
+    {
+        return false;
+    }
+    processes
+        .iter()
+        .filter_map(|process| process.completed_at)
+        .max()
+        .is_some_and(|last| (now - last).num_seconds() >= SETTLE_SECS)
+}
+
+async fn start_attempt<C: ContainerService + Sync>(
+    container: &C,
+    config: &Arc<RwLock<Config>>,
+    project: &Project,
+    task: &Task,
+    branch: &str,
+) -> Result<TaskAttempt, DependencyUpdateError> {
+    let pool = &container.db().pool;
+    let label = RepoConfig::load(&GitService::new(), &project.git_repo_path, branch)?
+        .profile_or(config.read().await.profile.clone());
+    let profile = ProfileConfigs::get_cached()
+        .get_profile(&label.profile)
+        .map(|profile| profile.default.label.clone())
+        .ok_or_else(|| DependencyUpdateError::ProfileNotFound(label.profile.clone()))?;
+    let attempt = TaskAttempt::create(
+        pool,
+        &CreateTaskAttempt {
+            profile,
+            base_branch: branch.to_string(),
+        },
+        task.id,
+    )
+    .await?;
+    container.start_attempt(&attempt, label, false).await?;
+    Ok(attempt)
+}
+
+/// Start one attempt per group of packages, from the project's current branch
+pub async fn start_runs<C: ContainerService + Sync>(
+    container: &C,
+    config: &Arc<RwLock<Config>>,
+    settings: &ProjectDependencyUpdates,
+) -> Result<Vec<DependencyUpdateRun>, DependencyUpdateError> {
+    let pool = &container.db().pool;
+    let project_id = settings.project_id;
+    if DependencyUpdateRun::find_running(pool)
+        .await?
+        .iter()
+        .any(|run| run.project_id == project_id)
+    {
+        return Err(DependencyUpdateError::AlreadyRunning);
+    }
+    let project = Project::find_by_id(pool, project_id)
+        .await?
+        .ok_or(DependencyUpdateError::ProjectNotFound(project_id))?;
+    let global_cap = config.read().await.budget.monthly_cap_usd;
+    SpendSummary::load(pool, global_cap, Utc::now())
+        .await?
+        .check(project_id)?;
+    // Recorded up front so a run that fails to start isn't retried until the next window
+    ProjectDependencyUpdates::set_last_run_at(pool, project_id, Utc::now()).await?;
+
+    let branch = GitService::new()
+        .get_current_branch(&project.git_repo_path)
+        .map_err(GitServiceError::from)?;
+    let manager = PackageManager::detect(&project.git_repo_path);
+    let mut runs = Vec::new();
+    for group in plan_groups(settings) {
+        let task = Task::create(
+            pool,
+            &CreateTask {
+                project_id,
+                title: task_title(&group.name),
+                description: Some(task_prompt(settings, &group, manager)),
+                parent_task_attempt: None,
+                priority: None,
+                labels: Vec::new(),
+                base_branch: None,
+            },
+            Uuid::new_v4(),
+        )
+        .await?;
+        let run = DependencyUpdateRun::create(pool, project_id, &group.name, task.id).await?;
+        match start_attempt(container, config, &project, &task, &branch).await {
+            Ok(attempt) => DependencyUpdateRun::set_attempt(pool, run.id, attempt.id).await?,
+            Err(e) => {
+                tracing::error!(
+                    "Failed to start the {} dependency update of project {}: {}",
+                    group.name,
+                    project_id,
+                    e
+                );
+                DependencyUpdateRun::finish(
+                    pool,
+                    run.id,
+                    DependencyUpdateRunStatus::Failed,
+                    None,
+                    Some(&e.to_string()),
+                )
+                .await?;
+            }
+        }
+        runs.push(run);
+    }
+    Ok(runs)
+}
+
+/// Push an attempt's branch and open a pull request for it, returning its URL
+async fn open_pr<C: ContainerService + Sync>(
+    container: &C,
+    config: &Arc<RwLock<Config>>,
+    project: &Project,
+    task: &Task,
+    attempt: &TaskAttempt,
+    branch: &str,
+    group_name: &str,
+) -> Result<String, DependencyUpdateError> {
+    let github_config = config.read().await.github.clone();
+    let token = github_auth::access_token(&github_config)
+        .await?
+        .ok_or(DependencyUpdateError::NoGitHubToken)?;
+    let github = GitHubService::new(&token)?;
+    let container_ref = container.ensure_container_exists(attempt).await?;
+    let worktree_path = Path::new(&container_ref);
+    secret_scan::scan_worktree(worktree_path, branch, &attempt.base_branch)?.check()?;
+
+    let git = GitService::new();
+    git.push_to_github(worktree_path, branch, &token)?;
+    let (owner, repo_name) = git.get_github_repo_info(&project.git_repo_path)?;
+    let body = format!(
+        "{}\n\nOpened by a scheduled dependency update.",
+        task.description.as_deref().unwrap_or_default().trim()
+    );
+    let pr = github
+        .create_pr(
+            &GitHubRepoInfo { owner, repo_name },
+            &CreatePrRequest {
+                title: pr_title(group_name),
+                body: Some(body),
+                head_branch: branch.to_string(),
+                base_branch: attempt.base_branch.clone(),
+                head_repo: None,
+            },
+        )
+        .await?;
+    TaskAttempt::update_pr_status(
+        &container.db().pool,
+        attempt.id,
+        pr.url.clone(),
+        pr.number,
+        pr.status,
+    )
+    .await?;
+    Ok(pr.url)
+}
+
+/// Settle a run whose attempt finished: open a pull request for its commits, or close its
+/// task when it changed nothing. Returns the status to record, `None` while it's running.
+async fn check_run<C: ContainerService + Sync>(
+    container: &C,
+    config: &Arc<RwLock<Config>>,
+    run: &DependencyUpdateRun,
+) -> Result<Option<(DependencyUpdateRunStatus, Option<String>)>, DependencyUpdateError> {
+    let pool = &container.db().pool;
+    let attempt = match run.task_attempt_id {
+        Some(id) => TaskAttempt::find_by_id(pool, id).await?,
+        None => None,
+    };
+    let Some(attempt) = attempt else {
+        return Err(DependencyUpdateError::AttemptDeleted);
+    };
+    let processes = ExecutionProcess::find_by_task_attempt_id(pool, attempt.id).await?;
+    if !is_settled(&processes, Utc::now()) {
+        return Ok(None);
+    }
+    if let Some(reason) = failure(&processes) {
+        return Ok(Some((
+            DependencyUpdateRunStatus::Failed,
+            Some(reason.to_string()),
+        )));
+    }
+
+    let project = Project::find_by_id(pool, run.project_id)
+        .await?
+        .ok_or(DependencyUpdateError::ProjectNotFound(run.project_id))?;
+    let task = Task::find_by_id(pool, run.task_id)
+        .await?
+        .ok_or(sqlx::Error::RowNotFound)?;
+    let Some(branch) = attempt.branch.clone() else {
+        return Ok(Some((DependencyUpdateRunStatus::NoChanges, None)));
+    };
+    let status = GitService::new().get_branch_status(
+        &project.git_repo_path,
+        &branch,
+        &attempt.base_branch,
+        false,
+    )?;
+    if status.commits_ahead == 0 {
+        Task::update_status(pool, task.id, TaskStatus::Done).await?;
+        return Ok(Some((DependencyUpdateRunStatus::NoChanges, None)));
+    }
+
+    let url = open_pr(
+        container,
+        config,
+        &project,
+        &task,
+        &attempt,
+        &branch,
+        &run.group_name,
+    )
+    .await?;
+    tracing::info!(
+        "Opened {} for the {} dependency update of project {}",
+        url,
+        run.group_name,
+        run.project_id
+    );
+    Ok(Some((DependencyUpdateRunStatus::PrOpened, Some(url))))
+}
+
+async fn check_running<C: ContainerService + Sync>(
+    container: &C,
+    config: &Arc<RwLock<Config>>,
+) -> Result<(), sqlx::Error> {
+    let pool = &container.db().pool;
+    for run in DependencyUpdateRun::find_running(pool).await? {
+        let (status, pr_url, error) = match check_run(container, config, &run).await {
+            Ok(None) => continue,
+            Ok(Some((DependencyUpdateRunStatus::PrOpened, url))) => {
+                (DependencyUpdateRunStatus::PrOpened, url, None)
+            }
+            Ok(Some((status, reason))) => (status, None, reason),
+            Err(e) => {
+                tracing::error!(
+                    "Failed to finish the {} dependency update of project {}: {}",
+                    run.group_name,
+                    run.project_id,
+                    e
+                );
+                (DependencyUpdateRunStatus::Failed, None, Some(e.to_string()))
+            }
+        };
+        DependencyUpdateRun::finish(pool, run.id, status, pr_url.as_deref(), error.as_deref())
+            .await?;
+    }
+    Ok(())
+}
+
+/// Start the dependency updates that are due and open pull requests for those that
+/// settled. Runs until the server stops.
+pub async fn run<C: ContainerService + Sync>(container: &C, config: Arc<RwLock<Config>>) {
+    let mut interval = tokio::time::interval(POLL_INTERVAL);
+    loop {
+        interval.tick().await;
+        if let Err(e) = check_running(container, &config).await {
+            tracing::error!("Failed to check running dependency updates: {}", e);
+        }
+        let due = match ProjectDependencyUpdates::find_enabled(&container.db().pool).await {
+            Ok(settings) => settings,
+            Err(e) => {
+                tracing::error!("Failed to load dependency update settings: {}", e);
+                continue;
+            }
+        };
+        let now = chrono::Local::now();
+        for settings in due
+            .iter()
+            .filter(|settings| is_due(settings.schedule, settings.last_run_at, now))
+        {
+            match start_runs(container, &config, settings).await {
+                Ok(_) | Err(DependencyUpdateError::AlreadyRunning) => {}
+                Err(e) => tracing::error!(
+                    "Failed to start dependency updates of project {}: {}",
+                    settings.project_id,
+                    e
+                ),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use db::models::dependency_update::DependencyGroup;
+    use sqlx::types::Json;
+
+    use super::*;
+
+    fn at(s: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(s).unwrap().with_timezone(&Utc)
+    }
+
+    fn settings(groups: Vec<DependencyGroup>, ignored: &[&str]) -> ProjectDependencyUpdates {
+        ProjectDependencyUpdates {
+            project_id: Uuid::nil(),
+            enabled: true,
+            schedule: DependencyUpdateSchedule::Weekly,
+            groups: Json(groups),
+            allow_major: false,
+            ignored: Json(ignored.iter().map(|p| p.to_string()).collect()),
+            instructions: Some("Keep react on 18".to_string()),
+            last_run_at: None,
+            updated_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_is_due_per_schedule() {
+        // A Thursday
+        let now = at("2025-09-18T10:00:00Z");
+        assert!(is_due(DependencyUpdateSchedule::Weekly, None, now));
+
+        let daily = DependencyUpdateSchedule::Daily;
+        assert!(is_due(daily, Some(at("2025-09-18T01:00:00Z")), now));
+        assert!(!is_due(daily, Some(at("2025-09-18T02:30:00Z")), now));
+
+        // Last Monday night's window started 2025-09-15T02:00
+        let weekly = DependencyUpdateSchedule::Weekly;
+        assert!(is_due(weekly, Some(at("2025-09-15T01:00:00Z")), now));
+        assert!(!is_due(weekly, Some(at("2025-09-15T03:00:00Z")), now));
+        assert!(!is_due(weekly, Some(at("2025-09-17T03:00:00Z")), now));
+    }
+
+    #[test]
+    fn test_groups_leave_claimed_packages_to_earlier_groups() {
+        let planned = plan_groups(&settings(Vec::new(), &["left-pad"]));
+        assert_eq!(planned.len(), 1);
+        assert_eq!(planned[0].name, ALL_GROUP);
+        assert_eq!(planned[0].exclude, vec!["left-pad"]);
+
+        let groups = vec![
+            DependencyGroup {
+                name: "types".to_string(),
+                patterns: vec!["@types/*".to_string()],
+            },
+            DependencyGroup {
+                name: "tooling".to_string(),
+                patterns: vec!["eslint*".to_string(), "@types/eslint".to_string()],
+            },
+        ];
+        let planned = plan_groups(&settings(groups, &["left-pad"]));
+        let names: Vec<&str> = planned.iter().map(|g| g.name.as_str()).collect();
+        assert_eq!(names, vec!["types", "tooling", OTHER_GROUP]);
+        assert_eq!(planned[0].exclude, vec!["left-pad"]);
+        assert_eq!(planned[1].exclude, vec!["@types/*", "left-pad"]);
+        assert!(planned[2].include.is_empty());
+        assert_eq!(
+            planned[2].exclude,
+            vec!["@types/*", "eslint*", "@types/eslint", "left-pad"]
+        );
+    }
+
+    #[test]
+    fn test_prompt_carries_the_constraints() {
+        let settings = settings(Vec::new(), &["left-pad"]);
+        let group = &plan_groups(&settings)[0];
+        let prompt = task_prompt(&settings, group, Some(PackageManager::Pnpm));
+        assert!(
+            prompt.starts_with("Update the dependencies of this repository, managed with pnpm.\n")
+        );
+        assert!(prompt.contains("Leave packages matching these alone: left-pad\n"));
+        assert!(prompt.contains("Stay within the current major version"));
+        assert!(prompt.contains("\nKeep react on 18\n"));
+        assert!(!prompt.contains("Only update"));
+        assert_eq!(task_title(ALL_GROUP), "Update dependencies");
+        assert_eq!(pr_title("types"), "chore(deps): update types dependencies");
+    }
+}
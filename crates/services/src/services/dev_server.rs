@@ -0,0 +1,257 @@
+use std::{collections::HashMap, path::Path, sync::Arc, time::Duration};
+
+use futures::StreamExt;
+use regex::Regex;
+use serde::Serialize;
+use tokio::{net::TcpStream, sync::RwLock, task::JoinHandle};
+use ts_rs::TS;
+use utils::{
+    log_msg::LogMsg,
+    msg_store::MsgStore,
+    port_file::{dev_server_port_file, read_port_file},
+};
+use uuid::Uuid;
+
+/// How long a port gets to accept a connection before it's reported as not listening
+const PROBE_TIMEOUT: Duration = Duration::from_millis(300);
+
+lazy_static::lazy_static! {
+    // `http://localhost:5173/`, `127.0.0.1:8080`, `[::]:3000`
+    static ref ADDRESS_REGEX: Regex = Regex::new(
+        r"(?i)(https?://)?(?:localhost|127\.0\.0\.1|0\.0\.0\.0|\[::1?\]):(\d{2,5})\b"
+    )
+    .unwrap();
+    // `Listening on port 3000`, `Server running at port: 8080`
+    static ref PHRASE_REGEX: Regex = Regex::new(
+        r"(?i)\b(?:listening|running|started|serving|available)\b.*?\bport\b\s*:?\s*(\d{2,5})\b"
+    )
+    .unwrap();
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, TS)]
+#[serde(rename_all = "snake_case")]
+pub enum PortSource {
+    Logs,
+    PortFile,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, TS)]
+pub struct DevServerUrl {
+    pub port: u16,
+    pub url: String,
+    pub source: PortSource,
+    /// Whether the port accepted a connection when the URLs were requested
+    pub listening: bool,
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct DevServerInfo {
+    pub execution_process_id: Uuid,
+    /// Port file the dev server can write its port to, passed to it as `VK_PORT_FILE`
+    pub port_file: String,
+    pub urls: Vec<DevServerUrl>,
+}
+
+/// A port announced in a log line
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DetectedPort {
+    pub port: u16,
+    pub https: bool,
+}
+
+/// Ports announced in a line of dev server output, in order of appearance
+pub fn parse_ports(line: &str) -> Vec<DetectedPort> {
+    // Dev servers like vite highlight the port, which splits it from the host
+    let line = strip_ansi_escapes::strip_str(line);
+    let mut ports: Vec<DetectedPort> = ADDRESS_REGEX
+        .captures_iter(&line)
+        .filter_map(|caps| {
+            let https = caps
+                .get(1)
+                .is_some_and(|scheme| scheme.as_str().eq_ignore_ascii_case("https://"));
+            Some(DetectedPort {
+                port: caps[2].parse().ok()?,
+                https,
+            })
+        })
+        .collect();
+    if ports.is_empty() {
+        ports.extend(
+            PHRASE_REGEX
+                .captures_iter(&line)
+                .filter_map(|caps| caps[1].parse().ok())
+                .map(|port| DetectedPort { port, https: false }),
+        );
+    }
+    ports.retain(|p| p.port != 0);
+    ports
+}
+
+/// Tracks the ports dev servers announce in their output, by execution process
+#[derive(Clone, Default)]
+pub struct DevServerService {
+    detected: Arc<RwLock<HashMap<Uuid, Vec<DetectedPort>>>>,
+}
+
+impl DevServerService {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Scan a dev server's output for ports until it finishes, then forget them and remove
+    /// its port file
+    pub fn watch(
+        &self,
+        execution_process_id: Uuid,
+        worktree: &Path,
+        store: Arc<MsgStore>,
+    ) -> JoinHandle<()> {
+        let detected = self.detected.clone();
+        let port_file = dev_server_port_file(worktree);
+        tokio::spawn(async move {
+            detected
+                .write()
+                .await
+                .insert(execution_process_id, Vec::new());
+            let mut stream = store.history_plus_stream();
+            // Output arrives in chunks that can split a line
+            let mut partial = String::new();
+            while let Some(Ok(msg)) = stream.next().await {
+                let chunk = match msg {
+                    LogMsg::Stdout(chunk) | LogMsg::Stderr(chunk) => chunk,
+                    LogMsg::Finished => break,
+                    _ => continue,
+                };
+                partial.push_str(&chunk);
+                let Some(end) = partial.rfind('\n') else {
+                    continue;
+                };
+                let complete: String = partial.drain(..=end).collect();
+                let found: Vec<_> = complete.lines().flat_map(parse_ports).collect();
+                if found.is_empty() {
+                    continue;
+                }
+                let mut detected = detected.write().await;
+                let ports = detected.entry(execution_process_id).or_default();
+                for port in found {
+                    if !ports.iter().any(|p| p.port == port.port) {
+                        tracing::info!(
+                            "Dev server {} announced port {}",
+                            execution_process_id,
+                            port.port
+                        );
+                        ports.push(port);
+                    }
+                }
+            }
+            detected.write().await.remove(&execution_process_id);
+            let _ = tokio::fs::remove_file(&port_file).await;
+        })
+    }
+
+    /// URLs of a running dev server. A port from the port file comes first, as the dev
+    /// server reported it directly.
+    pub async fn info(&self, execution_process_id: Uuid, worktree: &Path) -> DevServerInfo {
+        let port_file = dev_server_port_file(worktree);
+        let mut found: Vec<(DetectedPort, PortSource)> = Vec::new();
+        if let Some(port) = read_port_file(&port_file).await {
+            found.push((DetectedPort { port, https: false }, PortSource::PortFile));
+        }
+        if let Some(ports) = self.detected.read().await.get(&execution_process_id) {
+            for port in ports {
+                if !found.iter().any(|(p, _)| p.port == port.port) {
+                    found.push((*port, PortSource::Logs));
+                }
+            }
+        }
+
+        let mut urls = Vec::with_capacity(found.len());
+        for (port, source) in found {
+            let scheme = if port.https { "https" } else { "http" };
+            urls.push(DevServerUrl {
+                port: port.port,
+                url: format!("{scheme}://localhost:{}", port.port),
+                source,
+                listening: is_listening(port.port).await,
+            });
+        }
+        DevServerInfo {
+            execution_process_id,
+            port_file: port_file.to_string_lossy().into_owned(),
+            urls,
+        }
+    }
+
+    /// Where to send preview traffic for a dev server, the first URL that accepts
+    /// connections
+    pub async fn preview_target(
+        &self,
+        execution_process_id: Uuid,
+        worktree: &Path,
+    ) -> Option<DevServerUrl> {
+        self.info(execution_process_id, worktree)
+            .await
+            .urls
+            .into_iter()
+            .find(|url| url.listening)
+    }
+}
+
+async fn is_listening(port: u16) -> bool {
+    matches!(
+        tokio::time::timeout(PROBE_TIMEOUT, TcpStream::connect(("127.0.0.1", port))).await,
+        Ok(Ok(_))
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ports(line: &str) -> Vec<u16> {
+        parse_ports(line).into_iter().map(|p| p.port).collect()
+    }
+
+    #[test]
+    fn test_parse_ports_from_dev_server_output() {
+        assert_eq!(
+            ports(
+                "  \u{1b}[32m➜\u{1b}[39m  \u{1b}[1mLocal\u{1b}[22m:   \u{1b}[36mhttp://localhost:\u{1b}[1m5173\u{1b}[22m/\u{1b}[39m"
+            ),
+            [5173]
+        );
+        assert_eq!(ports("Server listening on 0.0.0.0:8080"), [8080]);
+        assert_eq!(ports("Listening on port 3000"), [3000]);
+        assert_eq!(ports("Server running at port: 4000"), [4000]);
+        assert_eq!(
+            ports("- Local: http://localhost:3000, Network: http://127.0.0.1:3001"),
+            [3000, 3001]
+        );
+        assert!(parse_ports("https://localhost:8443/")[0].https);
+        assert_eq!(ports("Compiled 42 modules in 1200ms"), Vec::<u16>::new());
+        assert_eq!(ports("Reached line 3000 of the report"), Vec::<u16>::new());
+    }
+
+    #[tokio::test]
+    async fn test_watch_collects_ports_until_finished() {
+        let service = DevServerService::new();
+        let store = Arc::new(MsgStore::new());
+        let id = Uuid::new_v4();
+        let worktree = tempfile::tempdir().unwrap();
+
+        store.push_stdout("Starting...\nListening on ");
+        store.push_stdout("port 3000\n");
+        store.push_stderr("also at http://localhost:3000 and http://localhost:3001\n");
+        let handle = service.watch(id, worktree.path(), store.clone());
+
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        let info = service.info(id, worktree.path()).await;
+        let found: Vec<_> = info.urls.iter().map(|u| (u.port, u.source)).collect();
+        assert_eq!(found, [(3000, PortSource::Logs), (3001, PortSource::Logs)]);
+        assert_eq!(info.urls[0].url, "http://localhost:3000");
+
+        store.push_finished();
+        handle.await.unwrap();
+        assert!(service.info(id, worktree.path()).await.urls.is_empty());
+    }
+}
@@ -0,0 +1,395 @@
+use std::{
+    collections::HashMap,
+    io,
+    path::{Path, PathBuf},
+};
+
+use db::models::project_devcontainer::ProjectDevcontainer;
+use executors::env::ContainerTarget;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use sqlx::SqlitePool;
+use thiserror::Error;
+use tokio::process::Command;
+use ts_rs::TS;
+use uuid::Uuid;
+
+#[derive(Debug, Error)]
+pub enum DevcontainerError {
+    #[error(transparent)]
+    Database(#[from] sqlx::Error),
+    #[error(transparent)]
+    Io(#[from] io::Error),
+    #[error("Invalid devcontainer.json: {0}")]
+    Config(#[from] serde_json::Error),
+    #[error("devcontainer.json names neither an image nor a Dockerfile")]
+    NoImage,
+    #[error("docker {command} failed: {stderr}")]
+    Docker { command: String, stderr: String },
+}
+
+/// Where repositories keep their devcontainer definition, in the order the spec looks
+pub const CONFIG_PATHS: [&str; 2] = [".devcontainer/devcontainer.json", ".devcontainer.json"];
+
+/// Label marking the containers of attempt worktrees with the worktree they run
+const WORKTREE_LABEL: &str = "vibe-kanban.worktree";
+
+/// Keeps a container without a long-running entrypoint alive, as the devcontainer CLI does
+const KEEP_ALIVE: &str = "trap 'exit 0' 15; while sleep 1 & wait $!; do :; done";
+
+/// Whether a project's repository defines a devcontainer, and if attempts run in it
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct ProjectDevcontainerStatus {
+    /// Path of the definition relative to the repository root
+    pub config_path: Option<String>,
+    pub settings: Option<ProjectDevcontainer>,
+}
+
+impl ProjectDevcontainerStatus {
+    pub async fn load(
+        pool: &SqlitePool,
+        project_id: Uuid,
+        repo_path: &Path,
+    ) -> Result<Self, sqlx::Error> {
+        let config_path = DevcontainerConfig::find(repo_path).and_then(|path| {
+            path.strip_prefix(repo_path)
+                .ok()
+                .map(|path| path.to_string_lossy().to_string())
+        });
+        Ok(Self {
+            config_path,
+            settings: ProjectDevcontainer::find_by_project_id(pool, project_id).await?,
+        })
+    }
+}
+
+/// The parts of `devcontainer.json` attempt environments use
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DevcontainerConfig {
+    pub image: Option<String>,
+    pub build: Option<DevcontainerBuild>,
+    /// Pre-`build` way of naming the Dockerfile
+    #[serde(rename = "dockerFile")]
+    pub docker_file: Option<String>,
+    #[serde(default)]
+    pub container_env: HashMap<String, String>,
+    pub remote_user: Option<String>,
+    pub container_user: Option<String>,
+    #[serde(default)]
+    pub run_args: Vec<String>,
+    /// A shell command or the program and its arguments
+    pub post_create_command: Option<serde_json::Value>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DevcontainerBuild {
+    pub dockerfile: Option<String>,
+    pub context: Option<String>,
+    #[serde(default)]
+    pub args: HashMap<String, String>,
+}
+
+impl DevcontainerConfig {
+    /// The repository's devcontainer definition, if it has one
+    pub fn find(repo_path: &Path) -> Option<PathBuf> {
+        CONFIG_PATHS
+            .iter()
+            .map(|path| repo_path.join(path))
+            .find(|path| path.is_file())
+    }
+
+    pub fn load(config_path: &Path) -> Result<Self, DevcontainerError> {
+        let raw = std::fs::read_to_string(config_path)?;
+        Ok(serde_json::from_str(&strip_jsonc(&raw))?)
+    }
+
+    /// Shell command for `postCreateCommand`, the object form running several commands in
+    /// parallel isn't supported
+    fn post_create_script(&self) -> Option<String> {
+        match self.post_create_command.as_ref()? {
+            serde_json::Value::String(script) => Some(script.clone()),
+            serde_json::Value::Array(args) => Some(
+                args.iter()
+                    .filter_map(|arg| arg.as_str())
+                    .map(utils::shell::quote_shell_arg)
+                    .collect::<Vec<_>>()
+                    .join(" "),
+            ),
+            _ => None,
+        }
+    }
+}
+
+/// devcontainer.json is JSON with comments and trailing commas
+fn strip_jsonc(raw: &str) -> String {
+    let mut out = String::with_capacity(raw.len());
+    let mut chars = raw.chars().peekable();
+    let mut in_string = false;
+    while let Some(c) = chars.next() {
+        if in_string {
+            out.push(c);
+            match c {
+                '\\' => out.extend(chars.next()),
+                '"' => in_string = false,
+                _ => {}
+            }
+            continue;
+        }
+        match (c, chars.peek()) {
+            ('"', _) => {
+                in_string = true;
+                out.push(c);
+            }
+            ('/', Some('/')) => {
+                for c in chars.by_ref() {
+                    if c == '\n' {
+                        out.push('\n');
+                        break;
+                    }
+                }
+            }
+            ('/', Some('*')) => {
+                chars.next();
+                let mut prev = '\0';
+                for c in chars.by_ref() {
+                    if prev == '*' && c == '/' {
+                        break;
+                    }
+                    prev = c;
+                }
+            }
+            (',', _) => {
+                // Drop the comma when only whitespace separates it from a closing bracket
+                let rest: String = chars.clone().collect();
+                let next = rest.trim_start().chars().next();
+                if !matches!(next, Some('}') | Some(']')) {
+                    out.push(c);
+                }
+            }
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+/// The devcontainer an attempt worktree's setup scripts and coding agents run in. There is
+/// one container per worktree, mounting it and the other host directories its processes use
+/// at the same paths, so paths in logs, diffs and git worktree links stay valid.
+pub struct Devcontainer {
+    config_path: PathBuf,
+    config: DevcontainerConfig,
+    worktree: PathBuf,
+}
+
+impl Devcontainer {
+    /// The worktree's devcontainer, `None` unless the project enabled them and the worktree
+    /// defines one
+    pub async fn for_worktree(
+        pool: &SqlitePool,
+        project_id: Uuid,
+        worktree: &Path,
+    ) -> Result<Option<Self>, DevcontainerError> {
+        let enabled = ProjectDevcontainer::find_by_project_id(pool, project_id)
+            .await?
+            .is_some_and(|settings| settings.enabled);
+        if !enabled {
+            return Ok(None);
+        }
+        let Some(config_path) = DevcontainerConfig::find(worktree) else {
+            return Ok(None);
+        };
+        Ok(Some(Self {
+            config: DevcontainerConfig::load(&config_path)?,
+            config_path,
+            worktree: worktree.to_path_buf(),
+        }))
+    }
+
+    fn container_name(worktree: &Path) -> String {
+        let hash = format!(
+            "{:x}",
+            Sha256::digest(worktree.to_string_lossy().as_bytes())
+        );
+        format!("vibe-kanban-{}", &hash[..12])
+    }
+
+    /// Start the worktree's container, reusing it when it exists, and building the image
+    /// unless it was built from the same Dockerfile before. `mounts` are the host directories
+    /// besides the worktree the processes use.
+    pub async fn ensure(&self, mounts: &[&Path]) -> Result<ContainerTarget, DevcontainerError> {
+        let name = Self::container_name(&self.worktree);
+        let target = ContainerTarget {
+            container_id: name.clone(),
+            user: self
+                .config
+                .remote_user
+                .clone()
+                .or_else(|| self.config.container_user.clone()),
+        };
+
+        let state = docker(&[
+            "ps",
+            "-a",
+            "--filter",
+            &format!("name=^/{name}$"),
+            "--format",
+            "{{.State}}",
+        ])
+        .await?;
+        match state.trim() {
+            "running" => return Ok(target),
+            "" => {}
+            _ => {
+                docker(&["start", &name]).await?;
+                return Ok(target);
+            }
+        }
+
+        let image = self.image().await?;
+        let worktree = self.worktree.to_string_lossy().to_string();
+        let mut args = vec![
+            "run".to_string(),
+            "-d".to_string(),
+            "--name".to_string(),
+            name.clone(),
+            "--label".to_string(),
+            format!("{WORKTREE_LABEL}={worktree}"),
+            "-w".to_string(),
+            worktree.clone(),
+            "-v".to_string(),
+            format!("{worktree}:{worktree}"),
+        ];
+        for mount in mounts {
+            let mount = mount.to_string_lossy();
+            args.extend(["-v".to_string(), format!("{mount}:{mount}")]);
+        }
+        for (key, value) in &self.config.container_env {
+            args.extend(["-e".to_string(), format!("{key}={value}")]);
+        }
+        if let Some(user) = &self.config.container_user {
+            args.extend(["-u".to_string(), user.clone()]);
+        }
+        args.extend(self.config.run_args.iter().cloned());
+        args.extend([
+            "--entrypoint".to_string(),
+            "/bin/sh".to_string(),
+            image,
+            "-c".to_string(),
+            KEEP_ALIVE.to_string(),
+        ]);
+        docker(&args.iter().map(String::as_str).collect::<Vec<_>>()).await?;
+        tracing::info!("Started devcontainer {} for {}", name, worktree);
+
+        if let Some(script) = self.config.post_create_script() {
+            let mut exec = vec!["exec", "-w", worktree.as_str()];
+            if let Some(user) = &target.user {
+                exec.extend(["-u", user.as_str()]);
+            }
+            exec.extend([name.as_str(), "sh", "-c", script.as_str()]);
+            docker(&exec).await?;
+        }
+        Ok(target)
+    }
+
+    /// The configured image, or one built from the configured Dockerfile
+    async fn image(&self) -> Result<String, DevcontainerError> {
+        if let Some(image) = &self.config.image {
+            return Ok(image.clone());
+        }
+        let build = self.config.build.clone().unwrap_or_default();
+        let dockerfile = build
+            .dockerfile
+            .or_else(|| self.config.docker_file.clone())
+            .ok_or(DevcontainerError::NoImage)?;
+        // Paths in devcontainer.json are relative to it
+        let config_dir = self.config_path.parent().unwrap_or(&self.worktree);
+        let dockerfile = config_dir.join(dockerfile);
+        let context = config_dir.join(build.context.as_deref().unwrap_or("."));
+
+        let mut hasher = Sha256::new();
+        hasher.update(std::fs::read(&dockerfile)?);
+        let mut build_args: Vec<_> = build.args.iter().collect();
+        build_args.sort();
+        for (key, value) in &build_args {
+            hasher.update(format!("{key}={value}"));
+        }
+        let tag = format!(
+            "vibe-kanban-devcontainer:{}",
+            &format!("{:x}", hasher.finalize())[..12]
+        );
+        if docker(&["image", "inspect", &tag]).await.is_ok() {
+            return Ok(tag);
+        }
+
+        let dockerfile = dockerfile.to_string_lossy().to_string();
+        let context = context.to_string_lossy().to_string();
+        let mut args = vec!["build", "-t", tag.as_str(), "-f", dockerfile.as_str()];
+        let build_args: Vec<String> = build_args
+            .iter()
+            .map(|(key, value)| format!("{key}={value}"))
+            .collect();
+        for arg in &build_args {
+            args.extend(["--build-arg", arg.as_str()]);
+        }
+        args.push(context.as_str());
+        tracing::info!("Building devcontainer image {} from {}", tag, dockerfile);
+        docker(&args).await?;
+        Ok(tag)
+    }
+
+    /// Remove the container of a worktree about to be deleted, if it has one
+    pub async fn remove(worktree: &Path) {
+        if DevcontainerConfig::find(worktree).is_none() {
+            return;
+        }
+        let name = Self::container_name(worktree);
+        if let Ok(state) = docker(&["ps", "-a", "-q", "--filter", &format!("name=^/{name}$")]).await
+            && !state.trim().is_empty()
+            && let Err(e) = docker(&["rm", "-f", &name]).await
+        {
+            tracing::warn!("Failed to remove devcontainer {}: {}", name, e);
+        }
+    }
+}
+
+async fn docker(args: &[&str]) -> Result<String, DevcontainerError> {
+    let output = Command::new("docker")
+        .args(args)
+        .kill_on_drop(true)
+        .output()
+        .await?;
+    if !output.status.success() {
+        return Err(DevcontainerError::Docker {
+            command: args.first().copied().unwrap_or_default().to_string(),
+            stderr: String::from_utf8_lossy(&output.stderr).trim().to_string(),
+        });
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_config_with_comments_and_trailing_commas() {
+        let raw = r#"{
+            // Built from the repository's Dockerfile
+            "build": { "dockerfile": "Dockerfile", "context": "..", },
+            /* runs once */
+            "postCreateCommand": ["npm", "install"],
+            "remoteUser": "node",
+            "containerEnv": { "URL": "http://localhost//x" },
+        }"#;
+        let config: DevcontainerConfig = serde_json::from_str(&strip_jsonc(raw)).unwrap();
+        assert_eq!(
+            config.build.as_ref().unwrap().dockerfile.as_deref(),
+            Some("Dockerfile")
+        );
+        assert_eq!(config.remote_user.as_deref(), Some("node"));
+        assert_eq!(config.container_env["URL"], "http://localhost//x");
+        assert!(config.post_create_script().unwrap().contains("install"));
+    }
+}
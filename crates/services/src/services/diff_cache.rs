@@ -0,0 +1,272 @@
+use std::{
+    collections::{HashMap, HashSet},
+    path::Path,
+    sync::{Arc, Mutex},
+};
+
+use db::models::task_attempt::TaskAttempt;
+use utils::diff::Diff;
+use uuid::Uuid;
+
+use super::git::{DiffTarget, GitService, GitServiceError};
+
+/// Attempts whose diffs are kept, the least recently used are dropped first
+const MAX_CACHED_ATTEMPTS: usize = 32;
+
+/// An attempt's diff against its base branch, with the state of the worktree or merge commit
+/// it was computed from
+#[derive(Debug, Clone)]
+pub struct AttemptDiffs {
+    pub key: String,
+    pub diffs: Arc<Vec<Diff>>,
+}
+
+struct CachedDiffs {
+    diffs: AttemptDiffs,
+    /// Recency of the last use, from `Entries::clock`
+    used: u64,
+}
+
+#[derive(Default)]
+struct Entries {
+    attempts: HashMap<Uuid, CachedDiffs>,
+    clock: u64,
+}
+
+impl Entries {
+    fn tick(&mut self) -> u64 {
+        self.clock += 1;
+        self.clock
+    }
+}
+
+/// Diffs of attempts, recomputed only when their worktree changes. Checking the worktree's
+/// HEAD and status is much cheaper than diffing every changed file against the base branch.
+/// While an attempt's diff is streamed, the files its watcher sees change are patched in.
+#[derive(Clone, Default)]
+pub struct DiffCache {
+    entries: Arc<Mutex<Entries>>,
+}
+
+impl DiffCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// State a worktree's diff depends on: its HEAD, the base branch's commit and what's
+    /// uncommitted
+    pub fn worktree_key(
+        git: &GitService,
+        worktree_path: &Path,
+        base_branch: &str,
+    ) -> Result<String, GitServiceError> {
+        Ok(format!(
+            "worktree:{:x}",
+            git.worktree_fingerprint(worktree_path, base_branch)?
+        ))
+    }
+
+    /// What the attempt changes on its base branch. Merged attempts use their merge commit in
+    /// the project repository, others their worktree including uncommitted changes.
+    pub fn attempt_diffs(
+        &self,
+        git: &GitService,
+        task_attempt: &TaskAttempt,
+        worktree_path: &Path,
+        project_repo_path: &Path,
+    ) -> Result<AttemptDiffs, GitServiceError> {
+        let key = match &task_attempt.merge_commit {
+            Some(merge_commit) => format!("merged:{merge_commit}"),
+            None => Self::worktree_key(git, worktree_path, &task_attempt.base_branch)?,
+        };
+        if let Some(cached) = self.get(task_attempt.id, &key) {
+            return Ok(cached);
+        }
+
+        let target = match &task_attempt.merge_commit {
+            Some(merge_commit) => DiffTarget::Commit {
+                repo_path: project_repo_path,
+                commit_sha: merge_commit,
+            },
+            None => DiffTarget::Worktree {
+                worktree_path,
+                branch_name: task_attempt.branch.as_deref().unwrap_or_default(),
+                base_branch: &task_attempt.base_branch,
+            },
+        };
+        let diffs = AttemptDiffs {
+            key,
+            diffs: Arc::new(git.get_diffs(target, None)?),
+        };
+        self.insert(task_attempt.id, diffs.clone());
+        Ok(diffs)
+    }
+
+    fn get(&self, attempt_id: Uuid, key: &str) -> Option<AttemptDiffs> {
+        let mut entries = self.entries.lock().unwrap();
+        let used = entries.tick();
+        let entry = entries.attempts.get_mut(&attempt_id)?;
+        if entry.diffs.key != key {
+            return None;
+        }
+        entry.used = used;
+        Some(entry.diffs.clone())
+    }
+
+    fn insert(&self, attempt_id: Uuid, diffs: AttemptDiffs) {
+        let mut entries = self.entries.lock().unwrap();
+        let used = entries.tick();
+        entries
+            .attempts
+            .insert(attempt_id, CachedDiffs { diffs, used });
+        while entries.attempts.len() > MAX_CACHED_ATTEMPTS {
+            let Some(oldest) = entries
+                .attempts
+                .iter()
+                .min_by_key(|(_, entry)| entry.used)
+                .map(|(id, _)| *id)
+            else {
+                break;
+            };
+            entries.attempts.remove(&oldest);
+        }
+    }
+
+    /// Replace the cached diffs of files that changed with their new diffs, `key` being the
+    /// worktree's state read before they were computed. Changed files without a diff are
+    /// back to their base version. Nothing is cached for attempts whose diff wasn't computed
+    /// yet.
+    pub fn apply_changes(
+        &self,
+        attempt_id: Uuid,
+        key: String,
+        changed_paths: &[String],
+        changed_diffs: &[Diff],
+    ) {
+        let mut entries = self.entries.lock().unwrap();
+        let used = entries.tick();
+        let Some(entry) = entries.attempts.get_mut(&attempt_id) else {
+            return;
+        };
+        let changed: HashSet<&str> = changed_paths
+            .iter()
+            .map(String::as_str)
+            .chain(changed_diffs.iter().flat_map(diff_paths))
+            .collect();
+        let mut diffs: Vec<Diff> = entry
+            .diffs
+            .diffs
+            .iter()
+            .filter(|diff| !diff_paths(diff).any(|path| changed.contains(path)))
+            .cloned()
+            .collect();
+        diffs.extend(changed_diffs.iter().cloned());
+        diffs.sort_by_key(GitService::diff_path);
+        entry.diffs = AttemptDiffs {
+            key,
+            diffs: Arc::new(diffs),
+        };
+        entry.used = used;
+    }
+}
+
+/// Old and new paths of a diff, which differ for renames
+fn diff_paths(diff: &Diff) -> impl Iterator<Item = &str> {
+    [&diff.old_file, &diff.new_file]
+        .into_iter()
+        .flatten()
+        .filter_map(|file| file.file_name.as_deref())
+}
+
+#[cfg(test)]
+mod tests {
+    use utils::diff::FileDiffDetails;
+
+    use super::*;
+
+    fn diff(path: &str, content: &str) -> Diff {
+        Diff {
+            old_file: None,
+            new_file: Some(FileDiffDetails {
+                file_name: Some(path.to_string()),
+                content: Some(content.to_string()),
+            }),
+            hunks: Vec::new(),
+        }
+    }
+
+    fn cached(diffs: Vec<Diff>) -> AttemptDiffs {
+        AttemptDiffs {
+            key: "worktree:1".to_string(),
+            diffs: Arc::new(diffs),
+        }
+    }
+
+    fn contents(diffs: &AttemptDiffs) -> Vec<(String, String)> {
+        diffs
+            .diffs
+            .iter()
+            .map(|d| {
+                let file = d.new_file.as_ref().unwrap();
+                (
+                    file.file_name.clone().unwrap(),
+                    file.content.clone().unwrap(),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_apply_changes_patches_changed_files() {
+        let cache = DiffCache::new();
+        let attempt_id = Uuid::new_v4();
+        cache.insert(
+            attempt_id,
+            cached(vec![
+                diff("a.rs", "a"),
+                diff("b.rs", "b"),
+                diff("c.rs", "c"),
+            ]),
+        );
+
+        // b.rs was edited, c.rs reverted, d.rs created
+        cache.apply_changes(
+            attempt_id,
+            "worktree:2".to_string(),
+            &["b.rs".to_string(), "c.rs".to_string(), "d.rs".to_string()],
+            &[diff("d.rs", "d"), diff("b.rs", "b2")],
+        );
+
+        assert!(cache.get(attempt_id, "worktree:1").is_none());
+        let patched = cache.get(attempt_id, "worktree:2").unwrap();
+        assert_eq!(
+            contents(&patched),
+            vec![
+                ("a.rs".to_string(), "a".to_string()),
+                ("b.rs".to_string(), "b2".to_string()),
+                ("d.rs".to_string(), "d".to_string()),
+            ]
+        );
+
+        // Attempts never diffed stay uncached
+        let other = Uuid::new_v4();
+        cache.apply_changes(other, "worktree:2".to_string(), &[], &[diff("a.rs", "a")]);
+        assert!(cache.get(other, "worktree:2").is_none());
+    }
+
+    #[test]
+    fn test_least_recently_used_attempts_are_dropped() {
+        let cache = DiffCache::new();
+        let first = Uuid::new_v4();
+        cache.insert(first, cached(Vec::new()));
+        for _ in 0..MAX_CACHED_ATTEMPTS {
+            // Keep the first attempt in use
+            assert!(cache.get(first, "worktree:1").is_some());
+            cache.insert(Uuid::new_v4(), cached(Vec::new()));
+        }
+
+        let entries = cache.entries.lock().unwrap();
+        assert_eq!(entries.attempts.len(), MAX_CACHED_ATTEMPTS);
+        assert!(entries.attempts.contains_key(&first));
+    }
+}
@@ -0,0 +1,205 @@
+use std::path::Path;
+
+use db::models::attempt_diff_risk::{DiffRisk, RiskFlag};
+use utils::diff::{Diff, FileDiffDetails, changed_line_counts};
+
+use super::git::{DiffTarget, GitService, GitServiceError};
+
+/// Lines added and removed above which a diff is too large to review line by line
+pub const LARGE_DIFF_LINES: i64 = 500;
+
+/// Removed lines below which a diff is never deletion-heavy
+const MIN_DELETION_HEAVY_LINES: i64 = 50;
+
+/// Path prefixes of CI and workflow definitions
+const CI_PATHS: &[&str] = &[
+    ".github/workflows/",
+    ".github/actions/",
+    ".gitlab-ci.yml",
+    ".gitlab/ci/",
+    ".circleci/",
+    ".buildkite/",
+    ".travis.yml",
+    "azure-pipelines.yml",
+    "Jenkinsfile",
+    "bitbucket-pipelines.yml",
+];
+
+/// File names of dependency manifests and lockfiles, in any directory
+const DEPENDENCY_MANIFESTS: &[&str] = &[
+    "Cargo.toml",
+    "Cargo.lock",
+    "package.json",
+    "package-lock.json",
+    "pnpm-lock.yaml",
+    "yarn.lock",
+    "bun.lockb",
+    "go.mod",
+    "go.sum",
+    "requirements.txt",
+    "pyproject.toml",
+    "poetry.lock",
+    "uv.lock",
+    "Pipfile",
+    "Pipfile.lock",
+    "Gemfile",
+    "Gemfile.lock",
+    "pom.xml",
+    "build.gradle",
+    "build.gradle.kts",
+    "composer.json",
+    "composer.lock",
+];
+
+/// Risk of the changes an attempt's branch makes on top of its base branch, including
+/// uncommitted changes in the worktree
+pub fn assess_worktree(
+    worktree_path: &Path,
+    branch_name: &str,
+    base_branch: &str,
+    expected_paths: &[String],
+) -> Result<DiffRisk, GitServiceError> {
+    let diffs = GitService::new().get_diffs(
+        DiffTarget::Worktree {
+            worktree_path,
+            branch_name,
+            base_branch,
+        },
+        None,
+    )?;
+    Ok(assess_diffs(&diffs, expected_paths))
+}
+
+pub fn assess_diffs(diffs: &[Diff], expected_paths: &[String]) -> DiffRisk {
+    let mut risk = DiffRisk::default();
+    let mut outside = Vec::new();
+    let mut ci = Vec::new();
+    let mut dependencies = Vec::new();
+
+    for diff in diffs {
+        let (added, removed) =
+            changed_line_counts(content(&diff.old_file), content(&diff.new_file));
+        risk.files_changed += 1;
+        risk.lines_added += added as i64;
+        risk.lines_removed += removed as i64;
+
+        // Renames count for both their old and new path
+        let mut paths: Vec<&str> = [&diff.old_file, &diff.new_file]
+            .into_iter()
+            .filter_map(|file| file.as_ref()?.file_name.as_deref())
+            .collect();
+        paths.dedup();
+        for path in paths {
+            if !expected_paths.is_empty()
+                && !expected_paths
+                    .iter()
+                    .any(|prefix| path.starts_with(prefix.as_str()))
+            {
+                outside.push(path.to_string());
+            }
+            if CI_PATHS.iter().any(|prefix| path.starts_with(prefix)) {
+                ci.push(path.to_string());
+            }
+            let file_name = path.rsplit('/').next().unwrap_or(path);
+            if DEPENDENCY_MANIFESTS.contains(&file_name) {
+                dependencies.push(path.to_string());
+            }
+        }
+    }
+
+    let lines_changed = risk.lines_added + risk.lines_removed;
+    if lines_changed > LARGE_DIFF_LINES {
+        risk.flags.push(RiskFlag::LargeDiff { lines_changed });
+    }
+    if !outside.is_empty() {
+        risk.flags
+            .push(RiskFlag::OutsideExpectedPaths { files: outside });
+    }
+    if !ci.is_empty() {
+        risk.flags.push(RiskFlag::CiChanges { files: ci });
+    }
+    if risk.lines_removed >= MIN_DELETION_HEAVY_LINES && risk.lines_removed > 2 * risk.lines_added {
+        risk.flags.push(RiskFlag::DeletionHeavy {
+            lines_removed: risk.lines_removed,
+            lines_added: risk.lines_added,
+        });
+    }
+    if !dependencies.is_empty() {
+        risk.flags.push(RiskFlag::DependencyChanges {
+            files: dependencies,
+        });
+    }
+    risk
+}
+
+/// Binary files count as changed without lines
+fn content(file: &Option<FileDiffDetails>) -> &str {
+    file.as_ref()
+        .and_then(|f| f.content.as_deref())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn file(name: &str, content: &str) -> Option<FileDiffDetails> {
+        Some(FileDiffDetails {
+            file_name: Some(name.to_string()),
+            content: Some(content.to_string()),
+        })
+    }
+
+    #[test]
+    fn test_assess_flags_risky_changes() {
+        let removed = "line\n".repeat(80);
+        let diffs = [
+            Diff {
+                old_file: file("src/lib.rs", &removed),
+                new_file: file("src/lib.rs", "fn main() {}\n"),
+                hunks: Vec::new(),
+            },
+            Diff {
+                old_file: file(".github/workflows/ci.yml", "on: push\n"),
+                new_file: file(".github/workflows/ci.yml", "on: [push, pull_request]\n"),
+                hunks: Vec::new(),
+            },
+            Diff {
+                old_file: None,
+                new_file: file("frontend/package.json", "{}\n"),
+                hunks: Vec::new(),
+            },
+        ];
+
+        let risk = assess_diffs(&diffs, &["src/".to_string(), "frontend/".to_string()]);
+        assert_eq!(risk.files_changed, 3);
+        assert_eq!((risk.lines_added, risk.lines_removed), (3, 81));
+        assert_eq!(
+            risk.flags,
+            vec![
+                RiskFlag::OutsideExpectedPaths {
+                    files: vec![".github/workflows/ci.yml".to_string()]
+                },
+                RiskFlag::CiChanges {
+                    files: vec![".github/workflows/ci.yml".to_string()]
+                },
+                RiskFlag::DeletionHeavy {
+                    lines_removed: 81,
+                    lines_added: 3
+                },
+                RiskFlag::DependencyChanges {
+                    files: vec!["frontend/package.json".to_string()]
+                },
+            ]
+        );
+
+        // Without expected paths nothing is outside them, small diffs are not large
+        let risk = assess_diffs(&diffs[2..], &[]);
+        assert_eq!(
+            risk.flags,
+            vec![RiskFlag::DependencyChanges {
+                files: vec!["frontend/package.json".to_string()]
+            }]
+        );
+    }
+}
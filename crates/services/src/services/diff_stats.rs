@@ -0,0 +1,349 @@
+use std::{collections::HashMap, path::Path, sync::Arc};
+
+use db::models::task_attempt::TaskAttempt;
+use serde::Serialize;
+use tokio::sync::RwLock;
+use ts_rs::TS;
+use utils::diff::{Diff, FileDiffDetails, changed_line_counts};
+use uuid::Uuid;
+
+use super::{
+    diff_cache::DiffCache,
+    git::{GitService, GitServiceError},
+};
+
+/// Languages by file extension, compared in lowercase
+const EXTENSION_LANGUAGES: &[(&str, &str)] = &[
+    ("rs", "Rust"),
+    ("ts", "TypeScript"),
+    ("tsx", "TypeScript"),
+    ("mts", "TypeScript"),
+    ("cts", "TypeScript"),
+    ("js", "JavaScript"),
+    ("jsx", "JavaScript"),
+    ("mjs", "JavaScript"),
+    ("cjs", "JavaScript"),
+    ("py", "Python"),
+    ("pyi", "Python"),
+    ("go", "Go"),
+    ("java", "Java"),
+    ("kt", "Kotlin"),
+    ("kts", "Kotlin"),
+    ("swift", "Swift"),
+    ("c", "C"),
+    ("h", "C"),
+    ("cc", "C++"),
+    ("cpp", "C++"),
+    ("cxx", "C++"),
+    ("hpp", "C++"),
+    ("hh", "C++"),
+    ("cs", "C#"),
+    ("rb", "Ruby"),
+    ("php", "PHP"),
+    ("scala", "Scala"),
+    ("ex", "Elixir"),
+    ("exs", "Elixir"),
+    ("erl", "Erlang"),
+    ("hs", "Haskell"),
+    ("ml", "OCaml"),
+    ("clj", "Clojure"),
+    ("dart", "Dart"),
+    ("lua", "Lua"),
+    ("zig", "Zig"),
+    ("sh", "Shell"),
+    ("bash", "Shell"),
+    ("zsh", "Shell"),
+    ("ps1", "PowerShell"),
+    ("sql", "SQL"),
+    ("html", "HTML"),
+    ("htm", "HTML"),
+    ("css", "CSS"),
+    ("scss", "SCSS"),
+    ("sass", "SCSS"),
+    ("less", "Less"),
+    ("vue", "Vue"),
+    ("svelte", "Svelte"),
+    ("md", "Markdown"),
+    ("mdx", "Markdown"),
+    ("json", "JSON"),
+    ("yml", "YAML"),
+    ("yaml", "YAML"),
+    ("toml", "TOML"),
+    ("xml", "XML"),
+    ("proto", "Protocol Buffers"),
+    ("graphql", "GraphQL"),
+    ("tf", "Terraform"),
+    ("nix", "Nix"),
+];
+
+/// Languages of files without a telling extension, by file name
+const FILE_NAME_LANGUAGES: &[(&str, &str)] = &[
+    ("Dockerfile", "Dockerfile"),
+    ("Makefile", "Makefile"),
+    ("CMakeLists.txt", "CMake"),
+    ("Jenkinsfile", "Groovy"),
+    ("Gemfile", "Ruby"),
+    ("Rakefile", "Ruby"),
+];
+
+/// Lines added and removed by one changed file
+#[derive(Debug, Clone, PartialEq, Serialize, TS)]
+pub struct FileDiffStats {
+    pub path: String,
+    /// `None` when the language isn't known from the file's name
+    pub language: Option<String>,
+    #[ts(type = "number")]
+    pub additions: i64,
+    #[ts(type = "number")]
+    pub deletions: i64,
+}
+
+/// Lines added and removed across the changed files of one language
+#[derive(Debug, Clone, PartialEq, Serialize, TS)]
+pub struct LanguageDiffStats {
+    /// `Other` for files of unknown languages
+    pub language: String,
+    #[ts(type = "number")]
+    pub files: i64,
+    #[ts(type = "number")]
+    pub additions: i64,
+    #[ts(type = "number")]
+    pub deletions: i64,
+}
+
+/// Size of an attempt's diff, without its contents
+#[derive(Debug, Clone, Default, PartialEq, Serialize, TS)]
+pub struct DiffStats {
+    #[ts(type = "number")]
+    pub files_changed: i64,
+    #[ts(type = "number")]
+    pub additions: i64,
+    #[ts(type = "number")]
+    pub deletions: i64,
+    /// Language with more than half of the changed lines
+    pub primary_language: Option<String>,
+    /// Most changed lines first
+    pub files: Vec<FileDiffStats>,
+    /// Most changed lines first
+    pub languages: Vec<LanguageDiffStats>,
+}
+
+pub fn language_of(path: &str) -> Option<&'static str> {
+    let file_name = path.rsplit('/').next().unwrap_or(path);
+    if let Some((_, language)) = FILE_NAME_LANGUAGES
+        .iter()
+        .find(|(name, _)| *name == file_name)
+    {
+        return Some(language);
+    }
+    let (stem, extension) = file_name.rsplit_once('.')?;
+    if stem.is_empty() {
+        // Dotfiles such as `.gitignore`
+        return None;
+    }
+    let extension = extension.to_lowercase();
+    EXTENSION_LANGUAGES
+        .iter()
+        .find(|(known, _)| *known == extension)
+        .map(|(_, language)| *language)
+}
+
+pub fn summarize(diffs: &[Diff]) -> DiffStats {
+    let mut stats = DiffStats::default();
+    let mut languages: HashMap<String, LanguageDiffStats> = HashMap::new();
+
+    for diff in diffs {
+        let (added, removed) =
+            changed_line_counts(content(&diff.old_file), content(&diff.new_file));
+        let path = GitService::diff_path(diff);
+        let language = language_of(&path);
+        let file = FileDiffStats {
+            language: language.map(str::to_string),
+            path,
+            additions: added as i64,
+            deletions: removed as i64,
+        };
+
+        let name = language.unwrap_or("Other");
+        let entry = languages
+            .entry(name.to_string())
+            .or_insert_with(|| LanguageDiffStats {
+                language: name.to_string(),
+                files: 0,
+                additions: 0,
+                deletions: 0,
+            });
+        entry.files += 1;
+        entry.additions += file.additions;
+        entry.deletions += file.deletions;
+
+        stats.files_changed += 1;
+        stats.additions += file.additions;
+        stats.deletions += file.deletions;
+        stats.files.push(file);
+    }
+
+    stats
+        .files
+        .sort_by(|a, b| (b.additions + b.deletions).cmp(&(a.additions + a.deletions)));
+    stats.languages = languages.into_values().collect();
+    stats.languages.sort_by(|a, b| {
+        (b.additions + b.deletions)
+            .cmp(&(a.additions + a.deletions))
+            .then_with(|| a.language.cmp(&b.language))
+    });
+
+    let lines_changed = stats.additions + stats.deletions;
+    stats.primary_language = stats
+        .languages
+        .first()
+        .filter(|top| {
+            top.language != "Other" && 2 * (top.additions + top.deletions) > lines_changed
+        })
+        .map(|top| top.language.clone());
+    stats
+}
+
+/// Binary files count as changed without lines
+fn content(file: &Option<FileDiffDetails>) -> &str {
+    file.as_ref()
+        .and_then(|f| f.content.as_deref())
+        .unwrap_or_default()
+}
+
+/// Diff statistics of attempts, recomputed only when their diff changes
+#[derive(Clone)]
+pub struct DiffStatsService {
+    diffs: DiffCache,
+    cache: Arc<RwLock<HashMap<Uuid, (String, DiffStats)>>>,
+}
+
+impl DiffStatsService {
+    pub fn new(diffs: DiffCache) -> Self {
+        Self {
+            diffs,
+            cache: Arc::default(),
+        }
+    }
+
+    /// The diffs the statistics are computed from, shared with the diff streams
+    pub fn diff_cache(&self) -> &DiffCache {
+        &self.diffs
+    }
+
+    /// Statistics of what the attempt changes on its base branch, see
+    /// `DiffCache::attempt_diffs`
+    pub async fn attempt_stats(
+        &self,
+        git: &GitService,
+        task_attempt: &TaskAttempt,
+        worktree_path: &Path,
+        project_repo_path: &Path,
+    ) -> Result<DiffStats, GitServiceError> {
+        let diffs =
+            self.diffs
+                .attempt_diffs(git, task_attempt, worktree_path, project_repo_path)?;
+        if let Some((cached, stats)) = self.cache.read().await.get(&task_attempt.id)
+            && *cached == diffs.key
+        {
+            return Ok(stats.clone());
+        }
+
+        let stats = summarize(&diffs.diffs);
+        self.cache
+            .write()
+            .await
+            .insert(task_attempt.id, (diffs.key, stats.clone()));
+        Ok(stats)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn file(name: &str, content: &str) -> Option<FileDiffDetails> {
+        Some(FileDiffDetails {
+            file_name: Some(name.to_string()),
+            content: Some(content.to_string()),
+        })
+    }
+
+    #[test]
+    fn test_language_of() {
+        assert_eq!(language_of("crates/server/src/main.rs"), Some("Rust"));
+        assert_eq!(language_of("frontend/src/App.TSX"), Some("TypeScript"));
+        assert_eq!(language_of("docker/Dockerfile"), Some("Dockerfile"));
+        assert_eq!(language_of(".gitignore"), None);
+        assert_eq!(language_of("LICENSE"), None);
+    }
+
+    #[test]
+    fn test_summarize_by_file_and_language() {
+        let diffs = [
+            Diff {
+                old_file: file("src/lib.rs", "a\nb\n"),
+                new_file: file("src/lib.rs", "a\nc\nd\ne\n"),
+                hunks: Vec::new(),
+            },
+            Diff {
+                old_file: None,
+                new_file: file("src/main.rs", "fn main() {}\n"),
+                hunks: Vec::new(),
+            },
+            Diff {
+                old_file: file("NOTES", "old\n"),
+                new_file: None,
+                hunks: Vec::new(),
+            },
+        ];
+
+        let stats = summarize(&diffs);
+        assert_eq!(
+            (stats.files_changed, stats.additions, stats.deletions),
+            (3, 4, 2)
+        );
+        assert_eq!(stats.primary_language.as_deref(), Some("Rust"));
+        assert_eq!(
+            stats.files[0],
+            FileDiffStats {
+                path: "src/lib.rs".to_string(),
+                language: Some("Rust".to_string()),
+                additions: 3,
+                deletions: 1,
+            }
+        );
+        assert_eq!(
+            stats.languages,
+            vec![
+                LanguageDiffStats {
+                    language: "Rust".to_string(),
+                    files: 2,
+                    additions: 4,
+                    deletions: 1,
+                },
+                LanguageDiffStats {
+                    language: "Other".to_string(),
+                    files: 1,
+                    additions: 0,
+                    deletions: 1,
+                },
+            ]
+        );
+
+        // Without a majority there is no primary language
+        let stats = summarize(&[
+            Diff {
+                old_file: None,
+                new_file: file("a.py", "x\n"),
+                hunks: Vec::new(),
+            },
+            Diff {
+                old_file: None,
+                new_file: file("b.go", "y\n"),
+                hunks: Vec::new(),
+            },
+        ]);
+        assert_eq!(stats.primary_language, None);
+    }
+}
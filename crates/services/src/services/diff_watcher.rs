@@ -0,0 +1,310 @@
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex, Weak},
+};
+
+use db::models::task_attempt::TaskAttempt;
+use futures::StreamExt;
+use notify::EventKind;
+use notify_debouncer_full::DebouncedEvent;
+use thiserror::Error;
+use tokio::{
+    sync::broadcast::{self, error::RecvError},
+    task::JoinHandle,
+};
+use utils::{diff::Diff, log_msg::LogMsg, msg_store::MsgStore};
+use uuid::Uuid;
+
+use super::{
+    diff_cache::DiffCache,
+    filesystem_watcher::{self, FilesystemWatcherError},
+    git::{DiffTarget, GitService, GitServiceError},
+};
+
+/// Changes kept for subscribers that fall behind, those further behind resync
+const CHANGES_CAPACITY: usize = 256;
+
+#[derive(Debug, Error)]
+pub enum DiffWatcherError {
+    #[error(transparent)]
+    Watcher(#[from] FilesystemWatcherError),
+    #[error(transparent)]
+    Git(#[from] GitServiceError),
+}
+
+/// Files of a worktree that changed, with their diffs against the base branch. Changed files
+/// without a diff are back to their base version.
+#[derive(Debug)]
+pub struct DiffChanges {
+    pub changed_paths: Vec<String>,
+    pub diffs: Vec<Diff>,
+}
+
+struct AttemptWatch {
+    worktree_path: PathBuf,
+    changes: broadcast::Sender<Arc<DiffChanges>>,
+    task: JoinHandle<()>,
+}
+
+impl Drop for AttemptWatch {
+    fn drop(&mut self) {
+        // Dropping the task's debouncer stops watching the worktree
+        self.task.abort();
+    }
+}
+
+/// Keeps an attempt's worktree watched until dropped
+pub struct DiffSubscription {
+    _watch: Arc<AttemptWatch>,
+    changes: broadcast::Receiver<Arc<DiffChanges>>,
+}
+
+impl DiffSubscription {
+    /// Next changes of the worktree. Subscribers that fell too far behind get
+    /// `RecvError::Lagged` and should diff the worktree again.
+    pub async fn recv(&mut self) -> Result<Arc<DiffChanges>, RecvError> {
+        self.changes.recv().await
+    }
+}
+
+/// One filesystem watcher per attempt worktree, shared by everything following its diff.
+/// Each batch of changed files is diffed once, patched into the diff cache and sent to every
+/// subscriber.
+#[derive(Clone)]
+pub struct DiffWatchers {
+    git: GitService,
+    diff_cache: DiffCache,
+    watches: Arc<Mutex<HashMap<Uuid, Weak<AttemptWatch>>>>,
+}
+
+impl DiffWatchers {
+    pub fn new(git: GitService, diff_cache: DiffCache) -> Self {
+        Self {
+            git,
+            diff_cache,
+            watches: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    /// Subscribe to the changes of an attempt's worktree, watching it unless it already is
+    pub fn subscribe(
+        &self,
+        attempt_id: Uuid,
+        worktree_path: &Path,
+        task_branch: &str,
+        base_branch: &str,
+    ) -> Result<DiffSubscription, DiffWatcherError> {
+        let mut watches = self.watches.lock().unwrap();
+        watches.retain(|_, watch| watch.strong_count() > 0);
+        if let Some(watch) = watches.get(&attempt_id).and_then(Weak::upgrade)
+            && watch.worktree_path == worktree_path
+        {
+            return Ok(DiffSubscription {
+                changes: watch.changes.subscribe(),
+                _watch: watch,
+            });
+        }
+
+        let (debouncer, mut events, canonical_path) =
+            filesystem_watcher::async_watcher(worktree_path.to_path_buf())?;
+        let (changes, receiver) = broadcast::channel(CHANGES_CAPACITY);
+        let sender = changes.clone();
+        let git = self.git.clone();
+        let diff_cache = self.diff_cache.clone();
+        let path = worktree_path.to_path_buf();
+        let task_branch = task_branch.to_string();
+        let base_branch = base_branch.to_string();
+        let task = tokio::spawn(async move {
+            let _debouncer = debouncer;
+            while let Some(result) = events.next().await {
+                let batch = match result {
+                    Ok(batch) => batch,
+                    Err(errors) => {
+                        tracing::warn!("Watching worktree {} failed: {:?}", path.display(), errors);
+                        continue;
+                    }
+                };
+                let changed_paths = changed_paths(&batch, &canonical_path, &path);
+                if changed_paths.is_empty() {
+                    continue;
+                }
+                match diff_changes(
+                    &git,
+                    &diff_cache,
+                    attempt_id,
+                    &path,
+                    &task_branch,
+                    &base_branch,
+                    changed_paths,
+                ) {
+                    // Without subscribers the changes only update the cache
+                    Ok(changes) => {
+                        let _ = sender.send(Arc::new(changes));
+                    }
+                    Err(e) => tracing::warn!(
+                        "Failed to diff changed files of attempt {}: {}",
+                        attempt_id,
+                        e
+                    ),
+                }
+            }
+        });
+
+        let watch = Arc::new(AttemptWatch {
+            worktree_path: worktree_path.to_path_buf(),
+            changes,
+            task,
+        });
+        watches.insert(attempt_id, Arc::downgrade(&watch));
+        Ok(DiffSubscription {
+            _watch: watch,
+            changes: receiver,
+        })
+    }
+
+    /// Keep the attempt's worktree watched while a coding agent edits it, until its output
+    /// finishes. Its diff is computed upfront and then patched, so it's current whenever
+    /// someone opens it.
+    pub fn watch_while_running(
+        &self,
+        task_attempt: &TaskAttempt,
+        worktree_path: &Path,
+        project_repo_path: &Path,
+        store: Arc<MsgStore>,
+    ) -> Result<JoinHandle<()>, DiffWatcherError> {
+        let subscription = self.subscribe(
+            task_attempt.id,
+            worktree_path,
+            task_attempt.branch.as_deref().unwrap_or_default(),
+            &task_attempt.base_branch,
+        )?;
+
+        let git = self.git.clone();
+        let diff_cache = self.diff_cache.clone();
+        let task_attempt = task_attempt.clone();
+        let worktree_path = worktree_path.to_path_buf();
+        let project_repo_path = project_repo_path.to_path_buf();
+        tokio::task::spawn_blocking(move || {
+            if let Err(e) =
+                diff_cache.attempt_diffs(&git, &task_attempt, &worktree_path, &project_repo_path)
+            {
+                tracing::warn!(
+                    "Failed to diff attempt {} before its agent runs: {}",
+                    task_attempt.id,
+                    e
+                );
+            }
+        });
+
+        Ok(tokio::spawn(async move {
+            let _subscription = subscription;
+            let mut stream = store.history_plus_stream();
+            while let Some(Ok(msg)) = stream.next().await {
+                if matches!(msg, LogMsg::Finished) {
+                    break;
+                }
+            }
+        }))
+    }
+}
+
+/// Worktree-relative paths of the files a batch of events changed, each once. Files only
+/// read are left out, diffing them would otherwise be reported as changing them.
+fn changed_paths(
+    events: &[DebouncedEvent],
+    canonical_worktree_path: &Path,
+    worktree_path: &Path,
+) -> Vec<String> {
+    let mut paths: Vec<String> = events
+        .iter()
+        .filter(|event| !matches!(event.kind, EventKind::Access(_)))
+        .flat_map(|event| &event.paths)
+        .filter_map(|path| {
+            path.strip_prefix(canonical_worktree_path)
+                .or_else(|_| path.strip_prefix(worktree_path))
+                .ok()
+                .map(|p| p.to_string_lossy().replace('\\', "/"))
+        })
+        .collect();
+    paths.sort();
+    paths.dedup();
+    paths
+}
+
+/// Diff the changed files and patch them into the cached diff
+fn diff_changes(
+    git: &GitService,
+    diff_cache: &DiffCache,
+    attempt_id: Uuid,
+    worktree_path: &Path,
+    task_branch: &str,
+    base_branch: &str,
+    changed_paths: Vec<String>,
+) -> Result<DiffChanges, GitServiceError> {
+    let path_filter: Vec<&str> = changed_paths.iter().map(String::as_str).collect();
+    // Read before diffing, later changes then make the cached diff outdated instead of being
+    // missed
+    let key = DiffCache::worktree_key(git, worktree_path, base_branch)?;
+    let diffs = git.get_diffs(
+        DiffTarget::Worktree {
+            worktree_path,
+            branch_name: task_branch,
+            base_branch,
+        },
+        Some(&path_filter),
+    )?;
+    diff_cache.apply_changes(attempt_id, key, &changed_paths, &diffs);
+    Ok(DiffChanges {
+        changed_paths,
+        diffs,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use tempfile::TempDir;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_subscribers_share_the_attempt_watcher() {
+        let dir = TempDir::new().unwrap();
+        let git = GitService::new();
+        git.initialize_repo_with_main_branch(dir.path()).unwrap();
+        let watchers = DiffWatchers::new(git, DiffCache::new());
+        let attempt_id = Uuid::new_v4();
+
+        let mut first = watchers
+            .subscribe(attempt_id, dir.path(), "main", "main")
+            .unwrap();
+        let mut second = watchers
+            .subscribe(attempt_id, dir.path(), "main", "main")
+            .unwrap();
+        assert!(Arc::ptr_eq(&first._watch, &second._watch));
+
+        std::fs::write(dir.path().join("notes.txt"), "hello\n").unwrap();
+        for subscription in [&mut first, &mut second] {
+            // Setting up the repository may still be reported first
+            let changes = tokio::time::timeout(Duration::from_secs(10), async {
+                loop {
+                    let changes = subscription.recv().await.unwrap();
+                    if changes.changed_paths.contains(&"notes.txt".to_string()) {
+                        return changes;
+                    }
+                }
+            })
+            .await
+            .unwrap();
+            assert_eq!(changes.diffs.len(), 1);
+        }
+
+        // The watcher stops with its last subscriber
+        let watch = Arc::downgrade(&first._watch);
+        drop(first);
+        drop(second);
+        assert!(watch.upgrade().is_none());
+    }
+}
@@ -0,0 +1,273 @@
+use std::{collections::HashSet, io, path::Path};
+
+use db::models::{
+    execution_process::ExecutionProcess, project_disk_quota::ProjectDiskQuota,
+    task_attempt::TaskAttempt,
+};
+use serde::Serialize;
+use sqlx::SqlitePool;
+use thiserror::Error;
+use ts_rs::TS;
+use uuid::Uuid;
+
+#[derive(Debug, Error)]
+pub enum DiskQuotaError {
+    #[error(transparent)]
+    Database(#[from] sqlx::Error),
+    #[error(transparent)]
+    Io(#[from] io::Error),
+    #[error(
+        "Worktrees of project {project} use {} of its {} disk quota.{}",
+        format_bytes(*.used_bytes),
+        format_bytes(*.max_bytes),
+        gc_hint(*.reclaimable_bytes)
+    )]
+    QuotaExceeded {
+        project: String,
+        used_bytes: i64,
+        max_bytes: i64,
+        reclaimable_bytes: i64,
+    },
+}
+
+fn gc_hint(reclaimable_bytes: i64) -> String {
+    if reclaimable_bytes > 0 {
+        format!(
+            " Cleaning up the worktrees of merged attempts would free {}.",
+            format_bytes(reclaimable_bytes)
+        )
+    } else {
+        " Delete attempts you no longer need to free space.".to_string()
+    }
+}
+
+pub fn format_bytes(bytes: i64) -> String {
+    const UNITS: &[&str] = &["KB", "MB", "GB", "TB"];
+    if bytes < 1024 {
+        return format!("{bytes} B");
+    }
+    let mut value = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    format!("{value:.1} {}", UNITS[unit])
+}
+
+/// Size of a directory's files, without following symlinks. Missing directories are empty.
+pub fn dir_size(path: &Path) -> io::Result<u64> {
+    let mut total = 0;
+    let mut pending = vec![path.to_path_buf()];
+    while let Some(dir) = pending.pop() {
+        let entries = match std::fs::read_dir(&dir) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => continue,
+            Err(e) => return Err(e),
+        };
+        for entry in entries {
+            let entry = entry?;
+            let metadata = match entry.metadata() {
+                Ok(metadata) => metadata,
+                // Removed while walking
+                Err(e) if e.kind() == io::ErrorKind::NotFound => continue,
+                Err(e) => return Err(e),
+            };
+            if metadata.is_dir() {
+                pending.push(entry.path());
+            } else if metadata.is_file() {
+                total += metadata.len();
+            }
+        }
+    }
+    Ok(total)
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, TS)]
+pub struct WorktreeUsage {
+    pub task_attempt_id: Uuid,
+    pub task_id: Uuid,
+    pub branch: Option<String>,
+    #[ts(type = "number")]
+    pub bytes: i64,
+    pub merged: bool,
+    /// Whether one of the attempt's processes is running, its worktree is then kept
+    pub running: bool,
+}
+
+impl WorktreeUsage {
+    fn collectable(&self) -> bool {
+        self.merged && !self.running
+    }
+}
+
+/// Disk space taken up by the worktrees of a project's attempts
+#[derive(Debug, Clone, PartialEq, Serialize, TS)]
+pub struct ProjectDiskUsage {
+    pub project_id: Uuid,
+    #[ts(type = "number")]
+    pub used_bytes: i64,
+    #[ts(type = "number | null")]
+    pub max_bytes: Option<i64>,
+    pub auto_gc: bool,
+    /// Taken up by worktrees of merged attempts that aren't running
+    #[ts(type = "number")]
+    pub reclaimable_bytes: i64,
+    /// Largest first
+    pub worktrees: Vec<WorktreeUsage>,
+}
+
+impl ProjectDiskUsage {
+    /// Measures the project's worktrees, which walks all of their files
+    pub async fn measure(pool: &SqlitePool, project_id: Uuid) -> Result<Self, DiskQuotaError> {
+        let quota = ProjectDiskQuota::find_by_project_id(pool, project_id).await?;
+        let attempts = TaskAttempt::find_with_worktree_by_project_id(pool, project_id).await?;
+        let running: HashSet<Uuid> = ExecutionProcess::find_running(pool)
+            .await?
+            .into_iter()
+            .map(|process| process.task_attempt_id)
+            .collect();
+
+        let paths: Vec<_> = attempts
+            .iter()
+            .map(|attempt| attempt.container_ref.clone().unwrap_or_default())
+            .collect();
+        let sizes = tokio::task::spawn_blocking(move || {
+            paths
+                .iter()
+                .map(|path| dir_size(Path::new(path)))
+                .collect::<io::Result<Vec<_>>>()
+        })
+        .await
+        .map_err(io::Error::other)??;
+
+        let worktrees = attempts
+            .iter()
+            .zip(sizes)
+            .map(|(attempt, bytes)| WorktreeUsage {
+                task_attempt_id: attempt.id,
+                task_id: attempt.task_id,
+                branch: attempt.branch.clone(),
+                bytes: bytes as i64,
+                merged: attempt.is_merged(),
+                running: running.contains(&attempt.id),
+            })
+            .collect();
+        Ok(Self::new(project_id, quota.as_ref(), worktrees))
+    }
+
+    fn new(
+        project_id: Uuid,
+        quota: Option<&ProjectDiskQuota>,
+        mut worktrees: Vec<WorktreeUsage>,
+    ) -> Self {
+        worktrees.sort_by(|a, b| b.bytes.cmp(&a.bytes));
+        Self {
+            project_id,
+            used_bytes: worktrees.iter().map(|w| w.bytes).sum(),
+            max_bytes: quota.map(|q| q.max_bytes),
+            auto_gc: quota.is_some_and(|q| q.auto_gc),
+            reclaimable_bytes: worktrees
+                .iter()
+                .filter(|w| w.collectable())
+                .map(|w| w.bytes)
+                .sum(),
+            worktrees,
+        }
+    }
+
+    pub fn exceeded(&self) -> bool {
+        self.max_bytes.is_some_and(|max| self.used_bytes > max)
+    }
+
+    /// Attempts whose worktrees can be removed: merged and not running. Their branches stay,
+    /// so the worktree is recreated if the attempt is opened again.
+    pub fn collectable_attempts(&self) -> Vec<Uuid> {
+        self.worktrees
+            .iter()
+            .filter(|w| w.collectable())
+            .map(|w| w.task_attempt_id)
+            .collect()
+    }
+
+    /// Whether a new attempt may start in the project, refused while its quota is exceeded
+    pub fn check(&self, project_name: &str) -> Result<(), DiskQuotaError> {
+        match self.max_bytes {
+            Some(max_bytes) if self.exceeded() => Err(DiskQuotaError::QuotaExceeded {
+                project: project_name.to_string(),
+                used_bytes: self.used_bytes,
+                max_bytes,
+                reclaimable_bytes: self.reclaimable_bytes,
+            }),
+            _ => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+
+    use super::*;
+
+    fn worktree(bytes: i64, merged: bool, running: bool) -> WorktreeUsage {
+        WorktreeUsage {
+            task_attempt_id: Uuid::new_v4(),
+            task_id: Uuid::new_v4(),
+            branch: None,
+            bytes,
+            merged,
+            running,
+        }
+    }
+
+    #[test]
+    fn test_check_refuses_exceeded_quota() {
+        let project_id = Uuid::new_v4();
+        let quota = ProjectDiskQuota {
+            project_id,
+            max_bytes: 3 * 1024 * 1024 * 1024,
+            auto_gc: false,
+            updated_at: Utc::now(),
+        };
+        let merged = worktree(2 * 1024 * 1024 * 1024, true, false);
+        let merged_running = worktree(1024 * 1024 * 1024, true, true);
+        let open = worktree(1024 * 1024 * 1024, false, false);
+        let usage = ProjectDiskUsage::new(
+            project_id,
+            Some(&quota),
+            vec![open.clone(), merged.clone(), merged_running],
+        );
+
+        assert!(usage.exceeded());
+        assert_eq!(usage.worktrees[0], merged);
+        assert_eq!(usage.collectable_attempts(), vec![merged.task_attempt_id]);
+        assert_eq!(
+            usage.check("web").unwrap_err().to_string(),
+            "Worktrees of project web use 4.0 GB of its 3.0 GB disk quota. Cleaning up the \
+             worktrees of merged attempts would free 2.0 GB."
+        );
+
+        // Without a quota nothing is refused
+        let usage = ProjectDiskUsage::new(project_id, None, vec![open]);
+        assert!(usage.check("web").is_ok());
+    }
+
+    #[test]
+    fn test_dir_size() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(dir.path().join("node_modules/a")).unwrap();
+        std::fs::write(dir.path().join("index.js"), "x".repeat(10)).unwrap();
+        std::fs::write(dir.path().join("node_modules/a/index.js"), "y".repeat(5)).unwrap();
+
+        assert_eq!(dir_size(dir.path()).unwrap(), 15);
+        assert_eq!(dir_size(&dir.path().join("missing")).unwrap(), 0);
+    }
+
+    #[test]
+    fn test_format_bytes() {
+        assert_eq!(format_bytes(512), "512 B");
+        assert_eq!(format_bytes(1536), "1.5 KB");
+        assert_eq!(format_bytes(5 * 1024 * 1024 * 1024), "5.0 GB");
+    }
+}
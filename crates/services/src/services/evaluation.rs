@@ -0,0 +1,551 @@
+use std::{path::Path, time::Duration};
+
+use db::models::{
+    attempt_usage::AttemptUsage,
+    evaluation::{
+        CreateEvaluationRun, Evaluation, EvaluationRun, EvaluationRunResult, EvaluationRunStatus,
+        EvaluationStatus,
+    },
+    execution_process::{ExecutionProcess, ExecutionProcessRunReason, ExecutionProcessStatus},
+    project::Project,
+    task::{Task, TaskStatus},
+    task_attempt::{CreateTaskAttempt, TaskAttempt, TaskAttemptError},
+    test_run::TestRun,
+};
+use executors::{
+    actions::{ExecutorActionType, script::ScriptContext},
+    executors::CodingAgent,
+    profile::ProfileVariantLabel,
+};
+use git2::{BranchType, Oid, Repository};
+use serde::{Deserialize, Serialize};
+use sqlx::SqlitePool;
+use thiserror::Error;
+use tokio::time::Instant;
+use ts_rs::TS;
+use uuid::Uuid;
+
+use super::container::{ContainerError, ContainerService};
+
+/// Replays are driven one at a time, polled until none of their executions run
+const POLL_INTERVAL: Duration = Duration::from_secs(5);
+/// Polls in a row without a running execution before an attempt counts as settled, so the
+/// gap between one stage of the chain and the next isn't taken for the end
+const SETTLED_POLLS: u32 = 2;
+/// Replays still running after this are stopped and failed
+const RUN_TIMEOUT: Duration = Duration::from_secs(60 * 60);
+
+#[derive(Debug, Error)]
+pub enum EvaluationError {
+    #[error(transparent)]
+    Database(#[from] sqlx::Error),
+    #[error(transparent)]
+    TaskAttempt(#[from] TaskAttemptError),
+    #[error(transparent)]
+    Git(#[from] git2::Error),
+    #[error(transparent)]
+    Container(#[from] ContainerError),
+    #[error("{0}")]
+    Invalid(String),
+    #[error("The replay didn't finish within {} minutes", RUN_TIMEOUT.as_secs() / 60)]
+    TimedOut,
+    #[error("The evaluation was cancelled")]
+    Cancelled,
+}
+
+#[derive(Debug, Deserialize, TS)]
+pub struct CreateEvaluation {
+    pub project_id: Uuid,
+    pub name: String,
+    /// Done tasks to replay, every done task of the project when empty
+    #[serde(default)]
+    pub task_ids: Vec<Uuid>,
+    pub profiles: Vec<ProfileVariantLabel>,
+}
+
+/// How one profile variant did across the replayed tasks
+#[derive(Debug, Clone, PartialEq, Serialize, TS)]
+pub struct ProfileScore {
+    pub profile: String,
+    pub variant: Option<String>,
+    pub runs: u32,
+    /// Runs that were scored or failed, failed ones scoring 0
+    pub finished: u32,
+    pub agent_completed: u32,
+    pub verified: u32,
+    /// Mean score of the finished runs, from 0 to 1
+    pub mean_score: f64,
+    pub cost_usd: f64,
+    #[ts(type = "number")]
+    pub input_tokens: i64,
+    #[ts(type = "number")]
+    pub output_tokens: i64,
+    /// `None` until a run completes
+    pub mean_duration_seconds: Option<f64>,
+}
+
+/// The runs of an evaluation and the profiles ranked by score, then by cost
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct EvaluationReport {
+    pub evaluation: Evaluation,
+    pub runs: Vec<EvaluationRun>,
+    pub leaderboard: Vec<ProfileScore>,
+}
+
+impl EvaluationReport {
+    pub async fn load(pool: &SqlitePool, id: Uuid) -> Result<Option<Self>, sqlx::Error> {
+        let Some(evaluation) = Evaluation::find_by_id(pool, id).await? else {
+            return Ok(None);
+        };
+        let runs = EvaluationRun::find_by_evaluation_id(pool, id).await?;
+        Ok(Some(Self {
+            evaluation,
+            leaderboard: leaderboard(&runs),
+            runs,
+        }))
+    }
+}
+
+/// Score of a settled replay from 0 to 1. An agent that didn't complete scores 0. Otherwise
+/// the share of passing tests when the tests reported results, else whether the
+/// verification or test script passed. Without either, completing counts as passing.
+pub fn score(agent_completed: bool, verified: Option<bool>, tests: Option<(i64, i64)>) -> f64 {
+    if !agent_completed {
+        return 0.0;
+    }
+    match tests {
+        Some((passed, failed)) if passed + failed > 0 => passed as f64 / (passed + failed) as f64,
+        _ => match verified {
+            Some(false) => 0.0,
+            Some(true) | None => 1.0,
+        },
+    }
+}
+
+pub fn leaderboard(runs: &[EvaluationRun]) -> Vec<ProfileScore> {
+    let mut scores: Vec<ProfileScore> = Vec::new();
+    let mut durations: Vec<(i64, u32)> = Vec::new();
+    for run in runs {
+        let index = match scores
+            .iter()
+            .position(|s| s.profile == run.profile && s.variant == run.variant)
+        {
+            Some(index) => index,
+            None => {
+                scores.push(ProfileScore {
+                    profile: run.profile.clone(),
+                    variant: run.variant.clone(),
+                    runs: 0,
+                    finished: 0,
+                    agent_completed: 0,
+                    verified: 0,
+                    mean_score: 0.0,
+                    cost_usd: 0.0,
+                    input_tokens: 0,
+                    output_tokens: 0,
+                    mean_duration_seconds: None,
+                });
+                durations.push((0, 0));
+                scores.len() - 1
+            }
+        };
+        let entry = &mut scores[index];
+        entry.runs += 1;
+        match run.status {
+            EvaluationRunStatus::Completed => {
+                entry.finished += 1;
+                entry.mean_score += run.score.unwrap_or(0.0);
+                if run.agent_completed == Some(true) {
+                    entry.agent_completed += 1;
+                }
+                if run.verified == Some(true) {
+                    entry.verified += 1;
+                }
+                if let Some(seconds) = run.duration_seconds {
+                    durations[index].0 += seconds;
+                    durations[index].1 += 1;
+                }
+            }
+            EvaluationRunStatus::Failed => entry.finished += 1,
+            EvaluationRunStatus::Pending | EvaluationRunStatus::Running => {}
+        }
+        entry.cost_usd += run.cost_usd.unwrap_or(0.0);
+        entry.input_tokens += run.input_tokens.unwrap_or(0);
+        entry.output_tokens += run.output_tokens.unwrap_or(0);
+    }
+    for (entry, (seconds, counted)) in scores.iter_mut().zip(durations) {
+        if entry.finished > 0 {
+            entry.mean_score /= entry.finished as f64;
+        }
+        if counted > 0 {
+            entry.mean_duration_seconds = Some(seconds as f64 / counted as f64);
+        }
+    }
+    scores.sort_by(|a, b| {
+        b.mean_score
+            .total_cmp(&a.mean_score)
+            .then(a.cost_usd.total_cmp(&b.cost_usd))
+    });
+    scores
+}
+
+/// Commit the task was started from: the base before the attempt's squash merge, or where
+/// the latest attempt's branch forked from its base branch
+fn replay_commit(repo: &Repository, attempts: &[TaskAttempt]) -> Result<Option<Oid>, git2::Error> {
+    for attempt in attempts {
+        if let Some(merge_commit) = &attempt.merge_commit
+            && let Ok(commit) = repo.find_commit(Oid::from_str(merge_commit)?)
+        {
+            return Ok(Some(commit.parent_id(0)?));
+        }
+    }
+    for attempt in attempts {
+        let Some(branch) = &attempt.branch else {
+            continue;
+        };
+        let (Ok(branch), Ok(base)) = (
+            repo.find_branch(branch, BranchType::Local),
+            repo.find_branch(&attempt.base_branch, BranchType::Local),
+        ) else {
+            continue;
+        };
+        if let (Some(tip), Some(base)) = (branch.get().target(), base.get().target()) {
+            return Ok(Some(repo.merge_base(tip, base)?));
+        }
+    }
+    Ok(None)
+}
+
+fn replay_branch(evaluation_id: Uuid, task_id: Uuid) -> String {
+    let short = |id: Uuid| id.simple().to_string()[..8].to_string();
+    format!("vk-eval/{}-{}", short(evaluation_id), short(task_id))
+}
+
+/// Record an evaluation of the project's done tasks and the runs replaying them, each from a
+/// local branch at the commit its task started from. Start it with `run`.
+pub async fn create(
+    pool: &SqlitePool,
+    project: &Project,
+    request: &CreateEvaluation,
+) -> Result<Evaluation, EvaluationError> {
+    let name = request.name.trim();
+    if name.is_empty() {
+        return Err(EvaluationError::Invalid(
+            "The evaluation needs a name".to_string(),
+        ));
+    }
+    let mut profiles: Vec<&ProfileVariantLabel> = Vec::new();
+    for label in &request.profiles {
+        CodingAgent::from_profile_variant_label(label)
+            .map_err(|e| EvaluationError::Invalid(e.to_string()))?;
+        if !profiles.contains(&label) {
+            profiles.push(label);
+        }
+    }
+    if profiles.is_empty() {
+        return Err(EvaluationError::Invalid(
+            "Pick at least one profile to evaluate".to_string(),
+        ));
+    }
+
+    // Ids and titles of the tasks to replay
+    let tasks: Vec<(Uuid, String)> = if request.task_ids.is_empty() {
+        Task::find_by_project_id_with_attempt_status(pool, project.id)
+            .await?
+            .into_iter()
+            .filter(|task| task.status == TaskStatus::Done)
+            .map(|task| (task.id, task.title))
+            .collect()
+    } else {
+        let mut tasks = Vec::new();
+        for id in &request.task_ids {
+            let task = Task::find_by_id_and_project_id(pool, *id, project.id)
+                .await?
+                .ok_or_else(|| {
+                    EvaluationError::Invalid(format!("Task {id} isn't in the project"))
+                })?;
+            if task.status != TaskStatus::Done {
+                return Err(EvaluationError::Invalid(format!(
+                    "Task \"{}\" isn't done",
+                    task.title
+                )));
+            }
+            tasks.push((task.id, task.title));
+        }
+        tasks
+    };
+    if tasks.is_empty() {
+        return Err(EvaluationError::Invalid(
+            "The project has no done tasks to replay".to_string(),
+        ));
+    }
+
+    let mut starts = Vec::new();
+    for (task_id, title) in &tasks {
+        let attempts = TaskAttempt::fetch_all(pool, Some(*task_id)).await?;
+        let repo = Repository::open(&project.git_repo_path)?;
+        let commit = replay_commit(&repo, &attempts)?.ok_or_else(|| {
+            EvaluationError::Invalid(format!(
+                "Task \"{title}\" has no merged attempt or attempt branch to replay from"
+            ))
+        })?;
+        starts.push((*task_id, commit));
+    }
+
+    let evaluation = Evaluation::create(pool, project.id, name).await?;
+    for (task_id, commit) in starts {
+        let base_branch = replay_branch(evaluation.id, task_id);
+        create_branch(&project.git_repo_path, &base_branch, commit)?;
+        for label in &profiles {
+            EvaluationRun::create(
+                pool,
+                evaluation.id,
+                &CreateEvaluationRun {
+                    task_id,
+                    profile: label.profile.clone(),
+                    variant: label.variant.clone(),
+                    base_branch: base_branch.clone(),
+                },
+            )
+            .await?;
+        }
+    }
+    Ok(evaluation)
+}
+
+fn create_branch(repo_path: &Path, name: &str, commit: Oid) -> Result<(), git2::Error> {
+    let repo = Repository::open(repo_path)?;
+    let commit = repo.find_commit(commit)?;
+    repo.branch(name, &commit, true)?;
+    Ok(())
+}
+
+/// Replay the pending runs of an evaluation one after another, then complete it
+pub async fn run<C: ContainerService + Sync>(container: &C, evaluation_id: Uuid) {
+    let pool = &container.db().pool;
+    if let Err(e) = run_pending(container, evaluation_id).await {
+        tracing::error!("Evaluation {} failed: {}", evaluation_id, e);
+        if let Err(e) = Evaluation::finish(
+            pool,
+            evaluation_id,
+            EvaluationStatus::Failed,
+            Some(&e.to_string()),
+        )
+        .await
+        {
+            tracing::error!(
+                "Failed to record evaluation {} failing: {}",
+                evaluation_id,
+                e
+            );
+        }
+    }
+}
+
+async fn run_pending<C: ContainerService + Sync>(
+    container: &C,
+    evaluation_id: Uuid,
+) -> Result<(), EvaluationError> {
+    let pool = &container.db().pool;
+    for run in EvaluationRun::find_by_evaluation_id(pool, evaluation_id).await? {
+        if run.status != EvaluationRunStatus::Pending {
+            continue;
+        }
+        if !is_running(pool, evaluation_id).await? {
+            return Ok(());
+        }
+        match replay(container, &run).await {
+            Ok(result) => EvaluationRun::complete(pool, run.id, &result).await?,
+            Err(e) => {
+                tracing::warn!("Evaluation run {} failed: {}", run.id, e);
+                EvaluationRun::fail(pool, run.id, &e.to_string()).await?;
+            }
+        }
+    }
+    Evaluation::finish(pool, evaluation_id, EvaluationStatus::Completed, None).await?;
+    Ok(())
+}
+
+async fn is_running(pool: &SqlitePool, evaluation_id: Uuid) -> Result<bool, sqlx::Error> {
+    Ok(Evaluation::find_by_id(pool, evaluation_id)
+        .await?
+        .is_some_and(|evaluation| evaluation.status == EvaluationStatus::Running))
+}
+
+async fn replay<C: ContainerService + Sync>(
+    container: &C,
+    run: &EvaluationRun,
+) -> Result<EvaluationRunResult, EvaluationError> {
+    let pool = &container.db().pool;
+    let task = Task::find_by_id(pool, run.task_id)
+        .await?
+        .ok_or(sqlx::Error::RowNotFound)?;
+    let attempt = TaskAttempt::create(
+        pool,
+        &CreateTaskAttempt {
+            profile: run.profile.clone(),
+            base_branch: run.base_branch.clone(),
+        },
+        task.id,
+    )
+    .await?;
+    EvaluationRun::start(pool, run.id, attempt.id).await?;
+
+    let label = ProfileVariantLabel {
+        profile: run.profile.clone(),
+        variant: run.variant.clone(),
+    };
+    let settled = match container.start_attempt(&attempt, label, false).await {
+        Ok(_) => wait_until_settled(container, &attempt, run.evaluation_id).await,
+        Err(e) => Err(e.into()),
+    };
+    // Running the attempt moved the task, put it back where it was
+    Task::update_status(pool, task.id, task.status).await?;
+    settled?;
+    score_attempt(pool, attempt.id).await
+}
+
+async fn wait_until_settled<C: ContainerService + Sync>(
+    container: &C,
+    attempt: &TaskAttempt,
+    evaluation_id: Uuid,
+) -> Result<(), EvaluationError> {
+    let pool = &container.db().pool;
+    let deadline = Instant::now() + RUN_TIMEOUT;
+    let mut idle_polls = 0;
+    loop {
+        tokio::time::sleep(POLL_INTERVAL).await;
+        if !is_running(pool, evaluation_id).await? {
+            container.try_stop(attempt).await;
+            return Err(EvaluationError::Cancelled);
+        }
+        if Instant::now() >= deadline {
+            container.try_stop(attempt).await;
+            return Err(EvaluationError::TimedOut);
+        }
+        let processes = ExecutionProcess::find_by_task_attempt_id(pool, attempt.id).await?;
+        if processes
+            .iter()
+            .any(|process| process.status == ExecutionProcessStatus::Running)
+        {
+            idle_polls = 0;
+        } else {
+            idle_polls += 1;
+            if idle_polls >= SETTLED_POLLS {
+                return Ok(());
+            }
+        }
+    }
+}
+
+pub(crate) fn is_verification(process: &ExecutionProcess) -> bool {
+    process.executor_action().is_ok_and(|action| {
+        matches!(
+            action.typ(),
+            ExecutorActionType::ScriptRequest(script) if matches!(
+                script.context,
+                ScriptContext::VerificationScript | ScriptContext::TestScript
+            )
+        )
+    })
+}
+
+async fn score_attempt(
+    pool: &SqlitePool,
+    task_attempt_id: Uuid,
+) -> Result<EvaluationRunResult, EvaluationError> {
+    let processes = ExecutionProcess::find_by_task_attempt_id(pool, task_attempt_id).await?;
+    let agent_runs: Vec<&ExecutionProcess> = processes
+        .iter()
+        .filter(|process| process.run_reason == ExecutionProcessRunReason::CodingAgent)
+        .collect();
+    let agent_completed = agent_runs
+        .last()
+        .is_some_and(|process| process.status == ExecutionProcessStatus::Completed);
+    let verified = processes
+        .iter()
+        .rev()
+        .find(|process| is_verification(process))
+        .map(|process| process.status == ExecutionProcessStatus::Completed);
+    let tests = TestRun::find_by_task_attempt_id(pool, task_attempt_id)
+        .await?
+        .first()
+        .map(|run| (run.passed, run.failed));
+    let duration_seconds = agent_runs
+        .iter()
+        .filter_map(|process| {
+            process
+                .completed_at
+                .map(|end| (end - process.started_at).num_seconds().max(0))
+        })
+        .sum();
+    let usage = AttemptUsage::find_by_id(pool, task_attempt_id).await?;
+    Ok(EvaluationRunResult {
+        agent_completed,
+        verified,
+        tests_passed: tests.map(|(passed, _)| passed),
+        tests_failed: tests.map(|(_, failed)| failed),
+        score: score(agent_completed, verified, tests),
+        cost_usd: usage.as_ref().map_or(0.0, |usage| usage.cost_usd),
+        input_tokens: usage.as_ref().map_or(0, |usage| usage.input_tokens),
+        output_tokens: usage.as_ref().map_or(0, |usage| usage.output_tokens),
+        duration_seconds,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+
+    use super::*;
+
+    fn run(profile: &str, status: EvaluationRunStatus, score: f64, cost: f64) -> EvaluationRun {
+        EvaluationRun {
+            id: Uuid::new_v4(),
+            evaluation_id: Uuid::nil(),
+            task_id: Uuid::new_v4(),
+            profile: profile.to_string(),
+            variant: None,
+            base_branch: "vk-eval/x".to_string(),
+            task_attempt_id: None,
+            status,
+            error: None,
+            agent_completed: Some(score > 0.0),
+            verified: Some(score == 1.0),
+            tests_passed: None,
+            tests_failed: None,
+            score: Some(score),
+            cost_usd: Some(cost),
+            input_tokens: Some(1_000),
+            output_tokens: Some(100),
+            duration_seconds: Some(60),
+            created_at: Utc::now(),
+            completed_at: None,
+        }
+    }
+
+    #[test]
+    fn test_leaderboard_ranks_by_score_then_cost() {
+        assert_eq!(score(false, Some(true), Some((10, 0))), 0.0);
+        assert_eq!(score(true, Some(false), Some((3, 1))), 0.75);
+        assert_eq!(score(true, Some(false), None), 0.0);
+        assert_eq!(score(true, None, Some((0, 0))), 1.0);
+
+        let runs = vec![
+            run("CLAUDE_CODE", EvaluationRunStatus::Completed, 1.0, 2.0),
+            run("CLAUDE_CODE", EvaluationRunStatus::Failed, 0.0, 1.0),
+            run("CODEX", EvaluationRunStatus::Completed, 1.0, 0.5),
+            run("CODEX", EvaluationRunStatus::Completed, 0.0, 0.5),
+            run("GEMINI", EvaluationRunStatus::Completed, 1.0, 0.1),
+            run("GEMINI", EvaluationRunStatus::Pending, 0.0, 0.0),
+        ];
+        let board = leaderboard(&runs);
+        let order: Vec<&str> = board.iter().map(|s| s.profile.as_str()).collect();
+        // Gemini's pending run doesn't count, the tie between the others goes to the cheaper
+        assert_eq!(order, ["GEMINI", "CODEX", "CLAUDE_CODE"]);
+        assert_eq!(board[2].finished, 2);
+        assert_eq!(board[2].mean_score, 0.5);
+        assert_eq!(board[2].mean_duration_seconds, Some(60.0));
+        assert_eq!(board[1].agent_completed, 1);
+    }
+}
@@ -0,0 +1,145 @@
+use serde::{Deserialize, Serialize};
+use strum::IntoEnumIterator;
+use strum_macros::{Display, EnumIter};
+use ts_rs::TS;
+
+use crate::services::config::FeatureFlags;
+
+/// An experimental subsystem gated by [`FeatureFlags`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS, Display, EnumIter)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum FeatureFlag {
+    MultiAgentPipelines,
+    ContainerSandbox,
+    RemoteExec,
+    MultiRepoAttempts,
+}
+
+impl FeatureFlag {
+    /// Variable overriding the configured value, e.g. `VK_FEATURE_CONTAINER_SANDBOX=0`
+    pub fn env_var(self) -> String {
+        format!("VK_FEATURE_{}", self.to_string().to_uppercase())
+    }
+
+    pub fn description(self) -> &'static str {
+        match self {
+            FeatureFlag::MultiAgentPipelines => {
+                "Reviewer agents and attempts that plan before they execute"
+            }
+            FeatureFlag::ContainerSandbox => "Run attempt processes in the project's devcontainer",
+            FeatureFlag::RemoteExec => "Run agents on another machine, not available yet",
+            FeatureFlag::MultiRepoAttempts => {
+                "Attempts that change linked projects' repositories together"
+            }
+        }
+    }
+
+    fn configured(self, flags: &FeatureFlags) -> bool {
+        match self {
+            FeatureFlag::MultiAgentPipelines => flags.multi_agent_pipelines,
+            FeatureFlag::ContainerSandbox => flags.container_sandbox,
+            FeatureFlag::RemoteExec => flags.remote_exec,
+            FeatureFlag::MultiRepoAttempts => flags.multi_repo_attempts,
+        }
+    }
+
+    /// Change the configured value, overrides from the environment still win
+    pub fn set(self, flags: &mut FeatureFlags, enabled: bool) {
+        let value = match self {
+            FeatureFlag::MultiAgentPipelines => &mut flags.multi_agent_pipelines,
+            FeatureFlag::ContainerSandbox => &mut flags.container_sandbox,
+            FeatureFlag::RemoteExec => &mut flags.remote_exec,
+            FeatureFlag::MultiRepoAttempts => &mut flags.multi_repo_attempts,
+        };
+        *value = enabled;
+    }
+}
+
+/// A flag's value and where it comes from, for the settings page
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct FeatureFlagState {
+    pub flag: FeatureFlag,
+    pub description: String,
+    /// Whether the subsystem is in use, the override if there is one
+    pub enabled: bool,
+    /// Value from the config file
+    pub configured: bool,
+    pub env_var: String,
+    /// Value of `env_var` when it is set, it can't be toggled from the app then
+    pub env_override: Option<bool>,
+}
+
+fn parse_override(value: &str) -> Option<bool> {
+    match value.trim().to_ascii_lowercase().as_str() {
+        "1" | "true" | "on" | "yes" => Some(true),
+        "0" | "false" | "off" | "no" => Some(false),
+        _ => None,
+    }
+}
+
+/// The flag's value from its environment variable, unset or unparseable values don't count
+pub fn env_override(flag: FeatureFlag) -> Option<bool> {
+    let value = std::env::var(flag.env_var()).ok()?;
+    let parsed = parse_override(&value);
+    if parsed.is_none() {
+        tracing::warn!("Ignoring {}={}, expected 1 or 0", flag.env_var(), value);
+    }
+    parsed
+}
+
+pub fn is_enabled(flags: &FeatureFlags, flag: FeatureFlag) -> bool {
+    env_override(flag).unwrap_or_else(|| flag.configured(flags))
+}
+
+/// The flags in effect, the config with environment overrides applied
+pub fn effective(flags: &FeatureFlags) -> FeatureFlags {
+    let mut effective = flags.clone();
+    for flag in FeatureFlag::iter() {
+        flag.set(&mut effective, is_enabled(flags, flag));
+    }
+    effective
+}
+
+pub fn states(flags: &FeatureFlags) -> Vec<FeatureFlagState> {
+    FeatureFlag::iter()
+        .map(|flag| {
+            let env_override = env_override(flag);
+            FeatureFlagState {
+                flag,
+                description: flag.description().to_string(),
+                enabled: env_override.unwrap_or_else(|| flag.configured(flags)),
+                configured: flag.configured(flags),
+                env_var: flag.env_var(),
+                env_override,
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_flags_map_to_config_fields_and_variables() {
+        let mut flags = FeatureFlags::default();
+        FeatureFlag::RemoteExec.set(&mut flags, true);
+        FeatureFlag::ContainerSandbox.set(&mut flags, false);
+        assert!(flags.remote_exec);
+        assert!(!flags.container_sandbox);
+        assert!(flags.multi_agent_pipelines);
+        assert_eq!(
+            FeatureFlag::MultiAgentPipelines.env_var(),
+            "VK_FEATURE_MULTI_AGENT_PIPELINES"
+        );
+    }
+
+    #[test]
+    fn test_parse_override() {
+        assert_eq!(parse_override(" TRUE "), Some(true));
+        assert_eq!(parse_override("0"), Some(false));
+        assert_eq!(parse_override("off"), Some(false));
+        assert_eq!(parse_override("maybe"), None);
+    }
+}
@@ -1,9 +1,14 @@
-use std::path::Path;
+use std::{
+    hash::{DefaultHasher, Hash, Hasher},
+    path::Path,
+};
 
 use chrono::{DateTime, Utc};
+use db::models::{attempt_commit_plan::PlannedCommit, branch_sync::BranchSyncStrategy};
 use git2::{
-    BranchType, CherrypickOptions, Cred, Delta, DiffFindOptions, DiffOptions, Error as GitError,
-    FetchOptions, RemoteCallbacks, Repository, Status, StatusOptions, build::CheckoutBuilder,
+    BranchType, CherrypickOptions, Commit, Cred, Delta, DiffFindOptions, DiffOptions,
+    Error as GitError, FetchOptions, Index, RemoteCallbacks, Repository, Sort, Status,
+    StatusOptions, build::CheckoutBuilder,
 };
 use regex;
 use serde::{Deserialize, Serialize};
@@ -29,17 +34,44 @@ pub enum GitServiceError {
     WorktreeDirty(String),
     #[error("Invalid file paths: {0}")]
     InvalidFilePaths(String),
+    #[error("Branch {0} contains merge commits and can only be synced by merging")]
+    MergeCommitsInBranch(String),
+    #[error("Commit {0} is not on branch {1}")]
+    CommitNotOnBranch(String, String),
+    #[error("Ref not found: {0}")]
+    RefNotFound(String),
 }
 
 /// Service for managing Git operations in task execution workflows
 #[derive(Clone)]
 pub struct GitService {}
 
+/// How far a clone got
+#[derive(Debug, Clone, Serialize, TS)]
+#[serde(tag = "stage", rename_all = "snake_case")]
+pub enum CloneProgress {
+    Receiving {
+        received_objects: usize,
+        total_objects: usize,
+        received_bytes: usize,
+    },
+    Resolving {
+        indexed_deltas: usize,
+        total_deltas: usize,
+    },
+    CheckingOut {
+        completed: usize,
+        total: usize,
+    },
+}
+
 #[derive(Debug, Serialize, TS)]
 pub struct GitBranch {
     pub name: String,
     pub is_current: bool,
     pub is_remote: bool,
+    /// A tag rather than a branch, attempts can start from it but not merge into it
+    pub is_tag: bool,
     #[ts(type = "Date")]
     pub last_commit_date: DateTime<Utc>,
 }
@@ -55,6 +87,31 @@ pub struct BranchStatus {
     pub base_branch_name: String,
 }
 
+/// A commit of a worktree's files on top of its HEAD
+#[derive(Debug, Clone, PartialEq)]
+pub struct WorktreeSnapshot {
+    pub commit: String,
+    pub head: String,
+}
+
+/// Result of bringing a branch up to date with its base branch
+#[derive(Debug, Clone, PartialEq)]
+pub enum BaseSyncOutcome {
+    /// The branch already contains the base branch
+    UpToDate,
+    /// The branch now contains the base branch and points to this commit
+    Synced(String),
+    /// Files conflicting with the base branch, the branch was left untouched
+    Conflicts(Vec<String>),
+}
+
+#[derive(Debug, Clone)]
+pub struct BaseSync {
+    /// Base branch commit the branch was synced with
+    pub base_commit: String,
+    pub outcome: BaseSyncOutcome,
+}
+
 /// Target for diff generation
 pub enum DiffTarget<'p> {
     /// Work-in-progress branch checked out in this worktree
@@ -69,6 +126,12 @@ pub enum DiffTarget<'p> {
         branch_name: &'p str,
         base_branch: &'p str,
     },
+    /// Work in progress in this worktree vs any ref: a branch, a tag, a remote branch or a
+    /// commit
+    WorktreeAgainst {
+        worktree_path: &'p Path,
+        against: &'p str,
+    },
     /// Specific commit vs base branch
     Commit {
         repo_path: &'p Path,
@@ -158,6 +221,7 @@ impl GitService {
         Ok(())
     }
 
+    #[tracing::instrument(skip_all)]
     pub fn commit(&self, path: &Path, message: &str) -> Result<(), GitServiceError> {
         let repo = Repository::open(path)?;
 
@@ -191,7 +255,9 @@ impl GitService {
         let tree_id = index.write_tree()?;
         let tree = repo.find_tree(tree_id)?;
 
-        let signature = repo.signature()?;
+        let signature = repo
+            .signature()
+            .or_else(|_| git2::Signature::now("Vibe Kanban", "noreply@vibekanban.com"))?;
         repo.commit(
             Some("HEAD"),
             &signature,
@@ -217,32 +283,20 @@ impl GitService {
                 base_branch,
             } => {
                 let repo = Repository::open(worktree_path)?;
-                let base_ref = repo
-                    .find_branch(base_branch, BranchType::Local)
-                    .map_err(|_| GitServiceError::BranchNotFound(base_branch.to_string()))?;
-                let base_tree = base_ref.get().peel_to_commit()?.tree()?;
-
-                let mut diff_opts = DiffOptions::new();
-                diff_opts
-                    .include_untracked(true)
-                    .include_typechange(true)
-                    .recurse_untracked_dirs(true);
-
-                // Add path filtering if specified
-                if let Some(paths) = path_filter {
-                    for path in paths {
-                        diff_opts.pathspec(*path);
-                    }
-                }
-
-                let mut diff =
-                    repo.diff_tree_to_workdir_with_index(Some(&base_tree), Some(&mut diff_opts))?;
-
-                // Enable rename detection
-                let mut find_opts = DiffFindOptions::new();
-                diff.find_similar(Some(&mut find_opts))?;
-
-                self.convert_diff_to_file_diffs(diff, &repo)
+                let base_tree = Self::find_branch_commit(&repo, base_branch)?.tree()?;
+                self.diff_tree_to_worktree(&repo, &base_tree, path_filter)
+            }
+            DiffTarget::WorktreeAgainst {
+                worktree_path,
+                against,
+            } => {
+                let repo = Repository::open(worktree_path)?;
+                let base_tree = repo
+                    .revparse_single(against)
+                    .and_then(|object| object.peel_to_commit())
+                    .map_err(|_| GitServiceError::RefNotFound(against.to_string()))?
+                    .tree()?;
+                self.diff_tree_to_worktree(&repo, &base_tree, path_filter)
             }
             DiffTarget::Branch {
                 repo_path,
@@ -250,12 +304,7 @@ impl GitService {
                 base_branch,
             } => {
                 let repo = self.open_repo(repo_path)?;
-                let base_tree = repo
-                    .find_branch(base_branch, BranchType::Local)
-                    .map_err(|_| GitServiceError::BranchNotFound(base_branch.to_string()))?
-                    .get()
-                    .peel_to_commit()?
-                    .tree()?;
+                let base_tree = Self::find_branch_commit(&repo, base_branch)?.tree()?;
                 let branch_tree = repo
                     .find_branch(branch_name, BranchType::Local)
                     .map_err(|_| GitServiceError::BranchNotFound(branch_name.to_string()))?
@@ -333,6 +382,36 @@ impl GitService {
         }
     }
 
+    /// Diff a tree against a worktree's files, untracked ones included
+    fn diff_tree_to_worktree(
+        &self,
+        repo: &Repository,
+        base_tree: &git2::Tree,
+        path_filter: Option<&[&str]>,
+    ) -> Result<Vec<Diff>, GitServiceError> {
+        let mut diff_opts = DiffOptions::new();
+        diff_opts
+            .include_untracked(true)
+            .include_typechange(true)
+            .recurse_untracked_dirs(true);
+
+        // Add path filtering if specified
+        if let Some(paths) = path_filter {
+            for path in paths {
+                diff_opts.pathspec(*path);
+            }
+        }
+
+        let mut diff =
+            repo.diff_tree_to_workdir_with_index(Some(base_tree), Some(&mut diff_opts))?;
+
+        // Enable rename detection
+        let mut find_opts = DiffFindOptions::new();
+        diff.find_similar(Some(&mut find_opts))?;
+
+        self.convert_diff_to_file_diffs(diff, repo)
+    }
+
     /// Convert git2::Diff to our Diff structs
     fn convert_diff_to_file_diffs(
         &self,
@@ -349,8 +428,8 @@ impl GitService {
 
                 let status = delta.status();
 
-                // Only build old_file for non-added entries
-                let old_file = if matches!(status, Delta::Added) {
+                // Only build old_file for non-added entries, untracked files have no old side
+                let old_file = if matches!(status, Delta::Added | Delta::Untracked) {
                     None
                 } else {
                     delta
@@ -479,6 +558,7 @@ impl GitService {
     }
 
     /// Merge changes from a worktree branch back to the main repository
+    #[tracing::instrument(skip(self, repo_path, worktree_path, commit_message))]
     pub fn merge_changes(
         &self,
         repo_path: &Path,
@@ -538,6 +618,78 @@ impl GitService {
         Ok(squash_commit_id.to_string())
     }
 
+    /// Number of commits on the branch after `commit_sha`, which must be on it
+    pub fn commits_since(
+        &self,
+        repo_path: &Path,
+        branch_name: &str,
+        commit_sha: &str,
+    ) -> Result<usize, GitServiceError> {
+        let repo = self.open_repo(repo_path)?;
+        let commit = git2::Oid::from_str(commit_sha)?;
+        let tip = Self::find_branch_commit(&repo, branch_name)?.id();
+        if tip != commit && !repo.graph_descendant_of(tip, commit)? {
+            return Err(GitServiceError::CommitNotOnBranch(
+                commit_sha.to_string(),
+                branch_name.to_string(),
+            ));
+        }
+        let (ahead, _) = repo.graph_ahead_behind(tip, commit)?;
+        Ok(ahead)
+    }
+
+    /// Undo a commit of the branch with a new commit on top of it, returns the new commit.
+    /// When the repository has the branch checked out its files follow, so it must have no
+    /// uncommitted changes.
+    pub fn revert_commit(
+        &self,
+        repo_path: &Path,
+        branch_name: &str,
+        commit_sha: &str,
+    ) -> Result<String, GitServiceError> {
+        let repo = self.open_repo(repo_path)?;
+        let reverted = repo.find_commit(git2::Oid::from_str(commit_sha)?)?;
+        let tip = Self::find_branch_commit(&repo, branch_name)?;
+        let checked_out = repo
+            .head()
+            .ok()
+            .and_then(|head| head.shorthand().map(|name| name == branch_name))
+            .unwrap_or(false);
+        if checked_out {
+            self.check_worktree_clean(&repo)?;
+        }
+
+        let mut index = repo.revert_commit(&reverted, &tip, 0, None)?;
+        if index.has_conflicts() {
+            return Err(GitServiceError::MergeConflicts(format!(
+                "Reverting {} conflicts with later changes to {}",
+                commit_sha,
+                Self::conflicted_paths(&index)?.join(", ")
+            )));
+        }
+        let tree = repo.find_tree(index.write_tree_to(&repo)?)?;
+        let signature = repo.signature()?;
+        let message = format!(
+            "Revert \"{}\"\n\nThis reverts commit {}.",
+            reverted.summary().unwrap_or_default(),
+            reverted.id()
+        );
+        let revert = repo.commit(None, &signature, &signature, &message, &tree, &[&tip])?;
+        repo.reference(
+            &format!("refs/heads/{branch_name}"),
+            revert,
+            true,
+            "Revert commit",
+        )?;
+
+        if checked_out {
+            let mut co = CheckoutBuilder::new();
+            co.force();
+            repo.checkout_head(Some(&mut co))?;
+        }
+        Ok(revert.to_string())
+    }
+
     pub fn get_branch_status(
         &self,
         repo_path: &Path,
@@ -564,12 +716,8 @@ impl GitService {
         }
         // Calculate ahead/behind counts using the stored base branch
         let (commits_ahead, commits_behind) =
-            if let Ok(base_branch) = repo.find_branch(base_branch_name, BranchType::Local) {
-                if let Some(base_oid) = base_branch.get().target() {
-                    repo.graph_ahead_behind(branch_oid, base_oid)?
-                } else {
-                    (0, 0) // Base branch has no commits
-                }
+            if let Ok(base_commit) = Self::find_branch_commit(&repo, base_branch_name) {
+                repo.graph_ahead_behind(branch_oid, base_commit.id())?
             } else {
                 // Base branch doesn't exist, assume no relationship
                 (0, 0)
@@ -645,6 +793,7 @@ impl GitService {
         }
     }
 
+    /// Local and remote branches, current first and then by their last commit
     pub fn get_all_branches(&self, repo_path: &Path) -> Result<Vec<GitBranch>, git2::Error> {
         let repo = Repository::open(repo_path)?;
         let current_branch = self.get_current_branch(repo_path).unwrap_or_default();
@@ -671,6 +820,7 @@ impl GitService {
                     name: name.to_string(),
                     is_current: name == current_branch,
                     is_remote: false,
+                    is_tag: false,
                     last_commit_date,
                 });
             }
@@ -688,6 +838,7 @@ impl GitService {
                         name: name.to_string(),
                         is_current: false,
                         is_remote: true,
+                        is_tag: false,
                         last_commit_date,
                     });
                 }
@@ -752,6 +903,7 @@ impl GitService {
     }
 
     /// Rebase a worktree branch onto a new base
+    #[tracing::instrument(skip(self, repo_path, worktree_path))]
     pub fn rebase_branch(
         &self,
         repo_path: &Path,
@@ -879,6 +1031,451 @@ impl GitService {
         Ok(final_commit.id().to_string())
     }
 
+    /// Bring a branch up to date with its base branch, leaving it untouched on conflicts.
+    ///
+    /// Runs in memory on the main repository so branches whose worktree was cleaned up can
+    /// be synced too, a worktree still checked out on the branch must be clean and is
+    /// updated along with it.
+    #[tracing::instrument(skip(self, repo_path, worktree_path))]
+    pub fn sync_branch_with_base(
+        &self,
+        repo_path: &Path,
+        worktree_path: Option<&Path>,
+        branch_name: &str,
+        base_branch_name: &str,
+        strategy: BranchSyncStrategy,
+    ) -> Result<BaseSync, GitServiceError> {
+        let repo = self.open_repo(repo_path)?;
+        let branch_commit = repo
+            .find_branch(branch_name, BranchType::Local)
+            .map_err(|_| GitServiceError::BranchNotFound(branch_name.to_string()))?
+            .get()
+            .peel_to_commit()?;
+        let base_commit = Self::find_branch_commit(&repo, base_branch_name)?;
+        let sync = |outcome| BaseSync {
+            base_commit: base_commit.id().to_string(),
+            outcome,
+        };
+
+        if branch_commit.id() == base_commit.id()
+            || repo.graph_descendant_of(branch_commit.id(), base_commit.id())?
+        {
+            return Ok(sync(BaseSyncOutcome::UpToDate));
+        }
+
+        let worktree_repo = match worktree_path {
+            Some(path) if path.exists() => {
+                let worktree_repo = Repository::open(path)?;
+                self.check_worktree_clean(&worktree_repo)?;
+                Some(worktree_repo)
+            }
+            _ => None,
+        };
+
+        let signature = repo.signature()?;
+        let new_head = match strategy {
+            BranchSyncStrategy::Merge => {
+                let mut index = repo.merge_commits(&branch_commit, &base_commit, None)?;
+                if index.has_conflicts() {
+                    return Ok(sync(BaseSyncOutcome::Conflicts(Self::conflicted_paths(
+                        &index,
+                    )?)));
+                }
+                let tree = repo.find_tree(index.write_tree_to(&repo)?)?;
+                repo.commit(
+                    None,
+                    &signature,
+                    &signature,
+                    &format!("Merge branch '{base_branch_name}' into {branch_name}"),
+                    &tree,
+                    &[&branch_commit, &base_commit],
+                )?
+            }
+            BranchSyncStrategy::Rebase => {
+                // Commits already on the base branch are hidden, the rest is replayed
+                let mut walker = repo.revwalk()?;
+                walker.push(branch_commit.id())?;
+                walker.hide(base_commit.id())?;
+                walker.set_sorting(Sort::TOPOLOGICAL | Sort::REVERSE)?;
+
+                let mut onto: Commit = base_commit.clone();
+                for commit_id in walker {
+                    let commit = repo.find_commit(commit_id?)?;
+                    if commit.parent_count() > 1 {
+                        return Err(GitServiceError::MergeCommitsInBranch(
+                            branch_name.to_string(),
+                        ));
+                    }
+                    let mut index = repo.cherrypick_commit(&commit, &onto, 0, None)?;
+                    if index.has_conflicts() {
+                        return Ok(sync(BaseSyncOutcome::Conflicts(Self::conflicted_paths(
+                            &index,
+                        )?)));
+                    }
+                    let tree = repo.find_tree(index.write_tree_to(&repo)?)?;
+                    let replayed = repo.commit(
+                        None,
+                        &commit.author(),
+                        &signature,
+                        commit.message().unwrap_or_default(),
+                        &tree,
+                        &[&onto],
+                    )?;
+                    onto = repo.find_commit(replayed)?;
+                }
+                onto.id()
+            }
+        };
+
+        repo.reference(
+            &format!("refs/heads/{branch_name}"),
+            new_head,
+            true,
+            "Sync with base branch",
+        )?;
+        if let Some(worktree_repo) = worktree_repo {
+            let mut co = CheckoutBuilder::new();
+            co.force();
+            worktree_repo.checkout_head(Some(&mut co))?;
+        }
+
+        Ok(sync(BaseSyncOutcome::Synced(new_head.to_string())))
+    }
+
+    /// Rewrite the changes of a worktree's branch since it left `base_branch_name`, its
+    /// uncommitted changes included, as one commit per planned group of files. Each commit
+    /// takes its files as they end up, files no commit names go in a last commit with
+    /// `remaining_message`, so the branch's final tree doesn't change. Returns the branch head
+    /// the split started from.
+    #[tracing::instrument(skip(self, worktree_path, commits))]
+    pub fn split_into_commits(
+        &self,
+        worktree_path: &Path,
+        base_branch_name: &str,
+        commits: &[PlannedCommit],
+        remaining_message: &str,
+    ) -> Result<String, GitServiceError> {
+        // Uncommitted changes are split along with the rest
+        self.commit(worktree_path, remaining_message)?;
+
+        let repo = self.open_repo(worktree_path)?;
+        let head = repo.head()?.peel_to_commit()?;
+        let final_tree = head.tree()?;
+        let base_commit = Self::find_branch_commit(&repo, base_branch_name)?;
+        let mut parent =
+            repo.find_commit(Self::get_merge_base(&repo, head.id(), base_commit.id())?)?;
+        if parent.id() == head.id() {
+            return Ok(head.id().to_string());
+        }
+
+        let mut final_index = Index::new()?;
+        final_index.read_tree(&final_tree)?;
+        let mut index = Index::new()?;
+        index.read_tree(&parent.tree()?)?;
+        let signature = repo
+            .signature()
+            .or_else(|_| git2::Signature::now("Vibe Kanban", "noreply@vibekanban.com"))?;
+
+        let commit_tree = |index: &mut Index, parent: &Commit, message: &str| {
+            let tree = repo.find_tree(index.write_tree_to(&repo)?)?;
+            if tree.id() == parent.tree_id() {
+                return Ok::<_, GitServiceError>(None);
+            }
+            let oid = repo.commit(None, &signature, &signature, message, &tree, &[parent])?;
+            Ok(Some(repo.find_commit(oid)?))
+        };
+        for commit in commits {
+            for file in &commit.files {
+                let path = Path::new(file);
+                match final_index.get_path(path, 0) {
+                    Some(entry) => index.add(&entry)?,
+                    None if index.get_path(path, 0).is_some() => index.remove_path(path)?,
+                    None => {}
+                }
+            }
+            if let Some(next) = commit_tree(&mut index, &parent, &commit.message)? {
+                parent = next;
+            }
+        }
+        if parent.tree_id() != final_tree.id() {
+            let mut index = Index::new()?;
+            index.read_tree(&final_tree)?;
+            if let Some(next) = commit_tree(&mut index, &parent, remaining_message)? {
+                parent = next;
+            }
+        }
+
+        repo.head()?
+            .set_target(parent.id(), "Split changes into commits")?;
+        Ok(head.id().to_string())
+    }
+
+    /// Commit a local or remote branch points to
+    pub fn get_branch_commit(
+        &self,
+        repo_path: &Path,
+        branch_name: &str,
+    ) -> Result<String, GitServiceError> {
+        let repo = self.open_repo(repo_path)?;
+        Ok(Self::find_branch_commit(&repo, branch_name)?
+            .id()
+            .to_string())
+    }
+
+    /// Rename a local branch, worktrees that have it checked out follow along
+    pub fn rename_branch(
+        &self,
+        repo_path: &Path,
+        old_name: &str,
+        new_name: &str,
+    ) -> Result<(), GitServiceError> {
+        let repo = self.open_repo(repo_path)?;
+        let mut branch = repo
+            .find_branch(old_name, BranchType::Local)
+            .map_err(|_| GitServiceError::BranchNotFound(old_name.to_string()))?;
+        branch.rename(new_name, false)?;
+        Ok(())
+    }
+
+    /// Delete the local branches starting with `prefix` that no worktree has checked out,
+    /// returns their names
+    pub fn delete_unused_branches(
+        &self,
+        repo_path: &Path,
+        prefix: &str,
+    ) -> Result<Vec<String>, GitServiceError> {
+        let repo = self.open_repo(repo_path)?;
+        let mut deleted = Vec::new();
+        for branch in repo.branches(Some(BranchType::Local))? {
+            let (mut branch, _) = branch?;
+            let Some(name) = branch.name()?.map(str::to_string) else {
+                continue;
+            };
+            // Deleting a branch checked out in a worktree fails
+            if name.starts_with(prefix) && branch.delete().is_ok() {
+                deleted.push(name);
+            }
+        }
+        Ok(deleted)
+    }
+
+    /// Changes whenever the diff of a worktree against its base branch may have changed: the
+    /// base and HEAD commits, and the status, size and modification time of each changed file
+    pub fn worktree_fingerprint(
+        &self,
+        worktree_path: &Path,
+        base_branch: &str,
+    ) -> Result<u64, GitServiceError> {
+        let repo = Repository::open(worktree_path)?;
+        let mut hasher = DefaultHasher::new();
+        Self::find_branch_commit(&repo, base_branch)?
+            .id()
+            .hash(&mut hasher);
+        repo.head()?.peel_to_commit()?.id().hash(&mut hasher);
+
+        let mut status_opts = StatusOptions::new();
+        status_opts
+            .include_untracked(true)
+            .recurse_untracked_dirs(true)
+            .include_ignored(false);
+        for entry in repo.statuses(Some(&mut status_opts))?.iter() {
+            entry.path_bytes().hash(&mut hasher);
+            entry.status().bits().hash(&mut hasher);
+            if let Some(path) = entry.path()
+                && let Ok(metadata) = std::fs::metadata(worktree_path.join(path))
+            {
+                metadata.len().hash(&mut hasher);
+                metadata.modified().ok().hash(&mut hasher);
+            }
+        }
+        Ok(hasher.finish())
+    }
+
+    /// Commit the worktree's files as they are, uncommitted and untracked ones included and
+    /// ignored ones not, on top of its HEAD under `ref_name`. Neither the branch nor the index
+    /// changes. Returns `None` when the worktree is the same as the `previous` snapshot.
+    pub fn snapshot_worktree(
+        &self,
+        worktree_path: &Path,
+        ref_name: &str,
+        message: &str,
+        previous: Option<&str>,
+    ) -> Result<Option<WorktreeSnapshot>, GitServiceError> {
+        let repo = Repository::open(worktree_path)?;
+        let head = repo.head()?.peel_to_commit()?;
+
+        // Only the in-memory copy of the index is updated, it's never written back
+        let mut index = repo.index()?;
+        index.add_all(["*"], git2::IndexAddOption::DEFAULT, None)?;
+        index.update_all(["*"], None)?;
+        let tree = repo.find_tree(index.write_tree()?)?;
+
+        if let Some(previous) = previous
+            && let Ok(previous) = repo
+                .revparse_single(previous)
+                .and_then(|o| o.peel_to_commit())
+            && previous.tree_id() == tree.id()
+            && previous.parent_id(0).ok() == Some(head.id())
+        {
+            return Ok(None);
+        }
+
+        let signature = repo
+            .signature()
+            .or_else(|_| git2::Signature::now("Vibe Kanban", "noreply@vibekanban.com"))?;
+        let commit = repo.commit(None, &signature, &signature, message, &tree, &[&head])?;
+        repo.reference(ref_name, commit, true, message)?;
+        Ok(Some(WorktreeSnapshot {
+            commit: commit.to_string(),
+            head: head.id().to_string(),
+        }))
+    }
+
+    /// Bring the worktree back to a snapshot: its branch to the HEAD it had, and its files to
+    /// the snapshot's with what was uncommitted left uncommitted. Untracked files the snapshot
+    /// doesn't have are removed, ignored ones are kept.
+    pub fn restore_snapshot(
+        &self,
+        worktree_path: &Path,
+        commit_sha: &str,
+    ) -> Result<(), GitServiceError> {
+        let repo = Repository::open(worktree_path)?;
+        let snapshot = repo.find_commit(git2::Oid::from_str(commit_sha)?)?;
+        let head = snapshot.parent(0)?;
+        repo.reset(head.as_object(), git2::ResetType::Hard, None)?;
+
+        let mut checkout = CheckoutBuilder::new();
+        checkout.force().remove_untracked(true);
+        repo.checkout_tree(snapshot.as_object(), Some(&mut checkout))?;
+
+        // Checking out the tree staged its files, unstage them again
+        let mut index = repo.index()?;
+        index.read_tree(&head.tree()?)?;
+        index.write()?;
+        Ok(())
+    }
+
+    pub fn delete_reference(
+        &self,
+        repo_path: &Path,
+        ref_name: &str,
+    ) -> Result<(), GitServiceError> {
+        let repo = self.open_repo(repo_path)?;
+        repo.find_reference(ref_name)?.delete()?;
+        Ok(())
+    }
+
+    /// Keep untracked files out of commits through the repository's exclude file, which all
+    /// its worktrees share. Paths that are already ignored are left alone.
+    pub fn exclude_paths(
+        &self,
+        worktree_path: &Path,
+        paths: &[String],
+    ) -> Result<(), GitServiceError> {
+        let repo = self.open_repo(worktree_path)?;
+        let mut patterns = Vec::new();
+        for path in paths {
+            if !repo.is_path_ignored(path)? {
+                patterns.push(format!("/{path}"));
+            }
+        }
+        if patterns.is_empty() {
+            return Ok(());
+        }
+        // A worktree's git directory points at the repository's in its `commondir` file
+        let git_dir = repo.path();
+        let common_dir = match std::fs::read_to_string(git_dir.join("commondir")) {
+            Ok(common_dir) => git_dir.join(common_dir.trim()),
+            Err(_) => git_dir.to_path_buf(),
+        };
+        let exclude = common_dir.join("info").join("exclude");
+        std::fs::create_dir_all(exclude.parent().unwrap())?;
+        let mut contents = std::fs::read_to_string(&exclude).unwrap_or_default();
+        if !contents.is_empty() && !contents.ends_with('\n') {
+            contents.push('\n');
+        }
+        for pattern in patterns {
+            contents.push_str(&pattern);
+            contents.push('\n');
+        }
+        std::fs::write(&exclude, contents)?;
+        Ok(())
+    }
+
+    /// The branches of `get_all_branches` followed by the tags, newest first, for choosing
+    /// where an attempt starts
+    pub fn get_all_branches_and_tags(
+        &self,
+        repo_path: &Path,
+    ) -> Result<Vec<GitBranch>, git2::Error> {
+        let mut branches = self.get_all_branches(repo_path)?;
+        let repo = Repository::open(repo_path)?;
+        let mut tags = Vec::new();
+        for name in repo.tag_names(None)?.iter().flatten() {
+            let Ok(commit) = repo
+                .find_reference(&format!("refs/tags/{name}"))
+                .and_then(|reference| reference.peel_to_commit())
+            else {
+                // Tags of trees or blobs can't be checked out
+                continue;
+            };
+            tags.push(GitBranch {
+                name: name.to_string(),
+                is_current: false,
+                is_remote: false,
+                is_tag: true,
+                last_commit_date: DateTime::from_timestamp(commit.time().seconds(), 0)
+                    .unwrap_or_else(Utc::now),
+            });
+        }
+        tags.sort_by(|a, b| b.last_commit_date.cmp(&a.last_commit_date));
+        branches.extend(tags);
+        Ok(branches)
+    }
+
+    /// Whether `name` is a tag of the repository, and not a local branch of the same name
+    pub fn is_tag(&self, repo_path: &Path, name: &str) -> Result<bool, GitServiceError> {
+        let repo = self.open_repo(repo_path)?;
+        Ok(repo.find_branch(name, BranchType::Local).is_err()
+            && repo.find_reference(&format!("refs/tags/{name}")).is_ok())
+    }
+
+    /// Fails with `BranchNotFound` unless attempts can start from `base`, a local or remote
+    /// branch or a tag
+    pub fn ensure_base_exists(&self, repo_path: &Path, base: &str) -> Result<(), GitServiceError> {
+        let repo = self.open_repo(repo_path)?;
+        Self::find_branch_commit(&repo, base)?;
+        Ok(())
+    }
+
+    /// The commit a local branch, remote branch or tag of that name points at, in that order
+    fn find_branch_commit<'r>(
+        repo: &'r Repository,
+        branch_name: &str,
+    ) -> Result<Commit<'r>, GitServiceError> {
+        let reference = repo
+            .find_branch(branch_name, BranchType::Local)
+            .or_else(|_| repo.find_branch(branch_name, BranchType::Remote))
+            .map(|branch| branch.into_reference())
+            .or_else(|_| repo.find_reference(&format!("refs/tags/{branch_name}")))
+            .map_err(|_| GitServiceError::BranchNotFound(branch_name.to_string()))?;
+        Ok(reference.peel_to_commit()?)
+    }
+
+    fn conflicted_paths(index: &Index) -> Result<Vec<String>, GitServiceError> {
+        let mut paths = Vec::new();
+        for conflict in index.conflicts()? {
+            let conflict = conflict?;
+            if let Some(entry) = conflict.our.or(conflict.their).or(conflict.ancestor) {
+                let path = String::from_utf8_lossy(&entry.path).to_string();
+                if !paths.contains(&path) {
+                    paths.push(path);
+                }
+            }
+        }
+        Ok(paths)
+    }
+
     /// Delete a file from the repository and commit the change
     pub fn delete_file_and_commit(
         &self,
@@ -942,6 +1539,57 @@ impl GitService {
         }
     }
 
+    /// Read a file as committed on `branch`, `None` if the branch doesn't contain it
+    pub fn read_file_at_branch(
+        &self,
+        repo_path: &Path,
+        branch: &str,
+        file_path: &Path,
+    ) -> Result<Option<String>, GitServiceError> {
+        let repo = self.open_repo(repo_path)?;
+        let tree = repo
+            .revparse_single(branch)
+            .map_err(|_| GitServiceError::BranchNotFound(branch.to_string()))?
+            .peel_to_tree()?;
+        let entry = match tree.get_path(file_path) {
+            Ok(entry) => entry,
+            Err(e) if e.code() == git2::ErrorCode::NotFound => return Ok(None),
+            Err(e) => return Err(e.into()),
+        };
+        let blob = entry.to_object(&repo)?.peel_to_blob()?;
+        Ok(Some(String::from_utf8_lossy(blob.content()).into_owned()))
+    }
+
+    /// Paths changed by the latest `commits` commits of HEAD, most recently changed first
+    pub fn recently_changed_paths(
+        &self,
+        repo_path: &Path,
+        commits: usize,
+    ) -> Result<Vec<String>, GitServiceError> {
+        let repo = self.open_repo(repo_path)?;
+        let mut walker = repo.revwalk()?;
+        walker.push_head()?;
+        walker.set_sorting(Sort::TIME)?;
+
+        let mut paths: Vec<String> = Vec::new();
+        for commit_id in walker.take(commits) {
+            let commit = repo.find_commit(commit_id?)?;
+            let parent_tree = match commit.parent(0) {
+                Ok(parent) => Some(parent.tree()?),
+                Err(_) => None,
+            };
+            let diff = repo.diff_tree_to_tree(parent_tree.as_ref(), Some(&commit.tree()?), None)?;
+            for delta in diff.deltas() {
+                if let Some(path) = delta.new_file().path().and_then(|p| p.to_str())
+                    && !paths.iter().any(|known| known == path)
+                {
+                    paths.push(path.to_string());
+                }
+            }
+        }
+        Ok(paths)
+    }
+
     /// Extract GitHub owner and repo name from git repo path
     pub fn get_github_repo_info(
         &self,
@@ -972,6 +1620,7 @@ impl GitService {
     }
 
     /// Push the branch to GitHub remote
+    #[tracing::instrument(skip(self, worktree_path, github_token))]
     pub fn push_to_github(
         &self,
         worktree_path: &Path,
@@ -996,6 +1645,19 @@ impl GitService {
         } else {
             remote_url.to_string()
         };
+        self.push_to_url(worktree_path, &https_url, branch_name, github_token)
+    }
+
+    /// Push the branch to another GitHub repository over HTTPS, e.g. the user's fork
+    #[tracing::instrument(skip(self, worktree_path, github_token))]
+    pub fn push_to_url(
+        &self,
+        worktree_path: &Path,
+        https_url: &str,
+        branch_name: &str,
+        github_token: &str,
+    ) -> Result<(), GitServiceError> {
+        let repo = Repository::open(worktree_path)?;
 
         // Create a temporary remote with HTTPS URL for pushing
         let temp_remote_name = "temp_https_origin";
@@ -1004,15 +1666,19 @@ impl GitService {
         let _ = repo.remote_delete(temp_remote_name);
 
         // Create temporary HTTPS remote
-        let mut temp_remote = repo.remote(temp_remote_name, &https_url)?;
+        let mut temp_remote = repo.remote(temp_remote_name, https_url)?;
 
         // Create refspec for pushing the branch
         let refspec = format!("refs/heads/{branch_name}:refs/heads/{branch_name}");
 
-        // Set up authentication callback using the GitHub token
+        // Set up authentication callback using the GitHub token. GitHub takes any user name
+        // with a personal token, app installation tokens need `x-access-token`
         let mut callbacks = git2::RemoteCallbacks::new();
         callbacks.credentials(|_url, username_from_url, _allowed_types| {
-            git2::Cred::userpass_plaintext(username_from_url.unwrap_or("git"), github_token)
+            git2::Cred::userpass_plaintext(
+                username_from_url.unwrap_or("x-access-token"),
+                github_token,
+            )
         });
 
         // Configure push options
@@ -1157,12 +1823,23 @@ impl GitService {
     }
 
     /// Clone a repository to the specified directory
-    #[cfg(feature = "cloud")]
     pub fn clone_repository(
         clone_url: &str,
         target_path: &Path,
         token: Option<&str>,
     ) -> Result<Repository, GitServiceError> {
+        Self::clone_repository_with_progress(clone_url, target_path, token, |_| {})
+    }
+
+    /// Clone a repository to the specified directory, reporting progress as objects arrive
+    /// and files are checked out
+    pub fn clone_repository_with_progress(
+        clone_url: &str,
+        target_path: &Path,
+        token: Option<&str>,
+        on_progress: impl FnMut(CloneProgress),
+    ) -> Result<Repository, GitServiceError> {
+        let on_progress = std::cell::RefCell::new(on_progress);
         if let Some(parent) = target_path.parent() {
             std::fs::create_dir_all(parent)?;
         }
@@ -1171,7 +1848,7 @@ impl GitService {
         let mut callbacks = RemoteCallbacks::new();
         if let Some(token) = token {
             callbacks.credentials(|_url, username_from_url, _allowed_types| {
-                Cred::userpass_plaintext(username_from_url.unwrap_or("git"), token)
+                Cred::userpass_plaintext(username_from_url.unwrap_or("x-access-token"), token)
             });
         } else {
             // Fallback to SSH agent and key file authentication
@@ -1190,13 +1867,36 @@ impl GitService {
             });
         }
 
+        callbacks.transfer_progress(|stats| {
+            let progress = if stats.received_objects() < stats.total_objects() {
+                CloneProgress::Receiving {
+                    received_objects: stats.received_objects(),
+                    total_objects: stats.total_objects(),
+                    received_bytes: stats.received_bytes(),
+                }
+            } else {
+                CloneProgress::Resolving {
+                    indexed_deltas: stats.indexed_deltas(),
+                    total_deltas: stats.total_deltas(),
+                }
+            };
+            (on_progress.borrow_mut())(progress);
+            true
+        });
+
         // Set up fetch options with our callbacks
         let mut fetch_opts = FetchOptions::new();
         fetch_opts.remote_callbacks(callbacks);
 
+        let mut checkout = CheckoutBuilder::new();
+        checkout.progress(|_path, completed, total| {
+            (on_progress.borrow_mut())(CloneProgress::CheckingOut { completed, total });
+        });
+
         // Create a repository builder with fetch options
         let mut builder = git2::build::RepoBuilder::new();
         builder.fetch_options(fetch_opts);
+        builder.with_checkout(checkout);
 
         let repo = builder.clone(clone_url, target_path)?;
 
@@ -0,0 +1,172 @@
+use std::{collections::HashMap, sync::Mutex};
+
+use chrono::{DateTime, Duration, Utc};
+use jsonwebtoken::EncodingKey;
+use octocrab::{
+    OctocrabBuilder,
+    models::{AppId, InstallationToken},
+};
+
+use crate::services::{config::GitHubConfig, github_service::GitHubServiceError};
+
+/// Installation tokens are replaced once they have less than this many seconds left
+const REFRESH_MARGIN_SECS: i64 = 5 * 60;
+
+lazy_static::lazy_static! {
+    /// Installation tokens by app and installation ID
+    static ref INSTALLATION_TOKENS: Mutex<HashMap<(u64, u64), CachedToken>> =
+        Mutex::new(HashMap::new());
+}
+
+/// GitHub App credentials, once all three are set
+struct AppCredentials<'a> {
+    app_id: u64,
+    installation_id: u64,
+    private_key: &'a str,
+}
+
+impl<'a> AppCredentials<'a> {
+    fn of(config: &'a GitHubConfig) -> Option<Self> {
+        Some(Self {
+            app_id: config.app_id?,
+            installation_id: config.app_installation_id?,
+            private_key: config
+                .app_private_key
+                .as_deref()
+                .filter(|key| !key.trim().is_empty())?,
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+struct CachedToken {
+    token: String,
+    expires_at: DateTime<Utc>,
+}
+
+impl CachedToken {
+    fn is_fresh(&self, now: DateTime<Utc>) -> bool {
+        self.expires_at - now > Duration::seconds(REFRESH_MARGIN_SECS)
+    }
+}
+
+/// Token for GitHub's API and for pushing over HTTPS. With an app configured this is an
+/// installation token, minted again shortly before the cached one expires.
+pub async fn access_token(config: &GitHubConfig) -> Result<Option<String>, GitHubServiceError> {
+    let Some(app) = AppCredentials::of(config) else {
+        return Ok(config.token());
+    };
+    let key = (app.app_id, app.installation_id);
+    if let Some(cached) = INSTALLATION_TOKENS
+        .lock()
+        .unwrap()
+        .get(&key)
+        .filter(|cached| cached.is_fresh(Utc::now()))
+    {
+        return Ok(Some(cached.token.clone()));
+    }
+
+    let minted = mint_installation_token(&app).await?;
+    let token = minted.token.clone();
+    INSTALLATION_TOKENS.lock().unwrap().insert(key, minted);
+    Ok(Some(token))
+}
+
+/// Check a GitHub App private key can sign the JWTs installation tokens are requested with
+pub fn validate_private_key(private_key: &str) -> Result<(), String> {
+    parse_private_key(private_key)
+        .map(|_| ())
+        .map_err(|e| format!("Invalid private key: {e}"))
+}
+
+fn parse_private_key(private_key: &str) -> jsonwebtoken::errors::Result<EncodingKey> {
+    EncodingKey::from_rsa_pem(private_key.trim().as_bytes())
+}
+
+async fn mint_installation_token(
+    app: &AppCredentials<'_>,
+) -> Result<CachedToken, GitHubServiceError> {
+    let key = parse_private_key(app.private_key)
+        .map_err(|e| GitHubServiceError::Auth(format!("Invalid GitHub App private key: {e}")))?;
+    // Octocrab signs each request of an app client with a fresh JWT
+    let client = OctocrabBuilder::new().app(AppId(app.app_id), key).build()?;
+    let token: InstallationToken = client
+        .post(
+            format!("/app/installations/{}/access_tokens", app.installation_id),
+            None::<&()>,
+        )
+        .await?;
+    let expires_at = token
+        .expires_at
+        .as_deref()
+        .and_then(|at| DateTime::parse_from_rfc3339(at).ok())
+        .map(|at| at.with_timezone(&Utc))
+        // Installation tokens last an hour
+        .unwrap_or_else(|| Utc::now() + Duration::hours(1));
+    Ok(CachedToken {
+        token: token.token,
+        expires_at,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> GitHubConfig {
+        GitHubConfig {
+            pat: Some("github_pat_11ABC".to_string()),
+            oauth_token: Some("gho_abc".to_string()),
+            ..GitHubConfig::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_access_token_reuses_fresh_installation_token() {
+        let config = GitHubConfig {
+            app_id: Some(101),
+            app_installation_id: Some(202),
+            app_private_key: Some("not a key".to_string()),
+            ..config()
+        };
+        assert!(matches!(
+            access_token(&config).await,
+            Err(GitHubServiceError::Auth(_))
+        ));
+
+        INSTALLATION_TOKENS.lock().unwrap().insert(
+            (101, 202),
+            CachedToken {
+                token: "ghs_cached".to_string(),
+                expires_at: Utc::now() + Duration::minutes(30),
+            },
+        );
+        assert_eq!(
+            access_token(&config).await.unwrap().as_deref(),
+            Some("ghs_cached")
+        );
+
+        let expiring = CachedToken {
+            token: "ghs_old".to_string(),
+            expires_at: Utc::now() + Duration::minutes(2),
+        };
+        assert!(!expiring.is_fresh(Utc::now()));
+    }
+
+    #[tokio::test]
+    async fn test_access_token_without_complete_app_uses_personal_token() {
+        let mut config = config();
+        config.app_id = Some(1);
+        config.app_private_key = Some("not a key".to_string());
+        assert_eq!(
+            access_token(&config).await.unwrap().as_deref(),
+            Some("github_pat_11ABC")
+        );
+
+        config.pat = None;
+        assert_eq!(
+            access_token(&config).await.unwrap().as_deref(),
+            Some("gho_abc")
+        );
+    }
+}
@@ -1,4 +1,4 @@
-use std::time::Duration;
+use std::{cmp::Ordering, time::Duration};
 
 use backon::{ExponentialBuilder, Retryable};
 use octocrab::{Octocrab, OctocrabBuilder};
@@ -6,6 +6,7 @@ use serde::{Deserialize, Serialize};
 use thiserror::Error;
 use tracing::info;
 use ts_rs::TS;
+use utils::pagination::{Listable, serialized_eq};
 
 use crate::services::git::GitServiceError;
 
@@ -98,12 +99,20 @@ pub struct GitHubRepoInfo {
     pub repo_name: String,
 }
 
+impl GitHubRepoInfo {
+    pub fn https_url(&self) -> String {
+        format!("https://github.com/{}/{}.git", self.owner, self.repo_name)
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct CreatePrRequest {
     pub title: String,
     pub body: Option<String>,
     pub head_branch: String,
     pub base_branch: String,
+    /// Fork holding the head branch, for pull requests across repositories
+    pub head_repo: Option<GitHubRepoInfo>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -116,8 +125,230 @@ pub struct PullRequestInfo {
     pub merge_commit_sha: Option<String>,
 }
 
+/// A branch as GitHub knows it
+#[derive(Debug, Clone)]
+pub struct RemoteBranch {
+    pub commit: String,
+    /// Status checks the branch's protection requires before merging
+    pub required_checks: Vec<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "snake_case")]
+pub enum CheckState {
+    Pending,
+    Success,
+    Failure,
+}
+
+/// A check run or commit status reported for a commit
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct CommitCheck {
+    pub name: String,
+    pub state: CheckState,
+    pub url: Option<String>,
+}
+
+/// A completed GitHub Actions workflow run
+#[derive(Debug, Clone, Deserialize)]
+pub struct WorkflowRun {
+    pub id: i64,
+    pub name: Option<String>,
+    /// Workflow file, e.g. `.github/workflows/ci.yml`
+    pub path: String,
+    pub head_sha: String,
+    pub conclusion: Option<String>,
+    pub html_url: String,
+}
+
+impl WorkflowRun {
+    pub fn failed(&self) -> bool {
+        matches!(self.conclusion.as_deref(), Some("failure" | "timed_out"))
+    }
+}
+
+/// A job of a workflow run
+#[derive(Debug, Clone, Deserialize)]
+pub struct WorkflowJob {
+    pub id: i64,
+    pub name: String,
+    pub conclusion: Option<String>,
+    pub html_url: Option<String>,
+    #[serde(default)]
+    pub steps: Vec<WorkflowStep>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct WorkflowStep {
+    pub name: String,
+    pub conclusion: Option<String>,
+}
+
+impl WorkflowJob {
+    pub fn failed(&self) -> bool {
+        matches!(self.conclusion.as_deref(), Some("failure" | "timed_out"))
+    }
+
+    /// Name of the first step that failed
+    pub fn failed_step(&self) -> Option<&str> {
+        self.steps
+            .iter()
+            .find(|step| step.conclusion.as_deref() == Some("failure"))
+            .map(|step| step.name.as_str())
+    }
+}
+
+#[derive(Deserialize)]
+struct BranchResponse {
+    commit: BranchCommitResponse,
+    #[serde(default)]
+    protection: Option<BranchProtectionResponse>,
+}
+
+#[derive(Deserialize)]
+struct BranchCommitResponse {
+    sha: String,
+}
+
+#[derive(Deserialize)]
+struct BranchProtectionResponse {
+    #[serde(default)]
+    required_status_checks: Option<RequiredStatusChecksResponse>,
+}
+
+#[derive(Deserialize)]
+struct RequiredStatusChecksResponse {
+    #[serde(default)]
+    contexts: Vec<String>,
+}
+
+#[derive(Deserialize)]
+struct CheckRunsResponse {
+    check_runs: Vec<CheckRunResponse>,
+}
+
+#[derive(Deserialize)]
+struct CheckRunResponse {
+    name: String,
+    status: String,
+    conclusion: Option<String>,
+    html_url: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct RepoResponse {
+    default_branch: String,
+    /// Only reported to users
+    #[serde(default)]
+    permissions: Option<RepoPermissionsResponse>,
+}
+
+#[derive(Deserialize)]
+struct RepoPermissionsResponse {
+    push: bool,
+}
+
+#[derive(Deserialize)]
+struct ForkResponse {
+    name: String,
+    owner: OwnerResponse,
+}
+
+#[derive(Deserialize)]
+struct OwnerResponse {
+    login: String,
+}
+
+#[derive(Deserialize)]
+struct WorkflowRunsResponse {
+    workflow_runs: Vec<WorkflowRun>,
+}
+
+#[derive(Deserialize)]
+struct WorkflowJobsResponse {
+    jobs: Vec<WorkflowJob>,
+}
+
+#[derive(Deserialize)]
+struct CombinedStatusResponse {
+    statuses: Vec<StatusResponse>,
+}
+
+#[derive(Deserialize)]
+struct StatusResponse {
+    context: String,
+    state: String,
+    target_url: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct InstallationRepositoriesResponse {
+    repositories: Vec<RepositoryResponse>,
+}
+
+#[derive(Deserialize)]
+struct RepositoryResponse {
+    id: i64,
+    name: String,
+    full_name: String,
+    owner: OwnerResponse,
+    description: Option<String>,
+    clone_url: String,
+    ssh_url: String,
+    default_branch: Option<String>,
+    private: bool,
+}
+
+impl From<RepositoryResponse> for RepositoryInfo {
+    fn from(repo: RepositoryResponse) -> Self {
+        Self {
+            id: repo.id,
+            name: repo.name,
+            full_name: repo.full_name,
+            owner: repo.owner.login,
+            description: repo.description,
+            clone_url: repo.clone_url,
+            ssh_url: repo.ssh_url,
+            // Empty repositories have no default branch yet
+            default_branch: repo.default_branch.unwrap_or_else(|| "main".to_string()),
+            private: repo.private,
+        }
+    }
+}
+
+impl From<CheckRunResponse> for CommitCheck {
+    fn from(run: CheckRunResponse) -> Self {
+        let state = match (run.status.as_str(), run.conclusion.as_deref()) {
+            ("completed", Some("success" | "neutral" | "skipped")) => CheckState::Success,
+            ("completed", _) => CheckState::Failure,
+            _ => CheckState::Pending,
+        };
+        Self {
+            name: run.name,
+            state,
+            url: run.html_url,
+        }
+    }
+}
+
+impl From<StatusResponse> for CommitCheck {
+    fn from(status: StatusResponse) -> Self {
+        let state = match status.state.as_str() {
+            "success" => CheckState::Success,
+            "pending" => CheckState::Pending,
+            _ => CheckState::Failure,
+        };
+        Self {
+            name: status.context,
+            state,
+            url: status.target_url,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, TS)]
 pub struct RepositoryInfo {
+    #[ts(type = "number")]
     pub id: i64,
     pub name: String,
     pub full_name: String,
@@ -129,9 +360,34 @@ pub struct RepositoryInfo {
     pub private: bool,
 }
 
+impl Listable for RepositoryInfo {
+    const SORT_FIELDS: &'static [&'static str] = &["name", "full_name"];
+    const FILTER_FIELDS: &'static [&'static str] = &["owner", "private"];
+
+    fn compare_by(&self, other: &Self, field: &str) -> Ordering {
+        match field {
+            "name" => self.name.to_lowercase().cmp(&other.name.to_lowercase()),
+            _ => self
+                .full_name
+                .to_lowercase()
+                .cmp(&other.full_name.to_lowercase()),
+        }
+    }
+
+    fn matches_filter(&self, field: &str, value: &str) -> bool {
+        match field {
+            "owner" => self.owner.eq_ignore_ascii_case(value),
+            "private" => serialized_eq(&self.private, value),
+            _ => true,
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct GitHubService {
     client: Octocrab,
+    /// Kept for downloads GitHub redirects to outside its API
+    token: String,
 }
 
 impl GitHubService {
@@ -141,11 +397,24 @@ impl GitHubService {
             .personal_token(github_token.to_string())
             .build()?;
 
-        Ok(Self { client })
+        Ok(Self {
+            client,
+            token: github_token.to_string(),
+        })
     }
 
     pub async fn check_token(&self) -> Result<(), GitHubServiceError> {
-        self.client.current().user().await?;
+        // Installation tokens act for a GitHub App, which has no user to look up
+        if self.token.starts_with("ghs_") {
+            self.client
+                .get::<serde_json::Value, _, _>(
+                    "/installation/repositories",
+                    Some(&[("per_page", 1)]),
+                )
+                .await?;
+        } else {
+            self.client.current().user().await?;
+        }
         Ok(())
     }
 
@@ -206,8 +475,9 @@ impl GitHubService {
             })?;
 
         // Check if the head branch exists
+        let head_repo = request.head_repo.as_ref().unwrap_or(repo_info);
         self.client
-            .repos(&repo_info.owner, &repo_info.repo_name)
+            .repos(&head_repo.owner, &head_repo.repo_name)
             .get_ref(&octocrab::params::repos::Reference::Branch(
                 request.head_branch.clone(),
             ))
@@ -219,11 +489,15 @@ impl GitHubService {
                 ))
             })?;
 
-        // Create the pull request
+        // Create the pull request, naming the fork's owner for a head branch in a fork
+        let head = match &request.head_repo {
+            Some(fork) => format!("{}:{}", fork.owner, request.head_branch),
+            None => request.head_branch.clone(),
+        };
         let pr = self
             .client
             .pulls(&repo_info.owner, &repo_info.repo_name)
-            .create(&request.title, &request.head_branch, &request.base_branch)
+            .create(&request.title, head, &request.base_branch)
             .body(request.body.as_deref().unwrap_or(""))
             .send()
             .await
@@ -333,11 +607,202 @@ impl GitHubService {
         Ok(pr_info)
     }
 
-    /// List repositories for the authenticated user with pagination
-    #[cfg(feature = "cloud")]
+    /// Look up a branch on GitHub, `None` when it wasn't pushed
+    pub async fn get_branch(
+        &self,
+        repo_info: &GitHubRepoInfo,
+        branch_name: &str,
+    ) -> Result<Option<RemoteBranch>, GitHubServiceError> {
+        let route = format!(
+            "/repos/{}/{}/branches/{}",
+            repo_info.owner, repo_info.repo_name, branch_name
+        );
+        let branch: BranchResponse = match self.client.get(route, None::<&()>).await {
+            Ok(branch) => branch,
+            Err(octocrab::Error::GitHub { source, .. }) if source.status_code.as_u16() == 404 => {
+                return Ok(None);
+            }
+            Err(e) => return Err(e.into()),
+        };
+        Ok(Some(RemoteBranch {
+            commit: branch.commit.sha,
+            required_checks: branch
+                .protection
+                .and_then(|protection| protection.required_status_checks)
+                .map(|checks| checks.contexts)
+                .unwrap_or_default(),
+        }))
+    }
+
+    /// Check runs and commit statuses reported for a commit
+    pub async fn get_commit_checks(
+        &self,
+        repo_info: &GitHubRepoInfo,
+        sha: &str,
+    ) -> Result<Vec<CommitCheck>, GitHubServiceError> {
+        let repo = format!("/repos/{}/{}", repo_info.owner, repo_info.repo_name);
+        let runs: CheckRunsResponse = self
+            .client
+            .get(
+                format!("{repo}/commits/{sha}/check-runs"),
+                Some(&[("per_page", "100")]),
+            )
+            .await?;
+        let statuses: CombinedStatusResponse = self
+            .client
+            .get(format!("{repo}/commits/{sha}/status"), None::<&()>)
+            .await?;
+        Ok(runs
+            .check_runs
+            .into_iter()
+            .map(CommitCheck::from)
+            .chain(statuses.statuses.into_iter().map(CommitCheck::from))
+            .collect())
+    }
+
+    pub async fn get_default_branch(
+        &self,
+        repo_info: &GitHubRepoInfo,
+    ) -> Result<String, GitHubServiceError> {
+        let repo: RepoResponse = self
+            .client
+            .get(
+                format!("/repos/{}/{}", repo_info.owner, repo_info.repo_name),
+                None::<&()>,
+            )
+            .await?;
+        Ok(repo.default_branch)
+    }
+
+    /// Whether the token may push branches to the repository. Tokens GitHub reports no
+    /// permissions for, like app installation tokens, are assumed to.
+    pub async fn can_push(&self, repo_info: &GitHubRepoInfo) -> Result<bool, GitHubServiceError> {
+        let repo: RepoResponse = self
+            .client
+            .get(
+                format!("/repos/{}/{}", repo_info.owner, repo_info.repo_name),
+                None::<&()>,
+            )
+            .await?;
+        Ok(repo.permissions.is_none_or(|permissions| permissions.push))
+    }
+
+    /// The user's fork of the repository, forked first if they have none yet
+    pub async fn ensure_fork(
+        &self,
+        repo_info: &GitHubRepoInfo,
+    ) -> Result<GitHubRepoInfo, GitHubServiceError> {
+        // GitHub answers with the existing fork when there is one
+        let fork: ForkResponse = self
+            .client
+            .post(
+                format!("/repos/{}/{}/forks", repo_info.owner, repo_info.repo_name),
+                None::<&()>,
+            )
+            .await?;
+        let fork = GitHubRepoInfo {
+            owner: fork.owner.login,
+            repo_name: fork.name,
+        };
+
+        // New forks are created in the background, they take pushes once their commits show
+        (|| async {
+            self.client
+                .get::<serde_json::Value, _, _>(
+                    format!("/repos/{}/{}/commits", fork.owner, fork.repo_name),
+                    Some(&[("per_page", 1)]),
+                )
+                .await
+                .map_err(GitHubServiceError::from)
+        })
+        .retry(
+            &ExponentialBuilder::default()
+                .with_min_delay(Duration::from_secs(1))
+                .with_max_delay(Duration::from_secs(10))
+                .with_max_times(6),
+        )
+        .when(|e| !matches!(e, GitHubServiceError::TokenInvalid))
+        .await?;
+
+        info!(
+            "Using fork {}/{} of {}/{}",
+            fork.owner, fork.repo_name, repo_info.owner, repo_info.repo_name
+        );
+        Ok(fork)
+    }
+
+    /// Completed workflow runs on a branch, newest first
+    pub async fn list_workflow_runs(
+        &self,
+        repo_info: &GitHubRepoInfo,
+        branch: &str,
+    ) -> Result<Vec<WorkflowRun>, GitHubServiceError> {
+        let runs: WorkflowRunsResponse = self
+            .client
+            .get(
+                format!(
+                    "/repos/{}/{}/actions/runs",
+                    repo_info.owner, repo_info.repo_name
+                ),
+                Some(&[
+                    ("branch", branch),
+                    ("status", "completed"),
+                    ("per_page", "30"),
+                ]),
+            )
+            .await?;
+        Ok(runs.workflow_runs)
+    }
+
+    pub async fn list_run_jobs(
+        &self,
+        repo_info: &GitHubRepoInfo,
+        run_id: i64,
+    ) -> Result<Vec<WorkflowJob>, GitHubServiceError> {
+        let jobs: WorkflowJobsResponse = self
+            .client
+            .get(
+                format!(
+                    "/repos/{}/{}/actions/runs/{run_id}/jobs",
+                    repo_info.owner, repo_info.repo_name
+                ),
+                Some(&[("filter", "latest"), ("per_page", "100")]),
+            )
+            .await?;
+        Ok(jobs.jobs)
+    }
+
+    /// Plain text log of a job. GitHub answers with a redirect to short-lived storage,
+    /// which reqwest follows.
+    pub async fn get_job_logs(
+        &self,
+        repo_info: &GitHubRepoInfo,
+        job_id: i64,
+    ) -> Result<String, GitHubServiceError> {
+        let url = format!(
+            "https://api.github.com/repos/{}/{}/actions/jobs/{job_id}/logs",
+            repo_info.owner, repo_info.repo_name
+        );
+        let response = reqwest::Client::new()
+            .get(url)
+            .bearer_auth(&self.token)
+            .header("User-Agent", "vibe-kanban")
+            .header("Accept", "application/vnd.github+json")
+            .send()
+            .await
+            .and_then(|response| response.error_for_status())
+            .map_err(|e| GitHubServiceError::Repository(format!("Failed to get job logs: {e}")))?;
+        response
+            .text()
+            .await
+            .map_err(|e| GitHubServiceError::Repository(format!("Failed to read job logs: {e}")))
+    }
+
+    /// List repositories the token can access, recently updated first, 100 a page. Those
+    /// of the installation for app installation tokens, the user's otherwise.
     pub async fn list_repositories(
         &self,
-        page: u8,
+        page: u32,
     ) -> Result<Vec<RepositoryInfo>, GitHubServiceError> {
         (|| async { self.list_repositories_internal(page).await })
             .retry(
@@ -358,50 +823,39 @@ impl GitHubService {
             .await
     }
 
-    #[cfg(feature = "cloud")]
     async fn list_repositories_internal(
         &self,
-        page: u8,
+        page: u32,
     ) -> Result<Vec<RepositoryInfo>, GitHubServiceError> {
-        let repos_page = self
-            .client
-            .current()
-            .list_repos_for_authenticated_user()
-            .type_("all")
-            .sort("updated")
-            .direction("desc")
-            .per_page(50)
-            .page(page)
-            .send()
-            .await
-            .map_err(|e| {
-                GitHubServiceError::Repository(format!("Failed to list repositories: {}", e))
-            })?;
-
-        let repositories: Vec<RepositoryInfo> = repos_page
-            .items
-            .into_iter()
-            .map(|repo| RepositoryInfo {
-                id: repo.id.0 as i64,
-                name: repo.name,
-                full_name: repo.full_name.unwrap_or_default(),
-                owner: repo.owner.map(|o| o.login).unwrap_or_default(),
-                description: repo.description,
-                clone_url: repo
-                    .clone_url
-                    .map(|url| url.to_string())
-                    .unwrap_or_default(),
-                ssh_url: repo.ssh_url.unwrap_or_default(),
-                default_branch: repo.default_branch.unwrap_or_else(|| "main".to_string()),
-                private: repo.private.unwrap_or(false),
-            })
-            .collect();
+        let page = page.to_string();
+        let repositories = if self.token.starts_with("ghs_") {
+            let response: InstallationRepositoriesResponse = self
+                .client
+                .get(
+                    "/installation/repositories",
+                    Some(&[("per_page", "100"), ("page", page.as_str())]),
+                )
+                .await?;
+            response.repositories
+        } else {
+            self.client
+                .get::<Vec<RepositoryResponse>, _, _>(
+                    "/user/repos",
+                    Some(&[
+                        ("affiliation", "owner,collaborator,organization_member"),
+                        ("sort", "updated"),
+                        ("per_page", "100"),
+                        ("page", page.as_str()),
+                    ]),
+                )
+                .await?
+        };
 
-        tracing::info!(
+        tracing::debug!(
             "Retrieved {} repositories from GitHub (page {})",
             repositories.len(),
             page
         );
-        Ok(repositories)
+        Ok(repositories.into_iter().map(RepositoryInfo::from).collect())
     }
 }
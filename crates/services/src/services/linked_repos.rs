@@ -0,0 +1,297 @@
+use std::{
+    collections::HashSet,
+    path::{Path, PathBuf},
+};
+
+use db::models::{
+    attempt_linked_repo::AttemptLinkedRepo, project::Project, task_attempt::TaskAttempt,
+};
+use serde::Serialize;
+use sqlx::SqlitePool;
+use thiserror::Error;
+use ts_rs::TS;
+use utils::diff::Diff;
+use uuid::Uuid;
+
+use crate::services::{
+    git::{DiffTarget, GitService, GitServiceError},
+    worktree_manager::{WorktreeError, WorktreeManager},
+};
+
+/// Directory in an attempt's worktree holding the worktrees of its linked repositories. It is
+/// excluded from the attempt's own commits and diff.
+pub const LINKED_REPOS_DIR: &str = ".linked";
+
+#[derive(Debug, Error)]
+pub enum LinkedRepoError {
+    #[error(transparent)]
+    Database(#[from] sqlx::Error),
+    #[error(transparent)]
+    Git(#[from] GitServiceError),
+    #[error(transparent)]
+    Worktree(#[from] WorktreeError),
+    #[error("Linked project {0} not found")]
+    ProjectNotFound(Uuid),
+    #[error("The attempt's own worktree has to exist before its linked ones")]
+    NoWorktree,
+}
+
+/// A linked repository's worktree as the agent is told about it
+#[derive(Debug, Clone, PartialEq)]
+pub struct LinkedWorktree {
+    pub project_name: String,
+    /// Relative to the attempt's worktree, e.g. `.linked/api`
+    pub path: String,
+    pub base_branch: String,
+}
+
+/// One linked repository's changes, shown next to the attempt's own diff
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct LinkedRepoDiff {
+    pub linked_repo: AttemptLinkedRepo,
+    pub project_name: String,
+    pub diffs: Vec<Diff>,
+}
+
+/// The folder name of the project's repository, e.g. `api` for `~/code/api`, with anything
+/// that isn't safe in a path replaced
+fn linked_dir_name(project: &Project) -> String {
+    let name = project
+        .git_repo_path
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_else(|| project.name.clone());
+    let name: String = name
+        .chars()
+        .map(|c| match c {
+            'a'..='z' | 'A'..='Z' | '0'..='9' | '-' | '_' | '.' => c,
+            _ => '-',
+        })
+        .collect();
+    match name.trim_matches('.') {
+        "" => project.id.simple().to_string(),
+        name => name.to_string(),
+    }
+}
+
+/// What the agent is told about the other repositories it can change, empty without any
+pub fn linked_repos_prompt(project_name: &str, worktrees: &[LinkedWorktree]) -> String {
+    if worktrees.is_empty() {
+        return String::new();
+    }
+    let mut prompt = format!(
+        "\n\nThis task spans several repositories. The working directory is {project_name}, \
+         the others are checked out in it and their changes land together with this one:"
+    );
+    for worktree in worktrees {
+        prompt.push_str(&format!(
+            "\n- `{}`: {} (based on {})",
+            worktree.path, worktree.project_name, worktree.base_branch
+        ));
+    }
+    prompt.push_str(
+        "\nMake the changes each repository needs, e.g. update a client along with the API \
+         it calls. Each repository is committed on its own.",
+    );
+    prompt
+}
+
+/// Create the worktrees of the attempt's linked repositories under its own worktree, on a
+/// branch named like the attempt's
+pub async fn create_worktrees(
+    pool: &SqlitePool,
+    git: &GitService,
+    task_attempt: &TaskAttempt,
+) -> Result<Vec<LinkedWorktree>, LinkedRepoError> {
+    let linked = AttemptLinkedRepo::find_by_task_attempt_id(pool, task_attempt.id).await?;
+    if linked.is_empty() {
+        return Ok(Vec::new());
+    }
+    let (Some(container_ref), Some(branch)) = (&task_attempt.container_ref, &task_attempt.branch)
+    else {
+        return Err(LinkedRepoError::NoWorktree);
+    };
+    let worktree_path = Path::new(container_ref);
+    git.exclude_paths(worktree_path, &[LINKED_REPOS_DIR.to_string()])?;
+
+    let mut dir_names = HashSet::new();
+    let mut worktrees = Vec::with_capacity(linked.len());
+    for repo in linked {
+        let project = Project::find_by_id(pool, repo.project_id)
+            .await?
+            .ok_or(LinkedRepoError::ProjectNotFound(repo.project_id))?;
+        let mut dir_name = linked_dir_name(&project);
+        if !dir_names.insert(dir_name.clone()) {
+            // Two repositories in folders of the same name
+            dir_name = format!("{dir_name}-{}", &project.id.simple().to_string()[..8]);
+            dir_names.insert(dir_name.clone());
+        }
+        let path = worktree_path.join(LINKED_REPOS_DIR).join(&dir_name);
+        WorktreeManager::create_worktree(
+            &project.git_repo_path,
+            branch,
+            &path,
+            Some(&repo.base_branch),
+            true,
+        )
+        .await?;
+        AttemptLinkedRepo::update_worktree(pool, repo.id, branch, &path.to_string_lossy()).await?;
+        worktrees.push(LinkedWorktree {
+            project_name: project.name,
+            path: format!("{LINKED_REPOS_DIR}/{dir_name}"),
+            base_branch: repo.base_branch,
+        });
+    }
+    Ok(worktrees)
+}
+
+/// Recreate linked worktrees that were cleaned up, after the attempt's own was recreated
+pub async fn ensure_worktrees(pool: &SqlitePool, attempt_id: Uuid) -> Result<(), LinkedRepoError> {
+    for repo in AttemptLinkedRepo::find_by_task_attempt_id(pool, attempt_id).await? {
+        let (Some(container_ref), Some(branch)) = (&repo.container_ref, &repo.branch) else {
+            continue;
+        };
+        let project = Project::find_by_id(pool, repo.project_id)
+            .await?
+            .ok_or(LinkedRepoError::ProjectNotFound(repo.project_id))?;
+        WorktreeManager::ensure_worktree_exists(
+            &project.git_repo_path,
+            branch,
+            Path::new(container_ref),
+        )
+        .await?;
+    }
+    Ok(())
+}
+
+/// Remove the linked worktrees of an attempt, before its own worktree goes. Failures are
+/// logged, a missing worktree is pruned by git later anyway.
+pub async fn cleanup_worktrees(pool: &SqlitePool, attempt_id: Uuid) -> Result<(), sqlx::Error> {
+    for repo in AttemptLinkedRepo::find_by_task_attempt_id(pool, attempt_id).await? {
+        let Some(container_ref) = &repo.container_ref else {
+            continue;
+        };
+        let git_repo_path = Project::find_by_id(pool, repo.project_id)
+            .await?
+            .map(|project| project.git_repo_path);
+        if let Err(e) =
+            WorktreeManager::cleanup_worktree(Path::new(container_ref), git_repo_path.as_deref())
+                .await
+        {
+            tracing::warn!(
+                "Failed to clean up linked worktree {} of task attempt {}: {}",
+                container_ref,
+                attempt_id,
+                e
+            );
+        }
+    }
+    Ok(())
+}
+
+/// Commit what the agent changed in each linked worktree, with the attempt's commit message
+pub async fn commit_all(
+    pool: &SqlitePool,
+    git: &GitService,
+    attempt_id: Uuid,
+    message: &str,
+) -> Result<(), LinkedRepoError> {
+    for repo in AttemptLinkedRepo::find_by_task_attempt_id(pool, attempt_id).await? {
+        if let Some(container_ref) = &repo.container_ref {
+            git.commit(Path::new(container_ref), message)?;
+        }
+    }
+    Ok(())
+}
+
+/// The changes of each linked repository: the worktree against its base while it exists, the
+/// committed branch after
+pub async fn linked_diffs(
+    pool: &SqlitePool,
+    git: &GitService,
+    attempt_id: Uuid,
+) -> Result<Vec<LinkedRepoDiff>, LinkedRepoError> {
+    let mut linked_diffs = Vec::new();
+    for repo in AttemptLinkedRepo::find_by_task_attempt_id(pool, attempt_id).await? {
+        let project = Project::find_by_id(pool, repo.project_id)
+            .await?
+            .ok_or(LinkedRepoError::ProjectNotFound(repo.project_id))?;
+        let worktree_path = repo.container_ref.as_ref().map(PathBuf::from);
+        let diffs = match (&worktree_path, &repo.branch) {
+            (Some(worktree_path), Some(branch)) if worktree_path.exists() => git.get_diffs(
+                DiffTarget::Worktree {
+                    worktree_path,
+                    branch_name: branch,
+                    base_branch: &repo.base_branch,
+                },
+                None,
+            )?,
+            (_, Some(branch)) => git.get_diffs(
+                DiffTarget::Branch {
+                    repo_path: &project.git_repo_path,
+                    branch_name: branch,
+                    base_branch: &repo.base_branch,
+                },
+                None,
+            )?,
+            // The attempt hasn't started yet
+            (_, None) => Vec::new(),
+        };
+        linked_diffs.push(LinkedRepoDiff {
+            linked_repo: repo,
+            project_name: project.name,
+            diffs,
+        });
+    }
+    Ok(linked_diffs)
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+
+    use super::*;
+
+    fn project(name: &str, git_repo_path: &str) -> Project {
+        Project {
+            id: Uuid::new_v4(),
+            name: name.to_string(),
+            git_repo_path: PathBuf::from(git_repo_path),
+            setup_script: None,
+            dev_script: None,
+            cleanup_script: None,
+            test_script: None,
+            copy_files: None,
+            test_result_format: None,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_linked_dir_name() {
+        assert_eq!(linked_dir_name(&project("API", "/code/api")), "api");
+        assert_eq!(
+            linked_dir_name(&project("Web", "/code/web client")),
+            "web-client"
+        );
+        // No folder name to go by, and a project name that is only dots
+        let hidden = project("...", "/");
+        assert_eq!(linked_dir_name(&hidden), hidden.id.simple().to_string());
+    }
+
+    #[test]
+    fn test_linked_repos_prompt() {
+        assert_eq!(linked_repos_prompt("web", &[]), "");
+        let prompt = linked_repos_prompt(
+            "web",
+            &[LinkedWorktree {
+                project_name: "api".to_string(),
+                path: ".linked/api".to_string(),
+                base_branch: "main".to_string(),
+            }],
+        );
+        assert!(prompt.contains("The working directory is web"));
+        assert!(prompt.contains("\n- `.linked/api`: api (based on main)"));
+    }
+}
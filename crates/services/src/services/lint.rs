@@ -0,0 +1,59 @@
+/// Lint fixes sent in a row before the attempt is handed back to the user
+pub const MAX_LINT_FIX_ROUNDS: usize = 3;
+
+/// Longer lint output is cut from the start, linters print their summary last
+const MAX_LINT_OUTPUT_CHARS: usize = 20_000;
+
+const LINT_FIX_PROMPT: &str =
+    "The lint command failed on your changes. Fix the violations below without disabling lints.";
+
+/// Follow-up asking the agent to fix the violations in `output`
+pub fn lint_fix_prompt(command: &str, output: &str) -> String {
+    let output = output.trim();
+    let output = match output.char_indices().rev().nth(MAX_LINT_OUTPUT_CHARS - 1) {
+        Some((start, _)) => format!("...\n{}", &output[start..]),
+        None => output.to_string(),
+    };
+    format!("{LINT_FIX_PROMPT}\n\nCommand: `{command}`\n\n```\n{output}\n```")
+}
+
+/// Lint fixes sent since the user's last prompt, given the attempt's prompts oldest first
+pub fn consecutive_lint_fixes<'a>(prompts: impl DoubleEndedIterator<Item = &'a str>) -> usize {
+    prompts
+        .rev()
+        .take_while(|prompt| prompt.starts_with(LINT_FIX_PROMPT))
+        .count()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lint_fix_prompt_keeps_end_of_long_output() {
+        let prompt = lint_fix_prompt("cargo clippy", "warning: unused\nerror: 1 warning");
+        assert!(prompt.starts_with(LINT_FIX_PROMPT));
+        assert!(prompt.contains("Command: `cargo clippy`"));
+        assert!(prompt.contains("warning: unused\nerror: 1 warning"));
+
+        let long = format!("{}summary", "x".repeat(MAX_LINT_OUTPUT_CHARS));
+        let prompt = lint_fix_prompt("eslint .", &long);
+        assert!(prompt.contains("...\nx"));
+        assert!(prompt.ends_with("summary\n```"));
+        assert!(prompt.len() < LINT_FIX_PROMPT.len() + MAX_LINT_OUTPUT_CHARS + 100);
+    }
+
+    #[test]
+    fn test_consecutive_lint_fixes_stop_at_user_prompt() {
+        let fix = lint_fix_prompt("eslint .", "1 error");
+        let prompts = [
+            "Add a login page",
+            fix.as_str(),
+            "Use a form",
+            fix.as_str(),
+            fix.as_str(),
+        ];
+        assert_eq!(consecutive_lint_fixes(prompts.iter().copied()), 2);
+        assert_eq!(consecutive_lint_fixes(prompts[..3].iter().copied()), 0);
+    }
+}
@@ -0,0 +1,301 @@
+use std::{collections::VecDeque, io};
+
+use chrono::{DateTime, Utc};
+use db::models::{
+    execution_process::ExecutionProcess, execution_process_logs::ExecutionProcessLogs,
+};
+use executors::logs::NormalizedEntry;
+use futures::{StreamExt, stream::BoxStream};
+use serde::Serialize;
+use sqlx::SqlitePool;
+use thiserror::Error;
+use utils::log_msg::LogMsg;
+use uuid::Uuid;
+
+/// Bytes of stored JSONL read from the database per query
+const CHUNK_BYTES: i64 = 256 * 1024;
+
+struct ExportState {
+    pool: SqlitePool,
+    processes: VecDeque<ExecutionProcess>,
+    current: Option<Uuid>,
+    offset: i64,
+    /// Start of a JSONL line whose end is in the next chunk
+    partial: Vec<u8>,
+}
+
+/// The plain stdout and stderr of `processes` in order, each headed by a line naming the
+/// process. Logs are read a chunk at a time, so memory use doesn't grow with their size.
+pub fn raw_log_text(
+    pool: SqlitePool,
+    processes: Vec<ExecutionProcess>,
+) -> BoxStream<'static, io::Result<Vec<u8>>> {
+    let state = ExportState {
+        pool,
+        processes: processes.into(),
+        current: None,
+        offset: 0,
+        partial: Vec::new(),
+    };
+    futures::stream::try_unfold(state, |mut state| async move {
+        let Some(execution_id) = state.current else {
+            let Some(process) = state.processes.pop_front() else {
+                return Ok(None);
+            };
+            state.current = Some(process.id);
+            state.offset = 0;
+            state.partial.clear();
+            return Ok(Some((process_header(&process).into_bytes(), state)));
+        };
+
+        let chunk =
+            ExecutionProcessLogs::read_chunk(&state.pool, execution_id, state.offset, CHUNK_BYTES)
+                .await
+                .map_err(io::Error::other)?;
+        if chunk.is_empty() {
+            state.current = None;
+            let rest = std::mem::take(&mut state.partial);
+            return Ok(Some((render_line(&rest), state)));
+        }
+        state.offset += chunk.len() as i64;
+        let text = render_lines(&mut state.partial, &chunk);
+        Ok(Some((text, state)))
+    })
+    .boxed()
+}
+
+fn process_header(process: &ExecutionProcess) -> String {
+    let run_reason = serde_json::to_value(&process.run_reason)
+        .ok()
+        .and_then(|value| value.as_str().map(str::to_string))
+        .unwrap_or_default();
+    format!(
+        "==> {} {} started {} <==\n",
+        run_reason,
+        process.id,
+        process.started_at.to_rfc3339()
+    )
+}
+
+/// Output of the complete JSONL lines in `partial` followed by `chunk`, keeping the incomplete
+/// last line in `partial`
+fn render_lines(partial: &mut Vec<u8>, chunk: &[u8]) -> Vec<u8> {
+    partial.extend_from_slice(chunk);
+    let Some(end) = partial.iter().rposition(|b| *b == b'\n') else {
+        return Vec::new();
+    };
+    let rest = partial.split_off(end + 1);
+    let complete = std::mem::replace(partial, rest);
+    complete
+        .split(|b| *b == b'\n')
+        .flat_map(render_line)
+        .collect()
+}
+
+fn render_line(line: &[u8]) -> Vec<u8> {
+    match serde_json::from_slice::<LogMsg>(line) {
+        Ok(LogMsg::Stdout(text)) | Ok(LogMsg::Stderr(text)) => text.into_bytes(),
+        _ => Vec::new(),
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum EntryFilterError {
+    #[error("Unknown entry type '{0}', expected one of {types}", types = ENTRY_TYPES.join(", "))]
+    UnknownType(String),
+}
+
+/// Types of normalized entries, as their `entry_type.type` is serialized
+const ENTRY_TYPES: [&str; 6] = [
+    "user_message",
+    "assistant_message",
+    "tool_use",
+    "system_message",
+    "error_message",
+    "thinking",
+];
+
+/// Which normalized entries an export includes
+#[derive(Debug, Clone, Default)]
+pub struct EntryFilter {
+    /// Entry types to keep, all of them when empty
+    types: Vec<&'static str>,
+    since: Option<DateTime<Utc>>,
+}
+
+impl EntryFilter {
+    /// `types` is a comma-separated list of entry types, the `_message` suffix can be left
+    /// out, e.g. `tool_use,assistant`
+    pub fn new(
+        types: Option<&str>,
+        since: Option<DateTime<Utc>>,
+    ) -> Result<Self, EntryFilterError> {
+        let types = types
+            .unwrap_or_default()
+            .split(',')
+            .map(str::trim)
+            .filter(|t| !t.is_empty())
+            .map(|t| {
+                ENTRY_TYPES
+                    .iter()
+                    .find(|known| **known == t || known.strip_suffix("_message") == Some(t))
+                    .copied()
+                    .ok_or_else(|| EntryFilterError::UnknownType(t.to_string()))
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(Self { types, since })
+    }
+
+    /// Entries without a timestamp of their own count as happening when their process started
+    fn matches(&self, entry: &NormalizedEntry, process_started: DateTime<Utc>) -> bool {
+        let entry_type = serde_json::to_value(&entry.entry_type)
+            .ok()
+            .and_then(|value| value.get("type")?.as_str().map(str::to_string))
+            .unwrap_or_default();
+        if !self.types.is_empty() && !self.types.contains(&entry_type.as_str()) {
+            return false;
+        }
+        let Some(since) = self.since else {
+            return true;
+        };
+        let at = entry
+            .timestamp
+            .as_deref()
+            .and_then(|ts| DateTime::parse_from_rfc3339(ts).ok())
+            .map(|ts| ts.with_timezone(&Utc))
+            .unwrap_or(process_started);
+        at >= since
+    }
+}
+
+/// One line of a normalized log export
+#[derive(Debug, Serialize)]
+pub struct ExportedEntry<'a> {
+    pub execution_process_id: Uuid,
+    /// Position of the entry in its process's conversation
+    pub index: usize,
+    #[serde(flatten)]
+    pub entry: &'a NormalizedEntry,
+}
+
+/// The conversation the JSON patches of a process's log build up, in order. Patches whose
+/// target is missing, because the history they built on was trimmed, are skipped.
+pub fn normalized_entries(history: &[LogMsg]) -> Vec<NormalizedEntry> {
+    let mut conversation = serde_json::json!({ "entries": [] });
+    for msg in history {
+        if let LogMsg::JsonPatch(patch) = msg {
+            let _ = json_patch::patch(&mut conversation, patch);
+        }
+    }
+    let entries = match conversation.get_mut("entries").map(serde_json::Value::take) {
+        Some(serde_json::Value::Array(entries)) => entries,
+        _ => return Vec::new(),
+    };
+    entries
+        .into_iter()
+        .filter(|entry| entry.get("type").and_then(|t| t.as_str()) == Some("NORMALIZED_ENTRY"))
+        .filter_map(|mut entry| serde_json::from_value(entry.get_mut("content")?.take()).ok())
+        .collect()
+}
+
+/// The entries of one process passing `filter` as JSON Lines
+pub fn entries_jsonl(
+    process: &ExecutionProcess,
+    entries: &[NormalizedEntry],
+    filter: &EntryFilter,
+) -> Vec<u8> {
+    let mut out = Vec::new();
+    for (index, entry) in entries.iter().enumerate() {
+        if !filter.matches(entry, process.started_at) {
+            continue;
+        }
+        let line = ExportedEntry {
+            execution_process_id: process.id,
+            index,
+            entry,
+        };
+        if serde_json::to_writer(&mut out, &line).is_ok() {
+            out.push(b'\n');
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use executors::logs::{ActionType, NormalizedEntryType};
+
+    use super::*;
+
+    fn jsonl(messages: &[LogMsg]) -> Vec<u8> {
+        ExecutionProcessLogs::serialize_logs(messages)
+            .unwrap()
+            .into_bytes()
+    }
+
+    #[test]
+    fn test_lines_split_across_chunks_are_rendered_once_complete() {
+        let log = jsonl(&[
+            LogMsg::Stdout("building\n".to_string()),
+            LogMsg::SessionId("abc".to_string()),
+            LogMsg::Stderr("warning: unused\n".to_string()),
+            LogMsg::Finished,
+        ]);
+
+        let mut partial = Vec::new();
+        let mut text = Vec::new();
+        for chunk in log.chunks(7) {
+            text.extend(render_lines(&mut partial, chunk));
+        }
+        text.extend(render_line(&partial));
+
+        assert_eq!(
+            String::from_utf8(text).unwrap(),
+            "building\nwarning: unused\n"
+        );
+    }
+
+    #[test]
+    fn test_entry_filter_by_type_and_time() {
+        let entry = |entry_type: NormalizedEntryType, timestamp: Option<&str>| NormalizedEntry {
+            timestamp: timestamp.map(str::to_string),
+            entry_type,
+            content: String::new(),
+            metadata: None,
+        };
+        let started = DateTime::parse_from_rfc3339("2025-09-01T10:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        let since = DateTime::parse_from_rfc3339("2025-09-01T11:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        let tool_use = NormalizedEntryType::ToolUse {
+            tool_name: "Read".to_string(),
+            action_type: ActionType::Other {
+                description: "read".to_string(),
+            },
+        };
+
+        let filter = EntryFilter::new(Some("tool_use, assistant"), Some(since)).unwrap();
+        assert!(filter.matches(
+            &entry(tool_use.clone(), Some("2025-09-01T11:30:00Z")),
+            started
+        ));
+        assert!(!filter.matches(&entry(tool_use, None), started));
+        assert!(filter.matches(
+            &entry(
+                NormalizedEntryType::AssistantMessage,
+                Some("2025-09-01T12:00:00+00:00")
+            ),
+            started
+        ));
+        assert!(!filter.matches(
+            &entry(NormalizedEntryType::Thinking, Some("2025-09-01T12:00:00Z")),
+            started
+        ));
+        assert!(matches!(
+            EntryFilter::new(Some("tools"), None),
+            Err(EntryFilterError::UnknownType(t)) if t == "tools"
+        ));
+    }
+}
@@ -0,0 +1,352 @@
+use std::{
+    path::{Path, PathBuf},
+    process::Stdio,
+    time::Duration,
+};
+
+use db::{
+    DBService,
+    models::{
+        branch_sync::BranchSyncStrategy,
+        merge_queue::{MergeQueueEntry, MergeQueueStatus},
+        task::{Task, TaskStatus},
+        task_attempt::{TaskAttempt, TaskAttemptContext, TaskAttemptError},
+    },
+};
+use serde::Serialize;
+use thiserror::Error;
+use tokio::{process::Command, time::interval};
+use tracing::{error, info, warn};
+use ts_rs::TS;
+use utils::{otel::attempt_span, shell::get_shell_command};
+use uuid::Uuid;
+
+use super::{
+    changelog::{self, ChangelogError},
+    conflicts::ConflictTracker,
+    git::{BaseSyncOutcome, GitService, GitServiceError},
+    repo_config::{RepoConfig, RepoConfigError},
+};
+
+/// Longest the verification command may run before the merge is given up
+const VERIFICATION_TIMEOUT: Duration = Duration::from_secs(30 * 60);
+
+/// Characters of verification output kept with a failed entry
+const MAX_OUTPUT_CHARS: usize = 4000;
+
+#[derive(Debug, Error)]
+pub enum MergeQueueError {
+    #[error(transparent)]
+    Database(#[from] sqlx::Error),
+    #[error(transparent)]
+    TaskAttempt(#[from] TaskAttemptError),
+    #[error(transparent)]
+    Git(#[from] GitServiceError),
+    #[error(transparent)]
+    RepoConfig(#[from] RepoConfigError),
+    #[error(transparent)]
+    Changelog(#[from] ChangelogError),
+    #[error("Task attempt {0} not found")]
+    AttemptNotFound(Uuid),
+    #[error("The attempt is already merged")]
+    AlreadyMerged,
+    #[error("The attempt is already in the merge queue")]
+    AlreadyQueued,
+    #[error("The attempt isn't waiting in the merge queue")]
+    NotQueued,
+    #[error("The attempt has no branch")]
+    NoBranch,
+    #[error("The attempt's worktree no longer exists")]
+    WorktreeMissing,
+    #[error("The attempt conflicts with {base_branch} in: {}", files.join(", "))]
+    Conflicts {
+        base_branch: String,
+        files: Vec<String>,
+    },
+    #[error("Verification failed:\n{0}")]
+    VerificationFailed(String),
+    #[error("Verification did not finish within {} minutes", VERIFICATION_TIMEOUT.as_secs() / 60)]
+    VerificationTimedOut,
+    #[error("Failed to run the verification command: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+/// An attempt's latest merge queue entry, with its place in line while it waits
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct MergeQueuePosition {
+    pub entry: Option<MergeQueueEntry>,
+    /// 0 for the attempt being merged, `None` once it left the queue
+    pub position: Option<usize>,
+}
+
+impl MergeQueuePosition {
+    pub async fn find(pool: &sqlx::SqlitePool, task_attempt_id: Uuid) -> Result<Self, sqlx::Error> {
+        let entry = MergeQueueEntry::find_latest_by_task_attempt_id(pool, task_attempt_id).await?;
+        let position = match &entry {
+            Some(entry) if entry.is_active() => {
+                MergeQueueEntry::find_active_by_project_id(pool, entry.project_id)
+                    .await?
+                    .iter()
+                    .position(|queued| queued.id == entry.id)
+            }
+            _ => None,
+        };
+        Ok(Self { entry, position })
+    }
+}
+
+/// Message of the squash commit an attempt is merged with
+pub fn merge_commit_message(task: &Task) -> String {
+    let task_uuid_str = task.id.to_string();
+    let first_uuid_section = task_uuid_str.split('-').next().unwrap_or(&task_uuid_str);
+    let mut message = format!("{} (vibe-kanban {})", task.title, first_uuid_section);
+    if let Some(description) = &task.description
+        && !description.trim().is_empty()
+    {
+        message.push_str("\n\n");
+        message.push_str(description);
+    }
+    message
+}
+
+/// Put an attempt at the end of its project's merge queue
+pub async fn enqueue(
+    pool: &sqlx::SqlitePool,
+    ctx: &TaskAttemptContext,
+) -> Result<MergeQueueEntry, MergeQueueError> {
+    if ctx.task_attempt.merge_commit.is_some() {
+        return Err(MergeQueueError::AlreadyMerged);
+    }
+    if ctx.task_attempt.branch.is_none() {
+        return Err(MergeQueueError::NoBranch);
+    }
+    if MergeQueueEntry::find_latest_by_task_attempt_id(pool, ctx.task_attempt.id)
+        .await?
+        .is_some_and(|entry| entry.is_active())
+    {
+        return Err(MergeQueueError::AlreadyQueued);
+    }
+    Ok(MergeQueueEntry::create(pool, ctx.project.id, ctx.task_attempt.id).await?)
+}
+
+/// Service merging queued attempts one at a time per project. Each is rebased onto its base
+/// branch as it is when its turn comes and verified again before it lands.
+pub struct MergeQueueService {
+    db: DBService,
+    git: GitService,
+    conflicts: ConflictTracker,
+    poll_interval: Duration,
+}
+
+impl MergeQueueService {
+    pub async fn spawn(
+        db: DBService,
+        git: GitService,
+        conflicts: ConflictTracker,
+    ) -> tokio::task::JoinHandle<()> {
+        let service = Self {
+            db,
+            git,
+            conflicts,
+            poll_interval: Duration::from_secs(5),
+        };
+        tokio::spawn(async move {
+            service.start().await;
+        })
+    }
+
+    async fn start(&self) {
+        info!(
+            "Starting merge queue service with interval {:?}",
+            self.poll_interval
+        );
+        match MergeQueueEntry::requeue_interrupted(&self.db.pool).await {
+            Ok(0) => {}
+            Ok(count) => info!("Requeued {} merge(s) interrupted by a restart", count),
+            Err(e) => error!("Failed to requeue interrupted merges: {}", e),
+        }
+
+        let mut interval = interval(self.poll_interval);
+        loop {
+            interval.tick().await;
+            if let Err(e) = self.process_queues().await {
+                error!("Error processing merge queues: {}", e);
+            }
+        }
+    }
+
+    async fn process_queues(&self) -> Result<(), sqlx::Error> {
+        for project_id in MergeQueueEntry::find_project_ids_with_queue(&self.db.pool).await? {
+            while let Some(next) =
+                MergeQueueEntry::find_active_by_project_id(&self.db.pool, project_id)
+                    .await?
+                    .into_iter()
+                    .next()
+            {
+                self.process(&next).await?;
+            }
+        }
+        Ok(())
+    }
+
+    async fn process(&self, entry: &MergeQueueEntry) -> Result<(), sqlx::Error> {
+        let pool = &self.db.pool;
+        // Direct merges of attempts editing the same files wait for this one
+        let _guard = self.conflicts.merge_guard(entry.project_id).await;
+        match self.land(entry).await {
+            Ok(merge_commit) => {
+                info!(
+                    "Merged attempt {} from the merge queue as {}",
+                    entry.task_attempt_id, merge_commit
+                );
+                self.conflicts
+                    .forget(entry.project_id, entry.task_attempt_id);
+                MergeQueueEntry::update_status(
+                    pool,
+                    entry.id,
+                    MergeQueueStatus::Merged,
+                    Some(&merge_commit),
+                    None,
+                )
+                .await
+            }
+            Err(MergeQueueError::Database(e)) => Err(e),
+            Err(e) => {
+                warn!(
+                    "Merge of attempt {} from the queue failed: {}",
+                    entry.task_attempt_id, e
+                );
+                MergeQueueEntry::update_status(
+                    pool,
+                    entry.id,
+                    MergeQueueStatus::Failed,
+                    None,
+                    Some(&e.to_string()),
+                )
+                .await
+            }
+        }
+    }
+
+    /// Rebase, verify and merge the entry's attempt, returning its merge commit
+    async fn land(&self, entry: &MergeQueueEntry) -> Result<String, MergeQueueError> {
+        let pool = &self.db.pool;
+        let attempt = TaskAttempt::find_by_id(pool, entry.task_attempt_id)
+            .await?
+            .ok_or(MergeQueueError::AttemptNotFound(entry.task_attempt_id))?;
+        let ctx =
+            TaskAttempt::load_context(pool, attempt.id, attempt.task_id, entry.project_id).await?;
+        if let Some(merge_commit) = ctx.task_attempt.merge_commit.clone() {
+            // Merged directly while it waited
+            return Ok(merge_commit);
+        }
+        let branch = ctx
+            .task_attempt
+            .branch
+            .clone()
+            .ok_or(MergeQueueError::NoBranch)?;
+        let worktree_path = ctx
+            .task_attempt
+            .container_ref
+            .as_ref()
+            .map(PathBuf::from)
+            .filter(|path| !ctx.task_attempt.worktree_deleted && path.exists())
+            .ok_or(MergeQueueError::WorktreeMissing)?;
+        let repo_path = ctx.project.git_repo_path.clone();
+        let base_branch = ctx.task_attempt.base_branch.clone();
+        let span = attempt_span("merge_queue", attempt.id);
+
+        MergeQueueEntry::update_status(pool, entry.id, MergeQueueStatus::Rebasing, None, None)
+            .await?;
+        let sync = span.in_scope(|| {
+            self.git.sync_branch_with_base(
+                &repo_path,
+                Some(&worktree_path),
+                &branch,
+                &base_branch,
+                BranchSyncStrategy::Rebase,
+            )
+        })?;
+        if let BaseSyncOutcome::Conflicts(files) = sync.outcome {
+            return Err(MergeQueueError::Conflicts { base_branch, files });
+        }
+
+        let repo_config = RepoConfig::load(&self.git, &repo_path, &branch)?;
+        if let Some(command) = repo_config
+            .verification_command
+            .as_deref()
+            .filter(|command| !command.trim().is_empty())
+        {
+            MergeQueueEntry::update_status(pool, entry.id, MergeQueueStatus::Verifying, None, None)
+                .await?;
+            verify(command, &worktree_path).await?;
+        }
+
+        let commit_message =
+            changelog::prepare_merge(pool, &self.git, &ctx, &worktree_path, &branch).await?;
+        let merge_commit = span.in_scope(|| {
+            self.git.merge_changes(
+                &repo_path,
+                &worktree_path,
+                &branch,
+                &base_branch,
+                &commit_message,
+            )
+        })?;
+        TaskAttempt::update_merge_commit(pool, attempt.id, &merge_commit).await?;
+        Task::update_status(pool, ctx.task.id, TaskStatus::Done).await?;
+        Ok(merge_commit)
+    }
+}
+
+/// Run the verification command in the worktree, failing with the end of its output
+async fn verify(command: &str, worktree_path: &Path) -> Result<(), MergeQueueError> {
+    let (shell_cmd, shell_arg) = get_shell_command();
+    let child = Command::new(shell_cmd)
+        .args([shell_arg, command])
+        .current_dir(worktree_path)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true)
+        .spawn()?;
+    let output = tokio::time::timeout(VERIFICATION_TIMEOUT, child.wait_with_output())
+        .await
+        .map_err(|_| MergeQueueError::VerificationTimedOut)??;
+    if output.status.success() {
+        return Ok(());
+    }
+    let mut text = String::from_utf8_lossy(&output.stdout).into_owned();
+    text.push_str(&String::from_utf8_lossy(&output.stderr));
+    Err(MergeQueueError::VerificationFailed(tail(
+        &text,
+        MAX_OUTPUT_CHARS,
+    )))
+}
+
+/// The last `max_chars` characters of `text`
+fn tail(text: &str, max_chars: usize) -> String {
+    let skip = text.chars().count().saturating_sub(max_chars);
+    text.chars().skip(skip).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use tempfile::TempDir;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_verification_failure_keeps_the_end_of_the_output() {
+        let dir = TempDir::new().unwrap();
+        verify("echo ok", dir.path()).await.unwrap();
+
+        let error = verify("echo first; echo last; exit 3", dir.path())
+            .await
+            .unwrap_err();
+        assert!(
+            matches!(error, MergeQueueError::VerificationFailed(ref output) if output == "first\nlast\n")
+        );
+        assert_eq!(tail("abcdef", 2), "ef");
+    }
+}
@@ -1,13 +1,77 @@
+pub mod agent_login;
 pub mod analytics;
+pub mod attachments;
+pub mod attempt_history;
 pub mod auth;
+pub mod backup;
+pub mod baseline;
+pub mod board;
+pub mod branch_sync;
+pub mod changelog;
+pub mod checkpoints;
+pub mod ci_checks;
+pub mod ci_triage;
+pub mod budget;
+pub mod commit_plan;
 pub mod config;
+pub mod config_watcher;
+pub mod conflicts;
 pub mod container;
+pub mod context_pack;
+pub mod cost_estimate;
+pub mod dependency_cache;
+pub mod dependency_updates;
+pub mod devcontainer;
+pub mod dev_server;
+pub mod diff_cache;
+pub mod diff_risk;
+pub mod diff_stats;
+pub mod diff_watcher;
+pub mod disk_quota;
+pub mod evaluation;
 pub mod events;
+pub mod feature_flags;
 pub mod filesystem;
 pub mod filesystem_watcher;
 pub mod git;
+pub mod github_auth;
 pub mod github_service;
+pub mod linked_repos;
+pub mod lint;
+pub mod log_export;
+pub mod merge_queue;
+pub mod nix_env;
 pub mod notification;
+pub mod package_manager;
+pub mod path_rules;
+pub mod planning;
 pub mod pr_monitor;
+pub mod presence;
+pub mod project_env;
+pub mod project_templates;
+pub mod provider_keys;
+pub mod pty;
+pub mod quick_task;
+pub mod repo_clone;
+pub mod repo_discovery;
+pub mod repo_config;
+pub mod review;
+pub mod script_detection;
+pub mod secret_scan;
+pub mod secrets;
+pub mod self_update;
 pub mod sentry;
+pub mod sentry_issues;
+pub mod share_links;
+pub mod shutdown;
+pub mod sounds;
+pub mod support_bundle;
+pub mod test_results;
+pub mod timeline;
+pub mod tool_usage;
+pub mod usage;
+pub mod webhooks;
+pub mod workspace_scan;
 pub mod worktree_manager;
+pub mod worktree_pool;
+pub mod worktree_templates;
@@ -0,0 +1,255 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    io,
+    path::{Path, PathBuf},
+};
+
+use db::models::project_nix_env::{NixTool, ProjectNixEnv};
+use executors::env::ExecutionEnv;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use sqlx::SqlitePool;
+use thiserror::Error;
+use tokio::{process::Command, sync::Mutex};
+use ts_rs::TS;
+use uuid::Uuid;
+
+#[derive(Debug, Error)]
+pub enum NixEnvError {
+    #[error(transparent)]
+    Database(#[from] sqlx::Error),
+    #[error(transparent)]
+    Io(#[from] io::Error),
+    #[error("Invalid development shell: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("`{command}` failed: {stderr}")]
+    Evaluation { command: String, stderr: String },
+}
+
+/// Variables of a development shell describing the build sandbox rather than the toolchain,
+/// left out as `nix develop` does
+const IGNORED_VARS: [&str; 21] = [
+    "BASHOPTS",
+    "HOME",
+    "NIX_BUILD_TOP",
+    "NIX_ENFORCE_PURITY",
+    "NIX_LOG_FD",
+    "NIX_REMOTE",
+    "OLDPWD",
+    "PPID",
+    "PWD",
+    "SHELL",
+    "SHELLOPTS",
+    "SHLVL",
+    "SSL_CERT_FILE",
+    "TEMP",
+    "TEMPDIR",
+    "TERM",
+    "TMP",
+    "TMPDIR",
+    "TZ",
+    "UID",
+    "_",
+];
+
+/// Held while evaluating a shell, so attempts starting together evaluate it once
+static EVALUATION: Mutex<()> = Mutex::const_new(());
+
+/// Files defining the shell of each tool, the first one required
+fn definition_files(tool: NixTool) -> &'static [&'static str] {
+    match tool {
+        NixTool::Flake => &["flake.nix", "flake.lock"],
+        NixTool::Devenv => &["devenv.nix", "devenv.yaml", "devenv.lock"],
+    }
+}
+
+/// The development shell a repository defines, flakes first
+pub fn detect(repo_path: &Path) -> Option<NixTool> {
+    [NixTool::Flake, NixTool::Devenv]
+        .into_iter()
+        .find(|tool| repo_path.join(definition_files(*tool)[0]).is_file())
+}
+
+/// Which shell a project's repository defines, and if its processes run in it
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct ProjectNixEnvStatus {
+    pub detected: Option<NixTool>,
+    pub settings: Option<ProjectNixEnv>,
+}
+
+impl ProjectNixEnvStatus {
+    pub async fn load(
+        pool: &SqlitePool,
+        project_id: Uuid,
+        repo_path: &Path,
+    ) -> Result<Self, sqlx::Error> {
+        Ok(Self {
+            detected: detect(repo_path),
+            settings: ProjectNixEnv::find_by_project_id(pool, project_id).await?,
+        })
+    }
+}
+
+/// Output of `nix print-dev-env --json`
+#[derive(Deserialize)]
+struct DevEnvJson {
+    variables: HashMap<String, DevEnvVar>,
+}
+
+#[derive(Deserialize)]
+struct DevEnvVar {
+    #[serde(rename = "type")]
+    kind: String,
+    value: serde_json::Value,
+}
+
+/// The exported variables of a development shell
+fn parse_dev_env(raw: &str) -> Result<BTreeMap<String, String>, NixEnvError> {
+    let dev_env: DevEnvJson = serde_json::from_str(raw)?;
+    Ok(dev_env
+        .variables
+        .into_iter()
+        .filter(|(name, var)| var.kind == "exported" && !IGNORED_VARS.contains(&name.as_str()))
+        .filter_map(|(name, var)| match var.value {
+            serde_json::Value::String(value) => Some((name, value)),
+            _ => None,
+        })
+        .collect())
+}
+
+/// The shell's variables as an execution environment. Like in `nix develop`, the shell's
+/// programs come first on the `PATH` and the host's after them.
+fn to_env(vars: BTreeMap<String, String>, host_path: Option<&str>) -> ExecutionEnv {
+    let mut env = ExecutionEnv::new();
+    for (name, value) in vars {
+        match (name.as_str(), host_path) {
+            ("PATH", Some(host_path)) => env.insert(name, format!("{value}:{host_path}")),
+            _ => env.insert(name, value),
+        }
+    }
+    env
+}
+
+/// The Nix development shell a worktree's processes run in, giving attempts the toolchain
+/// the repository pins instead of whatever the host has. Evaluating a shell takes seconds to
+/// minutes, so its variables are cached by the contents of its definition and applied to each
+/// process directly, rather than wrapping every command in `nix develop -c`. The shell hook
+/// isn't run.
+pub struct NixEnv {
+    tool: NixTool,
+    worktree: PathBuf,
+}
+
+impl NixEnv {
+    /// The worktree's shell, `None` unless the project enabled it and the worktree defines it
+    pub async fn for_worktree(
+        pool: &SqlitePool,
+        project_id: Uuid,
+        worktree: &Path,
+    ) -> Result<Option<Self>, NixEnvError> {
+        let Some(settings) = ProjectNixEnv::find_by_project_id(pool, project_id)
+            .await?
+            .filter(|settings| settings.enabled)
+        else {
+            return Ok(None);
+        };
+        let definition = worktree.join(definition_files(settings.tool)[0]);
+        Ok(definition.is_file().then(|| Self {
+            tool: settings.tool,
+            worktree: worktree.to_path_buf(),
+        }))
+    }
+
+    /// Identifies the shell's definition, an edit to it or its lock file evaluates it again
+    fn cache_key(&self) -> io::Result<String> {
+        let mut hasher = Sha256::new();
+        hasher.update(format!("{:?}", self.tool));
+        for file in definition_files(self.tool) {
+            let path = self.worktree.join(file);
+            if path.is_file() {
+                hasher.update(file);
+                hasher.update(std::fs::read(path)?);
+            }
+        }
+        Ok(format!("{:x}", hasher.finalize()))
+    }
+
+    /// The shell's variables, evaluating it unless this version of it was evaluated before
+    pub async fn env(&self) -> Result<ExecutionEnv, NixEnvError> {
+        let cache_dir = utils::path::get_nix_env_cache_dir();
+        let cached = cache_dir.join(format!("{}.json", self.cache_key()?));
+
+        let _evaluation = EVALUATION.lock().await;
+        let vars = match tokio::fs::read_to_string(&cached).await {
+            Ok(raw) => serde_json::from_str(&raw)?,
+            Err(_) => {
+                let vars = self.evaluate().await?;
+                tokio::fs::create_dir_all(&cache_dir).await?;
+                // Written aside first, so a crash never leaves a truncated shell behind
+                let partial = cached.with_extension("json.partial");
+                tokio::fs::write(&partial, serde_json::to_vec(&vars)?).await?;
+                tokio::fs::rename(&partial, &cached).await?;
+                vars
+            }
+        };
+        Ok(to_env(vars, std::env::var("PATH").ok().as_deref()))
+    }
+
+    async fn evaluate(&self) -> Result<BTreeMap<String, String>, NixEnvError> {
+        let (program, args): (&str, &[&str]) = match self.tool {
+            NixTool::Flake => (
+                "nix",
+                &[
+                    "--extra-experimental-features",
+                    "nix-command flakes",
+                    "print-dev-env",
+                    "--json",
+                ],
+            ),
+            NixTool::Devenv => ("devenv", &["print-dev-env", "--json"]),
+        };
+        tracing::info!(
+            "Evaluating the {:?} development shell of {}",
+            self.tool,
+            self.worktree.display()
+        );
+        let output = Command::new(program)
+            .args(args)
+            .current_dir(&self.worktree)
+            .kill_on_drop(true)
+            .output()
+            .await?;
+        if !output.status.success() {
+            return Err(NixEnvError::Evaluation {
+                command: format!("{program} {}", args.join(" ")),
+                stderr: String::from_utf8_lossy(&output.stderr).trim().to_string(),
+            });
+        }
+        parse_dev_env(&String::from_utf8_lossy(&output.stdout))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dev_env_keeps_exported_toolchain_variables() {
+        let raw = r#"{
+            "bashFunctions": {},
+            "variables": {
+                "PATH": { "type": "exported", "value": "/nix/store/abc-cargo/bin" },
+                "RUST_SRC_PATH": { "type": "exported", "value": "/nix/store/def-src" },
+                "HOME": { "type": "exported", "value": "/homeless-shelter" },
+                "out": { "type": "var", "value": "/nix/store/ghi-out" },
+                "buildInputs": { "type": "array", "value": ["a", "b"] }
+            }
+        }"#;
+        let env = to_env(parse_dev_env(raw).unwrap(), Some("/usr/bin"));
+        assert_eq!(env.get("PATH"), Some("/nix/store/abc-cargo/bin:/usr/bin"));
+        assert_eq!(env.get("RUST_SRC_PATH"), Some("/nix/store/def-src"));
+        assert_eq!(env.get("HOME"), None);
+        assert_eq!(env.get("out"), None);
+        assert_eq!(env.iter().count(), 2);
+    }
+}
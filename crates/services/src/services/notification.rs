@@ -1,45 +1,117 @@
 use std::sync::OnceLock;
 
-use db::models::execution_process::{ExecutionContext, ExecutionProcessStatus};
-use utils;
+use db::models::execution_process::{
+    ExecutionContext, ExecutionProcessRunReason, ExecutionProcessStatus,
+};
+use utils::{
+    self,
+    i18n::{Message, MessageCode},
+};
 
-use crate::services::config::SoundFile;
+use crate::services::{
+    config::NotificationConfig,
+    sounds::{Sound, SoundLibrary},
+};
 
 /// Service for handling cross-platform notifications including sound alerts and push notifications
 #[derive(Debug, Clone)]
 pub struct NotificationService {}
-use crate::services::config::NotificationConfig;
 
 /// Cache for WSL root path from PowerShell
 static WSL_ROOT_PATH_CACHE: OnceLock<Option<String>> = OnceLock::new();
 
+/// What a finished execution means for the user, each can have its own sound
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NotificationEvent {
+    Success,
+    /// The coding agent failed or was stopped
+    Failure,
+    /// A setup, verification or cleanup script failed and the attempt is waiting on the user
+    NeedsAttention,
+}
+
+impl NotificationEvent {
+    /// `None` while the process is still running
+    pub fn from_process(
+        status: &ExecutionProcessStatus,
+        run_reason: &ExecutionProcessRunReason,
+    ) -> Option<Self> {
+        match (status, run_reason) {
+            (ExecutionProcessStatus::Running, _) => None,
+            (ExecutionProcessStatus::Completed, _) => Some(Self::Success),
+            (_, ExecutionProcessRunReason::CodingAgent) => Some(Self::Failure),
+            _ => Some(Self::NeedsAttention),
+        }
+    }
+
+    /// The sound configured for this event, `sound_file` if there is none or it is invalid
+    pub fn sound(&self, config: &NotificationConfig) -> Sound {
+        let id = match self {
+            Self::Success => &config.event_sounds.success,
+            Self::Failure => &config.event_sounds.failure,
+            Self::NeedsAttention => &config.event_sounds.needs_attention,
+        };
+        id.as_deref()
+            .and_then(|id| {
+                Sound::parse(id)
+                    .inspect_err(|e| tracing::warn!("Ignoring notification sound: {}", e))
+                    .ok()
+            })
+            .unwrap_or_else(|| Sound::Builtin(config.sound_file.clone()))
+    }
+}
+
 impl NotificationService {
     pub async fn notify_execution_halted(config: NotificationConfig, ctx: &ExecutionContext) {
-        let title = format!("Task Complete: {}", ctx.task.title);
-        let message = match ctx.execution_process.status {
-            ExecutionProcessStatus::Completed => format!(
-                "✅ '{}' completed successfully\nBranch: {:?}\nExecutor: {}",
-                ctx.task.title, ctx.task_attempt.branch, ctx.task_attempt.profile
+        let process = &ctx.execution_process;
+        let Some(event) = NotificationEvent::from_process(&process.status, &process.run_reason)
+        else {
+            tracing::warn!(
+                "Tried to notify attempt completion for {} but process is still running!",
+                ctx.task_attempt.id
+            );
+            return;
+        };
+        let (title, message) = match event {
+            NotificationEvent::Success => (
+                MessageCode::AttemptFinishedTitle,
+                MessageCode::AttemptSucceeded,
             ),
-            ExecutionProcessStatus::Failed | ExecutionProcessStatus::Killed => format!(
-                "❌ '{}' execution failed\nBranch: {:?}\nExecutor: {}",
-                ctx.task.title, ctx.task_attempt.branch, ctx.task_attempt.profile
+            NotificationEvent::Failure => (
+                MessageCode::AttemptFinishedTitle,
+                MessageCode::AttemptFailed,
+            ),
+            NotificationEvent::NeedsAttention => (
+                MessageCode::AttemptNeedsAttentionTitle,
+                MessageCode::AttemptNeedsAttention,
             ),
-            _ => {
-                tracing::warn!(
-                    "Tried to notify attempt completion for {} but process is still running!",
-                    ctx.task_attempt.id
-                );
-                return;
-            }
         };
-        Self::notify(config, &title, &message).await;
+        let title = Message::new(title)
+            .with("title", &ctx.task.title)
+            .localized();
+        let message = Message::new(message)
+            .with("title", &ctx.task.title)
+            .with("branch", format!("{:?}", ctx.task_attempt.branch))
+            .with("executor", &ctx.task_attempt.profile)
+            .localized();
+        let sound = event.sound(&config);
+        Self::notify_with_sound(config, &sound, &title, &message).await;
     }
 
     /// Send both sound and push notifications if enabled
     pub async fn notify(config: NotificationConfig, title: &str, message: &str) {
+        let sound = Sound::Builtin(config.sound_file.clone());
+        Self::notify_with_sound(config, &sound, title, message).await;
+    }
+
+    async fn notify_with_sound(
+        config: NotificationConfig,
+        sound: &Sound,
+        title: &str,
+        message: &str,
+    ) {
         if config.sound_enabled {
-            Self::play_sound_notification(&config.sound_file).await;
+            Self::play_sound_notification(sound).await;
         }
 
         if config.push_enabled {
@@ -48,11 +120,11 @@ impl NotificationService {
     }
 
     /// Play a system sound notification across platforms
-    async fn play_sound_notification(sound_file: &SoundFile) {
-        let file_path = match sound_file.get_path().await {
+    async fn play_sound_notification(sound: &Sound) {
+        let file_path = match SoundLibrary::default().playback_path(sound).await {
             Ok(path) => path,
             Err(e) => {
-                tracing::error!("Failed to create cached sound file: {}", e);
+                tracing::error!("Failed to find notification sound: {}", e);
                 return;
             }
         };
@@ -247,3 +319,52 @@ impl NotificationService {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::config::SoundFile;
+
+    #[test]
+    fn test_event_from_process() {
+        use ExecutionProcessRunReason::*;
+        use ExecutionProcessStatus::*;
+
+        assert_eq!(
+            NotificationEvent::from_process(&Running, &CodingAgent),
+            None
+        );
+        assert_eq!(
+            NotificationEvent::from_process(&Completed, &CleanupScript),
+            Some(NotificationEvent::Success)
+        );
+        assert_eq!(
+            NotificationEvent::from_process(&Killed, &CodingAgent),
+            Some(NotificationEvent::Failure)
+        );
+        assert_eq!(
+            NotificationEvent::from_process(&Failed, &SetupScript),
+            Some(NotificationEvent::NeedsAttention)
+        );
+    }
+
+    #[test]
+    fn test_event_sound_falls_back_to_default() {
+        let mut config = NotificationConfig::default();
+        config.event_sounds.failure = Some("ROOSTER".to_string());
+        config.event_sounds.needs_attention = Some("../escape.wav".to_string());
+
+        assert_eq!(
+            NotificationEvent::Failure.sound(&config),
+            Sound::Builtin(SoundFile::Rooster)
+        );
+        assert_eq!(
+            NotificationEvent::NeedsAttention.sound(&config),
+            Sound::Builtin(SoundFile::CowMooing)
+        );
+        assert_eq!(
+            NotificationEvent::Success.sound(&config),
+            Sound::Builtin(SoundFile::CowMooing)
+        );
+    }
+}
@@ -0,0 +1,92 @@
+use std::path::Path;
+
+use serde::Serialize;
+use ts_rs::TS;
+
+/// Package manager a repository's lockfiles or manifests point at
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, TS)]
+#[serde(rename_all = "snake_case")]
+pub enum PackageManager {
+    Pnpm,
+    Yarn,
+    Bun,
+    Npm,
+    Cargo,
+    Uv,
+    Poetry,
+    Pip,
+    Go,
+    Bundler,
+}
+
+/// Files that identify each package manager, checked in order so lockfiles win over the
+/// manifests several managers share
+const MARKERS: &[(&str, PackageManager)] = &[
+    ("pnpm-lock.yaml", PackageManager::Pnpm),
+    ("yarn.lock", PackageManager::Yarn),
+    ("bun.lockb", PackageManager::Bun),
+    ("bun.lock", PackageManager::Bun),
+    ("package-lock.json", PackageManager::Npm),
+    ("package.json", PackageManager::Npm),
+    ("Cargo.toml", PackageManager::Cargo),
+    ("uv.lock", PackageManager::Uv),
+    ("poetry.lock", PackageManager::Poetry),
+    ("requirements.txt", PackageManager::Pip),
+    ("pyproject.toml", PackageManager::Pip),
+    ("go.mod", PackageManager::Go),
+    ("Gemfile", PackageManager::Bundler),
+];
+
+impl PackageManager {
+    /// Detect the package manager from the files at the root of a repository
+    pub fn detect(repo_path: &Path) -> Option<Self> {
+        MARKERS
+            .iter()
+            .find(|(file, _)| repo_path.join(file).is_file())
+            .map(|(_, manager)| *manager)
+    }
+
+    /// Command installing the dependencies of the repository at `repo_path`
+    pub fn setup_command(self, repo_path: &Path) -> &'static str {
+        match self {
+            PackageManager::Pnpm => "pnpm install",
+            PackageManager::Yarn => "yarn install",
+            PackageManager::Bun => "bun install",
+            PackageManager::Npm => "npm install",
+            PackageManager::Cargo => "cargo build",
+            PackageManager::Uv => "uv sync",
+            PackageManager::Poetry => "poetry install",
+            PackageManager::Pip if repo_path.join("requirements.txt").is_file() => {
+                "python3 -m venv .venv && .venv/bin/pip install -r requirements.txt"
+            }
+            PackageManager::Pip => "python3 -m venv .venv && .venv/bin/pip install -e .",
+            PackageManager::Go => "go mod download",
+            PackageManager::Bundler => "bundle install",
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tempfile::TempDir;
+
+    use super::*;
+
+    #[test]
+    fn test_lockfiles_win_over_shared_manifests() {
+        let dir = TempDir::new().unwrap();
+        assert_eq!(PackageManager::detect(dir.path()), None);
+
+        std::fs::write(dir.path().join("package.json"), "{}").unwrap();
+        assert_eq!(
+            PackageManager::detect(dir.path()),
+            Some(PackageManager::Npm)
+        );
+
+        std::fs::write(dir.path().join("pnpm-lock.yaml"), "").unwrap();
+        assert_eq!(
+            PackageManager::detect(dir.path()),
+            Some(PackageManager::Pnpm)
+        );
+    }
+}
@@ -0,0 +1,215 @@
+use std::path::Path;
+
+use db::models::project_path_rules::ProjectPathRules;
+use globset::{Glob, GlobSet, GlobSetBuilder};
+use serde::Serialize;
+use thiserror::Error;
+use ts_rs::TS;
+use utils::diff::Diff;
+
+use super::git::{DiffTarget, GitService, GitServiceError};
+
+const PATH_REVERT_PROMPT: &str = "You changed files outside the scope of this task. Revert your changes to the files below, keep the rest of your work as it is.";
+
+#[derive(Debug, Error)]
+pub enum PathRulesError {
+    #[error("Invalid path glob '{0}': {1}")]
+    InvalidGlob(String, globset::Error),
+    #[error(transparent)]
+    Git(#[from] GitServiceError),
+}
+
+/// Why a changed path breaks the project's path rules
+#[derive(Debug, Clone, PartialEq, Serialize, TS)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum PathViolationReason {
+    /// Matches a forbidden glob
+    Forbidden { pattern: String },
+    /// Matches none of the allowed globs
+    NotAllowed,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, TS)]
+pub struct PathViolation {
+    pub path: String,
+    pub reason: PathViolationReason,
+}
+
+/// Compiled allowed and forbidden globs of a project
+#[derive(Debug, Clone)]
+pub struct PathRules {
+    allowed: Option<GlobSet>,
+    forbidden: GlobSet,
+    forbidden_patterns: Vec<String>,
+}
+
+impl PathRules {
+    pub fn new(allowed: &[String], forbidden: &[String]) -> Result<Self, PathRulesError> {
+        let allowed = if allowed.is_empty() {
+            None
+        } else {
+            Some(build_set(allowed)?)
+        };
+        Ok(Self {
+            allowed,
+            forbidden: build_set(forbidden)?,
+            forbidden_patterns: forbidden.to_vec(),
+        })
+    }
+
+    pub fn from_project(rules: &ProjectPathRules) -> Result<Self, PathRulesError> {
+        Self::new(&rules.allowed_paths, &rules.forbidden_paths)
+    }
+
+    pub fn check(&self, path: &str) -> Option<PathViolationReason> {
+        if let Some(index) = self.forbidden.matches(path).first() {
+            return Some(PathViolationReason::Forbidden {
+                pattern: self.forbidden_patterns[*index].clone(),
+            });
+        }
+        match &self.allowed {
+            Some(allowed) if !allowed.is_match(path) => Some(PathViolationReason::NotAllowed),
+            _ => None,
+        }
+    }
+
+    /// Changed paths breaking the rules, renames count for both their old and new path
+    pub fn violations(&self, diffs: &[Diff]) -> Vec<PathViolation> {
+        let mut violations: Vec<PathViolation> = Vec::new();
+        for diff in diffs {
+            for path in [&diff.old_file, &diff.new_file]
+                .into_iter()
+                .filter_map(|file| file.as_ref()?.file_name.as_deref())
+            {
+                if violations.iter().any(|v| v.path == path) {
+                    continue;
+                }
+                if let Some(reason) = self.check(path) {
+                    violations.push(PathViolation {
+                        path: path.to_string(),
+                        reason,
+                    });
+                }
+            }
+        }
+        violations
+    }
+
+    /// Violations among the changes an attempt's branch makes on top of its base branch,
+    /// including uncommitted changes in the worktree
+    pub fn worktree_violations(
+        &self,
+        worktree_path: &Path,
+        branch_name: &str,
+        base_branch: &str,
+    ) -> Result<Vec<PathViolation>, PathRulesError> {
+        let diffs = GitService::new().get_diffs(
+            DiffTarget::Worktree {
+                worktree_path,
+                branch_name,
+                base_branch,
+            },
+            None,
+        )?;
+        Ok(self.violations(&diffs))
+    }
+}
+
+fn build_set(patterns: &[String]) -> Result<GlobSet, PathRulesError> {
+    let mut builder = GlobSetBuilder::new();
+    for pattern in patterns {
+        let glob =
+            Glob::new(pattern).map_err(|e| PathRulesError::InvalidGlob(pattern.clone(), e))?;
+        builder.add(glob);
+    }
+    builder
+        .build()
+        .map_err(|e| PathRulesError::InvalidGlob(patterns.join(", "), e))
+}
+
+/// Follow-up asking the agent to revert its out-of-scope changes
+pub fn path_revert_prompt(violations: &[PathViolation]) -> String {
+    let files: Vec<String> = violations
+        .iter()
+        .map(|v| match &v.reason {
+            PathViolationReason::Forbidden { pattern } => {
+                format!("- {} (forbidden by `{}`)", v.path, pattern)
+            }
+            PathViolationReason::NotAllowed => format!("- {} (not an allowed path)", v.path),
+        })
+        .collect();
+    format!("{PATH_REVERT_PROMPT}\n\n{}", files.join("\n"))
+}
+
+pub fn is_path_revert_prompt(prompt: &str) -> bool {
+    prompt.starts_with(PATH_REVERT_PROMPT)
+}
+
+#[cfg(test)]
+mod tests {
+    use utils::diff::FileDiffDetails;
+
+    use super::*;
+
+    fn diff(old: Option<&str>, new: Option<&str>) -> Diff {
+        let file = |name: &str| FileDiffDetails {
+            file_name: Some(name.to_string()),
+            content: Some(String::new()),
+        };
+        Diff {
+            old_file: old.map(file),
+            new_file: new.map(file),
+            hunks: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_violations_of_allowed_and_forbidden_globs() {
+        let rules = PathRules::new(
+            &["src/**".to_string(), "tests/**".to_string()],
+            &["src/generated/**".to_string()],
+        )
+        .unwrap();
+        let diffs = [
+            diff(Some("src/lib.rs"), Some("src/lib.rs")),
+            diff(None, Some("src/generated/api.rs")),
+            diff(Some("README.md"), Some("README.md")),
+            diff(Some("tests/old.rs"), Some("docs/old.rs")),
+        ];
+
+        let violations = rules.violations(&diffs);
+        assert_eq!(
+            violations,
+            vec![
+                PathViolation {
+                    path: "src/generated/api.rs".to_string(),
+                    reason: PathViolationReason::Forbidden {
+                        pattern: "src/generated/**".to_string()
+                    },
+                },
+                PathViolation {
+                    path: "README.md".to_string(),
+                    reason: PathViolationReason::NotAllowed,
+                },
+                PathViolation {
+                    path: "docs/old.rs".to_string(),
+                    reason: PathViolationReason::NotAllowed,
+                },
+            ]
+        );
+
+        let prompt = path_revert_prompt(&violations);
+        assert!(is_path_revert_prompt(&prompt));
+        assert!(prompt.contains("- src/generated/api.rs (forbidden by `src/generated/**`)"));
+        assert!(prompt.contains("- README.md (not an allowed path)"));
+
+        // Without allowed globs only forbidden paths are violations
+        let rules = PathRules::new(&[], &["*.lock".to_string()]).unwrap();
+        assert_eq!(rules.check("README.md"), None);
+        assert!(rules.check("Cargo.lock").is_some());
+        assert!(matches!(
+            PathRules::new(&["src/[".to_string()], &[]),
+            Err(PathRulesError::InvalidGlob(..))
+        ));
+    }
+}
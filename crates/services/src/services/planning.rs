@@ -0,0 +1,15 @@
+const PLAN_PROMPT: &str = "Don't change any files yet. Explore the code and write a step by step plan \
+for the task below, naming the files you would change. The plan is reviewed before you carry it out.";
+
+const EXECUTE_PROMPT: &str =
+    "Your plan was approved. Carry it out now, following the approved plan below.";
+
+/// Prompt of the planning phase of a plan-then-execute attempt
+pub fn plan_prompt(task_prompt: &str) -> String {
+    format!("{PLAN_PROMPT}\n\n{task_prompt}")
+}
+
+/// Follow-up starting the execution phase with the approved plan
+pub fn execute_prompt(plan: &str) -> String {
+    format!("{EXECUTE_PROMPT}\n\n{}", plan.trim())
+}
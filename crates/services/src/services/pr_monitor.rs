@@ -14,6 +14,7 @@ use tracing::{debug, error, info};
 
 use crate::services::{
     config::Config,
+    github_auth,
     github_service::{GitHubRepoInfo, GitHubService, GitHubServiceError},
 };
 
@@ -90,7 +91,9 @@ impl PrMonitorService {
     /// Check the status of a specific PR
     async fn check_pr_status(&self, pr_info: &PrInfo) -> Result<(), PrMonitorError> {
         let github_config = self.config.read().await.github.clone();
-        let github_token = github_config.token().ok_or(PrMonitorError::NoGitHubToken)?;
+        let github_token = github_auth::access_token(&github_config)
+            .await?
+            .ok_or(PrMonitorError::NoGitHubToken)?;
 
         let github_service = GitHubService::new(&github_token)?;
 
@@ -0,0 +1,225 @@
+use chrono::{DateTime, Utc};
+use executors::logs::{ActionType, NormalizedEntry, NormalizedEntryType};
+use serde::Serialize;
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// Longest activity line, short enough for a card badge or a terminal status line
+const MAX_ACTIVITY_CHARS: usize = 80;
+/// Task titles are cut to this in the status line, the activity matters more
+const MAX_STATUS_TITLE_CHARS: usize = 24;
+
+/// The last tool a running agent called
+#[derive(Debug, Clone, PartialEq, Serialize, TS)]
+pub struct PresenceToolCall {
+    pub tool_name: String,
+    /// File, command, query or URL the call was about, when it had one
+    pub target: Option<String>,
+    pub timestamp: Option<String>,
+}
+
+/// Where an agent's conversation has got to. Entries move it along one at a time, system
+/// messages leave it where it was.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct AgentActivity {
+    /// One line on what the agent is doing, `None` before its first entry
+    pub activity: Option<String>,
+    pub last_tool_call: Option<PresenceToolCall>,
+}
+
+impl AgentActivity {
+    pub fn from_entries(entries: &[NormalizedEntry]) -> Self {
+        entries
+            .iter()
+            .fold(Self::default(), |state, entry| state.next(entry))
+    }
+
+    fn next(mut self, entry: &NormalizedEntry) -> Self {
+        let activity = match &entry.entry_type {
+            NormalizedEntryType::UserMessage => "Reading the prompt".to_string(),
+            NormalizedEntryType::AssistantMessage => condense(&entry.content),
+            NormalizedEntryType::Thinking => "Thinking".to_string(),
+            NormalizedEntryType::ErrorMessage => condense(&format!("Error: {}", entry.content)),
+            NormalizedEntryType::SystemMessage => return self,
+            NormalizedEntryType::ToolUse {
+                tool_name,
+                action_type,
+            } => {
+                self.last_tool_call = Some(PresenceToolCall {
+                    tool_name: tool_name.clone(),
+                    target: tool_target(action_type).map(condense),
+                    timestamp: entry.timestamp.clone(),
+                });
+                condense(&describe_action(action_type, &entry.content))
+            }
+        };
+        if !activity.is_empty() {
+            self.activity = Some(activity);
+        }
+        self
+    }
+}
+
+fn tool_target(action_type: &ActionType) -> Option<&str> {
+    match action_type {
+        ActionType::FileRead { path } | ActionType::FileEdit { path, .. } => Some(path),
+        ActionType::CommandRun { command } => Some(command),
+        ActionType::Search { query } => Some(query),
+        ActionType::WebFetch { url } => Some(url),
+        ActionType::TaskCreate { description } => Some(description),
+        ActionType::PlanPresentation { .. }
+        | ActionType::TodoManagement { .. }
+        | ActionType::Other { .. } => None,
+    }
+}
+
+fn describe_action(action_type: &ActionType, content: &str) -> String {
+    match action_type {
+        ActionType::FileRead { path } => format!("Reading {path}"),
+        ActionType::FileEdit { path, .. } => format!("Editing {path}"),
+        ActionType::CommandRun { command } => format!("Running {command}"),
+        ActionType::Search { query } => format!("Searching for {query}"),
+        ActionType::WebFetch { url } => format!("Fetching {url}"),
+        ActionType::TaskCreate { description } => format!("Delegating: {description}"),
+        ActionType::PlanPresentation { .. } => "Presenting a plan".to_string(),
+        ActionType::TodoManagement { .. } => "Updating the todo list".to_string(),
+        ActionType::Other { description } if content.is_empty() => description.clone(),
+        ActionType::Other { .. } => content.to_string(),
+    }
+}
+
+/// `text` on a single line, cut off after `MAX_ACTIVITY_CHARS`
+fn condense(text: &str) -> String {
+    truncate(
+        &text.split_whitespace().collect::<Vec<_>>().join(" "),
+        MAX_ACTIVITY_CHARS,
+    )
+}
+
+fn truncate(text: &str, max_chars: usize) -> String {
+    match text.char_indices().nth(max_chars) {
+        Some((end, _)) => format!("{}...", text[..end].trim_end()),
+        None => text.to_string(),
+    }
+}
+
+/// A coding agent running right now, for the board's card badges and the status line
+#[derive(Debug, Clone, PartialEq, Serialize, TS)]
+pub struct AgentPresence {
+    pub execution_process_id: Uuid,
+    pub task_attempt_id: Uuid,
+    pub task_id: Uuid,
+    pub project_id: Uuid,
+    pub task_title: String,
+    pub profile: String,
+    pub variant: Option<String>,
+    pub started_at: DateTime<Utc>,
+    #[ts(type = "number")]
+    pub elapsed_secs: i64,
+    pub activity: Option<String>,
+    pub last_tool_call: Option<PresenceToolCall>,
+}
+
+/// `95` as `1m35s`, whole minutes from an hour on
+pub fn format_elapsed(secs: i64) -> String {
+    let secs = secs.max(0);
+    match (secs / 3600, secs / 60 % 60, secs % 60) {
+        (0, 0, s) => format!("{s}s"),
+        (0, m, s) => format!("{m}m{s:02}s"),
+        (h, m, _) => format!("{h}h{m:02}m"),
+    }
+}
+
+/// One line for a terminal status bar such as tmux's `status-right`, empty while no agent
+/// runs so the bar stays clean
+pub fn status_line(presences: &[AgentPresence]) -> String {
+    presences
+        .iter()
+        .map(|presence| {
+            let mut line = format!(
+                "{} [{} {}]",
+                truncate(&presence.task_title, MAX_STATUS_TITLE_CHARS),
+                presence.profile,
+                format_elapsed(presence.elapsed_secs)
+            );
+            if let Some(activity) = &presence.activity {
+                line.push_str(&format!(" {activity}"));
+            }
+            line
+        })
+        .collect::<Vec<_>>()
+        .join(" | ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(entry_type: NormalizedEntryType, content: &str) -> NormalizedEntry {
+        NormalizedEntry {
+            timestamp: None,
+            entry_type,
+            content: content.to_string(),
+            metadata: None,
+        }
+    }
+
+    #[test]
+    fn test_activity_follows_the_conversation() {
+        let edit = entry(
+            NormalizedEntryType::ToolUse {
+                tool_name: "edit".to_string(),
+                action_type: ActionType::FileEdit {
+                    path: "src/main.rs".to_string(),
+                    changes: Vec::new(),
+                },
+            },
+            "src/main.rs",
+        );
+        let mut entries = vec![
+            entry(NormalizedEntryType::UserMessage, "Fix the build"),
+            edit,
+            entry(NormalizedEntryType::SystemMessage, "Tokens used: 1200"),
+        ];
+        let activity = AgentActivity::from_entries(&entries);
+        assert_eq!(activity.activity.as_deref(), Some("Editing src/main.rs"));
+        let tool = activity.last_tool_call.unwrap();
+        assert_eq!(tool.tool_name, "edit");
+        assert_eq!(tool.target.as_deref(), Some("src/main.rs"));
+
+        entries.push(entry(
+            NormalizedEntryType::AssistantMessage,
+            "The build passes now.\n\nI fixed the import.",
+        ));
+        let activity = AgentActivity::from_entries(&entries);
+        assert_eq!(
+            activity.activity.as_deref(),
+            Some("The build passes now. I fixed the import.")
+        );
+        // The last tool call stays until the next one
+        assert_eq!(activity.last_tool_call.unwrap().tool_name, "edit");
+    }
+
+    #[test]
+    fn test_status_line() {
+        assert_eq!(status_line(&[]), "");
+        let presence = AgentPresence {
+            execution_process_id: Uuid::new_v4(),
+            task_attempt_id: Uuid::new_v4(),
+            task_id: Uuid::new_v4(),
+            project_id: Uuid::new_v4(),
+            task_title: "Add a presence endpoint for the board".to_string(),
+            profile: "claude-code".to_string(),
+            variant: None,
+            started_at: Utc::now(),
+            elapsed_secs: 95,
+            activity: Some("Thinking".to_string()),
+            last_tool_call: None,
+        };
+        assert_eq!(
+            status_line(&[presence]),
+            "Add a presence endpoint... [claude-code 1m35s] Thinking"
+        );
+        assert_eq!(format_elapsed(3 * 3600 + 5 * 60 + 7), "3h05m");
+    }
+}
@@ -0,0 +1,135 @@
+use std::collections::HashSet;
+
+use db::models::project_env_var::{ProjectEnvVar, ProjectEnvVarInput};
+use executors::env::ExecutionEnv;
+use sqlx::SqlitePool;
+use thiserror::Error;
+use uuid::Uuid;
+
+use super::secrets::{SecretsError, SecretsStore};
+
+#[derive(Debug, Error)]
+pub enum ProjectEnvError {
+    #[error(transparent)]
+    Database(#[from] sqlx::Error),
+    #[error(transparent)]
+    Secrets(#[from] SecretsError),
+    #[error("Invalid variable name {0:?}, use letters, digits and underscores")]
+    InvalidKey(String),
+    #[error("Variable {0} is defined twice")]
+    DuplicateKey(String),
+    #[error("Secret {0} needs a value")]
+    MissingSecret(String),
+}
+
+/// Key a secret variable's value is kept under in the secrets store
+pub fn secret_key(project_id: Uuid, key: &str) -> String {
+    format!("project.{project_id}.env.{key}")
+}
+
+/// Names a shell accepts for variables, e.g. `DATABASE_URL`
+pub fn is_valid_key(key: &str) -> bool {
+    let mut chars = key.chars();
+    chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+/// Replace the project's environment. Secret values go to the secrets store, and the stored
+/// values of secrets that were removed or made plain are deleted.
+pub async fn save(
+    pool: &SqlitePool,
+    store: &SecretsStore,
+    project_id: Uuid,
+    vars: &[ProjectEnvVarInput],
+) -> Result<Vec<ProjectEnvVar>, ProjectEnvError> {
+    let existing = ProjectEnvVar::find_by_project_id(pool, project_id).await?;
+    let stored_secrets: HashSet<&str> = existing
+        .iter()
+        .filter(|var| var.secret)
+        .map(|var| var.key.as_str())
+        .collect();
+
+    let mut keys = HashSet::new();
+    for var in vars {
+        if !is_valid_key(&var.key) {
+            return Err(ProjectEnvError::InvalidKey(var.key.clone()));
+        }
+        if !keys.insert(var.key.as_str()) {
+            return Err(ProjectEnvError::DuplicateKey(var.key.clone()));
+        }
+        if var.secret && var.value.is_none() && !stored_secrets.contains(var.key.as_str()) {
+            return Err(ProjectEnvError::MissingSecret(var.key.clone()));
+        }
+    }
+
+    for var in vars.iter().filter(|var| var.secret) {
+        if let Some(value) = &var.value {
+            store.set(&secret_key(project_id, &var.key), value)?;
+        }
+    }
+    let saved = ProjectEnvVar::replace(pool, project_id, vars).await?;
+    for key in stored_secrets {
+        if !vars.iter().any(|var| var.secret && var.key == key) {
+            store.delete(&secret_key(project_id, key))?;
+        }
+    }
+    Ok(saved)
+}
+
+/// Delete the stored values of the project's secrets, before the project is deleted
+pub async fn delete_secrets(
+    pool: &SqlitePool,
+    store: &SecretsStore,
+    project_id: Uuid,
+) -> Result<(), ProjectEnvError> {
+    for var in ProjectEnvVar::find_by_project_id(pool, project_id).await? {
+        if var.secret {
+            store.delete(&secret_key(project_id, &var.key))?;
+        }
+    }
+    Ok(())
+}
+
+/// The project's environment, with secret values read from the secrets store. Secrets
+/// missing from the store are left out rather than failing every process.
+pub async fn load(
+    pool: &SqlitePool,
+    store: &SecretsStore,
+    project_id: Uuid,
+) -> Result<ExecutionEnv, ProjectEnvError> {
+    let mut env = ExecutionEnv::new();
+    for var in ProjectEnvVar::find_by_project_id(pool, project_id).await? {
+        let value = if var.secret {
+            store.get(&secret_key(project_id, &var.key))?
+        } else {
+            var.value
+        };
+        match value {
+            Some(value) => env.insert(var.key, value),
+            None => tracing::warn!(
+                "Secret {} of project {} is missing from the secrets store",
+                var.key,
+                project_id
+            ),
+        }
+    }
+    Ok(env)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_valid_keys() {
+        assert!(is_valid_key("DATABASE_URL"));
+        assert!(is_valid_key("_private"));
+        assert!(is_valid_key("api_key2"));
+        assert!(!is_valid_key(""));
+        assert!(!is_valid_key("2FA_CODE"));
+        assert!(!is_valid_key("MY-VAR"));
+        assert!(!is_valid_key("A=B"));
+    }
+}
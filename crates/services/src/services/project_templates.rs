@@ -0,0 +1,287 @@
+use std::path::Path;
+
+use db::models::project::TestResultFormat;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use ts_rs::TS;
+
+use super::git::{GitService, GitServiceError};
+
+#[derive(Debug, Error)]
+pub enum ProjectTemplateError {
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    #[error(transparent)]
+    Git(#[from] GitServiceError),
+    #[error("Unknown project template: {0}")]
+    UnknownTemplate(String),
+    #[error("{0} already exists and isn't empty, new projects need an empty directory")]
+    TargetNotEmpty(String),
+    #[error("Failed to clone template repository: {0}")]
+    Clone(String),
+}
+
+/// A language scaffold new projects can start from
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct ProjectTemplate {
+    pub id: String,
+    pub name: String,
+    pub description: String,
+    pub setup_script: Option<String>,
+    pub dev_script: Option<String>,
+    pub test_script: Option<String>,
+    pub test_result_format: Option<TestResultFormat>,
+}
+
+/// What a brand-new project's repository starts with
+#[derive(Debug, Clone, Deserialize, TS)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum TemplateSource {
+    /// Only an empty initial commit
+    Empty,
+    /// One of the built-in language scaffolds
+    Builtin { id: String },
+    /// Files of another repository, without its history
+    Repository { url: String },
+}
+
+#[derive(Debug, Clone, Deserialize, TS)]
+pub struct CreateFirstTask {
+    pub title: String,
+    pub description: Option<String>,
+}
+
+/// A project created from scratch in a new directory
+#[derive(Debug, Clone, Deserialize, TS)]
+pub struct CreateProjectFromTemplate {
+    pub name: String,
+    pub git_repo_path: String,
+    pub template: TemplateSource,
+    /// Commands default to the built-in template's
+    pub setup_script: Option<String>,
+    pub dev_script: Option<String>,
+    pub cleanup_script: Option<String>,
+    pub copy_files: Option<String>,
+    pub test_script: Option<String>,
+    pub test_result_format: Option<TestResultFormat>,
+    pub first_task: Option<CreateFirstTask>,
+}
+
+struct Scaffold {
+    template: fn() -> ProjectTemplate,
+    /// Paths and contents, `{name}` is replaced with the package name
+    files: &'static [(&'static str, &'static str)],
+}
+
+const SCAFFOLDS: &[Scaffold] = &[
+    Scaffold {
+        template: || ProjectTemplate {
+            id: "rust".to_string(),
+            name: "Rust".to_string(),
+            description: "Cargo binary crate".to_string(),
+            setup_script: Some("cargo build".to_string()),
+            dev_script: Some("cargo run".to_string()),
+            test_script: Some("cargo test".to_string()),
+            test_result_format: None,
+        },
+        files: &[
+            (
+                "Cargo.toml",
+                "[package]\nname = \"{name}\"\nversion = \"0.1.0\"\nedition = \"2021\"\n\n[dependencies]\n",
+            ),
+            (
+                "src/main.rs",
+                "fn main() {\n    println!(\"Hello, world!\");\n}\n",
+            ),
+            (".gitignore", "/target\n"),
+        ],
+    },
+    Scaffold {
+        template: || ProjectTemplate {
+            id: "node".to_string(),
+            name: "Node.js".to_string(),
+            description: "npm package with the built-in test runner".to_string(),
+            setup_script: Some("npm install".to_string()),
+            dev_script: Some("npm start".to_string()),
+            test_script: Some("npm test".to_string()),
+            test_result_format: None,
+        },
+        files: &[
+            (
+                "package.json",
+                "{\n  \"name\": \"{name}\",\n  \"version\": \"0.1.0\",\n  \"private\": true,\n  \"type\": \"module\",\n  \"scripts\": {\n    \"start\": \"node index.js\",\n    \"test\": \"node --test\"\n  }\n}\n",
+            ),
+            ("index.js", "console.log('Hello, world!');\n"),
+            (
+                "index.test.js",
+                "import { test } from 'node:test';\nimport assert from 'node:assert';\n\ntest('works', () => {\n  assert.strictEqual(1 + 1, 2);\n});\n",
+            ),
+            (".gitignore", "node_modules/\n"),
+        ],
+    },
+    Scaffold {
+        template: || ProjectTemplate {
+            id: "python".to_string(),
+            name: "Python".to_string(),
+            description: "Package tested with pytest, in a virtualenv".to_string(),
+            setup_script: Some(
+                "python3 -m venv .venv && .venv/bin/pip install -e . pytest".to_string(),
+            ),
+            dev_script: Some(".venv/bin/python -m {name}".to_string()),
+            test_script: Some(".venv/bin/pytest --junitxml=/dev/stdout -q".to_string()),
+            test_result_format: Some(TestResultFormat::Junit),
+        },
+        files: &[
+            (
+                "pyproject.toml",
+                "[project]\nname = \"{name}\"\nversion = \"0.1.0\"\nrequires-python = \">=3.9\"\n",
+            ),
+            ("{name}/__init__.py", ""),
+            ("{name}/__main__.py", "print(\"Hello, world!\")\n"),
+            (
+                "tests/test_{name}.py",
+                "def test_works():\n    assert 1 + 1 == 2\n",
+            ),
+            (".gitignore", ".venv/\n__pycache__/\n*.egg-info/\n"),
+        ],
+    },
+    Scaffold {
+        template: || ProjectTemplate {
+            id: "go".to_string(),
+            name: "Go".to_string(),
+            description: "Go module with a main package".to_string(),
+            setup_script: Some("go mod download".to_string()),
+            dev_script: Some("go run .".to_string()),
+            test_script: Some("go test ./...".to_string()),
+            test_result_format: None,
+        },
+        files: &[
+            ("go.mod", "module {name}\n\ngo 1.21\n"),
+            (
+                "main.go",
+                "package main\n\nimport \"fmt\"\n\nfunc main() {\n\tfmt.Println(\"Hello, world!\")\n}\n",
+            ),
+        ],
+    },
+];
+
+/// The built-in language scaffolds
+pub fn builtin_templates() -> Vec<ProjectTemplate> {
+    SCAFFOLDS
+        .iter()
+        .map(|scaffold| (scaffold.template)())
+        .collect()
+}
+
+/// Package name for a project name, lowercase with underscores
+fn package_name(project_name: &str) -> String {
+    let name: String = project_name
+        .trim()
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() {
+                c.to_ascii_lowercase()
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    let name = name.trim_matches('_');
+    match name.chars().next() {
+        None => "app".to_string(),
+        Some(c) if c.is_ascii_digit() => format!("app_{name}"),
+        Some(_) => name.to_string(),
+    }
+}
+
+/// Create a git repository for a new project at `path` from the template, returning the
+/// built-in template it was scaffolded from
+pub fn create_repository(
+    git: &GitService,
+    path: &Path,
+    project_name: &str,
+    source: &TemplateSource,
+) -> Result<Option<ProjectTemplate>, ProjectTemplateError> {
+    if path.exists() && std::fs::read_dir(path)?.next().is_some() {
+        return Err(ProjectTemplateError::TargetNotEmpty(
+            path.display().to_string(),
+        ));
+    }
+
+    match source {
+        TemplateSource::Empty => {
+            git.initialize_repo_with_main_branch(path)?;
+            Ok(None)
+        }
+        TemplateSource::Builtin { id } => {
+            let scaffold = SCAFFOLDS
+                .iter()
+                .find(|scaffold| (scaffold.template)().id == *id)
+                .ok_or_else(|| ProjectTemplateError::UnknownTemplate(id.clone()))?;
+            let template = (scaffold.template)();
+            let name = package_name(project_name);
+            git.initialize_repo_with_main_branch(path)?;
+            for (file, contents) in scaffold.files {
+                let file_path = path.join(file.replace("{name}", &name));
+                if let Some(parent) = file_path.parent() {
+                    std::fs::create_dir_all(parent)?;
+                }
+                std::fs::write(file_path, contents.replace("{name}", &name))?;
+            }
+            git.commit(path, &format!("Scaffold {} project", template.name))?;
+            Ok(Some(ProjectTemplate {
+                dev_script: template
+                    .dev_script
+                    .map(|script| script.replace("{name}", &name)),
+                ..template
+            }))
+        }
+        TemplateSource::Repository { url } => {
+            GitService::clone_repository(url, path, None)
+                .map_err(|e| ProjectTemplateError::Clone(e.to_string()))?;
+            // Start a fresh history, the template's belongs to the template
+            std::fs::remove_dir_all(path.join(".git"))?;
+            git.initialize_repo_with_main_branch(path)?;
+            git.commit(path, &format!("Import template {url}"))?;
+            Ok(None)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tempfile::TempDir;
+
+    use super::*;
+
+    #[test]
+    fn test_builtin_template_scaffolds_a_committed_repository() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("My App");
+        let git = GitService::new();
+
+        let template = create_repository(
+            &git,
+            &path,
+            "My App",
+            &TemplateSource::Builtin {
+                id: "python".to_string(),
+            },
+        )
+        .unwrap()
+        .unwrap();
+        assert_eq!(
+            template.dev_script.as_deref(),
+            Some(".venv/bin/python -m my_app")
+        );
+        assert!(path.join("my_app/__main__.py").exists());
+        let repo = git2::Repository::open(&path).unwrap();
+        assert!(repo.statuses(None).unwrap().is_empty());
+
+        assert!(matches!(
+            create_repository(&git, &path, "My App", &TemplateSource::Empty),
+            Err(ProjectTemplateError::TargetNotEmpty(_))
+        ));
+        assert_eq!(package_name("42 Things!"), "app_42_things");
+    }
+}
@@ -0,0 +1,211 @@
+use std::time::Duration;
+
+use db::models::provider_key::{KeyProvider, ProviderKey};
+use executors::{env::ExecutionEnv, executors::CodingAgent};
+use reqwest::{RequestBuilder, StatusCode};
+use sqlx::SqlitePool;
+use thiserror::Error;
+
+use super::secrets::{SecretsError, SecretsStore};
+
+const VALIDATION_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Debug, Error)]
+pub enum ProviderKeyError {
+    #[error(transparent)]
+    Database(#[from] sqlx::Error),
+    #[error(transparent)]
+    Secrets(#[from] SecretsError),
+    #[error(transparent)]
+    Http(#[from] reqwest::Error),
+    #[error("API key is empty")]
+    Empty,
+}
+
+fn slug(provider: KeyProvider) -> &'static str {
+    match provider {
+        KeyProvider::Anthropic => "anthropic",
+        KeyProvider::OpenAi => "openai",
+        KeyProvider::Google => "google",
+        KeyProvider::Sourcegraph => "sourcegraph",
+    }
+}
+
+/// Key a provider's API key is kept under in the secrets store
+pub fn secret_key(provider: KeyProvider) -> String {
+    format!("provider.{}.api_key", slug(provider))
+}
+
+/// The end of a key, enough to tell two keys apart without revealing them
+pub fn key_hint(key: &str) -> String {
+    let chars: Vec<char> = key.chars().collect();
+    if chars.len() < 12 {
+        return "…".to_string();
+    }
+    format!("…{}", chars[chars.len() - 4..].iter().collect::<String>())
+}
+
+/// Variables the CLIs read a provider's key from
+pub fn env_vars(provider: KeyProvider) -> &'static [&'static str] {
+    match provider {
+        KeyProvider::Anthropic => &["ANTHROPIC_API_KEY"],
+        KeyProvider::OpenAi => &["OPENAI_API_KEY"],
+        KeyProvider::Google => &["GEMINI_API_KEY", "GOOGLE_GENERATIVE_AI_API_KEY"],
+        KeyProvider::Sourcegraph => &["AMP_API_KEY", "SRC_ACCESS_TOKEN"],
+    }
+}
+
+/// Providers whose keys an agent can use. Cursor only signs in with its own account.
+pub fn providers_for(agent: &CodingAgent) -> &'static [KeyProvider] {
+    match agent {
+        CodingAgent::ClaudeCode => &[KeyProvider::Anthropic],
+        CodingAgent::Codex => &[KeyProvider::OpenAi],
+        CodingAgent::Gemini => &[KeyProvider::Google],
+        CodingAgent::Amp => &[KeyProvider::Sourcegraph],
+        CodingAgent::Opencode => &[
+            KeyProvider::Anthropic,
+            KeyProvider::OpenAi,
+            KeyProvider::Google,
+        ],
+        CodingAgent::Cursor => &[],
+    }
+}
+
+enum Validation {
+    Valid,
+    Invalid(String),
+    /// The provider couldn't tell, e.g. it was down or rate limited the call
+    Unknown(String),
+}
+
+/// The cheapest authenticated call of each provider, listing models or the current user
+fn validation_request(
+    client: &reqwest::Client,
+    provider: KeyProvider,
+    key: &str,
+) -> RequestBuilder {
+    match provider {
+        KeyProvider::Anthropic => client
+            .get("https://api.anthropic.com/v1/models")
+            .header("x-api-key", key)
+            .header("anthropic-version", "2023-06-01"),
+        KeyProvider::OpenAi => client
+            .get("https://api.openai.com/v1/models")
+            .bearer_auth(key),
+        KeyProvider::Google => client
+            .get("https://generativelanguage.googleapis.com/v1beta/models")
+            .header("x-goog-api-key", key),
+        KeyProvider::Sourcegraph => client
+            .post("https://sourcegraph.com/.api/graphql")
+            .header("Authorization", format!("token {key}"))
+            .json(&serde_json::json!({ "query": "query { currentUser { username } }" })),
+    }
+}
+
+async fn check(provider: KeyProvider, key: &str) -> Result<Validation, ProviderKeyError> {
+    let client = reqwest::Client::builder()
+        .timeout(VALIDATION_TIMEOUT)
+        .build()?;
+    let response = match validation_request(&client, provider, key).send().await {
+        Ok(response) => response,
+        Err(e) => return Ok(Validation::Unknown(format!("Couldn't reach provider: {e}"))),
+    };
+    let status = response.status();
+    Ok(match status {
+        // Google answers a bad key with 400 rather than 401
+        StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN | StatusCode::BAD_REQUEST => {
+            Validation::Invalid(format!("Rejected by the provider ({status})"))
+        }
+        status if status.is_success() => Validation::Valid,
+        status => Validation::Unknown(format!("Provider answered {status}")),
+    })
+}
+
+/// Store a provider's key and check it with the provider
+pub async fn set(
+    pool: &SqlitePool,
+    store: &SecretsStore,
+    provider: KeyProvider,
+    key: &str,
+) -> Result<ProviderKey, ProviderKeyError> {
+    let key = key.trim();
+    if key.is_empty() {
+        return Err(ProviderKeyError::Empty);
+    }
+    store.set(&secret_key(provider), key)?;
+    let saved = ProviderKey::upsert(pool, provider, &key_hint(key)).await?;
+    Ok(validate(pool, store, provider).await?.unwrap_or(saved))
+}
+
+/// Check a stored key with the provider again, `None` when no key is stored
+pub async fn validate(
+    pool: &SqlitePool,
+    store: &SecretsStore,
+    provider: KeyProvider,
+) -> Result<Option<ProviderKey>, ProviderKeyError> {
+    if ProviderKey::find(pool, provider).await?.is_none() {
+        return Ok(None);
+    }
+    let (valid, error) = match store.get(&secret_key(provider))? {
+        Some(key) => match check(provider, &key).await? {
+            Validation::Valid => (Some(true), None),
+            Validation::Invalid(error) => (Some(false), Some(error)),
+            Validation::Unknown(error) => (None, Some(error)),
+        },
+        None => (
+            Some(false),
+            Some("Key is missing from the secrets store".to_string()),
+        ),
+    };
+    Ok(ProviderKey::record_validation(pool, provider, valid, error.as_deref()).await?)
+}
+
+pub async fn delete(
+    pool: &SqlitePool,
+    store: &SecretsStore,
+    provider: KeyProvider,
+) -> Result<(), ProviderKeyError> {
+    store.delete(&secret_key(provider))?;
+    ProviderKey::delete(pool, provider).await?;
+    Ok(())
+}
+
+/// Variables giving an agent the stored keys of the providers it uses. Keys the provider
+/// rejected are still passed, the agent's own error is clearer than a missing key.
+pub async fn agent_env(
+    pool: &SqlitePool,
+    store: &SecretsStore,
+    agent: &CodingAgent,
+) -> Result<ExecutionEnv, ProviderKeyError> {
+    let mut env = ExecutionEnv::new();
+    let stored = ProviderKey::find_all(pool).await?;
+    for provider in providers_for(agent) {
+        if !stored.iter().any(|key| key.provider == *provider) {
+            continue;
+        }
+        match store.get(&secret_key(*provider))? {
+            Some(key) => {
+                for var in env_vars(*provider) {
+                    env.insert(var.to_string(), key.clone());
+                }
+            }
+            None => tracing::warn!(
+                "API key of {} is missing from the secrets store",
+                slug(*provider)
+            ),
+        }
+    }
+    Ok(env)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_key_hint_reveals_only_the_end() {
+        assert_eq!(key_hint("sk-ant-api03-abcdefghWXYZ"), "…WXYZ");
+        assert_eq!(key_hint("short"), "…");
+        assert_eq!(secret_key(KeyProvider::OpenAi), "provider.openai.api_key");
+    }
+}
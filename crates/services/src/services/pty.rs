@@ -0,0 +1,341 @@
+use std::{
+    collections::HashMap,
+    io::{Read, Write},
+    path::Path,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+#[cfg(unix)]
+use nix::{
+    sys::signal::{Signal, killpg},
+    unistd::Pid,
+};
+pub use portable_pty::PtySize;
+use portable_pty::{ChildKiller, CommandBuilder, MasterPty, native_pty_system};
+use thiserror::Error;
+use tokio::sync::mpsc;
+use uuid::Uuid;
+
+use crate::services::config::{TerminalConfig, TerminalMode};
+
+/// How long a hung up process group gets to exit before it is killed
+const HANGUP_GRACE: Duration = Duration::from_secs(2);
+
+#[derive(Debug, Error)]
+pub enum PtyError {
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    #[error("Failed to open terminal: {0}")]
+    Pty(String),
+    #[error("The terminal is disabled")]
+    Disabled,
+    #[error("Command not allowed: {0}")]
+    NotAllowed(String),
+    #[error("Terminal requested from another site: {0}")]
+    CrossOrigin(String),
+}
+
+impl From<anyhow::Error> for PtyError {
+    fn from(e: anyhow::Error) -> Self {
+        Self::Pty(e.to_string())
+    }
+}
+
+/// What a terminal session runs
+#[derive(Debug, Clone, PartialEq)]
+pub enum PtyCommand {
+    /// The user's interactive shell
+    Shell,
+    /// A program and its arguments
+    Program(Vec<String>),
+}
+
+impl PtyCommand {
+    /// Check a requested command against the terminal config. Without a command line the
+    /// session is an interactive shell, which only `FullShell` allows.
+    pub fn from_config(
+        config: &TerminalConfig,
+        command_line: Option<&str>,
+    ) -> Result<Self, PtyError> {
+        let command_line = command_line.map(str::trim).filter(|c| !c.is_empty());
+        match (&config.mode, command_line) {
+            (TerminalMode::Disabled, _) => Err(PtyError::Disabled),
+            (TerminalMode::FullShell, None) => Ok(Self::Shell),
+            (TerminalMode::FullShell, Some(line)) => {
+                let (shell, flag) = utils::shell::get_shell_command();
+                Ok(Self::Program(vec![
+                    shell.to_string(),
+                    flag.to_string(),
+                    line.to_string(),
+                ]))
+            }
+            (TerminalMode::AllowedCommands, None) => Err(PtyError::NotAllowed(
+                "an interactive shell needs the full shell mode".to_string(),
+            )),
+            (TerminalMode::AllowedCommands, Some(line)) => {
+                // Run without a shell, so `;`, `&&` and redirects can't extend an allowed command
+                let words: Vec<String> = line.split_whitespace().map(String::from).collect();
+                let allowed = config.allowed_commands.iter().any(|allowed| {
+                    let prefix: Vec<&str> = allowed.split_whitespace().collect();
+                    !prefix.is_empty()
+                        && words.len() >= prefix.len()
+                        && words.iter().zip(&prefix).all(|(word, p)| word == p)
+                });
+                if allowed {
+                    Ok(Self::Program(words))
+                } else {
+                    Err(PtyError::NotAllowed(line.to_string()))
+                }
+            }
+        }
+    }
+}
+
+/// Output of a running session
+#[derive(Debug)]
+pub enum PtyEvent {
+    Output(Vec<u8>),
+    /// The process exited and its output is drained, `None` if the exit code is unknown
+    Exit(Option<u32>),
+}
+
+/// Process group of a session, with what's needed to stop it
+struct SessionProcess {
+    killer: Box<dyn ChildKiller + Send + Sync>,
+    pgid: Option<i32>,
+}
+
+impl SessionProcess {
+    /// Hang up the whole process group so background jobs started from the shell go too,
+    /// then kill whatever ignores the hangup
+    fn terminate(mut self) {
+        #[cfg(unix)]
+        if let Some(pgid) = self.pgid {
+            let pgid = Pid::from_raw(pgid);
+            let _ = killpg(pgid, Signal::SIGHUP);
+            let escalate = move || {
+                if killpg(pgid, None).is_ok() {
+                    tracing::debug!("Killing terminal process group {} after hangup", pgid);
+                    let _ = killpg(pgid, Signal::SIGKILL);
+                }
+            };
+            match tokio::runtime::Handle::try_current() {
+                Ok(handle) => {
+                    handle.spawn(async move {
+                        tokio::time::sleep(HANGUP_GRACE).await;
+                        escalate();
+                    });
+                }
+                Err(_) => {
+                    std::thread::sleep(HANGUP_GRACE);
+                    escalate();
+                }
+            }
+            return;
+        }
+        let _ = self.killer.kill();
+    }
+}
+
+/// Starts terminal sessions in attempt worktrees and keeps track of their processes, so
+/// none outlive the connection that opened them or the server.
+#[derive(Clone, Default)]
+pub struct PtyService {
+    sessions: Arc<Mutex<HashMap<Uuid, SessionProcess>>>,
+}
+
+impl PtyService {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn spawn(
+        &self,
+        cwd: &Path,
+        command: PtyCommand,
+        size: PtySize,
+    ) -> Result<PtySession, PtyError> {
+        let pair = native_pty_system().openpty(size)?;
+        let mut builder = match command {
+            PtyCommand::Shell => CommandBuilder::new_default_prog(),
+            PtyCommand::Program(argv) => {
+                CommandBuilder::from_argv(argv.into_iter().map(Into::into).collect())
+            }
+        };
+        builder.cwd(cwd);
+        builder.env("TERM", "xterm-256color");
+
+        let mut child = pair.slave.spawn_command(builder)?;
+        // Only the child may hold the slave side, otherwise reads never see the end of output
+        drop(pair.slave);
+
+        let id = Uuid::new_v4();
+        // The child is a session leader, so its pid is also its process group id
+        let pgid = child.process_id().map(|pid| pid as i32);
+        self.sessions.lock().unwrap().insert(
+            id,
+            SessionProcess {
+                killer: child.clone_killer(),
+                pgid,
+            },
+        );
+
+        let mut reader = pair.master.try_clone_reader()?;
+        let writer = pair.master.take_writer()?;
+        let (events, rx) = mpsc::channel(64);
+
+        let waiter = std::thread::spawn(move || child.wait().ok().map(|s| s.exit_code()));
+        std::thread::spawn(move || {
+            let mut buf = [0u8; 4096];
+            loop {
+                match reader.read(&mut buf) {
+                    // The pty reports EIO once the last process holding it exits
+                    Ok(0) | Err(_) => break,
+                    Ok(n) => {
+                        if events
+                            .blocking_send(PtyEvent::Output(buf[..n].to_vec()))
+                            .is_err()
+                        {
+                            break;
+                        }
+                    }
+                }
+            }
+            let code = waiter.join().ok().flatten();
+            let _ = events.blocking_send(PtyEvent::Exit(code));
+        });
+
+        tracing::debug!("Started terminal session {} in {}", id, cwd.display());
+        Ok(PtySession {
+            id,
+            master: pair.master,
+            writer,
+            events: rx,
+            sessions: self.sessions.clone(),
+        })
+    }
+
+    /// Stop every running session, used when the server shuts down
+    pub fn terminate_all(&self) {
+        let sessions: Vec<_> = self.sessions.lock().unwrap().drain().collect();
+        for (id, process) in sessions {
+            tracing::info!("Stopping terminal session {} on shutdown", id);
+            process.terminate();
+        }
+    }
+}
+
+/// A process running in a pseudo terminal. Dropping the session stops its process group.
+pub struct PtySession {
+    pub id: Uuid,
+    master: Box<dyn MasterPty + Send>,
+    writer: Box<dyn Write + Send>,
+    events: mpsc::Receiver<PtyEvent>,
+    sessions: Arc<Mutex<HashMap<Uuid, SessionProcess>>>,
+}
+
+impl PtySession {
+    /// Next output chunk or the exit, `None` once the exit has been delivered
+    pub async fn next_event(&mut self) -> Option<PtyEvent> {
+        self.events.recv().await
+    }
+
+    /// Send keystrokes to the process
+    pub fn write(&mut self, data: &[u8]) -> Result<(), PtyError> {
+        self.writer.write_all(data)?;
+        self.writer.flush()?;
+        Ok(())
+    }
+
+    pub fn resize(&self, cols: u16, rows: u16) -> Result<(), PtyError> {
+        self.master.resize(PtySize {
+            rows,
+            cols,
+            pixel_width: 0,
+            pixel_height: 0,
+        })?;
+        Ok(())
+    }
+}
+
+impl Drop for PtySession {
+    fn drop(&mut self) {
+        if let Some(process) = self.sessions.lock().unwrap().remove(&self.id) {
+            tracing::debug!("Stopping terminal session {}", self.id);
+            process.terminate();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_command_checked_against_config() {
+        let config = TerminalConfig::default();
+        assert_eq!(
+            PtyCommand::from_config(&config, Some("npm test -- --watch")).unwrap(),
+            PtyCommand::Program(vec![
+                "npm".to_string(),
+                "test".to_string(),
+                "--".to_string(),
+                "--watch".to_string()
+            ])
+        );
+        for line in [Some("npm publish"), Some("npmtest"), Some("  "), None] {
+            assert!(matches!(
+                PtyCommand::from_config(&config, line),
+                Err(PtyError::NotAllowed(_))
+            ));
+        }
+
+        let full_shell = TerminalConfig {
+            mode: TerminalMode::FullShell,
+            ..config.clone()
+        };
+        assert_eq!(
+            PtyCommand::from_config(&full_shell, None).unwrap(),
+            PtyCommand::Shell
+        );
+
+        let disabled = TerminalConfig {
+            mode: TerminalMode::Disabled,
+            ..config
+        };
+        assert!(matches!(
+            PtyCommand::from_config(&disabled, Some("npm test")),
+            Err(PtyError::Disabled)
+        ));
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_session_output_and_exit() {
+        let dir = tempfile::tempdir().unwrap();
+        let service = PtyService::new();
+        let mut session = service
+            .spawn(
+                dir.path(),
+                PtyCommand::Program(vec!["pwd".to_string()]),
+                PtySize::default(),
+            )
+            .unwrap();
+
+        let mut output = Vec::new();
+        let code = loop {
+            match session.next_event().await {
+                Some(PtyEvent::Output(chunk)) => output.extend(chunk),
+                Some(PtyEvent::Exit(code)) => break code,
+                None => panic!("session ended without an exit"),
+            }
+        };
+        assert_eq!(code, Some(0));
+        let expected = dir.path().canonicalize().unwrap();
+        assert!(String::from_utf8_lossy(&output).contains(&*expected.to_string_lossy()));
+
+        drop(session);
+        assert!(service.sessions.lock().unwrap().is_empty());
+    }
+}
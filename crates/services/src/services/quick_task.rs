@@ -0,0 +1,214 @@
+use db::models::{
+    project::Project,
+    task::{CreateTask, TaskPriority},
+};
+use executors::profile::{ProfileConfigs, ProfileVariantLabel};
+use serde::Deserialize;
+use thiserror::Error;
+use ts_rs::TS;
+use uuid::Uuid;
+
+#[derive(Debug, Error)]
+pub enum QuickTaskError {
+    #[error("The task needs a title besides its tokens")]
+    EmptyTitle,
+    #[error("Name the project with #project, there is no current one")]
+    NoProject,
+    #[error("Only one @profile can be given")]
+    MultipleProfiles,
+    #[error("Unknown profile: {0}")]
+    UnknownProfile(String),
+    #[error("Profile {profile} has no variant {variant}")]
+    UnknownVariant { profile: String, variant: String },
+}
+
+/// One line typed in the command palette, e.g. `fix flaky auth test !p1 #backend @codex`
+#[derive(Debug, Clone, Deserialize, TS)]
+pub struct QuickCreateTask {
+    pub text: String,
+    /// Project the task goes to unless the line names one, usually the board being looked at
+    #[serde(default)]
+    pub project_id: Option<Uuid>,
+}
+
+/// The tokens of a quick-create line, before they are matched against projects and profiles:
+/// - `!p0` to `!p3`, or `!urgent`, `!high`, `!medium` and `!low`, set the priority
+/// - `#name` picks the project called that, or adds a label when no project is
+/// - `@profile` or `@profile/variant` starts an attempt with that profile straight away
+///
+/// Tags have to start with a letter, so `#123` stays in the title. Everything else is the
+/// title, in the order it was typed.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct QuickTask {
+    pub title: String,
+    pub priority: Option<TaskPriority>,
+    pub tags: Vec<String>,
+    pub profile: Option<(String, Option<String>)>,
+}
+
+fn parse_priority(token: &str) -> Option<TaskPriority> {
+    match token.to_lowercase().as_str() {
+        "p0" | "urgent" => Some(TaskPriority::Urgent),
+        "p1" | "high" => Some(TaskPriority::High),
+        "p2" | "medium" => Some(TaskPriority::Medium),
+        "p3" | "low" => Some(TaskPriority::Low),
+        _ => None,
+    }
+}
+
+fn starts_with_letter(token: &str) -> bool {
+    token.chars().next().is_some_and(char::is_alphabetic)
+}
+
+/// Lowercase letters and digits only, so `#web-app` finds a project called "Web App"
+fn normalize(name: &str) -> String {
+    name.chars()
+        .filter(|c| c.is_alphanumeric())
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+impl QuickTask {
+    pub fn parse(text: &str) -> Result<Self, QuickTaskError> {
+        let mut task = QuickTask::default();
+        let mut words = Vec::new();
+        for word in text.split_whitespace() {
+            if let Some(priority) = word.strip_prefix('!').and_then(parse_priority) {
+                task.priority = Some(priority);
+            } else if let Some(tag) = word.strip_prefix('#').filter(|t| starts_with_letter(t)) {
+                task.tags.push(tag.to_string());
+            } else if let Some(profile) = word.strip_prefix('@').filter(|p| starts_with_letter(p)) {
+                if task.profile.is_some() {
+                    return Err(QuickTaskError::MultipleProfiles);
+                }
+                task.profile = Some(match profile.split_once('/') {
+                    Some((profile, variant)) => (profile.to_string(), Some(variant.to_string())),
+                    None => (profile.to_string(), None),
+                });
+            } else {
+                words.push(word);
+            }
+        }
+        if words.is_empty() {
+            return Err(QuickTaskError::EmptyTitle);
+        }
+        task.title = words.join(" ");
+        Ok(task)
+    }
+
+    /// The task to create, the first tag naming one of `projects` choosing the project and
+    /// the other tags becoming labels
+    pub fn into_create_task(
+        self,
+        projects: &[Project],
+        default_project_id: Option<Uuid>,
+    ) -> Result<CreateTask, QuickTaskError> {
+        let mut project_id = None;
+        let mut labels: Vec<String> = Vec::new();
+        for tag in self.tags {
+            let project = projects
+                .iter()
+                .find(|project| normalize(&project.name) == normalize(&tag));
+            match project {
+                Some(project) if project_id.is_none() => project_id = Some(project.id),
+                _ if labels.iter().any(|label| label.eq_ignore_ascii_case(&tag)) => {}
+                _ => labels.push(tag),
+            }
+        }
+        Ok(CreateTask {
+            project_id: project_id
+                .or(default_project_id)
+                .ok_or(QuickTaskError::NoProject)?,
+            title: self.title,
+            description: None,
+            parent_task_attempt: None,
+            priority: self.priority,
+            labels,
+            base_branch: None,
+        })
+    }
+
+    /// The profile of the `@profile` token, matched case-insensitively, `None` without one
+    pub fn profile_label(
+        &self,
+        profiles: &ProfileConfigs,
+    ) -> Result<Option<ProfileVariantLabel>, QuickTaskError> {
+        let Some((name, variant)) = &self.profile else {
+            return Ok(None);
+        };
+        let profile = profiles
+            .profiles
+            .iter()
+            .find(|profile| profile.default.label.eq_ignore_ascii_case(name))
+            .ok_or_else(|| QuickTaskError::UnknownProfile(name.clone()))?;
+        let label = profile.default.label.clone();
+        let Some(variant) = variant else {
+            return Ok(Some(ProfileVariantLabel::default(label)));
+        };
+        let found = profile
+            .variants
+            .iter()
+            .find(|config| config.label.eq_ignore_ascii_case(variant))
+            .ok_or_else(|| QuickTaskError::UnknownVariant {
+                profile: label.clone(),
+                variant: variant.clone(),
+            })?;
+        Ok(Some(ProfileVariantLabel::with_variant(
+            label,
+            found.label.clone(),
+        )))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_tokens() {
+        let task = QuickTask::parse("fix flaky auth test !p1 #backend @codex").unwrap();
+        assert_eq!(task.title, "fix flaky auth test");
+        assert_eq!(task.priority, Some(TaskPriority::High));
+        assert_eq!(task.tags, vec!["backend".to_string()]);
+        assert_eq!(task.profile, Some(("codex".to_string(), None)));
+
+        // Not tokens: issue numbers, unknown priorities, e-mail addresses
+        let task = QuickTask::parse("close #123 !soon for me@example.com @amp/plan").unwrap();
+        assert_eq!(task.title, "close #123 !soon for me@example.com");
+        assert_eq!(task.priority, None);
+        assert!(task.tags.is_empty());
+        assert_eq!(
+            task.profile,
+            Some(("amp".to_string(), Some("plan".to_string())))
+        );
+
+        assert!(matches!(
+            QuickTask::parse("!p0 #backend"),
+            Err(QuickTaskError::EmptyTitle)
+        ));
+        assert!(matches!(
+            QuickTask::parse("review @codex @amp"),
+            Err(QuickTaskError::MultipleProfiles)
+        ));
+    }
+
+    #[test]
+    fn test_tags_pick_project_then_labels() {
+        let default_project = Uuid::new_v4();
+        let task = QuickTask::parse("add rate limits #Web-App #infra #INFRA").unwrap();
+        let create = task.into_create_task(&[], Some(default_project)).unwrap();
+        assert_eq!(create.project_id, default_project);
+        assert_eq!(
+            create.labels,
+            vec!["Web-App".to_string(), "infra".to_string()]
+        );
+
+        let task = QuickTask::parse("add rate limits").unwrap();
+        assert!(matches!(
+            task.into_create_task(&[], None),
+            Err(QuickTaskError::NoProject)
+        ));
+
+        assert_eq!(normalize("Web App"), normalize("web-app"));
+    }
+}
@@ -0,0 +1,235 @@
+use std::{
+    collections::HashMap,
+    path::PathBuf,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use db::models::project::Project;
+use futures::{Stream, stream};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use tokio::sync::watch;
+use ts_rs::TS;
+use uuid::Uuid;
+
+use super::{
+    git::{CloneProgress, GitService, GitServiceError},
+    package_manager::PackageManager,
+};
+
+/// How long a finished clone's outcome stays available to late subscribers
+const FINISHED_RETENTION: Duration = Duration::from_secs(60);
+
+#[derive(Debug, Error)]
+pub enum RepoCloneError {
+    #[error(transparent)]
+    Git(#[from] GitServiceError),
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    #[error("Not a git repository URL: {0}")]
+    InvalidUrl(String),
+    #[error("{0} already exists and isn't empty")]
+    TargetExists(String),
+    #[error("No clone with id {0}")]
+    UnknownClone(Uuid),
+    #[error("Clone was interrupted: {0}")]
+    Interrupted(String),
+}
+
+/// A project added by cloning a remote repository
+#[derive(Debug, Clone, Deserialize, TS)]
+pub struct CloneProject {
+    pub url: String,
+    /// Directory the repository is cloned into, see [`default_clone_path`]
+    pub git_repo_path: Option<String>,
+    /// Defaults to the repository name
+    pub name: Option<String>,
+    /// Scripts left out are filled in with confident suggestions of script detection
+    pub setup_script: Option<String>,
+    pub dev_script: Option<String>,
+    pub test_script: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct CloneJob {
+    pub id: Uuid,
+    pub git_repo_path: String,
+}
+
+/// What the repository turned out to contain once cloned
+#[derive(Debug, Clone)]
+pub struct ClonedRepository {
+    pub default_branch: String,
+    pub package_manager: Option<PackageManager>,
+}
+
+/// Where a clone stands, streamed to the client that started it
+#[derive(Debug, Clone, Serialize, TS)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum CloneEvent {
+    Started,
+    Progress {
+        progress: CloneProgress,
+    },
+    Done {
+        project: Project,
+        default_branch: String,
+        package_manager: Option<PackageManager>,
+    },
+    Failed {
+        error: String,
+    },
+}
+
+impl CloneEvent {
+    fn is_final(&self) -> bool {
+        matches!(self, CloneEvent::Done { .. } | CloneEvent::Failed { .. })
+    }
+}
+
+/// Clones in progress, and recently finished ones, by id
+#[derive(Clone, Default)]
+pub struct CloneJobs {
+    jobs: Arc<Mutex<HashMap<Uuid, watch::Receiver<CloneEvent>>>>,
+}
+
+impl CloneJobs {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register a clone, its events are sent through the returned sender
+    pub fn start(&self) -> (Uuid, watch::Sender<CloneEvent>) {
+        let id = Uuid::new_v4();
+        let (tx, rx) = watch::channel(CloneEvent::Started);
+        self.jobs.lock().unwrap().insert(id, rx);
+        (id, tx)
+    }
+
+    /// Forget a finished clone once late subscribers had time to see its outcome
+    pub fn finish(&self, id: Uuid) {
+        let jobs = self.jobs.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(FINISHED_RETENTION).await;
+            jobs.lock().unwrap().remove(&id);
+        });
+    }
+
+    /// The clone's latest event and those that follow, ending with its outcome
+    pub fn events(&self, id: Uuid) -> Option<impl Stream<Item = CloneEvent> + Send + 'static> {
+        let rx = self.jobs.lock().unwrap().get(&id)?.clone();
+        Some(stream::unfold(
+            (rx, false, true),
+            |(mut rx, done, first)| async move {
+                if done || (!first && rx.changed().await.is_err()) {
+                    return None;
+                }
+                let event = rx.borrow_and_update().clone();
+                let done = event.is_final();
+                Some((event, (rx, done, false)))
+            },
+        ))
+    }
+}
+
+/// Where a repository is cloned when the request doesn't say: the configured workspace
+/// directory, or `~/vibe-kanban-projects` without one
+pub fn default_clone_path(repo_name: &str, workspace_dir: Option<&str>) -> PathBuf {
+    match workspace_dir.map(str::trim).filter(|dir| !dir.is_empty()) {
+        Some(dir) => utils::path::expand_tilde(dir),
+        None => dirs::home_dir()
+            .unwrap_or_else(std::env::temp_dir)
+            .join("vibe-kanban-projects"),
+    }
+    .join(repo_name)
+}
+
+/// Repository name a clone URL points at, e.g. `repo` for `git@github.com:owner/repo.git`
+pub fn repo_name_from_url(url: &str) -> Result<String, RepoCloneError> {
+    let url = url.trim();
+    let without_scheme = ["https://", "http://", "ssh://", "git://", "file://"]
+        .iter()
+        .find_map(|scheme| url.strip_prefix(scheme))
+        // scp-like syntax, user@host:path
+        .or_else(|| url.contains('@').then_some(url));
+    let name = without_scheme
+        .and_then(|rest| rest.trim_end_matches('/').split_once(['/', ':']))
+        .and_then(|(_host, path)| path.rsplit('/').next())
+        .map(|name| name.trim_end_matches(".git"))
+        .filter(|name| !name.is_empty() && *name != "." && *name != "..");
+    name.map(str::to_string)
+        .ok_or_else(|| RepoCloneError::InvalidUrl(url.to_string()))
+}
+
+/// Clone `url` into `path`, sending progress as it goes, and inspect the result
+pub async fn clone_repository(
+    url: String,
+    path: PathBuf,
+    token: Option<String>,
+    events: watch::Sender<CloneEvent>,
+) -> Result<ClonedRepository, RepoCloneError> {
+    if path.exists() && std::fs::read_dir(&path)?.next().is_some() {
+        return Err(RepoCloneError::TargetExists(path.display().to_string()));
+    }
+
+    let result = tokio::task::spawn_blocking(move || {
+        let repo =
+            GitService::clone_repository_with_progress(&url, &path, token.as_deref(), |progress| {
+                events.send_replace(CloneEvent::Progress { progress });
+            });
+        let repo = match repo {
+            Ok(repo) => repo,
+            Err(e) => {
+                // Leave no half-cloned directory behind, so the clone can be retried
+                let _ = std::fs::remove_dir_all(&path);
+                return Err(e.into());
+            }
+        };
+        let default_branch = repo
+            .head()
+            .ok()
+            .and_then(|head| head.shorthand().map(str::to_string))
+            .unwrap_or_else(|| "main".to_string());
+        Ok(ClonedRepository {
+            default_branch,
+            package_manager: PackageManager::detect(&path),
+        })
+    })
+    .await;
+    result.map_err(|e| RepoCloneError::Interrupted(e.to_string()))?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_repo_name_from_clone_urls() {
+        assert_eq!(
+            repo_name_from_url("https://github.com/owner/repo.git").unwrap(),
+            "repo"
+        );
+        assert_eq!(
+            repo_name_from_url("git@github.com:owner/repo.git").unwrap(),
+            "repo"
+        );
+        assert_eq!(
+            repo_name_from_url("ssh://git@host/group/sub/project/").unwrap(),
+            "project"
+        );
+        assert!(repo_name_from_url("not a url").is_err());
+        assert!(repo_name_from_url("https://github.com/").is_err());
+    }
+
+    #[test]
+    fn test_default_clone_path_uses_workspace_dir() {
+        assert_eq!(
+            default_clone_path("repo", Some("/work/code ")),
+            PathBuf::from("/work/code/repo")
+        );
+        let fallback = default_clone_path("repo", Some("  "));
+        assert!(fallback.ends_with("vibe-kanban-projects/repo"));
+        assert_eq!(default_clone_path("repo", None), fallback);
+    }
+}
@@ -0,0 +1,221 @@
+use std::path::Path;
+
+use executors::{
+    actions::{
+        ExecutorAction, ExecutorActionType,
+        script::{ScriptContext, ScriptRequest, ScriptRequestLanguage},
+    },
+    profile::ProfileVariantLabel,
+};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use utils::text::{git_branch_id, short_uuid};
+use uuid::Uuid;
+
+use crate::services::git::{GitService, GitServiceError};
+
+/// Checked into the root of a project's repository so its conventions apply on every machine
+pub const REPO_CONFIG_FILE: &str = ".vibe-kanban.toml";
+
+#[derive(Debug, Error)]
+pub enum RepoConfigError {
+    #[error(transparent)]
+    Git(#[from] GitServiceError),
+    #[error("Invalid {REPO_CONFIG_FILE}: {0}")]
+    Parse(#[from] toml::de::Error),
+}
+
+/// Settings from a repository's `.vibe-kanban.toml`. Each one that is set takes precedence
+/// over the server config and the project settings for attempts in that repository.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RepoConfig {
+    /// Profile used when an attempt doesn't pick one
+    pub profile: Option<String>,
+    pub variant: Option<String>,
+    /// Replaces the project's setup script
+    pub setup_script: Option<String>,
+    /// Name of attempt branches, `{title}` is replaced by a slug of the task title and
+    /// `{id}` by a short attempt id, e.g. `feature/{title}-{id}`
+    pub branch_template: Option<String>,
+    /// Run after every coding agent turn, e.g. `cargo test`
+    pub verification_command: Option<String>,
+    /// Run after every coding agent turn before the verification command, e.g.
+    /// `cargo clippy -- -D warnings`
+    pub lint_command: Option<String>,
+    /// Send the output of a failing lint command back to the agent as a follow-up
+    #[serde(default)]
+    pub lint_auto_fix: bool,
+    /// Path prefixes agents are expected to change, e.g. `["src/", "tests/"]`. Changes
+    /// elsewhere are flagged for review, nothing is flagged when empty.
+    #[serde(default)]
+    pub expected_paths: Vec<String>,
+}
+
+impl RepoConfig {
+    pub fn parse(raw: &str) -> Result<Self, RepoConfigError> {
+        Ok(toml::from_str(raw)?)
+    }
+
+    /// Config as committed on `branch`, empty if the branch has no config file
+    pub fn load(git: &GitService, repo_path: &Path, branch: &str) -> Result<Self, RepoConfigError> {
+        match git.read_file_at_branch(repo_path, branch, Path::new(REPO_CONFIG_FILE))? {
+            Some(raw) => Self::parse(&raw),
+            None => Ok(Self::default()),
+        }
+    }
+
+    /// The repository's profile if it sets one, otherwise `fallback`
+    pub fn profile_or(&self, fallback: ProfileVariantLabel) -> ProfileVariantLabel {
+        match &self.profile {
+            Some(profile) => ProfileVariantLabel {
+                profile: profile.clone(),
+                variant: self.variant.clone(),
+            },
+            None => fallback,
+        }
+    }
+
+    /// Attempt branch name from `branch_template`, `None` without a template
+    pub fn branch_name(&self, attempt_id: &Uuid, task_title: &str) -> Option<String> {
+        let template = self.branch_template.as_deref()?;
+        let name = template
+            .replace("{title}", &git_branch_id(task_title))
+            .replace("{id}", &short_uuid(attempt_id));
+        if git2::Branch::name_is_valid(&name).unwrap_or(false) {
+            Some(name)
+        } else {
+            tracing::warn!(
+                "{} branch_template produced an invalid branch name '{}', using the default",
+                REPO_CONFIG_FILE,
+                name
+            );
+            None
+        }
+    }
+
+    /// Run the lint command, if any, before `next`
+    pub fn with_lint(&self, next: Option<Box<ExecutorAction>>) -> Option<Box<ExecutorAction>> {
+        match &self.lint_command {
+            Some(command) if !command.trim().is_empty() => Some(Box::new(ExecutorAction::new(
+                ExecutorActionType::ScriptRequest(ScriptRequest {
+                    script: command.clone(),
+                    language: ScriptRequestLanguage::Bash,
+                    context: ScriptContext::LintScript,
+                }),
+                next,
+            ))),
+            _ => next,
+        }
+    }
+
+    /// Run the verification command, if any, before `next`
+    pub fn with_verification(
+        &self,
+        next: Option<Box<ExecutorAction>>,
+    ) -> Option<Box<ExecutorAction>> {
+        match &self.verification_command {
+            Some(command) if !command.trim().is_empty() => Some(Box::new(ExecutorAction::new(
+                ExecutorActionType::ScriptRequest(ScriptRequest {
+                    script: command.clone(),
+                    language: ScriptRequestLanguage::Bash,
+                    context: ScriptContext::VerificationScript,
+                }),
+                next,
+            ))),
+            _ => next,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_rejects_unknown_keys() {
+        let config = RepoConfig::parse(
+            r#"
+profile = "codex"
+setup_script = "pnpm install"
+branch_template = "feature/{title}-{id}"
+verification_command = "pnpm test"
+lint_command = "pnpm lint"
+lint_auto_fix = true
+expected_paths = ["src/", "tests/"]
+"#,
+        )
+        .unwrap();
+        assert_eq!(config.setup_script.as_deref(), Some("pnpm install"));
+        assert!(config.lint_auto_fix);
+        assert_eq!(config.expected_paths, vec!["src/", "tests/"]);
+        assert!(config.with_lint(None).is_some());
+        assert_eq!(
+            config.profile_or(ProfileVariantLabel::default("claude-code".to_string())),
+            ProfileVariantLabel::default("codex".to_string())
+        );
+
+        assert!(matches!(
+            RepoConfig::parse("setup = \"pnpm install\""),
+            Err(RepoConfigError::Parse(_))
+        ));
+    }
+
+    #[test]
+    fn test_branch_name_from_template() {
+        let id = Uuid::parse_str("abcd1234-0000-0000-0000-000000000000").unwrap();
+        let config = RepoConfig {
+            branch_template: Some("feature/{title}-{id}".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(
+            config.branch_name(&id, "Fix login page").as_deref(),
+            Some("feature/fix-login-abcd")
+        );
+
+        let invalid = RepoConfig {
+            branch_template: Some("feature..{title}".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(invalid.branch_name(&id, "Fix login page"), None);
+        assert_eq!(
+            RepoConfig::default().branch_name(&id, "Fix login page"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_read_config_from_branch() {
+        let dir = tempfile::tempdir().unwrap();
+        let git = GitService::new();
+        git.initialize_repo_with_main_branch(dir.path()).unwrap();
+        let mut git_config = git2::Repository::open(dir.path())
+            .unwrap()
+            .config()
+            .unwrap();
+        git_config.set_str("user.name", "Test User").unwrap();
+        git_config
+            .set_str("user.email", "test@example.com")
+            .unwrap();
+        assert_eq!(
+            RepoConfig::load(&git, dir.path(), "main").unwrap(),
+            RepoConfig::default()
+        );
+
+        std::fs::write(
+            dir.path().join(REPO_CONFIG_FILE),
+            "verification_command = \"cargo test\"\n",
+        )
+        .unwrap();
+        // Uncommitted changes are not picked up
+        assert_eq!(
+            RepoConfig::load(&git, dir.path(), "main").unwrap(),
+            RepoConfig::default()
+        );
+
+        git.commit(dir.path(), "Add repo config").unwrap();
+        let config = RepoConfig::load(&git, dir.path(), "main").unwrap();
+        assert_eq!(config.verification_command.as_deref(), Some("cargo test"));
+        assert!(config.with_verification(None).is_some());
+    }
+}
@@ -0,0 +1,160 @@
+use std::{
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+use crate::services::github_service::{GitHubService, GitHubServiceError, RepositoryInfo};
+
+/// How long a listing is served from memory before GitHub is asked again
+const CACHE_TTL: Duration = Duration::from_secs(5 * 60);
+/// Pages of 100 repositories read at most, larger accounts are cut to the most recent ones
+const MAX_PAGES: u32 = 10;
+
+lazy_static::lazy_static! {
+    static ref CACHE: Mutex<Option<CachedRepositories>> = Mutex::new(None);
+}
+
+struct CachedRepositories {
+    token: String,
+    fetched_at: Instant,
+    repositories: Vec<RepositoryInfo>,
+}
+
+/// Repositories `token` can access, recently updated first. Listings are cached per token
+/// for a few minutes unless `refresh` is set.
+pub async fn accessible_repositories(
+    token: &str,
+    refresh: bool,
+) -> Result<Vec<RepositoryInfo>, GitHubServiceError> {
+    if !refresh
+        && let Some(cached) = CACHE
+            .lock()
+            .unwrap()
+            .as_ref()
+            .filter(|cached| cached.token == token && cached.fetched_at.elapsed() < CACHE_TTL)
+    {
+        return Ok(cached.repositories.clone());
+    }
+
+    let github = GitHubService::new(token)?;
+    let mut repositories = Vec::new();
+    for page in 1..=MAX_PAGES {
+        let batch = github.list_repositories(page).await?;
+        let last_page = batch.len() < 100;
+        repositories.extend(batch);
+        if last_page {
+            break;
+        }
+    }
+
+    *CACHE.lock().unwrap() = Some(CachedRepositories {
+        token: token.to_string(),
+        fetched_at: Instant::now(),
+        repositories: repositories.clone(),
+    });
+    Ok(repositories)
+}
+
+/// Repositories fuzzily matching `query` on their name or owner/name, best matches first.
+/// A blank query keeps every repository in its order.
+pub fn search(repositories: Vec<RepositoryInfo>, query: &str) -> Vec<RepositoryInfo> {
+    if query.trim().is_empty() {
+        return repositories;
+    }
+    let mut scored: Vec<(u32, RepositoryInfo)> = repositories
+        .into_iter()
+        .filter_map(|repo| {
+            let score = fuzzy_score(query, &repo.name)
+                .map(|score| score + 1)
+                .max(fuzzy_score(query, &repo.full_name))?;
+            Some((score, repo))
+        })
+        .collect();
+    // Stable, so equal matches stay most recently updated first
+    scored.sort_by(|a, b| b.0.cmp(&a.0));
+    scored.into_iter().map(|(_, repo)| repo).collect()
+}
+
+/// Score of `candidate` holding the characters of `query` in order, ignoring case and
+/// spaces, higher is better. Characters following the previous match or starting a word
+/// score extra, as does the query appearing as is.
+fn fuzzy_score(query: &str, candidate: &str) -> Option<u32> {
+    let query: String = query
+        .to_lowercase()
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .collect();
+    let candidate = candidate.to_lowercase();
+    let chars: Vec<char> = candidate.chars().collect();
+
+    let mut score = if candidate.contains(&query) { 10 } else { 0 };
+    let mut position = 0;
+    let mut previous: Option<usize> = None;
+    for needle in query.chars() {
+        let index = (position..chars.len()).find(|&i| chars[i] == needle)?;
+        score += 1;
+        if index > 0 && previous == Some(index - 1) {
+            score += 4;
+        }
+        if index == 0 || matches!(chars[index - 1], '/' | '-' | '_' | '.') {
+            score += 3;
+        }
+        previous = Some(index);
+        position = index + 1;
+    }
+    Some(score)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn repo(full_name: &str) -> RepositoryInfo {
+        let (owner, name) = full_name.split_once('/').unwrap();
+        RepositoryInfo {
+            id: 1,
+            name: name.to_string(),
+            full_name: full_name.to_string(),
+            owner: owner.to_string(),
+            description: None,
+            clone_url: format!("https://github.com/{full_name}.git"),
+            ssh_url: format!("git@github.com:{full_name}.git"),
+            default_branch: "main".to_string(),
+            private: false,
+        }
+    }
+
+    fn names(repositories: Vec<RepositoryInfo>) -> Vec<String> {
+        repositories
+            .into_iter()
+            .map(|repo| repo.full_name)
+            .collect()
+    }
+
+    #[test]
+    fn test_fuzzy_score_needs_characters_in_order() {
+        assert!(fuzzy_score("vk", "vibe-kanban").is_some());
+        assert!(fuzzy_score("kv", "vibe-kanban").is_none());
+        assert!(fuzzy_score("kanban", "vibe-kanban") > fuzzy_score("vbkn", "vibe-kanban"));
+        assert!(fuzzy_score("Vibe Kan", "vibe-kanban").is_some());
+    }
+
+    #[test]
+    fn test_search_ranks_best_matches_first() {
+        let repositories = vec![
+            repo("acme/website"),
+            repo("acme/kanban-board"),
+            repo("bloop/vibe-kanban"),
+            repo("acme/api"),
+        ];
+        assert_eq!(
+            names(search(repositories.clone(), "kanban")),
+            ["acme/kanban-board", "bloop/vibe-kanban"]
+        );
+        assert_eq!(
+            names(search(repositories.clone(), "acme/api")),
+            ["acme/api"]
+        );
+        assert_eq!(names(search(repositories, " ")).len(), 4);
+    }
+}
@@ -0,0 +1,128 @@
+use db::models::attempt_review::{ReviewComment, ReviewVerdict};
+use serde::Deserialize;
+use utils::diff::{Diff, FileDiffDetails, create_unified_diff};
+
+use super::git::GitService;
+
+/// Longest diff handed to the reviewer, the rest is cut off
+const MAX_REVIEW_DIFF_CHARS: usize = 60_000;
+
+const REVIEW_PROMPT: &str = "You are reviewing another agent's changes for the task below. Don't \
+change any files. Check the diff for bugs, missing pieces of the task and risky changes.";
+
+const REVIEW_FORMAT: &str = r#"Finish with your review as a single JSON object in a ```json block:
+{"verdict": "approve" | "request_changes", "summary": "<one paragraph>", "comments": [{"file": "<path or null>", "line": <number or null>, "comment": "<remark>"}]}"#;
+
+/// Review as the reviewer reports it
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParsedReview {
+    pub verdict: ReviewVerdict,
+    pub summary: String,
+    pub comments: Vec<ReviewComment>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum ReviewerVerdict {
+    Approve,
+    RequestChanges,
+}
+
+#[derive(Deserialize)]
+struct ReviewerOutput {
+    verdict: ReviewerVerdict,
+    summary: String,
+    #[serde(default)]
+    comments: Vec<ReviewComment>,
+}
+
+/// Diff of the attempt in unified format, as the reviewer reads it
+pub fn render_diff(diffs: &[Diff]) -> String {
+    diffs
+        .iter()
+        .map(|diff| {
+            create_unified_diff(
+                &GitService::diff_path(diff),
+                content(&diff.old_file),
+                content(&diff.new_file),
+            )
+        })
+        .collect()
+}
+
+fn content(file: &Option<FileDiffDetails>) -> &str {
+    file.as_ref()
+        .and_then(|f| f.content.as_deref())
+        .unwrap_or_default()
+}
+
+/// `diff` cut down to the length an agent is handed
+pub(crate) fn cut_diff(diff: &str) -> String {
+    match diff.char_indices().nth(MAX_REVIEW_DIFF_CHARS) {
+        Some((end, _)) => format!("{}\n... (diff cut off)", &diff[..end]),
+        None => diff.to_string(),
+    }
+}
+
+/// Prompt of a reviewer run over `diff`
+pub fn review_prompt(task_prompt: &str, diff: &str) -> String {
+    let diff = cut_diff(diff);
+    format!("{REVIEW_PROMPT}\n\n{task_prompt}\n\n```diff\n{diff}\n```\n\n{REVIEW_FORMAT}")
+}
+
+/// Read the review from the reviewer's final answer: the last JSON object holding a verdict
+pub fn parse_review(output: &str) -> Option<ParsedReview> {
+    output.match_indices('{').rev().find_map(|(start, _)| {
+        let review = serde_json::Deserializer::from_str(&output[start..])
+            .into_iter::<ReviewerOutput>()
+            .next()?
+            .ok()?;
+        Some(ParsedReview {
+            verdict: match review.verdict {
+                ReviewerVerdict::Approve => ReviewVerdict::Approved,
+                ReviewerVerdict::RequestChanges => ReviewVerdict::ChangesRequested,
+            },
+            summary: review.summary,
+            comments: review.comments,
+        })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_review_takes_last_review_object() {
+        let output = r#"Looked at the diff, {"not": "a review"}.
+
+```json
+{"verdict": "request_changes", "summary": "Misses the error case", "comments": [{"file": "src/lib.rs", "line": 12, "comment": "Unwrap panics"}, {"comment": "Add a test"}]}
+```
+Done."#;
+        let review = parse_review(output).unwrap();
+        assert_eq!(review.verdict, ReviewVerdict::ChangesRequested);
+        assert_eq!(review.summary, "Misses the error case");
+        assert_eq!(review.comments.len(), 2);
+        assert_eq!(review.comments[0].file.as_deref(), Some("src/lib.rs"));
+        assert_eq!(review.comments[0].line, Some(12));
+        assert_eq!(review.comments[1].file, None);
+
+        let review = parse_review(r#"{"verdict":"approve","summary":"Looks good"}"#).unwrap();
+        assert_eq!(review.verdict, ReviewVerdict::Approved);
+        assert!(review.comments.is_empty());
+
+        assert_eq!(parse_review("LGTM"), None);
+        assert_eq!(parse_review(r#"{"verdict": "maybe", "summary": ""}"#), None);
+    }
+
+    #[test]
+    fn test_review_prompt_cuts_long_diffs() {
+        let prompt = review_prompt("Add a flag", "+x\n");
+        assert!(prompt.contains("Add a flag\n\n```diff\n+x\n\n```"));
+        assert!(prompt.ends_with(REVIEW_FORMAT));
+
+        let long = "y".repeat(MAX_REVIEW_DIFF_CHARS + 10);
+        assert!(review_prompt("t", &long).contains("... (diff cut off)"));
+    }
+}
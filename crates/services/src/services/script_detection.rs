@@ -0,0 +1,296 @@
+use std::{collections::HashMap, path::Path};
+
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+use super::package_manager::PackageManager;
+
+/// Suggestions at least this confident are filled in when a project is created without
+/// the script
+pub const CONFIDENT: f32 = 0.8;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, TS)]
+#[serde(rename_all = "snake_case")]
+pub enum ScriptKind {
+    Setup,
+    Dev,
+    Test,
+}
+
+/// A command proposed for one of the project's scripts
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct ScriptSuggestion {
+    pub kind: ScriptKind,
+    pub command: String,
+    /// From 0 to 1, how likely the command is what the project uses
+    pub confidence: f32,
+    /// File the suggestion was derived from
+    pub source: String,
+}
+
+/// Commands proposed for a repository, the most confident first for each kind
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct DetectedScripts {
+    pub package_manager: Option<PackageManager>,
+    pub suggestions: Vec<ScriptSuggestion>,
+}
+
+impl DetectedScripts {
+    /// The suggestion for `kind` confident enough to use without asking
+    pub fn confident(&self, kind: ScriptKind) -> Option<String> {
+        self.suggestions
+            .iter()
+            .find(|s| s.kind == kind && s.confidence >= CONFIDENT)
+            .map(|s| s.command.clone())
+    }
+}
+
+#[derive(Deserialize)]
+struct PackageJson {
+    #[serde(default)]
+    scripts: HashMap<String, String>,
+}
+
+/// What `npm init` puts in the test script of a package without tests
+const NPM_PLACEHOLDER_TEST: &str = "no test specified";
+
+struct Detector<'a> {
+    repo_path: &'a Path,
+    suggestions: Vec<ScriptSuggestion>,
+}
+
+impl Detector<'_> {
+    fn suggest(
+        &mut self,
+        kind: ScriptKind,
+        command: impl Into<String>,
+        confidence: f32,
+        source: &str,
+    ) {
+        let command = command.into();
+        if !self
+            .suggestions
+            .iter()
+            .any(|s| s.kind == kind && s.command == command)
+        {
+            self.suggestions.push(ScriptSuggestion {
+                kind,
+                command,
+                confidence,
+                source: source.to_string(),
+            });
+        }
+    }
+
+    fn exists(&self, file: &str) -> bool {
+        self.repo_path.join(file).exists()
+    }
+
+    fn read(&self, file: &str) -> Option<String> {
+        std::fs::read_to_string(self.repo_path.join(file)).ok()
+    }
+
+    fn package_json(&mut self, manager: PackageManager) {
+        let Some(package) = self
+            .read("package.json")
+            .and_then(|raw| serde_json::from_str::<PackageJson>(&raw).ok())
+        else {
+            return;
+        };
+        let runner = match manager {
+            PackageManager::Pnpm => "pnpm",
+            PackageManager::Yarn => "yarn",
+            PackageManager::Bun => "bun",
+            _ => "npm",
+        };
+        let candidates: &[(&str, ScriptKind, f32)] = &[
+            ("dev", ScriptKind::Dev, 0.9),
+            ("start", ScriptKind::Dev, 0.7),
+            ("serve", ScriptKind::Dev, 0.6),
+            ("test", ScriptKind::Test, 0.9),
+            ("test:unit", ScriptKind::Test, 0.6),
+        ];
+        for (script, kind, confidence) in candidates {
+            match package.scripts.get(*script) {
+                Some(body) if !body.contains(NPM_PLACEHOLDER_TEST) => {
+                    self.suggest(
+                        *kind,
+                        format!("{runner} run {script}"),
+                        *confidence,
+                        "package.json",
+                    );
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn makefile(&mut self) {
+        let Some(makefile) = self.read("Makefile") else {
+            return;
+        };
+        let targets: Vec<&str> = makefile
+            .lines()
+            .filter(|line| !line.starts_with(['\t', ' ', '.', '#']))
+            .filter_map(|line| line.split_once(':'))
+            .filter(|(_, rest)| !rest.starts_with('='))
+            .map(|(target, _)| target.trim())
+            .collect();
+        let candidates: &[(&[&str], ScriptKind, f32)] = &[
+            (
+                &["setup", "install", "deps", "bootstrap"],
+                ScriptKind::Setup,
+                0.7,
+            ),
+            (&["dev", "run", "serve", "start"], ScriptKind::Dev, 0.6),
+            (&["test", "check"], ScriptKind::Test, 0.7),
+        ];
+        for (names, kind, confidence) in candidates {
+            if let Some(target) = names.iter().find(|name| targets.contains(*name)) {
+                self.suggest(*kind, format!("make {target}"), *confidence, "Makefile");
+            }
+        }
+    }
+
+    fn python(&mut self, manager: PackageManager, source: &str) {
+        let manifests = [
+            self.read("pyproject.toml"),
+            self.read("requirements.txt"),
+            self.read("requirements-dev.txt"),
+        ];
+        let uses_pytest = manifests.iter().flatten().any(|m| m.contains("pytest"));
+        // Prefix running a tool installed in the project's environment
+        let run = match manager {
+            PackageManager::Uv => "uv run ",
+            PackageManager::Poetry => "poetry run ",
+            _ => ".venv/bin/",
+        };
+        if uses_pytest || self.exists("tests") {
+            let confidence = if uses_pytest { 0.8 } else { 0.5 };
+            self.suggest(ScriptKind::Test, format!("{run}pytest"), confidence, source);
+        }
+        if self.exists("manage.py") {
+            self.suggest(
+                ScriptKind::Dev,
+                format!("{run}python manage.py runserver"),
+                0.7,
+                "manage.py",
+            );
+        }
+    }
+}
+
+/// Propose setup, dev and test commands from the manifests at the root of a repository
+pub fn detect_scripts(repo_path: &Path) -> DetectedScripts {
+    let package_manager = PackageManager::detect(repo_path);
+    let mut detector = Detector {
+        repo_path,
+        suggestions: Vec::new(),
+    };
+
+    if let Some(manager) = package_manager {
+        let source = match manager {
+            PackageManager::Pnpm
+            | PackageManager::Yarn
+            | PackageManager::Bun
+            | PackageManager::Npm => "package.json",
+            PackageManager::Cargo => "Cargo.toml",
+            PackageManager::Uv | PackageManager::Poetry | PackageManager::Pip => {
+                if detector.exists("pyproject.toml") {
+                    "pyproject.toml"
+                } else {
+                    "requirements.txt"
+                }
+            }
+            PackageManager::Go => "go.mod",
+            PackageManager::Bundler => "Gemfile",
+        };
+        detector.suggest(
+            ScriptKind::Setup,
+            manager.setup_command(repo_path),
+            0.9,
+            source,
+        );
+        match manager {
+            PackageManager::Pnpm
+            | PackageManager::Yarn
+            | PackageManager::Bun
+            | PackageManager::Npm => detector.package_json(manager),
+            PackageManager::Cargo => {
+                detector.suggest(ScriptKind::Test, "cargo test", 0.9, source);
+                let confidence = if detector.exists("src/main.rs") {
+                    0.7
+                } else {
+                    0.3
+                };
+                detector.suggest(ScriptKind::Dev, "cargo run", confidence, source);
+            }
+            PackageManager::Uv | PackageManager::Poetry | PackageManager::Pip => {
+                detector.python(manager, source)
+            }
+            PackageManager::Go => {
+                detector.suggest(ScriptKind::Test, "go test ./...", 0.9, source);
+                if detector.exists("main.go") {
+                    detector.suggest(ScriptKind::Dev, "go run .", 0.7, source);
+                }
+            }
+            PackageManager::Bundler => {
+                if detector.exists("spec") {
+                    detector.suggest(ScriptKind::Test, "bundle exec rspec", 0.8, source);
+                } else {
+                    detector.suggest(ScriptKind::Test, "bundle exec rake test", 0.5, source);
+                }
+            }
+        }
+    }
+    detector.makefile();
+
+    let mut suggestions = detector.suggestions;
+    suggestions.sort_by(|a, b| b.confidence.total_cmp(&a.confidence));
+    DetectedScripts {
+        package_manager,
+        suggestions,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tempfile::TempDir;
+
+    use super::*;
+
+    #[test]
+    fn test_detects_package_scripts_and_make_targets() {
+        let dir = TempDir::new().unwrap();
+        std::fs::write(dir.path().join("pnpm-lock.yaml"), "").unwrap();
+        std::fs::write(
+            dir.path().join("package.json"),
+            r#"{"scripts": {"start": "node .", "dev": "vite", "test": "echo \"Error: no test specified\" && exit 1"}}"#,
+        )
+        .unwrap();
+        std::fs::write(
+            dir.path().join("Makefile"),
+            "VERSION := 1\n.PHONY: test\ntest:\n\tpnpm vitest\n",
+        )
+        .unwrap();
+
+        let detected = detect_scripts(dir.path());
+        assert_eq!(detected.package_manager, Some(PackageManager::Pnpm));
+        assert_eq!(
+            detected.confident(ScriptKind::Setup).as_deref(),
+            Some("pnpm install")
+        );
+        assert_eq!(
+            detected.confident(ScriptKind::Dev).as_deref(),
+            Some("pnpm run dev")
+        );
+        // The placeholder test script is skipped, the Makefile's is only a guess
+        assert_eq!(detected.confident(ScriptKind::Test), None);
+        assert!(
+            detected
+                .suggestions
+                .iter()
+                .any(|s| s.kind == ScriptKind::Test && s.command == "make test")
+        );
+    }
+}
@@ -0,0 +1,305 @@
+use std::{
+    collections::BTreeMap,
+    fs::OpenOptions,
+    io::Write,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex, OnceLock},
+};
+
+use aes_gcm::{
+    Aes256Gcm, Key, Nonce,
+    aead::{Aead, AeadCore, KeyInit, OsRng},
+};
+use base64::{Engine, engine::general_purpose::STANDARD as BASE64};
+use thiserror::Error;
+use utils::{
+    assets::asset_dir,
+    workspaces::{self, DEFAULT_WORKSPACE},
+};
+
+/// Prefix of config values that point into the secrets store instead of holding the secret
+pub const SECRET_REF_PREFIX: &str = "secret:";
+
+const KEYRING_SERVICE: &str = "vibe-kanban";
+const SECRETS_FILE: &str = "secrets.enc";
+const SECRETS_KEY_FILE: &str = "secrets.key";
+const NONCE_LEN: usize = 12;
+
+static DEFAULT_STORE: OnceLock<SecretsStore> = OnceLock::new();
+
+#[derive(Debug, Error)]
+pub enum SecretsError {
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    #[error(transparent)]
+    Json(#[from] serde_json::Error),
+    #[error("OS keychain error: {0}")]
+    Keyring(#[from] keyring::Error),
+    #[error("Secrets file is corrupt or was encrypted with a different key")]
+    Decrypt,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Backend {
+    Keychain,
+    EncryptedFile(PathBuf),
+}
+
+/// Stores credentials in the OS keychain, or in an AES-GCM encrypted file next to the
+/// config when no keychain is reachable (e.g. headless Linux without a secret service).
+#[derive(Debug, Clone)]
+pub struct SecretsStore {
+    backend: Backend,
+    /// Held across the read-modify-write of the encrypted file, so concurrent changes
+    /// aren't lost
+    file_lock: Arc<Mutex<()>>,
+}
+
+impl SecretsStore {
+    /// Store for the active data directory, the backend is chosen on first use.
+    /// Set `VK_SECRETS_BACKEND=file` to skip the keychain.
+    pub fn default_store() -> &'static SecretsStore {
+        DEFAULT_STORE.get_or_init(|| {
+            let force_file = std::env::var("VK_SECRETS_BACKEND")
+                .is_ok_and(|v| v.trim().eq_ignore_ascii_case("file"));
+            if !force_file && keychain_available() {
+                SecretsStore {
+                    backend: Backend::Keychain,
+                    file_lock: Arc::default(),
+                }
+            } else {
+                tracing::info!("OS keychain unavailable, storing secrets in an encrypted file");
+                SecretsStore::encrypted_file(asset_dir())
+            }
+        })
+    }
+
+    /// Encrypted-file store in `dir`
+    pub fn encrypted_file(dir: PathBuf) -> Self {
+        SecretsStore {
+            backend: Backend::EncryptedFile(dir),
+            file_lock: Arc::default(),
+        }
+    }
+
+    /// Config value referring to the secret stored under `key`
+    pub fn reference(key: &str) -> String {
+        format!("{SECRET_REF_PREFIX}{key}")
+    }
+
+    /// Key of a secret reference, `None` if `value` is a plain value
+    pub fn parse_reference(value: &str) -> Option<&str> {
+        value.strip_prefix(SECRET_REF_PREFIX)
+    }
+
+    pub fn get(&self, key: &str) -> Result<Option<String>, SecretsError> {
+        match &self.backend {
+            Backend::Keychain => match keychain_entry(key)?.get_password() {
+                Ok(secret) => Ok(Some(secret)),
+                Err(keyring::Error::NoEntry) => Ok(None),
+                Err(e) => Err(e.into()),
+            },
+            Backend::EncryptedFile(dir) => Ok(read_file_secrets(dir)?.remove(key)),
+        }
+    }
+
+    pub fn set(&self, key: &str, secret: &str) -> Result<(), SecretsError> {
+        match &self.backend {
+            Backend::Keychain => {
+                keychain_entry(key)?.set_password(secret)?;
+                Ok(())
+            }
+            Backend::EncryptedFile(dir) => {
+                let _lock = self.file_lock.lock().unwrap_or_else(|e| e.into_inner());
+                let mut secrets = read_file_secrets(dir)?;
+                if secrets.get(key).map(String::as_str) == Some(secret) {
+                    return Ok(());
+                }
+                secrets.insert(key.to_string(), secret.to_string());
+                write_file_secrets(dir, &secrets)
+            }
+        }
+    }
+
+    /// Remove a secret, succeeding if it was never stored
+    pub fn delete(&self, key: &str) -> Result<(), SecretsError> {
+        match &self.backend {
+            Backend::Keychain => match keychain_entry(key)?.delete_credential() {
+                Ok(()) | Err(keyring::Error::NoEntry) => Ok(()),
+                Err(e) => Err(e.into()),
+            },
+            Backend::EncryptedFile(dir) => {
+                let _lock = self.file_lock.lock().unwrap_or_else(|e| e.into_inner());
+                let mut secrets = read_file_secrets(dir)?;
+                if secrets.remove(key).is_some() {
+                    write_file_secrets(dir, &secrets)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Keychain entries are shared by every data directory, so namespace them per workspace
+fn keychain_entry(key: &str) -> Result<keyring::Entry, keyring::Error> {
+    match workspaces::active() {
+        Some((workspace, _)) if workspace != DEFAULT_WORKSPACE => {
+            keyring::Entry::new(KEYRING_SERVICE, &format!("{workspace}/{key}"))
+        }
+        _ => keyring::Entry::new(KEYRING_SERVICE, key),
+    }
+}
+
+/// Probe the keychain with a lookup, locked or missing stores fail rather than report no entry
+fn keychain_available() -> bool {
+    match keyring::Entry::new(KEYRING_SERVICE, "availability-check")
+        .and_then(|entry| entry.get_password())
+    {
+        Ok(_) | Err(keyring::Error::NoEntry) => true,
+        Err(e) => {
+            tracing::debug!("OS keychain check failed: {}", e);
+            false
+        }
+    }
+}
+
+fn read_file_secrets(dir: &Path) -> Result<BTreeMap<String, String>, SecretsError> {
+    let path = dir.join(SECRETS_FILE);
+    if !path.exists() {
+        return Ok(BTreeMap::new());
+    }
+    let encoded = std::fs::read_to_string(path)?;
+    let data = BASE64
+        .decode(encoded.trim())
+        .map_err(|_| SecretsError::Decrypt)?;
+    if data.len() < NONCE_LEN {
+        return Err(SecretsError::Decrypt);
+    }
+    let (nonce, ciphertext) = data.split_at(NONCE_LEN);
+    let plaintext = cipher(dir)?
+        .decrypt(Nonce::from_slice(nonce), ciphertext)
+        .map_err(|_| SecretsError::Decrypt)?;
+    Ok(serde_json::from_slice(&plaintext)?)
+}
+
+fn write_file_secrets(dir: &Path, secrets: &BTreeMap<String, String>) -> Result<(), SecretsError> {
+    let plaintext = serde_json::to_vec(secrets)?;
+    let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+    let ciphertext = cipher(dir)?
+        .encrypt(&nonce, plaintext.as_slice())
+        .map_err(|_| SecretsError::Decrypt)?;
+    let mut data = nonce.to_vec();
+    data.extend(ciphertext);
+    write_private(&dir.join(SECRETS_FILE), BASE64.encode(data).as_bytes())?;
+    Ok(())
+}
+
+/// Cipher keyed by the store's key file, created with a random key on first use
+fn cipher(dir: &Path) -> Result<Aes256Gcm, SecretsError> {
+    let key_path = dir.join(SECRETS_KEY_FILE);
+    let key = if key_path.exists() {
+        let key = BASE64
+            .decode(std::fs::read_to_string(&key_path)?.trim())
+            .map_err(|_| SecretsError::Decrypt)?;
+        if key.len() != 32 {
+            return Err(SecretsError::Decrypt);
+        }
+        *Key::<Aes256Gcm>::from_slice(&key)
+    } else {
+        std::fs::create_dir_all(dir)?;
+        let key = Aes256Gcm::generate_key(OsRng);
+        write_private(&key_path, BASE64.encode(key).as_bytes())?;
+        key
+    };
+    Ok(Aes256Gcm::new(&key))
+}
+
+/// Replace a file only the current user can read. The contents go to a temporary file
+/// created private, then renamed over `path`, so a crash leaves the old file intact.
+fn write_private(path: &Path, contents: &[u8]) -> std::io::Result<()> {
+    let mut file_name = path.file_name().unwrap_or_default().to_os_string();
+    file_name.push(".tmp");
+    let tmp_path = path.with_file_name(file_name);
+    // A leftover from a crash may not be ours to reuse
+    match std::fs::remove_file(&tmp_path) {
+        Err(e) if e.kind() != std::io::ErrorKind::NotFound => return Err(e),
+        _ => {}
+    }
+
+    let mut options = OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    let mut file = options.open(&tmp_path)?;
+    file.write_all(contents)?;
+    file.sync_all()?;
+    drop(file);
+    std::fs::rename(&tmp_path, path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encrypted_file_roundtrip() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = SecretsStore::encrypted_file(dir.path().to_path_buf());
+
+        assert_eq!(store.get("github.pat").unwrap(), None);
+        store.set("github.pat", "ghp_secret").unwrap();
+        assert_eq!(
+            store.get("github.pat").unwrap().as_deref(),
+            Some("ghp_secret")
+        );
+
+        let on_disk = std::fs::read_to_string(dir.path().join(SECRETS_FILE)).unwrap();
+        assert!(!on_disk.contains("ghp_secret"));
+
+        store.delete("github.pat").unwrap();
+        assert_eq!(store.get("github.pat").unwrap(), None);
+        store.delete("github.pat").unwrap();
+    }
+
+    #[test]
+    fn test_concurrent_sets_keep_every_secret() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = SecretsStore::encrypted_file(dir.path().to_path_buf());
+        std::thread::scope(|scope| {
+            for i in 0..8 {
+                let store = &store;
+                scope.spawn(move || store.set(&format!("key{i}"), "secret").unwrap());
+            }
+        });
+        for i in 0..8 {
+            assert!(store.get(&format!("key{i}")).unwrap().is_some());
+        }
+        assert!(!dir.path().join("secrets.enc.tmp").exists());
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            for file in [SECRETS_FILE, SECRETS_KEY_FILE] {
+                let mode = std::fs::metadata(dir.path().join(file))
+                    .unwrap()
+                    .permissions()
+                    .mode();
+                assert_eq!(mode & 0o777, 0o600);
+            }
+        }
+    }
+
+    #[test]
+    fn test_references() {
+        let reference = SecretsStore::reference("github.oauth_token");
+        assert_eq!(reference, "secret:github.oauth_token");
+        assert_eq!(
+            SecretsStore::parse_reference(&reference),
+            Some("github.oauth_token")
+        );
+        assert_eq!(SecretsStore::parse_reference("gho_plain"), None);
+    }
+}
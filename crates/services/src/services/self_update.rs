@@ -0,0 +1,203 @@
+use std::{sync::RwLock, time::Duration};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use ts_rs::TS;
+use utils::offline::is_offline;
+
+const PACKAGE: &str = "vibe-kanban";
+const REGISTRY_URL: &str = "https://registry.npmjs.org/vibe-kanban/latest";
+const RELEASES_URL: &str = "https://api.github.com/repos/BloopAI/vibe-kanban/releases/tags";
+const CHECK_INTERVAL: Duration = Duration::from_secs(6 * 60 * 60);
+const FETCH_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// Set by the npx wrapper on the server it launches, so updates can be handed to it
+pub const LAUNCHER_ENV: &str = "VIBE_KANBAN_LAUNCHER";
+/// Exit code asking the npx wrapper to install the latest version and start it
+pub const UPDATE_EXIT_CODE: i32 = 75;
+
+lazy_static::lazy_static! {
+    static ref LATEST: RwLock<Option<LatestRelease>> = RwLock::new(None);
+}
+
+#[derive(Debug, Error)]
+pub enum SelfUpdateError {
+    #[error(transparent)]
+    Http(#[from] reqwest::Error),
+    #[error("Offline mode, not checking for updates")]
+    Offline,
+}
+
+#[derive(Debug, Clone)]
+struct LatestRelease {
+    version: String,
+    notes: Option<String>,
+    url: Option<String>,
+    checked_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize)]
+struct RegistryPackage {
+    version: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct GitHubRelease {
+    body: Option<String>,
+    html_url: String,
+}
+
+/// Whether a newer version is published, as last checked against the npm registry
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct UpdateStatus {
+    pub current_version: String,
+    /// `None` until the registry has been checked
+    pub latest_version: Option<String>,
+    pub update_available: bool,
+    /// Changelog of the latest version, from its GitHub release
+    pub release_notes: Option<String>,
+    pub release_url: Option<String>,
+    pub checked_at: Option<DateTime<Utc>>,
+    /// Only servers started by the npx wrapper can be updated from the app
+    pub can_self_update: bool,
+}
+
+/// What `POST /maintenance/self-update` did
+#[derive(Debug, Clone, Serialize, TS)]
+#[serde(tag = "type", rename_all = "snake_case")]
+#[ts(tag = "type", rename_all = "snake_case")]
+pub enum SelfUpdateOutcome {
+    /// The server shuts down and the wrapper starts `version` in its place
+    Restarting {
+        version: String,
+    },
+    /// Not started by the wrapper, the user has to update the way they installed it
+    Manual {
+        instructions: String,
+    },
+    UpToDate,
+}
+
+pub fn current_version() -> &'static str {
+    env!("CARGO_PKG_VERSION")
+}
+
+/// Whether the npx wrapper launched this server and waits to install updates for it
+pub fn launched_by_wrapper() -> bool {
+    std::env::var(LAUNCHER_ENV).is_ok_and(|launcher| launcher == "npx")
+}
+
+pub fn status() -> UpdateStatus {
+    let latest = LATEST.read().unwrap().clone();
+    let current = current_version();
+    UpdateStatus {
+        current_version: current.to_string(),
+        update_available: latest
+            .as_ref()
+            .is_some_and(|latest| is_newer(&latest.version, current)),
+        latest_version: latest.as_ref().map(|latest| latest.version.clone()),
+        release_notes: latest.as_ref().and_then(|latest| latest.notes.clone()),
+        release_url: latest.as_ref().and_then(|latest| latest.url.clone()),
+        checked_at: latest.as_ref().map(|latest| latest.checked_at),
+        can_self_update: launched_by_wrapper(),
+    }
+}
+
+/// Ask the npm registry for the latest version, and GitHub for its release notes
+pub async fn check() -> Result<UpdateStatus, SelfUpdateError> {
+    if is_offline() {
+        return Err(SelfUpdateError::Offline);
+    }
+    let client = reqwest::Client::builder()
+        .timeout(FETCH_TIMEOUT)
+        .user_agent(PACKAGE)
+        .build()?;
+    let package: RegistryPackage = client
+        .get(REGISTRY_URL)
+        .send()
+        .await?
+        .error_for_status()?
+        .json()
+        .await?;
+    // A release without notes still counts as an update
+    let release = match client
+        .get(format!("{RELEASES_URL}/v{}", package.version))
+        .send()
+        .await
+        .and_then(|response| response.error_for_status())
+    {
+        Ok(response) => response.json::<GitHubRelease>().await.ok(),
+        Err(e) => {
+            tracing::debug!("No release notes for {}: {}", package.version, e);
+            None
+        }
+    };
+
+    *LATEST.write().unwrap() = Some(LatestRelease {
+        version: package.version,
+        notes: release
+            .as_ref()
+            .and_then(|release| release.body.clone())
+            .filter(|notes| !notes.trim().is_empty()),
+        url: release.map(|release| release.html_url),
+        checked_at: Utc::now(),
+    });
+    Ok(status())
+}
+
+/// Check for a new version at startup and every few hours after
+pub async fn run() {
+    let mut interval = tokio::time::interval(CHECK_INTERVAL);
+    loop {
+        interval.tick().await;
+        match check().await {
+            Ok(status) if status.update_available => tracing::info!(
+                "vibe-kanban {} is available, this is {}",
+                status.latest_version.unwrap_or_default(),
+                status.current_version
+            ),
+            Ok(_) => {}
+            Err(SelfUpdateError::Offline) => return,
+            Err(e) => tracing::debug!("Failed to check for updates: {}", e),
+        }
+    }
+}
+
+/// How to update outside the wrapper, for [`SelfUpdateOutcome::Manual`]
+pub fn manual_instructions() -> String {
+    format!(
+        "Restart with `npx {PACKAGE}@latest`, or update the way you installed {PACKAGE} if you \
+         built it yourself"
+    )
+}
+
+/// `a.b.c` versions compared numerically, pre-release suffixes are ignored
+fn is_newer(latest: &str, current: &str) -> bool {
+    fn parse(version: &str) -> Vec<u64> {
+        version
+            .trim_start_matches('v')
+            .split(['-', '+'])
+            .next()
+            .unwrap_or_default()
+            .split('.')
+            .map(|part| part.parse().unwrap_or(0))
+            .collect()
+    }
+    parse(latest) > parse(current)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_newer_compares_numerically() {
+        assert!(is_newer("0.0.62", "0.0.61"));
+        assert!(is_newer("0.1.0", "0.0.99"));
+        assert!(is_newer("v0.0.100", "0.0.99"));
+        assert!(!is_newer("0.0.61", "0.0.61"));
+        assert!(!is_newer("0.0.60", "0.0.61"));
+        assert!(!is_newer("0.0.61-beta.1", "0.0.61"));
+    }
+}
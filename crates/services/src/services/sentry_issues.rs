@@ -0,0 +1,606 @@
+use std::{
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use db::models::project::Project;
+use hmac::{Hmac, Mac};
+use reqwest::StatusCode;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::Sha256;
+use thiserror::Error;
+use ts_rs::TS;
+
+use super::secrets::{SecretsError, SecretsStore};
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(20);
+const DEFAULT_BASE_URL: &str = "https://sentry.io";
+/// Breadcrumbs leading up to the error that make it into the prompt
+const MAX_BREADCRUMBS: usize = 20;
+/// Frames of each exception that make it into the prompt, innermost first
+const MAX_FRAMES: usize = 30;
+
+pub const AUTH_TOKEN_KEY: &str = "integration.sentry.auth_token";
+pub const CLIENT_SECRET_KEY: &str = "integration.sentry.client_secret";
+const AUTH_TOKEN_ENV: &str = "SENTRY_AUTH_TOKEN";
+
+#[derive(Debug, Error)]
+pub enum SentryIssueError {
+    #[error(transparent)]
+    Http(#[from] reqwest::Error),
+    #[error(transparent)]
+    Secrets(#[from] SecretsError),
+    #[error("No Sentry auth token is set")]
+    NoToken,
+    #[error("Not a Sentry issue link: {0}")]
+    InvalidLink(String),
+    #[error("Sentry issue {0} not found")]
+    NotFound(String),
+    #[error("Sentry answered {0}")]
+    Api(StatusCode),
+}
+
+/// An issue on a Sentry server, sentry.io or self-hosted
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IssueRef {
+    pub base_url: String,
+    pub issue_id: String,
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct StackFrame {
+    pub path: String,
+    pub function: Option<String>,
+    #[ts(type = "number | null")]
+    pub line: Option<i64>,
+    /// Sentry thinks the frame is in the application rather than a library
+    pub in_app: bool,
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct SentryException {
+    pub kind: Option<String>,
+    pub value: Option<String>,
+    /// Innermost frame first
+    pub frames: Vec<StackFrame>,
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct Breadcrumb {
+    pub timestamp: Option<String>,
+    pub category: Option<String>,
+    pub level: Option<String>,
+    pub message: Option<String>,
+}
+
+/// An issue with the stack trace and breadcrumbs of its latest event
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct SentryIssue {
+    pub id: String,
+    pub short_id: Option<String>,
+    pub title: String,
+    pub culprit: Option<String>,
+    pub permalink: String,
+    pub exceptions: Vec<SentryException>,
+    pub breadcrumbs: Vec<Breadcrumb>,
+}
+
+/// Whether the integration can fetch issues and check webhook signatures
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct SentryIntegrationStatus {
+    pub auth_token_set: bool,
+    pub client_secret_set: bool,
+}
+
+#[derive(Debug, Clone, Deserialize, TS)]
+pub struct SetSentryCredentials {
+    pub auth_token: Option<String>,
+    pub client_secret: Option<String>,
+}
+
+/// Read the issue out of links like `https://acme.sentry.io/issues/123/`,
+/// `https://sentry.io/organizations/acme/issues/123/?project=4` or the same path on a
+/// self-hosted server
+pub fn parse_issue_link(link: &str) -> Result<IssueRef, SentryIssueError> {
+    let invalid = || SentryIssueError::InvalidLink(link.to_string());
+    let link = link.trim();
+    let (scheme, rest) = link.split_once("://").ok_or_else(invalid)?;
+    if scheme != "https" && scheme != "http" {
+        return Err(invalid());
+    }
+    let rest = rest.split(['?', '#']).next().unwrap_or_default();
+    let (host, path) = rest.split_once('/').ok_or_else(invalid)?;
+    let segments: Vec<&str> = path.split('/').filter(|s| !s.is_empty()).collect();
+    let issue_id = segments
+        .iter()
+        .position(|s| *s == "issues")
+        .and_then(|i| segments.get(i + 1))
+        .filter(|id| id.chars().all(|c| c.is_ascii_digit()))
+        .ok_or_else(invalid)?;
+
+    // Organisation subdomains share the API of their region, `acme.de.sentry.io` that of
+    // `de.sentry.io` and `acme.sentry.io` that of sentry.io itself
+    let host = match host.strip_suffix(".sentry.io") {
+        Some("us" | "de") | None => host.to_string(),
+        Some(org) => match org.split_once('.') {
+            Some((_, region)) => format!("{region}.sentry.io"),
+            None => "sentry.io".to_string(),
+        },
+    };
+    Ok(IssueRef {
+        base_url: format!("{scheme}://{host}"),
+        issue_id: issue_id.to_string(),
+    })
+}
+
+/// The issue a Sentry webhook is about, for issue and event alert webhooks
+pub fn issue_from_webhook(payload: &Value) -> Option<IssueRef> {
+    let data = payload.get("data")?;
+    let (id, url) = if let Some(issue) = data.get("issue") {
+        (
+            issue.get("id")?,
+            issue
+                .get("web_url")
+                .or_else(|| issue.get("permalink"))
+                .and_then(Value::as_str),
+        )
+    } else {
+        let event = data.get("event")?;
+        (
+            event.get("issue_id")?,
+            event.get("web_url").and_then(Value::as_str),
+        )
+    };
+    let issue_id = match id {
+        Value::String(id) => id.clone(),
+        Value::Number(id) => id.to_string(),
+        _ => return None,
+    };
+    let base_url = url
+        .and_then(|url| parse_issue_link(url).ok())
+        .map(|issue| issue.base_url)
+        .unwrap_or_else(|| DEFAULT_BASE_URL.to_string());
+    Some(IssueRef { base_url, issue_id })
+}
+
+/// Check the `Sentry-Hook-Signature` header, a hex HMAC-SHA256 of the body keyed with the
+/// integration's client secret
+pub fn verify_signature(client_secret: &str, body: &[u8], signature: &str) -> bool {
+    let Ok(expected) = (0..signature.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(signature.get(i..i + 2).unwrap_or("zz"), 16))
+        .collect::<Result<Vec<u8>, _>>()
+    else {
+        return false;
+    };
+    let mut mac = Hmac::<Sha256>::new_from_slice(client_secret.as_bytes())
+        .expect("HMAC takes keys of any length");
+    mac.update(body);
+    mac.verify_slice(&expected).is_ok()
+}
+
+pub fn auth_token(store: &SecretsStore) -> Result<Option<String>, SentryIssueError> {
+    if let Ok(token) = std::env::var(AUTH_TOKEN_ENV)
+        && !token.trim().is_empty()
+    {
+        return Ok(Some(token));
+    }
+    Ok(store.get(AUTH_TOKEN_KEY)?)
+}
+
+pub fn status(store: &SecretsStore) -> Result<SentryIntegrationStatus, SentryIssueError> {
+    Ok(SentryIntegrationStatus {
+        auth_token_set: auth_token(store)?.is_some(),
+        client_secret_set: store.get(CLIENT_SECRET_KEY)?.is_some(),
+    })
+}
+
+/// Store the credentials given, an empty one removes what was stored
+pub fn set_credentials(
+    store: &SecretsStore,
+    credentials: &SetSentryCredentials,
+) -> Result<SentryIntegrationStatus, SentryIssueError> {
+    for (key, value) in [
+        (AUTH_TOKEN_KEY, &credentials.auth_token),
+        (CLIENT_SECRET_KEY, &credentials.client_secret),
+    ] {
+        match value.as_deref().map(str::trim) {
+            Some("") => store.delete(key)?,
+            Some(value) => store.set(key, value)?,
+            None => {}
+        }
+    }
+    status(store)
+}
+
+async fn get_json(
+    client: &reqwest::Client,
+    token: &str,
+    url: &str,
+    issue_id: &str,
+) -> Result<Value, SentryIssueError> {
+    let response = client.get(url).bearer_auth(token).send().await?;
+    match response.status() {
+        StatusCode::NOT_FOUND => Err(SentryIssueError::NotFound(issue_id.to_string())),
+        status if status.is_success() => Ok(response.json().await?),
+        status => Err(SentryIssueError::Api(status)),
+    }
+}
+
+fn str_field(value: &Value, key: &str) -> Option<String> {
+    value
+        .get(key)
+        .and_then(Value::as_str)
+        .filter(|s| !s.is_empty())
+        .map(str::to_string)
+}
+
+fn entry<'a>(event: &'a Value, kind: &str) -> Option<&'a Value> {
+    event
+        .get("entries")?
+        .as_array()?
+        .iter()
+        .find(|entry| entry.get("type").and_then(Value::as_str) == Some(kind))
+        .and_then(|entry| entry.get("data"))
+}
+
+fn parse_exceptions(event: &Value) -> Vec<SentryException> {
+    let Some(values) = entry(event, "exception")
+        .and_then(|data| data.get("values"))
+        .and_then(Value::as_array)
+    else {
+        return Vec::new();
+    };
+    values
+        .iter()
+        .map(|exception| {
+            let frames = exception
+                .get("stacktrace")
+                .and_then(|trace| trace.get("frames"))
+                .and_then(Value::as_array)
+                .map(|frames| {
+                    // Sentry lists the outermost frame first
+                    frames
+                        .iter()
+                        .rev()
+                        .filter_map(|frame| {
+                            Some(StackFrame {
+                                path: str_field(frame, "filename")
+                                    .or_else(|| str_field(frame, "absPath"))?,
+                                function: str_field(frame, "function"),
+                                line: frame.get("lineNo").and_then(Value::as_i64),
+                                in_app: frame
+                                    .get("inApp")
+                                    .and_then(Value::as_bool)
+                                    .unwrap_or(false),
+                            })
+                        })
+                        .collect()
+                })
+                .unwrap_or_default();
+            SentryException {
+                kind: str_field(exception, "type"),
+                value: str_field(exception, "value"),
+                frames,
+            }
+        })
+        .collect()
+}
+
+fn parse_breadcrumbs(event: &Value) -> Vec<Breadcrumb> {
+    let Some(values) = entry(event, "breadcrumbs")
+        .and_then(|data| data.get("values"))
+        .and_then(Value::as_array)
+    else {
+        return Vec::new();
+    };
+    let skip = values.len().saturating_sub(MAX_BREADCRUMBS);
+    values
+        .iter()
+        .skip(skip)
+        .map(|crumb| Breadcrumb {
+            timestamp: str_field(crumb, "timestamp"),
+            category: str_field(crumb, "category"),
+            level: str_field(crumb, "level"),
+            message: str_field(crumb, "message").or_else(|| {
+                crumb
+                    .get("data")
+                    .filter(|data| !data.is_null())
+                    .map(Value::to_string)
+            }),
+        })
+        .collect()
+}
+
+fn parse_issue(issue_ref: &IssueRef, issue: &Value, event: &Value) -> SentryIssue {
+    SentryIssue {
+        id: issue_ref.issue_id.clone(),
+        short_id: str_field(issue, "shortId"),
+        title: str_field(issue, "title").unwrap_or_else(|| "Sentry issue".to_string()),
+        culprit: str_field(issue, "culprit"),
+        permalink: str_field(issue, "permalink")
+            .unwrap_or_else(|| format!("{}/issues/{}/", issue_ref.base_url, issue_ref.issue_id)),
+        exceptions: parse_exceptions(event),
+        breadcrumbs: parse_breadcrumbs(event),
+    }
+}
+
+/// Fetch an issue and its latest event from the Sentry API
+pub async fn fetch_issue(
+    store: &SecretsStore,
+    issue_ref: &IssueRef,
+) -> Result<SentryIssue, SentryIssueError> {
+    let token = auth_token(store)?.ok_or(SentryIssueError::NoToken)?;
+    let client = reqwest::Client::builder()
+        .timeout(REQUEST_TIMEOUT)
+        .build()?;
+    let issue_url = format!(
+        "{}/api/0/issues/{}/",
+        issue_ref.base_url, issue_ref.issue_id
+    );
+    let issue = get_json(&client, &token, &issue_url, &issue_ref.issue_id).await?;
+    let event = get_json(
+        &client,
+        &token,
+        &format!("{issue_url}events/latest/"),
+        &issue_ref.issue_id,
+    )
+    .await?;
+    Ok(parse_issue(issue_ref, &issue, &event))
+}
+
+/// Task title for an issue, e.g. `Fix PROJ-1A: TypeError: x is undefined`
+pub fn task_title(issue: &SentryIssue) -> String {
+    match &issue.short_id {
+        Some(short_id) => format!("Fix {short_id}: {}", issue.title),
+        None => format!("Fix {}", issue.title),
+    }
+}
+
+/// Task description giving an agent the error, where it was raised and what led to it
+pub fn task_prompt(issue: &SentryIssue) -> String {
+    let mut prompt = format!(
+        "Fix the error reported in Sentry: {}\n\n{}\n",
+        issue.permalink, issue.title
+    );
+    if let Some(culprit) = &issue.culprit {
+        prompt.push_str(&format!("Culprit: {culprit}\n"));
+    }
+    for exception in &issue.exceptions {
+        let kind = exception.kind.as_deref().unwrap_or("Exception");
+        match &exception.value {
+            Some(value) => prompt.push_str(&format!("\n{kind}: {value}\n")),
+            None => prompt.push_str(&format!("\n{kind}\n")),
+        }
+        for frame in exception.frames.iter().take(MAX_FRAMES) {
+            let location = match frame.line {
+                Some(line) => format!("{}:{line}", frame.path),
+                None => frame.path.clone(),
+            };
+            let function = frame.function.as_deref().unwrap_or("?");
+            let library = if frame.in_app { "" } else { " (library)" };
+            prompt.push_str(&format!("  at {function} ({location}){library}\n"));
+        }
+    }
+    if !issue.breadcrumbs.is_empty() {
+        prompt.push_str("\nBreadcrumbs, oldest first:\n");
+        for crumb in &issue.breadcrumbs {
+            let fields: Vec<&str> = [&crumb.timestamp, &crumb.level, &crumb.category]
+                .into_iter()
+                .filter_map(|field| field.as_deref())
+                .collect();
+            prompt.push_str(&format!(
+                "- [{}] {}\n",
+                fields.join(" "),
+                crumb.message.as_deref().unwrap_or("")
+            ));
+        }
+    }
+    prompt.push_str(
+        "\nFind the root cause in this repository, fix it and add a test that reproduces the error if practical.\n",
+    );
+    prompt
+}
+
+/// Path of a frame relative to wherever the app was deployed, e.g. without `webpack:///`
+fn frame_components(path: &str) -> Vec<&str> {
+    let path = path.split_once("://").map_or(path, |(_, rest)| rest);
+    path.split(['/', '\\'])
+        .filter(|c| !c.is_empty() && *c != "." && *c != "~")
+        .collect()
+}
+
+/// Components of the longest tail of `path` that is a file in `repo`
+fn matched_components(repo: &Path, path: &str) -> usize {
+    let components = frame_components(path);
+    (0..components.len())
+        .find(|&start| {
+            repo.join(components[start..].iter().collect::<PathBuf>())
+                .is_file()
+        })
+        .map_or(0, |start| components.len() - start)
+}
+
+/// The project whose repository holds the most of the issue's application frames, weighed
+/// by how much of each frame's path matched
+pub fn match_project<'a>(projects: &'a [Project], issue: &SentryIssue) -> Option<&'a Project> {
+    let frames: Vec<&StackFrame> = issue
+        .exceptions
+        .iter()
+        .flat_map(|exception| &exception.frames)
+        .filter(|frame| frame.in_app)
+        .collect();
+    projects
+        .iter()
+        .map(|project| {
+            let score: usize = frames
+                .iter()
+                .map(|frame| matched_components(&project.git_repo_path, &frame.path))
+                .sum();
+            (project, score)
+        })
+        .filter(|(_, score)| *score > 0)
+        .max_by_key(|(_, score)| *score)
+        .map(|(project, _)| project)
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+    use serde_json::json;
+    use uuid::Uuid;
+
+    use super::*;
+
+    fn project(name: &str, path: &Path) -> Project {
+        Project {
+            id: Uuid::new_v4(),
+            name: name.to_string(),
+            git_repo_path: path.to_path_buf(),
+            setup_script: None,
+            dev_script: None,
+            cleanup_script: None,
+            copy_files: None,
+            test_script: None,
+            test_result_format: None,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn parses_issue_links() {
+        let sentry_io = IssueRef {
+            base_url: "https://sentry.io".to_string(),
+            issue_id: "4512".to_string(),
+        };
+        assert_eq!(
+            parse_issue_link("https://acme.sentry.io/issues/4512/?project=7").unwrap(),
+            sentry_io
+        );
+        assert_eq!(
+            parse_issue_link("https://sentry.io/organizations/acme/issues/4512/").unwrap(),
+            sentry_io
+        );
+        assert_eq!(
+            parse_issue_link("http://sentry.internal:9000/organizations/acme/issues/9/")
+                .unwrap()
+                .base_url,
+            "http://sentry.internal:9000"
+        );
+        assert_eq!(
+            parse_issue_link("https://acme.de.sentry.io/issues/4512/")
+                .unwrap()
+                .base_url,
+            "https://de.sentry.io"
+        );
+        assert!(parse_issue_link("https://acme.sentry.io/issues/").is_err());
+        assert!(parse_issue_link("acme.sentry.io/issues/4512").is_err());
+    }
+
+    #[test]
+    fn reads_the_issue_of_a_webhook() {
+        let issue = json!({"action": "created", "data": {"issue": {
+            "id": "77", "web_url": "https://acme.sentry.io/issues/77/"
+        }}});
+        assert_eq!(
+            issue_from_webhook(&issue),
+            Some(IssueRef {
+                base_url: "https://sentry.io".to_string(),
+                issue_id: "77".to_string()
+            })
+        );
+        let alert = json!({"action": "triggered", "data": {"event": {"issue_id": 78}}});
+        assert_eq!(issue_from_webhook(&alert).unwrap().issue_id, "78");
+        assert_eq!(issue_from_webhook(&json!({"data": {}})), None);
+    }
+
+    #[test]
+    fn verifies_signatures() {
+        // HMAC-SHA256 test vector from RFC 4231
+        let signature = "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843";
+        assert!(verify_signature(
+            "Jefe",
+            b"what do ya want for nothing?",
+            signature
+        ));
+        assert!(!verify_signature("Jefe", b"something else", signature));
+        assert!(!verify_signature(
+            "Jefe",
+            b"what do ya want for nothing?",
+            "zz"
+        ));
+    }
+
+    #[test]
+    fn builds_a_prompt_from_the_latest_event() {
+        let issue_ref = parse_issue_link("https://acme.sentry.io/issues/5/").unwrap();
+        let event = json!({"entries": [
+            {"type": "exception", "data": {"values": [{
+                "type": "TypeError",
+                "value": "x is undefined",
+                "stacktrace": {"frames": [
+                    {"filename": "node_modules/react/index.js", "function": "render", "inApp": false},
+                    {"filename": "./src/cart.ts", "function": "total", "lineNo": 12, "inApp": true}
+                ]}
+            }]}},
+            {"type": "breadcrumbs", "data": {"values": [
+                {"timestamp": "2025-09-01T10:00:00Z", "category": "ui.click", "message": "button#checkout"}
+            ]}}
+        ]});
+        let issue = parse_issue(
+            &issue_ref,
+            &json!({"shortId": "SHOP-5", "title": "TypeError: x is undefined"}),
+            &event,
+        );
+        assert_eq!(task_title(&issue), "Fix SHOP-5: TypeError: x is undefined");
+        assert_eq!(issue.exceptions[0].frames[0].path, "./src/cart.ts");
+
+        let prompt = task_prompt(&issue);
+        assert!(prompt.contains("https://sentry.io/issues/5/"));
+        assert!(prompt.contains("  at total (./src/cart.ts:12)\n"));
+        assert!(prompt.contains("  at render (node_modules/react/index.js) (library)\n"));
+        assert!(prompt.contains("- [2025-09-01T10:00:00Z ui.click] button#checkout\n"));
+    }
+
+    #[test]
+    fn matches_the_project_holding_the_frames() {
+        let shop = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(shop.path().join("src")).unwrap();
+        std::fs::write(shop.path().join("src/cart.ts"), "").unwrap();
+        let blog = tempfile::tempdir().unwrap();
+        std::fs::write(blog.path().join("cart.ts"), "").unwrap();
+        let projects = vec![project("blog", blog.path()), project("shop", shop.path())];
+
+        let issue = SentryIssue {
+            id: "5".to_string(),
+            short_id: None,
+            title: "TypeError".to_string(),
+            culprit: None,
+            permalink: String::new(),
+            exceptions: vec![SentryException {
+                kind: None,
+                value: None,
+                frames: vec![StackFrame {
+                    path: "webpack:///./src/cart.ts".to_string(),
+                    function: None,
+                    line: None,
+                    in_app: true,
+                }],
+            }],
+            breadcrumbs: Vec::new(),
+        };
+        assert_eq!(match_project(&projects, &issue).unwrap().name, "shop");
+        assert!(
+            match_project(
+                &projects[..1],
+                &SentryIssue {
+                    exceptions: Vec::new(),
+                    ..issue
+                }
+            )
+            .is_none()
+        );
+    }
+}
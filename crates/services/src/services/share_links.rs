@@ -0,0 +1,127 @@
+use chrono::{DateTime, Duration, Utc};
+use db::models::{
+    attempt_share_link::{AttemptShareLink, CreateAttemptShareLink},
+    execution_process::{ExecutionProcessRunReason, ExecutionProcessStatus},
+};
+use executors::logs::NormalizedEntry;
+use serde::Serialize;
+use sqlx::SqlitePool;
+use thiserror::Error;
+use ts_rs::TS;
+use utils::diff::Diff;
+use uuid::Uuid;
+
+use crate::services::secret_scan;
+
+/// Longest a share link can stay valid for, 90 days
+pub const MAX_EXPIRY_HOURS: u32 = 24 * 90;
+
+#[derive(Debug, Error)]
+pub enum ShareLinkError {
+    #[error(transparent)]
+    Database(#[from] sqlx::Error),
+    #[error(transparent)]
+    Serde(#[from] serde_json::Error),
+    #[error("Share link not found, it may have been revoked")]
+    NotFound,
+    #[error("Share link expired")]
+    Expired,
+    #[error("Share links expire after 1 to {MAX_EXPIRY_HOURS} hours")]
+    InvalidExpiry,
+}
+
+/// One agent run of a shared attempt
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct SharedProcess {
+    pub run_reason: ExecutionProcessRunReason,
+    pub status: ExecutionProcessStatus,
+    pub started_at: DateTime<Utc>,
+    pub completed_at: Option<DateTime<Utc>>,
+    pub entries: Vec<NormalizedEntry>,
+}
+
+/// What a share link shows: the task, the agents' conversations and the diff, secrets
+/// masked. Nothing in it leads back to the board or paths on the machine.
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct SharedAttempt {
+    pub task_title: String,
+    pub task_description: Option<String>,
+    pub profile: String,
+    pub branch: Option<String>,
+    pub base_branch: String,
+    pub merged: bool,
+    pub created_at: DateTime<Utc>,
+    /// When the link stops working, `None` until it is revoked
+    pub expires_at: Option<DateTime<Utc>>,
+    pub processes: Vec<SharedProcess>,
+    pub diffs: Vec<Diff>,
+}
+
+impl SharedAttempt {
+    /// Mask secrets in the conversations and the diff before they leave the machine
+    pub fn redacted(mut self) -> Result<Self, ShareLinkError> {
+        for process in &mut self.processes {
+            for entry in &mut process.entries {
+                // Raw executor output, never shown in the conversation
+                entry.metadata = None;
+            }
+            process.entries = secret_scan::redact_json(&process.entries)?;
+        }
+        self.diffs = secret_scan::redact_json(&self.diffs)?;
+        Ok(self)
+    }
+}
+
+fn expires_at(
+    expires_in_hours: Option<u32>,
+    now: DateTime<Utc>,
+) -> Result<Option<DateTime<Utc>>, ShareLinkError> {
+    match expires_in_hours {
+        None => Ok(None),
+        Some(hours) if hours == 0 || hours > MAX_EXPIRY_HOURS => Err(ShareLinkError::InvalidExpiry),
+        Some(hours) => Ok(Some(now + Duration::hours(hours.into()))),
+    }
+}
+
+pub async fn create(
+    pool: &SqlitePool,
+    task_attempt_id: Uuid,
+    payload: &CreateAttemptShareLink,
+) -> Result<AttemptShareLink, ShareLinkError> {
+    let expires_at = expires_at(payload.expires_in_hours, Utc::now())?;
+    Ok(AttemptShareLink::create(pool, task_attempt_id, expires_at).await?)
+}
+
+/// The link a token belongs to, as long as it hasn't expired
+pub async fn resolve(pool: &SqlitePool, token: &str) -> Result<AttemptShareLink, ShareLinkError> {
+    let link = AttemptShareLink::find_by_token(pool, token)
+        .await?
+        .ok_or(ShareLinkError::NotFound)?;
+    if link.is_expired(Utc::now()) {
+        return Err(ShareLinkError::Expired);
+    }
+    Ok(link)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_expiry_is_bounded() {
+        let now = Utc::now();
+        assert_eq!(expires_at(None, now).unwrap(), None);
+        assert_eq!(
+            expires_at(Some(24), now).unwrap(),
+            Some(now + Duration::hours(24))
+        );
+        assert!(matches!(
+            expires_at(Some(0), now),
+            Err(ShareLinkError::InvalidExpiry)
+        ));
+        assert!(matches!(
+            expires_at(Some(MAX_EXPIRY_HOURS + 1), now),
+            Err(ShareLinkError::InvalidExpiry)
+        ));
+    }
+}
@@ -0,0 +1,64 @@
+use std::sync::{
+    Arc,
+    atomic::{AtomicBool, Ordering},
+};
+
+use futures::{StreamExt, stream::BoxStream};
+use tokio_util::sync::CancellationToken;
+
+/// Shared shutdown state, used to stop accepting new work and to end long-lived streams
+/// once the server starts shutting down.
+#[derive(Clone, Default)]
+pub struct ShutdownService {
+    draining: Arc<AtomicBool>,
+    streams: CancellationToken,
+    /// Cancelled when the app asks the server to stop, e.g. to update
+    requested: CancellationToken,
+    restart_for_update: Arc<AtomicBool>,
+}
+
+impl ShutdownService {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Stop accepting new attempts, follow-ups and dev servers
+    pub fn begin_draining(&self) {
+        self.draining.store(true, Ordering::SeqCst);
+    }
+
+    pub fn is_draining(&self) -> bool {
+        self.draining.load(Ordering::SeqCst)
+    }
+
+    /// End every stream wrapped with [`ShutdownService::guard_stream`]
+    pub fn cancel_streams(&self) {
+        self.streams.cancel();
+    }
+
+    /// Ends `stream` when [`ShutdownService::cancel_streams`] is called, so open SSE
+    /// connections don't hold the server open during shutdown.
+    pub fn guard_stream<T>(&self, stream: BoxStream<'static, T>) -> BoxStream<'static, T>
+    where
+        T: Send + 'static,
+    {
+        stream
+            .take_until(self.streams.clone().cancelled_owned())
+            .boxed()
+    }
+
+    /// Shut the server down so the npx wrapper can start the latest version
+    pub fn request_restart_for_update(&self) {
+        self.restart_for_update.store(true, Ordering::SeqCst);
+        self.requested.cancel();
+    }
+
+    pub fn restart_for_update_requested(&self) -> bool {
+        self.restart_for_update.load(Ordering::SeqCst)
+    }
+
+    /// Resolves once the app has asked the server to shut down
+    pub async fn requested(&self) {
+        self.requested.cancelled().await
+    }
+}
@@ -0,0 +1,369 @@
+use std::{
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use tokio::io::AsyncReadExt;
+use ts_rs::TS;
+use utils::{
+    assets::{SoundAssets, asset_dir},
+    magic::magic_at,
+};
+
+use crate::services::config::SoundFile;
+
+/// Largest custom sound accepted, notification sounds are a few seconds long
+pub const MAX_SOUND_BYTES: usize = 5 * 1024 * 1024;
+
+#[derive(Debug, Error)]
+pub enum SoundError {
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    #[error("Sound not found: {0}")]
+    NotFound(String),
+    #[error("Invalid sound name: {0}")]
+    InvalidName(String),
+    #[error("Unsupported audio format, expected WAV, MP3, OGG, FLAC or M4A")]
+    UnsupportedFormat,
+    #[error("Sound file is larger than {} MB", MAX_SOUND_BYTES / 1024 / 1024)]
+    TooLarge,
+}
+
+/// Audio formats the frontend and the platform players can both play
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AudioFormat {
+    Wav,
+    Mp3,
+    Ogg,
+    Flac,
+    M4a,
+}
+
+impl AudioFormat {
+    /// The audio format of an uploaded file, `None` if it isn't one we can play
+    pub fn detect(data: &[u8]) -> Option<Self> {
+        if magic_at(data, 0, b"RIFF") && magic_at(data, 8, b"WAVE") {
+            Some(Self::Wav)
+        } else if magic_at(data, 0, b"ID3")
+            || matches!(data, [0xFF, second, ..] if second & 0xE0 == 0xE0)
+        {
+            // A tagged file, or an untagged one starting with an MPEG frame sync
+            Some(Self::Mp3)
+        } else if magic_at(data, 0, b"OggS") {
+            Some(Self::Ogg)
+        } else if magic_at(data, 0, b"fLaC") {
+            Some(Self::Flac)
+        } else if magic_at(data, 4, b"ftyp") {
+            Some(Self::M4a)
+        } else {
+            None
+        }
+    }
+
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            Self::Wav => "audio/wav",
+            Self::Mp3 => "audio/mpeg",
+            Self::Ogg => "audio/ogg",
+            Self::Flac => "audio/flac",
+            Self::M4a => "audio/mp4",
+        }
+    }
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Self::Wav => "wav",
+            Self::Mp3 => "mp3",
+            Self::Ogg => "ogg",
+            Self::Flac => "flac",
+            Self::M4a => "m4a",
+        }
+    }
+}
+
+/// A sound id from the config: a `SoundFile` name, or the file name of a custom sound.
+/// Custom names always carry an extension so the two can't collide.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Sound {
+    Builtin(SoundFile),
+    Custom(String),
+}
+
+impl Sound {
+    pub fn parse(id: &str) -> Result<Self, SoundError> {
+        if let Ok(sound) = SoundFile::from_str(id) {
+            return Ok(Self::Builtin(sound));
+        }
+        if id.is_empty()
+            || id.starts_with('.')
+            || id.contains(['/', '\\'])
+            || Path::new(id).extension().is_none()
+        {
+            return Err(SoundError::InvalidName(id.to_string()));
+        }
+        Ok(Self::Custom(id.to_string()))
+    }
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct CustomSound {
+    pub name: String,
+    pub content_type: String,
+    #[ts(type = "number")]
+    pub size_bytes: u64,
+}
+
+#[derive(Debug, Deserialize, TS)]
+pub struct ImportSoundRequest {
+    /// Audio file on the server's machine, copied into the data directory
+    pub path: String,
+}
+
+/// Sound data ready to serve
+pub struct SoundData {
+    pub data: Vec<u8>,
+    pub content_type: &'static str,
+}
+
+/// User-provided notification sounds, kept in `sounds/` under the data directory
+#[derive(Debug, Clone)]
+pub struct SoundLibrary {
+    dir: PathBuf,
+}
+
+impl Default for SoundLibrary {
+    fn default() -> Self {
+        Self::new(asset_dir().join("sounds"))
+    }
+}
+
+impl SoundLibrary {
+    pub fn new(dir: PathBuf) -> Self {
+        Self { dir }
+    }
+
+    /// Custom sounds sorted by name
+    pub async fn list(&self) -> Result<Vec<CustomSound>, SoundError> {
+        if !self.dir.exists() {
+            return Ok(Vec::new());
+        }
+        let mut sounds = Vec::new();
+        let mut entries = tokio::fs::read_dir(&self.dir).await?;
+        while let Some(entry) = entries.next_entry().await? {
+            let Some(name) = entry.file_name().to_str().map(str::to_string) else {
+                continue;
+            };
+            if !entry.file_type().await?.is_file() || Sound::parse(&name).is_err() {
+                continue;
+            }
+            let mut header = [0u8; 12];
+            let read = tokio::fs::File::open(entry.path())
+                .await?
+                .read(&mut header)
+                .await?;
+            if let Some(format) = AudioFormat::detect(&header[..read]) {
+                sounds.push(CustomSound {
+                    name,
+                    content_type: format.content_type().to_string(),
+                    size_bytes: entry.metadata().await?.len(),
+                });
+            }
+        }
+        sounds.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(sounds)
+    }
+
+    /// Store an uploaded sound under a name derived from `file_name`, replacing any sound
+    /// with the same name. The extension comes from the detected format.
+    pub async fn save(&self, file_name: &str, data: &[u8]) -> Result<CustomSound, SoundError> {
+        if data.len() > MAX_SOUND_BYTES {
+            return Err(SoundError::TooLarge);
+        }
+        let format = AudioFormat::detect(data).ok_or(SoundError::UnsupportedFormat)?;
+        let name = format!("{}.{}", Self::sanitize_stem(file_name)?, format.extension());
+
+        tokio::fs::create_dir_all(&self.dir).await?;
+        tokio::fs::write(self.dir.join(&name), data).await?;
+        Ok(CustomSound {
+            name,
+            content_type: format.content_type().to_string(),
+            size_bytes: data.len() as u64,
+        })
+    }
+
+    /// Copy an audio file from elsewhere on disk into the library
+    pub async fn import(&self, path: &Path) -> Result<CustomSound, SoundError> {
+        if tokio::fs::metadata(path).await?.len() > MAX_SOUND_BYTES as u64 {
+            return Err(SoundError::TooLarge);
+        }
+        let data = tokio::fs::read(path).await?;
+        let file_name = path
+            .file_name()
+            .and_then(|n| n.to_str())
+            .ok_or_else(|| SoundError::InvalidName(path.display().to_string()))?;
+        self.save(file_name, &data).await
+    }
+
+    pub async fn delete(&self, name: &str) -> Result<(), SoundError> {
+        let path = self.custom_path(name)?;
+        tokio::fs::remove_file(path).await?;
+        Ok(())
+    }
+
+    /// Contents and content type of a built-in or custom sound
+    pub async fn load(&self, sound: &Sound) -> Result<SoundData, SoundError> {
+        let data = match sound {
+            Sound::Builtin(sound) => SoundAssets::get(sound.to_filename())
+                .ok_or_else(|| SoundError::NotFound(sound.to_filename().to_string()))?
+                .data
+                .into_owned(),
+            Sound::Custom(name) => tokio::fs::read(self.custom_path(name)?).await?,
+        };
+        let content_type = AudioFormat::detect(&data)
+            .map(|format| format.content_type())
+            .unwrap_or("application/octet-stream");
+        Ok(SoundData { data, content_type })
+    }
+
+    /// File a platform audio player can open for `sound`
+    pub async fn playback_path(&self, sound: &Sound) -> Result<PathBuf, SoundError> {
+        match sound {
+            Sound::Builtin(sound) => sound
+                .get_path()
+                .await
+                .map_err(|e| SoundError::Io(std::io::Error::other(e.to_string()))),
+            Sound::Custom(name) => self.custom_path(name),
+        }
+    }
+
+    /// Whether `sound` can be played, for validating config values
+    pub fn exists(&self, sound: &Sound) -> bool {
+        match sound {
+            Sound::Builtin(_) => true,
+            Sound::Custom(name) => self.custom_path(name).is_ok(),
+        }
+    }
+
+    fn custom_path(&self, name: &str) -> Result<PathBuf, SoundError> {
+        if !matches!(Sound::parse(name)?, Sound::Custom(_)) {
+            return Err(SoundError::InvalidName(name.to_string()));
+        }
+        let path = self.dir.join(name);
+        if !path.is_file() {
+            return Err(SoundError::NotFound(name.to_string()));
+        }
+        Ok(path)
+    }
+
+    /// File stem reduced to characters that are safe in a file name and a URL
+    fn sanitize_stem(file_name: &str) -> Result<String, SoundError> {
+        let stem = Path::new(file_name)
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .unwrap_or_default();
+        let sanitized: String = stem
+            .chars()
+            .map(|c| {
+                if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+                    c.to_ascii_lowercase()
+                } else {
+                    '-'
+                }
+            })
+            .collect();
+        let sanitized = sanitized.trim_matches('-');
+        if sanitized.is_empty() {
+            return Err(SoundError::InvalidName(file_name.to_string()));
+        }
+        Ok(sanitized.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const WAV_HEADER: &[u8] = b"RIFF\x24\x00\x00\x00WAVEfmt ";
+
+    #[test]
+    fn test_detect_audio_format() {
+        assert_eq!(AudioFormat::detect(WAV_HEADER), Some(AudioFormat::Wav));
+        assert_eq!(AudioFormat::detect(b"ID3\x04\x00"), Some(AudioFormat::Mp3));
+        assert_eq!(
+            AudioFormat::detect(&[0xFF, 0xFB, 0x90]),
+            Some(AudioFormat::Mp3)
+        );
+        assert_eq!(AudioFormat::detect(b"OggS\x00\x02"), Some(AudioFormat::Ogg));
+        assert_eq!(AudioFormat::detect(b"fLaC\x00"), Some(AudioFormat::Flac));
+        assert_eq!(
+            AudioFormat::detect(b"\x00\x00\x00\x20ftypM4A "),
+            Some(AudioFormat::M4a)
+        );
+        assert_eq!(AudioFormat::detect(b"<html>"), None);
+        assert_eq!(AudioFormat::detect(b""), None);
+    }
+
+    #[test]
+    fn test_parse_sound_ids() {
+        assert_eq!(
+            Sound::parse("COW_MOOING").unwrap(),
+            Sound::Builtin(SoundFile::CowMooing)
+        );
+        assert_eq!(
+            Sound::parse("chime.mp3").unwrap(),
+            Sound::Custom("chime.mp3".to_string())
+        );
+        for id in [
+            "",
+            "../config.json",
+            "sub/chime.wav",
+            ".hidden.wav",
+            "chime",
+        ] {
+            assert!(matches!(Sound::parse(id), Err(SoundError::InvalidName(_))));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_save_import_and_delete() {
+        let dir = tempfile::tempdir().unwrap();
+        let library = SoundLibrary::new(dir.path().join("sounds"));
+        assert!(library.list().await.unwrap().is_empty());
+
+        // The name is sanitized and the extension follows the content
+        let saved = library.save("My Chime!.mp3", WAV_HEADER).await.unwrap();
+        assert_eq!(saved.name, "my-chime.wav");
+        assert_eq!(saved.content_type, "audio/wav");
+        assert!(matches!(
+            library.save("page.wav", b"<html>").await,
+            Err(SoundError::UnsupportedFormat)
+        ));
+
+        let source = dir.path().join("bell.ogg");
+        std::fs::write(&source, b"OggS\x00\x02").unwrap();
+        library.import(&source).await.unwrap();
+
+        let names: Vec<_> = library
+            .list()
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|s| s.name)
+            .collect();
+        assert_eq!(names, ["bell.ogg", "my-chime.wav"]);
+
+        let sound = Sound::parse("bell.ogg").unwrap();
+        let loaded = library.load(&sound).await.unwrap();
+        assert_eq!(loaded.content_type, "audio/ogg");
+        assert!(library.exists(&sound));
+
+        library.delete("bell.ogg").await.unwrap();
+        assert!(!library.exists(&sound));
+        assert!(matches!(
+            library.delete("bell.ogg").await,
+            Err(SoundError::NotFound(_))
+        ));
+    }
+}
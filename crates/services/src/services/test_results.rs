@@ -0,0 +1,179 @@
+use db::models::{
+    project::{Project, TestResultFormat},
+    test_run::TestSummary,
+};
+use executors::actions::{
+    ExecutorAction, ExecutorActionType,
+    script::{ScriptContext, ScriptRequest, ScriptRequestLanguage},
+};
+use serde::Deserialize;
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum TestReportError {
+    #[error("No JUnit report found in the test output")]
+    NoJunitReport,
+    #[error("Invalid JUnit report: {0}")]
+    InvalidJunit(#[from] roxmltree::Error),
+    #[error("No test events found in the cargo JSON output")]
+    NoCargoEvents,
+}
+
+/// The project's test script as the last step of an execution chain, `None` without one
+pub fn test_action(project: &Project) -> Option<Box<ExecutorAction>> {
+    let script = project.test_script.as_ref()?;
+    if script.trim().is_empty() {
+        return None;
+    }
+    Some(Box::new(ExecutorAction::new(
+        ExecutorActionType::ScriptRequest(ScriptRequest {
+            script: script.clone(),
+            language: ScriptRequestLanguage::Bash,
+            context: ScriptContext::TestScript,
+        }),
+        None,
+    )))
+}
+
+/// Pass/fail counts from the stdout of a test script
+pub fn parse_test_output(
+    format: TestResultFormat,
+    stdout: &str,
+) -> Result<TestSummary, TestReportError> {
+    match format {
+        TestResultFormat::Junit => parse_junit(stdout),
+        TestResultFormat::CargoJson => parse_cargo_json(stdout),
+    }
+}
+
+/// The report may be surrounded by the runner's own output, e.g. with
+/// `pytest --junitxml=/dev/stdout`
+fn parse_junit(stdout: &str) -> Result<TestSummary, TestReportError> {
+    let start = ["<?xml", "<testsuites", "<testsuite"]
+        .iter()
+        .find_map(|tag| stdout.find(tag))
+        .ok_or(TestReportError::NoJunitReport)?;
+    let end = ["</testsuites>", "</testsuite>"]
+        .iter()
+        .find_map(|tag| stdout.rfind(tag).map(|i| i + tag.len()))
+        .filter(|end| *end > start)
+        .unwrap_or(stdout.len());
+    let doc = roxmltree::Document::parse(&stdout[start..end])?;
+
+    let mut summary = TestSummary::default();
+    for case in doc.descendants().filter(|n| n.has_tag_name("testcase")) {
+        let has_child = |name: &str| case.children().any(|c| c.has_tag_name(name));
+        if has_child("failure") || has_child("error") {
+            summary.failed += 1;
+            let name = case.attribute("name").unwrap_or_default();
+            summary
+                .failed_tests
+                .push(match case.attribute("classname") {
+                    Some(class) if !class.is_empty() => format!("{class}.{name}"),
+                    _ => name.to_string(),
+                });
+        } else if has_child("skipped") {
+            summary.skipped += 1;
+        } else {
+            summary.passed += 1;
+        }
+    }
+    Ok(summary)
+}
+
+#[derive(Deserialize)]
+struct CargoTestEvent {
+    #[serde(rename = "type")]
+    typ: String,
+    event: String,
+    name: Option<String>,
+}
+
+/// libtest prints one JSON event per line, other lines are cargo's own output
+fn parse_cargo_json(stdout: &str) -> Result<TestSummary, TestReportError> {
+    let mut summary = TestSummary::default();
+    let mut found = false;
+    for event in stdout
+        .lines()
+        .filter_map(|line| serde_json::from_str::<CargoTestEvent>(line.trim()).ok())
+    {
+        found = true;
+        if event.typ != "test" {
+            continue;
+        }
+        match event.event.as_str() {
+            "ok" => summary.passed += 1,
+            "failed" | "timeout" => {
+                summary.failed += 1;
+                summary.failed_tests.extend(event.name);
+            }
+            "ignored" => summary.skipped += 1,
+            _ => {}
+        }
+    }
+    if found {
+        Ok(summary)
+    } else {
+        Err(TestReportError::NoCargoEvents)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_junit_inside_runner_output() {
+        let stdout = r#"============ test session starts ============
+<?xml version="1.0" encoding="utf-8"?>
+<testsuites><testsuite name="pytest" tests="4">
+<testcase classname="tests.test_api" name="test_list" time="0.1"/>
+<testcase classname="tests.test_api" name="test_create" time="0.1"><failure message="assert 1 == 2"/></testcase>
+<testcase classname="tests.test_api" name="test_delete" time="0.1"><skipped/></testcase>
+<testcase classname="" name="test_io" time="0.1"><error message="boom"/></testcase>
+</testsuite></testsuites>
+============ 1 failed, 1 passed in 0.40s ============"#;
+
+        let summary = parse_test_output(TestResultFormat::Junit, stdout).unwrap();
+        assert_eq!(
+            summary,
+            TestSummary {
+                passed: 1,
+                failed: 2,
+                skipped: 1,
+                failed_tests: vec!["tests.test_api.test_create".into(), "test_io".into()],
+            }
+        );
+        assert!(matches!(
+            parse_test_output(TestResultFormat::Junit, "all good"),
+            Err(TestReportError::NoJunitReport)
+        ));
+    }
+
+    #[test]
+    fn test_parse_cargo_json_events() {
+        let stdout = r#"   Compiling app v0.1.0
+{ "type": "suite", "event": "started", "test_count": 4 }
+{ "type": "test", "event": "started", "name": "tests::adds" }
+{ "type": "test", "name": "tests::adds", "event": "ok" }
+{ "type": "test", "name": "tests::subtracts", "event": "failed", "stdout": "panicked" }
+{ "type": "test", "name": "tests::slow", "event": "ignored" }
+{ "type": "test", "name": "tests::divides", "event": "ok" }
+{ "type": "suite", "event": "failed", "passed": 2, "failed": 1, "ignored": 1 }"#;
+
+        let summary = parse_test_output(TestResultFormat::CargoJson, stdout).unwrap();
+        assert_eq!(
+            summary,
+            TestSummary {
+                passed: 2,
+                failed: 1,
+                skipped: 1,
+                failed_tests: vec!["tests::subtracts".into()],
+            }
+        );
+        assert!(matches!(
+            parse_test_output(TestResultFormat::CargoJson, "error: no tests"),
+            Err(TestReportError::NoCargoEvents)
+        ));
+    }
+}
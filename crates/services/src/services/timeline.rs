@@ -0,0 +1,269 @@
+use chrono::{DateTime, Utc};
+use db::models::{
+    execution_pause::ExecutionPause,
+    execution_process::{ExecutionProcess, ExecutionProcessRunReason, ExecutionProcessStatus},
+};
+use executors::actions::{ExecutorActionType, script::ScriptContext};
+use serde::Serialize;
+use ts_rs::TS;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, TS)]
+#[serde(rename_all = "snake_case")]
+pub enum TimelinePhaseKind {
+    /// From the attempt's creation until its first execution started
+    Queued,
+    Setup,
+    AgentRun,
+    /// Verification, test and lint stages
+    Verification,
+    Review,
+    Cleanup,
+}
+
+/// One phase of an attempt, each execution but the queued phase is its own phase
+#[derive(Debug, Clone, PartialEq, Serialize, TS)]
+pub struct TimelinePhase {
+    pub kind: TimelinePhaseKind,
+    /// `None` for the queued phase
+    pub execution_process_id: Option<Uuid>,
+    pub status: Option<ExecutionProcessStatus>,
+    pub started_at: DateTime<Utc>,
+    /// `None` while the phase is still going on
+    pub ended_at: Option<DateTime<Utc>>,
+    /// Up to now for a phase still going on, paused time included
+    #[ts(type = "number")]
+    pub duration_ms: i64,
+    #[ts(type = "number")]
+    pub paused_ms: i64,
+}
+
+/// Phases of an attempt in the order they started, dev servers left out
+#[derive(Debug, Clone, PartialEq, Serialize, TS)]
+pub struct AttemptTimeline {
+    pub started_at: DateTime<Utc>,
+    pub phases: Vec<TimelinePhase>,
+    /// From the attempt's creation until its last phase ended, or now while one is going on
+    #[ts(type = "number")]
+    pub total_ms: i64,
+}
+
+impl AttemptTimeline {
+    pub fn compute(
+        created_at: DateTime<Utc>,
+        processes: &[ExecutionProcess],
+        pauses: &[ExecutionPause],
+        now: DateTime<Utc>,
+    ) -> Self {
+        let mut processes: Vec<(TimelinePhaseKind, &ExecutionProcess)> = processes
+            .iter()
+            .filter_map(|process| phase_kind(process).map(|kind| (kind, process)))
+            .collect();
+        processes.sort_by_key(|(_, process)| process.started_at);
+
+        let queued_until = processes.first().map(|(_, process)| process.started_at);
+        let mut phases = vec![TimelinePhase {
+            kind: TimelinePhaseKind::Queued,
+            execution_process_id: None,
+            status: None,
+            started_at: created_at,
+            ended_at: queued_until,
+            duration_ms: (queued_until.unwrap_or(now) - created_at)
+                .num_milliseconds()
+                .max(0),
+            paused_ms: 0,
+        }];
+        for (kind, process) in processes {
+            let end = process.completed_at.unwrap_or(now);
+            phases.push(TimelinePhase {
+                kind,
+                execution_process_id: Some(process.id),
+                status: Some(process.status.clone()),
+                started_at: process.started_at,
+                ended_at: process.completed_at,
+                duration_ms: (end - process.started_at).num_milliseconds().max(0),
+                paused_ms: ExecutionPause::paused_ms(process, pauses, end),
+            });
+        }
+
+        let ended_at = if phases.iter().any(|phase| phase.ended_at.is_none()) {
+            now
+        } else {
+            phases
+                .iter()
+                .filter_map(|phase| phase.ended_at)
+                .max()
+                .unwrap_or(now)
+        };
+        Self {
+            started_at: created_at,
+            phases,
+            total_ms: (ended_at - created_at).num_milliseconds().max(0),
+        }
+    }
+}
+
+/// `None` for dev servers, which run alongside the attempt rather than as a phase of it
+fn phase_kind(process: &ExecutionProcess) -> Option<TimelinePhaseKind> {
+    match process.run_reason {
+        ExecutionProcessRunReason::SetupScript => Some(TimelinePhaseKind::Setup),
+        ExecutionProcessRunReason::CodingAgent => Some(TimelinePhaseKind::AgentRun),
+        ExecutionProcessRunReason::Review => Some(TimelinePhaseKind::Review),
+        // Commit planning tidies up the attempt's changes once they're done
+        ExecutionProcessRunReason::CommitPlan => Some(TimelinePhaseKind::Cleanup),
+        ExecutionProcessRunReason::DevServer => None,
+        // Verification stages run with the cleanup run reason
+        ExecutionProcessRunReason::CleanupScript => {
+            let verifies = process.executor_action().is_ok_and(|action| {
+                matches!(
+                    action.typ(),
+                    ExecutorActionType::ScriptRequest(script) if matches!(
+                        script.context,
+                        ScriptContext::VerificationScript
+                            | ScriptContext::TestScript
+                            | ScriptContext::LintScript
+                    )
+                )
+            });
+            Some(if verifies {
+                TimelinePhaseKind::Verification
+            } else {
+                TimelinePhaseKind::Cleanup
+            })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Duration;
+    use db::models::execution_process::ExecutorActionField;
+    use executors::actions::{
+        ExecutorAction,
+        script::{ScriptRequest, ScriptRequestLanguage},
+    };
+    use sqlx::types::Json;
+
+    use super::*;
+
+    fn process(
+        run_reason: ExecutionProcessRunReason,
+        context: ScriptContext,
+        started_at: DateTime<Utc>,
+        completed_at: Option<DateTime<Utc>>,
+    ) -> ExecutionProcess {
+        let action = ExecutorAction::new(
+            ExecutorActionType::ScriptRequest(ScriptRequest {
+                script: "true".to_string(),
+                language: ScriptRequestLanguage::Bash,
+                context,
+            }),
+            None,
+        );
+        ExecutionProcess {
+            id: Uuid::new_v4(),
+            task_attempt_id: Uuid::new_v4(),
+            run_reason,
+            executor_action: Json(ExecutorActionField::ExecutorAction(action)),
+            status: if completed_at.is_some() {
+                ExecutionProcessStatus::Completed
+            } else {
+                ExecutionProcessStatus::Running
+            },
+            exit_code: None,
+            started_at,
+            completed_at,
+            created_at: started_at,
+            updated_at: started_at,
+        }
+    }
+
+    #[test]
+    fn test_timeline_orders_phases_and_skips_dev_servers() {
+        let t0 = Utc::now();
+        let min = Duration::minutes(1);
+        let tests = process(
+            ExecutionProcessRunReason::CleanupScript,
+            ScriptContext::TestScript,
+            t0 + min * 6,
+            Some(t0 + min * 7),
+        );
+        let setup = process(
+            ExecutionProcessRunReason::SetupScript,
+            ScriptContext::SetupScript,
+            t0 + min,
+            Some(t0 + min * 2),
+        );
+        let agent = process(
+            ExecutionProcessRunReason::CodingAgent,
+            ScriptContext::SetupScript,
+            t0 + min * 2,
+            Some(t0 + min * 6),
+        );
+        let dev = process(
+            ExecutionProcessRunReason::DevServer,
+            ScriptContext::DevServer,
+            t0,
+            None,
+        );
+        let cleanup = process(
+            ExecutionProcessRunReason::CleanupScript,
+            ScriptContext::CleanupScript,
+            t0 + min * 7,
+            Some(t0 + min * 8),
+        );
+        let pauses = [ExecutionPause {
+            id: Uuid::new_v4(),
+            execution_process_id: agent.id,
+            task_attempt_id: agent.task_attempt_id,
+            paused_at: t0 + min * 3,
+            resumed_at: Some(t0 + min * 4),
+        }];
+
+        let timeline = AttemptTimeline::compute(
+            t0,
+            &[tests, setup, agent.clone(), dev, cleanup],
+            &pauses,
+            t0 + min * 20,
+        );
+        assert_eq!(
+            timeline
+                .phases
+                .iter()
+                .map(|phase| (phase.kind, phase.duration_ms))
+                .collect::<Vec<_>>(),
+            vec![
+                (TimelinePhaseKind::Queued, min.num_milliseconds()),
+                (TimelinePhaseKind::Setup, min.num_milliseconds()),
+                (TimelinePhaseKind::AgentRun, (min * 4).num_milliseconds()),
+                (TimelinePhaseKind::Verification, min.num_milliseconds()),
+                (TimelinePhaseKind::Cleanup, min.num_milliseconds()),
+            ]
+        );
+        assert_eq!(timeline.phases[2].execution_process_id, Some(agent.id));
+        assert_eq!(timeline.phases[2].paused_ms, min.num_milliseconds());
+        assert_eq!(timeline.total_ms, (min * 8).num_milliseconds());
+    }
+
+    #[test]
+    fn test_timeline_of_running_attempt_ends_now() {
+        let t0 = Utc::now();
+        let min = Duration::minutes(1);
+
+        let waiting = AttemptTimeline::compute(t0, &[], &[], t0 + min);
+        assert_eq!(waiting.phases.len(), 1);
+        assert_eq!(waiting.phases[0].ended_at, None);
+        assert_eq!(waiting.total_ms, min.num_milliseconds());
+
+        let agent = process(
+            ExecutionProcessRunReason::CodingAgent,
+            ScriptContext::SetupScript,
+            t0 + min,
+            None,
+        );
+        let running = AttemptTimeline::compute(t0, &[agent], &[], t0 + min * 3);
+        assert_eq!(running.phases[1].ended_at, None);
+        assert_eq!(running.phases[1].duration_ms, (min * 2).num_milliseconds());
+        assert_eq!(running.total_ms, (min * 3).num_milliseconds());
+    }
+}
@@ -0,0 +1,131 @@
+use executors::logs::ToolCall;
+use serde::Serialize;
+use ts_rs::TS;
+
+/// Calls an attempt's agents made to one tool
+#[derive(Debug, Clone, PartialEq, Serialize, TS)]
+pub struct ToolUsage {
+    pub tool: String,
+    /// MCP server providing the tool, `None` for the agent's built-in tools
+    pub mcp_server: Option<String>,
+    pub calls: u32,
+    pub failures: u32,
+    /// Calls whose result wasn't seen, e.g. because the run was stopped
+    pub unfinished: u32,
+    /// Summed over the calls the agent reported a duration for
+    #[ts(type = "number | null")]
+    pub total_duration_ms: Option<u64>,
+}
+
+/// Tool calls of an attempt's coding agent runs, most used tools first
+#[derive(Debug, Clone, PartialEq, Serialize, TS)]
+pub struct AttemptToolUsage {
+    pub tools: Vec<ToolUsage>,
+    pub total_calls: u32,
+    /// MCP servers configured for the attempt's profiles that no call used
+    pub unused_mcp_servers: Vec<String>,
+}
+
+impl AttemptToolUsage {
+    pub fn new(calls: &[ToolCall], configured_mcp_servers: &[String]) -> Self {
+        let mut tools: Vec<ToolUsage> = Vec::new();
+        for call in calls {
+            let index = match tools
+                .iter()
+                .position(|usage| usage.tool == call.tool && usage.mcp_server == call.mcp_server)
+            {
+                Some(index) => index,
+                None => {
+                    tools.push(ToolUsage {
+                        tool: call.tool.clone(),
+                        mcp_server: call.mcp_server.clone(),
+                        calls: 0,
+                        failures: 0,
+                        unfinished: 0,
+                        total_duration_ms: None,
+                    });
+                    tools.len() - 1
+                }
+            };
+            let usage = &mut tools[index];
+            usage.calls += 1;
+            match call.failed {
+                Some(true) => usage.failures += 1,
+                Some(false) => {}
+                None => usage.unfinished += 1,
+            }
+            if let Some(duration) = call.duration_ms {
+                usage.total_duration_ms = Some(usage.total_duration_ms.unwrap_or(0) + duration);
+            }
+        }
+        tools.sort_by(|a, b| b.calls.cmp(&a.calls).then(a.tool.cmp(&b.tool)));
+
+        let mut unused_mcp_servers: Vec<String> = configured_mcp_servers
+            .iter()
+            .filter(|server| {
+                !calls
+                    .iter()
+                    .any(|call| call.mcp_server.as_deref() == Some(server.as_str()))
+            })
+            .cloned()
+            .collect();
+        unused_mcp_servers.sort();
+        unused_mcp_servers.dedup();
+
+        Self {
+            total_calls: calls.len() as u32,
+            tools,
+            unused_mcp_servers,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn call(tool: &str, server: Option<&str>, failed: Option<bool>, ms: Option<u64>) -> ToolCall {
+        ToolCall {
+            tool: tool.to_string(),
+            mcp_server: server.map(str::to_string),
+            failed,
+            duration_ms: ms,
+        }
+    }
+
+    #[test]
+    fn test_usage_groups_calls_per_tool_and_lists_unused_servers() {
+        let calls = [
+            call("Read", None, Some(false), None),
+            call("get_issue", Some("linear"), Some(false), Some(200)),
+            call("Read", None, Some(false), None),
+            call("get_issue", Some("linear"), Some(true), Some(50)),
+            call("Bash", None, None, None),
+            call("get_issue", Some("linear"), Some(false), None),
+        ];
+        let usage = AttemptToolUsage::new(
+            &calls,
+            &[
+                "sentry".to_string(),
+                "linear".to_string(),
+                "github".to_string(),
+            ],
+        );
+
+        assert_eq!(usage.total_calls, 6);
+        assert_eq!(
+            usage
+                .tools
+                .iter()
+                .map(|t| (t.tool.as_str(), t.calls))
+                .collect::<Vec<_>>(),
+            vec![("get_issue", 3), ("Read", 2), ("Bash", 1)]
+        );
+        assert_eq!(usage.tools[0].mcp_server.as_deref(), Some("linear"));
+        assert_eq!(usage.tools[0].failures, 1);
+        assert_eq!(usage.tools[0].total_duration_ms, Some(250));
+        assert_eq!(usage.tools[1].total_duration_ms, None);
+        assert_eq!(usage.tools[2].unfinished, 1);
+        assert_eq!(usage.unused_mcp_servers, vec!["github", "sentry"]);
+    }
+}
@@ -0,0 +1,166 @@
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, Duration, Utc};
+use db::models::attempt_usage::AttemptUsage;
+use serde::{Deserialize, Serialize};
+use sqlx::SqlitePool;
+use ts_rs::TS;
+
+pub const DEFAULT_USAGE_DAYS: i64 = 30;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize, TS)]
+#[serde(rename_all = "lowercase")]
+pub enum UsageGroupBy {
+    #[default]
+    Day,
+    Profile,
+    Project,
+}
+
+/// Attempts of one day, profile or project and what they used
+#[derive(Debug, Clone, PartialEq, Serialize, TS)]
+pub struct UsageBucket {
+    /// `YYYY-MM-DD` (UTC), the profile's label, or the project's id
+    pub key: String,
+    /// What to show for the key, the project's name when grouped by project
+    pub label: String,
+    pub attempts: u32,
+    /// Attempts whose latest coding agent run completed
+    pub succeeded: u32,
+    pub merged: u32,
+    /// Share of attempts that succeeded, from 0 to 1
+    pub success_rate: f64,
+    #[ts(type = "number")]
+    pub input_tokens: i64,
+    #[ts(type = "number")]
+    pub output_tokens: i64,
+    pub cost_usd: f64,
+}
+
+/// Usage of the attempts started in the last days, from the stats stored for their runs.
+/// Tokens and costs only count runs whose agent reports them.
+#[derive(Debug, Clone, PartialEq, Serialize, TS)]
+pub struct UsageReport {
+    pub group_by: UsageGroupBy,
+    pub since: DateTime<Utc>,
+    pub buckets: Vec<UsageBucket>,
+    pub total: UsageBucket,
+}
+
+impl UsageReport {
+    pub async fn load(
+        pool: &SqlitePool,
+        group_by: UsageGroupBy,
+        days: i64,
+        now: DateTime<Utc>,
+    ) -> Result<Self, sqlx::Error> {
+        let since = now - Duration::days(days.max(1));
+        let attempts =
+            AttemptUsage::find_since(pool, &since.format("%Y-%m-%d %H:%M:%S").to_string()).await?;
+        Ok(Self {
+            group_by,
+            since,
+            buckets: aggregate(&attempts, group_by),
+            total: bucket("total".to_string(), "Total".to_string(), attempts.iter()),
+        })
+    }
+}
+
+fn bucket<'a>(
+    key: String,
+    label: String,
+    attempts: impl Iterator<Item = &'a AttemptUsage>,
+) -> UsageBucket {
+    let mut bucket = UsageBucket {
+        key,
+        label,
+        attempts: 0,
+        succeeded: 0,
+        merged: 0,
+        success_rate: 0.0,
+        input_tokens: 0,
+        output_tokens: 0,
+        cost_usd: 0.0,
+    };
+    for attempt in attempts {
+        bucket.attempts += 1;
+        bucket.succeeded += attempt.succeeded as u32;
+        bucket.merged += attempt.merged as u32;
+        bucket.input_tokens += attempt.input_tokens;
+        bucket.output_tokens += attempt.output_tokens;
+        bucket.cost_usd += attempt.cost_usd;
+    }
+    if bucket.attempts > 0 {
+        bucket.success_rate = bucket.succeeded as f64 / bucket.attempts as f64;
+    }
+    bucket
+}
+
+/// One bucket per day, profile or project with attempts, ordered by key
+fn aggregate(attempts: &[AttemptUsage], group_by: UsageGroupBy) -> Vec<UsageBucket> {
+    let mut groups: BTreeMap<String, (String, Vec<&AttemptUsage>)> = BTreeMap::new();
+    for attempt in attempts {
+        let (key, label) = match group_by {
+            UsageGroupBy::Day => {
+                let day = attempt.created_at.format("%Y-%m-%d").to_string();
+                (day.clone(), day)
+            }
+            UsageGroupBy::Profile => (attempt.profile.clone(), attempt.profile.clone()),
+            UsageGroupBy::Project => (attempt.project_id.to_string(), attempt.project_name.clone()),
+        };
+        groups
+            .entry(key)
+            .or_insert_with(|| (label, Vec::new()))
+            .1
+            .push(attempt);
+    }
+    groups
+        .into_iter()
+        .map(|(key, (label, attempts))| bucket(key, label, attempts.into_iter()))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+    use uuid::Uuid;
+
+    use super::*;
+
+    fn attempt(day: u32, profile: &str, succeeded: bool, cost_usd: f64) -> AttemptUsage {
+        AttemptUsage {
+            id: Uuid::new_v4(),
+            project_id: Uuid::nil(),
+            project_name: "api".to_string(),
+            profile: profile.to_string(),
+            created_at: Utc.with_ymd_and_hms(2025, 9, day, 12, 0, 0).unwrap(),
+            succeeded,
+            merged: false,
+            cost_usd,
+            input_tokens: 1_000,
+            output_tokens: 100,
+        }
+    }
+
+    #[test]
+    fn test_aggregate_by_day_and_profile() {
+        let attempts = [
+            attempt(1, "claude-code", true, 0.5),
+            attempt(1, "codex", false, 0.25),
+            attempt(2, "claude-code", true, 1.0),
+        ];
+
+        let by_day = aggregate(&attempts, UsageGroupBy::Day);
+        assert_eq!(by_day.len(), 2);
+        assert_eq!(by_day[0].key, "2025-09-01");
+        assert_eq!(by_day[0].attempts, 2);
+        assert_eq!(by_day[0].success_rate, 0.5);
+        assert_eq!(by_day[0].input_tokens, 2_000);
+
+        let by_profile = aggregate(&attempts, UsageGroupBy::Profile);
+        assert_eq!(by_profile[0].key, "claude-code");
+        assert_eq!(by_profile[0].cost_usd, 1.5);
+        assert_eq!(by_profile[0].success_rate, 1.0);
+        assert_eq!(by_profile[1].succeeded, 0);
+    }
+}
@@ -0,0 +1,356 @@
+use std::{sync::Arc, time::Duration};
+
+use chrono::Utc;
+use db::{
+    DBService,
+    models::webhook::{CreateWebhook, Webhook, WebhookDelivery, WebhookEvent},
+};
+use hmac::{Hmac, Mac};
+use reqwest::Client;
+use serde_json::{Value, json};
+use sha2::Sha256;
+use sqlx::SqlitePool;
+use thiserror::Error;
+use tokio::sync::broadcast::error::RecvError;
+use tracing::{error, info, warn};
+use utils::{log_msg::LogMsg, msg_store::MsgStore};
+use uuid::Uuid;
+
+use super::secrets::{SecretsError, SecretsStore};
+
+/// `t=<unix seconds>,v1=<hex HMAC-SHA256 of "<t>.<body>">`, keyed with the webhook's secret
+pub const SIGNATURE_HEADER: &str = "X-Vibe-Kanban-Signature";
+pub const EVENT_HEADER: &str = "X-Vibe-Kanban-Event";
+/// Id of the delivery, the same across its retries
+pub const DELIVERY_HEADER: &str = "X-Vibe-Kanban-Delivery";
+
+const DELIVERY_TIMEOUT: Duration = Duration::from_secs(10);
+/// Attempts before a delivery is given up on, spread over about three hours
+const MAX_ATTEMPTS: i64 = 10;
+const FIRST_RETRY_SECONDS: i64 = 30;
+const MAX_RETRY_SECONDS: i64 = 60 * 60;
+/// Deliveries that ended are kept this long for the delivery log
+const RETENTION_DAYS: i64 = 14;
+const DUE_BATCH: i64 = 20;
+
+#[derive(Debug, Error)]
+pub enum WebhookError {
+    #[error(transparent)]
+    Database(#[from] sqlx::Error),
+    #[error(transparent)]
+    Secrets(#[from] SecretsError),
+    #[error("Webhook URLs must be http or https, got {0}")]
+    InvalidUrl(String),
+    #[error("Webhook not found")]
+    NotFound,
+}
+
+/// Key a webhook's signing secret is kept under in the secrets store
+pub fn secret_key(webhook_id: Uuid) -> String {
+    format!("webhook.{webhook_id}.secret")
+}
+
+fn generate_secret() -> String {
+    format!(
+        "whsec_{}{}",
+        Uuid::new_v4().simple(),
+        Uuid::new_v4().simple()
+    )
+}
+
+fn hmac_hex(key: &[u8], message: &[u8]) -> String {
+    let mut mac = Hmac::<Sha256>::new_from_slice(key).expect("HMAC takes keys of any length");
+    mac.update(message);
+    format!("{:x}", mac.finalize().into_bytes())
+}
+
+/// Value of the signature header for a body sent at `timestamp`. Receivers recompute the
+/// HMAC over `<t>.<body>` and should reject old timestamps to stop replays.
+pub fn signature(secret: &str, timestamp: i64, body: &str) -> String {
+    let signed = format!("{timestamp}.{body}");
+    format!(
+        "t={timestamp},v1={}",
+        hmac_hex(secret.as_bytes(), signed.as_bytes())
+    )
+}
+
+/// Seconds to wait before the next attempt once `attempts` failed, doubling from 30 seconds
+/// up to an hour. `None` once the delivery should be given up on.
+pub fn retry_delay(attempts: i64) -> Option<i64> {
+    if attempts >= MAX_ATTEMPTS {
+        return None;
+    }
+    let doublings = (attempts - 1).clamp(0, 20) as u32;
+    Some((FIRST_RETRY_SECONDS << doublings).min(MAX_RETRY_SECONDS))
+}
+
+pub fn validate_url(url: &str) -> Result<(), WebhookError> {
+    match reqwest::Url::parse(url) {
+        Ok(parsed) if matches!(parsed.scheme(), "http" | "https") => Ok(()),
+        _ => Err(WebhookError::InvalidUrl(url.to_string())),
+    }
+}
+
+/// The event an entry of the events stream stands for, and the record it carries. Deletions
+/// only carry a rowid, so they aren't sent.
+pub fn event_for(entry: &Value) -> Option<(WebhookEvent, Value)> {
+    let db_op = entry.get("db_op")?.as_str()?;
+    let record = entry.get("record")?;
+    let data = record.get("data")?;
+    let event = match (record.get("type")?.as_str()?, db_op) {
+        ("TASK", "insert") => WebhookEvent::TaskCreated,
+        ("TASK", "update") => WebhookEvent::TaskUpdated,
+        ("TASK_ATTEMPT", "insert") => WebhookEvent::AttemptCreated,
+        ("TASK_ATTEMPT", "update") => WebhookEvent::AttemptUpdated,
+        ("EXECUTION_PROCESS", "insert") => WebhookEvent::ExecutionStarted,
+        ("EXECUTION_PROCESS", "update") => match data.get("status")?.as_str()? {
+            "completed" => WebhookEvent::ExecutionCompleted,
+            "failed" => WebhookEvent::ExecutionFailed,
+            "killed" => WebhookEvent::ExecutionKilled,
+            _ => return None,
+        },
+        _ => return None,
+    };
+    Some((event, data.clone()))
+}
+
+/// Add a webhook, returns it with its signing secret, which isn't shown again
+pub async fn create(
+    pool: &SqlitePool,
+    data: &CreateWebhook,
+) -> Result<(Webhook, String), WebhookError> {
+    validate_url(&data.url)?;
+    let webhook = Webhook::create(pool, data).await?;
+    let secret = generate_secret();
+    if let Err(e) = SecretsStore::default_store().set(&secret_key(webhook.id), &secret) {
+        Webhook::delete(pool, webhook.id).await?;
+        return Err(e.into());
+    }
+    Ok((webhook, secret))
+}
+
+/// Replace a webhook's signing secret, returns the new one
+pub async fn rotate_secret(pool: &SqlitePool, id: Uuid) -> Result<String, WebhookError> {
+    Webhook::find_by_id(pool, id)
+        .await?
+        .ok_or(WebhookError::NotFound)?;
+    let secret = generate_secret();
+    SecretsStore::default_store().set(&secret_key(id), &secret)?;
+    Ok(secret)
+}
+
+pub async fn delete(pool: &SqlitePool, id: Uuid) -> Result<(), WebhookError> {
+    if Webhook::delete(pool, id).await? == 0 {
+        return Err(WebhookError::NotFound);
+    }
+    SecretsStore::default_store().delete(&secret_key(id))?;
+    Ok(())
+}
+
+/// Queue a ping to a webhook, to check the endpoint receives and verifies deliveries
+pub async fn ping(pool: &SqlitePool, id: Uuid) -> Result<WebhookDelivery, WebhookError> {
+    let webhook = Webhook::find_by_id(pool, id)
+        .await?
+        .ok_or(WebhookError::NotFound)?;
+    Ok(WebhookDelivery::create(
+        pool,
+        webhook.id,
+        WebhookEvent::Ping,
+        &json!({ "webhook_id": webhook.id }),
+    )
+    .await?)
+}
+
+/// Turns board and execution events into deliveries and posts them to their webhooks
+pub struct WebhookService {
+    db: DBService,
+    events: Arc<MsgStore>,
+    client: Client,
+    poll_interval: Duration,
+}
+
+impl WebhookService {
+    pub async fn spawn(db: DBService, events: Arc<MsgStore>) -> tokio::task::JoinHandle<()> {
+        let service = Arc::new(Self {
+            db,
+            events,
+            client: Client::new(),
+            poll_interval: Duration::from_secs(2),
+        });
+        let listener = service.clone();
+        tokio::spawn(async move {
+            listener.enqueue_events().await;
+        });
+        tokio::spawn(async move {
+            service.deliver_due().await;
+        })
+    }
+
+    async fn enqueue_events(&self) {
+        let mut receiver = self.events.get_receiver();
+        loop {
+            let patch = match receiver.recv().await {
+                Ok(LogMsg::JsonPatch(patch)) => patch,
+                Ok(_) => continue,
+                Err(RecvError::Lagged(skipped)) => {
+                    warn!("Webhooks missed {} events", skipped);
+                    continue;
+                }
+                Err(RecvError::Closed) => return,
+            };
+            let Ok(Value::Array(operations)) = serde_json::to_value(&patch) else {
+                continue;
+            };
+            for (event, data) in operations
+                .iter()
+                .filter_map(|operation| event_for(operation.get("value")?))
+            {
+                if let Err(e) = self.enqueue(event, &data).await {
+                    error!("Failed to queue {:?} webhook deliveries: {}", event, e);
+                }
+            }
+        }
+    }
+
+    async fn enqueue(&self, event: WebhookEvent, data: &Value) -> Result<(), sqlx::Error> {
+        for webhook in Webhook::find_all(&self.db.pool).await? {
+            if webhook.subscribes_to(event) {
+                WebhookDelivery::create(&self.db.pool, webhook.id, event, data).await?;
+            }
+        }
+        Ok(())
+    }
+
+    async fn deliver_due(&self) {
+        info!(
+            "Starting webhook delivery service with interval {:?}",
+            self.poll_interval
+        );
+        let mut interval = tokio::time::interval(self.poll_interval);
+        let mut last_prune = None;
+        loop {
+            interval.tick().await;
+            let today = Utc::now().date_naive();
+            if last_prune != Some(today) {
+                last_prune = Some(today);
+                if let Err(e) = WebhookDelivery::prune(&self.db.pool, RETENTION_DAYS).await {
+                    error!("Failed to prune webhook deliveries: {}", e);
+                }
+            }
+            let due = match WebhookDelivery::find_due(&self.db.pool, DUE_BATCH).await {
+                Ok(due) => due,
+                Err(e) => {
+                    error!("Failed to load due webhook deliveries: {}", e);
+                    continue;
+                }
+            };
+            for delivery in due {
+                if let Err(e) = self.deliver(&delivery).await {
+                    error!("Failed to record webhook delivery {}: {}", delivery.id, e);
+                }
+            }
+        }
+    }
+
+    async fn deliver(&self, delivery: &WebhookDelivery) -> Result<(), WebhookError> {
+        let pool = &self.db.pool;
+        let Some(webhook) = Webhook::find_by_id(pool, delivery.webhook_id).await? else {
+            return Ok(());
+        };
+        let secret = SecretsStore::default_store()
+            .get(&secret_key(webhook.id))?
+            .unwrap_or_default();
+        let body = json!({
+            "id": delivery.id,
+            "event": delivery.event,
+            "created_at": delivery.created_at,
+            "data": delivery.data.0,
+        })
+        .to_string();
+        let event = serde_json::to_value(delivery.event)
+            .ok()
+            .and_then(|event| event.as_str().map(str::to_string))
+            .unwrap_or_default();
+        let response = self
+            .client
+            .post(&webhook.url)
+            .timeout(DELIVERY_TIMEOUT)
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .header(EVENT_HEADER, event)
+            .header(DELIVERY_HEADER, delivery.id.to_string())
+            .header(
+                SIGNATURE_HEADER,
+                signature(&secret, Utc::now().timestamp(), &body),
+            )
+            .body(body)
+            .send()
+            .await;
+        let (status, error) = match response {
+            Ok(response) if response.status().is_success() => {
+                WebhookDelivery::mark_delivered(
+                    pool,
+                    delivery.id,
+                    response.status().as_u16() as i64,
+                )
+                .await?;
+                return Ok(());
+            }
+            Ok(response) => (
+                Some(response.status().as_u16() as i64),
+                format!("Endpoint responded with {}", response.status()),
+            ),
+            Err(e) => (None, e.to_string()),
+        };
+        let retry = retry_delay(delivery.attempts + 1);
+        if retry.is_none() {
+            warn!(
+                "Giving up on webhook delivery {} to {} after {} attempts: {}",
+                delivery.id, webhook.url, MAX_ATTEMPTS, error
+            );
+        }
+        WebhookDelivery::record_failure(pool, delivery.id, status, &error, retry).await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_signature_retries_and_events() {
+        // RFC 4231, test case 2
+        assert_eq!(
+            hmac_hex(b"Jefe", b"what do ya want for nothing?"),
+            "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+        );
+        let header = signature("secret", 1_700_000_000, "{}");
+        assert!(header.starts_with("t=1700000000,v1="));
+        assert_eq!(header, signature("secret", 1_700_000_000, "{}"));
+        assert_ne!(header, signature("other", 1_700_000_000, "{}"));
+
+        assert_eq!(retry_delay(1), Some(30));
+        assert_eq!(retry_delay(2), Some(60));
+        assert_eq!(retry_delay(8), Some(MAX_RETRY_SECONDS));
+        assert_eq!(retry_delay(MAX_ATTEMPTS), None);
+
+        let finished = json!({
+            "db_op": "update",
+            "record": { "type": "EXECUTION_PROCESS", "data": { "status": "failed" } }
+        });
+        assert_eq!(
+            event_for(&finished).map(|(event, _)| event),
+            Some(WebhookEvent::ExecutionFailed)
+        );
+        let running = json!({
+            "db_op": "update",
+            "record": { "type": "EXECUTION_PROCESS", "data": { "status": "running" } }
+        });
+        assert_eq!(event_for(&running), None);
+        let deleted = json!({
+            "db_op": "delete",
+            "record": { "type": "DELETED_TASK", "data": { "rowid": 1 } }
+        });
+        assert_eq!(event_for(&deleted), None);
+    }
+}
@@ -0,0 +1,197 @@
+use std::{
+    collections::HashSet,
+    path::{Path, PathBuf},
+};
+
+use chrono::{DateTime, Utc};
+use git2::Repository;
+use serde::Serialize;
+use ts_rs::TS;
+
+use crate::services::filesystem::FilesystemError;
+
+/// Folders below the workspace directory searched for repositories
+const MAX_DEPTH: usize = 3;
+
+/// Dependency and build folders never holding checkouts worth registering
+const SKIPPED_DIRS: &[&str] = &["node_modules", "target", "vendor", "dist", "build"];
+
+/// A git repository in the workspace directory that isn't a project yet
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct DiscoveredRepo {
+    /// Folder name
+    pub name: String,
+    pub path: String,
+    /// URL of the `origin` remote
+    pub remote_url: Option<String>,
+    /// Checked out branch, `None` for a detached head
+    pub branch: Option<String>,
+    /// `None` for a repository without commits
+    pub last_commit: Option<LastCommit>,
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct LastCommit {
+    pub sha: String,
+    pub summary: String,
+    pub author: String,
+    pub committed_at: DateTime<Utc>,
+}
+
+/// Git repositories under `dir` none of the `registered` paths point at, most recently
+/// committed to first. Repositories aren't searched for nested ones.
+pub fn discover(
+    dir: &Path,
+    registered: &[PathBuf],
+) -> Result<Vec<DiscoveredRepo>, FilesystemError> {
+    if !dir.exists() {
+        return Err(FilesystemError::DirectoryDoesNotExist);
+    }
+    if !dir.is_dir() {
+        return Err(FilesystemError::PathIsNotDirectory);
+    }
+
+    let registered: HashSet<PathBuf> = registered.iter().map(|path| canonical(path)).collect();
+    let mut found = Vec::new();
+    collect_repos(dir, 0, &mut found)?;
+
+    let mut repos: Vec<DiscoveredRepo> = found
+        .into_iter()
+        .filter(|path| !registered.contains(&canonical(path)))
+        .filter_map(|path| describe(&path))
+        .collect();
+    repos.sort_by(|a, b| {
+        let committed_at =
+            |repo: &DiscoveredRepo| repo.last_commit.as_ref().map(|c| c.committed_at);
+        committed_at(b)
+            .cmp(&committed_at(a))
+            .then_with(|| a.name.cmp(&b.name))
+    });
+    Ok(repos)
+}
+
+fn canonical(path: &Path) -> PathBuf {
+    dunce::canonicalize(path).unwrap_or_else(|_| path.to_path_buf())
+}
+
+fn collect_repos(
+    dir: &Path,
+    depth: usize,
+    found: &mut Vec<PathBuf>,
+) -> Result<(), FilesystemError> {
+    if dir.join(".git").exists() {
+        found.push(dir.to_path_buf());
+        return Ok(());
+    }
+    if depth == MAX_DEPTH {
+        return Ok(());
+    }
+    for entry in std::fs::read_dir(dir)?.flatten() {
+        let name = entry.file_name();
+        let name = name.to_string_lossy();
+        if name.starts_with('.') || SKIPPED_DIRS.contains(&name.as_ref()) {
+            continue;
+        }
+        // Symlinks are skipped, they'd list a repository twice or loop
+        if entry.file_type().is_ok_and(|file_type| file_type.is_dir()) {
+            // A folder that can't be read shouldn't hide the rest of the workspace
+            if let Err(e) = collect_repos(&entry.path(), depth + 1, found) {
+                tracing::debug!("Skipping {}: {}", entry.path().display(), e);
+            }
+        }
+    }
+    Ok(())
+}
+
+fn describe(path: &Path) -> Option<DiscoveredRepo> {
+    let repo = Repository::open(path).ok()?;
+    let remote_url = repo
+        .find_remote("origin")
+        .ok()
+        .and_then(|remote| remote.url().map(str::to_string));
+    let head = repo.head().ok();
+    let branch = head
+        .as_ref()
+        .filter(|head| head.is_branch())
+        .and_then(|head| head.shorthand().map(str::to_string));
+    let last_commit = head
+        .and_then(|head| head.peel_to_commit().ok())
+        .map(|commit| LastCommit {
+            sha: commit.id().to_string(),
+            summary: commit.summary().unwrap_or_default().to_string(),
+            author: commit.author().name().unwrap_or_default().to_string(),
+            committed_at: DateTime::from_timestamp(commit.time().seconds(), 0).unwrap_or_default(),
+        });
+    Some(DiscoveredRepo {
+        name: path.file_name()?.to_string_lossy().to_string(),
+        path: path.to_string_lossy().to_string(),
+        remote_url,
+        branch,
+        last_commit,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use tempfile::TempDir;
+
+    use super::*;
+
+    fn init_repo(path: &Path, with_commit: bool) {
+        fs::create_dir_all(path).unwrap();
+        let repo = Repository::init(path).unwrap();
+        repo.remote("origin", "https://github.com/acme/app.git")
+            .unwrap();
+        if with_commit {
+            let signature = git2::Signature::now("Ada", "ada@example.com").unwrap();
+            let tree = repo
+                .find_tree(repo.index().unwrap().write_tree().unwrap())
+                .unwrap();
+            repo.commit(
+                Some("HEAD"),
+                &signature,
+                &signature,
+                "Initial commit",
+                &tree,
+                &[],
+            )
+            .unwrap();
+        }
+    }
+
+    #[test]
+    fn test_discover_skips_registered_and_nested_repos() {
+        let workspace = TempDir::new().unwrap();
+        init_repo(&workspace.path().join("app"), true);
+        init_repo(&workspace.path().join("app/packages/nested"), true);
+        init_repo(&workspace.path().join("clients/empty"), false);
+        init_repo(&workspace.path().join("node_modules/dep"), true);
+        init_repo(&workspace.path().join("registered"), true);
+        fs::create_dir_all(workspace.path().join("notes")).unwrap();
+
+        let registered = [workspace.path().join("registered")];
+        let repos = discover(workspace.path(), &registered).unwrap();
+
+        let names: Vec<_> = repos.iter().map(|repo| repo.name.as_str()).collect();
+        assert_eq!(names, ["app", "empty"]);
+        assert_eq!(
+            repos[0].remote_url.as_deref(),
+            Some("https://github.com/acme/app.git")
+        );
+        let commit = repos[0].last_commit.as_ref().unwrap();
+        assert_eq!(commit.summary, "Initial commit");
+        assert_eq!(commit.author, "Ada");
+        assert!(repos[1].last_commit.is_none());
+    }
+
+    #[test]
+    fn test_discover_requires_directory() {
+        let workspace = TempDir::new().unwrap();
+        assert!(matches!(
+            discover(&workspace.path().join("missing"), &[]),
+            Err(FilesystemError::DirectoryDoesNotExist)
+        ));
+    }
+}
@@ -39,6 +39,7 @@ pub struct WorktreeManager;
 
 impl WorktreeManager {
     /// Create a worktree with a new branch
+    #[tracing::instrument(skip(repo_path, worktree_path))]
     pub async fn create_worktree(
         repo_path: &Path,
         branch_name: &str,
@@ -55,8 +56,11 @@ impl WorktreeManager {
                 let repo = Repository::open(&repo_path_owned)?;
 
                 let base_reference = if let Some(base_branch) = base_branch_owned.as_deref() {
-                    let branch = repo.find_branch(base_branch, BranchType::Local)?;
-                    branch.into_reference()
+                    // Local branches first, then remote ones and tags, as in the branch picker
+                    repo.find_branch(base_branch, BranchType::Local)
+                        .or_else(|_| repo.find_branch(base_branch, BranchType::Remote))
+                        .map(|branch| branch.into_reference())
+                        .or_else(|_| repo.find_reference(&format!("refs/tags/{base_branch}")))?
                 } else {
                     // Handle new repositories without any commits
                     match repo.head() {
@@ -90,6 +94,7 @@ impl WorktreeManager {
 
     /// Ensure worktree exists, recreating if necessary with proper synchronization
     /// This is the main entry point for ensuring a worktree exists and prevents race conditions
+    #[tracing::instrument(skip(repo_path, worktree_path))]
     pub async fn ensure_worktree_exists(
         repo_path: &Path,
         branch_name: &str,
@@ -631,6 +636,6 @@ impl WorktreeManager {
 
     /// Get the base directory for vibe-kanban worktrees
     pub fn get_worktree_base_dir() -> std::path::PathBuf {
-        utils::path::get_vibe_kanban_temp_dir().join("worktrees")
+        utils::path::get_workspace_dir()
     }
 }
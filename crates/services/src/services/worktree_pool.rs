@@ -0,0 +1,196 @@
+use std::{
+    collections::{HashMap, HashSet},
+    path::PathBuf,
+    sync::{Arc, Mutex},
+};
+
+use serde::Serialize;
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// Most worktrees a project may keep ready, each holds a full checkout and its dependencies
+pub const MAX_POOL_SIZE: i64 = 4;
+
+/// Warm worktrees and their branches are named with this prefix until an attempt claims them
+pub const WARM_PREFIX: &str = "vk-warm-";
+
+/// A worktree checked out to a base branch, with the setup script already run
+#[derive(Debug, Clone, PartialEq)]
+pub struct WarmWorktree {
+    pub repo_path: PathBuf,
+    pub path: PathBuf,
+    pub branch: String,
+    pub base_branch: String,
+    pub base_commit: String,
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct WorktreePoolStatus {
+    #[ts(type = "number")]
+    pub size: i64,
+    /// Worktrees ready to be claimed
+    pub ready: usize,
+    /// Whether worktrees are being set up
+    pub filling: bool,
+}
+
+/// Worktrees set up ahead of attempts, so an attempt starts without waiting for a checkout
+/// and its setup script. Only kept in memory: worktrees left by a previous run are orphans
+/// and removed at startup.
+#[derive(Clone, Default)]
+pub struct WorktreePool {
+    ready: Arc<Mutex<HashMap<Uuid, Vec<WarmWorktree>>>>,
+    filling: Arc<Mutex<HashSet<Uuid>>>,
+}
+
+impl WorktreePool {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Takes a worktree of the project checked out to the base branch at its current commit
+    pub fn take(
+        &self,
+        project_id: Uuid,
+        base_branch: &str,
+        base_commit: &str,
+    ) -> Option<WarmWorktree> {
+        let mut ready = self.ready.lock().unwrap();
+        let worktrees = ready.get_mut(&project_id)?;
+        let index = worktrees
+            .iter()
+            .position(|w| w.base_branch == base_branch && w.base_commit == base_commit)?;
+        Some(worktrees.remove(index))
+    }
+
+    pub fn add(&self, project_id: Uuid, worktree: WarmWorktree) {
+        self.ready
+            .lock()
+            .unwrap()
+            .entry(project_id)
+            .or_default()
+            .push(worktree);
+    }
+
+    /// Removes and returns the project's worktrees not checked out to the base branch at its
+    /// current commit, an attempt would start on an outdated checkout
+    pub fn remove_stale(
+        &self,
+        project_id: Uuid,
+        base_branch: &str,
+        base_commit: &str,
+    ) -> Vec<WarmWorktree> {
+        let mut ready = self.ready.lock().unwrap();
+        let Some(worktrees) = ready.get_mut(&project_id) else {
+            return Vec::new();
+        };
+        let (fresh, stale) = std::mem::take(worktrees)
+            .into_iter()
+            .partition(|w| w.base_branch == base_branch && w.base_commit == base_commit);
+        *worktrees = fresh;
+        stale
+    }
+
+    /// Removes and returns the worktrees of projects whose pool isn't kept anymore
+    pub fn remove_projects_except(&self, project_ids: &HashSet<Uuid>) -> Vec<WarmWorktree> {
+        let mut ready = self.ready.lock().unwrap();
+        let removed = ready
+            .iter()
+            .filter(|(id, _)| !project_ids.contains(id))
+            .flat_map(|(_, worktrees)| worktrees.clone())
+            .collect();
+        ready.retain(|id, _| project_ids.contains(id));
+        removed
+    }
+
+    /// Removes and returns the worktrees beyond the pool's size
+    pub fn shrink(&self, project_id: Uuid, size: usize) -> Vec<WarmWorktree> {
+        let mut ready = self.ready.lock().unwrap();
+        match ready.get_mut(&project_id) {
+            Some(worktrees) if worktrees.len() > size => worktrees.split_off(size),
+            _ => Vec::new(),
+        }
+    }
+
+    pub fn ready_count(&self, project_id: Uuid) -> usize {
+        self.ready
+            .lock()
+            .unwrap()
+            .get(&project_id)
+            .map_or(0, Vec::len)
+    }
+
+    /// Marks the project's pool as being filled, `false` if it already is
+    pub fn start_filling(&self, project_id: Uuid) -> bool {
+        self.filling.lock().unwrap().insert(project_id)
+    }
+
+    pub fn finish_filling(&self, project_id: Uuid) {
+        self.filling.lock().unwrap().remove(&project_id);
+    }
+
+    pub fn status(&self, project_id: Uuid, size: i64) -> WorktreePoolStatus {
+        WorktreePoolStatus {
+            size,
+            ready: self.ready_count(project_id),
+            filling: self.filling.lock().unwrap().contains(&project_id),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn warm(base_commit: &str) -> WarmWorktree {
+        let name = format!("{WARM_PREFIX}{base_commit}");
+        WarmWorktree {
+            repo_path: PathBuf::from("/tmp/repo"),
+            path: PathBuf::from("/tmp").join(&name),
+            branch: name,
+            base_branch: "main".to_string(),
+            base_commit: base_commit.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_take_only_up_to_date_worktrees() {
+        let pool = WorktreePool::new();
+        let project_id = Uuid::new_v4();
+        pool.add(project_id, warm("old"));
+        pool.add(project_id, warm("new"));
+
+        assert_eq!(pool.take(project_id, "develop", "new"), None);
+        assert_eq!(pool.take(project_id, "main", "new"), Some(warm("new")));
+        assert_eq!(pool.take(project_id, "main", "new"), None);
+
+        assert_eq!(
+            pool.remove_stale(project_id, "main", "new"),
+            vec![warm("old")]
+        );
+        assert_eq!(pool.ready_count(project_id), 0);
+    }
+
+    #[test]
+    fn test_shrink_and_remove_projects() {
+        let pool = WorktreePool::new();
+        let kept = Uuid::new_v4();
+        let dropped = Uuid::new_v4();
+        pool.add(kept, warm("a"));
+        pool.add(kept, warm("b"));
+        pool.add(dropped, warm("c"));
+
+        assert_eq!(pool.shrink(kept, 1), vec![warm("b")]);
+        assert_eq!(
+            pool.remove_projects_except(&HashSet::from([kept])),
+            vec![warm("c")]
+        );
+        assert_eq!(pool.status(kept, 1).ready, 1);
+        assert_eq!(pool.ready_count(dropped), 0);
+
+        assert!(pool.start_filling(kept));
+        assert!(!pool.start_filling(kept));
+        pool.finish_filling(kept);
+        assert!(!pool.status(kept, 1).filling);
+    }
+}
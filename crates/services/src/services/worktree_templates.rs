@@ -0,0 +1,279 @@
+use std::path::{Component, Path, PathBuf};
+
+use chrono::{DateTime, Utc};
+use executors::env::ExecutionEnv;
+use serde::Serialize;
+use thiserror::Error;
+use ts_rs::TS;
+use utils::assets::asset_dir;
+use uuid::Uuid;
+
+/// Templates with this suffix are rendered, and written without it
+pub const RENDERED_SUFFIX: &str = ".tmpl";
+
+/// Largest template accepted, these are config files rather than data
+pub const MAX_TEMPLATE_BYTES: usize = 1024 * 1024;
+
+#[derive(Debug, Error)]
+pub enum WorktreeTemplateError {
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    #[error("Invalid template path: {0}")]
+    InvalidPath(String),
+    #[error("Template is larger than {} KB", MAX_TEMPLATE_BYTES / 1024)]
+    TooLarge,
+    #[error("Template not found: {0}")]
+    NotFound(String),
+    #[error("Template {0} is not valid UTF-8, only plain copies can be binary")]
+    NotText(String),
+}
+
+/// A file copied into each new worktree of a project
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct WorktreeTemplate {
+    /// Path relative to the template directory, and to the worktree without the
+    /// `.tmpl` suffix
+    pub path: String,
+    #[ts(type = "number")]
+    pub size_bytes: u64,
+    /// `${NAME}` placeholders are replaced with the project's environment variables
+    pub rendered: bool,
+    pub modified_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct WorktreeTemplateList {
+    /// Where the templates are kept, files can also be placed there directly
+    pub dir: String,
+    pub templates: Vec<WorktreeTemplate>,
+}
+
+/// Files that aren't in git but that builds need, such as `.env.local` or service account
+/// keys, kept in `worktree-templates/{project_id}/` under the data directory
+#[derive(Debug, Clone)]
+pub struct WorktreeTemplates {
+    dir: PathBuf,
+}
+
+impl WorktreeTemplates {
+    pub fn new(dir: PathBuf) -> Self {
+        Self { dir }
+    }
+
+    pub fn for_project(project_id: Uuid) -> Self {
+        Self::new(
+            asset_dir()
+                .join("worktree-templates")
+                .join(project_id.to_string()),
+        )
+    }
+
+    pub async fn list(&self) -> Result<WorktreeTemplateList, WorktreeTemplateError> {
+        let mut templates = Vec::new();
+        let mut pending = vec![self.dir.clone()];
+        while let Some(dir) = pending.pop() {
+            let mut entries = match tokio::fs::read_dir(&dir).await {
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => continue,
+                entries => entries?,
+            };
+            while let Some(entry) = entries.next_entry().await? {
+                let metadata = entry.metadata().await?;
+                if metadata.is_dir() {
+                    pending.push(entry.path());
+                    continue;
+                }
+                let path = entry
+                    .path()
+                    .strip_prefix(&self.dir)
+                    .unwrap_or(&entry.path())
+                    .to_string_lossy()
+                    .replace('\\', "/");
+                templates.push(WorktreeTemplate {
+                    rendered: path.ends_with(RENDERED_SUFFIX),
+                    path,
+                    size_bytes: metadata.len(),
+                    modified_at: metadata.modified()?.into(),
+                });
+            }
+        }
+        templates.sort_by(|a, b| a.path.cmp(&b.path));
+        Ok(WorktreeTemplateList {
+            dir: self.dir.to_string_lossy().into_owned(),
+            templates,
+        })
+    }
+
+    pub async fn save(
+        &self,
+        path: &str,
+        data: &[u8],
+    ) -> Result<WorktreeTemplate, WorktreeTemplateError> {
+        if data.len() > MAX_TEMPLATE_BYTES {
+            return Err(WorktreeTemplateError::TooLarge);
+        }
+        let relative = Self::validate_path(path)?;
+        if path.ends_with(RENDERED_SUFFIX) && std::str::from_utf8(data).is_err() {
+            return Err(WorktreeTemplateError::NotText(path.to_string()));
+        }
+        let file = self.dir.join(relative);
+        tokio::fs::create_dir_all(file.parent().unwrap()).await?;
+        tokio::fs::write(&file, data).await?;
+        Ok(WorktreeTemplate {
+            path: path.to_string(),
+            size_bytes: data.len() as u64,
+            rendered: path.ends_with(RENDERED_SUFFIX),
+            modified_at: Utc::now(),
+        })
+    }
+
+    pub async fn delete(&self, path: &str) -> Result<(), WorktreeTemplateError> {
+        let file = self.dir.join(Self::validate_path(path)?);
+        match tokio::fs::remove_file(&file).await {
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                Err(WorktreeTemplateError::NotFound(path.to_string()))
+            }
+            result => Ok(result?),
+        }
+    }
+
+    /// Remove the templates of a deleted project
+    pub async fn delete_all(&self) -> Result<(), WorktreeTemplateError> {
+        match tokio::fs::remove_dir_all(&self.dir).await {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e.into()),
+            _ => Ok(()),
+        }
+    }
+
+    /// Write the templates into a new worktree, rendering them with `env`. Files the
+    /// worktree already has, e.g. tracked ones, are kept. Returns the paths written.
+    pub async fn apply(
+        &self,
+        worktree_path: &Path,
+        env: &ExecutionEnv,
+    ) -> Result<Vec<String>, WorktreeTemplateError> {
+        let mut written = Vec::new();
+        for template in self.list().await?.templates {
+            let target = template
+                .path
+                .strip_suffix(RENDERED_SUFFIX)
+                .unwrap_or(&template.path)
+                .to_string();
+            let target_file = worktree_path.join(&target);
+            if tokio::fs::try_exists(&target_file).await? {
+                tracing::warn!(
+                    "Not writing template {} over the worktree's own {}",
+                    template.path,
+                    target
+                );
+                continue;
+            }
+            let data = tokio::fs::read(self.dir.join(&template.path)).await?;
+            let data = if template.rendered {
+                let text = String::from_utf8(data)
+                    .map_err(|_| WorktreeTemplateError::NotText(template.path.clone()))?;
+                render(&text, env).into_bytes()
+            } else {
+                data
+            };
+            if let Some(parent) = target_file.parent() {
+                tokio::fs::create_dir_all(parent).await?;
+            }
+            tokio::fs::write(&target_file, data).await?;
+            written.push(target);
+        }
+        Ok(written)
+    }
+
+    /// Relative path of a template, refusing ones that would escape the worktree or reach
+    /// into `.git`
+    fn validate_path(path: &str) -> Result<PathBuf, WorktreeTemplateError> {
+        let invalid = || WorktreeTemplateError::InvalidPath(path.to_string());
+        let relative = Path::new(path);
+        let mut components = relative.components().peekable();
+        if components.peek().is_none() || path.ends_with('/') {
+            return Err(invalid());
+        }
+        for component in components {
+            match component {
+                Component::Normal(name) if name != ".git" => {}
+                _ => return Err(invalid()),
+            }
+        }
+        Ok(relative.to_path_buf())
+    }
+}
+
+/// Replace `${NAME}` placeholders with the variables of `env`. Unknown ones are left as
+/// they are, so a missing variable shows in the file instead of becoming an empty value.
+pub fn render(template: &str, env: &ExecutionEnv) -> String {
+    let mut rendered = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find("${") {
+        rendered.push_str(&rest[..start]);
+        let after = &rest[start + 2..];
+        match after
+            .find('}')
+            .and_then(|end| env.get(&after[..end]).map(|value| (end, value)))
+        {
+            Some((end, value)) => {
+                rendered.push_str(value);
+                rest = &after[end + 1..];
+            }
+            None => {
+                rendered.push_str("${");
+                rest = after;
+            }
+        }
+    }
+    rendered.push_str(rest);
+    rendered
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_placeholders() {
+        let mut env = ExecutionEnv::new();
+        env.insert("DATABASE_URL", "postgres://localhost/app");
+        env.insert("PORT", "5432");
+        assert_eq!(
+            render("DATABASE_URL=${DATABASE_URL}\nPORT=${PORT}${PORT}\n", &env),
+            "DATABASE_URL=postgres://localhost/app\nPORT=54325432\n"
+        );
+        assert_eq!(
+            render("KEY=${MISSING} ${ unclosed", &env),
+            "KEY=${MISSING} ${ unclosed"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_apply_templates() {
+        let dir = tempfile::tempdir().unwrap();
+        let worktree = tempfile::tempdir().unwrap();
+        let templates = WorktreeTemplates::new(dir.path().to_path_buf());
+        templates
+            .save(".env.local.tmpl", b"TOKEN=${TOKEN}\n")
+            .await
+            .unwrap();
+        templates
+            .save("keys/service-account.json", b"{}")
+            .await
+            .unwrap();
+        templates.save("README.md", b"template").await.unwrap();
+        std::fs::write(worktree.path().join("README.md"), "tracked").unwrap();
+        assert!(templates.save("../escape", b"").await.is_err());
+        assert!(templates.save(".git/config", b"").await.is_err());
+
+        let mut env = ExecutionEnv::new();
+        env.insert("TOKEN", "abc");
+        let written = templates.apply(worktree.path(), &env).await.unwrap();
+
+        assert_eq!(written, vec![".env.local", "keys/service-account.json"]);
+        let read = |name: &str| std::fs::read_to_string(worktree.path().join(name)).unwrap();
+        assert_eq!(read(".env.local"), "TOKEN=abc\n");
+        assert_eq!(read("keys/service-account.json"), "{}");
+        assert_eq!(read("README.md"), "tracked");
+    }
+}
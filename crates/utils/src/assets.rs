@@ -1,10 +1,24 @@
+use std::sync::OnceLock;
+
 use directories::ProjectDirs;
 use rust_embed::RustEmbed;
 
 const PROJECT_ROOT: &str = env!("CARGO_MANIFEST_DIR");
 
+/// Data directory configured at startup, overriding the platform default
+static ASSET_DIR_OVERRIDE: OnceLock<std::path::PathBuf> = OnceLock::new();
+
+/// Override the data directory, call once at startup before anything reads it
+pub fn set_asset_dir(path: std::path::PathBuf) {
+    if ASSET_DIR_OVERRIDE.set(path).is_err() {
+        tracing::warn!("Asset directory already configured, ignoring override");
+    }
+}
+
 pub fn asset_dir() -> std::path::PathBuf {
-    let path = if cfg!(debug_assertions) {
+    let path = if let Some(path) = ASSET_DIR_OVERRIDE.get() {
+        path.clone()
+    } else if cfg!(debug_assertions) {
         std::path::PathBuf::from(PROJECT_ROOT).join("../../dev_assets")
     } else {
         ProjectDirs::from("ai", "bloop", "vibe-kanban")
@@ -28,10 +42,21 @@ pub fn config_path() -> std::path::PathBuf {
     asset_dir().join("config.json")
 }
 
+/// Previous versions of config.json, one file per save, from before the config history was
+/// kept in the database
+pub fn config_history_dir() -> std::path::PathBuf {
+    asset_dir().join("config_history")
+}
+
 pub fn profiles_path() -> std::path::PathBuf {
     asset_dir().join("profiles.json")
 }
 
+/// Last verified copy of the organization baseline config
+pub fn baseline_path() -> std::path::PathBuf {
+    asset_dir().join("baseline.json")
+}
+
 #[derive(RustEmbed)]
 #[folder = "../../assets/sounds"]
 pub struct SoundAssets;
@@ -61,6 +61,30 @@ pub fn create_unified_diff_hunk(old: &str, new: &str) -> String {
     out
 }
 
+/// Lines of `new` that are not in `old`, with their 1-based line number in `new`.
+pub fn added_lines<'a>(old: &str, new: &'a str) -> Vec<(usize, &'a str)> {
+    let new_lines: Vec<&str> = new.lines().collect();
+    TextDiff::from_lines(old, new)
+        .iter_all_changes()
+        .filter(|change| change.tag() == ChangeTag::Insert)
+        .filter_map(|change| {
+            let index = change.new_index()?;
+            Some((index + 1, *new_lines.get(index)?))
+        })
+        .collect()
+}
+
+/// Number of lines added to and removed from `old` to get `new`.
+pub fn changed_line_counts(old: &str, new: &str) -> (usize, usize) {
+    TextDiff::from_lines(old, new)
+        .iter_all_changes()
+        .fold((0, 0), |(added, removed), change| match change.tag() {
+            ChangeTag::Insert => (added + 1, removed),
+            ChangeTag::Delete => (added, removed + 1),
+            ChangeTag::Equal => (added, removed),
+        })
+}
+
 /// Creates a full unified diff with the file path in the header.
 pub fn create_unified_diff(file_path: &str, old: &str, new: &str) -> String {
     let mut out = String::new();
@@ -0,0 +1,707 @@
+//! Catalog of user-facing messages. Each message has a stable code that clients can match on,
+//! named parameters, and a template per locale the server renders it in.
+
+use std::{fmt, sync::RwLock};
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+/// Language messages are rendered in, set from the user's config
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize, TS, JsonSchema,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum Locale {
+    #[default]
+    En,
+    De,
+    Es,
+    Fr,
+}
+
+impl Locale {
+    pub const ALL: [Locale; 4] = [Locale::En, Locale::De, Locale::Es, Locale::Fr];
+
+    fn index(self) -> usize {
+        match self {
+            Locale::En => 0,
+            Locale::De => 1,
+            Locale::Es => 2,
+            Locale::Fr => 3,
+        }
+    }
+}
+
+static CURRENT_LOCALE: RwLock<Locale> = RwLock::new(Locale::En);
+
+/// Sets the locale messages are rendered in when no locale is passed explicitly
+pub fn set_locale(locale: Locale) {
+    *CURRENT_LOCALE.write().unwrap_or_else(|e| e.into_inner()) = locale;
+}
+
+pub fn current_locale() -> Locale {
+    *CURRENT_LOCALE.read().unwrap_or_else(|e| e.into_inner())
+}
+
+/// Stable identifier of a catalog message. The serialized code never changes once released,
+/// even if the wording of the message does.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, TS)]
+#[serde(rename_all = "snake_case")]
+pub enum MessageCode {
+    // Errors returned by `ApiError`, `{detail}` is the underlying error
+    ProjectError,
+    TaskAttemptError,
+    GitServiceError,
+    #[serde(rename = "github_service_error")]
+    GitHubServiceError,
+    AuthError,
+    DeploymentError,
+    ContainerError,
+    ExecutorError,
+    DatabaseError,
+    WorktreeError,
+    ConfigError,
+    BackupError,
+    AgentLoginError,
+    RepoConfigError,
+    SoundError,
+    PtyError,
+    AttachmentError,
+    BudgetError,
+    SecretScanError,
+    PathRulesError,
+    ContextPackError,
+    DiskQuotaError,
+    CheckpointError,
+    ProjectEnvError,
+    WorktreeTemplateError,
+    MergeQueueError,
+    ProjectTemplateError,
+    RepoCloneError,
+    ProviderKeyError,
+    EvaluationError,
+    WebhookError,
+    SentryError,
+    DependencyUpdateError,
+    ChangelogError,
+    SupportBundleError,
+    SelfUpdateError,
+    ShareLinkError,
+    BadRequest,
+    PreconditionFailed,
+
+    // Request handling
+    RequestBodyTooLarge,
+    RateLimited,
+    IdempotencyKeyInProgress,
+    IdempotencyKeyReused,
+    ResponseReadFailed,
+    ApiVersionMismatch,
+
+    // Filesystem and projects
+    DirectoryNotFound,
+    NotADirectory,
+    DirectoryReadFailed,
+    PathNotFound,
+    PathNotADirectory,
+    NotAGitRepository,
+    MainBranchSetupFailed,
+    DirectoryCreateFailed,
+    GitInitFailed,
+    ProjectRepoPathTaken,
+    ProjectGroupNameTaken,
+    SearchQueryRequired,
+
+    // GitHub
+    #[serde(rename = "github_push_failed")]
+    GitHubPushFailed,
+    PullRequestCreateFailed,
+
+    // Attempts
+    DevServerScriptMissing,
+    UnmergeNeedsConfirmation,
+    AttemptFinishedTitle,
+    AttemptSucceeded,
+    AttemptFailed,
+    AttemptNeedsAttentionTitle,
+    AttemptNeedsAttention,
+
+    // Settings
+    ConfigInvalid,
+    ProfileNotFound,
+    McpUnsupported,
+    McpConfigPathUnknown,
+    McpUpdateFailed,
+    ProfilesInvalid,
+    ProfilesUnknownFields,
+    ProfilesUnreadable,
+}
+
+impl MessageCode {
+    /// Templates in the order of `Locale::ALL`, `{name}` is replaced by the parameter `name`
+    fn templates(self) -> [&'static str; 4] {
+        use MessageCode::*;
+        match self {
+            ProjectError => [
+                "Project error: {detail}",
+                "Projektfehler: {detail}",
+                "Error del proyecto: {detail}",
+                "Erreur du projet : {detail}",
+            ],
+            TaskAttemptError => [
+                "Task attempt error: {detail}",
+                "Fehler im Aufgabenversuch: {detail}",
+                "Error del intento de tarea: {detail}",
+                "Erreur de la tentative : {detail}",
+            ],
+            GitServiceError => [
+                "Git error: {detail}",
+                "Git-Fehler: {detail}",
+                "Error de Git: {detail}",
+                "Erreur Git : {detail}",
+            ],
+            GitHubServiceError => [
+                "GitHub error: {detail}",
+                "GitHub-Fehler: {detail}",
+                "Error de GitHub: {detail}",
+                "Erreur GitHub : {detail}",
+            ],
+            AuthError => [
+                "Authentication error: {detail}",
+                "Authentifizierungsfehler: {detail}",
+                "Error de autenticación: {detail}",
+                "Erreur d'authentification : {detail}",
+            ],
+            DeploymentError => [
+                "Server error: {detail}",
+                "Serverfehler: {detail}",
+                "Error del servidor: {detail}",
+                "Erreur du serveur : {detail}",
+            ],
+            ContainerError => [
+                "Execution error: {detail}",
+                "Ausführungsfehler: {detail}",
+                "Error de ejecución: {detail}",
+                "Erreur d'exécution : {detail}",
+            ],
+            ExecutorError => [
+                "Agent error: {detail}",
+                "Agentenfehler: {detail}",
+                "Error del agente: {detail}",
+                "Erreur de l'agent : {detail}",
+            ],
+            DatabaseError => [
+                "Database error: {detail}",
+                "Datenbankfehler: {detail}",
+                "Error de la base de datos: {detail}",
+                "Erreur de base de données : {detail}",
+            ],
+            WorktreeError => [
+                "Worktree error: {detail}",
+                "Worktree-Fehler: {detail}",
+                "Error del worktree: {detail}",
+                "Erreur du worktree : {detail}",
+            ],
+            ConfigError => [
+                "Settings error: {detail}",
+                "Fehler in den Einstellungen: {detail}",
+                "Error de configuración: {detail}",
+                "Erreur de configuration : {detail}",
+            ],
+            BackupError => [
+                "Backup error: {detail}",
+                "Sicherungsfehler: {detail}",
+                "Error de copia de seguridad: {detail}",
+                "Erreur de sauvegarde : {detail}",
+            ],
+            AgentLoginError => [
+                "Agent login error: {detail}",
+                "Fehler bei der Agentenanmeldung: {detail}",
+                "Error de inicio de sesión del agente: {detail}",
+                "Erreur de connexion de l'agent : {detail}",
+            ],
+            RepoConfigError => [
+                "Repository config error: {detail}",
+                "Fehler in der Repository-Konfiguration: {detail}",
+                "Error en la configuración del repositorio: {detail}",
+                "Erreur de configuration du dépôt : {detail}",
+            ],
+            SoundError => [
+                "Sound error: {detail}",
+                "Tonfehler: {detail}",
+                "Error de sonido: {detail}",
+                "Erreur de son : {detail}",
+            ],
+            PtyError => [
+                "Terminal error: {detail}",
+                "Terminalfehler: {detail}",
+                "Error del terminal: {detail}",
+                "Erreur du terminal : {detail}",
+            ],
+            AttachmentError => [
+                "Attachment error: {detail}",
+                "Anhangsfehler: {detail}",
+                "Error del adjunto: {detail}",
+                "Erreur de pièce jointe : {detail}",
+            ],
+            BudgetError => [
+                "Budget error: {detail}",
+                "Budgetfehler: {detail}",
+                "Error de presupuesto: {detail}",
+                "Erreur de budget : {detail}",
+            ],
+            SecretScanError => [
+                "Secret scan error: {detail}",
+                "Fehler bei der Geheimnissuche: {detail}",
+                "Error en el análisis de secretos: {detail}",
+                "Erreur de l'analyse des secrets : {detail}",
+            ],
+            PathRulesError => [
+                "Protected path error: {detail}",
+                "Fehler bei geschützten Pfaden: {detail}",
+                "Error de rutas protegidas: {detail}",
+                "Erreur de chemins protégés : {detail}",
+            ],
+            ContextPackError => [
+                "Context packing error: {detail}",
+                "Fehler beim Kontextpaket: {detail}",
+                "Error del paquete de contexto: {detail}",
+                "Erreur du paquet de contexte : {detail}",
+            ],
+            DiskQuotaError => [
+                "Disk quota error: {detail}",
+                "Speicherkontingentfehler: {detail}",
+                "Error de cuota de disco: {detail}",
+                "Erreur de quota disque : {detail}",
+            ],
+            CheckpointError => [
+                "Checkpoint error: {detail}",
+                "Checkpoint-Fehler: {detail}",
+                "Error del punto de control: {detail}",
+                "Erreur du point de contrôle : {detail}",
+            ],
+            ProjectEnvError => [
+                "Environment variable error: {detail}",
+                "Fehler bei Umgebungsvariablen: {detail}",
+                "Error de variables de entorno: {detail}",
+                "Erreur de variables d'environnement : {detail}",
+            ],
+            WorktreeTemplateError => [
+                "Worktree template error: {detail}",
+                "Fehler bei Worktree-Vorlagen: {detail}",
+                "Error de plantilla del worktree: {detail}",
+                "Erreur de modèle de worktree : {detail}",
+            ],
+            MergeQueueError => [
+                "Merge queue error: {detail}",
+                "Fehler in der Merge-Warteschlange: {detail}",
+                "Error de la cola de fusión: {detail}",
+                "Erreur de la file de fusion : {detail}",
+            ],
+            ProjectTemplateError => [
+                "Project template error: {detail}",
+                "Fehler bei der Projektvorlage: {detail}",
+                "Error de la plantilla de proyecto: {detail}",
+                "Erreur du modèle de projet : {detail}",
+            ],
+            RepoCloneError => [
+                "Repository clone error: {detail}",
+                "Fehler beim Klonen des Repositorys: {detail}",
+                "Error al clonar el repositorio: {detail}",
+                "Erreur de clonage du dépôt : {detail}",
+            ],
+            ProviderKeyError => [
+                "Provider API key error: {detail}",
+                "Fehler beim API-Schlüssel des Anbieters: {detail}",
+                "Error de la clave API del proveedor: {detail}",
+                "Erreur de clé API du fournisseur : {detail}",
+            ],
+            EvaluationError => [
+                "Evaluation error: {detail}",
+                "Fehler bei der Evaluierung: {detail}",
+                "Error de la evaluación: {detail}",
+                "Erreur d'évaluation : {detail}",
+            ],
+            WebhookError => [
+                "Webhook error: {detail}",
+                "Webhook-Fehler: {detail}",
+                "Error del webhook: {detail}",
+                "Erreur de webhook : {detail}",
+            ],
+            SentryError => [
+                "Sentry error: {detail}",
+                "Sentry-Fehler: {detail}",
+                "Error de Sentry: {detail}",
+                "Erreur Sentry : {detail}",
+            ],
+            DependencyUpdateError => [
+                "Dependency update error: {detail}",
+                "Fehler bei der Abhängigkeitsaktualisierung: {detail}",
+                "Error al actualizar las dependencias: {detail}",
+                "Erreur de mise à jour des dépendances : {detail}",
+            ],
+            ChangelogError => [
+                "Changelog error: {detail}",
+                "Fehler beim Changelog: {detail}",
+                "Error del registro de cambios: {detail}",
+                "Erreur du journal des modifications : {detail}",
+            ],
+            SupportBundleError => [
+                "Support bundle error: {detail}",
+                "Fehler beim Erstellen des Support-Pakets: {detail}",
+                "Error del paquete de soporte: {detail}",
+                "Erreur du paquet de support : {detail}",
+            ],
+            SelfUpdateError => [
+                "Update check failed: {detail}",
+                "Suche nach Updates fehlgeschlagen: {detail}",
+                "Error al buscar actualizaciones: {detail}",
+                "Échec de la recherche de mises à jour : {detail}",
+            ],
+            ShareLinkError => [
+                "Share link error: {detail}",
+                "Fehler beim Freigabelink: {detail}",
+                "Error del enlace compartido: {detail}",
+                "Erreur du lien de partage : {detail}",
+            ],
+            BadRequest => [
+                "Bad request: {detail}",
+                "Ungültige Anfrage: {detail}",
+                "Solicitud no válida: {detail}",
+                "Requête invalide : {detail}",
+            ],
+            PreconditionFailed => [
+                "Precondition failed: {detail}",
+                "Vorbedingung nicht erfüllt: {detail}",
+                "Condición previa no cumplida: {detail}",
+                "Condition préalable non remplie : {detail}",
+            ],
+            RequestBodyTooLarge => [
+                "Request body too large",
+                "Anfrage zu groß",
+                "El cuerpo de la solicitud es demasiado grande",
+                "Corps de la requête trop volumineux",
+            ],
+            RateLimited => [
+                "Too many requests, slow down and retry later",
+                "Zu viele Anfragen, bitte später erneut versuchen",
+                "Demasiadas solicitudes, vuelve a intentarlo más tarde",
+                "Trop de requêtes, réessayez plus tard",
+            ],
+            IdempotencyKeyInProgress => [
+                "A request with this Idempotency-Key is still in progress",
+                "Eine Anfrage mit diesem Idempotency-Key wird noch bearbeitet",
+                "Una solicitud con esta Idempotency-Key sigue en curso",
+                "Une requête avec cette Idempotency-Key est toujours en cours",
+            ],
+            IdempotencyKeyReused => [
+                "Idempotency-Key was already used for a different request",
+                "Der Idempotency-Key wurde bereits für eine andere Anfrage verwendet",
+                "La Idempotency-Key ya se usó para otra solicitud",
+                "L'Idempotency-Key a déjà servi pour une autre requête",
+            ],
+            ResponseReadFailed => [
+                "Failed to read response",
+                "Antwort konnte nicht gelesen werden",
+                "No se pudo leer la respuesta",
+                "Impossible de lire la réponse",
+            ],
+            ApiVersionMismatch => [
+                "This page was built for API version {client}, the server runs {server}. Reload the page",
+                "Diese Seite wurde für API-Version {client} erstellt, der Server nutzt {server}. Seite neu laden",
+                "Esta página se creó para la versión {client} de la API, el servidor usa {server}. Recarga la página",
+                "Cette page a été conçue pour la version {client} de l'API, le serveur utilise {server}. Rechargez la page",
+            ],
+            DirectoryNotFound => [
+                "Directory does not exist",
+                "Verzeichnis existiert nicht",
+                "El directorio no existe",
+                "Le répertoire n'existe pas",
+            ],
+            NotADirectory => [
+                "Path is not a directory",
+                "Pfad ist kein Verzeichnis",
+                "La ruta no es un directorio",
+                "Le chemin n'est pas un répertoire",
+            ],
+            DirectoryReadFailed => [
+                "Failed to read directory: {detail}",
+                "Verzeichnis konnte nicht gelesen werden: {detail}",
+                "No se pudo leer el directorio: {detail}",
+                "Impossible de lire le répertoire : {detail}",
+            ],
+            PathNotFound => [
+                "The specified path does not exist",
+                "Der angegebene Pfad existiert nicht",
+                "La ruta indicada no existe",
+                "Le chemin indiqué n'existe pas",
+            ],
+            PathNotADirectory => [
+                "The specified path is not a directory",
+                "Der angegebene Pfad ist kein Verzeichnis",
+                "La ruta indicada no es un directorio",
+                "Le chemin indiqué n'est pas un répertoire",
+            ],
+            NotAGitRepository => [
+                "The specified directory is not a git repository",
+                "Das angegebene Verzeichnis ist kein Git-Repository",
+                "El directorio indicado no es un repositorio git",
+                "Le répertoire indiqué n'est pas un dépôt git",
+            ],
+            MainBranchSetupFailed => [
+                "Failed to ensure main branch exists: {detail}",
+                "Main-Branch konnte nicht angelegt werden: {detail}",
+                "No se pudo crear la rama main: {detail}",
+                "Impossible de créer la branche main : {detail}",
+            ],
+            DirectoryCreateFailed => [
+                "Failed to create directory: {detail}",
+                "Verzeichnis konnte nicht erstellt werden: {detail}",
+                "No se pudo crear el directorio: {detail}",
+                "Impossible de créer le répertoire : {detail}",
+            ],
+            GitInitFailed => [
+                "Failed to initialize git repository: {detail}",
+                "Git-Repository konnte nicht initialisiert werden: {detail}",
+                "No se pudo inicializar el repositorio git: {detail}",
+                "Impossible d'initialiser le dépôt git : {detail}",
+            ],
+            ProjectRepoPathTaken => [
+                "A project with this git repository path already exists",
+                "Ein Projekt mit diesem Repository-Pfad existiert bereits",
+                "Ya existe un proyecto con esta ruta de repositorio",
+                "Un projet avec ce chemin de dépôt existe déjà",
+            ],
+            ProjectGroupNameTaken => [
+                "A project group with this name already exists",
+                "Eine Projektgruppe mit diesem Namen existiert bereits",
+                "Ya existe un grupo de proyectos con este nombre",
+                "Un groupe de projets avec ce nom existe déjà",
+            ],
+            SearchQueryRequired => [
+                "Query parameter 'q' is required and cannot be empty",
+                "Der Parameter 'q' ist erforderlich und darf nicht leer sein",
+                "El parámetro 'q' es obligatorio y no puede estar vacío",
+                "Le paramètre 'q' est obligatoire et ne peut pas être vide",
+            ],
+            GitHubPushFailed => [
+                "Failed to push branch to GitHub",
+                "Branch konnte nicht zu GitHub gepusht werden",
+                "No se pudo subir la rama a GitHub",
+                "Impossible de pousser la branche vers GitHub",
+            ],
+            PullRequestCreateFailed => [
+                "Failed to create PR",
+                "PR konnte nicht erstellt werden",
+                "No se pudo crear el PR",
+                "Impossible de créer la PR",
+            ],
+            DevServerScriptMissing => [
+                "No dev server script configured for this project",
+                "Für dieses Projekt ist kein Dev-Server-Skript eingerichtet",
+                "Este proyecto no tiene un script de servidor de desarrollo",
+                "Aucun script de serveur de développement n'est configuré pour ce projet",
+            ],
+            UnmergeNeedsConfirmation => [
+                "{branch} has {count} commit(s) on top of the merge, confirm to revert it anyway",
+                "{branch} hat {count} Commit(s) nach dem Merge, bestätige, um ihn trotzdem zurückzusetzen",
+                "{branch} tiene {count} commit(s) encima del merge, confirma para revertirlo de todos modos",
+                "{branch} a {count} commit(s) après la fusion, confirmez pour l'annuler quand même",
+            ],
+            AttemptFinishedTitle => [
+                "Task Complete: {title}",
+                "Aufgabe abgeschlossen: {title}",
+                "Tarea completada: {title}",
+                "Tâche terminée : {title}",
+            ],
+            AttemptSucceeded => [
+                "✅ '{title}' completed successfully\nBranch: {branch}\nExecutor: {executor}",
+                "✅ '{title}' erfolgreich abgeschlossen\nBranch: {branch}\nAgent: {executor}",
+                "✅ '{title}' se completó correctamente\nRama: {branch}\nAgente: {executor}",
+                "✅ '{title}' s'est terminée avec succès\nBranche : {branch}\nAgent : {executor}",
+            ],
+            AttemptFailed => [
+                "❌ '{title}' execution failed\nBranch: {branch}\nExecutor: {executor}",
+                "❌ Ausführung von '{title}' fehlgeschlagen\nBranch: {branch}\nAgent: {executor}",
+                "❌ La ejecución de '{title}' falló\nRama: {branch}\nAgente: {executor}",
+                "❌ L'exécution de '{title}' a échoué\nBranche : {branch}\nAgent : {executor}",
+            ],
+            AttemptNeedsAttentionTitle => [
+                "Task Needs Attention: {title}",
+                "Aufgabe braucht Aufmerksamkeit: {title}",
+                "La tarea necesita atención: {title}",
+                "La tâche demande votre attention : {title}",
+            ],
+            AttemptNeedsAttention => [
+                "⚠️ A script for '{title}' failed\nBranch: {branch}\nExecutor: {executor}",
+                "⚠️ Ein Skript für '{title}' ist fehlgeschlagen\nBranch: {branch}\nAgent: {executor}",
+                "⚠️ Falló un script de '{title}'\nRama: {branch}\nAgente: {executor}",
+                "⚠️ Un script de '{title}' a échoué\nBranche : {branch}\nAgent : {executor}",
+            ],
+            ConfigInvalid => [
+                "The settings have {count} invalid field(s)",
+                "Die Einstellungen enthalten {count} ungültige(s) Feld(er)",
+                "La configuración tiene {count} campo(s) no válido(s)",
+                "La configuration contient {count} champ(s) invalide(s)",
+            ],
+            ProfileNotFound => [
+                "Profile not found: {profile}",
+                "Profil nicht gefunden: {profile}",
+                "Perfil no encontrado: {profile}",
+                "Profil introuvable : {profile}",
+            ],
+            McpUnsupported => [
+                "This executor does not support MCP servers",
+                "Dieser Agent unterstützt keine MCP-Server",
+                "Este agente no admite servidores MCP",
+                "Cet agent ne prend pas en charge les serveurs MCP",
+            ],
+            McpConfigPathUnknown => [
+                "Could not determine config file path",
+                "Pfad der Konfigurationsdatei konnte nicht ermittelt werden",
+                "No se pudo determinar la ruta del archivo de configuración",
+                "Impossible de déterminer le chemin du fichier de configuration",
+            ],
+            McpUpdateFailed => [
+                "Failed to update MCP servers: {detail}",
+                "MCP-Server konnten nicht aktualisiert werden: {detail}",
+                "No se pudieron actualizar los servidores MCP: {detail}",
+                "Impossible de mettre à jour les serveurs MCP : {detail}",
+            ],
+            ProfilesInvalid => [
+                "Invalid profiles format: {detail}",
+                "Ungültiges Profilformat: {detail}",
+                "Formato de perfiles no válido: {detail}",
+                "Format de profils invalide : {detail}",
+            ],
+            ProfilesUnknownFields => [
+                "profiles.json had unknown fields that were ignored: {fields}",
+                "Unbekannte Felder in profiles.json wurden ignoriert: {fields}",
+                "Se ignoraron campos desconocidos de profiles.json: {fields}",
+                "Des champs inconnus de profiles.json ont été ignorés : {fields}",
+            ],
+            ProfilesUnreadable => [
+                "profiles.json could not be read, showing the default profiles: {detail}",
+                "profiles.json konnte nicht gelesen werden, Standardprofile werden angezeigt: {detail}",
+                "No se pudo leer profiles.json, se muestran los perfiles predeterminados: {detail}",
+                "Impossible de lire profiles.json, les profils par défaut sont affichés : {detail}",
+            ],
+        }
+    }
+}
+
+impl fmt::Display for MessageCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match serde_json::to_value(self) {
+            Ok(serde_json::Value::String(code)) => f.write_str(&code),
+            _ => Err(fmt::Error),
+        }
+    }
+}
+
+/// A catalog message with its parameters, rendered once the locale is known
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Message {
+    pub code: MessageCode,
+    pub params: Vec<(&'static str, String)>,
+}
+
+impl Message {
+    pub fn new(code: MessageCode) -> Self {
+        Self {
+            code,
+            params: Vec::new(),
+        }
+    }
+
+    pub fn with(mut self, name: &'static str, value: impl ToString) -> Self {
+        self.params.push((name, value.to_string()));
+        self
+    }
+
+    /// Shorthand for messages whose only parameter is the underlying error
+    pub fn detail(code: MessageCode, detail: impl ToString) -> Self {
+        Self::new(code).with("detail", detail)
+    }
+
+    pub fn render(&self, locale: Locale) -> String {
+        let mut text = self.code.templates()[locale.index()].to_string();
+        for (name, value) in &self.params {
+            text = text.replace(&format!("{{{name}}}"), value);
+        }
+        text
+    }
+
+    /// Renders in the locale from the user's config
+    pub fn localized(&self) -> String {
+        self.render(current_locale())
+    }
+}
+
+impl From<MessageCode> for Message {
+    fn from(code: MessageCode) -> Self {
+        Self::new(code)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_fills_params_per_locale() {
+        let message = Message::new(MessageCode::AttemptFinishedTitle).with("title", "Fix login");
+        assert_eq!(message.render(Locale::En), "Task Complete: Fix login");
+        assert_eq!(
+            message.render(Locale::De),
+            "Aufgabe abgeschlossen: Fix login"
+        );
+
+        let message = Message::detail(MessageCode::DirectoryReadFailed, "permission denied");
+        assert_eq!(
+            message.render(Locale::Es),
+            "No se pudo leer el directorio: permission denied"
+        );
+    }
+
+    #[test]
+    fn test_codes_serialize_as_stable_strings() {
+        assert_eq!(
+            serde_json::to_value(MessageCode::GitHubPushFailed).unwrap(),
+            "github_push_failed"
+        );
+        assert_eq!(MessageCode::NotADirectory.to_string(), "not_a_directory");
+        assert_eq!(serde_json::to_value(Locale::Fr).unwrap(), "fr");
+    }
+
+    #[test]
+    fn test_translations_keep_placeholders() {
+        let codes = [
+            MessageCode::AttemptSucceeded,
+            MessageCode::AttemptFailed,
+            MessageCode::AttemptNeedsAttention,
+            MessageCode::ProjectError,
+            MessageCode::ProfileNotFound,
+        ];
+        for code in codes {
+            let templates = code.templates();
+            let placeholders = |template: &str| {
+                let mut names: Vec<_> = template
+                    .split('{')
+                    .skip(1)
+                    .filter_map(|rest| rest.split_once('}').map(|(name, _)| name.to_string()))
+                    .collect();
+                names.sort();
+                names
+            };
+            for locale in Locale::ALL {
+                assert_eq!(
+                    placeholders(templates[locale.index()]),
+                    placeholders(templates[Locale::En.index()]),
+                    "{code:?} in {locale:?}"
+                );
+            }
+        }
+    }
+}
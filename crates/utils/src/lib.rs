@@ -5,15 +5,23 @@ use directories::ProjectDirs;
 pub mod assets;
 pub mod browser;
 pub mod diff;
+pub mod i18n;
 pub mod log_msg;
+pub mod magic;
 pub mod msg_store;
+pub mod offline;
+pub mod output_encoding;
+pub mod otel;
+pub mod pagination;
 pub mod path;
 pub mod port_file;
 pub mod response;
 pub mod sentry;
+pub mod server_log;
 pub mod shell;
 pub mod stream_lines;
 pub mod text;
+pub mod workspaces;
 
 /// Cache for WSL2 detection result
 static WSL2_CACHE: OnceLock<bool> = OnceLock::new();
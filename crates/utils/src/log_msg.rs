@@ -6,14 +6,27 @@ pub const EV_STDOUT: &str = "stdout";
 pub const EV_STDERR: &str = "stderr";
 pub const EV_JSON_PATCH: &str = "json_patch";
 pub const EV_SESSION_ID: &str = "session_id";
+pub const EV_COST: &str = "cost";
+pub const EV_TOKENS: &str = "tokens";
 pub const EV_FINISHED: &str = "finished";
 
+/// Tokens an agent reported using for its run. Cached prompt reads and writes count as
+/// input.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TokenUsage {
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub enum LogMsg {
     Stdout(String),
     Stderr(String),
     JsonPatch(Patch),
     SessionId(String),
+    /// Cost in USD the agent reported for its run
+    Cost(f64),
+    Tokens(TokenUsage),
     Finished,
 }
 
@@ -24,6 +37,8 @@ impl LogMsg {
             LogMsg::Stderr(_) => EV_STDERR,
             LogMsg::JsonPatch(_) => EV_JSON_PATCH,
             LogMsg::SessionId(_) => EV_SESSION_ID,
+            LogMsg::Cost(_) => EV_COST,
+            LogMsg::Tokens(_) => EV_TOKENS,
             LogMsg::Finished => EV_FINISHED,
         }
     }
@@ -37,6 +52,11 @@ impl LogMsg {
                 Event::default().event(EV_JSON_PATCH).data(data)
             }
             LogMsg::SessionId(s) => Event::default().event(EV_SESSION_ID).data(s.clone()),
+            LogMsg::Cost(usd) => Event::default().event(EV_COST).data(usd.to_string()),
+            LogMsg::Tokens(usage) => {
+                let data = serde_json::to_string(usage).unwrap_or_else(|_| "{}".to_string());
+                Event::default().event(EV_TOKENS).data(data)
+            }
             LogMsg::Finished => Event::default().event(EV_FINISHED).data(""),
         }
     }
@@ -52,6 +72,8 @@ impl LogMsg {
                 EV_JSON_PATCH.len() + json_len + OVERHEAD
             }
             LogMsg::SessionId(s) => EV_SESSION_ID.len() + s.len() + OVERHEAD,
+            LogMsg::Cost(_) => EV_COST.len() + 8 + OVERHEAD,
+            LogMsg::Tokens(_) => EV_TOKENS.len() + 16 + OVERHEAD,
             LogMsg::Finished => EV_FINISHED.len() + OVERHEAD,
         }
     }
@@ -0,0 +1,6 @@
+//! Recognising file formats by their first bytes rather than trusting their names
+
+/// Whether `data` holds the bytes `magic` starting at `offset`
+pub fn magic_at(data: &[u8], offset: usize, magic: &[u8]) -> bool {
+    data.get(offset..offset + magic.len()) == Some(magic)
+}
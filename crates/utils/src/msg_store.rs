@@ -8,7 +8,10 @@ use futures::{StreamExt, TryStreamExt, future};
 use tokio::{sync::broadcast, task::JoinHandle};
 use tokio_stream::wrappers::BroadcastStream;
 
-use crate::{log_msg::LogMsg, stream_lines::LinesStreamExt};
+use crate::{
+    log_msg::{LogMsg, TokenUsage},
+    stream_lines::LinesStreamExt,
+};
 
 // 100 MB Limit
 const HISTORY_BYTES: usize = 100000 * 1024;
@@ -78,6 +81,14 @@ impl MsgStore {
         self.push(LogMsg::SessionId(session_id));
     }
 
+    pub fn push_cost(&self, usd: f64) {
+        self.push(LogMsg::Cost(usd));
+    }
+
+    pub fn push_tokens(&self, usage: TokenUsage) {
+        self.push(LogMsg::Tokens(usage));
+    }
+
     pub fn push_finished(&self) {
         self.push(LogMsg::Finished);
     }
@@ -0,0 +1,14 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+
+static OFFLINE: AtomicBool = AtomicBool::new(false);
+
+/// Keep vibe-kanban from contacting outside services, call once at startup
+pub fn set_offline(offline: bool) {
+    OFFLINE.store(offline, Ordering::Relaxed);
+}
+
+/// Whether features reaching outside services (telemetry, baseline fetches, GitHub
+/// polling) are turned off. Agents and git remotes are still used as configured.
+pub fn is_offline() -> bool {
+    OFFLINE.load(Ordering::Relaxed)
+}
@@ -0,0 +1,113 @@
+use opentelemetry::{
+    Context,
+    trace::{
+        SpanContext, SpanId, TraceContextExt, TraceFlags, TraceId, TraceState, TracerProvider,
+    },
+};
+use opentelemetry_otlp::{ExporterBuildError, SpanExporter, WithExportConfig};
+use opentelemetry_sdk::{
+    Resource,
+    trace::{SdkTracer, SdkTracerProvider},
+};
+use tracing::Span;
+use tracing_opentelemetry::{OpenTelemetryLayer, OpenTelemetrySpanExt};
+use uuid::Uuid;
+
+const SERVICE_NAME: &str = "vibe-kanban";
+
+/// Layer exporting spans to an OTLP collector over HTTP, e.g. `http://localhost:4318`.
+/// Shut the provider down before exiting to flush the last spans.
+pub fn otlp_layer<S>(
+    endpoint: &str,
+) -> Result<(OpenTelemetryLayer<S, SdkTracer>, SdkTracerProvider), ExporterBuildError>
+where
+    S: tracing::Subscriber,
+    S: for<'a> tracing_subscriber::registry::LookupSpan<'a>,
+{
+    let exporter = SpanExporter::builder()
+        .with_http()
+        .with_endpoint(format!("{}/v1/traces", endpoint.trim_end_matches('/')))
+        .build()?;
+    let provider = SdkTracerProvider::builder()
+        .with_batch_exporter(exporter)
+        .with_resource(Resource::builder().with_service_name(SERVICE_NAME).build())
+        .build();
+    let layer = tracing_opentelemetry::layer().with_tracer(provider.tracer(SERVICE_NAME));
+    Ok((layer, provider))
+}
+
+/// Span for work on a task attempt started outside a request of that attempt, e.g. by a
+/// background task. See [`join_attempt_trace`].
+pub fn attempt_span(name: &'static str, attempt_id: Uuid) -> Span {
+    let span = tracing::info_span!("attempt", otel.name = name, attempt_id = %attempt_id);
+    join_attempt_trace(&span, attempt_id);
+    span
+}
+
+/// Put `span`, and so everything that happens inside it, in the attempt's trace. The trace
+/// ID is the attempt ID, so requests, execution chains and git operations of an attempt
+/// end up in one trace even across restarts. Has to be called before `span` is entered,
+/// and does nothing while spans aren't exported.
+pub fn join_attempt_trace(span: &Span, attempt_id: Uuid) {
+    let parent = Context::new().with_remote_span_context(attempt_span_context(attempt_id));
+    let _ = span.set_parent(parent);
+}
+
+/// The trace's root stands for the attempt itself, it is never exported
+fn attempt_span_context(attempt_id: Uuid) -> SpanContext {
+    let bytes = attempt_id.into_bytes();
+    let mut span_id = [0; 8];
+    span_id.copy_from_slice(&bytes[8..]);
+    SpanContext::new(
+        TraceId::from_bytes(bytes),
+        SpanId::from_bytes(span_id),
+        TraceFlags::SAMPLED,
+        true,
+        TraceState::default(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use opentelemetry_sdk::trace::InMemorySpanExporter;
+    use tracing_subscriber::prelude::*;
+
+    use super::*;
+
+    #[test]
+    fn test_spans_of_an_attempt_share_its_trace() {
+        let exporter = InMemorySpanExporter::default();
+        let provider = SdkTracerProvider::builder()
+            .with_simple_exporter(exporter.clone())
+            .build();
+        let subscriber = tracing_subscriber::registry()
+            .with(tracing_opentelemetry::layer().with_tracer(provider.tracer("test")));
+        let attempt_id = Uuid::new_v4();
+
+        tracing::subscriber::with_default(subscriber, || {
+            let request = tracing::info_span!("request");
+            let _request = request.enter();
+            attempt_span("start_attempt", attempt_id).in_scope(|| {
+                tracing::info_span!("create_worktree").in_scope(|| {});
+            });
+            attempt_span("exit_monitor", attempt_id).in_scope(|| {});
+        });
+
+        let spans = exporter.get_finished_spans().unwrap();
+        let trace_id = TraceId::from_bytes(attempt_id.into_bytes());
+        let in_attempt: Vec<&str> = spans
+            .iter()
+            .filter(|span| span.span_context.trace_id() == trace_id)
+            .map(|span| span.name.as_ref())
+            .collect();
+        assert_eq!(
+            in_attempt,
+            vec!["create_worktree", "start_attempt", "exit_monitor"]
+        );
+        assert!(
+            spans
+                .iter()
+                .any(|span| span.name == "request" && span.span_context.trace_id() != trace_id)
+        );
+    }
+}
@@ -0,0 +1,147 @@
+//! Decoding of the bytes processes write to stdout and stderr. Output is read in chunks that
+//! can end inside a character, so decoding keeps state between them.
+
+use encoding_rs::{Decoder, Encoding, UTF_8, UTF_16LE};
+
+/// Encoding named by a WHATWG label such as `utf-8`, `utf-16le`, `windows-1252` or `shift_jis`.
+/// `None` and `auto` detect the encoding from the output.
+pub fn encoding_for_label(label: Option<&str>) -> Result<Option<&'static Encoding>, String> {
+    match label.map(str::trim) {
+        None | Some("") => Ok(None),
+        Some(label) if label.eq_ignore_ascii_case("auto") => Ok(None),
+        Some(label) => Encoding::for_label(label.as_bytes())
+            .map(Some)
+            .ok_or_else(|| format!("Unknown encoding: {label}")),
+    }
+}
+
+/// Charset of the locale from `LC_ALL`, `LC_CTYPE` or `LANG`, e.g. `ja_JP.SJIS`
+fn locale_encoding() -> Option<&'static Encoding> {
+    let locale = ["LC_ALL", "LC_CTYPE", "LANG"]
+        .into_iter()
+        .filter_map(|name| std::env::var(name).ok())
+        .find(|value| !value.is_empty())?;
+    let charset = locale.split_once('.')?.1;
+    let charset = charset.split('@').next().unwrap_or(charset);
+    Encoding::for_label(charset.as_bytes())
+}
+
+/// Whether `bytes` look like UTF-16LE without a byte order mark, as some Windows tools write:
+/// mostly ASCII text with every second byte zero
+fn looks_like_utf16le(bytes: &[u8]) -> bool {
+    let pairs = bytes.len() / 2;
+    if pairs < 2 {
+        return false;
+    }
+    let ascii_pairs = bytes
+        .chunks_exact(2)
+        .filter(|pair| pair[0] != 0 && pair[1] == 0)
+        .count();
+    ascii_pairs * 4 >= pairs * 3
+}
+
+/// Turns the chunks of one output stream into text. Invalid sequences become U+FFFD instead
+/// of failing, and a character split across chunks is decoded once its end arrives.
+pub struct OutputDecoder {
+    encoding: Option<&'static Encoding>,
+    decoder: Option<Decoder>,
+}
+
+impl OutputDecoder {
+    /// Decodes as `encoding`, or detects it from the first chunk when `None`: a byte order
+    /// mark, UTF-16LE without one, the locale's charset, then UTF-8
+    pub fn new(encoding: Option<&'static Encoding>) -> Self {
+        Self {
+            encoding,
+            decoder: None,
+        }
+    }
+
+    pub fn decode(&mut self, bytes: &[u8]) -> String {
+        self.decode_chunk(bytes, false)
+    }
+
+    /// Whatever is left of an incomplete character at the end of the stream
+    pub fn finish(&mut self) -> String {
+        self.decode_chunk(&[], true)
+    }
+
+    fn decode_chunk(&mut self, bytes: &[u8], last: bool) -> String {
+        let encoding = self.encoding;
+        let decoder = self.decoder.get_or_insert_with(|| {
+            let encoding = encoding.unwrap_or_else(|| {
+                if looks_like_utf16le(bytes) {
+                    UTF_16LE
+                } else {
+                    locale_encoding().unwrap_or(UTF_8)
+                }
+            });
+            // A byte order mark overrides the chosen encoding
+            encoding.new_decoder()
+        });
+        let mut text = String::with_capacity(
+            decoder
+                .max_utf8_buffer_length(bytes.len())
+                .unwrap_or(bytes.len() * 3),
+        );
+        let _ = decoder.decode_to_string(bytes, &mut text, last);
+        text
+    }
+}
+
+impl Default for OutputDecoder {
+    fn default() -> Self {
+        Self::new(None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn decode_in_chunks(decoder: &mut OutputDecoder, bytes: &[u8], size: usize) -> String {
+        let mut text: String = bytes.chunks(size).map(|c| decoder.decode(c)).collect();
+        text.push_str(&decoder.finish());
+        text
+    }
+
+    #[test]
+    fn test_characters_split_across_chunks_are_kept_whole() {
+        let mut decoder = OutputDecoder::new(Some(UTF_8));
+        let text = decode_in_chunks(&mut decoder, "héllo — 世界\n".as_bytes(), 1);
+        assert_eq!(text, "héllo — 世界\n");
+    }
+
+    #[test]
+    fn test_invalid_bytes_are_replaced() {
+        let mut decoder = OutputDecoder::new(Some(UTF_8));
+        assert_eq!(
+            decoder.decode(b"ok \xff\xfe done"),
+            "ok \u{fffd}\u{fffd} done"
+        );
+    }
+
+    #[test]
+    fn test_utf16_is_detected() {
+        let utf16: Vec<u8> = "Ubuntu\r\n"
+            .encode_utf16()
+            .flat_map(|unit| unit.to_le_bytes())
+            .collect();
+        let mut decoder = OutputDecoder::new(None);
+        assert_eq!(decode_in_chunks(&mut decoder, &utf16, 4), "Ubuntu\r\n");
+
+        let mut with_bom = vec![0xff, 0xfe];
+        with_bom.extend(&utf16);
+        let mut decoder = OutputDecoder::new(None);
+        assert_eq!(decode_in_chunks(&mut decoder, &with_bom, 5), "Ubuntu\r\n");
+    }
+
+    #[test]
+    fn test_codepage_override() {
+        let encoding = encoding_for_label(Some("windows-1252")).unwrap();
+        let mut decoder = OutputDecoder::new(encoding);
+        assert_eq!(decoder.decode(b"caf\xe9"), "café");
+        assert!(encoding_for_label(Some("auto")).unwrap().is_none());
+        assert!(encoding_for_label(Some("klingon")).is_err());
+    }
+}
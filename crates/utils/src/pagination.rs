@@ -0,0 +1,325 @@
+use std::{cmp::Ordering, collections::HashMap};
+
+use axum::{
+    extract::{FromRequestParts, Query},
+    http::{StatusCode, request::Parts},
+    response::{IntoResponse, Json, Response},
+};
+use serde::Serialize;
+use ts_rs::TS;
+
+use crate::response::ApiResponse;
+
+/// Page size used when `page` is given without `per_page`.
+pub const DEFAULT_PER_PAGE: u32 = 50;
+/// Upper bound on `per_page`, larger values are clamped.
+pub const MAX_PER_PAGE: u32 = 500;
+
+/// Envelope returned by list endpoints.
+#[derive(Debug, Serialize, TS)]
+pub struct Paginated<T> {
+    pub items: Vec<T>,
+    /// Number of items matching the filters, across all pages
+    pub total: u32,
+    pub page: u32,
+    /// `null` when the request was not paginated and every item was returned
+    pub per_page: Option<u32>,
+    pub has_more: bool,
+}
+
+/// Where a page sits in its list, repeated in the response envelope so clients can read it the
+/// same way for every list endpoint
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, TS)]
+pub struct PageMeta {
+    pub total: u32,
+    pub page: u32,
+    pub per_page: Option<u32>,
+    pub has_more: bool,
+}
+
+impl<T> Paginated<T> {
+    pub fn meta(&self) -> PageMeta {
+        PageMeta {
+            total: self.total,
+            page: self.page,
+            per_page: self.per_page,
+            has_more: self.has_more,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SortDirection {
+    Asc,
+    Desc,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SortSpec {
+    pub field: String,
+    pub direction: SortDirection,
+}
+
+/// A model that can be filtered and sorted by a [`ListQuery`].
+pub trait Listable {
+    /// Fields accepted in `?sort=`
+    const SORT_FIELDS: &'static [&'static str];
+    /// Fields accepted in `?filter[<field>]=`
+    const FILTER_FIELDS: &'static [&'static str];
+
+    /// Ascending comparison on `field`, which is always one of `SORT_FIELDS`.
+    fn compare_by(&self, other: &Self, field: &str) -> Ordering;
+
+    /// Whether `field` (always one of `FILTER_FIELDS`) matches `value`.
+    fn matches_filter(&self, field: &str, value: &str) -> bool;
+}
+
+/// Shared `?page=&per_page=&sort=&filter[<field>]=` extractor for list endpoints.
+///
+/// `sort` takes a field name, prefixed with `-` for descending order. Without `page`
+/// or `per_page` every matching item is returned, so existing clients keep working.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ListQuery {
+    pub page: Option<u32>,
+    pub per_page: Option<u32>,
+    pub sort: Option<SortSpec>,
+    pub filters: HashMap<String, String>,
+}
+
+#[derive(Debug)]
+pub struct ListQueryRejection(pub String);
+
+impl IntoResponse for ListQueryRejection {
+    fn into_response(self) -> Response {
+        (
+            StatusCode::BAD_REQUEST,
+            Json(ApiResponse::<()>::error(&self.0)),
+        )
+            .into_response()
+    }
+}
+
+impl<S> FromRequestParts<S> for ListQuery
+where
+    S: Send + Sync,
+{
+    type Rejection = ListQueryRejection;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        let Query(pairs) = Query::<Vec<(String, String)>>::try_from_uri(&parts.uri)
+            .map_err(|e| ListQueryRejection(e.body_text()))?;
+        Self::from_pairs(pairs).map_err(ListQueryRejection)
+    }
+}
+
+impl ListQuery {
+    /// Parses the list parameters out of raw query pairs, ignoring unrelated keys.
+    pub fn from_pairs<I>(pairs: I) -> Result<Self, String>
+    where
+        I: IntoIterator<Item = (String, String)>,
+    {
+        let mut query = ListQuery::default();
+        for (key, value) in pairs {
+            match key.as_str() {
+                "page" => query.page = Some(parse_positive(&key, &value)?),
+                "per_page" => {
+                    query.per_page = Some(parse_positive(&key, &value)?.min(MAX_PER_PAGE))
+                }
+                "sort" => query.sort = parse_sort(&value),
+                _ => {
+                    if let Some(field) = key
+                        .strip_prefix("filter[")
+                        .and_then(|rest| rest.strip_suffix(']'))
+                    {
+                        query.filters.insert(field.to_string(), value);
+                    }
+                }
+            }
+        }
+        Ok(query)
+    }
+
+    /// Filters, sorts and slices `items` according to this query.
+    pub fn apply<T: Listable>(&self, mut items: Vec<T>) -> Result<Paginated<T>, String> {
+        for field in self.filters.keys() {
+            if !T::FILTER_FIELDS.contains(&field.as_str()) {
+                return Err(format!(
+                    "Unknown filter '{}', expected one of: {}",
+                    field,
+                    T::FILTER_FIELDS.join(", ")
+                ));
+            }
+        }
+        if let Some(sort) = &self.sort
+            && !T::SORT_FIELDS.contains(&sort.field.as_str())
+        {
+            return Err(format!(
+                "Unknown sort field '{}', expected one of: {}",
+                sort.field,
+                T::SORT_FIELDS.join(", ")
+            ));
+        }
+
+        items.retain(|item| {
+            self.filters
+                .iter()
+                .all(|(field, value)| item.matches_filter(field, value))
+        });
+
+        if let Some(sort) = &self.sort {
+            items.sort_by(|a, b| {
+                let ordering = a.compare_by(b, &sort.field);
+                match sort.direction {
+                    SortDirection::Asc => ordering,
+                    SortDirection::Desc => ordering.reverse(),
+                }
+            });
+        }
+
+        let total = items.len() as u32;
+        if self.page.is_none() && self.per_page.is_none() {
+            return Ok(Paginated {
+                items,
+                total,
+                page: 1,
+                per_page: None,
+                has_more: false,
+            });
+        }
+
+        let page = self.page.unwrap_or(1);
+        let per_page = self.per_page.unwrap_or(DEFAULT_PER_PAGE);
+        let start = (page - 1).saturating_mul(per_page);
+        let items: Vec<T> = items
+            .into_iter()
+            .skip(start.min(total) as usize)
+            .take(per_page as usize)
+            .collect();
+        let has_more = start.saturating_add(items.len() as u32) < total;
+
+        Ok(Paginated {
+            items,
+            total,
+            page,
+            per_page: Some(per_page),
+            has_more,
+        })
+    }
+}
+
+/// Compares a serialized enum (or any string-like value) against a filter value.
+pub fn serialized_eq<T: Serialize>(value: &T, expected: &str) -> bool {
+    match serde_json::to_value(value) {
+        Ok(serde_json::Value::String(s)) => s.eq_ignore_ascii_case(expected),
+        Ok(other) => serde_json::from_str::<serde_json::Value>(expected).is_ok_and(|v| v == other),
+        Err(_) => false,
+    }
+}
+
+fn parse_positive(key: &str, value: &str) -> Result<u32, String> {
+    match value.parse::<u32>() {
+        Ok(n) if n > 0 => Ok(n),
+        _ => Err(format!("'{key}' must be a positive integer")),
+    }
+}
+
+fn parse_sort(value: &str) -> Option<SortSpec> {
+    let value = value.trim();
+    let (field, direction) = match value.strip_prefix('-') {
+        Some(field) => (field, SortDirection::Desc),
+        None => (value.strip_prefix('+').unwrap_or(value), SortDirection::Asc),
+    };
+    if field.is_empty() {
+        return None;
+    }
+    Some(SortSpec {
+        field: field.to_string(),
+        direction,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, PartialEq)]
+    struct Item {
+        n: u32,
+        kind: &'static str,
+    }
+
+    impl Listable for Item {
+        const SORT_FIELDS: &'static [&'static str] = &["n"];
+        const FILTER_FIELDS: &'static [&'static str] = &["kind"];
+
+        fn compare_by(&self, other: &Self, _field: &str) -> Ordering {
+            self.n.cmp(&other.n)
+        }
+
+        fn matches_filter(&self, _field: &str, value: &str) -> bool {
+            self.kind == value
+        }
+    }
+
+    fn items() -> Vec<Item> {
+        (1..=5)
+            .map(|n| Item {
+                n,
+                kind: if n % 2 == 0 { "even" } else { "odd" },
+            })
+            .collect()
+    }
+
+    fn query(pairs: &[(&str, &str)]) -> ListQuery {
+        ListQuery::from_pairs(pairs.iter().map(|(k, v)| (k.to_string(), v.to_string()))).unwrap()
+    }
+
+    #[test]
+    fn test_unpaginated_returns_everything() {
+        let result = ListQuery::default().apply(items()).unwrap();
+        assert_eq!(result.items.len(), 5);
+        assert_eq!(result.per_page, None);
+        assert!(!result.has_more);
+    }
+
+    #[test]
+    fn test_sort_filter_and_page() {
+        let q = query(&[
+            ("sort", "-n"),
+            ("filter[kind]", "odd"),
+            ("per_page", "2"),
+            ("project_id", "ignored"),
+        ]);
+        let result = q.apply(items()).unwrap();
+        assert_eq!(result.total, 3);
+        assert_eq!(
+            result.items.iter().map(|i| i.n).collect::<Vec<_>>(),
+            vec![5, 3]
+        );
+        assert!(result.has_more);
+
+        let q = query(&[
+            ("sort", "-n"),
+            ("filter[kind]", "odd"),
+            ("page", "2"),
+            ("per_page", "2"),
+        ]);
+        let result = q.apply(items()).unwrap();
+        assert_eq!(
+            result.items.iter().map(|i| i.n).collect::<Vec<_>>(),
+            vec![1]
+        );
+        assert!(!result.has_more);
+    }
+
+    #[test]
+    fn test_rejects_unknown_fields_and_bad_numbers() {
+        assert!(query(&[("sort", "missing")]).apply(items()).is_err());
+        assert!(query(&[("filter[missing]", "x")]).apply(items()).is_err());
+        assert!(ListQuery::from_pairs(vec![("page".to_string(), "0".to_string())]).is_err());
+        assert_eq!(
+            query(&[("per_page", "100000")]).per_page,
+            Some(MAX_PER_PAGE)
+        );
+    }
+}
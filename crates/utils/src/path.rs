@@ -1,4 +1,7 @@
-use std::path::{Path, PathBuf};
+use std::{
+    path::{Path, PathBuf},
+    sync::OnceLock,
+};
 
 /// Convert absolute paths to relative paths based on worktree path
 /// This is a robust implementation that handles symlinks and edge cases
@@ -101,6 +104,34 @@ fn normalize_macos_private_alias<P: AsRef<Path>>(p: P) -> PathBuf {
     p.to_path_buf()
 }
 
+/// Worktree directory configured at startup, overriding the default under the temp dir
+static WORKSPACE_DIR_OVERRIDE: OnceLock<std::path::PathBuf> = OnceLock::new();
+
+/// Override the directory worktrees are created in, call once at startup
+pub fn set_workspace_dir(path: std::path::PathBuf) {
+    if WORKSPACE_DIR_OVERRIDE.set(path).is_err() {
+        tracing::warn!("Workspace directory already configured, ignoring override");
+    }
+}
+
+/// Base directory for task attempt worktrees
+pub fn get_workspace_dir() -> std::path::PathBuf {
+    WORKSPACE_DIR_OVERRIDE
+        .get()
+        .cloned()
+        .unwrap_or_else(|| get_vibe_kanban_temp_dir().join("worktrees"))
+}
+
+/// Base directory for the package caches projects share across their worktrees
+pub fn get_dependency_cache_dir() -> std::path::PathBuf {
+    get_vibe_kanban_temp_dir().join("caches")
+}
+
+/// Directory of the evaluated Nix development shells, shared by all projects
+pub fn get_nix_env_cache_dir() -> std::path::PathBuf {
+    get_vibe_kanban_temp_dir().join("nix-envs")
+}
+
 pub fn get_vibe_kanban_temp_dir() -> std::path::PathBuf {
     let dir_name = if cfg!(debug_assertions) {
         "vibe-kanban-dev"
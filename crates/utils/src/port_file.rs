@@ -1,12 +1,40 @@
-use std::{env, path::PathBuf};
+use std::{
+    env,
+    path::{Path, PathBuf},
+};
 
 use tokio::fs;
 
+/// Environment variable pointing a dev server at its port file
+pub const DEV_SERVER_PORT_FILE_ENV: &str = "VK_PORT_FILE";
+
+fn port_file_dir() -> PathBuf {
+    env::temp_dir().join("vibe-kanban")
+}
+
 pub async fn write_port_file(port: u16) -> std::io::Result<PathBuf> {
-    let dir = env::temp_dir().join("vibe-kanban");
+    let dir = port_file_dir();
     let path = dir.join("vibe-kanban.port");
     tracing::debug!("Writing port {} to {:?}", port, path);
     fs::create_dir_all(&dir).await?;
     fs::write(&path, port.to_string()).await?;
     Ok(path)
 }
+
+/// Where a dev server running in `worktree` can write the port it listens on, in the
+/// same format as our own port file. Worktree directory names are unique per attempt.
+pub fn dev_server_port_file(worktree: &Path) -> PathBuf {
+    let name = worktree
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    port_file_dir()
+        .join("dev-servers")
+        .join(format!("{name}.port"))
+}
+
+/// Port in a port file, `None` when the file is missing or doesn't hold a port yet
+pub async fn read_port_file(path: &Path) -> Option<u16> {
+    let contents = fs::read_to_string(path).await.ok()?;
+    contents.trim().parse().ok().filter(|port| *port != 0)
+}
@@ -1,41 +1,186 @@
+use std::{future::Future, sync::Mutex};
+
 use serde::Serialize;
 use ts_rs::TS;
 
+use crate::{
+    i18n::{Message, MessageCode},
+    pagination::{PageMeta, Paginated},
+};
+
+/// Header carrying the request id, taken from the client when it sends one
+pub const REQUEST_ID_HEADER: &str = "x-request-id";
+
+/// A problem that didn't stop the request, e.g. part of the input being ignored
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, TS)]
+pub struct ApiWarning {
+    pub code: MessageCode,
+    /// In the locale from the user's config
+    pub message: String,
+}
+
+impl From<Message> for ApiWarning {
+    fn from(message: Message) -> Self {
+        Self {
+            code: message.code,
+            message: message.localized(),
+        }
+    }
+}
+
+struct RequestContext {
+    id: String,
+    warnings: Mutex<Vec<ApiWarning>>,
+}
+
+tokio::task_local! {
+    static REQUEST: RequestContext;
+}
+
+/// Runs `future` as the request `id`: responses built inside it carry the id, and the warnings
+/// passed to [`warn`] while it runs
+pub async fn scope_request<F: Future>(id: String, future: F) -> F::Output {
+    let context = RequestContext {
+        id,
+        warnings: Mutex::new(Vec::new()),
+    };
+    REQUEST.scope(context, future).await
+}
+
+/// Id of the request being handled, `None` outside of one
+pub fn current_request_id() -> Option<String> {
+    REQUEST.try_with(|request| request.id.clone()).ok()
+}
+
+/// Adds a warning to the response of the request being handled. Outside of a request, e.g. in
+/// background work, it is only logged.
+pub fn warn(message: impl Into<Message>) {
+    let warning = ApiWarning::from(message.into());
+    tracing::warn!("{}", warning.message);
+    let _ = REQUEST.try_with(|request| {
+        request
+            .warnings
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .push(warning)
+    });
+}
+
+fn take_warnings() -> Vec<ApiWarning> {
+    REQUEST
+        .try_with(|request| {
+            std::mem::take(&mut *request.warnings.lock().unwrap_or_else(|e| e.into_inner()))
+        })
+        .unwrap_or_default()
+}
+
 #[derive(Debug, Serialize, TS)]
 pub struct ApiResponse<T, E = T> {
     success: bool,
     data: Option<T>,
     error_data: Option<E>,
     message: Option<String>,
+    /// Catalog code of `message`, for clients that show their own text
+    code: Option<MessageCode>,
+    /// Problems that didn't stop the request, e.g. unknown fields that were ignored
+    warnings: Vec<ApiWarning>,
+    /// Set on list responses
+    pagination: Option<PageMeta>,
+    /// Also in the `x-request-id` header, for finding the request in the server log
+    request_id: Option<String>,
 }
 
 impl<T, E> ApiResponse<T, E> {
-    /// Creates a successful response, with `data` and no message.
-    pub fn success(data: T) -> Self {
+    /// Every response of a request carries its id and the warnings raised while it ran.
+    fn new(
+        success: bool,
+        data: Option<T>,
+        error_data: Option<E>,
+        message: Option<String>,
+        code: Option<MessageCode>,
+    ) -> Self {
         ApiResponse {
-            success: true,
-            data: Some(data),
-            message: None,
-            error_data: None,
+            success,
+            data,
+            error_data,
+            message,
+            code,
+            warnings: take_warnings(),
+            pagination: None,
+            request_id: current_request_id(),
         }
     }
 
+    /// Creates a successful response, with `data` and no message.
+    pub fn success(data: T) -> Self {
+        Self::new(true, Some(data), None, None, None)
+    }
+
     /// Creates an error response, with `message` and no data.
     pub fn error(message: &str) -> Self {
-        ApiResponse {
-            success: false,
-            data: None,
-            message: Some(message.to_string()),
-            error_data: None,
-        }
+        Self::new(false, None, None, Some(message.to_string()), None)
     }
+
+    /// Creates an error response from a catalog message, rendered in the configured locale.
+    pub fn localized_error(message: impl Into<Message>) -> Self {
+        let message = message.into();
+        Self::new(
+            false,
+            None,
+            None,
+            Some(message.localized()),
+            Some(message.code),
+        )
+    }
+
     /// Creates an error response, with no `data`, no `message`, but with arbitrary `error_data`.
     pub fn error_with_data(data: E) -> Self {
-        ApiResponse {
-            success: false,
-            data: None,
-            error_data: Some(data),
-            message: None,
-        }
+        Self::new(false, None, Some(data), None, None)
+    }
+
+    /// Adds a warning on top of those raised through [`warn`].
+    pub fn with_warning(mut self, message: impl Into<Message>) -> Self {
+        self.warnings.push(ApiWarning::from(message.into()));
+        self
+    }
+
+    pub fn warnings(&self) -> &[ApiWarning] {
+        &self.warnings
+    }
+}
+
+impl<T, E> ApiResponse<Paginated<T>, E> {
+    /// Creates a successful response for a page of a list, its position repeated in
+    /// `pagination`.
+    pub fn paginated(page: Paginated<T>) -> Self {
+        let mut response = Self::success(page);
+        response.pagination = response.data.as_ref().map(Paginated::meta);
+        response
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_request_scope_carries_id_and_warnings() {
+        warn(MessageCode::ProfilesInvalid);
+        let response = ApiResponse::<()>::success(());
+        assert!(response.warnings().is_empty());
+        assert_eq!(response.request_id, None);
+
+        let response = scope_request("req-1".to_string(), async {
+            warn(Message::new(MessageCode::ProfilesUnknownFields).with("fields", "/foo"));
+            ApiResponse::<()>::success(())
+        })
+        .await;
+        assert_eq!(response.request_id.as_deref(), Some("req-1"));
+        assert_eq!(response.warnings().len(), 1);
+        assert_eq!(
+            response.warnings()[0].code,
+            MessageCode::ProfilesUnknownFields
+        );
+        assert!(response.warnings()[0].message.ends_with("/foo"));
     }
 }
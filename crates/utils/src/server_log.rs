@@ -0,0 +1,205 @@
+use std::{
+    fs::{self, File, OpenOptions},
+    io::{self, Write},
+    path::{Path, PathBuf},
+    sync::Mutex,
+};
+
+use tracing_subscriber::{Layer, fmt::MakeWriter, registry::LookupSpan};
+
+use crate::assets::asset_dir;
+
+/// Size a log file grows to before it is rotated
+const MAX_FILE_BYTES: u64 = 10 * 1024 * 1024;
+/// Rotated files kept next to the current one, as `server.log.1` (newest) and up
+const KEEP_ROTATED: usize = 3;
+/// Most lines handed out by [`tail`]
+pub const MAX_TAIL: usize = 5000;
+
+pub fn server_log_path() -> PathBuf {
+    asset_dir().join("logs").join("server.log")
+}
+
+/// Layer writing events as JSON lines to [`server_log_path`], rotating the file once it
+/// passes a few megabytes
+pub fn file_layer<S>() -> io::Result<impl Layer<S>>
+where
+    S: tracing::Subscriber,
+    S: for<'a> LookupSpan<'a>,
+{
+    let writer = RotatingFile::open(server_log_path(), MAX_FILE_BYTES, KEEP_ROTATED)?;
+    Ok(tracing_subscriber::fmt::layer()
+        .json()
+        .with_current_span(false)
+        .with_ansi(false)
+        .with_writer(writer))
+}
+
+/// Log file that's moved aside to `<path>.1` once a write would take it past
+/// `max_bytes`, shifting older copies up and dropping the one past `keep`
+pub struct RotatingFile {
+    path: PathBuf,
+    max_bytes: u64,
+    keep: usize,
+    state: Mutex<FileState>,
+}
+
+struct FileState {
+    file: File,
+    size: u64,
+}
+
+impl RotatingFile {
+    pub fn open(path: PathBuf, max_bytes: u64, keep: usize) -> io::Result<Self> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let file = append(&path)?;
+        let size = file.metadata()?.len();
+        Ok(Self {
+            path,
+            max_bytes,
+            keep,
+            state: Mutex::new(FileState { file, size }),
+        })
+    }
+
+    fn write_line(&self, buf: &[u8]) -> io::Result<usize> {
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        if state.size > 0 && state.size + buf.len() as u64 > self.max_bytes {
+            self.rotate()?;
+            state.file = append(&self.path)?;
+            state.size = 0;
+        }
+        state.file.write_all(buf)?;
+        state.size += buf.len() as u64;
+        Ok(buf.len())
+    }
+
+    fn rotate(&self) -> io::Result<()> {
+        let _ = fs::remove_file(rotated_path(&self.path, self.keep));
+        for n in (1..self.keep).rev() {
+            let from = rotated_path(&self.path, n);
+            if from.exists() {
+                fs::rename(&from, rotated_path(&self.path, n + 1))?;
+            }
+        }
+        if self.keep == 0 {
+            fs::remove_file(&self.path)
+        } else {
+            fs::rename(&self.path, rotated_path(&self.path, 1))
+        }
+    }
+}
+
+fn append(path: &Path) -> io::Result<File> {
+    OpenOptions::new().create(true).append(true).open(path)
+}
+
+fn rotated_path(path: &Path, n: usize) -> PathBuf {
+    let mut name = path.as_os_str().to_owned();
+    name.push(format!(".{n}"));
+    PathBuf::from(name)
+}
+
+pub struct RotatingWriter<'a>(&'a RotatingFile);
+
+impl Write for RotatingWriter<'_> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.write_line(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl<'a> MakeWriter<'a> for RotatingFile {
+    type Writer = RotatingWriter<'a>;
+
+    fn make_writer(&'a self) -> Self::Writer {
+        RotatingWriter(self)
+    }
+}
+
+/// The last `lines` entries of the server log, oldest first, reaching into rotated files
+/// when the current one is shorter. Lines that aren't JSON, e.g. cut off by a crash, are
+/// skipped.
+pub fn tail(path: &Path, lines: usize) -> io::Result<Vec<serde_json::Value>> {
+    let lines = lines.min(MAX_TAIL);
+    let mut entries = Vec::new();
+    for n in 0..=KEEP_ROTATED {
+        if entries.len() >= lines {
+            break;
+        }
+        let file = if n == 0 {
+            path.to_path_buf()
+        } else {
+            rotated_path(path, n)
+        };
+        let content = match fs::read_to_string(&file) {
+            Ok(content) => content,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => break,
+            Err(e) => return Err(e),
+        };
+        let older: Vec<serde_json::Value> = content
+            .lines()
+            .rev()
+            .filter_map(|line| serde_json::from_str(line).ok())
+            .take(lines - entries.len())
+            .collect();
+        entries.extend(older);
+    }
+    entries.reverse();
+    Ok(entries)
+}
+
+#[cfg(test)]
+mod tests {
+    use uuid::Uuid;
+
+    use super::*;
+
+    fn temp_log() -> PathBuf {
+        std::env::temp_dir()
+            .join(format!("vk-server-log-{}", Uuid::new_v4()))
+            .join("server.log")
+    }
+
+    fn write(file: &RotatingFile, n: usize) {
+        let line = format!("{{\"n\":{n}}}\n");
+        file.make_writer().write_all(line.as_bytes()).unwrap();
+    }
+
+    #[test]
+    fn test_rotates_and_keeps_limited_copies() {
+        let path = temp_log();
+        // Each line is 8 bytes, so every file holds two
+        let file = RotatingFile::open(path.clone(), 16, 2).unwrap();
+        for n in 0..7 {
+            write(&file, n);
+        }
+
+        assert_eq!(fs::read_to_string(&path).unwrap(), "{\"n\":6}\n");
+        assert!(rotated_path(&path, 2).exists());
+        assert!(!rotated_path(&path, 3).exists());
+
+        let numbers = |entries: Vec<serde_json::Value>| -> Vec<u64> {
+            entries.iter().map(|e| e["n"].as_u64().unwrap()).collect()
+        };
+        assert_eq!(numbers(tail(&path, 4).unwrap()), [3, 4, 5, 6]);
+        assert_eq!(numbers(tail(&path, 100).unwrap()), [2, 3, 4, 5, 6]);
+        fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+
+    #[test]
+    fn test_tail_skips_partial_lines_and_missing_files() {
+        let path = temp_log();
+        assert!(tail(&path, 10).unwrap().is_empty());
+
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(&path, "{\"n\":1}\n{\"n\":2}\n{\"n\":").unwrap();
+        assert_eq!(tail(&path, 10).unwrap().len(), 2);
+        fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+}
@@ -18,6 +18,16 @@ pub fn get_shell_command() -> (&'static str, &'static str) {
     }
 }
 
+/// Quotes an argument for the command line run by [`get_shell_command`], so spaces,
+/// parentheses and globs reach the program as written.
+pub fn quote_shell_arg(arg: &str) -> String {
+    if cfg!(windows) {
+        format!("\"{}\"", arg.replace('"', "\"\""))
+    } else {
+        format!("'{}'", arg.replace('\'', r"'\''"))
+    }
+}
+
 /// Resolves the full path of an executable using the system's PATH environment variable.
 pub fn resolve_executable_path(executable: &str) -> Option<String> {
     which::which(executable)
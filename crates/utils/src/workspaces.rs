@@ -0,0 +1,123 @@
+use std::{
+    path::{Path, PathBuf},
+    sync::OnceLock,
+};
+
+/// Workspace that lives directly in the base data directory, as before workspaces existed
+pub const DEFAULT_WORKSPACE: &str = "default";
+
+const WORKSPACES_DIR: &str = "workspaces";
+const SELECTED_WORKSPACE_FILE: &str = "active_workspace";
+
+/// Workspace chosen at startup and the base data directory it was resolved from
+static ACTIVE_WORKSPACE: OnceLock<(String, PathBuf)> = OnceLock::new();
+
+/// Workspace names become directory names, so keep them to a safe character set
+pub fn validate_name(name: &str) -> Result<(), String> {
+    if name.is_empty() || name.len() > 64 {
+        return Err("Workspace name must be between 1 and 64 characters".to_string());
+    }
+    if !name
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    {
+        return Err("Workspace name may only contain letters, numbers, '-' and '_'".to_string());
+    }
+    Ok(())
+}
+
+/// Data directory of the workspace `name` under `base`
+pub fn workspace_dir(base: &Path, name: &str) -> PathBuf {
+    if name == DEFAULT_WORKSPACE {
+        base.to_path_buf()
+    } else {
+        base.join(WORKSPACES_DIR).join(name)
+    }
+}
+
+/// Every workspace under `base`, the default one first
+pub fn list(base: &Path) -> Vec<String> {
+    let mut names: Vec<String> = std::fs::read_dir(base.join(WORKSPACES_DIR))
+        .map(|entries| {
+            entries
+                .filter_map(Result::ok)
+                .filter(|e| e.path().is_dir())
+                .filter_map(|e| e.file_name().into_string().ok())
+                .filter(|name| validate_name(name).is_ok() && name != DEFAULT_WORKSPACE)
+                .collect()
+        })
+        .unwrap_or_default();
+    names.sort();
+    names.insert(0, DEFAULT_WORKSPACE.to_string());
+    names
+}
+
+/// Create the data directory for a new workspace
+pub fn create(base: &Path, name: &str) -> Result<PathBuf, String> {
+    validate_name(name)?;
+    let dir = workspace_dir(base, name);
+    if dir.exists() && name != DEFAULT_WORKSPACE {
+        return Err(format!("Workspace '{name}' already exists"));
+    }
+    std::fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+    Ok(dir)
+}
+
+/// Workspace recorded to be opened on the next start, if any
+pub fn read_selected(base: &Path) -> Option<String> {
+    std::fs::read_to_string(base.join(SELECTED_WORKSPACE_FILE))
+        .ok()
+        .map(|s| s.trim().to_string())
+        .filter(|name| validate_name(name).is_ok())
+}
+
+/// Record the workspace to open on the next start
+pub fn write_selected(base: &Path, name: &str) -> Result<(), String> {
+    validate_name(name)?;
+    if !list(base).iter().any(|n| n == name) {
+        return Err(format!("Workspace '{name}' does not exist"));
+    }
+    std::fs::write(base.join(SELECTED_WORKSPACE_FILE), name).map_err(|e| e.to_string())
+}
+
+/// Remember which workspace this process is serving, call once at startup
+pub fn set_active(name: String, base: PathBuf) {
+    if ACTIVE_WORKSPACE.set((name, base)).is_err() {
+        tracing::warn!("Active workspace already set, ignoring");
+    }
+}
+
+/// Name of the workspace this process is serving and the base data directory
+pub fn active() -> Option<(&'static str, &'static Path)> {
+    ACTIVE_WORKSPACE
+        .get()
+        .map(|(name, base)| (name.as_str(), base.as_path()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_name() {
+        assert!(validate_name("work").is_ok());
+        assert!(validate_name("side_project-2").is_ok());
+        assert!(validate_name("").is_err());
+        assert!(validate_name("../etc").is_err());
+        assert!(validate_name("a/b").is_err());
+        assert!(validate_name(&"x".repeat(65)).is_err());
+    }
+
+    #[test]
+    fn test_workspace_dir() {
+        let base = Path::new("/data");
+        assert_eq!(
+            workspace_dir(base, DEFAULT_WORKSPACE),
+            PathBuf::from("/data")
+        );
+        assert_eq!(
+            workspace_dir(base, "personal"),
+            PathBuf::from("/data/workspaces/personal")
+        );
+    }
+}
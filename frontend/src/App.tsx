@@ -4,6 +4,7 @@ import { Navbar } from '@/components/layout/navbar';
 import { Projects } from '@/pages/projects';
 import { ProjectTasks } from '@/pages/project-tasks';
 import { TaskDetailsPage } from '@/pages/task-details';
+import { SharedAttemptPage } from '@/pages/shared-attempt';
 
 import { Settings } from '@/pages/Settings';
 import { McpServers } from '@/pages/McpServers';
@@ -19,6 +20,8 @@ import * as Sentry from '@sentry/react';
 import { Loader } from '@/components/ui/loader';
 import { GitHubLoginDialog } from '@/components/GitHubLoginDialog';
 import { AppWithStyleOverride } from '@/utils/style-override';
+import { UpdateBanner } from '@/components/UpdateBanner';
+import { ApiWarningsBanner } from '@/components/ApiWarningsBanner';
 
 const SentryRoutes = Sentry.withSentryReactRouterV6Routing(Routes);
 
@@ -29,7 +32,9 @@ function AppContent() {
   const [showOnboarding, setShowOnboarding] = useState(false);
   const [showPrivacyOptIn, setShowPrivacyOptIn] = useState(false);
   const [showGitHubLogin, setShowGitHubLogin] = useState(false);
-  const showNavbar = !location.pathname.endsWith('/full');
+  const showNavbar =
+    !location.pathname.endsWith('/full') &&
+    !location.pathname.startsWith('/share/');
 
   useEffect(() => {
     if (config) {
@@ -71,7 +76,7 @@ function AppContent() {
       ...config,
       onboarding_acknowledged: true,
       profile: onboardingConfig.profile,
-      editor: onboardingConfig.editor,
+      editor: { ...config.editor, ...onboardingConfig.editor },
     };
 
     updateConfig(updatedConfig);
@@ -156,6 +161,8 @@ function AppContent() {
             onComplete={handlePrivacyOptInComplete}
           />
           {showNavbar && <Navbar />}
+          {showNavbar && <UpdateBanner />}
+          <ApiWarningsBanner />
           <div className="flex-1 overflow-y-scroll">
             <SentryRoutes>
               <Route path="/" element={<Projects />} />
@@ -184,6 +191,7 @@ function AppContent() {
 
               <Route path="/settings" element={<Settings />} />
               <Route path="/mcp-servers" element={<McpServers />} />
+              <Route path="/share/:token" element={<SharedAttemptPage />} />
             </SentryRoutes>
           </div>
         </div>
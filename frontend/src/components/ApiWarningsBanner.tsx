@@ -0,0 +1,49 @@
+import { useEffect, useState } from 'react';
+import { AlertTriangle, X } from 'lucide-react';
+import { Button } from '@/components/ui/button';
+import { onApiWarnings } from '@/lib/api';
+
+// Oldest warnings are dropped beyond this
+const MAX_WARNINGS = 5;
+
+// Shown under the navbar when a request succeeded with warnings, e.g. settings
+// fields the server ignored, until dismissed
+export function ApiWarningsBanner() {
+  const [messages, setMessages] = useState<string[]>([]);
+
+  useEffect(
+    () =>
+      onApiWarnings((warnings) =>
+        setMessages((prev) => {
+          const fresh = warnings
+            .map((warning) => warning.message)
+            .filter((message) => !prev.includes(message));
+          return [...prev, ...fresh].slice(-MAX_WARNINGS);
+        })
+      ),
+    []
+  );
+
+  if (messages.length === 0) return null;
+
+  return (
+    <div className="border-b bg-amber-500/10 px-4 py-2 text-sm">
+      <div className="flex items-start gap-3">
+        <AlertTriangle className="mt-0.5 h-4 w-4 shrink-0 text-amber-600" />
+        <ul className="flex-1 space-y-1">
+          {messages.map((message) => (
+            <li key={message}>{message}</li>
+          ))}
+        </ul>
+        <Button
+          variant="ghost"
+          size="icon"
+          className="h-7 w-7"
+          onClick={() => setMessages([])}
+        >
+          <X className="h-4 w-4" />
+        </Button>
+      </div>
+    </div>
+  );
+}
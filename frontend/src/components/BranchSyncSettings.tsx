@@ -0,0 +1,117 @@
+import { useEffect, useState } from 'react';
+import { Button } from '@/components/ui/button';
+import { Label } from '@/components/ui/label';
+import {
+  Select,
+  SelectContent,
+  SelectItem,
+  SelectTrigger,
+  SelectValue,
+} from '@/components/ui/select';
+import { Loader2 } from 'lucide-react';
+import { projectsApi } from '@/lib/api';
+import type { BranchSyncSchedule, BranchSyncStrategy } from 'shared/types';
+
+interface BranchSyncSettingsProps {
+  projectId: string;
+}
+
+// When and how long-lived attempt branches take in new commits of their base
+export function BranchSyncSettings({ projectId }: BranchSyncSettingsProps) {
+  const [schedule, setSchedule] = useState<BranchSyncSchedule>('off');
+  const [strategy, setStrategy] = useState<BranchSyncStrategy>('rebase');
+  const [loading, setLoading] = useState(true);
+  const [saving, setSaving] = useState(false);
+  const [saved, setSaved] = useState(false);
+  const [error, setError] = useState<string | null>(null);
+
+  useEffect(() => {
+    setLoading(true);
+    projectsApi
+      .getBranchSync(projectId)
+      .then((sync) => {
+        setSchedule(sync?.schedule ?? 'off');
+        setStrategy(sync?.strategy ?? 'rebase');
+      })
+      .catch((err) =>
+        setError(
+          err instanceof Error ? err.message : 'Failed to load branch sync'
+        )
+      )
+      .finally(() => setLoading(false));
+  }, [projectId]);
+
+  const handleSave = async () => {
+    setSaving(true);
+    setSaved(false);
+    setError(null);
+    try {
+      await projectsApi.updateBranchSync(projectId, { schedule, strategy });
+      setSaved(true);
+    } catch (err) {
+      setError(
+        err instanceof Error ? err.message : 'Failed to save branch sync'
+      );
+    } finally {
+      setSaving(false);
+    }
+  };
+
+  if (loading) {
+    return <Loader2 className="h-4 w-4 animate-spin" />;
+  }
+
+  return (
+    <div className="space-y-4">
+      <div className="space-y-2">
+        <Label htmlFor="branch-sync-schedule">Sync attempt branches</Label>
+        <Select
+          value={schedule}
+          onValueChange={(value) => setSchedule(value as BranchSyncSchedule)}
+        >
+          <SelectTrigger id="branch-sync-schedule">
+            <SelectValue />
+          </SelectTrigger>
+          <SelectContent>
+            <SelectItem value="off">Never</SelectItem>
+            <SelectItem value="nightly">Nightly</SelectItem>
+            <SelectItem value="on_base_change">
+              When the base branch moves
+            </SelectItem>
+          </SelectContent>
+        </Select>
+        <p className="text-sm text-muted-foreground">
+          Unmerged attempts of open tasks are brought up to date with their
+          base branch while no process runs. Attempts with uncommitted changes
+          are skipped.
+        </p>
+      </div>
+      <div className="space-y-2">
+        <Label htmlFor="branch-sync-strategy">Strategy</Label>
+        <Select
+          value={strategy}
+          onValueChange={(value) => setStrategy(value as BranchSyncStrategy)}
+        >
+          <SelectTrigger id="branch-sync-strategy">
+            <SelectValue />
+          </SelectTrigger>
+          <SelectContent>
+            <SelectItem value="rebase">Rebase onto base branch</SelectItem>
+            <SelectItem value="merge">Merge base branch in</SelectItem>
+          </SelectContent>
+        </Select>
+        <p className="text-sm text-muted-foreground">
+          On conflicts the branch is left untouched and its task card is
+          flagged.
+        </p>
+      </div>
+      {error && <p className="text-sm text-destructive">{error}</p>}
+      <div className="flex items-center justify-end gap-2">
+        {saved && <span className="text-sm text-green-600">Saved</span>}
+        <Button onClick={handleSave} disabled={saving}>
+          {saving ? 'Saving...' : 'Save'}
+        </Button>
+      </div>
+    </div>
+  );
+}
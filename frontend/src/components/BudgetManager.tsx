@@ -0,0 +1,124 @@
+import { useCallback, useEffect, useState } from 'react';
+import { Input } from '@/components/ui/input';
+import { Loader2 } from 'lucide-react';
+import { projectsApi, spendApi } from '@/lib/api';
+import type { Project, SpendSummary } from 'shared/types';
+
+const formatUsd = (usd: number) => `$${usd.toFixed(2)}`;
+
+// This month's spend per project, with the caps that stop new attempts
+export function BudgetManager() {
+  const [summary, setSummary] = useState<SpendSummary | null>(null);
+  const [projects, setProjects] = useState<Project[]>([]);
+  const [caps, setCaps] = useState<Record<string, string>>({});
+  const [error, setError] = useState<string | null>(null);
+
+  const load = useCallback(async () => {
+    try {
+      const [spend, allProjects] = await Promise.all([
+        spendApi.get(),
+        projectsApi.getAll(),
+      ]);
+      setSummary(spend);
+      setProjects(allProjects);
+      setCaps(
+        Object.fromEntries(
+          spend.projects.map((p) => [
+            p.project_id,
+            p.monthly_cap_usd?.toString() ?? '',
+          ])
+        )
+      );
+    } catch (err) {
+      setError(err instanceof Error ? err.message : 'Failed to load spend');
+    }
+  }, []);
+
+  useEffect(() => {
+    load();
+  }, [load]);
+
+  const saveCap = async (projectId: string) => {
+    const value = (caps[projectId] ?? '').trim();
+    const cap = value === '' ? null : Number(value);
+    if (cap !== null && (Number.isNaN(cap) || cap < 0)) {
+      setError('Caps must be a positive amount in USD');
+      return;
+    }
+    setError(null);
+    try {
+      await spendApi.updateProjectBudget(projectId, cap);
+      await load();
+    } catch (err) {
+      setError(err instanceof Error ? err.message : 'Failed to save cap');
+    }
+  };
+
+  if (!summary) {
+    return error ? (
+      <p className="text-sm text-destructive">{error}</p>
+    ) : (
+      <Loader2 className="h-4 w-4 animate-spin" />
+    );
+  }
+
+  const spendOf = (projectId: string) =>
+    summary.projects.find((p) => p.project_id === projectId)?.spend_usd ?? 0;
+  // Deleted projects keep counting towards the month
+  const deleted = summary.projects.filter(
+    (p) => !projects.some((project) => project.id === p.project_id)
+  );
+
+  return (
+    <div className="space-y-2">
+      <p className="text-sm">
+        Spent in {summary.month}:{' '}
+        <span className="font-medium">{formatUsd(summary.total_usd)}</span>
+        {summary.monthly_cap_usd !== null &&
+          ` of ${formatUsd(summary.monthly_cap_usd)}`}
+      </p>
+      <table className="w-full text-sm">
+        <thead>
+          <tr className="text-left text-muted-foreground">
+            <th className="font-normal">Project</th>
+            <th className="font-normal">Spent</th>
+            <th className="font-normal">Monthly cap (USD)</th>
+          </tr>
+        </thead>
+        <tbody>
+          {projects.map((project) => (
+            <tr key={project.id}>
+              <td className="py-1 pr-2">{project.name}</td>
+              <td className="py-1 pr-2">{formatUsd(spendOf(project.id))}</td>
+              <td className="py-1">
+                <Input
+                  type="number"
+                  min={0}
+                  step="0.01"
+                  placeholder="No cap"
+                  className="h-8 w-32"
+                  value={caps[project.id] ?? ''}
+                  onChange={(e) =>
+                    setCaps((prev) => ({
+                      ...prev,
+                      [project.id]: e.target.value,
+                    }))
+                  }
+                  onBlur={() => saveCap(project.id)}
+                />
+              </td>
+            </tr>
+          ))}
+          {deleted.map((p) => (
+            <tr key={p.project_id} className="text-muted-foreground">
+              <td className="py-1 pr-2">Deleted project</td>
+              <td className="py-1 pr-2">{formatUsd(p.spend_usd)}</td>
+              <td className="py-1" />
+            </tr>
+          ))}
+        </tbody>
+      </table>
+      {error && <p className="text-sm text-destructive">{error}</p>}
+    </div>
+  );
+}
@@ -0,0 +1,137 @@
+import { useEffect, useState } from 'react';
+import { Button } from '@/components/ui/button';
+import { Checkbox } from '@/components/ui/checkbox';
+import { Input } from '@/components/ui/input';
+import { Label } from '@/components/ui/label';
+import {
+  Select,
+  SelectContent,
+  SelectItem,
+  SelectTrigger,
+  SelectValue,
+} from '@/components/ui/select';
+import { Loader2 } from 'lucide-react';
+import { projectsApi } from '@/lib/api';
+import type { ChangelogFormat } from 'shared/types';
+
+interface ChangelogSettingsProps {
+  projectId: string;
+}
+
+// Changelog fragments and conventional commit messages written on merge
+export function ChangelogSettings({ projectId }: ChangelogSettingsProps) {
+  const [enabled, setEnabled] = useState(false);
+  const [format, setFormat] = useState<ChangelogFormat>('keep_a_changelog');
+  const [directory, setDirectory] = useState('changelog.d');
+  const [conventionalCommits, setConventionalCommits] = useState(false);
+  const [loading, setLoading] = useState(true);
+  const [saving, setSaving] = useState(false);
+  const [saved, setSaved] = useState(false);
+  const [error, setError] = useState<string | null>(null);
+
+  useEffect(() => {
+    setLoading(true);
+    projectsApi
+      .getChangelog(projectId)
+      .then((settings) => {
+        setEnabled(settings?.enabled ?? false);
+        setFormat(settings?.format ?? 'keep_a_changelog');
+        setDirectory(settings?.directory ?? 'changelog.d');
+        setConventionalCommits(settings?.conventional_commits ?? false);
+      })
+      .catch((err) =>
+        setError(
+          err instanceof Error ? err.message : 'Failed to load changelog'
+        )
+      )
+      .finally(() => setLoading(false));
+  }, [projectId]);
+
+  const handleSave = async () => {
+    setSaving(true);
+    setSaved(false);
+    setError(null);
+    try {
+      const settings = await projectsApi.updateChangelog(projectId, {
+        enabled,
+        format,
+        directory,
+        conventional_commits: conventionalCommits,
+      });
+      setDirectory(settings.directory);
+      setSaved(true);
+    } catch (err) {
+      setError(err instanceof Error ? err.message : 'Failed to save changelog');
+    } finally {
+      setSaving(false);
+    }
+  };
+
+  if (loading) {
+    return <Loader2 className="h-4 w-4 animate-spin" />;
+  }
+
+  return (
+    <div className="space-y-4">
+      <div className="flex items-center gap-2">
+        <Checkbox
+          id="changelog-enabled"
+          checked={enabled}
+          onCheckedChange={(checked) => setEnabled(checked === true)}
+        />
+        <Label htmlFor="changelog-enabled">
+          Write a changelog fragment on merge
+        </Label>
+      </div>
+      <p className="text-sm text-muted-foreground">
+        Each merged attempt adds a fragment derived from its task and changes,
+        committed to its branch so the merge commit includes it.
+      </p>
+      <div className="space-y-2">
+        <Label htmlFor="changelog-format">Format</Label>
+        <Select
+          value={format}
+          onValueChange={(value) => setFormat(value as ChangelogFormat)}
+        >
+          <SelectTrigger id="changelog-format">
+            <SelectValue />
+          </SelectTrigger>
+          <SelectContent>
+            <SelectItem value="keep_a_changelog">Keep a Changelog</SelectItem>
+            <SelectItem value="conventional_commits">
+              Conventional Commits
+            </SelectItem>
+          </SelectContent>
+        </Select>
+      </div>
+      <div className="space-y-2">
+        <Label htmlFor="changelog-directory">Fragment directory</Label>
+        <Input
+          id="changelog-directory"
+          placeholder="changelog.d"
+          value={directory}
+          onChange={(e) => setDirectory(e.target.value)}
+        />
+      </div>
+      <div className="flex items-center gap-2">
+        <Checkbox
+          id="changelog-conventional-commits"
+          checked={conventionalCommits}
+          onCheckedChange={(checked) =>
+            setConventionalCommits(checked === true)
+          }
+        />
+        <Label htmlFor="changelog-conventional-commits">
+          Give merge commits a conventional commit subject
+        </Label>
+      </div>
+      {error && <p className="text-sm text-destructive">{error}</p>}
+      <div className="flex items-center justify-end gap-2">
+        {saved && <span className="text-sm text-green-600">Saved</span>}
+        <Button onClick={handleSave} disabled={saving}>
+          {saving ? 'Saving...' : 'Save'}
+        </Button>
+      </div>
+    </div>
+  );
+}
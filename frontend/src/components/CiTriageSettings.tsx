@@ -0,0 +1,118 @@
+import { useEffect, useState } from 'react';
+import { Button } from '@/components/ui/button';
+import { Checkbox } from '@/components/ui/checkbox';
+import { Input } from '@/components/ui/input';
+import { Label } from '@/components/ui/label';
+import { Loader2 } from 'lucide-react';
+import { projectsApi } from '@/lib/api';
+
+interface CiTriageSettingsProps {
+  projectId: string;
+}
+
+// GitHub Actions workflows whose failures on the base branch become cards
+export function CiTriageSettings({ projectId }: CiTriageSettingsProps) {
+  const [enabled, setEnabled] = useState(false);
+  const [workflows, setWorkflows] = useState('');
+  const [branch, setBranch] = useState('');
+  const [startAgent, setStartAgent] = useState(false);
+  const [loading, setLoading] = useState(true);
+  const [saving, setSaving] = useState(false);
+  const [saved, setSaved] = useState(false);
+  const [error, setError] = useState<string | null>(null);
+
+  useEffect(() => {
+    setLoading(true);
+    projectsApi
+      .getCiTriage(projectId)
+      .then((triage) => {
+        setEnabled(triage?.enabled ?? false);
+        setWorkflows(triage?.workflows.join(', ') ?? '');
+        setBranch(triage?.branch ?? '');
+        setStartAgent(triage?.start_agent ?? false);
+      })
+      .catch((err) =>
+        setError(
+          err instanceof Error ? err.message : 'Failed to load CI triage'
+        )
+      )
+      .finally(() => setLoading(false));
+  }, [projectId]);
+
+  const handleSave = async () => {
+    setSaving(true);
+    setSaved(false);
+    setError(null);
+    try {
+      await projectsApi.updateCiTriage(projectId, {
+        enabled,
+        workflows: workflows.split(','),
+        branch: branch || null,
+        start_agent: startAgent,
+      });
+      setSaved(true);
+    } catch (err) {
+      setError(err instanceof Error ? err.message : 'Failed to save CI triage');
+    } finally {
+      setSaving(false);
+    }
+  };
+
+  if (loading) {
+    return <Loader2 className="h-4 w-4 animate-spin" />;
+  }
+
+  return (
+    <div className="space-y-4">
+      <div className="flex items-center gap-2">
+        <Checkbox
+          id="ci-triage-enabled"
+          checked={enabled}
+          onCheckedChange={(checked) => setEnabled(checked === true)}
+        />
+        <Label htmlFor="ci-triage-enabled">Triage failed CI runs</Label>
+      </div>
+      <p className="text-sm text-muted-foreground">
+        Completed GitHub Actions runs are checked every two minutes with your
+        GitHub token. A failed run opens a card with the failing job logs
+        attached, later failures of the same workflow update it while
+        it&apos;s open.
+      </p>
+      <div className="space-y-2">
+        <Label htmlFor="ci-triage-workflows">Workflows</Label>
+        <Input
+          id="ci-triage-workflows"
+          placeholder="ci.yml, release.yml (all when empty)"
+          value={workflows}
+          onChange={(e) => setWorkflows(e.target.value)}
+        />
+      </div>
+      <div className="space-y-2">
+        <Label htmlFor="ci-triage-branch">Branch</Label>
+        <Input
+          id="ci-triage-branch"
+          placeholder="The repository's default branch"
+          value={branch}
+          onChange={(e) => setBranch(e.target.value)}
+        />
+      </div>
+      <div className="flex items-center gap-2">
+        <Checkbox
+          id="ci-triage-start-agent"
+          checked={startAgent}
+          onCheckedChange={(checked) => setStartAgent(checked === true)}
+        />
+        <Label htmlFor="ci-triage-start-agent">
+          Start an attempt on new cards
+        </Label>
+      </div>
+      {error && <p className="text-sm text-destructive">{error}</p>}
+      <div className="flex items-center justify-end gap-2">
+        {saved && <span className="text-sm text-green-600">Saved</span>}
+        <Button onClick={handleSave} disabled={saving}>
+          {saving ? 'Saving...' : 'Save'}
+        </Button>
+      </div>
+    </div>
+  );
+}
@@ -0,0 +1,100 @@
+import { useCallback, useEffect, useState } from 'react';
+import { Loader2, RotateCcw } from 'lucide-react';
+import { Button } from '@/components/ui/button';
+import { useUserSystem } from '@/components/config-provider';
+import { configApi } from '@/lib/api';
+import type { ConfigVersion } from 'shared/types';
+
+// Fields listed per version before the rest are summed up
+const MAX_FIELDS_SHOWN = 4;
+
+// Configs from before the last saves, each restorable. Restoring keeps the
+// current config in the history too, so it can be undone the same way.
+export function ConfigHistoryManager() {
+  const { reloadSystem } = useUserSystem();
+  const [versions, setVersions] = useState<ConfigVersion[] | null>(null);
+  const [restoring, setRestoring] = useState<number | null>(null);
+  const [error, setError] = useState<string | null>(null);
+
+  const load = useCallback(() => {
+    configApi
+      .getHistory()
+      .then(setVersions)
+      .catch((err) =>
+        setError(err instanceof Error ? err.message : 'Failed to load history')
+      );
+  }, []);
+
+  useEffect(load, [load]);
+
+  const restore = async (n: number) => {
+    setRestoring(n);
+    setError(null);
+    try {
+      await configApi.rollback(n);
+      await reloadSystem();
+      load();
+    } catch (err) {
+      setError(err instanceof Error ? err.message : 'Failed to restore');
+    } finally {
+      setRestoring(null);
+    }
+  };
+
+  if (!versions) {
+    return error ? (
+      <p className="text-sm text-destructive">{error}</p>
+    ) : (
+      <Loader2 className="h-4 w-4 animate-spin" />
+    );
+  }
+
+  if (versions.length === 0) {
+    return (
+      <p className="text-sm text-muted-foreground">
+        No earlier configs yet, one is kept every time the settings are saved.
+      </p>
+    );
+  }
+
+  return (
+    <div className="space-y-2">
+      {versions.map((version) => {
+        const fields = version.changed_fields;
+        const more = fields.length - MAX_FIELDS_SHOWN;
+        return (
+          <div
+            key={version.n}
+            className="flex items-center justify-between gap-4 text-sm"
+          >
+            <div className="min-w-0">
+              <div>
+                Replaced {new Date(version.replaced_at).toLocaleString()}
+              </div>
+              <div className="truncate text-muted-foreground">
+                {fields.length === 0
+                  ? 'Same as the current config'
+                  : fields.slice(0, MAX_FIELDS_SHOWN).join(', ') +
+                    (more > 0 ? ` and ${more} more` : '')}
+              </div>
+            </div>
+            <Button
+              variant="outline"
+              size="sm"
+              onClick={() => restore(version.n)}
+              disabled={restoring !== null || fields.length === 0}
+            >
+              {restoring === version.n ? (
+                <Loader2 className="mr-2 h-4 w-4 animate-spin" />
+              ) : (
+                <RotateCcw className="mr-2 h-4 w-4" />
+              )}
+              Restore
+            </Button>
+          </div>
+        );
+      })}
+      {error && <p className="text-sm text-destructive">{error}</p>}
+    </div>
+  );
+}
@@ -0,0 +1,96 @@
+import { useEffect, useState } from 'react';
+import { Loader2 } from 'lucide-react';
+import { Button } from '@/components/ui/button';
+import { JSONEditor } from '@/components/ui/json-editor';
+import { useUserSystem } from '@/components/config-provider';
+import { configApi } from '@/lib/api';
+
+// The user's own changes on top of the stored config, as a JSON merge patch.
+// Saving the settings above leaves the overridden fields out of the stored
+// config, so clearing the override brings back what was there.
+export function ConfigOverrideManager() {
+  const { reloadSystem } = useUserSystem();
+  const [content, setContent] = useState<string | null>(null);
+  const [hasOverride, setHasOverride] = useState(false);
+  const [busy, setBusy] = useState(false);
+  const [error, setError] = useState<string | null>(null);
+
+  useEffect(() => {
+    configApi
+      .getOverride()
+      .then((patch) => {
+        setHasOverride(patch !== null);
+        setContent(patch ? JSON.stringify(patch, null, 2) : '{}');
+      })
+      .catch((err) =>
+        setError(err instanceof Error ? err.message : 'Failed to load')
+      );
+  }, []);
+
+  const run = async (action: () => Promise<unknown>) => {
+    setBusy(true);
+    setError(null);
+    try {
+      await action();
+      await reloadSystem();
+    } catch (err) {
+      setError(err instanceof Error ? err.message : 'Failed to save');
+    } finally {
+      setBusy(false);
+    }
+  };
+
+  const save = () =>
+    run(async () => {
+      let patch: unknown;
+      try {
+        patch = JSON.parse(content ?? '{}');
+      } catch {
+        throw new Error('The override is not valid JSON');
+      }
+      await configApi.saveOverride(patch as Record<string, unknown>);
+      setHasOverride(true);
+    });
+
+  const clear = () =>
+    run(async () => {
+      await configApi.clearOverride();
+      setContent('{}');
+      setHasOverride(false);
+    });
+
+  if (content === null) {
+    return error ? (
+      <p className="text-sm text-destructive">{error}</p>
+    ) : (
+      <Loader2 className="h-4 w-4 animate-spin" />
+    );
+  }
+
+  return (
+    <div className="space-y-3">
+      <JSONEditor
+        value={content}
+        onChange={setContent}
+        placeholder='{"workspace_dir": "~/work"}'
+        minHeight={120}
+        disabled={busy}
+      />
+      <div className="flex gap-2">
+        <Button size="sm" onClick={save} disabled={busy}>
+          {busy && <Loader2 className="mr-2 h-4 w-4 animate-spin" />}
+          Save Override
+        </Button>
+        <Button
+          variant="outline"
+          size="sm"
+          onClick={clear}
+          disabled={busy || !hasOverride}
+        >
+          Clear
+        </Button>
+      </div>
+      {error && <p className="text-sm text-destructive">{error}</p>}
+    </div>
+  );
+}
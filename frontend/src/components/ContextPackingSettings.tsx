@@ -0,0 +1,155 @@
+import { useEffect, useState } from 'react';
+import { Button } from '@/components/ui/button';
+import { Checkbox } from '@/components/ui/checkbox';
+import { Input } from '@/components/ui/input';
+import { Label } from '@/components/ui/label';
+import { Textarea } from '@/components/ui/textarea';
+import { Loader2 } from 'lucide-react';
+import { projectsApi } from '@/lib/api';
+
+interface ContextPackingSettingsProps {
+  projectId: string;
+}
+
+const toLines = (globs: string[]) => globs.join('\n');
+const fromLines = (text: string) =>
+  text
+    .split('\n')
+    .map((line) => line.trim())
+    .filter(Boolean);
+
+// Repository files packed into the initial prompt of the project's attempts
+export function ContextPackingSettings({
+  projectId,
+}: ContextPackingSettingsProps) {
+  const [enabled, setEnabled] = useState(false);
+  const [includePaths, setIncludePaths] = useState('');
+  const [recentCommits, setRecentCommits] = useState('0');
+  const [keywordSearch, setKeywordSearch] = useState(true);
+  const [tokenBudget, setTokenBudget] = useState('4000');
+  const [loading, setLoading] = useState(true);
+  const [saving, setSaving] = useState(false);
+  const [saved, setSaved] = useState(false);
+  const [error, setError] = useState<string | null>(null);
+
+  useEffect(() => {
+    setLoading(true);
+    projectsApi
+      .getContextPacking(projectId)
+      .then((packing) => {
+        setEnabled(packing?.enabled ?? false);
+        setIncludePaths(toLines(packing?.include_paths ?? []));
+        setRecentCommits(String(packing?.recent_commits ?? 0));
+        setKeywordSearch(packing?.keyword_search ?? true);
+        setTokenBudget(String(packing?.token_budget ?? 4000));
+      })
+      .catch((err) =>
+        setError(
+          err instanceof Error ? err.message : 'Failed to load context packing'
+        )
+      )
+      .finally(() => setLoading(false));
+  }, [projectId]);
+
+  const handleSave = async () => {
+    setSaving(true);
+    setSaved(false);
+    setError(null);
+    try {
+      await projectsApi.updateContextPacking(projectId, {
+        enabled,
+        include_paths: fromLines(includePaths),
+        recent_commits: Number(recentCommits) || 0,
+        keyword_search: keywordSearch,
+        token_budget: Number(tokenBudget) || 0,
+      });
+      setSaved(true);
+    } catch (err) {
+      setError(
+        err instanceof Error ? err.message : 'Failed to save context packing'
+      );
+    } finally {
+      setSaving(false);
+    }
+  };
+
+  if (loading) {
+    return <Loader2 className="h-4 w-4 animate-spin" />;
+  }
+
+  return (
+    <div className="space-y-4">
+      <div className="flex items-center space-x-2">
+        <Checkbox
+          id="context-packing-enabled"
+          checked={enabled}
+          onCheckedChange={(checked: boolean) => setEnabled(checked)}
+        />
+        <Label htmlFor="context-packing-enabled" className="cursor-pointer">
+          Add relevant repository files to the initial prompt
+        </Label>
+      </div>
+      <p className="text-sm text-muted-foreground">
+        Helps agents that don't explore the repository well. The start of each
+        selected file is shown, the best matches first.
+      </p>
+      <div className="space-y-2">
+        <Label htmlFor="context-include-paths">Always include</Label>
+        <Textarea
+          id="context-include-paths"
+          value={includePaths}
+          onChange={(e) => setIncludePaths(e.target.value)}
+          placeholder={'README.md\ndocs/architecture/**'}
+          rows={3}
+          className="font-mono"
+        />
+        <p className="text-sm text-muted-foreground">One glob per line.</p>
+      </div>
+      <div className="flex items-center space-x-2">
+        <Checkbox
+          id="context-keyword-search"
+          checked={keywordSearch}
+          onCheckedChange={(checked: boolean) => setKeywordSearch(checked)}
+        />
+        <Label htmlFor="context-keyword-search" className="cursor-pointer">
+          Include files whose path or content mentions words of the task
+        </Label>
+      </div>
+      <div className="grid grid-cols-2 gap-4">
+        <div className="space-y-2">
+          <Label htmlFor="context-recent-commits">Recent commits</Label>
+          <Input
+            id="context-recent-commits"
+            type="number"
+            min={0}
+            value={recentCommits}
+            onChange={(e) => setRecentCommits(e.target.value)}
+          />
+          <p className="text-sm text-muted-foreground">
+            Favor files changed by this many of the latest commits, 0 to skip.
+          </p>
+        </div>
+        <div className="space-y-2">
+          <Label htmlFor="context-token-budget">Token budget</Label>
+          <Input
+            id="context-token-budget"
+            type="number"
+            min={1}
+            value={tokenBudget}
+            onChange={(e) => setTokenBudget(e.target.value)}
+          />
+          <p className="text-sm text-muted-foreground">
+            Roughly how much the files may add to the prompt.
+          </p>
+        </div>
+      </div>
+      {error && <p className="text-sm text-destructive">{error}</p>}
+      <div className="flex items-center justify-end gap-2">
+        {saved && <span className="text-sm text-green-600">Saved</span>}
+        <Button onClick={handleSave} disabled={saving}>
+          {saving ? 'Saving...' : 'Save'}
+        </Button>
+      </div>
+    </div>
+  );
+}
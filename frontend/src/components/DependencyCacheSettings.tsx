@@ -0,0 +1,112 @@
+import { useEffect, useState } from 'react';
+import { Button } from '@/components/ui/button';
+import { Checkbox } from '@/components/ui/checkbox';
+import { Label } from '@/components/ui/label';
+import { Loader2 } from 'lucide-react';
+import { projectsApi } from '@/lib/api';
+
+interface DependencyCacheSettingsProps {
+  projectId: string;
+}
+
+// Package caches shared by all attempt worktrees of a project
+export function DependencyCacheSettings({
+  projectId,
+}: DependencyCacheSettingsProps) {
+  const [sharedCargoTarget, setSharedCargoTarget] = useState(false);
+  const [sharedPnpmStore, setSharedPnpmStore] = useState(false);
+  const [loading, setLoading] = useState(true);
+  const [saving, setSaving] = useState(false);
+  const [saved, setSaved] = useState(false);
+  const [error, setError] = useState<string | null>(null);
+
+  useEffect(() => {
+    setLoading(true);
+    projectsApi
+      .getDependencyCache(projectId)
+      .then((cache) => {
+        setSharedCargoTarget(cache?.shared_cargo_target ?? false);
+        setSharedPnpmStore(cache?.shared_pnpm_store ?? false);
+      })
+      .catch((err) =>
+        setError(
+          err instanceof Error ? err.message : 'Failed to load shared caches'
+        )
+      )
+      .finally(() => setLoading(false));
+  }, [projectId]);
+
+  const handleSave = async () => {
+    setSaving(true);
+    setSaved(false);
+    setError(null);
+    try {
+      await projectsApi.updateDependencyCache(projectId, {
+        shared_cargo_target: sharedCargoTarget,
+        shared_pnpm_store: sharedPnpmStore,
+      });
+      setSaved(true);
+    } catch (err) {
+      setError(
+        err instanceof Error ? err.message : 'Failed to save shared caches'
+      );
+    } finally {
+      setSaving(false);
+    }
+  };
+
+  if (loading) {
+    return <Loader2 className="h-4 w-4 animate-spin" />;
+  }
+
+  return (
+    <div className="space-y-4">
+      <p className="text-sm text-muted-foreground">
+        Every attempt gets a fresh worktree. Sharing caches between them saves
+        rebuilding and re-downloading dependencies, and the disk space of a
+        copy per attempt. Changes apply to the next process of each attempt.
+      </p>
+      <div className="space-y-2">
+        <div className="flex items-center space-x-2">
+          <Checkbox
+            id="shared-cargo-target"
+            checked={sharedCargoTarget}
+            onCheckedChange={(checked: boolean) =>
+              setSharedCargoTarget(checked)
+            }
+          />
+          <Label htmlFor="shared-cargo-target" className="cursor-pointer">
+            Share the Cargo target directory
+          </Label>
+        </div>
+        <p className="text-sm text-muted-foreground">
+          Sets <code>CARGO_TARGET_DIR</code>. Attempts building at the same
+          time wait for each other.
+        </p>
+      </div>
+      <div className="space-y-2">
+        <div className="flex items-center space-x-2">
+          <Checkbox
+            id="shared-pnpm-store"
+            checked={sharedPnpmStore}
+            onCheckedChange={(checked: boolean) => setSharedPnpmStore(checked)}
+          />
+          <Label htmlFor="shared-pnpm-store" className="cursor-pointer">
+            Share the pnpm store
+          </Label>
+        </div>
+        <p className="text-sm text-muted-foreground">
+          Each worktree's <code>node_modules</code> links to packages in one
+          store instead of a copy.
+        </p>
+      </div>
+      {error && <p className="text-sm text-destructive">{error}</p>}
+      <div className="flex items-center justify-end gap-2">
+        {saved && <span className="text-sm text-green-600">Saved</span>}
+        <Button onClick={handleSave} disabled={saving}>
+          {saving ? 'Saving...' : 'Save'}
+        </Button>
+      </div>
+    </div>
+  );
+}
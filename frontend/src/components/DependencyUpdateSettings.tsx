@@ -0,0 +1,281 @@
+import { useCallback, useEffect, useState } from 'react';
+import { Button } from '@/components/ui/button';
+import { Checkbox } from '@/components/ui/checkbox';
+import { Input } from '@/components/ui/input';
+import { Label } from '@/components/ui/label';
+import {
+  Select,
+  SelectContent,
+  SelectItem,
+  SelectTrigger,
+  SelectValue,
+} from '@/components/ui/select';
+import { Textarea } from '@/components/ui/textarea';
+import { Loader2 } from 'lucide-react';
+import { projectsApi } from '@/lib/api';
+import type {
+  DependencyGroup,
+  DependencyUpdateRun,
+  DependencyUpdateRunStatus,
+  DependencyUpdateSchedule,
+} from 'shared/types';
+
+interface DependencyUpdateSettingsProps {
+  projectId: string;
+}
+
+const STATUS_LABELS: Record<DependencyUpdateRunStatus, string> = {
+  running: 'Running',
+  pr_opened: 'PR opened',
+  no_changes: 'Up to date',
+  failed: 'Failed',
+};
+
+function formatGroups(groups: DependencyGroup[]): string {
+  return groups
+    .map((group) => `${group.name}: ${group.patterns.join(', ')}`)
+    .join('\n');
+}
+
+// One group per line, e.g. `types: @types/*, typescript`
+function parseGroups(text: string): DependencyGroup[] {
+  return text
+    .split('\n')
+    .map((line) => line.trim())
+    .filter((line) => line.length > 0)
+    .map((line) => {
+      const separator = line.indexOf(':');
+      if (separator === -1) {
+        throw new Error(`Write each group as "name: patterns": ${line}`);
+      }
+      return {
+        name: line.slice(0, separator).trim(),
+        patterns: line.slice(separator + 1).split(','),
+      };
+    });
+}
+
+// Scheduled agent runs updating dependencies, one pull request per group
+export function DependencyUpdateSettings({
+  projectId,
+}: DependencyUpdateSettingsProps) {
+  const [enabled, setEnabled] = useState(false);
+  const [schedule, setSchedule] = useState<DependencyUpdateSchedule>('weekly');
+  const [groups, setGroups] = useState('');
+  const [allowMajor, setAllowMajor] = useState(false);
+  const [ignored, setIgnored] = useState('');
+  const [instructions, setInstructions] = useState('');
+  const [runs, setRuns] = useState<DependencyUpdateRun[]>([]);
+  const [loading, setLoading] = useState(true);
+  const [saving, setSaving] = useState(false);
+  const [starting, setStarting] = useState(false);
+  const [saved, setSaved] = useState(false);
+  const [error, setError] = useState<string | null>(null);
+
+  const loadRuns = useCallback(() => {
+    projectsApi
+      .getDependencyUpdateRuns(projectId)
+      .then(setRuns)
+      .catch(() => setRuns([]));
+  }, [projectId]);
+
+  useEffect(() => {
+    setLoading(true);
+    projectsApi
+      .getDependencyUpdates(projectId)
+      .then((settings) => {
+        setEnabled(settings?.enabled ?? false);
+        setSchedule(settings?.schedule ?? 'weekly');
+        setGroups(formatGroups(settings?.groups ?? []));
+        setAllowMajor(settings?.allow_major ?? false);
+        setIgnored(settings?.ignored.join(', ') ?? '');
+        setInstructions(settings?.instructions ?? '');
+      })
+      .catch((err) =>
+        setError(
+          err instanceof Error
+            ? err.message
+            : 'Failed to load dependency updates'
+        )
+      )
+      .finally(() => setLoading(false));
+    loadRuns();
+  }, [projectId, loadRuns]);
+
+  const save = async () => {
+    await projectsApi.updateDependencyUpdates(projectId, {
+      enabled,
+      schedule,
+      groups: parseGroups(groups),
+      allow_major: allowMajor,
+      ignored: ignored.split(','),
+      instructions: instructions || null,
+    });
+  };
+
+  const handleSave = async () => {
+    setSaving(true);
+    setSaved(false);
+    setError(null);
+    try {
+      await save();
+      setSaved(true);
+    } catch (err) {
+      setError(
+        err instanceof Error ? err.message : 'Failed to save dependency updates'
+      );
+    } finally {
+      setSaving(false);
+    }
+  };
+
+  const handleRunNow = async () => {
+    setStarting(true);
+    setError(null);
+    try {
+      await save();
+      await projectsApi.runDependencyUpdates(projectId);
+      loadRuns();
+    } catch (err) {
+      setError(
+        err instanceof Error
+          ? err.message
+          : 'Failed to start dependency updates'
+      );
+    } finally {
+      setStarting(false);
+    }
+  };
+
+  if (loading) {
+    return <Loader2 className="h-4 w-4 animate-spin" />;
+  }
+
+  return (
+    <div className="space-y-4">
+      <div className="flex items-center gap-2">
+        <Checkbox
+          id="dependency-updates-enabled"
+          checked={enabled}
+          onCheckedChange={(checked) => setEnabled(checked === true)}
+        />
+        <Label htmlFor="dependency-updates-enabled">
+          Update dependencies on a schedule
+        </Label>
+      </div>
+      <p className="text-sm text-muted-foreground">
+        An agent updates each group of packages on its own attempt, from the
+        project&apos;s current branch, and runs the verification script. Updates
+        that pass are pushed and opened as pull requests with your GitHub token.
+      </p>
+      <div className="space-y-2">
+        <Label htmlFor="dependency-updates-schedule">Schedule</Label>
+        <Select
+          value={schedule}
+          onValueChange={(value) =>
+            setSchedule(value as DependencyUpdateSchedule)
+          }
+        >
+          <SelectTrigger id="dependency-updates-schedule">
+            <SelectValue />
+          </SelectTrigger>
+          <SelectContent>
+            <SelectItem value="daily">Nightly</SelectItem>
+            <SelectItem value="weekly">Monday nights</SelectItem>
+          </SelectContent>
+        </Select>
+      </div>
+      <div className="space-y-2">
+        <Label htmlFor="dependency-updates-groups">Groups</Label>
+        <Textarea
+          id="dependency-updates-groups"
+          value={groups}
+          onChange={(e) => setGroups(e.target.value)}
+          placeholder={'types: @types/*\nlint: eslint*, prettier'}
+          rows={3}
+          className="font-mono"
+        />
+        <p className="text-sm text-muted-foreground">
+          One group per line. Packages no group matches get a pull request of
+          their own.
+        </p>
+      </div>
+      <div className="space-y-2">
+        <Label htmlFor="dependency-updates-ignored">Never update</Label>
+        <Input
+          id="dependency-updates-ignored"
+          placeholder="react, react-dom"
+          value={ignored}
+          onChange={(e) => setIgnored(e.target.value)}
+        />
+      </div>
+      <div className="flex items-center gap-2">
+        <Checkbox
+          id="dependency-updates-allow-major"
+          checked={allowMajor}
+          onCheckedChange={(checked) => setAllowMajor(checked === true)}
+        />
+        <Label htmlFor="dependency-updates-allow-major">
+          Allow major version updates
+        </Label>
+      </div>
+      <div className="space-y-2">
+        <Label htmlFor="dependency-updates-instructions">Instructions</Label>
+        <Textarea
+          id="dependency-updates-instructions"
+          value={instructions}
+          onChange={(e) => setInstructions(e.target.value)}
+          placeholder="Keep Node typings on the version of our runtime"
+          rows={2}
+        />
+      </div>
+      {runs.length > 0 && (
+        <div className="space-y-1">
+          <Label>Recent runs</Label>
+          {runs.map((run) => (
+            <div
+              key={run.id}
+              className="flex items-center justify-between text-sm"
+            >
+              <span>
+                {run.group_name} &middot;{' '}
+                {new Date(run.created_at).toLocaleDateString()}
+              </span>
+              {run.pr_url ? (
+                <a
+                  href={run.pr_url}
+                  target="_blank"
+                  rel="noreferrer"
+                  className="underline"
+                >
+                  {STATUS_LABELS[run.status]}
+                </a>
+              ) : (
+                <span
+                  className="text-muted-foreground"
+                  title={run.error ?? undefined}
+                >
+                  {STATUS_LABELS[run.status]}
+                </span>
+              )}
+            </div>
+          ))}
+        </div>
+      )}
+      {error && <p className="text-sm text-destructive">{error}</p>}
+      <div className="flex items-center justify-end gap-2">
+        {saved && <span className="text-sm text-green-600">Saved</span>}
+        <Button
+          variant="outline"
+          onClick={handleRunNow}
+          disabled={saving || starting}
+        >
+          {starting ? 'Starting...' : 'Run now'}
+        </Button>
+        <Button onClick={handleSave} disabled={saving || starting}>
+          {saving ? 'Saving...' : 'Save'}
+        </Button>
+      </div>
+    </div>
+  );
+}
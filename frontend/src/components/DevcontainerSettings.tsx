@@ -0,0 +1,104 @@
+import { useEffect, useState } from 'react';
+import { Button } from '@/components/ui/button';
+import { Checkbox } from '@/components/ui/checkbox';
+import { Label } from '@/components/ui/label';
+import { Loader2 } from 'lucide-react';
+import { useUserSystem } from '@/components/config-provider';
+import { projectsApi } from '@/lib/api';
+
+interface DevcontainerSettingsProps {
+  projectId: string;
+}
+
+// Running attempts in the devcontainer the repository defines
+export function DevcontainerSettings({ projectId }: DevcontainerSettingsProps) {
+  const { featureFlags } = useUserSystem();
+  const [configPath, setConfigPath] = useState<string | null>(null);
+  const [enabled, setEnabled] = useState(false);
+  const [loading, setLoading] = useState(true);
+  const [saving, setSaving] = useState(false);
+  const [saved, setSaved] = useState(false);
+  const [error, setError] = useState<string | null>(null);
+
+  useEffect(() => {
+    setLoading(true);
+    projectsApi
+      .getDevcontainer(projectId)
+      .then((status) => {
+        setConfigPath(status.config_path);
+        setEnabled(status.settings?.enabled ?? false);
+      })
+      .catch((err) =>
+        setError(
+          err instanceof Error ? err.message : 'Failed to load devcontainer'
+        )
+      )
+      .finally(() => setLoading(false));
+  }, [projectId]);
+
+  const handleSave = async () => {
+    setSaving(true);
+    setSaved(false);
+    setError(null);
+    try {
+      await projectsApi.updateDevcontainer(projectId, { enabled });
+      setSaved(true);
+    } catch (err) {
+      setError(
+        err instanceof Error ? err.message : 'Failed to save devcontainer'
+      );
+    } finally {
+      setSaving(false);
+    }
+  };
+
+  if (loading) {
+    return <Loader2 className="h-4 w-4 animate-spin" />;
+  }
+
+  // Nothing to offer for repositories without a devcontainer, unless it was
+  // enabled before the definition was removed
+  if (!configPath && !enabled) {
+    return error ? <p className="text-sm text-destructive">{error}</p> : null;
+  }
+
+  return (
+    <div className="space-y-4">
+      <p className="text-sm text-muted-foreground">
+        The repository defines a devcontainer in <code>{configPath}</code>.
+        Each attempt can run its setup script and coding agent in its own
+        container, built from it and mounting the worktree. Dev servers still
+        run on the host.
+      </p>
+      {featureFlags?.container_sandbox === false && (
+        <p className="text-sm text-amber-600">
+          The container sandbox is turned off under Experimental Features,
+          attempts run on the host until it is turned back on.
+        </p>
+      )}
+      <div className="space-y-2">
+        <div className="flex items-center space-x-2">
+          <Checkbox
+            id="devcontainer-enabled"
+            checked={enabled}
+            onCheckedChange={(checked: boolean) => setEnabled(checked)}
+          />
+          <Label htmlFor="devcontainer-enabled" className="cursor-pointer">
+            Run attempts in the devcontainer
+          </Label>
+        </div>
+        <p className="text-sm text-muted-foreground">
+          Requires Docker. The coding agent's CLI must be installed in the
+          image, and applies to attempts started afterwards.
+        </p>
+      </div>
+      {error && <p className="text-sm text-destructive">{error}</p>}
+      <div className="flex items-center justify-end gap-2">
+        {saved && <span className="text-sm text-green-600">Saved</span>}
+        <Button onClick={handleSave} disabled={saving}>
+          {saving ? 'Saving...' : 'Save'}
+        </Button>
+      </div>
+    </div>
+  );
+}
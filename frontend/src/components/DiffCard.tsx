@@ -1,19 +1,22 @@
 import { DiffFile, DiffModeEnum, DiffView } from '@git-diff-view/react';
-import { ThemeMode } from 'shared/types';
+import { PathViolation, ThemeMode } from 'shared/types';
 import '../styles/diff-style-overrides.css';
 import { useConfig } from './config-provider';
 import { useContext } from 'react';
 import { TaskSelectedAttemptContext } from './context/taskDetailsContext';
 import { Button } from './ui/button';
 import { FolderOpen } from 'lucide-react';
-import { attemptsApi } from '@/lib/api';
+import { editorApi } from '@/lib/api';
 
 type Props = {
   diffFile: DiffFile;
   key: any;
+  violation?: PathViolation;
+  // No worktree to open the file in, e.g. on a share link
+  readOnly?: boolean;
 };
 
-const DiffCard = ({ diffFile, key }: Props) => {
+const DiffCard = ({ diffFile, key, violation, readOnly }: Props) => {
   const { config } = useConfig();
   const { selectedAttempt } = useContext(TaskSelectedAttemptContext);
 
@@ -25,11 +28,13 @@ const DiffCard = ({ diffFile, key }: Props) => {
   const handleOpenInIDE = async () => {
     if (!selectedAttempt?.id) return;
     try {
-      await attemptsApi.openEditor(
-        selectedAttempt.id,
-        undefined,
-        diffFile._newFileName
-      );
+      await editorApi.open({
+        attempt_id: selectedAttempt.id,
+        file_path: diffFile._newFileName,
+        line: null,
+        column: null,
+        editor_type: null,
+      });
     } catch (error) {
       console.error('Failed to open file in IDE:', error);
     }
@@ -49,16 +54,30 @@ const DiffCard = ({ diffFile, key }: Props) => {
           <span style={{ color: 'hsl(var(--console-error))' }}>
             -{diffFile.deletionLength}
           </span>
+          {violation && (
+            <span
+              className="ml-2 rounded border border-amber-500/50 px-1 font-sans text-amber-600"
+              title={
+                violation.reason.kind === 'forbidden'
+                  ? `Forbidden by ${violation.reason.pattern}`
+                  : 'Not an allowed path of this project'
+              }
+            >
+              Out of scope
+            </span>
+          )}
         </p>
-        <Button
-          variant="ghost"
-          size="sm"
-          onClick={handleOpenInIDE}
-          className="h-6 w-6 p-0 ml-2"
-          title="Open in IDE"
-        >
-          <FolderOpen className="h-3 w-3" />
-        </Button>
+        {!readOnly && (
+          <Button
+            variant="ghost"
+            size="sm"
+            onClick={handleOpenInIDE}
+            className="h-6 w-6 p-0 ml-2"
+            title="Open in IDE"
+          >
+            <FolderOpen className="h-3 w-3" />
+          </Button>
+        )}
       </div>
       <DiffView
         diffFile={diffFile}
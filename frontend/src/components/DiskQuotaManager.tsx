@@ -0,0 +1,174 @@
+import { useCallback, useEffect, useState } from 'react';
+import { Button } from '@/components/ui/button';
+import { Checkbox } from '@/components/ui/checkbox';
+import { Input } from '@/components/ui/input';
+import { Loader2 } from 'lucide-react';
+import { diskUsageApi, projectsApi } from '@/lib/api';
+import type { Project, ProjectDiskUsage } from 'shared/types';
+
+const GB = 1024 * 1024 * 1024;
+
+const formatBytes = (bytes: number) => {
+  if (bytes >= GB) return `${(bytes / GB).toFixed(1)} GB`;
+  return `${(bytes / (1024 * 1024)).toFixed(0)} MB`;
+};
+
+// Disk space of each project's worktrees, and the quotas that stop new attempts
+export function DiskQuotaManager() {
+  const [projects, setProjects] = useState<Project[]>([]);
+  const [usage, setUsage] = useState<Record<string, ProjectDiskUsage>>({});
+  const [quotas, setQuotas] = useState<Record<string, string>>({});
+  const [loading, setLoading] = useState(true);
+  const [collecting, setCollecting] = useState<string | null>(null);
+  const [error, setError] = useState<string | null>(null);
+
+  const showUsage = useCallback((projectUsage: ProjectDiskUsage) => {
+    setUsage((prev) => ({ ...prev, [projectUsage.project_id]: projectUsage }));
+    setQuotas((prev) => ({
+      ...prev,
+      [projectUsage.project_id]:
+        projectUsage.max_bytes === null
+          ? ''
+          : (projectUsage.max_bytes / GB).toString(),
+    }));
+  }, []);
+
+  const load = useCallback(async () => {
+    try {
+      const allProjects = await projectsApi.getAll();
+      setProjects(allProjects);
+      const all = await Promise.all(
+        allProjects.map((project) => diskUsageApi.get(project.id))
+      );
+      all.forEach(showUsage);
+    } catch (err) {
+      setError(
+        err instanceof Error ? err.message : 'Failed to load disk usage'
+      );
+    } finally {
+      setLoading(false);
+    }
+  }, [showUsage]);
+
+  useEffect(() => {
+    load();
+  }, [load]);
+
+  const saveQuota = async (projectId: string, autoGc: boolean) => {
+    const value = (quotas[projectId] ?? '').trim();
+    const gb = value === '' ? null : Number(value);
+    if (gb !== null && (Number.isNaN(gb) || gb <= 0)) {
+      setError('Quotas must be a positive number of GB');
+      return;
+    }
+    setError(null);
+    try {
+      await diskUsageApi.updateQuota(
+        projectId,
+        gb === null ? null : Math.round(gb * GB),
+        autoGc
+      );
+      showUsage(await diskUsageApi.get(projectId));
+    } catch (err) {
+      setError(err instanceof Error ? err.message : 'Failed to save quota');
+    }
+  };
+
+  const collect = async (projectId: string) => {
+    setCollecting(projectId);
+    setError(null);
+    try {
+      showUsage(await diskUsageApi.collect(projectId));
+    } catch (err) {
+      setError(
+        err instanceof Error ? err.message : 'Failed to clean up worktrees'
+      );
+    } finally {
+      setCollecting(null);
+    }
+  };
+
+  if (loading) {
+    return <Loader2 className="h-4 w-4 animate-spin" />;
+  }
+
+  return (
+    <div className="space-y-2">
+      <table className="w-full text-sm">
+        <thead>
+          <tr className="text-left text-muted-foreground">
+            <th className="font-normal">Project</th>
+            <th className="font-normal">Used</th>
+            <th className="font-normal">Quota (GB)</th>
+            <th className="font-normal">Auto clean up</th>
+            <th />
+          </tr>
+        </thead>
+        <tbody>
+          {projects.map((project) => {
+            const projectUsage = usage[project.id];
+            if (!projectUsage) return null;
+            const exceeded =
+              projectUsage.max_bytes !== null &&
+              projectUsage.used_bytes > projectUsage.max_bytes;
+            return (
+              <tr key={project.id}>
+                <td className="py-1 pr-2">{project.name}</td>
+                <td
+                  className={`py-1 pr-2 ${exceeded ? 'text-destructive' : ''}`}
+                >
+                  {formatBytes(projectUsage.used_bytes)} (
+                  {projectUsage.worktrees.length} worktree
+                  {projectUsage.worktrees.length === 1 ? '' : 's'})
+                </td>
+                <td className="py-1 pr-2">
+                  <Input
+                    type="number"
+                    min={0}
+                    step="0.5"
+                    placeholder="No quota"
+                    className="h-8 w-28"
+                    value={quotas[project.id] ?? ''}
+                    onChange={(e) =>
+                      setQuotas((prev) => ({
+                        ...prev,
+                        [project.id]: e.target.value,
+                      }))
+                    }
+                    onBlur={() => saveQuota(project.id, projectUsage.auto_gc)}
+                  />
+                </td>
+                <td className="py-1 pr-2">
+                  <Checkbox
+                    checked={projectUsage.auto_gc}
+                    disabled={projectUsage.max_bytes === null}
+                    onCheckedChange={(checked: boolean) =>
+                      saveQuota(project.id, checked)
+                    }
+                  />
+                </td>
+                <td className="py-1 text-right">
+                  <Button
+                    variant="outline"
+                    size="sm"
+                    disabled={
+                      projectUsage.reclaimable_bytes === 0 ||
+                      collecting === project.id
+                    }
+                    onClick={() => collect(project.id)}
+                  >
+                    {collecting === project.id && (
+                      <Loader2 className="mr-2 h-4 w-4 animate-spin" />
+                    )}
+                    Free {formatBytes(projectUsage.reclaimable_bytes)}
+                  </Button>
+                </td>
+              </tr>
+            );
+          })}
+        </tbody>
+      </table>
+      {error && <p className="text-sm text-destructive">{error}</p>}
+    </div>
+  );
+}
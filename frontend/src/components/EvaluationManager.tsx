@@ -0,0 +1,257 @@
+import { useCallback, useEffect, useState } from 'react';
+import { Button } from '@/components/ui/button';
+import { Checkbox } from '@/components/ui/checkbox';
+import { Input } from '@/components/ui/input';
+import { Label } from '@/components/ui/label';
+import { Loader2 } from 'lucide-react';
+import { useUserSystem } from '@/components/config-provider';
+import { evaluationsApi } from '@/lib/api';
+import type {
+  Evaluation,
+  EvaluationReport,
+  ProfileScore,
+  ProfileVariantLabel,
+} from 'shared/types';
+
+interface EvaluationManagerProps {
+  projectId: string;
+}
+
+// Reports of running evaluations are refreshed while they're shown
+const REFRESH_MS = 10_000;
+
+const labelKey = (label: ProfileVariantLabel) =>
+  `${label.profile}/${label.variant ?? ''}`;
+
+const labelName = (label: ProfileVariantLabel) =>
+  label.variant ? `${label.profile} (${label.variant})` : label.profile;
+
+const errorMessage = (err: unknown, fallback: string) =>
+  err instanceof Error ? err.message : fallback;
+
+const formatDuration = (seconds: number | null) =>
+  seconds === null ? '–' : `${Math.round(seconds / 60)} min`;
+
+function ScoreRow({ score, rank }: { score: ProfileScore; rank: number }) {
+  return (
+    <tr>
+      <td className="py-1 pr-2">{rank}</td>
+      <td className="py-1 pr-2">{labelName(score)}</td>
+      <td className="py-1 pr-2 text-right">
+        {score.finished > 0 ? `${Math.round(score.mean_score * 100)}%` : '–'}
+      </td>
+      <td className="py-1 pr-2 text-right">
+        {score.agent_completed} / {score.runs}
+      </td>
+      <td className="py-1 pr-2 text-right">{score.verified}</td>
+      <td className="py-1 pr-2 text-right">${score.cost_usd.toFixed(2)}</td>
+      <td className="py-1 text-right">
+        {formatDuration(score.mean_duration_seconds)}
+      </td>
+    </tr>
+  );
+}
+
+// Replays the project's done tasks against several profiles and ranks them
+export function EvaluationManager({ projectId }: EvaluationManagerProps) {
+  const { profiles } = useUserSystem();
+  const [evaluations, setEvaluations] = useState<Evaluation[]>([]);
+  const [report, setReport] = useState<EvaluationReport | null>(null);
+  const [name, setName] = useState('');
+  const [picked, setPicked] = useState<ProfileVariantLabel[]>([]);
+  const [loading, setLoading] = useState(true);
+  const [starting, setStarting] = useState(false);
+  const [error, setError] = useState<string | null>(null);
+
+  const labels: ProfileVariantLabel[] = (profiles ?? []).flatMap((p) => [
+    { profile: p.label, variant: null },
+    ...p.variants.map((v) => ({ profile: p.label, variant: v.label })),
+  ]);
+
+  const loadEvaluations = useCallback(
+    () =>
+      evaluationsApi
+        .list(projectId)
+        .then(setEvaluations)
+        .catch((err) =>
+          setError(errorMessage(err, 'Failed to load evaluations'))
+        ),
+    [projectId]
+  );
+
+  useEffect(() => {
+    setLoading(true);
+    setReport(null);
+    loadEvaluations().finally(() => setLoading(false));
+  }, [loadEvaluations]);
+
+  const showReport = useCallback((id: string) => {
+    evaluationsApi
+      .getReport(id)
+      .then(setReport)
+      .catch((err) =>
+        setError(errorMessage(err, 'Failed to load the report'))
+      );
+  }, []);
+
+  const reportId = report?.evaluation.id;
+  const reportRunning = report?.evaluation.status === 'running';
+  useEffect(() => {
+    if (!reportId || !reportRunning) return;
+    const interval = setInterval(() => showReport(reportId), REFRESH_MS);
+    return () => clearInterval(interval);
+  }, [reportId, reportRunning, showReport]);
+
+  const toggle = (label: ProfileVariantLabel, checked: boolean) =>
+    setPicked((prev) =>
+      checked
+        ? [...prev, label]
+        : prev.filter((l) => labelKey(l) !== labelKey(label))
+    );
+
+  const start = async () => {
+    setStarting(true);
+    setError(null);
+    try {
+      const evaluation = await evaluationsApi.create({
+        project_id: projectId,
+        name,
+        task_ids: [],
+        profiles: picked,
+      });
+      setName('');
+      await loadEvaluations();
+      showReport(evaluation.id);
+    } catch (err) {
+      setError(errorMessage(err, 'Failed to start the evaluation'));
+    } finally {
+      setStarting(false);
+    }
+  };
+
+  const cancel = async (id: string) => {
+    setError(null);
+    try {
+      await evaluationsApi.cancel(id);
+      await loadEvaluations();
+      showReport(id);
+    } catch (err) {
+      setError(errorMessage(err, 'Failed to cancel the evaluation'));
+    }
+  };
+
+  if (loading) {
+    return <Loader2 className="h-4 w-4 animate-spin" />;
+  }
+
+  return (
+    <div className="space-y-6">
+      <div className="space-y-3">
+        <p className="text-sm text-muted-foreground">
+          Replays every done task from where it started with each picked
+          profile, one at a time, and scores the results with the
+          project&apos;s verification and test scripts.
+        </p>
+        <Input
+          className="h-8"
+          placeholder="Evaluation name"
+          value={name}
+          onChange={(e) => setName(e.target.value)}
+        />
+        <div className="grid grid-cols-2 gap-2">
+          {labels.map((label) => {
+            const key = labelKey(label);
+            return (
+              <div key={key} className="flex items-center gap-2">
+                <Checkbox
+                  id={`evaluate-${key}`}
+                  checked={picked.some((l) => labelKey(l) === key)}
+                  onCheckedChange={(checked) => toggle(label, checked === true)}
+                />
+                <Label htmlFor={`evaluate-${key}`}>{labelName(label)}</Label>
+              </div>
+            );
+          })}
+        </div>
+        <Button
+          size="sm"
+          disabled={starting || !name.trim() || picked.length === 0}
+          onClick={start}
+        >
+          {starting && <Loader2 className="mr-2 h-4 w-4 animate-spin" />}
+          Start evaluation
+        </Button>
+      </div>
+
+      {evaluations.length > 0 && (
+        <div className="space-y-1">
+          {evaluations.map((evaluation) => (
+            <div
+              key={evaluation.id}
+              className="flex items-center justify-between text-sm"
+            >
+              <span>
+                {evaluation.name}{' '}
+                <span className="text-muted-foreground">
+                  {evaluation.status}
+                </span>
+              </span>
+              <div className="flex gap-2">
+                {evaluation.status === 'running' && (
+                  <Button
+                    size="sm"
+                    variant="outline"
+                    onClick={() => cancel(evaluation.id)}
+                  >
+                    Cancel
+                  </Button>
+                )}
+                <Button
+                  size="sm"
+                  variant="outline"
+                  onClick={() => showReport(evaluation.id)}
+                >
+                  Report
+                </Button>
+              </div>
+            </div>
+          ))}
+        </div>
+      )}
+
+      {report && (
+        <div className="space-y-2">
+          <p className="text-sm font-medium">
+            {report.evaluation.name}:{' '}
+            {report.runs.filter((r) => r.status !== 'pending').length} of{' '}
+            {report.runs.length} replays run
+          </p>
+          {report.evaluation.error && (
+            <p className="text-sm text-destructive">
+              {report.evaluation.error}
+            </p>
+          )}
+          <table className="w-full text-sm">
+            <thead>
+              <tr className="text-left text-muted-foreground">
+                <th className="font-normal">#</th>
+                <th className="font-normal">Profile</th>
+                <th className="font-normal text-right">Score</th>
+                <th className="font-normal text-right">Completed</th>
+                <th className="font-normal text-right">Verified</th>
+                <th className="font-normal text-right">Cost</th>
+                <th className="font-normal text-right">Avg time</th>
+              </tr>
+            </thead>
+            <tbody>
+              {report.leaderboard.map((score, i) => (
+                <ScoreRow key={labelKey(score)} score={score} rank={i + 1} />
+              ))}
+            </tbody>
+          </table>
+        </div>
+      )}
+      {error && <p className="text-sm text-destructive">{error}</p>}
+    </div>
+  );
+}
@@ -0,0 +1,77 @@
+import { useEffect, useState } from 'react';
+import { Loader2 } from 'lucide-react';
+import { Checkbox } from '@/components/ui/checkbox';
+import { Label } from '@/components/ui/label';
+import { featureFlagsApi } from '@/lib/api';
+import { toPrettyCase } from '@/utils/string';
+import type { FeatureFlag, FeatureFlagState } from 'shared/types';
+
+// Experimental subsystems, toggled straight away. Flags set through their
+// environment variable are shown but can't be changed here.
+export function FeatureFlagManager() {
+  const [flags, setFlags] = useState<FeatureFlagState[] | null>(null);
+  const [saving, setSaving] = useState<FeatureFlag | null>(null);
+  const [error, setError] = useState<string | null>(null);
+
+  useEffect(() => {
+    featureFlagsApi
+      .list()
+      .then(setFlags)
+      .catch((err) =>
+        setError(err instanceof Error ? err.message : 'Failed to load flags')
+      );
+  }, []);
+
+  const toggle = async (flag: FeatureFlag, enabled: boolean) => {
+    setSaving(flag);
+    setError(null);
+    try {
+      setFlags(await featureFlagsApi.set(flag, enabled));
+    } catch (err) {
+      setError(err instanceof Error ? err.message : 'Failed to save flag');
+    } finally {
+      setSaving(null);
+    }
+  };
+
+  if (!flags) {
+    return error ? (
+      <p className="text-sm text-destructive">{error}</p>
+    ) : (
+      <Loader2 className="h-4 w-4 animate-spin" />
+    );
+  }
+
+  return (
+    <div className="space-y-4">
+      {flags.map((state) => (
+        <div key={state.flag} className="flex items-center space-x-2">
+          <Checkbox
+            id={`feature-flag-${state.flag}`}
+            checked={state.enabled}
+            disabled={state.env_override !== null || saving !== null}
+            onCheckedChange={(checked: boolean) => toggle(state.flag, checked)}
+          />
+          <div className="space-y-0.5">
+            <Label
+              htmlFor={`feature-flag-${state.flag}`}
+              className="cursor-pointer"
+            >
+              {toPrettyCase(state.flag)}
+            </Label>
+            <p className="text-sm text-muted-foreground">
+              {state.description}.
+              {state.env_override !== null && (
+                <>
+                  {' '}
+                  Set by <code className="text-xs">{state.env_var}</code>.
+                </>
+              )}
+            </p>
+          </div>
+        </div>
+      ))}
+      {error && <p className="text-sm text-destructive">{error}</p>}
+    </div>
+  );
+}
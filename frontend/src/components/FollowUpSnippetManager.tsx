@@ -0,0 +1,285 @@
+import { useState, useEffect, useCallback } from 'react';
+import { Button } from '@/components/ui/button';
+import { Input } from '@/components/ui/input';
+import { Label } from '@/components/ui/label';
+import { Textarea } from '@/components/ui/textarea';
+import {
+  Dialog,
+  DialogContent,
+  DialogHeader,
+  DialogTitle,
+  DialogFooter,
+} from '@/components/ui/dialog';
+import { Plus, Edit2, Trash2, Loader2 } from 'lucide-react';
+import { followUpSnippetsApi } from '@/lib/api';
+import type {
+  FollowUpSnippet,
+  CreateFollowUpSnippet,
+  UpdateFollowUpSnippet,
+} from 'shared/types';
+
+interface FollowUpSnippetManagerProps {
+  projectId?: string;
+  isGlobal?: boolean;
+}
+
+export function FollowUpSnippetManager({
+  projectId,
+  isGlobal = false,
+}: FollowUpSnippetManagerProps) {
+  const [snippets, setSnippets] = useState<FollowUpSnippet[]>([]);
+  const [loading, setLoading] = useState(true);
+  const [isDialogOpen, setIsDialogOpen] = useState(false);
+  const [editingSnippet, setEditingSnippet] = useState<FollowUpSnippet | null>(
+    null
+  );
+  const [formData, setFormData] = useState({ name: '', prompt: '' });
+  const [saving, setSaving] = useState(false);
+  const [error, setError] = useState<string | null>(null);
+
+  const fetchSnippets = useCallback(async () => {
+    setLoading(true);
+    try {
+      const data = isGlobal
+        ? await followUpSnippetsApi.listGlobal()
+        : projectId
+          ? await followUpSnippetsApi.listForProject(projectId)
+          : [];
+
+      // A project's list includes the global snippets it doesn't replace
+      const filtered = data.filter((snippet) =>
+        isGlobal
+          ? snippet.project_id === null
+          : snippet.project_id === projectId
+      );
+
+      setSnippets(filtered);
+    } catch (err) {
+      console.error('Failed to fetch snippets:', err);
+    } finally {
+      setLoading(false);
+    }
+  }, [isGlobal, projectId]);
+
+  useEffect(() => {
+    fetchSnippets();
+  }, [fetchSnippets]);
+
+  const handleOpenDialog = useCallback((snippet?: FollowUpSnippet) => {
+    if (snippet) {
+      setEditingSnippet(snippet);
+      setFormData({ name: snippet.name, prompt: snippet.prompt });
+    } else {
+      setEditingSnippet(null);
+      setFormData({ name: '', prompt: '' });
+    }
+    setError(null);
+    setIsDialogOpen(true);
+  }, []);
+
+  const handleCloseDialog = useCallback(() => {
+    setIsDialogOpen(false);
+    setEditingSnippet(null);
+    setFormData({ name: '', prompt: '' });
+    setError(null);
+  }, []);
+
+  const handleSave = useCallback(async () => {
+    if (!formData.name.trim() || !formData.prompt.trim()) {
+      setError('Name and prompt are required');
+      return;
+    }
+
+    setSaving(true);
+    setError(null);
+
+    try {
+      if (editingSnippet) {
+        const updateData: UpdateFollowUpSnippet = {
+          name: formData.name,
+          prompt: formData.prompt,
+        };
+        await followUpSnippetsApi.update(editingSnippet.id, updateData);
+      } else {
+        const createData: CreateFollowUpSnippet = {
+          project_id: isGlobal ? null : projectId || null,
+          name: formData.name,
+          prompt: formData.prompt,
+        };
+        await followUpSnippetsApi.create(createData);
+      }
+      await fetchSnippets();
+      handleCloseDialog();
+    } catch (err: any) {
+      setError(err.message || 'Failed to save snippet');
+    } finally {
+      setSaving(false);
+    }
+  }, [
+    formData,
+    editingSnippet,
+    isGlobal,
+    projectId,
+    fetchSnippets,
+    handleCloseDialog,
+  ]);
+
+  const handleDelete = useCallback(
+    async (snippet: FollowUpSnippet) => {
+      if (
+        !confirm(
+          `Are you sure you want to delete the snippet "${snippet.name}"?`
+        )
+      ) {
+        return;
+      }
+
+      try {
+        await followUpSnippetsApi.delete(snippet.id);
+        await fetchSnippets();
+      } catch (err) {
+        console.error('Failed to delete snippet:', err);
+      }
+    },
+    [fetchSnippets]
+  );
+
+  if (loading) {
+    return (
+      <div className="flex items-center justify-center py-8">
+        <Loader2 className="h-8 w-8 animate-spin" />
+      </div>
+    );
+  }
+
+  return (
+    <div className="space-y-4">
+      <div className="flex justify-between items-center">
+        <h3 className="text-lg font-semibold">
+          {isGlobal ? 'Global Snippets' : 'Project Snippets'}
+        </h3>
+        <Button onClick={() => handleOpenDialog()} size="sm">
+          <Plus className="h-4 w-4 mr-2" />
+          Add Snippet
+        </Button>
+      </div>
+
+      {!isGlobal && (
+        <p className="text-sm text-muted-foreground">
+          A project snippet replaces the global snippet of the same name.
+        </p>
+      )}
+
+      {snippets.length === 0 ? (
+        <div className="text-center py-8 text-muted-foreground">
+          No snippets yet. Create your first snippet to get started.
+        </div>
+      ) : (
+        <div className="border rounded-lg overflow-hidden">
+          <div className="max-h-[400px] overflow-auto">
+            <table className="w-full">
+              <thead className="border-b bg-muted/50 sticky top-0">
+                <tr>
+                  <th className="text-left p-2 text-sm font-medium">Name</th>
+                  <th className="text-left p-2 text-sm font-medium">Prompt</th>
+                  <th className="text-right p-2 text-sm font-medium">
+                    Actions
+                  </th>
+                </tr>
+              </thead>
+              <tbody>
+                {snippets.map((snippet) => (
+                  <tr
+                    key={snippet.id}
+                    className="border-b hover:bg-muted/30 transition-colors"
+                  >
+                    <td className="p-2 text-sm font-medium">{snippet.name}</td>
+                    <td className="p-2 text-sm">
+                      <div
+                        className="max-w-[300px] truncate"
+                        title={snippet.prompt}
+                      >
+                        {snippet.prompt}
+                      </div>
+                    </td>
+                    <td className="p-2">
+                      <div className="flex justify-end gap-1">
+                        <Button
+                          variant="ghost"
+                          size="icon"
+                          className="h-7 w-7"
+                          onClick={() => handleOpenDialog(snippet)}
+                          title="Edit snippet"
+                        >
+                          <Edit2 className="h-3 w-3" />
+                        </Button>
+                        <Button
+                          variant="ghost"
+                          size="icon"
+                          className="h-7 w-7"
+                          onClick={() => handleDelete(snippet)}
+                          title="Delete snippet"
+                        >
+                          <Trash2 className="h-3 w-3" />
+                        </Button>
+                      </div>
+                    </td>
+                  </tr>
+                ))}
+              </tbody>
+            </table>
+          </div>
+        </div>
+      )}
+
+      <Dialog open={isDialogOpen} onOpenChange={setIsDialogOpen}>
+        <DialogContent className="sm:max-w-[500px]">
+          <DialogHeader>
+            <DialogTitle>
+              {editingSnippet ? 'Edit Snippet' : 'Create Snippet'}
+            </DialogTitle>
+          </DialogHeader>
+          <div className="space-y-4 py-4">
+            <div>
+              <Label htmlFor="snippet-name">Name</Label>
+              <Input
+                id="snippet-name"
+                value={formData.name}
+                onChange={(e) =>
+                  setFormData({ ...formData, name: e.target.value })
+                }
+                placeholder="e.g., Add tests"
+              />
+            </div>
+            <div>
+              <Label htmlFor="snippet-prompt">Prompt</Label>
+              <Textarea
+                id="snippet-prompt"
+                value={formData.prompt}
+                onChange={(e) =>
+                  setFormData({ ...formData, prompt: e.target.value })
+                }
+                placeholder="The follow-up prompt to insert"
+                rows={4}
+              />
+            </div>
+            {error && <div className="text-sm text-red-600">{error}</div>}
+          </div>
+          <DialogFooter>
+            <Button
+              variant="outline"
+              onClick={handleCloseDialog}
+              disabled={saving}
+            >
+              Cancel
+            </Button>
+            <Button onClick={handleSave} disabled={saving}>
+              {saving && <Loader2 className="mr-2 h-4 w-4 animate-spin" />}
+              {editingSnippet ? 'Update' : 'Create'}
+            </Button>
+          </DialogFooter>
+        </DialogContent>
+      </Dialog>
+    </div>
+  );
+}
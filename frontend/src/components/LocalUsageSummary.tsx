@@ -0,0 +1,44 @@
+import { useEffect, useState } from 'react';
+import { Loader2 } from 'lucide-react';
+import { analyticsApi } from '@/lib/api';
+import type { AnalyticsEventCount } from 'shared/types';
+
+// Usage events the server recorded locally, most frequent first
+export function LocalUsageSummary() {
+  const [counts, setCounts] = useState<AnalyticsEventCount[] | null>(null);
+  const [error, setError] = useState<string | null>(null);
+
+  useEffect(() => {
+    analyticsApi
+      .getSummary()
+      .then(setCounts)
+      .catch((err) =>
+        setError(
+          err instanceof Error ? err.message : 'Failed to load local usage'
+        )
+      );
+  }, []);
+
+  if (error) {
+    return <p className="text-sm text-destructive">{error}</p>;
+  }
+  if (!counts) {
+    return <Loader2 className="h-4 w-4 animate-spin" />;
+  }
+  if (counts.length === 0) {
+    return <p className="text-sm text-muted-foreground">No events yet.</p>;
+  }
+
+  return (
+    <ul className="space-y-1">
+      {counts.map((count) => (
+        <li key={count.name} className="flex justify-between text-sm">
+          <span className="font-mono">{count.name}</span>
+          <span className="text-muted-foreground">
+            {count.count} · last {new Date(count.last_at).toLocaleString()}
+          </span>
+        </li>
+      ))}
+    </ul>
+  );
+}
@@ -0,0 +1,126 @@
+import { useEffect, useState } from 'react';
+import { Button } from '@/components/ui/button';
+import { Checkbox } from '@/components/ui/checkbox';
+import { Label } from '@/components/ui/label';
+import {
+  Select,
+  SelectContent,
+  SelectItem,
+  SelectTrigger,
+  SelectValue,
+} from '@/components/ui/select';
+import { Loader2 } from 'lucide-react';
+import { projectsApi } from '@/lib/api';
+import type { NixTool } from 'shared/types';
+
+const toolLabels: Record<NixTool, string> = {
+  flake: 'Nix flake (flake.nix)',
+  devenv: 'devenv (devenv.nix)',
+};
+
+interface NixEnvSettingsProps {
+  projectId: string;
+}
+
+// Running attempts in the Nix development shell the repository defines
+export function NixEnvSettings({ projectId }: NixEnvSettingsProps) {
+  const [detected, setDetected] = useState<NixTool | null>(null);
+  const [enabled, setEnabled] = useState(false);
+  const [tool, setTool] = useState<NixTool>('flake');
+  const [loading, setLoading] = useState(true);
+  const [saving, setSaving] = useState(false);
+  const [saved, setSaved] = useState(false);
+  const [error, setError] = useState<string | null>(null);
+
+  useEffect(() => {
+    setLoading(true);
+    projectsApi
+      .getNixEnv(projectId)
+      .then((status) => {
+        setDetected(status.detected);
+        setEnabled(status.settings?.enabled ?? false);
+        setTool(status.settings?.tool ?? status.detected ?? 'flake');
+      })
+      .catch((err) =>
+        setError(
+          err instanceof Error ? err.message : 'Failed to load Nix settings'
+        )
+      )
+      .finally(() => setLoading(false));
+  }, [projectId]);
+
+  const handleSave = async () => {
+    setSaving(true);
+    setSaved(false);
+    setError(null);
+    try {
+      await projectsApi.updateNixEnv(projectId, { enabled, tool });
+      setSaved(true);
+    } catch (err) {
+      setError(
+        err instanceof Error ? err.message : 'Failed to save Nix settings'
+      );
+    } finally {
+      setSaving(false);
+    }
+  };
+
+  if (loading) {
+    return <Loader2 className="h-4 w-4 animate-spin" />;
+  }
+
+  // Nothing to offer for repositories without a shell, unless it was enabled
+  // before the definition was removed
+  if (!detected && !enabled) {
+    return error ? <p className="text-sm text-destructive">{error}</p> : null;
+  }
+
+  return (
+    <div className="space-y-4">
+      <p className="text-sm text-muted-foreground">
+        The repository defines a Nix development shell. Attempts can run their
+        setup scripts, coding agents, tests and dev servers with the toolchain
+        it pins, as in <code>nix develop -c</code>. The shell is evaluated once
+        per version of its definition and lock file, and reused by every
+        attempt afterwards.
+      </p>
+      <div className="flex items-center space-x-2">
+        <Checkbox
+          id="nix-env-enabled"
+          checked={enabled}
+          onCheckedChange={(checked: boolean) => setEnabled(checked)}
+        />
+        <Label htmlFor="nix-env-enabled" className="cursor-pointer">
+          Run attempts in the development shell
+        </Label>
+      </div>
+      <div className="space-y-2">
+        <Label htmlFor="nix-env-tool">Shell</Label>
+        <Select value={tool} onValueChange={(v) => setTool(v as NixTool)}>
+          <SelectTrigger id="nix-env-tool">
+            <SelectValue />
+          </SelectTrigger>
+          <SelectContent>
+            {(Object.keys(toolLabels) as NixTool[]).map((t) => (
+              <SelectItem key={t} value={t}>
+                {toolLabels[t]}
+              </SelectItem>
+            ))}
+          </SelectContent>
+        </Select>
+        <p className="text-sm text-muted-foreground">
+          Requires <code>nix</code> or <code>devenv</code> on the host. The
+          shell hook isn't run. Processes in a devcontainer don't use the
+          shell.
+        </p>
+      </div>
+      {error && <p className="text-sm text-destructive">{error}</p>}
+      <div className="flex items-center justify-end gap-2">
+        {saved && <span className="text-sm text-green-600">Saved</span>}
+        <Button onClick={handleSave} disabled={saving}>
+          {saving ? 'Saving...' : 'Save'}
+        </Button>
+      </div>
+    </div>
+  );
+}
@@ -0,0 +1,117 @@
+import { useEffect, useState } from 'react';
+import { Button } from '@/components/ui/button';
+import { Checkbox } from '@/components/ui/checkbox';
+import { Label } from '@/components/ui/label';
+import { Textarea } from '@/components/ui/textarea';
+import { Loader2 } from 'lucide-react';
+import { projectsApi } from '@/lib/api';
+
+interface PathRulesManagerProps {
+  projectId: string;
+}
+
+const toLines = (globs: string[]) => globs.join('\n');
+const fromLines = (text: string) =>
+  text
+    .split('\n')
+    .map((line) => line.trim())
+    .filter(Boolean);
+
+// Globs restricting which paths the project's attempts may change
+export function PathRulesManager({ projectId }: PathRulesManagerProps) {
+  const [allowed, setAllowed] = useState('');
+  const [forbidden, setForbidden] = useState('');
+  const [revert, setRevert] = useState(false);
+  const [loading, setLoading] = useState(true);
+  const [saving, setSaving] = useState(false);
+  const [saved, setSaved] = useState(false);
+  const [error, setError] = useState<string | null>(null);
+
+  useEffect(() => {
+    setLoading(true);
+    projectsApi
+      .getPathRules(projectId)
+      .then((rules) => {
+        setAllowed(toLines(rules?.allowed_paths ?? []));
+        setForbidden(toLines(rules?.forbidden_paths ?? []));
+        setRevert(rules?.revert_violations ?? false);
+      })
+      .catch((err) =>
+        setError(err instanceof Error ? err.message : 'Failed to load rules')
+      )
+      .finally(() => setLoading(false));
+  }, [projectId]);
+
+  const handleSave = async () => {
+    setSaving(true);
+    setSaved(false);
+    setError(null);
+    try {
+      await projectsApi.updatePathRules(projectId, {
+        allowed_paths: fromLines(allowed),
+        forbidden_paths: fromLines(forbidden),
+        revert_violations: revert,
+      });
+      setSaved(true);
+    } catch (err) {
+      setError(err instanceof Error ? err.message : 'Failed to save rules');
+    } finally {
+      setSaving(false);
+    }
+  };
+
+  if (loading) {
+    return <Loader2 className="h-4 w-4 animate-spin" />;
+  }
+
+  return (
+    <div className="space-y-4">
+      <div className="space-y-2">
+        <Label htmlFor="allowed-paths">Allowed paths</Label>
+        <Textarea
+          id="allowed-paths"
+          value={allowed}
+          onChange={(e) => setAllowed(e.target.value)}
+          placeholder={'src/**\ntests/**'}
+          rows={4}
+          className="font-mono"
+        />
+        <p className="text-sm text-muted-foreground">
+          One glob per line. Attempts may only change matching paths, any path
+          when empty.
+        </p>
+      </div>
+      <div className="space-y-2">
+        <Label htmlFor="forbidden-paths">Forbidden paths</Label>
+        <Textarea
+          id="forbidden-paths"
+          value={forbidden}
+          onChange={(e) => setForbidden(e.target.value)}
+          placeholder={'.github/**\n*.lock'}
+          rows={4}
+          className="font-mono"
+        />
+        <p className="text-sm text-muted-foreground">
+          Never to be changed, even when allowed.
+        </p>
+      </div>
+      <div className="flex items-center space-x-2">
+        <Checkbox
+          id="revert-violations"
+          checked={revert}
+          onCheckedChange={(checked: boolean) => setRevert(checked)}
+        />
+        <Label htmlFor="revert-violations" className="cursor-pointer">
+          Ask the agent to revert out-of-scope changes when it finishes
+        </Label>
+      </div>
+      {error && <p className="text-sm text-destructive">{error}</p>}
+      <div className="flex items-center justify-end gap-2">
+        {saved && <span className="text-sm text-green-600">Saved</span>}
+        <Button onClick={handleSave} disabled={saving}>
+          {saving ? 'Saving...' : 'Save Rules'}
+        </Button>
+      </div>
+    </div>
+  );
+}
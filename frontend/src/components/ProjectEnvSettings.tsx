@@ -0,0 +1,159 @@
+import { useEffect, useState } from 'react';
+import { Button } from '@/components/ui/button';
+import { Checkbox } from '@/components/ui/checkbox';
+import { Input } from '@/components/ui/input';
+import { Loader2, Plus, Trash2 } from 'lucide-react';
+import { projectsApi } from '@/lib/api';
+import type { ProjectEnvVar } from 'shared/types';
+
+interface ProjectEnvSettingsProps {
+  projectId: string;
+}
+
+type EnvRow = {
+  key: string;
+  value: string;
+  secret: boolean;
+  // The secret's value is already stored, an empty value keeps it
+  stored: boolean;
+};
+
+const toRow = (envVar: ProjectEnvVar): EnvRow => ({
+  key: envVar.key,
+  value: envVar.value ?? '',
+  secret: envVar.secret,
+  stored: envVar.secret,
+});
+
+// Variables set for the setup scripts, dev servers and agents of a project
+export function ProjectEnvSettings({ projectId }: ProjectEnvSettingsProps) {
+  const [rows, setRows] = useState<EnvRow[]>([]);
+  const [loading, setLoading] = useState(true);
+  const [saving, setSaving] = useState(false);
+  const [saved, setSaved] = useState(false);
+  const [error, setError] = useState<string | null>(null);
+
+  useEffect(() => {
+    setLoading(true);
+    projectsApi
+      .getEnv(projectId)
+      .then((vars) => setRows(vars.map(toRow)))
+      .catch((err) =>
+        setError(
+          err instanceof Error ? err.message : 'Failed to load environment'
+        )
+      )
+      .finally(() => setLoading(false));
+  }, [projectId]);
+
+  const updateRow = (index: number, change: Partial<EnvRow>) => {
+    setSaved(false);
+    setRows((current) =>
+      current.map((row, i) => (i === index ? { ...row, ...change } : row))
+    );
+  };
+
+  const handleSave = async () => {
+    setSaving(true);
+    setSaved(false);
+    setError(null);
+    try {
+      const vars = await projectsApi.updateEnv(projectId, {
+        vars: rows
+          .filter((row) => row.key.trim())
+          .map((row) => ({
+            key: row.key.trim(),
+            value: row.secret && row.stored && !row.value ? null : row.value,
+            secret: row.secret,
+          })),
+      });
+      setRows(vars.map(toRow));
+      setSaved(true);
+    } catch (err) {
+      setError(
+        err instanceof Error ? err.message : 'Failed to save environment'
+      );
+    } finally {
+      setSaving(false);
+    }
+  };
+
+  if (loading) {
+    return <Loader2 className="h-4 w-4 animate-spin" />;
+  }
+
+  return (
+    <div className="space-y-4">
+      <p className="text-sm text-muted-foreground">
+        Set for the setup script, dev server and coding agents of every
+        attempt, e.g. <code>DATABASE_URL</code> or test API keys. Secret values
+        are kept in the secrets store and never shown again. Changes apply to
+        the next process of each attempt.
+      </p>
+      <div className="space-y-2">
+        {rows.map((row, index) => (
+          <div key={index} className="flex items-center gap-2">
+            <Input
+              className="w-1/3 font-mono"
+              placeholder="NAME"
+              value={row.key}
+              onChange={(e) => updateRow(index, { key: e.target.value })}
+            />
+            <Input
+              className="flex-1 font-mono"
+              type={row.secret ? 'password' : 'text'}
+              placeholder={row.stored ? 'Unchanged' : 'value'}
+              value={row.value}
+              onChange={(e) => updateRow(index, { value: e.target.value })}
+            />
+            <div className="flex items-center space-x-1">
+              <Checkbox
+                id={`env-secret-${index}`}
+                checked={row.secret}
+                onCheckedChange={(checked: boolean) =>
+                  updateRow(index, { secret: checked })
+                }
+              />
+              <label
+                htmlFor={`env-secret-${index}`}
+                className="cursor-pointer text-sm"
+              >
+                Secret
+              </label>
+            </div>
+            <Button
+              variant="ghost"
+              size="sm"
+              onClick={() => {
+                setSaved(false);
+                setRows((current) => current.filter((_, i) => i !== index));
+              }}
+            >
+              <Trash2 className="h-3 w-3" />
+            </Button>
+          </div>
+        ))}
+        <Button
+          variant="outline"
+          size="sm"
+          onClick={() =>
+            setRows((current) => [
+              ...current,
+              { key: '', value: '', secret: false, stored: false },
+            ])
+          }
+        >
+          <Plus className="mr-1 h-3 w-3" />
+          Add variable
+        </Button>
+      </div>
+      {error && <p className="text-sm text-destructive">{error}</p>}
+      <div className="flex items-center justify-end gap-2">
+        {saved && <span className="text-sm text-green-600">Saved</span>}
+        <Button onClick={handleSave} disabled={saving}>
+          {saving ? 'Saving...' : 'Save'}
+        </Button>
+      </div>
+    </div>
+  );
+}
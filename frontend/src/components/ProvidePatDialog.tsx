@@ -56,10 +56,12 @@ export function ProvidePatDialog({
               'Your GitHub OAuth token does not have sufficient permissions to open a PR in this repository.'}
             <br />
             <br />
-            Please provide a Personal Access Token with <b>repo</b> permissions.
+            Please provide a classic Personal Access Token with <b>repo</b>
+            permissions, or a fine-grained token with read and write access to
+            the repository&apos;s <b>contents</b> and <b>pull requests</b>.
           </p>
           <Input
-            placeholder="ghp_xxxxxxxxxxxxxxxxxxxx"
+            placeholder="ghp_... or github_pat_..."
             value={pat}
             onChange={(e) => setPat(e.target.value)}
             autoFocus
@@ -0,0 +1,154 @@
+import { useEffect, useState } from 'react';
+import { Button } from '@/components/ui/button';
+import { Input } from '@/components/ui/input';
+import { Loader2 } from 'lucide-react';
+import { providerKeysApi } from '@/lib/api';
+import type { KeyProvider, ProviderKey } from 'shared/types';
+
+const providers: { provider: KeyProvider; label: string; agents: string }[] = [
+  {
+    provider: 'anthropic',
+    label: 'Anthropic',
+    agents: 'Claude Code, opencode',
+  },
+  { provider: 'openai', label: 'OpenAI', agents: 'Codex, opencode' },
+  { provider: 'google', label: 'Google Gemini', agents: 'Gemini, opencode' },
+  { provider: 'sourcegraph', label: 'Sourcegraph', agents: 'Amp' },
+];
+
+const status = (key: ProviderKey) => {
+  if (key.valid === true) {
+    return <span className="text-green-600">Valid</span>;
+  }
+  if (key.valid === false) {
+    return <span className="text-destructive">{key.validation_error}</span>;
+  }
+  return (
+    <span className="text-muted-foreground">
+      {key.validation_error ?? 'Not checked'}
+    </span>
+  );
+};
+
+// API keys of the model providers, passed to the agents that use them
+export function ProviderKeyManager() {
+  const [keys, setKeys] = useState<Partial<Record<KeyProvider, ProviderKey>>>(
+    {}
+  );
+  const [drafts, setDrafts] = useState<Partial<Record<KeyProvider, string>>>(
+    {}
+  );
+  const [loading, setLoading] = useState(true);
+  const [busy, setBusy] = useState<KeyProvider | null>(null);
+  const [error, setError] = useState<string | null>(null);
+
+  const showKey = (key: ProviderKey) =>
+    setKeys((prev) => ({ ...prev, [key.provider]: key }));
+
+  useEffect(() => {
+    providerKeysApi
+      .list()
+      .then((all) => all.forEach(showKey))
+      .catch((err) =>
+        setError(err instanceof Error ? err.message : 'Failed to load keys')
+      )
+      .finally(() => setLoading(false));
+  }, []);
+
+  const run = async (provider: KeyProvider, action: () => Promise<void>) => {
+    setBusy(provider);
+    setError(null);
+    try {
+      await action();
+    } catch (err) {
+      setError(err instanceof Error ? err.message : 'Failed to update key');
+    } finally {
+      setBusy(null);
+    }
+  };
+
+  const save = (provider: KeyProvider) =>
+    run(provider, async () => {
+      showKey(await providerKeysApi.set(provider, drafts[provider] ?? ''));
+      setDrafts((prev) => ({ ...prev, [provider]: '' }));
+    });
+
+  const validate = (provider: KeyProvider) =>
+    run(provider, async () => {
+      showKey(await providerKeysApi.validate(provider));
+    });
+
+  const remove = (provider: KeyProvider) =>
+    run(provider, async () => {
+      await providerKeysApi.delete(provider);
+      setKeys((prev) => {
+        const next = { ...prev };
+        delete next[provider];
+        return next;
+      });
+    });
+
+  if (loading) {
+    return <Loader2 className="h-4 w-4 animate-spin" />;
+  }
+
+  return (
+    <div className="space-y-4">
+      {providers.map(({ provider, label, agents }) => {
+        const key = keys[provider];
+        const draft = drafts[provider] ?? '';
+        return (
+          <div key={provider} className="space-y-2">
+            <div className="flex items-baseline justify-between text-sm">
+              <span className="font-medium">{label}</span>
+              <span className="text-muted-foreground">Used by {agents}</span>
+            </div>
+            <div className="flex items-center gap-2">
+              <Input
+                type="password"
+                className="h-8"
+                placeholder={key ? `Stored key ${key.key_hint}` : 'API key'}
+                value={draft}
+                onChange={(e) =>
+                  setDrafts((prev) => ({ ...prev, [provider]: e.target.value }))
+                }
+              />
+              <Button
+                size="sm"
+                disabled={!draft.trim() || busy === provider}
+                onClick={() => save(provider)}
+              >
+                {busy === provider && (
+                  <Loader2 className="mr-2 h-4 w-4 animate-spin" />
+                )}
+                Save
+              </Button>
+              {key && (
+                <>
+                  <Button
+                    size="sm"
+                    variant="outline"
+                    disabled={busy === provider}
+                    onClick={() => validate(provider)}
+                  >
+                    Check
+                  </Button>
+                  <Button
+                    size="sm"
+                    variant="outline"
+                    disabled={busy === provider}
+                    onClick={() => remove(provider)}
+                  >
+                    Remove
+                  </Button>
+                </>
+              )}
+            </div>
+            {key && <p className="text-sm">{status(key)}</p>}
+          </div>
+        );
+      })}
+      {error && <p className="text-sm text-destructive">{error}</p>}
+    </div>
+  );
+}
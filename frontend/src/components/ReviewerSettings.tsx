@@ -0,0 +1,160 @@
+import { useEffect, useState } from 'react';
+import { Button } from '@/components/ui/button';
+import { Checkbox } from '@/components/ui/checkbox';
+import { Label } from '@/components/ui/label';
+import {
+  Select,
+  SelectContent,
+  SelectItem,
+  SelectTrigger,
+  SelectValue,
+} from '@/components/ui/select';
+import { Loader2 } from 'lucide-react';
+import { useUserSystem } from '@/components/config-provider';
+import { projectsApi } from '@/lib/api';
+
+interface ReviewerSettingsProps {
+  projectId: string;
+}
+
+// Select items can't hold an empty value, this one stands for no variant
+const DEFAULT_VARIANT = '__default__';
+
+// Second agent profile reviewing each attempt once its coding agent finishes
+export function ReviewerSettings({ projectId }: ReviewerSettingsProps) {
+  const { profiles, featureFlags } = useUserSystem();
+  const [enabled, setEnabled] = useState(false);
+  const [profile, setProfile] = useState('');
+  const [variant, setVariant] = useState<string | null>(null);
+  const [gatePullRequests, setGatePullRequests] = useState(false);
+  const [loading, setLoading] = useState(true);
+  const [saving, setSaving] = useState(false);
+  const [saved, setSaved] = useState(false);
+  const [error, setError] = useState<string | null>(null);
+
+  useEffect(() => {
+    setLoading(true);
+    projectsApi
+      .getReviewer(projectId)
+      .then((reviewer) => {
+        setEnabled(reviewer?.enabled ?? false);
+        setProfile(reviewer?.profile ?? '');
+        setVariant(reviewer?.variant ?? null);
+        setGatePullRequests(reviewer?.gate_pull_requests ?? false);
+      })
+      .catch((err) =>
+        setError(err instanceof Error ? err.message : 'Failed to load reviewer')
+      )
+      .finally(() => setLoading(false));
+  }, [projectId]);
+
+  const selectedProfile = profiles?.find((p) => p.label === profile);
+
+  const handleSave = async () => {
+    setSaving(true);
+    setSaved(false);
+    setError(null);
+    try {
+      await projectsApi.updateReviewer(projectId, {
+        enabled,
+        profile,
+        variant,
+        gate_pull_requests: gatePullRequests,
+      });
+      setSaved(true);
+    } catch (err) {
+      setError(err instanceof Error ? err.message : 'Failed to save reviewer');
+    } finally {
+      setSaving(false);
+    }
+  };
+
+  if (loading) {
+    return <Loader2 className="h-4 w-4 animate-spin" />;
+  }
+
+  return (
+    <div className="space-y-4">
+      {featureFlags?.multi_agent_pipelines === false && (
+        <p className="text-sm text-amber-600">
+          Multi-agent pipelines are turned off under Experimental Features, no
+          reviews run until they are turned back on.
+        </p>
+      )}
+      <div className="flex items-center space-x-2">
+        <Checkbox
+          id="reviewer-enabled"
+          checked={enabled}
+          onCheckedChange={(checked: boolean) => setEnabled(checked)}
+        />
+        <Label htmlFor="reviewer-enabled" className="cursor-pointer">
+          Review attempts with a second agent when the coding agent finishes
+        </Label>
+      </div>
+      <div className="space-y-2">
+        <Label htmlFor="reviewer-profile">Reviewer profile</Label>
+        <div className="flex gap-2">
+          <Select
+            value={profile}
+            onValueChange={(value) => {
+              setProfile(value);
+              setVariant(null);
+            }}
+          >
+            <SelectTrigger id="reviewer-profile" className="flex-1">
+              <SelectValue placeholder="Select a profile" />
+            </SelectTrigger>
+            <SelectContent>
+              {profiles?.map((p) => (
+                <SelectItem key={p.label} value={p.label}>
+                  {p.label}
+                </SelectItem>
+              ))}
+            </SelectContent>
+          </Select>
+          {selectedProfile && selectedProfile.variants.length > 0 && (
+            <Select
+              value={variant ?? DEFAULT_VARIANT}
+              onValueChange={(value) =>
+                setVariant(value === DEFAULT_VARIANT ? null : value)
+              }
+            >
+              <SelectTrigger className="w-40">
+                <SelectValue />
+              </SelectTrigger>
+              <SelectContent>
+                <SelectItem value={DEFAULT_VARIANT}>Default</SelectItem>
+                {selectedProfile.variants.map((v) => (
+                  <SelectItem key={v.label} value={v.label}>
+                    {v.label}
+                  </SelectItem>
+                ))}
+              </SelectContent>
+            </Select>
+          )}
+        </div>
+        <p className="text-sm text-muted-foreground">
+          The reviewer reads the task and the attempt's diff and approves it or
+          requests changes. Its review is attached to the attempt.
+        </p>
+      </div>
+      <div className="flex items-center space-x-2">
+        <Checkbox
+          id="reviewer-gate-prs"
+          checked={gatePullRequests}
+          onCheckedChange={(checked: boolean) => setGatePullRequests(checked)}
+        />
+        <Label htmlFor="reviewer-gate-prs" className="cursor-pointer">
+          Only open pull requests for approved attempts
+        </Label>
+      </div>
+      {error && <p className="text-sm text-destructive">{error}</p>}
+      <div className="flex items-center justify-end gap-2">
+        {saved && <span className="text-sm text-green-600">Saved</span>}
+        <Button onClick={handleSave} disabled={saving || !profile}>
+          {saving ? 'Saving...' : 'Save'}
+        </Button>
+      </div>
+    </div>
+  );
+}
@@ -0,0 +1,143 @@
+import { useEffect, useState } from 'react';
+import { Button } from '@/components/ui/button';
+import { Checkbox } from '@/components/ui/checkbox';
+import { Input } from '@/components/ui/input';
+import { Label } from '@/components/ui/label';
+import { Loader2 } from 'lucide-react';
+import { sentryApi } from '@/lib/api';
+import type {
+  InboundTaskResponse,
+  SentryIntegrationStatus,
+} from 'shared/types';
+
+const errorMessage = (err: unknown, fallback: string) =>
+  err instanceof Error ? err.message : fallback;
+
+// Credentials for the Sentry API, and a form turning an issue into a task
+export function SentryIntegration() {
+  const [status, setStatus] = useState<SentryIntegrationStatus | null>(null);
+  const [authToken, setAuthToken] = useState('');
+  const [clientSecret, setClientSecret] = useState('');
+  const [link, setLink] = useState('');
+  const [startAgent, setStartAgent] = useState(true);
+  const [result, setResult] = useState<InboundTaskResponse | null>(null);
+  const [busy, setBusy] = useState(false);
+  const [error, setError] = useState<string | null>(null);
+
+  useEffect(() => {
+    sentryApi
+      .getStatus()
+      .then(setStatus)
+      .catch((err) => setError(errorMessage(err, 'Failed to load Sentry')));
+  }, []);
+
+  const run = async (action: () => Promise<void>, fallback: string) => {
+    setBusy(true);
+    setError(null);
+    try {
+      await action();
+    } catch (err) {
+      setError(errorMessage(err, fallback));
+    } finally {
+      setBusy(false);
+    }
+  };
+
+  const save = () =>
+    run(async () => {
+      setStatus(
+        await sentryApi.setCredentials({
+          auth_token: authToken || null,
+          client_secret: clientSecret || null,
+        })
+      );
+      setAuthToken('');
+      setClientSecret('');
+    }, 'Failed to save the credentials');
+
+  const importIssue = () =>
+    run(async () => {
+      setResult(
+        await sentryApi.importIssue({
+          link,
+          project: null,
+          start_agent: startAgent,
+        })
+      );
+      setLink('');
+    }, 'Failed to import the issue');
+
+  if (!status) {
+    return error ? (
+      <p className="text-sm text-destructive">{error}</p>
+    ) : (
+      <Loader2 className="h-4 w-4 animate-spin" />
+    );
+  }
+
+  return (
+    <div className="space-y-4">
+      <div className="space-y-2">
+        <Label htmlFor="sentry-token">Auth token</Label>
+        <Input
+          id="sentry-token"
+          type="password"
+          className="h-8"
+          placeholder={status.auth_token_set ? 'Set' : 'sntrys_…'}
+          value={authToken}
+          onChange={(e) => setAuthToken(e.target.value)}
+        />
+        <Label htmlFor="sentry-secret">Webhook client secret</Label>
+        <Input
+          id="sentry-secret"
+          type="password"
+          className="h-8"
+          placeholder={status.client_secret_set ? 'Set' : 'Optional'}
+          value={clientSecret}
+          onChange={(e) => setClientSecret(e.target.value)}
+        />
+        <Button
+          size="sm"
+          variant="outline"
+          disabled={busy || (!authToken && !clientSecret)}
+          onClick={save}
+        >
+          Save credentials
+        </Button>
+      </div>
+
+      <div className="space-y-2">
+        <Input
+          className="h-8"
+          placeholder="https://acme.sentry.io/issues/1234/"
+          value={link}
+          onChange={(e) => setLink(e.target.value)}
+        />
+        <div className="flex items-center gap-2">
+          <Checkbox
+            id="sentry-start-agent"
+            checked={startAgent}
+            onCheckedChange={(checked) => setStartAgent(checked === true)}
+          />
+          <Label htmlFor="sentry-start-agent">Start an agent right away</Label>
+        </div>
+        <Button
+          size="sm"
+          disabled={busy || !status.auth_token_set || !link.trim()}
+          onClick={importIssue}
+        >
+          {busy && <Loader2 className="mr-2 h-4 w-4 animate-spin" />}
+          Fix this error
+        </Button>
+        {result && (
+          <p className="text-sm text-muted-foreground">
+            {result.action === 'created' ? 'Created' : 'Updated'} &quot;
+            {result.task.title}&quot;
+            {result.agent_started && ', an agent is working on it'}
+          </p>
+        )}
+      </div>
+      {error && <p className="text-sm text-destructive">{error}</p>}
+    </div>
+  );
+}
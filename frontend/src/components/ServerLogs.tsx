@@ -0,0 +1,112 @@
+import { useState } from 'react';
+import { Button } from '@/components/ui/button';
+import { Input } from '@/components/ui/input';
+import { Label } from '@/components/ui/label';
+import { Copy, Download, LifeBuoy, Loader2 } from 'lucide-react';
+import { debugApi } from '@/lib/api';
+import { downloadBlob } from '@/lib/utils';
+
+const DEFAULT_TAIL = 500;
+
+// The end of the server's log file as JSON lines, and support bundles wrapping
+// it with the config and environment, to attach to bug reports
+export function ServerLogs() {
+  const [tail, setTail] = useState(DEFAULT_TAIL);
+  const [path, setPath] = useState<string | null>(null);
+  const [busy, setBusy] = useState(false);
+  const [message, setMessage] = useState<string | null>(null);
+
+  const fetchLines = async () => {
+    const logs = await debugApi.getServerLogs(tail);
+    setPath(logs.path);
+    return logs.entries.map((entry) => JSON.stringify(entry)).join('\n');
+  };
+
+  const run = async (action: (lines: string) => Promise<void> | void) => {
+    setBusy(true);
+    setMessage(null);
+    try {
+      await action(await fetchLines());
+    } catch (err) {
+      setMessage(err instanceof Error ? err.message : 'Failed to load logs');
+    } finally {
+      setBusy(false);
+    }
+  };
+
+  const download = () =>
+    run((lines) => {
+      const blob = new Blob([lines + '\n'], { type: 'application/x-ndjson' });
+      downloadBlob(blob, 'vibe-kanban-server.jsonl');
+    });
+
+  const copy = () =>
+    run(async (lines) => {
+      await navigator.clipboard.writeText(lines);
+      setMessage('Copied to the clipboard');
+    });
+
+  const supportBundle = async () => {
+    setBusy(true);
+    setMessage(null);
+    try {
+      const bundle = await debugApi.createSupportBundle({
+        attempt_id: null,
+        server_log_tail: tail,
+      });
+      downloadBlob(bundle, 'vibe-kanban-support.zip');
+    } catch (err) {
+      setMessage(
+        err instanceof Error ? err.message : 'Failed to create the bundle'
+      );
+    } finally {
+      setBusy(false);
+    }
+  };
+
+  return (
+    <div className="space-y-4">
+      <div className="flex items-end gap-2">
+        <div className="space-y-2">
+          <Label htmlFor="server-log-tail">Entries</Label>
+          <Input
+            id="server-log-tail"
+            type="number"
+            min={1}
+            className="w-32"
+            value={tail}
+            onChange={(e) => setTail(Math.max(1, Number(e.target.value)))}
+          />
+        </div>
+        <Button variant="outline" onClick={download} disabled={busy}>
+          {busy ? (
+            <Loader2 className="mr-2 h-4 w-4 animate-spin" />
+          ) : (
+            <Download className="mr-2 h-4 w-4" />
+          )}
+          Download
+        </Button>
+        <Button variant="outline" onClick={copy} disabled={busy}>
+          <Copy className="mr-2 h-4 w-4" />
+          Copy
+        </Button>
+        <Button
+          variant="outline"
+          onClick={supportBundle}
+          disabled={busy}
+          title="Config, profiles, environment and these entries as a zip, secrets redacted"
+        >
+          <LifeBuoy className="mr-2 h-4 w-4" />
+          Support Bundle
+        </Button>
+      </div>
+      {message && <p className="text-sm text-muted-foreground">{message}</p>}
+      {path && (
+        <p className="text-sm text-muted-foreground">
+          <span className="font-medium">Log file:</span>{' '}
+          <span className="font-mono text-xs">{path}</span>
+        </p>
+      )}
+    </div>
+  );
+}
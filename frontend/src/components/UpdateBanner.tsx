@@ -0,0 +1,126 @@
+import { useState } from 'react';
+import { ArrowUpCircle, ExternalLink, Loader2, X } from 'lucide-react';
+import { Button } from '@/components/ui/button';
+import MarkdownRenderer from '@/components/ui/markdown-renderer';
+import { useUserSystem } from '@/components/config-provider';
+import { maintenanceApi } from '@/lib/api';
+
+const DISMISSED_KEY = 'vk-update-dismissed';
+const HEALTH_POLL_MS = 1000;
+
+// Waits for the server started by the npx wrapper to answer again
+async function waitForRestart() {
+  for (;;) {
+    await new Promise((resolve) => setTimeout(resolve, HEALTH_POLL_MS));
+    try {
+      const response = await fetch('/api/health');
+      if (response.ok) return;
+    } catch {
+      // Still restarting
+    }
+  }
+}
+
+// Shown under the navbar while a newer vibe-kanban is published, with its
+// release notes and a button handing the update to the npx wrapper
+export function UpdateBanner() {
+  const { update } = useUserSystem();
+  const [dismissed, setDismissed] = useState(() =>
+    sessionStorage.getItem(DISMISSED_KEY)
+  );
+  const [showNotes, setShowNotes] = useState(false);
+  const [updating, setUpdating] = useState(false);
+  const [message, setMessage] = useState<string | null>(null);
+
+  if (!update?.update_available || !update.latest_version) return null;
+  if (dismissed === update.latest_version && !updating) return null;
+
+  const dismiss = () => {
+    sessionStorage.setItem(DISMISSED_KEY, update.latest_version!);
+    setDismissed(update.latest_version);
+  };
+
+  const startUpdate = async () => {
+    setUpdating(true);
+    setMessage(null);
+    try {
+      const outcome = await maintenanceApi.selfUpdate();
+      switch (outcome.type) {
+        case 'restarting':
+          setMessage(`Installing ${outcome.version} and restarting…`);
+          await waitForRestart();
+          window.location.reload();
+          return;
+        case 'manual':
+          setMessage(outcome.instructions);
+          break;
+        case 'up_to_date':
+          setMessage('Already up to date');
+          break;
+      }
+    } catch (err) {
+      setMessage(err instanceof Error ? err.message : 'Failed to update');
+    }
+    setUpdating(false);
+  };
+
+  return (
+    <div className="border-b bg-muted/50 px-4 py-2 text-sm">
+      <div className="flex items-center gap-3">
+        <ArrowUpCircle className="h-4 w-4 shrink-0 text-primary" />
+        <span className="flex-1">
+          vibe-kanban {update.latest_version} is available (you have{' '}
+          {update.current_version})
+        </span>
+        {update.release_notes && (
+          <Button
+            variant="ghost"
+            size="sm"
+            onClick={() => setShowNotes((show) => !show)}
+          >
+            {showNotes ? 'Hide notes' : 'Release notes'}
+          </Button>
+        )}
+        {update.release_url && (
+          <a
+            href={update.release_url}
+            target="_blank"
+            rel="noopener noreferrer"
+            className="inline-flex items-center gap-1 text-muted-foreground hover:text-foreground"
+          >
+            GitHub
+            <ExternalLink className="h-3 w-3" />
+          </a>
+        )}
+        <Button
+          size="sm"
+          onClick={startUpdate}
+          disabled={updating}
+          title={
+            update.can_self_update
+              ? undefined
+              : 'Started outside npx, shows how to update instead'
+          }
+        >
+          {updating && <Loader2 className="mr-2 h-4 w-4 animate-spin" />}
+          Update & restart
+        </Button>
+        <Button
+          variant="ghost"
+          size="icon"
+          className="h-7 w-7"
+          onClick={dismiss}
+          disabled={updating}
+        >
+          <X className="h-4 w-4" />
+        </Button>
+      </div>
+      {message && <p className="mt-2 text-muted-foreground">{message}</p>}
+      {showNotes && update.release_notes && (
+        <div className="mt-2 max-h-64 overflow-y-auto rounded border bg-background p-3">
+          <MarkdownRenderer content={update.release_notes} />
+        </div>
+      )}
+    </div>
+  );
+}
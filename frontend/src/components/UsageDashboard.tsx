@@ -0,0 +1,122 @@
+import { useEffect, useState } from 'react';
+import { Loader2 } from 'lucide-react';
+import {
+  Select,
+  SelectContent,
+  SelectItem,
+  SelectTrigger,
+  SelectValue,
+} from '@/components/ui/select';
+import { analyticsApi } from '@/lib/api';
+import type { UsageBucket, UsageGroupBy, UsageReport } from 'shared/types';
+
+const groupLabels: Record<UsageGroupBy, string> = {
+  day: 'Day',
+  profile: 'Profile',
+  project: 'Project',
+};
+
+const periods = [7, 30, 90];
+
+const formatTokens = (tokens: number) =>
+  tokens >= 1_000_000
+    ? `${(tokens / 1_000_000).toFixed(1)}M`
+    : `${(tokens / 1_000).toFixed(0)}k`;
+
+function UsageRow({ bucket, bold }: { bucket: UsageBucket; bold?: boolean }) {
+  return (
+    <tr className={bold ? 'font-medium' : undefined}>
+      <td className="py-1 pr-2">{bucket.label}</td>
+      <td className="py-1 pr-2 text-right">{bucket.attempts}</td>
+      <td className="py-1 pr-2 text-right">
+        {Math.round(bucket.success_rate * 100)}%
+      </td>
+      <td className="py-1 pr-2 text-right">{bucket.merged}</td>
+      <td className="py-1 pr-2 text-right">
+        {formatTokens(bucket.input_tokens)} /{' '}
+        {formatTokens(bucket.output_tokens)}
+      </td>
+      <td className="py-1 text-right">${bucket.cost_usd.toFixed(2)}</td>
+    </tr>
+  );
+}
+
+// Attempts, success rates, tokens and cost of the recent attempts
+export function UsageDashboard() {
+  const [groupBy, setGroupBy] = useState<UsageGroupBy>('day');
+  const [days, setDays] = useState(30);
+  const [report, setReport] = useState<UsageReport | null>(null);
+  const [error, setError] = useState<string | null>(null);
+
+  useEffect(() => {
+    setReport(null);
+    setError(null);
+    analyticsApi
+      .getUsage(groupBy, days)
+      .then(setReport)
+      .catch((err) =>
+        setError(err instanceof Error ? err.message : 'Failed to load usage')
+      );
+  }, [groupBy, days]);
+
+  return (
+    <div className="space-y-3">
+      <div className="flex gap-2">
+        <Select
+          value={groupBy}
+          onValueChange={(v) => setGroupBy(v as UsageGroupBy)}
+        >
+          <SelectTrigger className="h-8 w-36">
+            <SelectValue />
+          </SelectTrigger>
+          <SelectContent>
+            {(Object.keys(groupLabels) as UsageGroupBy[]).map((g) => (
+              <SelectItem key={g} value={g}>
+                By {groupLabels[g].toLowerCase()}
+              </SelectItem>
+            ))}
+          </SelectContent>
+        </Select>
+        <Select value={String(days)} onValueChange={(v) => setDays(Number(v))}>
+          <SelectTrigger className="h-8 w-36">
+            <SelectValue />
+          </SelectTrigger>
+          <SelectContent>
+            {periods.map((p) => (
+              <SelectItem key={p} value={String(p)}>
+                Last {p} days
+              </SelectItem>
+            ))}
+          </SelectContent>
+        </Select>
+      </div>
+      {error && <p className="text-sm text-destructive">{error}</p>}
+      {!report && !error && <Loader2 className="h-4 w-4 animate-spin" />}
+      {report && report.buckets.length === 0 && (
+        <p className="text-sm text-muted-foreground">
+          No attempts in this period.
+        </p>
+      )}
+      {report && report.buckets.length > 0 && (
+        <table className="w-full text-sm">
+          <thead>
+            <tr className="text-left text-muted-foreground">
+              <th className="font-normal">{groupLabels[groupBy]}</th>
+              <th className="font-normal text-right">Attempts</th>
+              <th className="font-normal text-right">Succeeded</th>
+              <th className="font-normal text-right">Merged</th>
+              <th className="font-normal text-right">Tokens in / out</th>
+              <th className="font-normal text-right">Cost</th>
+            </tr>
+          </thead>
+          <tbody>
+            {report.buckets.map((bucket) => (
+              <UsageRow key={bucket.key} bucket={bucket} />
+            ))}
+            <UsageRow bucket={report.total} bold />
+          </tbody>
+        </table>
+      )}
+    </div>
+  );
+}
@@ -0,0 +1,255 @@
+import { useEffect, useState } from 'react';
+import { Button } from '@/components/ui/button';
+import { Checkbox } from '@/components/ui/checkbox';
+import { Input } from '@/components/ui/input';
+import { Label } from '@/components/ui/label';
+import { Loader2 } from 'lucide-react';
+import { webhooksApi } from '@/lib/api';
+import type { Webhook, WebhookDelivery, WebhookEvent } from 'shared/types';
+
+const events: WebhookEvent[] = [
+  'task.created',
+  'task.updated',
+  'attempt.created',
+  'attempt.updated',
+  'execution.started',
+  'execution.completed',
+  'execution.failed',
+  'execution.killed',
+];
+
+const errorMessage = (err: unknown, fallback: string) =>
+  err instanceof Error ? err.message : fallback;
+
+function DeliveryLog({ deliveries }: { deliveries: WebhookDelivery[] }) {
+  if (deliveries.length === 0) {
+    return <p className="text-sm text-muted-foreground">No deliveries yet.</p>;
+  }
+  return (
+    <table className="w-full text-xs">
+      <thead>
+        <tr className="text-left text-muted-foreground">
+          <th className="font-normal">Event</th>
+          <th className="font-normal">Status</th>
+          <th className="font-normal text-right">Attempts</th>
+          <th className="font-normal">Response</th>
+          <th className="font-normal">Created</th>
+        </tr>
+      </thead>
+      <tbody>
+        {deliveries.map((delivery) => (
+          <tr key={delivery.id}>
+            <td className="py-1 pr-2">{delivery.event}</td>
+            <td
+              className={`py-1 pr-2 ${
+                delivery.status === 'failed' ? 'text-destructive' : ''
+              }`}
+            >
+              {delivery.status}
+            </td>
+            <td className="py-1 pr-2 text-right">{delivery.attempts}</td>
+            <td className="py-1 pr-2">
+              {delivery.last_error ?? delivery.response_status ?? '–'}
+            </td>
+            <td className="py-1">
+              {new Date(delivery.created_at).toLocaleString()}
+            </td>
+          </tr>
+        ))}
+      </tbody>
+    </table>
+  );
+}
+
+// Endpoints board and execution events are posted to, signed with a secret
+export function WebhookManager() {
+  const [webhooks, setWebhooks] = useState<Webhook[]>([]);
+  const [url, setUrl] = useState('');
+  const [picked, setPicked] = useState<WebhookEvent[]>([]);
+  const [secret, setSecret] = useState<{ url: string; secret: string } | null>(
+    null
+  );
+  const [deliveries, setDeliveries] = useState<{
+    id: string;
+    log: WebhookDelivery[];
+  } | null>(null);
+  const [loading, setLoading] = useState(true);
+  const [busy, setBusy] = useState(false);
+  const [error, setError] = useState<string | null>(null);
+
+  useEffect(() => {
+    webhooksApi
+      .list()
+      .then(setWebhooks)
+      .catch((err) => setError(errorMessage(err, 'Failed to load webhooks')))
+      .finally(() => setLoading(false));
+  }, []);
+
+  const run = async (action: () => Promise<void>, fallback: string) => {
+    setBusy(true);
+    setError(null);
+    try {
+      await action();
+    } catch (err) {
+      setError(errorMessage(err, fallback));
+    } finally {
+      setBusy(false);
+    }
+  };
+
+  const replace = (webhook: Webhook) =>
+    setWebhooks((prev) => prev.map((w) => (w.id === webhook.id ? webhook : w)));
+
+  const add = () =>
+    run(async () => {
+      const created = await webhooksApi.create({ url, events: picked });
+      setWebhooks((prev) => [...prev, created.webhook]);
+      setSecret({ url: created.webhook.url, secret: created.secret });
+      setUrl('');
+      setPicked([]);
+    }, 'Failed to add the webhook');
+
+  const toggleEnabled = (webhook: Webhook, enabled: boolean) =>
+    run(async () => {
+      replace(
+        await webhooksApi.update(webhook.id, {
+          url: webhook.url,
+          events: webhook.events,
+          enabled,
+        })
+      );
+    }, 'Failed to update the webhook');
+
+  const rotate = (webhook: Webhook) =>
+    run(async () => {
+      const rotated = await webhooksApi.rotateSecret(webhook.id);
+      setSecret({ url: webhook.url, secret: rotated });
+    }, 'Failed to rotate the secret');
+
+  const showDeliveries = (id: string) =>
+    run(async () => {
+      setDeliveries({ id, log: await webhooksApi.getDeliveries(id) });
+    }, 'Failed to load deliveries');
+
+  const ping = (id: string) =>
+    run(async () => {
+      await webhooksApi.ping(id);
+      setDeliveries({ id, log: await webhooksApi.getDeliveries(id) });
+    }, 'Failed to send a ping');
+
+  const remove = (id: string) =>
+    run(async () => {
+      await webhooksApi.delete(id);
+      setWebhooks((prev) => prev.filter((w) => w.id !== id));
+      setDeliveries((prev) => (prev?.id === id ? null : prev));
+    }, 'Failed to remove the webhook');
+
+  if (loading) {
+    return <Loader2 className="h-4 w-4 animate-spin" />;
+  }
+
+  return (
+    <div className="space-y-4">
+      {webhooks.map((webhook) => (
+        <div key={webhook.id} className="space-y-2">
+          <div className="flex items-center justify-between gap-2 text-sm">
+            <div className="flex min-w-0 items-center gap-2">
+              <Checkbox
+                checked={webhook.enabled}
+                disabled={busy}
+                onCheckedChange={(checked) =>
+                  toggleEnabled(webhook, checked === true)
+                }
+              />
+              <span className="truncate font-medium">{webhook.url}</span>
+              <span className="text-muted-foreground">
+                {webhook.events.length === 0
+                  ? 'All events'
+                  : webhook.events.join(', ')}
+              </span>
+            </div>
+            <div className="flex shrink-0 gap-2">
+              <Button
+                size="sm"
+                variant="outline"
+                disabled={busy}
+                onClick={() => ping(webhook.id)}
+              >
+                Ping
+              </Button>
+              <Button
+                size="sm"
+                variant="outline"
+                disabled={busy}
+                onClick={() => showDeliveries(webhook.id)}
+              >
+                Deliveries
+              </Button>
+              <Button
+                size="sm"
+                variant="outline"
+                disabled={busy}
+                onClick={() => rotate(webhook)}
+              >
+                New secret
+              </Button>
+              <Button
+                size="sm"
+                variant="outline"
+                disabled={busy}
+                onClick={() => remove(webhook.id)}
+              >
+                Remove
+              </Button>
+            </div>
+          </div>
+          {deliveries?.id === webhook.id && (
+            <DeliveryLog deliveries={deliveries.log} />
+          )}
+        </div>
+      ))}
+
+      {secret && (
+        <div className="rounded border p-2 text-sm">
+          <p>Signing secret for {secret.url}, it won&apos;t be shown again:</p>
+          <code className="break-all">{secret.secret}</code>
+        </div>
+      )}
+
+      <div className="space-y-2">
+        <Input
+          className="h-8"
+          placeholder="https://example.com/hooks/vibe-kanban"
+          value={url}
+          onChange={(e) => setUrl(e.target.value)}
+        />
+        <div className="grid grid-cols-2 gap-2">
+          {events.map((event) => (
+            <div key={event} className="flex items-center gap-2">
+              <Checkbox
+                id={`webhook-${event}`}
+                checked={picked.includes(event)}
+                onCheckedChange={(checked) =>
+                  setPicked((prev) =>
+                    checked === true
+                      ? [...prev, event]
+                      : prev.filter((e) => e !== event)
+                  )
+                }
+              />
+              <Label htmlFor={`webhook-${event}`}>{event}</Label>
+            </div>
+          ))}
+        </div>
+        <p className="text-sm text-muted-foreground">
+          Leave every event unchecked to receive all of them.
+        </p>
+        <Button size="sm" disabled={busy || !url.trim()} onClick={add}>
+          {busy && <Loader2 className="mr-2 h-4 w-4 animate-spin" />}
+          Add webhook
+        </Button>
+      </div>
+      {error && <p className="text-sm text-destructive">{error}</p>}
+    </div>
+  );
+}
@@ -0,0 +1,92 @@
+import { useEffect, useState } from 'react';
+import { Button } from '@/components/ui/button';
+import { Input } from '@/components/ui/input';
+import { Label } from '@/components/ui/label';
+import { Loader2 } from 'lucide-react';
+import { projectsApi } from '@/lib/api';
+import type { WorktreePoolStatus } from 'shared/types';
+
+const MAX_POOL_SIZE = 4;
+
+interface WorktreePoolSettingsProps {
+  projectId: string;
+}
+
+// Worktrees kept ready so new attempts skip the checkout and setup script
+export function WorktreePoolSettings({ projectId }: WorktreePoolSettingsProps) {
+  const [status, setStatus] = useState<WorktreePoolStatus | null>(null);
+  const [size, setSize] = useState('0');
+  const [saving, setSaving] = useState(false);
+  const [error, setError] = useState<string | null>(null);
+
+  useEffect(() => {
+    projectsApi
+      .getWorktreePool(projectId)
+      .then((pool) => {
+        setStatus(pool);
+        setSize(pool.size.toString());
+      })
+      .catch((err) =>
+        setError(
+          err instanceof Error ? err.message : 'Failed to load worktree pool'
+        )
+      );
+  }, [projectId]);
+
+  const handleSave = async () => {
+    const value = Number(size);
+    if (!Number.isInteger(value) || value < 0 || value > MAX_POOL_SIZE) {
+      setError(`Keep between 0 and ${MAX_POOL_SIZE} worktrees ready`);
+      return;
+    }
+    setSaving(true);
+    setError(null);
+    try {
+      setStatus(
+        await projectsApi.updateWorktreePool(projectId, { size: value })
+      );
+    } catch (err) {
+      setError(
+        err instanceof Error ? err.message : 'Failed to save worktree pool'
+      );
+    } finally {
+      setSaving(false);
+    }
+  };
+
+  if (!status && !error) {
+    return <Loader2 className="h-4 w-4 animate-spin" />;
+  }
+
+  return (
+    <div className="space-y-2">
+      <Label htmlFor="worktree-pool-size">Worktrees kept ready</Label>
+      <div className="flex items-center gap-2">
+        <Input
+          id="worktree-pool-size"
+          type="number"
+          min={0}
+          max={MAX_POOL_SIZE}
+          className="w-24"
+          value={size}
+          onChange={(e) => setSize(e.target.value)}
+        />
+        <Button variant="outline" onClick={handleSave} disabled={saving}>
+          {saving ? 'Saving...' : 'Save'}
+        </Button>
+        {status && status.size > 0 && (
+          <span className="text-sm text-muted-foreground">
+            {status.ready} of {status.size} ready
+            {status.filling && ', setting up more'}
+          </span>
+        )}
+      </div>
+      <p className="text-sm text-muted-foreground">
+        Checked out to the repository's current branch with the setup script
+        run, so attempts from that branch start right away. Kept for projects
+        used in the last week, and replaced when the branch moves.
+      </p>
+      {error && <p className="text-sm text-destructive">{error}</p>}
+    </div>
+  );
+}
@@ -0,0 +1,156 @@
+import { useCallback, useEffect, useRef, useState } from 'react';
+import { Button } from '@/components/ui/button';
+import { Checkbox } from '@/components/ui/checkbox';
+import { Input } from '@/components/ui/input';
+import { Label } from '@/components/ui/label';
+import { Loader2, Trash2, Upload } from 'lucide-react';
+import { projectsApi } from '@/lib/api';
+import type { WorktreeTemplateList } from 'shared/types';
+
+const RENDERED_SUFFIX = '.tmpl';
+
+interface WorktreeTemplateSettingsProps {
+  projectId: string;
+}
+
+// Untracked files, like .env.local, written into every new attempt worktree
+export function WorktreeTemplateSettings({
+  projectId,
+}: WorktreeTemplateSettingsProps) {
+  const [list, setList] = useState<WorktreeTemplateList | null>(null);
+  const [path, setPath] = useState('');
+  const [rendered, setRendered] = useState(false);
+  const [uploading, setUploading] = useState(false);
+  const [error, setError] = useState<string | null>(null);
+  const fileInput = useRef<HTMLInputElement>(null);
+
+  const load = useCallback(() => {
+    projectsApi
+      .getWorktreeTemplates(projectId)
+      .then(setList)
+      .catch((err) =>
+        setError(err instanceof Error ? err.message : 'Failed to load files')
+      );
+  }, [projectId]);
+
+  useEffect(() => {
+    load();
+  }, [load]);
+
+  const handleFile = async (file: File) => {
+    const target = path.trim() || file.name;
+    setUploading(true);
+    setError(null);
+    try {
+      await projectsApi.uploadWorktreeTemplate(
+        projectId,
+        rendered ? `${target}${RENDERED_SUFFIX}` : target,
+        file
+      );
+      setPath('');
+      load();
+    } catch (err) {
+      setError(err instanceof Error ? err.message : 'Failed to upload file');
+    } finally {
+      setUploading(false);
+    }
+  };
+
+  const handleDelete = async (templatePath: string) => {
+    setError(null);
+    try {
+      await projectsApi.deleteWorktreeTemplate(projectId, templatePath);
+      load();
+    } catch (err) {
+      setError(err instanceof Error ? err.message : 'Failed to delete file');
+    }
+  };
+
+  if (!list && !error) {
+    return <Loader2 className="h-4 w-4 animate-spin" />;
+  }
+
+  return (
+    <div className="space-y-4">
+      <p className="text-sm text-muted-foreground">
+        Files that aren't in git but builds need, such as{' '}
+        <code>.env.local</code> or service account keys, are written into each
+        new worktree before its setup script runs. Rendered files have <code>{'${NAME}'}</code>{' '}
+        replaced with the project's environment variables. They're kept out of
+        the attempt's commits.
+      </p>
+      {list && list.templates.length > 0 && (
+        <ul className="space-y-1">
+          {list.templates.map((template) => (
+            <li
+              key={template.path}
+              className="flex items-center justify-between gap-2 text-sm"
+            >
+              <span className="font-mono">
+                {template.rendered
+                  ? template.path.slice(0, -RENDERED_SUFFIX.length)
+                  : template.path}
+                {template.rendered && (
+                  <span className="text-muted-foreground"> (rendered)</span>
+                )}
+              </span>
+              <Button
+                variant="ghost"
+                size="sm"
+                onClick={() => handleDelete(template.path)}
+              >
+                <Trash2 className="h-3 w-3" />
+              </Button>
+            </li>
+          ))}
+        </ul>
+      )}
+      <div className="flex items-center gap-2">
+        <Input
+          className="flex-1 font-mono"
+          placeholder="Path in the worktree, e.g. config/.env.local"
+          value={path}
+          onChange={(e) => setPath(e.target.value)}
+        />
+        <div className="flex items-center space-x-1">
+          <Checkbox
+            id="template-rendered"
+            checked={rendered}
+            onCheckedChange={(checked: boolean) => setRendered(checked)}
+          />
+          <Label htmlFor="template-rendered" className="cursor-pointer">
+            Render
+          </Label>
+        </div>
+        <input
+          ref={fileInput}
+          type="file"
+          className="hidden"
+          onChange={(e) => {
+            const file = e.target.files?.[0];
+            e.target.value = '';
+            if (file) handleFile(file);
+          }}
+        />
+        <Button
+          variant="outline"
+          disabled={uploading}
+          onClick={() => fileInput.current?.click()}
+        >
+          {uploading ? (
+            <Loader2 className="mr-1 h-4 w-4 animate-spin" />
+          ) : (
+            <Upload className="mr-1 h-4 w-4" />
+          )}
+          Upload
+        </Button>
+      </div>
+      {list && (
+        <p className="text-xs text-muted-foreground">
+          Kept in <code>{list.dir}</code>
+        </p>
+      )}
+      {error && <p className="text-sm text-destructive">{error}</p>}
+    </div>
+  );
+}
@@ -10,7 +10,9 @@ import {
 import {
   type Config,
   type Environment,
+  type FeatureFlags,
   type ProfileConfig,
+  type UpdateStatus,
   type UserSystemInfo,
   CheckTokenResponse,
 } from 'shared/types';
@@ -20,6 +22,8 @@ interface UserSystemState {
   config: Config | null;
   environment: Environment | null;
   profiles: ProfileConfig[] | null;
+  update: UpdateStatus | null;
+  featureFlags: FeatureFlags | null;
 }
 
 interface UserSystemContextType {
@@ -35,6 +39,9 @@ interface UserSystemContextType {
   // System data access
   environment: Environment | null;
   profiles: ProfileConfig[] | null;
+  update: UpdateStatus | null;
+  // Experimental subsystems in use, environment overrides applied
+  featureFlags: FeatureFlags | null;
   setEnvironment: (env: Environment | null) => void;
   setProfiles: (profiles: ProfileConfig[] | null) => void;
 
@@ -59,6 +66,8 @@ export function UserSystemProvider({ children }: UserSystemProviderProps) {
   const [config, setConfig] = useState<Config | null>(null);
   const [environment, setEnvironment] = useState<Environment | null>(null);
   const [profiles, setProfiles] = useState<ProfileConfig[] | null>(null);
+  const [update, setUpdate] = useState<UpdateStatus | null>(null);
+  const [featureFlags, setFeatureFlags] = useState<FeatureFlags | null>(null);
   const [loading, setLoading] = useState(true);
   const [githubTokenInvalid, setGithubTokenInvalid] = useState(false);
 
@@ -69,6 +78,8 @@ export function UserSystemProvider({ children }: UserSystemProviderProps) {
         setConfig(userSystemInfo.config);
         setEnvironment(userSystemInfo.environment);
         setProfiles(userSystemInfo.profiles);
+        setUpdate(userSystemInfo.update);
+        setFeatureFlags(userSystemInfo.feature_flags);
       } catch (err) {
         console.error('Error loading user system:', err);
       } finally {
@@ -79,6 +90,26 @@ export function UserSystemProvider({ children }: UserSystemProviderProps) {
     loadUserSystem();
   }, []);
 
+  // Keep in sync with changes made in other tabs or by editing the config files
+  useEffect(() => {
+    const eventSource = new EventSource('/api/config/stream');
+    eventSource.addEventListener('config', (event) => {
+      try {
+        const userSystemInfo: UserSystemInfo = JSON.parse(
+          (event as MessageEvent).data
+        );
+        setConfig(userSystemInfo.config);
+        setEnvironment(userSystemInfo.environment);
+        setProfiles(userSystemInfo.profiles);
+        setUpdate(userSystemInfo.update);
+        setFeatureFlags(userSystemInfo.feature_flags);
+      } catch (err) {
+        console.error('Error parsing config update:', err);
+      }
+    });
+    return () => eventSource.close();
+  }, []);
+
   // Check GitHub token validity after config loads
   useEffect(() => {
     if (loading) return;
@@ -143,6 +174,8 @@ export function UserSystemProvider({ children }: UserSystemProviderProps) {
       setConfig(userSystemInfo.config);
       setEnvironment(userSystemInfo.environment);
       setProfiles(userSystemInfo.profiles);
+      setUpdate(userSystemInfo.update);
+      setFeatureFlags(userSystemInfo.feature_flags);
     } catch (err) {
       console.error('Error reloading user system:', err);
     } finally {
@@ -153,10 +186,12 @@ export function UserSystemProvider({ children }: UserSystemProviderProps) {
   // Memoize context value to prevent unnecessary re-renders
   const value = useMemo<UserSystemContextType>(
     () => ({
-      system: { config, environment, profiles },
+      system: { config, environment, profiles, update, featureFlags },
       config,
       environment,
       profiles,
+      update,
+      featureFlags,
       updateConfig,
       saveConfig,
       updateAndSaveConfig,
@@ -170,6 +205,8 @@ export function UserSystemProvider({ children }: UserSystemProviderProps) {
       config,
       environment,
       profiles,
+      update,
+      featureFlags,
       updateConfig,
       saveConfig,
       updateAndSaveConfig,
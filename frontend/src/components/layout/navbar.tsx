@@ -1,3 +1,4 @@
+import { useState } from 'react';
 import { Link, useLocation } from 'react-router-dom';
 import { Button } from '@/components/ui/button';
 import {
@@ -6,11 +7,35 @@ import {
   BookOpen,
   Server,
   MessageCircleQuestion,
+  Pause,
+  Play,
 } from 'lucide-react';
 import { Logo } from '@/components/logo';
+import { useConfig } from '@/components/config-provider';
+import { maintenanceApi } from '@/lib/api';
 
 export function Navbar() {
   const location = useLocation();
+  const { config, updateConfig } = useConfig();
+  const [switching, setSwitching] = useState(false);
+  const agentsPaused = config?.agents_paused ?? false;
+
+  // Stop the world: suspend every running agent and hold new work until resumed
+  const toggleAgentsPaused = async () => {
+    setSwitching(true);
+    try {
+      if (agentsPaused) {
+        await maintenanceApi.resumeAgents();
+      } else {
+        await maintenanceApi.pauseAgents();
+      }
+      updateConfig({ agents_paused: !agentsPaused });
+    } catch (err) {
+      console.error('Failed to switch agents pause:', err);
+    } finally {
+      setSwitching(false);
+    }
+  };
 
   return (
     <div className="border-b">
@@ -58,6 +83,24 @@ export function Navbar() {
             </div>
           </div>
           <div className="flex items-center space-x-1">
+            <Button
+              variant={agentsPaused ? 'destructive' : 'ghost'}
+              size="sm"
+              onClick={toggleAgentsPaused}
+              disabled={switching || !config}
+              title={
+                agentsPaused
+                  ? 'Continue every paused agent and start queued work'
+                  : 'Suspend every running agent and hold new work'
+              }
+            >
+              {agentsPaused ? (
+                <Play className="mr-2 h-4 w-4" />
+              ) : (
+                <Pause className="mr-2 h-4 w-4" />
+              )}
+              {agentsPaused ? 'Resume agents' : 'Pause agents'}
+            </Button>
             <Button asChild variant="ghost" size="sm">
               <a
                 href="https://vibekanban.com/"
@@ -0,0 +1,175 @@
+import { useEffect, useState } from 'react';
+import { Button } from '@/components/ui/button';
+import { Checkbox } from '@/components/ui/checkbox';
+import {
+  Dialog,
+  DialogContent,
+  DialogDescription,
+  DialogFooter,
+  DialogHeader,
+  DialogTitle,
+} from '@/components/ui/dialog';
+import { Alert, AlertDescription } from '@/components/ui/alert';
+import { AlertCircle, GitBranch, Loader2 } from 'lucide-react';
+import { projectsApi } from '@/lib/api';
+import { DiscoveredRepo } from 'shared/types';
+import { projectNameFromFolder } from './project-form-fields';
+
+const formatDate = (date: string) => new Date(date).toLocaleDateString();
+
+interface DiscoverProjectsDialogProps {
+  open: boolean;
+  onClose: () => void;
+  onSuccess: () => void;
+}
+
+// Git checkouts in the workspace directory that aren't projects yet, added to
+// the board in bulk with their scripts detected on the server
+export function DiscoverProjectsDialog({
+  open,
+  onClose,
+  onSuccess,
+}: DiscoverProjectsDialogProps) {
+  const [repos, setRepos] = useState<DiscoveredRepo[]>([]);
+  const [selected, setSelected] = useState<Set<string>>(new Set());
+  const [loading, setLoading] = useState(false);
+  const [adding, setAdding] = useState(false);
+  const [error, setError] = useState('');
+
+  useEffect(() => {
+    if (!open) return;
+    setLoading(true);
+    setError('');
+    setSelected(new Set());
+    projectsApi
+      .discover()
+      .then(setRepos)
+      .catch((e) => {
+        setRepos([]);
+        setError(e instanceof Error ? e.message : 'Failed to scan workspace');
+      })
+      .finally(() => setLoading(false));
+  }, [open]);
+
+  const toggle = (path: string, checked: boolean) => {
+    setSelected((prev) => {
+      const next = new Set(prev);
+      if (checked) next.add(path);
+      else next.delete(path);
+      return next;
+    });
+  };
+
+  const handleAdd = async () => {
+    setAdding(true);
+    setError('');
+    const failed: DiscoveredRepo[] = [];
+    for (const repo of repos.filter((r) => selected.has(r.path))) {
+      try {
+        await projectsApi.create({
+          name: projectNameFromFolder(repo.name),
+          git_repo_path: repo.path,
+          use_existing_repo: true,
+          setup_script: null,
+          dev_script: null,
+          cleanup_script: null,
+          copy_files: null,
+          test_script: null,
+          test_result_format: null,
+        });
+      } catch {
+        failed.push(repo);
+      }
+    }
+    setAdding(false);
+    if (failed.length > 0) {
+      setError(`Failed to add ${failed.map((r) => r.name).join(', ')}`);
+      setRepos(failed);
+      setSelected(new Set());
+      return;
+    }
+    onSuccess();
+  };
+
+  return (
+    <Dialog open={open} onOpenChange={(isOpen) => !isOpen && onClose()}>
+      <DialogContent className="sm:max-w-[600px]">
+        <DialogHeader>
+          <DialogTitle>Add Existing Repositories</DialogTitle>
+          <DialogDescription>
+            Git repositories in your workspace directory that aren't projects
+            yet
+          </DialogDescription>
+        </DialogHeader>
+
+        {error && (
+          <Alert variant="destructive">
+            <AlertCircle className="h-4 w-4" />
+            <AlertDescription>{error}</AlertDescription>
+          </Alert>
+        )}
+
+        {loading ? (
+          <div className="flex items-center justify-center py-8">
+            <Loader2 className="mr-2 h-4 w-4 animate-spin" />
+            Scanning workspace...
+          </div>
+        ) : repos.length === 0 ? (
+          !error && (
+            <p className="py-8 text-center text-sm text-muted-foreground">
+              Every repository in your workspace is already a project
+            </p>
+          )
+        ) : (
+          <div className="max-h-96 overflow-y-auto border rounded-md divide-y">
+            {repos.map((repo) => (
+              <label
+                key={repo.path}
+                className="flex items-start gap-3 p-3 cursor-pointer hover:bg-accent"
+              >
+                <Checkbox
+                  checked={selected.has(repo.path)}
+                  onCheckedChange={(checked: boolean) =>
+                    toggle(repo.path, checked)
+                  }
+                  className="mt-1"
+                />
+                <div className="min-w-0 flex-1 space-y-1">
+                  <div className="flex items-center gap-2">
+                    <span className="font-medium">{repo.name}</span>
+                    {repo.branch && (
+                      <span className="flex items-center text-xs text-muted-foreground">
+                        <GitBranch className="mr-1 h-3 w-3" />
+                        {repo.branch}
+                      </span>
+                    )}
+                  </div>
+                  <div className="text-xs text-muted-foreground truncate">
+                    {repo.remote_url ?? repo.path}
+                  </div>
+                  {repo.last_commit && (
+                    <div className="text-xs text-muted-foreground truncate">
+                      {repo.last_commit.summary} ·{' '}
+                      {repo.last_commit.author},{' '}
+                      {formatDate(repo.last_commit.committed_at)}
+                    </div>
+                  )}
+                </div>
+              </label>
+            ))}
+          </div>
+        )}
+
+        <DialogFooter>
+          <Button variant="outline" onClick={onClose} disabled={adding}>
+            Cancel
+          </Button>
+          <Button onClick={handleAdd} disabled={adding || selected.size === 0}>
+            {adding && <Loader2 className="mr-2 h-4 w-4 animate-spin" />}
+            Add Selected
+          </Button>
+        </DialogFooter>
+      </DialogContent>
+    </Dialog>
+  );
+}
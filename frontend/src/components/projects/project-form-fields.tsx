@@ -1,6 +1,13 @@
 import { Label } from '@/components/ui/label';
 import { Input } from '@/components/ui/input';
 import { Button } from '@/components/ui/button';
+import {
+  Select,
+  SelectContent,
+  SelectItem,
+  SelectTrigger,
+  SelectValue,
+} from '@/components/ui/select';
 import { Alert, AlertDescription } from '@/components/ui/alert';
 import { AlertCircle, Folder } from 'lucide-react';
 import {
@@ -9,11 +16,25 @@ import {
 } from '@/utils/script-placeholders';
 import { useUserSystem } from '@/components/config-provider';
 import { CopyFilesField } from './copy-files-field';
+import { ScriptSuggestions } from './script-suggestions';
+import { GitHubRepositoryPicker } from './github-repository-picker';
+import type { TestResultFormat } from 'shared/types';
+
+const LOGS_ONLY = '__logs_only__';
+
+export type RepoMode = 'existing' | 'new' | 'clone';
+
+/** Project name for a repository folder, e.g. "My Awesome Project" for my-awesome-project */
+export function projectNameFromFolder(folder: string) {
+  return folder
+    .replace(/[-_]/g, ' ')
+    .replace(/\b\w/g, (l) => l.toUpperCase());
+}
 
 interface ProjectFormFieldsProps {
   isEditing: boolean;
-  repoMode: 'existing' | 'new';
-  setRepoMode: (mode: 'existing' | 'new') => void;
+  repoMode: RepoMode;
+  setRepoMode: (mode: RepoMode) => void;
   gitRepoPath: string;
   handleGitRepoPathChange: (path: string) => void;
   setShowFolderPicker: (show: boolean) => void;
@@ -21,6 +42,8 @@ interface ProjectFormFieldsProps {
   setParentPath: (path: string) => void;
   folderName: string;
   setFolderName: (name: string) => void;
+  cloneUrl: string;
+  handleCloneUrlChange: (url: string) => void;
   setName: (name: string) => void;
   name: string;
   setupScript: string;
@@ -31,6 +54,10 @@ interface ProjectFormFieldsProps {
   setCleanupScript: (script: string) => void;
   copyFiles: string;
   setCopyFiles: (files: string) => void;
+  testScript: string;
+  setTestScript: (script: string) => void;
+  testResultFormat: TestResultFormat | null;
+  setTestResultFormat: (format: TestResultFormat | null) => void;
   error: string;
   projectId?: string;
 }
@@ -46,6 +73,8 @@ export function ProjectFormFields({
   setParentPath,
   folderName,
   setFolderName,
+  cloneUrl,
+  handleCloneUrlChange,
   setName,
   name,
   setupScript,
@@ -56,6 +85,10 @@ export function ProjectFormFields({
   setCleanupScript,
   copyFiles,
   setCopyFiles,
+  testScript,
+  setTestScript,
+  testResultFormat,
+  setTestResultFormat,
   error,
   projectId,
 }: ProjectFormFieldsProps) {
@@ -85,9 +118,7 @@ export function ProjectFormFields({
                 name="repoMode"
                 value="existing"
                 checked={repoMode === 'existing'}
-                onChange={(e) =>
-                  setRepoMode(e.target.value as 'existing' | 'new')
-                }
+                onChange={(e) => setRepoMode(e.target.value as RepoMode)}
                 className="text-primary"
               />
               <span className="text-sm">Use existing repository</span>
@@ -98,13 +129,22 @@ export function ProjectFormFields({
                 name="repoMode"
                 value="new"
                 checked={repoMode === 'new'}
-                onChange={(e) =>
-                  setRepoMode(e.target.value as 'existing' | 'new')
-                }
+                onChange={(e) => setRepoMode(e.target.value as RepoMode)}
                 className="text-primary"
               />
               <span className="text-sm">Create new repository</span>
             </label>
+            <label className="flex items-center space-x-2 cursor-pointer">
+              <input
+                type="radio"
+                name="repoMode"
+                value="clone"
+                checked={repoMode === 'clone'}
+                onChange={(e) => setRepoMode(e.target.value as RepoMode)}
+                className="text-primary"
+              />
+              <span className="text-sm">Clone from URL</span>
+            </label>
           </div>
         </div>
       )}
@@ -136,6 +176,55 @@ export function ProjectFormFields({
             </p>
           )}
         </div>
+      ) : repoMode === 'clone' ? (
+        <div className="space-y-4">
+          <div className="space-y-2">
+            <Label>GitHub Repository</Label>
+            <GitHubRepositoryPicker
+              selectedUrl={cloneUrl}
+              onRepositorySelect={(repository) =>
+                handleCloneUrlChange(repository.clone_url)
+              }
+            />
+          </div>
+
+          <div className="space-y-2">
+            <Label htmlFor="clone-url">Repository URL</Label>
+            <Input
+              id="clone-url"
+              type="text"
+              value={cloneUrl}
+              onChange={(e) => handleCloneUrlChange(e.target.value)}
+              placeholder="https://github.com/owner/repo.git"
+              required
+            />
+          </div>
+
+          <div className="space-y-2">
+            <Label htmlFor="parent-path">Parent Directory (Optional)</Label>
+            <div className="flex space-x-2">
+              <Input
+                id="parent-path"
+                type="text"
+                value={parentPath}
+                onChange={(e) => setParentPath(e.target.value)}
+                placeholder="~/vibe-kanban-projects"
+                className="flex-1"
+              />
+              <Button
+                type="button"
+                variant="outline"
+                onClick={() => setShowFolderPicker(true)}
+              >
+                <Folder className="h-4 w-4" />
+              </Button>
+            </div>
+            <p className="text-sm text-muted-foreground">
+              The repository is cloned into a folder named after it, the setup
+              script defaults to installing its dependencies
+            </p>
+          </div>
+        </div>
       ) : (
         <div className="space-y-4">
           <div className="space-y-2">
@@ -172,11 +261,7 @@ export function ProjectFormFields({
               onChange={(e) => {
                 setFolderName(e.target.value);
                 if (e.target.value) {
-                  setName(
-                    e.target.value
-                      .replace(/[-_]/g, ' ')
-                      .replace(/\b\w/g, (l) => l.toUpperCase())
-                  );
+                  setName(projectNameFromFolder(e.target.value));
                 }
               }}
               placeholder="my-awesome-project"
@@ -202,6 +287,17 @@ export function ProjectFormFields({
         />
       </div>
 
+      {isEditing && projectId && (
+        <ScriptSuggestions
+          projectId={projectId}
+          onApply={(kind, command) => {
+            if (kind === 'setup') setSetupScript(command);
+            else if (kind === 'dev') setDevScript(command);
+            else setTestScript(command);
+          }}
+        />
+      )}
+
       <div className="space-y-2">
         <Label htmlFor="setup-script">Setup Script (Optional)</Label>
         <textarea
@@ -253,6 +349,41 @@ export function ProjectFormFields({
         </p>
       </div>
 
+      <div className="space-y-2">
+        <Label htmlFor="test-script">Test Script (Optional)</Label>
+        <textarea
+          id="test-script"
+          value={testScript}
+          onChange={(e) => setTestScript(e.target.value)}
+          placeholder="cargo test -- -Z unstable-options --format json"
+          rows={2}
+          className="w-full px-3 py-2 border border-input bg-background text-foreground rounded-md resize-vertical focus:outline-none focus:ring-2 focus:ring-ring"
+        />
+        <Label htmlFor="test-result-format">Test Result Format</Label>
+        <Select
+          value={testResultFormat ?? LOGS_ONLY}
+          onValueChange={(value) =>
+            setTestResultFormat(
+              value === LOGS_ONLY ? null : (value as TestResultFormat)
+            )
+          }
+        >
+          <SelectTrigger id="test-result-format">
+            <SelectValue />
+          </SelectTrigger>
+          <SelectContent>
+            <SelectItem value={LOGS_ONLY}>Logs only</SelectItem>
+            <SelectItem value="junit">JUnit XML</SelectItem>
+            <SelectItem value="cargo_json">Cargo JSON</SelectItem>
+          </SelectContent>
+        </Select>
+        <p className="text-sm text-muted-foreground">
+          Runs last, after the cleanup script. Its stdout is parsed into pass
+          and fail counts shown on the task card, e.g. print a JUnit report with{' '}
+          <code>pytest --junitxml=/dev/stdout</code>.
+        </p>
+      </div>
+
       <div className="space-y-2">
         <Label htmlFor="copy-files">Copy Files (Optional)</Label>
         <CopyFilesField
@@ -11,10 +11,88 @@ import {
 import { Tabs, TabsContent, TabsList, TabsTrigger } from '@/components/ui/tabs';
 import { FolderPicker } from '@/components/ui/folder-picker';
 import { TaskTemplateManager } from '@/components/TaskTemplateManager';
-import { ProjectFormFields } from './project-form-fields';
-import { CreateProject, Project, UpdateProject } from 'shared/types';
+import { FollowUpSnippetManager } from '@/components/FollowUpSnippetManager';
+import { PathRulesManager } from '@/components/PathRulesManager';
+import { BranchSyncSettings } from '@/components/BranchSyncSettings';
+import { CiTriageSettings } from '@/components/CiTriageSettings';
+import { DependencyUpdateSettings } from '@/components/DependencyUpdateSettings';
+import { ChangelogSettings } from '@/components/ChangelogSettings';
+import { ReviewerSettings } from '@/components/ReviewerSettings';
+import { EvaluationManager } from '@/components/EvaluationManager';
+import { ContextPackingSettings } from '@/components/ContextPackingSettings';
+import { DependencyCacheSettings } from '@/components/DependencyCacheSettings';
+import { DevcontainerSettings } from '@/components/DevcontainerSettings';
+import { NixEnvSettings } from '@/components/NixEnvSettings';
+import { ProjectEnvSettings } from '@/components/ProjectEnvSettings';
+import { WorktreePoolSettings } from '@/components/WorktreePoolSettings';
+import { WorktreeTemplateSettings } from '@/components/WorktreeTemplateSettings';
+import {
+  ProjectFormFields,
+  projectNameFromFolder,
+  RepoMode,
+} from './project-form-fields';
+import {
+  EMPTY_TEMPLATE,
+  ProjectTemplateFields,
+  REPOSITORY_TEMPLATE,
+} from './project-template-fields';
+import {
+  CloneEvent,
+  CloneProgress,
+  CloneProject,
+  CreateProject,
+  CreateProjectFromTemplate,
+  Project,
+  ProjectTemplate,
+  TemplateSource,
+  TestResultFormat,
+  UpdateProject,
+} from 'shared/types';
 import { projectsApi } from '@/lib/api';
 
+function cloneProgressLabel(progress: CloneProgress) {
+  switch (progress.stage) {
+    case 'receiving':
+      return `Receiving objects ${progress.received_objects}/${progress.total_objects} (${(progress.received_bytes / 1024 / 1024).toFixed(1)} MiB)`;
+    case 'resolving':
+      return `Resolving deltas ${progress.indexed_deltas}/${progress.total_deltas}`;
+    case 'checking_out':
+      return `Checking out files ${progress.completed}/${progress.total}`;
+  }
+}
+
+/** Follow a clone until its project is created, reporting progress along the way */
+function waitForClone(cloneId: string, onProgress: (label: string) => void) {
+  return new Promise<void>((resolve, reject) => {
+    const eventSource = new EventSource(
+      `/api/projects/clones/${cloneId}/stream`
+    );
+    eventSource.addEventListener('clone', (event) => {
+      const cloneEvent: CloneEvent = JSON.parse((event as MessageEvent).data);
+      switch (cloneEvent.type) {
+        case 'started':
+          onProgress('Connecting…');
+          break;
+        case 'progress':
+          onProgress(cloneProgressLabel(cloneEvent.progress));
+          break;
+        case 'done':
+          eventSource.close();
+          resolve();
+          break;
+        case 'failed':
+          eventSource.close();
+          reject(new Error(cloneEvent.error));
+          break;
+      }
+    });
+    eventSource.onerror = () => {
+      eventSource.close();
+      reject(new Error('Lost connection while cloning the repository'));
+    };
+  });
+}
+
 interface ProjectFormProps {
   open: boolean;
   onClose: () => void;
@@ -36,12 +114,20 @@ export function ProjectForm({
     project?.cleanup_script ?? ''
   );
   const [copyFiles, setCopyFiles] = useState(project?.copy_files ?? '');
+  const [testScript, setTestScript] = useState(project?.test_script ?? '');
+  const [testResultFormat, setTestResultFormat] =
+    useState<TestResultFormat | null>(project?.test_result_format ?? null);
   const [loading, setLoading] = useState(false);
   const [error, setError] = useState('');
   const [showFolderPicker, setShowFolderPicker] = useState(false);
-  const [repoMode, setRepoMode] = useState<'existing' | 'new'>('existing');
+  const [repoMode, setRepoMode] = useState<RepoMode>('existing');
   const [parentPath, setParentPath] = useState('');
   const [folderName, setFolderName] = useState('');
+  const [templateId, setTemplateId] = useState(EMPTY_TEMPLATE);
+  const [templateUrl, setTemplateUrl] = useState('');
+  const [firstTaskTitle, setFirstTaskTitle] = useState('');
+  const [cloneUrl, setCloneUrl] = useState('');
+  const [cloneProgress, setCloneProgress] = useState('');
 
   const isEditing = !!project;
 
@@ -54,6 +140,8 @@ export function ProjectForm({
       setDevScript(project.dev_script ?? '');
       setCleanupScript(project.cleanup_script ?? '');
       setCopyFiles(project.copy_files ?? '');
+      setTestScript(project.test_script ?? '');
+      setTestResultFormat(project.test_result_format ?? null);
     } else {
       setName('');
       setGitRepoPath('');
@@ -61,6 +149,8 @@ export function ProjectForm({
       setDevScript('');
       setCleanupScript('');
       setCopyFiles('');
+      setTestScript('');
+      setTestResultFormat(null);
     }
   }, [project]);
 
@@ -82,6 +172,34 @@ export function ProjectForm({
     }
   };
 
+  // Name the clone's folder and project after the repository
+  const handleCloneUrlChange = (url: string) => {
+    setCloneUrl(url);
+    const repoName = url
+      .trim()
+      .replace(/\/+$/, '')
+      .split(/[/:]/)
+      .pop()
+      ?.replace(/\.git$/, '');
+    if (repoName) {
+      setFolderName(repoName);
+      setName(projectNameFromFolder(repoName));
+    }
+  };
+
+  // Built-in templates suggest commands, without replacing ones already typed
+  const handleTemplateChange = (
+    id: string,
+    template: ProjectTemplate | null
+  ) => {
+    setTemplateId(id);
+    if (!template) return;
+    if (!setupScript.trim()) setSetupScript(template.setup_script ?? '');
+    if (!devScript.trim()) setDevScript(template.dev_script ?? '');
+    if (!testScript.trim()) setTestScript(template.test_script ?? '');
+    if (!testResultFormat) setTestResultFormat(template.test_result_format);
+  };
+
   const handleSubmit = async (e: React.FormEvent) => {
     e.preventDefault();
     setError('');
@@ -102,6 +220,8 @@ export function ProjectForm({
           dev_script: devScript.trim() || null,
           cleanup_script: cleanupScript.trim() || null,
           copy_files: copyFiles.trim() || null,
+          test_script: testScript.trim() || null,
+          test_result_format: testResultFormat,
         };
 
         await projectsApi.update(project.id, updateData);
@@ -132,17 +252,56 @@ export function ProjectForm({
           finalGitRepoPath = `${parentPath}/${folderName}`.replace(/\/+/g, '/');
         }
 
-        const createData: CreateProject = {
-          name,
-          git_repo_path: finalGitRepoPath,
-          use_existing_repo: repoMode === 'existing',
-          setup_script: setupScript.trim() || null,
-          dev_script: devScript.trim() || null,
-          cleanup_script: cleanupScript.trim() || null,
-          copy_files: copyFiles.trim() || null,
-        };
+        if (repoMode === 'clone') {
+          const cloneData: CloneProject = {
+            url: cloneUrl.trim(),
+            git_repo_path: parentPath.trim()
+              ? `${parentPath}/${folderName}`.replace(/\/+/g, '/')
+              : null,
+            name: name.trim() || null,
+            setup_script: setupScript.trim() || null,
+            dev_script: devScript.trim() || null,
+            test_script: testScript.trim() || null,
+          };
+          const job = await projectsApi.clone(cloneData);
+          await waitForClone(job.id, setCloneProgress);
+        } else if (repoMode === 'new') {
+          const template: TemplateSource =
+            templateId === EMPTY_TEMPLATE
+              ? { type: 'empty' }
+              : templateId === REPOSITORY_TEMPLATE
+                ? { type: 'repository', url: templateUrl.trim() }
+                : { type: 'builtin', id: templateId };
+          const templateData: CreateProjectFromTemplate = {
+            name,
+            git_repo_path: finalGitRepoPath,
+            template,
+            setup_script: setupScript.trim() || null,
+            dev_script: devScript.trim() || null,
+            cleanup_script: cleanupScript.trim() || null,
+            copy_files: copyFiles.trim() || null,
+            test_script: testScript.trim() || null,
+            test_result_format: testResultFormat,
+            first_task: firstTaskTitle.trim()
+              ? { title: firstTaskTitle.trim(), description: null }
+              : null,
+          };
+          await projectsApi.createFromTemplate(templateData);
+        } else {
+          const createData: CreateProject = {
+            name,
+            git_repo_path: finalGitRepoPath,
+            use_existing_repo: true,
+            setup_script: setupScript.trim() || null,
+            dev_script: devScript.trim() || null,
+            cleanup_script: cleanupScript.trim() || null,
+            copy_files: copyFiles.trim() || null,
+            test_script: testScript.trim() || null,
+            test_result_format: testResultFormat,
+          };
 
-        await projectsApi.create(createData);
+          await projectsApi.create(createData);
+        }
         // }
       }
 
@@ -154,12 +313,19 @@ export function ProjectForm({
       setDevScript('');
       setCleanupScript('');
       setCopyFiles('');
+      setTestScript('');
+      setTestResultFormat(null);
       setParentPath('');
       setFolderName('');
+      setTemplateId(EMPTY_TEMPLATE);
+      setTemplateUrl('');
+      setFirstTaskTitle('');
+      setCloneUrl('');
     } catch (error) {
       setError(error instanceof Error ? error.message : 'An error occurred');
     } finally {
       setLoading(false);
+      setCloneProgress('');
     }
   };
 
@@ -170,15 +336,20 @@ export function ProjectForm({
       setSetupScript(project.setup_script ?? '');
       setDevScript(project.dev_script ?? '');
       setCopyFiles(project.copy_files ?? '');
+      setTestScript(project.test_script ?? '');
+      setTestResultFormat(project.test_result_format ?? null);
     } else {
       setName('');
       setGitRepoPath('');
       setSetupScript('');
       setDevScript('');
       setCopyFiles('');
+      setTestScript('');
+      setTestResultFormat(null);
     }
     setParentPath('');
     setFolderName('');
+    setCloneUrl('');
     setError('');
     onClose();
   };
@@ -201,9 +372,17 @@ export function ProjectForm({
 
         {isEditing ? (
           <Tabs defaultValue="general" className="w-full -mt-2">
-            <TabsList className="grid w-full grid-cols-2 mb-4">
+            <TabsList className="grid w-full grid-cols-10 mb-4">
               <TabsTrigger value="general">General</TabsTrigger>
               <TabsTrigger value="templates">Task Templates</TabsTrigger>
+              <TabsTrigger value="snippets">Snippets</TabsTrigger>
+              <TabsTrigger value="path-rules">Path Rules</TabsTrigger>
+              <TabsTrigger value="branch-sync">Branch Sync</TabsTrigger>
+              <TabsTrigger value="reviewer">Reviewer</TabsTrigger>
+              <TabsTrigger value="evaluations">Evaluations</TabsTrigger>
+              <TabsTrigger value="context">Context</TabsTrigger>
+              <TabsTrigger value="worktrees">Worktrees</TabsTrigger>
+              <TabsTrigger value="env">Environment</TabsTrigger>
             </TabsList>
             <TabsContent value="general" className="space-y-4">
               <form onSubmit={handleSubmit} className="space-y-4">
@@ -218,6 +397,8 @@ export function ProjectForm({
                   setParentPath={setParentPath}
                   folderName={folderName}
                   setFolderName={setFolderName}
+                  cloneUrl={cloneUrl}
+                  handleCloneUrlChange={handleCloneUrlChange}
                   setName={setName}
                   name={name}
                   setupScript={setupScript}
@@ -228,6 +409,10 @@ export function ProjectForm({
                   setCleanupScript={setCleanupScript}
                   copyFiles={copyFiles}
                   setCopyFiles={setCopyFiles}
+                  testScript={testScript}
+                  setTestScript={setTestScript}
+                  testResultFormat={testResultFormat}
+                  setTestResultFormat={setTestResultFormat}
                   error={error}
                   projectId={(project as any)?.id}
                 />
@@ -252,6 +437,37 @@ export function ProjectForm({
             <TabsContent value="templates" className="mt-0 pt-0">
               <TaskTemplateManager projectId={project?.id} />
             </TabsContent>
+            <TabsContent value="snippets" className="mt-0 pt-0">
+              <FollowUpSnippetManager projectId={project.id} />
+            </TabsContent>
+            <TabsContent value="path-rules" className="mt-0 pt-0">
+              <PathRulesManager projectId={project.id} />
+            </TabsContent>
+            <TabsContent value="branch-sync" className="mt-0 pt-0 space-y-6">
+              <BranchSyncSettings projectId={project.id} />
+              <CiTriageSettings projectId={project.id} />
+              <DependencyUpdateSettings projectId={project.id} />
+              <ChangelogSettings projectId={project.id} />
+            </TabsContent>
+            <TabsContent value="reviewer" className="mt-0 pt-0">
+              <ReviewerSettings projectId={project.id} />
+            </TabsContent>
+            <TabsContent value="evaluations" className="mt-0 pt-0">
+              <EvaluationManager projectId={project.id} />
+            </TabsContent>
+            <TabsContent value="context" className="mt-0 pt-0">
+              <ContextPackingSettings projectId={project.id} />
+            </TabsContent>
+            <TabsContent value="worktrees" className="mt-0 pt-0 space-y-6">
+              <WorktreePoolSettings projectId={project.id} />
+              <DependencyCacheSettings projectId={project.id} />
+              <DevcontainerSettings projectId={project.id} />
+              <NixEnvSettings projectId={project.id} />
+              <WorktreeTemplateSettings projectId={project.id} />
+            </TabsContent>
+            <TabsContent value="env" className="mt-0 pt-0">
+              <ProjectEnvSettings projectId={project.id} />
+            </TabsContent>
           </Tabs>
         ) : (
           <form onSubmit={handleSubmit} className="space-y-4">
@@ -329,6 +545,8 @@ export function ProjectForm({
               setParentPath={setParentPath}
               folderName={folderName}
               setFolderName={setFolderName}
+              cloneUrl={cloneUrl}
+              handleCloneUrlChange={handleCloneUrlChange}
               setName={setName}
               name={name}
               setupScript={setupScript}
@@ -339,10 +557,27 @@ export function ProjectForm({
               setCleanupScript={setCleanupScript}
               copyFiles={copyFiles}
               setCopyFiles={setCopyFiles}
+              testScript={testScript}
+              setTestScript={setTestScript}
+              testResultFormat={testResultFormat}
+              setTestResultFormat={setTestResultFormat}
               error={error}
               projectId={(project as any)?.id}
             />
+            {repoMode === 'new' && (
+              <ProjectTemplateFields
+                templateId={templateId}
+                onTemplateChange={handleTemplateChange}
+                templateUrl={templateUrl}
+                setTemplateUrl={setTemplateUrl}
+                firstTaskTitle={firstTaskTitle}
+                setFirstTaskTitle={setFirstTaskTitle}
+              />
+            )}
             {/* )} */}
+            {cloneProgress && (
+              <p className="text-sm text-muted-foreground">{cloneProgress}</p>
+            )}
             <DialogFooter>
               <Button
                 type="button"
@@ -359,10 +594,16 @@ export function ProjectForm({
                   !name.trim() ||
                   (repoMode === 'existing'
                     ? !gitRepoPath.trim()
-                    : !parentPath.trim() || !folderName.trim())
+                    : repoMode === 'clone'
+                      ? !cloneUrl.trim()
+                      : !parentPath.trim() || !folderName.trim())
                 }
               >
-                {loading ? 'Creating...' : 'Create Project'}
+                {loading
+                  ? repoMode === 'clone'
+                    ? 'Cloning...'
+                    : 'Creating...'
+                  : 'Create Project'}
               </Button>
             </DialogFooter>
           </form>
@@ -9,8 +9,9 @@ import { Card, CardContent } from '@/components/ui/card';
 import { Alert, AlertDescription } from '@/components/ui/alert';
 import { Project } from 'shared/types';
 import { ProjectForm } from './project-form';
+import { DiscoverProjectsDialog } from './discover-projects-dialog';
 import { projectsApi } from '@/lib/api';
-import { AlertCircle, Loader2, Plus } from 'lucide-react';
+import { AlertCircle, FolderSearch, Loader2, Plus } from 'lucide-react';
 import ProjectCard from '@/components/projects/ProjectCard.tsx';
 
 export function ProjectList() {
@@ -18,6 +19,7 @@ export function ProjectList() {
   const [projects, setProjects] = useState<Project[]>([]);
   const [loading, setLoading] = useState(false);
   const [showForm, setShowForm] = useState(false);
+  const [showDiscover, setShowDiscover] = useState(false);
   const [editingProject, setEditingProject] = useState<Project | null>(null);
   const [error, setError] = useState('');
   const [focusedProjectId, setFocusedProjectId] = useState<string | null>(null);
@@ -136,10 +138,16 @@ export function ProjectList() {
             Manage your projects and track their progress
           </p>
         </div>
-        <Button onClick={() => setShowForm(true)}>
-          <Plus className="mr-2 h-4 w-4" />
-          Create Project
-        </Button>
+        <div className="flex gap-2">
+          <Button variant="outline" onClick={() => setShowDiscover(true)}>
+            <FolderSearch className="mr-2 h-4 w-4" />
+            Add Existing
+          </Button>
+          <Button onClick={() => setShowForm(true)}>
+            <Plus className="mr-2 h-4 w-4" />
+            Create Project
+          </Button>
+        </div>
       </div>
 
       {error && (
@@ -195,6 +203,15 @@ export function ProjectList() {
         onSuccess={handleFormSuccess}
         project={editingProject}
       />
+
+      <DiscoverProjectsDialog
+        open={showDiscover}
+        onClose={() => setShowDiscover(false)}
+        onSuccess={() => {
+          setShowDiscover(false);
+          fetchProjects();
+        }}
+      />
     </div>
   );
 }
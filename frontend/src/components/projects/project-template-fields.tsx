@@ -0,0 +1,100 @@
+import { useEffect, useState } from 'react';
+import { Label } from '@/components/ui/label';
+import { Input } from '@/components/ui/input';
+import {
+  Select,
+  SelectContent,
+  SelectItem,
+  SelectTrigger,
+  SelectValue,
+} from '@/components/ui/select';
+import { projectsApi } from '@/lib/api';
+import type { ProjectTemplate } from 'shared/types';
+
+export const EMPTY_TEMPLATE = '__empty__';
+export const REPOSITORY_TEMPLATE = '__repository__';
+
+interface ProjectTemplateFieldsProps {
+  templateId: string;
+  onTemplateChange: (id: string, template: ProjectTemplate | null) => void;
+  templateUrl: string;
+  setTemplateUrl: (url: string) => void;
+  firstTaskTitle: string;
+  setFirstTaskTitle: (title: string) => void;
+}
+
+/** What a new repository starts with, and the first task of its project */
+export function ProjectTemplateFields({
+  templateId,
+  onTemplateChange,
+  templateUrl,
+  setTemplateUrl,
+  firstTaskTitle,
+  setFirstTaskTitle,
+}: ProjectTemplateFieldsProps) {
+  const [templates, setTemplates] = useState<ProjectTemplate[]>([]);
+
+  useEffect(() => {
+    projectsApi
+      .getTemplates()
+      .then(setTemplates)
+      .catch((err) => console.error('Failed to load project templates:', err));
+  }, []);
+
+  return (
+    <>
+      <div className="space-y-2">
+        <Label htmlFor="project-template">Template</Label>
+        <Select
+          value={templateId}
+          onValueChange={(id) =>
+            onTemplateChange(id, templates.find((t) => t.id === id) ?? null)
+          }
+        >
+          <SelectTrigger id="project-template">
+            <SelectValue />
+          </SelectTrigger>
+          <SelectContent>
+            <SelectItem value={EMPTY_TEMPLATE}>Empty repository</SelectItem>
+            {templates.map((template) => (
+              <SelectItem key={template.id} value={template.id}>
+                {template.name} – {template.description}
+              </SelectItem>
+            ))}
+            <SelectItem value={REPOSITORY_TEMPLATE}>
+              Template repository…
+            </SelectItem>
+          </SelectContent>
+        </Select>
+      </div>
+
+      {templateId === REPOSITORY_TEMPLATE && (
+        <div className="space-y-2">
+          <Label htmlFor="template-url">Template Repository URL</Label>
+          <Input
+            id="template-url"
+            type="text"
+            value={templateUrl}
+            onChange={(e) => setTemplateUrl(e.target.value)}
+            placeholder="https://github.com/owner/template.git"
+            required
+          />
+          <p className="text-sm text-muted-foreground">
+            Its files are copied into the new repository, without its history
+          </p>
+        </div>
+      )}
+
+      <div className="space-y-2">
+        <Label htmlFor="first-task">First Task (Optional)</Label>
+        <Input
+          id="first-task"
+          type="text"
+          value={firstTaskTitle}
+          onChange={(e) => setFirstTaskTitle(e.target.value)}
+          placeholder="e.g. Build a CLI that converts CSV to JSON"
+        />
+      </div>
+    </>
+  );
+}
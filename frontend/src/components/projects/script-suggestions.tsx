@@ -0,0 +1,88 @@
+import { useState } from 'react';
+import { Wand2 } from 'lucide-react';
+import { Button } from '@/components/ui/button';
+import { projectsApi } from '@/lib/api';
+import type { ScriptKind, ScriptSuggestion } from 'shared/types';
+
+const kindLabels: Record<ScriptKind, string> = {
+  setup: 'Setup',
+  dev: 'Dev',
+  test: 'Test',
+};
+
+interface ScriptSuggestionsProps {
+  projectId: string;
+  onApply: (kind: ScriptKind, command: string) => void;
+}
+
+/** Commands detected from the repository's manifests, applied to a script on click */
+export function ScriptSuggestions({
+  projectId,
+  onApply,
+}: ScriptSuggestionsProps) {
+  const [suggestions, setSuggestions] = useState<ScriptSuggestion[] | null>(
+    null
+  );
+  const [detecting, setDetecting] = useState(false);
+
+  const detect = async () => {
+    setDetecting(true);
+    try {
+      const detected = await projectsApi.detectScripts(projectId);
+      setSuggestions(detected.suggestions);
+    } catch (err) {
+      console.error('Failed to detect project scripts:', err);
+    } finally {
+      setDetecting(false);
+    }
+  };
+
+  return (
+    <div className="space-y-2">
+      <Button
+        type="button"
+        variant="outline"
+        size="sm"
+        onClick={detect}
+        disabled={detecting}
+      >
+        <Wand2 className="h-4 w-4 mr-2" />
+        {detecting ? 'Detecting...' : 'Detect Scripts'}
+      </Button>
+      {suggestions && suggestions.length === 0 && (
+        <p className="text-sm text-muted-foreground">
+          No scripts detected in the repository
+        </p>
+      )}
+      {suggestions && suggestions.length > 0 && (
+        <ul className="space-y-1">
+          {suggestions.map((suggestion) => (
+            <li
+              key={`${suggestion.kind}-${suggestion.command}`}
+              className="flex items-center justify-between gap-2 text-sm"
+            >
+              <span className="truncate">
+                <span className="text-muted-foreground">
+                  {kindLabels[suggestion.kind]}:
+                </span>{' '}
+                <code>{suggestion.command}</code>{' '}
+                <span className="text-xs text-muted-foreground">
+                  {Math.round(suggestion.confidence * 100)}% ·{' '}
+                  {suggestion.source}
+                </span>
+              </span>
+              <Button
+                type="button"
+                variant="ghost"
+                size="sm"
+                onClick={() => onApply(suggestion.kind, suggestion.command)}
+              >
+                Use
+              </Button>
+            </li>
+          ))}
+        </ul>
+      )}
+    </div>
+  );
+}
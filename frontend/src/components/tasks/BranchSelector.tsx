@@ -210,6 +210,11 @@ function BranchSelector({
                           remote
                         </span>
                       )}
+                      {branch.is_tag && (
+                        <span className="text-xs bg-amber-100 text-amber-800 px-1 rounded">
+                          tag
+                        </span>
+                      )}
                     </div>
                   </div>
                 </DropdownMenuItem>
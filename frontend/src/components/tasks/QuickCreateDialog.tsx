@@ -0,0 +1,82 @@
+import { FormEvent, useEffect, useState } from 'react';
+import {
+  Dialog,
+  DialogContent,
+  DialogDescription,
+  DialogHeader,
+  DialogTitle,
+} from '@/components/ui/dialog';
+import { Input } from '@/components/ui/input';
+import { tasksApi } from '@/lib/api';
+import type { Task } from 'shared/types';
+
+interface QuickCreateDialogProps {
+  open: boolean;
+  onOpenChange: (open: boolean) => void;
+  projectId?: string;
+  onCreated: (task: Task) => void;
+}
+
+// One line, parsed by the server: the title plus priority, project, label and
+// profile tokens
+export function QuickCreateDialog({
+  open,
+  onOpenChange,
+  projectId,
+  onCreated,
+}: QuickCreateDialogProps) {
+  const [text, setText] = useState('');
+  const [submitting, setSubmitting] = useState(false);
+  const [error, setError] = useState<string | null>(null);
+
+  useEffect(() => {
+    if (open) {
+      setText('');
+      setError(null);
+    }
+  }, [open]);
+
+  const handleSubmit = async (e: FormEvent) => {
+    e.preventDefault();
+    if (!text.trim() || submitting) return;
+    setSubmitting(true);
+    setError(null);
+    try {
+      const task = await tasksApi.quickCreate({
+        text,
+        project_id: projectId ?? null,
+      });
+      onOpenChange(false);
+      onCreated(task);
+    } catch (err) {
+      setError(err instanceof Error ? err.message : 'Failed to create task');
+    } finally {
+      setSubmitting(false);
+    }
+  };
+
+  return (
+    <Dialog open={open} onOpenChange={onOpenChange}>
+      <DialogContent className="sm:max-w-xl">
+        <DialogHeader>
+          <DialogTitle>Quick Create</DialogTitle>
+          <DialogDescription>
+            <code>!p0</code>-<code>!p3</code> priority, <code>#name</code>{' '}
+            project or label, <code>@profile</code> to start an agent right
+            away.
+          </DialogDescription>
+        </DialogHeader>
+        <form onSubmit={handleSubmit} className="space-y-2">
+          <Input
+            autoFocus
+            value={text}
+            onChange={(e) => setText(e.target.value)}
+            placeholder="fix flaky auth test !p1 #backend @codex"
+            disabled={submitting}
+          />
+          {error && <p className="text-sm text-destructive">{error}</p>}
+        </form>
+      </DialogContent>
+    </Dialog>
+  );
+}
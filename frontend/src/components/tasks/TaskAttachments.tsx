@@ -0,0 +1,136 @@
+import { useCallback, useEffect, useRef, useState } from 'react';
+import { FileText, Loader2, Paperclip, X } from 'lucide-react';
+import { Button } from '@/components/ui/button';
+import { attachmentsApi } from '@/lib/api';
+import type { TaskAttachment } from 'shared/types';
+
+interface TaskAttachmentsProps {
+  taskId: string;
+}
+
+const formatSize = (bytes: number) =>
+  bytes < 1024 * 1024
+    ? `${Math.max(1, Math.round(bytes / 1024))} KB`
+    : `${(bytes / 1024 / 1024).toFixed(1)} MB`;
+
+// Screenshots and files on a task, copied into the worktree when an attempt starts
+function TaskAttachments({ taskId }: TaskAttachmentsProps) {
+  const [attachments, setAttachments] = useState<TaskAttachment[]>([]);
+  const [uploading, setUploading] = useState(false);
+  const [error, setError] = useState<string | null>(null);
+  const inputRef = useRef<HTMLInputElement>(null);
+
+  useEffect(() => {
+    attachmentsApi
+      .list(taskId)
+      .then(setAttachments)
+      .catch(() => setAttachments([]));
+  }, [taskId]);
+
+  const upload = useCallback(
+    async (files: File[]) => {
+      if (files.length === 0) return;
+      setUploading(true);
+      setError(null);
+      try {
+        for (const file of files) {
+          const attachment = await attachmentsApi.upload(taskId, file);
+          setAttachments((prev) => [...prev, attachment]);
+        }
+      } catch (err) {
+        setError(err instanceof Error ? err.message : 'Failed to upload');
+      } finally {
+        setUploading(false);
+      }
+    },
+    [taskId]
+  );
+
+  const remove = async (id: string) => {
+    try {
+      await attachmentsApi.delete(id);
+      setAttachments((prev) => prev.filter((a) => a.id !== id));
+    } catch (err) {
+      setError(err instanceof Error ? err.message : 'Failed to delete');
+    }
+  };
+
+  return (
+    <div
+      className="mt-2"
+      onDragOver={(e) => e.preventDefault()}
+      onDrop={(e) => {
+        e.preventDefault();
+        upload(Array.from(e.dataTransfer.files));
+      }}
+      onPaste={(e) => upload(Array.from(e.clipboardData.files))}
+    >
+      <div className="flex flex-wrap items-center gap-2">
+        {attachments.map((attachment) => (
+          <div
+            key={attachment.id}
+            className="group relative flex items-center gap-1 rounded border bg-muted/20 text-xs"
+            title={`${attachment.file_name} (${formatSize(attachment.size_bytes)})`}
+          >
+            <a
+              href={attachmentsApi.url(attachment.id)}
+              target="_blank"
+              rel="noreferrer"
+              className="flex items-center gap-1 p-1"
+            >
+              {attachment.content_type.startsWith('image/') ? (
+                <img
+                  src={attachmentsApi.url(attachment.id)}
+                  alt={attachment.file_name}
+                  className="h-10 w-10 rounded object-cover"
+                />
+              ) : (
+                <>
+                  <FileText className="h-4 w-4 text-muted-foreground" />
+                  <span className="max-w-32 truncate">
+                    {attachment.file_name}
+                  </span>
+                </>
+              )}
+            </a>
+            <button
+              type="button"
+              onClick={() => remove(attachment.id)}
+              className="absolute -right-1.5 -top-1.5 hidden rounded-full bg-background border p-0.5 group-hover:block"
+              aria-label={`Remove ${attachment.file_name}`}
+            >
+              <X className="h-3 w-3" />
+            </button>
+          </div>
+        ))}
+        <Button
+          variant="ghost"
+          size="sm"
+          className="h-7 text-xs text-muted-foreground"
+          onClick={() => inputRef.current?.click()}
+          disabled={uploading}
+        >
+          {uploading ? (
+            <Loader2 className="h-3 w-3 mr-1 animate-spin" />
+          ) : (
+            <Paperclip className="h-3 w-3 mr-1" />
+          )}
+          Attach
+        </Button>
+        <input
+          ref={inputRef}
+          type="file"
+          multiple
+          className="hidden"
+          onChange={(e) => {
+            upload(Array.from(e.target.files ?? []));
+            e.target.value = '';
+          }}
+        />
+      </div>
+      {error && <p className="mt-1 text-xs text-destructive">{error}</p>}
+    </div>
+  );
+}
+
+export default TaskAttachments;
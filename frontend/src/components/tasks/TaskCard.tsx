@@ -8,6 +8,8 @@ import {
 } from '@/components/ui/dropdown-menu';
 import { KanbanCard } from '@/components/ui/shadcn-io/kanban';
 import {
+  AlertTriangle,
+  Bot,
   CheckCircle,
   Edit,
   Loader2,
@@ -15,10 +17,67 @@ import {
   Trash2,
   XCircle,
 } from 'lucide-react';
-import type { TaskWithAttemptStatus } from 'shared/types';
+import type {
+  AgentPresence,
+  RiskFlag,
+  TaskPriority,
+  TaskWithAttemptStatus,
+} from 'shared/types';
 
 type Task = TaskWithAttemptStatus;
 
+const priorityClass: Record<TaskPriority, string> = {
+  urgent: 'border-red-500/50 text-red-600',
+  high: 'border-orange-500/50 text-orange-600',
+  medium: 'border-blue-500/50 text-blue-600',
+  low: 'border-muted-foreground/30 text-muted-foreground',
+};
+
+const formatElapsed = (secs: number) => {
+  const hours = Math.floor(secs / 3600);
+  const minutes = Math.floor((secs % 3600) / 60);
+  if (hours > 0) return `${hours}h${String(minutes).padStart(2, '0')}m`;
+  if (minutes > 0) return `${minutes}m`;
+  return `${secs}s`;
+};
+
+const presenceDetail = (presence: AgentPresence) => {
+  const tool = presence.last_tool_call;
+  const lines = [presence.activity ?? 'Starting'];
+  if (tool) {
+    lines.push(
+      `Last tool: ${tool.tool_name}${tool.target ? ` ${tool.target}` : ''}`
+    );
+  }
+  return lines.join('\n');
+};
+
+const riskFlagLabel = (flag: RiskFlag) => {
+  switch (flag.kind) {
+    case 'large_diff':
+      return 'Large diff';
+    case 'outside_expected_paths':
+      return 'Unexpected paths';
+    case 'ci_changes':
+      return 'CI changes';
+    case 'deletion_heavy':
+      return 'Mostly deletions';
+    case 'dependency_changes':
+      return 'Dependencies';
+  }
+};
+
+const riskFlagDetail = (flag: RiskFlag) => {
+  switch (flag.kind) {
+    case 'large_diff':
+      return `${flag.lines_changed} lines changed`;
+    case 'deletion_heavy':
+      return `${flag.lines_removed} lines removed, ${flag.lines_added} added`;
+    default:
+      return flag.files.join('\n');
+  }
+};
+
 interface TaskCardProps {
   task: Task;
   index: number;
@@ -28,6 +87,8 @@ interface TaskCardProps {
   onViewDetails: (task: Task) => void;
   isFocused: boolean;
   tabIndex?: number;
+  conflictingFiles?: string[];
+  presence?: AgentPresence;
 }
 
 export function TaskCard({
@@ -39,6 +100,8 @@ export function TaskCard({
   onViewDetails,
   isFocused,
   tabIndex = -1,
+  conflictingFiles,
+  presence,
 }: TaskCardProps) {
   const localRef = useRef<HTMLDivElement>(null);
   useEffect(() => {
@@ -91,6 +154,14 @@ export function TaskCard({
             {task.has_merged_attempt && (
               <CheckCircle className="h-3 w-3 text-green-500" />
             )}
+            {/* Another attempt changed the same files */}
+            {conflictingFiles && conflictingFiles.length > 0 && (
+              <span
+                title={`Potential conflict, another attempt also changed:\n${conflictingFiles.join('\n')}`}
+              >
+                <AlertTriangle className="h-3 w-3 text-amber-500" />
+              </span>
+            )}
             {/* Failed Indicator */}
             {task.last_attempt_failed && !task.has_merged_attempt && (
               <XCircle className="h-3 w-3 text-red-500" />
@@ -129,6 +200,25 @@ export function TaskCard({
             </div>
           </div>
         </div>
+        {(task.priority || task.labels.length > 0) && (
+          <div className="flex flex-wrap items-center gap-1 text-xs">
+            {task.priority && (
+              <span
+                className={`rounded border px-1 capitalize ${priorityClass[task.priority]}`}
+              >
+                {task.priority}
+              </span>
+            )}
+            {task.labels.map((label) => (
+              <span
+                key={label}
+                className="rounded bg-muted px-1 text-muted-foreground"
+              >
+                #{label}
+              </span>
+            ))}
+          </div>
+        )}
         {task.description && (
           <div>
             <p className="text-xs text-muted-foreground break-words">
@@ -138,6 +228,68 @@ export function TaskCard({
             </p>
           </div>
         )}
+        {presence && (
+          <div
+            className="flex items-center gap-1 text-xs text-blue-600"
+            title={presenceDetail(presence)}
+          >
+            <Bot className="h-3 w-3 shrink-0" />
+            <span className="shrink-0">
+              {presence.profile} · {formatElapsed(presence.elapsed_secs)}
+            </span>
+            {presence.activity && (
+              <span className="truncate text-muted-foreground">
+                {presence.activity}
+              </span>
+            )}
+          </div>
+        )}
+        {task.tests_passed !== null && task.tests_failed !== null && (
+          <div
+            className={`text-xs ${task.tests_failed > 0 ? 'text-red-500' : 'text-green-600'}`}
+          >
+            Tests: {task.tests_passed} passed
+            {task.tests_failed > 0 && `, ${task.tests_failed} failed`}
+          </div>
+        )}
+        {task.diff_risk && (
+          <div className="flex flex-wrap items-center gap-1 text-xs">
+            <span className="text-muted-foreground">
+              <span className="text-green-600">
+                +{task.diff_risk.lines_added}
+              </span>{' '}
+              <span className="text-red-500">
+                -{task.diff_risk.lines_removed}
+              </span>
+            </span>
+            {task.diff_risk.flags.map((flag) => (
+              <span
+                key={flag.kind}
+                className="rounded border border-amber-500/50 px-1 text-amber-600"
+                title={riskFlagDetail(flag)}
+              >
+                {riskFlagLabel(flag)}
+              </span>
+            ))}
+          </div>
+        )}
+        {(task.branch_sync_status === 'conflicts' ||
+          task.branch_sync_status === 'failed') && (
+          <div className="text-xs text-red-500">
+            {task.branch_sync_status === 'conflicts'
+              ? 'Conflicts with base branch'
+              : 'Branch sync failed'}
+          </div>
+        )}
+        {task.plan_status === 'awaiting_approval' && (
+          <div className="text-xs text-amber-600">Plan awaiting approval</div>
+        )}
+        {task.review_verdict === 'changes_requested' && (
+          <div className="text-xs text-red-500">Reviewer requested changes</div>
+        )}
+        {task.review_verdict === 'approved' && (
+          <div className="text-xs text-green-600">Review approved</div>
+        )}
       </div>
     </KanbanCard>
   );
@@ -2,15 +2,32 @@ import { generateDiffFile } from '@git-diff-view/file';
 import { useDiffEntries } from '@/hooks/useDiffEntries';
 import { useMemo, useContext, useCallback, useState, useEffect } from 'react';
 import { TaskSelectedAttemptContext } from '@/components/context/taskDetailsContext.ts';
-import { Diff } from 'shared/types';
+import {
+  Diff,
+  DiffStats,
+  LinkedRepoDiff,
+  PathViolation,
+} from 'shared/types';
 import { getHighLightLanguageFromPath } from '@/utils/extToLanguage';
 import { Loader } from '@/components/ui/loader';
 import DiffCard from '@/components/DiffCard';
+import { Input } from '@/components/ui/input';
+import { attemptsApi } from '@/lib/api';
 
 function DiffTab() {
   const { selectedAttempt } = useContext(TaskSelectedAttemptContext);
   const [loading, setLoading] = useState(true);
-  const { diffs, error } = useDiffEntries(selectedAttempt?.id ?? null, true);
+  // Ref the worktree is compared against, the base branch when empty
+  const [against, setAgainst] = useState('');
+  const [againstDraft, setAgainstDraft] = useState('');
+  const { diffs, isConnected, error } = useDiffEntries(
+    selectedAttempt?.id ?? null,
+    true,
+    against || undefined
+  );
+  const [violations, setViolations] = useState<PathViolation[]>([]);
+  const [stats, setStats] = useState<DiffStats | null>(null);
+  const [linkedRepos, setLinkedRepos] = useState<LinkedRepoDiff[]>([]);
 
   useEffect(() => {
     if (diffs.length > 0 && loading) {
@@ -18,6 +35,32 @@ function DiffTab() {
     }
   }, [diffs, loading]);
 
+  // Rechecked as files enter or leave the diff
+  useEffect(() => {
+    if (!selectedAttempt?.id) return;
+    attemptsApi
+      .getPathViolations(selectedAttempt.id)
+      .then(setViolations)
+      .catch(() => setViolations([]));
+    attemptsApi
+      .getDiffStats(selectedAttempt.id)
+      .then(setStats)
+      .catch(() => setStats(null));
+    attemptsApi
+      .getLinkedRepos(selectedAttempt.id)
+      .then(setLinkedRepos)
+      .catch(() => setLinkedRepos([]));
+  }, [selectedAttempt?.id, diffs.length]);
+
+  const violationOf = useCallback(
+    (diffFile: { _oldFileName: string; _newFileName: string }) =>
+      violations.find(
+        (v) =>
+          v.path === diffFile._newFileName || v.path === diffFile._oldFileName
+      ),
+    [violations]
+  );
+
   const createDiffFile = useCallback((diff: Diff) => {
     const oldFileName = diff.oldFile?.fileName || 'old';
     const newFileName = diff.newFile?.fileName || 'new';
@@ -47,7 +90,7 @@ function DiffTab() {
       .filter((diffFile) => diffFile !== null);
   }, [diffs, createDiffFile]);
 
-  if (error) {
+  if (error && !against) {
     return (
       <div className="bg-red-50 border border-red-200 rounded-lg p-4 m-4">
         <div className="text-red-800 text-sm">Failed to load diff: {error}</div>
@@ -55,7 +98,7 @@ function DiffTab() {
     );
   }
 
-  if (loading) {
+  if (loading && !against) {
     return (
       <div className="flex items-center justify-center h-full">
         <Loader />
@@ -65,10 +108,85 @@ function DiffTab() {
 
   return (
     <div className="h-full flex flex-col">
+      <form
+        className="px-4 pt-4"
+        onSubmit={(e) => {
+          e.preventDefault();
+          setAgainst(againstDraft.trim());
+        }}
+      >
+        <Input
+          value={againstDraft}
+          onChange={(e) => setAgainstDraft(e.target.value)}
+          onBlur={() => setAgainst(againstDraft.trim())}
+          placeholder="Compare against a branch, tag or commit (base branch)"
+          className="h-8 font-mono text-sm"
+        />
+      </form>
       <div className="flex-1 overflow-y-auto px-4">
+        {against && error && (
+          <div className="mt-4 text-sm text-destructive">
+            Couldn&apos;t compare against {against}
+          </div>
+        )}
+        {against && !error && !isConnected && diffs.length === 0 && (
+          <div className="mt-4 text-sm text-muted-foreground">
+            No differences from {against}
+          </div>
+        )}
+        {!against && stats && stats.files_changed > 0 && (
+          <div className="mt-4 text-sm text-muted-foreground">
+            <span className="text-green-600">+{stats.additions}</span>{' '}
+            <span className="text-red-500">-{stats.deletions}</span> in{' '}
+            {stats.files_changed} file{stats.files_changed === 1 ? '' : 's'}
+            {stats.primary_language && `, mostly ${stats.primary_language}`}
+          </div>
+        )}
+        {violations.length > 0 && (
+          <div className="mt-4 rounded-lg border border-amber-500/50 p-3 text-sm text-amber-600">
+            {violations.length} file{violations.length === 1 ? '' : 's'}{' '}
+            changed outside the project's path rules
+          </div>
+        )}
         {diffFiles.map((diffFile, idx) => (
-          <DiffCard key={idx} diffFile={diffFile} />
+          <DiffCard
+            key={idx}
+            diffFile={diffFile}
+            violation={violationOf(diffFile)}
+          />
         ))}
+        {!against &&
+          linkedRepos.map((linked) => (
+            <div key={linked.linked_repo.id} className="mt-6">
+              <div className="text-sm font-medium">
+                {linked.project_name}
+                <span className="ml-2 font-normal text-muted-foreground">
+                  from {linked.linked_repo.base_branch}
+                </span>
+                {linked.linked_repo.pr_url && (
+                  <a
+                    href={linked.linked_repo.pr_url}
+                    target="_blank"
+                    rel="noopener noreferrer"
+                    className="ml-2 font-normal underline"
+                  >
+                    PR
+                  </a>
+                )}
+              </div>
+              {linked.diffs.length === 0 && (
+                <div className="mt-2 text-sm text-muted-foreground">
+                  No changes yet
+                </div>
+              )}
+              {linked.diffs
+                .map((diff) => createDiffFile(diff))
+                .map(
+                  (diffFile, idx) =>
+                    diffFile && <DiffCard key={idx} diffFile={diffFile} />
+                )}
+            </div>
+          ))}
       </div>
     </div>
   );
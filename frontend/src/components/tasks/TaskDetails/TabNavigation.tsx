@@ -1,4 +1,4 @@
-import { GitCompare, MessageSquare, Cog } from 'lucide-react';
+import { GitCompare, MessageSquare, Cog, Terminal } from 'lucide-react';
 import { useContext } from 'react';
 import { TaskAttemptDataContext } from '@/components/context/taskDetailsContext.ts';
 import type { TabType } from '@/types/tabs';
@@ -58,6 +58,19 @@ function TabNavigation({ activeTab, setActiveTab }: Props) {
             </span>
           )}
         </button>
+        <button
+          onClick={() => {
+            setActiveTab('terminal');
+          }}
+          className={`flex items-center px-4 py-2 text-sm font-medium border-b-2 transition-colors ${
+            activeTab === 'terminal'
+              ? 'border-primary text-primary bg-background'
+              : 'border-transparent text-muted-foreground hover:text-foreground hover:bg-muted/50'
+          }`}
+        >
+          <Terminal className="h-4 w-4 mr-2" />
+          Terminal
+        </button>
       </div>
     </div>
   );
@@ -0,0 +1,156 @@
+import { useContext, useEffect, useRef, useState } from 'react';
+import { Play, Square } from 'lucide-react';
+import { TaskSelectedAttemptContext } from '@/components/context/taskDetailsContext.ts';
+import { Button } from '@/components/ui/button';
+import { Input } from '@/components/ui/input';
+import { useUserSystem } from '@/components/config-provider';
+import type {
+  TerminalClientMessage,
+  TerminalServerMessage,
+} from 'shared/types';
+
+// Colors and cursor movement aren't rendered, the output is shown as plain text
+// eslint-disable-next-line no-control-regex
+const ANSI_ESCAPE = /\x1b\[[0-9;?]*[ -/]*[@-~]|\x1b\][^\x07]*\x07|\r(?!\n)/g;
+
+function TerminalTab() {
+  const { selectedAttempt } = useContext(TaskSelectedAttemptContext);
+  const { config } = useUserSystem();
+  const [command, setCommand] = useState('');
+  const [input, setInput] = useState('');
+  const [output, setOutput] = useState('');
+  const [running, setRunning] = useState(false);
+  const [error, setError] = useState<string | null>(null);
+  const socketRef = useRef<WebSocket | null>(null);
+  const outputRef = useRef<HTMLPreElement>(null);
+
+  const mode = config?.terminal.mode;
+
+  useEffect(() => {
+    return () => socketRef.current?.close();
+  }, [selectedAttempt?.id]);
+
+  useEffect(() => {
+    outputRef.current?.scrollTo({ top: outputRef.current.scrollHeight });
+  }, [output]);
+
+  const send = (message: TerminalClientMessage) => {
+    socketRef.current?.send(JSON.stringify(message));
+  };
+
+  const start = () => {
+    if (!selectedAttempt) return;
+    socketRef.current?.close();
+    setOutput('');
+    setError(null);
+
+    const params = new URLSearchParams({ cols: '120', rows: '32' });
+    if (command.trim()) params.set('command', command.trim());
+    const protocol = window.location.protocol === 'https:' ? 'wss' : 'ws';
+    const socket = new WebSocket(
+      `${protocol}://${window.location.host}/api/task-attempts/${selectedAttempt.id}/terminal?${params}`
+    );
+    socket.binaryType = 'arraybuffer';
+    const decoder = new TextDecoder();
+
+    socket.onopen = () => setRunning(true);
+    socket.onmessage = (event) => {
+      if (event.data instanceof ArrayBuffer) {
+        const text = decoder.decode(event.data, { stream: true });
+        setOutput((prev) => prev + text.replace(ANSI_ESCAPE, ''));
+        return;
+      }
+      const message: TerminalServerMessage = JSON.parse(event.data);
+      if (message.type === 'exit') {
+        setOutput(
+          (prev) => `${prev}\n[exited with code ${message.code ?? 'unknown'}]\n`
+        );
+      } else {
+        setError(message.message);
+      }
+    };
+    socket.onerror = () =>
+      setError(
+        'Failed to open terminal, check the command is allowed in Settings'
+      );
+    socket.onclose = () => {
+      setRunning(false);
+      if (socketRef.current === socket) socketRef.current = null;
+    };
+    socketRef.current = socket;
+  };
+
+  const stop = () => {
+    socketRef.current?.close();
+  };
+
+  const submitInput = () => {
+    send({ type: 'input', data: `${input}\n` });
+    setInput('');
+  };
+
+  if (mode === 'DISABLED') {
+    return (
+      <div className="flex-1 flex items-center justify-center text-sm text-muted-foreground">
+        The terminal is disabled in Settings
+      </div>
+    );
+  }
+
+  return (
+    <div className="flex-1 flex flex-col min-h-0 p-4 gap-3">
+      <div className="flex gap-2">
+        <Input
+          value={command}
+          onChange={(e) => setCommand(e.target.value)}
+          onKeyDown={(e) => {
+            if (e.key === 'Enter' && !running) start();
+          }}
+          placeholder={
+            mode === 'FULL_SHELL'
+              ? 'Command to run, leave empty for a shell'
+              : 'Command to run, e.g. npm test'
+          }
+          className="font-mono"
+          disabled={running}
+        />
+        {running ? (
+          <Button variant="outline" onClick={stop}>
+            <Square className="h-4 w-4 mr-2" />
+            Stop
+          </Button>
+        ) : (
+          <Button onClick={start} disabled={!selectedAttempt}>
+            <Play className="h-4 w-4 mr-2" />
+            Run
+          </Button>
+        )}
+      </div>
+      {mode === 'ALLOWED_COMMANDS' && config && (
+        <p className="text-xs text-muted-foreground">
+          Allowed: {config.terminal.allowed_commands.join(', ')}
+        </p>
+      )}
+      {error && <p className="text-sm text-destructive">{error}</p>}
+      <pre
+        ref={outputRef}
+        className="flex-1 min-h-0 overflow-auto rounded-md bg-muted p-3 text-xs font-mono whitespace-pre-wrap"
+      >
+        {output}
+      </pre>
+      {running && (
+        <Input
+          value={input}
+          onChange={(e) => setInput(e.target.value)}
+          onKeyDown={(e) => {
+            if (e.key === 'Enter') submitInput();
+          }}
+          placeholder="Input, sent on Enter"
+          className="font-mono"
+        />
+      )}
+    </div>
+  );
+}
+
+export default TerminalTab;
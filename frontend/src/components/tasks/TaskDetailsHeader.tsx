@@ -10,6 +10,7 @@ import {
 } from '@/components/ui/tooltip';
 import type { TaskStatus, TaskWithAttemptStatus } from 'shared/types';
 import { TaskDetailsContext } from '@/components/context/taskDetailsContext.ts';
+import TaskAttachments from '@/components/tasks/TaskAttachments';
 
 interface TaskDetailsHeaderProps {
   onClose: () => void;
@@ -165,6 +166,9 @@ function TaskDetailsHeader({
             )}
           </div>
         </div>
+
+        {/* Attachments */}
+        <TaskAttachments taskId={task.id} />
       </div>
     </div>
   );
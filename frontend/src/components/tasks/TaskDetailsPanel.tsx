@@ -11,6 +11,7 @@ import type { TabType } from '@/types/tabs';
 import DiffTab from '@/components/tasks/TaskDetails/DiffTab.tsx';
 import LogsTab from '@/components/tasks/TaskDetails/LogsTab.tsx';
 import ProcessesTab from '@/components/tasks/TaskDetails/ProcessesTab.tsx';
+import TerminalTab from '@/components/tasks/TaskDetails/TerminalTab.tsx';
 import DeleteFileConfirmationDialog from '@/components/tasks/DeleteFileConfirmationDialog.tsx';
 import TabNavigation from '@/components/tasks/TaskDetails/TabNavigation.tsx';
 import TaskDetailsProvider from '../context/TaskDetailsContextProvider.tsx';
@@ -109,6 +110,8 @@ export function TaskDetailsPanel({
                   <DiffTab />
                 ) : activeTab === 'processes' ? (
                   <ProcessesTab />
+                ) : activeTab === 'terminal' ? (
+                  <TerminalTab />
                 ) : (
                   <LogsTab />
                 )}
@@ -125,15 +125,23 @@ function TaskDetailsToolbar() {
   }, [latestAttempt, branches, selectedBranch]);
 
   const fetchProjectBranches = useCallback(async () => {
-    const result = await projectsApi.getBranches(projectId);
+    const result = await projectsApi.getBranches(projectId, true);
 
     setBranches(result);
-    // Set current branch as default
-    const currentBranch = result.find((b) => b.is_current);
-    if (currentBranch) {
-      setSelectedBranch((prev) => (!prev ? currentBranch.name : prev));
+    // Default to the task's own base branch or tag, else the current branch
+    const defaultBranch =
+      result.find((b) => b.name === task?.base_branch) ??
+      result.find((b) => b.is_current);
+    if (defaultBranch) {
+      setSelectedBranch((prev) => (!prev ? defaultBranch.name : prev));
     }
-  }, [projectId]);
+  }, [projectId, task?.base_branch]);
+
+  // Tags can start attempts but can't be rebased onto or merged into
+  const branchesWithoutTags = useMemo(
+    () => branches.filter((b) => !b.is_tag),
+    [branches]
+  );
 
   useEffect(() => {
     fetchProjectBranches();
@@ -361,7 +369,7 @@ function TaskDetailsToolbar() {
                   creatingPR={ui.creatingPR}
                   handleEnterCreateAttemptMode={handleEnterCreateAttemptMode}
                   handleAttemptSelect={handleAttemptSelect}
-                  branches={branches}
+                  branches={branchesWithoutTags}
                 />
               ) : (
                 <div className="text-center py-8">
@@ -398,7 +406,7 @@ function TaskDetailsToolbar() {
         showCreatePRDialog={ui.showCreatePRDialog}
         setCreatingPR={setCreatingPR}
         setError={setError}
-        branches={branches}
+        branches={branchesWithoutTags}
       />
     </>
   );
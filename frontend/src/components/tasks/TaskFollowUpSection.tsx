@@ -1,9 +1,19 @@
-import { AlertCircle, Send, ChevronDown } from 'lucide-react';
+import {
+  AlertCircle,
+  ArrowDown,
+  ArrowUp,
+  ChevronDown,
+  ListPlus,
+  MessageSquarePlus,
+  Paperclip,
+  Send,
+  X,
+} from 'lucide-react';
 import { Button } from '@/components/ui/button';
 import { Alert, AlertDescription } from '@/components/ui/alert';
 import { FileSearchTextarea } from '@/components/ui/file-search-textarea';
 import { useContext, useEffect, useMemo, useState, useRef } from 'react';
-import { attemptsApi } from '@/lib/api.ts';
+import { attemptsApi, followUpSnippetsApi } from '@/lib/api.ts';
 import {
   TaskAttemptDataContext,
   TaskDetailsContext,
@@ -19,6 +29,11 @@ import {
 } from '@/components/ui/dropdown-menu';
 import { cn } from '@/lib/utils';
 import { useVariantCyclingShortcut } from '@/lib/keyboard-shortcuts';
+import type {
+  ContextFile,
+  FollowUpSnippet,
+  QueuedFollowUp,
+} from 'shared/types';
 
 export function TaskFollowUpSection() {
   const { task, projectId } = useContext(TaskDetailsContext);
@@ -39,6 +54,79 @@ export function TaskFollowUpSection() {
   );
   const [isAnimating, setIsAnimating] = useState(false);
   const variantButtonRef = useRef<HTMLButtonElement>(null);
+  // Follow-ups submitted while the agent runs, sent in order once it's idle
+  const [queue, setQueue] = useState<QueuedFollowUp[]>([]);
+  // Files pasted since the last follow-up, the server mentions them in the next prompt
+  const [contextFiles, setContextFiles] = useState<ContextFile[]>([]);
+  const [snippets, setSnippets] = useState<FollowUpSnippet[]>([]);
+
+  useEffect(() => {
+    if (!projectId) return;
+    followUpSnippetsApi
+      .listForProject(projectId)
+      .then(setSnippets)
+      .catch(() => setSnippets([]));
+  }, [projectId]);
+
+  const insertSnippet = (snippet: FollowUpSnippet) => {
+    setFollowUpMessage((prev) =>
+      prev.trim() ? `${prev.trimEnd()}\n\n${snippet.prompt}` : snippet.prompt
+    );
+  };
+
+  useEffect(() => {
+    setContextFiles([]);
+  }, [selectedAttempt?.id]);
+
+  // A new process shows up when a queued follow-up is sent
+  useEffect(() => {
+    if (!selectedAttempt) return;
+    attemptsApi
+      .getFollowUpQueue(selectedAttempt.id)
+      .then(setQueue)
+      .catch(() => setQueue([]));
+  }, [selectedAttempt, attemptData.processes.length, isAttemptRunning]);
+
+  const moveQueued = async (index: number, offset: number) => {
+    if (!selectedAttempt) return;
+    const ids = queue.map((q) => q.id);
+    const [moved] = ids.splice(index, 1);
+    ids.splice(index + offset, 0, moved);
+    try {
+      setQueue(
+        await attemptsApi.reorderFollowUpQueue(selectedAttempt.id, ids)
+      );
+    } catch (error: unknown) {
+      // @ts-expect-error it is type ApiError
+      setFollowUpError(`Failed to reorder queue: ${error.message}`);
+    }
+  };
+
+  const removeQueued = async (id: string) => {
+    try {
+      await attemptsApi.deleteQueuedFollowUp(id);
+      setQueue((prev) => prev.filter((q) => q.id !== id));
+    } catch (error: unknown) {
+      // @ts-expect-error it is type ApiError
+      setFollowUpError(`Failed to remove queued follow-up: ${error.message}`);
+    }
+  };
+
+  const onPasteFiles = async (files: File[]) => {
+    if (!selectedAttempt) return;
+    try {
+      for (const file of files) {
+        const added = await attemptsApi.uploadContextFile(
+          selectedAttempt.id,
+          file
+        );
+        setContextFiles((prev) => [...prev, added]);
+      }
+    } catch (error: unknown) {
+      // @ts-expect-error it is type ApiError
+      setFollowUpError(`Failed to add pasted file: ${error.message}`);
+    }
+  };
 
   // Get the profile from the selected attempt
   const selectedProfile = selectedAttempt?.profile || null;
@@ -59,6 +147,13 @@ export function TaskFollowUpSection() {
     isAttemptRunning,
     isSendingFollowUp,
   ]);
+  // While the agent runs, follow-ups go into the attempt's queue instead
+  const canQueueFollowUp =
+    !!selectedAttempt &&
+    attemptData.processes.length > 0 &&
+    isAttemptRunning &&
+    !isSendingFollowUp;
+  const canSubmit = canSendFollowUp || canQueueFollowUp;
   const currentProfile = useMemo(() => {
     if (!selectedProfile || !profiles) return null;
     return profiles.find((p) => p.label === selectedProfile);
@@ -83,11 +178,25 @@ export function TaskFollowUpSection() {
     try {
       setIsSendingFollowUp(true);
       setFollowUpError(null);
-      await attemptsApi.followUp(selectedAttempt.id, {
+      const data = {
         prompt: followUpMessage.trim(),
         variant: selectedVariant,
+      };
+      if (canQueueFollowUp) {
+        const queued = await attemptsApi.queueFollowUp(
+          selectedAttempt.id,
+          data
+        );
+        setQueue((prev) => [...prev, queued]);
+        setFollowUpMessage('');
+        return;
+      }
+      await attemptsApi.followUp(selectedAttempt.id, {
+        ...data,
+        snippet_id: null,
       });
       setFollowUpMessage('');
+      setContextFiles([]);
       fetchAttemptData(selectedAttempt.id);
     } catch (error: unknown) {
       // @ts-expect-error it is type ApiError
@@ -107,10 +216,78 @@ export function TaskFollowUpSection() {
               <AlertDescription>{followUpError}</AlertDescription>
             </Alert>
           )}
-          <div className="space-y-2">
+          <div
+            className="space-y-2"
+            onPasteCapture={(e) => {
+              const files = Array.from(e.clipboardData.files);
+              if (files.length > 0 && canSubmit) {
+                e.preventDefault();
+                onPasteFiles(files);
+              }
+            }}
+          >
+            {queue.length > 0 && (
+              <div className="space-y-1">
+                <p className="text-xs text-muted-foreground">
+                  Queued, sent when the agent finishes
+                </p>
+                {queue.map((queued, index) => (
+                  <div
+                    key={queued.id}
+                    className="flex items-center gap-2 rounded border px-2 py-1 text-xs"
+                  >
+                    <span className="flex-1 truncate" title={queued.prompt}>
+                      {index + 1}. {queued.prompt}
+                    </span>
+                    <Button
+                      variant="ghost"
+                      size="icon"
+                      className="h-5 w-5"
+                      disabled={index === 0}
+                      onClick={() => moveQueued(index, -1)}
+                      aria-label="Move up"
+                    >
+                      <ArrowUp className="h-3 w-3" />
+                    </Button>
+                    <Button
+                      variant="ghost"
+                      size="icon"
+                      className="h-5 w-5"
+                      disabled={index === queue.length - 1}
+                      onClick={() => moveQueued(index, 1)}
+                      aria-label="Move down"
+                    >
+                      <ArrowDown className="h-3 w-3" />
+                    </Button>
+                    <Button
+                      variant="ghost"
+                      size="icon"
+                      className="h-5 w-5"
+                      onClick={() => removeQueued(queued.id)}
+                      aria-label="Remove from queue"
+                    >
+                      <X className="h-3 w-3" />
+                    </Button>
+                  </div>
+                ))}
+              </div>
+            )}
+            {contextFiles.length > 0 && (
+              <div className="flex flex-wrap gap-2 text-xs text-muted-foreground">
+                {contextFiles.map((file) => (
+                  <span
+                    key={file.path}
+                    className="flex items-center gap-1 rounded border px-2 py-0.5"
+                  >
+                    <Paperclip className="h-3 w-3" />
+                    {file.path}
+                  </span>
+                ))}
+              </div>
+            )}
             <div className="flex gap-2 items-start">
               <FileSearchTextarea
-                placeholder="Continue working on this task... Type @ to search files."
+                placeholder="Continue working on this task... Type @ to search files, paste to add screenshots or files."
                 value={followUpMessage}
                 onChange={(value) => {
                   setFollowUpMessage(value);
@@ -120,7 +297,7 @@ export function TaskFollowUpSection() {
                   if ((e.metaKey || e.ctrlKey) && e.key === 'Enter') {
                     e.preventDefault();
                     if (
-                      canSendFollowUp &&
+                      canSubmit &&
                       followUpMessage.trim() &&
                       !isSendingFollowUp
                     ) {
@@ -129,12 +306,39 @@ export function TaskFollowUpSection() {
                   }
                 }}
                 className="flex-1 min-h-[40px] resize-none"
-                disabled={!canSendFollowUp}
+                disabled={!canSubmit}
                 projectId={projectId}
                 rows={1}
                 maxRows={6}
               />
 
+              {snippets.length > 0 && (
+                <DropdownMenu>
+                  <DropdownMenuTrigger asChild>
+                    <Button
+                      variant="outline"
+                      size="sm"
+                      className="h-10 px-2"
+                      disabled={!canSubmit}
+                      title="Insert a snippet"
+                    >
+                      <MessageSquarePlus className="h-4 w-4" />
+                    </Button>
+                  </DropdownMenuTrigger>
+                  <DropdownMenuContent>
+                    {snippets.map((snippet) => (
+                      <DropdownMenuItem
+                        key={snippet.id}
+                        onClick={() => insertSnippet(snippet)}
+                        title={snippet.prompt}
+                      >
+                        {snippet.name}
+                      </DropdownMenuItem>
+                    ))}
+                  </DropdownMenuContent>
+                </DropdownMenu>
+              )}
+
               {/* Variant selector */}
               {(() => {
                 const hasVariants =
@@ -205,14 +409,17 @@ export function TaskFollowUpSection() {
               <Button
                 onClick={onSendFollowUp}
                 disabled={
-                  !canSendFollowUp ||
-                  !followUpMessage.trim() ||
-                  isSendingFollowUp
+                  !canSubmit || !followUpMessage.trim() || isSendingFollowUp
                 }
                 size="sm"
               >
                 {isSendingFollowUp ? (
                   <Loader size={16} className="mr-2" />
+                ) : canQueueFollowUp ? (
+                  <>
+                    <ListPlus className="h-4 w-4 mr-2" />
+                    Queue
+                  </>
                 ) : (
                   <>
                     <Send className="h-4 w-4 mr-2" />
@@ -7,7 +7,11 @@ import {
   KanbanProvider,
 } from '@/components/ui/shadcn-io/kanban';
 import { TaskCard } from './TaskCard';
-import type { TaskStatus, TaskWithAttemptStatus } from 'shared/types';
+import type {
+  AgentPresence,
+  TaskStatus,
+  TaskWithAttemptStatus,
+} from 'shared/types';
 import { useNavigate, useParams } from 'react-router-dom';
 import {
   useKeyboardShortcuts,
@@ -24,6 +28,10 @@ interface TaskKanbanBoardProps {
   onDeleteTask: (taskId: string) => void;
   onViewTaskDetails: (task: Task) => void;
   isPanelOpen: boolean;
+  /** Files each task's attempt changed that another task's attempt also changed */
+  conflictingFiles?: Record<string, string[]>;
+  /** The running agent of each task that has one */
+  presence?: Record<string, AgentPresence>;
 }
 
 const allTaskStatuses: TaskStatus[] = [
@@ -58,6 +66,8 @@ function TaskKanbanBoard({
   onDeleteTask,
   onViewTaskDetails,
   isPanelOpen,
+  conflictingFiles,
+  presence,
 }: TaskKanbanBoardProps) {
   const { projectId, taskId } = useParams<{
     projectId: string;
@@ -168,6 +178,8 @@ function TaskKanbanBoard({
                 onViewDetails={onViewTaskDetails}
                 isFocused={focusedTaskId === task.id}
                 tabIndex={focusedTaskId === task.id ? 0 : -1}
+                conflictingFiles={conflictingFiles?.[task.id]}
+                presence={presence?.[task.id]}
               />
             ))}
           </KanbanCards>
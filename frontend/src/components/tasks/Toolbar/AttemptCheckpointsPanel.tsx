@@ -0,0 +1,115 @@
+import { useCallback, useEffect, useState } from 'react';
+import { History, Loader2 } from 'lucide-react';
+import { Button } from '@/components/ui/button';
+import { attemptsApi } from '@/lib/api.ts';
+import type { AttemptCheckpoint, CheckpointReason } from 'shared/types';
+
+type Props = {
+  attemptId: string;
+  isAttemptRunning: boolean;
+};
+
+const REASON_LABELS: Record<CheckpointReason, string> = {
+  before_execution: 'Before a run',
+  periodic: 'While the agent ran',
+  agent_finished: 'After the agent',
+  before_rollback: 'Before a rollback',
+};
+
+// Snapshots of the attempt's worktree, to roll it back to when an agent
+// broke it
+function AttemptCheckpointsPanel({ attemptId, isAttemptRunning }: Props) {
+  const [checkpoints, setCheckpoints] = useState<AttemptCheckpoint[]>([]);
+  const [expanded, setExpanded] = useState(false);
+  const [rollingBack, setRollingBack] = useState<string | null>(null);
+  const [error, setError] = useState<string | null>(null);
+
+  const load = useCallback(() => {
+    attemptsApi
+      .getCheckpoints(attemptId)
+      .then(setCheckpoints)
+      .catch((err) => console.error('Failed to fetch checkpoints:', err));
+  }, [attemptId]);
+
+  useEffect(() => {
+    setCheckpoints([]);
+    setError(null);
+    load();
+  }, [load, isAttemptRunning]);
+
+  const rollback = async (checkpoint: AttemptCheckpoint) => {
+    const when = new Date(checkpoint.created_at).toLocaleString();
+    if (
+      !confirm(
+        `Roll the worktree back to ${when}? Its current state is kept as a checkpoint.`
+      )
+    ) {
+      return;
+    }
+    setRollingBack(checkpoint.id);
+    setError(null);
+    try {
+      await attemptsApi.rollback(attemptId, checkpoint.id);
+      load();
+    } catch (err) {
+      setError(err instanceof Error ? err.message : 'Failed to roll back');
+    } finally {
+      setRollingBack(null);
+    }
+  };
+
+  if (checkpoints.length === 0) return null;
+
+  const shown = expanded ? checkpoints : checkpoints.slice(0, 3);
+
+  return (
+    <div className="space-y-2 rounded-md border p-3">
+      <p className="flex items-center gap-2 text-sm font-medium">
+        <History className="h-4 w-4" />
+        {checkpoints.length} checkpoint{checkpoints.length === 1 ? '' : 's'}
+      </p>
+      <ul className="space-y-1">
+        {shown.map((checkpoint) => (
+          <li
+            key={checkpoint.id}
+            className="flex items-center justify-between gap-2 text-xs"
+          >
+            <span>
+              {REASON_LABELS[checkpoint.reason]}
+              <span className="text-muted-foreground">
+                {' '}
+                · {new Date(checkpoint.created_at).toLocaleString()} ·{' '}
+                <span className="font-mono">
+                  {checkpoint.commit_sha.slice(0, 7)}
+                </span>
+              </span>
+            </span>
+            <Button
+              variant="outline"
+              size="sm"
+              className="h-6 px-2 text-xs"
+              disabled={isAttemptRunning || rollingBack !== null}
+              onClick={() => rollback(checkpoint)}
+            >
+              {rollingBack === checkpoint.id && (
+                <Loader2 className="mr-1 h-3 w-3 animate-spin" />
+              )}
+              Roll back
+            </Button>
+          </li>
+        ))}
+      </ul>
+      {checkpoints.length > 3 && (
+        <button
+          className="text-xs text-muted-foreground hover:underline"
+          onClick={() => setExpanded(!expanded)}
+        >
+          {expanded ? 'Show fewer' : `Show all ${checkpoints.length}`}
+        </button>
+      )}
+      {error && <p className="text-xs text-destructive">{error}</p>}
+    </div>
+  );
+}
+
+export default AttemptCheckpointsPanel;
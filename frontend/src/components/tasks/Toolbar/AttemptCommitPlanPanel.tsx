@@ -0,0 +1,106 @@
+import { useCallback, useEffect, useState } from 'react';
+import { GitCommit, Loader2 } from 'lucide-react';
+import { Button } from '@/components/ui/button';
+import { attemptsApi } from '@/lib/api.ts';
+import type { AttemptCommitPlan, CommitPlanStatus } from 'shared/types';
+
+type Props = {
+  attemptId: string;
+  isAttemptRunning: boolean;
+  isMerged: boolean;
+};
+
+const STATUS_LABELS: Record<CommitPlanStatus, string> = {
+  planning: 'Planning commits...',
+  applied: 'Changes split into commits',
+  failed: 'Splitting into commits failed',
+};
+
+// Has an agent split the attempt's changes into logical commits before they
+// are merged or opened as a pull request
+function AttemptCommitPlanPanel({
+  attemptId,
+  isAttemptRunning,
+  isMerged,
+}: Props) {
+  const [plan, setPlan] = useState<AttemptCommitPlan | null>(null);
+  const [starting, setStarting] = useState(false);
+  const [error, setError] = useState<string | null>(null);
+
+  const load = useCallback(() => {
+    attemptsApi
+      .getCommitPlan(attemptId)
+      .then(setPlan)
+      .catch((err) => console.error('Failed to fetch commit plan:', err));
+  }, [attemptId]);
+
+  useEffect(() => {
+    setPlan(null);
+    setError(null);
+    load();
+  }, [load, isAttemptRunning]);
+
+  const split = async () => {
+    setStarting(true);
+    setError(null);
+    try {
+      setPlan(
+        await attemptsApi.createCommitPlan(attemptId, {
+          profile_variant_label: null,
+        })
+      );
+    } catch (err) {
+      setError(
+        err instanceof Error ? err.message : 'Failed to split into commits'
+      );
+    } finally {
+      setStarting(false);
+    }
+  };
+
+  if (isMerged && !plan) return null;
+
+  return (
+    <div className="space-y-2 rounded-md border p-3">
+      <div className="flex items-center justify-between gap-2">
+        <p className="flex items-center gap-2 text-sm font-medium">
+          <GitCommit className="h-4 w-4" />
+          {plan ? STATUS_LABELS[plan.status] : 'Commits'}
+        </p>
+        {!isMerged && (
+          <Button
+            variant="outline"
+            size="sm"
+            className="h-6 px-2 text-xs"
+            disabled={isAttemptRunning || starting}
+            onClick={split}
+            title="Have the agent group the changes into commits with conventional commit messages"
+          >
+            {starting && <Loader2 className="mr-1 h-3 w-3 animate-spin" />}
+            Split into commits
+          </Button>
+        )}
+      </div>
+      {plan?.status === 'applied' && (
+        <ul className="space-y-1">
+          {plan.commits.map((commit, index) => (
+            <li key={index} className="text-xs">
+              <span className="font-mono">{commit.message}</span>
+              <span className="text-muted-foreground">
+                {' '}
+                · {commit.files.length} file
+                {commit.files.length === 1 ? '' : 's'}
+              </span>
+            </li>
+          ))}
+        </ul>
+      )}
+      {plan?.status === 'failed' && plan.error && (
+        <p className="text-xs text-destructive">{plan.error}</p>
+      )}
+      {error && <p className="text-xs text-destructive">{error}</p>}
+    </div>
+  );
+}
+
+export default AttemptCommitPlanPanel;
@@ -0,0 +1,61 @@
+import { useEffect, useState } from 'react';
+import { MessageSquare } from 'lucide-react';
+import { attemptsApi } from '@/lib/api.ts';
+import type { AttemptReview, ReviewVerdict } from 'shared/types';
+
+type Props = {
+  attemptId: string;
+  isAttemptRunning: boolean;
+};
+
+const verdictLabel: Record<ReviewVerdict, string> = {
+  approved: 'Review approved',
+  changes_requested: 'Reviewer requested changes',
+  failed: 'Review failed',
+};
+
+// Latest review the project's reviewer profile left on the attempt
+function AttemptReviewPanel({ attemptId, isAttemptRunning }: Props) {
+  const [review, setReview] = useState<AttemptReview | null>(null);
+
+  useEffect(() => {
+    setReview(null);
+    attemptsApi
+      .getReviews(attemptId)
+      .then((reviews) => setReview(reviews[0] ?? null))
+      .catch((err) => console.error('Failed to fetch reviews:', err));
+  }, [attemptId, isAttemptRunning]);
+
+  if (!review) return null;
+
+  return (
+    <div className="space-y-2 rounded-md border p-3">
+      <p
+        className={`flex items-center gap-2 text-sm font-medium ${
+          review.verdict === 'approved' ? 'text-green-600' : 'text-destructive'
+        }`}
+      >
+        <MessageSquare className="h-4 w-4" />
+        {verdictLabel[review.verdict]}
+      </p>
+      <p className="text-xs whitespace-pre-wrap">{review.summary}</p>
+      {review.comments.length > 0 && (
+        <ul className="space-y-1">
+          {review.comments.map((comment, index) => (
+            <li key={index} className="text-xs">
+              {comment.file && (
+                <span className="font-mono text-muted-foreground">
+                  {comment.file}
+                  {comment.line !== null && `:${comment.line}`}{' '}
+                </span>
+              )}
+              {comment.comment}
+            </li>
+          ))}
+        </ul>
+      )}
+    </div>
+  );
+}
+
+export default AttemptReviewPanel;
@@ -0,0 +1,72 @@
+import { useEffect, useState } from 'react';
+import { Settings2 } from 'lucide-react';
+import { attemptsApi } from '@/lib/api.ts';
+import type { AttemptToolUsage } from 'shared/types';
+
+type Props = {
+  attemptId: string;
+  isAttemptRunning: boolean;
+};
+
+const formatDuration = (ms: number) =>
+  ms < 1000 ? `${ms}ms` : `${(ms / 1000).toFixed(1)}s`;
+
+// Tools the attempt's coding agent runs called, per tool and MCP server
+function AttemptToolUsagePanel({ attemptId, isAttemptRunning }: Props) {
+  const [usage, setUsage] = useState<AttemptToolUsage | null>(null);
+
+  useEffect(() => {
+    setUsage(null);
+    attemptsApi
+      .getToolUsage(attemptId)
+      .then(setUsage)
+      .catch((err) => console.error('Failed to fetch tool usage:', err));
+  }, [attemptId, isAttemptRunning]);
+
+  if (!usage || usage.total_calls === 0) return null;
+
+  return (
+    <div className="space-y-2 rounded-md border p-3">
+      <p className="flex items-center gap-2 text-sm font-medium">
+        <Settings2 className="h-4 w-4" />
+        {usage.total_calls} tool call{usage.total_calls === 1 ? '' : 's'}
+      </p>
+      <ul className="space-y-1">
+        {usage.tools.map((tool) => (
+          <li
+            key={`${tool.mcp_server ?? ''}/${tool.tool}`}
+            className="flex justify-between gap-2 text-xs"
+          >
+            <span className="font-mono">
+              {tool.mcp_server && (
+                <span className="text-muted-foreground">
+                  {tool.mcp_server}/
+                </span>
+              )}
+              {tool.tool}
+            </span>
+            <span className="text-muted-foreground">
+              {tool.calls}
+              {tool.failures > 0 && (
+                <span className="text-destructive">
+                  {' '}
+                  · {tool.failures} failed
+                </span>
+              )}
+              {tool.unfinished > 0 && ` · ${tool.unfinished} unfinished`}
+              {tool.total_duration_ms !== null &&
+                ` · ${formatDuration(tool.total_duration_ms)}`}
+            </span>
+          </li>
+        ))}
+      </ul>
+      {usage.unused_mcp_servers.length > 0 && (
+        <p className="text-xs text-muted-foreground">
+          Unused MCP servers: {usage.unused_mcp_servers.join(', ')}
+        </p>
+      )}
+    </div>
+  );
+}
+
+export default AttemptToolUsagePanel;
@@ -1,4 +1,10 @@
-import { Dispatch, SetStateAction, useCallback, useContext } from 'react';
+import {
+  Dispatch,
+  SetStateAction,
+  useCallback,
+  useContext,
+  useEffect,
+} from 'react';
 import { Button } from '@/components/ui/button.tsx';
 import { ArrowDown, Settings2, X } from 'lucide-react';
 import {
@@ -8,12 +14,16 @@ import {
   DropdownMenuTrigger,
 } from '@/components/ui/dropdown-menu.tsx';
 import type {
+  CostEstimate,
   ProfileConfig,
   GitBranch,
+  Project,
   ProfileVariantLabel,
+  VariantAgentConfig,
 } from 'shared/types';
 import type { TaskAttempt } from 'shared/types';
-import { attemptsApi } from '@/lib/api.ts';
+import { attemptsApi, projectsApi } from '@/lib/api.ts';
+import { Checkbox } from '@/components/ui/checkbox.tsx';
 import {
   TaskAttemptDataContext,
   TaskDetailsContext,
@@ -29,6 +39,47 @@ import {
   DialogTitle,
 } from '@/components/ui/dialog.tsx';
 import { useState } from 'react';
+import {
+  Select,
+  SelectContent,
+  SelectItem,
+  SelectTrigger,
+  SelectValue,
+} from '@/components/ui/select.tsx';
+import { useUserSystem } from '@/components/config-provider.tsx';
+
+// Variants that only plan, matching CodingAgent::supports_planning
+function supportsPlanning(variant: VariantAgentConfig): boolean {
+  if ('CLAUDE_CODE' in variant) {
+    return (
+      variant.CLAUDE_CODE.plan ||
+      variant.CLAUDE_CODE.permission_mode === 'plan'
+    );
+  }
+  if ('CODEX' in variant) {
+    return variant.CODEX.sandbox === 'read-only';
+  }
+  return false;
+}
+
+const formatTokens = (tokens: number) =>
+  tokens >= 1_000_000
+    ? `${(tokens / 1_000_000).toFixed(1)}M`
+    : `${Math.round(tokens / 1_000)}k`;
+
+// One line summing up what the attempt might cost
+function describeEstimate(estimate: CostEstimate): string {
+  const { input_tokens, cost, model } = estimate;
+  const tokens = `${formatTokens(input_tokens.low)}–${formatTokens(
+    input_tokens.high
+  )} input tokens`;
+  if (!cost) {
+    const on = model ? ` on ${model}` : '';
+    return `About ${tokens}${on}, no price is known for the model`;
+  }
+  const usd = `$${cost.low_usd.toFixed(2)}–$${cost.high_usd.toFixed(2)}`;
+  return `Estimated ${usd} on ${model} (${tokens})`;
+}
 
 type Props = {
   branches: GitBranch[];
@@ -55,7 +106,7 @@ function CreateAttempt({
   setSelectedProfile,
   availableProfiles,
 }: Props) {
-  const { task } = useContext(TaskDetailsContext);
+  const { task, projectId } = useContext(TaskDetailsContext);
   const { isAttemptRunning } = useContext(TaskAttemptDataContext);
 
   const [showCreateAttemptConfirmation, setShowCreateAttemptConfirmation] =
@@ -65,6 +116,71 @@ function CreateAttempt({
     string | undefined
   >(undefined);
 
+  // Planning variant that drafts a plan for approval before the chosen
+  // variant executes it, unless multi-agent pipelines are turned off
+  const { featureFlags } = useUserSystem();
+  const [planVariant, setPlanVariant] = useState<string | null>(null);
+  const profileVariants =
+    availableProfiles?.find((p) => p.label === selectedProfile?.profile)
+      ?.variants ?? [];
+  const planningVariants =
+    featureFlags?.multi_agent_pipelines === false
+      ? []
+      : profileVariants.filter(supportsPlanning);
+  const effectivePlanVariant = planningVariants.some(
+    (v) => v.label === planVariant
+  )
+    ? planVariant
+    : null;
+
+  // Summarize the task's earlier attempts in the new attempt's prompt
+  const [carryOverHistory, setCarryOverHistory] = useState(false);
+
+  // Other projects changed in the same attempt, from their current branch
+  const multiRepo = featureFlags?.multi_repo_attempts === true;
+  const [otherProjects, setOtherProjects] = useState<Project[]>([]);
+  const [linkedProjectIds, setLinkedProjectIds] = useState<string[]>([]);
+  useEffect(() => {
+    if (!multiRepo) return;
+    projectsApi
+      .getAll()
+      .then((projects) =>
+        setOtherProjects(projects.filter((p) => p.id !== projectId))
+      )
+      .catch(() => setOtherProjects([]));
+  }, [multiRepo, projectId]);
+  const toggleLinkedProject = (id: string, checked: boolean) =>
+    setLinkedProjectIds((prev) =>
+      checked ? [...prev, id] : prev.filter((p) => p !== id)
+    );
+
+  // Pre-flight estimate of the attempt's cost with the selected profile
+  const [estimate, setEstimate] = useState<CostEstimate | null>(null);
+  useEffect(() => {
+    setEstimate(null);
+    if (!selectedProfile) return;
+    let cancelled = false;
+    const prompt = task.description
+      ? `${task.title}\n\n${task.description}`
+      : task.title;
+    projectsApi
+      .estimateCost(projectId, {
+        prompt,
+        profile_variant_label: selectedProfile,
+        model: null,
+        repo_tokens: null,
+      })
+      .then((result) => {
+        if (!cancelled) setEstimate(result);
+      })
+      .catch(() => {
+        // The estimate is only a hint, starting works without it
+      });
+    return () => {
+      cancelled = true;
+    };
+  }, [projectId, task.title, task.description, selectedProfile]);
+
   // Create attempt logic
   const actuallyCreateAttempt = useCallback(
     async (profile: ProfileVariantLabel, baseBranch?: string) => {
@@ -78,10 +194,28 @@ function CreateAttempt({
         task_id: task.id,
         profile_variant_label: profile,
         base_branch: effectiveBaseBranch,
+        plan_variant: effectivePlanVariant,
+        carry_over_history: taskAttempts.length > 0 && carryOverHistory,
+        linked_repos: multiRepo
+          ? linkedProjectIds.map((id) => ({
+              project_id: id,
+              base_branch: null,
+            }))
+          : [],
       });
       fetchTaskAttempts();
     },
-    [task.id, selectedProfile, selectedBranch, fetchTaskAttempts]
+    [
+      task.id,
+      selectedProfile,
+      selectedBranch,
+      effectivePlanVariant,
+      carryOverHistory,
+      multiRepo,
+      linkedProjectIds,
+      taskAttempts.length,
+      fetchTaskAttempts,
+    ]
   );
 
   // Handler for Enter key or Start button
@@ -333,6 +467,91 @@ function CreateAttempt({
             </Button>
           </div>
         </div>
+
+        {estimate && (
+          <p className="text-xs text-muted-foreground">
+            {describeEstimate(estimate)}
+          </p>
+        )}
+
+        {planningVariants.length > 0 && (
+          <div className="flex items-center gap-3">
+            <label className="text-xs font-medium text-muted-foreground">
+              Plan first
+            </label>
+            <Select
+              value={effectivePlanVariant ?? 'off'}
+              onValueChange={(value) =>
+                setPlanVariant(value === 'off' ? null : value)
+              }
+            >
+              <SelectTrigger className="h-8 w-40 text-xs">
+                <SelectValue />
+              </SelectTrigger>
+              <SelectContent>
+                <SelectItem value="off">Off</SelectItem>
+                {planningVariants.map((variant) => (
+                  <SelectItem key={variant.label} value={variant.label}>
+                    {variant.label}
+                  </SelectItem>
+                ))}
+              </SelectContent>
+            </Select>
+            {effectivePlanVariant && (
+              <span className="text-xs text-muted-foreground">
+                The plan waits for your approval before{' '}
+                {selectedProfile?.variant || 'the default variant'} executes
+                it.
+              </span>
+            )}
+          </div>
+        )}
+
+        {taskAttempts.length > 0 && (
+          <div className="flex items-center gap-2">
+            <Checkbox
+              id="carry-over-history"
+              checked={carryOverHistory}
+              onCheckedChange={(checked: boolean) =>
+                setCarryOverHistory(checked)
+              }
+            />
+            <label
+              htmlFor="carry-over-history"
+              className="text-xs text-muted-foreground cursor-pointer"
+            >
+              Tell the agent what earlier attempts tried, how they ended and
+              what reviewers said
+            </label>
+          </div>
+        )}
+
+        {multiRepo && otherProjects.length > 0 && (
+          <div className="space-y-1">
+            <label className="text-xs font-medium text-muted-foreground">
+              Also change, from their current branch
+            </label>
+            <div className="flex flex-wrap gap-3">
+              {otherProjects.map((project) => (
+                <div key={project.id} className="flex items-center gap-1.5">
+                  <Checkbox
+                    id={`linked-${project.id}`}
+                    checked={linkedProjectIds.includes(project.id)}
+                    onCheckedChange={(checked: boolean) =>
+                      toggleLinkedProject(project.id, checked)
+                    }
+                  />
+                  <label
+                    htmlFor={`linked-${project.id}`}
+                    className="text-xs cursor-pointer"
+                  >
+                    {project.name}
+                  </label>
+                </div>
+              ))}
+            </div>
+          </div>
+        )}
       </div>
 
       {/* Confirmation Dialog */}
@@ -346,6 +565,7 @@ function CreateAttempt({
             <DialogDescription>
               Are you sure you want to start a new attempt for this task? This
               will create a new session and branch.
+              {estimate && ` ${describeEstimate(estimate)}.`}
             </DialogDescription>
           </DialogHeader>
           <DialogFooter>
@@ -10,6 +10,7 @@ import { Label } from '@radix-ui/react-label';
 import { Textarea } from '@/components/ui/textarea.tsx';
 import { Button } from '@/components/ui/button';
 import { Input } from '@/components/ui/input';
+import { Checkbox } from '@/components/ui/checkbox';
 import {
   Select,
   SelectContent,
@@ -25,7 +26,8 @@ import {
 import { attemptsApi } from '@/lib/api.ts';
 import { ProvidePatDialog } from '@/components/ProvidePatDialog';
 import { GitHubLoginDialog } from '@/components/GitHubLoginDialog';
-import { GitBranch, GitHubServiceError } from 'shared/types';
+import SecretFindings from '@/components/tasks/Toolbar/SecretFindings.tsx';
+import { GitBranch, GitHubServiceError, SecretFinding } from 'shared/types';
 
 type Props = {
   showCreatePRDialog: boolean;
@@ -51,9 +53,11 @@ function CreatePrDialog({
   const [prBaseBranch, setPrBaseBranch] = useState(
     selectedAttempt?.base_branch || 'main'
   );
+  const [pushToFork, setPushToFork] = useState(false);
   const [showPatDialog, setShowPatDialog] = useState(false);
   const [patDialogError, setPatDialogError] = useState<string | null>(null);
   const [showGitHubLoginDialog, setShowGitHubLoginDialog] = useState(false);
+  const [secretFindings, setSecretFindings] = useState<SecretFinding[]>([]);
 
   useEffect(() => {
     if (showCreatePRDialog) {
@@ -63,6 +67,16 @@ function CreatePrDialog({
     // eslint-disable-next-line react-hooks/exhaustive-deps
   }, [showCreatePRDialog]);
 
+  // Findings are shown up front, creating the PR then overrides the scan
+  useEffect(() => {
+    setSecretFindings([]);
+    if (!showCreatePRDialog || !selectedAttempt?.id) return;
+    attemptsApi
+      .getSecretScan(selectedAttempt.id)
+      .then((scan) => setSecretFindings(scan.findings))
+      .catch(() => setSecretFindings([]));
+  }, [showCreatePRDialog, selectedAttempt?.id]);
+
   // Update PR base branch when selected attempt changes
   useEffect(() => {
     if (selectedAttempt?.base_branch) {
@@ -75,11 +89,17 @@ function CreatePrDialog({
 
     setCreatingPR(true);
 
-    const result = await attemptsApi.createPR(selectedAttempt.id, {
-      title: prTitle,
-      body: prBody || null,
-      base_branch: prBaseBranch || null,
-    });
+    const result = await attemptsApi.createPR(
+      selectedAttempt.id,
+      {
+        title: prTitle,
+        body: prBody || null,
+        base_branch: prBaseBranch || null,
+        // Unchecked, the server still uses the fork without push access
+        push_to_fork: pushToFork ? true : null,
+      },
+      secretFindings.length > 0
+    );
 
     if (result.success) {
       window.open(result.data, '_blank');
@@ -88,6 +108,7 @@ function CreatePrDialog({
       setPrTitle('');
       setPrBody('');
       setPrBaseBranch(selectedAttempt?.base_branch || 'main');
+      setPushToFork(false);
     } else {
       if (result.error) {
         setShowCreatePRDialog(false);
@@ -120,6 +141,8 @@ function CreatePrDialog({
     prBaseBranch,
     prBody,
     prTitle,
+    pushToFork,
+    secretFindings,
     setCreatingPR,
     setError,
     setShowCreatePRDialog,
@@ -134,6 +157,7 @@ function CreatePrDialog({
     setPrTitle('');
     setPrBody('');
     setPrBaseBranch('main');
+    setPushToFork(false);
   }, [setShowCreatePRDialog]);
 
   return (
@@ -194,6 +218,26 @@ function CreatePrDialog({
                 </SelectContent>
               </Select>
             </div>
+            <div className="space-y-1">
+              <div className="flex items-center gap-2">
+                <Checkbox
+                  id="pr-push-to-fork"
+                  checked={pushToFork}
+                  onCheckedChange={(checked) => setPushToFork(checked === true)}
+                />
+                <Label htmlFor="pr-push-to-fork" className="text-sm">
+                  Push to my fork
+                </Label>
+              </div>
+              <p className="text-xs text-muted-foreground">
+                Opens the pull request from your fork of the repository, which
+                is created if needed. Done automatically when you can&apos;t
+                push to the repository.
+              </p>
+            </div>
+            {secretFindings.length > 0 && (
+              <SecretFindings findings={secretFindings} />
+            )}
           </div>
           <DialogFooter>
             <Button variant="outline" onClick={handleCancelCreatePR}>
@@ -204,7 +248,11 @@ function CreatePrDialog({
               disabled={creatingPR || !prTitle.trim()}
               className="bg-blue-600 hover:bg-blue-700"
             >
-              {creatingPR ? 'Creating...' : 'Create PR'}
+              {creatingPR
+                ? 'Creating...'
+                : secretFindings.length > 0
+                  ? 'Create PR Anyway'
+                  : 'Create PR'}
             </Button>
           </DialogFooter>
         </DialogContent>
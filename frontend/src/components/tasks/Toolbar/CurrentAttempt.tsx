@@ -1,13 +1,18 @@
 import {
   Check,
+  Download,
   ExternalLink,
   GitBranch as GitBranchIcon,
   GitPullRequest,
   History,
+  LifeBuoy,
+  ListOrdered,
+  Pause,
   Play,
   Plus,
   RefreshCw,
   Settings,
+  Share2,
   StopCircle,
 } from 'lucide-react';
 import {
@@ -32,7 +37,20 @@ import {
   DialogTitle,
 } from '@/components/ui/dialog.tsx';
 import BranchSelector from '@/components/tasks/BranchSelector.tsx';
-import { attemptsApi, executionProcessesApi } from '@/lib/api.ts';
+import PlanApproval from '@/components/tasks/Toolbar/PlanApproval.tsx';
+import AttemptReviewPanel from '@/components/tasks/Toolbar/AttemptReviewPanel.tsx';
+import AttemptCommitPlanPanel from '@/components/tasks/Toolbar/AttemptCommitPlanPanel.tsx';
+import AttemptToolUsagePanel from '@/components/tasks/Toolbar/AttemptToolUsagePanel.tsx';
+import AttemptCheckpointsPanel from '@/components/tasks/Toolbar/AttemptCheckpointsPanel.tsx';
+import SecretFindings from '@/components/tasks/Toolbar/SecretFindings.tsx';
+import ShareAttemptDialog from '@/components/tasks/Toolbar/ShareAttemptDialog.tsx';
+import {
+  ApiError,
+  attemptsApi,
+  debugApi,
+  executionProcessesApi,
+} from '@/lib/api.ts';
+import { downloadBlob } from '@/lib/utils.ts';
 import {
   Dispatch,
   SetStateAction,
@@ -42,7 +60,18 @@ import {
   useMemo,
   useState,
 } from 'react';
-import type { ExecutionProcess } from 'shared/types';
+import type {
+  AttemptBranchSync,
+  AttemptChecks,
+  AttemptRuntime,
+  ChecksState,
+  DevServerInfo,
+  ExecutionProcess,
+  MergeQueuePosition,
+  SecretFinding,
+  SuspendedExecution,
+  TestRun,
+} from 'shared/types';
 import type { BranchStatus, GitBranch, TaskAttempt } from 'shared/types';
 import {
   TaskAttemptDataContext,
@@ -52,6 +81,21 @@ import {
 import { useConfig } from '@/components/config-provider.tsx';
 import { useKeyboardShortcuts } from '@/lib/keyboard-shortcuts.ts';
 
+const checksLabels: Record<ChecksState, string> = {
+  not_pushed: 'Checks: branch not pushed',
+  outdated: 'Checks: push latest commits',
+  pending: 'Checks running',
+  passed: 'Checks passed',
+  failed: 'Checks failed',
+};
+
+const formatDuration = (ms: number) => {
+  const minutes = Math.floor(ms / 60000);
+  if (minutes < 1) return `${Math.floor(ms / 1000)}s`;
+  if (minutes < 60) return `${minutes}m`;
+  return `${Math.floor(minutes / 60)}h ${minutes % 60}m`;
+};
+
 // Helper function to get the display name for different editor types
 function getEditorDisplayName(editorType: string): string {
   switch (editorType) {
@@ -106,15 +150,32 @@ function CurrentAttempt({
   const [isStartingDevServer, setIsStartingDevServer] = useState(false);
   const [merging, setMerging] = useState(false);
   const [rebasing, setRebasing] = useState(false);
+  const [unmerging, setUnmerging] = useState(false);
   const [devServerDetails, setDevServerDetails] =
     useState<ExecutionProcess | null>(null);
   const [isHoveringDevServer, setIsHoveringDevServer] = useState(false);
   const [branchStatus, setBranchStatus] = useState<BranchStatus | null>(null);
   const [branchStatusLoading, setBranchStatusLoading] = useState(false);
   const [showRebaseDialog, setShowRebaseDialog] = useState(false);
+  const [showShareDialog, setShowShareDialog] = useState(false);
   const [selectedRebaseBranch, setSelectedRebaseBranch] = useState<string>('');
   const [showStopConfirmation, setShowStopConfirmation] = useState(false);
   const [copied, setCopied] = useState(false);
+  const [devServerInfo, setDevServerInfo] = useState<DevServerInfo | null>(
+    null
+  );
+  const [runtime, setRuntime] = useState<AttemptRuntime | null>(null);
+  const [isPausing, setIsPausing] = useState(false);
+  const [suspended, setSuspended] = useState<SuspendedExecution | null>(null);
+  const [isResumingSession, setIsResumingSession] = useState(false);
+  const [latestTestRun, setLatestTestRun] = useState<TestRun | null>(null);
+  const [branchSync, setBranchSync] = useState<AttemptBranchSync | null>(null);
+  const [mergeQueue, setMergeQueue] = useState<MergeQueuePosition | null>(
+    null
+  );
+  const [queueingMerge, setQueueingMerge] = useState(false);
+  const [checks, setChecks] = useState<AttemptChecks | null>(null);
+  const [secretFindings, setSecretFindings] = useState<SecretFinding[]>([]);
 
   const processedDevServerLogs = useMemo(() => {
     if (!devServerDetails) return 'No output yet...';
@@ -156,6 +217,186 @@ function CurrentAttempt({
     return () => clearInterval(interval);
   }, [isHoveringDevServer, runningDevServer, fetchDevServerDetails]);
 
+  // Ports show up in the dev server output a little after it starts
+  useEffect(() => {
+    if (!runningDevServer || !selectedAttempt) {
+      setDevServerInfo(null);
+      return;
+    }
+
+    const fetchDevServerInfo = async () => {
+      try {
+        setDevServerInfo(await attemptsApi.getDevServer(selectedAttempt.id));
+      } catch (err) {
+        console.error('Failed to fetch dev server URLs:', err);
+      }
+    };
+    fetchDevServerInfo();
+    const interval = setInterval(fetchDevServerInfo, 3000);
+    return () => clearInterval(interval);
+  }, [runningDevServer?.id, selectedAttempt?.id]);
+
+  const fetchRuntime = useCallback(async () => {
+    if (!selectedAttempt) return;
+    try {
+      setRuntime(await attemptsApi.getRuntime(selectedAttempt.id));
+    } catch (err) {
+      console.error('Failed to fetch attempt runtime:', err);
+    }
+  }, [selectedAttempt?.id]);
+
+  // A session cut off by a shutdown or reboot, resumable once nothing runs
+  useEffect(() => {
+    if (!selectedAttempt || isAttemptRunning) {
+      setSuspended(null);
+      return;
+    }
+    attemptsApi
+      .getSuspended(selectedAttempt.id)
+      .then(setSuspended)
+      .catch((err) => console.error('Failed to fetch suspended session:', err));
+  }, [selectedAttempt?.id, isAttemptRunning]);
+
+  const resumeSession = async () => {
+    if (!selectedAttempt) return;
+    setIsResumingSession(true);
+    try {
+      await attemptsApi.resume(selectedAttempt.id);
+      setSuspended(null);
+      fetchAttemptData(selectedAttempt.id);
+    } catch (err) {
+      setError(
+        err instanceof Error ? err.message : 'Failed to resume the session'
+      );
+    } finally {
+      setIsResumingSession(false);
+    }
+  };
+
+  useEffect(() => {
+    fetchRuntime();
+    if (!isAttemptRunning) return;
+    const interval = setInterval(fetchRuntime, 5000);
+    return () => clearInterval(interval);
+  }, [fetchRuntime, isAttemptRunning]);
+
+  // The test stage reports once the chain finishes
+  useEffect(() => {
+    setLatestTestRun(null);
+    if (!selectedAttempt || isAttemptRunning) return;
+    attemptsApi
+      .getTestRuns(selectedAttempt.id)
+      .then((runs) => setLatestTestRun(runs[0] ?? null))
+      .catch((err) => console.error('Failed to fetch test runs:', err));
+  }, [selectedAttempt?.id, isAttemptRunning]);
+
+  // Automatic syncs with the base branch run in the background
+  useEffect(() => {
+    setBranchSync(null);
+    if (!selectedAttempt) return;
+    attemptsApi
+      .getBranchSync(selectedAttempt.id)
+      .then(setBranchSync)
+      .catch((err) => console.error('Failed to fetch branch sync:', err));
+  }, [selectedAttempt?.id, branchStatus?.commits_behind]);
+
+  // Merges wait for the pushed branch's GitHub checks when configured, poll
+  // while they run
+  const requirePassingChecks = Boolean(config?.require_passing_checks);
+  useEffect(() => {
+    setChecks(null);
+    if (!selectedAttempt || !requirePassingChecks) return;
+    let cancelled = false;
+    const fetchChecks = () =>
+      attemptsApi
+        .getChecks(selectedAttempt.id)
+        .then((result) => !cancelled && setChecks(result))
+        .catch((err) => console.error('Failed to fetch checks:', err));
+    fetchChecks();
+    const interval = setInterval(fetchChecks, 15000);
+    return () => {
+      cancelled = true;
+      clearInterval(interval);
+    };
+  }, [selectedAttempt?.id, requirePassingChecks, branchStatus?.commits_ahead]);
+
+  // Queued merges land in the background, poll until the attempt leaves the queue
+  const isMergeQueued = mergeQueue?.position != null;
+  useEffect(() => {
+    setMergeQueue(null);
+    if (!selectedAttempt) return;
+    attemptsApi
+      .getMergeQueue(selectedAttempt.id)
+      .then(setMergeQueue)
+      .catch((err) => console.error('Failed to fetch merge queue:', err));
+  }, [selectedAttempt?.id]);
+
+  useEffect(() => {
+    if (!selectedAttempt || !isMergeQueued) return;
+    const interval = setInterval(async () => {
+      try {
+        const result = await attemptsApi.getMergeQueue(selectedAttempt.id);
+        setMergeQueue(result);
+        if (result.position == null) fetchBranchStatus();
+      } catch (err) {
+        console.error('Failed to fetch merge queue:', err);
+      }
+    }, 5000);
+    return () => clearInterval(interval);
+  }, [selectedAttempt?.id, isMergeQueued]);
+
+  const handleQueueMergeClick = async () => {
+    if (!selectedAttempt) return;
+    try {
+      setQueueingMerge(true);
+      if (isMergeQueued) {
+        await attemptsApi.dequeueMerge(selectedAttempt.id);
+        setMergeQueue(await attemptsApi.getMergeQueue(selectedAttempt.id));
+      } else {
+        setMergeQueue(await attemptsApi.enqueueMerge(selectedAttempt.id));
+      }
+    } catch (err) {
+      setError(
+        err instanceof Error ? err.message : 'Failed to update merge queue'
+      );
+    } finally {
+      setQueueingMerge(false);
+    }
+  };
+
+  const togglePause = async () => {
+    if (!selectedAttempt || !runtime) return;
+    setIsPausing(true);
+    try {
+      if (runtime.paused) {
+        await attemptsApi.resume(selectedAttempt.id);
+      } else {
+        await attemptsApi.pause(selectedAttempt.id);
+      }
+      await fetchRuntime();
+    } catch (err) {
+      setError(
+        err instanceof Error ? err.message : 'Failed to pause the attempt'
+      );
+    } finally {
+      setIsPausing(false);
+    }
+  };
+
+  const downloadSupportBundle = async (attemptId: string) => {
+    try {
+      const bundle = await debugApi.createSupportBundle({
+        attempt_id: attemptId,
+        server_log_tail: null,
+      });
+      downloadBlob(bundle, `support-${attemptId}.zip`);
+    } catch (err) {
+      setError(
+        err instanceof Error ? err.message : 'Failed to create the bundle'
+      );
+    }
+  };
+
   const startDevServer = async () => {
     if (!task || !selectedAttempt) return;
 
@@ -232,6 +473,17 @@ function CurrentAttempt({
   const handleMergeClick = async () => {
     if (!projectId || !selectedAttempt?.id || !selectedAttempt?.task_id) return;
 
+    try {
+      const scan = await attemptsApi.getSecretScan(selectedAttempt.id);
+      if (scan.findings.length > 0) {
+        setSecretFindings(scan.findings);
+        return;
+      }
+    } catch (err) {
+      setError(err instanceof Error ? err.message : 'Failed to scan changes');
+      return;
+    }
+
     // Directly perform merge without checking branch status
     await performMerge();
   };
@@ -260,12 +512,12 @@ function CurrentAttempt({
     }
   }, [selectedAttempt, fetchBranchStatus]);
 
-  const performMerge = async () => {
+  const performMerge = async (allowSecrets = false) => {
     if (!projectId || !selectedAttempt?.id || !selectedAttempt?.task_id) return;
 
     try {
       setMerging(true);
-      await attemptsApi.merge(selectedAttempt.id);
+      await attemptsApi.merge(selectedAttempt.id, allowSecrets);
       // Refetch branch status to show updated state
       fetchBranchStatus();
     } catch (error) {
@@ -277,6 +529,38 @@ function CurrentAttempt({
     }
   };
 
+  // Reverts the merge on the base branch, asking again when commits landed on
+  // top of it since
+  const handleUnmergeClick = async () => {
+    if (!selectedAttempt?.id) return;
+    if (
+      !confirm(
+        `Revert the merge of this attempt on ${selectedAttempt.base_branch}?`
+      )
+    ) {
+      return;
+    }
+
+    try {
+      setUnmerging(true);
+      try {
+        await attemptsApi.unmerge(selectedAttempt.id);
+      } catch (err) {
+        if (
+          !(err instanceof ApiError && err.code === 'unmerge_needs_confirmation')
+        )
+          throw err;
+        if (!confirm(err.message)) return;
+        await attemptsApi.unmerge(selectedAttempt.id, true);
+      }
+      fetchBranchStatus();
+    } catch (err) {
+      setError(err instanceof Error ? err.message : 'Failed to revert merge');
+    } finally {
+      setUnmerging(false);
+    }
+  };
+
   const handleRebaseClick = async () => {
     if (!projectId || !selectedAttempt?.id || !selectedAttempt?.task_id) return;
 
@@ -414,6 +698,52 @@ function CurrentAttempt({
               {branchStatus?.base_branch_name || selectedBranchDisplayName}
             </span>
           </div>
+          {(branchSync?.status === 'conflicts' ||
+            branchSync?.status === 'failed') && (
+            <div
+              className="text-xs text-red-500 mt-1"
+              title={
+                branchSync.status === 'conflicts'
+                  ? branchSync.conflicted_files.join('\n')
+                  : (branchSync.error ?? undefined)
+              }
+            >
+              {branchSync.status === 'conflicts'
+                ? `Sync conflicts in ${branchSync.conflicted_files.length} file(s)`
+                : 'Automatic sync failed'}
+            </div>
+          )}
+          {checks && (
+            <div
+              className={`text-xs mt-1 ${
+                checks.state === 'failed'
+                  ? 'text-red-500'
+                  : checks.state === 'passed'
+                    ? 'text-green-600'
+                    : 'text-muted-foreground'
+              }`}
+              title={checks.checks
+                .map((check) => `${check.name}: ${check.state}`)
+                .join('\n')}
+            >
+              {checksLabels[checks.state]}
+            </div>
+          )}
+          {mergeQueue?.entry && isMergeQueued && (
+            <div className="text-xs text-muted-foreground mt-1">
+              {mergeQueue.entry.status === 'queued'
+                ? `Merge queue: position ${(mergeQueue.position ?? 0) + 1}`
+                : `Merge queue: ${mergeQueue.entry.status}`}
+            </div>
+          )}
+          {mergeQueue?.entry?.status === 'failed' && (
+            <div
+              className="text-xs text-red-500 mt-1"
+              title={mergeQueue.entry.error ?? undefined}
+            >
+              Queued merge failed
+            </div>
+          )}
         </div>
 
         <div>
@@ -430,6 +760,17 @@ function CurrentAttempt({
                 <span className="text-xs font-mono text-muted-foreground">
                   ({selectedAttempt.merge_commit.slice(0, 8)})
                 </span>
+                {selectedAttempt.pr_status !== 'merged' && (
+                  <Button
+                    variant="outline"
+                    size="sm"
+                    className="h-6 px-2 text-xs"
+                    disabled={unmerging}
+                    onClick={handleUnmergeClick}
+                  >
+                    {unmerging ? 'Reverting...' : 'Undo merge'}
+                  </Button>
+                )}
               </div>
             ) : (
               <div className="flex items-center gap-1.5">
@@ -536,6 +877,44 @@ function CurrentAttempt({
               </TooltipContent>
             </Tooltip>
           </TooltipProvider>
+          {devServerInfo?.urls.map((devServerUrl) => (
+            <Button
+              key={devServerUrl.port}
+              variant="outline"
+              size="sm"
+              className="gap-1"
+              disabled={!devServerUrl.listening}
+              title={
+                devServerUrl.listening
+                  ? devServerUrl.url
+                  : `Nothing is listening on port ${devServerUrl.port} yet`
+              }
+              onClick={() =>
+                window.open(devServerUrl.url, '_blank', 'noopener,noreferrer')
+              }
+            >
+              <ExternalLink className="h-3 w-3" />:{devServerUrl.port}
+            </Button>
+          ))}
+          {devServerInfo?.urls.some((devServerUrl) => devServerUrl.listening) &&
+            selectedAttempt && (
+              <Button
+                variant="outline"
+                size="sm"
+                className="gap-1"
+                title="Open the dev server through Vibe Kanban, for when its port isn't reachable"
+                onClick={() =>
+                  window.open(
+                    `/preview/${selectedAttempt.id}/`,
+                    '_blank',
+                    'noopener,noreferrer'
+                  )
+                }
+              >
+                <ExternalLink className="h-3 w-3" />
+                Preview
+              </Button>
+            )}
         </div>
 
         <div className="flex items-center gap-2 flex-wrap">
@@ -580,6 +959,84 @@ function CurrentAttempt({
             </DropdownMenu>
           )}
 
+          {selectedAttempt && (
+            <TooltipProvider>
+              <Tooltip>
+                <TooltipTrigger asChild>
+                  <Button variant="outline" size="sm" className="gap-2" asChild>
+                    <a
+                      href={`/api/task-attempts/${selectedAttempt.id}/logs/raw.gz`}
+                      download
+                    >
+                      <Download className="h-4 w-4" />
+                      Logs
+                    </a>
+                  </Button>
+                </TooltipTrigger>
+                <TooltipContent>
+                  <p>Download the full log of this attempt (gzipped)</p>
+                </TooltipContent>
+              </Tooltip>
+              <Tooltip>
+                <TooltipTrigger asChild>
+                  <Button variant="outline" size="sm" className="gap-2" asChild>
+                    <a
+                      href={`/api/task-attempts/${selectedAttempt.id}/logs/export`}
+                      download
+                    >
+                      <Download className="h-4 w-4" />
+                      JSONL
+                    </a>
+                  </Button>
+                </TooltipTrigger>
+                <TooltipContent>
+                  <p>
+                    Download the agent conversation entries as JSON Lines, filter
+                    with ?types= and ?since=
+                  </p>
+                </TooltipContent>
+              </Tooltip>
+              <Tooltip>
+                <TooltipTrigger asChild>
+                  <Button
+                    variant="outline"
+                    size="sm"
+                    className="gap-2"
+                    onClick={() => downloadSupportBundle(selectedAttempt.id)}
+                  >
+                    <LifeBuoy className="h-4 w-4" />
+                    Bundle
+                  </Button>
+                </TooltipTrigger>
+                <TooltipContent>
+                  <p>
+                    Download a support bundle with this attempt&apos;s logs, the
+                    config and recent server logs, secrets redacted
+                  </p>
+                </TooltipContent>
+              </Tooltip>
+              <Tooltip>
+                <TooltipTrigger asChild>
+                  <Button
+                    variant="outline"
+                    size="sm"
+                    className="gap-2"
+                    onClick={() => setShowShareDialog(true)}
+                  >
+                    <Share2 className="h-4 w-4" />
+                    Share
+                  </Button>
+                </TooltipTrigger>
+                <TooltipContent>
+                  <p>
+                    Create a read-only link to this attempt&apos;s conversation
+                    and diff
+                  </p>
+                </TooltipContent>
+              </Tooltip>
+            </TooltipProvider>
+          )}
+
           {/* Git Operations */}
           {selectedAttempt && branchStatus && (
             <>
@@ -623,6 +1080,7 @@ function CurrentAttempt({
                       onClick={handleMergeClick}
                       disabled={
                         merging ||
+                        (requirePassingChecks && checks?.state !== 'passed') ||
                         Boolean(branchStatus.is_behind) ||
                         isAttemptRunning
                       }
@@ -632,12 +1090,87 @@ function CurrentAttempt({
                       <GitBranchIcon className="h-3 w-3" />
                       {merging ? 'Merging...' : 'Merge'}
                     </Button>
+                    <Button
+                      onClick={handleQueueMergeClick}
+                      disabled={
+                        queueingMerge ||
+                        isAttemptRunning ||
+                        (isMergeQueued &&
+                          mergeQueue?.entry?.status !== 'queued')
+                      }
+                      variant="outline"
+                      size="sm"
+                      className="gap-1"
+                      title="Rebase and verify against the latest base branch, then merge once earlier queued attempts landed"
+                    >
+                      <ListOrdered className="h-3 w-3" />
+                      {isMergeQueued ? 'Leave Queue' : 'Queue Merge'}
+                    </Button>
                   </>
                 )
               }
             </>
           )}
 
+          {runtime && runtime.running_ms + runtime.paused_ms > 0 && (
+            <span
+              className="text-xs text-muted-foreground"
+              title="Time the attempt's executions ran, and spent paused"
+            >
+              {formatDuration(runtime.running_ms)}
+              {runtime.paused_ms > 0 &&
+                `, paused ${formatDuration(runtime.paused_ms)}`}
+            </span>
+          )}
+          {latestTestRun && (
+            <span
+              className={`text-xs ${latestTestRun.failed > 0 ? 'text-red-500' : 'text-green-600'}`}
+              title={latestTestRun.failed_tests.join('\n') || undefined}
+            >
+              Tests: {latestTestRun.passed} passed
+              {latestTestRun.failed > 0 && `, ${latestTestRun.failed} failed`}
+              {latestTestRun.skipped > 0 &&
+                `, ${latestTestRun.skipped} skipped`}
+            </span>
+          )}
+          {isAttemptRunning && !isStopping && runtime && (
+            <Button
+              variant="outline"
+              size="sm"
+              onClick={togglePause}
+              disabled={isPausing}
+              className="gap-2"
+              title={
+                runtime.paused
+                  ? 'Continue the agent where it left off'
+                  : 'Suspend the agent to free the CPU, without losing its progress'
+              }
+            >
+              {runtime.paused ? (
+                <Play className="h-4 w-4" />
+              ) : (
+                <Pause className="h-4 w-4" />
+              )}
+              {runtime.paused ? 'Resume' : 'Pause'}
+            </Button>
+          )}
+          {!isAttemptRunning && suspended?.session_id && (
+            <Button
+              variant="outline"
+              size="sm"
+              onClick={resumeSession}
+              disabled={isResumingSession}
+              className="gap-2"
+              title={
+                suspended.reason === 'crash'
+                  ? 'The agent was cut off when the server or machine stopped, continue its session'
+                  : 'The agent was stopped when the server shut down, continue its session'
+              }
+            >
+              <Play className="h-4 w-4" />
+              {isResumingSession ? 'Resuming...' : 'Resume Session'}
+            </Button>
+          )}
           {isStopping || isAttemptRunning ? (
             <Button
               variant="destructive"
@@ -663,6 +1196,33 @@ function CurrentAttempt({
         </div>
       </div>
 
+      <PlanApproval
+        attemptId={selectedAttempt.id}
+        isAttemptRunning={isAttemptRunning}
+        onApproved={() => fetchAttemptData(selectedAttempt.id)}
+      />
+
+      <AttemptReviewPanel
+        attemptId={selectedAttempt.id}
+        isAttemptRunning={isAttemptRunning}
+      />
+
+      <AttemptCommitPlanPanel
+        attemptId={selectedAttempt.id}
+        isAttemptRunning={isAttemptRunning}
+        isMerged={!!selectedAttempt.merge_commit}
+      />
+
+      <AttemptToolUsagePanel
+        attemptId={selectedAttempt.id}
+        isAttemptRunning={isAttemptRunning}
+      />
+
+      <AttemptCheckpointsPanel
+        attemptId={selectedAttempt.id}
+        isAttemptRunning={isAttemptRunning}
+      />
+
       {/* Rebase Dialog */}
       <Dialog open={showRebaseDialog} onOpenChange={setShowRebaseDialog}>
         <DialogContent className="sm:max-w-md">
@@ -706,6 +1266,46 @@ function CurrentAttempt({
         </DialogContent>
       </Dialog>
 
+      {selectedAttempt && (
+        <ShareAttemptDialog
+          attemptId={selectedAttempt.id}
+          open={showShareDialog}
+          onOpenChange={setShowShareDialog}
+        />
+      )}
+
+      {/* Secret Scan Findings Dialog */}
+      <Dialog
+        open={secretFindings.length > 0}
+        onOpenChange={(open) => !open && setSecretFindings([])}
+      >
+        <DialogContent className="sm:max-w-lg">
+          <DialogHeader>
+            <DialogTitle>Merge With Possible Secrets?</DialogTitle>
+            <DialogDescription>
+              The secret scan flagged lines this attempt adds. Merging will
+              commit them to {selectedAttempt.base_branch}.
+            </DialogDescription>
+          </DialogHeader>
+          <SecretFindings findings={secretFindings} />
+          <DialogFooter>
+            <Button variant="outline" onClick={() => setSecretFindings([])}>
+              Cancel
+            </Button>
+            <Button
+              variant="destructive"
+              onClick={async () => {
+                setSecretFindings([]);
+                await performMerge(true);
+              }}
+              disabled={merging}
+            >
+              Merge Anyway
+            </Button>
+          </DialogFooter>
+        </DialogContent>
+      </Dialog>
+
       {/* Stop Execution Confirmation Dialog */}
       <Dialog
         open={showStopConfirmation}
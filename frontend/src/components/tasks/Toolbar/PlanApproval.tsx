@@ -0,0 +1,110 @@
+import { useEffect, useState } from 'react';
+import { ClipboardList } from 'lucide-react';
+import { Button } from '@/components/ui/button.tsx';
+import { Textarea } from '@/components/ui/textarea.tsx';
+import { attemptsApi } from '@/lib/api.ts';
+import type { AttemptPlan } from 'shared/types';
+
+type Props = {
+  attemptId: string;
+  isAttemptRunning: boolean;
+  onApproved: () => void;
+};
+
+// Plan drafted by the planning phase, approved (possibly edited) or rejected
+// before the execution phase runs
+function PlanApproval({ attemptId, isAttemptRunning, onApproved }: Props) {
+  const [attemptPlan, setAttemptPlan] = useState<AttemptPlan | null>(null);
+  const [plan, setPlan] = useState('');
+  const [submitting, setSubmitting] = useState(false);
+  const [error, setError] = useState<string | null>(null);
+
+  useEffect(() => {
+    setAttemptPlan(null);
+    attemptsApi
+      .getPlan(attemptId)
+      .then((result) => {
+        setAttemptPlan(result);
+        setPlan(result?.plan ?? '');
+      })
+      .catch((err) => console.error('Failed to fetch plan:', err));
+  }, [attemptId, isAttemptRunning]);
+
+  if (!attemptPlan) return null;
+
+  if (attemptPlan.status === 'failed') {
+    return (
+      <p className="text-xs text-destructive">
+        The planning phase ended without a plan. Start a new attempt or send a
+        follow-up.
+      </p>
+    );
+  }
+
+  if (attemptPlan.status !== 'awaiting_approval') return null;
+
+  const handleApprove = async () => {
+    setSubmitting(true);
+    setError(null);
+    try {
+      setAttemptPlan(await attemptsApi.approvePlan(attemptId, { plan }));
+      onApproved();
+    } catch (err) {
+      setError(err instanceof Error ? err.message : 'Failed to approve plan');
+    } finally {
+      setSubmitting(false);
+    }
+  };
+
+  const handleReject = async () => {
+    setSubmitting(true);
+    setError(null);
+    try {
+      setAttemptPlan(await attemptsApi.rejectPlan(attemptId));
+    } catch (err) {
+      setError(err instanceof Error ? err.message : 'Failed to reject plan');
+    } finally {
+      setSubmitting(false);
+    }
+  };
+
+  return (
+    <div className="space-y-2 rounded-md border p-3">
+      <p className="flex items-center gap-2 text-sm font-medium">
+        <ClipboardList className="h-4 w-4" />
+        Plan awaiting approval
+      </p>
+      <Textarea
+        value={plan}
+        onChange={(e) => setPlan(e.target.value)}
+        rows={10}
+        className="font-mono text-xs"
+        disabled={submitting}
+      />
+      <p className="text-xs text-muted-foreground">
+        Edit the plan if needed. Approving runs it as the execution phase of
+        this attempt.
+      </p>
+      {error && <p className="text-xs text-destructive">{error}</p>}
+      <div className="flex justify-end gap-2">
+        <Button
+          variant="outline"
+          size="sm"
+          onClick={handleReject}
+          disabled={submitting}
+        >
+          Reject
+        </Button>
+        <Button
+          size="sm"
+          onClick={handleApprove}
+          disabled={submitting || !plan.trim()}
+        >
+          Approve &amp; Execute
+        </Button>
+      </div>
+    </div>
+  );
+}
+
+export default PlanApproval;
@@ -0,0 +1,38 @@
+import { AlertTriangle } from 'lucide-react';
+import type { SecretFinding } from 'shared/types';
+
+type Props = {
+  findings: SecretFinding[];
+};
+
+// Possible secrets the attempt adds, shown before its changes leave the worktree
+function SecretFindings({ findings }: Props) {
+  return (
+    <div className="space-y-2 rounded-md border border-destructive/50 p-3">
+      <p className="flex items-center gap-2 text-sm font-medium text-destructive">
+        <AlertTriangle className="h-4 w-4" />
+        {findings.length} possible secret{findings.length === 1 ? '' : 's'}{' '}
+        found in the changes
+      </p>
+      <ul className="max-h-48 space-y-1 overflow-y-auto text-xs">
+        {findings.map((finding, i) => (
+          <li key={i}>
+            <span className="font-mono">
+              {finding.file}:{finding.line}
+            </span>{' '}
+            {finding.description}{' '}
+            <span className="font-mono text-muted-foreground">
+              {finding.redacted}
+            </span>
+          </li>
+        ))}
+      </ul>
+      <p className="text-xs text-muted-foreground">
+        Remove them, or add <code>secret-scan:allow</code> to lines that are
+        not secrets.
+      </p>
+    </div>
+  );
+}
+
+export default SecretFindings;
@@ -0,0 +1,180 @@
+import { useCallback, useEffect, useState } from 'react';
+import { Copy, Trash2 } from 'lucide-react';
+import { Button } from '@/components/ui/button.tsx';
+import {
+  Dialog,
+  DialogContent,
+  DialogDescription,
+  DialogFooter,
+  DialogHeader,
+  DialogTitle,
+} from '@/components/ui/dialog.tsx';
+import {
+  Select,
+  SelectContent,
+  SelectItem,
+  SelectTrigger,
+  SelectValue,
+} from '@/components/ui/select.tsx';
+import { attemptsApi } from '@/lib/api.ts';
+import type { AttemptShareLink } from 'shared/types';
+
+const NEVER = 'never';
+
+const EXPIRY_OPTIONS: { label: string; hours: string }[] = [
+  { label: 'Expires in 1 day', hours: '24' },
+  { label: 'Expires in 7 days', hours: '168' },
+  { label: 'Expires in 30 days', hours: '720' },
+  { label: 'Never expires', hours: NEVER },
+];
+
+const shareUrl = (link: AttemptShareLink) =>
+  `${window.location.origin}/share/${link.token}`;
+
+type Props = {
+  attemptId: string;
+  open: boolean;
+  onOpenChange: (open: boolean) => void;
+};
+
+// Read-only links to the attempt's conversation and diff, for showing a run to
+// someone without giving them the board
+function ShareAttemptDialog({ attemptId, open, onOpenChange }: Props) {
+  const [links, setLinks] = useState<AttemptShareLink[]>([]);
+  const [expiry, setExpiry] = useState('168');
+  const [busy, setBusy] = useState(false);
+  const [copied, setCopied] = useState<string | null>(null);
+  const [error, setError] = useState<string | null>(null);
+
+  const load = useCallback(async () => {
+    try {
+      setLinks(await attemptsApi.getShareLinks(attemptId));
+    } catch (err) {
+      setError(err instanceof Error ? err.message : 'Failed to load links');
+    }
+  }, [attemptId]);
+
+  useEffect(() => {
+    if (open) load();
+  }, [open, load]);
+
+  const copy = async (link: AttemptShareLink) => {
+    await navigator.clipboard.writeText(shareUrl(link));
+    setCopied(link.id);
+  };
+
+  const create = async () => {
+    setBusy(true);
+    setError(null);
+    try {
+      const link = await attemptsApi.createShareLink(attemptId, {
+        expires_in_hours: expiry === NEVER ? null : Number(expiry),
+      });
+      await load();
+      await copy(link);
+    } catch (err) {
+      setError(err instanceof Error ? err.message : 'Failed to create link');
+    } finally {
+      setBusy(false);
+    }
+  };
+
+  const revoke = async (link: AttemptShareLink) => {
+    setError(null);
+    try {
+      await attemptsApi.revokeShareLink(attemptId, link.id);
+      await load();
+    } catch (err) {
+      setError(err instanceof Error ? err.message : 'Failed to revoke link');
+    }
+  };
+
+  const now = Date.now();
+
+  return (
+    <Dialog open={open} onOpenChange={onOpenChange}>
+      <DialogContent className="sm:max-w-lg">
+        <DialogHeader>
+          <DialogTitle>Share Attempt</DialogTitle>
+          <DialogDescription>
+            Anyone with a link can read this attempt&apos;s agent conversation
+            and diff, secrets masked, but nothing else on the board. They need
+            to be able to reach this server.
+          </DialogDescription>
+        </DialogHeader>
+
+        <div className="space-y-4">
+          {links.length > 0 && (
+            <div className="space-y-2">
+              {links.map((link) => {
+                const expired =
+                  link.expires_at !== null &&
+                  new Date(link.expires_at).getTime() <= now;
+                return (
+                  <div
+                    key={link.id}
+                    className="flex items-center gap-2 rounded border p-2"
+                  >
+                    <div className="min-w-0 flex-1">
+                      <p className="truncate font-mono text-xs">
+                        {shareUrl(link)}
+                      </p>
+                      <p className="text-xs text-muted-foreground">
+                        {link.expires_at === null
+                          ? 'Never expires'
+                          : `${expired ? 'Expired' : 'Expires'} ${new Date(
+                              link.expires_at
+                            ).toLocaleString()}`}
+                      </p>
+                    </div>
+                    <Button
+                      variant="ghost"
+                      size="sm"
+                      onClick={() => copy(link)}
+                      disabled={expired}
+                      title="Copy link"
+                    >
+                      <Copy className="h-4 w-4" />
+                      {copied === link.id && (
+                        <span className="ml-1 text-xs">Copied</span>
+                      )}
+                    </Button>
+                    <Button
+                      variant="ghost"
+                      size="sm"
+                      onClick={() => revoke(link)}
+                      title="Revoke link"
+                    >
+                      <Trash2 className="h-4 w-4" />
+                    </Button>
+                  </div>
+                );
+              })}
+            </div>
+          )}
+          {error && <p className="text-sm text-destructive">{error}</p>}
+        </div>
+
+        <DialogFooter className="gap-2 sm:items-center">
+          <Select value={expiry} onValueChange={setExpiry}>
+            <SelectTrigger className="w-44">
+              <SelectValue />
+            </SelectTrigger>
+            <SelectContent>
+              {EXPIRY_OPTIONS.map((option) => (
+                <SelectItem key={option.hours} value={option.hours}>
+                  {option.label}
+                </SelectItem>
+              ))}
+            </SelectContent>
+          </Select>
+          <Button onClick={create} disabled={busy}>
+            {busy ? 'Creating...' : 'Create & Copy Link'}
+          </Button>
+        </DialogFooter>
+      </DialogContent>
+    </Dialog>
+  );
+}
+
+export default ShareAttemptDialog;
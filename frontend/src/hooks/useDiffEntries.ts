@@ -10,9 +10,14 @@ interface UseDiffEntriesResult {
 
 export const useDiffEntries = (
   attemptId: string | null,
-  enabled: boolean
+  enabled: boolean,
+  against?: string
 ): UseDiffEntriesResult => {
-  const { data, isConnected, error } = useDiffStream(attemptId, enabled);
+  const { data, isConnected, error } = useDiffStream(
+    attemptId,
+    enabled,
+    against
+  );
 
   const diffs = useMemo(() => {
     if (!data) return [];
@@ -12,12 +12,16 @@ interface UseDiffStreamResult {
   error: string | null;
 }
 
+// `against` compares a snapshot of the worktree with another ref instead of
+// following the diff against the base branch
 export const useDiffStream = (
   attemptId: string | null,
-  enabled: boolean
+  enabled: boolean,
+  against?: string
 ): UseDiffStreamResult => {
+  const query = against ? `?against=${encodeURIComponent(against)}` : '';
   const endpoint = attemptId
-    ? `/api/task-attempts/${attemptId}/diff`
+    ? `/api/task-attempts/${attemptId}/diff${query}`
     : undefined;
 
   const initialData = useCallback(
@@ -1,38 +1,160 @@
 // Import all necessary types from shared types
 
 import {
+  AnalyticsEvent,
+  AnalyticsEventCount,
+  UsageGroupBy,
+  UsageReport,
+  CreateEvaluation,
+  Evaluation,
+  EvaluationReport,
+  CreateWebhook,
+  UpdateWebhook,
+  Webhook,
+  WebhookDelivery,
+  WebhookWithSecret,
+  ImportSentryIssue,
+  InboundTaskResponse,
+  SentryIntegrationStatus,
+  SetSentryCredentials,
   ApiResponse,
+  MessageCode,
+  ProblemDetails,
+  AttemptCheckpoint,
+  AttemptChecks,
+  AttemptConflict,
+  MergeQueueEntry,
+  MergeQueuePosition,
+  AttemptUnmerge,
+  AttemptRuntime,
+  SuspendedExecution,
+  AttemptTimeline,
   BranchStatus,
   CheckTokenResponse,
   Config,
+  ContextFile,
+  CustomSound,
   CreateFollowUpAttempt,
+  CreateFollowUpSnippet,
   CreateGitHubPrRequest,
+  CreateCommitPlanRequest,
+  CreateQueuedFollowUp,
   CreateTask,
   CreateTaskAttemptBody,
   CreateTaskTemplate,
+  DependencyUpdateRun,
+  DevServerInfo,
   DeviceFlowStartResponse,
+  AttemptBranchSync,
+  AttemptPlan,
+  AttemptReview,
+  AttemptCommitPlan,
+  AttemptToolUsage,
+  ApprovePlanRequest,
   DevicePollStatus,
   DirectoryListResponse,
+  DiscoveredRepo,
+  EditorOpenRequest,
+  EditorOpenResponse,
   EditorType,
+  DiffRisk,
+  DiffStats,
   ExecutionProcess,
+  FollowUpSnippet,
   GitBranch,
+  Paginated,
+  PathViolation,
   Project,
+  ProjectTemplate,
+  ProjectBudget,
+  ProjectBranchSync,
+  ProjectCiTriage,
+  ProjectDependencyUpdates,
+  ProjectChangelog,
+  ProjectContextPacking,
+  ProjectDependencyCache,
+  ProjectDevcontainer,
+  ProjectDevcontainerStatus,
+  ProjectNixEnv,
+  ProjectNixEnvStatus,
+  CostEstimate,
+  CostEstimateRequest,
+  ProjectDiskQuota,
+  ProjectDiskUsage,
+  ProjectEnvVar,
+  ProjectPathRules,
+  ProjectReviewer,
   CreateProject,
+  CloneJob,
+  CloneProject,
+  CreateProjectFromTemplate,
+  CreateSupportBundle,
+  DetectedScripts,
+  QueuedFollowUp,
   RebaseTaskAttemptRequest,
   RepositoryInfo,
   SearchResult,
+  SecretScanReport,
+  SelfUpdateOutcome,
+  ServerLogs,
+  SpendSummary,
+  KeyProvider,
+  ProviderKey,
   Task,
+  TaskAttachment,
   TaskAttempt,
   TaskTemplate,
   TaskWithAttemptStatus,
+  TestRun,
   UpdateProject,
+  UpdateProjectBranchSync,
+  UpdateProjectCiTriage,
+  UpdateProjectDependencyUpdates,
+  UpdateProjectChangelog,
+  UpdateProjectContextPacking,
+  UpdateProjectDependencyCache,
+  UpdateProjectDevcontainer,
+  UpdateProjectNixEnv,
+  UpdateProjectEnv,
+  UpdateProjectWorktreePool,
+  UpdateProjectPathRules,
+  UpdateProjectReviewer,
   UpdateTask,
   UpdateTaskTemplate,
+  UpdateFollowUpSnippet,
+  UpdateStatus,
   UserSystemInfo,
+  WorktreePoolStatus,
+  WorktreeTemplate,
+  WorktreeTemplateList,
   GitHubServiceError,
   McpServerQuery,
   UpdateMcpServersBody,
   GetMcpServerResponse,
+  BackupInfo,
+  RestoreBackupRequest,
+  WorkspaceRequest,
+  WorkspacesResponse,
+  ProjectGroup,
+  CreateProjectGroup,
+  UpdateProjectGroup,
+  ProjectGroupBoard,
+  CreateGroupTask,
+  AgentLoginSession,
+  StartAgentLoginRequest,
+  AgentLoginInput,
+  FeatureFlag,
+  FeatureFlagState,
+  AttemptShareLink,
+  CreateAttemptShareLink,
+  SharedAttempt,
+  AgentPresence,
+  QuickCreateTask,
+  SetTaskBaseBranch,
+  LinkedRepoDiff,
+  API_VERSION,
+  ApiWarning,
+  ConfigVersion,
 } from 'shared/types';
 
 // Re-export types for convenience
@@ -41,23 +163,31 @@ export type { RepositoryInfo } from 'shared/types';
 export class ApiError<E = unknown> extends Error {
   public status?: number;
   public error_data?: E;
+  public code?: MessageCode;
+  /** Set when the server answered with a problem+json body */
+  public problem?: ProblemDetails;
 
   constructor(
     message: string,
     public statusCode?: number,
     public response?: Response,
-    error_data?: E
+    error_data?: E,
+    code?: MessageCode
   ) {
     super(message);
     this.name = 'ApiError';
     this.status = statusCode;
     this.error_data = error_data;
+    this.code = code;
   }
 }
 
+// Requests name the types this build was generated from, the server rejects
+// them with a 409 once it runs other ones
 export const makeRequest = async (url: string, options: RequestInit = {}) => {
   const headers = {
     'Content-Type': 'application/json',
+    'X-Api-Version': API_VERSION,
     ...(options.headers || {}),
   };
 
@@ -67,12 +197,40 @@ export const makeRequest = async (url: string, options: RequestInit = {}) => {
   });
 };
 
+// A remote editor is opened by the browser through the link the server returns
+const openEditorLink = (result: EditorOpenResponse | null) => {
+  if (result?.url) {
+    window.open(result.url, '_blank');
+  }
+};
+
 export interface FollowUpResponse {
   message: string;
   actual_attempt_id: string;
   created_new_attempt: boolean;
 }
 
+type ApiWarningListener = (warnings: ApiWarning[]) => void;
+const warningListeners = new Set<ApiWarningListener>();
+
+// Successful responses can still carry warnings, e.g. fields that were
+// ignored, for the UI to show
+export const onApiWarnings = (listener: ApiWarningListener) => {
+  warningListeners.add(listener);
+  return () => {
+    warningListeners.delete(listener);
+  };
+};
+
+const reportWarnings = (result: ApiResponse<unknown, unknown>) => {
+  if (!result.warnings?.length) return;
+  console.warn('[API Warning]', {
+    warnings: result.warnings,
+    request_id: result.request_id,
+  });
+  warningListeners.forEach((listener) => listener(result.warnings));
+};
+
 // Result type for endpoints that need typed errors
 export type Result<T, E> =
   | { success: true; data: T }
@@ -88,8 +246,9 @@ const handleApiResponseAsResult = async <T, E>(
 
     try {
       const errorData = await response.json();
-      if (errorData.message) {
-        errorMessage = errorData.message;
+      // Error statuses carry a problem+json body, older handlers an ApiResponse
+      if (errorData.detail || errorData.message) {
+        errorMessage = errorData.detail || errorData.message;
       }
     } catch {
       errorMessage = response.statusText || errorMessage;
@@ -103,6 +262,7 @@ const handleApiResponseAsResult = async <T, E>(
   }
 
   const result: ApiResponse<T, E> = await response.json();
+  reportWarnings(result);
 
   if (!result.success) {
     return {
@@ -118,11 +278,18 @@ const handleApiResponseAsResult = async <T, E>(
 const handleApiResponse = async <T, E = T>(response: Response): Promise<T> => {
   if (!response.ok) {
     let errorMessage = `Request failed with status ${response.status}`;
+    let errorCode: MessageCode | undefined;
+    let problem: ProblemDetails | undefined;
 
     try {
       const errorData = await response.json();
-      if (errorData.message) {
-        errorMessage = errorData.message;
+      // Error statuses carry a problem+json body, older handlers an ApiResponse
+      if (errorData.detail || errorData.message) {
+        errorMessage = errorData.detail || errorData.message;
+      }
+      errorCode = errorData.code ?? undefined;
+      if (errorData.type && errorData.status) {
+        problem = errorData;
       }
     } catch {
       // Fallback to status text if JSON parsing fails
@@ -131,15 +298,25 @@ const handleApiResponse = async <T, E = T>(response: Response): Promise<T> => {
 
     console.error('[API Error]', {
       message: errorMessage,
+      code: errorCode,
       status: response.status,
       response,
       endpoint: response.url,
       timestamp: new Date().toISOString(),
     });
-    throw new ApiError<E>(errorMessage, response.status, response);
+    const error = new ApiError<E>(
+      errorMessage,
+      response.status,
+      response,
+      undefined,
+      errorCode
+    );
+    error.problem = problem;
+    throw error;
   }
 
   const result: ApiResponse<T, E> = await response.json();
+  reportWarnings(result);
 
   if (!result.success) {
     // Check for error_data first (structured errors), then fall back to message
@@ -163,6 +340,7 @@ const handleApiResponse = async <T, E = T>(response: Response): Promise<T> => {
 
     console.error('[API Error]', {
       message: result.message || 'API request failed',
+      code: result.code,
       status: response.status,
       response,
       endpoint: response.url,
@@ -171,7 +349,9 @@ const handleApiResponse = async <T, E = T>(response: Response): Promise<T> => {
     throw new ApiError<E>(
       result.message || 'API request failed',
       response.status,
-      response
+      response,
+      undefined,
+      result.code ?? undefined
     );
   }
 
@@ -182,7 +362,8 @@ const handleApiResponse = async <T, E = T>(response: Response): Promise<T> => {
 export const projectsApi = {
   getAll: async (): Promise<Project[]> => {
     const response = await makeRequest('/api/projects');
-    return handleApiResponse<Project[]>(response);
+    const page = await handleApiResponse<Paginated<Project>>(response);
+    return page.items;
   },
 
   getById: async (id: string): Promise<Project> => {
@@ -198,6 +379,34 @@ export const projectsApi = {
     return handleApiResponse<Project>(response);
   },
 
+  discover: async (): Promise<DiscoveredRepo[]> => {
+    const response = await makeRequest('/api/projects/discover');
+    return handleApiResponse<DiscoveredRepo[]>(response);
+  },
+
+  getTemplates: async (): Promise<ProjectTemplate[]> => {
+    const response = await makeRequest('/api/projects/templates');
+    return handleApiResponse<ProjectTemplate[]>(response);
+  },
+
+  createFromTemplate: async (
+    data: CreateProjectFromTemplate
+  ): Promise<Project> => {
+    const response = await makeRequest('/api/projects/from-template', {
+      method: 'POST',
+      body: JSON.stringify(data),
+    });
+    return handleApiResponse<Project>(response);
+  },
+
+  clone: async (data: CloneProject): Promise<CloneJob> => {
+    const response = await makeRequest('/api/projects/clone', {
+      method: 'POST',
+      body: JSON.stringify(data),
+    });
+    return handleApiResponse<CloneJob>(response);
+  },
+
   update: async (id: string, data: UpdateProject): Promise<Project> => {
     const response = await makeRequest(`/api/projects/${id}`, {
       method: 'PUT',
@@ -218,14 +427,33 @@ export const projectsApi = {
       method: 'POST',
       body: JSON.stringify(null),
     });
-    return handleApiResponse<void>(response);
+    openEditorLink(await handleApiResponse<EditorOpenResponse>(response));
   },
 
-  getBranches: async (id: string): Promise<GitBranch[]> => {
-    const response = await makeRequest(`/api/projects/${id}/branches`);
+  getBranches: async (
+    id: string,
+    includeTags = false
+  ): Promise<GitBranch[]> => {
+    const query = includeTags ? '?include_tags=true' : '';
+    const response = await makeRequest(`/api/projects/${id}/branches${query}`);
     return handleApiResponse<GitBranch[]>(response);
   },
 
+  getConflicts: async (id: string): Promise<AttemptConflict[]> => {
+    const response = await makeRequest(`/api/projects/${id}/conflicts`);
+    return handleApiResponse<AttemptConflict[]>(response);
+  },
+
+  getMergeQueue: async (id: string): Promise<MergeQueueEntry[]> => {
+    const response = await makeRequest(`/api/projects/${id}/merge-queue`);
+    return handleApiResponse<MergeQueueEntry[]>(response);
+  },
+
+  detectScripts: async (id: string): Promise<DetectedScripts> => {
+    const response = await makeRequest(`/api/projects/${id}/detect-scripts`);
+    return handleApiResponse<DetectedScripts>(response);
+  },
+
   searchFiles: async (
     id: string,
     query: string,
@@ -237,13 +465,319 @@ export const projectsApi = {
     );
     return handleApiResponse<SearchResult[]>(response);
   },
+
+  getPathRules: async (projectId: string): Promise<ProjectPathRules | null> => {
+    const response = await makeRequest(`/api/projects/${projectId}/path-rules`);
+    return handleApiResponse<ProjectPathRules | null>(response);
+  },
+
+  updatePathRules: async (
+    projectId: string,
+    data: UpdateProjectPathRules
+  ): Promise<ProjectPathRules> => {
+    const response = await makeRequest(
+      `/api/projects/${projectId}/path-rules`,
+      {
+        method: 'PUT',
+        body: JSON.stringify(data),
+      }
+    );
+    return handleApiResponse<ProjectPathRules>(response);
+  },
+
+  getBranchSync: async (
+    projectId: string
+  ): Promise<ProjectBranchSync | null> => {
+    const response = await makeRequest(
+      `/api/projects/${projectId}/branch-sync`
+    );
+    return handleApiResponse<ProjectBranchSync | null>(response);
+  },
+
+  updateBranchSync: async (
+    projectId: string,
+    data: UpdateProjectBranchSync
+  ): Promise<ProjectBranchSync> => {
+    const response = await makeRequest(
+      `/api/projects/${projectId}/branch-sync`,
+      {
+        method: 'PUT',
+        body: JSON.stringify(data),
+      }
+    );
+    return handleApiResponse<ProjectBranchSync>(response);
+  },
+
+  getCiTriage: async (projectId: string): Promise<ProjectCiTriage | null> => {
+    const response = await makeRequest(`/api/projects/${projectId}/ci-triage`);
+    return handleApiResponse<ProjectCiTriage | null>(response);
+  },
+
+  updateCiTriage: async (
+    projectId: string,
+    data: UpdateProjectCiTriage
+  ): Promise<ProjectCiTriage> => {
+    const response = await makeRequest(`/api/projects/${projectId}/ci-triage`, {
+      method: 'PUT',
+      body: JSON.stringify(data),
+    });
+    return handleApiResponse<ProjectCiTriage>(response);
+  },
+
+  getDependencyUpdates: async (
+    projectId: string
+  ): Promise<ProjectDependencyUpdates | null> => {
+    const response = await makeRequest(
+      `/api/projects/${projectId}/dependency-updates`
+    );
+    return handleApiResponse<ProjectDependencyUpdates | null>(response);
+  },
+
+  updateDependencyUpdates: async (
+    projectId: string,
+    data: UpdateProjectDependencyUpdates
+  ): Promise<ProjectDependencyUpdates> => {
+    const response = await makeRequest(
+      `/api/projects/${projectId}/dependency-updates`,
+      {
+        method: 'PUT',
+        body: JSON.stringify(data),
+      }
+    );
+    return handleApiResponse<ProjectDependencyUpdates>(response);
+  },
+
+  getDependencyUpdateRuns: async (
+    projectId: string
+  ): Promise<DependencyUpdateRun[]> => {
+    const response = await makeRequest(
+      `/api/projects/${projectId}/dependency-updates/runs`
+    );
+    return handleApiResponse<DependencyUpdateRun[]>(response);
+  },
+
+  runDependencyUpdates: async (
+    projectId: string
+  ): Promise<DependencyUpdateRun[]> => {
+    const response = await makeRequest(
+      `/api/projects/${projectId}/dependency-updates/runs`,
+      { method: 'POST' }
+    );
+    return handleApiResponse<DependencyUpdateRun[]>(response);
+  },
+
+  getChangelog: async (projectId: string): Promise<ProjectChangelog | null> => {
+    const response = await makeRequest(`/api/projects/${projectId}/changelog`);
+    return handleApiResponse<ProjectChangelog | null>(response);
+  },
+
+  updateChangelog: async (
+    projectId: string,
+    data: UpdateProjectChangelog
+  ): Promise<ProjectChangelog> => {
+    const response = await makeRequest(`/api/projects/${projectId}/changelog`, {
+      method: 'PUT',
+      body: JSON.stringify(data),
+    });
+    return handleApiResponse<ProjectChangelog>(response);
+  },
+
+  getReviewer: async (projectId: string): Promise<ProjectReviewer | null> => {
+    const response = await makeRequest(`/api/projects/${projectId}/reviewer`);
+    return handleApiResponse<ProjectReviewer | null>(response);
+  },
+
+  updateReviewer: async (
+    projectId: string,
+    data: UpdateProjectReviewer
+  ): Promise<ProjectReviewer> => {
+    const response = await makeRequest(`/api/projects/${projectId}/reviewer`, {
+      method: 'PUT',
+      body: JSON.stringify(data),
+    });
+    return handleApiResponse<ProjectReviewer>(response);
+  },
+
+  getContextPacking: async (
+    projectId: string
+  ): Promise<ProjectContextPacking | null> => {
+    const response = await makeRequest(
+      `/api/projects/${projectId}/context-packing`
+    );
+    return handleApiResponse<ProjectContextPacking | null>(response);
+  },
+
+  updateContextPacking: async (
+    projectId: string,
+    data: UpdateProjectContextPacking
+  ): Promise<ProjectContextPacking> => {
+    const response = await makeRequest(
+      `/api/projects/${projectId}/context-packing`,
+      {
+        method: 'PUT',
+        body: JSON.stringify(data),
+      }
+    );
+    return handleApiResponse<ProjectContextPacking>(response);
+  },
+
+  getDependencyCache: async (
+    projectId: string
+  ): Promise<ProjectDependencyCache | null> => {
+    const response = await makeRequest(
+      `/api/projects/${projectId}/dependency-cache`
+    );
+    return handleApiResponse<ProjectDependencyCache | null>(response);
+  },
+
+  updateDependencyCache: async (
+    projectId: string,
+    data: UpdateProjectDependencyCache
+  ): Promise<ProjectDependencyCache> => {
+    const response = await makeRequest(
+      `/api/projects/${projectId}/dependency-cache`,
+      {
+        method: 'PUT',
+        body: JSON.stringify(data),
+      }
+    );
+    return handleApiResponse<ProjectDependencyCache>(response);
+  },
+
+  getDevcontainer: async (
+    projectId: string
+  ): Promise<ProjectDevcontainerStatus> => {
+    const response = await makeRequest(
+      `/api/projects/${projectId}/devcontainer`
+    );
+    return handleApiResponse<ProjectDevcontainerStatus>(response);
+  },
+
+  updateDevcontainer: async (
+    projectId: string,
+    data: UpdateProjectDevcontainer
+  ): Promise<ProjectDevcontainer> => {
+    const response = await makeRequest(
+      `/api/projects/${projectId}/devcontainer`,
+      {
+        method: 'PUT',
+        body: JSON.stringify(data),
+      }
+    );
+    return handleApiResponse<ProjectDevcontainer>(response);
+  },
+
+  getNixEnv: async (projectId: string): Promise<ProjectNixEnvStatus> => {
+    const response = await makeRequest(`/api/projects/${projectId}/nix-env`);
+    return handleApiResponse<ProjectNixEnvStatus>(response);
+  },
+
+  updateNixEnv: async (
+    projectId: string,
+    data: UpdateProjectNixEnv
+  ): Promise<ProjectNixEnv> => {
+    const response = await makeRequest(`/api/projects/${projectId}/nix-env`, {
+      method: 'PUT',
+      body: JSON.stringify(data),
+    });
+    return handleApiResponse<ProjectNixEnv>(response);
+  },
+
+  estimateCost: async (
+    projectId: string,
+    data: CostEstimateRequest
+  ): Promise<CostEstimate> => {
+    const response = await makeRequest(
+      `/api/projects/${projectId}/cost-estimate`,
+      {
+        method: 'POST',
+        body: JSON.stringify(data),
+      }
+    );
+    return handleApiResponse<CostEstimate>(response);
+  },
+
+  getEnv: async (projectId: string): Promise<ProjectEnvVar[]> => {
+    const response = await makeRequest(`/api/projects/${projectId}/env`);
+    return handleApiResponse<ProjectEnvVar[]>(response);
+  },
+
+  updateEnv: async (
+    projectId: string,
+    data: UpdateProjectEnv
+  ): Promise<ProjectEnvVar[]> => {
+    const response = await makeRequest(`/api/projects/${projectId}/env`, {
+      method: 'PUT',
+      body: JSON.stringify(data),
+    });
+    return handleApiResponse<ProjectEnvVar[]>(response);
+  },
+
+  getWorktreeTemplates: async (
+    projectId: string
+  ): Promise<WorktreeTemplateList> => {
+    const response = await makeRequest(
+      `/api/projects/${projectId}/worktree-templates`
+    );
+    return handleApiResponse<WorktreeTemplateList>(response);
+  },
+
+  uploadWorktreeTemplate: async (
+    projectId: string,
+    path: string,
+    file: File
+  ): Promise<WorktreeTemplate> => {
+    const response = await makeRequest(
+      `/api/projects/${projectId}/worktree-templates?path=${encodeURIComponent(path)}`,
+      {
+        method: 'PUT',
+        headers: { 'Content-Type': 'application/octet-stream' },
+        body: file,
+      }
+    );
+    return handleApiResponse<WorktreeTemplate>(response);
+  },
+
+  deleteWorktreeTemplate: async (
+    projectId: string,
+    path: string
+  ): Promise<void> => {
+    const response = await makeRequest(
+      `/api/projects/${projectId}/worktree-templates?path=${encodeURIComponent(path)}`,
+      { method: 'DELETE' }
+    );
+    return handleApiResponse<void>(response);
+  },
+
+  getWorktreePool: async (projectId: string): Promise<WorktreePoolStatus> => {
+    const response = await makeRequest(
+      `/api/projects/${projectId}/worktree-pool`
+    );
+    return handleApiResponse<WorktreePoolStatus>(response);
+  },
+
+  updateWorktreePool: async (
+    projectId: string,
+    data: UpdateProjectWorktreePool
+  ): Promise<WorktreePoolStatus> => {
+    const response = await makeRequest(
+      `/api/projects/${projectId}/worktree-pool`,
+      {
+        method: 'PUT',
+        body: JSON.stringify(data),
+      }
+    );
+    return handleApiResponse<WorktreePoolStatus>(response);
+  },
 };
 
 // Task Management APIs
 export const tasksApi = {
   getAll: async (projectId: string): Promise<TaskWithAttemptStatus[]> => {
     const response = await makeRequest(`/api/tasks?project_id=${projectId}`);
-    return handleApiResponse<TaskWithAttemptStatus[]>(response);
+    const page =
+      await handleApiResponse<Paginated<TaskWithAttemptStatus>>(response);
+    return page.items;
   },
 
   getById: async (taskId: string): Promise<Task> => {
@@ -267,6 +801,14 @@ export const tasksApi = {
     return handleApiResponse<TaskWithAttemptStatus>(response);
   },
 
+  quickCreate: async (data: QuickCreateTask): Promise<Task> => {
+    const response = await makeRequest(`/api/tasks/quick`, {
+      method: 'POST',
+      body: JSON.stringify(data),
+    });
+    return handleApiResponse<Task>(response);
+  },
+
   update: async (taskId: string, data: UpdateTask): Promise<Task> => {
     const response = await makeRequest(`/api/tasks/${taskId}`, {
       method: 'PUT',
@@ -275,6 +817,17 @@ export const tasksApi = {
     return handleApiResponse<Task>(response);
   },
 
+  setBaseBranch: async (
+    taskId: string,
+    data: SetTaskBaseBranch
+  ): Promise<Task> => {
+    const response = await makeRequest(`/api/tasks/${taskId}/base-branch`, {
+      method: 'PUT',
+      body: JSON.stringify(data),
+    });
+    return handleApiResponse<Task>(response);
+  },
+
   delete: async (taskId: string): Promise<void> => {
     const response = await makeRequest(`/api/tasks/${taskId}`, {
       method: 'DELETE',
@@ -294,7 +847,8 @@ export const attemptsApi = {
 
   getAll: async (taskId: string): Promise<TaskAttempt[]> => {
     const response = await makeRequest(`/api/task-attempts?task_id=${taskId}`);
-    return handleApiResponse<TaskAttempt[]>(response);
+    const page = await handleApiResponse<Paginated<TaskAttempt>>(response);
+    return page.items;
   },
 
   create: async (data: CreateTaskAttemptBody): Promise<TaskAttempt> => {
@@ -312,6 +866,70 @@ export const attemptsApi = {
     return handleApiResponse<void>(response);
   },
 
+  pause: async (attemptId: string): Promise<void> => {
+    const response = await makeRequest(
+      `/api/task-attempts/${attemptId}/pause`,
+      { method: 'POST' }
+    );
+    return handleApiResponse<void>(response);
+  },
+
+  resume: async (attemptId: string): Promise<void> => {
+    const response = await makeRequest(
+      `/api/task-attempts/${attemptId}/resume`,
+      { method: 'POST' }
+    );
+    return handleApiResponse<void>(response);
+  },
+
+  getSuspended: async (
+    attemptId: string
+  ): Promise<SuspendedExecution | null> => {
+    const response = await makeRequest(
+      `/api/task-attempts/${attemptId}/suspended`
+    );
+    return handleApiResponse<SuspendedExecution | null>(response);
+  },
+
+  getRuntime: async (attemptId: string): Promise<AttemptRuntime> => {
+    const response = await makeRequest(
+      `/api/task-attempts/${attemptId}/runtime`
+    );
+    return handleApiResponse<AttemptRuntime>(response);
+  },
+
+  getTimeline: async (attemptId: string): Promise<AttemptTimeline> => {
+    const response = await makeRequest(
+      `/api/task-attempts/${attemptId}/timeline`
+    );
+    return handleApiResponse<AttemptTimeline>(response);
+  },
+
+  getCheckpoints: async (attemptId: string): Promise<AttemptCheckpoint[]> => {
+    const response = await makeRequest(
+      `/api/task-attempts/${attemptId}/checkpoints`
+    );
+    return handleApiResponse<AttemptCheckpoint[]>(response);
+  },
+
+  rollback: async (
+    attemptId: string,
+    checkpointId: string
+  ): Promise<AttemptCheckpoint | null> => {
+    const response = await makeRequest(
+      `/api/task-attempts/${attemptId}/rollback?checkpoint=${checkpointId}`,
+      { method: 'POST' }
+    );
+    return handleApiResponse<AttemptCheckpoint | null>(response);
+  },
+
+  getTestRuns: async (attemptId: string): Promise<TestRun[]> => {
+    const response = await makeRequest(
+      `/api/task-attempts/${attemptId}/test-runs`
+    );
+    return handleApiResponse<TestRun[]>(response);
+  },
+
   followUp: async (
     attemptId: string,
     data: CreateFollowUpAttempt
@@ -326,6 +944,48 @@ export const attemptsApi = {
     return handleApiResponse<void>(response);
   },
 
+  getFollowUpQueue: async (attemptId: string): Promise<QueuedFollowUp[]> => {
+    const response = await makeRequest(
+      `/api/task-attempts/${attemptId}/follow-up-queue`
+    );
+    return handleApiResponse<QueuedFollowUp[]>(response);
+  },
+
+  queueFollowUp: async (
+    attemptId: string,
+    data: CreateQueuedFollowUp
+  ): Promise<QueuedFollowUp> => {
+    const response = await makeRequest(
+      `/api/task-attempts/${attemptId}/follow-up-queue`,
+      {
+        method: 'POST',
+        body: JSON.stringify(data),
+      }
+    );
+    return handleApiResponse<QueuedFollowUp>(response);
+  },
+
+  reorderFollowUpQueue: async (
+    attemptId: string,
+    ids: string[]
+  ): Promise<QueuedFollowUp[]> => {
+    const response = await makeRequest(
+      `/api/task-attempts/${attemptId}/follow-up-queue`,
+      {
+        method: 'PUT',
+        body: JSON.stringify({ ids }),
+      }
+    );
+    return handleApiResponse<QueuedFollowUp[]>(response);
+  },
+
+  deleteQueuedFollowUp: async (queuedId: string): Promise<void> => {
+    const response = await makeRequest(`/api/queued-follow-ups/${queuedId}`, {
+      method: 'DELETE',
+    });
+    return handleApiResponse<void>(response);
+  },
+
   deleteFile: async (
     attemptId: string,
     fileToDelete: string
@@ -359,7 +1019,7 @@ export const attemptsApi = {
         ),
       }
     );
-    return handleApiResponse<void>(response);
+    openEditorLink(await handleApiResponse<EditorOpenResponse>(response));
   },
 
   getBranchStatus: async (attemptId: string): Promise<BranchStatus> => {
@@ -369,16 +1029,202 @@ export const attemptsApi = {
     return handleApiResponse<BranchStatus>(response);
   },
 
-  merge: async (attemptId: string): Promise<void> => {
+  getDiffRisk: async (attemptId: string): Promise<DiffRisk> => {
     const response = await makeRequest(
-      `/api/task-attempts/${attemptId}/merge`,
-      {
+      `/api/task-attempts/${attemptId}/diff-risk`
+    );
+    return handleApiResponse<DiffRisk>(response);
+  },
+
+  getLinkedRepos: async (attemptId: string): Promise<LinkedRepoDiff[]> => {
+    const response = await makeRequest(
+      `/api/task-attempts/${attemptId}/linked-repos`
+    );
+    return handleApiResponse<LinkedRepoDiff[]>(response);
+  },
+
+  getDiffStats: async (attemptId: string): Promise<DiffStats> => {
+    const response = await makeRequest(
+      `/api/task-attempts/${attemptId}/diff/stats`
+    );
+    return handleApiResponse<DiffStats>(response);
+  },
+
+  getPathViolations: async (attemptId: string): Promise<PathViolation[]> => {
+    const response = await makeRequest(
+      `/api/task-attempts/${attemptId}/path-violations`
+    );
+    return handleApiResponse<PathViolation[]>(response);
+  },
+
+  getBranchSync: async (
+    attemptId: string
+  ): Promise<AttemptBranchSync | null> => {
+    const response = await makeRequest(
+      `/api/task-attempts/${attemptId}/branch-sync`
+    );
+    return handleApiResponse<AttemptBranchSync | null>(response);
+  },
+
+  getChecks: async (attemptId: string): Promise<AttemptChecks> => {
+    const response = await makeRequest(
+      `/api/task-attempts/${attemptId}/checks`
+    );
+    return handleApiResponse<AttemptChecks>(response);
+  },
+
+  getPlan: async (attemptId: string): Promise<AttemptPlan | null> => {
+    const response = await makeRequest(`/api/task-attempts/${attemptId}/plan`);
+    return handleApiResponse<AttemptPlan | null>(response);
+  },
+
+  approvePlan: async (
+    attemptId: string,
+    data: ApprovePlanRequest
+  ): Promise<AttemptPlan> => {
+    const response = await makeRequest(
+      `/api/task-attempts/${attemptId}/plan/approve`,
+      {
         method: 'POST',
+        body: JSON.stringify(data),
       }
     );
+    return handleApiResponse<AttemptPlan>(response);
+  },
+
+  rejectPlan: async (attemptId: string): Promise<AttemptPlan> => {
+    const response = await makeRequest(
+      `/api/task-attempts/${attemptId}/plan/reject`,
+      {
+        method: 'POST',
+      }
+    );
+    return handleApiResponse<AttemptPlan>(response);
+  },
+
+  getReviews: async (attemptId: string): Promise<AttemptReview[]> => {
+    const response = await makeRequest(
+      `/api/task-attempts/${attemptId}/reviews`
+    );
+    return handleApiResponse<AttemptReview[]>(response);
+  },
+
+  getShareLinks: async (attemptId: string): Promise<AttemptShareLink[]> => {
+    const response = await makeRequest(
+      `/api/task-attempts/${attemptId}/share-links`
+    );
+    return handleApiResponse<AttemptShareLink[]>(response);
+  },
+
+  createShareLink: async (
+    attemptId: string,
+    data: CreateAttemptShareLink
+  ): Promise<AttemptShareLink> => {
+    const response = await makeRequest(
+      `/api/task-attempts/${attemptId}/share-links`,
+      {
+        method: 'POST',
+        body: JSON.stringify(data),
+      }
+    );
+    return handleApiResponse<AttemptShareLink>(response);
+  },
+
+  revokeShareLink: async (
+    attemptId: string,
+    linkId: string
+  ): Promise<void> => {
+    const response = await makeRequest(
+      `/api/task-attempts/${attemptId}/share-links/${linkId}`,
+      { method: 'DELETE' }
+    );
+    return handleApiResponse<void>(response);
+  },
+
+  getCommitPlan: async (
+    attemptId: string
+  ): Promise<AttemptCommitPlan | null> => {
+    const response = await makeRequest(
+      `/api/task-attempts/${attemptId}/commit-plan`
+    );
+    return handleApiResponse<AttemptCommitPlan | null>(response);
+  },
+
+  createCommitPlan: async (
+    attemptId: string,
+    data: CreateCommitPlanRequest
+  ): Promise<AttemptCommitPlan> => {
+    const response = await makeRequest(
+      `/api/task-attempts/${attemptId}/commit-plan`,
+      {
+        method: 'POST',
+        body: JSON.stringify(data),
+      }
+    );
+    return handleApiResponse<AttemptCommitPlan>(response);
+  },
+
+  getToolUsage: async (attemptId: string): Promise<AttemptToolUsage> => {
+    const response = await makeRequest(
+      `/api/task-attempts/${attemptId}/tool-usage`
+    );
+    return handleApiResponse<AttemptToolUsage>(response);
+  },
+
+  getSecretScan: async (attemptId: string): Promise<SecretScanReport> => {
+    const response = await makeRequest(
+      `/api/task-attempts/${attemptId}/secret-scan`
+    );
+    return handleApiResponse<SecretScanReport>(response);
+  },
+
+  merge: async (attemptId: string, allowSecrets = false): Promise<void> => {
+    const response = await makeRequest(
+      `/api/task-attempts/${attemptId}/merge?allow_secrets=${allowSecrets}`,
+      {
+        method: 'POST',
+      }
+    );
+    return handleApiResponse<void>(response);
+  },
+
+  getMergeQueue: async (attemptId: string): Promise<MergeQueuePosition> => {
+    const response = await makeRequest(
+      `/api/task-attempts/${attemptId}/merge-queue`
+    );
+    return handleApiResponse<MergeQueuePosition>(response);
+  },
+
+  enqueueMerge: async (
+    attemptId: string,
+    allowSecrets = false
+  ): Promise<MergeQueuePosition> => {
+    const response = await makeRequest(
+      `/api/task-attempts/${attemptId}/merge-queue?allow_secrets=${allowSecrets}`,
+      { method: 'POST' }
+    );
+    return handleApiResponse<MergeQueuePosition>(response);
+  },
+
+  dequeueMerge: async (attemptId: string): Promise<void> => {
+    const response = await makeRequest(
+      `/api/task-attempts/${attemptId}/merge-queue`,
+      { method: 'DELETE' }
+    );
     return handleApiResponse<void>(response);
   },
 
+  unmerge: async (
+    attemptId: string,
+    allowSubsequent = false
+  ): Promise<AttemptUnmerge> => {
+    const response = await makeRequest(
+      `/api/task-attempts/${attemptId}/unmerge?allow_subsequent=${allowSubsequent}`,
+      { method: 'POST' }
+    );
+    return handleApiResponse<AttemptUnmerge>(response);
+  },
+
   rebase: async (
     attemptId: string,
     data: RebaseTaskAttemptRequest
@@ -395,12 +1241,16 @@ export const attemptsApi = {
 
   createPR: async (
     attemptId: string,
-    data: CreateGitHubPrRequest
+    data: CreateGitHubPrRequest,
+    allowSecrets = false
   ): Promise<Result<string, GitHubServiceError>> => {
-    const response = await makeRequest(`/api/task-attempts/${attemptId}/pr`, {
-      method: 'POST',
-      body: JSON.stringify(data),
-    });
+    const response = await makeRequest(
+      `/api/task-attempts/${attemptId}/pr?allow_secrets=${allowSecrets}`,
+      {
+        method: 'POST',
+        body: JSON.stringify(data),
+      }
+    );
     return handleApiResponseAsResult<string, GitHubServiceError>(response);
   },
 
@@ -413,6 +1263,35 @@ export const attemptsApi = {
     );
     return handleApiResponse<void>(response);
   },
+
+  getDevServer: async (attemptId: string): Promise<DevServerInfo | null> => {
+    const response = await makeRequest(
+      `/api/task-attempts/${attemptId}/dev-server`
+    );
+    return handleApiResponse<DevServerInfo | null>(response);
+  },
+
+  getContextFiles: async (attemptId: string): Promise<ContextFile[]> => {
+    const response = await makeRequest(
+      `/api/task-attempts/${attemptId}/context-files`
+    );
+    return handleApiResponse<ContextFile[]>(response);
+  },
+
+  uploadContextFile: async (
+    attemptId: string,
+    file: File
+  ): Promise<ContextFile> => {
+    const response = await makeRequest(
+      `/api/task-attempts/${attemptId}/context-files?file_name=${encodeURIComponent(file.name)}`,
+      {
+        method: 'POST',
+        headers: { 'Content-Type': file.type || 'application/octet-stream' },
+        body: file,
+      }
+    );
+    return handleApiResponse<ContextFile>(response);
+  },
 };
 
 // Execution Process APIs
@@ -423,7 +1302,8 @@ export const executionProcessesApi = {
     const response = await makeRequest(
       `/api/execution-processes?task_attempt_id=${attemptId}`
     );
-    return handleApiResponse<ExecutionProcess[]>(response);
+    const page = await handleApiResponse<Paginated<ExecutionProcess>>(response);
+    return page.items;
   },
 
   getDetails: async (processId: string): Promise<ExecutionProcess> => {
@@ -466,6 +1346,113 @@ export const configApi = {
     });
     return handleApiResponse<Config>(response);
   },
+  getConfigSchema: async (): Promise<Record<string, unknown>> => {
+    const response = await makeRequest('/api/config/schema');
+    return handleApiResponse<Record<string, unknown>>(response);
+  },
+  // Previous configs, newest first
+  getHistory: async (): Promise<ConfigVersion[]> => {
+    const response = await makeRequest('/api/config/history');
+    return handleApiResponse<ConfigVersion[]>(response);
+  },
+  // Restore the nth previous config, 1 being the one before the last save
+  rollback: async (n: number): Promise<Config> => {
+    const response = await makeRequest(`/api/config/rollback/${n}`, {
+      method: 'POST',
+    });
+    return handleApiResponse<Config>(response);
+  },
+  // The user's JSON merge patch over the stored config, null without one
+  getOverride: async (): Promise<Record<string, unknown> | null> => {
+    const response = await makeRequest('/api/config/override');
+    return handleApiResponse<Record<string, unknown> | null>(response);
+  },
+  saveOverride: async (patch: Record<string, unknown>): Promise<Config> => {
+    const response = await makeRequest('/api/config/override', {
+      method: 'PUT',
+      body: JSON.stringify(patch),
+    });
+    return handleApiResponse<Config>(response);
+  },
+  clearOverride: async (): Promise<Config> => {
+    const response = await makeRequest('/api/config/override', {
+      method: 'DELETE',
+    });
+    return handleApiResponse<Config>(response);
+  },
+};
+
+// Editor APIs
+export const editorApi = {
+  // Open a file in an attempt's worktree, at a line when given
+  open: async (request: EditorOpenRequest): Promise<void> => {
+    const response = await makeRequest('/api/editor/open', {
+      method: 'POST',
+      body: JSON.stringify(request),
+    });
+    openEditorLink(await handleApiResponse<EditorOpenResponse>(response));
+  },
+};
+
+// Custom notification sounds
+export const soundsApi = {
+  list: async (): Promise<CustomSound[]> => {
+    const response = await makeRequest('/api/sounds');
+    return handleApiResponse<CustomSound[]>(response);
+  },
+  upload: async (file: File): Promise<CustomSound> => {
+    const response = await makeRequest(
+      `/api/sounds/${encodeURIComponent(file.name)}`,
+      {
+        method: 'PUT',
+        headers: { 'Content-Type': file.type || 'application/octet-stream' },
+        body: file,
+      }
+    );
+    return handleApiResponse<CustomSound>(response);
+  },
+  import: async (path: string): Promise<CustomSound> => {
+    const response = await makeRequest('/api/sounds/import', {
+      method: 'POST',
+      body: JSON.stringify({ path }),
+    });
+    return handleApiResponse<CustomSound>(response);
+  },
+  delete: async (name: string): Promise<void> => {
+    const response = await makeRequest(
+      `/api/sounds/${encodeURIComponent(name)}`,
+      { method: 'DELETE' }
+    );
+    return handleApiResponse<void>(response);
+  },
+};
+
+// Files attached to tasks, passed to agents when an attempt starts
+export const attachmentsApi = {
+  list: async (taskId: string): Promise<TaskAttachment[]> => {
+    const response = await makeRequest(`/api/tasks/${taskId}/attachments`);
+    return handleApiResponse<TaskAttachment[]>(response);
+  },
+  upload: async (taskId: string, file: File): Promise<TaskAttachment> => {
+    const response = await makeRequest(
+      `/api/tasks/${taskId}/attachments?file_name=${encodeURIComponent(file.name)}`,
+      {
+        method: 'POST',
+        headers: { 'Content-Type': file.type || 'application/octet-stream' },
+        body: file,
+      }
+    );
+    return handleApiResponse<TaskAttachment>(response);
+  },
+  url: (attachmentId: string): string =>
+    `/api/task-attachments/${attachmentId}`,
+  delete: async (attachmentId: string): Promise<void> => {
+    const response = await makeRequest(
+      `/api/task-attachments/${attachmentId}`,
+      { method: 'DELETE' }
+    );
+    return handleApiResponse<void>(response);
+  },
 };
 
 // GitHub Device Auth APIs
@@ -490,9 +1477,22 @@ export const githubAuthApi = {
 
 // GitHub APIs (only available in cloud mode)
 export const githubApi = {
-  listRepositories: async (page: number = 1): Promise<RepositoryInfo[]> => {
-    const response = await makeRequest(`/api/github/repositories?page=${page}`);
-    return handleApiResponse<RepositoryInfo[]>(response);
+  listRepositories: async (
+    query: string,
+    page: number = 1,
+    refresh: boolean = false
+  ): Promise<Result<Paginated<RepositoryInfo>, GitHubServiceError>> => {
+    const params = new URLSearchParams({
+      query,
+      page: String(page),
+      per_page: '20',
+    });
+    if (refresh) params.set('refresh', 'true');
+    const response = await makeRequest(`/api/github/repos?${params}`);
+    return handleApiResponseAsResult<
+      Paginated<RepositoryInfo>,
+      GitHubServiceError
+    >(response);
   },
   // createProjectFromRepository: async (
   //   data: CreateProjectFromGitHub
@@ -558,6 +1558,48 @@ export const templatesApi = {
   },
 };
 
+// Follow-up snippet APIs
+export const followUpSnippetsApi = {
+  listGlobal: async (): Promise<FollowUpSnippet[]> => {
+    const response = await makeRequest('/api/follow-up-snippets?global=true');
+    return handleApiResponse<FollowUpSnippet[]>(response);
+  },
+
+  // The project's snippets and the global ones it doesn't replace
+  listForProject: async (projectId: string): Promise<FollowUpSnippet[]> => {
+    const response = await makeRequest(
+      `/api/follow-up-snippets?project_id=${projectId}`
+    );
+    return handleApiResponse<FollowUpSnippet[]>(response);
+  },
+
+  create: async (data: CreateFollowUpSnippet): Promise<FollowUpSnippet> => {
+    const response = await makeRequest('/api/follow-up-snippets', {
+      method: 'POST',
+      body: JSON.stringify(data),
+    });
+    return handleApiResponse<FollowUpSnippet>(response);
+  },
+
+  update: async (
+    snippetId: string,
+    data: UpdateFollowUpSnippet
+  ): Promise<FollowUpSnippet> => {
+    const response = await makeRequest(`/api/follow-up-snippets/${snippetId}`, {
+      method: 'PUT',
+      body: JSON.stringify(data),
+    });
+    return handleApiResponse<FollowUpSnippet>(response);
+  },
+
+  delete: async (snippetId: string): Promise<void> => {
+    const response = await makeRequest(`/api/follow-up-snippets/${snippetId}`, {
+      method: 'DELETE',
+    });
+    return handleApiResponse<void>(response);
+  },
+};
+
 // MCP Servers APIs
 export const mcpServersApi = {
   load: async (query: McpServerQuery): Promise<GetMcpServerResponse> => {
@@ -609,3 +1651,425 @@ export const profilesApi = {
     return handleApiResponse<string>(response);
   },
 };
+
+// Maintenance APIs
+export const maintenanceApi = {
+  createBackup: async (): Promise<BackupInfo> => {
+    const response = await makeRequest('/api/maintenance/backup', {
+      method: 'POST',
+    });
+    return handleApiResponse<BackupInfo>(response);
+  },
+  listBackups: async (): Promise<BackupInfo[]> => {
+    const response = await makeRequest('/api/maintenance/backups');
+    return handleApiResponse<BackupInfo[]>(response);
+  },
+  restoreBackup: async (data: RestoreBackupRequest): Promise<BackupInfo> => {
+    const response = await makeRequest('/api/maintenance/restore', {
+      method: 'POST',
+      body: JSON.stringify(data),
+    });
+    return handleApiResponse<BackupInfo>(response);
+  },
+  pauseAgents: async (): Promise<number> => {
+    const response = await makeRequest('/api/maintenance/pause-agents', {
+      method: 'POST',
+    });
+    return handleApiResponse<number>(response);
+  },
+  resumeAgents: async (): Promise<number> => {
+    const response = await makeRequest('/api/maintenance/resume-agents', {
+      method: 'POST',
+    });
+    return handleApiResponse<number>(response);
+  },
+  checkForUpdate: async (): Promise<UpdateStatus> => {
+    const response = await makeRequest('/api/maintenance/update-check', {
+      method: 'POST',
+    });
+    return handleApiResponse<UpdateStatus>(response);
+  },
+  selfUpdate: async (): Promise<SelfUpdateOutcome> => {
+    const response = await makeRequest('/api/maintenance/self-update', {
+      method: 'POST',
+    });
+    return handleApiResponse<SelfUpdateOutcome>(response);
+  },
+};
+
+// Share Link APIs, read-only views of single attempts
+export const shareApi = {
+  get: async (token: string): Promise<SharedAttempt> => {
+    const response = await makeRequest(
+      `/api/share/${encodeURIComponent(token)}`
+    );
+    return handleApiResponse<SharedAttempt>(response);
+  },
+};
+
+// Presence APIs, what the running agents are doing
+export const presenceApi = {
+  list: async (projectId?: string): Promise<AgentPresence[]> => {
+    const query = projectId
+      ? `?${new URLSearchParams({ project_id: projectId })}`
+      : '';
+    const response = await makeRequest(`/api/presence${query}`);
+    return handleApiResponse<AgentPresence[]>(response);
+  },
+};
+
+// Feature Flag APIs
+export const featureFlagsApi = {
+  list: async (): Promise<FeatureFlagState[]> => {
+    const response = await makeRequest('/api/feature-flags');
+    return handleApiResponse<FeatureFlagState[]>(response);
+  },
+  set: async (
+    flag: FeatureFlag,
+    enabled: boolean
+  ): Promise<FeatureFlagState[]> => {
+    const response = await makeRequest(`/api/feature-flags/${flag}`, {
+      method: 'PUT',
+      body: JSON.stringify({ enabled }),
+    });
+    return handleApiResponse<FeatureFlagState[]>(response);
+  },
+};
+
+// Debug APIs
+export const debugApi = {
+  getServerLogs: async (tail: number): Promise<ServerLogs> => {
+    const response = await makeRequest(`/api/debug/server-logs?tail=${tail}`);
+    return handleApiResponse<ServerLogs>(response);
+  },
+  createSupportBundle: async (data: CreateSupportBundle): Promise<Blob> => {
+    const response = await makeRequest('/api/debug/support-bundle', {
+      method: 'POST',
+      body: JSON.stringify(data),
+    });
+    if (!response.ok) {
+      // Throws with the problem details of the failure
+      await handleApiResponse<void>(response);
+    }
+    return response.blob();
+  },
+};
+
+// Spend APIs
+export const analyticsApi = {
+  getEvents: async (
+    name?: string,
+    limit?: number
+  ): Promise<AnalyticsEvent[]> => {
+    const params = new URLSearchParams();
+    if (name) params.set('name', name);
+    if (limit !== undefined) params.set('limit', String(limit));
+    const query = params.toString();
+    const response = await makeRequest(
+      `/api/analytics/events${query ? `?${query}` : ''}`
+    );
+    return handleApiResponse<AnalyticsEvent[]>(response);
+  },
+  getSummary: async (): Promise<AnalyticsEventCount[]> => {
+    const response = await makeRequest('/api/analytics/summary');
+    return handleApiResponse<AnalyticsEventCount[]>(response);
+  },
+  getUsage: async (
+    groupBy: UsageGroupBy,
+    days?: number
+  ): Promise<UsageReport> => {
+    const params = new URLSearchParams({ group_by: groupBy });
+    if (days !== undefined) params.set('days', String(days));
+    const response = await makeRequest(`/api/analytics/usage?${params}`);
+    return handleApiResponse<UsageReport>(response);
+  },
+};
+
+export const spendApi = {
+  get: async (): Promise<SpendSummary> => {
+    const response = await makeRequest('/api/spend');
+    return handleApiResponse<SpendSummary>(response);
+  },
+  updateProjectBudget: async (
+    projectId: string,
+    monthlyCapUsd: number | null
+  ): Promise<ProjectBudget | null> => {
+    const response = await makeRequest(`/api/projects/${projectId}/budget`, {
+      method: 'PUT',
+      body: JSON.stringify({ monthly_cap_usd: monthlyCapUsd }),
+    });
+    return handleApiResponse<ProjectBudget | null>(response);
+  },
+};
+
+export const providerKeysApi = {
+  list: async (): Promise<ProviderKey[]> => {
+    const response = await makeRequest('/api/provider-keys');
+    return handleApiResponse<ProviderKey[]>(response);
+  },
+  set: async (provider: KeyProvider, key: string): Promise<ProviderKey> => {
+    const response = await makeRequest(`/api/provider-keys/${provider}`, {
+      method: 'PUT',
+      body: JSON.stringify({ key }),
+    });
+    return handleApiResponse<ProviderKey>(response);
+  },
+  validate: async (provider: KeyProvider): Promise<ProviderKey> => {
+    const response = await makeRequest(
+      `/api/provider-keys/${provider}/validate`,
+      { method: 'POST' }
+    );
+    return handleApiResponse<ProviderKey>(response);
+  },
+  delete: async (provider: KeyProvider): Promise<void> => {
+    const response = await makeRequest(`/api/provider-keys/${provider}`, {
+      method: 'DELETE',
+    });
+    return handleApiResponse<void>(response);
+  },
+};
+
+export const evaluationsApi = {
+  list: async (projectId: string): Promise<Evaluation[]> => {
+    const response = await makeRequest(
+      `/api/evaluations?project_id=${projectId}`
+    );
+    return handleApiResponse<Evaluation[]>(response);
+  },
+  create: async (data: CreateEvaluation): Promise<Evaluation> => {
+    const response = await makeRequest('/api/evaluations', {
+      method: 'POST',
+      body: JSON.stringify(data),
+    });
+    return handleApiResponse<Evaluation>(response);
+  },
+  getReport: async (id: string): Promise<EvaluationReport> => {
+    const response = await makeRequest(`/api/evaluations/${id}`);
+    return handleApiResponse<EvaluationReport>(response);
+  },
+  cancel: async (id: string): Promise<Evaluation> => {
+    const response = await makeRequest(`/api/evaluations/${id}/cancel`, {
+      method: 'POST',
+    });
+    return handleApiResponse<Evaluation>(response);
+  },
+};
+
+export const webhooksApi = {
+  list: async (): Promise<Webhook[]> => {
+    const response = await makeRequest('/api/webhooks');
+    return handleApiResponse<Webhook[]>(response);
+  },
+  create: async (data: CreateWebhook): Promise<WebhookWithSecret> => {
+    const response = await makeRequest('/api/webhooks', {
+      method: 'POST',
+      body: JSON.stringify(data),
+    });
+    return handleApiResponse<WebhookWithSecret>(response);
+  },
+  update: async (id: string, data: UpdateWebhook): Promise<Webhook> => {
+    const response = await makeRequest(`/api/webhooks/${id}`, {
+      method: 'PUT',
+      body: JSON.stringify(data),
+    });
+    return handleApiResponse<Webhook>(response);
+  },
+  delete: async (id: string): Promise<void> => {
+    const response = await makeRequest(`/api/webhooks/${id}`, {
+      method: 'DELETE',
+    });
+    return handleApiResponse<void>(response);
+  },
+  rotateSecret: async (id: string): Promise<string> => {
+    const response = await makeRequest(`/api/webhooks/${id}/secret`, {
+      method: 'POST',
+    });
+    return handleApiResponse<string>(response);
+  },
+  ping: async (id: string): Promise<WebhookDelivery> => {
+    const response = await makeRequest(`/api/webhooks/${id}/ping`, {
+      method: 'POST',
+    });
+    return handleApiResponse<WebhookDelivery>(response);
+  },
+  getDeliveries: async (id: string): Promise<WebhookDelivery[]> => {
+    const response = await makeRequest(`/api/webhooks/${id}/deliveries`);
+    return handleApiResponse<WebhookDelivery[]>(response);
+  },
+};
+
+export const sentryApi = {
+  getStatus: async (): Promise<SentryIntegrationStatus> => {
+    const response = await makeRequest('/api/integrations/sentry');
+    return handleApiResponse<SentryIntegrationStatus>(response);
+  },
+  setCredentials: async (
+    data: SetSentryCredentials
+  ): Promise<SentryIntegrationStatus> => {
+    const response = await makeRequest('/api/integrations/sentry', {
+      method: 'PUT',
+      body: JSON.stringify(data),
+    });
+    return handleApiResponse<SentryIntegrationStatus>(response);
+  },
+  importIssue: async (
+    data: ImportSentryIssue
+  ): Promise<InboundTaskResponse> => {
+    const response = await makeRequest('/api/integrations/sentry/issues', {
+      method: 'POST',
+      body: JSON.stringify(data),
+    });
+    return handleApiResponse<InboundTaskResponse>(response);
+  },
+};
+
+export const diskUsageApi = {
+  get: async (projectId: string): Promise<ProjectDiskUsage> => {
+    const response = await makeRequest(`/api/projects/${projectId}/disk-usage`);
+    return handleApiResponse<ProjectDiskUsage>(response);
+  },
+  updateQuota: async (
+    projectId: string,
+    maxBytes: number | null,
+    autoGc: boolean
+  ): Promise<ProjectDiskQuota | null> => {
+    const response = await makeRequest(
+      `/api/projects/${projectId}/disk-quota`,
+      {
+        method: 'PUT',
+        body: JSON.stringify({ max_bytes: maxBytes, auto_gc: autoGc }),
+      }
+    );
+    return handleApiResponse<ProjectDiskQuota | null>(response);
+  },
+  // Removes the worktrees of merged attempts
+  collect: async (projectId: string): Promise<ProjectDiskUsage> => {
+    const response = await makeRequest(
+      `/api/projects/${projectId}/disk-usage/gc`,
+      { method: 'POST' }
+    );
+    return handleApiResponse<ProjectDiskUsage>(response);
+  },
+};
+
+// Workspaces APIs
+export const workspacesApi = {
+  list: async (): Promise<WorkspacesResponse> => {
+    const response = await makeRequest('/api/workspaces');
+    return handleApiResponse<WorkspacesResponse>(response);
+  },
+  create: async (data: WorkspaceRequest): Promise<WorkspacesResponse> => {
+    const response = await makeRequest('/api/workspaces', {
+      method: 'POST',
+      body: JSON.stringify(data),
+    });
+    return handleApiResponse<WorkspacesResponse>(response);
+  },
+  select: async (data: WorkspaceRequest): Promise<WorkspacesResponse> => {
+    const response = await makeRequest('/api/workspaces/select', {
+      method: 'POST',
+      body: JSON.stringify(data),
+    });
+    return handleApiResponse<WorkspacesResponse>(response);
+  },
+};
+
+// Project Groups APIs
+export const projectGroupsApi = {
+  list: async (): Promise<ProjectGroup[]> => {
+    const response = await makeRequest('/api/project-groups');
+    return handleApiResponse<ProjectGroup[]>(response);
+  },
+
+  get: async (groupId: string): Promise<ProjectGroup> => {
+    const response = await makeRequest(`/api/project-groups/${groupId}`);
+    return handleApiResponse<ProjectGroup>(response);
+  },
+
+  create: async (data: CreateProjectGroup): Promise<ProjectGroup> => {
+    const response = await makeRequest('/api/project-groups', {
+      method: 'POST',
+      body: JSON.stringify(data),
+    });
+    return handleApiResponse<ProjectGroup>(response);
+  },
+
+  update: async (
+    groupId: string,
+    data: UpdateProjectGroup
+  ): Promise<ProjectGroup> => {
+    const response = await makeRequest(`/api/project-groups/${groupId}`, {
+      method: 'PUT',
+      body: JSON.stringify(data),
+    });
+    return handleApiResponse<ProjectGroup>(response);
+  },
+
+  delete: async (groupId: string): Promise<void> => {
+    const response = await makeRequest(`/api/project-groups/${groupId}`, {
+      method: 'DELETE',
+    });
+    return handleApiResponse<void>(response);
+  },
+
+  getBoard: async (groupId: string): Promise<ProjectGroupBoard> => {
+    const response = await makeRequest(`/api/project-groups/${groupId}/board`);
+    return handleApiResponse<ProjectGroupBoard>(response);
+  },
+
+  createTasks: async (
+    groupId: string,
+    data: CreateGroupTask
+  ): Promise<TaskWithAttemptStatus[]> => {
+    const response = await makeRequest(
+      `/api/project-groups/${groupId}/tasks`,
+      {
+        method: 'POST',
+        body: JSON.stringify(data),
+      }
+    );
+    return handleApiResponse<TaskWithAttemptStatus[]>(response);
+  },
+};
+
+// Onboarding APIs
+export const onboardingApi = {
+  startAgentLogin: async (
+    data: StartAgentLoginRequest
+  ): Promise<AgentLoginSession> => {
+    const response = await makeRequest('/api/onboarding/agent-logins', {
+      method: 'POST',
+      body: JSON.stringify(data),
+    });
+    return handleApiResponse<AgentLoginSession>(response);
+  },
+
+  getAgentLogin: async (loginId: string): Promise<AgentLoginSession> => {
+    const response = await makeRequest(
+      `/api/onboarding/agent-logins/${loginId}`
+    );
+    return handleApiResponse<AgentLoginSession>(response);
+  },
+
+  sendAgentLoginInput: async (
+    loginId: string,
+    data: AgentLoginInput
+  ): Promise<void> => {
+    const response = await makeRequest(
+      `/api/onboarding/agent-logins/${loginId}/input`,
+      {
+        method: 'POST',
+        body: JSON.stringify(data),
+      }
+    );
+    return handleApiResponse<void>(response);
+  },
+
+  cancelAgentLogin: async (loginId: string): Promise<AgentLoginSession> => {
+    const response = await makeRequest(
+      `/api/onboarding/agent-logins/${loginId}/cancel`,
+      { method: 'POST' }
+    );
+    return handleApiResponse<AgentLoginSession>(response);
+  },
+};
@@ -15,6 +15,7 @@ export interface KeyboardShortcutContext {
   navigate?: ReturnType<typeof useNavigate>;
   closeDialog?: () => void;
   onC?: () => void;
+  onQ?: () => void;
   currentPath?: string;
   hasOpenDialog?: boolean;
   location?: ReturnType<typeof useLocation>;
@@ -86,6 +87,15 @@ export const createKeyboardShortcuts = (
       }
     },
   },
+  KeyQ: {
+    key: 'q',
+    description: 'Quick create task from one line',
+    action: () => {
+      if (context.onQ) {
+        context.onQ();
+      }
+    },
+  },
   KeyS: {
     key: 's',
     description: 'Stop all executions',
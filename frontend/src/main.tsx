@@ -12,7 +12,11 @@ import {
   matchRoutes,
 } from 'react-router-dom';
 
+// The server marks the page when it runs offline, errors then stay local
+const offline = document.querySelector('meta[name="vibe-kanban-offline"]');
+
 Sentry.init({
+  enabled: !offline,
   dsn: 'https://1065a1d276a581316999a07d5dffee26@o4509603705192449.ingest.de.sentry.io/4509605576441937',
   tracesSampleRate: 1.0,
   environment: import.meta.env.MODE === 'development' ? 'dev' : 'production',
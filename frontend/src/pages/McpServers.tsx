@@ -76,7 +76,7 @@ export function McpServers() {
         setMcpServers(configJson);
         setMcpConfigPath(result.config_path);
       } catch (err: any) {
-        if (err?.message && err.message.includes('does not support MCP')) {
+        if (err?.code === 'mcp_unsupported') {
           setMcpError(err.message);
         } else {
           console.error('Error loading MCP servers:', err);
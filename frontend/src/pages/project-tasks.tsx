@@ -5,8 +5,14 @@ import { Card, CardContent } from '@/components/ui/card';
 import { Input } from '@/components/ui/input';
 import { FolderOpen, Plus, Settings, LibraryBig, Globe2 } from 'lucide-react';
 import { Loader } from '@/components/ui/loader';
-import { projectsApi, tasksApi, templatesApi } from '@/lib/api';
+import {
+  presenceApi,
+  projectsApi,
+  tasksApi,
+  templatesApi,
+} from '@/lib/api';
 import { TaskFormDialog } from '@/components/tasks/TaskFormDialog';
+import { QuickCreateDialog } from '@/components/tasks/QuickCreateDialog';
 import { ProjectForm } from '@/components/projects/project-form';
 import { TaskTemplateManager } from '@/components/TaskTemplateManager';
 import { useKeyboardShortcuts } from '@/lib/keyboard-shortcuts';
@@ -33,6 +39,8 @@ import {
 import TaskKanbanBoard from '@/components/tasks/TaskKanbanBoard';
 import { TaskDetailsPanel } from '@/components/tasks/TaskDetailsPanel';
 import type {
+  AgentPresence,
+  BoardDelta,
   TaskStatus,
   TaskWithAttemptStatus,
   Project,
@@ -43,6 +51,8 @@ import type { DragEndEvent } from '@/components/ui/shadcn-io/kanban';
 
 type Task = TaskWithAttemptStatus;
 
+const PRESENCE_REFRESH_MS = 3000;
+
 export function ProjectTasks() {
   const { projectId, taskId } = useParams<{
     projectId: string;
@@ -54,6 +64,7 @@ export function ProjectTasks() {
   const [loading, setLoading] = useState(true);
   const [error, setError] = useState<string | null>(null);
   const [isTaskDialogOpen, setIsTaskDialogOpen] = useState(false);
+  const [isQuickCreateOpen, setIsQuickCreateOpen] = useState(false);
   const [editingTask, setEditingTask] = useState<Task | null>(null);
   const [isProjectSettingsOpen, setIsProjectSettingsOpen] = useState(false);
   const [searchQuery, setSearchQuery] = useState('');
@@ -67,6 +78,10 @@ export function ProjectTasks() {
 
   // Panel state
   const [selectedTask, setSelectedTask] = useState<Task | null>(null);
+  const [conflictingFiles, setConflictingFiles] = useState<
+    Record<string, string[]>
+  >({});
+  const [presence, setPresence] = useState<Record<string, AgentPresence>>({});
   const [isPanelOpen, setIsPanelOpen] = useState(false);
 
   // Define task creation handler
@@ -168,6 +183,34 @@ export function ProjectTasks() {
     [projectId]
   );
 
+  const applyBoardDelta = useCallback((delta: BoardDelta) => {
+    switch (delta.type) {
+      case 'SNAPSHOT':
+        setTasks(delta.tasks);
+        setSelectedTask((prev) =>
+          prev ? delta.tasks.find((task) => task.id === prev.id) || prev : prev
+        );
+        break;
+      case 'TASK_UPSERTED':
+        setTasks((prevTasks) =>
+          prevTasks.some((task) => task.id === delta.task.id)
+            ? prevTasks.map((task) =>
+                task.id === delta.task.id ? delta.task : task
+              )
+            : [...prevTasks, delta.task]
+        );
+        setSelectedTask((prev) =>
+          prev && prev.id === delta.task.id ? delta.task : prev
+        );
+        break;
+      case 'TASK_REMOVED':
+        setTasks((prevTasks) =>
+          prevTasks.filter((task) => task.id !== delta.task_id)
+        );
+        break;
+    }
+  }, []);
+
   const handleCreateTask = useCallback(
     async (title: string, description: string) => {
       try {
@@ -176,6 +219,9 @@ export function ProjectTasks() {
           title,
           description: description || null,
           parent_task_attempt: null,
+          priority: null,
+          labels: [],
+          base_branch: null,
         });
         await fetchTasks();
         // Open the newly created task in the details panel
@@ -197,6 +243,9 @@ export function ProjectTasks() {
           title,
           description: description || null,
           parent_task_attempt: null,
+          priority: null,
+          labels: [],
+          base_branch: null,
         };
         const result = await tasksApi.createAndStart(payload);
         await fetchTasks();
@@ -316,9 +365,13 @@ export function ProjectTasks() {
     navigate,
     currentPath: window.location.pathname,
     hasOpenDialog:
-      isTaskDialogOpen || isTemplateManagerOpen || isProjectSettingsOpen,
+      isTaskDialogOpen ||
+      isQuickCreateOpen ||
+      isTemplateManagerOpen ||
+      isProjectSettingsOpen,
     closeDialog: () => setIsTaskDialogOpen(false),
     onC: handleCreateNewTask,
+    onQ: () => setIsQuickCreateOpen(true),
   });
 
   // Initialize data when projectId changes
@@ -328,16 +381,65 @@ export function ProjectTasks() {
       fetchTasks();
       fetchTemplates();
 
-      // Set up polling to refresh tasks every 5 seconds
-      const interval = setInterval(() => {
-        fetchTasks(true); // Skip loading spinner for polling
-      }, 2000);
+      // Keep the board in sync with changes pushed by the server
+      const eventSource = new EventSource(
+        `/api/projects/${projectId}/board/stream`
+      );
+      eventSource.addEventListener('board', (event) => {
+        try {
+          const delta: BoardDelta = JSON.parse((event as MessageEvent).data);
+          applyBoardDelta(delta);
+        } catch (err) {
+          console.error('Error parsing board update:', err);
+        }
+      });
 
-      // Cleanup interval on unmount
-      return () => clearInterval(interval);
+      return () => eventSource.close();
     }
   }, [projectId]);
 
+  // Edits of running agents show up as board changes, check for overlapping files after each
+  useEffect(() => {
+    if (!projectId) return;
+    const timeout = setTimeout(async () => {
+      try {
+        const conflicts = await projectsApi.getConflicts(projectId);
+        const files: Record<string, string[]> = {};
+        for (const conflict of conflicts) {
+          for (const taskId of [conflict.task_id, conflict.other_task_id]) {
+            files[taskId] = [
+              ...new Set([...(files[taskId] || []), ...conflict.paths]),
+            ];
+          }
+        }
+        setConflictingFiles(files);
+      } catch (err) {
+        console.error('Failed to load conflicts:', err);
+      }
+    }, 1000);
+    return () => clearTimeout(timeout);
+  }, [projectId, tasks]);
+
+  // Card badges with what each running agent is doing
+  useEffect(() => {
+    if (!projectId) return;
+    const fetchPresence = async () => {
+      try {
+        const presences = await presenceApi.list(projectId);
+        const byTask: Record<string, AgentPresence> = {};
+        for (const agent of presences) {
+          byTask[agent.task_id] = agent;
+        }
+        setPresence(byTask);
+      } catch (err) {
+        console.error('Failed to load agent presence:', err);
+      }
+    };
+    fetchPresence();
+    const interval = setInterval(fetchPresence, PRESENCE_REFRESH_MS);
+    return () => clearInterval(interval);
+  }, [projectId]);
+
   // Handle direct navigation to task URLs
   useEffect(() => {
     if (taskId && tasks.length > 0) {
@@ -502,6 +604,8 @@ export function ProjectTasks() {
                 onDeleteTask={handleDeleteTask}
                 onViewTaskDetails={handleViewTaskDetails}
                 isPanelOpen={isPanelOpen}
+                conflictingFiles={conflictingFiles}
+                presence={presence}
               />
             </div>
           </div>
@@ -538,6 +642,16 @@ export function ProjectTasks() {
         initialTemplate={selectedTemplate}
       />
 
+      <QuickCreateDialog
+        open={isQuickCreateOpen}
+        onOpenChange={setIsQuickCreateOpen}
+        projectId={projectId}
+        onCreated={async (task) => {
+          await fetchTasks();
+          navigate(`/projects/${task.project_id}/tasks/${task.id}`);
+        }}
+      />
+
       <ProjectForm
         open={isProjectSettingsOpen}
         onClose={() => setIsProjectSettingsOpen(false)}
@@ -0,0 +1,137 @@
+import { useEffect, useMemo, useState } from 'react';
+import { useParams } from 'react-router-dom';
+import { generateDiffFile } from '@git-diff-view/file';
+import { GitBranch, Link2Off } from 'lucide-react';
+import DiffCard from '@/components/DiffCard';
+import DisplayConversationEntry from '@/components/NormalizedConversation/DisplayConversationEntry';
+import { Loader } from '@/components/ui/loader';
+import MarkdownRenderer from '@/components/ui/markdown-renderer.tsx';
+import { shareApi } from '@/lib/api';
+import { getHighLightLanguageFromPath } from '@/utils/extToLanguage';
+import { toPrettyCase } from '@/utils/string';
+import type { Diff, SharedAttempt } from 'shared/types';
+
+const createDiffFile = (diff: Diff) => {
+  const oldFileName = diff.oldFile?.fileName || 'old';
+  const newFileName = diff.newFile?.fileName || 'new';
+  try {
+    const instance = generateDiffFile(
+      oldFileName,
+      diff.oldFile?.content || '',
+      newFileName,
+      diff.newFile?.content || '',
+      getHighLightLanguageFromPath(oldFileName) || 'plaintext',
+      getHighLightLanguageFromPath(newFileName) || 'plaintext'
+    );
+    instance.initRaw();
+    return instance;
+  } catch (error) {
+    console.error('Failed to parse diff:', error);
+    return null;
+  }
+};
+
+// What a share link opens: the attempt's conversation and diff, read-only and
+// without the navbar, since whoever has the link can't use the rest of the app
+export function SharedAttemptPage() {
+  const { token } = useParams<{ token: string }>();
+  const [shared, setShared] = useState<SharedAttempt | null>(null);
+  const [error, setError] = useState<string | null>(null);
+
+  useEffect(() => {
+    if (!token) return;
+    shareApi
+      .get(token)
+      .then(setShared)
+      .catch((err) =>
+        setError(err instanceof Error ? err.message : 'Failed to load attempt')
+      );
+  }, [token]);
+
+  const diffFiles = useMemo(
+    () =>
+      (shared?.diffs ?? [])
+        .map(createDiffFile)
+        .filter((diffFile) => diffFile !== null),
+    [shared]
+  );
+
+  if (error) {
+    return (
+      <div className="flex h-full flex-col items-center justify-center gap-2 p-8 text-center">
+        <Link2Off className="h-8 w-8 text-muted-foreground" />
+        <p className="font-medium">This link doesn&apos;t work anymore</p>
+        <p className="text-sm text-muted-foreground">{error}</p>
+      </div>
+    );
+  }
+
+  if (!shared) {
+    return <Loader message="Loading attempt..." size={32} className="py-8" />;
+  }
+
+  return (
+    <div className="mx-auto max-w-5xl space-y-8 p-6">
+      <div className="space-y-2">
+        <h1 className="text-2xl font-semibold">{shared.task_title}</h1>
+        <p className="flex flex-wrap items-center gap-2 text-sm text-muted-foreground">
+          <span>{shared.profile}</span>
+          {shared.branch && (
+            <span className="flex items-center gap-1 font-mono">
+              <GitBranch className="h-3 w-3" />
+              {shared.branch} → {shared.base_branch}
+            </span>
+          )}
+          {shared.merged && <span>Merged</span>}
+          <span>{new Date(shared.created_at).toLocaleString()}</span>
+          {shared.expires_at && (
+            <span>
+              Link expires {new Date(shared.expires_at).toLocaleString()}
+            </span>
+          )}
+        </p>
+        {shared.task_description && (
+          <MarkdownRenderer
+            content={shared.task_description}
+            className="text-sm"
+          />
+        )}
+      </div>
+
+      <section className="space-y-4">
+        <h2 className="text-lg font-semibold">Conversation</h2>
+        {shared.processes.length === 0 && (
+          <p className="text-sm text-muted-foreground">No agent output yet.</p>
+        )}
+        {shared.processes.map((process, processIndex) => (
+          <div key={processIndex} className="space-y-2 rounded border p-4">
+            <p className="text-xs text-muted-foreground">
+              {toPrettyCase(process.run_reason)} ·{' '}
+              {toPrettyCase(process.status)} ·{' '}
+              {new Date(process.started_at).toLocaleString()}
+            </p>
+            {process.entries.map((entry, index) => (
+              <DisplayConversationEntry
+                key={entry.timestamp ?? index}
+                entry={entry}
+                index={index}
+                diffDeletable={false}
+              />
+            ))}
+          </div>
+        ))}
+      </section>
+
+      <section>
+        <h2 className="text-lg font-semibold">Changes</h2>
+        {diffFiles.length === 0 ? (
+          <p className="mt-4 text-sm text-muted-foreground">No changes.</p>
+        ) : (
+          diffFiles.map((diffFile, index) => (
+            <DiffCard key={index} diffFile={diffFile} readOnly />
+          ))
+        )}
+      </section>
+    </div>
+  );
+}
@@ -22,6 +22,12 @@ export default defineConfig({
       '/api': {
         target: `http://localhost:${process.env.BACKEND_PORT || '3001'}`,
         changeOrigin: true,
+        ws: true,
+      },
+      '/preview': {
+        target: `http://localhost:${process.env.BACKEND_PORT || '3001'}`,
+        changeOrigin: true,
+        ws: true,
       },
     },
   },
@@ -60,6 +60,31 @@ function getBinaryName(base) {
   return platform === "win32" ? `${base}.exe` : base;
 }
 
+// The server exits with this code when the user asked to update from the app
+const UPDATE_EXIT_CODE = 75;
+
+// npx runs the package from its cache, asking for @latest fetches the new
+// version. A global install is updated in place and started again.
+function installUpdateAndRestart() {
+  const args = process.argv.slice(2);
+  const isNpx = __dirname.split(path.sep).includes("_npx");
+  console.log(`⬆️  Updating vibe-kanban...`);
+  try {
+    if (isNpx) {
+      execSync(`npx -y vibe-kanban@latest ${args.join(" ")}`, {
+        stdio: "inherit",
+      });
+    } else {
+      execSync("npm install -g vibe-kanban@latest", { stdio: "inherit" });
+      execSync(`"${process.execPath}" "${process.argv[1]}" ${args.join(" ")}`, {
+        stdio: "inherit",
+      });
+    }
+  } catch (e) {
+    process.exit(e.status || 1);
+  }
+}
+
 const platformDir = getPlatformDir();
 const extractDir = path.join(__dirname, "..", "dist", platformDir);
 const isMcpMode = process.argv.includes("--mcp");
@@ -115,10 +140,15 @@ if (isMcpMode) {
   console.log(`📦 Extracting vibe-kanban...`);
   extractAndRun("vibe-kanban", (bin) => {
     console.log(`🚀 Launching vibe-kanban...`);
-    if (platform === "win32") {
-      execSync(`"${bin}"`, { stdio: "inherit" });
-    } else {
-      execSync(`"${bin}"`, { stdio: "inherit" });
+    try {
+      // Tells the server it can ask for updates to be installed
+      execSync(`"${bin}"`, {
+        stdio: "inherit",
+        env: { ...process.env, VIBE_KANBAN_LAUNCHER: "npx" },
+      });
+    } catch (e) {
+      if (e.status !== UPDATE_EXIT_CODE) process.exit(e.status || 1);
+      installUpdateAndRestart();
     }
   });
 }
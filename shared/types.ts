@@ -4,81 +4,1334 @@
 
 // If you are an AI, and you absolutely have to edit this file, please confirm with the user first.
 
+// Sent as `x-api-version`, the server rejects requests built against other types
+export const API_VERSION = "ebf55e45c88c9954";
+
 export type DirectoryEntry = { name: string, path: string, is_directory: boolean, is_git_repo: boolean, last_modified: bigint | null, };
 
-export type DirectoryListResponse = { entries: Array<DirectoryEntry>, current_path: string, };
+export type DirectoryListResponse = { entries: Array<DirectoryEntry>, current_path: string, };
+
+export type Project = { id: string, name: string, git_repo_path: string, setup_script: string | null, dev_script: string | null, cleanup_script: string | null, copy_files: string | null, test_script: string | null, test_result_format: TestResultFormat | null, created_at: Date, updated_at: Date, };
+
+export type ProjectWithBranch = { id: string, name: string, git_repo_path: string, setup_script: string | null, dev_script: string | null, cleanup_script: string | null, copy_files: string | null, test_script: string | null, test_result_format: TestResultFormat | null, current_branch: string | null, created_at: Date, updated_at: Date, };
+
+export type CreateProject = { name: string, git_repo_path: string, use_existing_repo: boolean, setup_script: string | null, dev_script: string | null, cleanup_script: string | null, copy_files: string | null, test_script: string | null, test_result_format: TestResultFormat | null, };
+
+export type UpdateProject = { name: string | null, git_repo_path: string | null, setup_script: string | null, dev_script: string | null, cleanup_script: string | null, copy_files: string | null, test_script: string | null, test_result_format: TestResultFormat | null, };
+
+export type SearchResult = { path: string, is_file: boolean, match_type: SearchMatchType, };
+
+export type SearchMatchType = "FileName" | "DirectoryName" | "FullPath";
+
+export type TestResultFormat = "junit" | "cargo_json";
+
+export type ExecutorAction = { typ: ExecutorActionType, next_action: ExecutorAction | null, };
+
+export type McpConfig = { servers: { [key in string]?: JsonValue }, servers_path: Array<string>, template: JsonValue, vibe_kanban: JsonValue, is_toml_config: boolean, };
+
+export type ExecutorActionType = { "type": "CodingAgentInitialRequest" } & CodingAgentInitialRequest | { "type": "CodingAgentFollowUpRequest" } & CodingAgentFollowUpRequest | { "type": "ScriptRequest" } & ScriptRequest;
+
+export type ScriptContext = "SetupScript" | "CleanupScript" | "DevServer" | "VerificationScript" | "TestScript" | "LintScript";
+
+export type ScriptRequest = { script: string, language: ScriptRequestLanguage, context: ScriptContext, };
+
+export type ScriptRequestLanguage = "Bash";
+
+export type TaskTemplate = { id: string, project_id: string | null, title: string, description: string | null, template_name: string, created_at: string, updated_at: string, };
+
+export type CreateTaskTemplate = { project_id: string | null, title: string, description: string | null, template_name: string, };
+
+export type UpdateTaskTemplate = { title: string | null, description: string | null, template_name: string | null, };
+
+export type FollowUpSnippet = { id: string, 
+/**
+ * `None` for global snippets, a project's snippet replaces the global one of the same name
+ */
+project_id: string | null, name: string, prompt: string, created_at: string, updated_at: string, };
+
+export type CreateFollowUpSnippet = { project_id: string | null, name: string, prompt: string, };
+
+export type UpdateFollowUpSnippet = { name: string | null, prompt: string | null, };
+
+export type ProjectGroup = { id: string, name: string, 
+/**
+ * Member projects in swimlane order
+ */
+project_ids: Array<string>, created_at: string, updated_at: string, };
+
+export type CreateProjectGroup = { name: string, project_ids: Array<string>, };
+
+export type UpdateProjectGroup = { name: string | null, 
+/**
+ * Replaces the members, in swimlane order
+ */
+project_ids: Array<string> | null, };
+
+export type TaskAttachment = { id: string, task_id: string, file_name: string, content_type: string, size_bytes: number, created_at: string, };
+
+export type QueuedFollowUp = { id: string, task_attempt_id: string, prompt: string, variant: string | null, position: number, created_at: string, };
+
+export type CreateQueuedFollowUp = { prompt: string, variant: string | null, };
+
+export type ReorderQueuedFollowUps = { 
+/**
+ * Queued follow-up ids in their new dispatch order
+ */
+ids: Array<string>, };
+
+export type AttemptRuntime = { running_ms: number, paused_ms: number, 
+/**
+ * Whether an execution of the attempt is paused right now
+ */
+paused: boolean, };
+
+export type SuspendReason = "shutdown" | "crash";
+
+export type SuspendedExecution = { id: string, execution_process_id: string, task_attempt_id: string, session_id: string | null, reason: SuspendReason, profile: string | null, variant: string | null, 
+/**
+ * Worktree the execution ran in, recreated from the attempt branch if it's gone
+ */
+worktree_path: string | null, 
+/**
+ * Prompt of the stopped execution
+ */
+prompt: string | null, resumed_at: string | null, created_at: string, };
+
+export type CheckpointReason = "before_execution" | "periodic" | "agent_finished" | "before_rollback";
+
+export type AttemptCheckpoint = { id: string, task_attempt_id: string, execution_process_id: string | null, reason: CheckpointReason, commit_sha: string, head_commit: string, created_at: string, };
+
+export type AttemptUnmerge = { id: string, task_attempt_id: string, base_branch: string, merge_commit: string, revert_commit: string, 
+/**
+ * Commits the base branch had on top of the merge when it was reverted
+ */
+subsequent_commits: number, created_at: string, };
+
+export type ProjectBudget = { project_id: string, monthly_cap_usd: number, updated_at: string, };
+
+export type ProjectPathRules = { project_id: string, 
+/**
+ * Only these paths may change, any path when empty
+ */
+allowed_paths: Array<string>, 
+/**
+ * These paths may never change, even when allowed
+ */
+forbidden_paths: Array<string>, 
+/**
+ * Ask the agent to revert its out-of-scope changes once its run completes
+ */
+revert_violations: boolean, updated_at: string, };
+
+export type UpdateProjectPathRules = { allowed_paths: Array<string>, forbidden_paths: Array<string>, revert_violations: boolean, };
+
+export type UpdateProjectBudget = { 
+/**
+ * `null` removes the cap
+ */
+monthly_cap_usd: number | null, };
+
+export type ProjectDiskQuota = { project_id: string, max_bytes: number, 
+/**
+ * Remove the worktrees of merged attempts before refusing a new attempt
+ */
+auto_gc: boolean, updated_at: string, };
+
+export type UpdateProjectDiskQuota = { 
+/**
+ * `null` removes the quota
+ */
+max_bytes: number | null, auto_gc: boolean, };
+
+export type KeyProvider = "anthropic" | "openai" | "google" | "sourcegraph";
+
+export type ProviderKey = { provider: KeyProvider, 
+/**
+ * Last characters of the key, to tell keys apart
+ */
+key_hint: string, 
+/**
+ * Whether the provider accepted the key, `None` before it was checked or when the
+ * provider couldn't be reached
+ */
+valid: boolean | null, validation_error: string | null, validated_at: string | null, updated_at: string, };
+
+export type SetProviderKey = { key: string, };
+
+export type TestRun = { id: string, task_attempt_id: string, execution_process_id: string, passed: number, failed: number, skipped: number, failed_tests: Array<string>, created_at: string, };
+
+export type RiskFlag = { "kind": "large_diff", lines_changed: number, } | { "kind": "outside_expected_paths", files: Array<string>, } | { "kind": "ci_changes", files: Array<string>, } | { "kind": "deletion_heavy", lines_removed: number, lines_added: number, } | { "kind": "dependency_changes", files: Array<string>, };
+
+export type DiffRisk = { files_changed: number, lines_added: number, lines_removed: number, flags: Array<RiskFlag>, };
+
+export type TaskStatus = "todo" | "inprogress" | "inreview" | "done" | "cancelled";
+
+/**
+ * Declared from most to least pressing, so sorting by priority puts urgent tasks first
+ */
+export type TaskPriority = "urgent" | "high" | "medium" | "low";
+
+export type Task = { id: string, project_id: string, title: string, description: string | null, status: TaskStatus, parent_task_attempt: string | null, priority: TaskPriority | null, labels: Array<string>, 
+/**
+ * Branch or tag attempts start from, `None` for the project's current branch
+ */
+base_branch: string | null, created_at: string, updated_at: string, };
+
+export type TaskWithAttemptStatus = { id: string, project_id: string, title: string, description: string | null, status: TaskStatus, parent_task_attempt: string | null, priority: TaskPriority | null, labels: Array<string>, 
+/**
+ * Branch or tag attempts start from, `None` for the project's current branch
+ */
+base_branch: string | null, created_at: string, updated_at: string, has_in_progress_attempt: boolean, has_merged_attempt: boolean, last_attempt_failed: boolean, profile: string, 
+/**
+ * Counts of the latest test run across the task's attempts
+ */
+tests_passed: number | null, tests_failed: number | null, 
+/**
+ * Diff risk of the task's latest attempt, once an agent run finished
+ */
+diff_risk: DiffRisk | null, 
+/**
+ * Outcome of the latest automatic sync of the latest attempt's branch with its base
+ */
+branch_sync_status: BranchSyncStatus | null, 
+/**
+ * Phase of the latest attempt when it plans before executing
+ */
+plan_status: PlanStatus | null, 
+/**
+ * Verdict of the latest review of the latest attempt
+ */
+review_verdict: ReviewVerdict | null, };
+
+export type CreateTask = { project_id: string, title: string, description: string | null, parent_task_attempt: string | null, priority: TaskPriority | null, labels: Array<string>, base_branch: string | null, };
+
+export type UpdateTask = { title: string | null, description: string | null, status: TaskStatus | null, parent_task_attempt: string | null, };
+
+/**
+ * One line typed in the command palette, e.g. `fix flaky auth test !p1 #backend @codex`
+ */
+export type QuickCreateTask = { text: string, 
+/**
+ * Project the task goes to unless the line names one, usually the board being looked at
+ */
+project_id: string | null, };
+
+export type ApiResponse<T, E = T> = { success: boolean, data: T | null, error_data: E | null, message: string | null, 
+/**
+ * Catalog code of `message`, for clients that show their own text
+ */
+code: MessageCode | null, 
+/**
+ * Problems that didn't stop the request, e.g. unknown fields that were ignored
+ */
+warnings: Array<ApiWarning>, 
+/**
+ * Set on list responses
+ */
+pagination: PageMeta | null, 
+/**
+ * Also in the `x-request-id` header, for finding the request in the server log
+ */
+request_id: string | null, };
+
+/**
+ * A problem that didn't stop the request, e.g. part of the input being ignored
+ */
+export type ApiWarning = { code: MessageCode, 
+/**
+ * In the locale from the user's config
+ */
+message: string, };
+
+export type Locale = "en" | "de" | "es" | "fr";
+
+export type MessageCode = "project_error" | "task_attempt_error" | "git_service_error" | "github_service_error" | "auth_error" | "deployment_error" | "container_error" | "executor_error" | "database_error" | "worktree_error" | "config_error" | "backup_error" | "agent_login_error" | "repo_config_error" | "sound_error" | "pty_error" | "attachment_error" | "budget_error" | "secret_scan_error" | "path_rules_error" | "context_pack_error" | "disk_quota_error" | "checkpoint_error" | "project_env_error" | "worktree_template_error" | "merge_queue_error" | "project_template_error" | "repo_clone_error" | "provider_key_error" | "evaluation_error" | "webhook_error" | "sentry_error" | "dependency_update_error" | "changelog_error" | "support_bundle_error" | "self_update_error" | "share_link_error" | "bad_request" | "precondition_failed" | "request_body_too_large" | "rate_limited" | "idempotency_key_in_progress" | "idempotency_key_reused" | "response_read_failed" | "api_version_mismatch" | "directory_not_found" | "not_a_directory" | "directory_read_failed" | "path_not_found" | "path_not_a_directory" | "not_a_git_repository" | "main_branch_setup_failed" | "directory_create_failed" | "git_init_failed" | "project_repo_path_taken" | "project_group_name_taken" | "search_query_required" | "github_push_failed" | "pull_request_create_failed" | "dev_server_script_missing" | "unmerge_needs_confirmation" | "attempt_finished_title" | "attempt_succeeded" | "attempt_failed" | "attempt_needs_attention_title" | "attempt_needs_attention" | "config_invalid" | "profile_not_found" | "mcp_unsupported" | "mcp_config_path_unknown" | "mcp_update_failed" | "profiles_invalid" | "profiles_unknown_fields" | "profiles_unreadable";
+
+/**
+ * Error body in the RFC 7807 problem details format
+ */
+export type ProblemDetails = { 
+/**
+ * `urn:vibe-kanban:problem:{code}`
+ */
+type: string, 
+/**
+ * Reason phrase of `status`
+ */
+title: string, status: number, 
+/**
+ * What went wrong, in the locale from the user's config
+ */
+detail: string, code: MessageCode, errors: Array<ProblemField>, };
+
+/**
+ * A problem with one field of the request body
+ */
+export type ProblemField = { 
+/**
+ * JSON pointer to the field, e.g. `/editor/custom_command`
+ */
+pointer: string, detail: string, };
+
+export type Paginated<T> = { items: Array<T>, 
+/**
+ * Number of items matching the filters, across all pages
+ */
+total: number, page: number, 
+/**
+ * `null` when the request was not paginated and every item was returned
+ */
+per_page: number | null, has_more: boolean, };
+
+/**
+ * Where a page sits in its list, repeated in the response envelope so clients can read it the
+ * same way for every list endpoint
+ */
+export type PageMeta = { total: number, page: number, per_page: number | null, has_more: boolean, };
+
+export type UserSystemInfo = { config: Config, environment: Environment, 
+/**
+ * Whether a newer version is out, refreshed in the background
+ */
+update: UpdateStatus, 
+/**
+ * Experimental subsystems in use, environment overrides applied
+ */
+feature_flags: FeatureFlags, profiles: Array<ProfileConfig>, };
+
+export type Environment = { os_type: string, os_version: string, os_architecture: string, bitness: string, };
+
+/**
+ * Whether a newer version is published, as last checked against the npm registry
+ */
+export type UpdateStatus = { current_version: string, 
+/**
+ * `None` until the registry has been checked
+ */
+latest_version: string | null, update_available: boolean, 
+/**
+ * Changelog of the latest version, from its GitHub release
+ */
+release_notes: string | null, release_url: string | null, checked_at: string | null, 
+/**
+ * Only servers started by the npx wrapper can be updated from the app
+ */
+can_self_update: boolean, };
+
+/**
+ * What `POST /maintenance/self-update` did
+ */
+export type SelfUpdateOutcome = { "type": "restarting", version: string, } | { "type": "manual", instructions: string, } | { "type": "up_to_date" };
+
+/**
+ * An experimental subsystem gated by [`FeatureFlags`]
+ */
+export type FeatureFlag = "multi_agent_pipelines" | "container_sandbox" | "remote_exec" | "multi_repo_attempts";
+
+/**
+ * A flag's value and where it comes from, for the settings page
+ */
+export type FeatureFlagState = { flag: FeatureFlag, description: string, 
+/**
+ * Whether the subsystem is in use, the override if there is one
+ */
+enabled: boolean, 
+/**
+ * Value from the config file
+ */
+configured: boolean, env_var: string, 
+/**
+ * Value of `env_var` when it is set, it can't be toggled from the app then
+ */
+env_override: boolean | null, };
+
+export type SetFeatureFlag = { enabled: boolean, };
+
+export type McpServerQuery = { profile: string, };
+
+export type UpdateMcpServersBody = { servers: { [key in string]?: JsonValue }, };
+
+export type GetMcpServerResponse = { mcp_config: McpConfig, config_path: string, };
+
+/**
+ * The end of the server's JSON lines log, for attaching to bug reports
+ */
+export type ServerLogs = { 
+/**
+ * Where the log file lives, rotated copies sit next to it
+ */
+path: string, 
+/**
+ * Log events oldest first, as written by the tracing JSON formatter
+ */
+entries: Array<JsonValue>, };
+
+export type CreateSupportBundle = { 
+/**
+ * Attempt whose normalized agent logs are included, typically the one that failed
+ */
+attempt_id: string | null, 
+/**
+ * Most recent server log entries included, 1000 by default
+ */
+server_log_tail: number | null, };
+
+export type CreateFollowUpAttempt = { 
+/**
+ * Added after the snippet's prompt when there is one
+ */
+prompt: string, variant: string | null, 
+/**
+ * A follow-up snippet, global or of the attempt's project, to start the prompt with
+ */
+snippet_id: string | null, };
+
+export type CreateGitHubPrRequest = { title: string, body: string | null, base_branch: string | null, 
+/**
+ * Push the branch to the user's fork and open the pull request from there. Left
+ * out, the fork is used when the user can't push to the repository.
+ */
+push_to_fork: boolean | null, };
+
+export enum GitHubServiceError { TOKEN_INVALID = "TOKEN_INVALID", INSUFFICIENT_PERMISSIONS = "INSUFFICIENT_PERMISSIONS", REPO_NOT_FOUND_OR_NO_ACCESS = "REPO_NOT_FOUND_OR_NO_ACCESS" }
+
+export type Config = { config_version: string, theme: ThemeMode, profile: ProfileVariantLabel, disclaimer_acknowledged: boolean, onboarding_acknowledged: boolean, github_login_acknowledged: boolean, telemetry_acknowledged: boolean, notifications: NotificationConfig, editor: EditorConfig, github: GitHubConfig, analytics_enabled: boolean | null, workspace_dir: string | null, shutdown: ShutdownConfig, backup: BackupConfig, terminal: TerminalConfig, 
+/**
+ * Set while every agent is paused, so nothing starts until they are resumed. Only
+ * changed through the pause endpoints, which also suspend running agents.
+ */
+agents_paused: boolean, budget: BudgetConfig, telemetry: TelemetryConfig, 
+/**
+ * Language of error and status messages from the server
+ */
+language: Locale, 
+/**
+ * Encoding of agent and script output, as a label such as `utf-16le` or `windows-1252`.
+ * Unset detects it from the output and falls back to the locale's charset, then UTF-8.
+ */
+output_encoding: string | null, 
+/**
+ * Merge attempts that changed the same files as another unmerged attempt one at a time
+ */
+serialize_conflicting_merges: boolean, 
+/**
+ * Refuse local merges until GitHub checks of the pushed attempt branch passed
+ */
+require_passing_checks: boolean, 
+/**
+ * Experimental subsystems turned on, `VK_FEATURE_<NAME>` variables override them
+ */
+feature_flags: FeatureFlags, };
+
+export type NotificationConfig = { sound_enabled: boolean, push_enabled: boolean, sound_file: SoundFile, event_sounds: EventSounds, };
+
+/**
+ * Sound played for each kind of notification, either a `SoundFile` name or the file name
+ * of a custom sound. Events without one play `sound_file`.
+ */
+export type EventSounds = { success: string | null, failure: string | null, needs_attention: string | null, };
+
+export enum ThemeMode { LIGHT = "LIGHT", DARK = "DARK", SYSTEM = "SYSTEM", PURPLE = "PURPLE", GREEN = "GREEN", BLUE = "BLUE", ORANGE = "ORANGE", RED = "RED" }
+
+export type EditorConfig = { editor_type: EditorType, custom_command: string | null, 
+/**
+ * Host the server runs on, as seen from the browser's machine. When set, VS Code style
+ * editors open worktrees over Remote SSH, like `code --remote ssh-remote+<host>`.
+ */
+remote_ssh_host: string | null, remote_ssh_user: string | null, };
+
+export enum EditorType { VS_CODE = "VS_CODE", CURSOR = "CURSOR", WINDSURF = "WINDSURF", INTELLI_J = "INTELLI_J", WEBSTORM = "WEBSTORM", PYCHARM = "PYCHARM", GOLAND = "GOLAND", RUST_ROVER = "RUST_ROVER", CLION = "CLION", ZED = "ZED", CUSTOM = "CUSTOM" }
+
+export type GitHubConfig = { pat: string | null, oauth_token: string | null, username: string | null, primary_email: string | null, default_pr_base: string | null, 
+/**
+ * GitHub App acting for vibe-kanban instead of a personal token, used once the
+ * installation and private key are set as well
+ */
+app_id: number | null, app_installation_id: number | null, 
+/**
+ * PEM encoded private key of the app
+ */
+app_private_key: string | null, };
+
+export enum SoundFile { ABSTRACT_SOUND1 = "ABSTRACT_SOUND1", ABSTRACT_SOUND2 = "ABSTRACT_SOUND2", ABSTRACT_SOUND3 = "ABSTRACT_SOUND3", ABSTRACT_SOUND4 = "ABSTRACT_SOUND4", COW_MOOING = "COW_MOOING", PHONE_VIBRATION = "PHONE_VIBRATION", ROOSTER = "ROOSTER" }
+
+export type CustomSound = { name: string, content_type: string, size_bytes: number, };
+
+export type ImportSoundRequest = { 
+/**
+ * Audio file on the server's machine, copied into the data directory
+ */
+path: string, };
+
+export type ShutdownConfig = { mode: ShutdownMode, wait_timeout_secs: number, };
+
+/**
+ * What happens to running agents when the server is asked to stop. `Suspend` stops them
+ * straight away and records their sessions so they can be resumed, `Wait` lets them finish
+ * and suspends whatever is still running after `wait_timeout_secs`.
+ */
+export enum ShutdownMode { SUSPEND = "SUSPEND", WAIT = "WAIT" }
+
+export type BackupConfig = { 
+/**
+ * Where snapshots are written, defaults to `backups` in the data directory
+ */
+directory: string | null, daily_snapshots: boolean, 
+/**
+ * Number of snapshots kept, older ones are deleted after each new snapshot
+ */
+retention_count: number, };
+
+export type BudgetConfig = { 
+/**
+ * Estimated spend across all projects after which new attempts are refused, per
+ * calendar month (UTC)
+ */
+monthly_cap_usd: number | null, };
+
+export type TelemetryConfig = { exporter: TelemetryExporter, 
+/**
+ * Base URL of an OTLP/HTTP collector, e.g. `http://localhost:4318`. Events are sent
+ * to its `/v1/logs` as log records.
+ */
+otlp_endpoint: string | null, };
+
+export enum TelemetryExporter { POSTHOG = "POSTHOG", OTLP = "OTLP", NONE = "NONE" }
+
+/**
+ * Experimental subsystems that can be turned off at runtime. The ones that shipped before
+ * they were gated default to on, so upgrading keeps configured reviewers and devcontainers.
+ */
+export type FeatureFlags = { 
+/**
+ * Agents handing work to other agents: reviewer agents and plan-then-execute attempts
+ */
+multi_agent_pipelines: boolean, 
+/**
+ * Running attempt processes in the project's devcontainer
+ */
+container_sandbox: boolean, 
+/**
+ * Running agents on another machine, nothing uses it yet
+ */
+remote_exec: boolean, 
+/**
+ * Attempts with worktrees in several projects' repositories, for changes landing together
+ */
+multi_repo_attempts: boolean, };
+
+export type ConfigFieldError = { 
+/**
+ * Dotted path of the field, e.g. `editor.custom_command`
+ */
+field: string, message: string, };
+
+/**
+ * The config as it was before one of the saves since
+ */
+export type ConfigVersion = { 
+/**
+ * 1 for the config before the last save, 2 for the one before that, and so on
+ */
+n: number, 
+/**
+ * When the save replacing it happened
+ */
+replaced_at: string, 
+/**
+ * Dotted paths of the fields that differ from the current config, e.g. `editor.custom_command`
+ */
+changed_fields: Array<string>, 
+/**
+ * Credentials redacted
+ */
+config: Config, };
+
+export type BackupInfo = { file_name: string, path: string, size_bytes: number, created_at: string, };
+
+export type RestoreBackupRequest = { file_name: string, };
+
+export type ProjectSpendSummary = { project_id: string, 
+/**
+ * `None` once the project is deleted, its spend still counts towards the month
+ */
+project_name: string | null, spend_usd: number, monthly_cap_usd: number | null, };
+
+export type SpendSummary = { 
+/**
+ * `YYYY-MM`
+ */
+month: string, total_usd: number, monthly_cap_usd: number | null, projects: Array<ProjectSpendSummary>, };
+
+export type CostEstimateRequest = { prompt: string, profile_variant_label: ProfileVariantLabel, 
+/**
+ * Model to price, instead of the one the profile runs
+ */
+model: string | null, 
+/**
+ * Tokens of the repository, instead of counting its tracked files
+ */
+repo_tokens: number | null, };
+
+export type TokenRange = { low: number, high: number, };
+
+export type CostRange = { low_usd: number, high_usd: number, };
+
+/**
+ * What an attempt might use before it starts, from the prompt, the model and the size of
+ * the repository. Prompt caching isn't accounted for, so the high end is pessimistic.
+ */
+export type CostEstimate = { 
+/**
+ * `None` when neither the profile nor the agent's default names a model
+ */
+model: string | null, prompt_tokens: number, repo_tokens: number, input_tokens: TokenRange, output_tokens: TokenRange, 
+/**
+ * `None` when the model has no bundled price
+ */
+cost: CostRange | null, };
+
+export type UsageGroupBy = "day" | "profile" | "project";
+
+/**
+ * Attempts of one day, profile or project and what they used
+ */
+export type UsageBucket = { 
+/**
+ * `YYYY-MM-DD` (UTC), the profile's label, or the project's id
+ */
+key: string, 
+/**
+ * What to show for the key, the project's name when grouped by project
+ */
+label: string, attempts: number, 
+/**
+ * Attempts whose latest coding agent run completed
+ */
+succeeded: number, merged: number, 
+/**
+ * Share of attempts that succeeded, from 0 to 1
+ */
+success_rate: number, input_tokens: number, output_tokens: number, cost_usd: number, };
+
+/**
+ * Usage of the attempts started in the last days, from the stats stored for their runs.
+ * Tokens and costs only count runs whose agent reports them.
+ */
+export type UsageReport = { group_by: UsageGroupBy, since: string, buckets: Array<UsageBucket>, total: UsageBucket, };
+
+export type EvaluationStatus = "running" | "completed" | "failed" | "cancelled";
+
+export type EvaluationRunStatus = "pending" | "running" | "completed" | "failed";
+
+/**
+ * Completed tasks of a project replayed against several profiles
+ */
+export type Evaluation = { id: string, project_id: string, name: string, status: EvaluationStatus, error: string | null, created_at: string, completed_at: string | null, };
+
+/**
+ * One task replayed by one profile variant
+ */
+export type EvaluationRun = { id: string, evaluation_id: string, task_id: string, profile: string, variant: string | null, 
+/**
+ * Branch the replay starts from
+ */
+base_branch: string, task_attempt_id: string | null, status: EvaluationRunStatus, error: string | null, agent_completed: boolean | null, 
+/**
+ * Whether the verification or test script passed, `None` when neither ran
+ */
+verified: boolean | null, tests_passed: number | null, tests_failed: number | null, 
+/**
+ * From 0 to 1, see `services::evaluation::score`
+ */
+score: number | null, cost_usd: number | null, input_tokens: number | null, output_tokens: number | null, 
+/**
+ * Time the coding agent ran for
+ */
+duration_seconds: number | null, created_at: string, completed_at: string | null, };
+
+export type CreateEvaluation = { project_id: string, name: string, 
+/**
+ * Done tasks to replay, every done task of the project when empty
+ */
+task_ids: Array<string>, profiles: Array<ProfileVariantLabel>, };
+
+/**
+ * How one profile variant did across the replayed tasks
+ */
+export type ProfileScore = { profile: string, variant: string | null, runs: number, 
+/**
+ * Runs that were scored or failed, failed ones scoring 0
+ */
+finished: number, agent_completed: number, verified: number, 
+/**
+ * Mean score of the finished runs, from 0 to 1
+ */
+mean_score: number, cost_usd: number, input_tokens: number, output_tokens: number, 
+/**
+ * `None` until a run completes
+ */
+mean_duration_seconds: number | null, };
+
+/**
+ * The runs of an evaluation and the profiles ranked by score, then by cost
+ */
+export type EvaluationReport = { evaluation: Evaluation, runs: Array<EvaluationRun>, leaderboard: Array<ProfileScore>, };
+
+export type WebhookEvent = "task.created" | "task.updated" | "attempt.created" | "attempt.updated" | "execution.started" | "execution.completed" | "execution.failed" | "execution.killed" | "ping";
+
+export type WebhookDeliveryStatus = "pending" | "delivered" | "failed";
+
+export type Webhook = { id: string, url: string, 
+/**
+ * Events posted to the endpoint, all of them when empty
+ */
+events: Array<WebhookEvent>, enabled: boolean, created_at: string, updated_at: string, };
+
+export type CreateWebhook = { url: string, events: Array<WebhookEvent>, };
+
+export type UpdateWebhook = { url: string, events: Array<WebhookEvent>, enabled: boolean, };
+
+/**
+ * An event posted, or to be posted, to a webhook
+ */
+export type WebhookDelivery = { id: string, webhook_id: string, event: WebhookEvent, 
+/**
+ * The task, attempt or execution process the event is about
+ */
+data: JsonValue, status: WebhookDeliveryStatus, attempts: number, next_attempt_at: string, 
+/**
+ * HTTP status of the last response, `None` when the endpoint couldn't be reached
+ */
+response_status: number | null, last_error: string | null, created_at: string, delivered_at: string | null, };
+
+/**
+ * A webhook with its signing secret, only returned when the secret is set
+ */
+export type WebhookWithSecret = { webhook: Webhook, secret: string, };
+
+/**
+ * The card an alert's dedupe key points at
+ */
+export type InboundTask = { project_id: string, dedupe_key: string, task_id: string, 
+/**
+ * Sender of the alert, e.g. `sentry`
+ */
+source: string | null, occurrences: number, first_seen_at: string, last_seen_at: string, };
+
+/**
+ * An alert posted by an integration such as Sentry, PagerDuty or CI
+ */
+export type InboundTaskRequest = { title: string, body: string | null, 
+/**
+ * Alerts with the same key update one open card instead of adding more
+ */
+dedupe_key: string | null, 
+/**
+ * Project id, name or repository folder name. May be left out when there's only one
+ * project.
+ */
+project: string | null, 
+/**
+ * Sender of the alert, e.g. `sentry`
+ */
+source: string | null, 
+/**
+ * Start an agent on the card when it's created
+ */
+start_agent: boolean, };
+
+export type InboundTaskAction = "created" | "updated";
+
+export type InboundTaskResponse = { task: Task, action: InboundTaskAction, 
+/**
+ * Times the alert has fired on this card
+ */
+occurrences: number, agent_started: boolean, };
+
+/**
+ * Whether the integration can fetch issues and check webhook signatures
+ */
+export type SentryIntegrationStatus = { auth_token_set: boolean, client_secret_set: boolean, };
+
+export type SetSentryCredentials = { auth_token: string | null, client_secret: string | null, };
+
+export type ImportSentryIssue = { 
+/**
+ * Link to the issue in Sentry
+ */
+link: string, 
+/**
+ * Project id, name or repository folder name, matched against the stack trace when
+ * left out
+ */
+project: string | null, start_agent: boolean, };
+
+export type WorktreeUsage = { task_attempt_id: string, task_id: string, branch: string | null, bytes: number, merged: boolean, 
+/**
+ * Whether one of the attempt's processes is running, its worktree is then kept
+ */
+running: boolean, };
+
+export type ProjectDiskUsage = { project_id: string, used_bytes: number, max_bytes: number | null, auto_gc: boolean, 
+/**
+ * Taken up by worktrees of merged attempts that aren't running
+ */
+reclaimable_bytes: number, 
+/**
+ * Largest first
+ */
+worktrees: Array<WorktreeUsage>, };
+
+export type SecretFinding = { rule_id: string, description: string, file: string, 
+/**
+ * 1-based line in the new version of the file
+ */
+line: number, 
+/**
+ * The secret with all but its first and last four characters masked
+ */
+redacted: string, };
+
+export type SecretScanReport = { findings: Array<SecretFinding>, };
+
+export type PathViolationReason = { "kind": "forbidden", pattern: string, } | { "kind": "not_allowed" };
+
+export type PathViolation = { path: string, reason: PathViolationReason, };
+
+export type BranchSyncStrategy = "rebase" | "merge";
+
+export type BranchSyncSchedule = "off" | "nightly" | "on_base_change";
+
+export type ProjectBranchSync = { project_id: string, strategy: BranchSyncStrategy, schedule: BranchSyncSchedule, updated_at: string, };
+
+export type UpdateProjectBranchSync = { strategy: BranchSyncStrategy, schedule: BranchSyncSchedule, };
+
+/**
+ * GitHub Actions workflows watched for failures on a project's base branch
+ */
+export type ProjectCiTriage = { project_id: string, enabled: boolean, 
+/**
+ * Workflow files watched, e.g. `ci.yml`, every workflow when empty
+ */
+workflows: Array<string>, 
+/**
+ * Branch watched, the repository's default branch when `None`
+ */
+branch: string | null, 
+/**
+ * Start an attempt on each card a failure opens
+ */
+start_agent: boolean, 
+/**
+ * Newest workflow run already looked at
+ */
+last_run_id: number | null, updated_at: string, };
+
+export type UpdateProjectCiTriage = { enabled: boolean, workflows: Array<string>, branch: string | null, start_agent: boolean, };
+
+export type DependencyUpdateSchedule = "daily" | "weekly";
+
+/**
+ * Packages updated together in one pull request
+ */
+export type DependencyGroup = { name: string, 
+/**
+ * Package names, `*` matching any run of characters, e.g. `@types/*`
+ */
+patterns: Array<string>, };
+
+/**
+ * Scheduled dependency updates of a project
+ */
+export type ProjectDependencyUpdates = { project_id: string, enabled: boolean, schedule: DependencyUpdateSchedule, 
+/**
+ * Packages no group matches are updated in one more pull request
+ */
+groups: Array<DependencyGroup>, 
+/**
+ * Allow updates across major versions
+ */
+allow_major: boolean, 
+/**
+ * Packages never updated, same patterns as groups
+ */
+ignored: Array<string>, 
+/**
+ * Extra instructions for the agent
+ */
+instructions: string | null, last_run_at: string | null, updated_at: string, };
+
+export type UpdateProjectDependencyUpdates = { enabled: boolean, schedule: DependencyUpdateSchedule, groups: Array<DependencyGroup>, allow_major: boolean, ignored: Array<string>, instructions: string | null, };
+
+export type DependencyUpdateRunStatus = "running" | "pr_opened" | "no_changes" | "failed";
+
+/**
+ * One group of packages updated by one attempt
+ */
+export type DependencyUpdateRun = { id: string, project_id: string, group_name: string, task_id: string, task_attempt_id: string | null, status: DependencyUpdateRunStatus, pr_url: string | null, error: string | null, created_at: string, completed_at: string | null, };
+
+export type ChangelogFormat = "keep_a_changelog" | "conventional_commits";
+
+/**
+ * Changelog fragment written into the repository when an attempt of a project is merged
+ */
+export type ProjectChangelog = { project_id: string, enabled: boolean, format: ChangelogFormat, 
+/**
+ * Directory of the fragments, relative to the repository root
+ */
+directory: string, 
+/**
+ * Merge commits get a conventional commit subject
+ */
+conventional_commits: boolean, updated_at: string, };
+
+export type UpdateProjectChangelog = { enabled: boolean, format: ChangelogFormat, directory: string, conventional_commits: boolean, };
+
+export type BranchSyncStatus = "up_to_date" | "synced" | "conflicts" | "failed";
+
+export type AttemptBranchSync = { task_attempt_id: string, status: BranchSyncStatus, 
+/**
+ * Base branch commit the sync ran against
+ */
+base_commit: string | null, conflicted_files: Array<string>, error: string | null, synced_at: string, };
+
+export type MergeQueueStatus = "queued" | "rebasing" | "verifying" | "merged" | "failed";
+
+/**
+ * An attempt's place in its project's merge queue
+ */
+export type MergeQueueEntry = { id: string, project_id: string, task_attempt_id: string, status: MergeQueueStatus, merge_commit: string | null, error: string | null, created_at: string, updated_at: string, };
+
+export type PlanStatus = "planning" | "awaiting_approval" | "approved" | "rejected" | "failed";
+
+export type AttemptPlan = { task_attempt_id: string, status: PlanStatus, plan: string | null, 
+/**
+ * Variant of the attempt's profile that executes the approved plan
+ */
+execute_variant: string | null, created_at: string, updated_at: string, };
+
+export type ProjectReviewer = { project_id: string, enabled: boolean, profile: string, variant: string | null, 
+/**
+ * Pull requests need the latest review to approve the changes
+ */
+gate_pull_requests: boolean, updated_at: string, };
+
+export type UpdateProjectReviewer = { enabled: boolean, profile: string, variant: string | null, gate_pull_requests: boolean, };
+
+export type ProjectContextPacking = { project_id: string, enabled: boolean, 
+/**
+ * Globs relative to the repository root, matching files are always included
+ */
+include_paths: Array<string>, 
+/**
+ * Include files changed by this many of the latest commits, none when 0
+ */
+recent_commits: number, 
+/**
+ * Include files whose path or content mentions words of the task
+ */
+keyword_search: boolean, 
+/**
+ * Rough number of tokens the packed files may add to the prompt
+ */
+token_budget: number, updated_at: string, };
+
+export type UpdateProjectContextPacking = { enabled: boolean, include_paths: Array<string>, recent_commits: number, keyword_search: boolean, token_budget: number, };
+
+export type ProjectDependencyCache = { project_id: string, 
+/**
+ * One Cargo target directory for all attempts, through `CARGO_TARGET_DIR`
+ */
+shared_cargo_target: boolean, 
+/**
+ * One pnpm store for all attempts, through `npm_config_store_dir`
+ */
+shared_pnpm_store: boolean, updated_at: string, };
+
+export type UpdateProjectDependencyCache = { shared_cargo_target: boolean, shared_pnpm_store: boolean, };
 
-export type Project = { id: string, name: string, git_repo_path: string, setup_script: string | null, dev_script: string | null, cleanup_script: string | null, copy_files: string | null, created_at: Date, updated_at: Date, };
+export type ProjectDevcontainer = { project_id: string, 
+/**
+ * Run setup scripts and coding agents inside the devcontainer instead of on the host
+ */
+enabled: boolean, updated_at: string, };
 
-export type ProjectWithBranch = { id: string, name: string, git_repo_path: string, setup_script: string | null, dev_script: string | null, cleanup_script: string | null, copy_files: string | null, current_branch: string | null, created_at: Date, updated_at: Date, };
+export type UpdateProjectDevcontainer = { enabled: boolean, };
 
-export type CreateProject = { name: string, git_repo_path: string, use_existing_repo: boolean, setup_script: string | null, dev_script: string | null, cleanup_script: string | null, copy_files: string | null, };
+export type NixTool = "flake" | "devenv";
 
-export type UpdateProject = { name: string | null, git_repo_path: string | null, setup_script: string | null, dev_script: string | null, cleanup_script: string | null, copy_files: string | null, };
+export type ProjectNixEnv = { project_id: string, 
+/**
+ * Run setup scripts, coding agents, test scripts and dev servers in the shell
+ */
+enabled: boolean, tool: NixTool, updated_at: string, };
 
-export type SearchResult = { path: string, is_file: boolean, match_type: SearchMatchType, };
+export type UpdateProjectNixEnv = { enabled: boolean, tool: NixTool, };
 
-export type SearchMatchType = "FileName" | "DirectoryName" | "FullPath";
+export type ProjectEnvVar = { project_id: string, key: string, value: string | null, secret: boolean, updated_at: string, };
 
-export type ExecutorAction = { typ: ExecutorActionType, next_action: ExecutorAction | null, };
+export type ProjectEnvVarInput = { key: string, value: string | null, secret: boolean, };
 
-export type McpConfig = { servers: { [key in string]?: JsonValue }, servers_path: Array<string>, template: JsonValue, vibe_kanban: JsonValue, is_toml_config: boolean, };
+export type UpdateProjectEnv = { vars: Array<ProjectEnvVarInput>, };
 
-export type ExecutorActionType = { "type": "CodingAgentInitialRequest" } & CodingAgentInitialRequest | { "type": "CodingAgentFollowUpRequest" } & CodingAgentFollowUpRequest | { "type": "ScriptRequest" } & ScriptRequest;
+export type ProjectWorktreePool = { project_id: string, size: number, updated_at: string, };
 
-export type ScriptContext = "SetupScript" | "CleanupScript" | "DevServer";
+export type UpdateProjectWorktreePool = { size: number, };
 
-export type ScriptRequest = { script: string, language: ScriptRequestLanguage, context: ScriptContext, };
+export type WorktreePoolStatus = { size: number, 
+/**
+ * Worktrees ready to be claimed
+ */
+ready: number, 
+/**
+ * Whether worktrees are being set up
+ */
+filling: boolean, };
 
-export type ScriptRequestLanguage = "Bash";
+export type WorktreeTemplate = { 
+/**
+ * Path relative to the template directory, and to the worktree without the
+ * `.tmpl` suffix
+ */
+path: string, size_bytes: number, 
+/**
+ * `${NAME}` placeholders are replaced with the project's environment variables
+ */
+rendered: boolean, modified_at: string, };
 
-export type TaskTemplate = { id: string, project_id: string | null, title: string, description: string | null, template_name: string, created_at: string, updated_at: string, };
+export type WorktreeTemplateList = { 
+/**
+ * Where the templates are kept, files can also be placed there directly
+ */
+dir: string, templates: Array<WorktreeTemplate>, };
 
-export type CreateTaskTemplate = { project_id: string | null, title: string, description: string | null, template_name: string, };
+/**
+ * A language scaffold new projects can start from
+ */
+export type ProjectTemplate = { id: string, name: string, description: string, setup_script: string | null, dev_script: string | null, test_script: string | null, test_result_format: TestResultFormat | null, };
 
-export type UpdateTaskTemplate = { title: string | null, description: string | null, template_name: string | null, };
+/**
+ * What a brand-new project's repository starts with
+ */
+export type TemplateSource = { "type": "empty" } | { "type": "builtin", id: string, } | { "type": "repository", url: string, };
 
-export type TaskStatus = "todo" | "inprogress" | "inreview" | "done" | "cancelled";
+export type CreateFirstTask = { title: string, description: string | null, };
 
-export type Task = { id: string, project_id: string, title: string, description: string | null, status: TaskStatus, parent_task_attempt: string | null, created_at: string, updated_at: string, };
+/**
+ * A project created from scratch in a new directory
+ */
+export type CreateProjectFromTemplate = { name: string, git_repo_path: string, template: TemplateSource, 
+/**
+ * Commands default to the built-in template's
+ */
+setup_script: string | null, dev_script: string | null, cleanup_script: string | null, copy_files: string | null, test_script: string | null, test_result_format: TestResultFormat | null, first_task: CreateFirstTask | null, };
 
-export type TaskWithAttemptStatus = { id: string, project_id: string, title: string, description: string | null, status: TaskStatus, parent_task_attempt: string | null, created_at: string, updated_at: string, has_in_progress_attempt: boolean, has_merged_attempt: boolean, last_attempt_failed: boolean, profile: string, };
+/**
+ * How far a clone got
+ */
+export type CloneProgress = { "stage": "receiving", received_objects: number, total_objects: number, received_bytes: number, } | { "stage": "resolving", indexed_deltas: number, total_deltas: number, } | { "stage": "checking_out", completed: number, total: number, };
 
-export type CreateTask = { project_id: string, title: string, description: string | null, parent_task_attempt: string | null, };
+/**
+ * Package manager a repository's lockfiles or manifests point at
+ */
+export type PackageManager = "pnpm" | "yarn" | "bun" | "npm" | "cargo" | "uv" | "poetry" | "pip" | "go" | "bundler";
 
-export type UpdateTask = { title: string | null, description: string | null, status: TaskStatus | null, parent_task_attempt: string | null, };
+/**
+ * A project added by cloning a remote repository
+ */
+export type CloneProject = { url: string, 
+/**
+ * Directory the repository is cloned into, see [`default_clone_path`]
+ */
+git_repo_path: string | null, 
+/**
+ * Defaults to the repository name
+ */
+name: string | null, 
+/**
+ * Scripts left out are filled in with confident suggestions of script detection
+ */
+setup_script: string | null, dev_script: string | null, test_script: string | null, };
 
-export type ApiResponse<T, E = T> = { success: boolean, data: T | null, error_data: E | null, message: string | null, };
+export type CloneJob = { id: string, git_repo_path: string, };
 
-export type UserSystemInfo = { config: Config, environment: Environment, profiles: Array<ProfileConfig>, };
+/**
+ * Where a clone stands, streamed to the client that started it
+ */
+export type CloneEvent = { "type": "started" } | { "type": "progress", progress: CloneProgress, } | { "type": "done", project: Project, default_branch: string, package_manager: PackageManager | null, } | { "type": "failed", error: string, };
 
-export type Environment = { os_type: string, os_version: string, os_architecture: string, bitness: string, };
+/**
+ * A git repository in the workspace directory that isn't a project yet
+ */
+export type DiscoveredRepo = { 
+/**
+ * Folder name
+ */
+name: string, path: string, 
+/**
+ * URL of the `origin` remote
+ */
+remote_url: string | null, 
+/**
+ * Checked out branch, `None` for a detached head
+ */
+branch: string | null, 
+/**
+ * `None` for a repository without commits
+ */
+last_commit: LastCommit | null, };
 
-export type McpServerQuery = { profile: string, };
+export type LastCommit = { sha: string, summary: string, author: string, committed_at: string, };
 
-export type UpdateMcpServersBody = { servers: { [key in string]?: JsonValue }, };
+export type ScriptKind = "setup" | "dev" | "test";
 
-export type GetMcpServerResponse = { mcp_config: McpConfig, config_path: string, };
+/**
+ * A command proposed for one of the project's scripts
+ */
+export type ScriptSuggestion = { kind: ScriptKind, command: string, 
+/**
+ * From 0 to 1, how likely the command is what the project uses
+ */
+confidence: number, 
+/**
+ * File the suggestion was derived from
+ */
+source: string, };
+
+/**
+ * Commands proposed for a repository, the most confident first for each kind
+ */
+export type DetectedScripts = { package_manager: PackageManager | null, suggestions: Array<ScriptSuggestion>, };
 
-export type CreateFollowUpAttempt = { prompt: string, variant: string | null, };
+export type ProjectDevcontainerStatus = { 
+/**
+ * Path of the definition relative to the repository root
+ */
+config_path: string | null, settings: ProjectDevcontainer | null, };
 
-export type CreateGitHubPrRequest = { title: string, body: string | null, base_branch: string | null, };
+export type ProjectNixEnvStatus = { detected: NixTool | null, settings: ProjectNixEnv | null, };
 
-export enum GitHubServiceError { TOKEN_INVALID = "TOKEN_INVALID", INSUFFICIENT_PERMISSIONS = "INSUFFICIENT_PERMISSIONS", REPO_NOT_FOUND_OR_NO_ACCESS = "REPO_NOT_FOUND_OR_NO_ACCESS" }
+export type ReviewVerdict = "approved" | "changes_requested" | "failed";
 
-export type Config = { config_version: string, theme: ThemeMode, profile: ProfileVariantLabel, disclaimer_acknowledged: boolean, onboarding_acknowledged: boolean, github_login_acknowledged: boolean, telemetry_acknowledged: boolean, notifications: NotificationConfig, editor: EditorConfig, github: GitHubConfig, analytics_enabled: boolean | null, workspace_dir: string | null, };
+export type ReviewComment = { file: string | null, line: number | null, comment: string, };
 
-export type NotificationConfig = { sound_enabled: boolean, push_enabled: boolean, sound_file: SoundFile, };
+export type AttemptReview = { id: string, task_attempt_id: string, execution_process_id: string, verdict: ReviewVerdict, summary: string, comments: Array<ReviewComment>, created_at: string, };
 
-export enum ThemeMode { LIGHT = "LIGHT", DARK = "DARK", SYSTEM = "SYSTEM", PURPLE = "PURPLE", GREEN = "GREEN", BLUE = "BLUE", ORANGE = "ORANGE", RED = "RED" }
+/**
+ * Read-only link showing an attempt's transcript and diff at `/share/{token}`
+ */
+export type AttemptShareLink = { id: string, task_attempt_id: string, token: string, 
+/**
+ * `None` for links that work until they are revoked
+ */
+expires_at: string | null, created_at: string, };
 
-export type EditorConfig = { editor_type: EditorType, custom_command: string | null, };
+export type CreateAttemptShareLink = { 
+/**
+ * Hours until the link stops working, it never expires when `None`
+ */
+expires_in_hours: number | null, };
+
+/**
+ * One agent run of a shared attempt
+ */
+export type SharedProcess = { run_reason: ExecutionProcessRunReason, status: ExecutionProcessStatus, started_at: string, completed_at: string | null, entries: Array<NormalizedEntry>, };
 
-export enum EditorType { VS_CODE = "VS_CODE", CURSOR = "CURSOR", WINDSURF = "WINDSURF", INTELLI_J = "INTELLI_J", ZED = "ZED", CUSTOM = "CUSTOM" }
+/**
+ * What a share link shows: the task, the agents' conversations and the diff, secrets
+ * masked. Nothing in it leads back to the board or paths on the machine.
+ */
+export type SharedAttempt = { task_title: string, task_description: string | null, profile: string, branch: string | null, base_branch: string, merged: boolean, created_at: string, 
+/**
+ * When the link stops working, `None` until it is revoked
+ */
+expires_at: string | null, processes: Array<SharedProcess>, diffs: Array<Diff>, };
 
-export type GitHubConfig = { pat: string | null, oauth_token: string | null, username: string | null, primary_email: string | null, default_pr_base: string | null, };
+/**
+ * Another project's repository an attempt changes alongside its own, in a worktree nested
+ * under the attempt's worktree so the agent sees both
+ */
+export type AttemptLinkedRepo = { id: string, task_attempt_id: string, project_id: string, base_branch: string, 
+/**
+ * `None` until the worktree is created with the attempt's
+ */
+branch: string | null, container_ref: string | null, merge_commit: string | null, pr_url: string | null, created_at: string, };
 
-export enum SoundFile { ABSTRACT_SOUND1 = "ABSTRACT_SOUND1", ABSTRACT_SOUND2 = "ABSTRACT_SOUND2", ABSTRACT_SOUND3 = "ABSTRACT_SOUND3", ABSTRACT_SOUND4 = "ABSTRACT_SOUND4", COW_MOOING = "COW_MOOING", PHONE_VIBRATION = "PHONE_VIBRATION", ROOSTER = "ROOSTER" }
+/**
+ * A project to change in the same attempt
+ */
+export type CreateAttemptLinkedRepo = { project_id: string, 
+/**
+ * Branch or tag the worktree starts from, the project's current branch when `None`
+ */
+base_branch: string | null, };
+
+/**
+ * One linked repository's changes, shown next to the attempt's own diff
+ */
+export type LinkedRepoDiff = { linked_repo: AttemptLinkedRepo, project_name: string, diffs: Array<Diff>, };
+
+export type CommitPlanStatus = "planning" | "applied" | "failed";
+
+/**
+ * One commit of a plan, with the files it takes in their final state
+ */
+export type PlannedCommit = { message: string, files: Array<string>, };
+
+/**
+ * Split of an attempt's changes into logical commits, planned by an agent run
+ */
+export type AttemptCommitPlan = { id: string, task_attempt_id: string, execution_process_id: string, status: CommitPlanStatus, 
+/**
+ * In commit order, files no commit names end up in a last commit
+ */
+commits: Array<PlannedCommit>, 
+/**
+ * Branch head before the split
+ */
+original_head: string | null, error: string | null, created_at: string, applied_at: string | null, };
+
+export type CreateCommitPlanRequest = { 
+/**
+ * Profile planning the commits, a cheaper one will do. The attempt's profile when `None`
+ */
+profile_variant_label: ProfileVariantLabel | null, };
+
+export type AgentLoginStatus = "running" | "verifying" | "succeeded" | "failed" | "cancelled";
+
+export type AgentLoginSession = { id: string, profile: string, command: string, status: AgentLoginStatus, 
+/**
+ * Whether the agent's credentials were checked after the login flow finished
+ */
+verified: boolean, error: string | null, started_at: string, };
+
+export type StartAgentLoginRequest = { profile: string, };
+
+export type AgentLoginInput = { 
+/**
+ * Sent to the login process followed by a newline, e.g. a pasted auth code
+ */
+input: string, };
+
+export type WorkspaceInfo = { name: string, path: string, };
+
+export type WorkspacesResponse = { 
+/**
+ * Workspace this server is running on
+ */
+active: string, 
+/**
+ * Workspace that will be opened on the next start
+ */
+selected: string, workspaces: Array<WorkspaceInfo>, };
+
+export type WorkspaceRequest = { name: string, };
+
+export type EditorOpenRequest = { attempt_id: string, 
+/**
+ * Relative to the attempt's worktree, the worktree itself when omitted
+ */
+file_path: string | null, 
+/**
+ * 1-based, e.g. the first line of a diff hunk
+ */
+line: number | null, column: number | null, 
+/**
+ * Overrides the configured editor
+ */
+editor_type: string | null, };
+
+export type EditorOpenResponse = { 
+/**
+ * Set when the editor runs on the browser's machine over Remote SSH, the browser
+ * opens this link instead of the server launching the editor
+ */
+url: string | null, };
+
+export type TerminalQuery = { 
+/**
+ * Command to run, an interactive shell when omitted
+ */
+command: string | null, cols: number | null, rows: number | null, };
+
+/**
+ * Sent by the browser as text frames
+ */
+export type TerminalClientMessage = { "type": "input", data: string, } | { "type": "resize", cols: number, rows: number, };
+
+/**
+ * Sent to the browser as text frames, terminal output arrives as binary frames
+ */
+export type TerminalServerMessage = { "type": "exit", code: number | null, } | { "type": "error", message: string, };
+
+export type TerminalConfig = { mode: TerminalMode, 
+/**
+ * Command prefixes, e.g. `npm test` allows `npm test -- --watch` but not `npm publish`
+ */
+allowed_commands: Array<string>, };
+
+/**
+ * What the in-app terminal may run. `AllowedCommands` only starts commands whose leading
+ * words match an entry of `allowed_commands`, `FullShell` opens the user's shell.
+ */
+export enum TerminalMode { DISABLED = "DISABLED", ALLOWED_COMMANDS = "ALLOWED_COMMANDS", FULL_SHELL = "FULL_SHELL" }
+
+export type PortSource = "logs" | "port_file";
+
+export type DevServerUrl = { port: number, url: string, source: PortSource, 
+/**
+ * Whether the port accepted a connection when the URLs were requested
+ */
+listening: boolean, };
+
+export type DevServerInfo = { execution_process_id: string, 
+/**
+ * Port file the dev server can write its port to, passed to it as `VK_PORT_FILE`
+ */
+port_file: string, urls: Array<DevServerUrl>, };
+
+export type FileDiffStats = { path: string, 
+/**
+ * `None` when the language isn't known from the file's name
+ */
+language: string | null, additions: number, deletions: number, };
+
+export type LanguageDiffStats = { 
+/**
+ * `Other` for files of unknown languages
+ */
+language: string, files: number, additions: number, deletions: number, };
+
+export type DiffStats = { files_changed: number, additions: number, deletions: number, 
+/**
+ * Language with more than half of the changed lines
+ */
+primary_language: string | null, 
+/**
+ * Most changed lines first
+ */
+files: Array<FileDiffStats>, 
+/**
+ * Most changed lines first
+ */
+languages: Array<LanguageDiffStats>, };
+
+export type ContextFile = { 
+/**
+ * Path relative to the worktree root
+ */
+path: string, size_bytes: number, is_image: boolean, added_at: string, };
+
+export type ProjectSwimlane = { project: Project, tasks: Array<TaskWithAttemptStatus>, };
+
+export type ProjectGroupBoard = { group: ProjectGroup, swimlanes: Array<ProjectSwimlane>, };
+
+export type CreateGroupTask = { title: string, description: string | null, 
+/**
+ * Defaults to every project in the group
+ */
+project_ids: Array<string> | null, };
 
 export type DeviceFlowStartResponse = { user_code: string, verification_uri: string, expires_in: number, interval: number, };
 
@@ -86,7 +1339,11 @@ export enum DevicePollStatus { SLOW_DOWN = "SLOW_DOWN", AUTHORIZATION_PENDING =
 
 export enum CheckTokenResponse { VALID = "VALID", INVALID = "INVALID" }
 
-export type GitBranch = { name: string, is_current: boolean, is_remote: boolean, last_commit_date: Date, };
+export type GitBranch = { name: string, is_current: boolean, is_remote: boolean, 
+/**
+ * A tag rather than a branch, attempts can start from it but not merge into it
+ */
+is_tag: boolean, last_commit_date: Date, };
 
 export type BranchStatus = { is_behind: boolean, commits_behind: number, commits_ahead: number, up_to_date: boolean, merged: boolean, has_uncommitted_changes: boolean, base_branch_name: string, };
 
@@ -94,7 +1351,83 @@ export type Diff = { oldFile: FileDiffDetails | null, newFile: FileDiffDetails |
 
 export type FileDiffDetails = { fileName: string | null, content: string | null, };
 
-export type RepositoryInfo = { id: bigint, name: string, full_name: string, owner: string, description: string | null, clone_url: string, ssh_url: string, default_branch: string, private: boolean, };
+export type CheckState = "pending" | "success" | "failure";
+
+/**
+ * A check run or commit status reported for a commit
+ */
+export type CommitCheck = { name: string, state: CheckState, url: string | null, };
+
+export type ChecksState = "not_pushed" | "outdated" | "pending" | "passed" | "failed";
+
+/**
+ * GitHub checks of an attempt branch's pushed commit
+ */
+export type AttemptChecks = { state: ChecksState, local_commit: string, 
+/**
+ * Commit the attempt branch points to on GitHub
+ */
+remote_commit: string | null, 
+/**
+ * Checks the base branch's protection requires, every check counts when empty
+ */
+required: Array<string>, checks: Array<CommitCheck>, };
+
+export type RepositoryInfo = { id: number, name: string, full_name: string, owner: string, description: string | null, clone_url: string, ssh_url: string, default_branch: string, private: boolean, };
+
+export type AnalyticsEvent = { name: string, properties: JsonValue, timestamp: string, };
+
+export type AnalyticsEventCount = { name: string, count: number, last_at: string, };
+
+export type ToolUsage = { tool: string, 
+/**
+ * MCP server providing the tool, `None` for the agent's built-in tools
+ */
+mcp_server: string | null, calls: number, failures: number, 
+/**
+ * Calls whose result wasn't seen, e.g. because the run was stopped
+ */
+unfinished: number, 
+/**
+ * Summed over the calls the agent reported a duration for
+ */
+total_duration_ms: number | null, };
+
+export type AttemptToolUsage = { tools: Array<ToolUsage>, total_calls: number, 
+/**
+ * MCP servers configured for the attempt's profiles that no call used
+ */
+unused_mcp_servers: Array<string>, };
+
+export type PresenceToolCall = { tool_name: string, 
+/**
+ * File, command, query or URL the call was about, when it had one
+ */
+target: string | null, timestamp: string | null, };
+
+export type AgentPresence = { execution_process_id: string, task_attempt_id: string, task_id: string, project_id: string, task_title: string, profile: string, variant: string | null, started_at: string, elapsed_secs: number, activity: string | null, last_tool_call: PresenceToolCall | null, };
+
+export type TimelinePhaseKind = "queued" | "setup" | "agent_run" | "verification" | "review" | "cleanup";
+
+export type TimelinePhase = { kind: TimelinePhaseKind, 
+/**
+ * `None` for the queued phase
+ */
+execution_process_id: string | null, status: ExecutionProcessStatus | null, started_at: string, 
+/**
+ * `None` while the phase is still going on
+ */
+ended_at: string | null, 
+/**
+ * Up to now for a phase still going on, paused time included
+ */
+duration_ms: number, paused_ms: number, };
+
+export type AttemptTimeline = { started_at: string, phases: Array<TimelinePhase>, 
+/**
+ * From the attempt's creation until its last phase ended, or now while one is going on
+ */
+total_ms: number, };
 
 export type CommandBuilder = { 
 /**
@@ -106,6 +1439,8 @@ base: string,
  */
 params: Array<string> | null, };
 
+export type PermissionMode = "default" | "acceptEdits" | "bypassPermissions" | "plan";
+
 export type ProfileVariantLabel = { profile: string, variant: string | null, };
 
 export type ProfileConfig = { 
@@ -134,13 +1469,25 @@ mcp_config_path: string | null, } & ({ "CLAUDE_CODE": ClaudeCode } | { "AMP": Am
 
 export type ProfileConfigs = { profiles: Array<ProfileConfig>, };
 
-export type ClaudeCode = { command: CommandBuilder, plan: boolean, };
+export type ClaudeCode = { command: CommandBuilder, plan: boolean, permission_mode?: PermissionMode | null, 
+/**
+ * Tools allowed without asking, e.g. `Bash(git diff:*)`
+ */
+allowed_tools?: Array<string>, 
+/**
+ * Tools never allowed, even in bypass mode
+ */
+disallowed_tools?: Array<string>, max_turns?: number | null, };
 
 export type Gemini = { command: CommandBuilder, };
 
 export type Amp = { command: CommandBuilder, };
 
-export type Codex = { command: CommandBuilder, };
+export type SandboxMode = "read-only" | "workspace-write" | "danger-full-access";
+
+export type ApprovalPolicy = "untrusted" | "on-failure" | "on-request" | "never";
+
+export type Codex = { command: CommandBuilder, sandbox?: SandboxMode | null, approval?: ApprovalPolicy | null, };
 
 export type Cursor = { command: CommandBuilder, };
 
@@ -150,7 +1497,32 @@ export type CodingAgentInitialRequest = { prompt: string, profile_variant_label:
 
 export type CodingAgentFollowUpRequest = { prompt: string, session_id: string, profile_variant_label: ProfileVariantLabel, };
 
-export type CreateTaskAttemptBody = { task_id: string, profile_variant_label: ProfileVariantLabel | null, base_branch: string, };
+export type SetTaskBaseBranch = { 
+/**
+ * `None` goes back to the project's current branch
+ */
+base_branch: string | null, };
+
+export type CreateTaskAttemptBody = { task_id: string, profile_variant_label: ProfileVariantLabel | null, base_branch: string, 
+/**
+ * Variant of the profile that plans first. The attempt then waits for the plan to be
+ * approved before the chosen variant executes it.
+ */
+plan_variant: string | null, 
+/**
+ * Summarize the task's earlier attempts in the prompt
+ */
+carry_over_history: boolean, 
+/**
+ * Other projects to change in the same attempt, their worktrees nested in this one's
+ */
+linked_repos: Array<CreateAttemptLinkedRepo>, };
+
+export type ApprovePlanRequest = { 
+/**
+ * The plan as edited by the reviewer, the agent's plan when `None`
+ */
+plan: string | null, };
 
 export type RebaseTaskAttemptRequest = { new_base_branch: string | null, };
 
@@ -160,7 +1532,7 @@ export type ExecutionProcess = { id: string, task_attempt_id: string, run_reason
 
 export type ExecutionProcessStatus = "running" | "completed" | "failed" | "killed";
 
-export type ExecutionProcessRunReason = "setupscript" | "cleanupscript" | "codingagent" | "devserver";
+export type ExecutionProcessRunReason = "setupscript" | "cleanupscript" | "codingagent" | "devserver" | "review" | "commitplan";
 
 export type EventPatch = { op: string, path: string, value: EventPatchInner, };
 
@@ -168,6 +1540,30 @@ export type EventPatchInner = { db_op: string, record: RecordTypes, };
 
 export type RecordTypes = { "type": "TASK", "data": Task } | { "type": "TASK_ATTEMPT", "data": TaskAttempt } | { "type": "EXECUTION_PROCESS", "data": ExecutionProcess } | { "type": "DELETED_TASK", "data": { rowid: bigint, } } | { "type": "DELETED_TASK_ATTEMPT", "data": { rowid: bigint, } } | { "type": "DELETED_EXECUTION_PROCESS", "data": { rowid: bigint, } };
 
+/**
+ * A change to a project's task board
+ */
+export type BoardDelta = { "type": "SNAPSHOT", tasks: Array<TaskWithAttemptStatus>, } | { "type": "TASK_UPSERTED", task: TaskWithAttemptStatus, previous_status: TaskStatus | null, } | { "type": "TASK_REMOVED", task_id: string, };
+
+/**
+ * Two unmerged attempts of a project that changed some of the same files against the same
+ * base branch, so merging one will likely make the other conflict
+ */
+export type AttemptConflict = { task_attempt_id: string, task_id: string, other_task_attempt_id: string, other_task_id: string, 
+/**
+ * Worktree-relative paths both attempts changed
+ */
+paths: Array<string>, };
+
+/**
+ * An attempt's latest merge queue entry, with its place in line while it waits
+ */
+export type MergeQueuePosition = { entry: MergeQueueEntry | null, 
+/**
+ * 0 for the attempt being merged, `None` once it left the queue
+ */
+position: number | null, };
+
 export type NormalizedConversation = { entries: Array<NormalizedEntry>, session_id: string | null, executor_type: string, prompt: string | null, summary: string | null, };
 
 export type NormalizedEntry = { timestamp: string | null, entry_type: NormalizedEntryType, content: string, };